@@ -170,7 +170,7 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
     /// If file logging is enabled, this function returns a guard that must be kept alive to ensure
     /// that all logs are flushed to disk.
     pub fn init_tracing(&self) -> eyre::Result<Option<FileWorkerGuard>> {
-        let guard = self.logs.init_tracing()?;
+        let guard = self.logs.init_tracing_with_stdout_to_stderr(self.command.is_json_output())?;
         Ok(guard)
     }
 }
@@ -223,6 +223,17 @@ pub enum Commands<Ext: clap::Args + fmt::Debug = NoArgs> {
     Recover(recover::Command),
 }
 
+impl<Ext: clap::Args + fmt::Debug> Commands<Ext> {
+    /// Returns `true` if the command reports its progress as JSON events on stdout.
+    pub const fn is_json_output(&self) -> bool {
+        match self {
+            Self::Import(command) => command.is_json_output(),
+            Self::Stage(command) => command.is_json_output(),
+            _ => false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -235,6 +246,15 @@ mod tests {
         assert_eq!(reth.logs.color, ColorMode::Always);
     }
 
+    #[test]
+    fn parse_json_output() {
+        let reth = Cli::try_parse_args_from(["reth", "import", "--output", "json", "."]).unwrap();
+        assert!(reth.command.is_json_output());
+
+        let reth = Cli::try_parse_args_from(["reth", "import", "."]).unwrap();
+        assert!(!reth.command.is_json_output());
+    }
+
     /// Tests that the help message is parsed correctly. This ensures that clap args are configured
     /// correctly and no conflicts are introduced via attributes that would result in a panic at
     /// runtime
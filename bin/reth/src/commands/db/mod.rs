@@ -13,6 +13,7 @@ mod clear;
 mod diff;
 mod get;
 mod list;
+mod repair;
 mod stats;
 /// DB List TUI
 mod tui;
@@ -48,6 +49,8 @@ pub enum Subcommands {
     },
     /// Deletes all table entries
     Clear(clear::Command),
+    /// Repairs inconsistent database entries
+    Repair(repair::Command),
     /// Lists current and local database versions
     Version,
     /// Returns the full database path
@@ -122,6 +125,10 @@ impl Command {
                 let Environment { provider_factory, .. } = self.env.init(AccessRights::RW)?;
                 command.execute(provider_factory)?;
             }
+            Subcommands::Repair(command) => {
+                let Environment { provider_factory, .. } = self.env.init(AccessRights::RW)?;
+                command.execute(provider_factory)?;
+            }
             Subcommands::Version => {
                 let local_db_version = match get_db_version(&db_path) {
                     Ok(version) => Some(version),
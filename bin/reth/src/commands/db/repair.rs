@@ -0,0 +1,65 @@
+use clap::{Parser, Subcommand};
+use reth_db_api::database::Database;
+use reth_db_common::repair::{repair_header_td, DEFAULT_REPAIR_BATCH_SIZE};
+use reth_primitives::BlockNumber;
+use reth_provider::ProviderFactory;
+
+/// The arguments for the `reth db repair` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    #[clap(subcommand)]
+    subcommand: Subcommands,
+}
+
+impl Command {
+    /// Execute `db repair` command
+    pub fn execute<DB: Database>(self, provider_factory: ProviderFactory<DB>) -> eyre::Result<()> {
+        match self.subcommand {
+            Subcommands::Td { from, fix, batch_size } => {
+                let repair = repair_header_td(&provider_factory, from, fix, batch_size)?;
+
+                let Some(checked) = repair.checked.as_ref() else {
+                    println!("No canonical headers found in the database");
+                    return Ok(())
+                };
+                println!("Checked the total difficulties of blocks {checked:?}");
+                for range in &repair.inserted {
+                    println!("Inserted the missing total difficulties of blocks {range:?}");
+                }
+                for mismatch in &repair.mismatches {
+                    println!(
+                        "Total difficulty of block #{} mismatch: stored {}, expected {}",
+                        mismatch.block_number, mismatch.stored, mismatch.expected
+                    );
+                }
+                let mismatches = repair.mismatches.len();
+                if mismatches > 0 && fix {
+                    println!("Overwrote {mismatches} mismatching total difficulties");
+                } else if mismatches > 0 {
+                    println!("Found {mismatches} mismatching total difficulties");
+                    println!("Run with --fix to overwrite them");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Subcommands {
+    /// Recomputes the total difficulties of the canonical headers and inserts the missing ones
+    Td {
+        /// The block to start the repair from, defaults to the first canonical header in the
+        /// database. The total difficulty of the block before it is trusted.
+        #[arg(long)]
+        from: Option<BlockNumber>,
+        /// Overwrites the stored total difficulties that don't match the recomputed ones, instead
+        /// of only reporting them
+        #[arg(long)]
+        fix: bool,
+        /// The number of blocks to repair before committing
+        #[arg(long, default_value_t = DEFAULT_REPAIR_BATCH_SIZE)]
+        batch_size: u64,
+    },
+}
@@ -1,11 +1,9 @@
 use crate::{commands::db::checksum::ChecksumViewer, utils::DbTool};
 use clap::Parser;
 use comfy_table::{Cell, Row, Table as ComfyTable};
-use eyre::WrapErr;
 use human_bytes::human_bytes;
 use itertools::Itertools;
-use reth_db::{mdbx, static_file::iter_static_files, DatabaseEnv, TableViewer, Tables};
-use reth_db_api::database::Database;
+use reth_db::{static_file::iter_static_files, DatabaseEnv, TableViewer, Tables};
 use reth_fs_util as fs;
 use reth_node_core::dirs::{ChainPath, DataDirPath};
 use reth_primitives::static_file::{find_fixed_range, SegmentRangeInclusive};
@@ -69,70 +67,44 @@ impl Command {
             "Total Size",
         ]);
 
-        tool.provider_factory.db_ref().view(|tx| {
-            let mut db_tables = Tables::ALL.iter().map(|table| table.name()).collect::<Vec<_>>();
-            db_tables.sort();
-            let mut total_size = 0;
-            for db_table in db_tables {
-                let table_db = tx.inner.open_db(Some(db_table)).wrap_err("Could not open db.")?;
-
-                let stats = tx
-                    .inner
-                    .db_stat(&table_db)
-                    .wrap_err(format!("Could not find table: {db_table}"))?;
-
-                // Defaults to 16KB right now but we should
-                // re-evaluate depending on the DB we end up using
-                // (e.g. REDB does not have these options as configurable intentionally)
-                let page_size = stats.page_size() as usize;
-                let leaf_pages = stats.leaf_pages();
-                let branch_pages = stats.branch_pages();
-                let overflow_pages = stats.overflow_pages();
-                let num_pages = leaf_pages + branch_pages + overflow_pages;
-                let table_size = page_size * num_pages;
-
-                total_size += table_size;
-                let mut row = Row::new();
-                row.add_cell(Cell::new(db_table))
-                    .add_cell(Cell::new(stats.entries()))
-                    .add_cell(Cell::new(branch_pages))
-                    .add_cell(Cell::new(leaf_pages))
-                    .add_cell(Cell::new(overflow_pages))
-                    .add_cell(Cell::new(human_bytes(table_size as f64)));
-                table.add_row(row);
-            }
-
-            let max_widths = table.column_max_content_widths();
-            let mut separator = Row::new();
-            for width in max_widths {
-                separator.add_cell(Cell::new("-".repeat(width as usize)));
-            }
-            table.add_row(separator);
-
+        let stats = tool.provider_factory.db_ref().stats()?;
+        let mut tables_stats = stats.tables.iter().collect::<Vec<_>>();
+        tables_stats.sort_by_key(|table_stats| table_stats.table.name());
+        for table_stats in tables_stats {
             let mut row = Row::new();
-            row.add_cell(Cell::new("Tables"))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(human_bytes(total_size as f64)));
+            row.add_cell(Cell::new(table_stats.table.name()))
+                .add_cell(Cell::new(table_stats.entries))
+                .add_cell(Cell::new(table_stats.branch_pages))
+                .add_cell(Cell::new(table_stats.leaf_pages))
+                .add_cell(Cell::new(table_stats.overflow_pages))
+                .add_cell(Cell::new(human_bytes(table_stats.size as f64)));
             table.add_row(row);
+        }
 
-            let freelist = tx.inner.env().freelist()?;
-            let pagesize = tx.inner.db_stat(&mdbx::Database::freelist_db())?.page_size() as usize;
-            let freelist_size = freelist * pagesize;
+        let max_widths = table.column_max_content_widths();
+        let mut separator = Row::new();
+        for width in max_widths {
+            separator.add_cell(Cell::new("-".repeat(width as usize)));
+        }
+        table.add_row(separator);
 
-            let mut row = Row::new();
-            row.add_cell(Cell::new("Freelist"))
-                .add_cell(Cell::new(freelist))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(human_bytes(freelist_size as f64)));
-            table.add_row(row);
+        let mut row = Row::new();
+        row.add_cell(Cell::new("Tables"))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(human_bytes(stats.tables_size() as f64)));
+        table.add_row(row);
 
-            Ok::<(), eyre::Report>(())
-        })??;
+        let mut row = Row::new();
+        row.add_cell(Cell::new("Freelist"))
+            .add_cell(Cell::new(stats.free_pages))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(human_bytes(stats.freelist_size() as f64)));
+        table.add_row(row);
 
         Ok(table)
     }
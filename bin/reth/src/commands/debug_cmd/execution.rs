@@ -217,6 +217,7 @@ impl Command {
                 latest_block_number,
                 events,
                 provider_factory.db_ref().clone(),
+                None,
             ),
         );
 
@@ -1,39 +1,43 @@
 //! Command that initializes the node by importing a chain from a file.
 
 use crate::{
-    commands::common::{AccessRights, Environment, EnvironmentArgs},
+    args::OutputFormat,
+    commands::{
+        common::{AccessRights, Environment, EnvironmentArgs},
+        import_verify::{
+            verify_imported_blocks, VerificationSampling, DEFAULT_VERIFY_INTERVAL,
+            DEFAULT_VERIFY_LAST, DEFAULT_VERIFY_STATE_ROOT_INTERVAL,
+        },
+    },
     macros::block_executor,
     version::SHORT_VERSION,
 };
 use clap::Parser;
-use futures::{Stream, StreamExt};
 use reth_beacon_consensus::EthBeaconConsensus;
 use reth_config::Config;
-use reth_consensus::Consensus;
 use reth_db::tables;
 use reth_db_api::{database::Database, transaction::DbTx};
-use reth_downloaders::{
-    bodies::bodies::BodiesDownloaderBuilder,
-    file_client::{ChunkedFileReader, FileClient, DEFAULT_BYTE_LEN_CHUNK_CHAIN_FILE},
-    headers::reverse_headers::ReverseHeadersDownloaderBuilder,
+use reth_downloaders::file_client::{
+    ChunkedFileReader, FileClient, DEFAULT_BYTE_LEN_CHUNK_CHAIN_FILE,
 };
-use reth_network_p2p::{
-    bodies::downloader::BodyDownloader,
-    headers::downloader::{HeaderDownloader, SyncTarget},
-};
-use reth_node_events::node::NodeEvent;
-use reth_primitives::B256;
-use reth_provider::{
-    BlockNumReader, ChainSpecProvider, HeaderProvider, ProviderError, ProviderFactory,
-    StageCheckpointReader,
+use reth_node_builder::setup::build_import_pipeline;
+use reth_node_events::json::{
+    duration_ms, handle_json_events, JsonEvent, JsonEventWriter, RunStatus,
 };
+use reth_provider::{ChainSpecProvider, ProviderFactory, StageCheckpointReader};
 use reth_prune_types::PruneModes;
-use reth_stages::{prelude::*, Pipeline, StageId, StageSet};
+use reth_stages::StageId;
 use reth_static_file::StaticFileProducer;
-use std::{path::PathBuf, sync::Arc};
-use tokio::sync::watch;
+use std::{collections::BTreeMap, io::Write, path::PathBuf, sync::Arc, time::Instant};
 use tracing::{debug, error, info};
 
+#[cfg(not(feature = "optimism"))]
+use reth_config::config::ExecutionConfig;
+#[cfg(not(feature = "optimism"))]
+use reth_evm::execute::IntermediateStateRoots;
+#[cfg(not(feature = "optimism"))]
+use reth_provider::StateRootProvider;
+
 /// Syncs RLP encoded blocks from a file.
 #[derive(Debug, Parser)]
 pub struct ImportCommand {
@@ -48,6 +52,65 @@ pub struct ImportCommand {
     #[arg(long, value_name = "CHUNK_LEN", verbatim_doc_comment)]
     chunk_len: Option<u64>,
 
+    /// The format the import progress is reported in.
+    ///
+    /// With `json`, one event per line is written to stdout and the logs are written to stderr.
+    #[arg(long, value_name = "FORMAT", default_value_t = OutputFormat::Human, verbatim_doc_comment)]
+    output: OutputFormat,
+
+    /// Verifies the receipts root of blocks before Byzantium.
+    ///
+    /// Receipts of these blocks commit to the state root after each transaction, so this computes
+    /// a state root per transaction and is slow. Without it, only the logs bloom and the gas used
+    /// of these blocks are verified.
+    #[arg(long = "import.verify-pre-byzantium-receipts", verbatim_doc_comment)]
+    verify_pre_byzantium_receipts: bool,
+
+    /// Verifies a sample of the imported blocks against the file.
+    ///
+    /// After each chunk is imported, the canonical hash and the stored receipts of the sampled
+    /// blocks, and for some of them the state root of the imported state, are compared with the
+    /// blocks in the file. The import fails at the first block that doesn't match.
+    #[arg(long, conflicts_with = "no_state", verbatim_doc_comment)]
+    verify: bool,
+
+    /// Blocks whose number is a multiple of the interval are verified.
+    #[arg(
+        long = "verify.interval",
+        value_name = "BLOCKS",
+        default_value_t = DEFAULT_VERIFY_INTERVAL,
+        value_parser = clap::value_parser!(u64).range(1..),
+        requires = "verify",
+        verbatim_doc_comment
+    )]
+    verify_interval: u64,
+
+    /// The number of blocks at the end of each chunk that are verified.
+    #[arg(
+        long = "verify.last",
+        value_name = "BLOCKS",
+        default_value_t = DEFAULT_VERIFY_LAST,
+        requires = "verify",
+        verbatim_doc_comment
+    )]
+    verify_last: u64,
+
+    /// Blocks whose number is a multiple of the interval also have their state root verified,
+    /// as does the last block of each chunk.
+    #[arg(
+        long = "verify.state-root-interval",
+        value_name = "BLOCKS",
+        default_value_t = DEFAULT_VERIFY_STATE_ROOT_INTERVAL,
+        value_parser = clap::value_parser!(u64).range(1..),
+        requires = "verify",
+        verbatim_doc_comment
+    )]
+    verify_state_root_interval: u64,
+
+    /// Disables the verification of state roots.
+    #[arg(long = "verify.no-state-roots", requires = "verify", verbatim_doc_comment)]
+    verify_no_state_roots: bool,
+
     /// The path to a block file for import.
     ///
     /// The online stages (headers and bodies) are replaced by a file import, after which the
@@ -57,83 +120,221 @@ pub struct ImportCommand {
 }
 
 impl ImportCommand {
+    /// Returns `true` if the progress is reported as JSON events on stdout.
+    pub const fn is_json_output(&self) -> bool {
+        self.output.is_json()
+    }
+
+    /// Returns the sampling of the verified blocks, if imported blocks are verified.
+    pub const fn verification_sampling(&self) -> Option<VerificationSampling> {
+        if !self.verify {
+            return None
+        }
+        Some(VerificationSampling {
+            interval: self.verify_interval,
+            last: self.verify_last,
+            state_root_interval: if self.verify_no_state_roots {
+                None
+            } else {
+                Some(self.verify_state_root_interval)
+            },
+        })
+    }
+
     /// Execute `import` command
     pub async fn execute(self) -> eyre::Result<()> {
+        let json = self.output.is_json().then(JsonEventWriter::stdout);
+        self.execute_with_json_writer(json).await
+    }
+
+    /// Execute `import` command, reporting the progress to the given JSON writer if any.
+    async fn execute_with_json_writer<W>(self, json: Option<JsonEventWriter<W>>) -> eyre::Result<()>
+    where
+        W: Write + Send + 'static,
+    {
         info!(target: "reth::cli", "reth {} starting", SHORT_VERSION);
 
         if self.no_state {
             info!(target: "reth::cli", "Disabled stages requiring state");
         }
 
-        debug!(target: "reth::cli",
-            chunk_byte_len=self.chunk_len.unwrap_or(DEFAULT_BYTE_LEN_CHUNK_CHAIN_FILE),
-            "Chunking chain import"
-        );
+        let chunk_len = self.chunk_len.unwrap_or(DEFAULT_BYTE_LEN_CHUNK_CHAIN_FILE);
+        debug!(target: "reth::cli", chunk_byte_len=chunk_len, "Chunking chain import");
 
-        let Environment { provider_factory, config, .. } = self.env.init(AccessRights::RW)?;
+        let Environment { provider_factory, mut config, .. } = self.env.init(AccessRights::RW)?;
 
         let consensus = Arc::new(EthBeaconConsensus::new(self.env.chain.clone()));
         info!(target: "reth::cli", "Consensus engine initialized");
 
-        // open file
-        let mut reader = ChunkedFileReader::new(&self.path, self.chunk_len).await?;
+        let executor = self.block_executor(&provider_factory, &mut config);
 
-        let mut total_decoded_blocks = 0;
-        let mut total_decoded_txns = 0;
+        let sampling = self.verification_sampling();
+        if let Some(sampling) = &sampling {
+            info!(target: "reth::cli", ?sampling, "Verifying a sample of the imported blocks");
+        }
 
-        while let Some(file_client) = reader.next_chunk::<FileClient>().await? {
-            // create a new FileClient from chunk read from file
-            info!(target: "reth::cli",
-                "Importing chain file chunk"
-            );
+        if let Some(json) = &json {
+            json.emit(JsonEvent::RunStarted {
+                command: "import".to_string(),
+                config: BTreeMap::from([
+                    ("chain".to_string(), self.env.chain.chain.to_string().into()),
+                    ("path".to_string(), self.path.display().to_string().into()),
+                    ("no_state".to_string(), self.no_state.into()),
+                    ("chunk_len".to_string(), chunk_len.into()),
+                    (
+                        "verify_pre_byzantium_receipts".to_string(),
+                        self.verify_pre_byzantium_receipts.into(),
+                    ),
+                    ("verify".to_string(), self.verify.into()),
+                ]),
+            })?;
+        }
+        let start = Instant::now();
 
-            let tip = file_client.tip().ok_or(eyre::eyre!("file client has no tip"))?;
-            info!(target: "reth::cli", "Chain file chunk read");
-
-            total_decoded_blocks += file_client.headers_len();
-            total_decoded_txns += file_client.total_transactions();
-
-            let (mut pipeline, events) = build_import_pipeline(
-                &config,
-                provider_factory.clone(),
-                &consensus,
-                Arc::new(file_client),
-                StaticFileProducer::new(provider_factory.clone(), PruneModes::default()),
-                self.no_state,
-            )
-            .await?;
-
-            // override the tip
-            pipeline.set_tip(tip);
-            debug!(target: "reth::cli", ?tip, "Tip manually set");
-
-            let provider = provider_factory.provider()?;
-
-            let latest_block_number =
-                provider.get_stage_checkpoint(StageId::Finish)?.map(|ch| ch.block_number);
-            tokio::spawn(reth_node_events::node::handle_events(
-                None,
-                latest_block_number,
-                events,
-                provider_factory.db_ref().clone(),
-            ));
-
-            // Run pipeline
-            info!(target: "reth::cli", "Starting sync pipeline");
-            tokio::select! {
-                res = pipeline.run() => res?,
-                _ = tokio::signal::ctrl_c() => {},
+        let mut total_decoded_blocks = 0;
+        let mut total_decoded_txns = 0;
+        let mut total_decoded_gas = 0;
+
+        let imported = async {
+            // open file
+            let mut reader = ChunkedFileReader::new(&self.path, self.chunk_len).await?;
+
+            let mut chunk = 0;
+            let mut interrupted = false;
+            while let Some(file_client) = reader.next_chunk::<FileClient>().await? {
+                // create a new FileClient from chunk read from file
+                info!(target: "reth::cli",
+                    "Importing chain file chunk"
+                );
+                chunk += 1;
+                let chunk_start = Instant::now();
+
+                let tip = file_client.tip().ok_or(eyre::eyre!("file client has no tip"))?;
+                info!(target: "reth::cli", "Chain file chunk read");
+
+                let chunk_blocks = file_client.headers_len();
+                let chunk_txns = file_client.total_transactions();
+                let chunk_gas =
+                    file_client.headers_iter().map(|header| header.gas_used).sum::<u64>();
+                total_decoded_blocks += chunk_blocks;
+                total_decoded_txns += chunk_txns;
+                total_decoded_gas += chunk_gas;
+
+                let file_client = Arc::new(file_client);
+                let (mut pipeline, events) = build_import_pipeline(
+                    &config.stages,
+                    provider_factory.clone(),
+                    consensus.clone(),
+                    file_client.clone(),
+                    StaticFileProducer::new(provider_factory.clone(), PruneModes::default()),
+                    executor.clone(),
+                    self.no_state,
+                )
+                .await?;
+
+                // override the tip
+                pipeline.set_tip(tip);
+                debug!(target: "reth::cli", ?tip, "Tip manually set");
+
+                let events_handle = if let Some(json) = &json {
+                    tokio::spawn(handle_json_events(events, json.clone()))
+                } else {
+                    let provider = provider_factory.provider()?;
+
+                    let latest_block_number =
+                        provider.get_stage_checkpoint(StageId::Finish)?.map(|ch| ch.block_number);
+                    tokio::spawn(reth_node_events::node::handle_events(
+                        None,
+                        latest_block_number,
+                        events,
+                        provider_factory.db_ref().clone(),
+                        None,
+                    ))
+                };
+
+                // Run pipeline
+                info!(target: "reth::cli", "Starting sync pipeline");
+                tokio::select! {
+                    res = pipeline.run() => res?,
+                    _ = tokio::signal::ctrl_c() => interrupted = true,
+                }
+
+                if let Some(json) = &json {
+                    // the event stream ends with the pipeline, so all stage events of the chunk
+                    // are written before the chunk is reported
+                    drop(pipeline);
+                    let _ = events_handle.await;
+
+                    let checkpoint = provider_factory
+                        .provider()?
+                        .get_stage_checkpoint(StageId::Finish)?
+                        .map(|ch| ch.block_number);
+                    json.emit(JsonEvent::ChunkProgress {
+                        chunk,
+                        blocks: chunk_blocks as u64,
+                        transactions: chunk_txns as u64,
+                        gas: chunk_gas,
+                        duration_ms: duration_ms(chunk_start.elapsed()),
+                        checkpoint,
+                    })?;
+                }
+
+                if let Some(sampling) = sampling.filter(|_| !interrupted) {
+                    let verify_start = Instant::now();
+                    let report =
+                        verify_imported_blocks(&provider_factory, &file_client, &sampling)?;
+                    if let Some(block) = report.first_mismatch() {
+                        let mismatches = block
+                            .mismatches
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        eyre::bail!(
+                            "imported block {} ({}) doesn't match the file: {mismatches}",
+                            block.number,
+                            block.hash
+                        );
+                    }
+                    info!(target: "reth::cli",
+                        chunk,
+                        blocks = report.blocks.len(),
+                        state_roots = report.state_roots(),
+                        elapsed = ?verify_start.elapsed(),
+                        "Verified imported blocks"
+                    );
+                }
             }
+
+            Ok::<_, eyre::Error>(interrupted)
         }
+        .await;
+
+        let interrupted = match imported {
+            Ok(interrupted) => interrupted,
+            Err(err) => {
+                if let Some(json) = &json {
+                    json.emit(JsonEvent::Warning { stage: None, message: err.to_string() })?;
+                    json.emit(JsonEvent::Summary {
+                        blocks: total_decoded_blocks as u64,
+                        transactions: Some(total_decoded_txns as u64),
+                        gas: Some(total_decoded_gas),
+                        duration_ms: duration_ms(start.elapsed()),
+                        status: RunStatus::Failed,
+                    })?;
+                }
+                return Err(err)
+            }
+        };
 
         let provider = provider_factory.provider()?;
 
         let total_imported_blocks = provider.tx_ref().entries::<tables::HeaderNumbers>()?;
         let total_imported_txns = provider.tx_ref().entries::<tables::TransactionHashNumbers>()?;
 
-        if total_decoded_blocks != total_imported_blocks ||
-            total_decoded_txns != total_imported_txns
-        {
+        let partially_imported = total_decoded_blocks != total_imported_blocks ||
+            total_decoded_txns != total_imported_txns;
+        if partially_imported {
             error!(target: "reth::cli",
                 total_decoded_blocks,
                 total_imported_blocks,
@@ -149,87 +350,98 @@ impl ImportCommand {
             "Chain file imported"
         );
 
+        if let Some(json) = &json {
+            if partially_imported {
+                json.emit(JsonEvent::Warning {
+                    stage: None,
+                    message: format!(
+                        "chain was partially imported, decoded {total_decoded_blocks} blocks and \
+                         {total_decoded_txns} transactions, imported {total_imported_blocks} \
+                         blocks and {total_imported_txns} transactions"
+                    ),
+                })?;
+            }
+
+            let status = if interrupted {
+                RunStatus::Interrupted
+            } else if partially_imported {
+                RunStatus::Partial
+            } else {
+                RunStatus::Success
+            };
+            json.emit(JsonEvent::Summary {
+                blocks: total_imported_blocks as u64,
+                transactions: Some(total_imported_txns as u64),
+                gas: Some(total_decoded_gas),
+                duration_ms: duration_ms(start.elapsed()),
+                status,
+            })?;
+        }
+
         Ok(())
     }
-}
 
-/// Builds import pipeline.
-///
-/// If configured to execute, all stages will run. Otherwise, only stages that don't require state
-/// will run.
-pub async fn build_import_pipeline<DB, C>(
-    config: &Config,
-    provider_factory: ProviderFactory<DB>,
-    consensus: &Arc<C>,
-    file_client: Arc<FileClient>,
-    static_file_producer: StaticFileProducer<DB>,
-    disable_exec: bool,
-) -> eyre::Result<(Pipeline<DB>, impl Stream<Item = NodeEvent>)>
-where
-    DB: Database + Clone + Unpin + 'static,
-    C: Consensus + 'static,
-{
-    if !file_client.has_canonical_blocks() {
-        eyre::bail!("unable to import non canonical blocks");
+    /// Returns the executor of the imported blocks.
+    ///
+    /// If the receipts of blocks before Byzantium are verified, the executor computes the state
+    /// root after each of their transactions on top of the hashed state. The hashed state only
+    /// matches the state the executor reads from at the start of a pipeline run, so the execution
+    /// stage is configured to execute each chunk without committing in between.
+    #[cfg(not(feature = "optimism"))]
+    fn block_executor<DB>(
+        &self,
+        provider_factory: &ProviderFactory<DB>,
+        config: &mut Config,
+    ) -> reth_node_ethereum::EthExecutorProvider
+    where
+        DB: Database + Clone + 'static,
+    {
+        let executor = block_executor!(provider_factory.chain_spec());
+        if !self.verify_pre_byzantium_receipts {
+            return executor
+        }
+
+        info!(target: "reth::cli", "Verifying receipts of blocks before Byzantium");
+        config.stages.execution = ExecutionConfig {
+            max_blocks: None,
+            max_changes: None,
+            max_cumulative_gas: None,
+            max_duration: None,
+            prefetch_blocks: config.stages.execution.prefetch_blocks,
+        };
+        let provider_factory = provider_factory.clone();
+        executor.with_intermediate_state_roots(IntermediateStateRoots::new(move |changes| {
+            provider_factory.latest()?.state_root(changes)
+        }))
     }
 
-    // Retrieve latest header found in the database.
-    let last_block_number = provider_factory.last_block_number()?;
-    let local_head = provider_factory
-        .sealed_header(last_block_number)?
-        .ok_or(ProviderError::HeaderNotFound(last_block_number.into()))?;
-
-    let mut header_downloader = ReverseHeadersDownloaderBuilder::new(config.stages.headers)
-        .build(file_client.clone(), consensus.clone())
-        .into_task();
-    // TODO: The pipeline should correctly configure the downloader on its own.
-    // Find the possibility to remove unnecessary pre-configuration.
-    header_downloader.update_local_head(local_head);
-    header_downloader.update_sync_target(SyncTarget::Tip(file_client.tip().unwrap()));
-
-    let mut body_downloader = BodiesDownloaderBuilder::new(config.stages.bodies)
-        .build(file_client.clone(), consensus.clone(), provider_factory.clone())
-        .into_task();
-    // TODO: The pipeline should correctly configure the downloader on its own.
-    // Find the possibility to remove unnecessary pre-configuration.
-    body_downloader
-        .set_download_range(file_client.min_block().unwrap()..=file_client.max_block().unwrap())
-        .expect("failed to set download range");
-
-    let (tip_tx, tip_rx) = watch::channel(B256::ZERO);
-    let executor = block_executor!(provider_factory.chain_spec());
-
-    let max_block = file_client.max_block().unwrap_or(0);
-
-    let pipeline = Pipeline::builder()
-        .with_tip_sender(tip_tx)
-        // we want to sync all blocks the file client provides or 0 if empty
-        .with_max_block(max_block)
-        .add_stages(
-            DefaultStages::new(
-                provider_factory.clone(),
-                tip_rx,
-                consensus.clone(),
-                header_downloader,
-                body_downloader,
-                executor,
-                config.stages.clone(),
-                PruneModes::default(),
-            )
-            .builder()
-            .disable_all_if(&StageId::STATE_REQUIRED, || disable_exec),
-        )
-        .build(provider_factory, static_file_producer);
-
-    let events = pipeline.events().map(Into::into);
-
-    Ok((pipeline, events))
+    /// Returns the executor of the imported blocks.
+    #[cfg(feature = "optimism")]
+    fn block_executor<DB>(
+        &self,
+        provider_factory: &ProviderFactory<DB>,
+        _config: &mut Config,
+    ) -> reth_node_optimism::OpExecutorProvider
+    where
+        DB: Database + Clone + 'static,
+    {
+        if self.verify_pre_byzantium_receipts {
+            tracing::warn!(
+                target: "reth::cli",
+                "Receipts of blocks before Byzantium can't be verified with the optimism executor"
+            );
+        }
+        block_executor!(provider_factory.chain_spec())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloy_rlp::Encodable;
     use reth_node_core::args::utils::SUPPORTED_CHAINS;
+    use reth_node_events::json::JsonEventLine;
+    use reth_primitives::{Block, Header, Withdrawals, DEV};
 
     #[test]
     fn parse_common_import_command_chain_args() {
@@ -242,4 +454,100 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn parse_verify_args() {
+        let args = ImportCommand::parse_from([
+            "reth",
+            "--verify",
+            "--verify.interval",
+            "100",
+            "--verify.no-state-roots",
+            ".",
+        ]);
+        assert_eq!(
+            args.verification_sampling(),
+            Some(VerificationSampling {
+                interval: 100,
+                last: DEFAULT_VERIFY_LAST,
+                state_root_interval: None
+            })
+        );
+        assert_eq!(ImportCommand::parse_from(["reth", "."]).verification_sampling(), None);
+
+        assert!(ImportCommand::try_parse_from(["reth", "--verify", "--no-state", "."]).is_err());
+        assert!(ImportCommand::try_parse_from(["reth", "--verify", "--verify.interval", "0", "."])
+            .is_err());
+        assert!(ImportCommand::try_parse_from(["reth", "--verify.last", "4", "."]).is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn import_with_json_output() {
+        let datadir = tempfile::tempdir().unwrap();
+
+        // a few empty blocks on top of the dev genesis
+        let mut parent = DEV.sealed_genesis_header();
+        let mut chain_file = Vec::new();
+        for _ in 0..3 {
+            let timestamp = parent.timestamp + 12;
+            let header = Header {
+                parent_hash: parent.hash(),
+                number: parent.number + 1,
+                timestamp,
+                base_fee_per_gas: parent
+                    .next_block_base_fee(DEV.base_fee_params_at_timestamp(timestamp)),
+                excess_blob_gas: parent.next_block_excess_blob_gas(),
+                ..parent.header().clone()
+            };
+            let block = Block {
+                header,
+                body: Vec::new(),
+                ommers: Vec::new(),
+                withdrawals: Some(Withdrawals::default()),
+                ..Default::default()
+            };
+            block.encode(&mut chain_file);
+            parent = block.header.seal_slow();
+        }
+        let path = datadir.path().join("chain.rlp");
+        std::fs::write(&path, chain_file).unwrap();
+
+        let command = ImportCommand::parse_from([
+            "reth",
+            "--chain",
+            "dev",
+            "--datadir",
+            datadir.path().to_str().unwrap(),
+            "--no-state",
+            "--output",
+            "json",
+            path.to_str().unwrap(),
+        ]);
+        assert!(command.is_json_output());
+
+        let writer = JsonEventWriter::new(Vec::new());
+        command.execute_with_json_writer(Some(writer.clone())).await.unwrap();
+
+        let output = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        let events = output
+            .lines()
+            .map(|line| serde_json::from_str::<JsonEventLine>(line).unwrap().event)
+            .collect::<Vec<_>>();
+
+        assert!(
+            matches!(events.first(), Some(JsonEvent::RunStarted { command, .. }) if command == "import")
+        );
+        assert!(events.iter().any(|event| matches!(
+            event,
+            JsonEvent::StageProgress { stage, checkpoint: 3, done: true, .. } if stage == "Headers"
+        )));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            JsonEvent::ChunkProgress { chunk: 1, blocks: 3, checkpoint: Some(3), .. }
+        )));
+        assert!(matches!(
+            events.last(),
+            Some(JsonEvent::Summary { status, .. }) if *status != RunStatus::Failed
+        ));
+    }
 }
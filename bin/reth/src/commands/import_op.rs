@@ -2,10 +2,8 @@
 //! file.
 
 use crate::{
-    commands::{
-        common::{AccessRights, Environment, EnvironmentArgs},
-        import::build_import_pipeline,
-    },
+    commands::common::{AccessRights, Environment, EnvironmentArgs},
+    macros::block_executor,
     version::SHORT_VERSION,
 };
 use clap::Parser;
@@ -15,8 +13,9 @@ use reth_db_api::transaction::DbTx;
 use reth_downloaders::file_client::{
     ChunkedFileReader, FileClient, DEFAULT_BYTE_LEN_CHUNK_CHAIN_FILE,
 };
+use reth_node_builder::setup::build_import_pipeline;
 use reth_optimism_primitives::bedrock_import::is_dup_tx;
-use reth_provider::StageCheckpointReader;
+use reth_provider::{ChainSpecProvider, StageCheckpointReader};
 use reth_prune_types::PruneModes;
 use reth_stages::StageId;
 use reth_static_file::StaticFileProducer;
@@ -90,11 +89,12 @@ impl ImportOpCommand {
             }
 
             let (mut pipeline, events) = build_import_pipeline(
-                &config,
+                &config.stages,
                 provider_factory.clone(),
-                &consensus,
+                consensus.clone(),
                 Arc::new(file_client),
                 StaticFileProducer::new(provider_factory.clone(), PruneModes::default()),
+                block_executor!(provider_factory.chain_spec()),
                 true,
             )
             .await?;
@@ -112,6 +112,7 @@ impl ImportOpCommand {
                 latest_block_number,
                 events,
                 provider_factory.db_ref().clone(),
+                None,
             ));
 
             // Run pipeline
@@ -0,0 +1,447 @@
+//! Spot checks of imported blocks against the chain file they were imported from.
+//!
+//! For a sample of the blocks of a chain file, the canonical hash and the receipts stored by the
+//! import are compared with the headers in the file, and for a sparser sample also the state root
+//! of the imported state. This catches a chain file and a database that don't match without
+//! executing any block a second time.
+
+use reth_db_api::database::Database;
+use reth_downloaders::file_client::FileClient;
+use reth_primitives::{BlockNumber, ChainSpec, GotExpected, Header, Receipt, B256};
+use reth_provider::{
+    BlockHashReader, ChainSpecProvider, ProviderFactory, ReceiptProvider, StageCheckpointReader,
+    StateRootProvider,
+};
+use reth_stages::StageId;
+use std::{fmt, ops::RangeInclusive};
+use tracing::{debug, error};
+
+/// The default interval of the block numbers that are verified.
+pub const DEFAULT_VERIFY_INTERVAL: u64 = 1_000;
+
+/// The default number of blocks at the end of a chunk that are verified.
+pub const DEFAULT_VERIFY_LAST: u64 = 16;
+
+/// The default interval of the block numbers whose state root is verified.
+pub const DEFAULT_VERIFY_STATE_ROOT_INTERVAL: u64 = 10_000;
+
+/// The blocks of a range that are verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerificationSampling {
+    /// Blocks whose number is a multiple of the interval are verified. No blocks are verified
+    /// by interval if zero.
+    pub interval: u64,
+    /// The number of blocks at the end of the range that are verified.
+    pub last: u64,
+    /// Blocks whose number is a multiple of the interval are verified including their state
+    /// root, as is the last block of the range.
+    ///
+    /// State roots are expensive to compute, so the interval is usually a multiple of
+    /// [`Self::interval`]. If `None`, no state roots are verified.
+    pub state_root_interval: Option<u64>,
+}
+
+impl Default for VerificationSampling {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_VERIFY_INTERVAL,
+            last: DEFAULT_VERIFY_LAST,
+            state_root_interval: Some(DEFAULT_VERIFY_STATE_ROOT_INTERVAL),
+        }
+    }
+}
+
+impl VerificationSampling {
+    /// Returns the numbers of the verified blocks of the range in ascending order, together with
+    /// whether their state root is verified.
+    ///
+    /// The genesis block is never verified, since it's initialized from the chain spec instead of
+    /// imported.
+    pub fn samples(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> impl Iterator<Item = (BlockNumber, bool)> + '_ {
+        let end = *range.end();
+        range.filter(|number| *number > 0).filter_map(move |number| {
+            let state_root = self
+                .state_root_interval
+                .is_some_and(|interval| is_multiple(number, interval) || number == end);
+            let sampled =
+                state_root || is_multiple(number, self.interval) || end - number < self.last;
+            sampled.then_some((number, state_root))
+        })
+    }
+}
+
+/// A value of a verified block that doesn't match the header in the chain file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationMismatch {
+    /// The block wasn't imported.
+    NotImported,
+    /// The canonical hash of the imported block.
+    Hash(GotExpected<B256>),
+    /// The receipts root of the stored receipts.
+    ReceiptsRoot(GotExpected<B256>),
+    /// The gas used by the stored receipts.
+    GasUsed(GotExpected<u64>),
+    /// The state root of the imported state after the block.
+    StateRoot(GotExpected<B256>),
+}
+
+impl fmt::Display for VerificationMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotImported => write!(f, "block was not imported"),
+            Self::Hash(diff) => write!(f, "block hash mismatch: {diff}"),
+            Self::ReceiptsRoot(diff) => write!(f, "receipts root mismatch: {diff}"),
+            Self::GasUsed(diff) => write!(f, "gas used mismatch: {diff}"),
+            Self::StateRoot(diff) => write!(f, "state root mismatch: {diff}"),
+        }
+    }
+}
+
+/// The outcome of verifying a single block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedBlock {
+    /// The number of the block.
+    pub number: BlockNumber,
+    /// The hash of the block in the chain file.
+    pub hash: B256,
+    /// Whether the receipts root was verified.
+    ///
+    /// Receipts of blocks before Byzantium commit to the state root after each transaction, which
+    /// isn't stored, so only their gas used is verified.
+    pub receipts_root: bool,
+    /// Whether the state root was verified.
+    pub state_root: bool,
+    /// The values that don't match the header in the chain file.
+    pub mismatches: Vec<VerificationMismatch>,
+}
+
+impl VerifiedBlock {
+    /// Returns `true` if all verified values match the header in the chain file.
+    pub fn is_valid(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// The blocks verified by [`verify_imported_blocks`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerificationReport {
+    /// The verified blocks in ascending order.
+    ///
+    /// Verification stops at the first block that doesn't match, so only the last block can be
+    /// invalid.
+    pub blocks: Vec<VerifiedBlock>,
+}
+
+impl VerificationReport {
+    /// Returns `true` if all verified blocks match the chain file.
+    pub fn is_valid(&self) -> bool {
+        self.blocks.iter().all(VerifiedBlock::is_valid)
+    }
+
+    /// Returns the first block that doesn't match the chain file, if any.
+    pub fn first_mismatch(&self) -> Option<&VerifiedBlock> {
+        self.blocks.iter().find(|block| !block.is_valid())
+    }
+
+    /// Returns the number of blocks whose state root was verified.
+    pub fn state_roots(&self) -> usize {
+        self.blocks.iter().filter(|block| block.state_root).count()
+    }
+}
+
+/// Verifies a sample of the blocks of the file client against the imported chain.
+///
+/// The canonical hash of each sampled block is compared with the hash of the block in the file,
+/// and the receipts root and the gas used of the stored receipts are compared with its header. If
+/// sampled, the state root of the imported state after the block is computed from the hashed state
+/// and its changesets, and compared as well. Blocks above the checkpoint of the execution stage
+/// count as not imported. Verification stops at the first block that doesn't match.
+pub fn verify_imported_blocks<DB: Database>(
+    provider_factory: &ProviderFactory<DB>,
+    file_client: &FileClient,
+    sampling: &VerificationSampling,
+) -> eyre::Result<VerificationReport> {
+    let mut report = VerificationReport::default();
+    let (Some(min_block), Some(max_block)) = (file_client.min_block(), file_client.max_block())
+    else {
+        return Ok(report)
+    };
+
+    let provider = provider_factory.provider()?;
+    let last_executed =
+        provider.get_stage_checkpoint(StageId::Execution)?.unwrap_or_default().block_number;
+    let chain_spec = provider_factory.chain_spec();
+    for (number, state_root) in sampling.samples(min_block..=max_block) {
+        let block = file_client
+            .sealed_block(number)
+            .ok_or_else(|| eyre::eyre!("block {number} is missing from the chain file"))?;
+        let hash = block.hash();
+
+        let mut verified = VerifiedBlock {
+            number,
+            hash,
+            receipts_root: false,
+            state_root: false,
+            mismatches: Vec::new(),
+        };
+        match provider.block_hash(number)? {
+            None => verified.mismatches.push(VerificationMismatch::NotImported),
+            Some(canonical) if canonical != hash => verified
+                .mismatches
+                .push(VerificationMismatch::Hash(GotExpected { got: canonical, expected: hash })),
+            Some(_) if number > last_executed => {
+                verified.mismatches.push(VerificationMismatch::NotImported)
+            }
+            Some(_) => {
+                let receipts = provider
+                    .receipts_by_block(number.into())?
+                    .ok_or_else(|| eyre::eyre!("receipts of block {number} are missing"))?;
+                verified.receipts_root = chain_spec.is_byzantium_active_at_block(number);
+                verified.mismatches = verify_receipts(
+                    &chain_spec,
+                    block.header.header(),
+                    &receipts,
+                    verified.receipts_root,
+                );
+                if state_root {
+                    verified.state_root = true;
+                    let got = provider_factory
+                        .history_by_block_number(number)?
+                        .state_root(&Default::default())?;
+                    if got != block.header.state_root {
+                        verified.mismatches.push(VerificationMismatch::StateRoot(GotExpected {
+                            got,
+                            expected: block.header.state_root,
+                        }));
+                    }
+                }
+            }
+        }
+
+        if verified.is_valid() {
+            debug!(target: "reth::cli", number, state_root, "Verified imported block");
+        } else {
+            for mismatch in &verified.mismatches {
+                error!(target: "reth::cli", number, %hash, %mismatch, "Imported block doesn't match the chain file");
+            }
+        }
+
+        let valid = verified.is_valid();
+        report.blocks.push(verified);
+        if !valid {
+            break
+        }
+    }
+
+    Ok(report)
+}
+
+/// Compares the stored receipts of a block with its header in the chain file.
+fn verify_receipts(
+    chain_spec: &ChainSpec,
+    header: &Header,
+    receipts: &[Receipt],
+    verify_receipts_root: bool,
+) -> Vec<VerificationMismatch> {
+    let mut mismatches = Vec::new();
+    if verify_receipts_root {
+        let receipts_root = receipts_root(chain_spec, header, receipts);
+        if receipts_root != header.receipts_root {
+            mismatches.push(VerificationMismatch::ReceiptsRoot(GotExpected {
+                got: receipts_root,
+                expected: header.receipts_root,
+            }));
+        }
+    }
+    let gas_used = receipts.last().map_or(0, |receipt| receipt.cumulative_gas_used);
+    if gas_used != header.gas_used {
+        mismatches.push(VerificationMismatch::GasUsed(GotExpected {
+            got: gas_used,
+            expected: header.gas_used,
+        }));
+    }
+    mismatches
+}
+
+/// Returns `true` if the block number is a multiple of the non-zero interval.
+const fn is_multiple(number: BlockNumber, interval: u64) -> bool {
+    interval != 0 && number % interval == 0
+}
+
+/// Computes the receipts root of the block.
+#[cfg(not(feature = "optimism"))]
+fn receipts_root(_chain_spec: &ChainSpec, _header: &Header, receipts: &[Receipt]) -> B256 {
+    reth_primitives::proofs::calculate_receipt_root_no_memo(&receipts.iter().collect::<Vec<_>>())
+}
+
+/// Computes the receipts root of the block.
+#[cfg(feature = "optimism")]
+fn receipts_root(chain_spec: &ChainSpec, header: &Header, receipts: &[Receipt]) -> B256 {
+    reth_primitives::proofs::calculate_receipt_root_no_memo_optimism(
+        &receipts.iter().collect::<Vec<_>>(),
+        chain_spec,
+        header.timestamp,
+    )
+}
+
+#[cfg(all(test, not(feature = "optimism")))]
+mod tests {
+    use super::*;
+    use alloy_rlp::Encodable;
+    use reth_beacon_consensus::EthBeaconConsensus;
+    use reth_config::config::{ExecutionConfig, StageConfig};
+    use reth_db::tables;
+    use reth_db_api::transaction::DbTxMut;
+    use reth_db_common::init::init_genesis;
+    use reth_evm_ethereum::test_utils::ChainGenerator;
+    use reth_node_builder::setup::build_import_pipeline;
+    use reth_node_ethereum::EthExecutorProvider;
+    use reth_primitives::{
+        proofs::calculate_receipt_root_no_memo, Account, ChainSpecBuilder, SealedBlock, U256,
+    };
+    use reth_provider::{test_utils::create_test_provider_factory_with_chain_spec, BlockNumReader};
+    use reth_prune_types::PruneModes;
+    use reth_static_file::StaticFileProducer;
+    use std::{path::Path, sync::Arc};
+
+    /// Writes the blocks to a chain file.
+    fn write_chain_file(path: &Path, blocks: &[SealedBlock]) {
+        let mut chain_file = Vec::new();
+        for block in blocks {
+            block.clone().unseal().encode(&mut chain_file);
+        }
+        std::fs::write(path, chain_file).unwrap();
+    }
+
+    /// Runs the import pipeline once over the chain file and returns whether it unwound.
+    async fn import_chain_file<DB>(provider_factory: &ProviderFactory<DB>, path: &Path) -> bool
+    where
+        DB: Database + Clone + Unpin + 'static,
+    {
+        let chain_spec = provider_factory.chain_spec();
+        let file_client = FileClient::new(path).await.unwrap();
+        let tip = file_client.tip().unwrap();
+        // commit after every block, so the blocks before a bad block stay imported
+        let stage_config = StageConfig {
+            execution: ExecutionConfig { max_blocks: Some(1), ..Default::default() },
+            ..Default::default()
+        };
+        let (mut pipeline, _events) = build_import_pipeline(
+            &stage_config,
+            provider_factory.clone(),
+            Arc::new(EthBeaconConsensus::new(chain_spec.clone())),
+            Arc::new(file_client),
+            StaticFileProducer::new(provider_factory.clone(), PruneModes::default()),
+            EthExecutorProvider::ethereum(chain_spec),
+            false,
+        )
+        .await
+        .unwrap();
+        pipeline.set_tip(tip);
+        pipeline.run_loop().await.unwrap().is_unwind()
+    }
+
+    #[test]
+    fn samples_interval_last_and_state_roots() {
+        let sampling = VerificationSampling { interval: 4, last: 2, state_root_interval: Some(8) };
+        assert_eq!(
+            sampling.samples(0..=12).collect::<Vec<_>>(),
+            vec![(4, false), (8, true), (11, false), (12, true)]
+        );
+        assert_eq!(sampling.samples(5..=7).collect::<Vec<_>>(), vec![(6, false), (7, true)]);
+
+        let sampling = VerificationSampling { interval: 4, last: 0, state_root_interval: None };
+        assert_eq!(sampling.samples(1..=10).collect::<Vec<_>>(), vec![(4, false), (8, false)]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn verification_catches_corrupted_receipt() {
+        let mut generator = ChainGenerator::new(
+            &Arc::new(ChainSpecBuilder::mainnet().shanghai_activated().build()),
+            1,
+            2,
+        );
+        let blocks =
+            generator.generate_blocks(12).into_iter().map(|block| block.block).collect::<Vec<_>>();
+        let chain_spec = generator.chain_spec();
+        let sampling = VerificationSampling { interval: 4, last: 2, state_root_interval: Some(8) };
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain.rlp");
+        write_chain_file(&path, &blocks);
+
+        // corrupt a receipt of the last block in the chain file
+        let mut receipts = generator.receipts()[11].clone();
+        receipts[0].cumulative_gas_used += 1;
+        let mut corrupted = blocks.clone();
+        let mut header = corrupted[11].header.header().clone();
+        header.receipts_root = calculate_receipt_root_no_memo(&receipts.iter().collect::<Vec<_>>());
+        corrupted[11].header = header.seal_slow();
+        let corrupted_path = dir.path().join("corrupted.rlp");
+        write_chain_file(&corrupted_path, &corrupted);
+
+        // the import of the chain file matches it
+        let provider_factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+        init_genesis(provider_factory.clone()).unwrap();
+        assert!(!import_chain_file(&provider_factory, &path).await);
+        assert_eq!(provider_factory.last_block_number().unwrap(), 12);
+
+        let file_client = FileClient::new(&path).await.unwrap();
+        let report = verify_imported_blocks(&provider_factory, &file_client, &sampling).unwrap();
+        assert!(report.is_valid());
+        assert_eq!(
+            report.blocks.iter().map(|block| block.number).collect::<Vec<_>>(),
+            vec![4, 8, 11, 12]
+        );
+        assert!(report.blocks.iter().all(|block| block.receipts_root));
+        assert_eq!(report.state_roots(), 2);
+
+        // but not the corrupted file
+        let file_client = FileClient::new(&corrupted_path).await.unwrap();
+        let report = verify_imported_blocks(&provider_factory, &file_client, &sampling).unwrap();
+        assert_eq!(
+            report.first_mismatch().unwrap().mismatches,
+            vec![VerificationMismatch::Hash(GotExpected {
+                got: blocks[11].hash(),
+                expected: corrupted[11].hash(),
+            })]
+        );
+
+        // a corrupted imported state is caught at the first state root sample
+        let provider_rw = provider_factory.provider_rw().unwrap();
+        provider_rw
+            .tx_ref()
+            .put::<tables::HashedAccounts>(
+                B256::with_last_byte(1),
+                Account { balance: U256::from(1), ..Default::default() },
+            )
+            .unwrap();
+        provider_rw.commit().unwrap();
+        let file_client = FileClient::new(&path).await.unwrap();
+        let report = verify_imported_blocks(&provider_factory, &file_client, &sampling).unwrap();
+        let mismatch = report.first_mismatch().unwrap();
+        assert_eq!(mismatch.number, 8);
+        assert!(matches!(mismatch.mismatches[..], [VerificationMismatch::StateRoot(_)]));
+
+        // the import of the corrupted file stops before the corrupted block, which is caught at the
+        // first sample after the imported blocks
+        let provider_factory = create_test_provider_factory_with_chain_spec(chain_spec);
+        init_genesis(provider_factory.clone()).unwrap();
+        assert!(import_chain_file(&provider_factory, &corrupted_path).await);
+        assert_eq!(provider_factory.last_block_number().unwrap(), 11);
+
+        let file_client = FileClient::new(&corrupted_path).await.unwrap();
+        let report = verify_imported_blocks(&provider_factory, &file_client, &sampling).unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.blocks.iter().map(|block| block.number).collect::<Vec<_>>(),
+            vec![4, 8, 11, 12]
+        );
+        let mismatch = report.first_mismatch().unwrap();
+        assert_eq!(mismatch.number, 12);
+        assert_eq!(mismatch.mismatches, vec![VerificationMismatch::NotImported]);
+    }
+}
@@ -171,6 +171,28 @@ impl Command {
                 )?;
                 insert_genesis_header::<DatabaseEnv>(tx, &static_file_provider, self.env.chain)?;
             }
+            StageEnum::TransactionHistory => {
+                tx.clear::<tables::AddressTransactions>()?;
+                tx.put::<tables::StageCheckpoints>(
+                    StageId::TransactionHistory.to_string(),
+                    Default::default(),
+                )?;
+                tx.delete::<tables::StageCheckpointProgresses>(
+                    StageId::TransactionHistory.to_string(),
+                    None,
+                )?;
+            }
+            StageEnum::HeaderAccumulator => {
+                tx.clear::<tables::HeaderAccumulator>()?;
+                tx.put::<tables::StageCheckpoints>(
+                    StageId::HeaderAccumulator.to_string(),
+                    Default::default(),
+                )?;
+                tx.delete::<tables::StageCheckpointProgresses>(
+                    StageId::HeaderAccumulator.to_string(),
+                    None,
+                )?;
+            }
         }
 
         tx.put::<tables::StageCheckpoints>(StageId::Finish.to_string(), Default::default())?;
@@ -5,6 +5,7 @@ use reth_cli_runner::CliContext;
 
 pub mod drop;
 pub mod dump;
+pub mod plan;
 pub mod run;
 pub mod unwind;
 
@@ -31,9 +32,19 @@ pub enum Subcommands {
     Dump(dump::Command),
     /// Unwinds a certain block range, deleting it from the database.
     Unwind(unwind::Command),
+    /// Plans a pipeline run to the target block without writing anything.
+    Plan(plan::Command),
 }
 
 impl Command {
+    /// Returns `true` if the subcommand reports its progress as JSON events on stdout.
+    pub const fn is_json_output(&self) -> bool {
+        match &self.command {
+            Subcommands::Run(command) => command.is_json_output(),
+            _ => false,
+        }
+    }
+
     /// Execute `stage` command
     pub async fn execute(self, ctx: CliContext) -> eyre::Result<()> {
         match self.command {
@@ -41,6 +52,7 @@ impl Command {
             Subcommands::Drop(command) => command.execute().await,
             Subcommands::Dump(command) => command.execute().await,
             Subcommands::Unwind(command) => command.execute().await,
+            Subcommands::Plan(command) => command.execute().await,
         }
     }
 }
@@ -0,0 +1,89 @@
+//! Planning a pipeline run
+
+use clap::Parser;
+use reth_beacon_consensus::EthBeaconConsensus;
+use reth_config::Config;
+use reth_consensus::Consensus;
+use reth_db_api::database::Database;
+use reth_downloaders::{bodies::noop::NoopBodiesDownloader, headers::noop::NoopHeaderDownloader};
+use reth_primitives::{BlockNumber, B256};
+use reth_provider::{ChainSpecProvider, ProviderFactory};
+use reth_prune_types::PruneModes;
+use reth_stages::{sets::DefaultStages, Pipeline};
+use reth_static_file::StaticFileProducer;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+use crate::{
+    commands::common::{AccessRights, Environment, EnvironmentArgs},
+    macros::block_executor,
+};
+
+/// `reth stage plan` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    #[command(flatten)]
+    env: EnvironmentArgs,
+
+    /// The block number the pipeline would sync or unwind to.
+    #[arg(long, value_name = "BLOCK_NUMBER")]
+    target: BlockNumber,
+}
+
+impl Command {
+    /// Execute `stage plan` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let Environment { provider_factory, config, .. } = self.env.init(AccessRights::RO)?;
+
+        let pipeline = Self::build_pipeline(config, provider_factory)?;
+        let plan = pipeline.plan(self.target)?;
+        print!("{plan}");
+
+        if !plan.is_ok() {
+            eyre::bail!("The pipeline run to block {} would fail", self.target)
+        }
+
+        Ok(())
+    }
+
+    fn build_pipeline<DB: Database + 'static>(
+        config: Config,
+        provider_factory: ProviderFactory<Arc<DB>>,
+    ) -> eyre::Result<Pipeline<Arc<DB>>> {
+        let consensus: Arc<dyn Consensus> =
+            Arc::new(EthBeaconConsensus::new(provider_factory.chain_spec()));
+        let prune_modes = config.prune.clone().map(|prune| prune.segments).unwrap_or_default();
+
+        let (tip_tx, tip_rx) = watch::channel(B256::ZERO);
+        let executor = block_executor!(provider_factory.chain_spec());
+
+        let pipeline = Pipeline::builder()
+            .with_tip_sender(tip_tx)
+            .add_stages(DefaultStages::new(
+                provider_factory.clone(),
+                tip_rx,
+                consensus,
+                NoopHeaderDownloader::default(),
+                NoopBodiesDownloader::default(),
+                executor,
+                config.stages,
+                prune_modes,
+            ))
+            .build(
+                provider_factory.clone(),
+                StaticFileProducer::new(provider_factory, PruneModes::default()),
+            );
+        Ok(pipeline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_plan() {
+        let cmd = Command::parse_from(["reth", "--datadir", "dir", "--target", "100"]);
+        assert_eq!(cmd.target, 100);
+    }
+}
@@ -3,7 +3,7 @@
 //! Stage debugging tool
 
 use crate::{
-    args::{get_secret_key, NetworkArgs, StageEnum},
+    args::{get_secret_key, NetworkArgs, OutputFormat, StageEnum},
     commands::common::{AccessRights, Environment, EnvironmentArgs},
     macros::block_executor,
     prometheus_exporter,
@@ -11,21 +11,25 @@ use crate::{
 use clap::Parser;
 use reth_beacon_consensus::EthBeaconConsensus;
 use reth_cli_runner::CliContext;
-use reth_config::config::{HashingConfig, SenderRecoveryConfig, TransactionLookupConfig};
+use reth_config::config::{
+    HashingConfig, HeaderAccumulatorConfig, SenderRecoveryConfig, TransactionHistoryConfig,
+    TransactionLookupConfig,
+};
 use reth_downloaders::bodies::bodies::BodiesDownloaderBuilder;
 use reth_exex::ExExManagerHandle;
+use reth_node_events::json::{duration_ms, JsonEvent, JsonEventWriter, RunStatus};
 use reth_provider::{
     ChainSpecProvider, StageCheckpointReader, StageCheckpointWriter, StaticFileProviderFactory,
 };
 use reth_stages::{
     stages::{
         AccountHashingStage, BodyStage, ExecutionStage, ExecutionStageThresholds,
-        IndexAccountHistoryStage, IndexStorageHistoryStage, MerkleStage, SenderRecoveryStage,
-        StorageHashingStage, TransactionLookupStage,
+        HeaderAccumulatorStage, IndexAccountHistoryStage, IndexStorageHistoryStage, MerkleStage,
+        SenderRecoveryStage, StorageHashingStage, TransactionHistoryStage, TransactionLookupStage,
     },
     ExecInput, ExecOutput, Stage, StageExt, UnwindInput, UnwindOutput,
 };
-use std::{any::Any, net::SocketAddr, sync::Arc, time::Instant};
+use std::{any::Any, collections::BTreeMap, net::SocketAddr, sync::Arc, time::Instant};
 use tracing::*;
 
 /// `reth stage` command
@@ -76,11 +80,22 @@ pub struct Command {
     #[arg(long)]
     checkpoints: bool,
 
+    /// The format the stage progress is reported in.
+    ///
+    /// With `json`, one event per line is written to stdout and the logs are written to stderr.
+    #[arg(long, value_name = "FORMAT", default_value_t = OutputFormat::Human)]
+    output: OutputFormat,
+
     #[command(flatten)]
     network: NetworkArgs,
 }
 
 impl Command {
+    /// Returns `true` if the progress is reported as JSON events on stdout.
+    pub const fn is_json_output(&self) -> bool {
+        self.output.is_json()
+    }
+
     /// Execute `stage` command
     pub async fn execute(self, ctx: CliContext) -> eyre::Result<()> {
         // Raise the fd limit of the process.
@@ -227,6 +242,23 @@ impl Command {
                     )),
                     None,
                 ),
+                StageEnum::TransactionHistory => (
+                    Box::new(TransactionHistoryStage::new(
+                        TransactionHistoryConfig {
+                            commit_threshold: batch_size,
+                            ..config.stages.transaction_history
+                        },
+                        prune_modes.transaction_history,
+                    )),
+                    None,
+                ),
+                StageEnum::HeaderAccumulator => (
+                    Box::new(HeaderAccumulatorStage::new(HeaderAccumulatorConfig {
+                        commit_threshold: batch_size,
+                        ..config.stages.header_accumulator
+                    })),
+                    None,
+                ),
                 _ => return Ok(()),
             };
         if let Some(unwind_stage) = &unwind_stage {
@@ -234,56 +266,119 @@ impl Command {
         }
 
         let checkpoint = provider_rw.get_stage_checkpoint(exec_stage.id())?.unwrap_or_default();
+        let stage_id = exec_stage.id().to_string();
+
+        let json = self.output.is_json().then(JsonEventWriter::stdout);
+        if let Some(json) = &json {
+            json.emit(JsonEvent::RunStarted {
+                command: "stage run".to_string(),
+                config: BTreeMap::from([
+                    ("stage".to_string(), stage_id.clone().into()),
+                    ("from".to_string(), self.from.into()),
+                    ("to".to_string(), self.to.into()),
+                    ("batch_size".to_string(), batch_size.into()),
+                    ("skip_unwind".to_string(), self.skip_unwind.into()),
+                    ("commit".to_string(), self.commit.into()),
+                    ("checkpoints".to_string(), self.checkpoints.into()),
+                ]),
+            })?;
+        }
+
+        let start = Instant::now();
+        let result = async {
+            let unwind_stage = unwind_stage.as_mut().unwrap_or(&mut exec_stage);
 
-        let unwind_stage = unwind_stage.as_mut().unwrap_or(&mut exec_stage);
+            let mut unwind = UnwindInput {
+                checkpoint: checkpoint.with_block_number(self.to),
+                unwind_to: self.from,
+                bad_block: None,
+            };
 
-        let mut unwind = UnwindInput {
-            checkpoint: checkpoint.with_block_number(self.to),
-            unwind_to: self.from,
-            bad_block: None,
-        };
+            if !self.skip_unwind {
+                while unwind.checkpoint.block_number > self.from {
+                    let UnwindOutput { checkpoint } = unwind_stage.unwind(&provider_rw, unwind)?;
+                    unwind.checkpoint = checkpoint;
 
-        if !self.skip_unwind {
-            while unwind.checkpoint.block_number > self.from {
-                let UnwindOutput { checkpoint } = unwind_stage.unwind(&provider_rw, unwind)?;
-                unwind.checkpoint = checkpoint;
+                    if self.checkpoints {
+                        provider_rw.save_stage_checkpoint(unwind_stage.id(), checkpoint)?;
+                    }
 
-                if self.checkpoints {
-                    provider_rw.save_stage_checkpoint(unwind_stage.id(), checkpoint)?;
+                    if self.commit {
+                        provider_rw.commit()?;
+                        provider_rw = provider_factory.provider_rw()?;
+                    }
+
+                    if let Some(json) = &json {
+                        json.emit(JsonEvent::StageUnwound {
+                            stage: unwind_stage.id().to_string(),
+                            checkpoint: checkpoint.block_number,
+                        })?;
+                    }
                 }
+            }
+
+            let mut input = ExecInput {
+                target: Some(self.to),
+                checkpoint: Some(checkpoint.with_block_number(self.from)),
+            };
+
+            info!(target: "reth::cli", stage = %self.stage, "Executing stage");
+            loop {
+                let batch_start = Instant::now();
+                let previous = input.checkpoint().block_number;
 
+                exec_stage.execute_ready(input).await?;
+                let ExecOutput { checkpoint, done } = exec_stage.execute(&provider_rw, input)?;
+
+                input.checkpoint = Some(checkpoint);
+
+                if self.checkpoints {
+                    provider_rw.save_stage_checkpoint(exec_stage.id(), checkpoint)?;
+                }
                 if self.commit {
                     provider_rw.commit()?;
                     provider_rw = provider_factory.provider_rw()?;
                 }
-            }
-        }
-
-        let mut input = ExecInput {
-            target: Some(self.to),
-            checkpoint: Some(checkpoint.with_block_number(self.from)),
-        };
 
-        let start = Instant::now();
-        info!(target: "reth::cli", stage = %self.stage, "Executing stage");
-        loop {
-            exec_stage.execute_ready(input).await?;
-            let ExecOutput { checkpoint, done } = exec_stage.execute(&provider_rw, input)?;
-
-            input.checkpoint = Some(checkpoint);
+                if let Some(json) = &json {
+                    json.emit(JsonEvent::StageProgress {
+                        stage: stage_id.clone(),
+                        blocks: checkpoint.block_number.saturating_sub(previous),
+                        checkpoint: checkpoint.block_number,
+                        target: Some(self.to),
+                        done,
+                        duration_ms: duration_ms(batch_start.elapsed()),
+                    })?;
+                }
 
-            if self.checkpoints {
-                provider_rw.save_stage_checkpoint(exec_stage.id(), checkpoint)?;
-            }
-            if self.commit {
-                provider_rw.commit()?;
-                provider_rw = provider_factory.provider_rw()?;
+                if done {
+                    break
+                }
             }
 
-            if done {
-                break
+            Ok::<_, eyre::Error>(input.checkpoint().block_number)
+        }
+        .await;
+
+        if let Some(json) = &json {
+            if let Err(err) = &result {
+                json.emit(JsonEvent::Warning {
+                    stage: Some(stage_id.clone()),
+                    message: err.to_string(),
+                })?;
             }
+            json.emit(JsonEvent::Summary {
+                blocks: result
+                    .as_ref()
+                    .map_or(0, |checkpoint| checkpoint.saturating_sub(self.from)),
+                transactions: None,
+                gas: None,
+                duration_ms: duration_ms(start.elapsed()),
+                status: if result.is_ok() { RunStatus::Success } else { RunStatus::Failed },
+            })?;
         }
+        result?;
+
         info!(target: "reth::cli", stage = %self.stage, time = ?start.elapsed(), "Finished stage");
 
         Ok(())
@@ -17,7 +17,7 @@ use reth_prune_types::PruneModes;
 use reth_stages::{
     sets::DefaultStages,
     stages::{ExecutionStage, ExecutionStageThresholds},
-    Pipeline, StageSet,
+    unwind_horizon, Pipeline, PipelineError, StageId, StageSet,
 };
 use reth_static_file::StaticFileProducer;
 use std::{ops::RangeInclusive, sync::Arc};
@@ -38,6 +38,13 @@ pub struct Command {
     #[command(flatten)]
     network: NetworkArgs,
 
+    /// Unwind stages below the blocks whose data they require to unwind was pruned.
+    ///
+    /// Only allowed if every stage that would be unwound below its unwind horizon can handle a
+    /// lossy unwind.
+    #[arg(long)]
+    force: bool,
+
     #[command(subcommand)]
     command: Subcommands,
 }
@@ -72,6 +79,15 @@ impl Command {
             info!(target: "reth::cli", ?range, "Executing a database unwind.");
             let provider = provider_factory.provider_rw()?;
 
+            // The database unwind reverts the state and history with the changesets, so it can't
+            // unwind the blocks whose changesets were pruned.
+            let target = (*range.start()).saturating_sub(1);
+            if let Some(horizon) =
+                unwind_horizon(&*provider, StageId::ALL)?.filter(|horizon| horizon.block > target)
+            {
+                return Err(PipelineError::UnwindBelowHorizon { target, horizon }.into())
+            }
+
             let _ = provider
                 .take_block_and_execution_range(range.clone())
                 .map_err(|err| eyre::eyre!("Transaction error on unwind: {err}"))?;
@@ -107,6 +123,7 @@ impl Command {
 
         let pipeline = Pipeline::builder()
             .with_tip_sender(tip_tx)
+            .with_lossy_unwind(self.force)
             .add_stages(
                 DefaultStages::new(
                     provider_factory.clone(),
@@ -189,5 +206,9 @@ mod tests {
 
         let cmd = Command::parse_from(["reth", "--datadir", "dir", "num-blocks", "100"]);
         assert_eq!(cmd.command, Subcommands::NumBlocks { amount: 100 });
+        assert!(!cmd.force);
+
+        let cmd = Command::parse_from(["reth", "--datadir", "dir", "--force", "to-block", "100"]);
+        assert!(cmd.force);
     }
 }
@@ -2,6 +2,7 @@
 
 use reth_consensus::ConsensusError;
 use reth_execution_errors::{BlockExecutionError, BlockValidationError};
+use reth_execution_types::TransactionOutcome;
 use reth_primitives::{BlockHash, BlockNumber, SealedBlock};
 pub use reth_storage_errors::provider::ProviderError;
 
@@ -47,6 +48,16 @@ pub enum BlockchainTreeError {
     /// Thrown when trying to access genesis parent.
     #[error("genesis block has no parent")]
     GenesisBlockHasNoParent,
+    /// Thrown if a disconnected block is too far above the canonical tip to be buffered.
+    #[error("block #{block_number} is more than {max_distance} blocks above tip #{canonical_tip}")]
+    BlockTooFarAhead {
+        /// The number of the block.
+        block_number: BlockNumber,
+        /// The number of the canonical tip.
+        canonical_tip: BlockNumber,
+        /// The max distance above the canonical tip at which blocks are buffered.
+        max_distance: u64,
+    },
 }
 
 /// Canonical Errors
@@ -216,6 +227,15 @@ pub enum InsertBlockErrorKind {
     /// Block violated consensus rules.
     #[error(transparent)]
     Consensus(#[from] ConsensusError),
+    /// The result of the block execution violated consensus rules, e.g. the receipts root
+    /// differs.
+    #[error("{error}")]
+    PostExecution {
+        /// The violated consensus rule.
+        error: ConsensusError,
+        /// The index and outcome of each transaction in the block that reverted or halted.
+        unsuccessful: Vec<(usize, TransactionOutcome)>,
+    },
     /// Block execution failed.
     #[error(transparent)]
     Execution(#[from] BlockExecutionError),
@@ -244,7 +264,7 @@ impl InsertBlockErrorKind {
 
     /// Returns true if the error is a consensus error
     pub const fn is_consensus_error(&self) -> bool {
-        matches!(self, Self::Consensus(_))
+        matches!(self, Self::Consensus(_) | Self::PostExecution { .. })
     }
 
     /// Returns true if this error is a state root error
@@ -283,7 +303,7 @@ impl InsertBlockErrorKind {
     #[allow(clippy::match_same_arms)]
     pub const fn is_invalid_block(&self) -> bool {
         match self {
-            Self::SenderRecovery | Self::Consensus(_) => true,
+            Self::SenderRecovery | Self::Consensus(_) | Self::PostExecution { .. } => true,
             // other execution errors that are considered internal errors
             Self::Execution(err) => {
                 match err {
@@ -293,6 +313,7 @@ impl InsertBlockErrorKind {
                     }
                     // these are internal errors, not caused by an invalid block
                     BlockExecutionError::LatestBlock(_) |
+                    BlockExecutionError::Cancelled { .. } |
                     BlockExecutionError::Pruning(_) |
                     BlockExecutionError::CanonicalRevert { .. } |
                     BlockExecutionError::CanonicalCommit { .. } |
@@ -311,7 +332,8 @@ impl InsertBlockErrorKind {
                     BlockchainTreeError::BlockNumberNotFoundInChain { .. } |
                     BlockchainTreeError::BlockHashNotFoundInChain { .. } |
                     BlockchainTreeError::BlockBufferingFailed { .. } |
-                    BlockchainTreeError::GenesisBlockHasNoParent => false,
+                    BlockchainTreeError::GenesisBlockHasNoParent |
+                    BlockchainTreeError::BlockTooFarAhead { .. } => false,
                 }
             }
             Self::Provider(_) | Self::Internal(_) => {
@@ -345,6 +367,20 @@ impl InsertBlockErrorKind {
         matches!(self, Self::Execution(_))
     }
 
+    /// Returns true if the execution of the block was cancelled.
+    ///
+    /// The block is not known to be invalid in this case.
+    pub const fn is_execution_cancelled(&self) -> bool {
+        matches!(self, Self::Execution(BlockExecutionError::Cancelled { .. }))
+    }
+
+    /// Returns true if the block was not buffered because it is too far above the canonical tip.
+    ///
+    /// The block is not known to be invalid in this case.
+    pub const fn is_block_too_far_ahead(&self) -> bool {
+        matches!(self, Self::Tree(BlockchainTreeError::BlockTooFarAhead { .. }))
+    }
+
     /// Returns true if the error is an internal error
     pub const fn is_internal(&self) -> bool {
         matches!(self, Self::Internal(_))
@@ -361,7 +397,16 @@ impl InsertBlockErrorKind {
     /// Returns the error if it is a consensus error
     pub const fn as_consensus_error(&self) -> Option<&ConsensusError> {
         match self {
-            Self::Consensus(err) => Some(err),
+            Self::Consensus(err) | Self::PostExecution { error: err, .. } => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Returns the index and outcome of each transaction that reverted or halted, if the block
+    /// was executed before it was found to be invalid.
+    pub fn unsuccessful_transactions(&self) -> Option<&[(usize, TransactionOutcome)]> {
+        match self {
+            Self::PostExecution { unsuccessful, .. } => Some(unsuccessful),
             _ => None,
         }
     }
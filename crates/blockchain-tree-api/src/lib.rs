@@ -9,12 +9,13 @@
 
 use self::error::CanonicalError;
 use crate::error::InsertBlockError;
+use reth_execution_types::Chain;
 use reth_primitives::{
     BlockHash, BlockNumHash, BlockNumber, Receipt, SealedBlock, SealedBlockWithSenders,
     SealedHeader,
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, time::Duration};
 
 pub mod error;
 
@@ -113,6 +114,27 @@ pub trait BlockchainTreeEngine: BlockchainTreeViewer + Send + Sync {
     ///
     /// Returns `Ok` if the blocks were canonicalized, or if the blocks were already canonical.
     fn make_canonical(&self, block_hash: BlockHash) -> Result<CanonicalOutcome, CanonicalError>;
+
+    /// Unwind the canonical chain down to and excluding `unwind_to` by removing all blocks above
+    /// it from the database.
+    ///
+    /// Unlike a reorg, the reverted blocks are not reinserted into the tree, and all sidechains
+    /// that no longer connect to the canonical chain are dropped. Canonical state subscribers are
+    /// notified about the reverted blocks.
+    ///
+    /// This only reverts blocks that were committed by the tree. Blocks that were already written
+    /// to static files can only be unwound by the pipeline, so the chain is not unwound below the
+    /// highest of them.
+    ///
+    /// Returns the number of the new canonical tip.
+    fn unwind_canonical_chain(&self, unwind_to: BlockNumber)
+        -> Result<BlockNumber, CanonicalError>;
+
+    /// Notifies canonical state subscribers about blocks that were removed from the canonical
+    /// chain without the tree, for example by unwinding the pipeline.
+    ///
+    /// Subscribers are notified the same way as for [`Self::unwind_canonical_chain`].
+    fn notify_canonical_chain_unwound(&self, unwound: Chain) -> Result<(), CanonicalError>;
 }
 
 /// Represents the kind of validation that should be performed when inserting a block.
@@ -248,13 +270,38 @@ impl BlockAttachment {
 /// How a payload was inserted if it was valid.
 ///
 /// If the payload was valid, but has already been seen, [`InsertPayloadOk::AlreadySeen(_)`] is
-/// returned, otherwise [`InsertPayloadOk::Inserted(_)`] is returned.
+/// returned, otherwise [`InsertPayloadOk::Inserted(_)`] is returned. If the tree is congested, the
+/// payload is not validated and [`InsertPayloadOk::Deferred`] is returned.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum InsertPayloadOk {
     /// The payload was valid, but we have already seen it.
     AlreadySeen(BlockStatus),
     /// The payload was valid and inserted into the tree.
     Inserted(BlockStatus),
+    /// The payload was neither validated nor inserted, because the tree is
+    /// [congested](TreeCongestion::Congested).
+    Deferred {
+        /// How long to wait before the payload is sent again.
+        retry_after: Duration,
+    },
+}
+
+/// Whether the blockchain tree accepts new blocks.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TreeCongestion {
+    /// New blocks are inserted.
+    #[default]
+    Clear,
+    /// The buffered blocks or chains of the tree exceed their budgets, new blocks are
+    /// [deferred](InsertPayloadOk::Deferred) until they are drained.
+    Congested,
+}
+
+impl TreeCongestion {
+    /// Returns `true` if new blocks are deferred.
+    pub const fn is_congested(&self) -> bool {
+        matches!(self, Self::Congested)
+    }
 }
 
 /// Allows read only functionality on the blockchain tree.
@@ -307,7 +354,8 @@ pub trait BlockchainTreeViewer: Send + Sync {
     /// Return `BlockchainTree` best known canonical chain tip (`BlockHash`, `BlockNumber`)
     fn canonical_tip(&self) -> BlockNumHash;
 
-    /// Return block number and hash that extends the canonical chain tip by one.
+    /// Return block number and hash of the pending block, the highest block of the chain that
+    /// extends the canonical chain tip.
     ///
     /// If there is no such block, this returns `None`.
     fn pending_block_num_hash(&self) -> Option<BlockNumHash>;
@@ -0,0 +1,349 @@
+//! Backup of the blocks that only live in the [`BlockchainTree`].
+//!
+//! Sidechain and buffered blocks are never written to the database, so a restart during tip sync
+//! throws them away and the consensus layer has to resend them. The backup writes these blocks to
+//! a file on shutdown (and periodically), and re-inserts them into the tree on the next startup.
+
+use crate::BlockchainTree;
+use alloy_rlp::{RlpDecodable, RlpEncodable};
+use parking_lot::RwLock;
+use reth_blockchain_tree_api::{BlockValidationKind, InsertPayloadOk};
+use reth_db_api::database::Database;
+use reth_evm::execute::BlockExecutorProvider;
+use reth_fs_util::FsPathError;
+use reth_primitives::{Address, SealedBlock, SealedBlockWithSenders};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tracing::{debug, error, info, trace, warn};
+
+/// Default maximum age of a backed up block that is still re-inserted on startup.
+pub const DEFAULT_BLOCKS_BACKUP_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Default interval at which the blocks are backed up while the node is running.
+pub const DEFAULT_BLOCKS_BACKUP_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Settings for the backup of the sidechain and buffered blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlocksBackupConfig {
+    /// Path to the blocks backup file, the backup is disabled if not set.
+    pub blocks_path: Option<PathBuf>,
+    /// Backed up blocks older than this are discarded on startup.
+    pub max_age: Duration,
+    /// Interval at which the blocks are backed up while the node is running.
+    pub interval: Duration,
+}
+
+impl BlocksBackupConfig {
+    /// Receive path to the blocks backup and return initialized config
+    pub const fn with_blocks_backup(blocks_path: PathBuf) -> Self {
+        Self {
+            blocks_path: Some(blocks_path),
+            max_age: DEFAULT_BLOCKS_BACKUP_MAX_AGE,
+            interval: DEFAULT_BLOCKS_BACKUP_INTERVAL,
+        }
+    }
+
+    /// Sets the maximum age of backed up blocks that are still re-inserted on startup.
+    pub const fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+}
+
+impl Default for BlocksBackupConfig {
+    fn default() -> Self {
+        Self {
+            blocks_path: None,
+            max_age: DEFAULT_BLOCKS_BACKUP_MAX_AGE,
+            interval: DEFAULT_BLOCKS_BACKUP_INTERVAL,
+        }
+    }
+}
+
+/// A block of the backup file.
+#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+struct BackedUpBlock {
+    /// Unix timestamp in seconds of when the block was backed up.
+    saved_at: u64,
+    /// The recovered senders of the block transactions.
+    senders: Vec<Address>,
+    /// The block itself.
+    block: SealedBlock,
+}
+
+/// Errors possible during blocks backup load and decode
+#[derive(thiserror::Error, Debug)]
+pub enum BlocksBackupError {
+    /// Error during RLP decoding of blocks
+    #[error("failed to apply blocks backup. Encountered RLP decode error: {0}")]
+    Decode(#[from] alloy_rlp::Error),
+    /// Error during file upload
+    #[error("failed to apply blocks backup. Encountered file error: {0}")]
+    FsPath(#[from] FsPathError),
+}
+
+/// Writes all sidechain and buffered blocks of the tree to the given file.
+///
+/// Removes a previous backup if the tree holds no such blocks.
+pub fn save_blocks_backup<DB, E>(tree: &BlockchainTree<DB, E>, file_path: &Path) {
+    let blocks = tree.non_canonical_blocks();
+    if blocks.is_empty() {
+        trace!(target: "blockchain_tree", "no sidechain or buffered blocks to save");
+        if file_path.exists() {
+            if let Err(err) = reth_fs_util::remove_file(file_path) {
+                warn!(target: "blockchain_tree", %err, blocks_file=?file_path, "Failed to remove outdated blocks backup");
+            }
+        }
+        return
+    }
+
+    let saved_at = unix_timestamp();
+    let blocks = blocks
+        .into_iter()
+        .map(|block| {
+            let (block, senders) = block.into_components();
+            BackedUpBlock { saved_at, senders, block }
+        })
+        .collect::<Vec<_>>();
+
+    let num_blocks = blocks.len();
+    let mut buf = Vec::new();
+    alloy_rlp::encode_list(&blocks, &mut buf);
+    debug!(target: "blockchain_tree", blocks_file=?file_path, %num_blocks, "Saving sidechain and buffered blocks");
+    let parent_dir = file_path.parent().map(std::fs::create_dir_all).transpose();
+
+    match parent_dir.map(|_| reth_fs_util::write(file_path, buf)) {
+        Ok(_) => {
+            debug!(target: "blockchain_tree", blocks_file=?file_path, "Wrote blocks to file");
+        }
+        Err(err) => {
+            warn!(target: "blockchain_tree", %err, blocks_file=?file_path, "Failed to write blocks to file");
+        }
+    }
+}
+
+/// Re-inserts the blocks of the given backup file into the tree and removes the file.
+///
+/// Blocks are inserted in ascending order through [`BlockchainTree::insert_block`], so they are
+/// fully validated again. Blocks that were backed up more than `max_age` ago, or that fail to be
+/// inserted, for example because they no longer connect to the finalized chain, are discarded.
+///
+/// Returns the number of inserted blocks.
+pub fn load_blocks_backup<DB, E>(
+    tree: &mut BlockchainTree<DB, E>,
+    file_path: &Path,
+    max_age: Duration,
+) -> Result<usize, BlocksBackupError>
+where
+    DB: Database + Clone,
+    E: BlockExecutorProvider,
+{
+    if !file_path.exists() {
+        return Ok(0);
+    }
+
+    debug!(target: "blockchain_tree", blocks_file=?file_path, "Check local persistent storage for saved blocks");
+    let data = reth_fs_util::read(file_path)?;
+
+    let mut inserted = 0;
+    if !data.is_empty() {
+        let mut blocks: Vec<BackedUpBlock> = alloy_rlp::Decodable::decode(&mut data.as_slice())?;
+        blocks.sort_unstable_by_key(|backed_up| backed_up.block.number);
+
+        let now = unix_timestamp();
+        for BackedUpBlock { saved_at, senders, block } in blocks {
+            if now.saturating_sub(saved_at) > max_age.as_secs() {
+                trace!(target: "blockchain_tree", hash=?block.hash(), saved_at, "Discarding stale backed up block");
+                continue
+            }
+
+            let Some(block) = SealedBlockWithSenders::new(block, senders) else {
+                debug!(target: "blockchain_tree", "Discarding backed up block with mismatched senders");
+                continue
+            };
+
+            match tree.insert_block(block, BlockValidationKind::Exhaustive) {
+                Ok(InsertPayloadOk::Inserted(_)) => inserted += 1,
+                Ok(InsertPayloadOk::AlreadySeen(_) | InsertPayloadOk::Deferred { .. }) => {}
+                Err(err) => {
+                    debug!(target: "blockchain_tree", %err, "Discarding backed up block");
+                }
+            }
+        }
+    }
+
+    info!(target: "blockchain_tree", blocks_file=?file_path, num_blocks=%inserted, "Reinserted sidechain and buffered blocks from file");
+    reth_fs_util::remove_file(file_path)?;
+    Ok(inserted)
+}
+
+/// Task which manages saving the sidechain and buffered blocks to the persistent file,
+/// periodically and in case of shutdown. Reloads the blocks from the file on the boot up and
+/// inserts them into the tree.
+pub async fn backup_blocks_task<DB, E>(
+    shutdown: reth_tasks::shutdown::GracefulShutdown,
+    tree: Arc<RwLock<BlockchainTree<DB, E>>>,
+    config: BlocksBackupConfig,
+) where
+    DB: Database + Clone,
+    E: BlockExecutorProvider,
+{
+    let Some(blocks_path) = config.blocks_path else {
+        // nothing to do
+        return
+    };
+
+    if let Err(err) = load_blocks_backup(&mut tree.write(), &blocks_path, config.max_age) {
+        error!(target: "blockchain_tree", "{}", err)
+    }
+
+    let mut interval =
+        tokio::time::interval_at(tokio::time::Instant::now() + config.interval, config.interval);
+    tokio::pin!(shutdown);
+
+    let graceful_guard = loop {
+        tokio::select! {
+            guard = &mut shutdown => break guard,
+            _ = interval.tick() => save_blocks_backup(&tree.read(), &blocks_path),
+        }
+    };
+
+    // write blocks to disk
+    save_blocks_backup(&tree.read(), &blocks_path);
+
+    drop(graceful_guard)
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlockchainTreeConfig, TreeExternals};
+    use reth_blockchain_tree_api::CanonicalOutcome;
+    use reth_consensus::test_utils::TestConsensus;
+    use reth_db::{tables, test_utils::TempDatabase, DatabaseEnv};
+    use reth_db_api::transaction::DbTxMut;
+    use reth_evm_ethereum::{execute::EthExecutorProvider, test_utils::ChainGenerator};
+    use reth_primitives::{keccak256, ChainSpecBuilder, MAINNET};
+    use reth_provider::{
+        test_utils::create_test_provider_factory_with_chain_spec, ProviderFactory,
+    };
+
+    type TestTree = BlockchainTree<Arc<TempDatabase<DatabaseEnv>>, EthExecutorProvider>;
+
+    fn setup() -> (ChainGenerator, ProviderFactory<Arc<TempDatabase<DatabaseEnv>>>) {
+        let generator = ChainGenerator::new(
+            &Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .shanghai_activated()
+                    .build(),
+            ),
+            1,
+            1,
+        );
+        let chain_spec = generator.chain_spec();
+        let provider_factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+
+        let provider_rw = provider_factory.provider_rw().unwrap();
+        provider_rw
+            .insert_block(
+                SealedBlock::new(chain_spec.sealed_genesis_header(), Default::default())
+                    .try_seal_with_senders()
+                    .unwrap(),
+                None,
+            )
+            .unwrap();
+        for (address, (account, _)) in generator.genesis_state() {
+            provider_rw.tx_ref().put::<tables::PlainAccountState>(*address, *account).unwrap();
+            provider_rw
+                .tx_ref()
+                .put::<tables::HashedAccounts>(keccak256(address), *account)
+                .unwrap();
+        }
+        provider_rw.commit().unwrap();
+
+        (generator, provider_factory)
+    }
+
+    fn new_tree(
+        generator: &ChainGenerator,
+        provider_factory: &ProviderFactory<Arc<TempDatabase<DatabaseEnv>>>,
+    ) -> TestTree {
+        let externals = TreeExternals::new(
+            provider_factory.clone(),
+            Arc::new(TestConsensus::default()),
+            EthExecutorProvider::ethereum(generator.chain_spec()),
+        );
+        BlockchainTree::new(externals, BlockchainTreeConfig::default(), None)
+            .expect("failed to create tree")
+    }
+
+    #[test]
+    fn reloads_sidechain_after_restart() {
+        let (mut generator, provider_factory) = setup();
+        let dir = tempfile::tempdir().unwrap();
+        let blocks_path = dir.path().join("blocks.rlp");
+
+        let fork_block = generator.generate_block();
+        let mut sidechain = generator.fork(2);
+        let canonical_block = generator.generate_block();
+        let sidechain_blocks = sidechain.generate_blocks(3);
+
+        let mut tree = new_tree(&generator, &provider_factory);
+        for block in [&fork_block, &canonical_block] {
+            tree.insert_block(block.clone(), BlockValidationKind::Exhaustive).unwrap();
+            tree.make_canonical(block.hash()).unwrap();
+        }
+        for block in &sidechain_blocks {
+            tree.insert_block(block.clone(), BlockValidationKind::Exhaustive).unwrap();
+        }
+        assert_eq!(tree.non_canonical_blocks(), sidechain_blocks);
+
+        // shut down the node
+        save_blocks_backup(&tree, &blocks_path);
+        drop(tree);
+
+        let mut tree = new_tree(&generator, &provider_factory);
+        assert!(tree.non_canonical_blocks().is_empty());
+
+        let inserted =
+            load_blocks_backup(&mut tree, &blocks_path, DEFAULT_BLOCKS_BACKUP_MAX_AGE).unwrap();
+        assert_eq!(inserted, 3);
+        assert_eq!(tree.non_canonical_blocks(), sidechain_blocks);
+        assert!(!blocks_path.exists());
+
+        let tip = sidechain_blocks.last().unwrap();
+        assert_eq!(
+            tree.make_canonical(tip.hash()).unwrap(),
+            CanonicalOutcome::Committed { head: tip.header.clone() }
+        );
+        assert!(tree.non_canonical_blocks().is_empty());
+    }
+
+    #[test]
+    fn discards_stale_blocks() {
+        let (mut generator, provider_factory) = setup();
+        let dir = tempfile::tempdir().unwrap();
+        let blocks_path = dir.path().join("blocks.rlp");
+
+        let (block, senders) = generator.generate_block().into_components();
+        let mut buf = Vec::new();
+        alloy_rlp::encode_list(&[BackedUpBlock { saved_at: 0, senders, block }], &mut buf);
+        reth_fs_util::write(&blocks_path, buf).unwrap();
+
+        let mut tree = new_tree(&generator, &provider_factory);
+        let inserted =
+            load_blocks_backup(&mut tree, &blocks_path, DEFAULT_BLOCKS_BACKUP_MAX_AGE).unwrap();
+        assert_eq!(inserted, 0);
+        assert!(tree.non_canonical_blocks().is_empty());
+        assert!(!blocks_path.exists());
+    }
+}
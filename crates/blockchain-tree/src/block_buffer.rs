@@ -66,22 +66,48 @@ impl BlockBuffer {
     }
 
     /// Insert a correct block inside the buffer.
-    pub fn insert_block(&mut self, block: SealedBlockWithSenders) {
+    ///
+    /// If the buffer is full, the least recently inserted block is evicted and its hash is
+    /// returned.
+    pub fn insert_block(&mut self, block: SealedBlockWithSenders) -> Option<BlockHash> {
         let hash = block.hash();
 
         self.parent_to_child.entry(block.parent_hash).or_default().insert(hash);
         self.earliest_blocks.entry(block.number).or_default().insert(hash);
         self.blocks.insert(hash, block);
 
+        let mut evicted = None;
         if let (_, Some(evicted_hash)) = self.lru.insert_and_get_evicted(hash) {
             // evict the block if limit is hit
             if let Some(evicted_block) = self.remove_block(&evicted_hash) {
                 // evict the block if limit is hit
                 self.remove_from_parent(evicted_block.parent_hash, &evicted_hash);
+                self.metrics.evicted_blocks.increment(1);
+                evicted = Some(evicted_hash);
+            }
+        }
+        self.metrics.blocks.set(self.blocks.len() as f64);
+        evicted
+    }
+
+    /// Set the max number of blocks in the buffer.
+    ///
+    /// If the buffer holds more blocks than the new limit, the least recently used blocks are
+    /// evicted.
+    pub fn set_limit(&mut self, limit: u32) {
+        // iterated from the most recently used block, so reinsert in reverse to keep the order
+        let hashes = self.lru.iter().copied().collect::<Vec<_>>();
+        self.lru = LruCache::new(limit);
+        for hash in hashes.into_iter().rev() {
+            if let (_, Some(evicted_hash)) = self.lru.insert_and_get_evicted(hash) {
+                if self.remove_block(&evicted_hash).is_some() {
+                    self.metrics.evicted_blocks.increment(1);
+                }
             }
         }
         self.metrics.blocks.set(self.blocks.len() as f64);
     }
+
     /// Removes the given block from the buffer and also all the children of the block.
     ///
     /// This is used to get all the blocks that are dependent on the block that is included.
@@ -486,4 +512,66 @@ mod tests {
 
         assert_buffer_lengths(&buffer, 3);
     }
+
+    #[test]
+    fn eviction_order_and_reinsertion() {
+        let mut rng = generators::rng();
+
+        let blocks = (0..6)
+            .map(|number| {
+                let parent = rng.gen();
+                create_block(&mut rng, 10 + number, parent)
+            })
+            .collect::<Vec<_>>();
+
+        let mut buffer = BlockBuffer::new(3);
+
+        for block in &blocks[..3] {
+            assert_eq!(buffer.insert_block(block.clone()), None);
+        }
+
+        // past the cap, blocks are evicted in insertion order
+        for (evicted, block) in blocks.iter().zip(&blocks[3..]) {
+            assert_eq!(buffer.insert_block(block.clone()), Some(evicted.hash()));
+            assert_block_removal(&buffer, evicted);
+            assert_buffer_lengths(&buffer, 3);
+        }
+
+        // an evicted block can be buffered again and evicts the oldest remaining block
+        assert_eq!(buffer.insert_block(blocks[0].clone()), Some(blocks[3].hash()));
+        assert_eq!(buffer.block(&blocks[0].hash()), Some(&blocks[0]));
+        assert_eq!(buffer.lowest_ancestor(&blocks[0].hash()), Some(&blocks[0]));
+        assert_buffer_lengths(&buffer, 3);
+    }
+
+    #[test]
+    fn shrink_limit_evicts_least_recently_used() {
+        let mut rng = generators::rng();
+
+        let main_parent = BlockNumHash::new(9, rng.gen());
+        let block1 = create_block(&mut rng, 10, main_parent.hash);
+        let block2 = create_block(&mut rng, 11, block1.hash());
+        let block3 = create_block(&mut rng, 12, block2.hash());
+        let parent4 = rng.gen();
+        let block4 = create_block(&mut rng, 13, parent4);
+
+        let mut buffer = BlockBuffer::new(3);
+
+        buffer.insert_block(block1.clone());
+        buffer.insert_block(block2.clone());
+        buffer.insert_block(block3.clone());
+
+        // block1 and block2 are the least recently used
+        buffer.set_limit(1);
+        assert_block_removal(&buffer, &block1);
+        assert_block_removal(&buffer, &block2);
+        assert_buffer_lengths(&buffer, 1);
+
+        // a larger limit doesn't evict anything on insertion
+        buffer.set_limit(2);
+        buffer.insert_block(block4.clone());
+        assert_buffer_lengths(&buffer, 2);
+        assert_eq!(buffer.block(&block3.hash()), Some(&block3));
+        assert_eq!(buffer.block(&block4.hash()), Some(&block4));
+    }
 }
@@ -24,8 +24,9 @@ pub struct BlockIndices {
     /// Index needed when discarding the chain, so we can remove connected chains from tree.
     ///
     /// This maintains insertion order for all child blocks, so
-    /// [`BlockIndices::pending_block_num_hash`] returns always the same block: the first child
-    /// block we inserted.
+    /// [`BlockchainTree::pending_block_num_hash`](crate::BlockchainTree::pending_block_num_hash)
+    /// picks the same block among equally long chains: the one of the first child block we
+    /// inserted.
     ///
     /// NOTE: It contains just blocks that are forks as a key and not all blocks.
     fork_to_child: HashMap<BlockHash, LinkedHashSet<BlockHash>>,
@@ -67,14 +68,9 @@ impl BlockIndices {
         &self.blocks_to_chain
     }
 
-    /// Returns the hash and number of the pending block.
-    ///
-    /// It is possible that multiple child blocks for the canonical tip exist.
-    /// This will always return the _first_ child we recorded for the canonical tip.
-    pub(crate) fn pending_block_num_hash(&self) -> Option<BlockNumHash> {
-        let canonical_tip = self.canonical_tip();
-        let hash = self.fork_to_child.get(&canonical_tip.hash)?.front().copied()?;
-        Some(BlockNumHash { number: canonical_tip.number + 1, hash })
+    /// Return block number to block hashes
+    pub const fn block_number_to_block_hashes(&self) -> &BTreeMap<BlockNumber, HashSet<BlockHash>> {
+        &self.block_number_to_block_hashes
     }
 
     /// Returns all pending block hashes.
@@ -346,6 +342,75 @@ impl BlockIndices {
         lose_chains
     }
 
+    /// Unlinks all chains that fork off canonical blocks below `block_number`.
+    ///
+    /// Return list of chains for removal that fork off those canonical blocks.
+    pub(crate) fn remove_forks_below(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> BTreeSet<BlockchainId> {
+        let fork_blocks: Vec<BlockHash> = self
+            .canonical_chain
+            .iter()
+            .take_while(|(number, _)| *number < block_number)
+            .map(|(_, hash)| hash)
+            .collect();
+
+        let mut lose_chains = BTreeSet::new();
+        for block_hash in fork_blocks {
+            if let Some(fork_blocks) = self.fork_to_child.remove(&block_hash) {
+                for fork_child in fork_blocks {
+                    if let Some(lose_chain) = self.blocks_to_chain.remove(&fork_child) {
+                        lose_chains.insert(lose_chain);
+                    }
+                }
+            }
+        }
+        lose_chains
+    }
+
+    /// Drops all canonical hashes below `retain_from` from the in-memory window.
+    ///
+    /// Return list of chains for removal that fork off the dropped canonical blocks.
+    pub(crate) fn trim_canonical_chain(
+        &mut self,
+        retain_from: BlockNumber,
+    ) -> BTreeSet<BlockchainId> {
+        let trimmed_blocks: Vec<BlockHash> = self
+            .canonical_chain
+            .iter()
+            .take_while(|(number, _)| *number < retain_from)
+            .map(|(_, hash)| hash)
+            .collect();
+        if trimmed_blocks.is_empty() {
+            return BTreeSet::new()
+        }
+
+        self.canonical_chain.retain(|&number, _| number >= retain_from);
+
+        let mut lose_chains = BTreeSet::new();
+        for block_hash in trimmed_blocks {
+            if let Some(fork_blocks) = self.fork_to_child.remove(&block_hash) {
+                for fork_child in fork_blocks {
+                    if let Some(lose_chain) = self.blocks_to_chain.remove(&fork_child) {
+                        lose_chains.insert(lose_chain);
+                    }
+                }
+            }
+        }
+        lose_chains
+    }
+
+    /// Inserts canonical hashes that were read back from the database below the in-memory window.
+    ///
+    /// NOTE: Hashes are expected to be contiguous with the retained canonical chain.
+    pub(crate) fn rehydrate_canonical_hashes(
+        &mut self,
+        hashes: impl IntoIterator<Item = (BlockNumber, BlockHash)>,
+    ) {
+        self.canonical_chain.extend(hashes.into_iter())
+    }
+
     /// Returns the block hash of the canonical block with the given number.
     #[inline]
     pub fn canonical_hash(&self, block_number: &BlockNumber) -> Option<BlockHash> {
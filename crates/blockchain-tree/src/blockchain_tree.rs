@@ -2,8 +2,13 @@
 
 use crate::{
     metrics::{MakeCanonicalAction, MakeCanonicalDurationsRecorder, TreeMetrics},
+    reorg_simulation::{
+        BalanceChange, ReorgSimulation, ReorgViolation, REORG_SIMULATION_TOP_ACCOUNTS,
+    },
+    sender_cache::{SenderCache, DEFAULT_SENDER_CACHE_SIZE},
     state::{BlockchainId, TreeState},
     AppendableChain, BlockIndices, BlockchainTreeConfig, ExecutionData, TreeExternals,
+    TreeSoftLimits, TreeUsage,
 };
 use reth_blockchain_tree_api::{
     error::{BlockchainTreeError, CanonicalError, InsertBlockError, InsertBlockErrorKind},
@@ -13,21 +18,22 @@ use reth_consensus::{Consensus, ConsensusError};
 use reth_db_api::database::Database;
 use reth_evm::execute::BlockExecutorProvider;
 use reth_execution_errors::{BlockExecutionError, BlockValidationError};
+use reth_metrics::MetricsRegistrar;
 use reth_primitives::{
     BlockHash, BlockNumHash, BlockNumber, ForkBlock, GotExpected, Hardfork, Receipt, SealedBlock,
     SealedBlockWithSenders, SealedHeader, StaticFileSegment, B256, U256,
 };
 use reth_provider::{
-    BlockExecutionWriter, BlockNumReader, BlockWriter, CanonStateNotification,
-    CanonStateNotificationSender, CanonStateNotifications, Chain, ChainSpecProvider, ChainSplit,
-    ChainSplitTarget, DisplayBlocksChain, ExecutionOutcome, HeaderProvider, ProviderError,
-    StaticFileProviderFactory,
+    AccountReader, BlockExecutionWriter, BlockHashReader, BlockNumReader, BlockReader, BlockWriter,
+    CanonStateNotification, CanonStateNotificationSender, CanonStateNotifications, Chain,
+    ChainSpecProvider, ChainSplit, ChainSplitTarget, ChangeSetReader, DisplayBlocksChain,
+    ExecutionOutcome, HeaderProvider, ProviderError, ReceiptProvider, StaticFileProviderFactory,
 };
 use reth_prune_types::PruneModes;
 use reth_stages_api::{MetricEvent, MetricEventsSender};
 use reth_storage_errors::provider::{ProviderResult, RootMismatch};
 use std::{
-    collections::{btree_map::Entry, BTreeMap, HashSet},
+    collections::{btree_map::Entry, BTreeMap, HashMap, HashSet},
     sync::Arc,
 };
 use tracing::{debug, error, info, instrument, trace, warn};
@@ -55,6 +61,8 @@ use tracing::{debug, error, info, instrument, trace, warn};
 /// * [`BlockchainTree::finalize_block`]: Remove chains that branch off of the now finalized block.
 /// * [`BlockchainTree::make_canonical`]: Check if we have the hash of a block that is the current
 ///   canonical head and commit it to db.
+/// * [`BlockchainTree::trim_retained_blocks`]: Drop canonical blocks that fall out of the
+///   [`memory_block_retention`](BlockchainTreeConfig::memory_block_retention) window.
 #[derive(Debug)]
 pub struct BlockchainTree<DB, E> {
     /// The state of the tree
@@ -73,6 +81,8 @@ pub struct BlockchainTree<DB, E> {
     sync_metrics_tx: Option<MetricEventsSender>,
     /// Metrics for the blockchain tree.
     metrics: TreeMetrics,
+    /// Recovered senders of recently inserted blocks.
+    sender_cache: SenderCache,
 }
 
 impl<DB, E> BlockchainTree<DB, E> {
@@ -87,6 +97,70 @@ impl<DB, E> BlockchainTree<DB, E> {
     pub fn canon_state_notification_sender(&self) -> CanonStateNotificationSender {
         self.canon_state_notification_sender.clone()
     }
+
+    /// Returns all blocks of the sidechains and the block buffer, ordered by block number.
+    ///
+    /// These blocks only live in memory and are lost on restart, see [`crate::backup`].
+    pub fn non_canonical_blocks(&self) -> Vec<SealedBlockWithSenders> {
+        let mut blocks = self
+            .state
+            .chains
+            .values()
+            .flat_map(|chain| chain.blocks().values())
+            .chain(self.state.buffered_blocks.blocks().values())
+            .filter(|block| self.state.block_indices.canonical_number(&block.hash()).is_none())
+            .cloned()
+            .collect::<Vec<_>>();
+        blocks.sort_unstable_by_key(|block| block.number);
+        blocks
+    }
+
+    /// Returns the configuration of the tree.
+    pub const fn config(&self) -> &BlockchainTreeConfig {
+        &self.config
+    }
+
+    /// Replaces the limits that can be adjusted while the tree is running.
+    ///
+    /// A smaller buffer capacity evicts the least recently used buffered blocks right away, a
+    /// smaller memory block retention trims the retained blocks on the next canonicalization.
+    pub(crate) fn set_soft_limits(&mut self, limits: TreeSoftLimits) {
+        debug!(target: "blockchain_tree", ?limits, "Updating soft limits");
+        self.config = self.config.with_soft_limits(limits);
+        self.state.buffered_blocks.set_limit(limits.max_unconnected_blocks);
+    }
+
+    /// Returns the resource usage of the tree.
+    ///
+    /// The memory usage is estimated from the blocks of the chains and the buffer, the execution
+    /// outcomes of the chains are not accounted for.
+    pub fn usage(&self) -> TreeUsage {
+        let buffered_blocks = self.state.buffered_blocks.blocks();
+        let memory = self
+            .state
+            .chains
+            .values()
+            .flat_map(|chain| chain.blocks().values())
+            .chain(buffered_blocks.values())
+            .map(|block| block.size())
+            .sum();
+        TreeUsage {
+            buffered_blocks: buffered_blocks.len(),
+            chains: self.state.chains.len(),
+            memory,
+        }
+    }
+
+    /// Returns `true` if any buffered block is a child of the block with the given hash.
+    ///
+    /// Inserting such a block connects its buffered descendants, which drains the buffer.
+    pub fn has_buffered_children(&self, hash: &BlockHash) -> bool {
+        self.state
+            .buffered_blocks
+            .parent_to_child
+            .get(hash)
+            .is_some_and(|children| !children.is_empty())
+    }
 }
 
 impl<DB, E> BlockchainTree<DB, E>
@@ -142,6 +216,7 @@ where
             canon_state_notification_sender,
             sync_metrics_tx: None,
             metrics: Default::default(),
+            sender_cache: SenderCache::new(DEFAULT_SENDER_CACHE_SIZE),
         })
     }
 
@@ -166,6 +241,13 @@ where
         self
     }
 
+    /// Set the number of blocks whose recovered transaction senders are cached, see
+    /// [`BlockchainTree::insert_block_without_senders`]. Defaults to
+    /// [`DEFAULT_SENDER_CACHE_SIZE`], `0` disables the cache.
+    pub fn set_sender_cache_size(&mut self, size: usize) {
+        self.sender_cache.set_size(size);
+    }
+
     /// Check if the block is known to blockchain tree or database and return its status.
     ///
     /// Function will check:
@@ -215,6 +297,11 @@ where
         Ok(None)
     }
 
+    /// Registers all metrics of the tree with the given registrar.
+    pub fn register_metrics(&self, registrar: &mut MetricsRegistrar) {
+        crate::metrics::register_metrics(registrar)
+    }
+
     /// Expose internal indices of the `BlockchainTree`.
     #[inline]
     pub const fn block_indices(&self) -> &BlockIndices {
@@ -247,9 +334,28 @@ where
         self.state.receipts_by_block_hash(block_hash)
     }
 
+    /// Returns the hash and number of the pending block: the highest block of the chain that
+    /// extends the canonical tip.
+    ///
+    /// It is possible that multiple chains extend the canonical tip. This returns the tip of the
+    /// longest one, and of the chain of the _first_ child we recorded among equally long chains.
+    pub fn pending_block_num_hash(&self) -> Option<BlockNumHash> {
+        let (_, children) = self.block_indices().pending_blocks();
+        children
+            .iter()
+            .rev()
+            .filter_map(|hash| self.state.block_indices.get_block_chain_id(hash))
+            .filter_map(|chain_id| self.state.chains.get(&chain_id))
+            .map(|chain| chain.tip().num_hash())
+            // returns the last maximum, which is the first recorded one
+            .max_by_key(|block| block.number)
+    }
+
     /// Returns the block that's considered the `Pending` block, if it exists.
+    ///
+    /// See [`BlockchainTree::pending_block_num_hash`].
     pub fn pending_block(&self) -> Option<&SealedBlock> {
-        let b = self.block_indices().pending_block_num_hash()?;
+        let b = self.pending_block_num_hash()?;
         self.sidechain_block_by_hash(b.hash)
     }
 
@@ -314,8 +420,10 @@ where
     /// Try inserting a validated [Self::validate_block] block inside the tree.
     ///
     /// If the block's parent block is unknown, this returns [`BlockStatus::Disconnected`] and the
-    /// block will be buffered until the parent block is inserted and then attached to sidechain
-    #[instrument(level = "trace", skip_all, fields(block = ?block.num_hash()), target = "blockchain_tree", ret)]
+    /// block will be buffered until the parent block is inserted and then attached to sidechain.
+    /// Blocks more than [`BlockchainTreeConfig::max_buffered_block_distance`] above the canonical
+    /// tip are rejected with [`BlockchainTreeError::BlockTooFarAhead`] instead.
+    #[instrument(level = "trace", skip_all, fields(block = ?block.num_hash(), execution_elapsed = tracing::field::Empty), target = "blockchain_tree", ret)]
     fn try_insert_validated_block(
         &mut self,
         block: SealedBlockWithSenders,
@@ -356,8 +464,21 @@ where
             self.externals.consensus.validate_header_against_parent(&block, buffered_parent)?;
         }
 
+        // reject blocks that are too far ahead instead of letting them fill the buffer
+        let canonical_tip = self.state.block_indices.canonical_tip().number;
+        let max_distance = self.config.max_buffered_block_distance();
+        if block.number > canonical_tip.saturating_add(max_distance) {
+            self.metrics.distant_blocks_rejected.increment(1);
+            return Err(BlockchainTreeError::BlockTooFarAhead {
+                block_number: block.number,
+                canonical_tip,
+                max_distance,
+            }
+            .into())
+        }
+
         // insert block inside unconnected block buffer. Delaying its execution.
-        self.state.buffered_blocks.insert_block(block.clone());
+        self.insert_into_buffer(block.clone());
 
         let block_hash = block.hash();
         // find the lowest ancestor of the block in the buffer to return as the missing parent
@@ -411,6 +532,7 @@ where
             .into())
         }
 
+        // the header is looked up by the parent hash, so the hash can be trusted
         let parent_header = provider
             .header(&block.parent_hash)?
             .ok_or_else(|| BlockchainTreeError::CanonicalChain { block_hash: block.parent_hash })?
@@ -667,12 +789,14 @@ where
     ///
     /// # Note
     ///
-    /// This recovers transaction signers (unlike [`BlockchainTree::insert_block`]).
+    /// This recovers transaction signers (unlike [`BlockchainTree::insert_block`]). The signers of
+    /// recently inserted blocks are cached, so inserting the same block again doesn't recover
+    /// them again.
     pub fn insert_block_without_senders(
         &mut self,
         block: SealedBlock,
     ) -> Result<InsertPayloadOk, InsertBlockError> {
-        match block.try_seal_with_senders() {
+        match self.sender_cache.seal_with_senders(block) {
             Ok(block) => self.insert_block(block, BlockValidationKind::Exhaustive),
             Err(block) => Err(InsertBlockError::sender_recovery_error(block)),
         }
@@ -680,6 +804,10 @@ where
 
     /// Insert block for future execution.
     ///
+    /// Unlike blocks inserted with [`BlockchainTree::insert_block`], the block is buffered
+    /// regardless of its distance to the canonical tip, since the canonical tip lags behind while
+    /// the pipeline syncs.
+    ///
     /// Returns an error if the block is invalid.
     pub fn buffer_block(&mut self, block: SealedBlockWithSenders) -> Result<(), InsertBlockError> {
         // validate block consensus rules
@@ -687,10 +815,18 @@ where
             return Err(InsertBlockError::consensus_error(err, block.block))
         }
 
-        self.state.buffered_blocks.insert_block(block);
+        self.insert_into_buffer(block);
         Ok(())
     }
 
+    /// Inserts the block into the buffer of unconnected blocks, evicting the least recently
+    /// inserted block if the buffer is full.
+    fn insert_into_buffer(&mut self, block: SealedBlockWithSenders) {
+        if let Some(evicted) = self.state.buffered_blocks.insert_block(block) {
+            debug!(target: "blockchain_tree", %evicted, "Evicted block from the full buffer");
+        }
+    }
+
     /// Validate if block is correct and satisfies all the consensus rules that concern the header
     /// and block body itself.
     fn validate_block(&self, block: &SealedBlockWithSenders) -> Result<(), ConsensusError> {
@@ -814,6 +950,78 @@ where
         Ok(())
     }
 
+    /// Drops canonical blocks that fall out of the
+    /// [`memory_block_retention`](BlockchainTreeConfig::memory_block_retention) window, together
+    /// with all sidechains (and their execution outcomes) that fork off them.
+    ///
+    /// This runs after every canonicalization, so the in-memory state stays bounded even if
+    /// finalization lags behind. Reorgs deeper than the retained window re-read the fork block
+    /// from the database and unwind the canonical chain using the changesets.
+    pub fn trim_retained_blocks(&mut self) {
+        let tip = self.state.block_indices.canonical_tip().number;
+        let retain_from = tip.saturating_sub(self.config.memory_block_retention() - 1);
+
+        let mut remove_chains = self.state.block_indices.trim_canonical_chain(retain_from);
+        // remove chains of trimmed blocks
+        while let Some(chain_id) = remove_chains.pop_first() {
+            if let Some(chain) = self.state.chains.remove(&chain_id) {
+                trace!(target: "blockchain_tree", ?chain_id, "Dropping sidechain outside of retained window");
+                remove_chains.extend(self.state.block_indices.remove_chain(&chain));
+            }
+        }
+
+        self.update_retention_metrics();
+    }
+
+    /// Drops all sidechains that fork off the canonical chain below the `finalized` block, together
+    /// with the chains built on top of them.
+    ///
+    /// Sidechains forking at or above `finalized` are kept. Returns the hashes of all removed
+    /// blocks.
+    pub fn prune_sidechains_below(&mut self, finalized: BlockNumber) -> Vec<BlockHash> {
+        let mut remove_chains = self.state.block_indices.remove_forks_below(finalized);
+
+        let mut removed_blocks = Vec::new();
+        // remove chains of unlinked forks and all chains that depend on them
+        while let Some(chain_id) = remove_chains.pop_first() {
+            if let Some(chain) = self.state.chains.remove(&chain_id) {
+                trace!(target: "blockchain_tree", ?chain_id, "Pruning stale sidechain");
+                remove_chains.extend(self.state.block_indices.remove_chain(&chain));
+                removed_blocks.extend(chain.blocks().values().map(|block| block.hash()));
+            }
+        }
+
+        self.update_chains_metrics();
+        removed_blocks
+    }
+
+    /// Reads the canonical hashes `[fork_block, lowest retained)` back from the database if the
+    /// fork block was already trimmed from the in-memory window.
+    fn rehydrate_canonical_hashes(&mut self, fork_block: BlockNumber) -> ProviderResult<()> {
+        let Some((lowest_retained, _)) =
+            self.state.block_indices.canonical_chain().inner().first_key_value()
+        else {
+            return Ok(())
+        };
+        let lowest_retained = *lowest_retained;
+        if fork_block >= lowest_retained {
+            return Ok(())
+        }
+
+        debug!(target: "blockchain_tree", fork_block, lowest_retained, "Rehydrating canonical hashes from database");
+        let hashes = self
+            .externals
+            .provider_factory
+            .provider()?
+            .canonical_hashes_range(fork_block, lowest_retained)?;
+        self.state
+            .block_indices
+            .rehydrate_canonical_hashes((fork_block..lowest_retained).zip(hashes));
+        self.metrics.retention_rehydrations.increment(1);
+
+        Ok(())
+    }
+
     /// Reads the last `N` canonical hashes from the database and updates the block indices of the
     /// tree by attempting to connect the buffered blocks to canonical hashes.
     ///
@@ -992,6 +1200,8 @@ where
             header = provider.header(hash)?
         }
 
+        // the header is looked up by the hash or by its canonical number, so the hash can be
+        // trusted
         Ok(header.map(|header| header.seal(*hash)))
     }
 
@@ -1003,6 +1213,132 @@ where
         self.find_canonical_header(hash).map(|header| header.is_some())
     }
 
+    /// Simulates [`Self::make_canonical`] for the given block without changing the tree or the
+    /// database.
+    ///
+    /// Computes the canonical blocks that would be reverted and the sidechain blocks that would be
+    /// applied, the number of transactions that would return to the pool, the accounts with the
+    /// largest balance changes and the rules the reorg would violate. If the block is already
+    /// canonical, nothing would change.
+    pub fn simulate_make_canonical(
+        &self,
+        block_hash: BlockHash,
+    ) -> Result<ReorgSimulation, CanonicalError> {
+        if let Some(header) = self.find_canonical_header(&block_hash)? {
+            return Ok(ReorgSimulation::new(header.num_hash()))
+        }
+
+        let Some(mut chain_id) = self.block_indices().get_block_chain_id(&block_hash) else {
+            return Err(CanonicalError::from(BlockchainTreeError::BlockHashNotFoundInChain {
+                block_hash,
+            }))
+        };
+
+        // collect the sidechain segments that would be promoted, from the block down to the
+        // canonical chain, in the same way as `make_canonical` splits the chains
+        let mut segments = Vec::new();
+        let mut segment_tip = block_hash;
+        let fork_block = loop {
+            let Some(chain) = self.state.chains.get(&chain_id) else {
+                return Err(CanonicalError::from(BlockchainTreeError::BlockSideChainIdConsistency {
+                    chain_id: chain_id.into(),
+                }))
+            };
+            let Some(tip_number) = chain.block_number(segment_tip) else {
+                return Err(CanonicalError::from(BlockchainTreeError::BlockHashNotFoundInChain {
+                    block_hash: segment_tip,
+                }))
+            };
+            segments.push((chain, tip_number));
+
+            let fork_block = chain.fork_block();
+            match self.block_indices().get_block_chain_id(&fork_block.hash) {
+                Some(fork_chain_id) => {
+                    chain_id = fork_chain_id;
+                    segment_tip = fork_block.hash;
+                }
+                None => break fork_block,
+            }
+        };
+
+        let mut simulation = ReorgSimulation::new(fork_block);
+
+        // balances on the new canonical chain, for the accounts changed by the applied blocks
+        let mut applied_balances = HashMap::new();
+        let mut applied_transactions = HashSet::new();
+        for (chain, tip_number) in segments.into_iter().rev() {
+            for block in chain.blocks().range(..=tip_number).map(|(_, block)| block) {
+                simulation.applied_blocks.push(block.num_hash());
+                applied_transactions.extend(block.body.iter().map(|tx| tx.hash()));
+            }
+            let outcome = chain.execution_outcome_at_block(tip_number).ok_or_else(|| {
+                BlockchainTreeError::BlockHashNotFoundInChain { block_hash: chain.tip().hash() }
+            })?;
+            for (address, account) in outcome.bundle_accounts_iter() {
+                let balance = account.info.as_ref().map(|info| info.balance).unwrap_or_default();
+                applied_balances.insert(address, balance);
+            }
+        }
+        simulation.applied_transactions = applied_transactions.len();
+
+        // balances at the fork block, for the accounts changed by the reverted blocks
+        let mut fork_balances = HashMap::new();
+        let provider = self.externals.provider_factory.provider()?;
+        let canonical_tip = self.block_indices().canonical_tip();
+        if canonical_tip.number > fork_block.number {
+            let reverted = fork_block.number + 1..=canonical_tip.number;
+            for block in provider.sealed_block_with_senders_range(reverted.clone())? {
+                simulation.reverted_blocks.push(block.num_hash());
+                simulation.reverted_transactions += block.body.len();
+                simulation.returned_transactions += block
+                    .body
+                    .iter()
+                    .filter(|tx| !applied_transactions.contains(&tx.hash()))
+                    .count();
+            }
+
+            // the changeset of the lowest reverted block holds the state at the fork block
+            for number in reverted.rev() {
+                for change in provider.account_block_changeset(number)? {
+                    let balance = change.info.map(|info| info.balance).unwrap_or_default();
+                    fork_balances.insert(change.address, balance);
+                }
+            }
+        }
+
+        let changed = fork_balances.keys().chain(applied_balances.keys()).copied();
+        for address in changed.collect::<HashSet<_>>() {
+            let before =
+                provider.basic_account(address)?.map(|account| account.balance).unwrap_or_default();
+            let after = applied_balances
+                .get(&address)
+                .or_else(|| fork_balances.get(&address))
+                .copied()
+                .unwrap_or(before);
+            if before != after {
+                simulation.balance_changes.push(BalanceChange { address, before, after });
+            }
+        }
+        simulation.balance_changes.sort_unstable_by(|a, b| {
+            b.abs_delta().cmp(&a.abs_delta()).then_with(|| a.address.cmp(&b.address))
+        });
+        simulation.balance_changes.truncate(REORG_SIMULATION_TOP_ACCOUNTS);
+
+        let last_finalized = self.block_indices().last_finalized_block();
+        if !simulation.reverted_blocks.is_empty() && fork_block.number < last_finalized {
+            simulation.violations.push(ReorgViolation::RevertsFinalized { last_finalized });
+        }
+        let max_reorg_depth = self.config.max_reorg_depth();
+        if simulation.depth() > max_reorg_depth {
+            simulation.violations.push(ReorgViolation::ExceedsMaxReorgDepth {
+                depth: simulation.depth(),
+                max_reorg_depth,
+            });
+        }
+
+        Ok(simulation)
+    }
+
     /// Make a block and its parent(s) part of the canonical chain and commit them to the database
     ///
     /// # Note
@@ -1133,6 +1469,8 @@ where
         } else {
             // It forks to canonical block that is not the tip.
             let canon_fork: BlockNumHash = new_canon_chain.fork_block();
+            // the fork block might be older than the retained window
+            self.rehydrate_canonical_hashes(canon_fork.number)?;
             // sanity check
             if self.block_indices().canonical_hash(&canon_fork.number) != Some(canon_fork.hash) {
                 error!(
@@ -1202,6 +1540,8 @@ where
 
         durations_recorder.record_relative(MakeCanonicalAction::ClearTrieUpdatesForOtherChilds);
 
+        self.trim_retained_blocks();
+
         // Send notification about new canonical chain and return outcome of canonicalization.
         let outcome = CanonicalOutcome::Committed { head: chain_notification.tip().header.clone() };
         let _ = self.canon_state_notification_sender.send(chain_notification);
@@ -1289,6 +1629,74 @@ where
         Ok(())
     }
 
+    /// Unwinds the canonical chain down to `unwind_to` and drops everything that was built on top
+    /// of the reverted blocks.
+    ///
+    /// In contrast to [`Self::unwind`], the reverted blocks are _not_ inserted back into the tree.
+    /// Blocks that were written to static files are not reverted, so the chain is unwound to the
+    /// highest of them if `unwind_to` is below it.
+    ///
+    /// Returns the number of the new canonical tip.
+    pub fn unwind_canonical_chain(
+        &mut self,
+        unwind_to: BlockNumber,
+    ) -> Result<BlockNumber, CanonicalError> {
+        let unwind_to = unwind_to.max(
+            self.externals
+                .provider_factory
+                .static_file_provider()
+                .get_highest_static_file_block(StaticFileSegment::Headers)
+                .unwrap_or_default(),
+        );
+
+        // nothing to be done if unwind_to is higher then the tip
+        let tip = self.block_indices().canonical_tip().number;
+        if tip <= unwind_to {
+            return Ok(tip)
+        }
+
+        let Some(old_canon_chain) = self.revert_canonical_from_database(unwind_to)? else {
+            return Ok(unwind_to)
+        };
+
+        // remove all sidechains that were connected to the reverted blocks
+        self.update_block_hashes_and_clear_buffered()?;
+
+        self.notify_canonical_chain_unwound(old_canon_chain)?;
+        Ok(unwind_to)
+    }
+
+    /// Notifies canonical state subscribers about blocks that were removed from the canonical
+    /// chain.
+    ///
+    /// Subscribers receive a [`CanonStateNotification::Reorg`] whose new chain only consists of the
+    /// new canonical head, the parent of the first unwound block, so they can rewind their state.
+    pub fn notify_canonical_chain_unwound(
+        &mut self,
+        old_canon_chain: Chain,
+    ) -> Result<(), CanonicalError> {
+        let unwind_to = old_canon_chain.fork_block().number;
+        let provider = self.externals.provider_factory.provider()?;
+        let head = provider
+            .sealed_block_with_senders_range(unwind_to..=unwind_to)?
+            .pop()
+            .ok_or(ProviderError::BlockNotFound(unwind_to.into()))?;
+        let receipts = provider.receipts_by_block(unwind_to.into())?.unwrap_or_default();
+        let new_canon_chain = Chain::from_block(
+            head,
+            ExecutionOutcome::new(Default::default(), receipts.into(), unwind_to, Vec::new()),
+            None,
+        );
+
+        self.update_reorg_metrics(old_canon_chain.len() as f64);
+        let _ = self.canon_state_notification_sender.send(CanonStateNotification::Reorg {
+            old: Arc::new(old_canon_chain),
+            new: Arc::new(new_canon_chain),
+        });
+
+        Ok(())
+    }
+
     /// Reverts the canonical chain down to the given block from the database and returns the
     /// unwound chain.
     ///
@@ -1344,6 +1752,22 @@ where
         self.metrics.latest_reorg_depth.set(reorg_depth);
     }
 
+    /// Update metrics of the blocks retained in-memory.
+    fn update_retention_metrics(&self) {
+        let (sidechain_blocks, sidechain_bytes) = self
+            .state
+            .chains
+            .values()
+            .flat_map(|chain| chain.blocks().values())
+            .fold((0, 0), |(blocks, bytes), block| (blocks + 1, bytes + block.size()));
+
+        self.metrics
+            .retained_canonical_blocks
+            .set(self.state.block_indices.canonical_chain().inner().len() as f64);
+        self.metrics.retained_sidechain_blocks.set(sidechain_blocks as f64);
+        self.metrics.retained_sidechain_bytes.set(sidechain_bytes as f64);
+    }
+
     /// Update blockchain tree chains (canonical and sidechains) and sync metrics.
     ///
     /// NOTE: this method should not be called during the pipeline sync, because otherwise the sync
@@ -1369,33 +1793,62 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{ShareableBlockchainTree, TreeBackpressure, TreeConfigError};
     use assert_matches::assert_matches;
     use linked_hash_set::LinkedHashSet;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use proptest::{
+        arbitrary::any,
+        collection::vec,
+        test_runner::{Config as ProptestConfig, RngAlgorithm, TestRng, TestRunner},
+    };
+    use reth_blockchain_tree_api::{BlockchainTreeEngine, BlockchainTreeViewer};
     use reth_consensus::test_utils::TestConsensus;
-    use reth_db::{tables, test_utils::TempDatabase, DatabaseEnv};
-    use reth_db_api::transaction::DbTxMut;
+    use reth_db::{
+        tables,
+        test_utils::{create_test_rw_db, create_test_static_files_dir, TempDatabase},
+        DatabaseEnv,
+    };
+    use reth_db_api::{transaction::DbTxMut, DatabaseError};
     use reth_evm::test_utils::MockExecutorProvider;
-    use reth_evm_ethereum::execute::EthExecutorProvider;
-    #[cfg(not(feature = "optimism"))]
-    use reth_primitives::proofs::calculate_receipt_root;
-    #[cfg(feature = "optimism")]
-    use reth_primitives::proofs::calculate_receipt_root_optimism;
+    use reth_evm_ethereum::{execute::EthExecutorProvider, test_utils::ChainGenerator};
     use reth_primitives::{
         constants::{EIP1559_INITIAL_BASE_FEE, EMPTY_ROOT_HASH, ETHEREUM_BLOCK_GAS_LIMIT},
-        keccak256,
-        proofs::{calculate_transaction_root, state_root_unhashed},
-        revm_primitives::AccountInfo,
-        Account, Address, ChainSpecBuilder, Genesis, GenesisAccount, Header, Signature,
-        Transaction, TransactionSigned, TransactionSignedEcRecovered, TxEip1559, Withdrawals, B256,
-        MAINNET,
+        keccak256, ChainSpecBuilder, Genesis, Header, Withdrawals, B256, MAINNET,
     };
     use reth_provider::{
+        providers::StaticFileProvider,
         test_utils::{blocks::BlockchainTestData, create_test_provider_factory_with_chain_spec},
         ProviderFactory,
     };
     use reth_stages_api::StageCheckpoint;
     use reth_trie::StateRoot;
-    use std::collections::HashMap;
+    use std::{
+        collections::HashMap,
+        sync::atomic::{AtomicUsize, Ordering},
+        time::{Duration, Instant},
+    };
+
+    /// A database that counts the read transactions that are opened on it.
+    #[derive(Debug, Clone)]
+    struct CountingDatabase<DB> {
+        inner: DB,
+        read_transactions: Arc<AtomicUsize>,
+    }
+
+    impl<DB: Database> Database for CountingDatabase<DB> {
+        type TX = DB::TX;
+        type TXMut = DB::TXMut;
+
+        fn tx(&self) -> Result<Self::TX, DatabaseError> {
+            self.read_transactions.fetch_add(1, Ordering::Relaxed);
+            self.inner.tx()
+        }
+
+        fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError> {
+            self.inner.tx_mut()
+        }
+    }
 
     fn setup_externals(
         exec_res: Vec<ExecutionOutcome>,
@@ -1415,16 +1868,13 @@ mod tests {
         TreeExternals::new(provider_factory, consensus, executor_factory)
     }
 
-    fn setup_genesis<DB: Database>(factory: &ProviderFactory<DB>, mut genesis: SealedBlock) {
+    fn setup_genesis<DB: Database>(factory: &ProviderFactory<DB>, genesis: &SealedBlock) {
         // insert genesis to db.
-
-        genesis.header.set_block_number(10);
-        genesis.header.set_state_root(EMPTY_ROOT_HASH);
         let provider = factory.provider_rw().unwrap();
 
         provider
             .insert_historical_block(
-                genesis.try_seal_with_senders().expect("invalid tx signature in genesis"),
+                genesis.clone().try_seal_with_senders().expect("invalid tx signature in genesis"),
                 None,
             )
             .unwrap();
@@ -1443,6 +1893,18 @@ mod tests {
         provider.commit().unwrap();
     }
 
+    /// Applies `f` to the header of the block and seals it again, e.g. to derive a competing block
+    /// at the same height.
+    fn reseal(
+        mut block: SealedBlockWithSenders,
+        f: impl FnOnce(&mut Header),
+    ) -> SealedBlockWithSenders {
+        let mut header = block.header.clone().unseal();
+        f(&mut header);
+        block.block.header = header.seal_slow();
+        block
+    }
+
     /// Test data structure that will check tree internals
     #[derive(Default, Debug)]
     struct TreeTester {
@@ -1520,21 +1982,18 @@ mod tests {
 
     #[test]
     fn consecutive_reorgs() {
-        let signer = Address::random();
-        let initial_signer_balance = U256::from(10).pow(U256::from(18));
-        let chain_spec = Arc::new(
-            ChainSpecBuilder::default()
-                .chain(MAINNET.chain)
-                .genesis(Genesis {
-                    alloc: BTreeMap::from([(
-                        signer,
-                        GenesisAccount { balance: initial_signer_balance, ..Default::default() },
-                    )]),
-                    ..MAINNET.genesis.clone()
-                })
-                .shanghai_activated()
-                .build(),
+        let mut generator = ChainGenerator::new(
+            &Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .shanghai_activated()
+                    .build(),
+            ),
+            1,
+            1,
         );
+        let chain_spec = generator.chain_spec();
         let provider_factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
         let consensus = Arc::new(TestConsensus::default());
         let executor_provider = EthExecutorProvider::ethereum(chain_spec.clone());
@@ -1549,101 +2008,25 @@ mod tests {
                     None,
                 )
                 .unwrap();
-            let account = Account { balance: initial_signer_balance, ..Default::default() };
-            provider_rw.tx_ref().put::<tables::PlainAccountState>(signer, account).unwrap();
-            provider_rw.tx_ref().put::<tables::HashedAccounts>(keccak256(signer), account).unwrap();
+            for (address, (account, _)) in generator.genesis_state() {
+                provider_rw.tx_ref().put::<tables::PlainAccountState>(*address, *account).unwrap();
+                provider_rw
+                    .tx_ref()
+                    .put::<tables::HashedAccounts>(keccak256(address), *account)
+                    .unwrap();
+            }
             provider_rw.commit().unwrap();
         }
 
-        let single_tx_cost = U256::from(EIP1559_INITIAL_BASE_FEE * 21_000);
-        let mock_tx = |nonce: u64| -> TransactionSignedEcRecovered {
-            TransactionSigned::from_transaction_and_signature(
-                Transaction::Eip1559(TxEip1559 {
-                    chain_id: chain_spec.chain.id(),
-                    nonce,
-                    gas_limit: 21_000,
-                    to: Address::ZERO.into(),
-                    max_fee_per_gas: EIP1559_INITIAL_BASE_FEE as u128,
-                    ..Default::default()
-                }),
-                Signature::default(),
-            )
-            .with_signer(signer)
-        };
-
-        let mock_block = |number: u64,
-                          parent: Option<B256>,
-                          body: Vec<TransactionSignedEcRecovered>,
-                          num_of_signer_txs: u64|
-         -> SealedBlockWithSenders {
-            let transactions_root = calculate_transaction_root(&body);
-            let receipts = body
-                .iter()
-                .enumerate()
-                .map(|(idx, tx)| {
-                    Receipt {
-                        tx_type: tx.tx_type(),
-                        success: true,
-                        cumulative_gas_used: (idx as u64 + 1) * 21_000,
-                        ..Default::default()
-                    }
-                    .with_bloom()
-                })
-                .collect::<Vec<_>>();
-
-            #[cfg(not(feature = "optimism"))]
-            let receipts_root = calculate_receipt_root(&receipts);
-
-            #[cfg(feature = "optimism")]
-            let receipts_root = calculate_receipt_root_optimism(&receipts, &chain_spec, 0);
-
-            SealedBlockWithSenders::new(
-                SealedBlock {
-                    header: Header {
-                        number,
-                        parent_hash: parent.unwrap_or_default(),
-                        gas_used: body.len() as u64 * 21_000,
-                        gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
-                        mix_hash: B256::random(),
-                        base_fee_per_gas: Some(EIP1559_INITIAL_BASE_FEE),
-                        transactions_root,
-                        receipts_root,
-                        state_root: state_root_unhashed(HashMap::from([(
-                            signer,
-                            (
-                                AccountInfo {
-                                    balance: initial_signer_balance -
-                                        (single_tx_cost * U256::from(num_of_signer_txs)),
-                                    nonce: num_of_signer_txs,
-                                    ..Default::default()
-                                },
-                                EMPTY_ROOT_HASH,
-                            ),
-                        )])),
-                        ..Default::default()
-                    }
-                    .seal_slow(),
-                    body: body.clone().into_iter().map(|tx| tx.into_signed()).collect(),
-                    ommers: Vec::new(),
-                    withdrawals: Some(Withdrawals::default()),
-                    requests: None,
-                },
-                body.iter().map(|tx| tx.signer()).collect(),
-            )
-            .unwrap()
-        };
-
-        let fork_block = mock_block(1, Some(chain_spec.genesis_hash()), Vec::from([mock_tx(0)]), 1);
+        let fork_block = generator.generate_block();
+        let mut sidechain = generator.fork(2);
 
-        let canonical_block_1 =
-            mock_block(2, Some(fork_block.hash()), Vec::from([mock_tx(1), mock_tx(2)]), 3);
-        let canonical_block_2 = mock_block(3, Some(canonical_block_1.hash()), Vec::new(), 3);
-        let canonical_block_3 =
-            mock_block(4, Some(canonical_block_2.hash()), Vec::from([mock_tx(3)]), 4);
+        let canonical_block_1 = generator.generate_block();
+        let canonical_block_2 = generator.generate_block();
+        let canonical_block_3 = generator.generate_block();
 
-        let sidechain_block_1 = mock_block(2, Some(fork_block.hash()), Vec::from([mock_tx(1)]), 2);
-        let sidechain_block_2 =
-            mock_block(3, Some(sidechain_block_1.hash()), Vec::from([mock_tx(2)]), 3);
+        let sidechain_block_1 = sidechain.generate_block();
+        let sidechain_block_2 = sidechain.generate_block();
 
         let mut tree = BlockchainTree::new(
             TreeExternals::new(provider_factory, consensus, executor_provider),
@@ -1711,75 +2094,613 @@ mod tests {
     }
 
     #[test]
-    fn sidechain_block_hashes() {
-        let data = BlockchainTestData::default_from_number(11);
-        let (block1, exec1) = data.blocks[0].clone();
-        let (block2, exec2) = data.blocks[1].clone();
-        let (block3, exec3) = data.blocks[2].clone();
-        let (block4, exec4) = data.blocks[3].clone();
-        let genesis = data.genesis;
+    fn simulate_make_canonical_matches_reorg() {
+        let mut generator = ChainGenerator::new(
+            &Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .shanghai_activated()
+                    .build(),
+            ),
+            1,
+            3,
+        )
+        .with_txs_per_block(2);
+        let chain_spec = generator.chain_spec();
+        let provider_factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+        {
+            let provider_rw = provider_factory.provider_rw().unwrap();
+            provider_rw
+                .insert_block(
+                    SealedBlock::new(chain_spec.sealed_genesis_header(), Default::default())
+                        .try_seal_with_senders()
+                        .unwrap(),
+                    None,
+                )
+                .unwrap();
+            for (address, (account, _)) in generator.genesis_state() {
+                provider_rw.tx_ref().put::<tables::PlainAccountState>(*address, *account).unwrap();
+                provider_rw
+                    .tx_ref()
+                    .put::<tables::HashedAccounts>(keccak256(address), *account)
+                    .unwrap();
+            }
+            provider_rw.commit().unwrap();
+        }
 
-        // test pops execution results from vector, so order is from last to first.
-        let externals =
-            setup_externals(vec![exec3.clone(), exec2.clone(), exec4, exec3, exec2, exec1]);
+        let fork_block = generator.generate_block();
+        let mut sidechain = generator.fork(2);
+        let canonical = generator.generate_blocks(2);
+        let sidechain = sidechain.generate_blocks(3);
 
-        // last finalized block would be number 9.
-        setup_genesis(&externals.provider_factory, genesis);
+        let mut tree = BlockchainTree::new(
+            TreeExternals::new(
+                provider_factory.clone(),
+                Arc::new(TestConsensus::default()),
+                EthExecutorProvider::ethereum(chain_spec.clone()),
+            ),
+            BlockchainTreeConfig::default(),
+            None,
+        )
+        .expect("failed to create tree");
 
-        // make tree
-        let config = BlockchainTreeConfig::new(1, 2, 3, 2);
-        let mut tree = BlockchainTree::new(externals, config, None).expect("failed to create tree");
-        // genesis block 10 is already canonical
-        tree.make_canonical(B256::ZERO).unwrap();
+        for block in std::iter::once(&fork_block).chain(&canonical) {
+            tree.insert_block(block.clone(), BlockValidationKind::Exhaustive).unwrap();
+            tree.make_canonical(block.hash()).unwrap();
+        }
+        for block in &sidechain {
+            assert_matches!(
+                tree.insert_block(block.clone(), BlockValidationKind::Exhaustive),
+                Ok(InsertPayloadOk::Inserted(BlockStatus::Valid(_)))
+            );
+        }
 
-        // make genesis block 10 as finalized
-        tree.finalize_block(10).unwrap();
+        let sidechain_tip = sidechain.last().unwrap();
+        let simulation = tree.simulate_make_canonical(sidechain_tip.hash()).unwrap();
+        assert_eq!(simulation.fork_block, fork_block.num_hash());
+        assert!(simulation.is_safe());
+        assert!(!simulation.balance_changes.is_empty());
 
-        assert_eq!(
-            tree.insert_block(block1.clone(), BlockValidationKind::Exhaustive).unwrap(),
-            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical))
-        );
+        // the simulation didn't change the tree
+        assert_eq!(tree.block_indices().canonical_tip(), canonical[1].num_hash());
+        assert_eq!(tree.state.chains.len(), 1);
 
-        assert_eq!(
-            tree.insert_block(block2.clone(), BlockValidationKind::Exhaustive).unwrap(),
-            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical))
+        let mut canon_notif = tree.subscribe_canon_state();
+        tree.make_canonical(sidechain_tip.hash()).unwrap();
+        let (old, new) = assert_matches!(
+            canon_notif.try_recv(),
+            Ok(CanonStateNotification::Reorg { old, new }) => (old, new)
         );
 
-        assert_eq!(
-            tree.insert_block(block3.clone(), BlockValidationKind::Exhaustive).unwrap(),
-            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical))
-        );
+        let num_hashes = |chain: &Chain| chain.headers().map(|h| h.num_hash()).collect::<Vec<_>>();
+        assert_eq!(simulation.reverted_blocks, num_hashes(&old));
+        assert_eq!(simulation.applied_blocks, num_hashes(&new));
+        assert_eq!(simulation.depth(), 2);
 
+        let new_transactions = new
+            .blocks_iter()
+            .flat_map(|block| &block.body)
+            .map(|tx| tx.hash())
+            .collect::<HashSet<_>>();
         assert_eq!(
-            tree.insert_block(block4, BlockValidationKind::Exhaustive).unwrap(),
-            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical))
+            simulation.reverted_transactions,
+            old.blocks_iter().map(|block| block.body.len()).sum::<usize>()
         );
-
-        let mut block2a = block2;
-        let block2a_hash = B256::new([0x34; 32]);
-        block2a.set_hash(block2a_hash);
-
+        assert_eq!(simulation.applied_transactions, new_transactions.len());
         assert_eq!(
-            tree.insert_block(block2a.clone(), BlockValidationKind::Exhaustive).unwrap(),
-            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::HistoricalFork))
+            simulation.returned_transactions,
+            old.blocks_iter()
+                .flat_map(|block| &block.body)
+                .filter(|tx| !new_transactions.contains(&tx.hash()))
+                .count()
         );
 
-        let mut block3a = block3;
-        let block3a_hash = B256::new([0x35; 32]);
-        block3a.set_hash(block3a_hash);
-        block3a.set_parent_hash(block2a.hash());
+        let provider = provider_factory.provider().unwrap();
+        for change in &simulation.balance_changes {
+            let balance = provider.basic_account(change.address).unwrap().unwrap_or_default();
+            assert_eq!(balance.balance, change.after, "{change:?}");
+        }
 
+        // once canonical, there's nothing left to simulate
         assert_eq!(
-            tree.insert_block(block3a.clone(), BlockValidationKind::Exhaustive).unwrap(),
-            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical)) /* TODO: this is incorrect, figure out why */
+            tree.simulate_make_canonical(sidechain_tip.hash()).unwrap(),
+            ReorgSimulation::new(sidechain_tip.num_hash())
         );
+    }
 
-        let block3a_chain_id =
-            tree.state.block_indices.get_block_chain_id(&block3a.hash()).unwrap();
-        assert_eq!(
-            tree.all_chain_hashes(block3a_chain_id),
-            BTreeMap::from([
-                (block1.number, block1.hash()),
+    #[test]
+    fn execution_soft_limit_cancels_between_transactions() {
+        let mut generator = ChainGenerator::new(
+            &Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .shanghai_activated()
+                    .build(),
+            ),
+            1,
+            4,
+        )
+        .with_txs_per_block(50);
+        let chain_spec = generator.chain_spec();
+        let provider_factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+
+        {
+            let provider_rw = provider_factory.provider_rw().unwrap();
+            provider_rw
+                .insert_block(
+                    SealedBlock::new(chain_spec.sealed_genesis_header(), Default::default())
+                        .try_seal_with_senders()
+                        .unwrap(),
+                    None,
+                )
+                .unwrap();
+            for (address, (account, _)) in generator.genesis_state() {
+                provider_rw.tx_ref().put::<tables::PlainAccountState>(*address, *account).unwrap();
+                provider_rw
+                    .tx_ref()
+                    .put::<tables::HashedAccounts>(keccak256(address), *account)
+                    .unwrap();
+            }
+            provider_rw.commit().unwrap();
+        }
+
+        let block = generator.generate_block();
+
+        let externals = TreeExternals::new(
+            provider_factory,
+            Arc::new(TestConsensus::default()),
+            EthExecutorProvider::ethereum(chain_spec.clone()),
+        )
+        .with_execution_soft_limit(Duration::ZERO);
+        let mut tree = BlockchainTree::new(externals, BlockchainTreeConfig::default(), None)
+            .expect("failed to create tree");
+
+        // the limit is exceeded right away, so only the first transaction is executed
+        let err = tree.insert_block(block.clone(), BlockValidationKind::Exhaustive).unwrap_err();
+        assert_matches!(
+            err.kind(),
+            InsertBlockErrorKind::Execution(BlockExecutionError::Cancelled {
+                executed: 1,
+                total: 50
+            })
+        );
+        assert!(err.kind().is_execution_cancelled());
+        assert!(!err.kind().is_invalid_block());
+        TreeTester::default().with_chain_num(0).assert(&tree);
+
+        // the tree is still usable and accepts the block once it has enough time
+        tree.externals.execution_soft_limit = None;
+        assert_eq!(
+            tree.insert_block(block.clone(), BlockValidationKind::Exhaustive).unwrap(),
+            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical))
+        );
+        assert_eq!(
+            tree.make_canonical(block.hash()).unwrap(),
+            CanonicalOutcome::Committed { head: block.header.clone() }
+        );
+    }
+
+    #[test]
+    fn block_execution_uses_single_read_transaction() {
+        let mut generator = ChainGenerator::new(
+            &Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .shanghai_activated()
+                    .build(),
+            ),
+            1,
+            4,
+        )
+        .with_txs_per_block(50)
+        .with_withdrawals_per_block(8);
+        let chain_spec = generator.chain_spec();
+        let read_transactions = Arc::new(AtomicUsize::new(0));
+        let db = CountingDatabase {
+            inner: create_test_rw_db(),
+            read_transactions: read_transactions.clone(),
+        };
+        let (static_dir, _) = create_test_static_files_dir();
+        let provider_factory = ProviderFactory::new(
+            db,
+            chain_spec.clone(),
+            StaticFileProvider::read_write(static_dir.into_path()).unwrap(),
+        );
+
+        {
+            let provider_rw = provider_factory.provider_rw().unwrap();
+            provider_rw
+                .insert_block(
+                    SealedBlock::new(chain_spec.sealed_genesis_header(), Default::default())
+                        .try_seal_with_senders()
+                        .unwrap(),
+                    None,
+                )
+                .unwrap();
+            for (address, (account, _)) in generator.genesis_state() {
+                provider_rw.tx_ref().put::<tables::PlainAccountState>(*address, *account).unwrap();
+            }
+            provider_rw.commit().unwrap();
+        }
+
+        // every transaction sends value to a fresh address, so each of them reads accounts that
+        // are not cached yet
+        let block = generator.generate_block();
+
+        let externals = TreeExternals::new(
+            provider_factory,
+            Arc::new(TestConsensus::default()),
+            EthExecutorProvider::ethereum(chain_spec.clone()),
+        );
+        let genesis = chain_spec.sealed_genesis_header();
+        let canonical_block_hashes = BTreeMap::from([(genesis.number, genesis.hash())]);
+
+        let opened = read_transactions.load(Ordering::Relaxed);
+        let chain = AppendableChain::new_canonical_fork(
+            block.clone(),
+            &genesis,
+            &canonical_block_hashes,
+            genesis.num_hash(),
+            &externals,
+            BlockAttachment::Canonical,
+            BlockValidationKind::SkipStateRootValidation,
+        )
+        .unwrap();
+        assert_eq!(chain.tip().hash(), block.hash());
+
+        // the withdrawal lookup and all state lookups of the execution share one transaction
+        assert_eq!(read_transactions.load(Ordering::Relaxed) - opened, 1);
+    }
+
+    #[test]
+    fn retained_blocks_are_trimmed_and_rehydrated() {
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::default()
+                .chain(MAINNET.chain)
+                .genesis(Genesis { alloc: BTreeMap::default(), ..MAINNET.genesis.clone() })
+                .shanghai_activated()
+                .build(),
+        );
+        let provider_factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+        {
+            let provider_rw = provider_factory.provider_rw().unwrap();
+            provider_rw
+                .insert_block(
+                    SealedBlock::new(chain_spec.sealed_genesis_header(), Default::default())
+                        .try_seal_with_senders()
+                        .unwrap(),
+                    None,
+                )
+                .unwrap();
+            provider_rw.commit().unwrap();
+        }
+
+        let empty_block = |number: u64, parent: B256| -> SealedBlockWithSenders {
+            SealedBlockWithSenders::new(
+                SealedBlock {
+                    header: Header {
+                        number,
+                        parent_hash: parent,
+                        gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+                        mix_hash: B256::random(),
+                        base_fee_per_gas: Some(EIP1559_INITIAL_BASE_FEE),
+                        transactions_root: EMPTY_ROOT_HASH,
+                        receipts_root: EMPTY_ROOT_HASH,
+                        state_root: EMPTY_ROOT_HASH,
+                        ..Default::default()
+                    }
+                    .seal_slow(),
+                    body: Vec::new(),
+                    ommers: Vec::new(),
+                    withdrawals: Some(Withdrawals::default()),
+                    requests: None,
+                },
+                Vec::new(),
+            )
+            .unwrap()
+        };
+
+        let mut tree = BlockchainTree::new(
+            TreeExternals::new(
+                provider_factory,
+                Arc::new(TestConsensus::default()),
+                EthExecutorProvider::ethereum(chain_spec.clone()),
+            ),
+            BlockchainTreeConfig::default().with_memory_block_retention(10),
+            None,
+        )
+        .expect("failed to create tree");
+
+        // advance the canonical chain by 1000 blocks
+        let mut canonical = vec![chain_spec.genesis_hash()];
+        for number in 1..=1000 {
+            let block = empty_block(number, canonical[number as usize - 1]);
+            tree.insert_block(block.clone(), BlockValidationKind::Exhaustive).unwrap();
+            tree.make_canonical(block.hash()).unwrap();
+            canonical.push(block.hash());
+        }
+
+        // only the last 10 blocks are retained in-memory
+        assert_eq!(tree.block_indices().canonical_chain().inner().len(), 10);
+        assert_eq!(tree.block_indices().canonical_hash(&990), None);
+        assert_eq!(tree.block_indices().canonical_hash(&991), Some(canonical[991]));
+        TreeTester::default().with_chain_num(0).assert(&tree);
+
+        // build a sidechain that forks off block 988, i.e. a reorg of depth 12
+        let mut canon_notif = tree.subscribe_canon_state();
+        let mut sidechain = Vec::new();
+        let mut parent = canonical[988];
+        for number in 989..=1001 {
+            let block = empty_block(number, parent);
+            parent = block.hash();
+            assert_matches!(
+                tree.insert_block(block.clone(), BlockValidationKind::Exhaustive),
+                Ok(InsertPayloadOk::Inserted(BlockStatus::Valid(_)))
+            );
+            sidechain.push(block);
+        }
+
+        let sidechain_tip = sidechain.last().unwrap();
+        assert_eq!(
+            tree.make_canonical(sidechain_tip.hash()).unwrap(),
+            CanonicalOutcome::Committed { head: sidechain_tip.header.clone() }
+        );
+
+        // the reorged blocks were read back from the database
+        assert_matches!(canon_notif.try_recv(), Ok(CanonStateNotification::Reorg { old, new }) => {
+            assert_eq!(
+                old.blocks().values().map(|block| block.hash()).collect::<Vec<_>>(),
+                canonical[989..=1000].to_vec()
+            );
+            assert_eq!(new.tip().hash(), sidechain_tip.hash());
+        });
+
+        let provider = tree.externals.provider_factory.provider().unwrap();
+        assert_eq!(provider.block_hash(988).unwrap(), Some(canonical[988]));
+        for block in &sidechain {
+            assert_eq!(provider.block_hash(block.number).unwrap(), Some(block.hash()));
+        }
+
+        // the unwound chain forks off below the retained window and is dropped again
+        assert_eq!(tree.block_indices().canonical_chain().inner().len(), 10);
+        TreeTester::default().with_chain_num(0).assert(&tree);
+    }
+
+    #[test]
+    fn notifies_reorgs_between_sidechains() {
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::default()
+                .chain(MAINNET.chain)
+                .genesis(Genesis { alloc: BTreeMap::default(), ..MAINNET.genesis.clone() })
+                .shanghai_activated()
+                .build(),
+        );
+        let provider_factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+        {
+            let provider_rw = provider_factory.provider_rw().unwrap();
+            provider_rw
+                .insert_block(
+                    SealedBlock::new(chain_spec.sealed_genesis_header(), Default::default())
+                        .try_seal_with_senders()
+                        .unwrap(),
+                    None,
+                )
+                .unwrap();
+            provider_rw.commit().unwrap();
+        }
+
+        let empty_block = |number: u64, parent: B256| -> SealedBlockWithSenders {
+            SealedBlockWithSenders::new(
+                SealedBlock {
+                    header: Header {
+                        number,
+                        parent_hash: parent,
+                        gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+                        mix_hash: B256::random(),
+                        base_fee_per_gas: Some(EIP1559_INITIAL_BASE_FEE),
+                        transactions_root: EMPTY_ROOT_HASH,
+                        receipts_root: EMPTY_ROOT_HASH,
+                        state_root: EMPTY_ROOT_HASH,
+                        ..Default::default()
+                    }
+                    .seal_slow(),
+                    body: Vec::new(),
+                    ommers: Vec::new(),
+                    withdrawals: Some(Withdrawals::default()),
+                    requests: None,
+                },
+                Vec::new(),
+            )
+            .unwrap()
+        };
+        let hashes = |chain: &Chain| chain.blocks().values().map(|b| b.hash()).collect::<Vec<_>>();
+
+        let tree = ShareableBlockchainTree::new(
+            BlockchainTree::new(
+                TreeExternals::new(
+                    provider_factory,
+                    Arc::new(TestConsensus::default()),
+                    EthExecutorProvider::ethereum(chain_spec.clone()),
+                ),
+                BlockchainTreeConfig::default(),
+                None,
+            )
+            .expect("failed to create tree"),
+        );
+        let mut canon_notif = tree.subscribe_canon_state();
+
+        // canonical chain: genesis <- 1 <- 2
+        let block1 = empty_block(1, chain_spec.genesis_hash());
+        let block2 = empty_block(2, block1.hash());
+        for block in [&block1, &block2] {
+            tree.insert_block(block.clone(), BlockValidationKind::Exhaustive).unwrap();
+            tree.make_canonical(block.hash()).unwrap();
+            assert_matches!(canon_notif.try_recv(), Ok(CanonStateNotification::Commit { new }) => {
+                assert_eq!(hashes(&new), vec![block.hash()]);
+            });
+        }
+
+        // two sidechains fork off block 1: 1 <- a2 and 1 <- b2 <- b3
+        let a2 = empty_block(2, block1.hash());
+        let b2 = empty_block(2, block1.hash());
+        let b3 = empty_block(3, b2.hash());
+        for block in [&a2, &b2, &b3] {
+            assert_matches!(
+                tree.insert_block(block.clone(), BlockValidationKind::Exhaustive),
+                Ok(InsertPayloadOk::Inserted(BlockStatus::Valid(_)))
+            );
+        }
+        assert_matches!(canon_notif.try_recv(), Err(_));
+
+        // the longer sidechain replaces block 2
+        tree.make_canonical(b3.hash()).unwrap();
+        assert_matches!(canon_notif.try_recv(), Ok(CanonStateNotification::Reorg { old, new }) => {
+            assert_eq!(hashes(&old), vec![block2.hash()]);
+            assert_eq!(hashes(&new), vec![b2.hash(), b3.hash()]);
+        });
+
+        // the other sidechain replaces it again
+        tree.make_canonical(a2.hash()).unwrap();
+        assert_matches!(canon_notif.try_recv(), Ok(CanonStateNotification::Reorg { old, new }) => {
+            assert_eq!(hashes(&old), vec![b2.hash(), b3.hash()]);
+            assert_eq!(hashes(&new), vec![a2.hash()]);
+        });
+        assert_matches!(canon_notif.try_recv(), Err(_));
+    }
+
+    #[test]
+    fn prune_sidechains_below_finalized() {
+        let data = BlockchainTestData::default_from_number(11);
+        let externals = setup_externals(vec![]);
+        setup_genesis(&externals.provider_factory, &data.genesis);
+        let mut tree = BlockchainTree::new(externals, BlockchainTreeConfig::default(), None)
+            .expect("failed to create tree");
+
+        // canonical chain 10..=15
+        let canonical_hash = |number: BlockNumber| B256::with_last_byte(number as u8);
+        tree.state =
+            TreeState::new(9, (10..=15).map(|number| (number, canonical_hash(number))), 10);
+
+        let block = |number: BlockNumber, parent_hash: B256, seed: u64| {
+            let header =
+                Header { number, parent_hash, difficulty: U256::from(seed), ..Default::default() };
+            SealedBlockWithSenders::new(
+                SealedBlock { header: header.seal_slow(), ..Default::default() },
+                Vec::new(),
+            )
+            .unwrap()
+        };
+        let mut insert_chain = |blocks: Vec<SealedBlockWithSenders>| {
+            let chain = Chain::new(blocks, ExecutionOutcome::default(), None);
+            tree.insert_chain(AppendableChain::new(chain)).unwrap()
+        };
+
+        // sidechain forking off block 11, and a chain built on top of it
+        let a12 = block(12, canonical_hash(11), 1);
+        let a13 = block(13, a12.hash(), 1);
+        insert_chain(vec![a12.clone(), a13.clone()]);
+        let d13 = block(13, a12.hash(), 4);
+        insert_chain(vec![d13.clone()]);
+        // sidechains forking off the finalized block and above it
+        let b14 = block(14, canonical_hash(13), 2);
+        let b_id = insert_chain(vec![b14.clone()]);
+        let c15 = block(15, canonical_hash(14), 3);
+        let c_id = insert_chain(vec![c15.clone()]);
+        TreeTester::default().with_chain_num(4).assert(&tree);
+
+        let removed = tree.prune_sidechains_below(13);
+        assert_eq!(removed, vec![a12.hash(), a13.hash(), d13.hash()]);
+
+        TreeTester::default()
+            .with_chain_num(2)
+            .with_block_to_chain(HashMap::from([(b14.hash(), b_id), (c15.hash(), c_id)]))
+            .with_fork_to_child(HashMap::from([
+                (canonical_hash(13), HashSet::from([b14.hash()])),
+                (canonical_hash(14), HashSet::from([c15.hash()])),
+            ]))
+            .assert(&tree);
+        // no dangling block number entries are left behind
+        assert_eq!(
+            *tree.state.block_indices.block_number_to_block_hashes(),
+            BTreeMap::from([(14, HashSet::from([b14.hash()])), (15, HashSet::from([c15.hash()]))])
+        );
+
+        // nothing left to prune
+        assert!(tree.prune_sidechains_below(13).is_empty());
+        TreeTester::default().with_chain_num(2).assert(&tree);
+    }
+
+    #[test]
+    fn sidechain_block_hashes() {
+        let data = BlockchainTestData::default_from_number(11);
+        let (block1, exec1) = data.blocks[0].clone();
+        let (block2, exec2) = data.blocks[1].clone();
+        let (block3, exec3) = data.blocks[2].clone();
+        let (block4, exec4) = data.blocks[3].clone();
+        let genesis = data.genesis;
+
+        // test pops execution results from vector, so order is from last to first.
+        let externals =
+            setup_externals(vec![exec3.clone(), exec2.clone(), exec4, exec3, exec2, exec1]);
+
+        // last finalized block would be number 9.
+        setup_genesis(&externals.provider_factory, &genesis);
+
+        // make tree
+        let config = BlockchainTreeConfig::new(1, 2, 3, 2);
+        let mut tree = BlockchainTree::new(externals, config, None).expect("failed to create tree");
+        // genesis block 10 is already canonical
+        tree.make_canonical(genesis.hash()).unwrap();
+
+        // make genesis block 10 as finalized
+        tree.finalize_block(10).unwrap();
+
+        assert_eq!(
+            tree.insert_block(block1.clone(), BlockValidationKind::Exhaustive).unwrap(),
+            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical))
+        );
+
+        assert_eq!(
+            tree.insert_block(block2.clone(), BlockValidationKind::Exhaustive).unwrap(),
+            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical))
+        );
+
+        assert_eq!(
+            tree.insert_block(block3.clone(), BlockValidationKind::Exhaustive).unwrap(),
+            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical))
+        );
+
+        assert_eq!(
+            tree.insert_block(block4, BlockValidationKind::Exhaustive).unwrap(),
+            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical))
+        );
+
+        let block2a = reseal(block2, |header| header.mix_hash = B256::new([0x34; 32]));
+
+        assert_eq!(
+            tree.insert_block(block2a.clone(), BlockValidationKind::Exhaustive).unwrap(),
+            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::HistoricalFork))
+        );
+
+        let block3a = reseal(block3, |header| {
+            header.mix_hash = B256::new([0x35; 32]);
+            header.parent_hash = block2a.hash();
+        });
+
+        assert_eq!(
+            tree.insert_block(block3a.clone(), BlockValidationKind::Exhaustive).unwrap(),
+            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical)) /* TODO: this is incorrect, figure out why */
+        );
+
+        let block3a_chain_id =
+            tree.state.block_indices.get_block_chain_id(&block3a.hash()).unwrap();
+        assert_eq!(
+            tree.all_chain_hashes(block3a_chain_id),
+            BTreeMap::from([
+                (block1.number, block1.hash()),
                 (block2a.number, block2a.hash()),
                 (block3a.number, block3a.hash()),
             ])
@@ -1800,13 +2721,13 @@ mod tests {
         let externals = setup_externals(vec![exec5.clone(), exec4, exec3, exec2, exec1]);
 
         // last finalized block would be number 9.
-        setup_genesis(&externals.provider_factory, genesis);
+        setup_genesis(&externals.provider_factory, &genesis);
 
         // make tree
         let config = BlockchainTreeConfig::new(1, 2, 3, 2);
         let mut tree = BlockchainTree::new(externals, config, None).expect("failed to create tree");
         // genesis block 10 is already canonical
-        tree.make_canonical(B256::ZERO).unwrap();
+        tree.make_canonical(genesis.hash()).unwrap();
 
         // make genesis block 10 as finalized
         tree.finalize_block(10).unwrap();
@@ -1885,13 +2806,13 @@ mod tests {
         let externals = setup_externals(vec![exec2.clone(), exec2, exec1]);
 
         // last finalized block would be number 9.
-        setup_genesis(&externals.provider_factory, genesis);
+        setup_genesis(&externals.provider_factory, &genesis);
 
         // make tree
         let config = BlockchainTreeConfig::new(1, 2, 3, 2);
         let mut tree = BlockchainTree::new(externals, config, None).expect("failed to create tree");
         // genesis block 10 is already canonical
-        tree.make_canonical(B256::ZERO).unwrap();
+        tree.make_canonical(genesis.hash()).unwrap();
 
         // make genesis block 10 as finalized
         tree.finalize_block(10).unwrap();
@@ -1928,9 +2849,7 @@ mod tests {
             )]))
             .assert(&tree);
 
-        let mut block2a = block2.clone();
-        let block2a_hash = B256::new([0x34; 32]);
-        block2a.set_hash(block2a_hash);
+        let block2a = reseal(block2.clone(), |header| header.mix_hash = B256::new([0x34; 32]));
 
         assert_eq!(
             tree.insert_block(block2a.clone(), BlockValidationKind::Exhaustive).unwrap(),
@@ -1972,6 +2891,107 @@ mod tests {
         assert_eq!(chain1.first_block(), block2.number);
     }
 
+    #[test]
+    fn pending_block_and_receipts_from_chain_extending_canonical_tip() {
+        let data = BlockchainTestData::default_from_number(11);
+        let (block1, exec1) = data.blocks[0].clone();
+        let (block2, exec2) = data.blocks[1].clone();
+
+        // test pops execution results from vector, so order is from last to first.
+        let externals = setup_externals(vec![exec1.clone(), exec2.clone(), exec1.clone()]);
+        setup_genesis(&externals.provider_factory, &data.genesis);
+
+        let config = BlockchainTreeConfig::new(1, 2, 3, 2);
+        let tree = ShareableBlockchainTree::new(
+            BlockchainTree::new(externals, config, None).expect("failed to create tree"),
+        );
+        tree.make_canonical(data.genesis.hash()).unwrap();
+        tree.finalize_block(10).unwrap();
+        assert_eq!(tree.pending_block_and_receipts(), None);
+
+        let block1a = reseal(block1.clone(), |header| header.mix_hash = B256::new([0x1a; 32]));
+
+        // two competing chains off the canonical tip, the longer one is pending
+        for block in [block1.clone(), block2.clone(), block1a.clone()] {
+            assert_matches!(
+                tree.insert_block(block, BlockValidationKind::Exhaustive),
+                Ok(InsertPayloadOk::Inserted(BlockStatus::Valid(_)))
+            );
+        }
+        let receipts = |exec: &ExecutionOutcome, number| {
+            exec.receipts_by_block(number).iter().flatten().cloned().collect::<Vec<_>>()
+        };
+        let receipts2 = receipts(&exec2, block2.number);
+        assert!(!receipts2.is_empty());
+        assert_eq!(tree.pending_block_num_hash(), Some(block2.num_hash()));
+        assert_eq!(
+            tree.pending_block_and_receipts(),
+            Some((block2.block.clone(), receipts2.clone()))
+        );
+        assert_eq!(tree.pending_receipts(), Some(receipts2.clone()));
+
+        // once the other fork is canonical, no chain extends the canonical tip anymore, but the
+        // receipts of the sidechain blocks are still served from memory
+        tree.make_canonical(block1a.hash()).unwrap();
+        assert_eq!(tree.pending_block_and_receipts(), None);
+        assert_eq!(tree.receipts_by_block_hash(block2.hash()), Some(receipts2));
+        assert_eq!(
+            tree.receipts_by_block_hash(block1.hash()),
+            Some(receipts(&exec1, block1.number))
+        );
+        assert_eq!(tree.receipts_by_block_hash(block1a.hash()), None);
+    }
+
+    #[test]
+    fn block_insert_updates_metrics() {
+        let data = BlockchainTestData::default_from_number(11);
+        let (block1, exec1) = data.blocks[0].clone();
+
+        let externals = setup_externals(vec![exec1]);
+        setup_genesis(&externals.provider_factory, &data.genesis);
+
+        // the tree metrics are registered when the tree is created, the execution metrics when a
+        // block is executed
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let config = BlockchainTreeConfig::new(1, 2, 3, 2);
+        let tree = metrics::with_local_recorder(&recorder, || {
+            ShareableBlockchainTree::new(
+                BlockchainTree::new(externals, config, None).expect("failed to create tree"),
+            )
+        });
+        tree.make_canonical(data.genesis.hash()).unwrap();
+        tree.finalize_block(10).unwrap();
+
+        let metric = |name: &str| {
+            snapshotter
+                .snapshot()
+                .into_vec()
+                .into_iter()
+                .find_map(|(key, _, _, value)| (key.key().name() == name).then_some(value))
+        };
+        assert_matches!(
+            metric("blockchain_tree.sidechains"),
+            Some(DebugValue::Gauge(chains)) if chains.0 == 0.0
+        );
+        assert!(metric("blockchain_tree.block_execution.duration").is_none());
+
+        metrics::with_local_recorder(&recorder, || {
+            assert_matches!(
+                tree.insert_block(block1, BlockValidationKind::Exhaustive),
+                Ok(InsertPayloadOk::Inserted(BlockStatus::Valid(_)))
+            );
+        });
+        assert_matches!(
+            metric("blockchain_tree.sidechains"),
+            Some(DebugValue::Gauge(chains)) if chains.0 == 1.0
+        );
+        assert_matches!(
+            metric("blockchain_tree.block_execution.duration"),
+            Some(DebugValue::Histogram(durations)) if durations.len() == 1
+        );
+    }
+
     #[test]
     fn sanity_path() {
         let data = BlockchainTestData::default_from_number(11);
@@ -1983,7 +3003,7 @@ mod tests {
         let externals = setup_externals(vec![exec2.clone(), exec1.clone(), exec2, exec1]);
 
         // last finalized block would be number 9.
-        setup_genesis(&externals.provider_factory, genesis);
+        setup_genesis(&externals.provider_factory, &genesis);
 
         // make tree
         let config = BlockchainTreeConfig::new(1, 2, 3, 2);
@@ -1991,11 +3011,11 @@ mod tests {
 
         let mut canon_notif = tree.subscribe_canon_state();
         // genesis block 10 is already canonical
-        let head = BlockNumHash::new(10, B256::ZERO);
+        let head = BlockNumHash::new(10, genesis.hash());
         tree.make_canonical(head.hash).unwrap();
 
         // make sure is_block_hash_canonical returns true for genesis block
-        tree.is_block_hash_canonical(&B256::ZERO).unwrap();
+        tree.is_block_hash_canonical(&genesis.hash()).unwrap();
 
         // make genesis block 10 as finalized
         tree.finalize_block(head.number).unwrap();
@@ -2098,12 +3118,10 @@ mod tests {
 
         /**** INSERT SIDE BLOCKS *** */
 
-        let mut block1a = block1.clone();
-        let block1a_hash = B256::new([0x33; 32]);
-        block1a.set_hash(block1a_hash);
-        let mut block2a = block2.clone();
-        let block2a_hash = B256::new([0x34; 32]);
-        block2a.set_hash(block2a_hash);
+        let block1a = reseal(block1.clone(), |header| header.mix_hash = B256::new([0x33; 32]));
+        let block1a_hash = block1a.hash();
+        let block2a = reseal(block2.clone(), |header| header.mix_hash = B256::new([0x34; 32]));
+        let block2a_hash = block2a.hash();
 
         // reinsert two blocks that point to canonical chain
         assert_eq!(
@@ -2313,9 +3331,7 @@ mod tests {
             if *new.blocks() == BTreeMap::from([(block2.number,block2.clone())]));
 
         // insert unconnected block2b
-        let mut block2b = block2a.clone();
-        block2b.set_hash(B256::new([0x99; 32]));
-        block2b.set_parent_hash(B256::new([0x88; 32]));
+        let block2b = reseal(block2a.clone(), |header| header.parent_hash = B256::new([0x88; 32]));
 
         assert_eq!(
             tree.insert_block(block2b.clone(), BlockValidationKind::Exhaustive).unwrap(),
@@ -2368,15 +3384,21 @@ mod tests {
             provider_factory: externals.provider_factory.clone(),
             executor_factory: externals.executor_factory.clone(),
             consensus: externals.consensus.clone(),
+            bytecode_cache: externals.bytecode_cache.clone(),
+            execution_soft_limit: externals.execution_soft_limit,
+            state_root_pool: externals.state_root_pool.clone(),
         };
         let cloned_externals_2 = TreeExternals {
             provider_factory: externals.provider_factory.clone(),
             executor_factory: externals.executor_factory.clone(),
             consensus: externals.consensus.clone(),
+            bytecode_cache: externals.bytecode_cache.clone(),
+            execution_soft_limit: externals.execution_soft_limit,
+            state_root_pool: externals.state_root_pool.clone(),
         };
 
         // last finalized block would be number 9.
-        setup_genesis(&externals.provider_factory, genesis);
+        setup_genesis(&externals.provider_factory, &genesis);
 
         // make tree
         let config = BlockchainTreeConfig::new(1, 2, 3, 2);
@@ -2404,9 +3426,7 @@ mod tests {
             BlockchainTree::new(cloned_externals_1, config, None).expect("failed to create tree");
         assert_eq!(tree.block_indices().last_finalized_block(), 0);
 
-        let mut block1a = block1;
-        let block1a_hash = B256::new([0x33; 32]);
-        block1a.set_hash(block1a_hash);
+        let block1a = reseal(block1, |header| header.mix_hash = B256::new([0x33; 32]));
 
         assert_eq!(
             tree.insert_block(block1a.clone(), BlockValidationKind::Exhaustive).unwrap(),
@@ -2422,4 +3442,679 @@ mod tests {
 
         assert_eq!(tree.block_indices().last_finalized_block(), block1a.number);
     }
+
+    #[test]
+    fn defers_blocks_while_congested() {
+        let data = BlockchainTestData::default_from_number(11);
+        let (block1, exec1) = data.blocks[0].clone();
+        let (block2, exec2) = data.blocks[1].clone();
+
+        // test pops execution results from vector, so order is from last to first.
+        let externals = setup_externals(vec![exec2.clone(), exec2.clone(), exec2, exec1]);
+        setup_genesis(&externals.provider_factory, &data.genesis);
+
+        // congested with more than one buffered block, clear once the buffer is empty
+        let marks = TreeUsage { buffered_blocks: 1, chains: 10, memory: usize::MAX };
+        let retry_after = Duration::from_millis(100);
+        let backpressure = TreeBackpressure { high_water: marks, low_water: marks, retry_after };
+        let config = BlockchainTreeConfig::new(1, 2, 3, 10).with_backpressure(Some(backpressure));
+        let tree = ShareableBlockchainTree::new(
+            BlockchainTree::new(externals, config, None).expect("failed to create tree"),
+        );
+        let congestion = tree.subscribe_to_congestion();
+
+        tree.make_canonical(data.genesis.hash()).unwrap();
+        tree.finalize_block(10).unwrap();
+
+        let sibling =
+            |seed: u8| reseal(block2.clone(), |header| header.mix_hash = B256::new([seed; 32]));
+
+        // two disconnected blocks are buffered and congest the tree
+        for block in [block2.clone(), sibling(0x2a)] {
+            assert_matches!(
+                tree.insert_block(block, BlockValidationKind::Exhaustive),
+                Ok(InsertPayloadOk::Inserted(BlockStatus::Disconnected { .. }))
+            );
+        }
+        assert!(congestion.borrow().is_congested());
+
+        // new blocks are deferred
+        assert_eq!(
+            tree.insert_block(sibling(0x2b), BlockValidationKind::Exhaustive).unwrap(),
+            InsertPayloadOk::Deferred { retry_after }
+        );
+
+        // the parent of the buffered blocks is admitted and drains the buffer
+        assert_eq!(
+            tree.insert_block(block1, BlockValidationKind::Exhaustive).unwrap(),
+            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical))
+        );
+        assert_eq!(tree.tree.read().usage().buffered_blocks, 0);
+        assert!(!congestion.borrow().is_congested());
+
+        // the deferred block is admitted again
+        assert_eq!(
+            tree.insert_block(sibling(0x2b), BlockValidationKind::Exhaustive).unwrap(),
+            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::HistoricalFork))
+        );
+    }
+
+    #[test]
+    fn slow_executions_congest_the_tree() {
+        let data = BlockchainTestData::default_from_number(11);
+        let (block1, exec1) = data.blocks[0].clone();
+
+        // every execution takes a while, the results of cancelled executions aren't consumed
+        let delay = Duration::from_millis(50);
+        let mut externals = setup_externals(vec![exec1.clone(), exec1]);
+        externals.executor_factory = externals.executor_factory.with_execution_delay(delay);
+        let executor = externals.executor_factory.clone();
+        setup_genesis(&externals.provider_factory, &data.genesis);
+
+        // congested with more than one chain, clear once no chain is left
+        let marks = TreeUsage { buffered_blocks: 10, chains: 1, memory: usize::MAX };
+        let retry_after = Duration::from_millis(100);
+        let backpressure = TreeBackpressure { high_water: marks, low_water: marks, retry_after };
+        let config = BlockchainTreeConfig::new(1, 2, 3, 10).with_backpressure(Some(backpressure));
+        let tree = ShareableBlockchainTree::new(
+            BlockchainTree::new(externals, config, None).expect("failed to create tree"),
+        );
+        let mut congestion = tree.subscribe_to_congestion();
+
+        tree.make_canonical(data.genesis.hash()).unwrap();
+        tree.finalize_block(10).unwrap();
+
+        let sibling =
+            |seed: u8| reseal(block1.clone(), |header| header.mix_hash = B256::new([seed; 32]));
+
+        // an execution that exceeds the soft limit is cancelled and doesn't occupy the tree
+        tree.tree.write().externals.execution_soft_limit = Some(delay / 2);
+        let err = tree.insert_block(block1.clone(), BlockValidationKind::Exhaustive).unwrap_err();
+        assert!(err.kind().is_execution_cancelled());
+        assert_eq!(executor.executions(), 0);
+        assert_eq!(tree.tree.read().usage().chains, 0);
+        assert!(!congestion.has_changed().unwrap());
+        tree.tree.write().externals.execution_soft_limit = None;
+
+        // the slow executions of two competing blocks fill the tree
+        for block in [block1.clone(), sibling(0x2a)] {
+            let start = Instant::now();
+            assert_matches!(
+                tree.insert_block(block, BlockValidationKind::Exhaustive),
+                Ok(InsertPayloadOk::Inserted(BlockStatus::Valid(_)))
+            );
+            assert!(start.elapsed() >= delay);
+        }
+        assert_eq!(executor.executions(), 2);
+        assert!(congestion.has_changed().unwrap());
+        assert!(congestion.borrow_and_update().is_congested());
+
+        // new blocks are deferred without being executed
+        assert_eq!(
+            tree.insert_block(sibling(0x2b), BlockValidationKind::Exhaustive).unwrap(),
+            InsertPayloadOk::Deferred { retry_after }
+        );
+        assert_eq!(executor.executions(), 2);
+
+        // the tree stays congested until the competing chain is finalized away
+        tree.make_canonical(block1.hash()).unwrap();
+        assert!(!congestion.has_changed().unwrap());
+        tree.finalize_block(11).unwrap();
+        assert_eq!(tree.tree.read().usage().chains, 0);
+        assert!(congestion.has_changed().unwrap());
+        assert!(!congestion.borrow_and_update().is_congested());
+    }
+
+    #[test]
+    fn adjusts_buffer_capacity_at_runtime() {
+        let data = BlockchainTestData::default_from_number(11);
+        let (block2, _) = data.blocks[1].clone();
+
+        let externals = setup_externals(vec![]);
+        setup_genesis(&externals.provider_factory, &data.genesis);
+
+        let config = BlockchainTreeConfig::new(1, 2, 3, 3);
+        let tree = ShareableBlockchainTree::new(
+            BlockchainTree::new(externals, config, None).expect("failed to create tree"),
+        );
+        let handle = tree.config_handle();
+
+        tree.make_canonical(data.genesis.hash()).unwrap();
+        tree.finalize_block(10).unwrap();
+
+        let siblings = (1..=6u8)
+            .map(|seed| reseal(block2.clone(), |header| header.mix_hash = B256::new([seed; 32])))
+            .collect::<Vec<_>>();
+        let is_buffered = |block: &SealedBlockWithSenders| {
+            tree.tree.read().get_buffered_block(&block.hash()).is_some()
+        };
+
+        for block in &siblings[..3] {
+            tree.buffer_block(block.clone()).unwrap();
+        }
+        assert_eq!(tree.tree.read().usage().buffered_blocks, 3);
+
+        // the smaller capacity is applied on the next operation, which evicts the two least
+        // recently used blocks to make room for the new one
+        handle.set_max_unconnected_blocks(2).unwrap();
+        assert_eq!(tree.tree.read().usage().buffered_blocks, 3);
+        tree.buffer_block(siblings[3].clone()).unwrap();
+        assert_eq!(tree.tree.read().usage().buffered_blocks, 2);
+        assert!(!is_buffered(&siblings[0]));
+        assert!(!is_buffered(&siblings[1]));
+        assert!(is_buffered(&siblings[2]));
+        assert!(is_buffered(&siblings[3]));
+
+        // a capacity below the max reorg depth is rejected and keeps the current capacity
+        assert_eq!(
+            handle.set_max_unconnected_blocks(0),
+            Err(TreeConfigError::BufferBelowReorgDepth {
+                max_unconnected_blocks: 0,
+                max_reorg_depth: 1
+            })
+        );
+        assert_eq!(handle.soft_limits().max_unconnected_blocks, 2);
+
+        // with a larger capacity nothing is evicted anymore
+        handle.set_max_unconnected_blocks(4).unwrap();
+        for block in &siblings[4..] {
+            tree.buffer_block(block.clone()).unwrap();
+        }
+        assert_eq!(tree.tree.read().usage().buffered_blocks, 4);
+        assert!(siblings[2..].iter().all(is_buffered));
+        assert_eq!(tree.tree.read().config().max_unconnected_blocks(), 4);
+    }
+
+    #[test]
+    fn rejects_blocks_too_far_ahead() {
+        let data = BlockchainTestData::default_from_number(11);
+        let (block2, _) = data.blocks[1].clone();
+
+        let externals = setup_externals(vec![]);
+        setup_genesis(&externals.provider_factory, &data.genesis);
+
+        let config = BlockchainTreeConfig::new(1, 2, 3, 10).with_max_buffered_block_distance(2);
+        let mut tree = BlockchainTree::new(externals, config, None).expect("failed to create tree");
+        tree.make_canonical(data.genesis.hash()).unwrap();
+        tree.finalize_block(10).unwrap();
+
+        // block 12 is two blocks above the canonical tip and gets buffered
+        assert_matches!(
+            tree.insert_block(block2.clone(), BlockValidationKind::Exhaustive),
+            Ok(InsertPayloadOk::Inserted(BlockStatus::Disconnected { .. }))
+        );
+
+        // block 13 is rejected
+        let block3 = reseal(block2, |header| {
+            header.number = 13;
+            header.parent_hash = B256::new([0x12; 32]);
+        });
+        let err = tree.insert_block(block3.clone(), BlockValidationKind::Exhaustive).unwrap_err();
+        assert_eq!(
+            err.kind().as_tree_error(),
+            Some(BlockchainTreeError::BlockTooFarAhead {
+                block_number: 13,
+                canonical_tip: 10,
+                max_distance: 2
+            })
+        );
+        assert!(!err.kind().is_invalid_block());
+        assert_eq!(tree.get_buffered_block(&block3.hash()), None);
+
+        // blocks buffered during the pipeline sync are not limited
+        tree.buffer_block(block3.clone()).unwrap();
+        assert_eq!(tree.get_buffered_block(&block3.hash()), Some(&block3));
+    }
+
+    /// Blocks of a few interleaved forks, generated from a seed, that the model-based tests
+    /// operate on.
+    struct TreeUniverse {
+        /// Generator at the genesis block.
+        generator: ChainGenerator,
+        blocks: Vec<SealedBlockWithSenders>,
+    }
+
+    impl TreeUniverse {
+        /// Generates a main chain of six blocks, with forks off the genesis, off blocks
+        /// two and four, and a fork off the fork of block two.
+        fn new(seed: u64) -> Self {
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .shanghai_activated()
+                    .build(),
+            );
+            let generator = ChainGenerator::new(&chain_spec, seed, 1);
+
+            let mut main = generator.clone();
+            let mut genesis_fork = main.fork(seed.wrapping_add(1));
+            let mut blocks = main.generate_blocks(2);
+            let mut early_fork = main.fork(seed.wrapping_add(2));
+            blocks.extend(main.generate_blocks(2));
+            let mut late_fork = main.fork(seed.wrapping_add(3));
+            blocks.extend(main.generate_blocks(2));
+
+            blocks.extend(genesis_fork.generate_blocks(2));
+            blocks.push(early_fork.generate_block());
+            let mut nested_fork = early_fork.fork(seed.wrapping_add(4));
+            blocks.extend(early_fork.generate_blocks(2));
+            blocks.extend(nested_fork.generate_blocks(2));
+            blocks.extend(late_fork.generate_blocks(2));
+
+            Self { generator, blocks }
+        }
+
+        /// Returns a tree on top of a fresh database that only contains the genesis block.
+        fn tree(&self) -> BlockchainTree<Arc<TempDatabase<DatabaseEnv>>, EthExecutorProvider> {
+            let chain_spec = self.generator.chain_spec();
+            let provider_factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+            {
+                let provider_rw = provider_factory.provider_rw().unwrap();
+                provider_rw
+                    .insert_block(
+                        SealedBlock::new(chain_spec.sealed_genesis_header(), Default::default())
+                            .try_seal_with_senders()
+                            .unwrap(),
+                        None,
+                    )
+                    .unwrap();
+                for (address, (account, _)) in self.generator.genesis_state() {
+                    provider_rw
+                        .tx_ref()
+                        .put::<tables::PlainAccountState>(*address, *account)
+                        .unwrap();
+                    provider_rw
+                        .tx_ref()
+                        .put::<tables::HashedAccounts>(keccak256(address), *account)
+                        .unwrap();
+                }
+                provider_rw.commit().unwrap();
+            }
+
+            BlockchainTree::new(
+                TreeExternals::new(
+                    provider_factory,
+                    Arc::new(TestConsensus::default()),
+                    EthExecutorProvider::ethereum(chain_spec),
+                ),
+                BlockchainTreeConfig::default(),
+                None,
+            )
+            .expect("failed to create tree")
+        }
+    }
+
+    /// An operation on the tree, decoded from a pair of bytes.
+    #[derive(Debug, Clone, Copy)]
+    enum TreeOperation {
+        /// Inserts the block with the given index in the universe.
+        Insert(usize),
+        /// Makes the block with the given index in the universe canonical.
+        MakeCanonical(usize),
+        /// Finalizes the block with the given number.
+        Finalize(BlockNumber),
+        /// Unwinds the canonical chain to the block with the given number.
+        Unwind(BlockNumber),
+    }
+
+    impl TreeOperation {
+        /// Decodes an operation that is structurally valid for the state of the model: blocks
+        /// are finalized and unwound between the finalized block and the canonical tip.
+        fn decode((kind, arg): (u8, u8), universe: &TreeUniverse, model: &TreeModel) -> Self {
+            let index = arg as usize % universe.blocks.len();
+            let non_finalized = model.tip_number() - model.finalized + 1;
+            match kind % 8 {
+                // inserts are the most frequent, to deliver blocks out of order
+                0..=3 => Self::Insert(index),
+                4 | 5 => Self::MakeCanonical(index),
+                6 => Self::Finalize(model.finalized + arg as u64 % non_finalized),
+                _ => Self::Unwind(model.tip_number() - arg as u64 % non_finalized),
+            }
+        }
+    }
+
+    /// Observable outcome of a [`TreeOperation`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum TreeOutcome {
+        AlreadySeen,
+        Inserted,
+        Buffered,
+        AlreadyCanonical,
+        Committed,
+        Failed,
+        Done,
+    }
+
+    /// Where a block is tracked.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum TrackedBlock {
+        Canonical,
+        Sidechain,
+        Buffered,
+        Unknown,
+    }
+
+    /// A map based reimplementation of the observable state of the tree.
+    #[derive(Debug)]
+    struct TreeModel {
+        /// Number and parent hash of all blocks.
+        headers: HashMap<BlockHash, (BlockNumber, BlockHash)>,
+        /// Canonical hashes, indexed by block number.
+        canonical: Vec<BlockHash>,
+        /// Executed blocks that are not canonical.
+        sidechain: HashSet<BlockHash>,
+        /// Blocks with an unknown ancestor.
+        buffered: HashSet<BlockHash>,
+        /// The last finalized block.
+        finalized: BlockNumber,
+    }
+
+    impl TreeModel {
+        fn new(universe: &TreeUniverse) -> Self {
+            Self {
+                headers: universe
+                    .blocks
+                    .iter()
+                    .map(|block| (block.hash(), (block.number, block.parent_hash)))
+                    .collect(),
+                canonical: vec![universe.generator.genesis_header().hash()],
+                sidechain: HashSet::new(),
+                buffered: HashSet::new(),
+                finalized: 0,
+            }
+        }
+
+        fn tip_number(&self) -> BlockNumber {
+            self.canonical.len() as BlockNumber - 1
+        }
+
+        fn status(&self, hash: &BlockHash) -> TrackedBlock {
+            if self.canonical.contains(hash) {
+                TrackedBlock::Canonical
+            } else if self.sidechain.contains(hash) {
+                TrackedBlock::Sidechain
+            } else if self.buffered.contains(hash) {
+                TrackedBlock::Buffered
+            } else {
+                TrackedBlock::Unknown
+            }
+        }
+
+        /// Returns the number of the canonical block the sidechain of the block forks off.
+        fn canonical_fork(&self, mut hash: BlockHash) -> BlockNumber {
+            while self.sidechain.contains(&hash) {
+                hash = self.headers[&hash].1;
+            }
+            self.canonical.iter().position(|canonical| *canonical == hash).unwrap() as BlockNumber
+        }
+
+        fn insert(&mut self, hash: BlockHash) -> TreeOutcome {
+            let (number, parent) = self.headers[&hash];
+            match self.status(&hash) {
+                TrackedBlock::Canonical => return TreeOutcome::AlreadySeen,
+                _ if number <= self.finalized => return TreeOutcome::Failed,
+                TrackedBlock::Sidechain | TrackedBlock::Buffered => return TreeOutcome::AlreadySeen,
+                TrackedBlock::Unknown => {}
+            }
+
+            if self.status(&parent) == TrackedBlock::Canonical ||
+                self.status(&parent) == TrackedBlock::Sidechain
+            {
+                // the block and its buffered descendants are executed
+                let mut connected = vec![hash];
+                while let Some(hash) = connected.pop() {
+                    self.buffered.remove(&hash);
+                    self.sidechain.insert(hash);
+                    connected.extend(
+                        self.buffered.iter().copied().filter(|child| self.headers[child].1 == hash),
+                    );
+                }
+                TreeOutcome::Inserted
+            } else {
+                self.buffered.insert(hash);
+                TreeOutcome::Buffered
+            }
+        }
+
+        fn make_canonical(&mut self, hash: BlockHash) -> TreeOutcome {
+            match self.status(&hash) {
+                TrackedBlock::Canonical => return TreeOutcome::AlreadyCanonical,
+                TrackedBlock::Buffered | TrackedBlock::Unknown => return TreeOutcome::Failed,
+                TrackedBlock::Sidechain => {}
+            }
+
+            let fork = self.canonical_fork(hash);
+            let mut promoted = vec![hash];
+            while self.sidechain.contains(&self.headers[promoted.last().unwrap()].1) {
+                promoted.push(self.headers[promoted.last().unwrap()].1);
+            }
+
+            // the reorged canonical blocks become a sidechain
+            self.sidechain.extend(self.canonical.split_off(fork as usize + 1));
+            for hash in promoted.into_iter().rev() {
+                self.sidechain.remove(&hash);
+                self.canonical.push(hash);
+            }
+            TreeOutcome::Committed
+        }
+
+        fn finalize(&mut self, finalized: BlockNumber) -> TreeOutcome {
+            self.finalized = finalized;
+
+            // sidechains that fork off blocks below the finalized block are dropped
+            let dropped = self
+                .sidechain
+                .iter()
+                .copied()
+                .filter(|hash| self.canonical_fork(*hash) < finalized)
+                .collect::<Vec<_>>();
+            for hash in dropped {
+                self.sidechain.remove(&hash);
+            }
+
+            // buffered blocks up to the finalized block are dropped with their descendants
+            let mut dropped = self
+                .buffered
+                .iter()
+                .copied()
+                .filter(|hash| self.headers[hash].0 <= finalized)
+                .collect::<Vec<_>>();
+            while let Some(hash) = dropped.pop() {
+                self.buffered.remove(&hash);
+                dropped.extend(
+                    self.buffered.iter().copied().filter(|child| self.headers[child].1 == hash),
+                );
+            }
+            TreeOutcome::Done
+        }
+
+        fn unwind(&mut self, unwind_to: BlockNumber) -> TreeOutcome {
+            // the unwound blocks become a sidechain
+            self.sidechain.extend(self.canonical.split_off(unwind_to as usize + 1));
+            TreeOutcome::Done
+        }
+    }
+
+    fn apply_to_tree<DB: Database + Clone, E: BlockExecutorProvider>(
+        tree: &mut BlockchainTree<DB, E>,
+        universe: &TreeUniverse,
+        operation: TreeOperation,
+    ) -> TreeOutcome {
+        match operation {
+            TreeOperation::Insert(index) => {
+                let block = universe.blocks[index].clone();
+                match tree.insert_block(block, BlockValidationKind::Exhaustive) {
+                    Ok(InsertPayloadOk::AlreadySeen(_)) => TreeOutcome::AlreadySeen,
+                    Ok(InsertPayloadOk::Inserted(BlockStatus::Disconnected { .. })) => {
+                        TreeOutcome::Buffered
+                    }
+                    Ok(InsertPayloadOk::Inserted(_)) => TreeOutcome::Inserted,
+                    Err(_) => TreeOutcome::Failed,
+                }
+            }
+            TreeOperation::MakeCanonical(index) => {
+                match tree.make_canonical(universe.blocks[index].hash()) {
+                    Ok(CanonicalOutcome::AlreadyCanonical { .. }) => TreeOutcome::AlreadyCanonical,
+                    Ok(CanonicalOutcome::Committed { .. }) => TreeOutcome::Committed,
+                    Err(_) => TreeOutcome::Failed,
+                }
+            }
+            TreeOperation::Finalize(number) => {
+                tree.finalize_block(number).unwrap();
+                TreeOutcome::Done
+            }
+            TreeOperation::Unwind(number) => {
+                tree.unwind(number).unwrap();
+                TreeOutcome::Done
+            }
+        }
+    }
+
+    fn apply_to_model(
+        model: &mut TreeModel,
+        universe: &TreeUniverse,
+        operation: TreeOperation,
+    ) -> TreeOutcome {
+        match operation {
+            TreeOperation::Insert(index) => model.insert(universe.blocks[index].hash()),
+            TreeOperation::MakeCanonical(index) => {
+                model.make_canonical(universe.blocks[index].hash())
+            }
+            TreeOperation::Finalize(number) => model.finalize(number),
+            TreeOperation::Unwind(number) => model.unwind(number),
+        }
+    }
+
+    /// Asserts the invariants between the chains and the block indices of the tree.
+    fn assert_tree_invariants<DB: Database + Clone, E: BlockExecutorProvider>(
+        tree: &BlockchainTree<DB, E>,
+    ) {
+        let indices = tree.block_indices();
+
+        let mut chain_blocks = 0;
+        for (chain_id, chain) in &tree.state.chains {
+            for block in chain.blocks().values() {
+                assert_eq!(indices.get_block_chain_id(&block.hash()), Some(*chain_id));
+            }
+            chain_blocks += chain.blocks().len();
+
+            let fork = chain.fork_block();
+            assert!(
+                indices.canonical_hash(&fork.number) == Some(fork.hash) ||
+                    indices.get_block_chain_id(&fork.hash).is_some_and(|id| id != *chain_id),
+                "chain {chain_id:?} forks off unknown block {fork:?}"
+            );
+            assert!(
+                indices
+                    .fork_to_child()
+                    .get(&fork.hash)
+                    .is_some_and(|children| children.contains(&chain.first().hash())),
+                "chain {chain_id:?} is missing in the children of its fork block {fork:?}"
+            );
+        }
+        // no block is part of two chains, and no removed block is left in the index
+        assert_eq!(indices.blocks_to_chain().len(), chain_blocks);
+
+        let provider = tree.externals.provider_factory.provider().unwrap();
+        for (number, hash) in indices.canonical_chain().inner() {
+            assert_eq!(provider.block_hash(*number).unwrap(), Some(*hash), "block {number}");
+        }
+        assert_eq!(provider.last_block_number().unwrap(), indices.canonical_tip().number);
+    }
+
+    /// Runs the operations against a fresh tree and the model, and asserts after every operation
+    /// that the observable state of the tree matches the model and that its invariants hold.
+    fn run_tree_operations(universe: &TreeUniverse, operations: &[(u8, u8)]) {
+        let mut tree = universe.tree();
+        let mut model = TreeModel::new(universe);
+
+        for (step, operation) in operations.iter().enumerate() {
+            let operation = TreeOperation::decode(*operation, universe, &model);
+            let context = format!("step {step}: {operation:?}");
+
+            let expected = apply_to_model(&mut model, universe, operation);
+            assert_eq!(apply_to_tree(&mut tree, universe, operation), expected, "{context}");
+
+            for (index, block) in universe.blocks.iter().enumerate() {
+                let hash = block.hash();
+                let tracked = if tree.is_block_hash_canonical(&hash).unwrap() {
+                    TrackedBlock::Canonical
+                } else if tree.sidechain_block_by_hash(hash).is_some() {
+                    TrackedBlock::Sidechain
+                } else if tree.get_buffered_block(&hash).is_some() {
+                    TrackedBlock::Buffered
+                } else {
+                    TrackedBlock::Unknown
+                };
+                assert_eq!(tracked, model.status(&hash), "{context}, block {index}");
+            }
+            assert_eq!(
+                tree.block_indices().canonical_tip().hash,
+                *model.canonical.last().unwrap(),
+                "{context}"
+            );
+            assert_eq!(tree.block_indices().last_finalized_block(), model.finalized, "{context}");
+            let buffered = tree.state.buffered_blocks.blocks().len();
+            assert_eq!(buffered, model.buffered.len(), "{context}");
+            assert_tree_invariants(&tree);
+        }
+    }
+
+    /// Fuzz target for long runs with `cargo test-fuzz`.
+    #[test_fuzz::test_fuzz]
+    fn fuzz_tree_operations(seed: u64, operations: Vec<(u8, u8)>) {
+        run_tree_operations(&TreeUniverse::new(seed), &operations);
+    }
+
+    #[test]
+    fn tree_operations_regressions() {
+        // out of order delivery of a fork and a nested fork, reorgs between them, an unwind, a
+        // finalization that drops the fork off the genesis, and a late fork on top of a reorg
+        fuzz_tree_operations(
+            1,
+            vec![
+                // canonical chain up to block 2, then the early fork in reverse order
+                (0, 0),
+                (0, 1),
+                (4, 1),
+                (0, 10),
+                (0, 9),
+                (0, 8),
+                // main chain blocks 3 and 4 out of order, the fork off the genesis
+                (0, 3),
+                (0, 2),
+                (0, 6),
+                (4, 10),
+                // nested fork in reverse order, reorg to it and unwind to block 3
+                (0, 12),
+                (0, 11),
+                (4, 12),
+                (7, 2),
+                // finalize block 1, then reorg back to the main chain and extend the late fork
+                (6, 1),
+                (0, 7),
+                (4, 3),
+                (0, 13),
+                (0, 14),
+                (4, 14),
+                // blocks at or below the finalized block are rejected
+                (0, 6),
+            ],
+        );
+    }
+
+    #[test]
+    fn tree_operations_match_model() {
+        let universe = TreeUniverse::new(1);
+        let config = ProptestConfig { cases: 32, failure_persistence: None, ..Default::default() };
+        let mut runner =
+            TestRunner::new_with_rng(config, TestRng::deterministic_rng(RngAlgorithm::ChaCha));
+        runner
+            .run(&vec(any::<(u8, u8)>(), 1..48), |operations| {
+                run_tree_operations(&universe, &operations);
+                Ok(())
+            })
+            .unwrap();
+    }
 }
@@ -4,14 +4,18 @@
 //! blocks, as well as a list of the blocks the chain is composed of.
 
 use super::externals::TreeExternals;
-use crate::BundleStateDataRef;
+use crate::{metrics::BlockExecutionMetrics, BundleStateDataRef};
 use reth_blockchain_tree_api::{
     error::{BlockchainTreeError, InsertBlockErrorKind},
     BlockAttachment, BlockValidationKind,
 };
 use reth_consensus::{Consensus, ConsensusError, PostExecutionInput};
+use reth_consensus_common::validation::validate_withdrawal_index_continuity;
 use reth_db_api::database::Database;
-use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider, Executor};
+use reth_evm::execute::{
+    BlockExecutionInput, BlockExecutionOutput, BlockExecutorProvider, ExecutionCancellation,
+    Executor,
+};
 use reth_execution_errors::BlockExecutionError;
 use reth_primitives::{
     BlockHash, BlockNumber, ForkBlock, GotExpected, SealedBlockWithSenders, SealedHeader, U256,
@@ -19,6 +23,7 @@ use reth_primitives::{
 use reth_provider::{
     providers::{BundleStateProvider, ConsistentDbView},
     Chain, ExecutionOutcome, FullExecutionDataProvider, ProviderError, StateRootProvider,
+    WithdrawalsProvider,
 };
 use reth_revm::database::StateProviderDatabase;
 use reth_trie::updates::TrieUpdates;
@@ -91,6 +96,7 @@ impl AppendableChain {
         let (bundle_state, trie_updates) = Self::validate_and_execute(
             block.clone(),
             parent_header,
+            None,
             state_provider,
             externals,
             block_attachment,
@@ -134,9 +140,11 @@ impl AppendableChain {
             canonical_block_hashes,
             canonical_fork,
         };
+        let last_withdrawal_index = self.last_withdrawal_index(parent_number);
         let (block_state, _) = Self::validate_and_execute(
             block.clone(),
             parent,
+            last_withdrawal_index,
             bundle_state_data,
             externals,
             BlockAttachment::HistoricalFork,
@@ -166,14 +174,18 @@ impl AppendableChain {
     ///   - [`BlockAttachment`] represents if the block extends the canonical chain, and thus we can
     ///     cache the trie state updates.
     ///   - [`BlockValidationKind`] determines if the state root __should__ be validated.
+    ///
+    /// The `last_withdrawal_index` is the index of the last withdrawal in the sidechain blocks
+    /// preceding the block, if there is none the canonical chain up to the fork is checked.
     fn validate_and_execute<EDP, DB, E>(
         block: SealedBlockWithSenders,
         parent_block: &SealedHeader,
+        last_withdrawal_index: Option<u64>,
         bundle_state_data_provider: EDP,
         externals: &TreeExternals<DB, E>,
         block_attachment: BlockAttachment,
         block_validation_kind: BlockValidationKind,
-    ) -> Result<(ExecutionOutcome, Option<TrieUpdates>), BlockExecutionError>
+    ) -> Result<(ExecutionOutcome, Option<TrieUpdates>), InsertBlockErrorKind>
     where
         EDP: FullExecutionDataProvider,
         DB: Database + Clone,
@@ -185,17 +197,33 @@ impl AppendableChain {
         // get the state provider.
         let canonical_fork = bundle_state_data_provider.canonical_fork();
 
-        // SAFETY: For block execution and parallel state root computation below we open multiple
-        // independent database transactions. Upon opening the database transaction the consistent
-        // view will check a current tip in the database and throw an error if it doesn't match
-        // the one recorded during initialization.
+        // All reads of the execution are served by the single read transaction of this view, it's
+        // dropped together with the state provider once the block is executed and validated.
+        let mut read_view = externals.consistent_read_view()?;
+
+        // the withdrawals have to continue the global withdrawal index sequence of the parent chain
+        if let Some(withdrawals) = &block.withdrawals {
+            let last_withdrawal_index = match last_withdrawal_index {
+                Some(index) => Some(index),
+                None => read_view
+                    .provider()?
+                    .latest_withdrawal_until(canonical_fork.number)?
+                    .map(|withdrawal| withdrawal.index),
+            };
+            validate_withdrawal_index_continuity(withdrawals, last_withdrawal_index)?;
+        }
+
+        // SAFETY: The parallel state root computation below opens multiple independent database
+        // transactions. Upon opening the database transaction the consistent view will check a
+        // current tip in the database and throw an error if it doesn't match the one the
+        // execution observed.
         // It is safe to use consistent view without any special error handling as long as
         // we guarantee that plain state cannot change during processing of new payload.
         // The usage has to be re-evaluated if that was ever to change.
         let consistent_view =
-            ConsistentDbView::new_with_latest_tip(externals.provider_factory.clone())?;
-        let state_provider = consistent_view
-            .provider_ro()?
+            ConsistentDbView::new(externals.provider_factory.clone(), read_view.tip()?);
+        let state_provider = read_view
+            .into_provider()?
             // State root calculation can take a while, and we're sure no write transaction
             // will be open in parallel. See https://github.com/paradigmxyz/reth/issues/7509.
             .disable_long_read_transaction_safety()
@@ -203,16 +231,45 @@ impl AppendableChain {
 
         let provider = BundleStateProvider::new(state_provider, bundle_state_data_provider);
 
-        let db = StateProviderDatabase::new(&provider);
+        let db = StateProviderDatabase::new(&provider)
+            .with_bytecode_cache(externals.bytecode_cache.clone());
         let executor = externals.executor_factory.executor(db);
         let block_hash = block.hash();
         let block = block.unseal();
 
-        let state = executor.execute((&block, U256::MAX).into())?;
-        let BlockExecutionOutput { state, receipts, requests, .. } = state;
-        externals
+        // the execution is cancelled between transactions once it exceeds the soft limit, the
+        // executor is consumed, so the partially executed state is dropped with it
+        let cancellation =
+            externals.execution_soft_limit.map(ExecutionCancellation::with_soft_limit);
+        let mut input = BlockExecutionInput::new(&block, U256::MAX);
+        input.cancellation = cancellation.as_ref();
+
+        let start = Instant::now();
+        let output = executor.execute(input);
+        let elapsed = start.elapsed();
+
+        let metrics = BlockExecutionMetrics::default();
+        metrics.duration.record(elapsed);
+        tracing::Span::current().record("execution_elapsed", tracing::field::debug(elapsed));
+        if output.as_ref().is_err_and(BlockExecutionError::is_cancelled) {
+            metrics.cancelled.increment(1);
+            tracing::warn!(
+                target: "blockchain_tree::chain",
+                number = block.number,
+                hash = %block_hash,
+                ?elapsed,
+                "Block execution exceeded the soft limit and was cancelled"
+            );
+        }
+
+        let BlockExecutionOutput { state, receipts, requests, unsuccessful, .. } = output?;
+        // the outcomes of the failed transactions help to tell why the result differs
+        if let Err(error) = externals
             .consensus
-            .validate_block_post_execution(&block, PostExecutionInput::new(&receipts, &requests))?;
+            .validate_block_post_execution(&block, PostExecutionInput::new(&receipts, &requests))
+        {
+            return Err(InsertBlockErrorKind::PostExecution { error, unsuccessful })
+        }
 
         let initial_execution_outcome =
             ExecutionOutcome::new(state, receipts.into(), block.number, vec![requests.into()]);
@@ -225,9 +282,16 @@ impl AppendableChain {
             let (state_root, trie_updates) = if block_attachment.is_canonical() {
                 let mut execution_outcome =
                     provider.block_execution_data_provider.execution_outcome().clone();
+                // the parallel state root opens its own transactions, so the read transaction of
+                // the execution is released right away
+                drop(provider);
                 execution_outcome.extend(initial_execution_outcome.clone());
                 let hashed_state = execution_outcome.hash_state_slow();
-                ParallelStateRoot::new(consistent_view, hashed_state)
+                let mut state_root = ParallelStateRoot::new(consistent_view, hashed_state);
+                if let Some(pool) = &externals.state_root_pool {
+                    state_root = state_root.with_thread_pool(pool.clone());
+                }
+                state_root
                     .incremental_root_with_updates()
                     .map(|(root, updates)| (root, Some(updates)))
                     .map_err(ProviderError::from)?
@@ -283,6 +347,7 @@ impl AppendableChain {
         E: BlockExecutorProvider,
     {
         let parent_block = self.chain.tip();
+        let last_withdrawal_index = self.last_withdrawal_index(parent_block.number);
 
         let bundle_state_data = BundleStateDataRef {
             execution_outcome: self.execution_outcome(),
@@ -294,6 +359,7 @@ impl AppendableChain {
         let (block_state, _) = Self::validate_and_execute(
             block.clone(),
             parent_block,
+            last_withdrawal_index,
             bundle_state_data,
             externals,
             block_attachment,
@@ -304,4 +370,12 @@ impl AppendableChain {
 
         Ok(())
     }
+
+    /// Returns the index of the last withdrawal in the blocks of this chain up to and including
+    /// the given block number.
+    fn last_withdrawal_index(&self, block_number: BlockNumber) -> Option<u64> {
+        self.blocks().range(..=block_number).rev().find_map(|(_, block)| {
+            block.withdrawals.as_ref().and_then(|withdrawals| withdrawals.last()).map(|w| w.index)
+        })
+    }
 }
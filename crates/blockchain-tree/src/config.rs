@@ -1,5 +1,8 @@
 //! Blockchain tree configuration
 
+use std::{sync::Arc, time::Duration};
+use tokio::sync::watch;
+
 /// The configuration for the blockchain tree.
 #[derive(Clone, Copy, Debug)]
 pub struct BlockchainTreeConfig {
@@ -11,6 +14,10 @@ pub struct BlockchainTreeConfig {
     max_reorg_depth: u64,
     /// The number of unconnected blocks that we are buffering
     max_unconnected_blocks: u32,
+    /// The max distance above the canonical tip at which unconnected blocks are buffered.
+    ///
+    /// Blocks further ahead are rejected instead of filling the buffer.
+    max_buffered_block_distance: u64,
     /// Number of additional block hashes to save in blockchain tree. For `BLOCKHASH` EVM opcode we
     /// need last 256 block hashes.
     ///
@@ -19,6 +26,13 @@ pub struct BlockchainTreeConfig {
     /// be 256. It covers both number of blocks required for reorg, and number of blocks
     /// required for `BLOCKHASH` EVM opcode.
     num_of_additional_canonical_block_hashes: u64,
+    /// Number of most recent canonical blocks that are kept in-memory after they were persisted.
+    ///
+    /// Sidechains that fork off below this window are dropped together with their execution
+    /// outcomes, and reorgs deeper than the window are served from the database changesets.
+    memory_block_retention: u64,
+    /// Budgets above which new blocks are deferred. If [None], blocks are never deferred.
+    backpressure: Option<TreeBackpressure>,
 }
 
 impl Default for BlockchainTreeConfig {
@@ -33,11 +47,22 @@ impl Default for BlockchainTreeConfig {
             num_of_additional_canonical_block_hashes: 256,
             // max unconnected blocks.
             max_unconnected_blocks: 200,
+            // Well above the distance at which the engine syncs with the pipeline instead.
+            max_buffered_block_distance: 1024,
+            // Covers the `BLOCKHASH` window, so the common case never has to hit the database.
+            memory_block_retention: 256,
+            backpressure: None,
         }
     }
 }
 
 impl BlockchainTreeConfig {
+    /// Returns a builder that starts from the default configuration and validates it on
+    /// [build](BlockchainTreeConfigBuilder::build).
+    pub fn builder() -> BlockchainTreeConfigBuilder {
+        BlockchainTreeConfigBuilder::default()
+    }
+
     /// Create tree configuration.
     pub fn new(
         max_reorg_depth: u64,
@@ -54,9 +79,35 @@ impl BlockchainTreeConfig {
             max_reorg_depth,
             num_of_additional_canonical_block_hashes,
             max_unconnected_blocks,
+            max_buffered_block_distance: 1024,
+            memory_block_retention: 256,
+            backpressure: None,
         }
     }
 
+    /// Set the max distance above the canonical tip at which unconnected blocks are buffered.
+    pub const fn with_max_buffered_block_distance(mut self, max_distance: u64) -> Self {
+        self.max_buffered_block_distance = max_distance;
+        self
+    }
+
+    /// Set the number of canonical blocks that are retained in-memory after persistence.
+    ///
+    /// # Panics
+    ///
+    /// If `memory_block_retention` is zero, the canonical tip always has to be retained.
+    pub const fn with_memory_block_retention(mut self, memory_block_retention: u64) -> Self {
+        assert!(memory_block_retention > 0, "Canonical tip should always be retained");
+        self.memory_block_retention = memory_block_retention;
+        self
+    }
+
+    /// Set the budgets above which new blocks are deferred.
+    pub const fn with_backpressure(mut self, backpressure: Option<TreeBackpressure>) -> Self {
+        self.backpressure = backpressure;
+        self
+    }
+
     /// Return the maximum reorg depth.
     pub const fn max_reorg_depth(&self) -> u64 {
         self.max_reorg_depth
@@ -88,4 +139,441 @@ impl BlockchainTreeConfig {
     pub const fn max_unconnected_blocks(&self) -> u32 {
         self.max_unconnected_blocks
     }
+
+    /// Return the max distance above the canonical tip at which unconnected blocks are buffered.
+    pub const fn max_buffered_block_distance(&self) -> u64 {
+        self.max_buffered_block_distance
+    }
+
+    /// Return the number of canonical blocks that are retained in-memory after persistence.
+    pub const fn memory_block_retention(&self) -> u64 {
+        self.memory_block_retention
+    }
+
+    /// Return the budgets above which new blocks are deferred, if any.
+    pub const fn backpressure(&self) -> Option<TreeBackpressure> {
+        self.backpressure
+    }
+
+    /// Return the limits that can be adjusted while the tree is running.
+    pub const fn soft_limits(&self) -> TreeSoftLimits {
+        TreeSoftLimits {
+            max_unconnected_blocks: self.max_unconnected_blocks,
+            memory_block_retention: self.memory_block_retention,
+            backpressure: self.backpressure,
+        }
+    }
+
+    /// Replace the limits that can be adjusted while the tree is running.
+    ///
+    /// The limits are not validated, see [`BlockchainTreeConfig::validate`].
+    pub const fn with_soft_limits(mut self, limits: TreeSoftLimits) -> Self {
+        self.max_unconnected_blocks = limits.max_unconnected_blocks;
+        self.memory_block_retention = limits.memory_block_retention;
+        self.backpressure = limits.backpressure;
+        self
+    }
+
+    /// Checks that the configured limits are consistent with each other.
+    pub const fn validate(&self) -> Result<(), TreeConfigError> {
+        if self.max_reorg_depth > self.max_blocks_in_chain {
+            return Err(TreeConfigError::ReorgDepthExceedsChainLength {
+                max_reorg_depth: self.max_reorg_depth,
+                max_blocks_in_chain: self.max_blocks_in_chain,
+            })
+        }
+        if self.memory_block_retention == 0 {
+            return Err(TreeConfigError::ZeroMemoryBlockRetention)
+        }
+        if self.memory_block_retention < self.max_reorg_depth {
+            return Err(TreeConfigError::RetentionBelowReorgDepth {
+                memory_block_retention: self.memory_block_retention,
+                max_reorg_depth: self.max_reorg_depth,
+            })
+        }
+        if (self.max_unconnected_blocks as u64) < self.max_reorg_depth {
+            return Err(TreeConfigError::BufferBelowReorgDepth {
+                max_unconnected_blocks: self.max_unconnected_blocks,
+                max_reorg_depth: self.max_reorg_depth,
+            })
+        }
+        if let Some(backpressure) = self.backpressure {
+            let TreeBackpressure { high_water, low_water, .. } = backpressure;
+            if low_water.buffered_blocks > high_water.buffered_blocks ||
+                low_water.chains > high_water.chains ||
+                low_water.memory > high_water.memory
+            {
+                return Err(TreeConfigError::LowWaterAboveHighWater { low_water, high_water })
+            }
+            if high_water.buffered_blocks >= self.max_unconnected_blocks as usize {
+                return Err(TreeConfigError::HighWaterAboveBufferCapacity {
+                    buffered_blocks: high_water.buffered_blocks,
+                    max_unconnected_blocks: self.max_unconnected_blocks,
+                })
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Builder for a validated [`BlockchainTreeConfig`].
+///
+/// Every setter documents the default that is used if it is not called.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BlockchainTreeConfigBuilder {
+    config: BlockchainTreeConfig,
+}
+
+impl BlockchainTreeConfigBuilder {
+    /// Set the number of blocks that can be re-orged. Defaults to `64`.
+    pub const fn max_reorg_depth(mut self, max_reorg_depth: u64) -> Self {
+        self.config.max_reorg_depth = max_reorg_depth;
+        self
+    }
+
+    /// Set the number of blocks after the last finalized block that are stored. Defaults to `65`.
+    pub const fn max_blocks_in_chain(mut self, max_blocks_in_chain: u64) -> Self {
+        self.config.max_blocks_in_chain = max_blocks_in_chain;
+        self
+    }
+
+    /// Set the number of additional canonical block hashes to retain. Defaults to `256`.
+    pub const fn num_of_additional_canonical_block_hashes(mut self, num: u64) -> Self {
+        self.config.num_of_additional_canonical_block_hashes = num;
+        self
+    }
+
+    /// Set the number of unconnected blocks that are buffered. Defaults to `200`.
+    pub const fn max_unconnected_blocks(mut self, max_unconnected_blocks: u32) -> Self {
+        self.config.max_unconnected_blocks = max_unconnected_blocks;
+        self
+    }
+
+    /// Set the max distance above the canonical tip at which unconnected blocks are buffered.
+    /// Defaults to `1024`.
+    pub const fn max_buffered_block_distance(mut self, max_distance: u64) -> Self {
+        self.config.max_buffered_block_distance = max_distance;
+        self
+    }
+
+    /// Set the number of canonical blocks that are retained in-memory after persistence.
+    /// Defaults to `256`.
+    pub const fn memory_block_retention(mut self, memory_block_retention: u64) -> Self {
+        self.config.memory_block_retention = memory_block_retention;
+        self
+    }
+
+    /// Set the budgets above which new blocks are deferred. Defaults to [None].
+    pub const fn backpressure(mut self, backpressure: Option<TreeBackpressure>) -> Self {
+        self.config.backpressure = backpressure;
+        self
+    }
+
+    /// Validates and returns the configuration.
+    pub fn build(self) -> Result<BlockchainTreeConfig, TreeConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+/// Inconsistent [`BlockchainTreeConfig`] limits.
+#[derive(thiserror::Error, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeConfigError {
+    /// The side chains can't hold a reorg of the maximum depth.
+    #[error("max reorg depth {max_reorg_depth} exceeds max blocks in chain {max_blocks_in_chain}")]
+    ReorgDepthExceedsChainLength {
+        /// The configured max reorg depth.
+        max_reorg_depth: u64,
+        /// The configured max number of blocks in one chain.
+        max_blocks_in_chain: u64,
+    },
+    /// The canonical tip always has to be retained.
+    #[error("memory block retention must not be zero")]
+    ZeroMemoryBlockRetention,
+    /// Reorgs that the consensus allows would have to be served from the database.
+    #[error("retention of {memory_block_retention} blocks is below reorg depth {max_reorg_depth}")]
+    RetentionBelowReorgDepth {
+        /// The configured memory block retention.
+        memory_block_retention: u64,
+        /// The configured max reorg depth.
+        max_reorg_depth: u64,
+    },
+    /// The buffer can't hold the blocks of a reorg of the maximum depth, which the consensus
+    /// layer sends in a burst if the parent is missing.
+    #[error("buffer of {max_unconnected_blocks} blocks is below reorg depth {max_reorg_depth}")]
+    BufferBelowReorgDepth {
+        /// The configured max number of buffered blocks.
+        max_unconnected_blocks: u32,
+        /// The configured max reorg depth.
+        max_reorg_depth: u64,
+    },
+    /// A low-water mark is above its high-water mark, so a congested tree might never clear.
+    #[error("low-water marks {low_water:?} are above high-water marks {high_water:?}")]
+    LowWaterAboveHighWater {
+        /// The configured low-water marks.
+        low_water: TreeUsage,
+        /// The configured high-water marks.
+        high_water: TreeUsage,
+    },
+    /// The buffer evicts blocks before the tree becomes congested.
+    #[error("high-water of {buffered_blocks} blocks reaches buffer of {max_unconnected_blocks}")]
+    HighWaterAboveBufferCapacity {
+        /// The configured high-water mark of buffered blocks.
+        buffered_blocks: usize,
+        /// The configured max number of buffered blocks.
+        max_unconnected_blocks: u32,
+    },
+}
+
+/// Limits of the blockchain tree that can be adjusted while the tree is running.
+///
+/// Limits that affect the consensus behavior, like the max reorg depth, are fixed once the tree
+/// is created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TreeSoftLimits {
+    /// The number of unconnected blocks that are buffered.
+    pub max_unconnected_blocks: u32,
+    /// The number of canonical blocks that are retained in-memory after persistence.
+    pub memory_block_retention: u64,
+    /// Budgets above which new blocks are deferred.
+    pub backpressure: Option<TreeBackpressure>,
+}
+
+/// Handle to adjust the [soft limits](TreeSoftLimits) of a running
+/// [`ShareableBlockchainTree`](crate::ShareableBlockchainTree).
+///
+/// New limits are validated against the fixed limits of the tree and applied at the start of the
+/// next write operation on the tree, never in the middle of one.
+#[derive(Clone, Debug)]
+pub struct TreeConfigHandle {
+    /// The configuration the tree was created with.
+    config: BlockchainTreeConfig,
+    /// The latest requested soft limits.
+    limits: Arc<watch::Sender<TreeSoftLimits>>,
+}
+
+impl TreeConfigHandle {
+    /// Creates a new handle for a tree with the given configuration.
+    pub(crate) const fn new(
+        config: BlockchainTreeConfig,
+        limits: Arc<watch::Sender<TreeSoftLimits>>,
+    ) -> Self {
+        Self { config, limits }
+    }
+
+    /// Returns the latest requested soft limits.
+    pub fn soft_limits(&self) -> TreeSoftLimits {
+        *self.limits.borrow()
+    }
+
+    /// Requests new soft limits.
+    ///
+    /// Returns an error and keeps the current limits if they are inconsistent with the
+    /// configuration of the tree.
+    pub fn set_soft_limits(&self, limits: TreeSoftLimits) -> Result<(), TreeConfigError> {
+        self.update_soft_limits(|current| *current = limits)
+    }
+
+    /// Requests a new capacity of the block buffer.
+    ///
+    /// If the buffer holds more blocks, the least recently used ones are evicted.
+    pub fn set_max_unconnected_blocks(
+        &self,
+        max_unconnected_blocks: u32,
+    ) -> Result<(), TreeConfigError> {
+        self.update_soft_limits(|limits| limits.max_unconnected_blocks = max_unconnected_blocks)
+    }
+
+    /// Requests a new number of canonical blocks that are retained in-memory after persistence.
+    ///
+    /// The retained window is trimmed on the next canonicalization.
+    pub fn set_memory_block_retention(
+        &self,
+        memory_block_retention: u64,
+    ) -> Result<(), TreeConfigError> {
+        self.update_soft_limits(|limits| limits.memory_block_retention = memory_block_retention)
+    }
+
+    /// Requests new budgets above which new blocks are deferred.
+    pub fn set_backpressure(
+        &self,
+        backpressure: Option<TreeBackpressure>,
+    ) -> Result<(), TreeConfigError> {
+        self.update_soft_limits(|limits| limits.backpressure = backpressure)
+    }
+
+    /// Applies the update to the latest requested soft limits if the result is consistent with
+    /// the configuration of the tree.
+    ///
+    /// The update happens under the lock of the channel, so concurrent updates don't overwrite
+    /// each other.
+    fn update_soft_limits(
+        &self,
+        update: impl FnOnce(&mut TreeSoftLimits),
+    ) -> Result<(), TreeConfigError> {
+        let mut result = Ok(());
+        self.limits.send_if_modified(|current| {
+            let mut limits = *current;
+            update(&mut limits);
+            result = self.config.with_soft_limits(limits).validate();
+            if result.is_err() || limits == *current {
+                return false
+            }
+            *current = limits;
+            true
+        });
+        result
+    }
+}
+
+/// Budgets of the blockchain tree above which new blocks are deferred.
+///
+/// The tree becomes congested once any usage exceeds its high-water mark, and clears once all
+/// usages are below their low-water marks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TreeBackpressure {
+    /// Usage above which the tree becomes congested.
+    pub high_water: TreeUsage,
+    /// Usage below which a congested tree clears.
+    pub low_water: TreeUsage,
+    /// How long the consensus layer should wait before it sends a deferred block again.
+    pub retry_after: Duration,
+}
+
+impl Default for TreeBackpressure {
+    fn default() -> Self {
+        Self {
+            // Below the default `max_unconnected_blocks`, which evicts blocks instead.
+            high_water: TreeUsage { buffered_blocks: 150, chains: 64, memory: 1024 * 1024 * 1024 },
+            low_water: TreeUsage { buffered_blocks: 100, chains: 32, memory: 512 * 1024 * 1024 },
+            retry_after: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Resource usage of the blockchain tree.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TreeUsage {
+    /// Number of blocks in the block buffer.
+    pub buffered_blocks: usize,
+    /// Number of tracked chains.
+    pub chains: usize,
+    /// Estimated in-memory size of the blocks of the tracked chains and the block buffer, in
+    /// bytes.
+    pub memory: usize,
+}
+
+impl TreeUsage {
+    /// Returns `true` if any usage is above its mark.
+    pub const fn exceeds(&self, marks: &Self) -> bool {
+        self.buffered_blocks > marks.buffered_blocks ||
+            self.chains > marks.chains ||
+            self.memory > marks.memory
+    }
+
+    /// Returns `true` if all usages are below their marks.
+    pub const fn is_below(&self, marks: &Self) -> bool {
+        self.buffered_blocks < marks.buffered_blocks &&
+            self.chains < marks.chains &&
+            self.memory < marks.memory
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_is_valid() {
+        assert_eq!(BlockchainTreeConfig::builder().build().map(|_| ()), Ok(()));
+        let backpressure = Some(TreeBackpressure::default());
+        assert!(BlockchainTreeConfig::builder().backpressure(backpressure).build().is_ok());
+    }
+
+    #[test]
+    fn rejects_reorg_depth_above_chain_length() {
+        assert_eq!(
+            BlockchainTreeConfig::builder().max_reorg_depth(66).build().unwrap_err(),
+            TreeConfigError::ReorgDepthExceedsChainLength {
+                max_reorg_depth: 66,
+                max_blocks_in_chain: 65
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_zero_memory_block_retention() {
+        assert_eq!(
+            BlockchainTreeConfig::builder().memory_block_retention(0).build().unwrap_err(),
+            TreeConfigError::ZeroMemoryBlockRetention
+        );
+    }
+
+    #[test]
+    fn rejects_retention_below_reorg_depth() {
+        assert_eq!(
+            BlockchainTreeConfig::builder().memory_block_retention(63).build().unwrap_err(),
+            TreeConfigError::RetentionBelowReorgDepth {
+                memory_block_retention: 63,
+                max_reorg_depth: 64
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_buffer_below_reorg_depth() {
+        assert_eq!(
+            BlockchainTreeConfig::builder().max_unconnected_blocks(63).build().unwrap_err(),
+            TreeConfigError::BufferBelowReorgDepth {
+                max_unconnected_blocks: 63,
+                max_reorg_depth: 64
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_low_water_above_high_water() {
+        let mut backpressure = TreeBackpressure::default();
+        backpressure.low_water.chains = backpressure.high_water.chains + 1;
+        assert_eq!(
+            BlockchainTreeConfig::builder().backpressure(Some(backpressure)).build().unwrap_err(),
+            TreeConfigError::LowWaterAboveHighWater {
+                low_water: backpressure.low_water,
+                high_water: backpressure.high_water
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_high_water_above_buffer_capacity() {
+        let backpressure = Some(TreeBackpressure::default());
+        assert_eq!(
+            BlockchainTreeConfig::builder()
+                .max_unconnected_blocks(150)
+                .backpressure(backpressure)
+                .build()
+                .unwrap_err(),
+            TreeConfigError::HighWaterAboveBufferCapacity {
+                buffered_blocks: 150,
+                max_unconnected_blocks: 150
+            }
+        );
+    }
+
+    #[test]
+    fn handle_keeps_limits_on_invalid_update() {
+        let config = BlockchainTreeConfig::default();
+        let (limits, _) = watch::channel(config.soft_limits());
+        let handle = TreeConfigHandle::new(config, Arc::new(limits));
+
+        assert_eq!(handle.set_max_unconnected_blocks(100), Ok(()));
+        assert_eq!(
+            handle.set_memory_block_retention(0),
+            Err(TreeConfigError::ZeroMemoryBlockRetention)
+        );
+        assert_eq!(
+            handle.soft_limits(),
+            TreeSoftLimits { max_unconnected_blocks: 100, ..config.soft_limits() }
+        );
+    }
 }
@@ -1,15 +1,17 @@
 //! Blockchain tree externals.
 
+use rayon::ThreadPool;
 use reth_consensus::Consensus;
 use reth_db::{static_file::HeaderMask, tables};
 use reth_db_api::{cursor::DbCursorRO, database::Database, transaction::DbTx};
 use reth_primitives::{BlockHash, BlockNumber, StaticFileSegment};
 use reth_provider::{
-    FinalizedBlockReader, FinalizedBlockWriter, ProviderFactory, StaticFileProviderFactory,
-    StatsReader,
+    providers::ConsistentReadView, FinalizedBlockReader, FinalizedBlockWriter, ProviderFactory,
+    StaticFileProviderFactory, StatsReader,
 };
+use reth_revm::bytecode_cache::BytecodeCache;
 use reth_storage_errors::provider::ProviderResult;
-use std::{collections::BTreeMap, sync::Arc};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
 
 /// A container for external components.
 ///
@@ -19,6 +21,9 @@ use std::{collections::BTreeMap, sync::Arc};
 /// - A handle to the database
 /// - A handle to the consensus engine
 /// - The executor factory to execute blocks with
+/// - The bytecode cache shared by the executors
+/// - The soft limit of the execution time of a block
+/// - The thread pool to compute the storage roots of the state root on
 /// - The chain spec
 #[derive(Debug)]
 pub struct TreeExternals<DB, E> {
@@ -28,6 +33,15 @@ pub struct TreeExternals<DB, E> {
     pub(crate) consensus: Arc<dyn Consensus>,
     /// The executor factory to execute blocks with.
     pub(crate) executor_factory: E,
+    /// The bytecode cache shared by all executors, so a contract is only loaded and analyzed
+    /// once instead of once per block.
+    pub(crate) bytecode_cache: BytecodeCache,
+    /// The execution time after which the execution of a block is cancelled between
+    /// transactions, if any.
+    pub(crate) execution_soft_limit: Option<Duration>,
+    /// The thread pool to compute the storage roots of the state root on, the global rayon pool
+    /// is used if unset.
+    pub(crate) state_root_pool: Option<Arc<ThreadPool>>,
 }
 
 impl<DB, E> TreeExternals<DB, E> {
@@ -37,11 +51,58 @@ impl<DB, E> TreeExternals<DB, E> {
         consensus: Arc<dyn Consensus>,
         executor_factory: E,
     ) -> Self {
-        Self { provider_factory, consensus, executor_factory }
+        Self {
+            provider_factory,
+            consensus,
+            executor_factory,
+            bytecode_cache: Default::default(),
+            execution_soft_limit: None,
+            state_root_pool: None,
+        }
+    }
+
+    /// Sets the bytecode cache of the executors, e.g. to share it with other components.
+    pub fn with_bytecode_cache(mut self, bytecode_cache: BytecodeCache) -> Self {
+        self.bytecode_cache = bytecode_cache;
+        self
+    }
+
+    /// Sets the soft limit of the execution time of a block.
+    ///
+    /// Once the limit is exceeded, the execution is cancelled before the next transaction and the
+    /// block is rejected with [`BlockExecutionError::Cancelled`]. The block is not considered
+    /// invalid, so it can be inserted again later.
+    ///
+    /// [`BlockExecutionError::Cancelled`]: reth_evm::execute::BlockExecutionError::Cancelled
+    pub const fn with_execution_soft_limit(mut self, soft_limit: Duration) -> Self {
+        self.execution_soft_limit = Some(soft_limit);
+        self
+    }
+
+    /// Sets the thread pool to compute the storage roots of the state root on, instead of the
+    /// global rayon pool.
+    ///
+    /// See [`ParallelStateRoot::with_thread_pool`].
+    ///
+    /// [`ParallelStateRoot::with_thread_pool`]: reth_trie_parallel::parallel_root::ParallelStateRoot::with_thread_pool
+    pub fn with_state_root_pool(mut self, pool: Arc<ThreadPool>) -> Self {
+        self.state_root_pool = Some(pool);
+        self
     }
 }
 
 impl<DB: Database, E> TreeExternals<DB, E> {
+    /// Returns a view pinned to a new read transaction, so that all reads through it observe the
+    /// same database snapshot.
+    ///
+    /// This is used for the execution of a block: the withdrawal lookup and all state lookups of
+    /// the executor are served by the one transaction of the view.
+    pub(crate) fn consistent_read_view(
+        &self,
+    ) -> ProviderResult<ConsistentReadView<DB, ProviderFactory<DB>>> {
+        self.provider_factory.consistent_view()
+    }
+
     /// Fetches the latest canonical block hashes by walking backwards from the head.
     ///
     /// Returns the hashes sorted by increasing block numbers
@@ -21,6 +21,9 @@
 /// Re-export of the blockchain tree API.
 pub use reth_blockchain_tree_api::*;
 
+pub mod backup;
+pub use backup::BlocksBackupConfig;
+
 pub mod blockchain_tree;
 pub use blockchain_tree::BlockchainTree;
 
@@ -31,14 +34,23 @@ pub mod chain;
 pub use chain::AppendableChain;
 
 pub mod config;
-pub use config::BlockchainTreeConfig;
+pub use config::{
+    BlockchainTreeConfig, BlockchainTreeConfigBuilder, TreeBackpressure, TreeConfigError,
+    TreeConfigHandle, TreeSoftLimits, TreeUsage,
+};
 
 pub mod externals;
 pub use externals::TreeExternals;
 
+pub mod reorg_simulation;
+pub use reorg_simulation::{BalanceChange, ReorgSimulation, ReorgViolation};
+
 pub mod shareable;
 pub use shareable::ShareableBlockchainTree;
 
+mod sender_cache;
+pub use sender_cache::DEFAULT_SENDER_CACHE_SIZE;
+
 mod bundle;
 pub use bundle::{BundleStateDataRef, ExecutionData};
 
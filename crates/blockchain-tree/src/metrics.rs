@@ -1,10 +1,20 @@
 use metrics::Histogram;
 use reth_metrics::{
     metrics::{Counter, Gauge},
-    Metrics,
+    Metrics, MetricsRegistrar,
 };
 use std::time::{Duration, Instant};
 
+/// Declares all metrics of the blockchain tree.
+pub(crate) fn register_metrics(registrar: &mut MetricsRegistrar) {
+    registrar.describe("blockchain_tree", || {
+        TreeMetrics::describe();
+        BlockExecutionMetrics::describe();
+        BlockBufferMetrics::describe();
+        MakeCanonicalMetrics::describe();
+    });
+}
+
 /// Metrics for the entire blockchain tree
 #[derive(Metrics)]
 #[metrics(scope = "blockchain_tree")]
@@ -23,6 +33,28 @@ pub struct TreeMetrics {
     pub trie_updates_insert_cached: Counter,
     /// The number of times trie updates were recomputed for insert.
     pub trie_updates_insert_recomputed: Counter,
+    /// The number of canonical blocks retained in-memory
+    pub retained_canonical_blocks: Gauge,
+    /// The number of sidechain blocks (and their execution outcomes) retained in-memory
+    pub retained_sidechain_blocks: Gauge,
+    /// Approximate size in bytes of the sidechain blocks retained in-memory
+    pub retained_sidechain_bytes: Gauge,
+    /// The number of times a reorg went past the retained window and the fork block had to be
+    /// re-read from the database.
+    pub retention_rehydrations: Counter,
+    /// The number of unconnected blocks that were rejected for being too far above the
+    /// canonical tip.
+    pub distant_blocks_rejected: Counter,
+}
+
+/// Metrics for the execution of the blocks inserted into the blockchain tree
+#[derive(Metrics)]
+#[metrics(scope = "blockchain_tree.block_execution")]
+pub(crate) struct BlockExecutionMetrics {
+    /// Wall time of the execution of a block
+    pub(crate) duration: Histogram,
+    /// The number of block executions that were cancelled after exceeding the soft limit
+    pub(crate) cancelled: Counter,
 }
 
 /// Metrics for the blockchain tree block buffer
@@ -31,6 +63,8 @@ pub struct TreeMetrics {
 pub struct BlockBufferMetrics {
     /// Total blocks in the block buffer
     pub blocks: Gauge,
+    /// The number of blocks that were evicted because the buffer was full
+    pub evicted_blocks: Counter,
 }
 
 #[derive(Debug)]
@@ -151,3 +185,24 @@ impl MakeCanonicalMetrics {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_tree_metrics() {
+        let mut registrar = MetricsRegistrar::new();
+        register_metrics(&mut registrar);
+
+        for name in [
+            "blockchain_tree.sidechains",
+            "blockchain_tree.block_execution.duration",
+            "blockchain_tree.block_buffer.blocks",
+            "blockchain_tree.make_canonical.split_chain",
+        ] {
+            let metric = registrar.get(name).unwrap_or_else(|| panic!("{name} is not registered"));
+            assert_eq!(metric.component, "blockchain_tree");
+        }
+    }
+}
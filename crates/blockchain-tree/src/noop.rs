@@ -10,7 +10,7 @@ use reth_primitives::{
 };
 use reth_provider::{
     BlockchainTreePendingStateProvider, CanonStateNotificationSender, CanonStateNotifications,
-    CanonStateSubscriptions, FullExecutionDataProvider,
+    CanonStateSubscriptions, Chain, FullExecutionDataProvider,
 };
 use reth_storage_errors::provider::ProviderResult;
 use std::collections::BTreeMap;
@@ -54,6 +54,17 @@ impl BlockchainTreeEngine for NoopBlockchainTree {
         Ok(())
     }
 
+    fn unwind_canonical_chain(
+        &self,
+        unwind_to: BlockNumber,
+    ) -> Result<BlockNumber, CanonicalError> {
+        Ok(unwind_to)
+    }
+
+    fn notify_canonical_chain_unwound(&self, _unwound: Chain) -> Result<(), CanonicalError> {
+        Ok(())
+    }
+
     fn connect_buffered_blocks_to_canonical_hashes_and_finalize(
         &self,
         _last_finalized_block: BlockNumber,
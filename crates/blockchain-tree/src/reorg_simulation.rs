@@ -0,0 +1,101 @@
+//! Dry-run of [`BlockchainTree::make_canonical`](crate::BlockchainTree::make_canonical).
+//!
+//! Before forcing a head change, operators want to know the blast radius of the reorg: which
+//! blocks are reverted, how many transactions return to the pool, whose balances change and
+//! whether the reorg breaks any rules. See
+//! [`BlockchainTree::simulate_make_canonical`](crate::BlockchainTree::simulate_make_canonical).
+
+use reth_primitives::{Address, BlockNumHash, BlockNumber, U256};
+
+/// Number of accounts with the largest balance changes that are reported by a
+/// [`ReorgSimulation`].
+pub const REORG_SIMULATION_TOP_ACCOUNTS: usize = 10;
+
+/// The effect that making a block canonical would have.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReorgSimulation {
+    /// The canonical block the new canonical chain forks off.
+    pub fork_block: BlockNumHash,
+    /// The canonical blocks that would be reverted, in ascending order.
+    pub reverted_blocks: Vec<BlockNumHash>,
+    /// The sidechain blocks that would become canonical, in ascending order.
+    pub applied_blocks: Vec<BlockNumHash>,
+    /// Number of transactions in the reverted blocks.
+    pub reverted_transactions: usize,
+    /// Number of transactions in the applied blocks.
+    pub applied_transactions: usize,
+    /// Number of reverted transactions that are not included in the applied blocks, i.e. that
+    /// would return to the pool.
+    pub returned_transactions: usize,
+    /// The accounts with the largest balance changes, ordered by the absolute change.
+    ///
+    /// At most [`REORG_SIMULATION_TOP_ACCOUNTS`] accounts are reported.
+    pub balance_changes: Vec<BalanceChange>,
+    /// The rules the reorg would violate.
+    pub violations: Vec<ReorgViolation>,
+}
+
+impl ReorgSimulation {
+    /// Creates a simulation that neither reverts nor applies any blocks.
+    pub const fn new(fork_block: BlockNumHash) -> Self {
+        Self {
+            fork_block,
+            reverted_blocks: Vec::new(),
+            applied_blocks: Vec::new(),
+            reverted_transactions: 0,
+            applied_transactions: 0,
+            returned_transactions: 0,
+            balance_changes: Vec::new(),
+            violations: Vec::new(),
+        }
+    }
+
+    /// Returns the depth of the reorg, i.e. the number of reverted blocks.
+    pub fn depth(&self) -> u64 {
+        self.reverted_blocks.len() as u64
+    }
+
+    /// Returns `true` if the reorg doesn't violate any rules.
+    pub fn is_safe(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// The balance of an account before and after the reorg.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BalanceChange {
+    /// The address of the account.
+    pub address: Address,
+    /// The balance on the current canonical chain.
+    pub before: U256,
+    /// The balance on the new canonical chain.
+    pub after: U256,
+}
+
+impl BalanceChange {
+    /// Returns the absolute difference between the balances.
+    pub fn abs_delta(&self) -> U256 {
+        if self.after > self.before {
+            self.after - self.before
+        } else {
+            self.before - self.after
+        }
+    }
+}
+
+/// A rule that a reorg would violate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReorgViolation {
+    /// The reorg would revert finalized blocks.
+    RevertsFinalized {
+        /// The last finalized block.
+        last_finalized: BlockNumber,
+    },
+    /// The reorg is deeper than the configured maximum reorg depth.
+    ExceedsMaxReorgDepth {
+        /// The depth of the reorg.
+        depth: u64,
+        /// The maximum reorg depth of the tree.
+        max_reorg_depth: u64,
+    },
+}
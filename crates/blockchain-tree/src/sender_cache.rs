@@ -0,0 +1,145 @@
+//! Cache of recovered transaction senders.
+
+use reth_network::cache::LruMap;
+use reth_primitives::{Address, BlockHash, SealedBlock, SealedBlockWithSenders};
+
+/// Default number of blocks whose recovered senders are cached by the tree.
+pub const DEFAULT_SENDER_CACHE_SIZE: usize = 8;
+
+/// Caches the recovered transaction senders of the most recently inserted blocks, by block hash.
+///
+/// The same block can be inserted into the tree more than once, e.g. if the consensus layer
+/// resends a payload. Caching the senders avoids repeating the signature recovery of its
+/// transactions.
+///
+/// The senders are recovered before the block is validated, so the cache is only used for bodies
+/// that match the transactions root of their header. The root is committed to by the block hash,
+/// so the cached senders of a block hash always belong to the same transactions.
+#[derive(Debug)]
+pub(crate) struct SenderCache {
+    /// The recovered senders by block hash.
+    senders: LruMap<BlockHash, Vec<Address>>,
+    /// Maximum number of cached blocks, `0` disables the cache.
+    size: usize,
+}
+
+impl SenderCache {
+    /// Creates a new cache for `size` blocks.
+    pub(crate) fn new(size: usize) -> Self {
+        Self { senders: LruMap::new(size.try_into().unwrap_or(u32::MAX)), size }
+    }
+
+    /// Sets the maximum number of cached blocks and clears the cache. `0` disables the cache.
+    pub(crate) fn set_size(&mut self, size: usize) {
+        *self = Self::new(size);
+    }
+
+    /// Seals the block with its transaction senders, recovering them if they are not cached.
+    ///
+    /// Returns the block if the recovery of a sender fails.
+    pub(crate) fn seal_with_senders(
+        &mut self,
+        block: SealedBlock,
+    ) -> Result<SealedBlockWithSenders, SealedBlock> {
+        self.seal_with_senders_by(block, SealedBlock::senders)
+    }
+
+    /// Seals the block with its transaction senders, using `recover` to recover them if they are
+    /// not cached.
+    fn seal_with_senders_by(
+        &mut self,
+        block: SealedBlock,
+        recover: impl FnOnce(&SealedBlock) -> Option<Vec<Address>>,
+    ) -> Result<SealedBlockWithSenders, SealedBlock> {
+        // a body that doesn't match its header is rejected by the validation of the block
+        if self.size == 0 || block.ensure_transaction_root_valid().is_err() {
+            return match recover(&block) {
+                Some(senders) => Ok(SealedBlockWithSenders { block, senders }),
+                None => Err(block),
+            }
+        }
+
+        if let Some(senders) = self.senders.get(&block.hash()) {
+            let senders = senders.clone();
+            return Ok(SealedBlockWithSenders { block, senders })
+        }
+
+        let Some(senders) = recover(&block) else { return Err(block) };
+        self.senders.insert(block.hash(), senders.clone());
+        Ok(SealedBlockWithSenders { block, senders })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_testing_utils::generators::{self, random_block};
+
+    /// Seals the block with `cache`, counting the recoveries.
+    fn seal(cache: &mut SenderCache, block: &SealedBlock, recoveries: &mut usize) -> Vec<Address> {
+        cache
+            .seal_with_senders_by(block.clone(), |block| {
+                *recoveries += 1;
+                block.senders()
+            })
+            .unwrap()
+            .senders
+    }
+
+    #[test]
+    fn recovers_once() {
+        let mut rng = generators::rng();
+        let block = random_block(&mut rng, 1, None, Some(3), None);
+        let other = random_block(&mut rng, 1, None, Some(3), None);
+        let mut cache = SenderCache::new(DEFAULT_SENDER_CACHE_SIZE);
+        let mut recoveries = 0;
+
+        let senders = seal(&mut cache, &block, &mut recoveries);
+        assert_eq!(senders, block.senders().unwrap());
+        assert_eq!(seal(&mut cache, &block, &mut recoveries), senders);
+        assert_eq!(recoveries, 1);
+
+        seal(&mut cache, &other, &mut recoveries);
+        assert_eq!(recoveries, 2);
+
+        // blocks with invalid signatures are not cached
+        let mut invalid = random_block(&mut rng, 2, None, Some(3), None);
+        invalid.body[0].signature.r = Default::default();
+        assert!(cache.seal_with_senders(invalid.clone()).is_err());
+        assert!(cache.seal_with_senders(invalid).is_err());
+    }
+
+    #[test]
+    fn bypassed_on_body_mismatch() {
+        let mut rng = generators::rng();
+        let block = random_block(&mut rng, 1, None, Some(3), None);
+        let other = random_block(&mut rng, 1, None, Some(3), None);
+        let mut cache = SenderCache::new(DEFAULT_SENDER_CACHE_SIZE);
+        let mut recoveries = 0;
+
+        // a body of the same length that doesn't match the header is neither cached nor served
+        // from the cache
+        let mut mismatched = block.clone();
+        mismatched.body.clone_from(&other.body);
+        assert_eq!(seal(&mut cache, &mismatched, &mut recoveries), other.senders().unwrap());
+        assert_eq!(seal(&mut cache, &block, &mut recoveries), block.senders().unwrap());
+        assert_eq!(seal(&mut cache, &mismatched, &mut recoveries), other.senders().unwrap());
+        assert_eq!(recoveries, 3);
+
+        assert_eq!(seal(&mut cache, &block, &mut recoveries), block.senders().unwrap());
+        assert_eq!(recoveries, 3);
+    }
+
+    #[test]
+    fn disabled() {
+        let mut rng = generators::rng();
+        let block = random_block(&mut rng, 1, None, Some(3), None);
+        let mut cache = SenderCache::new(DEFAULT_SENDER_CACHE_SIZE);
+        cache.set_size(0);
+        let mut recoveries = 0;
+
+        seal(&mut cache, &block, &mut recoveries);
+        seal(&mut cache, &block, &mut recoveries);
+        assert_eq!(recoveries, 2);
+    }
+}
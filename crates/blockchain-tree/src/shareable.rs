@@ -1,37 +1,135 @@
 //! Wrapper around `BlockchainTree` that allows for it to be shared.
 
 use super::BlockchainTree;
+use crate::{ReorgSimulation, TreeConfigHandle, TreeSoftLimits};
 use parking_lot::RwLock;
 use reth_blockchain_tree_api::{
     error::{CanonicalError, InsertBlockError},
     BlockValidationKind, BlockchainTreeEngine, BlockchainTreeViewer, CanonicalOutcome,
-    InsertPayloadOk,
+    InsertPayloadOk, TreeCongestion,
 };
 use reth_db_api::database::Database;
 use reth_evm::execute::BlockExecutorProvider;
+use reth_metrics::MetricsRegistrar;
 use reth_primitives::{
     BlockHash, BlockNumHash, BlockNumber, Receipt, SealedBlock, SealedBlockWithSenders,
     SealedHeader,
 };
 use reth_provider::{
-    BlockchainTreePendingStateProvider, CanonStateSubscriptions, FullExecutionDataProvider,
-    ProviderError,
+    BlockchainTreePendingStateProvider, CanonStateNotifications, CanonStateSubscriptions, Chain,
+    FullExecutionDataProvider, ProviderError,
 };
 use reth_storage_errors::provider::ProviderResult;
 use std::{collections::BTreeMap, sync::Arc};
-use tracing::trace;
+use tokio::sync::watch;
+use tracing::{debug, trace};
 
 /// Shareable blockchain tree that is behind a `RwLock`
+///
+/// If [`BlockchainTreeConfig::backpressure`](crate::BlockchainTreeConfig::backpressure) is set,
+/// the tree becomes [congested](TreeCongestion::Congested) when its usage exceeds the high-water
+/// marks. New blocks are then [deferred](InsertPayloadOk::Deferred) until the usage drops below
+/// the low-water marks, except for blocks that connect buffered blocks and so drain the buffer.
+///
+/// The [soft limits](TreeSoftLimits) of the tree can be adjusted through the
+/// [`TreeConfigHandle`], they are applied when the next write operation acquires the tree.
 #[derive(Clone, Debug)]
 pub struct ShareableBlockchainTree<DB, E> {
     /// `BlockchainTree`
     pub tree: Arc<RwLock<BlockchainTree<DB, E>>>,
+    /// Congestion state of the tree, updated after every change of the tree.
+    congestion: Arc<watch::Sender<TreeCongestion>>,
+    /// Soft limits requested through the [`TreeConfigHandle`].
+    limits: Arc<watch::Sender<TreeSoftLimits>>,
 }
 
 impl<DB, E> ShareableBlockchainTree<DB, E> {
     /// Create a new shareable database.
     pub fn new(tree: BlockchainTree<DB, E>) -> Self {
-        Self { tree: Arc::new(RwLock::new(tree)) }
+        let (congestion, _) = watch::channel(TreeCongestion::default());
+        let (limits, _) = watch::channel(tree.config().soft_limits());
+        Self {
+            tree: Arc::new(RwLock::new(tree)),
+            congestion: Arc::new(congestion),
+            limits: Arc::new(limits),
+        }
+    }
+
+    /// Subscribe to the canonical state notifications of the tree.
+    ///
+    /// A notification is sent after every canonical chain update was written to the database.
+    /// Receivers that lag behind miss notifications instead of blocking the tree.
+    pub fn subscribe_canon_state(&self) -> CanonStateNotifications {
+        self.tree.read().subscribe_canon_state()
+    }
+
+    /// Registers all metrics of the tree with the given registrar.
+    pub fn register_metrics(&self, registrar: &mut MetricsRegistrar) {
+        self.tree.read().register_metrics(registrar)
+    }
+
+    /// Returns a handle to adjust the soft limits of the tree while it is running.
+    pub fn config_handle(&self) -> TreeConfigHandle {
+        TreeConfigHandle::new(*self.tree.read().config(), self.limits.clone())
+    }
+
+    /// Applies the soft limits requested through the [`TreeConfigHandle`], if they changed.
+    ///
+    /// Called right after a write operation acquired the tree, so the limits never change in the
+    /// middle of an operation.
+    fn apply_soft_limits(&self, tree: &mut BlockchainTree<DB, E>) {
+        let limits = *self.limits.borrow();
+        if limits != tree.config().soft_limits() {
+            tree.set_soft_limits(limits);
+        }
+    }
+
+    /// Subscribe to changes of the congestion state of the tree.
+    pub fn subscribe_to_congestion(&self) -> watch::Receiver<TreeCongestion> {
+        self.congestion.subscribe()
+    }
+
+    /// Updates the congestion state from the current usage of the tree.
+    fn update_congestion(&self, tree: &BlockchainTree<DB, E>) {
+        let Some(backpressure) = tree.config().backpressure() else {
+            // backpressure might have been disabled while the tree was congested
+            self.congestion.send_if_modified(|congestion| {
+                std::mem::replace(congestion, TreeCongestion::Clear).is_congested()
+            });
+            return
+        };
+        let usage = tree.usage();
+        self.congestion.send_if_modified(|congestion| {
+            let next = match congestion {
+                TreeCongestion::Clear if usage.exceeds(&backpressure.high_water) => {
+                    TreeCongestion::Congested
+                }
+                TreeCongestion::Congested if usage.is_below(&backpressure.low_water) => {
+                    TreeCongestion::Clear
+                }
+                _ => return false,
+            };
+            debug!(target: "blockchain_tree", ?usage, congestion = ?next, "Tree congestion changed");
+            *congestion = next;
+            true
+        });
+    }
+}
+
+impl<DB, E> ShareableBlockchainTree<DB, E>
+where
+    DB: Database + Clone,
+    E: BlockExecutorProvider,
+{
+    /// Simulates making the given block canonical, without changing the tree.
+    ///
+    /// See [`BlockchainTree::simulate_make_canonical`].
+    pub fn simulate_make_canonical(
+        &self,
+        block_hash: BlockHash,
+    ) -> Result<ReorgSimulation, CanonicalError> {
+        trace!(target: "blockchain_tree", ?block_hash, "Simulating make canonical");
+        self.tree.read().simulate_make_canonical(block_hash)
     }
 }
 
@@ -42,9 +140,12 @@ where
 {
     fn buffer_block(&self, block: SealedBlockWithSenders) -> Result<(), InsertBlockError> {
         let mut tree = self.tree.write();
+        self.apply_soft_limits(&mut tree);
         // Blockchain tree metrics shouldn't be updated here, see
         // `BlockchainTree::update_chains_metrics` documentation.
-        tree.buffer_block(block)
+        let res = tree.buffer_block(block);
+        self.update_congestion(&tree);
+        res
     }
 
     fn insert_block(
@@ -54,16 +155,26 @@ where
     ) -> Result<InsertPayloadOk, InsertBlockError> {
         trace!(target: "blockchain_tree", hash = %block.hash(), number = block.number, parent_hash = %block.parent_hash, "Inserting block");
         let mut tree = self.tree.write();
+        self.apply_soft_limits(&mut tree);
+        if self.congestion.borrow().is_congested() && !tree.has_buffered_children(&block.hash()) {
+            if let Some(backpressure) = tree.config().backpressure() {
+                debug!(target: "blockchain_tree", hash = %block.hash(), number = block.number, "Deferring block, the tree is congested");
+                return Ok(InsertPayloadOk::Deferred { retry_after: backpressure.retry_after })
+            }
+        }
         let res = tree.insert_block(block, validation_kind);
         tree.update_chains_metrics();
+        self.update_congestion(&tree);
         res
     }
 
     fn finalize_block(&self, finalized_block: BlockNumber) -> ProviderResult<()> {
         trace!(target: "blockchain_tree", finalized_block, "Finalizing block");
         let mut tree = self.tree.write();
+        self.apply_soft_limits(&mut tree);
         tree.finalize_block(finalized_block)?;
         tree.update_chains_metrics();
+        self.update_congestion(&tree);
 
         Ok(())
     }
@@ -74,9 +185,11 @@ where
     ) -> Result<(), CanonicalError> {
         trace!(target: "blockchain_tree", last_finalized_block, "Connecting buffered blocks to canonical hashes and finalizing the tree");
         let mut tree = self.tree.write();
+        self.apply_soft_limits(&mut tree);
         let res =
             tree.connect_buffered_blocks_to_canonical_hashes_and_finalize(last_finalized_block);
         tree.update_chains_metrics();
+        self.update_congestion(&tree);
         Ok(res?)
     }
 
@@ -84,26 +197,50 @@ where
         &self,
     ) -> Result<BTreeMap<BlockNumber, BlockHash>, CanonicalError> {
         let mut tree = self.tree.write();
+        self.apply_soft_limits(&mut tree);
         let res = tree.update_block_hashes_and_clear_buffered();
         tree.update_chains_metrics();
+        self.update_congestion(&tree);
         Ok(res?)
     }
 
     fn connect_buffered_blocks_to_canonical_hashes(&self) -> Result<(), CanonicalError> {
         trace!(target: "blockchain_tree", "Connecting buffered blocks to canonical hashes");
         let mut tree = self.tree.write();
+        self.apply_soft_limits(&mut tree);
         let res = tree.connect_buffered_blocks_to_canonical_hashes();
         tree.update_chains_metrics();
+        self.update_congestion(&tree);
         Ok(res?)
     }
 
     fn make_canonical(&self, block_hash: BlockHash) -> Result<CanonicalOutcome, CanonicalError> {
         trace!(target: "blockchain_tree", %block_hash, "Making block canonical");
         let mut tree = self.tree.write();
+        self.apply_soft_limits(&mut tree);
         let res = tree.make_canonical(block_hash);
         tree.update_chains_metrics();
+        self.update_congestion(&tree);
+        res
+    }
+
+    fn unwind_canonical_chain(
+        &self,
+        unwind_to: BlockNumber,
+    ) -> Result<BlockNumber, CanonicalError> {
+        trace!(target: "blockchain_tree", unwind_to, "Unwinding canonical chain");
+        let mut tree = self.tree.write();
+        self.apply_soft_limits(&mut tree);
+        let res = tree.unwind_canonical_chain(unwind_to);
+        tree.update_chains_metrics();
+        self.update_congestion(&tree);
         res
     }
+
+    fn notify_canonical_chain_unwound(&self, unwound: Chain) -> Result<(), CanonicalError> {
+        trace!(target: "blockchain_tree", range = ?unwound.range(), "Notifying about unwound canonical chain");
+        self.tree.write().notify_canonical_chain_unwound(unwound)
+    }
 }
 
 impl<DB, E> BlockchainTreeViewer for ShareableBlockchainTree<DB, E>
@@ -146,16 +283,17 @@ where
     }
 
     fn pending_block_num_hash(&self) -> Option<BlockNumHash> {
-        trace!(target: "blockchain_tree", "Returning first pending block");
-        self.tree.read().block_indices().pending_block_num_hash()
+        trace!(target: "blockchain_tree", "Returning pending block");
+        self.tree.read().pending_block_num_hash()
     }
 
     fn pending_block(&self) -> Option<SealedBlock> {
-        trace!(target: "blockchain_tree", "Returning first pending block");
+        trace!(target: "blockchain_tree", "Returning pending block");
         self.tree.read().pending_block().cloned()
     }
 
     fn pending_block_and_receipts(&self) -> Option<(SealedBlock, Vec<Receipt>)> {
+        trace!(target: "blockchain_tree", "Returning pending block and receipts");
         let tree = self.tree.read();
         let pending_block = tree.pending_block()?.clone();
         let receipts =
@@ -164,6 +302,7 @@ where
     }
 
     fn receipts_by_block_hash(&self, block_hash: BlockHash) -> Option<Vec<Receipt>> {
+        trace!(target: "blockchain_tree", ?block_hash, "Returning receipts by block hash");
         let tree = self.tree.read();
         Some(tree.receipts_by_block_hash(block_hash)?.into_iter().cloned().collect())
     }
@@ -189,8 +328,8 @@ where
     DB: Send + Sync,
     E: Send + Sync,
 {
-    fn subscribe_to_canonical_state(&self) -> reth_provider::CanonStateNotifications {
+    fn subscribe_to_canonical_state(&self) -> CanonStateNotifications {
         trace!(target: "blockchain_tree", "Registered subscriber for canonical state");
-        self.tree.read().subscribe_canon_state()
+        self.subscribe_canon_state()
     }
 }
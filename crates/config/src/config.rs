@@ -82,6 +82,10 @@ pub struct StageConfig {
     pub index_account_history: IndexHistoryConfig,
     /// Index Storage History stage configuration.
     pub index_storage_history: IndexHistoryConfig,
+    /// Transaction History stage configuration.
+    pub transaction_history: TransactionHistoryConfig,
+    /// Header Accumulator stage configuration.
+    pub header_accumulator: HeaderAccumulatorConfig,
     /// Common ETL related configuration.
     pub etl: EtlConfig,
 }
@@ -118,12 +122,19 @@ pub struct HeadersConfig {
     pub downloader_request_limit: u64,
     /// The maximum number of headers to download before committing progress to the database.
     pub commit_threshold: u64,
+    /// The maximum number of blocks the headers stage unwinds on its own if the local head is no
+    /// longer canonical. Deeper reorgs require operator intervention, `0` disables the automatic
+    /// unwind.
+    ///
+    /// Default: 64
+    pub max_auto_unwind_depth: u64,
 }
 
 impl Default for HeadersConfig {
     fn default() -> Self {
         Self {
             commit_threshold: 10_000,
+            max_auto_unwind_depth: 64,
             downloader_request_limit: 1_000,
             downloader_max_concurrent_requests: 100,
             downloader_min_concurrent_requests: 5,
@@ -201,6 +212,10 @@ pub struct ExecutionConfig {
         deserialize_with = "deserialize_duration"
     )]
     pub max_duration: Option<Duration>,
+    /// The number of blocks that are read ahead of the executed block, `0` disables prefetching.
+    ///
+    /// Blocks are never read past the end of the batch determined by `max_blocks`.
+    pub prefetch_blocks: usize,
 }
 
 impl Default for ExecutionConfig {
@@ -212,6 +227,7 @@ impl Default for ExecutionConfig {
             max_cumulative_gas: Some(30_000_000 * 50_000),
             // 10 minutes
             max_duration: Some(Duration::from_secs(10 * 60)),
+            prefetch_blocks: 3,
         }
     }
 }
@@ -310,6 +326,44 @@ impl Default for IndexHistoryConfig {
     }
 }
 
+/// Transaction History stage configuration.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct TransactionHistoryConfig {
+    /// Whether to index the transactions of each address. Disabled by default.
+    ///
+    /// Disabling it on a node that already built the index removes the index.
+    pub enabled: bool,
+    /// Whether to also index the transactions that emitted a log from an address.
+    pub index_logs: bool,
+    /// The maximum number of blocks to process before committing progress to the database.
+    pub commit_threshold: u64,
+}
+
+impl Default for TransactionHistoryConfig {
+    fn default() -> Self {
+        Self { enabled: false, index_logs: false, commit_threshold: 100_000 }
+    }
+}
+
+/// Header Accumulator stage configuration.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct HeaderAccumulatorConfig {
+    /// Whether to accumulate the canonical header hashes. Disabled by default.
+    ///
+    /// Disabling it on a node that already built the accumulator removes the accumulator.
+    pub enabled: bool,
+    /// The maximum number of blocks to process before committing progress to the database.
+    pub commit_threshold: u64,
+}
+
+impl Default for HeaderAccumulatorConfig {
+    fn default() -> Self {
+        Self { enabled: false, commit_threshold: 500_000 }
+    }
+}
+
 /// Pruning configuration.
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default)]
@@ -1,5 +1,6 @@
 use crate::engine::hooks::EngineHookError;
 use reth_errors::{DatabaseError, RethError};
+use reth_primitives::{BlockHashOrNumber, BlockNumber};
 use reth_rpc_types::engine::ForkchoiceUpdateError;
 use reth_stages_api::PipelineError;
 
@@ -101,3 +102,42 @@ impl BeaconOnNewPayloadError {
         Self::Internal(Box::new(e))
     }
 }
+
+/// Represents all error cases when rolling back the canonical head, see
+/// [`BeaconConsensusEngineHandle::set_head`](crate::BeaconConsensusEngineHandle::set_head).
+#[derive(Debug, thiserror::Error)]
+pub enum BeaconSetHeadError {
+    /// Thrown when the engine task is unavailable/stopped.
+    #[error("beacon consensus engine task stopped")]
+    EngineUnavailable,
+    /// Thrown when another rollback of the canonical head is still in progress.
+    #[error("another head rollback is already in progress")]
+    AlreadyInProgress,
+    /// Thrown when the target block is not part of the canonical chain.
+    #[error("block {0:?} is not part of the canonical chain")]
+    NotCanonical(BlockHashOrNumber),
+    /// Thrown when the target block is below the finalized block and the rollback was not forced.
+    #[error("block {target} is below the finalized block {finalized}")]
+    BelowFinalized {
+        /// The requested new head.
+        target: BlockNumber,
+        /// The current finalized block.
+        finalized: BlockNumber,
+    },
+    /// An internal error occurred while unwinding.
+    #[error(transparent)]
+    Internal(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl BeaconSetHeadError {
+    /// Create a new internal error.
+    pub fn internal<E: std::error::Error + Send + Sync + 'static>(e: E) -> Self {
+        Self::Internal(Box::new(e))
+    }
+}
+
+impl From<RethError> for BeaconSetHeadError {
+    fn from(e: RethError) -> Self {
+        Self::internal(e)
+    }
+}
@@ -2,11 +2,12 @@
 
 use crate::{
     engine::message::OnForkChoiceUpdated, BeaconConsensusEngineEvent, BeaconEngineMessage,
-    BeaconForkChoiceUpdateError, BeaconOnNewPayloadError,
+    BeaconForkChoiceUpdateError, BeaconOnNewPayloadError, BeaconSetHeadError, SetHeadOutcome,
 };
 use futures::TryFutureExt;
 use reth_engine_primitives::EngineTypes;
 use reth_errors::RethResult;
+use reth_primitives::BlockHashOrNumber;
 use reth_rpc_types::engine::{
     CancunPayloadFields, ExecutionPayload, ForkchoiceState, ForkchoiceUpdated, PayloadStatus,
 };
@@ -91,6 +92,21 @@ where
         let _ = self.to_engine.send(BeaconEngineMessage::TransitionConfigurationExchanged);
     }
 
+    /// Sends a message to roll back the canonical head to the given canonical block and waits for
+    /// the rollback to finish.
+    ///
+    /// Until then, the engine responds with `SYNCING` to all new payloads and forkchoice updates.
+    /// Targets below the finalized block are refused unless `force` is set.
+    pub async fn set_head(
+        &self,
+        target: BlockHashOrNumber,
+        force: bool,
+    ) -> Result<SetHeadOutcome, BeaconSetHeadError> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.to_engine.send(BeaconEngineMessage::SetHead { target, force, tx });
+        rx.await.map_err(|_| BeaconSetHeadError::EngineUnavailable)?
+    }
+
     /// Creates a new [`BeaconConsensusEngineEvent`] listener stream.
     pub fn event_listener(&self) -> EventStream<BeaconConsensusEngineEvent> {
         self.event_sender.new_listener()
@@ -2,7 +2,8 @@ use reth_metrics::{
     metrics::{Counter, Gauge},
     Metrics,
 };
-use reth_primitives::{Header, SealedHeader, B256};
+use reth_primitives::{alloy_primitives::Keccak256, Header, SealedHeader, B256};
+use reth_rpc_types::engine::{CancunPayloadFields, ExecutionPayload, PayloadStatus};
 use schnellru::{ByLength, LruMap};
 use std::sync::Arc;
 use tracing::warn;
@@ -13,17 +14,30 @@ use tracing::warn;
 /// allow for reprocessing.
 const INVALID_HEADER_HIT_EVICTION_THRESHOLD: u8 = 128;
 
+/// The max number of payloads that were rejected before execution in the cache.
+///
+/// The payloads are identified by a digest of their contents, so that a cached response is only
+/// returned for an identical payload: the block hash of a payload isn't verified until it's
+/// well-formed.
+const MAX_REJECTED_PAYLOADS: u32 = 64;
+
 /// Keeps track of invalid headers.
 pub(crate) struct InvalidHeaderCache {
     /// This maps a header hash to a reference to its invalid ancestor.
     headers: LruMap<B256, HeaderEntry>,
+    /// Payloads that were rejected before execution, by their block hash.
+    rejected_payloads: LruMap<B256, RejectedPayload>,
     /// Metrics for the cache.
     metrics: InvalidHeaderCacheMetrics,
 }
 
 impl InvalidHeaderCache {
     pub(crate) fn new(max_length: u32) -> Self {
-        Self { headers: LruMap::new(ByLength::new(max_length)), metrics: Default::default() }
+        Self {
+            headers: LruMap::new(ByLength::new(max_length)),
+            rejected_payloads: LruMap::new(ByLength::new(MAX_REJECTED_PAYLOADS)),
+            metrics: Default::default(),
+        }
     }
 
     fn insert_entry(&mut self, hash: B256, header: Arc<Header>) {
@@ -77,6 +91,51 @@ impl InvalidHeaderCache {
             self.metrics.count.set(self.headers.len() as f64);
         }
     }
+
+    /// Returns the response to the payload if an identical payload was rejected before execution.
+    pub(crate) fn get_rejected_payload(
+        &mut self,
+        payload: &ExecutionPayload,
+        cancun_fields: &Option<CancunPayloadFields>,
+    ) -> Option<PayloadStatus> {
+        let entry = self.rejected_payloads.get(&payload.block_hash())?;
+        if entry.digest != payload_digest(payload, cancun_fields) {
+            return None
+        }
+        self.metrics.rejected_payload_hits.increment(1);
+        Some(entry.status.clone())
+    }
+
+    /// Inserts a payload that was rejected before execution by its block hash and
+    /// [digest](payload_digest), with the response to the consensus layer.
+    ///
+    /// If `settled` is false, the latest valid hash of the response depends on the known blocks,
+    /// and the entry is removed on the next [`Self::on_chain_update`].
+    pub(crate) fn insert_rejected_payload(
+        &mut self,
+        block_hash: B256,
+        digest: B256,
+        status: PayloadStatus,
+        settled: bool,
+    ) {
+        self.rejected_payloads.insert(block_hash, RejectedPayload { digest, status, settled });
+        self.metrics.rejected_payloads.set(self.rejected_payloads.len() as f64);
+    }
+
+    /// Removes the rejected payloads whose latest valid hash may have changed, because blocks
+    /// were inserted or the canonical chain changed.
+    pub(crate) fn on_chain_update(&mut self) {
+        let unsettled = self
+            .rejected_payloads
+            .iter()
+            .filter(|(_, entry)| !entry.settled)
+            .map(|(hash, _)| *hash)
+            .collect::<Vec<_>>();
+        for hash in unsettled {
+            self.rejected_payloads.remove(&hash);
+        }
+        self.metrics.rejected_payloads.set(self.rejected_payloads.len() as f64);
+    }
 }
 
 struct HeaderEntry {
@@ -86,6 +145,95 @@ struct HeaderEntry {
     header: Arc<Header>,
 }
 
+/// Returns a digest of the payload and the cancun fields that were sent with it.
+///
+/// Every field is fed into the hasher, variable length fields are prefixed with their length.
+pub(crate) fn payload_digest(
+    payload: &ExecutionPayload,
+    cancun_fields: &Option<CancunPayloadFields>,
+) -> B256 {
+    let mut hasher = Keccak256::new();
+    let (v2, v3, v4) = match payload {
+        ExecutionPayload::V1(_) => (None, None, None),
+        ExecutionPayload::V2(v2) => (Some(v2), None, None),
+        ExecutionPayload::V3(v3) => (Some(&v3.payload_inner), Some(v3), None),
+        ExecutionPayload::V4(v4) => {
+            (Some(&v4.payload_inner.payload_inner), Some(&v4.payload_inner), Some(v4))
+        }
+    };
+
+    let v1 = payload.as_v1();
+    hasher.update(v1.parent_hash);
+    hasher.update(v1.fee_recipient);
+    hasher.update(v1.state_root);
+    hasher.update(v1.receipts_root);
+    hasher.update(v1.logs_bloom);
+    hasher.update(v1.prev_randao);
+    for value in [v1.block_number, v1.gas_limit, v1.gas_used, v1.timestamp] {
+        hasher.update(value.to_be_bytes());
+    }
+    hasher.update((v1.extra_data.len() as u64).to_be_bytes());
+    hasher.update(&v1.extra_data);
+    hasher.update(v1.base_fee_per_gas.to_be_bytes::<32>());
+    hasher.update(v1.block_hash);
+    hasher.update((v1.transactions.len() as u64).to_be_bytes());
+    for transaction in &v1.transactions {
+        hasher.update((transaction.len() as u64).to_be_bytes());
+        hasher.update(transaction);
+    }
+
+    if let Some(v2) = v2 {
+        hasher.update((v2.withdrawals.len() as u64).to_be_bytes());
+        for withdrawal in &v2.withdrawals {
+            hasher.update(withdrawal.index.to_be_bytes());
+            hasher.update(withdrawal.validator_index.to_be_bytes());
+            hasher.update(withdrawal.address);
+            hasher.update(withdrawal.amount.to_be_bytes());
+        }
+    }
+    if let Some(v3) = v3 {
+        hasher.update(v3.blob_gas_used.to_be_bytes());
+        hasher.update(v3.excess_blob_gas.to_be_bytes());
+    }
+    if let Some(v4) = v4 {
+        hasher.update((v4.deposit_requests.len() as u64).to_be_bytes());
+        for request in &v4.deposit_requests {
+            hasher.update(request.pubkey);
+            hasher.update(request.withdrawal_credentials);
+            hasher.update(request.amount.to_be_bytes());
+            hasher.update(request.signature);
+            hasher.update(request.index.to_be_bytes());
+        }
+        hasher.update((v4.withdrawal_requests.len() as u64).to_be_bytes());
+        for request in &v4.withdrawal_requests {
+            hasher.update(request.source_address);
+            hasher.update(request.validator_public_key);
+            hasher.update(request.amount.to_be_bytes());
+        }
+    }
+
+    if let Some(CancunPayloadFields { parent_beacon_block_root, versioned_hashes }) = cancun_fields
+    {
+        hasher.update(parent_beacon_block_root);
+        hasher.update((versioned_hashes.len() as u64).to_be_bytes());
+        for versioned_hash in versioned_hashes {
+            hasher.update(versioned_hash);
+        }
+    }
+    hasher.finalize()
+}
+
+/// A payload that was rejected before execution, with the response to the consensus layer.
+struct RejectedPayload {
+    /// The [digest](payload_digest) of the rejected payload.
+    digest: B256,
+    /// The response to the consensus layer.
+    status: PayloadStatus,
+    /// Whether the latest valid hash of the response is independent of the known blocks, i.e.
+    /// it's the parent of the payload or it must be `null`.
+    settled: bool,
+}
+
 /// Metrics for the invalid headers cache.
 #[derive(Metrics)]
 #[metrics(scope = "consensus.engine.beacon.invalid_headers")]
@@ -98,11 +246,18 @@ struct InvalidHeaderCacheMetrics {
     unique_inserts: Counter,
     /// The number of times a header was evicted from the cache because it was hit too many times.
     hit_evictions: Counter,
+    /// The number of payloads that were rejected before execution in the cache.
+    rejected_payloads: Gauge,
+    /// The number of resent payloads that were rejected with the cached response.
+    rejected_payload_hits: Counter,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use reth_rpc_types::engine::{ExecutionPayloadV1, PayloadStatusEnum};
+    use reth_rpc_types_compat::engine::payload::block_to_payload_v1;
+    use reth_testing_utils::generators::{self, random_block};
 
     #[test]
     fn test_hit_eviction() {
@@ -118,4 +273,42 @@ mod tests {
 
         assert!(cache.get(&header.hash()).is_none());
     }
+
+    #[test]
+    fn test_rejected_payloads() {
+        let mut rng = generators::rng();
+        let mut cache = InvalidHeaderCache::new(10);
+        let invalid = PayloadStatusEnum::Invalid { validation_error: "invalid".to_string() };
+        let payload_v1 = block_to_payload_v1(random_block(&mut rng, 1, None, Some(1), None));
+        let payload = ExecutionPayload::from(payload_v1.clone());
+        let settled: ExecutionPayload =
+            block_to_payload_v1(random_block(&mut rng, 1, None, Some(1), None)).into();
+        let status = PayloadStatus::new(invalid.clone(), None);
+        let settled_status = PayloadStatus::new(invalid, Some(settled.parent_hash()));
+
+        assert_eq!(cache.get_rejected_payload(&payload, &None), None);
+        let digest = payload_digest(&payload, &None);
+        cache.insert_rejected_payload(payload.block_hash(), digest, status.clone(), false);
+        let digest = payload_digest(&settled, &None);
+        cache.insert_rejected_payload(settled.block_hash(), digest, settled_status.clone(), true);
+        for _ in 0..99 {
+            assert_eq!(cache.get_rejected_payload(&payload, &None), Some(status.clone()));
+        }
+
+        // a different payload or different cancun fields under the same block hash
+        let mut transaction = payload_v1.transactions[0].to_vec();
+        *transaction.last_mut().unwrap() ^= 1;
+        let other =
+            ExecutionPayloadV1 { transactions: vec![transaction.into()], ..payload_v1.clone() };
+        assert_eq!(cache.get_rejected_payload(&other.into(), &None), None);
+        let other = ExecutionPayloadV1 { extra_data: vec![1; 32].into(), ..payload_v1 };
+        assert_eq!(cache.get_rejected_payload(&other.into(), &None), None);
+        let cancun_fields = Some(CancunPayloadFields::default());
+        assert_eq!(cache.get_rejected_payload(&payload, &cancun_fields), None);
+
+        // only the settled response survives a chain update
+        cache.on_chain_update();
+        assert_eq!(cache.get_rejected_payload(&payload, &None), None);
+        assert_eq!(cache.get_rejected_payload(&settled, &None), Some(settled_status));
+    }
 }
@@ -1,8 +1,13 @@
-use crate::engine::{error::BeaconOnNewPayloadError, forkchoice::ForkchoiceStatus};
+use crate::engine::{
+    error::{BeaconOnNewPayloadError, BeaconSetHeadError},
+    forkchoice::ForkchoiceStatus,
+    SetHeadOutcome,
+};
 use futures::{future::Either, FutureExt};
 use reth_engine_primitives::EngineTypes;
 use reth_errors::RethResult;
 use reth_payload_builder::error::PayloadBuilderError;
+use reth_primitives::BlockHashOrNumber;
 use reth_rpc_types::engine::{
     CancunPayloadFields, ExecutionPayload, ForkChoiceUpdateResult, ForkchoiceState,
     ForkchoiceUpdateError, ForkchoiceUpdated, PayloadId, PayloadStatus, PayloadStatusEnum,
@@ -159,4 +164,13 @@ pub enum BeaconEngineMessage<Engine: EngineTypes> {
     },
     /// Message with exchanged transition configuration.
     TransitionConfigurationExchanged,
+    /// Message to roll back the canonical head to an earlier canonical block.
+    SetHead {
+        /// The canonical block that becomes the new head.
+        target: BlockHashOrNumber,
+        /// Whether the rollback is allowed to go below the finalized block.
+        force: bool,
+        /// The sender for returning the outcome of the rollback.
+        tx: oneshot::Sender<Result<SetHeadOutcome, BeaconSetHeadError>>,
+    },
 }
@@ -16,12 +16,12 @@ use reth_payload_builder::PayloadBuilderHandle;
 use reth_payload_primitives::{PayloadAttributes, PayloadBuilderAttributes};
 use reth_payload_validator::ExecutionPayloadValidator;
 use reth_primitives::{
-    constants::EPOCH_SLOTS, BlockNumHash, BlockNumber, Head, Header, SealedBlock, SealedHeader,
-    B256,
+    constants::EPOCH_SLOTS, BlockHashOrNumber, BlockNumHash, BlockNumber, Head, Header,
+    SealedBlock, SealedHeader, B256,
 };
 use reth_provider::{
-    BlockIdReader, BlockReader, BlockSource, CanonChainTracker, ChainSpecProvider, ProviderError,
-    StageCheckpointReader,
+    BlockIdReader, BlockReader, BlockSource, CanonChainTracker, Chain, ChainSpecProvider,
+    ExecutionOutcome, ProviderError, StageCheckpointReader,
 };
 use reth_rpc_types::engine::{
     CancunPayloadFields, ExecutionPayload, ForkchoiceState, PayloadStatus, PayloadStatusEnum,
@@ -31,10 +31,11 @@ use reth_stages_api::{ControlFlow, Pipeline, PipelineTarget, StageId};
 use reth_tasks::TaskSpawner;
 use reth_tokio_util::EventSender;
 use std::{
+    ops::RangeInclusive,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
 };
 use tokio::sync::{
     mpsc::{self, UnboundedSender},
@@ -49,11 +50,11 @@ pub use message::{BeaconEngineMessage, OnForkChoiceUpdated};
 mod error;
 pub use error::{
     BeaconConsensusEngineError, BeaconEngineResult, BeaconForkChoiceUpdateError,
-    BeaconOnNewPayloadError,
+    BeaconOnNewPayloadError, BeaconSetHeadError,
 };
 
 mod invalid_headers;
-use invalid_headers::InvalidHeaderCache;
+use invalid_headers::{payload_digest, InvalidHeaderCache};
 
 mod event;
 pub use event::{BeaconConsensusEngineEvent, ConsensusEngineLiveSyncProgress};
@@ -68,6 +69,10 @@ use forkchoice::{ForkchoiceStateHash, ForkchoiceStateTracker};
 mod metrics;
 use metrics::EngineMetrics;
 
+mod set_head;
+pub use set_head::SetHeadOutcome;
+use set_head::SetHeadState;
+
 pub(crate) mod sync;
 use sync::{EngineSyncController, EngineSyncEvent};
 
@@ -196,6 +201,10 @@ where
         Option<EngineT::PayloadAttributes>,
         oneshot::Sender<RethResult<OnForkChoiceUpdated>>,
     )>,
+    /// Requested rollback of the canonical head, see [`BeaconEngineMessage::SetHead`].
+    ///
+    /// While this is set, new payloads and forkchoice updates are answered with `SYNCING`.
+    set_head: Option<SetHeadState>,
     /// Tracks the header of invalid payloads that were rejected by the engine because they're
     /// invalid.
     invalid_headers: InvalidHeaderCache,
@@ -312,6 +321,7 @@ where
             invalid_headers: InvalidHeaderCache::new(MAX_INVALID_HEADERS),
             blockchain_tree_action: None,
             pending_forkchoice_update: None,
+            set_head: None,
             pipeline_run_threshold,
             hooks: EngineHooksController::new(hooks),
             event_sender,
@@ -391,6 +401,10 @@ where
     ) -> Result<OnForkChoiceUpdated, CanonicalError> {
         match make_canonical_result {
             Ok(outcome) => {
+                // The head may have been written outside of the tree, e.g. by the pipeline, and
+                // may be the parent of a rejected payload
+                self.invalid_headers.on_chain_update();
+
                 let should_update_head = match &outcome {
                     CanonicalOutcome::AlreadyCanonical { head, header } => {
                         self.on_head_already_canonical(head, header, &mut attrs)
@@ -1104,6 +1118,50 @@ where
         //
         // This validation **MUST** be instantly run in all cases even during active sync process.
         let parent_hash = payload.parent_hash();
+
+        // A consensus layer that is stuck on an invalid payload resends it, so identical payloads
+        // that were rejected before are rejected again without validating them
+        if let Some(status) = self.invalid_headers.get_rejected_payload(&payload, &cancun_fields) {
+            debug!(target: "consensus::engine", block_hash=?payload.block_hash(), "Payload was rejected before");
+            return Ok(Either::Left(status))
+        }
+
+        // Reject payloads that exceed the structural block limits before their transactions are
+        // decoded
+        if let Err(error) = self.payload_validator.ensure_payload_within_limits(&payload) {
+            error!(target: "consensus::engine", %error, "Payload exceeds block limits");
+            let latest_valid_hash = self
+                .latest_valid_hash_for_invalid_payload(parent_hash)
+                .map_err(BeaconOnNewPayloadError::internal)?;
+            let status = PayloadStatusEnum::Invalid { validation_error: error.to_string() };
+            let status = PayloadStatus::new(status, latest_valid_hash);
+            self.invalid_headers.insert_rejected_payload(
+                payload.block_hash(),
+                payload_digest(&payload, &cancun_fields),
+                status.clone(),
+                latest_valid_hash == Some(parent_hash),
+            );
+            return Ok(Either::Left(status))
+        }
+
+        // The consensus layer is authoritative for the payload timestamp, so unlike headers from
+        // the network, the payload may not be ahead of our clock at all. A payload from the future
+        // isn't invalid, our clock is behind, so the consensus layer is told to retry with
+        // `SYNCING` until our clock catches up.
+        let present_timestamp =
+            SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+        if let Err(error) =
+            self.payload_validator.ensure_payload_timestamp(&payload, present_timestamp)
+        {
+            warn!(target: "consensus::engine", %error, "Payload timestamp is in the future, is the local clock behind?");
+            return Ok(Either::Left(PayloadStatus::from_status(PayloadStatusEnum::Syncing)))
+        }
+
+        // The payload is consumed by the validation, a clone is kept to identify it if it's
+        // rejected. The transactions and the extra data are reference counted, so their contents
+        // aren't copied.
+        let sent = payload.clone();
+        let digest_fields = cancun_fields.clone();
         let block = match self
             .payload_validator
             .ensure_well_formed_payload(payload, cancun_fields.into())
@@ -1113,22 +1171,36 @@ where
                 error!(target: "consensus::engine", %error, "Invalid payload");
                 // we need to convert the error to a payload status (response to the CL)
 
-                let latest_valid_hash =
+                let (latest_valid_hash, settled) =
                     if error.is_block_hash_mismatch() || error.is_invalid_versioned_hashes() {
                         // Engine-API rules:
                         // > `latestValidHash: null` if the blockHash validation has failed (<https://github.com/ethereum/execution-apis/blob/fe8e13c288c592ec154ce25c534e26cb7ce0530d/src/engine/shanghai.md?plain=1#L113>)
                         // > `latestValidHash: null` if the expected and the actual arrays don't match (<https://github.com/ethereum/execution-apis/blob/fe8e13c288c592ec154ce25c534e26cb7ce0530d/src/engine/cancun.md?plain=1#L103>)
-                        None
+                        (None, true)
                     } else {
-                        self.latest_valid_hash_for_invalid_payload(parent_hash)
-                            .map_err(BeaconOnNewPayloadError::internal)?
+                        let latest_valid_hash = self
+                            .latest_valid_hash_for_invalid_payload(parent_hash)
+                            .map_err(BeaconOnNewPayloadError::internal)?;
+                        (latest_valid_hash, latest_valid_hash == Some(parent_hash))
                     };
 
-                let status = PayloadStatusEnum::from(error);
-                return Ok(Either::Left(PayloadStatus::new(status, latest_valid_hash)))
+                let status = PayloadStatus::new(PayloadStatusEnum::from(error), latest_valid_hash);
+                self.invalid_headers.insert_rejected_payload(
+                    sent.block_hash(),
+                    payload_digest(&sent, &digest_fields),
+                    status.clone(),
+                    settled,
+                );
+                return Ok(Either::Left(status))
             }
         };
 
+        // track how far the local head is behind the latest payload, the network uses this to
+        // suppress transaction gossip while the node is far behind the chain tip
+        self.sync_state_updater.update_sync_distance(
+            block.number.saturating_sub(self.blockchain.canonical_tip().number),
+        );
+
         let mut lowest_buffered_ancestor = self.lowest_buffered_ancestor_or(block.hash());
         if lowest_buffered_ancestor == block.hash() {
             lowest_buffered_ancestor = block.parent_hash;
@@ -1236,6 +1308,8 @@ where
         let status = match status {
             InsertPayloadOk::Inserted(BlockStatus::Valid(attachment)) => {
                 latest_valid_hash = Some(block_hash);
+                // the block may be the parent of a rejected payload
+                self.invalid_headers.on_chain_update();
                 let block = Arc::new(block);
                 let event = if attachment.is_canonical() {
                     BeaconConsensusEngineEvent::CanonicalBlockAdded(block, elapsed)
@@ -1263,6 +1337,11 @@ where
                 // not known to be invalid, but we don't know anything else
                 PayloadStatusEnum::Syncing
             }
+            InsertPayloadOk::Deferred { retry_after } => {
+                // the tree is congested, the CL has to send the payload again
+                debug!(target: "consensus::engine", hash=?block_hash, ?retry_after, "Deferred new payload");
+                PayloadStatusEnum::Syncing
+            }
         };
         Ok(PayloadStatus::new(status, latest_valid_hash))
     }
@@ -1314,6 +1393,21 @@ where
         }
     }
 
+    /// This handles blocks that are disconnected from the canonical chain and too far above the
+    /// canonical tip to be buffered in the tree.
+    ///
+    /// Downloading the missing ancestors would only produce more blocks that can't be buffered, so
+    /// the pipeline is run instead. It syncs to the head of the current forkchoice target if there
+    /// is one, or else to the block itself.
+    fn on_block_too_far_ahead(&mut self, block: BlockNumHash) {
+        let target = self
+            .forkchoice_state_tracker
+            .sync_target_state()
+            .map_or(block.hash, |state| state.head_block_hash);
+        debug!(target: "consensus::engine", ?block, ?target, "Block too far ahead to buffer, running pipeline");
+        self.sync.set_pipeline_sync_target(target.into());
+    }
+
     /// Attempt to form a new canonical chain based on the current sync target.
     ///
     /// This is invoked when we successfully __downloaded__ a new block from the network which
@@ -1337,6 +1431,7 @@ where
         match make_canonical_result {
             Ok(outcome) => {
                 if let CanonicalOutcome::Committed { head } = &outcome {
+                    self.invalid_headers.on_chain_update();
                     self.event_sender.notify(BeaconConsensusEngineEvent::CanonicalChainCommitted(
                         Box::new(head.clone()),
                         elapsed,
@@ -1436,6 +1531,18 @@ where
     /// Updates the internal sync state depending on the pipeline configuration,
     /// the outcome of the pipeline run and the last observed forkchoice state.
     fn on_pipeline_outcome(&mut self, ctrl: ControlFlow) -> RethResult<()> {
+        // Pipeline unwound the stages on behalf of a head rollback, finish it.
+        if let Some(SetHeadState::Unwinding { mut outcome, unwound, tx }) = self.set_head.take() {
+            let result = self
+                .blockchain
+                .notify_canonical_chain_unwound(unwound)
+                .map_err(RethError::from)
+                .and_then(|_| self.finish_set_head(&mut outcome))
+                .map(|_| outcome);
+            let _ = tx.send(result.map_err(BeaconSetHeadError::from));
+            return Ok(())
+        }
+
         // Pipeline unwound, memorize the invalid block and wait for CL for next sync target.
         if let ControlFlow::Unwind { bad_block, .. } = ctrl {
             warn!(target: "consensus::engine", invalid_hash=?bad_block.hash(), invalid_number=?bad_block.number, "Bad block detected in unwind");
@@ -1444,6 +1551,16 @@ where
             return Ok(())
         }
 
+        // Pipeline unwound blocks that were reorged out, they are not invalid. Wait for CL for
+        // next sync target.
+        if let ControlFlow::Reorg { target } = ctrl {
+            warn!(target: "consensus::engine", unwind_target=?target, "Pipeline unwound reorged blocks");
+            return Ok(())
+        }
+
+        // The pipeline may have written the parents of rejected payloads
+        self.invalid_headers.on_chain_update();
+
         let sync_target_state = match self.forkchoice_state_tracker.sync_target_state() {
             Some(current_state) => current_state,
             None => {
@@ -1535,6 +1652,179 @@ where
         Ok(())
     }
 
+    /// Invoked when a rollback of the canonical head was requested.
+    ///
+    /// The rollback is queued until the pipeline and any hook with database write access are
+    /// idle, see [`Self::start_set_head`].
+    fn on_set_head(
+        &mut self,
+        target: BlockHashOrNumber,
+        force: bool,
+        tx: oneshot::Sender<Result<SetHeadOutcome, BeaconSetHeadError>>,
+    ) {
+        if self.set_head.is_some() {
+            let _ = tx.send(Err(BeaconSetHeadError::AlreadyInProgress));
+            return
+        }
+        info!(target: "consensus::engine", ?target, force, "Received head rollback request");
+        self.set_head = Some(SetHeadState::Queued { target, force, tx });
+    }
+
+    /// Starts a queued rollback of the canonical head.
+    ///
+    /// Blocks that were committed by the tree are reverted right away. If the new head is below
+    /// the blocks in static files, the remaining blocks are reverted by unwinding the pipeline,
+    /// and the rollback is finished once the pipeline is done, see [`Self::on_pipeline_outcome`].
+    fn start_set_head(&mut self) {
+        debug_assert!(self.sync.is_pipeline_idle(), "pipeline must be idle");
+
+        let Some(SetHeadState::Queued { target, force, tx }) = self.set_head.take() else { return };
+
+        match self.try_start_set_head(target, force) {
+            Ok((outcome, Some(unwound))) => {
+                self.set_head = Some(SetHeadState::Unwinding { outcome, unwound, tx });
+            }
+            Ok((mut outcome, None)) => {
+                let result = self.finish_set_head(&mut outcome).map(|_| outcome);
+                let _ = tx.send(result.map_err(BeaconSetHeadError::from));
+            }
+            Err(error) => {
+                warn!(target: "consensus::engine", ?target, %error, "Failed to roll back the canonical head");
+                let _ = tx.send(Err(error));
+            }
+        }
+    }
+
+    /// Validates the rollback target and reverts all blocks that can be reverted by the tree.
+    ///
+    /// Returns the blocks that are left for the pipeline to unwind, if any.
+    fn try_start_set_head(
+        &mut self,
+        target: BlockHashOrNumber,
+        force: bool,
+    ) -> Result<(SetHeadOutcome, Option<Chain>), BeaconSetHeadError> {
+        let number = match target {
+            BlockHashOrNumber::Hash(hash) => {
+                self.blockchain.block_number(hash).map_err(BeaconSetHeadError::internal)?
+            }
+            BlockHashOrNumber::Number(number) => Some(number),
+        };
+        let new_head = number
+            .map(|number| self.blockchain.sealed_header(number))
+            .transpose()
+            .map_err(BeaconSetHeadError::internal)?
+            .flatten()
+            .filter(|header| match target {
+                BlockHashOrNumber::Hash(hash) => header.hash() == hash,
+                BlockHashOrNumber::Number(_) => true,
+            })
+            .ok_or(BeaconSetHeadError::NotCanonical(target))?;
+
+        if !force {
+            let finalized =
+                self.blockchain.finalized_block_number().map_err(BeaconSetHeadError::internal)?;
+            if let Some(finalized) = finalized.filter(|finalized| new_head.number < *finalized) {
+                return Err(BeaconSetHeadError::BelowFinalized {
+                    target: new_head.number,
+                    finalized,
+                })
+            }
+        }
+
+        let last_block_number =
+            self.blockchain.last_block_number().map_err(BeaconSetHeadError::internal)?;
+        let unwound: Vec<_> = self
+            .blockchain
+            .sealed_headers_range(new_head.number + 1..=last_block_number)
+            .map_err(BeaconSetHeadError::internal)?
+            .iter()
+            .map(|header| header.num_hash())
+            .collect();
+        let mut outcome = SetHeadOutcome {
+            previous_head: unwound.last().copied().unwrap_or_else(|| new_head.num_hash()),
+            new_head: new_head.num_hash(),
+            unwound,
+            pipeline_unwound: false,
+            safe_clamped: false,
+            finalized_clamped: false,
+        };
+        if outcome.is_noop() {
+            return Ok((outcome, None))
+        }
+
+        // The tree can only revert the blocks it committed itself, the blocks that were already
+        // written to static files have to be unwound by the pipeline.
+        let tip = self
+            .blockchain
+            .unwind_canonical_chain(new_head.number)
+            .map_err(BeaconSetHeadError::internal)?;
+        let mut unwound = None;
+        if tip > new_head.number {
+            // read the blocks before the pipeline removes them, so they can be announced
+            unwound = Some(
+                self.canonical_chain(new_head.number + 1..=tip)
+                    .map_err(BeaconSetHeadError::internal)?,
+            );
+            outcome.pipeline_unwound = true;
+            self.sync.set_pipeline_sync_target(PipelineTarget::Unwind(new_head.number));
+        }
+
+        info!(target: "consensus::engine", previous_head = ?outcome.previous_head, new_head = ?outcome.new_head, pipeline_unwound = outcome.pipeline_unwound, "Rolling back the canonical head");
+        Ok((outcome, unwound))
+    }
+
+    /// Returns the canonical blocks in the given range together with their receipts.
+    fn canonical_chain(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Chain> {
+        let first = *range.start();
+        let blocks = self.blockchain.sealed_block_with_senders_range(range.clone())?;
+        let receipts = range
+            .map(|number| {
+                let receipts = self.blockchain.receipts_by_block(number.into())?;
+                Ok(receipts.unwrap_or_default().into_iter().map(Some).collect())
+            })
+            .collect::<ProviderResult<Vec<_>>>()?;
+        Ok(Chain::new(
+            blocks,
+            ExecutionOutcome::new(Default::default(), receipts.into(), first, Vec::new()),
+            None,
+        ))
+    }
+
+    /// Finishes a rollback of the canonical head after all blocks were reverted from the database.
+    ///
+    /// This re-syncs the tree with the database, clamps the safe and finalized blocks to the new
+    /// head and resets the forkchoice tracking, so the engine waits for the next forkchoice update.
+    fn finish_set_head(&mut self, outcome: &mut SetHeadOutcome) -> RethResult<()> {
+        // drop everything in the tree that no longer connects to the canonical chain
+        self.blockchain.update_block_hashes_and_clear_buffered()?;
+        self.sync.clear_block_download_requests();
+
+        let new_head = self
+            .blockchain
+            .sealed_header(outcome.new_head.number)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(outcome.new_head.number.into()))?;
+        self.blockchain.set_canonical_head(new_head.clone());
+
+        if self.blockchain.safe_block_num_hash()?.is_some_and(|safe| safe.number > new_head.number)
+        {
+            self.blockchain.set_safe(new_head.clone());
+            outcome.safe_clamped = true;
+        }
+        if self
+            .blockchain
+            .finalized_block_num_hash()?
+            .is_some_and(|finalized| finalized.number > new_head.number)
+        {
+            self.blockchain.set_finalized(new_head.clone());
+            outcome.finalized_clamped = true;
+        }
+
+        self.forkchoice_state_tracker = ForkchoiceStateTracker::default();
+
+        info!(target: "consensus::engine", new_head = ?outcome.new_head, unwound = outcome.unwound.len(), "Rolled back the canonical head");
+        Ok(())
+    }
+
     fn set_canonical_head(&self, max_block: BlockNumber) -> RethResult<()> {
         let max_header = self.blockchain.sealed_header(max_block)
         .inspect_err(|error| {
@@ -1639,6 +1929,28 @@ where
                         warn!(target: "consensus::engine", %error, "Error while processing payload");
 
                         let (block, error) = error.split();
+                        if error.is_block_too_far_ahead() {
+                            // the block is disconnected, but too far ahead to be buffered, so its
+                            // missing ancestors are synced by the pipeline instead
+                            let status = match self
+                                .check_invalid_ancestor_with_head(block.parent_hash, block_hash)?
+                            {
+                                Some(status) => status,
+                                None => {
+                                    self.on_block_too_far_ahead(block_num_hash);
+                                    PayloadStatus::from_status(PayloadStatusEnum::Syncing)
+                                }
+                            };
+                            let _ = tx.send(Ok(status));
+                            return Ok(EngineEventOutcome::Processed)
+                        }
+                        if error.is_execution_cancelled() {
+                            // the execution exceeded the soft limit, this doesn't prove the block
+                            // is invalid, so the CL may retry it
+                            let _ =
+                                tx.send(Ok(PayloadStatus::from_status(PayloadStatusEnum::Syncing)));
+                            return Ok(EngineEventOutcome::Processed)
+                        }
                         if !error.is_invalid_block() {
                             // TODO: revise if any error should be considered fatal at this point.
                             let _ =
@@ -1727,6 +2039,7 @@ where
                     Ok(status) => {
                         match status {
                             InsertPayloadOk::Inserted(BlockStatus::Valid(_)) => {
+                                self.invalid_headers.on_chain_update();
                                 // block is connected to the canonical chain and is valid.
                                 // if it's not connected to current canonical head, the state root
                                 // has not been validated.
@@ -1757,12 +2070,18 @@ where
                                     head,
                                 );
                             }
+                            InsertPayloadOk::Deferred { .. } => {
+                                // the block is downloaded again for the next forkchoice update
+                                debug!(target: "consensus::engine", ?downloaded_num_hash, "Deferred downloaded block");
+                            }
                             _ => (),
                         }
                     }
                     Err(err) => {
                         warn!(target: "consensus::engine", %err, "Failed to insert downloaded block");
-                        if err.kind().is_invalid_block() {
+                        if err.kind().is_block_too_far_ahead() {
+                            self.on_block_too_far_ahead(downloaded_num_hash);
+                        } else if err.kind().is_invalid_block() {
                             let (block, err) = err.split();
                             warn!(target: "consensus::engine", invalid_number=?block.number, invalid_hash=?block.hash(), %err, "Marking block as invalid");
 
@@ -1841,6 +2160,15 @@ where
                         );
                         continue
                     }
+
+                    // Start a queued head rollback once the pipeline is fully drained.
+                    if matches!(this.set_head, Some(SetHeadState::Queued { .. })) &&
+                        this.sync.is_pipeline_idle() &&
+                        !this.sync.is_pipeline_sync_pending()
+                    {
+                        this.start_set_head();
+                        continue
+                    }
                 }
 
                 // Process one incoming message from the CL. We don't drain the messages right away,
@@ -1850,6 +2178,16 @@ where
                 // sensitive, hence they are polled first.
                 if let Poll::Ready(Some(msg)) = this.engine_message_stream.poll_next_unpin(cx) {
                     match msg {
+                        // Reject all chain updates while the head is being rolled back.
+                        BeaconEngineMessage::ForkchoiceUpdated { tx, .. }
+                            if this.set_head.is_some() =>
+                        {
+                            let _ = tx.send(Ok(OnForkChoiceUpdated::syncing()));
+                        }
+                        BeaconEngineMessage::NewPayload { tx, .. } if this.set_head.is_some() => {
+                            let _ =
+                                tx.send(Ok(PayloadStatus::from_status(PayloadStatusEnum::Syncing)));
+                        }
                         BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx } => {
                             this.on_forkchoice_updated(state, payload_attrs, tx);
                         }
@@ -1871,6 +2209,9 @@ where
                         BeaconEngineMessage::TransitionConfigurationExchanged => {
                             this.blockchain.on_transition_configuration_exchanged();
                         }
+                        BeaconEngineMessage::SetHead { target, force, tx } => {
+                            this.on_set_head(target, force, tx);
+                        }
                     }
                     continue
                 }
@@ -2013,7 +2354,7 @@ mod tests {
             .await;
         assert_matches!(
             res.await,
-            Ok(Err(BeaconConsensusEngineError::Pipeline(n))) if matches!(*n.as_ref(),PipelineError::Stage(StageError::ChannelClosed))
+            Ok(Err(BeaconConsensusEngineError::Pipeline(n))) if matches!(n.as_ref(), PipelineError::Stage(err) if matches!(err.inner(), StageError::ChannelClosed))
         );
     }
 
@@ -2062,7 +2403,7 @@ mod tests {
                 Ok(result) => {
                     assert_matches!(
                         result,
-                        Err(BeaconConsensusEngineError::Pipeline(n)) if matches!(*n.as_ref(), PipelineError::Stage(StageError::ChannelClosed))
+                        Err(BeaconConsensusEngineError::Pipeline(n)) if matches!(n.as_ref(), PipelineError::Stage(err) if matches!(err.inner(), StageError::ChannelClosed))
                     );
                     break
                 }
@@ -2114,7 +2455,7 @@ mod tests {
 
         assert_matches!(
             rx.await,
-            Ok(Err(BeaconConsensusEngineError::Pipeline(n)))  if matches!(*n.as_ref(),PipelineError::Stage(StageError::ChannelClosed))
+            Ok(Err(BeaconConsensusEngineError::Pipeline(n)))  if matches!(n.as_ref(), PipelineError::Stage(err) if matches!(err.inner(), StageError::ChannelClosed))
         );
     }
 
@@ -2402,14 +2743,17 @@ mod tests {
             let genesis = random_block(&mut rng, 0, None, None, Some(0));
             let mut block1 = random_block(&mut rng, 1, Some(genesis.hash()), None, Some(0));
             block1.header.set_difficulty(U256::from(1));
+            block1 = block1.unseal().seal_slow();
 
             // a second pre-merge block
             let mut block2 = random_block(&mut rng, 1, Some(genesis.hash()), None, Some(0));
             block2.header.set_difficulty(U256::from(1));
+            block2 = block2.unseal().seal_slow();
 
             // a transition block
             let mut block3 = random_block(&mut rng, 1, Some(genesis.hash()), None, Some(0));
             block3.header.set_difficulty(U256::from(1));
+            block3 = block3.unseal().seal_slow();
 
             let (_static_dir, static_dir_path) = create_test_static_files_dir();
             insert_blocks(
@@ -2490,8 +2834,10 @@ mod tests {
 
     mod new_payload {
         use super::*;
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+        use reth_consensus::ConsensusError;
         use reth_db::test_utils::create_test_static_files_dir;
-        use reth_primitives::{genesis::Genesis, Hardfork, U256};
+        use reth_primitives::{genesis::Genesis, Bytes, Chain, Hardfork, U256};
         use reth_provider::{
             providers::StaticFileProvider, test_utils::blocks::BlockchainTestData,
         };
@@ -2541,6 +2887,128 @@ mod tests {
             assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
         }
 
+        #[tokio::test]
+        async fn payload_exceeding_block_limits() {
+            let mut rng = generators::rng();
+            let genesis: Genesis = serde_json::from_str(
+                r#"{
+                    "config": {
+                        "chainId": 1337,
+                        "blockLimits": {
+                            "maxGasLimit": 100000000,
+                            "maxTransactions": 2,
+                            "maxBlockSize": 300,
+                            "maxTransactionSize": 200
+                        }
+                    },
+                    "difficulty": "0x0",
+                    "gasLimit": "0x1c9c380",
+                    "alloc": {}
+                }"#,
+            )
+            .unwrap();
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(Chain::dev())
+                    .genesis(genesis)
+                    .paris_activated()
+                    .build(),
+            );
+
+            let (consensus_engine, env) = TestConsensusEngineBuilder::new(chain_spec.clone())
+                .with_pipeline_exec_outputs(VecDeque::from([Ok(ExecOutput {
+                    checkpoint: StageCheckpoint::new(0),
+                    done: true,
+                })]))
+                .build();
+            let executor = env.executor.clone().unwrap();
+
+            let mut engine_rx = spawn_consensus_engine(consensus_engine);
+
+            // the transactions are never decoded, so they don't have to be valid
+            let tx = |size: usize| Bytes::from(vec![0u8; size]);
+            let violations = [
+                (
+                    100_000_001,
+                    vec![],
+                    ConsensusError::GasLimitExceedsMax { gas_limit: 100_000_001, max: 100_000_000 },
+                ),
+                (
+                    30_000_000,
+                    vec![tx(1); 3],
+                    ConsensusError::TooManyTransactions { count: 3, max: 2 },
+                ),
+                (
+                    30_000_000,
+                    vec![tx(1), tx(201)],
+                    ConsensusError::TransactionTooLarge { index: 1, size: 201, max: 200 },
+                ),
+                (
+                    30_000_000,
+                    vec![tx(200), tx(101)],
+                    ConsensusError::BlockTooLarge { size: 301, max: 300 },
+                ),
+            ];
+            for (gas_limit, transactions, error) in violations {
+                let block =
+                    random_block(&mut rng, 1, Some(chain_spec.genesis_hash()), Some(0), Some(0));
+                let mut payload = block_to_payload_v1(block);
+                payload.gas_limit = gas_limit;
+                payload.transactions = transactions;
+
+                let status = env.send_new_payload(payload, None).await.unwrap().status;
+                assert_eq!(
+                    status,
+                    PayloadStatusEnum::Invalid { validation_error: error.to_string() }
+                );
+            }
+
+            // all payloads were rejected before reaching the executor
+            assert_eq!(executor.executions(), 0);
+            assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
+        }
+
+        #[tokio::test]
+        async fn payload_with_future_timestamp() {
+            let mut rng = generators::rng();
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+
+            let (consensus_engine, env) = TestConsensusEngineBuilder::new(chain_spec.clone())
+                .with_pipeline_exec_outputs(VecDeque::from([Ok(ExecOutput {
+                    checkpoint: StageCheckpoint::new(0),
+                    done: true,
+                })]))
+                .build();
+            let executor = env.executor.clone().unwrap();
+
+            let mut engine_rx = spawn_consensus_engine(consensus_engine);
+
+            // within the tolerance of headers from the network, but payloads of the consensus layer
+            // may not be ahead of our clock at all
+            let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+            let block =
+                random_block(&mut rng, 1, Some(chain_spec.genesis_hash()), Some(0), Some(0));
+            let mut payload = block_to_payload_v1(block);
+            payload.timestamp = now + 10;
+
+            // our clock is behind, so the consensus layer is told to retry later
+            let status = env.send_new_payload(payload.clone(), None).await.unwrap();
+            assert_eq!(status, PayloadStatus::from_status(PayloadStatusEnum::Syncing));
+
+            // the payload isn't remembered as invalid
+            let status = env.send_new_payload(payload, None).await.unwrap();
+            assert_eq!(status, PayloadStatus::from_status(PayloadStatusEnum::Syncing));
+
+            assert_eq!(executor.executions(), 0);
+            assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
+        }
+
         #[tokio::test]
         async fn payload_known() {
             let mut rng = generators::rng();
@@ -2599,6 +3067,114 @@ mod tests {
             assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
         }
 
+        #[tokio::test]
+        async fn rejected_payload_resent() {
+            const HITS: &str = "consensus.engine.beacon.invalid_headers.rejected_payload_hits";
+
+            let mut rng = generators::rng();
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+
+            // the metrics of the engine are registered with the recorder when it's built
+            let recorder = DebuggingRecorder::new();
+            let snapshotter = recorder.snapshotter();
+            let (consensus_engine, env) = ::metrics::with_local_recorder(&recorder, || {
+                TestConsensusEngineBuilder::new(chain_spec.clone())
+                    .with_pipeline_exec_outputs(VecDeque::from([Ok(ExecOutput {
+                        checkpoint: StageCheckpoint::new(0),
+                        done: true,
+                    })]))
+                    .build()
+            });
+            let executor = env.executor.clone().unwrap();
+            let hits = || {
+                snapshotter.snapshot().into_vec().into_iter().find_map(|(key, _, _, value)| {
+                    match value {
+                        DebugValue::Counter(value) if key.key().name() == HITS => Some(value),
+                        _ => None,
+                    }
+                })
+            };
+
+            let genesis = random_block(&mut rng, 0, None, None, Some(0));
+            let block1 = random_block(&mut rng, 1, Some(genesis.hash()), None, Some(0));
+            let block2 = random_block(&mut rng, 2, Some(block1.hash()), None, Some(0));
+            let block3 = random_block(&mut rng, 3, Some(block2.hash()), None, Some(0));
+
+            let (_static_dir, static_dir_path) = create_test_static_files_dir();
+            insert_blocks(
+                ProviderFactory::new(
+                    env.db.as_ref(),
+                    chain_spec.clone(),
+                    StaticFileProvider::read_write(static_dir_path).unwrap(),
+                ),
+                [&genesis, &block1].into_iter(),
+            );
+
+            let mut engine_rx = spawn_consensus_engine(consensus_engine);
+
+            let res = env
+                .send_forkchoice_updated(ForkchoiceState {
+                    head_block_hash: block1.hash(),
+                    finalized_block_hash: block1.hash(),
+                    ..Default::default()
+                })
+                .await;
+            assert_matches!(res, Ok(ForkchoiceUpdated { payload_status, .. }) => {
+                assert_eq!(payload_status.status, PayloadStatusEnum::Valid);
+            });
+
+            // a malformed child of the unknown block 2 is rejected without a latest valid hash
+            let mut malformed = block_to_payload_v1(block3);
+            malformed.extra_data = Bytes::from(vec![0; 33]);
+            let first = env.send_new_payload(malformed.clone(), None).await.unwrap();
+            assert_matches!(first.status, PayloadStatusEnum::Invalid { .. });
+            assert_eq!(first.latest_valid_hash, None);
+            for _ in 0..99 {
+                assert_eq!(env.send_new_payload(malformed.clone(), None).await.unwrap(), first);
+            }
+            // the payload was validated once, every resent payload is served from the cache
+            assert_eq!(hits(), Some(99));
+
+            // the parent is written to the database, the cached response is still returned until
+            // the canonical chain is updated
+            let (_static_dir, static_dir_path) = create_test_static_files_dir();
+            insert_blocks(
+                ProviderFactory::new(
+                    env.db.as_ref(),
+                    chain_spec.clone(),
+                    StaticFileProvider::read_write(static_dir_path).unwrap(),
+                ),
+                std::iter::once(&block2),
+            );
+            assert_eq!(env.send_new_payload(malformed.clone(), None).await.unwrap(), first);
+            assert_eq!(hits(), Some(100));
+
+            let res = env
+                .send_forkchoice_updated(ForkchoiceState {
+                    head_block_hash: block2.hash(),
+                    finalized_block_hash: block1.hash(),
+                    ..Default::default()
+                })
+                .await;
+            assert_matches!(res, Ok(ForkchoiceUpdated { payload_status, .. }) => {
+                assert_eq!(payload_status.status, PayloadStatusEnum::Valid);
+            });
+
+            // the response is recomputed with the parent as the latest valid hash
+            let resent = env.send_new_payload(malformed, None).await.unwrap();
+            assert_eq!(resent.status, first.status);
+            assert_eq!(resent.latest_valid_hash, Some(block2.hash()));
+            assert_eq!(hits(), Some(100));
+            assert_eq!(executor.executions(), 0);
+            assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
+        }
+
         #[tokio::test]
         async fn simple_validate_block() {
             let mut rng = generators::rng();
@@ -2716,6 +3292,67 @@ mod tests {
             assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
         }
 
+        #[tokio::test]
+        async fn payload_too_far_ahead_runs_pipeline() {
+            let mut rng = generators::rng();
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+
+            let (consensus_engine, env) = TestConsensusEngineBuilder::new(chain_spec.clone())
+                .with_pipeline_exec_outputs(VecDeque::from([
+                    Ok(ExecOutput { checkpoint: StageCheckpoint::new(0), done: true }),
+                    Ok(ExecOutput { checkpoint: StageCheckpoint::new(0), done: true }),
+                ]))
+                .build();
+
+            let genesis = random_block(&mut rng, 0, None, None, Some(0));
+
+            let (_static_dir, static_dir_path) = create_test_static_files_dir();
+
+            insert_blocks(
+                ProviderFactory::new(
+                    env.db.as_ref(),
+                    chain_spec.clone(),
+                    StaticFileProvider::read_write(static_dir_path).unwrap(),
+                ),
+                std::iter::once(&genesis),
+            );
+
+            let _engine_rx = spawn_consensus_engine(consensus_engine);
+
+            // Send forkchoice
+            let res = env
+                .send_forkchoice_updated(ForkchoiceState {
+                    head_block_hash: genesis.hash(),
+                    finalized_block_hash: genesis.hash(),
+                    ..Default::default()
+                })
+                .await;
+            let expected_result = PayloadStatus::from_status(PayloadStatusEnum::Valid)
+                .with_latest_valid_hash(genesis.hash());
+            assert_matches!(res, Ok(ForkchoiceUpdated { payload_status, .. }) => assert_eq!(payload_status, expected_result));
+
+            // Send a new payload more than the max buffered block distance above the tip
+            let parent = rng.gen();
+            let block = random_block(&mut rng, 2_000, Some(parent), None, Some(0));
+            let block_hash = block.hash();
+            let res = env.send_new_payload(block_to_payload_v1(block), None).await;
+            let expected_result = PayloadStatus::from_status(PayloadStatusEnum::Syncing);
+            assert_matches!(res, Ok(result) => assert_eq!(result, expected_result));
+
+            // the pipeline syncs to the payload, since there is no forkchoice target
+            let mut tip_rx = env.tip_rx.clone();
+            tokio::time::timeout(Duration::from_secs(5), tip_rx.wait_for(|tip| *tip == block_hash))
+                .await
+                .expect("pipeline was not started")
+                .unwrap();
+        }
+
         #[tokio::test]
         async fn payload_pre_merge() {
             let data = BlockchainTestData::default();
@@ -2793,4 +3430,199 @@ mod tests {
             assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
         }
     }
+
+    mod set_head {
+        use super::*;
+        use reth_blockchain_tree_api::BlockchainTreeViewer;
+        use reth_db::tables;
+        use reth_db_api::transaction::{DbTx, DbTxMut};
+        use reth_primitives::{constants::EMPTY_ROOT_HASH, StaticFileSegment, U256};
+        use reth_provider::{
+            providers::StaticFileWriter, BlockNumReader, CanonStateNotification,
+            CanonStateSubscriptions, HeaderProvider, StaticFileProviderFactory,
+        };
+        use reth_stages::{test_utils::TEST_STAGE_ID, UnwindOutput};
+        use reth_testing_utils::generators::random_block_range;
+
+        /// Returns a chain of empty blocks `0..=10` that can be reverted from a database without
+        /// state.
+        fn empty_blocks() -> Vec<SealedBlock> {
+            let mut parent_hash = B256::ZERO;
+            (0..=10)
+                .map(|number| {
+                    let header = Header {
+                        number,
+                        parent_hash,
+                        state_root: EMPTY_ROOT_HASH,
+                        ..Default::default()
+                    }
+                    .seal_slow();
+                    parent_hash = header.hash();
+                    SealedBlock { header, ..Default::default() }
+                })
+                .collect()
+        }
+
+        #[tokio::test]
+        async fn rolls_back_blocks_committed_by_tree() {
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+
+            let (consensus_engine, env) =
+                TestConsensusEngineBuilder::new(chain_spec.clone()).build();
+            let blockchain = consensus_engine.blockchain.clone();
+
+            let blocks = empty_blocks();
+            insert_blocks(
+                ProviderFactory::new(
+                    env.db.as_ref(),
+                    chain_spec.clone(),
+                    blockchain.static_file_provider(),
+                ),
+                blocks.iter(),
+            );
+            env.db
+                .update(|tx| {
+                    for stage_id in StageId::ALL {
+                        tx.put::<tables::StageCheckpoints>(
+                            stage_id.to_string(),
+                            StageCheckpoint::new(10),
+                        )?;
+                    }
+                    Ok::<_, reth_db::DatabaseError>(())
+                })
+                .unwrap()
+                .unwrap();
+            blockchain.update_block_hashes_and_clear_buffered().unwrap();
+            let mut canon_state_rx = blockchain.subscribe_to_canonical_state();
+
+            let mut engine_rx = spawn_consensus_engine(consensus_engine);
+
+            let outcome = env.send_set_head(BlockHashOrNumber::Number(5), false).await.unwrap();
+            assert_eq!(outcome.previous_head, blocks[10].header.num_hash());
+            assert_eq!(outcome.new_head, blocks[5].header.num_hash());
+            assert_eq!(
+                outcome.unwound,
+                blocks[6..].iter().map(|block| block.header.num_hash()).collect::<Vec<_>>()
+            );
+            assert!(!outcome.pipeline_unwound);
+
+            // the database, the tree and the stage checkpoints agree on the new head
+            assert_eq!(blockchain.last_block_number().unwrap(), 5);
+            assert_eq!(blockchain.best_block_number().unwrap(), 5);
+            assert_eq!(blockchain.header_by_number(6).unwrap(), None);
+            assert_eq!(blockchain.canonical_tip(), blocks[5].header.num_hash());
+            for stage_id in StageId::ALL {
+                assert_eq!(
+                    blockchain.get_stage_checkpoint(stage_id).unwrap(),
+                    Some(StageCheckpoint::new(5)),
+                    "{stage_id}"
+                );
+            }
+
+            assert_matches!(
+                canon_state_rx.try_recv(),
+                Ok(CanonStateNotification::Reorg { old, new })
+                    if old.range() == (6..=10) && new.tip().hash() == blocks[5].hash()
+            );
+
+            assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
+        }
+
+        #[tokio::test]
+        async fn rolls_back_blocks_in_static_files() {
+            let mut rng = generators::rng();
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+
+            let (consensus_engine, env) = TestConsensusEngineBuilder::new(chain_spec.clone())
+                .with_pipeline_unwind_outputs(VecDeque::from([Ok(UnwindOutput {
+                    checkpoint: StageCheckpoint::new(5),
+                })]))
+                .build();
+
+            let blockchain = consensus_engine.blockchain.clone();
+
+            // the tree can't revert blocks in static files, so the pipeline has to unwind them
+            let blocks = random_block_range(&mut rng, 0..=10, B256::ZERO, 0..1);
+            let mut writer = blockchain
+                .static_file_provider()
+                .latest_writer(StaticFileSegment::Headers)
+                .unwrap();
+            for block in &blocks {
+                writer
+                    .append_header(block.header.header().clone(), U256::ZERO, block.hash())
+                    .unwrap();
+            }
+            writer.commit().unwrap();
+            drop(writer);
+
+            insert_blocks(
+                ProviderFactory::new(
+                    env.db.as_ref(),
+                    chain_spec.clone(),
+                    blockchain.static_file_provider(),
+                ),
+                blocks.iter(),
+            );
+            env.db
+                .update(|tx| {
+                    for stage_id in [StageId::Finish, TEST_STAGE_ID] {
+                        tx.put::<tables::StageCheckpoints>(
+                            stage_id.to_string(),
+                            StageCheckpoint::new(10),
+                        )?;
+                    }
+                    Ok::<_, reth_db::DatabaseError>(())
+                })
+                .unwrap()
+                .unwrap();
+            blockchain.update_block_hashes_and_clear_buffered().unwrap();
+            let mut canon_state_rx = blockchain.subscribe_to_canonical_state();
+
+            let mut engine_rx = spawn_consensus_engine(consensus_engine);
+
+            // unknown blocks are rejected
+            let unknown = BlockHashOrNumber::Hash(rng.gen());
+            assert_matches!(
+                env.send_set_head(unknown, false).await,
+                Err(BeaconSetHeadError::NotCanonical(target)) if target == unknown
+            );
+
+            let outcome = env.send_set_head(BlockHashOrNumber::Number(5), false).await.unwrap();
+            assert_eq!(outcome.previous_head, blocks[10].header.num_hash());
+            assert_eq!(outcome.new_head, blocks[5].header.num_hash());
+            assert_eq!(
+                outcome.unwound,
+                blocks[6..].iter().map(|block| block.header.num_hash()).collect::<Vec<_>>()
+            );
+            assert!(outcome.pipeline_unwound);
+
+            let checkpoint = env
+                .db
+                .view(|tx| tx.get::<tables::StageCheckpoints>(TEST_STAGE_ID.to_string()))
+                .unwrap()
+                .unwrap();
+            assert_eq!(checkpoint, Some(StageCheckpoint::new(5)));
+
+            // the blocks unwound by the pipeline are announced as well
+            assert_matches!(
+                canon_state_rx.try_recv(),
+                Ok(CanonStateNotification::Reorg { old, new })
+                    if old.range() == (6..=10) && new.tip().hash() == blocks[5].hash()
+            );
+
+            assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
+        }
+    }
 }
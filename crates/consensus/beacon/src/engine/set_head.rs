@@ -0,0 +1,48 @@
+use crate::engine::error::BeaconSetHeadError;
+use reth_primitives::{BlockHashOrNumber, BlockNumHash};
+use reth_provider::Chain;
+use tokio::sync::oneshot;
+
+/// The outcome of a rollback of the canonical head, see
+/// [`BeaconConsensusEngineHandle::set_head`](crate::BeaconConsensusEngineHandle::set_head).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetHeadOutcome {
+    /// The canonical head before the rollback.
+    pub previous_head: BlockNumHash,
+    /// The canonical head after the rollback.
+    pub new_head: BlockNumHash,
+    /// The blocks that were removed from the canonical chain, in ascending order.
+    pub unwound: Vec<BlockNumHash>,
+    /// Whether the pipeline stages had to be unwound, because the new head is below the blocks
+    /// that were written to static files.
+    pub pipeline_unwound: bool,
+    /// Whether the safe block was clamped to the new head.
+    pub safe_clamped: bool,
+    /// Whether the finalized block was clamped to the new head.
+    pub finalized_clamped: bool,
+}
+
+impl SetHeadOutcome {
+    /// Returns `true` if no blocks were unwound, because the target already was the head.
+    pub fn is_noop(&self) -> bool {
+        self.unwound.is_empty()
+    }
+}
+
+/// Tracks a requested rollback of the canonical head.
+#[derive(Debug)]
+pub(crate) enum SetHeadState {
+    /// The rollback is waiting for the pipeline and any database write hook to finish.
+    Queued {
+        target: BlockHashOrNumber,
+        force: bool,
+        tx: oneshot::Sender<Result<SetHeadOutcome, BeaconSetHeadError>>,
+    },
+    /// The blocks committed by the tree were reverted, and the pipeline is unwinding the rest.
+    Unwinding {
+        outcome: SetHeadOutcome,
+        /// The blocks unwound by the pipeline, announced once it is done.
+        unwound: Chain,
+        tx: oneshot::Sender<Result<SetHeadOutcome, BeaconSetHeadError>>,
+    },
+}
@@ -1,7 +1,8 @@
 use crate::{
     engine::hooks::PruneHook, hooks::EngineHooks, BeaconConsensusEngine,
     BeaconConsensusEngineError, BeaconConsensusEngineHandle, BeaconForkChoiceUpdateError,
-    BeaconOnNewPayloadError, EthBeaconConsensus, MIN_BLOCKS_FOR_PIPELINE_RUN,
+    BeaconOnNewPayloadError, BeaconSetHeadError, EthBeaconConsensus, SetHeadOutcome,
+    MIN_BLOCKS_FOR_PIPELINE_RUN,
 };
 use reth_blockchain_tree::{
     config::BlockchainTreeConfig, externals::TreeExternals, BlockchainTree, ShareableBlockchainTree,
@@ -22,7 +23,7 @@ use reth_network_p2p::{
     test_utils::NoopFullBlockClient,
 };
 use reth_payload_builder::test_utils::spawn_test_payload_service;
-use reth_primitives::{BlockNumber, ChainSpec, B256};
+use reth_primitives::{BlockHashOrNumber, BlockNumber, ChainSpec, B256};
 use reth_provider::{
     providers::BlockchainProvider, test_utils::create_test_provider_factory_with_chain_spec,
     ExecutionOutcome,
@@ -32,7 +33,9 @@ use reth_prune_types::PruneModes;
 use reth_rpc_types::engine::{
     CancunPayloadFields, ExecutionPayload, ForkchoiceState, ForkchoiceUpdated, PayloadStatus,
 };
-use reth_stages::{sets::DefaultStages, test_utils::TestStages, ExecOutput, Pipeline, StageError};
+use reth_stages::{
+    sets::DefaultStages, test_utils::TestStages, ExecOutput, Pipeline, StageError, UnwindOutput,
+};
 use reth_static_file::StaticFileProducer;
 use reth_tasks::TokioTaskExecutor;
 use std::{collections::VecDeque, sync::Arc};
@@ -50,10 +53,11 @@ type TestBeaconConsensusEngine<Client> = BeaconConsensusEngine<
 #[derive(Debug)]
 pub struct TestEnv<DB> {
     pub db: DB,
-    // Keep the tip receiver around, so it's not dropped.
-    #[allow(dead_code)]
-    tip_rx: watch::Receiver<B256>,
+    /// The receiver of the tips the pipeline syncs to.
+    pub tip_rx: watch::Receiver<B256>,
     engine_handle: BeaconConsensusEngineHandle<EthEngineTypes>,
+    /// The mocked executor, if the engine doesn't use the real one.
+    pub executor: Option<MockExecutorProvider>,
 }
 
 impl<DB> TestEnv<DB> {
@@ -61,8 +65,9 @@ impl<DB> TestEnv<DB> {
         db: DB,
         tip_rx: watch::Receiver<B256>,
         engine_handle: BeaconConsensusEngineHandle<EthEngineTypes>,
+        executor: Option<MockExecutorProvider>,
     ) -> Self {
-        Self { db, tip_rx, engine_handle }
+        Self { db, tip_rx, engine_handle, executor }
     }
 
     pub async fn send_new_payload<T: Into<ExecutionPayload>>(
@@ -109,6 +114,14 @@ impl<DB> TestEnv<DB> {
             }
         }
     }
+
+    pub async fn send_set_head(
+        &self,
+        target: BlockHashOrNumber,
+        force: bool,
+    ) -> Result<SetHeadOutcome, BeaconSetHeadError> {
+        self.engine_handle.set_head(target, force).await
+    }
 }
 
 // TODO: add with_consensus in case we want to use the TestConsensus purposeful failure - this
@@ -159,6 +172,7 @@ impl Default for TestExecutorConfig {
 pub struct TestConsensusEngineBuilder {
     chain_spec: Arc<ChainSpec>,
     pipeline_config: TestPipelineConfig,
+    pipeline_unwind_outputs: VecDeque<Result<UnwindOutput, StageError>>,
     executor_config: TestExecutorConfig,
     pipeline_run_threshold: Option<u64>,
     max_block: Option<BlockNumber>,
@@ -171,6 +185,7 @@ impl TestConsensusEngineBuilder {
         Self {
             chain_spec,
             pipeline_config: Default::default(),
+            pipeline_unwind_outputs: Default::default(),
             executor_config: Default::default(),
             pipeline_run_threshold: None,
             max_block: None,
@@ -187,6 +202,15 @@ impl TestConsensusEngineBuilder {
         self
     }
 
+    /// Set the pipeline unwind outputs to use for the test consensus engine.
+    pub fn with_pipeline_unwind_outputs(
+        mut self,
+        pipeline_unwind_outputs: VecDeque<Result<UnwindOutput, StageError>>,
+    ) -> Self {
+        self.pipeline_unwind_outputs = pipeline_unwind_outputs;
+        self
+    }
+
     /// Set the executor results to use for the test consensus engine.
     pub fn with_executor_results(mut self, executor_results: Vec<ExecutionOutcome>) -> Self {
         self.executor_config = TestExecutorConfig::Test(executor_results);
@@ -340,10 +364,12 @@ where
         );
 
         // use either test executor or real executor
+        let mut mock_executor = None;
         let executor_factory = match self.base_config.executor_config {
             TestExecutorConfig::Test(results) => {
                 let executor_factory = MockExecutorProvider::default();
                 executor_factory.extend(results);
+                mock_executor = Some(executor_factory.clone());
                 Either::Left(executor_factory)
             }
             TestExecutorConfig::Real => {
@@ -358,7 +384,7 @@ where
         let (tip_tx, tip_rx) = watch::channel(B256::default());
         let mut pipeline = match self.base_config.pipeline_config {
             TestPipelineConfig::Test(outputs) => Pipeline::builder()
-                .add_stages(TestStages::new(outputs, Default::default()))
+                .add_stages(TestStages::new(outputs, self.base_config.pipeline_unwind_outputs))
                 .with_tip_sender(tip_tx),
             TestPipelineConfig::Real => {
                 let header_downloader = ReverseHeadersDownloaderBuilder::default()
@@ -429,7 +455,7 @@ where
             engine.sync.set_max_block(max_block)
         }
 
-        (engine, TestEnv::new(provider_factory.db_ref().clone(), tip_rx, handle))
+        (engine, TestEnv::new(provider_factory.db_ref().clone(), tip_rx, handle, mock_executor))
     }
 }
 
@@ -1,12 +1,13 @@
 //! Collection of methods for block validation.
 
-use reth_consensus::ConsensusError;
+use reth_consensus::{BlockLimits, ConsensusError};
 use reth_primitives::{
     constants::{
         eip4844::{DATA_GAS_PER_BLOB, MAX_DATA_GAS_PER_BLOCK},
         MAXIMUM_EXTRA_DATA_SIZE,
     },
-    ChainSpec, GotExpected, Hardfork, Header, SealedBlock, SealedHeader,
+    ChainSpec, GotExpected, Hardfork, Header, SealedBlock, SealedHeader, TransactionSigned,
+    Withdrawal,
 };
 
 /// Validate header standalone
@@ -62,6 +63,19 @@ pub fn validate_header_standalone(
     Ok(())
 }
 
+/// Validates that the block is within the structural [`BlockLimits`]: the header gas limit, the
+/// number of transactions, the size of each transaction and the size of the block.
+///
+/// This is cheap compared to the other checks, so it should be done first to reject absurd blocks
+/// before anything expensive is computed.
+pub fn validate_block_limits(
+    block: &SealedBlock,
+    limits: &BlockLimits,
+) -> Result<(), ConsensusError> {
+    limits.ensure_gas_limit(block.gas_limit)?;
+    limits.ensure_transactions(block.body.iter().map(TransactionSigned::length_without_header))
+}
+
 /// Validate a block without regard for state:
 ///
 /// - Compares the ommer hash in the block header to the block body
@@ -129,6 +143,28 @@ pub fn validate_block_pre_execution(
     Ok(())
 }
 
+/// Validates that the indices of the block's withdrawals continue the global withdrawal sequence.
+///
+/// Every withdrawal index must be the previous one + 1, starting at `last_withdrawal_index` + 1.
+/// If there is no previous withdrawal, e.g. for the first blocks after the Shanghai activation, the
+/// first index of the block establishes the sequence.
+pub fn validate_withdrawal_index_continuity(
+    withdrawals: &[Withdrawal],
+    last_withdrawal_index: Option<u64>,
+) -> Result<(), ConsensusError> {
+    let mut expected = last_withdrawal_index.map(|index| index.saturating_add(1));
+    for withdrawal in withdrawals {
+        if let Some(expected) = expected.filter(|expected| *expected != withdrawal.index) {
+            return Err(ConsensusError::WithdrawalIndexDiscontinuity(GotExpected {
+                got: withdrawal.index,
+                expected,
+            }))
+        }
+        expected = Some(withdrawal.index.saturating_add(1));
+    }
+    Ok(())
+}
+
 /// Validates that the EIP-4844 header fields exist and conform to the spec. This ensures that:
 ///
 ///  * `blob_gas_used` exists as a header field
@@ -204,6 +240,11 @@ mod tests {
         impl WithdrawalsProvider for WithdrawalsProvider {
             fn latest_withdrawal(&self) -> ProviderResult<Option<Withdrawal>> ;
 
+            fn latest_withdrawal_until(
+                &self,
+                _block: BlockNumber,
+            ) -> ProviderResult<Option<Withdrawal>> ;
+
             fn withdrawals_by_block(
                 &self,
                 _id: BlockHashOrNumber,
@@ -293,6 +334,13 @@ mod tests {
         fn latest_withdrawal(&self) -> ProviderResult<Option<Withdrawal>> {
             self.withdrawals_provider.latest_withdrawal()
         }
+
+        fn latest_withdrawal_until(
+            &self,
+            block: BlockNumber,
+        ) -> ProviderResult<Option<Withdrawal>> {
+            self.withdrawals_provider.latest_withdrawal_until(block)
+        }
     }
 
     fn mock_blob_tx(nonce: u64, num_blobs: usize) -> TransactionSigned {
@@ -410,6 +458,50 @@ mod tests {
             .return_const(Ok(Some(Withdrawal { index: 2, ..Default::default() })));
     }
 
+    #[test]
+    fn withdrawal_index_continuity() {
+        let withdrawals = |indexes: &[u64]| {
+            indexes
+                .iter()
+                .map(|idx| Withdrawal { index: *idx, ..Default::default() })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(validate_withdrawal_index_continuity(&withdrawals(&[3, 4, 5]), Some(2)), Ok(()));
+        // blocks without withdrawals don't advance the sequence
+        assert_eq!(validate_withdrawal_index_continuity(&[], Some(2)), Ok(()));
+
+        // skipped index
+        assert_eq!(
+            validate_withdrawal_index_continuity(&withdrawals(&[4, 5]), Some(2)),
+            Err(ConsensusError::WithdrawalIndexDiscontinuity(GotExpected { got: 4, expected: 3 }))
+        );
+        assert_eq!(
+            validate_withdrawal_index_continuity(&withdrawals(&[3, 5]), Some(2)),
+            Err(ConsensusError::WithdrawalIndexDiscontinuity(GotExpected { got: 5, expected: 4 }))
+        );
+
+        // repeated index
+        assert_eq!(
+            validate_withdrawal_index_continuity(&withdrawals(&[2, 3]), Some(2)),
+            Err(ConsensusError::WithdrawalIndexDiscontinuity(GotExpected { got: 2, expected: 3 }))
+        );
+        assert_eq!(
+            validate_withdrawal_index_continuity(&withdrawals(&[3, 3]), Some(2)),
+            Err(ConsensusError::WithdrawalIndexDiscontinuity(GotExpected { got: 3, expected: 4 }))
+        );
+
+        // the first withdrawals after the activation establish the sequence
+        assert_eq!(validate_withdrawal_index_continuity(&withdrawals(&[100, 101]), None), Ok(()));
+        assert_eq!(
+            validate_withdrawal_index_continuity(&withdrawals(&[100, 102]), None),
+            Err(ConsensusError::WithdrawalIndexDiscontinuity(GotExpected {
+                got: 102,
+                expected: 101
+            }))
+        );
+    }
+
     #[test]
     fn shanghai_block_zero_withdrawals() {
         // ensures that if shanghai is activated, and we include a block with a withdrawals root,
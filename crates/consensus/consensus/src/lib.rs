@@ -18,6 +18,21 @@ use std::fmt::Debug;
 /// A consensus implementation that does nothing.
 pub mod noop;
 
+mod limits;
+pub use limits::{
+    BlockLimits, BLOCK_LIMITS_GENESIS_KEY, DEFAULT_MAX_BLOCK_SIZE, DEFAULT_MAX_GAS_LIMIT,
+    DEFAULT_MAX_TRANSACTIONS, DEFAULT_MAX_TRANSACTION_SIZE,
+};
+
+mod policy;
+pub use policy::{CheckSeverity, RelaxableCheck, ValidationPolicy, VALIDATION_POLICY_GENESIS_KEY};
+
+mod timestamp;
+pub use timestamp::{
+    ensure_timestamp_not_in_future, FutureTimestampCheck, CLOCK_SKEW_WARNING_THRESHOLD,
+    CONSENSUS_LAYER_FUTURE_TIMESTAMP_TOLERANCE, DEFAULT_FUTURE_TIMESTAMP_TOLERANCE,
+};
+
 #[cfg(any(test, feature = "test-utils"))]
 /// test helpers for mocking consensus
 pub mod test_utils;
@@ -29,12 +44,23 @@ pub struct PostExecutionInput<'a> {
     pub receipts: &'a [Receipt],
     /// EIP-7685 requests of the block.
     pub requests: &'a [Request],
+    /// State roots after each transaction of the block, if they were computed.
+    ///
+    /// Receipts of blocks before Byzantium commit to these instead of the transaction status, so
+    /// their receipts root can only be verified with them.
+    pub intermediate_state_roots: Option<&'a [B256]>,
 }
 
 impl<'a> PostExecutionInput<'a> {
     /// Creates a new instance of `PostExecutionInput`.
     pub const fn new(receipts: &'a [Receipt], requests: &'a [Request]) -> Self {
-        Self { receipts, requests }
+        Self { receipts, requests, intermediate_state_roots: None }
+    }
+
+    /// Sets the state roots after each transaction of the block.
+    pub const fn with_intermediate_state_roots(mut self, state_roots: &'a [B256]) -> Self {
+        self.intermediate_state_roots = Some(state_roots);
+        self
     }
 }
 
@@ -197,13 +223,18 @@ pub enum ConsensusError {
         block_number: BlockNumber,
     },
 
-    /// Error when the block timestamp is in the future compared to our clock time.
-    #[error("block timestamp {timestamp} is in the future compared to our clock time {present_timestamp}")]
+    /// Error when the block timestamp is too far in the future compared to our clock time.
+    #[error(
+        "block timestamp {timestamp} is more than {tolerance}s in the future compared to our \
+         clock time {present_timestamp}"
+    )]
     TimestampIsInFuture {
         /// The block's timestamp.
         timestamp: u64,
         /// The current timestamp.
         present_timestamp: u64,
+        /// The number of seconds the block may be ahead of our clock.
+        tolerance: u64,
     },
 
     /// Error when the base fee is missing.
@@ -221,6 +252,44 @@ pub enum ConsensusError {
         len: usize,
     },
 
+    /// Error when the gas limit of the header exceeds the configured ceiling.
+    #[error("gas limit {gas_limit} exceeds the maximum of {max}")]
+    GasLimitExceedsMax {
+        /// The gas limit of the header.
+        gas_limit: u64,
+        /// The maximum gas limit.
+        max: u64,
+    },
+
+    /// Error when the block contains more transactions than allowed.
+    #[error("block contains {count} transactions, exceeding the maximum of {max}")]
+    TooManyTransactions {
+        /// The number of transactions in the block.
+        count: usize,
+        /// The maximum number of transactions.
+        max: usize,
+    },
+
+    /// Error when the encoded block exceeds the maximum size.
+    #[error("block size of {size} bytes exceeds the maximum of {max}")]
+    BlockTooLarge {
+        /// The size of the encoded block.
+        size: usize,
+        /// The maximum block size.
+        max: usize,
+    },
+
+    /// Error when an encoded transaction of the block exceeds the maximum size.
+    #[error("transaction {index} of {size} bytes exceeds the maximum of {max}")]
+    TransactionTooLarge {
+        /// The index of the transaction in the block.
+        index: usize,
+        /// The size of the encoded transaction.
+        size: usize,
+        /// The maximum transaction size.
+        max: usize,
+    },
+
     /// Error when the difficulty after a merge is not zero.
     #[error("difficulty after merge is not zero")]
     TheMergeDifficultyIsNotZero,
@@ -253,6 +322,10 @@ pub enum ConsensusError {
     #[error("missing withdrawals")]
     BodyWithdrawalsMissing,
 
+    /// Error when the withdrawal indices don't continue the sequence of the previous withdrawals.
+    #[error("withdrawal index is not continuous: {0}")]
+    WithdrawalIndexDiscontinuity(GotExpected<u64>),
+
     /// Error when requests are missing.
     #[error("missing requests")]
     BodyRequestsMissing,
@@ -0,0 +1,222 @@
+//! Structural upper bounds of a block that can be checked before anything is decoded or executed.
+
+use crate::ConsensusError;
+use reth_primitives::{Chain, ChainSpec};
+
+/// The key of the [`BlockLimits`] in the extra fields of the genesis config.
+pub const BLOCK_LIMITS_GENESIS_KEY: &str = "blockLimits";
+
+/// Default ceiling of the header gas limit, more than 300 times the mainnet gas limit.
+pub const DEFAULT_MAX_GAS_LIMIT: u64 = 10_000_000_000;
+
+/// Default maximum number of transactions in a block.
+///
+/// A block at the [`DEFAULT_MAX_GAS_LIMIT`] can't contain more transactions of 21000 gas.
+pub const DEFAULT_MAX_TRANSACTIONS: usize = 500_000;
+
+/// Default maximum total size of the encoded transactions of a block, 128 MiB.
+pub const DEFAULT_MAX_BLOCK_SIZE: usize = 128 * 1024 * 1024;
+
+/// Default maximum size of a single encoded transaction, 32 MiB.
+pub const DEFAULT_MAX_TRANSACTION_SIZE: usize = 32 * 1024 * 1024;
+
+/// Upper bounds of the size of a block.
+///
+/// These are not consensus rules, but sanity bounds that reject absurd blocks before they are
+/// decoded or executed. The defaults are far above anything a valid mainnet block can reach.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockLimits {
+    /// Maximum gas limit of the block header.
+    pub max_gas_limit: u64,
+    /// Maximum number of transactions in the block.
+    pub max_transactions: usize,
+    /// Maximum size of the encoded block.
+    ///
+    /// The transactions make up nearly all of a block, so the size of the block is measured as the
+    /// total size of its encoded transactions. This way it can be checked on a payload before
+    /// anything is decoded.
+    pub max_block_size: usize,
+    /// Maximum size of a single encoded transaction.
+    pub max_transaction_size: usize,
+}
+
+impl BlockLimits {
+    /// Reads the limits from the `blockLimits` object of the genesis config, e.g.
+    ///
+    /// ```json
+    /// "blockLimits": { "maxGasLimit": 100000000000, "maxTransactions": 1000000 }
+    /// ```
+    ///
+    /// Missing or invalid entries use the default. Mainnet always uses the defaults.
+    pub fn from_chain_spec(chain_spec: &ChainSpec) -> Self {
+        let mut limits = Self::default();
+        if chain_spec.chain == Chain::mainnet() {
+            return limits
+        }
+
+        let Some(config) = chain_spec
+            .genesis
+            .config
+            .extra_fields
+            .get(BLOCK_LIMITS_GENESIS_KEY)
+            .and_then(|value| value.as_object())
+        else {
+            return limits
+        };
+        let get = |key: &str| config.get(key).and_then(|value| value.as_u64());
+
+        if let Some(max) = get("maxGasLimit") {
+            limits.max_gas_limit = max;
+        }
+        if let Some(max) = get("maxTransactions") {
+            limits.max_transactions = max as usize;
+        }
+        if let Some(max) = get("maxBlockSize") {
+            limits.max_block_size = max as usize;
+        }
+        if let Some(max) = get("maxTransactionSize") {
+            limits.max_transaction_size = max as usize;
+        }
+
+        limits
+    }
+
+    /// Ensures the gas limit of the header does not exceed the ceiling.
+    pub const fn ensure_gas_limit(&self, gas_limit: u64) -> Result<(), ConsensusError> {
+        if gas_limit > self.max_gas_limit {
+            return Err(ConsensusError::GasLimitExceedsMax { gas_limit, max: self.max_gas_limit })
+        }
+        Ok(())
+    }
+
+    /// Ensures the block does not contain too many transactions.
+    pub const fn ensure_transaction_count(&self, count: usize) -> Result<(), ConsensusError> {
+        if count > self.max_transactions {
+            return Err(ConsensusError::TooManyTransactions { count, max: self.max_transactions })
+        }
+        Ok(())
+    }
+
+    /// Ensures the encoded block is not too large.
+    pub const fn ensure_block_size(&self, size: usize) -> Result<(), ConsensusError> {
+        if size > self.max_block_size {
+            return Err(ConsensusError::BlockTooLarge { size, max: self.max_block_size })
+        }
+        Ok(())
+    }
+
+    /// Ensures the encoded transaction at the given index of the block is not too large.
+    pub const fn ensure_transaction_size(
+        &self,
+        index: usize,
+        size: usize,
+    ) -> Result<(), ConsensusError> {
+        if size > self.max_transaction_size {
+            return Err(ConsensusError::TransactionTooLarge {
+                index,
+                size,
+                max: self.max_transaction_size,
+            })
+        }
+        Ok(())
+    }
+
+    /// Ensures the number of transactions, the size of each and their total size are within the
+    /// limits, given the sizes of the encoded transactions in block order.
+    ///
+    /// Stops at the first violation, so the remaining transactions are never looked at.
+    pub fn ensure_transactions<I>(&self, sizes: I) -> Result<(), ConsensusError>
+    where
+        I: IntoIterator<Item = usize>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let sizes = sizes.into_iter();
+        self.ensure_transaction_count(sizes.len())?;
+
+        let mut block_size = 0usize;
+        for (index, size) in sizes.enumerate() {
+            self.ensure_transaction_size(index, size)?;
+            block_size = block_size.saturating_add(size);
+            self.ensure_block_size(block_size)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for BlockLimits {
+    fn default() -> Self {
+        Self {
+            max_gas_limit: DEFAULT_MAX_GAS_LIMIT,
+            max_transactions: DEFAULT_MAX_TRANSACTIONS,
+            max_block_size: DEFAULT_MAX_BLOCK_SIZE,
+            max_transaction_size: DEFAULT_MAX_TRANSACTION_SIZE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{ChainSpecBuilder, Genesis, MAINNET};
+
+    fn chain_spec_with_limits(chain: Chain) -> ChainSpec {
+        let genesis: Genesis = serde_json::from_str(
+            r#"{
+                "config": {
+                    "chainId": 1337,
+                    "blockLimits": { "maxGasLimit": 100000000000, "maxTransactions": "many" }
+                },
+                "difficulty": "0x0",
+                "gasLimit": "0x1c9c380",
+                "alloc": {}
+            }"#,
+        )
+        .unwrap();
+        ChainSpecBuilder::default().chain(chain).genesis(genesis).build()
+    }
+
+    #[test]
+    fn limits_from_genesis() {
+        assert_eq!(BlockLimits::from_chain_spec(&MAINNET), BlockLimits::default());
+
+        let limits = BlockLimits::from_chain_spec(&chain_spec_with_limits(Chain::dev()));
+        assert_eq!(limits.max_gas_limit, 100_000_000_000);
+        // invalid entries use the default
+        assert_eq!(limits.max_transactions, DEFAULT_MAX_TRANSACTIONS);
+        assert_eq!(limits.max_block_size, DEFAULT_MAX_BLOCK_SIZE);
+
+        let limits = BlockLimits::from_chain_spec(&chain_spec_with_limits(Chain::mainnet()));
+        assert_eq!(limits, BlockLimits::default());
+    }
+
+    #[test]
+    fn limits_are_inclusive() {
+        let limits = BlockLimits::default();
+        assert_eq!(limits.ensure_gas_limit(DEFAULT_MAX_GAS_LIMIT), Ok(()));
+        assert_eq!(
+            limits.ensure_gas_limit(u64::MAX),
+            Err(ConsensusError::GasLimitExceedsMax {
+                gas_limit: u64::MAX,
+                max: DEFAULT_MAX_GAS_LIMIT
+            })
+        );
+        assert_eq!(limits.ensure_transaction_count(DEFAULT_MAX_TRANSACTIONS), Ok(()));
+        assert_eq!(limits.ensure_block_size(DEFAULT_MAX_BLOCK_SIZE), Ok(()));
+        assert_eq!(limits.ensure_transactions([DEFAULT_MAX_TRANSACTION_SIZE; 4]), Ok(()));
+        assert_eq!(
+            limits.ensure_transactions([DEFAULT_MAX_TRANSACTION_SIZE; 5]),
+            Err(ConsensusError::BlockTooLarge {
+                size: DEFAULT_MAX_TRANSACTION_SIZE * 5,
+                max: DEFAULT_MAX_BLOCK_SIZE
+            })
+        );
+        assert_eq!(
+            limits.ensure_transactions([1, 1, 1, DEFAULT_MAX_TRANSACTION_SIZE + 1]),
+            Err(ConsensusError::TransactionTooLarge {
+                index: 3,
+                size: DEFAULT_MAX_TRANSACTION_SIZE + 1,
+                max: DEFAULT_MAX_TRANSACTION_SIZE
+            })
+        );
+    }
+}
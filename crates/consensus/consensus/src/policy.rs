@@ -0,0 +1,219 @@
+//! Severity of consensus checks that some deployments legitimately relax.
+
+use crate::ConsensusError;
+use reth_primitives::{Chain, ChainSpec};
+use std::{fmt, str::FromStr};
+use tracing::warn;
+
+/// The key of the [`ValidationPolicy`] in the extra fields of the genesis config.
+pub const VALIDATION_POLICY_GENESIS_KEY: &str = "validationPolicy";
+
+/// How a failing check is treated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CheckSeverity {
+    /// Reject the block or header.
+    #[default]
+    Enforce,
+    /// Log a warning and continue as if the check passed.
+    Warn,
+    /// Don't perform the check at all.
+    Skip,
+}
+
+impl fmt::Display for CheckSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Enforce => f.write_str("enforce"),
+            Self::Warn => f.write_str("warn"),
+            Self::Skip => f.write_str("skip"),
+        }
+    }
+}
+
+impl FromStr for CheckSeverity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "enforce" => Ok(Self::Enforce),
+            "warn" => Ok(Self::Warn),
+            "skip" => Ok(Self::Skip),
+            _ => Err(format!("unknown check severity: {s}, expected enforce, warn or skip")),
+        }
+    }
+}
+
+/// Consensus checks whose severity can be configured with a [`ValidationPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RelaxableCheck {
+    /// Pre-merge headers must not be too far in the future compared to the local clock.
+    FutureTimestamp,
+    /// The header extra data must not exceed the maximum length.
+    ExtraData,
+}
+
+impl RelaxableCheck {
+    /// The name of the check in the genesis config.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::FutureTimestamp => "futureTimestamp",
+            Self::ExtraData => "extraData",
+        }
+    }
+}
+
+/// Maps each [`RelaxableCheck`] to the [`CheckSeverity`] it is validated with.
+///
+/// The default policy enforces every check.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ValidationPolicy {
+    future_timestamp: CheckSeverity,
+    extra_data: CheckSeverity,
+}
+
+impl ValidationPolicy {
+    /// Returns a policy that enforces every check.
+    pub const fn enforce_all() -> Self {
+        Self { future_timestamp: CheckSeverity::Enforce, extra_data: CheckSeverity::Enforce }
+    }
+
+    /// Reads the policy from the `validationPolicy` object of the genesis config, e.g.
+    ///
+    /// ```json
+    /// "validationPolicy": { "futureTimestamp": "warn", "extraData": "skip" }
+    /// ```
+    ///
+    /// Missing or invalid entries are enforced. Mainnet always enforces every check, it can only
+    /// be relaxed explicitly via [`ValidationPolicy::with_severity`].
+    pub fn from_chain_spec(chain_spec: &ChainSpec) -> Self {
+        let mut policy = Self::enforce_all();
+        if chain_spec.chain == Chain::mainnet() {
+            return policy
+        }
+
+        let Some(config) = chain_spec
+            .genesis
+            .config
+            .extra_fields
+            .get(VALIDATION_POLICY_GENESIS_KEY)
+            .and_then(|value| value.as_object())
+        else {
+            return policy
+        };
+
+        for check in [RelaxableCheck::FutureTimestamp, RelaxableCheck::ExtraData] {
+            if let Some(severity) = config
+                .get(check.name())
+                .and_then(|value| value.as_str())
+                .and_then(|value| value.parse().ok())
+            {
+                policy = policy.with_severity(check, severity);
+            }
+        }
+
+        policy
+    }
+
+    /// Returns the severity of the given check.
+    pub const fn severity(&self, check: RelaxableCheck) -> CheckSeverity {
+        match check {
+            RelaxableCheck::FutureTimestamp => self.future_timestamp,
+            RelaxableCheck::ExtraData => self.extra_data,
+        }
+    }
+
+    /// Sets the severity of the given check.
+    pub const fn with_severity(mut self, check: RelaxableCheck, severity: CheckSeverity) -> Self {
+        match check {
+            RelaxableCheck::FutureTimestamp => self.future_timestamp = severity,
+            RelaxableCheck::ExtraData => self.extra_data = severity,
+        }
+        self
+    }
+
+    /// Returns true if the given check should be performed at all.
+    pub const fn should_check(&self, check: RelaxableCheck) -> bool {
+        !matches!(self.severity(check), CheckSeverity::Skip)
+    }
+
+    /// Applies the severity of the check to its outcome.
+    ///
+    /// Errors of checks that are not enforced are logged and discarded.
+    pub fn apply(
+        &self,
+        check: RelaxableCheck,
+        outcome: Result<(), ConsensusError>,
+    ) -> Result<(), ConsensusError> {
+        match (self.severity(check), outcome) {
+            (CheckSeverity::Enforce, outcome) => outcome,
+            (CheckSeverity::Warn, Err(err)) => {
+                warn!(target: "consensus", check = check.name(), %err, "Ignoring failed consensus check");
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{ChainSpecBuilder, Genesis, MAINNET};
+
+    fn chain_spec_with_policy(chain: Chain) -> ChainSpec {
+        let genesis: Genesis = serde_json::from_str(
+            r#"{
+                "config": {
+                    "chainId": 1337,
+                    "validationPolicy": { "futureTimestamp": "warn", "extraData": "skip" }
+                },
+                "difficulty": "0x0",
+                "gasLimit": "0x1c9c380",
+                "alloc": {}
+            }"#,
+        )
+        .unwrap();
+        ChainSpecBuilder::default().chain(chain).genesis(genesis).build()
+    }
+
+    #[test]
+    fn default_policy_enforces_everything() {
+        let policy = ValidationPolicy::default();
+        assert_eq!(policy, ValidationPolicy::enforce_all());
+        assert_eq!(ValidationPolicy::from_chain_spec(&MAINNET), policy);
+
+        let err = ConsensusError::ExtraDataExceedsMax { len: 33 };
+        assert_eq!(policy.apply(RelaxableCheck::ExtraData, Err(err.clone())), Err(err));
+    }
+
+    #[test]
+    fn policy_from_genesis() {
+        let policy = ValidationPolicy::from_chain_spec(&chain_spec_with_policy(Chain::dev()));
+        assert_eq!(policy.severity(RelaxableCheck::FutureTimestamp), CheckSeverity::Warn);
+        assert_eq!(policy.severity(RelaxableCheck::ExtraData), CheckSeverity::Skip);
+        assert!(!policy.should_check(RelaxableCheck::ExtraData));
+
+        let err = ConsensusError::ExtraDataExceedsMax { len: 33 };
+        assert_eq!(policy.apply(RelaxableCheck::FutureTimestamp, Err(err.clone())), Ok(()));
+        assert_eq!(policy.apply(RelaxableCheck::ExtraData, Err(err)), Ok(()));
+    }
+
+    #[test]
+    fn mainnet_policy_ignores_genesis() {
+        let policy = ValidationPolicy::from_chain_spec(&chain_spec_with_policy(Chain::mainnet()));
+        assert_eq!(policy, ValidationPolicy::enforce_all());
+
+        // only the explicit override relaxes mainnet
+        let policy = policy.with_severity(RelaxableCheck::FutureTimestamp, CheckSeverity::Warn);
+        assert_eq!(policy.severity(RelaxableCheck::FutureTimestamp), CheckSeverity::Warn);
+        assert_eq!(policy.severity(RelaxableCheck::ExtraData), CheckSeverity::Enforce);
+    }
+
+    #[test]
+    fn parse_severity() {
+        for severity in [CheckSeverity::Enforce, CheckSeverity::Warn, CheckSeverity::Skip] {
+            assert_eq!(severity.to_string().parse::<CheckSeverity>().unwrap(), severity);
+        }
+        assert!("relaxed".parse::<CheckSeverity>().is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! Check that headers are not too far in the future compared to the local clock.
+
+use crate::ConsensusError;
+use reth_primitives::constants::ALLOWED_FUTURE_BLOCK_TIME_SECONDS;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use tracing::warn;
+
+/// Default number of seconds a header received from the network may be ahead of the local clock.
+pub const DEFAULT_FUTURE_TIMESTAMP_TOLERANCE: u64 = ALLOWED_FUTURE_BLOCK_TIME_SECONDS;
+
+/// Number of seconds a payload received from the consensus layer may be ahead of the local clock.
+///
+/// The consensus layer is authoritative for the timestamps of its payloads and usually runs on the
+/// same clock, so there's no tolerance for clock drift.
+pub const CONSENSUS_LAYER_FUTURE_TIMESTAMP_TOLERANCE: u64 = 0;
+
+/// Number of accepted headers close to the edge of the tolerance after which the local clock is
+/// considered to be behind.
+pub const CLOCK_SKEW_WARNING_THRESHOLD: usize = 32;
+
+/// Ensures that the `timestamp` is at most `tolerance` seconds ahead of the `present_timestamp`.
+pub const fn ensure_timestamp_not_in_future(
+    timestamp: u64,
+    present_timestamp: u64,
+    tolerance: u64,
+) -> Result<(), ConsensusError> {
+    if timestamp > present_timestamp.saturating_add(tolerance) {
+        return Err(ConsensusError::TimestampIsInFuture { timestamp, present_timestamp, tolerance })
+    }
+    Ok(())
+}
+
+/// The future timestamp check of headers received from the network.
+///
+/// Headers may be up to the configured tolerance ahead of the local clock. If many of the accepted
+/// headers are close to the edge of the tolerance, the local clock is likely behind and a warning
+/// is logged once.
+#[derive(Debug)]
+pub struct FutureTimestampCheck {
+    /// Number of seconds a header may be ahead of the local clock.
+    tolerance: u64,
+    /// Number of accepted headers in the upper half of the tolerance.
+    near_edge: AtomicUsize,
+    /// Whether the clock skew warning was already logged.
+    warned: AtomicBool,
+}
+
+impl FutureTimestampCheck {
+    /// Creates a new check with the given tolerance in seconds.
+    pub const fn new(tolerance: u64) -> Self {
+        Self { tolerance, near_edge: AtomicUsize::new(0), warned: AtomicBool::new(false) }
+    }
+
+    /// Returns the tolerance in seconds.
+    pub const fn tolerance(&self) -> u64 {
+        self.tolerance
+    }
+
+    /// Ensures that the `timestamp` is within the tolerance of the `present_timestamp`.
+    pub fn check(&self, timestamp: u64, present_timestamp: u64) -> Result<(), ConsensusError> {
+        ensure_timestamp_not_in_future(timestamp, present_timestamp, self.tolerance)?;
+
+        let ahead = timestamp.saturating_sub(present_timestamp);
+        if ahead > 0 && ahead * 2 > self.tolerance {
+            let near_edge = self.near_edge.fetch_add(1, Ordering::Relaxed) + 1;
+            if near_edge >= CLOCK_SKEW_WARNING_THRESHOLD &&
+                !self.warned.swap(true, Ordering::Relaxed)
+            {
+                warn!(
+                    target: "consensus",
+                    near_edge,
+                    tolerance = self.tolerance,
+                    "Local clock seems to be behind the network, check the NTP setup"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for FutureTimestampCheck {
+    fn default() -> Self {
+        Self::new(DEFAULT_FUTURE_TIMESTAMP_TOLERANCE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tolerance_applies() {
+        let now = 1_000;
+        let check = FutureTimestampCheck::default();
+        assert_eq!(check.check(now + 10, now), Ok(()));
+        assert_eq!(check.check(now + DEFAULT_FUTURE_TIMESTAMP_TOLERANCE, now), Ok(()));
+        assert_eq!(
+            check.check(now + 16, now),
+            Err(ConsensusError::TimestampIsInFuture {
+                timestamp: now + 16,
+                present_timestamp: now,
+                tolerance: DEFAULT_FUTURE_TIMESTAMP_TOLERANCE,
+            })
+        );
+
+        let strict = FutureTimestampCheck::new(0);
+        assert_eq!(strict.check(now, now), Ok(()));
+        assert_eq!(
+            strict.check(now + 10, now),
+            Err(ConsensusError::TimestampIsInFuture {
+                timestamp: now + 10,
+                present_timestamp: now,
+                tolerance: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn warns_once_about_clock_skew() {
+        let now = 1_000;
+        let check = FutureTimestampCheck::default();
+
+        // headers in the lower half of the tolerance are not counted
+        for _ in 0..CLOCK_SKEW_WARNING_THRESHOLD {
+            check.check(now + 5, now).unwrap();
+        }
+        assert!(!check.warned.load(Ordering::Relaxed));
+
+        for _ in 0..CLOCK_SKEW_WARNING_THRESHOLD {
+            check.check(now + 14, now).unwrap();
+        }
+        assert!(check.warned.load(Ordering::Relaxed));
+        assert_eq!(check.near_edge.load(Ordering::Relaxed), CLOCK_SKEW_WARNING_THRESHOLD);
+    }
+}
@@ -8,9 +8,13 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
-use reth_consensus::{Consensus, ConsensusError, PostExecutionInput};
+use reth_consensus::{
+    BlockLimits, Consensus, ConsensusError, FutureTimestampCheck, PostExecutionInput,
+    RelaxableCheck, ValidationPolicy,
+};
 use reth_consensus_common::validation::{
-    validate_block_pre_execution, validate_header_extradata, validate_header_standalone,
+    validate_block_limits, validate_block_pre_execution, validate_header_extradata,
+    validate_header_standalone,
 };
 use reth_primitives::{
     BlockWithSenders, Chain, ChainSpec, Hardfork, Header, SealedBlock, SealedHeader,
@@ -28,17 +32,64 @@ pub use validation::validate_block_post_execution;
 pub struct EthBeaconConsensus {
     /// Configuration
     chain_spec: Arc<ChainSpec>,
+    /// Severity of the checks that can be relaxed.
+    policy: ValidationPolicy,
+    /// Structural bounds that are checked before anything else.
+    limits: BlockLimits,
+    /// How far pre-merge headers may be ahead of the local clock.
+    future_timestamp: FutureTimestampCheck,
 }
 
 impl EthBeaconConsensus {
     /// Create a new instance of [`EthBeaconConsensus`]
+    ///
+    /// The [`ValidationPolicy`] and the [`BlockLimits`] are read from the chain spec, see
+    /// [`ValidationPolicy::from_chain_spec`] and [`BlockLimits::from_chain_spec`].
     pub fn new(chain_spec: Arc<ChainSpec>) -> Self {
-        Self { chain_spec }
+        let policy = ValidationPolicy::from_chain_spec(&chain_spec);
+        let limits = BlockLimits::from_chain_spec(&chain_spec);
+        Self { chain_spec, policy, limits, future_timestamp: FutureTimestampCheck::default() }
+    }
+
+    /// Sets the [`ValidationPolicy`] to use.
+    pub const fn with_validation_policy(mut self, policy: ValidationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the configured [`ValidationPolicy`].
+    pub const fn validation_policy(&self) -> &ValidationPolicy {
+        &self.policy
+    }
+
+    /// Sets the [`BlockLimits`] to use.
+    pub const fn with_block_limits(mut self, limits: BlockLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Returns the configured [`BlockLimits`].
+    pub const fn block_limits(&self) -> &BlockLimits {
+        &self.limits
+    }
+
+    /// Sets the number of seconds a pre-merge header may be ahead of the local clock.
+    ///
+    /// Defaults to [`reth_consensus::DEFAULT_FUTURE_TIMESTAMP_TOLERANCE`].
+    pub const fn with_future_timestamp_tolerance(mut self, tolerance: u64) -> Self {
+        self.future_timestamp = FutureTimestampCheck::new(tolerance);
+        self
+    }
+
+    /// Returns the number of seconds a pre-merge header may be ahead of the local clock.
+    pub const fn future_timestamp_tolerance(&self) -> u64 {
+        self.future_timestamp.tolerance()
     }
 }
 
 impl Consensus for EthBeaconConsensus {
     fn validate_header(&self, header: &SealedHeader) -> Result<(), ConsensusError> {
+        self.limits.ensure_gas_limit(header.gas_limit)?;
         validate_header_standalone(header, &self.chain_spec)?;
         Ok(())
     }
@@ -84,7 +135,9 @@ impl Consensus for EthBeaconConsensus {
             // is greater than its parent timestamp.
 
             // validate header extradata for all networks post merge
-            validate_header_extradata(header)?;
+            if self.policy.should_check(RelaxableCheck::ExtraData) {
+                self.policy.apply(RelaxableCheck::ExtraData, validate_header_extradata(header))?;
+            }
 
             // mixHash is used instead of difficulty inside EVM
             // https://eips.ethereum.org/EIPS/eip-4399#using-mixhash-field-instead-of-difficulty
@@ -93,22 +146,26 @@ impl Consensus for EthBeaconConsensus {
             //  * difficulty, mix_hash & nonce aka PoW stuff
             // low priority as syncing is done in reverse order
 
-            // Check if timestamp is in the future. Clock can drift but this can be consensus issue.
-            let present_timestamp =
-                SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+            // Check if timestamp is in the future. Clock can drift, so the header may be ahead of
+            // our clock by the configured tolerance.
+            if self.policy.should_check(RelaxableCheck::FutureTimestamp) {
+                let present_timestamp =
+                    SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
 
-            if header.exceeds_allowed_future_timestamp(present_timestamp) {
-                return Err(ConsensusError::TimestampIsInFuture {
-                    timestamp: header.timestamp,
-                    present_timestamp,
-                })
+                self.policy.apply(
+                    RelaxableCheck::FutureTimestamp,
+                    self.future_timestamp.check(header.timestamp, present_timestamp),
+                )?;
             }
 
             // Goerli and early OP exception:
             //  * If the network is goerli pre-merge, ignore the extradata check, since we do not
             //  support clique. Same goes for OP blocks below Bedrock.
-            if self.chain_spec.chain != Chain::goerli() && !self.chain_spec.is_optimism() {
-                validate_header_extradata(header)?;
+            if self.chain_spec.chain != Chain::goerli() &&
+                !self.chain_spec.is_optimism() &&
+                self.policy.should_check(RelaxableCheck::ExtraData)
+            {
+                self.policy.apply(RelaxableCheck::ExtraData, validate_header_extradata(header))?;
             }
         }
 
@@ -116,6 +173,7 @@ impl Consensus for EthBeaconConsensus {
     }
 
     fn validate_block_pre_execution(&self, block: &SealedBlock) -> Result<(), ConsensusError> {
+        validate_block_limits(block, &self.limits)?;
         validate_block_pre_execution(block, &self.chain_spec)
     }
 
@@ -124,6 +182,129 @@ impl Consensus for EthBeaconConsensus {
         block: &BlockWithSenders,
         input: PostExecutionInput<'_>,
     ) -> Result<(), ConsensusError> {
-        validate_block_post_execution(block, &self.chain_spec, input.receipts, input.requests)
+        validate_block_post_execution(
+            block,
+            &self.chain_spec,
+            input.receipts,
+            input.requests,
+            input.intermediate_state_roots,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use reth_consensus::CheckSeverity;
+    use reth_primitives::{
+        ChainSpecBuilder, Signature, Transaction, TransactionSigned, TxLegacy, MAINNET,
+    };
+
+    fn future_header() -> Header {
+        header_ahead_by(60 * 60)
+    }
+
+    fn header_ahead_by(seconds: u64) -> Header {
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+        Header { timestamp: now + seconds, difficulty: U256::from(1), ..Default::default() }
+    }
+
+    #[test]
+    fn future_timestamp_enforced_by_default() {
+        let consensus = EthBeaconConsensus::new(MAINNET.clone());
+        assert_eq!(consensus.validation_policy(), &ValidationPolicy::enforce_all());
+
+        let header = future_header();
+        assert!(matches!(
+            consensus.validate_header_with_total_difficulty(&header, U256::ZERO),
+            Err(ConsensusError::TimestampIsInFuture { .. })
+        ));
+    }
+
+    #[test]
+    fn future_timestamp_warn_policy() {
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().build());
+        let policy = ValidationPolicy::default()
+            .with_severity(RelaxableCheck::FutureTimestamp, CheckSeverity::Warn);
+        let consensus = EthBeaconConsensus::new(chain_spec).with_validation_policy(policy);
+
+        let header = future_header();
+        assert_eq!(consensus.validate_header_with_total_difficulty(&header, U256::ZERO), Ok(()));
+    }
+
+    #[test]
+    fn future_timestamp_tolerance() {
+        let header = header_ahead_by(10);
+
+        let consensus = EthBeaconConsensus::new(MAINNET.clone());
+        assert_eq!(consensus.validate_header_with_total_difficulty(&header, U256::ZERO), Ok(()));
+
+        let consensus = EthBeaconConsensus::new(MAINNET.clone()).with_future_timestamp_tolerance(0);
+        assert_matches!(
+            consensus.validate_header_with_total_difficulty(&header, U256::ZERO),
+            Err(ConsensusError::TimestampIsInFuture { timestamp, tolerance: 0, .. }) => {
+                assert_eq!(timestamp, header.timestamp);
+            }
+        );
+    }
+
+    #[test]
+    fn block_limits() {
+        let limits = BlockLimits {
+            max_gas_limit: 100_000_000,
+            max_transactions: 2,
+            max_block_size: 300,
+            max_transaction_size: 200,
+        };
+        let consensus = EthBeaconConsensus::new(MAINNET.clone()).with_block_limits(limits);
+        assert_eq!(
+            EthBeaconConsensus::new(MAINNET.clone()).block_limits(),
+            &BlockLimits::default()
+        );
+
+        let tx = |input_len: usize| {
+            let tx = Transaction::Legacy(TxLegacy {
+                input: vec![1; input_len].into(),
+                ..Default::default()
+            });
+            TransactionSigned::from_transaction_and_signature(tx, Signature::default())
+        };
+        let block = |gas_limit: u64, body: Vec<TransactionSigned>| {
+            // the roots are wrong, the limits are checked before them
+            SealedBlock {
+                header: Header { gas_limit, ..Default::default() }.seal_slow(),
+                body,
+                ..Default::default()
+            }
+        };
+
+        let header = block(u64::MAX, vec![]).header;
+        assert_eq!(
+            consensus.validate_header(&header),
+            Err(ConsensusError::GasLimitExceedsMax { gas_limit: u64::MAX, max: 100_000_000 })
+        );
+        assert_eq!(
+            consensus.validate_block_pre_execution(&block(u64::MAX, vec![])),
+            Err(ConsensusError::GasLimitExceedsMax { gas_limit: u64::MAX, max: 100_000_000 })
+        );
+        assert_eq!(
+            consensus.validate_block_pre_execution(&block(30_000_000, vec![tx(0); 3])),
+            Err(ConsensusError::TooManyTransactions { count: 3, max: 2 })
+        );
+        assert_matches!(
+            consensus.validate_block_pre_execution(&block(30_000_000, vec![tx(0), tx(250)])),
+            Err(ConsensusError::TransactionTooLarge { index: 1, max: 200, .. })
+        );
+        assert_matches!(
+            consensus.validate_block_pre_execution(&block(30_000_000, vec![tx(150), tx(150)])),
+            Err(ConsensusError::BlockTooLarge { max: 300, .. })
+        );
+
+        // within the limits, the remaining checks are performed
+        assert_matches!(
+            consensus.validate_block_pre_execution(&block(30_000_000, vec![tx(10), tx(10)])),
+            Err(ConsensusError::BodyTransactionRootDiff(_))
+        );
     }
 }
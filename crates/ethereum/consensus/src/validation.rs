@@ -1,30 +1,44 @@
 use reth_consensus::ConsensusError;
 use reth_primitives::{
     gas_spent_by_transactions, BlockWithSenders, Bloom, ChainSpec, GotExpected, Receipt, Request,
-    B256,
+    Withdrawal, B256,
 };
 
 /// Validate a block with regard to execution results:
 ///
 /// - Compares the receipts root in the block header to the block body
 /// - Compares the gas used in the block header to the actual gas usage after execution
+/// - Compares the withdrawals root in the block header to the withdrawals of the block, after
+///   Shanghai
+///
+/// The receipts root of blocks before Byzantium is only compared if the state roots after each
+/// transaction are provided, otherwise only the logs bloom is compared.
 pub fn validate_block_post_execution(
     block: &BlockWithSenders,
     chain_spec: &ChainSpec,
     receipts: &[Receipt],
     requests: &[Request],
+    intermediate_state_roots: Option<&[B256]>,
 ) -> Result<(), ConsensusError> {
     // Before Byzantium, receipts contained state root that would mean that expensive
     // operation as hashing that is required for state root got calculated in every
     // transaction This was replaced with is_success flag.
     // See more about EIP here: https://eips.ethereum.org/EIPS/eip-658
-    if chain_spec.is_byzantium_active_at_block(block.header.number) {
-        if let Err(error) =
-            verify_receipts(block.header.receipts_root, block.header.logs_bloom, receipts)
-        {
-            tracing::debug!(%error, ?receipts, "receipts verification failed");
-            return Err(error)
-        }
+    let verified = if chain_spec.is_byzantium_active_at_block(block.header.number) {
+        verify_receipts(block.header.receipts_root, block.header.logs_bloom, receipts, None)
+    } else if let Some(state_roots) = intermediate_state_roots {
+        verify_receipts(
+            block.header.receipts_root,
+            block.header.logs_bloom,
+            receipts,
+            Some(state_roots),
+        )
+    } else {
+        verify_logs_bloom(block.header.logs_bloom, receipts)
+    };
+    if let Err(error) = verified {
+        tracing::debug!(%error, ?receipts, "receipts verification failed");
+        return Err(error)
     }
 
     // Check if gas used matches the value set in header.
@@ -37,6 +51,15 @@ pub fn validate_block_post_execution(
         })
     }
 
+    // Validate that the header withdrawals root matches the withdrawals of the block
+    if chain_spec.is_shanghai_active_at_timestamp(block.timestamp) {
+        if let Some(header_withdrawals_root) = block.header.withdrawals_root {
+            let withdrawals =
+                block.withdrawals.as_ref().ok_or(ConsensusError::BodyWithdrawalsMissing)?;
+            verify_withdrawals_root(header_withdrawals_root, withdrawals)?;
+        }
+    }
+
     // Validate that the header requests root matches the calculated requests root
     if chain_spec.is_prague_active_at_timestamp(block.timestamp) {
         let Some(header_requests_root) = block.header.requests_root else {
@@ -55,13 +78,22 @@ pub fn validate_block_post_execution(
 
 /// Calculate the receipts root, and compare it against against the expected receipts root and logs
 /// bloom.
+///
+/// If the state roots after each transaction are given, the receipts are encoded with these
+/// instead of the transaction status, as before Byzantium.
 fn verify_receipts(
     expected_receipts_root: B256,
     expected_logs_bloom: Bloom,
     receipts: &[Receipt],
+    state_roots: Option<&[B256]>,
 ) -> Result<(), ConsensusError> {
     // Calculate receipts root.
-    let receipts_with_bloom = receipts.iter().map(Receipt::with_bloom_ref).collect::<Vec<_>>();
+    let mut receipts_with_bloom = receipts.iter().map(Receipt::with_bloom_ref).collect::<Vec<_>>();
+    if let Some(state_roots) = state_roots {
+        for (receipt, state_root) in receipts_with_bloom.iter_mut().zip(state_roots) {
+            receipt.post_state = Some(*state_root);
+        }
+    }
     let receipts_root = reth_primitives::proofs::calculate_receipt_root_ref(&receipts_with_bloom);
 
     // Calculate header logs bloom.
@@ -77,6 +109,36 @@ fn verify_receipts(
     Ok(())
 }
 
+/// Calculate the withdrawals root, and compare it against the expected withdrawals root.
+fn verify_withdrawals_root(
+    expected_withdrawals_root: B256,
+    withdrawals: &[Withdrawal],
+) -> Result<(), ConsensusError> {
+    let withdrawals_root = reth_primitives::proofs::calculate_withdrawals_root(withdrawals);
+    if withdrawals_root != expected_withdrawals_root {
+        return Err(ConsensusError::BodyWithdrawalsRootDiff(
+            GotExpected { got: withdrawals_root, expected: expected_withdrawals_root }.into(),
+        ))
+    }
+
+    Ok(())
+}
+
+/// Calculate the logs bloom of the receipts, and compare it against the expected logs bloom.
+fn verify_logs_bloom(
+    expected_logs_bloom: Bloom,
+    receipts: &[Receipt],
+) -> Result<(), ConsensusError> {
+    let logs_bloom = receipts.iter().fold(Bloom::ZERO, |bloom, r| bloom | r.bloom_slow());
+    if logs_bloom != expected_logs_bloom {
+        return Err(ConsensusError::BodyBloomLogDiff(
+            GotExpected { got: logs_bloom, expected: expected_logs_bloom }.into(),
+        ))
+    }
+
+    Ok(())
+}
+
 /// Compare the calculated receipts root with the expected receipts root, also compare
 /// the calculated logs bloom with the expected logs bloom.
 fn compare_receipts_root_and_logs_bloom(
@@ -99,3 +161,46 @@ fn compare_receipts_root_and_logs_bloom(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use reth_primitives::{
+        proofs::calculate_withdrawals_root, Address, Block, ChainSpecBuilder, Header, Withdrawals,
+    };
+
+    #[test]
+    fn withdrawals_root() {
+        let chain_spec = ChainSpecBuilder::mainnet().shanghai_activated().build();
+        let withdrawals = (0..2)
+            .map(|index| Withdrawal {
+                index,
+                validator_index: index,
+                address: Address::with_last_byte(index as u8),
+                amount: 1,
+            })
+            .collect::<Vec<_>>();
+        let block = |withdrawals_root| {
+            let header = Header { number: 1, timestamp: 1, withdrawals_root, ..Default::default() };
+            let block = Block {
+                header,
+                withdrawals: Some(Withdrawals::new(withdrawals.clone())),
+                ..Default::default()
+            };
+            BlockWithSenders::new(block, vec![]).unwrap()
+        };
+
+        let matching = block(Some(calculate_withdrawals_root(&withdrawals)));
+        assert_matches!(
+            validate_block_post_execution(&matching, &chain_spec, &[], &[], None),
+            Ok(())
+        );
+
+        let mismatching = block(Some(calculate_withdrawals_root(&withdrawals[..1])));
+        assert_matches!(
+            validate_block_post_execution(&mismatching, &chain_spec, &[], &[], None),
+            Err(ConsensusError::BodyWithdrawalsRootDiff(_))
+        );
+    }
+}
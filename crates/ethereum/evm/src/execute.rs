@@ -8,19 +8,20 @@ use reth_ethereum_consensus::validate_block_post_execution;
 use reth_evm::{
     execute::{
         BatchExecutor, BlockExecutionError, BlockExecutionInput, BlockExecutionOutput,
-        BlockExecutorProvider, BlockValidationError, Executor, ProviderError,
+        BlockExecutorProvider, BlockValidationError, ExecutionCancellation, Executor,
+        IntermediateStateRoots, ProviderError,
     },
     ConfigureEvm,
 };
-use reth_execution_types::ExecutionOutcome;
+use reth_execution_types::{ExecutionOutcome, TransactionOutcome};
 use reth_primitives::{
-    BlockNumber, BlockWithSenders, ChainSpec, Hardfork, Header, Receipt, Request, Withdrawals,
-    MAINNET, U256,
+    Address, BlockNumber, BlockWithSenders, ChainSpec, Hardfork, Header, Receipt, Request,
+    TransactionSigned, TxHash, Withdrawals, B256, MAINNET, U256,
 };
 use reth_prune_types::PruneModes;
 use reth_revm::{
     batch::{BlockBatchRecord, BlockExecutorStats},
-    db::states::bundle_state::BundleRetention,
+    db::{states::bundle_state::BundleRetention, BundleState},
     state_change::{
         apply_beacon_root_contract_call, apply_blockhashes_update,
         apply_withdrawal_requests_contract_call, post_block_balance_increments,
@@ -29,15 +30,19 @@ use reth_revm::{
 };
 use revm_primitives::{
     db::{Database, DatabaseCommit},
-    BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ResultAndState,
+    BlockEnv, CfgEnvWithHandlerCfg, EVMError, EnvWithHandlerCfg, InvalidTransaction,
+    ResultAndState,
 };
 use std::sync::Arc;
+use tracing::{debug, trace};
 
 /// Provides executors to execute regular ethereum blocks
 #[derive(Debug, Clone)]
 pub struct EthExecutorProvider<EvmConfig = EthEvmConfig> {
     chain_spec: Arc<ChainSpec>,
     evm_config: EvmConfig,
+    /// Computes the state roots that receipts of blocks before Byzantium commit to, if set.
+    intermediate_state_roots: Option<IntermediateStateRoots>,
 }
 
 impl EthExecutorProvider {
@@ -55,7 +60,16 @@ impl EthExecutorProvider {
 impl<EvmConfig> EthExecutorProvider<EvmConfig> {
     /// Creates a new executor provider.
     pub fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig) -> Self {
-        Self { chain_spec, evm_config }
+        Self { chain_spec, evm_config, intermediate_state_roots: None }
+    }
+
+    /// Verifies the receipts root of blocks before Byzantium, by computing the state root after
+    /// each of their transactions.
+    ///
+    /// Without this, only the logs bloom and the gas used of these blocks are verified.
+    pub fn with_intermediate_state_roots(mut self, state_roots: IntermediateStateRoots) -> Self {
+        self.intermediate_state_roots = Some(state_roots);
+        self
     }
 }
 
@@ -67,11 +81,13 @@ where
     where
         DB: Database<Error = ProviderError>,
     {
-        EthBlockExecutor::new(
+        let mut executor = EthBlockExecutor::new(
             self.chain_spec.clone(),
             self.evm_config.clone(),
             State::builder().with_database(db).with_bundle_update().without_state_clear().build(),
-        )
+        );
+        executor.executor.intermediate_state_roots = self.intermediate_state_roots.clone();
+        executor
     }
 }
 
@@ -109,6 +125,32 @@ struct EthExecuteOutput {
     receipts: Vec<Receipt>,
     requests: Vec<Request>,
     gas_used: u64,
+    /// The state roots after each transaction, if they are computed for the block.
+    intermediate_state_roots: Option<Vec<B256>>,
+    /// The index and outcome of each transaction that reverted or halted, e.g. out of gas.
+    ///
+    /// Receipts only record that these transactions failed, not why.
+    unsuccessful: Vec<(usize, TransactionOutcome)>,
+}
+
+/// The gas every transaction uses at least, see `G_transaction` in the yellow paper.
+const MIN_TRANSACTION_GAS: u64 = 21_000;
+
+/// The output of [`EthBlockExecutor::execute_transactions_until`].
+#[derive(Debug, Clone, Default)]
+pub struct PartialExecutionOutput {
+    /// The executed transactions and their senders, in execution order.
+    pub transactions: Vec<(TransactionSigned, Address)>,
+    /// The receipts of the executed transactions.
+    pub receipts: Vec<Receipt>,
+    /// The gas used by the executed transactions.
+    pub gas_used: u64,
+    /// The transactions that were skipped because their gas limit exceeds the remaining gas.
+    pub skipped: Vec<TxHash>,
+    /// The transactions that are invalid in the current state, e.g. because of their nonce.
+    pub failed: Vec<(TxHash, InvalidTransaction)>,
+    /// The index and outcome of each executed transaction that reverted or halted.
+    pub unsuccessful: Vec<(usize, TransactionOutcome)>,
 }
 
 /// Helper container type for EVM with chain spec.
@@ -118,6 +160,8 @@ struct EthEvmExecutor<EvmConfig> {
     chain_spec: Arc<ChainSpec>,
     /// How to create an EVM.
     evm_config: EvmConfig,
+    /// Computes the state roots after each transaction of blocks before Byzantium, if set.
+    intermediate_state_roots: Option<IntermediateStateRoots>,
 }
 
 impl<EvmConfig> EthEvmExecutor<EvmConfig>
@@ -128,7 +172,8 @@ where
     /// block, the total gas used and the list of EIP-7685 [requests](Request).
     ///
     /// This applies the pre-execution and post-execution changes that require an [EVM](Evm), and
-    /// executes the transactions.
+    /// executes the transactions. The execution is aborted between transactions once the
+    /// cancellation is set.
     ///
     /// # Note
     ///
@@ -138,6 +183,7 @@ where
         &self,
         block: &BlockWithSenders,
         mut evm: Evm<'_, Ext, &mut State<DB>>,
+        cancellation: Option<&ExecutionCancellation>,
     ) -> Result<EthExecuteOutput, BlockExecutionError>
     where
         DB: Database<Error = ProviderError>,
@@ -158,10 +204,28 @@ where
             block.parent_hash,
         )?;
 
+        // receipts of blocks before Byzantium commit to the state root after each transaction
+        let mut intermediate_state_roots = self
+            .intermediate_state_roots
+            .as_ref()
+            .filter(|_| !self.chain_spec.is_byzantium_active_at_block(block.number))
+            .map(|state_roots| (state_roots, Vec::with_capacity(block.body.len())));
+
         // execute transactions
         let mut cumulative_gas_used = 0;
         let mut receipts = Vec::with_capacity(block.body.len());
+        let mut unsuccessful = Vec::new();
         for (sender, transaction) in block.transactions_with_sender() {
+            // abort between transactions if the execution was cancelled
+            if !receipts.is_empty() &&
+                cancellation.map_or(false, ExecutionCancellation::is_cancelled)
+            {
+                return Err(BlockExecutionError::Cancelled {
+                    executed: receipts.len(),
+                    total: block.body.len(),
+                })
+            }
+
             // The sum of the transaction’s gas limit, Tg, and the gas utilized in this block prior,
             // must be no greater than the block’s gasLimit.
             let block_available_gas = block.header.gas_limit - cumulative_gas_used;
@@ -185,6 +249,22 @@ where
             })?;
             evm.db_mut().commit(state);
 
+            if let Some((state_roots, roots)) = &mut intermediate_state_roots {
+                roots.push(intermediate_state_root(evm.db(), state_roots)?);
+            }
+
+            if !result.is_success() {
+                let outcome = TransactionOutcome::from(result.clone());
+                trace!(
+                    target: "evm",
+                    block = block.number,
+                    index = receipts.len(),
+                    ?outcome,
+                    "Transaction failed"
+                );
+                unsuccessful.push((receipts.len(), outcome));
+            }
+
             // append gas used
             cumulative_gas_used += result.gas_used();
 
@@ -217,8 +297,28 @@ where
             vec![]
         };
 
-        Ok(EthExecuteOutput { receipts, requests, gas_used: cumulative_gas_used })
+        Ok(EthExecuteOutput {
+            receipts,
+            requests,
+            gas_used: cumulative_gas_used,
+            intermediate_state_roots: intermediate_state_roots.map(|(_, roots)| roots),
+            unsuccessful,
+        })
+    }
+}
+
+/// Returns the state root after all changes that were committed to the state so far.
+fn intermediate_state_root<DB>(
+    state: &State<DB>,
+    state_roots: &IntermediateStateRoots,
+) -> Result<B256, ProviderError> {
+    // the changes of the current block are only merged into the bundle after the block, so they
+    // are applied to a copy of it
+    let mut changes = state.bundle_state.clone();
+    if let Some(transitions) = state.transition_state.clone() {
+        changes.apply_transitions_and_create_reverts(transitions, BundleRetention::PlainState);
     }
+    state_roots.state_root(&changes)
 }
 
 /// A basic Ethereum block executor.
@@ -237,7 +337,10 @@ pub struct EthBlockExecutor<EvmConfig, DB> {
 impl<EvmConfig, DB> EthBlockExecutor<EvmConfig, DB> {
     /// Creates a new Ethereum block executor.
     pub fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig, state: State<DB>) -> Self {
-        Self { executor: EthEvmExecutor { chain_spec, evm_config }, state }
+        Self {
+            executor: EthEvmExecutor { chain_spec, evm_config, intermediate_state_roots: None },
+            state,
+        }
     }
 
     #[inline]
@@ -286,7 +389,18 @@ where
         &mut self,
         block: &BlockWithSenders,
         total_difficulty: U256,
+        cancellation: Option<&ExecutionCancellation>,
     ) -> Result<EthExecuteOutput, BlockExecutionError> {
+        // EIP-4895: withdrawals are only processed from Shanghai on
+        if block.withdrawals.is_some() &&
+            !self.chain_spec().is_shanghai_active_at_timestamp(block.timestamp)
+        {
+            return Err(BlockValidationError::WithdrawalsBeforeShanghai {
+                hash: block.header.hash_slow(),
+            }
+            .into())
+        }
+
         // 1. prepare state on new block
         self.on_new_block(&block.header);
 
@@ -294,7 +408,7 @@ where
         let env = self.evm_env_for_block(&block.header, total_difficulty);
         let output = {
             let evm = self.executor.evm_config.evm_with_env(&mut self.state, env);
-            self.executor.execute_state_transitions(block, evm)
+            self.executor.execute_state_transitions(block, evm, cancellation)
         }?;
 
         // 3. apply post execution changes
@@ -303,6 +417,98 @@ where
         Ok(output)
     }
 
+    /// Executes the transactions one by one on top of the current state, until the cumulative gas
+    /// used reaches `gas_limit` or the block's gas limit, whichever is lower.
+    ///
+    /// This is meant for building payloads from pool transactions, so, unlike block execution,
+    /// transactions that don't fit into the remaining gas are skipped and transactions that are
+    /// invalid in the current state are reported as failed, instead of failing the execution.
+    /// Once the remaining gas can't fit any transaction, the execution stops without consuming
+    /// the remaining transactions from the iterator.
+    ///
+    /// The state changes of the executed transactions are committed to the state. Pre-execution
+    /// and post-execution changes are not applied, for the latter see [`Self::post_execution`].
+    pub fn execute_transactions_until(
+        &mut self,
+        header: &Header,
+        total_difficulty: U256,
+        transactions: impl Iterator<Item = (TransactionSigned, Address)>,
+        gas_limit: u64,
+    ) -> Result<PartialExecutionOutput, BlockExecutionError> {
+        self.on_new_block(header);
+
+        let env = self.evm_env_for_block(header, total_difficulty);
+        let mut evm = self.executor.evm_config.evm_with_env(&mut self.state, env);
+
+        let gas_limit = gas_limit.min(header.gas_limit);
+        let mut output = PartialExecutionOutput::default();
+        for (transaction, sender) in transactions {
+            let available_gas = gas_limit - output.gas_used;
+            if transaction.gas_limit() > available_gas {
+                trace!(
+                    target: "evm",
+                    hash = %transaction.hash(),
+                    transaction_gas_limit = transaction.gas_limit(),
+                    available_gas,
+                    "Skipping transaction that exceeds the available gas"
+                );
+                output.skipped.push(transaction.hash());
+                continue
+            }
+
+            EvmConfig::fill_tx_env(evm.tx_mut(), &transaction, sender);
+
+            let ResultAndState { result, state } = match evm.transact() {
+                Ok(result) => result,
+                Err(EVMError::Transaction(err)) => {
+                    trace!(target: "evm", hash = %transaction.hash(), %err, "Transaction failed");
+                    output.failed.push((transaction.hash(), err));
+                    continue
+                }
+                Err(err) => {
+                    return Err(BlockValidationError::EVM {
+                        hash: transaction.recalculate_hash(),
+                        error: err.into(),
+                    }
+                    .into())
+                }
+            };
+            evm.db_mut().commit(state);
+
+            if !result.is_success() {
+                let outcome = TransactionOutcome::from(result.clone());
+                output.unsuccessful.push((output.receipts.len(), outcome));
+            }
+
+            output.gas_used += result.gas_used();
+            output.receipts.push(
+                #[allow(clippy::needless_update)] // side-effect of optimism fields
+                Receipt {
+                    tx_type: transaction.tx_type(),
+                    success: result.is_success(),
+                    cumulative_gas_used: output.gas_used,
+                    logs: result.into_logs(),
+                    ..Default::default()
+                },
+            );
+            output.transactions.push((transaction, sender));
+
+            // no transaction fits into the remaining gas
+            if gas_limit - output.gas_used < MIN_TRANSACTION_GAS {
+                break
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Merges the state transitions committed so far, keeping their reverts, and returns the
+    /// resulting bundle.
+    pub fn take_bundle(&mut self) -> BundleState {
+        self.state.merge_transitions(BundleRetention::Reverts);
+        self.state.take_bundle()
+    }
+
     /// Apply settings before a new block is executed.
     pub(crate) fn on_new_block(&mut self, header: &Header) {
         // Set state clear flag if the block is after the Spurious Dragon hardfork.
@@ -367,14 +573,20 @@ where
     ///
     /// State changes are committed to the database.
     fn execute(mut self, input: Self::Input<'_>) -> Result<Self::Output, Self::Error> {
-        let BlockExecutionInput { block, total_difficulty } = input;
-        let EthExecuteOutput { receipts, requests, gas_used } =
-            self.execute_without_verification(block, total_difficulty)?;
+        let BlockExecutionInput { block, total_difficulty, cancellation } = input;
+        let EthExecuteOutput { receipts, requests, gas_used, unsuccessful, .. } =
+            self.execute_without_verification(block, total_difficulty, cancellation)?;
 
         // NOTE: we need to merge keep the reverts for the bundle retention
         self.state.merge_transitions(BundleRetention::Reverts);
 
-        Ok(BlockExecutionOutput { state: self.state.take_bundle(), receipts, requests, gas_used })
+        Ok(BlockExecutionOutput {
+            state: self.state.take_bundle(),
+            receipts,
+            requests,
+            gas_used,
+            unsuccessful,
+        })
     }
 }
 
@@ -410,11 +622,27 @@ where
     type Error = BlockExecutionError;
 
     fn execute_and_verify_one(&mut self, input: Self::Input<'_>) -> Result<(), Self::Error> {
-        let BlockExecutionInput { block, total_difficulty } = input;
-        let EthExecuteOutput { receipts, requests, gas_used: _ } =
-            self.executor.execute_without_verification(block, total_difficulty)?;
-
-        validate_block_post_execution(block, self.executor.chain_spec(), &receipts, &requests)?;
+        let BlockExecutionInput { block, total_difficulty, cancellation } = input;
+        let EthExecuteOutput { receipts, requests, intermediate_state_roots, unsuccessful, .. } =
+            self.executor.execute_without_verification(block, total_difficulty, cancellation)?;
+
+        if let Err(err) = validate_block_post_execution(
+            block,
+            self.executor.chain_spec(),
+            &receipts,
+            &requests,
+            intermediate_state_roots.as_deref(),
+        ) {
+            // the receipts only record whether transactions failed, not why
+            debug!(
+                target: "evm",
+                number = block.number,
+                %err,
+                ?unsuccessful,
+                "Block failed post execution validation"
+            );
+            return Err(err.into())
+        }
 
         // prepare the state according to the prune mode
         let retention = self.batch_record.bundle_retention(block.number);
@@ -461,19 +689,60 @@ mod tests {
         eip4788::{BEACON_ROOTS_ADDRESS, BEACON_ROOTS_CODE, SYSTEM_ADDRESS},
         eip7002::{WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS, WITHDRAWAL_REQUEST_PREDEPLOY_CODE},
     };
+    use alloy_rlp::Encodable;
+    use reth_consensus::ConsensusError;
     use reth_primitives::{
         constants::{EMPTY_ROOT_HASH, ETH_TO_WEI},
-        keccak256, public_key_to_address, Account, Block, ChainSpecBuilder, ForkCondition,
-        Transaction, TxKind, TxLegacy, B256,
+        keccak256,
+        proofs::{calculate_receipt_root_ref, triehash::KeccakHasher},
+        public_key_to_address, Account, Address, Block, Bloom, Bytecode, ChainSpecBuilder,
+        ForkCondition, StorageKey, Transaction, TxKind, TxLegacy, B256,
     };
     use reth_revm::{
-        database::StateProviderDatabase, state_change::HISTORY_SERVE_WINDOW,
-        test_utils::StateProviderTest, TransitionState,
+        bytecode_cache::BytecodeCache,
+        database::{EvmStateProvider, StateProviderDatabase},
+        state_change::HISTORY_SERVE_WINDOW,
+        test_utils::StateProviderTest,
+        TransitionState,
     };
     use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
-    use revm_primitives::{b256, fixed_bytes, Bytes};
+    use revm_primitives::{
+        b256, bytes, fixed_bytes, AccountInfo, Bytes, HaltReason, OutOfGasError, KECCAK_EMPTY,
+    };
     use secp256k1::{Keypair, Secp256k1};
-    use std::collections::HashMap;
+    use std::{
+        collections::HashMap,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    /// A [`StateProviderTest`] that counts the bytecode reads.
+    struct CountingStateProvider {
+        inner: StateProviderTest,
+        bytecode_reads: Arc<AtomicUsize>,
+    }
+
+    impl EvmStateProvider for CountingStateProvider {
+        fn basic_account(&self, address: Address) -> Result<Option<Account>, ProviderError> {
+            EvmStateProvider::basic_account(&self.inner, address)
+        }
+
+        fn block_hash(&self, number: BlockNumber) -> Result<Option<B256>, ProviderError> {
+            EvmStateProvider::block_hash(&self.inner, number)
+        }
+
+        fn bytecode_by_hash(&self, code_hash: B256) -> Result<Option<Bytecode>, ProviderError> {
+            self.bytecode_reads.fetch_add(1, Ordering::Relaxed);
+            EvmStateProvider::bytecode_by_hash(&self.inner, code_hash)
+        }
+
+        fn storage(
+            &self,
+            account: Address,
+            storage_key: StorageKey,
+        ) -> Result<Option<U256>, ProviderError> {
+            EvmStateProvider::storage(&self.inner, account, storage_key)
+        }
+    }
 
     fn create_state_provider_with_beacon_root_contract() -> StateProviderTest {
         let mut db = StateProviderTest::default();
@@ -514,7 +783,58 @@ mod tests {
     }
 
     fn executor_provider(chain_spec: Arc<ChainSpec>) -> EthExecutorProvider<EthEvmConfig> {
-        EthExecutorProvider { chain_spec, evm_config: Default::default() }
+        EthExecutorProvider::new(chain_spec, Default::default())
+    }
+
+    #[test]
+    fn bytecode_cache_shared_across_executors() {
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::from(&*MAINNET)
+                .shanghai_activated()
+                .with_fork(Hardfork::Cancun, ForkCondition::Timestamp(1))
+                .build(),
+        );
+        let provider = executor_provider(chain_spec);
+
+        let db = create_state_provider_with_beacon_root_contract();
+        let bytecode_reads = Arc::new(AtomicUsize::new(0));
+        let cache = BytecodeCache::default();
+
+        // each block calls the beacon root contract, using a new executor per block
+        for number in 1..=2 {
+            let header = Header {
+                timestamp: number,
+                number,
+                excess_blob_gas: Some(0),
+                parent_beacon_block_root: Some(B256::with_last_byte(0x69)),
+                ..Header::default()
+            };
+            let state = CountingStateProvider {
+                inner: db.clone(),
+                bytecode_reads: Arc::clone(&bytecode_reads),
+            };
+            provider
+                .executor(StateProviderDatabase::new(state).with_bytecode_cache(cache.clone()))
+                .execute_without_verification(
+                    &BlockWithSenders {
+                        block: Block {
+                            header,
+                            body: vec![],
+                            ommers: vec![],
+                            withdrawals: None,
+                            requests: None,
+                        },
+                        senders: vec![],
+                    },
+                    U256::ZERO,
+                    None,
+                )
+                .unwrap();
+
+            // only the first executor loads the contract
+            assert_eq!(bytecode_reads.load(Ordering::Relaxed), 1);
+        }
+        assert_eq!(cache.len(), 1);
     }
 
     #[test]
@@ -580,6 +900,7 @@ mod tests {
                     senders: vec![],
                 },
                 U256::ZERO,
+                None,
             )
             .unwrap();
 
@@ -1405,4 +1726,360 @@ mod tests {
             ),
         }
     }
+
+    #[test]
+    fn reverts_and_halts_are_distinct() {
+        let chain_spec = Arc::new(ChainSpecBuilder::from(&*MAINNET).berlin_activated().build());
+        let mut db = StateProviderTest::default();
+
+        let key_pair = Keypair::new(&Secp256k1::new(), &mut generators::rng());
+        let sender = public_key_to_address(key_pair.public_key());
+        db.insert_account(
+            sender,
+            Account { nonce: 0, balance: U256::from(ETH_TO_WEI), bytecode_hash: None },
+            None,
+            HashMap::new(),
+        );
+
+        // PUSH1 0 PUSH1 0 REVERT
+        let reverting = Address::with_last_byte(1);
+        // JUMPDEST PUSH1 0 JUMP, which loops until it runs out of gas
+        let looping = Address::with_last_byte(2);
+        for (address, code) in [(reverting, bytes!("60006000fd")), (looping, bytes!("5b600056"))] {
+            let account =
+                Account { nonce: 1, balance: U256::ZERO, bytecode_hash: Some(keccak256(&code)) };
+            db.insert_account(address, account, Some(code), HashMap::new());
+        }
+
+        let body = [reverting, looping]
+            .into_iter()
+            .enumerate()
+            .map(|(nonce, to)| {
+                sign_tx_with_key_pair(
+                    key_pair,
+                    Transaction::Legacy(TxLegacy {
+                        chain_id: Some(chain_spec.chain.id()),
+                        nonce: nonce as u64,
+                        gas_price: 0,
+                        gas_limit: 50_000,
+                        to: TxKind::Call(to),
+                        value: U256::ZERO,
+                        input: Bytes::new(),
+                    }),
+                )
+            })
+            .collect();
+        let header = Header { number: 1, gas_limit: 1_000_000, ..Header::default() };
+        let block = Block { header, body, ommers: vec![], withdrawals: None, requests: None }
+            .with_recovered_senders()
+            .unwrap();
+
+        let expected = vec![
+            (0, TransactionOutcome::Revert { output: Bytes::new() }),
+            (
+                1,
+                TransactionOutcome::Halt {
+                    reason: HaltReason::OutOfGas(OutOfGasError::Basic),
+                    gas_used: 50_000,
+                },
+            ),
+        ];
+
+        let provider = executor_provider(chain_spec);
+        let output = provider
+            .executor(StateProviderDatabase::new(&db))
+            .execute_without_verification(&block, U256::ZERO, None)
+            .unwrap();
+
+        // both transactions failed, but only the one that ran out of gas consumed all of its gas
+        assert!(output.receipts.iter().all(|receipt| !receipt.success));
+        assert!(output.receipts[0].cumulative_gas_used < 50_000);
+        assert_eq!(output.gas_used, output.receipts[0].cumulative_gas_used + 50_000);
+        assert_eq!(output.unsuccessful, expected);
+
+        // the outcomes are surfaced in the output of the block execution
+        let output = provider
+            .executor(StateProviderDatabase::new(&db))
+            .execute((&block, U256::ZERO).into())
+            .unwrap();
+        assert_eq!(output.unsuccessful, expected);
+
+        let transactions = block
+            .transactions_with_sender()
+            .map(|(sender, tx)| (tx.clone(), *sender))
+            .collect::<Vec<_>>();
+        let output = provider
+            .executor(StateProviderDatabase::new(&db))
+            .execute_transactions_until(
+                &block.header,
+                U256::ZERO,
+                transactions.into_iter(),
+                100_000,
+            )
+            .unwrap();
+        assert_eq!(output.unsuccessful, expected);
+    }
+
+    #[test]
+    fn execute_transactions_until_gas_limit() {
+        let chain_spec = Arc::new(ChainSpecBuilder::from(&*MAINNET).berlin_activated().build());
+        let mut db = StateProviderTest::default();
+
+        let key_pair = Keypair::new(&Secp256k1::new(), &mut generators::rng());
+        let sender = public_key_to_address(key_pair.public_key());
+        db.insert_account(
+            sender,
+            Account { nonce: 0, balance: U256::from(ETH_TO_WEI), bytecode_hash: None },
+            None,
+            HashMap::new(),
+        );
+
+        let transfer = |nonce: u64, gas_limit: u64| {
+            let transaction = sign_tx_with_key_pair(
+                key_pair,
+                Transaction::Legacy(TxLegacy {
+                    chain_id: Some(chain_spec.chain.id()),
+                    nonce,
+                    gas_price: 0,
+                    gas_limit,
+                    to: TxKind::Call(Address::with_last_byte(1)),
+                    value: U256::from(1),
+                    input: Bytes::new(),
+                }),
+            );
+            (transaction, sender)
+        };
+        let transactions = vec![
+            transfer(0, 21_000),
+            // exceeds the remaining 49_000 gas
+            transfer(1, 50_000),
+            transfer(1, 21_000),
+            // nonce too high
+            transfer(5, 21_000),
+            // leaves 7_000 gas, which is not enough for another transaction
+            transfer(2, 21_000),
+            transfer(3, 21_000),
+        ];
+        let hashes = transactions.iter().map(|(tx, _)| tx.hash()).collect::<Vec<_>>();
+
+        let header = Header { number: 1, gas_limit: 1_000_000, ..Header::default() };
+        let mut executor = executor_provider(chain_spec).executor(StateProviderDatabase::new(&db));
+        let mut transactions = transactions.into_iter();
+        let output = executor
+            .execute_transactions_until(&header, U256::ZERO, transactions.by_ref(), 70_000)
+            .unwrap();
+
+        assert_eq!(
+            output.transactions.iter().map(|(tx, _)| tx.hash()).collect::<Vec<_>>(),
+            vec![hashes[0], hashes[2], hashes[4]]
+        );
+        assert_eq!(output.gas_used, 63_000);
+        assert_eq!(
+            output.receipts.iter().map(|receipt| receipt.cumulative_gas_used).collect::<Vec<_>>(),
+            vec![21_000, 42_000, 63_000]
+        );
+        assert_eq!(output.skipped, vec![hashes[1]]);
+        assert_eq!(output.failed.len(), 1);
+        assert_eq!(output.failed[0].0, hashes[3]);
+        assert!(matches!(output.failed[0].1, InvalidTransaction::NonceTooHigh { .. }));
+
+        // the execution stopped before the last transaction
+        assert_eq!(transactions.next().map(|(tx, _)| tx.hash()), Some(hashes[5]));
+
+        let bundle = executor.take_bundle();
+        assert_eq!(bundle.account(&sender).unwrap().info.as_ref().unwrap().nonce, 3);
+    }
+
+    #[test]
+    fn withdrawals_before_shanghai() {
+        let chain_spec = Arc::new(ChainSpecBuilder::from(&*MAINNET).berlin_activated().build());
+        let db = StateProviderTest::default();
+
+        let header = Header { number: 1, gas_limit: 1_000_000, ..Header::default() };
+        let block = Block {
+            header,
+            body: vec![],
+            ommers: vec![],
+            withdrawals: Some(Withdrawals::default()),
+            requests: None,
+        }
+        .with_recovered_senders()
+        .unwrap();
+
+        let err = executor_provider(chain_spec)
+            .executor(StateProviderDatabase::new(&db))
+            .execute((&block, U256::ZERO).into())
+            .unwrap_err();
+        assert_eq!(
+            *err.as_validation().unwrap(),
+            BlockValidationError::WithdrawalsBeforeShanghai { hash: block.header.hash_slow() }
+        );
+    }
+
+    /// RLP encodes the given fields as a list.
+    fn rlp_list(fields: &[&dyn Encodable]) -> Vec<u8> {
+        let mut out = Vec::new();
+        alloy_rlp::encode_list::<dyn Encodable, _>(fields, &mut out);
+        out
+    }
+
+    /// Executes a Frontier block with a transfer and a call to a contract that emits a log, and
+    /// verifies its receipts root with the state roots the executor computed after each
+    /// transaction.
+    ///
+    /// Mainnet fixtures aren't available in this tree, so the expected roots are computed with
+    /// `triehash` instead.
+    #[test]
+    fn pre_byzantium_receipts_root_from_execution() {
+        let chain_spec = Arc::new(ChainSpecBuilder::from(&*MAINNET).frontier_activated().build());
+        let mut db = StateProviderTest::default();
+        let mut accounts = HashMap::new();
+
+        let key_pair = Keypair::new(&Secp256k1::new(), &mut generators::rng());
+        let sender = public_key_to_address(key_pair.public_key());
+        db.insert_account(
+            sender,
+            Account { nonce: 0, balance: U256::from(ETH_TO_WEI), bytecode_hash: None },
+            None,
+            HashMap::new(),
+        );
+        accounts.insert(
+            sender,
+            AccountInfo {
+                balance: U256::from(ETH_TO_WEI),
+                nonce: 0,
+                code_hash: KECCAK_EMPTY,
+                code: None,
+            },
+        );
+
+        // PUSH1 0 PUSH1 0 LOG0
+        let logger = Address::with_last_byte(1);
+        let code = bytes!("60006000a0");
+        let code_hash = keccak256(&code);
+        db.insert_account(
+            logger,
+            Account { nonce: 1, balance: U256::ZERO, bytecode_hash: Some(code_hash) },
+            Some(code),
+            HashMap::new(),
+        );
+        accounts
+            .insert(logger, AccountInfo { balance: U256::ZERO, nonce: 1, code_hash, code: None });
+
+        // none of the accounts have storage, so the state root only depends on the accounts
+        let state_roots = IntermediateStateRoots::new(move |changes: &BundleState| {
+            let mut accounts = accounts.clone();
+            for (address, account) in &changes.state {
+                match &account.info {
+                    Some(info) => accounts.insert(*address, info.clone()),
+                    None => accounts.remove(address),
+                };
+            }
+            Ok(triehash::sec_trie_root::<KeccakHasher, _, _, _>(accounts.into_iter().map(
+                |(address, info)| {
+                    let account: [&dyn Encodable; 4] =
+                        [&info.nonce, &info.balance, &EMPTY_ROOT_HASH, &info.code_hash];
+                    (address, rlp_list(&account))
+                },
+            )))
+        });
+
+        let body = [(Address::with_last_byte(2), 21_000), (logger, 50_000)]
+            .into_iter()
+            .enumerate()
+            .map(|(nonce, (to, gas_limit))| {
+                sign_tx_with_key_pair(
+                    key_pair,
+                    Transaction::Legacy(TxLegacy {
+                        chain_id: None,
+                        nonce: nonce as u64,
+                        gas_price: 1,
+                        gas_limit,
+                        to: TxKind::Call(to),
+                        value: U256::from(1),
+                        input: Bytes::new(),
+                    }),
+                )
+            })
+            .collect();
+        let header = Header { number: 1, gas_limit: 1_000_000, ..Header::default() };
+        let mut block = Block { header, body, ommers: vec![], withdrawals: None, requests: None }
+            .with_recovered_senders()
+            .unwrap();
+
+        let provider = executor_provider(chain_spec).with_intermediate_state_roots(state_roots);
+        let output = provider
+            .executor(StateProviderDatabase::new(&db))
+            .execute_without_verification(&block, U256::ZERO, None)
+            .unwrap();
+        let roots = output.intermediate_state_roots.expect("block is before Byzantium");
+        assert_eq!(roots.len(), 2);
+        assert_ne!(roots[0], roots[1]);
+        assert_eq!(output.receipts[1].logs.len(), 1);
+
+        // The expected receipts root is built without the receipt encoding and the trie of reth:
+        // before Byzantium, a receipt is the list of the state root after the transaction, the
+        // cumulative gas used, the logs bloom and the logs.
+        let blooms = output
+            .receipts
+            .iter()
+            .map(|receipt| {
+                receipt.logs.iter().fold(Bloom::ZERO, |mut bloom, log| {
+                    bloom.accrue_log(log);
+                    bloom
+                })
+            })
+            .collect::<Vec<_>>();
+        let receipts_root = triehash::ordered_trie_root::<KeccakHasher, _>(
+            output.receipts.iter().zip(&roots).zip(&blooms).map(|((receipt, root), bloom)| {
+                let receipt: [&dyn Encodable; 4] =
+                    [root, &receipt.cumulative_gas_used, bloom, &receipt.logs];
+                rlp_list(&receipt)
+            }),
+        );
+        let receipts_with_bloom = output
+            .receipts
+            .iter()
+            .zip(&roots)
+            .map(|(receipt, root)| receipt.with_bloom_ref().with_post_state(*root))
+            .collect::<Vec<_>>();
+        assert_eq!(calculate_receipt_root_ref(&receipts_with_bloom), receipts_root);
+
+        // commit the header to the executed receipts
+        block.block.header.gas_used = output.gas_used;
+        block.block.header.logs_bloom = blooms.iter().fold(Bloom::ZERO, |acc, bloom| acc | *bloom);
+        block.block.header.receipts_root = receipts_root;
+
+        provider
+            .batch_executor(StateProviderDatabase::new(&db), PruneModes::none())
+            .execute_and_verify_one((&block, U256::ZERO).into())
+            .expect("receipts root matches the executed state roots");
+
+        // a receipts root of the status encoded receipts, as after Byzantium, is rejected
+        let mut status_encoded = block.clone();
+        status_encoded.block.header.receipts_root = calculate_receipt_root_ref(
+            &output.receipts.iter().map(Receipt::with_bloom_ref).collect::<Vec<_>>(),
+        );
+        let err = provider
+            .batch_executor(StateProviderDatabase::new(&db), PruneModes::none())
+            .execute_and_verify_one((&status_encoded, U256::ZERO).into())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            BlockExecutionError::Consensus(ConsensusError::BodyReceiptRootDiff(_))
+        ));
+
+        // without the state roots, only the logs bloom and the gas used are verified
+        executor_provider(provider.chain_spec.clone())
+            .batch_executor(StateProviderDatabase::new(&db), PruneModes::none())
+            .execute_and_verify_one((&status_encoded, U256::ZERO).into())
+            .expect("receipts root isn't verified without state roots");
+        let mut missing_log = status_encoded;
+        missing_log.block.header.logs_bloom = Bloom::ZERO;
+        let err = executor_provider(provider.chain_spec.clone())
+            .batch_executor(StateProviderDatabase::new(&db), PruneModes::none())
+            .execute_and_verify_one((&missing_log, U256::ZERO).into())
+            .unwrap_err();
+        assert!(matches!(err, BlockExecutionError::Consensus(ConsensusError::BodyBloomLogDiff(_))));
+    }
 }
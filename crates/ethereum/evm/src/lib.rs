@@ -24,6 +24,9 @@ pub mod dao_fork;
 /// [EIP-6110](https://eips.ethereum.org/EIPS/eip-6110) handling.
 pub mod eip6110;
 
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
 /// Ethereum-related EVM configuration.
 #[derive(Debug, Clone, Copy, Default)]
 #[non_exhaustive]
@@ -0,0 +1,582 @@
+//! Deterministic generator of valid, executable test chains.
+
+use crate::{execute::EthExecutorProvider, EthEvmConfig};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider, Executor};
+use reth_primitives::{
+    constants::{EMPTY_OMMER_ROOT_HASH, ETHEREUM_BLOCK_GAS_LIMIT, ETH_TO_WEI},
+    hex_literal::hex,
+    keccak256, proofs, public_key_to_address,
+    revm::compat::into_reth_acc,
+    Account, Address, Block, BlockWithSenders, Bloom, Bytes, ChainSpec, ChainSpecBuilder, Genesis,
+    GenesisAccount, Header, Receipt, SealedBlockWithSenders, SealedHeader, Transaction,
+    TransactionSigned, TxEip1559, TxKind, Withdrawal, Withdrawals, B256, U256,
+};
+use reth_revm::{database::StateProviderDatabase, test_utils::StateProviderTest};
+use reth_testing_utils::generators::{generate_keys, sign_tx_with_key_pair};
+use secp256k1::Keypair;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+/// Plain state of the generated chain: accounts with their non-zero storage slots.
+pub type GeneratedState = BTreeMap<Address, (Account, BTreeMap<B256, U256>)>;
+
+/// Balance every generated genesis account is funded with.
+const GENESIS_BALANCE: u128 = 1_000_000 * ETH_TO_WEI;
+
+/// Priority fee paid by every generated transaction.
+const PRIORITY_FEE: u128 = 1_000_000_000;
+
+/// Seconds between two generated blocks.
+const BLOCK_TIME: u64 = 12;
+
+/// Gas limit of plain value transfers.
+const TRANSFER_GAS: u64 = 21_000;
+
+/// Gas limit of contract deployments.
+const DEPLOY_GAS: u64 = 200_000;
+
+/// Gas limit of contract calls.
+const CALL_GAS: u64 = 100_000;
+
+/// Number of distinct storage slots written by the [`Workload::DeployAndCall`] workload.
+const STORAGE_SLOTS: u64 = 16;
+
+/// Runtime code that stores the first calldata word at the slot given by the second word.
+///
+/// `PUSH1 0 CALLDATALOAD PUSH1 32 CALLDATALOAD SSTORE STOP`
+const STORAGE_WRITER_CODE: [u8; 8] = [0x60, 0x00, 0x35, 0x60, 0x20, 0x35, 0x55, 0x00];
+
+/// Runtime code that emits four `LOG2` entries, each with the first calldata word and the log
+/// index as topics.
+///
+/// `(PUSH1 i PUSH1 0 CALLDATALOAD PUSH1 32 PUSH1 0 LOG2) * 4 STOP`
+const LOG_EMITTER_CODE: [u8; 41] = [
+    0x60, 0x00, 0x60, 0x00, 0x35, 0x60, 0x20, 0x60, 0x00, 0xa2, // log 0
+    0x60, 0x01, 0x60, 0x00, 0x35, 0x60, 0x20, 0x60, 0x00, 0xa2, // log 1
+    0x60, 0x02, 0x60, 0x00, 0x35, 0x60, 0x20, 0x60, 0x00, 0xa2, // log 2
+    0x60, 0x03, 0x60, 0x00, 0x35, 0x60, 0x20, 0x60, 0x00, 0xa2, // log 3
+    0x00,
+];
+
+/// The kind of transactions a [`ChainGenerator`] fills blocks with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Workload {
+    /// Plain value transfers to fresh addresses.
+    #[default]
+    Transfers,
+    /// Deployments of a storage writing contract mixed with calls that write its storage.
+    DeployAndCall,
+    /// Calls into contracts that emit several logs per call.
+    LogHeavy,
+}
+
+/// Generates chains of valid blocks by executing generated transactions against an in-memory
+/// state.
+///
+/// The generator starts from a genesis allocation of funded accounts with known signers. Every
+/// generated block is executed with the [`EthExecutorProvider`], so its gas used, receipts root,
+/// logs bloom and state root are the ones a node would compute when executing it. The same seed
+/// always produces the same chain.
+///
+/// The chain spec is expected to activate Paris at genesis. Withdrawals are generated once Shanghai
+/// is active, Prague requests are not supported.
+#[derive(Debug, Clone)]
+pub struct ChainGenerator {
+    /// The chain spec with the generated genesis allocation.
+    chain_spec: Arc<ChainSpec>,
+    /// Source of all randomness.
+    rng: StdRng,
+    /// Signers of the funded genesis accounts.
+    signers: Vec<Keypair>,
+    /// The kind of transactions to generate.
+    workload: Workload,
+    /// Number of transactions per block.
+    txs_per_block: usize,
+    /// Number of withdrawals per block once Shanghai is active.
+    withdrawals_per_block: usize,
+    /// The state at genesis.
+    genesis_state: GeneratedState,
+    /// The state after the last generated block.
+    state: GeneratedState,
+    /// Bytecodes of all deployed contracts.
+    bytecodes: HashMap<B256, Bytes>,
+    /// Deployed storage writing contracts.
+    storage_writers: Vec<Address>,
+    /// Deployed log emitting contracts.
+    log_emitters: Vec<Address>,
+    /// Contracts deployed by the block that is currently generated.
+    pending_deployments: Vec<Address>,
+    /// Index of the next withdrawal.
+    withdrawal_index: u64,
+    /// Hashes of the genesis and all generated blocks.
+    block_hashes: BTreeMap<u64, B256>,
+    /// Header of the last generated block.
+    tip: SealedHeader,
+    /// Total difficulty at the tip.
+    total_difficulty: U256,
+    /// All generated blocks.
+    blocks: Vec<SealedBlockWithSenders>,
+    /// Receipts of all generated blocks.
+    receipts: Vec<Vec<Receipt>>,
+}
+
+impl ChainGenerator {
+    /// Creates a new generator seeded with `seed`.
+    ///
+    /// The genesis allocation of `base` is replaced by `accounts` funded accounts, all other
+    /// genesis fields and the hardforks are kept.
+    ///
+    /// # Panics
+    ///
+    /// If `accounts` is zero.
+    pub fn new(base: &Arc<ChainSpec>, seed: u64, accounts: usize) -> Self {
+        assert!(accounts > 0, "at least one funded account is required");
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let signers = generate_keys(&mut rng, accounts);
+        let alloc = signers
+            .iter()
+            .map(|signer| {
+                let account = GenesisAccount::default().with_balance(U256::from(GENESIS_BALANCE));
+                (public_key_to_address(signer.public_key()), account)
+            })
+            .collect::<BTreeMap<_, _>>();
+        let genesis_state = alloc
+            .iter()
+            .map(|(address, account)| {
+                let account = Account { balance: account.balance, ..Default::default() };
+                (*address, (account, BTreeMap::new()))
+            })
+            .collect::<GeneratedState>();
+
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::from(base).genesis(Genesis { alloc, ..base.genesis.clone() }).build(),
+        );
+        let tip = chain_spec.sealed_genesis_header();
+
+        Self {
+            rng,
+            signers,
+            workload: Workload::default(),
+            txs_per_block: 2,
+            withdrawals_per_block: 0,
+            state: genesis_state.clone(),
+            genesis_state,
+            bytecodes: HashMap::new(),
+            storage_writers: Vec::new(),
+            log_emitters: Vec::new(),
+            pending_deployments: Vec::new(),
+            withdrawal_index: 0,
+            block_hashes: BTreeMap::from([(tip.number, tip.hash())]),
+            total_difficulty: tip.difficulty,
+            tip,
+            blocks: Vec::new(),
+            receipts: Vec::new(),
+            chain_spec,
+        }
+    }
+
+    /// Sets the kind of transactions to generate.
+    pub const fn with_workload(mut self, workload: Workload) -> Self {
+        self.workload = workload;
+        self
+    }
+
+    /// Sets the number of transactions per block.
+    pub const fn with_txs_per_block(mut self, txs_per_block: usize) -> Self {
+        self.txs_per_block = txs_per_block;
+        self
+    }
+
+    /// Sets the number of withdrawals per block once Shanghai is active.
+    pub const fn with_withdrawals_per_block(mut self, withdrawals_per_block: usize) -> Self {
+        self.withdrawals_per_block = withdrawals_per_block;
+        self
+    }
+
+    /// Returns a generator that continues from the current tip with a differently seeded rng.
+    ///
+    /// Blocks generated by the fork are siblings of the blocks generated by `self`.
+    pub fn fork(&self, seed: u64) -> Self {
+        let mut fork = self.clone();
+        fork.rng = StdRng::seed_from_u64(seed);
+        fork
+    }
+
+    /// Returns the chain spec that contains the generated genesis allocation.
+    pub fn chain_spec(&self) -> Arc<ChainSpec> {
+        self.chain_spec.clone()
+    }
+
+    /// Returns the sealed genesis header.
+    pub fn genesis_header(&self) -> SealedHeader {
+        self.chain_spec.sealed_genesis_header()
+    }
+
+    /// Returns the header of the last generated block, or the genesis header.
+    pub const fn tip(&self) -> &SealedHeader {
+        &self.tip
+    }
+
+    /// Returns the total difficulty at the tip.
+    pub const fn total_difficulty(&self) -> U256 {
+        self.total_difficulty
+    }
+
+    /// Returns all generated blocks.
+    pub fn blocks(&self) -> &[SealedBlockWithSenders] {
+        &self.blocks
+    }
+
+    /// Returns the receipts of all generated blocks.
+    pub fn receipts(&self) -> &[Vec<Receipt>] {
+        &self.receipts
+    }
+
+    /// Returns the state at genesis.
+    pub const fn genesis_state(&self) -> &GeneratedState {
+        &self.genesis_state
+    }
+
+    /// Returns the expected state after executing all generated blocks.
+    pub const fn state(&self) -> &GeneratedState {
+        &self.state
+    }
+
+    /// Returns the bytecodes of all deployed contracts.
+    pub const fn bytecodes(&self) -> &HashMap<B256, Bytes> {
+        &self.bytecodes
+    }
+
+    /// Computes the state root of [`Self::state`].
+    pub fn state_root(&self) -> B256 {
+        proofs::state_root_unhashed(self.state.iter().map(|(address, (account, storage))| {
+            let storage_root =
+                proofs::storage_root_unhashed(storage.iter().map(|(slot, value)| (*slot, *value)));
+            (*address, (*account, storage_root))
+        }))
+    }
+
+    /// Generates `count` consecutive blocks on top of the tip.
+    pub fn generate_blocks(&mut self, count: usize) -> Vec<SealedBlockWithSenders> {
+        (0..count).map(|_| self.generate_block()).collect()
+    }
+
+    /// Generates and executes the next block on top of the tip.
+    ///
+    /// # Panics
+    ///
+    /// If the generated block fails to execute.
+    pub fn generate_block(&mut self) -> SealedBlockWithSenders {
+        let parent = self.tip.clone();
+        let timestamp = parent.timestamp + BLOCK_TIME;
+        let base_fee_per_gas = self.chain_spec.next_block_base_fee(&parent, timestamp);
+        let (body, senders) = self.next_transactions(base_fee_per_gas.unwrap_or_default() as u128);
+        let withdrawals = self
+            .chain_spec
+            .is_shanghai_active_at_timestamp(timestamp)
+            .then(|| self.next_withdrawals());
+        let is_cancun = self.chain_spec.is_cancun_active_at_timestamp(timestamp);
+
+        let header = Header {
+            parent_hash: parent.hash(),
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            beneficiary: Address::from(self.rng.gen::<[u8; 20]>()),
+            transactions_root: proofs::calculate_transaction_root(&body),
+            withdrawals_root: withdrawals
+                .as_ref()
+                .map(|withdrawals| proofs::calculate_withdrawals_root(withdrawals)),
+            number: parent.number + 1,
+            gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+            timestamp,
+            mix_hash: B256::from(self.rng.gen::<[u8; 32]>()),
+            base_fee_per_gas,
+            blob_gas_used: is_cancun.then_some(0),
+            excess_blob_gas: is_cancun
+                .then(|| parent.next_block_excess_blob_gas().unwrap_or_default()),
+            parent_beacon_block_root: is_cancun.then_some(B256::ZERO),
+            ..Default::default()
+        };
+        let mut block = BlockWithSenders {
+            block: Block {
+                header,
+                body,
+                ommers: Vec::new(),
+                withdrawals: withdrawals.map(Withdrawals::new),
+                requests: None,
+            },
+            senders,
+        };
+
+        let total_difficulty = self.total_difficulty + block.header.difficulty;
+        let db = self.state_provider(&self.state);
+        let BlockExecutionOutput { state, receipts, gas_used, .. } =
+            EthExecutorProvider::new(self.chain_spec.clone(), EthEvmConfig::default())
+                .executor(StateProviderDatabase::new(&db))
+                .execute((&block, total_difficulty).into())
+                .expect("generated block should execute");
+
+        for (address, account) in state.state() {
+            let Some(info) = &account.info else {
+                self.state.remove(address);
+                continue
+            };
+            let (plain_account, storage) = self.state.entry(*address).or_default();
+            if account.was_destroyed() {
+                storage.clear();
+            }
+            *plain_account = into_reth_acc(info.clone());
+            for (slot, value) in &account.storage {
+                let slot = B256::new(slot.to_be_bytes());
+                if value.present_value.is_zero() {
+                    storage.remove(&slot);
+                } else {
+                    storage.insert(slot, value.present_value);
+                }
+            }
+        }
+        for (hash, bytecode) in &state.contracts {
+            self.bytecodes.insert(*hash, bytecode.original_bytes());
+        }
+        self.confirm_deployments();
+
+        let receipts_with_bloom = receipts.iter().map(Receipt::with_bloom_ref).collect::<Vec<_>>();
+        block.block.header.gas_used = gas_used;
+        block.block.header.receipts_root = proofs::calculate_receipt_root_ref(&receipts_with_bloom);
+        block.block.header.logs_bloom =
+            receipts_with_bloom.iter().fold(Bloom::ZERO, |bloom, receipt| bloom | receipt.bloom);
+        block.block.header.state_root = self.state_root();
+
+        let block = block.seal_slow();
+        self.block_hashes.insert(block.number, block.hash());
+        self.total_difficulty = total_difficulty;
+        self.tip = block.header.clone();
+        self.blocks.push(block.clone());
+        self.receipts.push(receipts);
+
+        block
+    }
+
+    /// Builds an in-memory state provider over `state` and the known block hashes.
+    fn state_provider(&self, state: &GeneratedState) -> StateProviderTest {
+        let mut db = StateProviderTest::default();
+        for (address, (account, storage)) in state {
+            let bytecode =
+                account.bytecode_hash.and_then(|hash| self.bytecodes.get(&hash).cloned());
+            let storage = storage.iter().map(|(slot, value)| (*slot, *value)).collect();
+            db.insert_account(*address, *account, bytecode, storage);
+        }
+        for (number, hash) in &self.block_hashes {
+            db.insert_block_hash(*number, *hash);
+        }
+        db
+    }
+
+    /// Sorts the contracts deployed by the last block by their code, dropping failed deployments.
+    fn confirm_deployments(&mut self) {
+        let storage_writer_hash = keccak256(STORAGE_WRITER_CODE);
+        let log_emitter_hash = keccak256(LOG_EMITTER_CODE);
+        for address in std::mem::take(&mut self.pending_deployments) {
+            match self.state.get(&address).and_then(|(account, _)| account.bytecode_hash) {
+                Some(hash) if hash == storage_writer_hash => self.storage_writers.push(address),
+                Some(hash) if hash == log_emitter_hash => self.log_emitters.push(address),
+                _ => {}
+            }
+        }
+    }
+
+    /// Generates the signed transactions of the next block and their senders.
+    fn next_transactions(&mut self, base_fee: u128) -> (Vec<TransactionSigned>, Vec<Address>) {
+        let mut nonces = HashMap::new();
+        let mut transactions = Vec::with_capacity(self.txs_per_block);
+        let mut senders = Vec::with_capacity(self.txs_per_block);
+
+        for _ in 0..self.txs_per_block {
+            let signer = self.signers[self.rng.gen_range(0..self.signers.len())];
+            let sender = public_key_to_address(signer.public_key());
+            let nonce = *nonces.entry(sender).or_insert_with(|| {
+                self.state.get(&sender).map(|(account, _)| account.nonce).unwrap_or_default()
+            });
+            nonces.insert(sender, nonce + 1);
+
+            let (to, value, input, gas_limit) = self.next_payload(sender, nonce);
+            let transaction = Transaction::Eip1559(TxEip1559 {
+                chain_id: self.chain_spec.chain.id(),
+                nonce,
+                gas_limit,
+                max_fee_per_gas: base_fee + PRIORITY_FEE,
+                max_priority_fee_per_gas: PRIORITY_FEE,
+                to,
+                value,
+                input,
+                ..Default::default()
+            });
+            transactions.push(sign_tx_with_key_pair(signer, transaction));
+            senders.push(sender);
+        }
+
+        (transactions, senders)
+    }
+
+    /// Generates the destination, value, input and gas limit of the next transaction.
+    fn next_payload(&mut self, sender: Address, nonce: u64) -> (TxKind, U256, Bytes, u64) {
+        match self.workload {
+            Workload::Transfers => {
+                let to = Address::from(self.rng.gen::<[u8; 20]>());
+                let value = U256::from(self.rng.gen_range(1..=ETH_TO_WEI / 1000));
+                (TxKind::Call(to), value, Bytes::new(), TRANSFER_GAS)
+            }
+            Workload::DeployAndCall => {
+                if self.storage_writers.is_empty() || self.rng.gen_ratio(1, 4) {
+                    return self.deploy(sender, nonce, &STORAGE_WRITER_CODE)
+                }
+                let contract =
+                    self.storage_writers[self.rng.gen_range(0..self.storage_writers.len())];
+                let value = U256::from(self.rng.gen_range(1..=u64::MAX));
+                let slot = U256::from(self.rng.gen_range(0..STORAGE_SLOTS));
+                let input = [value.to_be_bytes::<32>(), slot.to_be_bytes::<32>()].concat();
+                (TxKind::Call(contract), U256::ZERO, input.into(), CALL_GAS)
+            }
+            Workload::LogHeavy => {
+                if self.log_emitters.is_empty() {
+                    return self.deploy(sender, nonce, &LOG_EMITTER_CODE)
+                }
+                let contract = self.log_emitters[self.rng.gen_range(0..self.log_emitters.len())];
+                let topic = self.rng.gen::<[u8; 32]>();
+                (TxKind::Call(contract), U256::ZERO, Bytes::copy_from_slice(&topic), CALL_GAS)
+            }
+        }
+    }
+
+    /// Generates the payload of a transaction that deploys `runtime_code`.
+    fn deploy(
+        &mut self,
+        sender: Address,
+        nonce: u64,
+        runtime_code: &[u8],
+    ) -> (TxKind, U256, Bytes, u64) {
+        self.pending_deployments.push(sender.create(nonce));
+
+        // PUSH1 len DUP1 PUSH1 11 PUSH1 0 CODECOPY PUSH1 0 RETURN, followed by the runtime code
+        let init_code =
+            [&[0x60, runtime_code.len() as u8][..], &hex!("80600b6000396000f3"), runtime_code]
+                .concat();
+
+        (TxKind::Create, U256::ZERO, init_code.into(), DEPLOY_GAS)
+    }
+
+    /// Generates the withdrawals of the next block.
+    fn next_withdrawals(&mut self) -> Vec<Withdrawal> {
+        (0..self.withdrawals_per_block)
+            .map(|_| {
+                let withdrawal = Withdrawal {
+                    index: self.withdrawal_index,
+                    validator_index: self.rng.gen_range(0..1024),
+                    address: Address::from(self.rng.gen::<[u8; 20]>()),
+                    amount: self.rng.gen_range(1..=1_000_000),
+                };
+                self.withdrawal_index += 1;
+                withdrawal
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_evm::execute::BatchExecutor;
+    use reth_primitives::MAINNET;
+    use reth_prune_types::PruneModes;
+
+    fn base_chain_spec() -> Arc<ChainSpec> {
+        Arc::new(ChainSpecBuilder::from(&*MAINNET).shanghai_activated().build())
+    }
+
+    /// Re-executes the generated blocks from genesis, verifying receipts and gas used.
+    fn verify_chain(generator: &ChainGenerator) {
+        let db = generator.state_provider(generator.genesis_state());
+
+        let mut executor = EthExecutorProvider::ethereum(generator.chain_spec())
+            .batch_executor(StateProviderDatabase::new(&db), PruneModes::none());
+        for block in generator.blocks() {
+            executor
+                .execute_and_verify_one((&block.clone().unseal(), U256::ZERO).into())
+                .expect("generated block should pass verification");
+        }
+        let outcome = executor.finalize();
+
+        assert_eq!(outcome.receipts().len(), generator.blocks().len());
+        for (address, account) in outcome.state().state() {
+            assert_eq!(
+                account.info.clone().map(into_reth_acc),
+                generator.state().get(address).map(|(account, _)| *account)
+            );
+        }
+    }
+
+    #[test]
+    fn generated_chains_pass_execution_verification() {
+        for workload in [Workload::Transfers, Workload::DeployAndCall, Workload::LogHeavy] {
+            let mut generator = ChainGenerator::new(&base_chain_spec(), 1, 3)
+                .with_workload(workload)
+                .with_txs_per_block(4)
+                .with_withdrawals_per_block(2);
+            let blocks = generator.generate_blocks(5);
+
+            assert_eq!(blocks.len(), 5);
+            assert_eq!(blocks[0].parent_hash, generator.genesis_header().hash());
+            assert_eq!(blocks[4].state_root, generator.state_root());
+            assert!(blocks.iter().all(|block| block.body.len() == 4));
+            verify_chain(&generator);
+        }
+    }
+
+    #[test]
+    fn workloads_produce_expected_effects() {
+        let mut generator =
+            ChainGenerator::new(&base_chain_spec(), 1, 2).with_workload(Workload::DeployAndCall);
+        generator.generate_blocks(4);
+        assert!(!generator.storage_writers.is_empty());
+        assert!(generator.state().values().any(|(_, storage)| !storage.is_empty()));
+
+        let mut generator =
+            ChainGenerator::new(&base_chain_spec(), 1, 2).with_workload(Workload::LogHeavy);
+        generator.generate_blocks(2);
+        assert_eq!(generator.receipts()[1].iter().map(|r| r.logs.len()).sum::<usize>(), 8);
+    }
+
+    #[test]
+    fn same_seed_generates_same_chain() {
+        let generate = |seed| {
+            ChainGenerator::new(&base_chain_spec(), seed, 2)
+                .with_workload(Workload::DeployAndCall)
+                .with_withdrawals_per_block(1)
+                .generate_blocks(3)
+                .into_iter()
+                .map(|block| block.hash())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(generate(7), generate(7));
+        assert_ne!(generate(7), generate(8));
+    }
+
+    #[test]
+    fn forks_build_sibling_blocks() {
+        let mut generator = ChainGenerator::new(&base_chain_spec(), 1, 1);
+        let fork_block = generator.generate_block();
+        let mut sidechain = generator.fork(2);
+
+        let canonical = generator.generate_block();
+        let side = sidechain.generate_block();
+
+        assert_eq!(canonical.parent_hash, fork_block.hash());
+        assert_eq!(side.parent_hash, fork_block.hash());
+        assert_ne!(canonical.hash(), side.hash());
+        verify_chain(&sidechain);
+    }
+}
@@ -4,6 +4,7 @@ use crate::{EthEngineTypes, EthEvmConfig};
 use reth_auto_seal_consensus::AutoSealConsensus;
 use reth_basic_payload_builder::{BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig};
 use reth_beacon_consensus::EthBeaconConsensus;
+use reth_consensus::RelaxableCheck;
 use reth_evm_ethereum::execute::EthExecutorProvider;
 use reth_network::NetworkHandle;
 use reth_node_builder::{
@@ -18,7 +19,7 @@ use reth_payload_builder::{PayloadBuilderHandle, PayloadBuilderService};
 use reth_provider::CanonStateSubscriptions;
 use reth_tracing::tracing::{debug, info};
 use reth_transaction_pool::{
-    blobstore::DiskFileBlobStore, EthTransactionPool, TransactionPool,
+    blobstore::DiskFileBlobStore, EthTransactionPool, PersistentRejections, TransactionPool,
     TransactionValidationTaskExecutor,
 };
 use std::sync::Arc;
@@ -129,8 +130,13 @@ where
                 blob_store.clone(),
             );
 
-        let transaction_pool =
+        let persistent_rejections = pool_config.persistent_rejections;
+        let mut transaction_pool =
             reth_transaction_pool::Pool::eth_pool(validator, blob_store, pool_config);
+        if let Some(config) = persistent_rejections {
+            let persistent = PersistentRejections::open(data_dir.txpool_rejected(), config)?;
+            transaction_pool = transaction_pool.with_persistent_rejections(persistent);
+        }
         info!(target: "reth::cli", "Transaction pool initialized");
         let transactions_path = data_dir.txpool_transactions();
 
@@ -153,6 +159,15 @@ where
                 },
             );
 
+            // spawn the task evicting expired transactions
+            ctx.task_executor().spawn_critical(
+                "txpool eviction task",
+                reth_transaction_pool::maintain::MaintenanceTask::new(
+                    pool.clone(),
+                    reth_transaction_pool::maintain::DEFAULT_MAINTENANCE_INTERVAL,
+                ),
+            );
+
             // spawn the maintenance task
             ctx.task_executor().spawn_critical(
                 "txpool maintenance task",
@@ -251,7 +266,17 @@ where
         if ctx.is_dev() {
             Ok(Arc::new(AutoSealConsensus::new(ctx.chain_spec())))
         } else {
-            Ok(Arc::new(EthBeaconConsensus::new(ctx.chain_spec())))
+            let mut consensus = EthBeaconConsensus::new(ctx.chain_spec());
+            if let Some(tolerance) = ctx.config().debug.future_timestamp_tolerance {
+                consensus = consensus.with_future_timestamp_tolerance(tolerance);
+            }
+            if let Some(severity) = ctx.config().debug.future_timestamp_check {
+                let policy = consensus
+                    .validation_policy()
+                    .with_severity(RelaxableCheck::FutureTimestamp, severity);
+                consensus = consensus.with_validation_policy(policy);
+            }
+            Ok(Arc::new(consensus))
         }
     }
 }
@@ -0,0 +1,189 @@
+//! Chain import through the pipeline of the node builder.
+
+use reth::tasks::TaskManager;
+use reth_beacon_consensus::EthBeaconConsensus;
+use reth_config::config::StageConfig;
+use reth_consensus::{Consensus, ConsensusError, PostExecutionInput};
+use reth_db_common::init::init_genesis;
+use reth_downloaders::file_client::FileClient;
+use reth_evm_ethereum::test_utils::ChainGenerator;
+use reth_node_builder::{
+    setup::build_import_pipeline, BuilderContext, NodeBuilder, NodeConfig, NodeHandle,
+};
+use reth_node_core::args::{DiscoveryArgs, NetworkArgs, RpcServerArgs};
+use reth_node_ethereum::{EthExecutorProvider, EthereumNode};
+use reth_primitives::{
+    BlockWithSenders, ChainSpecBuilder, Header, SealedBlock, SealedHeader, U256,
+};
+use reth_provider::{
+    test_utils::create_test_provider_factory_with_chain_spec, BlockNumReader, HeaderProvider,
+    ReceiptProvider,
+};
+use reth_prune_types::PruneModes;
+use reth_static_file::StaticFileProducer;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+/// A consensus that counts the headers it validates.
+#[derive(Debug, Clone)]
+struct CountingConsensus {
+    inner: Arc<EthBeaconConsensus>,
+    headers: Arc<AtomicUsize>,
+}
+
+impl Consensus for CountingConsensus {
+    fn validate_header(&self, header: &SealedHeader) -> Result<(), ConsensusError> {
+        self.headers.fetch_add(1, Ordering::Relaxed);
+        self.inner.validate_header(header)
+    }
+
+    fn validate_header_against_parent(
+        &self,
+        header: &SealedHeader,
+        parent: &SealedHeader,
+    ) -> Result<(), ConsensusError> {
+        self.inner.validate_header_against_parent(header, parent)
+    }
+
+    fn validate_header_with_total_difficulty(
+        &self,
+        header: &Header,
+        total_difficulty: U256,
+    ) -> Result<(), ConsensusError> {
+        self.inner.validate_header_with_total_difficulty(header, total_difficulty)
+    }
+
+    fn validate_block_pre_execution(&self, block: &SealedBlock) -> Result<(), ConsensusError> {
+        self.inner.validate_block_pre_execution(block)
+    }
+
+    fn validate_block_post_execution(
+        &self,
+        block: &BlockWithSenders,
+        input: PostExecutionInput<'_>,
+    ) -> Result<(), ConsensusError> {
+        self.inner.validate_block_post_execution(block, input)
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn import_into_launched_node() {
+    let mut generator = ChainGenerator::new(
+        &Arc::new(ChainSpecBuilder::mainnet().shanghai_activated().build()),
+        1,
+        2,
+    );
+    let blocks =
+        generator.generate_blocks(8).into_iter().map(|block| block.block).collect::<Vec<_>>();
+    let chain_spec = generator.chain_spec();
+
+    // an ephemeral node with the ethereum components and a custom consensus
+    let tasks = TaskManager::current();
+    let config = NodeConfig::test()
+        .with_chain(chain_spec.clone())
+        .with_network(NetworkArgs {
+            discovery: DiscoveryArgs { disable_discovery: true, ..DiscoveryArgs::default() },
+            ..NetworkArgs::default()
+        })
+        .with_unused_ports()
+        .with_rpc(RpcServerArgs::default().with_unused_ports());
+    let consensus = CountingConsensus {
+        inner: Arc::new(EthBeaconConsensus::new(chain_spec)),
+        headers: Arc::default(),
+    };
+    let components = EthereumNode::components().consensus({
+        let consensus = consensus.clone();
+        move |_: &BuilderContext<_>| async move { eyre::Ok(consensus) }
+    });
+    let NodeHandle { node, node_exit_future: _ } = NodeBuilder::new(config)
+        .testing_node(tasks.executor())
+        .with_types::<EthereumNode>()
+        .with_components(components)
+        .launch()
+        .await
+        .unwrap();
+    assert_eq!(node.provider.last_block_number().unwrap(), 0);
+
+    let file_client = Arc::new(FileClient::from_blocks(blocks.clone()));
+    let tip = file_client.tip().unwrap();
+    let (mut pipeline, _events) = node.pipeline.import_pipeline(file_client, false).await.unwrap();
+    pipeline.set_tip(tip);
+    pipeline.run().await.unwrap();
+
+    // the pipeline validated the blocks with the consensus of the node
+    assert!(consensus.headers.load(Ordering::Relaxed) >= blocks.len());
+    assert_eq!(node.provider.last_block_number().unwrap(), 8);
+    for (block, receipts) in blocks.iter().zip(generator.receipts()) {
+        assert_eq!(node.provider.sealed_header(block.number).unwrap(), Some(block.header.clone()));
+        assert_eq!(
+            node.provider.receipts_by_block(block.number.into()).unwrap().as_ref(),
+            Some(receipts)
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn import_generated_chain() {
+    let mut generator = ChainGenerator::new(
+        &Arc::new(ChainSpecBuilder::mainnet().shanghai_activated().build()),
+        1,
+        2,
+    );
+    let blocks =
+        generator.generate_blocks(8).into_iter().map(|block| block.block).collect::<Vec<_>>();
+    let chain_spec = generator.chain_spec();
+
+    let provider_factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+    init_genesis(provider_factory.clone()).unwrap();
+
+    let file_client = FileClient::from_blocks(blocks.clone());
+    let tip = file_client.tip().unwrap();
+    let (mut pipeline, _events) = build_import_pipeline(
+        &StageConfig::default(),
+        provider_factory.clone(),
+        Arc::new(EthBeaconConsensus::new(chain_spec.clone())),
+        Arc::new(file_client),
+        StaticFileProducer::new(provider_factory.clone(), PruneModes::default()),
+        EthExecutorProvider::ethereum(chain_spec),
+        false,
+    )
+    .await
+    .unwrap();
+    pipeline.set_tip(tip);
+    pipeline.run().await.unwrap();
+
+    assert_eq!(provider_factory.last_block_number().unwrap(), 8);
+    for (block, receipts) in blocks.iter().zip(generator.receipts()) {
+        assert_eq!(
+            provider_factory.sealed_header(block.number).unwrap(),
+            Some(block.header.clone())
+        );
+        assert_eq!(
+            provider_factory.receipts_by_block(block.number.into()).unwrap().as_ref(),
+            Some(receipts)
+        );
+    }
+}
+
+#[tokio::test]
+async fn import_empty_file() {
+    let chain_spec = Arc::new(ChainSpecBuilder::mainnet().shanghai_activated().build());
+    let provider_factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+    init_genesis(provider_factory.clone()).unwrap();
+
+    let err = build_import_pipeline(
+        &StageConfig::default(),
+        provider_factory.clone(),
+        Arc::new(EthBeaconConsensus::new(chain_spec.clone())),
+        Arc::new(FileClient::from_blocks(Vec::new())),
+        StaticFileProducer::new(provider_factory, PruneModes::default()),
+        EthExecutorProvider::ethereum(chain_spec),
+        false,
+    )
+    .await
+    .err()
+    .unwrap();
+    assert_eq!(err.to_string(), "no blocks to import");
+}
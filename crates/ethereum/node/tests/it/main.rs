@@ -1,4 +1,5 @@
 mod builder;
 mod exex;
+mod import;
 
 const fn main() {}
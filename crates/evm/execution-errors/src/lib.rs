@@ -60,6 +60,12 @@ pub enum BlockValidationError {
         /// The hash of the block
         hash: B256,
     },
+    /// Error for a block before Shanghai that has a withdrawals field
+    #[error("block {hash} has withdrawals before Shanghai")]
+    WithdrawalsBeforeShanghai {
+        /// The hash of the block
+        hash: B256,
+    },
     /// Error for missing total difficulty
     #[error("missing total difficulty for block {hash}")]
     MissingTotalDifficulty {
@@ -142,6 +148,16 @@ pub enum BlockExecutionError {
     /// Error when fetching latest block state.
     #[error(transparent)]
     LatestBlock(#[from] ProviderError),
+    /// The execution was cancelled before all transactions were executed.
+    ///
+    /// This does not mean the block is invalid.
+    #[error("block execution was cancelled after {executed} of {total} transactions")]
+    Cancelled {
+        /// The number of transactions that were executed.
+        executed: usize,
+        /// The number of transactions in the block.
+        total: usize,
+    },
     /// Arbitrary Block Executor Errors
     #[cfg(feature = "std")]
     #[error(transparent)]
@@ -183,4 +199,9 @@ impl BlockExecutionError {
     pub const fn is_state_root_error(&self) -> bool {
         matches!(self, Self::Validation(BlockValidationError::StateRoot(_)))
     }
+
+    /// Returns `true` if the execution was cancelled.
+    pub const fn is_cancelled(&self) -> bool {
+        matches!(self, Self::Cancelled { .. })
+    }
 }
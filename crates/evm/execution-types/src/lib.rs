@@ -13,3 +13,6 @@ pub use bundle::*;
 
 mod chain;
 pub use chain::*;
+
+mod transaction;
+pub use transaction::*;
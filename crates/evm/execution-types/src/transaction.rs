@@ -0,0 +1,56 @@
+use reth_primitives::{Bytes, Log};
+use revm::primitives::{ExecutionResult, HaltReason, Output};
+
+/// The outcome of executing a single transaction.
+///
+/// Receipts only record whether a transaction succeeded, this keeps apart transactions that
+/// reverted and transactions that halted exceptionally, e.g. because they ran out of gas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionOutcome {
+    /// The transaction executed successfully.
+    Success {
+        /// The output of the call or create.
+        output: Output,
+        /// The logs emitted by the transaction.
+        logs: Vec<Log>,
+    },
+    /// The transaction reverted, its state changes were discarded.
+    Revert {
+        /// The revert data.
+        output: Bytes,
+    },
+    /// The transaction halted exceptionally and consumed all of its gas.
+    Halt {
+        /// The reason the transaction halted.
+        reason: HaltReason,
+        /// The gas used by the transaction.
+        gas_used: u64,
+    },
+}
+
+impl TransactionOutcome {
+    /// Returns `true` if the transaction executed successfully.
+    pub const fn is_success(&self) -> bool {
+        matches!(self, Self::Success { .. })
+    }
+
+    /// Returns `true` if the transaction reverted.
+    pub const fn is_revert(&self) -> bool {
+        matches!(self, Self::Revert { .. })
+    }
+
+    /// Returns `true` if the transaction halted exceptionally.
+    pub const fn is_halt(&self) -> bool {
+        matches!(self, Self::Halt { .. })
+    }
+}
+
+impl From<ExecutionResult> for TransactionOutcome {
+    fn from(result: ExecutionResult) -> Self {
+        match result {
+            ExecutionResult::Success { output, logs, .. } => Self::Success { output, logs },
+            ExecutionResult::Revert { output, .. } => Self::Revert { output },
+            ExecutionResult::Halt { reason, gas_used } => Self::Halt { reason, gas_used },
+        }
+    }
+}
@@ -1,10 +1,18 @@
 //! Traits for execution.
 
-use reth_execution_types::ExecutionOutcome;
-use reth_primitives::{BlockNumber, BlockWithSenders, Receipt, Request, U256};
+use reth_execution_types::{ExecutionOutcome, TransactionOutcome};
+use reth_primitives::{BlockNumber, BlockWithSenders, Receipt, Request, B256, U256};
 use reth_prune_types::PruneModes;
 use revm::db::BundleState;
 use revm_primitives::db::Database;
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
 pub use reth_execution_errors::{BlockExecutionError, BlockValidationError};
 pub use reth_storage_errors::provider::ProviderError;
@@ -102,6 +110,10 @@ pub struct BlockExecutionOutput<T> {
     pub requests: Vec<Request>,
     /// The total gas used by the block.
     pub gas_used: u64,
+    /// The index and outcome of each transaction in the block that reverted or halted.
+    ///
+    /// Receipts only record that these transactions failed, not why.
+    pub unsuccessful: Vec<(usize, TransactionOutcome)>,
 }
 
 /// A helper type for ethereum block inputs that consists of a block and the total difficulty.
@@ -111,12 +123,20 @@ pub struct BlockExecutionInput<'a, Block> {
     pub block: &'a Block,
     /// The total difficulty of the block.
     pub total_difficulty: U256,
+    /// Aborts the execution of the block between transactions once it's cancelled.
+    pub cancellation: Option<&'a ExecutionCancellation>,
 }
 
 impl<'a, Block> BlockExecutionInput<'a, Block> {
     /// Creates a new input.
     pub const fn new(block: &'a Block, total_difficulty: U256) -> Self {
-        Self { block, total_difficulty }
+        Self { block, total_difficulty, cancellation: None }
+    }
+
+    /// Sets the cancellation that aborts the execution of the block.
+    pub const fn with_cancellation(mut self, cancellation: &'a ExecutionCancellation) -> Self {
+        self.cancellation = Some(cancellation);
+        self
     }
 }
 
@@ -126,6 +146,82 @@ impl<'a, Block> From<(&'a Block, U256)> for BlockExecutionInput<'a, Block> {
     }
 }
 
+/// A flag that aborts the execution of a block.
+///
+/// Executors check the flag after every transaction and abort the block with
+/// [`BlockExecutionError::Cancelled`] once it's set, either with [`Self::cancel`] or because the
+/// soft time limit passed. The execution is only aborted between transactions, so a single
+/// transaction always runs to completion.
+///
+/// The state of a cancelled executor contains a partially executed block, so the executor must be
+/// dropped and not be used again.
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionCancellation {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl ExecutionCancellation {
+    /// Creates a new cancellation that is only set with [`Self::cancel`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new cancellation that is set once the soft limit has passed from now on.
+    pub fn with_soft_limit(soft_limit: Duration) -> Self {
+        Self { cancelled: Default::default(), deadline: Instant::now().checked_add(soft_limit) }
+    }
+
+    /// Cancels the execution.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the execution was cancelled, setting the flag if the soft limit passed.
+    pub fn is_cancelled(&self) -> bool {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return true
+        }
+        if self.deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+            self.cancel();
+            return true
+        }
+        false
+    }
+}
+
+/// Computes the state root of the executor's database with the given state changes applied.
+///
+/// Receipts of blocks before Byzantium commit to the state root after each transaction, so their
+/// receipts root can only be verified by executors that compute these roots. This computes a state
+/// root per transaction and is therefore slow.
+#[derive(Clone)]
+pub struct IntermediateStateRoots(
+    Arc<dyn Fn(&BundleState) -> Result<B256, ProviderError> + Send + Sync>,
+);
+
+impl IntermediateStateRoots {
+    /// Creates a new instance from a function that computes the state root of the database the
+    /// executor reads from, with the given state changes applied.
+    pub fn new<F>(state_root: F) -> Self
+    where
+        F: Fn(&BundleState) -> Result<B256, ProviderError> + Send + Sync + 'static,
+    {
+        Self(Arc::new(state_root))
+    }
+
+    /// Returns the state root with the given state changes applied.
+    pub fn state_root(&self, changes: &BundleState) -> Result<B256, ProviderError> {
+        (self.0)(changes)
+    }
+}
+
+impl fmt::Debug for IntermediateStateRoots {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IntermediateStateRoots").finish_non_exhaustive()
+    }
+}
+
 /// A type that can create a new executor for block execution.
 pub trait BlockExecutorProvider: Send + Sync + Clone + Unpin + 'static {
     /// An executor that can execute a single block given a database.
@@ -1,7 +1,8 @@
 //! Helpers for testing.
 
 use crate::execute::{
-    BatchExecutor, BlockExecutionInput, BlockExecutionOutput, BlockExecutorProvider, Executor,
+    BatchExecutor, BlockExecutionInput, BlockExecutionOutput, BlockExecutorProvider,
+    ExecutionCancellation, Executor,
 };
 use parking_lot::Mutex;
 use reth_execution_errors::BlockExecutionError;
@@ -10,12 +11,22 @@ use reth_primitives::{BlockNumber, BlockWithSenders, Receipt};
 use reth_prune_types::PruneModes;
 use reth_storage_errors::provider::ProviderError;
 use revm_primitives::db::Database;
-use std::sync::Arc;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 /// A [`BlockExecutorProvider`] that returns mocked execution results.
 #[derive(Clone, Debug, Default)]
 pub struct MockExecutorProvider {
     exec_results: Arc<Mutex<Vec<ExecutionOutcome>>>,
+    /// The number of executed blocks, shared by all clones.
+    executions: Arc<AtomicUsize>,
+    /// How long the execution of a block takes.
+    execution_delay: Duration,
 }
 
 impl MockExecutorProvider {
@@ -23,6 +34,20 @@ impl MockExecutorProvider {
     pub fn extend(&self, results: impl IntoIterator<Item = impl Into<ExecutionOutcome>>) {
         self.exec_results.lock().extend(results.into_iter().map(Into::into));
     }
+
+    /// Makes the execution of every block take at least the given time.
+    ///
+    /// The cancellation of the execution is checked once the delay has passed, so a slow block is
+    /// cancelled without consuming its mocked execution result.
+    pub const fn with_execution_delay(mut self, delay: Duration) -> Self {
+        self.execution_delay = delay;
+        self
+    }
+
+    /// Returns the number of blocks executed by this provider and all its executors.
+    pub fn executions(&self) -> usize {
+        self.executions.load(Ordering::Relaxed)
+    }
 }
 
 impl BlockExecutorProvider for MockExecutorProvider {
@@ -50,7 +75,18 @@ impl<DB> Executor<DB> for MockExecutorProvider {
     type Output = BlockExecutionOutput<Receipt>;
     type Error = BlockExecutionError;
 
-    fn execute(self, _: Self::Input<'_>) -> Result<Self::Output, Self::Error> {
+    fn execute(self, input: Self::Input<'_>) -> Result<Self::Output, Self::Error> {
+        if !self.execution_delay.is_zero() {
+            std::thread::sleep(self.execution_delay);
+            if input.cancellation.map_or(false, ExecutionCancellation::is_cancelled) {
+                return Err(BlockExecutionError::Cancelled {
+                    executed: 0,
+                    total: input.block.body.len(),
+                })
+            }
+        }
+
+        self.executions.fetch_add(1, Ordering::Relaxed);
         let ExecutionOutcome { bundle, receipts, requests, first_block: _ } =
             self.exec_results.lock().pop().unwrap();
         Ok(BlockExecutionOutput {
@@ -58,6 +94,7 @@ impl<DB> Executor<DB> for MockExecutorProvider {
             receipts: receipts.into_iter().flatten().flatten().collect(),
             requests: requests.into_iter().flatten().collect(),
             gas_used: 0,
+            unsuccessful: Vec::new(),
         })
     }
 }
@@ -68,6 +105,7 @@ impl<DB> BatchExecutor<DB> for MockExecutorProvider {
     type Error = BlockExecutionError;
 
     fn execute_and_verify_one(&mut self, _: Self::Input<'_>) -> Result<(), Self::Error> {
+        self.executions.fetch_add(1, Ordering::Relaxed);
         Ok(())
     }
 
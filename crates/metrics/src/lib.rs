@@ -16,6 +16,9 @@
 /// Metrics derive macro.
 pub use reth_metrics_derive::Metrics;
 
+mod registrar;
+pub use registrar::{MetricKind, MetricsRegistrar, RegisteredMetric};
+
 /// Implementation of common metric utilities.
 #[cfg(feature = "common")]
 pub mod common;
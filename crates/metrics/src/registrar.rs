@@ -0,0 +1,189 @@
+//! Registration of the metrics exposed by the components of a node.
+
+use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+use std::sync::Mutex;
+
+/// The type of a metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetricKind {
+    /// A monotonically increasing counter.
+    Counter,
+    /// A gauge that can go up and down.
+    Gauge,
+    /// A histogram of observed values.
+    Histogram,
+}
+
+/// A metric declared by a component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredMetric {
+    /// The component that exposes the metric, e.g. `transaction_pool`.
+    pub component: &'static str,
+    /// The type of the metric.
+    pub kind: MetricKind,
+    /// The name of the metric, without the prefix of the recorder.
+    pub name: String,
+    /// The labels the metric is recorded with.
+    pub labels: &'static [&'static str],
+    /// The unit of the metric, if any.
+    pub unit: Option<Unit>,
+    /// The description of the metric.
+    pub description: String,
+}
+
+/// Collects the metrics declared by the components of a node.
+///
+/// Each component declares all of its metrics in one place, its `register_metrics` function, by
+/// passing the generated `describe` functions of its [`Metrics`](crate::Metrics) structs to
+/// [`MetricsRegistrar::describe`]. The descriptions are forwarded to the current recorder, so the
+/// registration has to happen after the recorder is installed.
+#[derive(Debug, Default)]
+pub struct MetricsRegistrar {
+    metrics: Vec<RegisteredMetric>,
+}
+
+impl MetricsRegistrar {
+    /// Creates an empty registrar.
+    pub const fn new() -> Self {
+        Self { metrics: Vec::new() }
+    }
+
+    /// Registers all metrics that are described by `describe`, e.g. the generated `describe`
+    /// function of a [`Metrics`](crate::Metrics) struct.
+    pub fn describe(&mut self, component: &'static str, describe: impl FnOnce()) -> &mut Self {
+        self.describe_with_labels(component, &[], describe)
+    }
+
+    /// Registers all metrics that are described by `describe`, which are recorded with the given
+    /// labels.
+    pub fn describe_with_labels(
+        &mut self,
+        component: &'static str,
+        labels: &'static [&'static str],
+        describe: impl FnOnce(),
+    ) -> &mut Self {
+        let capture = DescriptionRecorder::default();
+        metrics::with_local_recorder(&capture, describe);
+
+        let descriptions = capture.descriptions.into_inner().unwrap_or_else(|err| err.into_inner());
+        for (kind, name, unit, description) in descriptions {
+            metrics::with_recorder(|recorder| match kind {
+                MetricKind::Counter => {
+                    recorder.describe_counter(name.clone(), unit, description.clone())
+                }
+                MetricKind::Gauge => {
+                    recorder.describe_gauge(name.clone(), unit, description.clone())
+                }
+                MetricKind::Histogram => {
+                    recorder.describe_histogram(name.clone(), unit, description.clone())
+                }
+            });
+
+            self.metrics.push(RegisteredMetric {
+                component,
+                kind,
+                name: name.as_str().to_string(),
+                labels,
+                unit,
+                description: description.into_owned(),
+            });
+        }
+
+        self
+    }
+
+    /// Returns all registered metrics, in the order they were registered.
+    pub fn metrics(&self) -> &[RegisteredMetric] {
+        &self.metrics
+    }
+
+    /// Returns the registered metric with the given name.
+    pub fn get(&self, name: &str) -> Option<&RegisteredMetric> {
+        self.metrics.iter().find(|metric| metric.name == name)
+    }
+
+    /// Returns true if a metric with the given name was registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+}
+
+/// A recorder that captures the descriptions of metrics.
+#[derive(Debug, Default)]
+struct DescriptionRecorder {
+    descriptions: Mutex<Vec<(MetricKind, KeyName, Option<Unit>, SharedString)>>,
+}
+
+impl DescriptionRecorder {
+    fn push(&self, kind: MetricKind, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.descriptions.lock().unwrap_or_else(|err| err.into_inner()).push((
+            kind,
+            key,
+            unit,
+            description,
+        ));
+    }
+}
+
+impl Recorder for DescriptionRecorder {
+    fn describe_counter(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.push(MetricKind::Counter, key, unit, description)
+    }
+
+    fn describe_gauge(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.push(MetricKind::Gauge, key, unit, description)
+    }
+
+    fn describe_histogram(&self, key: KeyName, unit: Option<Unit>, description: SharedString) {
+        self.push(MetricKind::Histogram, key, unit, description)
+    }
+
+    fn register_counter(&self, _: &Key, _: &Metadata<'_>) -> Counter {
+        Counter::noop()
+    }
+
+    fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics::{describe_counter, describe_gauge, describe_histogram};
+
+    #[test]
+    fn registers_described_metrics() {
+        let mut registrar = MetricsRegistrar::new();
+        registrar
+            .describe("first", || {
+                describe_counter!("first.counter", "A counter");
+                describe_gauge!("first.gauge", Unit::Bytes, "A gauge");
+            })
+            .describe_with_labels("second", &["kind"], || {
+                describe_histogram!("second.histogram", "A histogram");
+            });
+
+        assert_eq!(registrar.metrics().len(), 3);
+        assert_eq!(
+            registrar.get("first.gauge"),
+            Some(&RegisteredMetric {
+                component: "first",
+                kind: MetricKind::Gauge,
+                name: "first.gauge".to_string(),
+                labels: &[],
+                unit: Some(Unit::Bytes),
+                description: "A gauge".to_string(),
+            })
+        );
+        let histogram = registrar.get("second.histogram").unwrap();
+        assert_eq!(histogram.kind, MetricKind::Histogram);
+        assert_eq!(histogram.labels, &["kind"]);
+        assert!(registrar.contains("first.counter"));
+        assert!(!registrar.contains("second.counter"));
+    }
+}
@@ -5,7 +5,7 @@
 
 use alloy_rlp::Encodable;
 use reth_net_common::ban_list::BanList;
-use reth_net_nat::{NatResolver, ResolveNatInterval};
+use reth_net_nat::{NatResolver, ResolveNatInterval, DEFAULT_MIN_VOTES};
 use reth_primitives::{bytes::Bytes, NodeRecord};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -65,6 +65,9 @@ pub struct Discv4Config {
     /// If configured and a `external_ip_resolver` is configured, try to resolve the external ip
     /// using this interval.
     pub resolve_external_ip_interval: Option<Duration>,
+    /// The number of peers that must agree on the external IP they observe for us before it is
+    /// advertised. Only used if the `external_ip_resolver` doesn't explicitly configure the IP.
+    pub external_ip_min_votes: usize,
     /// The duration after which we consider a bond expired.
     pub bond_expiration: Duration,
 }
@@ -137,6 +140,7 @@ impl Default for Discv4Config {
             external_ip_resolver: Some(Default::default()),
             // By default retry public IP using a 5min interval
             resolve_external_ip_interval: Some(Duration::from_secs(60 * 5)),
+            external_ip_min_votes: DEFAULT_MIN_VOTES,
         }
     }
 }
@@ -306,6 +310,12 @@ impl Discv4ConfigBuilder {
         self
     }
 
+    /// Sets the number of peers that must agree on our external IP before it is advertised.
+    pub fn external_ip_min_votes(&mut self, external_ip_min_votes: usize) -> &mut Self {
+        self.config.external_ip_min_votes = external_ip_min_votes;
+        self
+    }
+
     /// Returns the configured [`Discv4Config`]
     pub fn build(&self) -> Discv4Config {
         self.config.clone()
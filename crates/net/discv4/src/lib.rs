@@ -82,7 +82,7 @@ pub use reth_primitives::NodeRecord;
 pub mod test_utils;
 
 use crate::table::PongTable;
-use reth_net_nat::ResolveNatInterval;
+use reth_net_nat::{ExternalIpVotes, ResolveNatInterval, DEFAULT_MAX_VOTES};
 /// reexport to get public ip.
 pub use reth_net_nat::{external_ip, NatResolver};
 
@@ -482,6 +482,10 @@ pub struct Discv4Service {
     ping_interval: Interval,
     /// The interval at which to attempt resolving external IP again.
     resolve_external_ip_interval: Option<ResolveNatInterval>,
+    /// Votes on the external IP from the endpoints that are reported back in `Pong`s.
+    ///
+    /// This is only tracked if the external IP wasn't explicitly configured.
+    external_ip_votes: Option<ExternalIpVotes>,
     /// How this services is configured
     config: Discv4Config,
     /// Buffered events populated during poll.
@@ -586,6 +590,10 @@ impl Discv4Service {
             evict_expired_requests_interval,
             lookup_rotator,
             resolve_external_ip_interval: config.resolve_external_ip_interval(),
+            external_ip_votes: config
+                .external_ip_resolver
+                .filter(|resolver| resolver.allows_passive_discovery())
+                .map(|_| ExternalIpVotes::new(config.external_ip_min_votes, DEFAULT_MAX_VOTES)),
             config,
             queued_events: Default::default(),
             received_pongs: Default::default(),
@@ -614,7 +622,13 @@ impl Discv4Service {
 
     /// Sets the given ip address as the node's external IP in the node record announced in
     /// discovery
+    ///
+    /// If the address changed, this also updates the local ENR which increments its sequence
+    /// number.
     pub fn set_external_ip_addr(&mut self, external_ip: IpAddr) {
+        if let Some(votes) = &mut self.external_ip_votes {
+            votes.set_current(external_ip);
+        }
         if self.local_node_record.address != external_ip {
             debug!(target: "discv4", ?external_ip, "Updating external ip");
             self.local_node_record.address = external_ip;
@@ -1200,6 +1214,16 @@ impl Discv4Service {
         // keep track of the pong
         self.received_pongs.on_pong(remote_id, remote_addr.ip());
 
+        // the pong tells us how the remote sees our endpoint
+        if let Some(ip) = self
+            .external_ip_votes
+            .as_mut()
+            .and_then(|votes| votes.on_report(remote_addr.ip(), pong.to.address))
+        {
+            debug!(target: "discv4", ?ip, "Learned external ip from pongs");
+            self.set_external_ip_addr(ip);
+        }
+
         match reason {
             PingReason::InitialInsert => {
                 self.update_on_pong(node, pong.enr_sq);
@@ -2347,6 +2371,36 @@ mod tests {
         assert_eq!(rotator.next(&id), id);
     }
 
+    #[tokio::test]
+    async fn test_external_ip_enr_seq() {
+        let (_, mut service) = create_discv4().await;
+        assert!(service.external_ip_votes.is_some());
+
+        let seq = service.local_eip_868_enr.seq();
+        let current = service.local_node_record.address;
+
+        // setting the same address doesn't touch the ENR
+        service.set_external_ip_addr(current);
+        assert_eq!(service.local_eip_868_enr.seq(), seq);
+
+        let external: IpAddr = "203.0.113.7".parse().unwrap();
+        service.set_external_ip_addr(external);
+        assert_eq!(service.local_eip_868_enr.seq(), seq + 1);
+        assert_eq!(service.local_eip_868_enr.ip4(), Some("203.0.113.7".parse().unwrap()));
+        assert_eq!(service.handle().node_record().address, external);
+
+        service.set_external_ip_addr(external);
+        assert_eq!(service.local_eip_868_enr.seq(), seq + 1);
+    }
+
+    #[tokio::test]
+    async fn test_no_external_ip_votes_with_explicit_ip() {
+        let mut config = Discv4Config::default();
+        config.external_ip_resolver = Some(NatResolver::ExternalIp("203.0.113.7".parse().unwrap()));
+        let (_, service) = create_discv4_with_config(config).await;
+        assert!(service.external_ip_votes.is_none());
+    }
+
     #[tokio::test]
     async fn test_pending_ping() {
         let (_, mut service) = create_discv4().await;
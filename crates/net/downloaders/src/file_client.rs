@@ -11,7 +11,7 @@ use reth_network_p2p::{
 use reth_network_peers::PeerId;
 use reth_primitives::{
     BlockBody, BlockHash, BlockHashOrNumber, BlockNumber, BytesMut, Header, HeadersDirection,
-    SealedHeader, B256,
+    SealedBlock, SealedHeader, B256,
 };
 use std::{collections::HashMap, io, path::Path};
 use thiserror::Error;
@@ -89,6 +89,25 @@ impl FileClient {
         Ok(Self::from_reader(&reader[..], file_len).await?.0)
     }
 
+    /// Create a new file client from blocks that are already in memory, e.g. a generated chain.
+    pub fn from_blocks(blocks: impl IntoIterator<Item = SealedBlock>) -> Self {
+        let mut headers = HashMap::new();
+        let mut hash_to_number = HashMap::new();
+        let mut bodies = HashMap::new();
+
+        for SealedBlock { header, body, ommers, withdrawals, requests } in blocks {
+            let (header, block_hash) = header.split();
+            hash_to_number.insert(block_hash, header.number);
+            headers.insert(header.number, header);
+            bodies.insert(
+                block_hash,
+                BlockBody { transactions: body, ommers, withdrawals, requests },
+            );
+        }
+
+        Self { headers, hash_to_number, bodies }
+    }
+
     /// Get the tip hash of the chain.
     pub fn tip(&self) -> Option<B256> {
         self.headers.get(&self.max_block()?).map(|h| h.hash_slow())
@@ -163,6 +182,13 @@ impl FileClient {
         self.headers.values()
     }
 
+    /// Returns the block with the given number, if the client has its header and body.
+    pub fn sealed_block(&self, number: BlockNumber) -> Option<SealedBlock> {
+        let header = self.headers.get(&number)?.clone().seal_slow();
+        let body = self.bodies.get(&header.hash())?.clone();
+        Some(SealedBlock::new(header, body))
+    }
+
     /// Returns a mutable iterator over bodies in the client.
     ///
     /// Panics, if file client headers and bodies are not mapping 1-1.
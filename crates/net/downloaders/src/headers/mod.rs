@@ -4,6 +4,9 @@ pub mod reverse_headers;
 /// A header downloader that does nothing. Useful to build unwind-only pipelines.
 pub mod noop;
 
+/// Per-peer negotiation of the headers request size.
+pub mod request_size;
+
 /// A downloader implementation that spawns a downloader to a task
 pub mod task;
 
@@ -0,0 +1,222 @@
+//! Per-peer negotiation of the number of headers a peer serves in a single response.
+//!
+//! Some peers (or proxies in front of them) silently truncate large `GetBlockHeaders` responses.
+//! Such a response is otherwise valid, so instead of penalizing the peer over and over, the
+//! downloader tracks how many headers the peer is willing to return and accepts responses of that
+//! size, re-requesting the remainder.
+
+use reth_network_peers::PeerId;
+use std::collections::HashMap;
+
+/// The lowest request size a peer can negotiate.
+///
+/// Responses with fewer headers than this are always considered too short.
+pub const MIN_HEADERS_REQUEST_SIZE: u64 = 16;
+
+/// The number of consecutive short responses after which the request size of a peer is lowered.
+const SHORT_RESPONSES_BEFORE_DECREASE: u32 = 2;
+
+/// The number of consecutive full responses after which the request size of a peer is raised.
+const FULL_RESPONSES_BEFORE_INCREASE: u32 = 8;
+
+/// The maximum number of peers with a lowered request size that are tracked.
+const MAX_TRACKED_PEERS: usize = 512;
+
+/// How to handle a headers response that is shorter than requested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ShortResponseOutcome {
+    /// The response matches the negotiated size of the peer and can be used.
+    Accept,
+    /// The peer may be truncating its responses, but hasn't done so consistently yet, the request
+    /// should be retried without penalizing the peer.
+    Retry,
+    /// The response is too short.
+    Reject,
+}
+
+/// Header request statistics of a single peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerHeadersStats {
+    /// The number of headers the peer is expected to return in a response.
+    pub request_size: u64,
+    /// Consecutive responses shorter than the `request_size`.
+    short_responses: u32,
+    /// Consecutive full responses of at least `request_size` headers.
+    full_responses: u32,
+}
+
+impl PeerHeadersStats {
+    const fn new(request_size: u64) -> Self {
+        Self { request_size, short_responses: 0, full_responses: 0 }
+    }
+}
+
+/// Tracks the negotiated request size of all peers that returned short responses.
+///
+/// Peers without an entry are expected to serve the configured maximum.
+#[derive(Debug)]
+pub(crate) struct PeerRequestSizes {
+    /// The configured maximum request size.
+    max: u64,
+    /// Stats of the peers that returned short responses.
+    peers: HashMap<PeerId, PeerHeadersStats>,
+}
+
+impl PeerRequestSizes {
+    /// Creates a new tracker with the configured maximum request size.
+    pub(crate) fn new(max: u64) -> Self {
+        Self { max, peers: HashMap::new() }
+    }
+
+    /// Returns the lowest request size a peer can negotiate.
+    pub(crate) fn min(&self) -> u64 {
+        MIN_HEADERS_REQUEST_SIZE.min(self.max)
+    }
+
+    /// Returns the number of headers the peer is expected to return in a response.
+    pub(crate) fn request_size(&self, peer_id: &PeerId) -> u64 {
+        self.peers.get(peer_id).map_or(self.max, |stats| stats.request_size)
+    }
+
+    /// Returns the stats of the peer, if it ever returned a short response.
+    pub(crate) fn stats(&self, peer_id: &PeerId) -> Option<&PeerHeadersStats> {
+        self.peers.get(peer_id)
+    }
+
+    /// Records a response that contains all requested headers.
+    ///
+    /// If the peer keeps returning at least as many headers as negotiated, its request size is
+    /// slowly raised back towards the configured maximum.
+    pub(crate) fn on_full_response(&mut self, peer_id: PeerId, len: u64) {
+        let Some(stats) = self.peers.get_mut(&peer_id) else { return };
+        stats.short_responses = 0;
+        if len < stats.request_size {
+            return
+        }
+
+        stats.full_responses += 1;
+        if stats.full_responses >= FULL_RESPONSES_BEFORE_INCREASE {
+            stats.full_responses = 0;
+            stats.request_size =
+                (stats.request_size + (stats.request_size / 4).max(1)).min(self.max);
+        }
+
+        if stats.request_size == self.max {
+            self.peers.remove(&peer_id);
+        }
+    }
+
+    /// Records a response that contains fewer headers than requested and returns how it should be
+    /// handled.
+    ///
+    /// If the peer consistently returns short responses, its request size is lowered to the
+    /// length of the response, but never below [`MIN_HEADERS_REQUEST_SIZE`].
+    pub(crate) fn on_short_response(&mut self, peer_id: PeerId, len: u64) -> ShortResponseOutcome {
+        if len < self.min() {
+            return ShortResponseOutcome::Reject
+        }
+
+        if !self.peers.contains_key(&peer_id) && self.peers.len() >= MAX_TRACKED_PEERS {
+            if let Some(evicted) = self.peers.keys().next().copied() {
+                self.peers.remove(&evicted);
+            }
+        }
+        let stats = self.peers.entry(peer_id).or_insert_with(|| PeerHeadersStats::new(self.max));
+        stats.full_responses = 0;
+
+        if len >= stats.request_size {
+            stats.short_responses = 0;
+            return ShortResponseOutcome::Accept
+        }
+
+        stats.short_responses += 1;
+        if stats.short_responses < SHORT_RESPONSES_BEFORE_DECREASE {
+            return ShortResponseOutcome::Retry
+        }
+
+        stats.short_responses = 0;
+        stats.request_size = len;
+        ShortResponseOutcome::Accept
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_size_bounds() {
+        let peer = PeerId::random();
+
+        let mut sizes = PeerRequestSizes::new(1024);
+        assert_eq!(sizes.min(), MIN_HEADERS_REQUEST_SIZE);
+        assert_eq!(sizes.request_size(&peer), 1024);
+
+        // responses below the minimum are never accepted
+        for _ in 0..SHORT_RESPONSES_BEFORE_DECREASE {
+            assert_eq!(sizes.on_short_response(peer, 15), ShortResponseOutcome::Reject);
+        }
+        assert_eq!(sizes.request_size(&peer), 1024);
+
+        for _ in 0..SHORT_RESPONSES_BEFORE_DECREASE {
+            sizes.on_short_response(peer, MIN_HEADERS_REQUEST_SIZE);
+        }
+        assert_eq!(sizes.request_size(&peer), MIN_HEADERS_REQUEST_SIZE);
+
+        // the request size is never raised above the configured maximum
+        for _ in 0..FULL_RESPONSES_BEFORE_INCREASE * 100 {
+            sizes.on_full_response(peer, 1024);
+        }
+        assert_eq!(sizes.request_size(&peer), 1024);
+        assert!(sizes.stats(&peer).is_none());
+
+        // the minimum doesn't exceed a smaller configured maximum
+        let mut sizes = PeerRequestSizes::new(3);
+        assert_eq!(sizes.min(), 3);
+        assert_eq!(sizes.on_short_response(peer, 2), ShortResponseOutcome::Reject);
+    }
+
+    #[test]
+    fn request_size_adjustment() {
+        let peer = PeerId::random();
+        let mut sizes = PeerRequestSizes::new(1024);
+
+        // a single short response is retried, consistent ones lower the request size
+        assert_eq!(sizes.on_short_response(peer, 192), ShortResponseOutcome::Retry);
+        assert_eq!(sizes.request_size(&peer), 1024);
+        assert_eq!(sizes.on_short_response(peer, 192), ShortResponseOutcome::Accept);
+        assert_eq!(sizes.request_size(&peer), 192);
+
+        // responses matching the negotiated size are accepted right away
+        assert_eq!(sizes.on_short_response(peer, 192), ShortResponseOutcome::Accept);
+        assert_eq!(sizes.on_short_response(peer, 500), ShortResponseOutcome::Accept);
+        assert_eq!(sizes.request_size(&peer), 192);
+
+        // full responses smaller than the negotiated size don't count
+        for _ in 0..FULL_RESPONSES_BEFORE_INCREASE {
+            sizes.on_full_response(peer, 100);
+        }
+        assert_eq!(sizes.request_size(&peer), 192);
+
+        // consistent full responses raise the request size slowly
+        for _ in 0..FULL_RESPONSES_BEFORE_INCREASE - 1 {
+            sizes.on_full_response(peer, 192);
+        }
+        assert_eq!(sizes.request_size(&peer), 192);
+        sizes.on_full_response(peer, 192);
+        assert_eq!(sizes.request_size(&peer), 240);
+
+        // a short response interrupts the full responses streak
+        for _ in 0..FULL_RESPONSES_BEFORE_INCREASE - 1 {
+            sizes.on_full_response(peer, 240);
+        }
+        sizes.on_short_response(peer, 240);
+        sizes.on_full_response(peer, 240);
+        assert_eq!(sizes.request_size(&peer), 240);
+
+        // the request size is lowered again if the peer returns even less
+        assert_eq!(sizes.on_short_response(peer, 64), ShortResponseOutcome::Retry);
+        assert_eq!(sizes.on_short_response(peer, 64), ShortResponseOutcome::Accept);
+        assert_eq!(sizes.request_size(&peer), 64);
+    }
+}
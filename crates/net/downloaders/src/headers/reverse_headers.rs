@@ -1,6 +1,9 @@
 //! A headers downloader that can handle multiple requests concurrently.
 
-use super::task::TaskDownloader;
+use super::{
+    request_size::{PeerHeadersStats, PeerRequestSizes, ShortResponseOutcome},
+    task::TaskDownloader,
+};
 use crate::metrics::HeaderDownloaderMetrics;
 use futures::{stream::Stream, FutureExt};
 use futures_util::{stream::FuturesUnordered, StreamExt};
@@ -81,6 +84,10 @@ pub struct ReverseHeadersDownloader<H: HeadersClient> {
     next_chain_tip_block_number: u64,
     /// The batch size per one request
     request_limit: u64,
+    /// The number of headers each peer is expected to return in a response.
+    ///
+    /// This is at most the `request_limit`.
+    peer_request_sizes: PeerRequestSizes,
     /// Minimum amount of requests to handle concurrently.
     min_concurrent_requests: usize,
     /// Maximum amount of requests to handle concurrently.
@@ -102,6 +109,8 @@ pub struct ReverseHeadersDownloader<H: HeadersClient> {
     ///
     /// Note: headers are sorted from high to low
     queued_validated_headers: Vec<SealedHeader>,
+    /// The peer that must not serve requests, see [`HeaderDownloader::exclude_peer`].
+    excluded_peer: Option<PeerId>,
     /// Header downloader metrics.
     metrics: HeaderDownloaderMetrics,
 }
@@ -117,6 +126,19 @@ where
         ReverseHeadersDownloaderBuilder::default()
     }
 
+    /// Returns the number of headers the given peer is expected to return in a response.
+    ///
+    /// This starts at the configured request limit and is lowered if the peer consistently
+    /// truncates its responses.
+    pub fn peer_request_size(&self, peer_id: &PeerId) -> u64 {
+        self.peer_request_sizes.request_size(peer_id)
+    }
+
+    /// Returns the header request stats of the given peer, if it ever returned a short response.
+    pub fn peer_headers_stats(&self, peer_id: &PeerId) -> Option<&PeerHeadersStats> {
+        self.peer_request_sizes.stats(peer_id)
+    }
+
     /// Returns the block number the local node is at.
     #[inline]
     fn local_block_number(&self) -> Option<BlockNumber> {
@@ -249,6 +271,7 @@ where
     ) -> Result<(), ReverseHeadersDownloaderError> {
         let mut validated = Vec::with_capacity(headers.len());
 
+        // the hashes of headers received from peers can't be trusted, so they're recomputed
         let sealed_headers = headers.into_par_iter().map(|h| h.seal_slow()).collect::<Vec<_>>();
         for parent in sealed_headers {
             // Validate that the header is the parent header of the last validated header.
@@ -298,6 +321,7 @@ where
                     local_head: Box::new(head.clone()),
                     header: Box::new(last_header.clone()),
                     error: Box::new(error),
+                    peer_id: Some(peer_id),
                 }
                 .into())
             }
@@ -432,7 +456,7 @@ where
         response: HeadersRequestOutcome,
     ) -> Result<(), ReverseHeadersDownloaderError> {
         let requested_block_number = response.block_number();
-        let HeadersRequestOutcome { request, outcome } = response;
+        let HeadersRequestOutcome { mut request, outcome } = response;
 
         match outcome {
             Ok(res) => {
@@ -452,17 +476,36 @@ where
                     .into())
                 }
 
-                if (headers.len() as u64) != request.limit {
-                    return Err(HeadersResponseError {
-                        peer_id: Some(peer_id),
-                        error: DownloadError::HeadersResponseTooShort(GotExpected {
-                            got: headers.len() as u64,
-                            expected: request.limit,
-                        }),
-                        request,
+                let len = headers.len() as u64;
+                let short_response = if len == request.limit {
+                    self.peer_request_sizes.on_full_response(peer_id, len);
+                    None
+                } else {
+                    let outcome = if len > request.limit {
+                        ShortResponseOutcome::Reject
+                    } else {
+                        self.peer_request_sizes.on_short_response(peer_id, len)
+                    };
+                    match outcome {
+                        ShortResponseOutcome::Accept => Some(len),
+                        ShortResponseOutcome::Retry => {
+                            trace!(target: "downloaders::headers", ?peer_id, len, limit=request.limit, "Retrying short headers response");
+                            self.submit_request(request, Priority::High);
+                            return Ok(())
+                        }
+                        ShortResponseOutcome::Reject => {
+                            return Err(HeadersResponseError {
+                                peer_id: Some(peer_id),
+                                error: DownloadError::HeadersResponseTooShort(GotExpected {
+                                    got: len,
+                                    expected: request.limit,
+                                }),
+                                request,
+                            }
+                            .into())
+                        }
                     }
-                    .into())
-                }
+                };
 
                 // sort headers from highest to lowest block number
                 headers.sort_unstable_by_key(|h| Reverse(h.number));
@@ -484,6 +527,21 @@ where
                     .into())
                 }
 
+                // the response matches the negotiated size of the peer, request the remaining
+                // headers separately
+                if let Some(len) = short_response {
+                    trace!(target: "downloaders::headers", ?peer_id, len, limit=request.limit, "Accepted short headers response");
+                    self.submit_request(
+                        HeadersRequest {
+                            start: (requested_block_number - len).into(),
+                            limit: request.limit - len,
+                            direction: request.direction,
+                        },
+                        Priority::High,
+                    );
+                    request.limit = len;
+                }
+
                 // check if the response is the next expected
                 if highest.number == self.next_chain_tip_block_number {
                     // is next response, validate it
@@ -581,10 +639,11 @@ where
         priority: Priority,
     ) -> HeadersRequestFuture<H::Output> {
         let client = Arc::clone(&self.client);
-        HeadersRequestFuture {
-            request: Some(request.clone()),
-            fut: client.get_headers_with_priority(request, priority),
-        }
+        let fut = match self.excluded_peer {
+            Some(peer_id) => client.get_headers_excluding_peer(request.clone(), priority, peer_id),
+            None => client.get_headers_with_priority(request.clone(), priority),
+        };
+        HeadersRequestFuture { request: Some(request), fut }
     }
 
     /// Validate whether the header is valid in relation to it's parent
@@ -652,6 +711,17 @@ where
     H: HeadersClient + 'static,
 {
     fn update_local_head(&mut self, head: SealedHeader) {
+        // if the local head moved backwards, for example because it was unwound, the headers below
+        // the previous local head haven't been requested yet, so we start over from the sync target
+        if self.local_block_number().map_or(false, |number| head.number < number) {
+            if let Some(target_block_number) = self.sync_target.as_ref().and_then(|t| t.number()) {
+                trace!(target: "downloaders::headers", new=?head.number, "Local head moved backwards, restarting download");
+                self.next_request_block_number = target_block_number;
+                self.next_chain_tip_block_number = target_block_number;
+                self.clear();
+            }
+        }
+
         // ensure we're only yielding headers that are in range and follow the current local head.
         while self
             .queued_validated_headers
@@ -731,6 +801,13 @@ where
     fn set_batch_size(&mut self, batch_size: usize) {
         self.stream_batch_size = batch_size;
     }
+
+    fn exclude_peer(&mut self, peer_id: Option<PeerId>) {
+        if self.excluded_peer != peer_id {
+            trace!(target: "downloaders::headers", ?peer_id, "Update excluded peer");
+            self.excluded_peer = peer_id;
+        }
+    }
 }
 
 impl<H> Stream for ReverseHeadersDownloader<H>
@@ -1173,6 +1250,7 @@ impl ReverseHeadersDownloaderBuilder {
             next_chain_tip_block_number: 0,
             lowest_validated_header: None,
             request_limit,
+            peer_request_sizes: PeerRequestSizes::new(request_limit),
             min_concurrent_requests,
             max_concurrent_requests,
             stream_batch_size,
@@ -1181,6 +1259,7 @@ impl ReverseHeadersDownloaderBuilder {
             in_progress_queue: Default::default(),
             buffered_responses: Default::default(),
             queued_validated_headers: Default::default(),
+            excluded_peer: None,
             metrics: Default::default(),
         }
     }
@@ -1211,7 +1290,66 @@ mod tests {
     use crate::headers::test_utils::child_header;
     use assert_matches::assert_matches;
     use reth_consensus::test_utils::TestConsensus;
-    use reth_network_p2p::test_utils::TestHeadersClient;
+    use reth_network_p2p::{download::DownloadClient, test_utils::TestHeadersClient};
+    use reth_network_peers::WithPeerId;
+    use std::sync::{
+        atomic::{self, AtomicUsize},
+        Mutex,
+    };
+
+    /// A client for a single peer that returns at most `max_response_len` headers per response.
+    #[derive(Debug)]
+    struct TruncatingHeadersClient {
+        peer_id: PeerId,
+        /// The chain, indexed by block number.
+        headers: Vec<SealedHeader>,
+        max_response_len: u64,
+        bad_messages: AtomicUsize,
+        /// The peers that requests asked to exclude.
+        excluded_peers: Mutex<Vec<PeerId>>,
+    }
+
+    impl DownloadClient for TruncatingHeadersClient {
+        fn report_bad_message(&self, _peer_id: PeerId) {
+            self.bad_messages.fetch_add(1, atomic::Ordering::SeqCst);
+        }
+
+        fn num_connected_peers(&self) -> usize {
+            1
+        }
+    }
+
+    impl HeadersClient for TruncatingHeadersClient {
+        type Output = futures::future::Ready<PeerRequestResult<Vec<Header>>>;
+
+        fn get_headers_with_priority(
+            &self,
+            request: HeadersRequest,
+            _priority: Priority,
+        ) -> Self::Output {
+            let start = match request.start {
+                BlockHashOrNumber::Number(number) => number,
+                BlockHashOrNumber::Hash(hash) => {
+                    self.headers.iter().find(|h| h.hash() == hash).expect("known header").number
+                }
+            };
+            let headers = (0..request.limit.min(self.max_response_len))
+                .map_while(|offset| start.checked_sub(offset))
+                .map(|number| self.headers[number as usize].as_ref().clone())
+                .collect();
+            futures::future::ready(Ok(WithPeerId::from((self.peer_id, headers))))
+        }
+
+        fn get_headers_excluding_peer(
+            &self,
+            request: HeadersRequest,
+            priority: Priority,
+            excluded_peer: PeerId,
+        ) -> Self::Output {
+            self.excluded_peers.lock().unwrap().push(excluded_peer);
+            self.get_headers_with_priority(request, priority)
+        }
+    }
 
     /// Tests that `replace_number` works the same way as `Option::replace`
     #[test]
@@ -1291,7 +1429,7 @@ mod tests {
         assert!(downloader.sync_target_request.is_some());
 
         downloader.sync_target_request.take();
-        let target = SyncTarget::Gap(Header::default().seal(B256::random()));
+        let target = SyncTarget::Gap(Header::default().seal_slow());
         downloader.update_sync_target(target);
         assert!(downloader.sync_target_request.is_none());
         assert_matches!(
@@ -1315,7 +1453,7 @@ mod tests {
         downloader.queued_validated_headers.push(header.clone());
         let mut next = header.as_ref().clone();
         next.number += 1;
-        downloader.update_local_head(next.seal(B256::random()));
+        downloader.update_local_head(next.seal_slow());
         assert!(downloader.queued_validated_headers.is_empty());
     }
 
@@ -1504,4 +1642,74 @@ mod tests {
 
         assert!(downloader.next().await.is_none());
     }
+
+    #[tokio::test]
+    async fn download_from_peer_truncating_responses() {
+        reth_tracing::init_test_tracing();
+
+        let mut headers = vec![SealedHeader::default()];
+        for _ in 0..2_000 {
+            headers.push(child_header(headers.last().unwrap()));
+        }
+        let client = Arc::new(TruncatingHeadersClient {
+            peer_id: PeerId::random(),
+            headers: headers.clone(),
+            max_response_len: 192,
+            bad_messages: AtomicUsize::new(0),
+            excluded_peers: Default::default(),
+        });
+
+        let mut downloader = ReverseHeadersDownloaderBuilder::default()
+            .request_limit(1024)
+            .build(Arc::clone(&client), Arc::new(TestConsensus::default()));
+        downloader.update_local_head(headers[0].clone());
+        downloader.update_sync_target(SyncTarget::Tip(headers.last().unwrap().hash()));
+
+        let mut downloaded = Vec::new();
+        while let Some(batch) = downloader.next().await {
+            downloaded.extend(batch.unwrap());
+        }
+
+        // all headers above the local head are downloaded, from the tip down
+        let expected = headers.into_iter().skip(1).rev().collect::<Vec<_>>();
+        assert_eq!(downloaded, expected);
+        // the peer was never penalized for its short responses
+        assert_eq!(client.bad_messages.load(atomic::Ordering::SeqCst), 0);
+        assert_eq!(downloader.peer_request_size(&client.peer_id), 192);
+    }
+
+    /// Tests that an excluded peer is passed along with every request.
+    #[tokio::test]
+    async fn excluded_peer_is_passed_to_requests() {
+        let mut headers = vec![SealedHeader::default()];
+        for _ in 0..10 {
+            headers.push(child_header(headers.last().unwrap()));
+        }
+
+        let client = Arc::new(TruncatingHeadersClient {
+            peer_id: PeerId::random(),
+            headers: headers.clone(),
+            max_response_len: 4,
+            bad_messages: AtomicUsize::new(0),
+            excluded_peers: Default::default(),
+        });
+        let excluded = PeerId::random();
+
+        let mut downloader = ReverseHeadersDownloaderBuilder::default()
+            .request_limit(4)
+            .build(Arc::clone(&client), Arc::new(TestConsensus::default()));
+        downloader.exclude_peer(Some(excluded));
+        downloader.update_local_head(headers[0].clone());
+        downloader.update_sync_target(SyncTarget::Tip(headers.last().unwrap().hash()));
+
+        let mut downloaded = Vec::new();
+        while let Some(batch) = downloader.next().await {
+            downloaded.extend(batch.unwrap());
+        }
+        assert_eq!(downloaded.len(), 10);
+
+        let requests = client.excluded_peers.lock().unwrap();
+        assert!(!requests.is_empty());
+        assert!(requests.iter().all(|peer| *peer == excluded));
+    }
 }
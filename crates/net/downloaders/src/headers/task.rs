@@ -5,6 +5,7 @@ use reth_network_p2p::headers::{
     downloader::{HeaderDownloader, SyncTarget},
     error::HeadersDownloaderResult,
 };
+use reth_network_peers::PeerId;
 use reth_primitives::SealedHeader;
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
 use std::{
@@ -97,6 +98,10 @@ impl HeaderDownloader for TaskDownloader {
     fn set_batch_size(&mut self, limit: usize) {
         let _ = self.to_downloader.send(DownloaderUpdates::SetBatchSize(limit));
     }
+
+    fn exclude_peer(&mut self, peer_id: Option<PeerId>) {
+        let _ = self.to_downloader.send(DownloaderUpdates::ExcludePeer(peer_id));
+    }
 }
 
 impl Stream for TaskDownloader {
@@ -142,6 +147,9 @@ impl<T: HeaderDownloader> Future for SpawnedDownloader<T> {
                         DownloaderUpdates::SetBatchSize(limit) => {
                             this.downloader.set_batch_size(limit);
                         }
+                        DownloaderUpdates::ExcludePeer(peer_id) => {
+                            this.downloader.exclude_peer(peer_id);
+                        }
                     },
                 }
             }
@@ -175,6 +183,7 @@ enum DownloaderUpdates {
     UpdateLocalHead(SealedHeader),
     UpdateSyncTarget(SyncTarget),
     SetBatchSize(usize),
+    ExcludePeer(Option<PeerId>),
 }
 
 #[cfg(test)]
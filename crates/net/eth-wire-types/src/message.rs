@@ -31,7 +31,33 @@ pub enum MessageError {
     Invalid(EthVersion, EthMessageID),
     /// Thrown when rlp decoding a message message failed.
     #[error("RLP error: {0}")]
-    RlpError(#[from] alloy_rlp::Error),
+    RlpError(alloy_rlp::Error),
+    /// Thrown when the message is not encoded canonically, e.g. an integer with leading zeros or
+    /// a string header that is longer than necessary.
+    #[error("non-canonical RLP: {0}")]
+    NonCanonical(alloy_rlp::Error),
+    /// Thrown when bytes are left over after decoding the message.
+    #[error("{0} trailing bytes after message")]
+    TrailingBytes(usize),
+}
+
+impl MessageError {
+    /// Returns true if the message was rejected by the strict decoding of network messages, see
+    /// [`ProtocolMessage::decode_message`].
+    pub const fn is_non_canonical(&self) -> bool {
+        matches!(self, Self::NonCanonical(_) | Self::TrailingBytes(_))
+    }
+}
+
+impl From<alloy_rlp::Error> for MessageError {
+    fn from(err: alloy_rlp::Error) -> Self {
+        match err {
+            alloy_rlp::Error::LeadingZero |
+            alloy_rlp::Error::NonCanonicalSingleByte |
+            alloy_rlp::Error::NonCanonicalSize => Self::NonCanonical(err),
+            err => Self::RlpError(err),
+        }
+    }
 }
 
 /// An `eth` protocol message, containing a message ID and payload.
@@ -46,6 +72,10 @@ pub struct ProtocolMessage {
 
 impl ProtocolMessage {
     /// Create a new `ProtocolMessage` from a message type and message rlp bytes.
+    ///
+    /// The decoding is strict, as messages received from the network are untrusted: the message
+    /// must consume the entire buffer and integers must be encoded canonically, without leading
+    /// zeros. Types decoded from the database are not affected by this.
     pub fn decode_message(version: EthVersion, buf: &mut &[u8]) -> Result<Self, MessageError> {
         let message_type = EthMessageID::decode(buf)?;
 
@@ -114,6 +144,9 @@ impl ProtocolMessage {
                 EthMessage::Receipts(request_pair)
             }
         };
+        if !buf.is_empty() {
+            return Err(MessageError::TrailingBytes(buf.len()))
+        }
         Ok(Self { message_type, message })
     }
 }
@@ -496,10 +529,11 @@ where
 mod tests {
     use super::MessageError;
     use crate::{
-        message::RequestPair, EthMessage, EthMessageID, GetNodeData, NodeData, ProtocolMessage,
+        message::RequestPair, EthMessage, EthMessageID, EthVersion, GetNodeData, NodeData,
+        ProtocolMessage, Status,
     };
-    use alloy_rlp::{Decodable, Encodable, Error};
-    use reth_primitives::hex;
+    use alloy_rlp::{Decodable, Encodable, Error, Header};
+    use reth_primitives::{hex, Address, Bytes, U256};
 
     fn encode<T: Encodable>(value: T) -> Vec<u8> {
         let mut buf = vec![];
@@ -507,6 +541,34 @@ mod tests {
         buf
     }
 
+    /// Encodes the already encoded items as a list.
+    fn list(items: &[&[u8]]) -> Vec<u8> {
+        let mut buf = vec![];
+        Header { list: true, payload_length: items.iter().map(|item| item.len()).sum() }
+            .encode(&mut buf);
+        for item in items {
+            buf.extend_from_slice(item);
+        }
+        buf
+    }
+
+    /// Prefixes the payload with the message id.
+    fn message(id: EthMessageID, payload: &[u8]) -> Vec<u8> {
+        let mut buf = vec![id as u8];
+        buf.extend_from_slice(payload);
+        buf
+    }
+
+    /// Appends a trailing empty string to the message.
+    fn with_trailing_byte(mut message: Vec<u8>) -> Vec<u8> {
+        message.push(0x80);
+        message
+    }
+
+    fn decode(message: &[u8]) -> Result<ProtocolMessage, MessageError> {
+        ProtocolMessage::decode_message(EthVersion::Eth68, &mut &message[..])
+    }
+
     #[test]
     fn test_removed_message_at_eth67() {
         let get_node_data =
@@ -568,4 +630,107 @@ mod tests {
         let result = RequestPair::<Vec<u8>>::decode(&mut &*raw_pair);
         assert!(matches!(result, Err(Error::UnexpectedLength)));
     }
+
+    #[test]
+    fn strict_decoding_status() {
+        let status = Status::default();
+        let status_with_chain = |chain: &[u8]| {
+            let status = list(&[
+                &encode(status.version),
+                chain,
+                &encode(status.total_difficulty),
+                &encode(status.blockhash),
+                &encode(status.genesis),
+                &encode(status.forkid),
+            ]);
+            message(EthMessageID::Status, &status)
+        };
+
+        let canonical = status_with_chain(&encode(status.chain));
+        assert_eq!(canonical, encode(ProtocolMessage::from(EthMessage::Status(status))));
+        assert!(decode(&canonical).is_ok());
+        assert!(matches!(
+            decode(&with_trailing_byte(canonical)),
+            Err(MessageError::TrailingBytes(1))
+        ));
+
+        // mainnet chain id as a single byte string
+        assert!(matches!(
+            decode(&status_with_chain(&hex!("8101"))),
+            Err(MessageError::NonCanonical(Error::NonCanonicalSingleByte))
+        ));
+        // mainnet chain id with a leading zero
+        assert!(matches!(
+            decode(&status_with_chain(&hex!("820001"))),
+            Err(MessageError::NonCanonical(Error::LeadingZero))
+        ));
+    }
+
+    #[test]
+    fn strict_decoding_get_block_headers() {
+        // request 1111: 5 headers starting at block 9999, skipping 5, not reversed
+        let canonical = message(EthMessageID::GetBlockHeaders, &hex!("ca820457c682270f050580"));
+        assert!(decode(&canonical).is_ok());
+        assert!(matches!(
+            decode(&with_trailing_byte(canonical)),
+            Err(MessageError::TrailingBytes(1))
+        ));
+
+        // request id with a leading zero
+        let leading_zero =
+            message(EthMessageID::GetBlockHeaders, &hex!("cb83000457c682270f050580"));
+        assert!(matches!(
+            decode(&leading_zero),
+            Err(MessageError::NonCanonical(Error::LeadingZero))
+        ));
+
+        // limit as a single byte string
+        let single_byte = message(EthMessageID::GetBlockHeaders, &hex!("cb820457c782270f81050580"));
+        assert!(matches!(
+            decode(&single_byte),
+            Err(MessageError::NonCanonical(Error::NonCanonicalSingleByte))
+        ));
+    }
+
+    #[test]
+    fn strict_decoding_transactions() {
+        // a legacy transaction with the given encoded nonce, signed with v = 27 and r = s = 1
+        let transactions = |nonce: &[u8]| {
+            let transaction = list(&[
+                nonce,
+                &encode(1u64),
+                &encode(21_000u64),
+                &encode(Address::ZERO),
+                &encode(U256::ZERO),
+                &encode(Bytes::new()),
+                &encode(27u64),
+                &encode(U256::from(1)),
+                &encode(U256::from(1)),
+            ]);
+            message(EthMessageID::Transactions, &list(&[&transaction]))
+        };
+
+        let canonical = transactions(&encode(1u64));
+        assert!(decode(&canonical).is_ok());
+        assert!(matches!(
+            decode(&with_trailing_byte(canonical)),
+            Err(MessageError::TrailingBytes(1))
+        ));
+
+        assert!(matches!(
+            decode(&transactions(&hex!("8101"))),
+            Err(MessageError::NonCanonical(Error::NonCanonicalSingleByte))
+        ));
+        assert!(matches!(
+            decode(&transactions(&hex!("820001"))),
+            Err(MessageError::NonCanonical(Error::LeadingZero))
+        ));
+
+        // a list header in the long form for a short list
+        let long_header = message(EthMessageID::Transactions, &hex!("f803808080"));
+        assert!(matches!(
+            decode(&long_header),
+            Err(MessageError::NonCanonical(Error::NonCanonicalSize))
+        ));
+    }
 }
@@ -0,0 +1,562 @@
+//! Implements the `snap/1` protocol message types:
+//! <https://github.com/ethereum/devp2p/blob/master/caps/snap.md>
+//!
+//! Unlike the `eth` protocol, every `snap` request and response carries its request id as the
+//! first field of the message itself.
+
+use crate::message::MessageError;
+use alloy_rlp::{Decodable, Encodable, Header, RlpDecodable, RlpEncodable};
+use reth_codecs_derive::derive_arbitrary;
+use reth_primitives::{
+    bytes::{Buf, BufMut},
+    constants::{EMPTY_ROOT_HASH, KECCAK_EMPTY},
+    Bytes, B256, U256,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Requests an unknown number of accounts from the account trie with the given root, starting at
+/// [`starting_hash`](#structfield.starting_hash).
+///
+/// The response stops at the first account past [`limit_hash`](#structfield.limit_hash) or once
+/// [`response_bytes`](#structfield.response_bytes) is exceeded.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetAccountRange {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The root of the account trie to serve.
+    pub root_hash: B256,
+    /// The hash of the first account to retrieve.
+    pub starting_hash: B256,
+    /// The hash after which to stop serving accounts.
+    pub limit_hash: B256,
+    /// The soft limit of the response size in bytes.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetAccountRange`], containing consecutive accounts ordered by hash and the
+/// trie nodes proving the first and the last account of the range.
+///
+/// An empty response without proof means the peer does not serve the requested root.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AccountRange {
+    /// The id of the request this is a response to.
+    pub request_id: u64,
+    /// The accounts of the range.
+    pub accounts: Vec<AccountData>,
+    /// The trie nodes proving the boundaries of the range.
+    pub proof: Vec<Bytes>,
+}
+
+/// A single account of an [`AccountRange`].
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AccountData {
+    /// The hash of the account address.
+    pub hash: B256,
+    /// The account in slim format.
+    pub body: SnapAccount,
+}
+
+/// An account as transferred by the `snap` protocol.
+///
+/// The account is encoded in the slim format, which replaces the empty storage root and the empty
+/// code hash with empty byte strings.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SnapAccount {
+    /// The account nonce.
+    pub nonce: u64,
+    /// The account balance.
+    pub balance: U256,
+    /// The root of the account storage trie.
+    pub storage_root: B256,
+    /// The hash of the account bytecode.
+    pub code_hash: B256,
+}
+
+impl SnapAccount {
+    /// Returns the storage root as encoded in the slim format.
+    fn slim_storage_root(&self) -> &[u8] {
+        if self.storage_root == EMPTY_ROOT_HASH {
+            &[]
+        } else {
+            self.storage_root.as_slice()
+        }
+    }
+
+    /// Returns the code hash as encoded in the slim format.
+    fn slim_code_hash(&self) -> &[u8] {
+        if self.code_hash == KECCAK_EMPTY {
+            &[]
+        } else {
+            self.code_hash.as_slice()
+        }
+    }
+
+    fn payload_length(&self) -> usize {
+        self.nonce.length() +
+            self.balance.length() +
+            self.slim_storage_root().length() +
+            self.slim_code_hash().length()
+    }
+}
+
+impl Encodable for SnapAccount {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        self.nonce.encode(out);
+        self.balance.encode(out);
+        self.slim_storage_root().encode(out);
+        self.slim_code_hash().encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for SnapAccount {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+
+        let initial_length = buf.len();
+        let nonce = u64::decode(buf)?;
+        let balance = U256::decode(buf)?;
+        let storage_root = decode_slim_hash(buf, EMPTY_ROOT_HASH)?;
+        let code_hash = decode_slim_hash(buf, KECCAK_EMPTY)?;
+
+        if initial_length - buf.len() != header.payload_length {
+            return Err(alloy_rlp::Error::UnexpectedLength)
+        }
+
+        Ok(Self { nonce, balance, storage_root, code_hash })
+    }
+}
+
+/// Decodes a hash of the slim account format, where an empty byte string stands for `empty`.
+fn decode_slim_hash(buf: &mut &[u8], empty: B256) -> alloy_rlp::Result<B256> {
+    let bytes = Header::decode_bytes(buf, false)?;
+    match bytes.len() {
+        0 => Ok(empty),
+        32 => Ok(B256::from_slice(bytes)),
+        _ => Err(alloy_rlp::Error::UnexpectedLength),
+    }
+}
+
+/// Requests the storage slots of the given accounts from the state trie with the given root.
+///
+/// The [`starting_hash`](#structfield.starting_hash) and [`limit_hash`](#structfield.limit_hash)
+/// only apply to the first account, and are encoded as byte strings which may be empty.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetStorageRanges {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The root of the state trie to serve.
+    pub root_hash: B256,
+    /// The hashes of the accounts to retrieve the storage of.
+    pub account_hashes: Vec<B256>,
+    /// The hash of the first storage slot to retrieve.
+    pub starting_hash: Bytes,
+    /// The hash after which to stop serving storage slots.
+    pub limit_hash: Bytes,
+    /// The soft limit of the response size in bytes.
+    pub response_bytes: u64,
+}
+
+impl GetStorageRanges {
+    /// Returns the hash of the first requested slot, an empty origin starts at the first slot.
+    pub fn origin(&self) -> B256 {
+        bytes_to_hash(&self.starting_hash)
+    }
+
+    /// Returns the hash of the last requested slot, an empty limit serves up to the last slot.
+    pub fn limit(&self) -> B256 {
+        if self.limit_hash.is_empty() {
+            B256::repeat_byte(0xff)
+        } else {
+            bytes_to_hash(&self.limit_hash)
+        }
+    }
+}
+
+/// Converts the bytes to a hash, left-padding them or keeping the last 32 bytes.
+fn bytes_to_hash(bytes: &[u8]) -> B256 {
+    let bytes = &bytes[bytes.len().saturating_sub(32)..];
+    B256::left_padding_from(bytes)
+}
+
+/// The response to [`GetStorageRanges`], containing the storage slots of consecutive requested
+/// accounts.
+///
+/// If the slots of the last account are incomplete, the proof contains the trie nodes proving the
+/// boundaries of its range.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StorageRanges {
+    /// The id of the request this is a response to.
+    pub request_id: u64,
+    /// The storage slots of each served account.
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(
+            strategy = "proptest::collection::vec(proptest::collection::vec(proptest::arbitrary::any::<StorageData>(), 0..=10), 0..=5)"
+        )
+    )]
+    pub slots: Vec<Vec<StorageData>>,
+    /// The trie nodes proving the boundaries of the last served range.
+    pub proof: Vec<Bytes>,
+}
+
+/// A single storage slot of [`StorageRanges`].
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StorageData {
+    /// The hash of the storage slot.
+    pub hash: B256,
+    /// The RLP encoded value of the storage slot.
+    pub data: Bytes,
+}
+
+/// Requests contract bytecodes by their hashes.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetByteCodes {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The hashes of the bytecodes to retrieve.
+    pub hashes: Vec<B256>,
+    /// The soft limit of the response size in bytes.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetByteCodes`], containing the requested bytecodes in request order.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ByteCodes {
+    /// The id of the request this is a response to.
+    pub request_id: u64,
+    /// The requested bytecodes.
+    pub codes: Vec<Bytes>,
+}
+
+/// Requests trie nodes of the state trie with the given root by their paths.
+///
+/// Each path set starts with the path of an account trie node, optionally followed by paths of
+/// nodes in the storage trie of that account.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetTrieNodes {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The root of the state trie to serve.
+    pub root_hash: B256,
+    /// The sets of paths of the nodes to retrieve.
+    #[cfg_attr(
+        any(test, feature = "arbitrary"),
+        proptest(
+            strategy = "proptest::collection::vec(proptest::collection::vec(proptest::arbitrary::any::<Bytes>(), 0..=5), 0..=5)"
+        )
+    )]
+    pub paths: Vec<Vec<Bytes>>,
+    /// The soft limit of the response size in bytes.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetTrieNodes`], containing the requested trie nodes in request order.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TrieNodes {
+    /// The id of the request this is a response to.
+    pub request_id: u64,
+    /// The requested trie nodes.
+    pub nodes: Vec<Bytes>,
+}
+
+/// A `snap` protocol message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SnapMessage {
+    /// Represents a `GetAccountRange` request.
+    GetAccountRange(GetAccountRange),
+    /// Represents an `AccountRange` response.
+    AccountRange(AccountRange),
+    /// Represents a `GetStorageRanges` request.
+    GetStorageRanges(GetStorageRanges),
+    /// Represents a `StorageRanges` response.
+    StorageRanges(StorageRanges),
+    /// Represents a `GetByteCodes` request.
+    GetByteCodes(GetByteCodes),
+    /// Represents a `ByteCodes` response.
+    ByteCodes(ByteCodes),
+    /// Represents a `GetTrieNodes` request.
+    GetTrieNodes(GetTrieNodes),
+    /// Represents a `TrieNodes` response.
+    TrieNodes(TrieNodes),
+}
+
+impl SnapMessage {
+    /// Returns the message's ID.
+    pub const fn message_id(&self) -> SnapMessageID {
+        match self {
+            Self::GetAccountRange(_) => SnapMessageID::GetAccountRange,
+            Self::AccountRange(_) => SnapMessageID::AccountRange,
+            Self::GetStorageRanges(_) => SnapMessageID::GetStorageRanges,
+            Self::StorageRanges(_) => SnapMessageID::StorageRanges,
+            Self::GetByteCodes(_) => SnapMessageID::GetByteCodes,
+            Self::ByteCodes(_) => SnapMessageID::ByteCodes,
+            Self::GetTrieNodes(_) => SnapMessageID::GetTrieNodes,
+            Self::TrieNodes(_) => SnapMessageID::TrieNodes,
+        }
+    }
+
+    /// Returns the id of the request or response.
+    pub const fn request_id(&self) -> u64 {
+        match self {
+            Self::GetAccountRange(msg) => msg.request_id,
+            Self::AccountRange(msg) => msg.request_id,
+            Self::GetStorageRanges(msg) => msg.request_id,
+            Self::StorageRanges(msg) => msg.request_id,
+            Self::GetByteCodes(msg) => msg.request_id,
+            Self::ByteCodes(msg) => msg.request_id,
+            Self::GetTrieNodes(msg) => msg.request_id,
+            Self::TrieNodes(msg) => msg.request_id,
+        }
+    }
+
+    /// Decodes a message prefixed with its [`SnapMessageID`].
+    ///
+    /// Like [`ProtocolMessage::decode_message`](crate::ProtocolMessage::decode_message), the
+    /// decoding is strict: the message must consume the entire buffer and be encoded canonically.
+    pub fn decode_message(buf: &mut &[u8]) -> Result<Self, MessageError> {
+        let message = match SnapMessageID::decode(buf)? {
+            SnapMessageID::GetAccountRange => Self::GetAccountRange(Decodable::decode(buf)?),
+            SnapMessageID::AccountRange => Self::AccountRange(Decodable::decode(buf)?),
+            SnapMessageID::GetStorageRanges => Self::GetStorageRanges(Decodable::decode(buf)?),
+            SnapMessageID::StorageRanges => Self::StorageRanges(Decodable::decode(buf)?),
+            SnapMessageID::GetByteCodes => Self::GetByteCodes(Decodable::decode(buf)?),
+            SnapMessageID::ByteCodes => Self::ByteCodes(Decodable::decode(buf)?),
+            SnapMessageID::GetTrieNodes => Self::GetTrieNodes(Decodable::decode(buf)?),
+            SnapMessageID::TrieNodes => Self::TrieNodes(Decodable::decode(buf)?),
+        };
+        if !buf.is_empty() {
+            return Err(MessageError::TrailingBytes(buf.len()))
+        }
+        Ok(message)
+    }
+
+    /// Encodes the message prefixed with its [`SnapMessageID`].
+    pub fn encode_message(&self, out: &mut dyn BufMut) {
+        self.message_id().encode(out);
+        match self {
+            Self::GetAccountRange(msg) => msg.encode(out),
+            Self::AccountRange(msg) => msg.encode(out),
+            Self::GetStorageRanges(msg) => msg.encode(out),
+            Self::StorageRanges(msg) => msg.encode(out),
+            Self::GetByteCodes(msg) => msg.encode(out),
+            Self::ByteCodes(msg) => msg.encode(out),
+            Self::GetTrieNodes(msg) => msg.encode(out),
+            Self::TrieNodes(msg) => msg.encode(out),
+        }
+    }
+}
+
+/// Represents message IDs for `snap` protocol messages.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SnapMessageID {
+    /// Requests a range of accounts.
+    GetAccountRange = 0x00,
+    /// Represents a range of accounts.
+    AccountRange = 0x01,
+    /// Requests ranges of storage slots.
+    GetStorageRanges = 0x02,
+    /// Represents ranges of storage slots.
+    StorageRanges = 0x03,
+    /// Requests bytecodes.
+    GetByteCodes = 0x04,
+    /// Represents bytecodes.
+    ByteCodes = 0x05,
+    /// Requests trie nodes.
+    GetTrieNodes = 0x06,
+    /// Represents trie nodes.
+    TrieNodes = 0x07,
+}
+
+impl SnapMessageID {
+    /// Returns the max value.
+    pub const fn max() -> u8 {
+        Self::TrieNodes as u8
+    }
+}
+
+impl Encodable for SnapMessageID {
+    fn encode(&self, out: &mut dyn BufMut) {
+        out.put_u8(*self as u8);
+    }
+    fn length(&self) -> usize {
+        1
+    }
+}
+
+impl Decodable for SnapMessageID {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let id = match buf.first().ok_or(alloy_rlp::Error::InputTooShort)? {
+            0x00 => Self::GetAccountRange,
+            0x01 => Self::AccountRange,
+            0x02 => Self::GetStorageRanges,
+            0x03 => Self::StorageRanges,
+            0x04 => Self::GetByteCodes,
+            0x05 => Self::ByteCodes,
+            0x06 => Self::GetTrieNodes,
+            0x07 => Self::TrieNodes,
+            _ => return Err(alloy_rlp::Error::Custom("Invalid message ID")),
+        };
+        buf.advance(1);
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::hex;
+
+    #[test]
+    fn slim_account_encoding() {
+        let account = SnapAccount {
+            nonce: 0,
+            balance: U256::ZERO,
+            storage_root: EMPTY_ROOT_HASH,
+            code_hash: KECCAK_EMPTY,
+        };
+
+        let encoded = alloy_rlp::encode(account);
+        assert_eq!(encoded, hex!("c480808080"));
+        assert_eq!(encoded.len(), account.length());
+        assert_eq!(SnapAccount::decode(&mut &encoded[..]).unwrap(), account);
+
+        let account = SnapAccount { storage_root: B256::repeat_byte(1), ..account };
+        let encoded = alloy_rlp::encode(account);
+        assert_eq!(encoded.len(), account.length());
+        assert_eq!(SnapAccount::decode(&mut &encoded[..]).unwrap(), account);
+    }
+
+    #[test]
+    fn reject_malformed_slim_hash() {
+        // storage root of two bytes
+        let encoded = hex!("c6808082010180");
+        assert!(SnapAccount::decode(&mut &encoded[..]).is_err());
+    }
+
+    #[test]
+    fn storage_range_bounds() {
+        let request = GetStorageRanges::default();
+        assert_eq!(request.origin(), B256::ZERO);
+        assert_eq!(request.limit(), B256::repeat_byte(0xff));
+
+        let request = GetStorageRanges {
+            starting_hash: B256::repeat_byte(1).into(),
+            limit_hash: B256::repeat_byte(2).into(),
+            ..Default::default()
+        };
+        assert_eq!(request.origin(), B256::repeat_byte(1));
+        assert_eq!(request.limit(), B256::repeat_byte(2));
+    }
+
+    #[test]
+    fn message_roundtrip() {
+        let messages = [
+            SnapMessage::GetAccountRange(GetAccountRange {
+                request_id: 1,
+                root_hash: B256::repeat_byte(1),
+                starting_hash: B256::ZERO,
+                limit_hash: B256::repeat_byte(0xff),
+                response_bytes: 512 * 1024,
+            }),
+            SnapMessage::AccountRange(AccountRange {
+                request_id: 1,
+                accounts: vec![AccountData {
+                    hash: B256::repeat_byte(2),
+                    body: SnapAccount {
+                        nonce: 1,
+                        balance: U256::from(10),
+                        storage_root: EMPTY_ROOT_HASH,
+                        code_hash: B256::repeat_byte(3),
+                    },
+                }],
+                proof: vec![Bytes::from_static(&[0xc0])],
+            }),
+            SnapMessage::StorageRanges(StorageRanges {
+                request_id: 2,
+                slots: vec![vec![StorageData {
+                    hash: B256::repeat_byte(4),
+                    data: Bytes::from_static(&[0x01]),
+                }]],
+                proof: vec![],
+            }),
+            SnapMessage::GetTrieNodes(GetTrieNodes {
+                request_id: 3,
+                root_hash: B256::repeat_byte(1),
+                paths: vec![vec![Bytes::from_static(&[0x01]), Bytes::from_static(&[0x02])]],
+                response_bytes: 1024,
+            }),
+            SnapMessage::ByteCodes(ByteCodes { request_id: 4, codes: vec![] }),
+        ];
+
+        for message in messages {
+            let mut encoded = Vec::new();
+            message.encode_message(&mut encoded);
+            assert_eq!(encoded[0], message.message_id() as u8);
+
+            let decoded = SnapMessage::decode_message(&mut &encoded[..]).unwrap();
+            assert_eq!(decoded, message);
+        }
+    }
+
+    #[test]
+    fn reject_non_canonical_messages() {
+        let message = SnapMessage::GetByteCodes(GetByteCodes {
+            request_id: 1,
+            hashes: vec![B256::repeat_byte(1)],
+            response_bytes: 1024,
+        });
+        let mut encoded = Vec::new();
+        message.encode_message(&mut encoded);
+        encoded.extend_from_slice(&[0x80, 0x80]);
+        let err = SnapMessage::decode_message(&mut &encoded[..]).unwrap_err();
+        assert!(matches!(err, MessageError::TrailingBytes(2)));
+        assert!(err.is_non_canonical());
+
+        // the request id with a leading zero
+        let mut payload = hex!("820001").to_vec();
+        vec![B256::repeat_byte(1)].encode(&mut payload);
+        1024u64.encode(&mut payload);
+        let mut encoded = vec![SnapMessageID::GetByteCodes as u8];
+        Header { list: true, payload_length: payload.len() }.encode(&mut encoded);
+        encoded.extend_from_slice(&payload);
+        let err = SnapMessage::decode_message(&mut &encoded[..]).unwrap_err();
+        assert!(matches!(err, MessageError::NonCanonical(alloy_rlp::Error::LeadingZero)));
+        assert!(err.is_non_canonical());
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::{
     errors::P2PStreamError, message::MessageError, version::ParseVersionError, DisconnectReason,
+    EthMessageID,
 };
 use reth_primitives::{Chain, GotExpected, GotExpectedBoxed, ValidationError, B256};
 use std::io;
@@ -21,9 +22,24 @@ pub enum EthStreamError {
     /// Thrown when decoding a message message failed.
     #[error(transparent)]
     InvalidMessage(#[from] MessageError),
-    #[error("message size ({0}) exceeds max length (10MB)")]
+    #[error("received message {id:?} of size {size} exceeding max length (10MB)")]
     /// Received a message whose size exceeds the standard limit.
-    MessageTooBig(usize),
+    InboundMessageTooBig {
+        /// The size of the received message.
+        size: usize,
+        /// The id of the received message, if it is a known eth message id.
+        id: Option<EthMessageID>,
+    },
+    #[error("outgoing message {id:?} of size {size} exceeds max length (10MB)")]
+    /// Attempted to send a message whose size exceeds the standard limit.
+    ///
+    /// The message was not sent, the peer is not at fault.
+    OutboundMessageTooBig {
+        /// The size of the encoded message.
+        size: usize,
+        /// The id of the message.
+        id: EthMessageID,
+    },
     #[error("TransactionHashes invalid len of fields: hashes_len={hashes_len} types_len={types_len} sizes_len={sizes_len}")]
     /// Received malformed transaction hashes message with discrepancies in field lengths.
     TransactionHashesInvalidLenOfFields {
@@ -51,6 +67,12 @@ impl EthStreamError {
         }
     }
 
+    /// Returns true if the error was caused by a message we tried to send exceeding the size
+    /// limit.
+    pub const fn is_outbound_message_too_big(&self) -> bool {
+        matches!(self, Self::OutboundMessageTooBig { .. })
+    }
+
     /// Returns the [`io::Error`] if it was caused by IO
     pub const fn as_io(&self) -> Option<&io::Error> {
         if let Self::P2PStreamError(P2PStreamError::Io(io)) = self {
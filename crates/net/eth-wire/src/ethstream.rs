@@ -2,7 +2,7 @@ use crate::{
     errors::{EthHandshakeError, EthStreamError},
     message::{EthBroadcastMessage, ProtocolBroadcastMessage},
     p2pstream::HANDSHAKE_TIMEOUT,
-    CanDisconnect, DisconnectReason, EthMessage, EthVersion, ProtocolMessage, Status,
+    CanDisconnect, DisconnectReason, EthMessage, EthMessageID, EthVersion, ProtocolMessage, Status,
 };
 use futures::{ready, Sink, SinkExt, StreamExt};
 use pin_project::pin_project;
@@ -23,6 +23,26 @@ use tracing::{debug, trace};
 // https://github.com/ethereum/go-ethereum/blob/30602163d5d8321fbc68afdcbbaf2362b2641bde/eth/protocols/eth/protocol.go#L50
 pub const MAX_MESSAGE_SIZE: usize = 10 * 1024 * 1024;
 
+/// Returns the error for a received message that exceeds the [`MAX_MESSAGE_SIZE`].
+///
+/// The message id is the first byte of the message, so it can be determined without decoding.
+fn inbound_message_too_big(msg: &[u8]) -> EthStreamError {
+    let id = msg.first().and_then(|id| EthMessageID::try_from(*id as usize).ok());
+    EthStreamError::InboundMessageTooBig { size: msg.len(), id }
+}
+
+/// Encodes an outgoing message and ensures it does not exceed the [`MAX_MESSAGE_SIZE`].
+fn encode_outbound<T: alloy_rlp::Encodable>(
+    msg: T,
+    id: EthMessageID,
+) -> Result<Bytes, EthStreamError> {
+    let encoded = alloy_rlp::encode(msg);
+    if encoded.len() > MAX_MESSAGE_SIZE {
+        return Err(EthStreamError::OutboundMessageTooBig { size: encoded.len(), id })
+    }
+    Ok(encoded.into())
+}
+
 /// An un-authenticated [`EthStream`]. This is consumed and returns a [`EthStream`] after the
 /// `Status` handshake is completed.
 #[pin_project]
@@ -101,7 +121,7 @@ where
 
         if their_msg.len() > MAX_MESSAGE_SIZE {
             self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
-            return Err(EthStreamError::MessageTooBig(their_msg.len()))
+            return Err(inbound_message_too_big(&their_msg))
         }
 
         let version = EthVersion::try_from(status.version)?;
@@ -109,7 +129,13 @@ where
             Ok(m) => m,
             Err(err) => {
                 debug!("decode error in eth handshake: msg={their_msg:x}");
-                self.inner.disconnect(DisconnectReason::DisconnectRequested).await?;
+                // a non-canonical encoding is never produced by a conforming implementation
+                let reason = if err.is_non_canonical() {
+                    DisconnectReason::ProtocolBreach
+                } else {
+                    DisconnectReason::DisconnectRequested
+                };
+                self.inner.disconnect(reason).await?;
                 return Err(EthStreamError::InvalidMessage(err))
             }
         };
@@ -236,9 +262,8 @@ where
         &mut self,
         item: EthBroadcastMessage,
     ) -> Result<(), EthStreamError> {
-        self.inner.start_send_unpin(Bytes::from(alloy_rlp::encode(
-            ProtocolBroadcastMessage::from(item),
-        )))?;
+        let id = item.message_id();
+        self.inner.start_send_unpin(encode_outbound(ProtocolBroadcastMessage::from(item), id)?)?;
 
         Ok(())
     }
@@ -261,7 +286,7 @@ where
         };
 
         if bytes.len() > MAX_MESSAGE_SIZE {
-            return Poll::Ready(Some(Err(EthStreamError::MessageTooBig(bytes.len()))))
+            return Poll::Ready(Some(Err(inbound_message_too_big(&bytes))))
         }
 
         let msg = match ProtocolMessage::decode_message(*this.version, &mut bytes.as_ref()) {
@@ -316,9 +341,8 @@ where
             return Err(EthStreamError::EthHandshakeError(EthHandshakeError::StatusNotInHandshake))
         }
 
-        self.project()
-            .inner
-            .start_send(Bytes::from(alloy_rlp::encode(ProtocolMessage::from(item))))?;
+        let id = item.message_id();
+        self.project().inner.start_send(encode_outbound(ProtocolMessage::from(item), id)?)?;
 
         Ok(())
     }
@@ -344,18 +368,23 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::UnauthedEthStream;
+    use super::{UnauthedEthStream, MAX_MESSAGE_SIZE};
     use crate::{
         broadcast::BlockHashNumber,
-        errors::{EthHandshakeError, EthStreamError},
+        errors::{EthHandshakeError, EthStreamError, P2PStreamError},
+        message::MessageError,
         p2pstream::{ProtocolVersion, UnauthedP2PStream},
-        EthMessage, EthStream, EthVersion, HelloMessageWithProtocols, PassthroughCodec, Status,
+        BlockHeaders, DisconnectReason, EthMessage, EthMessageID, EthStream, EthVersion,
+        HelloMessageWithProtocols, PassthroughCodec, ProtocolMessage, RequestPair, Status,
     };
     use futures::{SinkExt, StreamExt};
     use reth_discv4::DEFAULT_DISCOVERY_PORT;
     use reth_ecies::stream::ECIESStream;
     use reth_network_peers::pk2id;
-    use reth_primitives::{ForkFilter, Head, NamedChain, B256, U256};
+    use reth_primitives::{
+        bytes::{BufMut, BytesMut},
+        ForkFilter, Head, Header, NamedChain, B256, U256,
+    };
     use secp256k1::{SecretKey, SECP256K1};
     use std::time::Duration;
     use tokio::net::{TcpListener, TcpStream};
@@ -543,6 +572,54 @@ mod tests {
         handle.await.unwrap();
     }
 
+    #[tokio::test]
+    async fn oversized_messages() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        let handle = tokio::spawn(async move {
+            let (incoming, _) = listener.accept().await.unwrap();
+            let stream = PassthroughCodec::builder()
+                .max_frame_length(2 * MAX_MESSAGE_SIZE)
+                .new_framed(incoming);
+            let mut stream = EthStream::new(EthVersion::Eth67, stream);
+
+            // the id of the message is known without decoding it
+            let err = stream.next().await.unwrap().unwrap_err();
+            assert!(matches!(
+                err,
+                EthStreamError::InboundMessageTooBig { size, id: Some(EthMessageID::BlockHeaders) }
+                if size == MAX_MESSAGE_SIZE + 1
+            ));
+        });
+
+        let outgoing = TcpStream::connect(local_addr).await.unwrap();
+        let sink =
+            PassthroughCodec::builder().max_frame_length(2 * MAX_MESSAGE_SIZE).new_framed(outgoing);
+        let mut client_stream = EthStream::new(EthVersion::Eth67, sink);
+
+        // oversized messages are rejected before they're sent
+        let header = Header { extra_data: vec![0; MAX_MESSAGE_SIZE].into(), ..Default::default() };
+        let msg = EthMessage::BlockHeaders(RequestPair {
+            request_id: 1,
+            message: BlockHeaders(vec![header]),
+        });
+        let err = client_stream.send(msg).await.unwrap_err();
+        assert!(matches!(
+            err,
+            EthStreamError::OutboundMessageTooBig { id: EthMessageID::BlockHeaders, .. }
+        ));
+        assert!(err.is_outbound_message_too_big());
+
+        // bypass the check by sending the raw frame
+        let mut raw = BytesMut::with_capacity(MAX_MESSAGE_SIZE + 1);
+        raw.put_u8(EthMessageID::BlockHeaders as u8);
+        raw.put_bytes(0, MAX_MESSAGE_SIZE);
+        client_stream.inner_mut().send(raw.freeze()).await.unwrap();
+
+        handle.await.unwrap();
+    }
+
     #[tokio::test]
     async fn can_write_and_read_ecies() {
         let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -667,6 +744,82 @@ mod tests {
         handle.await.unwrap();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn non_canonical_status_is_protocol_breach() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let server_key = SecretKey::new(&mut rand::thread_rng());
+
+        let genesis = B256::random();
+        let fork_filter = ForkFilter::new(Head::default(), genesis, 0, Vec::new());
+
+        let status = Status {
+            version: EthVersion::Eth67 as u8,
+            chain: NamedChain::Mainnet.into(),
+            total_difficulty: U256::ZERO,
+            blockhash: B256::random(),
+            genesis,
+            // Pass the current fork id.
+            forkid: fork_filter.current(),
+        };
+
+        let status_copy = status;
+        let handle = tokio::spawn(async move {
+            let (incoming, _) = listener.accept().await.unwrap();
+            let stream = ECIESStream::incoming(incoming, server_key).await.unwrap();
+
+            let server_hello = HelloMessageWithProtocols {
+                protocol_version: ProtocolVersion::V5,
+                client_version: "bitcoind/1.0.0".to_string(),
+                protocols: vec![EthVersion::Eth67.into()],
+                port: DEFAULT_DISCOVERY_PORT,
+                id: pk2id(&server_key.public_key(SECP256K1)),
+            };
+
+            let unauthed_stream = UnauthedP2PStream::new(stream);
+            let (p2p_stream, _) = unauthed_stream.handshake(server_hello).await.unwrap();
+            let handshake_res =
+                UnauthedEthStream::new(p2p_stream).handshake(status_copy, fork_filter).await;
+            assert!(matches!(
+                handshake_res,
+                Err(EthStreamError::InvalidMessage(MessageError::TrailingBytes(1)))
+            ));
+        });
+
+        let server_id = pk2id(&server_key.public_key(SECP256K1));
+        let client_key = SecretKey::new(&mut rand::thread_rng());
+
+        let outgoing = TcpStream::connect(local_addr).await.unwrap();
+        let sink = ECIESStream::connect(outgoing, client_key, server_id).await.unwrap();
+
+        let client_hello = HelloMessageWithProtocols {
+            protocol_version: ProtocolVersion::V5,
+            client_version: "bitcoind/1.0.0".to_string(),
+            protocols: vec![EthVersion::Eth67.into()],
+            port: DEFAULT_DISCOVERY_PORT,
+            id: pk2id(&client_key.public_key(SECP256K1)),
+        };
+
+        let unauthed_stream = UnauthedP2PStream::new(sink);
+        let (mut p2p_stream, _) = unauthed_stream.handshake(client_hello).await.unwrap();
+
+        // send the status with a trailing byte
+        let mut raw = BytesMut::from(
+            alloy_rlp::encode(ProtocolMessage::from(EthMessage::Status(status))).as_slice(),
+        );
+        raw.put_u8(0);
+        p2p_stream.send(raw.freeze()).await.unwrap();
+
+        // the status of the server is followed by its disconnect
+        assert!(p2p_stream.next().await.unwrap().is_ok());
+        assert!(matches!(
+            p2p_stream.next().await.unwrap(),
+            Err(P2PStreamError::Disconnected(DisconnectReason::ProtocolBreach))
+        ));
+
+        handle.await.unwrap();
+    }
+
     #[tokio::test]
     async fn handshake_should_timeout() {
         let genesis = B256::random();
@@ -0,0 +1,41 @@
+//! Strict decoding tests for [`ProtocolMessage`]
+
+use reth_eth_wire::{message::MessageError, EthVersion, ProtocolMessage};
+use reth_primitives::hex;
+use std::{fs, path::PathBuf};
+
+/// Reads the test vectors of the [EIP-2481](https://eips.ethereum.org/EIPS/eip-2481) spec, one
+/// message per line: the message name and the message id followed by the RLP payload.
+fn message_vectors() -> Vec<(String, Vec<u8>)> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata/eth_message_vectors");
+    let data = fs::read_to_string(path).expect("Unable to read file");
+    data.lines()
+        .map(|line| {
+            let (name, message) = line.split_once(' ').unwrap();
+            (name.to_string(), hex::decode(message).unwrap())
+        })
+        .collect()
+}
+
+#[test]
+fn decode_message_vectors() {
+    for (name, message) in message_vectors() {
+        let decoded = ProtocolMessage::decode_message(EthVersion::Eth67, &mut &message[..])
+            .unwrap_or_else(|err| panic!("failed to decode {name}: {err}"));
+        assert_eq!(format!("{:?}", decoded.message_type), name);
+    }
+}
+
+#[test]
+fn reject_message_vectors_with_trailing_bytes() {
+    for (name, mut message) in message_vectors() {
+        message.extend_from_slice(&[0x80, 0x80]);
+        assert!(
+            matches!(
+                ProtocolMessage::decode_message(EthVersion::Eth67, &mut &message[..]),
+                Err(MessageError::TrailingBytes(2))
+            ),
+            "{name}"
+        );
+    }
+}
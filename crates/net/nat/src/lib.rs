@@ -12,6 +12,9 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+mod votes;
+pub use votes::{ExternalIpVotes, DEFAULT_HYSTERESIS, DEFAULT_MAX_VOTES, DEFAULT_MIN_VOTES};
+
 use std::{
     fmt,
     future::{poll_fn, Future},
@@ -53,6 +56,14 @@ impl NatResolver {
     pub async fn external_addr(self) -> Option<IpAddr> {
         external_addr_with(self).await
     }
+
+    /// Returns true if the external IP may be learned from the addresses remote peers report
+    /// back, see [`ExternalIpVotes`].
+    ///
+    /// This is not the case if the external IP was explicitly configured or disabled.
+    pub const fn allows_passive_discovery(&self) -> bool {
+        !matches!(self, Self::ExternalIp(_) | Self::None)
+    }
 }
 
 impl fmt::Display for NatResolver {
@@ -236,4 +247,14 @@ mod tests {
         assert_eq!(ip, s.parse().unwrap());
         assert_eq!(ip.to_string().as_str(), s);
     }
+
+    #[test]
+    fn test_allows_passive_discovery() {
+        assert!(NatResolver::Any.allows_passive_discovery());
+        assert!(NatResolver::Upnp.allows_passive_discovery());
+        assert!(!NatResolver::None.allows_passive_discovery());
+        assert!(
+            !NatResolver::ExternalIp(IpAddr::V4(Ipv4Addr::LOCALHOST)).allows_passive_discovery()
+        );
+    }
 }
@@ -0,0 +1,221 @@
+//! Passive discovery of the external IP address from the addresses remote peers report back.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    net::IpAddr,
+};
+
+/// Default number of agreeing reports required before an address is adopted.
+pub const DEFAULT_MIN_VOTES: usize = 10;
+
+/// Default number of the most recent reporters that are taken into account.
+pub const DEFAULT_MAX_VOTES: usize = 100;
+
+/// Default number of votes a new address must lead the current address by before it replaces it.
+pub const DEFAULT_HYSTERESIS: usize = 2;
+
+/// Majority vote over the external address of the local node as reported by remote peers, for
+/// example via the `to` endpoint of a discovery `Pong`.
+///
+/// Every reporter has a single vote, a new report of the same reporter replaces its previous vote.
+/// Only the most recent `max_votes` reporters are taken into account.
+///
+/// An address is adopted once it has at least `min_votes` votes. Once an address was adopted it is
+/// only replaced by an address that leads it by more than `hysteresis` votes, so that a few
+/// conflicting reports can't make the advertised address flap.
+#[derive(Debug, Clone)]
+pub struct ExternalIpVotes {
+    /// Minimum number of votes required to adopt an address.
+    min_votes: usize,
+    /// Maximum number of tracked reporters.
+    max_votes: usize,
+    /// Number of votes a new address must lead the current one by.
+    hysteresis: usize,
+    /// Reporters in the order their latest vote was cast, oldest first.
+    reporters: VecDeque<IpAddr>,
+    /// The reported address by reporter.
+    votes: HashMap<IpAddr, IpAddr>,
+    /// The currently adopted external address.
+    current: Option<IpAddr>,
+}
+
+impl ExternalIpVotes {
+    /// Creates a new instance that requires `min_votes` agreeing reports out of the last
+    /// `max_votes` reporters.
+    pub fn new(min_votes: usize, max_votes: usize) -> Self {
+        Self {
+            min_votes: min_votes.max(1),
+            max_votes: max_votes.max(1),
+            hysteresis: DEFAULT_HYSTERESIS,
+            reporters: VecDeque::new(),
+            votes: HashMap::new(),
+            current: None,
+        }
+    }
+
+    /// Sets the number of votes a new address must lead the current address by.
+    pub const fn with_hysteresis(mut self, hysteresis: usize) -> Self {
+        self.hysteresis = hysteresis;
+        self
+    }
+
+    /// Returns the currently adopted external address, if any.
+    pub const fn current(&self) -> Option<IpAddr> {
+        self.current
+    }
+
+    /// Sets the currently adopted external address, for example after it was resolved by a
+    /// [`NatResolver`](crate::NatResolver).
+    pub fn set_current(&mut self, ip: IpAddr) {
+        self.current = Some(ip);
+    }
+
+    /// Returns the number of votes for the given address.
+    pub fn votes_for(&self, ip: IpAddr) -> usize {
+        self.votes.values().filter(|reported| **reported == ip).count()
+    }
+
+    /// Records that `reporter` observed our address as `reported`.
+    ///
+    /// Returns the new external address if this report changed the outcome of the vote.
+    pub fn on_report(&mut self, reporter: IpAddr, reported: IpAddr) -> Option<IpAddr> {
+        if reported.is_unspecified() || reported.is_loopback() || reported.is_multicast() {
+            return None
+        }
+
+        if self.votes.insert(reporter, reported).is_some() {
+            self.reporters.retain(|r| *r != reporter);
+        }
+        self.reporters.push_back(reporter);
+
+        while self.reporters.len() > self.max_votes {
+            if let Some(oldest) = self.reporters.pop_front() {
+                self.votes.remove(&oldest);
+            }
+        }
+
+        // tally the addresses in the order of their earliest vote
+        let mut tally = Vec::<(IpAddr, usize)>::new();
+        for reporter in &self.reporters {
+            let ip = self.votes[reporter];
+            match tally.iter_mut().find(|(candidate, _)| *candidate == ip) {
+                Some((_, votes)) => *votes += 1,
+                None => tally.push((ip, 1)),
+            }
+        }
+
+        // ties are won by the address with the earliest vote, a tie with the current address never
+        // passes the hysteresis check below
+        let (candidate, candidate_votes) =
+            tally.iter().copied().reduce(|best, next| if next.1 > best.1 { next } else { best })?;
+        if candidate_votes < self.min_votes || Some(candidate) == self.current {
+            return None
+        }
+
+        if let Some(current) = self.current {
+            let current_votes = self.votes_for(current);
+            if candidate_votes <= current_votes + self.hysteresis {
+                return None
+            }
+        }
+
+        self.current = Some(candidate);
+        self.current
+    }
+}
+
+impl Default for ExternalIpVotes {
+    fn default() -> Self {
+        Self::new(DEFAULT_MIN_VOTES, DEFAULT_MAX_VOTES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn ip(last: u8) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::new(203, 0, 113, last))
+    }
+
+    fn reporter(n: u8) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::new(198, 51, 100, n))
+    }
+
+    #[test]
+    fn adopts_majority_after_min_votes() {
+        let mut votes = ExternalIpVotes::new(3, 10);
+        assert_eq!(votes.on_report(reporter(1), ip(1)), None);
+        assert_eq!(votes.on_report(reporter(2), ip(2)), None);
+        assert_eq!(votes.on_report(reporter(3), ip(1)), None);
+        assert_eq!(votes.on_report(reporter(4), ip(1)), Some(ip(1)));
+        assert_eq!(votes.current(), Some(ip(1)));
+
+        // further agreeing reports don't change anything
+        assert_eq!(votes.on_report(reporter(5), ip(1)), None);
+    }
+
+    #[test]
+    fn repeated_reports_count_once() {
+        let mut votes = ExternalIpVotes::new(2, 10);
+        assert_eq!(votes.on_report(reporter(1), ip(1)), None);
+        assert_eq!(votes.on_report(reporter(1), ip(1)), None);
+        assert_eq!(votes.votes_for(ip(1)), 1);
+
+        // a reporter changing its mind moves its vote
+        assert_eq!(votes.on_report(reporter(1), ip(2)), None);
+        assert_eq!(votes.votes_for(ip(1)), 0);
+        assert_eq!(votes.votes_for(ip(2)), 1);
+    }
+
+    #[test]
+    fn conflicting_reports_respect_hysteresis() {
+        let mut votes = ExternalIpVotes::new(2, 20).with_hysteresis(2);
+        for n in 0..4 {
+            votes.on_report(reporter(n), ip(1));
+        }
+        assert_eq!(votes.current(), Some(ip(1)));
+
+        // 4 votes for the current address, the new one needs more than 6
+        for n in 10..16 {
+            assert_eq!(votes.on_report(reporter(n), ip(2)), None);
+        }
+        assert_eq!(votes.on_report(reporter(16), ip(2)), Some(ip(2)));
+        assert_eq!(votes.current(), Some(ip(2)));
+    }
+
+    #[test]
+    fn old_reports_are_evicted() {
+        let mut votes = ExternalIpVotes::new(3, 3).with_hysteresis(0);
+        for n in 0..3 {
+            votes.on_report(reporter(n), ip(1));
+        }
+        assert_eq!(votes.current(), Some(ip(1)));
+
+        assert_eq!(votes.on_report(reporter(10), ip(2)), None);
+        assert_eq!(votes.on_report(reporter(11), ip(2)), None);
+        assert_eq!(votes.on_report(reporter(12), ip(2)), Some(ip(2)));
+        assert_eq!(votes.votes_for(ip(1)), 0);
+    }
+
+    #[test]
+    fn ties_are_won_by_earliest_vote() {
+        let mut votes = ExternalIpVotes::new(2, 10).with_hysteresis(1);
+        votes.set_current(ip(3));
+        for (n, reported) in [ip(3), ip(1), ip(2), ip(1), ip(2)].into_iter().enumerate() {
+            assert_eq!(votes.on_report(reporter(n as u8), reported), None);
+        }
+
+        // once the only vote for the current address moves, both tied addresses lead it
+        assert_eq!(votes.on_report(reporter(0), ip(4)), Some(ip(1)));
+    }
+
+    #[test]
+    fn ignores_unroutable_reports() {
+        let mut votes = ExternalIpVotes::new(1, 10);
+        assert_eq!(votes.on_report(reporter(1), IpAddr::V4(Ipv4Addr::UNSPECIFIED)), None);
+        assert_eq!(votes.on_report(reporter(1), IpAddr::V4(Ipv4Addr::LOCALHOST)), None);
+        assert_eq!(votes.current(), None);
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::{
     eth_requests::EthRequestHandler,
+    snap::{SnapProtocolHandler, SnapRequestHandler, SnapServerConfig},
     transactions::{TransactionsManager, TransactionsManagerConfig},
     NetworkHandle, NetworkManager,
 };
@@ -12,6 +13,10 @@ use tokio::sync::mpsc;
 /// 256 requests with malicious 10MB body requests is 2.6GB which can be absorbed by the node.
 pub(crate) const ETH_REQUEST_CHANNEL_CAPACITY: usize = 256;
 
+/// We set the max channel capacity of the `SnapRequestHandler` to 256, requests beyond that are
+/// dropped and time out on the peer's side.
+pub(crate) const SNAP_REQUEST_CHANNEL_CAPACITY: usize = 256;
+
 /// A builder that can configure all components of the network.
 #[allow(missing_debug_implementations)]
 pub struct NetworkBuilder<C, Tx, Eth> {
@@ -77,4 +82,16 @@ impl<C, Tx, Eth> NetworkBuilder<C, Tx, Eth> {
         let request_handler = EthRequestHandler::new(client, peers, rx);
         NetworkBuilder { network, request_handler, transactions }
     }
+
+    /// Creates a new [`SnapRequestHandler`] and installs the `snap/1` protocol, which forwards the
+    /// requests of peers to it.
+    pub fn snap_request_handler<Client>(
+        &mut self,
+        client: Client,
+        config: SnapServerConfig,
+    ) -> SnapRequestHandler<Client> {
+        let (tx, rx) = mpsc::channel(SNAP_REQUEST_CHANNEL_CAPACITY);
+        self.network.add_rlpx_sub_protocol(SnapProtocolHandler::new(tx));
+        SnapRequestHandler::new(client, config, rx)
+    }
 }
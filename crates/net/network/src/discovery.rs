@@ -4,6 +4,7 @@ use crate::{
     cache::LruMap,
     error::{NetworkError, ServiceKind},
     manager::DiscoveredEvent,
+    peers::PeerSource,
 };
 use enr::Enr;
 use futures::StreamExt;
@@ -200,23 +201,30 @@ impl Discovery {
     }
 
     /// Processes an incoming [`NodeRecord`] update from a discovery service
-    fn on_node_record_update(&mut self, record: NodeRecord, fork_id: Option<ForkId>) {
+    fn on_node_record_update(
+        &mut self,
+        record: NodeRecord,
+        fork_id: Option<ForkId>,
+        source: PeerSource,
+    ) {
         let id = record.id;
         let addr = record.tcp_addr();
-        _ =
-            self.discovered_nodes.get_or_insert(id, || {
-                self.queued_events.push_back(DiscoveryEvent::NewNode(
-                    DiscoveredEvent::EventQueued { peer_id: id, socket_addr: addr, fork_id },
-                ));
-
-                addr
-            })
+        _ = self.discovered_nodes.get_or_insert(id, || {
+            self.queued_events.push_back(DiscoveryEvent::NewNode(DiscoveredEvent::EventQueued {
+                peer_id: id,
+                socket_addr: addr,
+                fork_id,
+                source,
+            }));
+
+            addr
+        })
     }
 
     fn on_discv4_update(&mut self, update: DiscoveryUpdate) {
         match update {
             DiscoveryUpdate::Added(record) | DiscoveryUpdate::DiscoveredAtCapacity(record) => {
-                self.on_node_record_update(record, None);
+                self.on_node_record_update(record, None, PeerSource::Discv4);
             }
             DiscoveryUpdate::EnrForkId(node, fork_id) => {
                 self.queued_events.push_back(DiscoveryEvent::EnrForkId(node.id, fork_id))
@@ -255,7 +263,7 @@ impl Discovery {
                     if let Some(DiscoveredPeer { node_record, fork_id }) =
                         discv5.on_discv5_update(update)
                     {
-                        self.on_node_record_update(node_record, fork_id);
+                        self.on_node_record_update(node_record, fork_id, PeerSource::Discv5);
                     }
                 }
             }
@@ -271,7 +279,7 @@ impl Discovery {
                         "failed adding node discovered by dns to discv5"
                     );
                 }
-                self.on_node_record_update(update.node_record, update.fork_id);
+                self.on_node_record_update(update.node_record, update.fork_id, PeerSource::Dns);
             }
 
             if self.queued_events.is_empty() {
@@ -417,7 +425,8 @@ mod tests {
             DiscoveryEvent::NewNode(DiscoveredEvent::EventQueued {
                 peer_id: discv4_id_2,
                 socket_addr: discv4_enr_2.tcp_addr(),
-                fork_id: None
+                fork_id: None,
+                source: PeerSource::Discv4,
             }),
             event_node_1
         );
@@ -425,7 +434,8 @@ mod tests {
             DiscoveryEvent::NewNode(DiscoveredEvent::EventQueued {
                 peer_id: discv4_id_1,
                 socket_addr: discv4_enr_1.tcp_addr(),
-                fork_id: None
+                fork_id: None,
+                source: PeerSource::Discv4,
             }),
             event_node_2
         );
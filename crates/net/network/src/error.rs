@@ -235,15 +235,19 @@ impl SessionError for PendingSessionHandshakeError {
         match self {
             Self::Eth(eth) => eth.merits_discovery_ban(),
             Self::Ecies(_) => true,
-            Self::Timeout => false,
+            Self::Timeout(_) => false,
         }
     }
 
     fn is_fatal_protocol_error(&self) -> bool {
         match self {
+            // a non-canonical handshake message is never sent by a conforming implementation, an
+            // active session dropped because of one is only penalized, see
+            // `PeersManager::on_active_session_dropped`
+            Self::Eth(EthStreamError::InvalidMessage(err)) => err.is_non_canonical(),
             Self::Eth(eth) => eth.is_fatal_protocol_error(),
             Self::Ecies(_) => true,
-            Self::Timeout => false,
+            Self::Timeout(_) => false,
         }
     }
 
@@ -251,7 +255,7 @@ impl SessionError for PendingSessionHandshakeError {
         match self {
             Self::Eth(eth) => eth.should_backoff(),
             Self::Ecies(_) => Some(BackoffKind::Low),
-            Self::Timeout => Some(BackoffKind::Medium),
+            Self::Timeout(_) => Some(BackoffKind::Medium),
         }
     }
 }
@@ -283,6 +287,7 @@ impl SessionError for io::Error {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use reth_eth_wire::message::MessageError;
     use std::net::{Ipv4Addr, SocketAddrV4};
 
     #[test]
@@ -296,6 +301,19 @@ mod tests {
         assert!(err.is_fatal_protocol_error());
     }
 
+    #[test]
+    fn test_is_fatal_non_canonical_handshake() {
+        let err = PendingSessionHandshakeError::Eth(EthStreamError::InvalidMessage(
+            MessageError::TrailingBytes(1),
+        ));
+        assert!(err.is_fatal_protocol_error());
+
+        let err = PendingSessionHandshakeError::Eth(EthStreamError::InvalidMessage(
+            MessageError::RlpError(alloy_rlp::Error::InputTooShort),
+        ));
+        assert!(!err.is_fatal_protocol_error());
+    }
+
     #[test]
     fn test_should_backoff() {
         let err = EthStreamError::P2PStreamError(P2PStreamError::HandshakeError(
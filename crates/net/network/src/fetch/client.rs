@@ -62,7 +62,36 @@ impl HeadersClient for FetchClient {
         let (response, rx) = oneshot::channel();
         if self
             .request_tx
-            .send(DownloadRequest::GetBlockHeaders { request, response, priority })
+            .send(DownloadRequest::GetBlockHeaders {
+                request,
+                response,
+                priority,
+                excluded_peer: None,
+            })
+            .is_ok()
+        {
+            Either::Left(FlattenedResponse::from(rx))
+        } else {
+            Either::Right(future::err(RequestError::ChannelClosed))
+        }
+    }
+
+    /// Sends a `GetBlockHeaders` request to an available peer other than `excluded_peer`.
+    fn get_headers_excluding_peer(
+        &self,
+        request: HeadersRequest,
+        priority: Priority,
+        excluded_peer: PeerId,
+    ) -> Self::Output {
+        let (response, rx) = oneshot::channel();
+        if self
+            .request_tx
+            .send(DownloadRequest::GetBlockHeaders {
+                request,
+                response,
+                priority,
+                excluded_peer: Some(excluded_peer),
+            })
             .is_ok()
         {
             Either::Left(FlattenedResponse::from(rx))
@@ -130,8 +130,13 @@ impl StateFetcher {
     /// Returns the _next_ idle peer that's ready to accept a request,
     /// prioritizing those with the lowest timeout/latency and those that recently responded with
     /// adequate data.
-    fn next_best_peer(&self) -> Option<PeerId> {
-        let mut idle = self.peers.iter().filter(|(_, peer)| peer.state.is_idle());
+    ///
+    /// The excluded peer is never returned.
+    fn next_best_peer(&self, excluded: Option<&PeerId>) -> Option<PeerId> {
+        let mut idle = self
+            .peers
+            .iter()
+            .filter(|(peer_id, peer)| peer.state.is_idle() && Some(*peer_id) != excluded);
 
         let mut best_peer = idle.next()?;
 
@@ -160,7 +165,10 @@ impl StateFetcher {
             return PollAction::NoRequests
         }
 
-        let Some(peer_id) = self.next_best_peer() else { return PollAction::NoPeersAvailable };
+        let excluded = self.queued_requests.front().and_then(DownloadRequest::excluded_peer);
+        let Some(peer_id) = self.next_best_peer(excluded) else {
+            return PollAction::NoPeersAvailable
+        };
 
         let request = self.queued_requests.pop_front().expect("not empty");
         let request = self.prepare_block_request(peer_id, request);
@@ -408,6 +416,8 @@ pub(crate) enum DownloadRequest {
         request: HeadersRequest,
         response: oneshot::Sender<PeerRequestResult<Vec<Header>>>,
         priority: Priority,
+        /// The peer that must not serve the request, if any.
+        excluded_peer: Option<PeerId>,
     },
     /// Download the requested headers and send response through channel
     GetBlockBodies {
@@ -441,6 +451,14 @@ impl DownloadRequest {
     const fn is_normal_priority(&self) -> bool {
         self.get_priority().is_normal()
     }
+
+    /// Returns the peer that must not serve this request, if any.
+    const fn excluded_peer(&self) -> Option<&PeerId> {
+        match self {
+            Self::GetBlockHeaders { excluded_peer, .. } => excluded_peer.as_ref(),
+            Self::GetBlockBodies { .. } => None,
+        }
+    }
 }
 
 /// An action the syncer can emit.
@@ -469,7 +487,7 @@ pub(crate) enum BlockResponseOutcome {
 mod tests {
     use super::*;
     use crate::{peers::PeersManager, PeersConfig};
-    use reth_primitives::{SealedHeader, B512};
+    use reth_primitives::{HeadersDirection, SealedHeader, B512};
     use std::future::poll_fn;
 
     #[tokio::test(flavor = "multi_thread")]
@@ -502,17 +520,17 @@ mod tests {
         fetcher.new_active_peer(peer1, B256::random(), 1, Arc::new(AtomicU64::new(1)));
         fetcher.new_active_peer(peer2, B256::random(), 2, Arc::new(AtomicU64::new(1)));
 
-        let first_peer = fetcher.next_best_peer().unwrap();
+        let first_peer = fetcher.next_best_peer(None).unwrap();
         assert!(first_peer == peer1 || first_peer == peer2);
         // Pending disconnect for first_peer
         fetcher.on_pending_disconnect(&first_peer);
         // first_peer now isn't idle, so we should get other peer
-        let second_peer = fetcher.next_best_peer().unwrap();
+        let second_peer = fetcher.next_best_peer(None).unwrap();
         assert!(first_peer == peer1 || first_peer == peer2);
         assert_ne!(first_peer, second_peer);
         // without idle peers, returns None
         fetcher.on_pending_disconnect(&second_peer);
-        assert_eq!(fetcher.next_best_peer(), None);
+        assert_eq!(fetcher.next_best_peer(None), None);
     }
 
     #[tokio::test]
@@ -531,13 +549,55 @@ mod tests {
         fetcher.new_active_peer(peer3, B256::random(), 3, Arc::new(AtomicU64::new(50)));
 
         // Must always get peer1 (lowest timeout)
-        assert_eq!(fetcher.next_best_peer(), Some(peer1));
-        assert_eq!(fetcher.next_best_peer(), Some(peer1));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer1));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer1));
         // peer2's timeout changes below peer1's
         peer2_timeout.store(10, Ordering::Relaxed);
         // Then we get peer 2 always (now lowest)
-        assert_eq!(fetcher.next_best_peer(), Some(peer2));
-        assert_eq!(fetcher.next_best_peer(), Some(peer2));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer2));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer2));
+    }
+
+    #[tokio::test]
+    async fn test_excluded_peer() {
+        let manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher = StateFetcher::new(manager.handle(), Default::default());
+        let peer1 = B512::random();
+        let peer2 = B512::random();
+        fetcher.new_active_peer(peer1, B256::random(), 1, Arc::new(AtomicU64::new(10)));
+        fetcher.new_active_peer(peer2, B256::random(), 2, Arc::new(AtomicU64::new(100)));
+
+        // peer1 is the best peer, but it must not serve the request
+        let (tx, _rx) = oneshot::channel();
+        fetcher.queued_requests.push_back(DownloadRequest::GetBlockHeaders {
+            request: HeadersRequest {
+                start: 0u64.into(),
+                limit: 1,
+                direction: HeadersDirection::Rising,
+            },
+            response: tx,
+            priority: Priority::default(),
+            excluded_peer: Some(peer1),
+        });
+        assert!(matches!(
+            fetcher.poll_action(),
+            PollAction::Ready(FetchAction::BlockRequest { peer_id, .. }) if peer_id == peer2
+        ));
+
+        // without another idle peer, the request waits
+        let (tx, _rx) = oneshot::channel();
+        fetcher.queued_requests.push_back(DownloadRequest::GetBlockHeaders {
+            request: HeadersRequest {
+                start: 0u64.into(),
+                limit: 1,
+                direction: HeadersDirection::Rising,
+            },
+            response: tx,
+            priority: Priority::default(),
+            excluded_peer: Some(peer1),
+        });
+        assert!(matches!(fetcher.poll_action(), PollAction::NoPeersAvailable));
+        assert_eq!(fetcher.queued_requests.len(), 1);
     }
 
     #[tokio::test]
@@ -132,8 +132,10 @@ mod network;
 pub mod peers;
 pub mod protocol;
 mod session;
+pub mod snap;
 mod state;
 mod swarm;
+pub mod sync_target;
 pub mod transactions;
 
 pub use builder::NetworkBuilder;
@@ -145,9 +147,9 @@ pub use message::PeerRequest;
 pub use network::{NetworkEvents, NetworkHandle, NetworkProtocols};
 pub use peers::PeersConfig;
 pub use session::{
-    ActiveSessionHandle, ActiveSessionMessage, Direction, PeerInfo, PendingSessionEvent,
-    PendingSessionHandle, PendingSessionHandshakeError, SessionCommand, SessionEvent, SessionId,
-    SessionLimits, SessionManager, SessionsConfig,
+    ActiveSessionHandle, ActiveSessionMessage, Direction, HandshakePhase, HandshakeTimings,
+    PeerInfo, PendingSessionEvent, PendingSessionHandle, PendingSessionHandshakeError,
+    SessionCommand, SessionEvent, SessionId, SessionLimits, SessionManager, SessionsConfig,
 };
 pub use transactions::{FilterAnnouncement, MessageFilter, ValidateTx68};
 
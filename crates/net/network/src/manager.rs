@@ -26,7 +26,7 @@ use crate::{
     message::{NewBlockMessage, PeerMessage, PeerRequest, PeerRequestSender},
     metrics::{DisconnectMetrics, NetworkMetrics, NETWORK_POOL_TRANSACTIONS_SCOPE},
     network::{NetworkHandle, NetworkHandleMessage},
-    peers::{PeersHandle, PeersManager},
+    peers::{PeerSource, PeersHandle, PeersManager},
     poll_nested_stream_with_budget,
     protocol::IntoRlpxSubProtocol,
     session::SessionManager,
@@ -39,6 +39,7 @@ use futures::{Future, StreamExt};
 use parking_lot::Mutex;
 use reth_eth_wire::{
     capability::{Capabilities, CapabilityMessage},
+    errors::EthStreamError,
     DisconnectReason, EthVersion, Status,
 };
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
@@ -191,8 +192,7 @@ where
             transactions_manager_config: _,
         } = config;
 
-        let peers_manager = PeersManager::new(peers_config);
-        let peers_handle = peers_manager.handle();
+        let mut peers_manager = PeersManager::new(peers_config);
 
         let incoming = ConnectionListener::bind(listener_addr).await.map_err(|err| {
             NetworkError::from_io_error(err, ServiceKind::Listener(listener_addr))
@@ -224,6 +224,10 @@ where
         // need to retrieve the addr here since provided port could be `0`
         let local_peer_id = discovery.local_id();
         let discv4 = discovery.discv4();
+        if let Some(discv4) = &discv4 {
+            peers_manager.set_discv4(discv4.clone());
+        }
+        let peers_handle = peers_manager.handle();
 
         let num_active_peers = Arc::new(AtomicUsize::new(0));
 
@@ -556,7 +560,8 @@ where
             NetworkHandleMessage::AddPeerAddress(peer, kind, addr) => {
                 // only add peer if we are not shutting down
                 if !self.swarm.is_shutting_down() {
-                    self.swarm.state_mut().add_peer_kind(peer, kind, addr);
+                    // peers added through the handle are configured by the user
+                    self.swarm.state_mut().add_peer_kind(peer, kind, PeerSource::Static, addr);
                 }
             }
             NetworkHandleMessage::RemovePeer(peer_id, kind) => {
@@ -707,7 +712,7 @@ where
                 self.event_sender.notify(NetworkEvent::PeerRemoved(peer_id));
                 self.metrics.tracked_peers.set(self.swarm.state().peers().num_known_peers() as f64);
             }
-            SwarmEvent::SessionClosed { peer_id, remote_addr, error } => {
+            SwarmEvent::SessionClosed { peer_id, remote_addr, mut reason, error } => {
                 let total_active = self.num_active_peers.fetch_sub(1, Ordering::Relaxed) - 1;
                 self.metrics.connected_peers.set(total_active as f64);
                 trace!(
@@ -715,12 +720,33 @@ where
                     ?remote_addr,
                     ?peer_id,
                     ?total_active,
+                    ?reason,
                     ?error,
                     "Session disconnected"
                 );
 
-                let mut reason = None;
                 if let Some(ref err) = error {
+                    match err {
+                        EthStreamError::OutboundMessageTooBig { size, id } => {
+                            warn!(
+                                target: "net",
+                                ?peer_id,
+                                ?id,
+                                size,
+                                "Attempted to send oversized message"
+                            );
+                        }
+                        EthStreamError::InboundMessageTooBig { size, id } => {
+                            debug!(
+                                target: "net",
+                                ?peer_id,
+                                ?id,
+                                size,
+                                "Peer sent oversized message"
+                            );
+                        }
+                        _ => {}
+                    }
                     // If the connection was closed due to an error, we report
                     // the peer
                     self.swarm.state_mut().peers_mut().on_active_session_dropped(
@@ -729,6 +755,13 @@ where
                         err,
                     );
                     reason = err.as_disconnected();
+                } else if let Some(reason) = reason {
+                    // The peer disconnected and told us why
+                    self.swarm.state_mut().peers_mut().on_active_session_disconnected_by_remote(
+                        &remote_addr,
+                        &peer_id,
+                        reason,
+                    );
                 } else {
                     // Gracefully disconnected
                     self.swarm.state_mut().peers_mut().on_active_session_gracefully_closed(peer_id);
@@ -1028,7 +1061,12 @@ pub enum NetworkEvent {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DiscoveredEvent {
-    EventQueued { peer_id: PeerId, socket_addr: SocketAddr, fork_id: Option<ForkId> },
+    EventQueued {
+        peer_id: PeerId,
+        socket_addr: SocketAddr,
+        fork_id: Option<ForkId>,
+        source: PeerSource,
+    },
 }
 
 #[derive(Debug, Default)]
@@ -3,6 +3,7 @@
 //! An `RLPx` stream is multiplexed via the prepended message-id of a framed message.
 //! Capabilities are exchanged via the `RLPx` `Hello` message as pairs of `(id, version)`, <https://github.com/ethereum/devp2p/blob/master/rlpx.md#capability-messaging>
 
+use alloy_rlp::Encodable;
 use futures::FutureExt;
 use reth_eth_wire::{
     capability::RawCapabilityMessage, message::RequestPair, BlockBodies, BlockHeaders, EthMessage,
@@ -291,6 +292,22 @@ impl PeerResponseResult {
         }
     }
 
+    /// Drops trailing items of the response until the encoded response message fits into
+    /// `max_size` bytes and returns the number of dropped items.
+    ///
+    /// Peers must accept responses that contain fewer items than requested, so this is used
+    /// instead of failing to send an oversized response. Pooled transactions are assembled within
+    /// a byte budget in the first place and are never truncated.
+    pub fn truncate_to_fit(&mut self, max_size: usize) -> usize {
+        match self {
+            Self::BlockHeaders(Ok(headers)) => truncate_items(headers, max_size),
+            Self::BlockBodies(Ok(bodies)) => truncate_items(bodies, max_size),
+            Self::NodeData(Ok(data)) => truncate_items(data, max_size),
+            Self::Receipts(Ok(receipts)) => truncate_items(receipts, max_size),
+            _ => 0,
+        }
+    }
+
     /// Returns the `Err` value if the result is an error.
     pub fn err(&self) -> Option<&RequestError> {
         match self {
@@ -308,6 +325,27 @@ impl PeerResponseResult {
     }
 }
 
+/// Upper bound of the bytes a response message adds to the encoded items: the message id, the
+/// request id and the list headers of the request pair and the items.
+const RESPONSE_MESSAGE_OVERHEAD: usize = 1 + 9 + 9 + 9;
+
+/// Keeps the longest prefix of the items that fits into a response message of `max_size` bytes
+/// and returns the number of dropped items.
+fn truncate_items<T: Encodable>(items: &mut Vec<T>, max_size: usize) -> usize {
+    let budget = max_size.saturating_sub(RESPONSE_MESSAGE_OVERHEAD);
+    let mut size = 0usize;
+    let keep = items
+        .iter()
+        .take_while(|item| {
+            size += item.length();
+            size <= budget
+        })
+        .count();
+    let dropped = items.len() - keep;
+    items.truncate(keep);
+    dropped
+}
+
 /// A Cloneable connection for sending _requests_ directly to the session of a peer.
 #[derive(Clone)]
 pub struct PeerRequestSender {
@@ -341,3 +379,32 @@ impl fmt::Debug for PeerRequestSender {
         f.debug_struct("PeerRequestSender").field("peer_id", &self.peer_id).finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_eth_wire::{ProtocolMessage, MAX_MESSAGE_SIZE};
+
+    #[test]
+    fn truncate_oversized_headers_response() {
+        let headers = (0..12)
+            .map(|number| Header {
+                number,
+                extra_data: vec![0; 1024 * 1024].into(),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+
+        // responses within the limit are left untouched
+        let mut resp = PeerResponseResult::BlockHeaders(Ok(headers[..9].to_vec()));
+        assert_eq!(resp.truncate_to_fit(MAX_MESSAGE_SIZE), 0);
+
+        let mut resp = PeerResponseResult::BlockHeaders(Ok(headers.clone()));
+        assert_eq!(resp.truncate_to_fit(MAX_MESSAGE_SIZE), 3);
+
+        let msg = resp.try_into_message(1).unwrap();
+        let EthMessage::BlockHeaders(RequestPair { message, .. }) = &msg else { unreachable!() };
+        assert_eq!(message.0, headers[..9]);
+        assert!(alloy_rlp::encode(ProtocolMessage::from(msg)).len() <= MAX_MESSAGE_SIZE);
+    }
+}
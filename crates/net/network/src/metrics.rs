@@ -2,13 +2,28 @@ use metrics::Histogram;
 use reth_eth_wire::DisconnectReason;
 use reth_metrics::{
     metrics::{Counter, Gauge},
-    Metrics,
+    Metrics, MetricsRegistrar,
 };
 use reth_primitives::TxType;
 
 /// Scope for monitoring transactions sent from the manager to the tx manager
 pub(crate) const NETWORK_POOL_TRANSACTIONS_SCOPE: &str = "network.pool.transactions";
 
+/// Declares all metrics of the network.
+pub(crate) fn register_metrics(registrar: &mut MetricsRegistrar) {
+    registrar.describe("network", || {
+        NetworkMetrics::describe();
+        PeersManagerMetrics::describe();
+        SessionManagerMetrics::describe();
+        TransactionsManagerMetrics::describe();
+        TransactionFetcherMetrics::describe();
+        DisconnectMetrics::describe();
+        EthRequestHandlerMetrics::describe();
+        SnapRequestHandlerMetrics::describe();
+        AnnouncedTxTypesMetrics::describe();
+    });
+}
+
 /// Metrics for the entire network, handled by `NetworkManager`
 #[derive(Metrics)]
 #[metrics(scope = "network")]
@@ -77,12 +92,35 @@ pub struct NetworkMetrics {
     pub(crate) acc_duration_poll_swarm: Gauge,
 }
 
+/// Metrics for the `PeersManager`
+#[derive(Metrics)]
+#[metrics(scope = "network.peers")]
+pub struct PeersManagerMetrics {
+    /// Total number of applied reputation changes
+    pub(crate) reputation_changes: Counter,
+
+    /// Total number of banned peers
+    pub(crate) banned_peers: Counter,
+}
+
 /// Metrics for `SessionManager`
 #[derive(Metrics)]
 #[metrics(scope = "network")]
 pub struct SessionManagerMetrics {
     /// Number of successful outgoing dial attempts.
     pub(crate) total_dial_successes: Counter,
+    /// Duration of the `ECIES` handshake of established sessions.
+    ///
+    /// Duration in seconds.
+    pub(crate) handshake_ecies_duration: Histogram,
+    /// Duration of the `Hello` exchange of established sessions.
+    ///
+    /// Duration in seconds.
+    pub(crate) handshake_hello_duration: Histogram,
+    /// Duration of the `Status` exchange of established sessions.
+    ///
+    /// Duration in seconds.
+    pub(crate) handshake_status_duration: Histogram,
 }
 
 /// Metrics for the [`TransactionsManager`](crate::transactions::TransactionsManager).
@@ -95,6 +133,18 @@ pub struct TransactionsManagerMetrics {
     /// Total number of reported bad transactions
     pub(crate) reported_bad_transactions: Counter,
 
+    /* -- Propagation batches -- */
+    /// Number of transactions in a propagated batch.
+    pub(crate) propagation_batch_size: Histogram,
+    /// Total number of batches propagated because the interval elapsed.
+    pub(crate) propagation_flushes_interval: Counter,
+    /// Total number of batches propagated because they reached the max size.
+    pub(crate) propagation_flushes_size: Counter,
+    /// Total number of batches of local transactions that bypassed batching.
+    pub(crate) propagation_flushes_local: Counter,
+    /// Total number of batches propagated on shutdown.
+    pub(crate) propagation_flushes_shutdown: Counter,
+
     /* -- Freq txns already marked as seen by peer -- */
     /// Total number of messages from a peer, announcing transactions that have already been
     /// marked as seen by that peer.
@@ -327,6 +377,30 @@ pub struct EthRequestHandlerMetrics {
     pub(crate) acc_duration_poll_eth_req_handler: Gauge,
 }
 
+/// Metrics for the `SnapRequestHandler`
+#[derive(Metrics)]
+#[metrics(scope = "network")]
+pub struct SnapRequestHandlerMetrics {
+    /// Number of `GetAccountRange` requests received
+    pub(crate) snap_account_range_requests_received_total: Counter,
+
+    /// Number of `GetStorageRanges` requests received
+    pub(crate) snap_storage_ranges_requests_received_total: Counter,
+
+    /// Number of `GetByteCodes` requests received
+    pub(crate) snap_bytecodes_requests_received_total: Counter,
+
+    /// Number of `GetTrieNodes` requests received
+    pub(crate) snap_trie_nodes_requests_received_total: Counter,
+
+    /// Number of requests answered empty because the peer exhausted its budget
+    pub(crate) snap_budget_exhausted_total: Counter,
+
+    /// Duration in seconds of call to poll
+    /// [`SnapRequestHandler`](crate::snap::SnapRequestHandler).
+    pub(crate) acc_duration_poll_snap_req_handler: Gauge,
+}
+
 /// Eth67 announcement metrics, track entries by `TxType`
 #[derive(Metrics)]
 #[metrics(scope = "network.transaction_fetcher")]
@@ -383,3 +457,24 @@ impl AnnouncedTxTypesMetrics {
         self.eip4844.record(tx_types_counter.eip4844 as f64);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_network_metrics() {
+        let mut registrar = MetricsRegistrar::new();
+        register_metrics(&mut registrar);
+
+        for name in [
+            "network.connected_peers",
+            "network.peers.reputation_changes",
+            "network.peers.banned_peers",
+            "network.transaction_fetcher.eip4844",
+        ] {
+            let metric = registrar.get(name).unwrap_or_else(|| panic!("{name} is not registered"));
+            assert_eq!(metric.component, "network");
+        }
+    }
+}
@@ -7,6 +7,7 @@ use enr::Enr;
 use parking_lot::Mutex;
 use reth_discv4::Discv4;
 use reth_eth_wire::{DisconnectReason, NewBlock, NewPooledTransactionHashes, SharedTransactions};
+use reth_metrics::MetricsRegistrar;
 use reth_network_api::{
     NetworkError, NetworkInfo, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
     ReputationChangeKind,
@@ -67,6 +68,7 @@ impl NetworkHandle {
             network_mode,
             is_syncing: Arc::new(AtomicBool::new(false)),
             initial_sync_done: Arc::new(AtomicBool::new(false)),
+            sync_distance: Arc::new(AtomicU64::new(0)),
             chain_id,
             tx_gossip_disabled,
             discv4,
@@ -87,6 +89,11 @@ impl NetworkHandle {
         &self.inner.peers
     }
 
+    /// Registers all metrics of the network with the given registrar.
+    pub fn register_metrics(&self, registrar: &mut MetricsRegistrar) {
+        crate::metrics::register_metrics(registrar)
+    }
+
     fn manager(&self) -> &UnboundedSender<NetworkHandleMessage> {
         &self.inner.to_manager_tx
     }
@@ -351,6 +358,13 @@ impl SyncStateProvider for NetworkHandle {
         }
         self.inner.is_syncing.load(Ordering::Relaxed)
     }
+
+    fn sync_distance(&self) -> Option<u64> {
+        if !self.inner.is_syncing.load(Ordering::Relaxed) {
+            return None
+        }
+        Some(self.inner.sync_distance.load(Ordering::Relaxed))
+    }
 }
 
 impl NetworkSyncUpdater for NetworkHandle {
@@ -361,6 +375,13 @@ impl NetworkSyncUpdater for NetworkHandle {
         if syncing_to_idle_state_transition {
             self.inner.initial_sync_done.store(true, Ordering::Relaxed);
         }
+        if !future_state {
+            self.inner.sync_distance.store(0, Ordering::Relaxed);
+        }
+    }
+
+    fn update_sync_distance(&self, distance: u64) {
+        self.inner.sync_distance.store(distance, Ordering::Relaxed);
     }
 
     /// Update the status of the node.
@@ -389,6 +410,8 @@ struct NetworkInner {
     is_syncing: Arc<AtomicBool>,
     /// Used to differentiate between an initial pipeline sync or a live sync
     initial_sync_done: Arc<AtomicBool>,
+    /// Number of blocks the local head is behind the sync target, only relevant while syncing.
+    sync_distance: Arc<AtomicU64>,
     /// The chain id
     chain_id: Arc<AtomicU64>,
     /// Whether to disable transaction gossip
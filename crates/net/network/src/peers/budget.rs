@@ -0,0 +1,248 @@
+//! Source-aware budgeting of outbound connection slots.
+//!
+//! If all outbound dial candidates come from the same source, whoever dominates that source (for
+//! example the discv4 responses of a few nodes) also dominates our outbound connections. The
+//! [`DialBudgetConfig`] caps the share of outbound slots per [`PeerSource`] and reserves slots for
+//! sources that are harder to manipulate, like DNS discovery or statically configured peers.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// Default maximum share of the outbound slots, in percent, for peers found via discv4.
+pub const DEFAULT_MAX_DISCV4_OUTBOUND_SHARE: u8 = 50;
+
+/// Default number of outbound slots reserved for peers from DNS discovery or static peers.
+pub const DEFAULT_RESERVED_OUTBOUND_SLOTS: usize = 2;
+
+/// Default duration after which the reserved outbound slots are released if there are no
+/// candidates from the reserved sources.
+pub const DEFAULT_RESERVATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Where a peer was learned from.
+///
+/// The variants are ordered by how hard they are to manipulate for a remote attacker: if a peer is
+/// learned from multiple sources, the greatest one is tracked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PeerSource {
+    /// The peer connected to us, we only know the address of the incoming connection.
+    Incoming,
+    /// The peer was found via discv4.
+    #[default]
+    Discv4,
+    /// The peer was found via discv5.
+    Discv5,
+    /// The peer was found via DNS discovery.
+    Dns,
+    /// The peer was loaded from the persisted peers file.
+    Persisted,
+    /// The peer was configured statically, as trusted peer or added manually.
+    Static,
+}
+
+/// Limits on the outbound slots that can be filled with peers of a single [`PeerSource`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct DialBudgetConfig {
+    /// Maximum share of the outbound slots, in percent, that peers of a source can occupy.
+    ///
+    /// Sources without an entry are not limited.
+    pub max_shares: BTreeMap<PeerSource, u8>,
+    /// Number of outbound slots that are kept free for peers of the `reserved_sources`.
+    pub reserved_slots: usize,
+    /// The sources the `reserved_slots` are reserved for.
+    pub reserved_sources: BTreeSet<PeerSource>,
+    /// How long the reserved slots are kept free after the last candidate of a reserved source
+    /// was available to dial.
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+    pub reservation_timeout: Duration,
+}
+
+impl DialBudgetConfig {
+    /// Returns a budget that doesn't restrict any source.
+    pub fn unrestricted() -> Self {
+        Self { max_shares: Default::default(), reserved_slots: 0, ..Default::default() }
+    }
+
+    /// Sets the maximum share of the outbound slots, in percent, for peers of the given source.
+    pub fn with_max_share(mut self, source: PeerSource, percent: u8) -> Self {
+        self.max_shares.insert(source, percent.min(100));
+        self
+    }
+
+    /// Reserves the given number of outbound slots for peers of the given sources.
+    pub fn with_reserved_slots(
+        mut self,
+        reserved_slots: usize,
+        sources: impl IntoIterator<Item = PeerSource>,
+    ) -> Self {
+        self.reserved_slots = reserved_slots;
+        self.reserved_sources = sources.into_iter().collect();
+        self
+    }
+
+    /// Sets how long the reserved slots are kept free without candidates of a reserved source.
+    pub const fn with_reservation_timeout(mut self, reservation_timeout: Duration) -> Self {
+        self.reservation_timeout = reservation_timeout;
+        self
+    }
+
+    /// Returns the number of outbound slots that peers of the given source can occupy.
+    ///
+    /// A source with a non-zero share can always occupy at least one slot.
+    fn max_slots(&self, source: PeerSource, max_outbound: usize) -> usize {
+        match self.max_shares.get(&source) {
+            Some(0) => 0,
+            Some(share) => (max_outbound * *share as usize / 100).max(1),
+            None => usize::MAX,
+        }
+    }
+}
+
+impl Default for DialBudgetConfig {
+    fn default() -> Self {
+        Self {
+            max_shares: BTreeMap::from([(PeerSource::Discv4, DEFAULT_MAX_DISCV4_OUTBOUND_SHARE)]),
+            reserved_slots: DEFAULT_RESERVED_OUTBOUND_SLOTS,
+            reserved_sources: BTreeSet::from([PeerSource::Dns, PeerSource::Static]),
+            reservation_timeout: DEFAULT_RESERVATION_TIMEOUT,
+        }
+    }
+}
+
+/// The number of outbound slots, active or pending, occupied per [`PeerSource`].
+#[derive(Debug, Default)]
+pub(crate) struct OutboundSlots {
+    by_source: HashMap<PeerSource, usize>,
+    total: usize,
+}
+
+impl OutboundSlots {
+    /// Returns the number of slots occupied by peers of the given source.
+    pub(crate) fn get(&self, source: PeerSource) -> usize {
+        self.by_source.get(&source).copied().unwrap_or_default()
+    }
+
+    /// Records another slot occupied by a peer of the given source.
+    pub(crate) fn inc(&mut self, source: PeerSource) {
+        *self.by_source.entry(source).or_default() += 1;
+        self.total += 1;
+    }
+}
+
+impl FromIterator<PeerSource> for OutboundSlots {
+    fn from_iter<I: IntoIterator<Item = PeerSource>>(iter: I) -> Self {
+        let mut slots = Self::default();
+        for source in iter {
+            slots.inc(source);
+        }
+        slots
+    }
+}
+
+/// Decides which sources may fill the free outbound slots.
+#[derive(Debug)]
+pub(crate) struct DialBudget {
+    config: DialBudgetConfig,
+    /// The last time a candidate of a reserved source was available to dial.
+    ///
+    /// The reservation is only held for [`DialBudgetConfig::reservation_timeout`] after that.
+    last_reserved_candidate: Option<Instant>,
+}
+
+impl DialBudget {
+    /// Creates a new budget with the given limits.
+    pub(crate) const fn new(config: DialBudgetConfig) -> Self {
+        Self { config, last_reserved_candidate: None }
+    }
+
+    /// Records that a peer of the given source is available to dial.
+    pub(crate) fn on_candidate(&mut self, source: PeerSource, now: Instant) {
+        if self.config.reserved_sources.contains(&source) {
+            self.last_reserved_candidate = Some(now);
+        }
+    }
+
+    /// Returns `true` if the reserved slots are still kept free.
+    fn is_reservation_held(&self, now: Instant) -> bool {
+        self.config.reserved_slots > 0 &&
+            self.last_reserved_candidate.is_some_and(|last| {
+                now.saturating_duration_since(last) < self.config.reservation_timeout
+            })
+    }
+
+    /// Returns `true` if another outbound slot can be filled with a peer of the given source.
+    pub(crate) fn allows(
+        &self,
+        source: PeerSource,
+        occupied: &OutboundSlots,
+        max_outbound: usize,
+        now: Instant,
+    ) -> bool {
+        if occupied.get(source) >= self.config.max_slots(source, max_outbound) {
+            return false
+        }
+        if self.config.reserved_sources.contains(&source) || !self.is_reservation_held(now) {
+            return true
+        }
+
+        let occupied_reserved =
+            self.config.reserved_sources.iter().map(|source| occupied.get(*source)).sum::<usize>();
+        let unused_reservation = self.config.reserved_slots.saturating_sub(occupied_reserved);
+        max_outbound.saturating_sub(occupied.total) > unused_reservation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caps_share_of_source() {
+        let budget = DialBudget::new(DialBudgetConfig::default());
+        let now = Instant::now();
+
+        let occupied = OutboundSlots::from_iter([PeerSource::Discv4; 4]);
+        assert!(budget.allows(PeerSource::Discv4, &occupied, 10, now));
+
+        let occupied = OutboundSlots::from_iter([PeerSource::Discv4; 5]);
+        assert!(!budget.allows(PeerSource::Discv4, &occupied, 10, now));
+        assert!(budget.allows(PeerSource::Discv5, &occupied, 10, now));
+
+        // a source with a share always gets a slot
+        assert!(budget.allows(PeerSource::Discv4, &OutboundSlots::default(), 1, now));
+    }
+
+    #[test]
+    fn releases_unused_reservation() {
+        let config = DialBudgetConfig::unrestricted().with_reserved_slots(2, [PeerSource::Dns]);
+        let timeout = config.reservation_timeout;
+        let mut budget = DialBudget::new(config);
+        let now = Instant::now();
+
+        let occupied = OutboundSlots::from_iter([PeerSource::Discv4; 8]);
+        // no reserved candidate seen yet
+        assert!(budget.allows(PeerSource::Discv4, &occupied, 10, now));
+
+        budget.on_candidate(PeerSource::Dns, now);
+        assert!(!budget.allows(PeerSource::Discv4, &occupied, 10, now));
+        assert!(budget.allows(PeerSource::Dns, &occupied, 10, now));
+
+        // one reserved slot is used, so only one has to be kept free
+        let occupied = OutboundSlots::from_iter(
+            std::iter::repeat(PeerSource::Discv4).take(8).chain([PeerSource::Dns]),
+        );
+        assert!(!budget.allows(PeerSource::Discv4, &occupied, 10, now));
+        let occupied = OutboundSlots::from_iter(
+            std::iter::repeat(PeerSource::Discv4).take(7).chain([PeerSource::Dns]),
+        );
+        assert!(budget.allows(PeerSource::Discv4, &occupied, 10, now));
+
+        let occupied = OutboundSlots::from_iter([PeerSource::Discv4; 8]);
+        assert!(budget.allows(PeerSource::Discv4, &occupied, 10, now + timeout));
+    }
+}
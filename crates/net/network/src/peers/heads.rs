@@ -0,0 +1,141 @@
+//! Chain heads reported by the active peers.
+
+use reth_network_peers::PeerId;
+use reth_primitives::{B256, U256};
+use std::{cmp::Reverse, collections::HashMap};
+
+/// The chain head a peer reported, either in its `Status` message or by announcing a new block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerHead {
+    /// Hash of the head block.
+    pub hash: B256,
+    /// Number of the head block.
+    ///
+    /// The `Status` message only contains the hash, so this is only known once the peer announced
+    /// a block.
+    pub number: Option<u64>,
+    /// Total difficulty of the chain up to the head block, if it was reported.
+    pub total_difficulty: Option<U256>,
+}
+
+/// A head reported by multiple peers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgreedHead {
+    /// Hash of the head block.
+    pub hash: B256,
+    /// The highest number any of the peers reported for the block.
+    pub number: Option<u64>,
+    /// The highest total difficulty any of the peers reported for the block.
+    pub total_difficulty: Option<U256>,
+    /// The peers that reported the head.
+    pub peers: Vec<PeerId>,
+}
+
+impl AgreedHead {
+    /// Groups the heads by hash and returns the ones that were reported by at least `min_peers`
+    /// distinct peers.
+    ///
+    /// The heads are ordered best first: by number, then total difficulty, then the number of
+    /// peers that reported them.
+    pub fn from_peer_heads(
+        heads: impl IntoIterator<Item = (PeerId, PeerHead)>,
+        min_peers: usize,
+    ) -> Vec<Self> {
+        let mut by_hash = HashMap::<B256, Self>::new();
+        for (peer_id, head) in heads {
+            let agreed = by_hash.entry(head.hash).or_insert_with(|| Self {
+                hash: head.hash,
+                number: None,
+                total_difficulty: None,
+                peers: Vec::new(),
+            });
+            agreed.number = agreed.number.max(head.number);
+            agreed.total_difficulty = agreed.total_difficulty.max(head.total_difficulty);
+            if !agreed.peers.contains(&peer_id) {
+                agreed.peers.push(peer_id);
+            }
+        }
+
+        let mut agreed = by_hash
+            .into_values()
+            .filter(|head| head.peers.len() >= min_peers.max(1))
+            .collect::<Vec<_>>();
+        agreed.sort_unstable_by_key(|head| {
+            Reverse((head.number, head.total_difficulty, head.peers.len()))
+        });
+        agreed
+    }
+}
+
+/// Tracks the heads of all active peers.
+#[derive(Debug, Default)]
+pub(crate) struct PeerHeads {
+    heads: HashMap<PeerId, PeerHead>,
+}
+
+impl PeerHeads {
+    /// Records the head from the `Status` message of a new session.
+    pub(crate) fn on_status(&mut self, peer_id: PeerId, hash: B256, total_difficulty: U256) {
+        self.heads.insert(
+            peer_id,
+            PeerHead { hash, number: None, total_difficulty: Some(total_difficulty) },
+        );
+    }
+
+    /// Records a new head announced by an active peer.
+    ///
+    /// The total difficulty of the previous head is kept, since announced hashes don't carry one.
+    pub(crate) fn on_new_block(&mut self, peer_id: &PeerId, hash: B256, number: u64) {
+        if let Some(head) = self.heads.get_mut(peer_id) {
+            if head.number.map_or(true, |current| number > current) {
+                head.hash = hash;
+                head.number = Some(number);
+            }
+        }
+    }
+
+    /// Removes the head of a peer whose session was closed.
+    pub(crate) fn remove(&mut self, peer_id: &PeerId) {
+        self.heads.remove(peer_id);
+    }
+
+    /// Returns the heads of all active peers.
+    pub(crate) fn heads(&self) -> Vec<(PeerId, PeerHead)> {
+        self.heads.iter().map(|(peer_id, head)| (*peer_id, *head)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn agreed_heads() {
+        let (a, b) = (B256::with_last_byte(1), B256::with_last_byte(2));
+        let peers = (0..4).map(|_| PeerId::random()).collect::<Vec<_>>();
+
+        let mut heads = PeerHeads::default();
+        for peer in &peers {
+            heads.on_status(*peer, a, U256::from(100));
+        }
+        // a single peer advances, announcements of older blocks are ignored
+        heads.on_new_block(&peers[0], b, 10);
+        heads.on_new_block(&peers[0], a, 9);
+        // unknown peers are ignored
+        heads.on_new_block(&PeerId::random(), b, 10);
+
+        let agreed = AgreedHead::from_peer_heads(heads.heads(), 1);
+        assert_eq!(agreed.iter().map(|head| head.hash).collect::<Vec<_>>(), vec![b, a]);
+        assert_eq!(agreed[0].number, Some(10));
+        assert_eq!(agreed[0].total_difficulty, Some(U256::from(100)));
+        assert_eq!(agreed[1].peers.len(), 3);
+
+        // `b` lacks agreement
+        let agreed = AgreedHead::from_peer_heads(heads.heads(), 2);
+        assert_eq!(agreed.iter().map(|head| head.hash).collect::<Vec<_>>(), vec![a]);
+
+        heads.remove(&peers[1]);
+        heads.remove(&peers[2]);
+        assert!(AgreedHead::from_peer_heads(heads.heads(), 2).is_empty());
+    }
+}
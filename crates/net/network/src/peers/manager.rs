@@ -1,6 +1,9 @@
 use crate::{
     error::{BackoffKind, SessionError},
+    metrics::PeersManagerMetrics,
     peers::{
+        budget::{DialBudget, DialBudgetConfig, OutboundSlots, PeerSource},
+        heads::{PeerHead, PeerHeads},
         reputation::{
             is_banned_reputation, DEFAULT_REPUTATION, MAX_TRUSTED_PEER_REPUTATION_CHANGE,
         },
@@ -11,11 +14,15 @@ use crate::{
     swarm::NetworkConnectionState,
 };
 use futures::StreamExt;
-use reth_eth_wire::{errors::EthStreamError, DisconnectReason};
+use reth_discv4::Discv4;
+use reth_eth_wire::{
+    errors::{EthStreamError, P2PStreamError},
+    DisconnectReason,
+};
 use reth_net_common::ban_list::BanList;
 use reth_network_api::{PeerKind, ReputationChangeKind};
 use reth_network_peers::PeerId;
-use reth_primitives::{ForkId, NodeRecord};
+use reth_primitives::{ForkId, NodeRecord, B256, U256};
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     fmt::Display,
@@ -38,6 +45,8 @@ use tracing::{info, trace};
 pub struct PeersHandle {
     /// Sender half of command channel back to the [`PeersManager`]
     manager_tx: mpsc::UnboundedSender<PeerCommand>,
+    /// Handle to the discv4 service, if discv4 is enabled.
+    discv4: Option<Discv4>,
 }
 
 // === impl PeersHandle ===
@@ -77,6 +86,21 @@ impl PeersHandle {
 
         rx.await.unwrap_or_default()
     }
+
+    /// Returns the chain heads reported by all active peers.
+    pub async fn peer_heads(&self) -> Vec<(PeerId, PeerHead)> {
+        let (tx, rx) = oneshot::channel();
+        self.send(PeerCommand::GetHeads(tx));
+
+        rx.await.unwrap_or_default()
+    }
+
+    /// Returns the endpoint currently advertised to other peers, or `None` if discv4 is disabled.
+    ///
+    /// This follows the external IP learned from the endpoints reported by other nodes.
+    pub fn advertised_endpoint(&self) -> Option<NodeRecord> {
+        self.discv4.as_ref().map(Discv4::node_record)
+    }
 }
 
 /// Maintains the state of _all_ the peers known to the network.
@@ -126,6 +150,14 @@ pub struct PeersManager {
     max_backoff_count: u8,
     /// Tracks the connection state of the node
     net_connection_state: NetworkConnectionState,
+    /// Limits the outbound slots per source of the dialed peers.
+    dial_budget: DialBudget,
+    /// The chain heads reported by the active peers.
+    heads: PeerHeads,
+    /// Metrics for the peers manager.
+    metrics: PeersManagerMetrics,
+    /// Handle to the discv4 service, if discv4 is enabled.
+    discv4: Option<Discv4>,
 }
 
 impl PeersManager {
@@ -142,6 +174,7 @@ impl PeersManager {
             trusted_nodes_only,
             basic_nodes,
             max_backoff_count,
+            dial_budget,
         } = config;
         let (manager_tx, handle_rx) = mpsc::unbounded_channel();
         let now = Instant::now();
@@ -158,7 +191,9 @@ impl PeersManager {
         }
 
         for NodeRecord { address, tcp_port, udp_port: _, id } in basic_nodes {
-            peers.entry(id).or_insert_with(|| Peer::new(SocketAddr::from((address, tcp_port))));
+            peers.entry(id).or_insert_with(|| {
+                Peer::with_source(SocketAddr::from((address, tcp_port)), PeerSource::Persisted)
+            });
         }
 
         Self {
@@ -179,12 +214,21 @@ impl PeersManager {
             last_tick: Instant::now(),
             max_backoff_count,
             net_connection_state: NetworkConnectionState::default(),
+            dial_budget: DialBudget::new(dial_budget),
+            heads: PeerHeads::default(),
+            metrics: Default::default(),
+            discv4: None,
         }
     }
 
     /// Returns a new [`PeersHandle`] that can send commands to this type.
     pub(crate) fn handle(&self) -> PeersHandle {
-        PeersHandle { manager_tx: self.manager_tx.clone() }
+        PeersHandle { manager_tx: self.manager_tx.clone(), discv4: self.discv4.clone() }
+    }
+
+    /// Sets the discv4 service that tracks the endpoint advertised to other peers.
+    pub(crate) fn set_discv4(&mut self, discv4: Discv4) {
+        self.discv4 = Some(discv4);
     }
 
     /// Returns the number of peers in the peer set
@@ -221,6 +265,21 @@ impl PeersManager {
         self.connection_info.num_pending_out
     }
 
+    /// Records the chain head from the `Status` message of a newly activated session.
+    pub(crate) fn on_peer_status(&mut self, peer_id: PeerId, hash: B256, total_difficulty: U256) {
+        self.heads.on_status(peer_id, hash, total_difficulty)
+    }
+
+    /// Records a block announced by an active peer as its new chain head.
+    pub(crate) fn on_peer_new_block(&mut self, peer_id: &PeerId, hash: B256, number: u64) {
+        self.heads.on_new_block(peer_id, hash, number)
+    }
+
+    /// Forgets the chain head of a peer whose session was closed.
+    pub(crate) fn on_peer_head_removed(&mut self, peer_id: &PeerId) {
+        self.heads.remove(peer_id)
+    }
+
     /// Returns the number of currently backed off peers.
     #[inline]
     pub(crate) fn num_backed_off_peers(&self) -> usize {
@@ -338,6 +397,7 @@ impl PeersManager {
                 // peer is missing in the table, we add it but mark it as to be removed after
                 // disconnect, because we only know the outgoing port
                 let mut peer = Peer::with_state(addr, PeerConnectionState::In);
+                peer.source = PeerSource::Incoming;
                 peer.remove_after_disconnect = true;
                 entry.insert(peer);
                 self.queued_actions.push_back(PeerAction::PeerAdded(peer_id));
@@ -365,6 +425,7 @@ impl PeersManager {
 
         self.ban_list.ban_peer_until(peer_id, std::time::Instant::now() + ban_duration);
         self.queued_actions.push_back(PeerAction::BanPeer { peer_id });
+        self.metrics.banned_peers.increment(1);
     }
 
     /// Bans the IP temporarily with the configured ban timeout
@@ -451,6 +512,7 @@ impl PeersManager {
         } else {
             return
         };
+        self.metrics.reputation_changes.increment(1);
 
         match outcome {
             ReputationChangeOutcome::None => {}
@@ -461,7 +523,7 @@ impl PeersManager {
             ReputationChangeOutcome::DisconnectAndBan => {
                 self.queued_actions.push_back(PeerAction::Disconnect {
                     peer_id: *peer_id,
-                    reason: Some(DisconnectReason::DisconnectRequested),
+                    reason: Some(DisconnectReason::ProtocolBreach),
                 });
                 self.ban_peer(*peer_id);
             }
@@ -525,13 +587,46 @@ impl PeersManager {
     ///
     /// Depending on whether the error is fatal, the peer will be removed from the peer set
     /// otherwise its reputation is slashed.
+    ///
+    /// If we failed to send an oversized message, the peer is not at fault and the session is
+    /// treated as gracefully closed. Receiving an oversized message from the peer is penalized as a
+    /// bad message.
     pub(crate) fn on_active_session_dropped(
         &mut self,
         remote_addr: &SocketAddr,
         peer_id: &PeerId,
         err: &EthStreamError,
     ) {
-        self.on_connection_failure(remote_addr, peer_id, err, ReputationChangeKind::Dropped)
+        let reputation_change = match err {
+            EthStreamError::OutboundMessageTooBig { .. } => {
+                return self.on_active_session_gracefully_closed(*peer_id)
+            }
+            EthStreamError::InboundMessageTooBig { .. } => ReputationChangeKind::BadMessage,
+            // the message was not encoded by a conforming implementation
+            EthStreamError::InvalidMessage(err) if err.is_non_canonical() => {
+                ReputationChangeKind::BadProtocol
+            }
+            _ => ReputationChangeKind::Dropped,
+        };
+        self.on_connection_failure(remote_addr, peer_id, err, reputation_change)
+    }
+
+    /// Called when the remote peer gracefully disconnected an _active_ session and told us why.
+    ///
+    /// The reason is recorded for the peer. Whether the peer is removed and banned or backed off is
+    /// decided by the reason, like for a session that failed with it, but its reputation is not
+    /// slashed.
+    pub(crate) fn on_active_session_disconnected_by_remote(
+        &mut self,
+        remote_addr: &SocketAddr,
+        peer_id: &PeerId,
+        reason: DisconnectReason,
+    ) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.last_disconnect_reason = Some(reason);
+        }
+        let err = EthStreamError::from(P2PStreamError::Disconnected(reason));
+        self.on_connection_failure(remote_addr, peer_id, &err, ReputationChangeKind::Dropped)
     }
 
     /// Called when an attempt to create an _outgoing_ pending session failed while setting up a tcp
@@ -659,11 +754,17 @@ impl PeersManager {
         }
     }
 
-    /// Called for a newly discovered peer.
+    /// Called for a newly discovered peer that was found by the given source.
     ///
     /// If the peer already exists, then the address, kind and `fork_id` will be updated.
-    pub(crate) fn add_peer(&mut self, peer_id: PeerId, addr: SocketAddr, fork_id: Option<ForkId>) {
-        self.add_peer_kind(peer_id, PeerKind::Basic, addr, fork_id)
+    pub(crate) fn add_peer(
+        &mut self,
+        peer_id: PeerId,
+        addr: SocketAddr,
+        fork_id: Option<ForkId>,
+        source: PeerSource,
+    ) {
+        self.add_peer_kind(peer_id, PeerKind::Basic, source, addr, fork_id)
     }
 
     /// Marks the given peer as trusted.
@@ -676,16 +777,18 @@ impl PeersManager {
     /// If the peer already exists, then the address and kind will be updated.
     #[allow(dead_code)]
     pub(crate) fn add_trusted_peer(&mut self, peer_id: PeerId, addr: SocketAddr) {
-        self.add_peer_kind(peer_id, PeerKind::Trusted, addr, None)
+        self.add_peer_kind(peer_id, PeerKind::Trusted, PeerSource::Static, addr, None)
     }
 
-    /// Called for a newly discovered peer.
+    /// Called for a newly discovered peer that was found by the given source.
     ///
-    /// If the peer already exists, then the address, kind and `fork_id` will be updated.
+    /// If the peer already exists, then the address, kind and `fork_id` will be updated. The
+    /// source of an existing peer is only replaced by a more trustworthy one, see [`PeerSource`].
     pub(crate) fn add_peer_kind(
         &mut self,
         peer_id: PeerId,
         kind: PeerKind,
+        source: PeerSource,
         addr: SocketAddr,
         fork_id: Option<ForkId>,
     ) {
@@ -697,6 +800,7 @@ impl PeersManager {
             Entry::Occupied(mut entry) => {
                 let peer = entry.get_mut();
                 peer.kind = kind;
+                peer.source = peer.source.max(source);
                 peer.fork_id = fork_id;
                 peer.addr = addr;
 
@@ -710,6 +814,7 @@ impl PeersManager {
             Entry::Vacant(entry) => {
                 trace!(target: "net::peers", ?peer_id, ?addr, "discovered new node");
                 let mut peer = Peer::with_kind(addr, kind);
+                peer.source = source;
                 peer.fork_id = fork_id;
                 entry.insert(peer);
                 self.queued_actions.push_back(PeerAction::PeerAdded(peer_id));
@@ -761,17 +866,37 @@ impl PeersManager {
         self.trusted_peer_ids.remove(&peer_id);
     }
 
+    /// Returns the outbound slots, active or pending, occupied per [`PeerSource`].
+    fn occupied_outbound_slots(&self) -> OutboundSlots {
+        self.peers
+            .values()
+            .filter(|peer| {
+                matches!(peer.state, PeerConnectionState::Out | PeerConnectionState::PendingOut)
+            })
+            .map(|peer| peer.source)
+            .collect()
+    }
+
     /// Returns the idle peer with the highest reputation.
     ///
     /// Peers that are `trusted`, see [`PeerKind`], are prioritized as long as they're not currently
     /// marked as banned or backed off.
     ///
+    /// Other peers are only considered if the dial budget of their [`PeerSource`] allows another
+    /// outbound slot, see [`DialBudgetConfig`]. Peers of the source that occupies the fewest slots
+    /// are preferred, so free slots are filled from under-represented sources first.
+    ///
     /// If `trusted_nodes_only` is enabled, see [`PeersConfig`], then this will only consider
     /// `trusted` peers.
     ///
     /// Returns `None` if no peer is available.
-    fn best_unconnected(&mut self) -> Option<(PeerId, &mut Peer)> {
-        let mut unconnected = self.peers.iter_mut().filter(|(_, peer)| {
+    fn best_unconnected(
+        &mut self,
+        occupied: &OutboundSlots,
+        now: Instant,
+    ) -> Option<(PeerId, &mut Peer)> {
+        let max_outbound = self.connection_info.max_outbound;
+        let unconnected = self.peers.iter().filter(|(_, peer)| {
             !peer.is_backed_off() &&
                 !peer.is_banned() &&
                 peer.state.is_unconnected() &&
@@ -779,24 +904,36 @@ impl PeersManager {
         });
 
         // keep track of the best peer, if there's one
-        let mut best_peer = unconnected.next()?;
+        let mut best_peer: Option<(&PeerId, &Peer)> = None;
 
-        if best_peer.1.is_trusted() {
-            return Some((*best_peer.0, best_peer.1))
-        }
+        for (peer_id, peer) in unconnected {
+            self.dial_budget.on_candidate(peer.source, now);
 
-        for maybe_better in unconnected {
             // if the peer is trusted, return it immediately
-            if maybe_better.1.is_trusted() {
-                return Some((*maybe_better.0, maybe_better.1))
+            if peer.is_trusted() {
+                best_peer = Some((peer_id, peer));
+                break
+            }
+
+            if !self.dial_budget.allows(peer.source, occupied, max_outbound, now) {
+                continue
             }
 
-            // otherwise we keep track of the best peer using the reputation
-            if maybe_better.1.reputation > best_peer.1.reputation {
-                best_peer = maybe_better;
+            // otherwise we keep track of the best peer using the occupied slots of its source and
+            // the reputation
+            let is_better = best_peer.map_or(true, |(_, best)| {
+                let (occupied_by_peer, occupied_by_best) =
+                    (occupied.get(peer.source), occupied.get(best.source));
+                occupied_by_peer < occupied_by_best ||
+                    (occupied_by_peer == occupied_by_best && peer.reputation > best.reputation)
+            });
+            if is_better {
+                best_peer = Some((peer_id, peer));
             }
         }
-        Some((*best_peer.0, best_peer.1))
+
+        let peer_id = *best_peer?.0;
+        self.peers.get_mut(&peer_id).map(|peer| (peer_id, peer))
     }
 
     /// If there's capacity for new outbound connections, this will queue new
@@ -812,17 +949,21 @@ impl PeersManager {
             return
         }
 
+        let now = Instant::now();
+        let mut occupied = self.occupied_outbound_slots();
+
         // as long as there a slots available fill them with the best peers
         while self.connection_info.has_out_capacity() {
             let action = {
-                let (peer_id, peer) = match self.best_unconnected() {
+                let (peer_id, peer) = match self.best_unconnected(&occupied, now) {
                     Some(peer) => peer,
                     _ => break,
                 };
 
-                trace!(target: "net::peers", ?peer_id, addr=?peer.addr, "schedule outbound connection");
+                trace!(target: "net::peers", ?peer_id, addr=?peer.addr, source=?peer.source, "schedule outbound connection");
 
                 peer.state = PeerConnectionState::PendingOut;
+                occupied.inc(peer.source);
                 PeerAction::Connect { peer_id, remote_addr: peer.addr }
             };
 
@@ -861,7 +1002,7 @@ impl PeersManager {
             while let Poll::Ready(Some(cmd)) = self.handle_rx.poll_next_unpin(cx) {
                 match cmd {
                     PeerCommand::Add(peer_id, addr) => {
-                        self.add_peer(peer_id, addr, None);
+                        self.add_peer(peer_id, addr, None, PeerSource::Static);
                     }
                     PeerCommand::Remove(peer) => self.remove_peer(peer),
                     PeerCommand::ReputationChange(peer_id, rep) => {
@@ -873,6 +1014,9 @@ impl PeersManager {
                     PeerCommand::GetPeers(tx) => {
                         let _ = tx.send(self.iter_peers().collect());
                     }
+                    PeerCommand::GetHeads(tx) => {
+                        let _ = tx.send(self.heads.heads());
+                    }
                 }
             }
 
@@ -1027,10 +1171,14 @@ pub struct Peer {
     remove_after_disconnect: bool,
     /// The kind of peer
     kind: PeerKind,
+    /// Where the peer was learned from.
+    source: PeerSource,
     /// Whether the peer is currently backed off.
     backed_off: bool,
     /// Counts number of times the peer was backed off due to a severe [`BackoffKind`].
     severe_backoff_counter: u8,
+    /// The reason the peer sent when it last disconnected from us.
+    last_disconnect_reason: Option<DisconnectReason>,
 }
 
 // === impl Peer ===
@@ -1041,7 +1189,7 @@ impl Peer {
     }
 
     fn trusted(addr: SocketAddr) -> Self {
-        Self { kind: PeerKind::Trusted, ..Self::new(addr) }
+        Self { kind: PeerKind::Trusted, source: PeerSource::Static, ..Self::new(addr) }
     }
 
     /// Returns the reputation of the peer
@@ -1049,6 +1197,16 @@ impl Peer {
         self.reputation
     }
 
+    /// Returns where the peer was learned from.
+    pub const fn source(&self) -> PeerSource {
+        self.source
+    }
+
+    /// Returns the reason the peer sent when it last disconnected from us, if any.
+    pub const fn last_disconnect_reason(&self) -> Option<DisconnectReason> {
+        self.last_disconnect_reason
+    }
+
     fn with_state(addr: SocketAddr, state: PeerConnectionState) -> Self {
         Self {
             addr,
@@ -1057,8 +1215,10 @@ impl Peer {
             fork_id: None,
             remove_after_disconnect: false,
             kind: Default::default(),
+            source: Default::default(),
             backed_off: false,
             severe_backoff_counter: 0,
+            last_disconnect_reason: None,
         }
     }
 
@@ -1066,6 +1226,10 @@ impl Peer {
         Self { kind, ..Self::new(addr) }
     }
 
+    fn with_source(addr: SocketAddr, source: PeerSource) -> Self {
+        Self { source, ..Self::new(addr) }
+    }
+
     /// Resets the reputation of the peer to the default value. This always returns
     /// [`ReputationChangeOutcome::None`].
     fn reset_reputation(&mut self) -> ReputationChangeOutcome {
@@ -1205,6 +1369,8 @@ pub(crate) enum PeerCommand {
     GetPeer(PeerId, oneshot::Sender<Option<Peer>>),
     /// Get node information on all peers
     GetPeers(oneshot::Sender<Vec<NodeRecord>>),
+    /// Get the chain heads reported by all active peers
+    GetHeads(oneshot::Sender<Vec<(PeerId, PeerHead)>>),
 }
 
 /// Actions the peer manager can trigger.
@@ -1304,6 +1470,8 @@ pub struct PeersConfig {
     ///
     /// The backoff duration increases with number of backoff attempts.
     pub backoff_durations: PeerBackoffDurations,
+    /// Limits on the outbound slots per source of the dialed peers.
+    pub dial_budget: DialBudgetConfig,
 }
 
 impl Default for PeersConfig {
@@ -1320,6 +1488,7 @@ impl Default for PeersConfig {
             trusted_nodes_only: false,
             basic_nodes: Default::default(),
             max_backoff_count: 5,
+            dial_budget: Default::default(),
         }
     }
 }
@@ -1422,6 +1591,12 @@ impl PeersConfig {
         self
     }
 
+    /// Configures the limits on the outbound slots per source of the dialed peers.
+    pub fn with_dial_budget(mut self, dial_budget: DialBudgetConfig) -> Self {
+        self.dial_budget = dial_budget;
+        self
+    }
+
     /// Returns the maximum number of peers, inbound and outbound.
     pub const fn max_peers(&self) -> usize {
         self.connection_info.max_outbound + self.connection_info.max_inbound
@@ -1545,22 +1720,24 @@ mod tests {
         peers::{
             manager::{ConnectionInfo, PeerBackoffDurations, PeerConnectionState},
             reputation::DEFAULT_REPUTATION,
-            InboundConnectionError, PeerAction,
+            DialBudgetConfig, InboundConnectionError, PeerAction, PeerSource,
         },
         session::PendingSessionHandshakeError,
         PeersConfig,
     };
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder, Snapshotter};
     use reth_discv4::NodeRecord;
     use reth_eth_wire::{
         errors::{EthHandshakeError, EthStreamError, P2PHandshakeError, P2PStreamError},
-        DisconnectReason,
+        message::MessageError,
+        DisconnectReason, EthMessageID, MAX_MESSAGE_SIZE,
     };
     use reth_net_common::ban_list::BanList;
     use reth_network_api::{Direction, ReputationChangeKind};
     use reth_network_peers::PeerId;
     use reth_primitives::B512;
     use std::{
-        collections::HashSet,
+        collections::{HashMap, HashSet},
         future::{poll_fn, Future},
         io,
         net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -1581,6 +1758,13 @@ mod tests {
         }
     }
 
+    fn counter(snapshotter: &Snapshotter, name: &str) -> Option<u64> {
+        snapshotter.snapshot().into_vec().into_iter().find_map(|(key, _, _, value)| match value {
+            DebugValue::Counter(value) if key.key().name() == name => Some(value),
+            _ => None,
+        })
+    }
+
     macro_rules! event {
         ($peers:expr) => {
             PeerActionFuture { peers: &mut $peers }.await
@@ -1592,7 +1776,7 @@ mod tests {
         let peer = PeerId::random();
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
         let mut peers = PeersManager::default();
-        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
 
         match event!(peers) {
             PeerAction::PeerAdded(peer_id) => {
@@ -1609,13 +1793,33 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_reputation_change_metrics() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let mut peers = metrics::with_local_recorder(&recorder, PeersManager::default);
+        let reputation_changes = || counter(&snapshotter, "network.peers.reputation_changes");
+
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
+        assert_eq!(reputation_changes(), Some(0));
+
+        peers.apply_reputation_change(&peer, ReputationChangeKind::BadMessage);
+        assert_eq!(reputation_changes(), Some(1));
+
+        // changes for unknown peers aren't applied
+        peers.apply_reputation_change(&PeerId::random(), ReputationChangeKind::BadMessage);
+        assert_eq!(reputation_changes(), Some(1));
+    }
+
     #[tokio::test]
     async fn test_ban() {
         let peer = PeerId::random();
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
         let mut peers = PeersManager::default();
         peers.ban_peer(peer);
-        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
 
         match event!(peers) {
             PeerAction::BanPeer { peer_id } => {
@@ -1637,7 +1841,7 @@ mod tests {
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
         let mut peers = PeersManager::default();
         peers.ban_peer(peer);
-        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
 
         match event!(peers) {
             PeerAction::BanPeer { peer_id } => {
@@ -1674,7 +1878,7 @@ mod tests {
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
 
         let mut peers = PeersManager::new(PeersConfig::test());
-        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
 
         match event!(peers) {
             PeerAction::PeerAdded(peer_id) => {
@@ -1725,6 +1929,93 @@ mod tests {
         assert!(!peers.peers.get(&peer).unwrap().is_backed_off());
     }
 
+    #[tokio::test]
+    async fn test_message_too_big_on_active_drop() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+
+        let mut peers = PeersManager::new(PeersConfig::test());
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
+        match event!(peers) {
+            PeerAction::PeerAdded(peer_id) => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+
+        // we failed to send an oversized response, the peer is not at fault
+        peers.on_active_session_dropped(
+            &socket_addr,
+            &peer,
+            &EthStreamError::OutboundMessageTooBig {
+                size: MAX_MESSAGE_SIZE + 1,
+                id: EthMessageID::BlockHeaders,
+            },
+        );
+        let p = peers.peers.get(&peer).unwrap();
+        assert_eq!(p.reputation, DEFAULT_REPUTATION);
+        assert!(!p.is_backed_off());
+        assert_eq!(p.state, PeerConnectionState::Idle);
+
+        // the peer sent an oversized message
+        peers.on_active_outgoing_established(peer);
+        peers.on_active_session_dropped(
+            &socket_addr,
+            &peer,
+            &EthStreamError::InboundMessageTooBig {
+                size: MAX_MESSAGE_SIZE + 1,
+                id: Some(EthMessageID::BlockHeaders),
+            },
+        );
+        let p = peers.peers.get(&peer).unwrap();
+        assert_eq!(
+            p.reputation,
+            DEFAULT_REPUTATION +
+                peers.reputation_weights.change(ReputationChangeKind::BadMessage).as_i32()
+        );
+        assert_eq!(p.state, PeerConnectionState::Idle);
+    }
+
+    #[tokio::test]
+    async fn test_non_canonical_message_on_active_drop() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+
+        let mut peers = PeersManager::new(PeersConfig::test());
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
+        match event!(peers) {
+            PeerAction::PeerAdded(peer_id) => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+
+        // the peer sent a message with trailing bytes
+        peers.on_active_session_dropped(
+            &socket_addr,
+            &peer,
+            &EthStreamError::InvalidMessage(MessageError::TrailingBytes(1)),
+        );
+        let p = peers.peers.get(&peer).unwrap();
+        assert_eq!(
+            p.reputation,
+            DEFAULT_REPUTATION +
+                peers.reputation_weights.change(ReputationChangeKind::BadProtocol).as_i32()
+        );
+    }
+
     #[tokio::test]
     async fn test_backoff_on_no_response() {
         let peer = PeerId::random();
@@ -1733,7 +2024,7 @@ mod tests {
         let backoff_durations = PeerBackoffDurations::test();
         let config = PeersConfig { backoff_durations, ..PeersConfig::test() };
         let mut peers = PeersManager::new(config);
-        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
 
         match event!(peers) {
             PeerAction::PeerAdded(peer_id) => {
@@ -1790,7 +2081,7 @@ mod tests {
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
         let config = PeersConfig::test();
         let mut peers = PeersManager::new(config);
-        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
         let peer_struct = peers.peers.get_mut(&peer).unwrap();
 
         let backoff_timestamp = peers
@@ -1807,7 +2098,7 @@ mod tests {
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
         let config = PeersConfig::default();
         let mut peers = PeersManager::new(config);
-        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
         let peer_struct = peers.peers.get_mut(&peer).unwrap();
 
         // Simulate a peer that was already backed off once
@@ -1835,7 +2126,7 @@ mod tests {
         let peer = PeerId::random();
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
         let mut peers = PeersManager::default();
-        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
 
         match event!(peers) {
             PeerAction::PeerAdded(peer_id) => {
@@ -1886,13 +2177,48 @@ mod tests {
         assert!(!peers.peers.contains_key(&peer));
     }
 
+    #[tokio::test]
+    async fn test_backoff_on_remote_disconnect() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::default();
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
+
+        match event!(peers) {
+            PeerAction::PeerAdded(peer_id) => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+
+        let reputation = peers.peers.get(&peer).unwrap().reputation();
+        peers.on_active_session_disconnected_by_remote(
+            &socket_addr,
+            &peer,
+            DisconnectReason::TooManyPeers,
+        );
+
+        // the peer is busy, so we back off without slashing its reputation
+        let peer_struct = peers.peers.get(&peer).unwrap();
+        assert_eq!(peer_struct.last_disconnect_reason(), Some(DisconnectReason::TooManyPeers));
+        assert_eq!(peer_struct.reputation(), reputation);
+        assert!(peer_struct.backed_off);
+        assert!(peer_struct.state.is_unconnected());
+    }
+
     #[tokio::test]
     async fn test_remove_on_max_backoff_count() {
         let peer = PeerId::random();
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
         let config = PeersConfig::test();
         let mut peers = PeersManager::new(config.clone());
-        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
         let peer_struct = peers.peers.get_mut(&peer).unwrap();
 
         // Simulate a peer that was already backed off once
@@ -1946,7 +2272,7 @@ mod tests {
         let peer = PeerId::random();
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
         let mut peers = PeersManager::default();
-        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
 
         match event!(peers) {
             PeerAction::PeerAdded(peer_id) => {
@@ -1997,6 +2323,68 @@ mod tests {
         assert!(!peers.peers.contains_key(&peer));
     }
 
+    #[tokio::test]
+    async fn test_ban_on_non_canonical_pending_drop() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::default();
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
+
+        match event!(peers) {
+            PeerAction::PeerAdded(peer_id) => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+
+        poll_fn(|cx| {
+            assert!(peers.poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+
+        // the peer sent a status with trailing bytes
+        peers.on_outgoing_pending_session_dropped(
+            &socket_addr,
+            &peer,
+            &PendingSessionHandshakeError::Eth(EthStreamError::InvalidMessage(
+                MessageError::TrailingBytes(1),
+            )),
+        );
+
+        match event!(peers) {
+            PeerAction::PeerRemoved(peer_id) => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::BanPeer { peer_id } => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+
+        assert!(!peers.peers.contains_key(&peer));
+
+        // the ip of an incoming session is banned
+        let remote_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 8008);
+        assert!(peers.on_incoming_pending_session(remote_addr.ip()).is_ok());
+        peers.on_incoming_pending_session_dropped(
+            remote_addr,
+            &PendingSessionHandshakeError::Eth(EthStreamError::InvalidMessage(
+                MessageError::TrailingBytes(1),
+            )),
+        );
+        assert!(peers.ban_list.is_banned_ip(&remote_addr.ip()));
+    }
+
     #[tokio::test]
     async fn test_internally_closed_incoming() {
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
@@ -2058,7 +2446,7 @@ mod tests {
         let peer = PeerId::random();
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
         let mut peers = PeersManager::default();
-        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
 
         match event!(peers) {
             PeerAction::PeerAdded(peer_id) => {
@@ -2253,7 +2641,7 @@ mod tests {
         let peer = PeerId::random();
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
         let mut peers = PeersManager::default();
-        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
         assert_eq!(peers.get_reputation(&peer), Some(0));
 
         peers.apply_reputation_change(&peer, ReputationChangeKind::Other(1024));
@@ -2268,7 +2656,7 @@ mod tests {
         let peer = PeerId::random();
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
         let mut peers = PeersManager::default();
-        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
 
         match event!(peers) {
             PeerAction::PeerAdded(peer_id) => {
@@ -2305,7 +2693,7 @@ mod tests {
         let p = peers.peers.get(&peer).unwrap();
         assert_eq!(p.state, PeerConnectionState::PendingOut);
 
-        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
         let p = peers.peers.get(&peer).unwrap();
         assert_eq!(p.state, PeerConnectionState::PendingOut);
 
@@ -2318,7 +2706,7 @@ mod tests {
         let peer = PeerId::random();
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
         let mut peers = PeersManager::default();
-        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
 
         match event!(peers) {
             PeerAction::PeerAdded(peer_id) => {
@@ -2353,7 +2741,7 @@ mod tests {
         let peer = PeerId::random();
         let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
         let mut peers = PeersManager::default();
-        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
 
         match event!(peers) {
             PeerAction::PeerAdded(peer_id) => {
@@ -2387,7 +2775,7 @@ mod tests {
         let ban_list = BanList::new(HashSet::new(), vec![ip]);
         let config = PeersConfig::default().with_ban_list(ban_list);
         let mut peer_manager = PeersManager::new(config);
-        peer_manager.add_peer(B512::default(), socket_addr, None);
+        peer_manager.add_peer(B512::default(), socket_addr, None, PeerSource::Static);
 
         assert!(peer_manager.peers.is_empty());
     }
@@ -2490,7 +2878,7 @@ mod tests {
 
         let basic_peer = PeerId::random();
         let basic_sock = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8009);
-        peers.add_peer(basic_peer, basic_sock, None);
+        peers.add_peer(basic_peer, basic_sock, None, PeerSource::Static);
 
         match event!(peers) {
             PeerAction::PeerAdded(peer_id) => {
@@ -2530,7 +2918,7 @@ mod tests {
 
         let basic_peer = PeerId::random();
         let basic_sock = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8009);
-        peers.add_peer(basic_peer, basic_sock, None);
+        peers.add_peer(basic_peer, basic_sock, None, PeerSource::Static);
 
         match event!(peers) {
             PeerAction::PeerAdded(peer_id) => {
@@ -2638,7 +3026,7 @@ mod tests {
         let config = PeersConfig::test();
         let mut peer_manager = PeersManager::new(config);
         let peer_id = PeerId::random();
-        peer_manager.add_peer(peer_id, socket_addr, None);
+        peer_manager.add_peer(peer_id, socket_addr, None, PeerSource::Static);
 
         tokio::time::sleep(Duration::from_secs(1)).await;
         peer_manager.tick();
@@ -2693,7 +3081,7 @@ mod tests {
         assert!(peer.remove_after_disconnect);
 
         // trigger discovery manually while the peer is still connected
-        peers.add_peer(peer_id, addr, None);
+        peers.add_peer(peer_id, addr, None, PeerSource::Static);
 
         peers.on_active_session_gracefully_closed(peer_id);
 
@@ -2709,7 +3097,7 @@ mod tests {
         let mut peers = PeersManager::default();
 
         peers.on_incoming_pending_session(addr.ip()).unwrap();
-        peers.add_peer(peer_id, addr, None);
+        peers.add_peer(peer_id, addr, None, PeerSource::Static);
 
         match event!(peers) {
             PeerAction::PeerAdded(_) => {}
@@ -2737,7 +3125,7 @@ mod tests {
         let mut peers = PeersManager::default();
 
         peers.on_incoming_pending_session(addr.ip()).unwrap();
-        peers.add_peer(peer_id, addr, None);
+        peers.add_peer(peer_id, addr, None, PeerSource::Static);
 
         match event!(peers) {
             PeerAction::PeerAdded(_) => {}
@@ -2770,7 +3158,7 @@ mod tests {
         let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2));
         let socket_addr = SocketAddr::new(ip, 8008);
         for _ in 0..peer_manager.connection_info.max_concurrent_outbound_dials * 2 {
-            peer_manager.add_peer(PeerId::random(), socket_addr, None);
+            peer_manager.add_peer(PeerId::random(), socket_addr, None, PeerSource::Static);
         }
 
         peer_manager.fill_outbound_slots();
@@ -2791,7 +3179,7 @@ mod tests {
 
         // add more peers than allowed
         for _ in 0..peer_manager.connection_info.max_concurrent_outbound_dials * 2 {
-            peer_manager.add_peer(PeerId::random(), socket_addr, None);
+            peer_manager.add_peer(PeerId::random(), socket_addr, None, PeerSource::Static);
         }
 
         for _ in 0..peer_manager.connection_info.max_concurrent_outbound_dials * 2 {
@@ -2839,4 +3227,93 @@ mod tests {
         // no more pending outbound connections
         assert_eq!(peer_manager.connection_info.num_pending_out, 0);
     }
+
+    /// Dials until no more candidates are allowed and returns the outbound peers per source.
+    fn establish_outbound(peers: &mut PeersManager) -> HashMap<PeerSource, usize> {
+        loop {
+            peers.fill_outbound_slots();
+            let pending = peers
+                .peers
+                .iter()
+                .filter(|(_, peer)| peer.state == PeerConnectionState::PendingOut)
+                .map(|(peer_id, _)| *peer_id)
+                .collect::<Vec<_>>();
+            if pending.is_empty() {
+                break
+            }
+            for peer_id in pending {
+                peers.on_active_outgoing_established(peer_id);
+            }
+        }
+
+        let mut outbound = HashMap::new();
+        for peer in peers.peers.values().filter(|peer| peer.state == PeerConnectionState::Out) {
+            *outbound.entry(peer.source()).or_default() += 1;
+        }
+        outbound
+    }
+
+    #[tokio::test]
+    async fn test_dial_budget_flooded_by_single_source() {
+        let config = PeersConfig::test().with_max_outbound(10);
+        let mut peers = PeersManager::new(config);
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+
+        for _ in 0..200 {
+            peers.add_peer(PeerId::random(), socket_addr, None, PeerSource::Discv4);
+        }
+        for source in [PeerSource::Discv5, PeerSource::Discv5, PeerSource::Dns, PeerSource::Dns] {
+            peers.add_peer(PeerId::random(), socket_addr, None, source);
+        }
+
+        let outbound = establish_outbound(&mut peers);
+        assert_eq!(outbound.get(&PeerSource::Discv4), Some(&5));
+        assert_eq!(outbound.get(&PeerSource::Discv5), Some(&2));
+        assert_eq!(outbound.get(&PeerSource::Dns), Some(&2));
+        assert_eq!(peers.num_outbound_connections(), 9);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_dial_budget_keeps_reserved_slots() {
+        let reservation_timeout = Duration::from_millis(100);
+        let dial_budget = DialBudgetConfig::default()
+            .with_max_share(PeerSource::Discv4, 100)
+            .with_reservation_timeout(reservation_timeout);
+        let config = PeersConfig::test().with_max_outbound(10).with_dial_budget(dial_budget);
+        let mut peers = PeersManager::new(config);
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+
+        for _ in 0..200 {
+            peers.add_peer(PeerId::random(), socket_addr, None, PeerSource::Discv4);
+        }
+        peers.add_peer(PeerId::random(), socket_addr, None, PeerSource::Dns);
+
+        // the single DNS candidate is connected, one more slot is kept free for DNS or static peers
+        let outbound = establish_outbound(&mut peers);
+        assert_eq!(outbound.get(&PeerSource::Discv4), Some(&8));
+        assert_eq!(outbound.get(&PeerSource::Dns), Some(&1));
+
+        // without new DNS or static candidates, the reservation is released
+        tokio::time::advance(reservation_timeout).await;
+        let outbound = establish_outbound(&mut peers);
+        assert_eq!(outbound.get(&PeerSource::Discv4), Some(&9));
+        assert_eq!(peers.num_outbound_connections(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_peer_source_upgrade() {
+        let mut peers = PeersManager::new(PeersConfig::test());
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+
+        peers.add_peer(peer, socket_addr, None, PeerSource::Dns);
+        assert_eq!(peers.peers.get(&peer).unwrap().source(), PeerSource::Dns);
+
+        // rediscovering the peer via discv4 doesn't downgrade it
+        peers.add_peer(peer, socket_addr, None, PeerSource::Discv4);
+        assert_eq!(peers.peers.get(&peer).unwrap().source(), PeerSource::Dns);
+
+        peers.add_peer(peer, socket_addr, None, PeerSource::Static);
+        assert_eq!(peers.peers.get(&peer).unwrap().source(), PeerSource::Static);
+    }
 }
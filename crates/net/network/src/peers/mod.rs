@@ -1,8 +1,15 @@
 //! Peer related implementations
 
+mod budget;
+mod heads;
 mod manager;
 mod reputation;
 
+pub use budget::{
+    DialBudgetConfig, PeerSource, DEFAULT_MAX_DISCV4_OUTBOUND_SHARE, DEFAULT_RESERVATION_TIMEOUT,
+    DEFAULT_RESERVED_OUTBOUND_SLOTS,
+};
+pub use heads::{AgreedHead, PeerHead};
 pub(crate) use manager::InboundConnectionError;
 pub use manager::{ConnectionInfo, Peer, PeerAction, PeersConfig, PeersHandle, PeersManager};
 pub use reputation::ReputationChangeWeights;
@@ -3,7 +3,7 @@
 use crate::{
     message::{NewBlockMessage, PeerMessage, PeerRequest, PeerResponse, PeerResponseResult},
     session::{
-        config::INITIAL_REQUEST_TIMEOUT,
+        config::{DISCONNECT_FLUSH_TIMEOUT, INITIAL_REQUEST_TIMEOUT},
         conn::EthRlpxConnection,
         handle::{ActiveSessionMessage, SessionCommand},
         SessionId,
@@ -16,7 +16,7 @@ use reth_eth_wire::{
     capability::Capabilities,
     errors::{EthHandshakeError, EthStreamError, P2PStreamError},
     message::{EthBroadcastMessage, RequestPair},
-    DisconnectP2P, DisconnectReason, EthMessage,
+    DisconnectP2P, DisconnectReason, EthMessage, MAX_MESSAGE_SIZE,
 };
 use reth_metrics::common::mpsc::MeteredPollSender;
 use reth_network_p2p::error::RequestError;
@@ -32,7 +32,7 @@ use std::{
 };
 use tokio::{
     sync::{mpsc::error::TrySendError, oneshot},
-    time::Interval,
+    time::{Interval, Sleep},
 };
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_util::sync::PollSender;
@@ -95,6 +95,8 @@ pub(crate) struct ActiveSession {
     pub(crate) protocol_breach_request_timeout: Duration,
     /// Used to reserve a slot to guarantee that the termination message is delivered
     pub(crate) terminate_message: Option<(PollSender<ActiveSessionMessage>, ActiveSessionMessage)>,
+    /// A requested disconnect that waits until the queued messages are flushed.
+    pub(crate) pending_disconnect: Option<PendingDisconnect>,
 }
 
 impl ActiveSession {
@@ -285,8 +287,20 @@ impl ActiveSession {
 
     /// Handle a Response to the peer
     ///
-    /// This will queue the response to be sent to the peer
-    fn handle_outgoing_response(&mut self, id: u64, resp: PeerResponseResult) {
+    /// This will queue the response to be sent to the peer. Responses exceeding the
+    /// [`MAX_MESSAGE_SIZE`] are truncated, the remaining items are dropped.
+    fn handle_outgoing_response(&mut self, id: u64, mut resp: PeerResponseResult) {
+        let dropped = resp.truncate_to_fit(MAX_MESSAGE_SIZE);
+        if dropped > 0 {
+            debug!(
+                target: "net::session",
+                remote_peer_id=?self.remote_peer_id,
+                request_id=id,
+                dropped,
+                "Truncated oversized response"
+            );
+        }
+
         match resp.try_into_message(id) {
             Ok(msg) => {
                 self.queued_outgoing.push_back(msg.into());
@@ -359,11 +373,23 @@ impl ActiveSession {
     }
 
     /// Report back that this session has been closed.
-    fn emit_disconnect(&mut self, cx: &mut Context<'_>) -> Poll<()> {
-        trace!(target: "net::session", remote_peer_id=?self.remote_peer_id, "emitting disconnect");
+    ///
+    /// The reason is set if the remote peer initiated the disconnect.
+    fn emit_disconnect(
+        &mut self,
+        reason: Option<DisconnectReason>,
+        cx: &mut Context<'_>,
+    ) -> Poll<()> {
+        trace!(
+            target: "net::session",
+            remote_peer_id=?self.remote_peer_id,
+            ?reason,
+            "emitting disconnect"
+        );
         let msg = ActiveSessionMessage::Disconnected {
             peer_id: self.remote_peer_id,
             remote_addr: self.remote_addr,
+            reason,
         };
 
         self.terminate_message = Some((self.to_session_manager.inner().clone(), msg));
@@ -396,7 +422,7 @@ impl ActiveSession {
 
         // try to close the flush out the remaining Disconnect message
         let _ = ready!(self.conn.poll_close_unpin(cx));
-        self.emit_disconnect(cx)
+        self.emit_disconnect(None, cx)
     }
 
     /// Attempts to disconnect by sending the given disconnect reason
@@ -413,6 +439,66 @@ impl ActiveSession {
         }
     }
 
+    /// Starts a graceful disconnect.
+    ///
+    /// Responses and block announcements that are still queued are sent before the `Disconnect`
+    /// message, but for at most [`DISCONNECT_FLUSH_TIMEOUT`]. Transaction gossip and our own
+    /// requests are dropped, since they're of no use once the session is closed.
+    fn start_graceful_disconnect(
+        &mut self,
+        reason: DisconnectReason,
+        cx: &mut Context<'_>,
+    ) -> Poll<()> {
+        self.queued_outgoing.retain(OutgoingMessage::is_flushed_on_disconnect);
+        self.pending_disconnect = Some(PendingDisconnect {
+            reason,
+            deadline: Box::pin(tokio::time::sleep(DISCONNECT_FLUSH_TIMEOUT)),
+        });
+        self.poll_pending_disconnect(cx)
+    }
+
+    /// Flushes the queued messages of a pending disconnect and sends the `Disconnect` message once
+    /// they're written or the deadline elapsed.
+    fn poll_pending_disconnect(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let pending = self.pending_disconnect.as_mut().expect("disconnect is pending");
+        if pending.deadline.as_mut().poll(cx).is_ready() {
+            debug!(
+                target: "net::session",
+                remote_peer_id=?self.remote_peer_id,
+                unsent=self.queued_outgoing.len(),
+                "timed out flushing messages before disconnect"
+            );
+        } else {
+            loop {
+                if self.queued_outgoing.is_empty() {
+                    // the messages must be written out, because starting the disconnect clears the
+                    // buffer of the connection
+                    match self.conn.poll_flush_unpin(cx) {
+                        Poll::Ready(Ok(())) => break,
+                        Poll::Ready(Err(err)) => return self.close_on_error(err, cx),
+                        Poll::Pending => return Poll::Pending,
+                    }
+                }
+
+                match self.conn.poll_ready_unpin(cx) {
+                    Poll::Ready(Ok(())) => {}
+                    Poll::Ready(Err(err)) => return self.close_on_error(err, cx),
+                    Poll::Pending => return Poll::Pending,
+                }
+                let res = match self.queued_outgoing.pop_front().expect("not empty") {
+                    OutgoingMessage::Eth(msg) => self.conn.start_send_unpin(msg),
+                    OutgoingMessage::Broadcast(msg) => self.conn.start_send_broadcast(msg),
+                };
+                if let Err(err) = res {
+                    return self.close_on_error(err, cx)
+                }
+            }
+        }
+
+        let reason = self.pending_disconnect.take().expect("disconnect is pending").reason;
+        self.try_disconnect(reason, cx)
+    }
+
     /// Checks for _internally_ timed out requests.
     ///
     /// If a requests misses its deadline, then it is timed out internally.
@@ -482,6 +568,10 @@ impl Future for ActiveSession {
             return this.poll_disconnect(cx)
         }
 
+        if this.pending_disconnect.is_some() {
+            return this.poll_pending_disconnect(cx)
+        }
+
         // The receive loop can be CPU intensive since it involves message decoding which could take
         // up a lot of resources and increase latencies for other sessions if not yielded manually.
         // If the budget is exhausted we manually yield back control to the (coop) scheduler. This
@@ -515,7 +605,7 @@ impl Future for ActiveSession {
                                 let reason =
                                     reason.unwrap_or(DisconnectReason::DisconnectRequested);
 
-                                return this.try_disconnect(reason, cx)
+                                return this.start_graceful_disconnect(reason, cx)
                             }
                             SessionCommand::Message(msg) => {
                                 this.on_internal_peer_message(msg);
@@ -599,7 +689,7 @@ impl Future for ActiveSession {
                             break
                         } else {
                             debug!(target: "net::session", remote_peer_id=?this.remote_peer_id, "eth stream completed");
-                            return this.emit_disconnect(cx)
+                            return this.emit_disconnect(None, cx)
                         }
                     }
                     Poll::Ready(Some(res)) => {
@@ -624,6 +714,10 @@ impl Future for ActiveSession {
                                 }
                             }
                             Err(err) => {
+                                if let Some(reason) = err.as_disconnected() {
+                                    debug!(target: "net::session", ?reason, remote_peer_id=?this.remote_peer_id, "peer disconnected");
+                                    return this.emit_disconnect(Some(reason), cx)
+                                }
                                 debug!(target: "net::session", %err, remote_peer_id=?this.remote_peer_id, "failed to receive message");
                                 return this.close_on_error(err, cx)
                             }
@@ -719,6 +813,14 @@ impl From<Result<(), ActiveSessionMessage>> for OnIncomingMessageOutcome {
     }
 }
 
+/// A disconnect that's delayed until the queued outgoing messages are flushed.
+pub(crate) struct PendingDisconnect {
+    /// The reason that's sent to the peer.
+    reason: DisconnectReason,
+    /// When to stop waiting for the queued messages.
+    deadline: Pin<Box<Sleep>>,
+}
+
 enum RequestState {
     /// Waiting for the response
     Waiting(PeerRequest),
@@ -734,6 +836,27 @@ pub(crate) enum OutgoingMessage {
     Broadcast(EthBroadcastMessage),
 }
 
+impl OutgoingMessage {
+    /// Returns `true` if the message is still sent before a graceful disconnect, see
+    /// [`ActiveSession::start_graceful_disconnect`].
+    const fn is_flushed_on_disconnect(&self) -> bool {
+        match self {
+            Self::Eth(msg) => !matches!(
+                msg,
+                EthMessage::Transactions(_) |
+                    EthMessage::NewPooledTransactionHashes66(_) |
+                    EthMessage::NewPooledTransactionHashes68(_) |
+                    EthMessage::GetBlockHeaders(_) |
+                    EthMessage::GetBlockBodies(_) |
+                    EthMessage::GetPooledTransactions(_) |
+                    EthMessage::GetNodeData(_) |
+                    EthMessage::GetReceipts(_)
+            ),
+            Self::Broadcast(msg) => matches!(msg, EthBroadcastMessage::NewBlock(_)),
+        }
+    }
+}
+
 impl From<EthMessage> for OutgoingMessage {
     fn from(value: EthMessage) -> Self {
         Self::Eth(value)
@@ -760,16 +883,18 @@ fn calculate_new_timeout(current_timeout: Duration, estimated_rtt: Duration) ->
 mod tests {
     use super::*;
     use crate::session::{
-        config::PROTOCOL_BREACH_REQUEST_TIMEOUT, handle::PendingSessionEvent,
+        config::PROTOCOL_BREACH_REQUEST_TIMEOUT,
+        handle::PendingSessionEvent,
+        handshake::{HandshakePhase, HandshakeProgress},
         start_pending_incoming_session,
     };
     use reth_ecies::stream::ECIESStream;
     use reth_eth_wire::{
-        EthStream, GetBlockBodies, HelloMessageWithProtocols, P2PStream, Status, StatusBuilder,
-        UnauthedEthStream, UnauthedP2PStream,
+        BlockHashNumber, EthStream, GetBlockBodies, HelloMessageWithProtocols, NewBlockHashes,
+        P2PStream, SharedTransactions, Status, StatusBuilder, UnauthedEthStream, UnauthedP2PStream,
     };
     use reth_network_peers::pk2id;
-    use reth_primitives::{ForkFilter, Hardfork, MAINNET};
+    use reth_primitives::{ForkFilter, Hardfork, B256, MAINNET};
     use secp256k1::{SecretKey, SECP256K1};
     use tokio::{
         net::{TcpListener, TcpStream},
@@ -848,6 +973,7 @@ mod tests {
                 self.status,
                 self.fork_filter.clone(),
                 Default::default(),
+                HandshakeProgress::new(HandshakePhase::Ecies),
             ));
 
             let mut stream = ReceiverStream::new(pending_sessions_rx);
@@ -892,6 +1018,7 @@ mod tests {
                         )),
                         protocol_breach_request_timeout: PROTOCOL_BREACH_REQUEST_TIMEOUT,
                         terminate_message: None,
+                        pending_disconnect: None,
                     }
                 }
                 ev => {
@@ -950,6 +1077,72 @@ mod tests {
         fut.await;
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn graceful_disconnect_flushes_queued_messages() {
+        let mut builder = SessionBuilder::default();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        let hashes = NewBlockHashes(vec![BlockHashNumber { hash: B256::random(), number: 1 }]);
+        let expected = EthMessage::NewBlockHashes(hashes.clone());
+
+        let fut = builder.with_client_stream(local_addr, move |mut client_stream| async move {
+            // the block announcement is delivered, the queued transactions are dropped
+            let msg = client_stream.next().await.unwrap().unwrap();
+            assert_eq!(msg, expected);
+            let err = client_stream.next().await.unwrap().unwrap_err();
+            assert_eq!(err.as_disconnected(), Some(DisconnectReason::TooManyPeers));
+        });
+
+        tokio::task::spawn(async move {
+            let (incoming, _) = listener.accept().await.unwrap();
+            let mut session = builder.connect_incoming(incoming).await;
+
+            session.on_internal_peer_message(PeerMessage::NewBlockHashes(hashes));
+            session.on_internal_peer_message(PeerMessage::SendTransactions(SharedTransactions(
+                Vec::new(),
+            )));
+            builder.to_sessions[0]
+                .send(SessionCommand::Disconnect { reason: Some(DisconnectReason::TooManyPeers) })
+                .await
+                .unwrap();
+            session.await;
+
+            match builder.active_session_rx.next().await.unwrap() {
+                ActiveSessionMessage::Disconnected { reason: None, .. } => {}
+                ev => unreachable!("{ev:?}"),
+            }
+        });
+
+        fut.await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn records_remote_disconnect_reason() {
+        let mut builder = SessionBuilder::default();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        let fut = builder.with_client_stream(local_addr, move |client_stream| async move {
+            client_stream.into_inner().disconnect(DisconnectReason::ClientQuitting).await.unwrap();
+        });
+        tokio::task::spawn(fut);
+
+        let (incoming, _) = listener.accept().await.unwrap();
+        let session = builder.connect_incoming(incoming).await;
+        session.await;
+
+        // the reason is reported instead of a connection error
+        match builder.active_session_rx.next().await.unwrap() {
+            ActiveSessionMessage::Disconnected { reason, .. } => {
+                assert_eq!(reason, Some(DisconnectReason::ClientQuitting));
+            }
+            ev => unreachable!("{ev:?}"),
+        }
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn handle_dropped_stream() {
         let mut builder = SessionBuilder::default();
@@ -11,14 +11,20 @@ use std::time::Duration;
 /// This represents the amount of time we wait for a response until we consider it timed out.
 pub const INITIAL_REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
 
-/// Default timeout after which a pending session attempt is considered failed.
-pub const PENDING_SESSION_TIMEOUT: Duration = Duration::from_secs(20);
+/// Default deadline for the handshake of a pending session.
+///
+/// This covers all phases of the handshake: the TCP connect of outgoing sessions, the `ECIES`
+/// handshake and the `Hello` and `Status` exchanges.
+pub const PENDING_SESSION_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Default timeout after which we'll consider the peer to be in violation of the protocol.
 ///
 /// This is the time a peer has to answer a response.
 pub const PROTOCOL_BREACH_REQUEST_TIMEOUT: Duration = Duration::from_secs(2 * 60);
 
+/// Max time to flush the queued messages of a session before it sends the `Disconnect` message.
+pub const DISCONNECT_FLUSH_TIMEOUT: Duration = Duration::from_secs(1);
+
 /// The default maximum number of peers.
 const DEFAULT_MAX_PEERS: usize =
     DEFAULT_MAX_COUNT_PEERS_OUTBOUND as usize + DEFAULT_MAX_COUNT_PEERS_INBOUND as usize;
@@ -51,7 +57,8 @@ pub struct SessionsConfig {
     /// `PROTOCOL_BREACH_REQUEST_TIMEOUT`) this is considered a protocol violation and results in a
     /// dropped session.
     pub protocol_breach_request_timeout: Duration,
-    /// The timeout after which a pending session attempt is considered failed.
+    /// The deadline for the entire handshake of a pending session, after which the attempt is
+    /// considered failed.
     pub pending_session_timeout: Duration,
 }
 
@@ -103,6 +110,12 @@ impl SessionsConfig {
         }
         self
     }
+
+    /// Sets the deadline for the entire handshake of a pending session.
+    pub const fn with_pending_session_timeout(mut self, timeout: Duration) -> Self {
+        self.pending_session_timeout = timeout;
+        self
+    }
 }
 
 /// Limits for sessions.
@@ -2,7 +2,7 @@
 
 use crate::{
     message::PeerMessage,
-    session::{conn::EthRlpxConnection, Direction, SessionId},
+    session::{conn::EthRlpxConnection, Direction, HandshakeTimings, SessionId},
     PendingSessionHandshakeError,
 };
 use reth_ecies::ECIESError;
@@ -179,6 +179,8 @@ pub enum PendingSessionEvent {
         direction: Direction,
         /// The remote node's user agent, usually containing the client name and version
         client_id: String,
+        /// How long the phases of the handshake took
+        timings: HandshakeTimings,
     },
     /// Handshake unsuccessful, session was disconnected.
     Disconnected {
@@ -237,6 +239,8 @@ pub enum ActiveSessionMessage {
         peer_id: PeerId,
         /// The remote node's socket address
         remote_addr: SocketAddr,
+        /// The reason the remote node sent, if it initiated the disconnect
+        reason: Option<DisconnectReason>,
     },
     /// Session was closed due an error
     ClosedOnConnectionError {
@@ -0,0 +1,120 @@
+//! Phases and timings of the handshake of a pending session.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// A phase of the handshake of a pending session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum HandshakePhase {
+    /// Establishing the TCP connection, only for outgoing sessions.
+    Connect = 0,
+    /// The `ECIES` handshake that encrypts the stream.
+    Ecies = 1,
+    /// The exchange of the `p2p` `Hello` messages.
+    Hello = 2,
+    /// The exchange of the `eth` `Status` messages.
+    Status = 3,
+}
+
+impl HandshakePhase {
+    const fn from_u8(phase: u8) -> Self {
+        match phase {
+            0 => Self::Connect,
+            1 => Self::Ecies,
+            2 => Self::Hello,
+            _ => Self::Status,
+        }
+    }
+}
+
+impl fmt::Display for HandshakePhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Connect => f.write_str("connect"),
+            Self::Ecies => f.write_str("ECIES"),
+            Self::Hello => f.write_str("hello"),
+            Self::Status => f.write_str("status"),
+        }
+    }
+}
+
+/// The phase a pending session handshake is in.
+///
+/// Shared between the handshake and its deadline, so a timed out handshake reports the phase it
+/// stalled in.
+#[derive(Debug, Clone)]
+pub(crate) struct HandshakeProgress(Arc<AtomicU8>);
+
+impl HandshakeProgress {
+    /// Creates the progress of a handshake that starts in the given phase.
+    pub(crate) fn new(phase: HandshakePhase) -> Self {
+        Self(Arc::new(AtomicU8::new(phase as u8)))
+    }
+
+    /// Returns the current phase.
+    pub(crate) fn phase(&self) -> HandshakePhase {
+        HandshakePhase::from_u8(self.0.load(Ordering::Relaxed))
+    }
+
+    fn enter(&self, phase: HandshakePhase) {
+        self.0.store(phase as u8, Ordering::Relaxed);
+    }
+}
+
+/// How long the phases of a successful handshake took.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HandshakeTimings {
+    /// Duration of the `ECIES` handshake.
+    pub ecies: Duration,
+    /// Duration of the `Hello` exchange.
+    pub hello: Duration,
+    /// Duration of the `Status` exchange.
+    pub status: Duration,
+}
+
+/// Measures the phases of a handshake and reports them to its [`HandshakeProgress`].
+#[derive(Debug)]
+pub(crate) struct HandshakeTimer {
+    progress: HandshakeProgress,
+    phase_start: Instant,
+    timings: HandshakeTimings,
+}
+
+impl HandshakeTimer {
+    /// Enters the first measured phase of the handshake.
+    pub(crate) fn start(progress: HandshakeProgress, phase: HandshakePhase) -> Self {
+        progress.enter(phase);
+        Self { progress, phase_start: Instant::now(), timings: HandshakeTimings::default() }
+    }
+
+    /// Ends the current phase and enters the next one.
+    pub(crate) fn enter(&mut self, phase: HandshakePhase) {
+        self.end_phase();
+        self.progress.enter(phase);
+    }
+
+    /// Ends the current phase and returns the timings of the handshake.
+    pub(crate) fn finish(mut self) -> HandshakeTimings {
+        self.end_phase();
+        self.timings
+    }
+
+    fn end_phase(&mut self) {
+        let now = Instant::now();
+        let elapsed = now - self.phase_start;
+        match self.progress.phase() {
+            HandshakePhase::Connect => {}
+            HandshakePhase::Ecies => self.timings.ecies = elapsed,
+            HandshakePhase::Hello => self.timings.hello = elapsed,
+            HandshakePhase::Status => self.timings.status = elapsed,
+        }
+        self.phase_start = now;
+    }
+}
@@ -41,9 +41,12 @@ mod active;
 mod config;
 mod conn;
 mod handle;
+mod handshake;
 pub use crate::message::PeerRequestSender;
 use crate::protocol::{IntoRlpxSubProtocol, RlpxSubProtocolHandlers, RlpxSubProtocols};
 pub use config::{SessionLimits, SessionsConfig};
+use handshake::{HandshakeProgress, HandshakeTimer};
+pub use handshake::{HandshakePhase, HandshakeTimings};
 pub use handle::{
     ActiveSessionHandle, ActiveSessionMessage, PendingSessionEvent, PendingSessionHandle,
     SessionCommand,
@@ -238,12 +241,14 @@ impl SessionManager {
         let status = self.status;
         let fork_filter = self.fork_filter.clone();
         let extra_handlers = self.extra_protocols.on_incoming(remote_addr);
+        let progress = HandshakeProgress::new(HandshakePhase::Ecies);
         self.spawn(pending_session_with_timeout(
             self.pending_session_timeout,
             session_id,
             remote_addr,
             Direction::Incoming,
             pending_events.clone(),
+            progress.clone(),
             start_pending_incoming_session(
                 disconnect_rx,
                 session_id,
@@ -255,6 +260,7 @@ impl SessionManager {
                 status,
                 fork_filter,
                 extra_handlers,
+                progress,
             ),
         ));
 
@@ -279,12 +285,14 @@ impl SessionManager {
             let fork_filter = self.fork_filter.clone();
             let status = self.status;
             let extra_handlers = self.extra_protocols.on_outgoing(remote_addr, remote_peer_id);
+            let progress = HandshakeProgress::new(HandshakePhase::Connect);
             self.spawn(pending_session_with_timeout(
                 self.pending_session_timeout,
                 session_id,
                 remote_addr,
                 Direction::Outgoing(remote_peer_id),
                 pending_events.clone(),
+                progress.clone(),
                 start_pending_outbound_session(
                     disconnect_rx,
                     pending_events,
@@ -296,6 +304,7 @@ impl SessionManager {
                     status,
                     fork_filter,
                     extra_handlers,
+                    progress,
                 ),
             ));
 
@@ -368,14 +377,15 @@ impl SessionManager {
             }
             Poll::Ready(Some(event)) => {
                 return match event {
-                    ActiveSessionMessage::Disconnected { peer_id, remote_addr } => {
+                    ActiveSessionMessage::Disconnected { peer_id, remote_addr, reason } => {
                         trace!(
                             target: "net::session",
                             ?peer_id,
+                            ?reason,
                             "gracefully disconnected active session."
                         );
                         self.remove_active_session(&peer_id);
-                        Poll::Ready(SessionEvent::Disconnected { peer_id, remote_addr })
+                        Poll::Ready(SessionEvent::Disconnected { peer_id, remote_addr, reason })
                     }
                     ActiveSessionMessage::ClosedOnConnectionError {
                         peer_id,
@@ -423,6 +433,7 @@ impl SessionManager {
                 status,
                 direction,
                 client_id,
+                timings,
             } => {
                 // move from pending to established.
                 self.remove_pending_session(&session_id);
@@ -484,6 +495,7 @@ impl SessionManager {
                     internal_request_timeout: Arc::clone(&timeout),
                     protocol_breach_request_timeout: self.protocol_breach_request_timeout,
                     terminate_message: None,
+                    pending_disconnect: None,
                 };
 
                 self.spawn(session);
@@ -509,6 +521,9 @@ impl SessionManager {
                 if direction.is_outgoing() {
                     self.metrics.total_dial_successes.increment(1);
                 }
+                self.metrics.handshake_ecies_duration.record(timings.ecies.as_secs_f64());
+                self.metrics.handshake_hello_duration.record(timings.hello.as_secs_f64());
+                self.metrics.handshake_status_duration.record(timings.status.as_secs_f64());
 
                 Poll::Ready(SessionEvent::SessionEstablished {
                     peer_id,
@@ -724,6 +739,8 @@ pub enum SessionEvent {
         peer_id: PeerId,
         /// The remote node's socket address that we were connected to
         remote_addr: SocketAddr,
+        /// The reason the remote node sent, if it initiated the disconnect
+        reason: Option<DisconnectReason>,
     },
 }
 
@@ -736,9 +753,10 @@ pub enum PendingSessionHandshakeError {
     /// The pending session failed due to an error while establishing the ECIES stream
     #[error(transparent)]
     Ecies(ECIESError),
-    /// Thrown when the authentication timed out
-    #[error("authentication timed out")]
-    Timeout,
+    /// Thrown when the handshake didn't complete before the deadline, with the phase it stalled
+    /// in.
+    #[error("authentication timed out during the {0} phase")]
+    Timeout(HandshakePhase),
 }
 
 impl PendingSessionHandshakeError {
@@ -758,23 +776,29 @@ impl PendingSessionHandshakeError {
 pub struct ExceedsSessionLimit(pub(crate) u32);
 
 /// Starts a pending session authentication with a timeout.
+///
+/// The timeout is a deadline for the entire handshake. If it passes, the handshake is dropped,
+/// which closes the connection, and the phase it stalled in is reported.
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn pending_session_with_timeout<F>(
     timeout: Duration,
     session_id: SessionId,
     remote_addr: SocketAddr,
     direction: Direction,
     events: mpsc::Sender<PendingSessionEvent>,
+    progress: HandshakeProgress,
     f: F,
 ) where
     F: Future<Output = ()>,
 {
     if tokio::time::timeout(timeout, f).await.is_err() {
-        debug!(target: "net::session", ?remote_addr, ?direction, "pending session timed out");
+        let phase = progress.phase();
+        debug!(target: "net::session", ?remote_addr, ?direction, %phase, "pending session timed out");
         let event = PendingSessionEvent::Disconnected {
             remote_addr,
             session_id,
             direction,
-            error: Some(PendingSessionHandshakeError::Timeout),
+            error: Some(PendingSessionHandshakeError::Timeout(phase)),
         };
         let _ = events.send(event).await;
     }
@@ -795,6 +819,7 @@ pub(crate) async fn start_pending_incoming_session(
     status: Status,
     fork_filter: ForkFilter,
     extra_handlers: RlpxSubProtocolHandlers,
+    progress: HandshakeProgress,
 ) {
     authenticate(
         disconnect_rx,
@@ -808,6 +833,7 @@ pub(crate) async fn start_pending_incoming_session(
         status,
         fork_filter,
         extra_handlers,
+        progress,
     )
     .await
 }
@@ -826,6 +852,7 @@ async fn start_pending_outbound_session(
     status: Status,
     fork_filter: ForkFilter,
     extra_handlers: RlpxSubProtocolHandlers,
+    progress: HandshakeProgress,
 ) {
     let stream = match TcpStream::connect(remote_addr).await {
         Ok(stream) => {
@@ -858,6 +885,7 @@ async fn start_pending_outbound_session(
         status,
         fork_filter,
         extra_handlers,
+        progress,
     )
     .await
 }
@@ -876,8 +904,10 @@ async fn authenticate(
     status: Status,
     fork_filter: ForkFilter,
     extra_handlers: RlpxSubProtocolHandlers,
+    progress: HandshakeProgress,
 ) {
     let local_addr = stream.local_addr().ok();
+    let mut timer = HandshakeTimer::start(progress, HandshakePhase::Ecies);
     let stream = match get_eciess_stream(stream, secret_key, direction).await {
         Ok(stream) => stream,
         Err(error) => {
@@ -894,6 +924,7 @@ async fn authenticate(
     };
 
     let unauthed = UnauthedP2PStream::new(stream);
+    timer.enter(HandshakePhase::Hello);
 
     let auth = authenticate_stream(
         unauthed,
@@ -905,6 +936,7 @@ async fn authenticate(
         status,
         fork_filter,
         extra_handlers,
+        timer,
     )
     .boxed();
 
@@ -957,6 +989,7 @@ async fn authenticate_stream(
     mut status: Status,
     fork_filter: ForkFilter,
     mut extra_handlers: RlpxSubProtocolHandlers,
+    mut timer: HandshakeTimer,
 ) -> PendingSessionEvent {
     // Add extra protocols to the hello message
     extra_handlers.retain(|handler| hello.try_add_protocol(handler.protocol()).is_ok());
@@ -987,6 +1020,7 @@ async fn authenticate_stream(
         }
     };
 
+    timer.enter(HandshakePhase::Status);
     let (conn, their_status) = if p2p_stream.shared_capabilities().len() == 1 {
         // if the hello handshake was successful we can try status handshake
         //
@@ -1036,5 +1070,192 @@ async fn authenticate_stream(
         conn,
         direction,
         client_id: their_hello.client_version,
+        timings: timer.finish(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics::{
+        Counter, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder, SharedString,
+        Unit,
+    };
+    use reth_eth_wire::StatusBuilder;
+    use reth_network_peers::pk2id;
+    use reth_primitives::{Hardfork, MAINNET};
+    use reth_tasks::TokioTaskExecutor;
+    use secp256k1::SECP256K1;
+    use std::future::poll_fn;
+    use tokio::net::TcpListener;
+
+    const TEST_PENDING_SESSION_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Records the number of samples of every histogram.
+    #[derive(Default)]
+    struct HistogramRecorder(parking_lot::Mutex<HashMap<String, Arc<HistogramSamples>>>);
+
+    #[derive(Default)]
+    struct HistogramSamples(AtomicU64);
+
+    impl HistogramFn for HistogramSamples {
+        fn record(&self, _: f64) {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    impl HistogramRecorder {
+        fn samples(&self, name: &str) -> u64 {
+            self.0
+                .lock()
+                .get(name)
+                .map_or(0, |samples| samples.0.load(std::sync::atomic::Ordering::Relaxed))
+        }
+    }
+
+    impl Recorder for HistogramRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, _: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::noop()
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::from_arc(self.0.lock().entry(key.name().to_string()).or_default().clone())
+        }
+    }
+
+    fn status() -> Status {
+        StatusBuilder::default().build()
+    }
+
+    fn fork_filter() -> ForkFilter {
+        MAINNET
+            .hardfork_fork_filter(Hardfork::Frontier)
+            .expect("The Frontier fork filter should exist on mainnet")
+    }
+
+    fn hello(secret_key: &SecretKey) -> HelloMessageWithProtocols {
+        HelloMessageWithProtocols::builder(pk2id(&secret_key.public_key(SECP256K1))).build()
+    }
+
+    fn session_manager(secret_key: SecretKey, limits: SessionLimits) -> SessionManager {
+        let config = SessionsConfig { limits, ..Default::default() }
+            .with_pending_session_timeout(TEST_PENDING_SESSION_TIMEOUT);
+        SessionManager::new(
+            secret_key,
+            config,
+            Box::<TokioTaskExecutor>::default(),
+            status(),
+            hello(&secret_key),
+            fork_filter(),
+            Default::default(),
+        )
+    }
+
+    /// Connects to the session manager and runs the handshake up to the given phase, where it
+    /// stalls. Completes the handshake if no phase is given.
+    async fn connect_and_stall(
+        local_addr: SocketAddr,
+        local_peer_id: PeerId,
+        stall: Option<HandshakePhase>,
+    ) {
+        let key = SecretKey::new(&mut rand::thread_rng());
+        let outgoing = TcpStream::connect(local_addr).await.unwrap();
+        if stall == Some(HandshakePhase::Ecies) {
+            return std::future::pending().await
+        }
+
+        let stream = ECIESStream::connect(outgoing, key, local_peer_id).await.unwrap();
+        if stall == Some(HandshakePhase::Hello) {
+            return std::future::pending().await
+        }
+
+        let (p2p_stream, _) = UnauthedP2PStream::new(stream).handshake(hello(&key)).await.unwrap();
+        if stall == Some(HandshakePhase::Status) {
+            return std::future::pending().await
+        }
+
+        let (_eth_stream, _) =
+            UnauthedEthStream::new(p2p_stream).handshake(status(), fork_filter()).await.unwrap();
+        std::future::pending().await
+    }
+
+    async fn assert_stalled_handshake_times_out(stall: HandshakePhase) {
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let local_peer_id = pk2id(&secret_key.public_key(SECP256K1));
+        let mut manager =
+            session_manager(secret_key, SessionLimits::default().with_max_pending_inbound(1));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        tokio::task::spawn(connect_and_stall(local_addr, local_peer_id, Some(stall)));
+
+        let (incoming, remote_addr) = listener.accept().await.unwrap();
+        manager.on_incoming(incoming, remote_addr).unwrap();
+        assert!(manager.counter.ensure_pending_inbound().is_err());
+
+        let started = Instant::now();
+        let event = poll_fn(|cx| manager.poll(cx)).await;
+        match event {
+            SessionEvent::IncomingPendingSessionClosed {
+                error: Some(PendingSessionHandshakeError::Timeout(phase)),
+                ..
+            } => assert_eq!(phase, stall),
+            ev => panic!("unexpected event {ev:?}"),
+        }
+        assert!(started.elapsed() >= TEST_PENDING_SESSION_TIMEOUT / 2);
+
+        // the slot of the stalled handshake is released
+        assert_eq!(manager.num_pending_connections(), 0);
+        assert!(manager.counter.ensure_pending_inbound().is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stalled_ecies_handshake_times_out() {
+        assert_stalled_handshake_times_out(HandshakePhase::Ecies).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stalled_hello_handshake_times_out() {
+        assert_stalled_handshake_times_out(HandshakePhase::Hello).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stalled_status_handshake_times_out() {
+        assert_stalled_handshake_times_out(HandshakePhase::Status).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn established_session_records_handshake_timings() {
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let local_peer_id = pk2id(&secret_key.public_key(SECP256K1));
+        let recorder = HistogramRecorder::default();
+        let mut manager = metrics::with_local_recorder(&recorder, || {
+            session_manager(secret_key, SessionLimits::default())
+        });
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        tokio::task::spawn(connect_and_stall(local_addr, local_peer_id, None));
+
+        let (incoming, remote_addr) = listener.accept().await.unwrap();
+        manager.on_incoming(incoming, remote_addr).unwrap();
+
+        let event = poll_fn(|cx| manager.poll(cx)).await;
+        assert!(matches!(event, SessionEvent::SessionEstablished { .. }), "{event:?}");
+        for name in [
+            "network.handshake_ecies_duration",
+            "network.handshake_hello_duration",
+            "network.handshake_status_duration",
+        ] {
+            assert_eq!(recorder.samples(name), 1, "{name}");
+        }
     }
 }
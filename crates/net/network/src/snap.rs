@@ -0,0 +1,929 @@
+//! Serves the `snap/1` protocol: <https://github.com/ethereum/devp2p/blob/master/caps/snap.md>
+//!
+//! The protocol runs as an additional `RLPx` sub-protocol. A [`SnapProtocolHandler`] is installed
+//! via [`NetworkProtocols::add_rlpx_sub_protocol`](crate::NetworkProtocols), which forwards all
+//! incoming requests to the [`SnapRequestHandler`] that answers them from the hashed state tables.
+//! [`NetworkBuilder::snap_request_handler`](crate::NetworkBuilder::snap_request_handler) sets up
+//! both.
+//!
+//! Only the latest state root is served, since the hashed state tables do not retain older state.
+//! Requests for any other root are answered with an empty response, which tells the peer that the
+//! root is not available.
+
+use crate::{
+    budget::DEFAULT_BUDGET_TRY_DRAIN_DOWNLOADERS,
+    metered_poll_nested_stream_with_budget,
+    metrics::SnapRequestHandlerMetrics,
+    protocol::{ConnectionHandler, OnNotSupported, ProtocolHandler},
+};
+use alloy_rlp::Encodable;
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, Stream, StreamExt};
+use reth_eth_wire::{
+    capability::{Capability, SharedCapabilities},
+    multiplex::ProtocolConnection,
+    protocol::Protocol,
+    AccountData, AccountRange, ByteCodes, GetAccountRange, GetByteCodes, GetStorageRanges,
+    GetTrieNodes, SnapAccount, SnapMessage, StorageData, StorageRanges, TrieNodes,
+};
+use reth_network_api::Direction;
+use reth_network_peers::PeerId;
+use reth_primitives::{BytesMut, B256, U256};
+use reth_provider::{HashedStateRangeProvider, ProviderResult};
+use std::{
+    collections::HashMap,
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::sync::{
+    mpsc::{error::TrySendError, Receiver, Sender},
+    oneshot,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, trace};
+
+/// Maximum size of replies to `snap` requests, regardless of the requested size.
+const SOFT_RESPONSE_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Number of accounts or storage slots read from the database at once.
+const RANGE_BATCH_SIZE: usize = 128;
+
+/// The number of response bytes a peer may request per [`DEFAULT_PEER_BUDGET_WINDOW`].
+pub const DEFAULT_PEER_BUDGET_BYTES: u64 = 32 * 1024 * 1024;
+
+/// The window after which the budget of a peer is replenished.
+pub const DEFAULT_PEER_BUDGET_WINDOW: Duration = Duration::from_secs(10);
+
+/// The number of messages reserved by the `snap/1` protocol.
+const SNAP_MESSAGE_COUNT: u8 = 8;
+
+/// Configures how much work the [`SnapRequestHandler`] does for a single peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapServerConfig {
+    /// The number of response bytes a peer may request per window.
+    pub peer_budget_bytes: u64,
+    /// The window after which the budget of a peer is replenished.
+    pub peer_budget_window: Duration,
+}
+
+impl Default for SnapServerConfig {
+    fn default() -> Self {
+        Self {
+            peer_budget_bytes: DEFAULT_PEER_BUDGET_BYTES,
+            peer_budget_window: DEFAULT_PEER_BUDGET_WINDOW,
+        }
+    }
+}
+
+/// Tracks the response bytes served to each peer in the current window.
+#[derive(Debug)]
+struct PeerBudgets {
+    config: SnapServerConfig,
+    peers: HashMap<PeerId, PeerBudget>,
+}
+
+#[derive(Debug)]
+struct PeerBudget {
+    window_start: Instant,
+    spent: u64,
+}
+
+impl PeerBudgets {
+    fn new(config: SnapServerConfig) -> Self {
+        Self { config, peers: HashMap::new() }
+    }
+
+    /// Returns the number of bytes the peer may still request in the current window.
+    fn remaining(&mut self, peer_id: PeerId, now: Instant) -> u64 {
+        let window = self.config.peer_budget_window;
+        self.peers.retain(|_, budget| now.duration_since(budget.window_start) < window);
+        let spent = self.peers.get(&peer_id).map_or(0, |budget| budget.spent);
+        self.config.peer_budget_bytes.saturating_sub(spent)
+    }
+
+    /// Records the bytes served to the peer.
+    fn spend(&mut self, peer_id: PeerId, bytes: u64, now: Instant) {
+        let budget =
+            self.peers.entry(peer_id).or_insert(PeerBudget { window_start: now, spent: 0 });
+        budget.spent = budget.spent.saturating_add(bytes);
+    }
+}
+
+/// Manages `snap` requests on top of the p2p network.
+///
+/// This can be spawned to another task and is supposed to be run as background service.
+#[derive(Debug)]
+#[must_use = "Manager does nothing unless polled."]
+pub struct SnapRequestHandler<C> {
+    /// The client type that can interact with the chain.
+    client: C,
+    /// Incoming requests from the [`SnapProtocolHandler`] connections.
+    incoming_requests: ReceiverStream<IncomingSnapRequest>,
+    /// The response bytes served to each peer.
+    budgets: PeerBudgets,
+    /// Metrics for the snap request handler.
+    metrics: SnapRequestHandlerMetrics,
+}
+
+// === impl SnapRequestHandler ===
+impl<C> SnapRequestHandler<C> {
+    /// Create a new instance
+    pub fn new(
+        client: C,
+        config: SnapServerConfig,
+        incoming: Receiver<IncomingSnapRequest>,
+    ) -> Self {
+        Self {
+            client,
+            incoming_requests: ReceiverStream::new(incoming),
+            budgets: PeerBudgets::new(config),
+            metrics: Default::default(),
+        }
+    }
+}
+
+impl<C> SnapRequestHandler<C>
+where
+    C: HashedStateRangeProvider,
+{
+    /// Returns `true` if the root is the state root of the latest block and the hashed state
+    /// tables are in sync with it.
+    fn serves_root(&self, root: B256) -> ProviderResult<bool> {
+        Ok(self.client.hashed_state_root()? == Some(root))
+    }
+
+    /// Returns the size limit of the response to the peer, which is zero once its budget is spent.
+    fn response_limit(&mut self, peer_id: PeerId, requested: u64) -> usize {
+        let remaining = self.budgets.remaining(peer_id, Instant::now());
+        if remaining == 0 {
+            self.metrics.snap_budget_exhausted_total.increment(1);
+        }
+        requested.min(remaining).min(SOFT_RESPONSE_LIMIT as u64) as usize
+    }
+
+    /// Returns the accounts of the requested range and the proof of its boundaries.
+    fn get_account_range_response(
+        &self,
+        request: &GetAccountRange,
+        limit: usize,
+    ) -> ProviderResult<AccountRange> {
+        let GetAccountRange { request_id, root_hash, starting_hash, limit_hash, .. } = *request;
+        let mut response = AccountRange { request_id, ..Default::default() };
+        if limit == 0 || starting_hash > limit_hash || !self.serves_root(root_hash)? {
+            return Ok(response)
+        }
+
+        let mut size = 0;
+        let mut next = Some(starting_hash);
+        'range: while let Some(start) = next {
+            let accounts = self.client.hashed_account_range(start, RANGE_BATCH_SIZE)?;
+            next = next_batch_start(accounts.len(), accounts.last().map(|(hash, _)| *hash));
+
+            for (hash, account) in accounts {
+                let account = AccountData {
+                    hash,
+                    body: SnapAccount {
+                        nonce: account.nonce,
+                        balance: account.balance,
+                        storage_root: account.storage_root,
+                        code_hash: account.code_hash,
+                    },
+                };
+                size += account.length();
+                response.accounts.push(account);
+
+                // the first account past the limit is included to prove the end of the range
+                if hash >= limit_hash || size >= limit {
+                    break 'range
+                }
+            }
+        }
+
+        let mut targets = vec![starting_hash];
+        targets.extend(response.accounts.last().map(|account| account.hash));
+        response.proof = self.client.account_range_proof(&targets)?;
+
+        // the state may have advanced while the response was assembled
+        if !self.serves_root(root_hash)? {
+            return Ok(AccountRange { request_id, ..Default::default() })
+        }
+
+        Ok(response)
+    }
+
+    /// Returns the storage slots of the requested accounts.
+    ///
+    /// A proof of the range boundaries is only included for the last account, if its range does
+    /// not start at the first slot or is incomplete.
+    fn get_storage_ranges_response(
+        &self,
+        request: &GetStorageRanges,
+        limit: usize,
+    ) -> ProviderResult<StorageRanges> {
+        let request_id = request.request_id;
+        let mut response = StorageRanges { request_id, ..Default::default() };
+        if limit == 0 || !self.serves_root(request.root_hash)? {
+            return Ok(response)
+        }
+
+        let mut size = 0;
+        for (idx, hashed_address) in request.account_hashes.iter().copied().enumerate() {
+            if size >= limit {
+                break
+            }
+
+            // the requested bounds only apply to the first account
+            let (origin, limit_hash) = if idx == 0 {
+                (request.origin(), request.limit())
+            } else {
+                (B256::ZERO, B256::repeat_byte(0xff))
+            };
+
+            let mut slots = Vec::new();
+            let mut aborted = false;
+            let mut next = Some(origin);
+            'range: while let Some(start) = next {
+                let entries =
+                    self.client.hashed_storage_range(hashed_address, start, RANGE_BATCH_SIZE)?;
+                next = next_batch_start(entries.len(), entries.last().map(|entry| entry.key));
+
+                for entry in entries {
+                    if size >= limit {
+                        aborted = true;
+                        break 'range
+                    }
+
+                    let slot = StorageData {
+                        hash: entry.key,
+                        data: alloy_rlp::encode(entry.value).into(),
+                    };
+                    size += slot.length();
+                    slots.push(slot);
+
+                    if entry.key >= limit_hash {
+                        break 'range
+                    }
+                }
+            }
+
+            let last = slots.last().map(|slot| slot.hash);
+            response.slots.push(slots);
+
+            if origin != B256::ZERO || (aborted && last.is_some()) {
+                let mut targets = vec![origin];
+                targets.extend(last);
+                response.proof = self.client.storage_range_proof(hashed_address, &targets)?;
+                break
+            }
+        }
+
+        // the state may have advanced while the response was assembled
+        if !self.serves_root(request.root_hash)? {
+            return Ok(StorageRanges { request_id, ..Default::default() })
+        }
+
+        Ok(response)
+    }
+
+    fn on_account_range_request(
+        &mut self,
+        peer_id: PeerId,
+        request: GetAccountRange,
+        response: oneshot::Sender<AccountRange>,
+    ) {
+        self.metrics.snap_account_range_requests_received_total.increment(1);
+
+        let limit = self.response_limit(peer_id, request.response_bytes);
+        let range = self.get_account_range_response(&request, limit).unwrap_or_else(|err| {
+            debug!(target: "net::snap", %peer_id, %err, "Failed to serve account range");
+            AccountRange { request_id: request.request_id, ..Default::default() }
+        });
+
+        self.budgets.spend(peer_id, range.length() as u64, Instant::now());
+        let _ = response.send(range);
+    }
+
+    fn on_storage_ranges_request(
+        &mut self,
+        peer_id: PeerId,
+        request: GetStorageRanges,
+        response: oneshot::Sender<StorageRanges>,
+    ) {
+        self.metrics.snap_storage_ranges_requests_received_total.increment(1);
+
+        let limit = self.response_limit(peer_id, request.response_bytes);
+        let ranges = self.get_storage_ranges_response(&request, limit).unwrap_or_else(|err| {
+            debug!(target: "net::snap", %peer_id, %err, "Failed to serve storage ranges");
+            StorageRanges { request_id: request.request_id, ..Default::default() }
+        });
+
+        self.budgets.spend(peer_id, ranges.length() as u64, Instant::now());
+        let _ = response.send(ranges);
+    }
+}
+
+/// Returns the key to continue reading from, if the batch was full.
+fn next_batch_start(batch_len: usize, last: Option<B256>) -> Option<B256> {
+    if batch_len < RANGE_BATCH_SIZE {
+        return None
+    }
+    let next = U256::from_be_bytes(last?.0).checked_add(U256::from(1))?;
+    Some(B256::from(next.to_be_bytes::<32>()))
+}
+
+/// An endless future.
+///
+/// This should be spawned or used as part of `tokio::select!`.
+impl<C> Future for SnapRequestHandler<C>
+where
+    C: HashedStateRangeProvider + Unpin,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        let mut acc = Duration::ZERO;
+        let maybe_more_incoming_requests = metered_poll_nested_stream_with_budget!(
+            acc,
+            "net::snap",
+            "Incoming snap requests stream",
+            DEFAULT_BUDGET_TRY_DRAIN_DOWNLOADERS,
+            this.incoming_requests.poll_next_unpin(cx),
+            |incoming| {
+                match incoming {
+                    IncomingSnapRequest::GetAccountRange { peer_id, request, response } => {
+                        this.on_account_range_request(peer_id, request, response)
+                    }
+                    IncomingSnapRequest::GetStorageRanges { peer_id, request, response } => {
+                        this.on_storage_ranges_request(peer_id, request, response)
+                    }
+                    IncomingSnapRequest::GetByteCodes { request, response, .. } => {
+                        this.metrics.snap_bytecodes_requests_received_total.increment(1);
+                        let _ = response
+                            .send(ByteCodes { request_id: request.request_id, codes: Vec::new() });
+                    }
+                    IncomingSnapRequest::GetTrieNodes { request, response, .. } => {
+                        this.metrics.snap_trie_nodes_requests_received_total.increment(1);
+                        let _ = response
+                            .send(TrieNodes { request_id: request.request_id, nodes: Vec::new() });
+                    }
+                }
+            },
+        );
+
+        this.metrics.acc_duration_poll_snap_req_handler.set(acc.as_secs_f64());
+
+        // stream is fully drained and import futures pending
+        if maybe_more_incoming_requests {
+            // make sure we're woken up again
+            cx.waker().wake_by_ref();
+            return Poll::Pending
+        }
+
+        Poll::Pending
+    }
+}
+
+/// All `snap` requests sent by peers.
+///
+/// Note: `ByteCodes` and `TrieNodes` requests are currently answered with empty responses.
+#[derive(Debug)]
+pub enum IncomingSnapRequest {
+    /// Request a range of accounts.
+    GetAccountRange {
+        /// The ID of the peer to request the accounts from.
+        peer_id: PeerId,
+        /// The specific range of accounts requested.
+        request: GetAccountRange,
+        /// The channel sender for the response.
+        response: oneshot::Sender<AccountRange>,
+    },
+    /// Request ranges of storage slots.
+    GetStorageRanges {
+        /// The ID of the peer to request the storage slots from.
+        peer_id: PeerId,
+        /// The specific ranges of storage slots requested.
+        request: GetStorageRanges,
+        /// The channel sender for the response.
+        response: oneshot::Sender<StorageRanges>,
+    },
+    /// Request contract bytecodes.
+    GetByteCodes {
+        /// The ID of the peer to request the bytecodes from.
+        peer_id: PeerId,
+        /// The specific bytecodes requested.
+        request: GetByteCodes,
+        /// The channel sender for the response.
+        response: oneshot::Sender<ByteCodes>,
+    },
+    /// Request trie nodes.
+    GetTrieNodes {
+        /// The ID of the peer to request the trie nodes from.
+        peer_id: PeerId,
+        /// The specific trie nodes requested.
+        request: GetTrieNodes,
+        /// The channel sender for the response.
+        response: oneshot::Sender<TrieNodes>,
+    },
+}
+
+/// The [`ProtocolHandler`] of the `snap/1` protocol.
+///
+/// Forwards the requests of all connected peers to the [`SnapRequestHandler`].
+#[derive(Debug, Clone)]
+pub struct SnapProtocolHandler {
+    to_request_handler: Sender<IncomingSnapRequest>,
+}
+
+impl SnapProtocolHandler {
+    /// Creates a new handler that forwards requests to the given channel.
+    pub const fn new(to_request_handler: Sender<IncomingSnapRequest>) -> Self {
+        Self { to_request_handler }
+    }
+
+    /// Returns the `snap/1` protocol.
+    pub const fn protocol() -> Protocol {
+        Protocol::new(Capability::new_static("snap", 1), SNAP_MESSAGE_COUNT)
+    }
+
+    fn connection_handler(&self) -> SnapConnectionHandler {
+        SnapConnectionHandler { to_request_handler: self.to_request_handler.clone() }
+    }
+}
+
+impl ProtocolHandler for SnapProtocolHandler {
+    type ConnectionHandler = SnapConnectionHandler;
+
+    fn on_incoming(&self, _socket_addr: SocketAddr) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+
+    fn on_outgoing(
+        &self,
+        _socket_addr: SocketAddr,
+        _peer_id: PeerId,
+    ) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+}
+
+/// The [`ConnectionHandler`] of the `snap/1` protocol.
+#[derive(Debug)]
+pub struct SnapConnectionHandler {
+    to_request_handler: Sender<IncomingSnapRequest>,
+}
+
+impl ConnectionHandler for SnapConnectionHandler {
+    type Connection = SnapConnection;
+
+    fn protocol(&self) -> Protocol {
+        SnapProtocolHandler::protocol()
+    }
+
+    fn on_unsupported_by_peer(
+        self,
+        _supported: &SharedCapabilities,
+        _direction: Direction,
+        _peer_id: PeerId,
+    ) -> OnNotSupported {
+        OnNotSupported::KeepAlive
+    }
+
+    fn into_connection(
+        self,
+        _direction: Direction,
+        peer_id: PeerId,
+        conn: ProtocolConnection,
+    ) -> Self::Connection {
+        SnapConnection {
+            peer_id,
+            conn,
+            to_request_handler: self.to_request_handler,
+            pending_responses: FuturesUnordered::new(),
+        }
+    }
+}
+
+/// A `snap/1` connection to a peer, which yields the responses to the requests of the peer.
+///
+/// The connection is closed if the peer sends a message that can not be decoded.
+#[must_use = "Streams do nothing unless polled."]
+pub struct SnapConnection {
+    peer_id: PeerId,
+    conn: ProtocolConnection,
+    to_request_handler: Sender<IncomingSnapRequest>,
+    pending_responses: FuturesUnordered<BoxFuture<'static, Option<SnapMessage>>>,
+}
+
+impl SnapConnection {
+    /// Forwards the request to the [`SnapRequestHandler`].
+    ///
+    /// Returns `false` if the request handler is gone.
+    fn on_request(&mut self, message: SnapMessage) -> bool {
+        let peer_id = self.peer_id;
+        let (request, response) = match message {
+            SnapMessage::GetAccountRange(request) => {
+                let (tx, rx) = oneshot::channel();
+                let response = rx.map(|res| res.ok().map(SnapMessage::AccountRange)).boxed();
+                (IncomingSnapRequest::GetAccountRange { peer_id, request, response: tx }, response)
+            }
+            SnapMessage::GetStorageRanges(request) => {
+                let (tx, rx) = oneshot::channel();
+                let response = rx.map(|res| res.ok().map(SnapMessage::StorageRanges)).boxed();
+                (IncomingSnapRequest::GetStorageRanges { peer_id, request, response: tx }, response)
+            }
+            SnapMessage::GetByteCodes(request) => {
+                let (tx, rx) = oneshot::channel();
+                let response = rx.map(|res| res.ok().map(SnapMessage::ByteCodes)).boxed();
+                (IncomingSnapRequest::GetByteCodes { peer_id, request, response: tx }, response)
+            }
+            SnapMessage::GetTrieNodes(request) => {
+                let (tx, rx) = oneshot::channel();
+                let response = rx.map(|res| res.ok().map(SnapMessage::TrieNodes)).boxed();
+                (IncomingSnapRequest::GetTrieNodes { peer_id, request, response: tx }, response)
+            }
+            response => {
+                // we never send requests, so there is nothing to match responses against
+                let id = response.message_id();
+                trace!(target: "net::snap", %peer_id, ?id, "Ignoring unsolicited response");
+                return true
+            }
+        };
+
+        match self.to_request_handler.try_send(request) {
+            Ok(()) => {
+                self.pending_responses.push(response);
+                true
+            }
+            Err(TrySendError::Full(_)) => {
+                // the peer will time out the request
+                trace!(target: "net::snap", %peer_id, "Dropping request, request handler is busy");
+                true
+            }
+            Err(TrySendError::Closed(_)) => false,
+        }
+    }
+}
+
+impl Stream for SnapConnection {
+    type Item = BytesMut;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Poll::Ready(Some(response)) = this.pending_responses.poll_next_unpin(cx) {
+                let Some(response) = response else { continue };
+                let mut buf = BytesMut::new();
+                response.encode_message(&mut buf);
+                return Poll::Ready(Some(buf))
+            }
+
+            let Some(msg) = ready!(this.conn.poll_next_unpin(cx)) else { return Poll::Ready(None) };
+
+            let message = match SnapMessage::decode_message(&mut &msg[..]) {
+                Ok(message) => message,
+                Err(err) => {
+                    let peer_id = this.peer_id;
+                    debug!(target: "net::snap", %peer_id, %err, "Failed to decode snap message");
+                    return Poll::Ready(None)
+                }
+            };
+
+            if !this.on_request(message) {
+                return Poll::Ready(None)
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for SnapConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapConnection")
+            .field("peer_id", &self.peer_id)
+            .field("pending_responses", &self.pending_responses.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{
+        address,
+        constants::EMPTY_ROOT_HASH,
+        keccak256,
+        proofs::{state_root, state_root_unhashed, storage_root_unhashed},
+        Account, Address, Bytes, Header, SealedBlock, SealedBlockWithSenders, StorageEntry,
+    };
+    use reth_provider::{
+        test_utils::create_test_provider_factory, BlockWriter, HashingWriter, HeaderProvider,
+        StageCheckpointWriter,
+    };
+    use reth_stages_types::{StageCheckpoint, StageId};
+    use reth_trie_types::{proof::verify_proof, Nibbles, TrieAccount};
+    use tokio::sync::mpsc;
+
+    /// The address of the only account with storage.
+    const STORAGE_ACCOUNT: Address = address!("0000000000000000000000000000000000000001");
+
+    fn storage() -> Vec<StorageEntry> {
+        (1..=3u8)
+            .map(|i| StorageEntry { key: B256::with_last_byte(i), value: U256::from(i) })
+            .collect()
+    }
+
+    /// Inserts the given number of accounts and a genesis block committing to their state.
+    ///
+    /// Returns the state root.
+    fn insert_state(provider: &(impl HashingWriter + BlockWriter), accounts: u8) -> B256 {
+        let accounts = (1..=accounts)
+            .map(|i| {
+                let account =
+                    Account { nonce: i as u64, balance: U256::from(i), bytecode_hash: None };
+                (Address::with_last_byte(i), account)
+            })
+            .collect::<Vec<_>>();
+        let storage_root =
+            storage_root_unhashed(storage().into_iter().map(|entry| (entry.key, entry.value)));
+        let root = state_root_unhashed(accounts.iter().map(|(address, account)| {
+            let storage_root =
+                if *address == STORAGE_ACCOUNT { storage_root } else { EMPTY_ROOT_HASH };
+            (*address, (*account, storage_root))
+        }));
+
+        provider
+            .insert_account_for_hashing(
+                accounts.into_iter().map(|(address, account)| (address, Some(account))),
+            )
+            .unwrap();
+        provider.insert_storage_for_hashing([(STORAGE_ACCOUNT, storage())]).unwrap();
+        let header = Header { state_root: root, ..Default::default() }.seal_slow();
+        provider
+            .insert_block(
+                SealedBlockWithSenders {
+                    block: SealedBlock { header, ..Default::default() },
+                    senders: Vec::new(),
+                },
+                None,
+            )
+            .unwrap();
+
+        root
+    }
+
+    /// Returns the trie encoding of the account.
+    fn encode_account(data: &AccountData) -> Vec<u8> {
+        alloy_rlp::encode(TrieAccount {
+            nonce: data.body.nonce,
+            balance: data.body.balance,
+            storage_root: data.body.storage_root,
+            code_hash: data.body.code_hash,
+        })
+    }
+
+    /// Asserts that the nodes of a range proof prove the value of the key against the root.
+    ///
+    /// A range proof contains the paths to both boundaries of the range, so the path to the key
+    /// is searched by following the node hashes down from the root.
+    fn assert_range_proof(root: B256, key: B256, value: Option<Vec<u8>>, proof: &[Bytes]) {
+        fn find_path(
+            root: B256,
+            key: B256,
+            value: &Option<Vec<u8>>,
+            proof: &[Bytes],
+            path: &mut Vec<Bytes>,
+        ) -> bool {
+            if verify_proof(root, Nibbles::unpack(key), value.clone(), &*path).is_ok() {
+                return true
+            }
+            for node in proof {
+                let hash = keccak256(node);
+                let linked = path.last().map_or(hash == root, |parent| {
+                    parent.windows(B256::len_bytes()).any(|window| window == hash.as_slice())
+                });
+                if linked && !path.contains(node) {
+                    path.push(node.clone());
+                    if find_path(root, key, value, proof, path) {
+                        return true
+                    }
+                    path.pop();
+                }
+            }
+            false
+        }
+
+        assert!(find_path(root, key, &value, proof, &mut Vec::new()), "{key} is not proven");
+    }
+
+    #[test]
+    fn serves_full_account_range() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let root = insert_state(&*provider, 10);
+        provider.commit().unwrap();
+        let handler =
+            SnapRequestHandler::new(factory, SnapServerConfig::default(), mpsc::channel(1).1);
+        let request = GetAccountRange {
+            request_id: 1,
+            root_hash: root,
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: SOFT_RESPONSE_LIMIT as u64,
+        };
+
+        let response = handler.get_account_range_response(&request, SOFT_RESPONSE_LIMIT).unwrap();
+        assert_eq!(response.request_id, 1);
+        assert_eq!(response.accounts.len(), 10);
+        assert!(response.accounts.windows(2).all(|pair| pair[0].hash < pair[1].hash));
+
+        // the complete range rebuilds the requested root
+        let accounts = response.accounts.iter().map(|data| {
+            let account = Account {
+                nonce: data.body.nonce,
+                balance: data.body.balance,
+                bytecode_hash: Some(data.body.code_hash),
+            };
+            (data.hash, (account, data.body.storage_root))
+        });
+        assert_eq!(state_root(accounts), root);
+
+        // both boundaries are proven against the root
+        let last = response.accounts.last().unwrap();
+        assert_range_proof(root, B256::ZERO, None, &response.proof);
+        assert_range_proof(root, last.hash, Some(encode_account(last)), &response.proof);
+    }
+
+    #[test]
+    fn serves_partial_account_range_with_proof() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let root = insert_state(&*provider, 10);
+        provider.commit().unwrap();
+        let handler =
+            SnapRequestHandler::new(factory, SnapServerConfig::default(), mpsc::channel(1).1);
+        let request = GetAccountRange {
+            request_id: 1,
+            root_hash: root,
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: 1,
+        };
+
+        // the first account already exceeds the limit
+        let response = handler.get_account_range_response(&request, 1).unwrap();
+        assert_eq!(response.accounts.len(), 1);
+
+        // the proof proves the start of the range and the last account against the root
+        let last = &response.accounts[0];
+        assert_range_proof(root, B256::ZERO, None, &response.proof);
+        assert_range_proof(root, last.hash, Some(encode_account(last)), &response.proof);
+    }
+
+    #[test]
+    fn unknown_root_is_served_empty() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        insert_state(&*provider, 10);
+        provider.commit().unwrap();
+        let handler =
+            SnapRequestHandler::new(factory, SnapServerConfig::default(), mpsc::channel(1).1);
+        let request = GetAccountRange {
+            request_id: 1,
+            root_hash: B256::repeat_byte(1),
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: SOFT_RESPONSE_LIMIT as u64,
+        };
+
+        let response = handler.get_account_range_response(&request, SOFT_RESPONSE_LIMIT).unwrap();
+        assert_eq!(response, AccountRange { request_id: 1, ..Default::default() });
+    }
+
+    #[test]
+    fn lagging_trie_is_served_empty() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let root = insert_state(&*provider, 10);
+
+        // an empty block on top of the genesis block keeps the state root
+        let parent_hash = provider.sealed_header(0).unwrap().unwrap().hash();
+        let header = Header { number: 1, parent_hash, state_root: root, ..Default::default() };
+        provider
+            .insert_block(
+                SealedBlockWithSenders {
+                    block: SealedBlock { header: header.seal_slow(), ..Default::default() },
+                    senders: Vec::new(),
+                },
+                None,
+            )
+            .unwrap();
+        for stage_id in [StageId::Finish, StageId::AccountHashing, StageId::StorageHashing] {
+            provider.save_stage_checkpoint(stage_id, StageCheckpoint::new(1)).unwrap();
+        }
+        provider.commit().unwrap();
+        let handler =
+            SnapRequestHandler::new(factory, SnapServerConfig::default(), mpsc::channel(1).1);
+        let request = GetAccountRange {
+            request_id: 1,
+            root_hash: root,
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: SOFT_RESPONSE_LIMIT as u64,
+        };
+
+        // the trie nodes have not been brought up to the best block yet
+        let response = handler.get_account_range_response(&request, SOFT_RESPONSE_LIMIT).unwrap();
+        assert_eq!(response, AccountRange { request_id: 1, ..Default::default() });
+
+        let provider = handler.client.provider_rw().unwrap();
+        provider.save_stage_checkpoint(StageId::MerkleExecute, StageCheckpoint::new(1)).unwrap();
+        provider.commit().unwrap();
+        let response = handler.get_account_range_response(&request, SOFT_RESPONSE_LIMIT).unwrap();
+        assert_eq!(response.accounts.len(), 10);
+    }
+
+    #[test]
+    fn serves_storage_ranges() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let root = insert_state(&*provider, 10);
+        provider.commit().unwrap();
+        let handler =
+            SnapRequestHandler::new(factory, SnapServerConfig::default(), mpsc::channel(1).1);
+        let hashed_address = keccak256(STORAGE_ACCOUNT);
+        let mut request = GetStorageRanges {
+            request_id: 2,
+            root_hash: root,
+            account_hashes: vec![hashed_address, keccak256(Address::with_last_byte(2))],
+            response_bytes: SOFT_RESPONSE_LIMIT as u64,
+            ..Default::default()
+        };
+
+        // complete ranges don't need a proof
+        let response = handler.get_storage_ranges_response(&request, SOFT_RESPONSE_LIMIT).unwrap();
+        assert_eq!(response.slots.len(), 2);
+        assert_eq!(response.slots[0].len(), 3);
+        assert!(response.slots[1].is_empty());
+        assert!(response.proof.is_empty());
+        let mut expected = storage()
+            .into_iter()
+            .map(|entry| (keccak256(entry.key), alloy_rlp::encode(entry.value)))
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(
+            response.slots[0]
+                .iter()
+                .map(|slot| (slot.hash, slot.data.to_vec()))
+                .collect::<Vec<_>>(),
+            expected
+        );
+
+        // an incomplete range is proven
+        request.account_hashes.truncate(1);
+        let response = handler.get_storage_ranges_response(&request, 1).unwrap();
+        assert_eq!(response.slots.len(), 1);
+        assert_eq!(response.slots[0].len(), 1);
+
+        // the proof proves the start of the range and the last slot against the storage root
+        let storage_root =
+            storage_root_unhashed(storage().into_iter().map(|entry| (entry.key, entry.value)));
+        let last = &response.slots[0][0];
+        assert_range_proof(storage_root, B256::ZERO, None, &response.proof);
+        assert_range_proof(storage_root, last.hash, Some(last.data.to_vec()), &response.proof);
+    }
+
+    #[test]
+    fn exhausted_budget_is_served_empty() {
+        let config = SnapServerConfig { peer_budget_bytes: 1, ..Default::default() };
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let root = insert_state(&*provider, 10);
+        provider.commit().unwrap();
+        let mut handler = SnapRequestHandler::new(factory, config, mpsc::channel(1).1);
+        let peer_id = PeerId::random();
+        let request = GetAccountRange {
+            request_id: 1,
+            root_hash: root,
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: SOFT_RESPONSE_LIMIT as u64,
+        };
+
+        let (tx, rx) = oneshot::channel();
+        handler.on_account_range_request(peer_id, request.clone(), tx);
+        assert_eq!(rx.blocking_recv().unwrap().accounts.len(), 1);
+
+        let (tx, rx) = oneshot::channel();
+        handler.on_account_range_request(peer_id, request.clone(), tx);
+        assert!(rx.blocking_recv().unwrap().accounts.is_empty());
+
+        // other peers have their own budget
+        let (tx, rx) = oneshot::channel();
+        handler.on_account_range_request(PeerId::random(), request, tx);
+        assert_eq!(rx.blocking_recv().unwrap().accounts.len(), 1);
+    }
+}
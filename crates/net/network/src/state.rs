@@ -9,7 +9,7 @@ use crate::{
         BlockRequest, NewBlockMessage, PeerRequest, PeerRequestSender, PeerResponse,
         PeerResponseResult,
     },
-    peers::{PeerAction, PeersManager},
+    peers::{PeerAction, PeerSource, PeersManager},
     FetchClient,
 };
 use rand::seq::SliceRandom;
@@ -134,6 +134,7 @@ where
         let block_number =
             self.client.block_number(status.blockhash).ok().flatten().unwrap_or_default();
         self.state_fetcher.new_active_peer(peer, status.blockhash, block_number, timeout);
+        self.peers_manager.on_peer_status(peer, status.blockhash, status.total_difficulty);
 
         self.active_peers.insert(
             peer,
@@ -153,6 +154,7 @@ where
     pub(crate) fn on_session_closed(&mut self, peer: PeerId) {
         self.active_peers.remove(&peer);
         self.state_fetcher.on_session_closed(&peer);
+        self.peers_manager.on_peer_head_removed(&peer);
     }
 
     /// Starts propagating the new block to peers that haven't reported the block yet.
@@ -231,6 +233,7 @@ where
             peer.best_hash = hash;
         }
         self.state_fetcher.update_peer_block(peer_id, hash, number);
+        self.peers_manager.on_peer_new_block(peer_id, hash, number);
     }
 
     /// Invoked when a new [`ForkId`] is activated.
@@ -250,6 +253,11 @@ where
 
     /// Invoked for a `NewBlockHashes` broadcast message.
     pub(crate) fn on_new_block_hashes(&mut self, peer_id: PeerId, hashes: Vec<BlockHashNumber>) {
+        // the latest announced block is the new head of the peer
+        if let Some(latest) = hashes.iter().max_by_key(|block| block.number) {
+            self.peers_manager.on_peer_new_block(&peer_id, latest.hash, latest.number);
+        }
+
         // Mark the blocks as seen
         if let Some(peer) = self.active_peers.get_mut(&peer_id) {
             peer.blocks.extend(hashes.into_iter().map(|b| b.hash));
@@ -274,8 +282,14 @@ where
     }
 
     /// Adds a peer and its address with the given kind to the peerset.
-    pub(crate) fn add_peer_kind(&mut self, peer_id: PeerId, kind: PeerKind, addr: SocketAddr) {
-        self.peers_manager.add_peer_kind(peer_id, kind, addr, None)
+    pub(crate) fn add_peer_kind(
+        &mut self,
+        peer_id: PeerId,
+        kind: PeerKind,
+        source: PeerSource,
+        addr: SocketAddr,
+    ) {
+        self.peers_manager.add_peer_kind(peer_id, kind, source, addr, None)
     }
 
     pub(crate) fn remove_peer(&mut self, peer_id: PeerId, kind: PeerKind) {
@@ -292,11 +306,13 @@ where
                 peer_id,
                 socket_addr,
                 fork_id,
+                source,
             }) => {
                 self.queued_messages.push_back(StateAction::DiscoveredNode {
                     peer_id,
                     socket_addr,
                     fork_id,
+                    source,
                 });
             }
             DiscoveryEvent::EnrForkId(peer_id, fork_id) => {
@@ -316,10 +332,19 @@ where
                 self.state_fetcher.on_pending_disconnect(&peer_id);
                 self.queued_messages.push_back(StateAction::Disconnect { peer_id, reason });
             }
-            PeerAction::DisconnectBannedIncoming { peer_id } |
+            PeerAction::DisconnectBannedIncoming { peer_id } => {
+                self.state_fetcher.on_pending_disconnect(&peer_id);
+                self.queued_messages.push_back(StateAction::Disconnect {
+                    peer_id,
+                    reason: Some(DisconnectReason::ProtocolBreach),
+                });
+            }
             PeerAction::DisconnectUntrustedIncoming { peer_id } => {
                 self.state_fetcher.on_pending_disconnect(&peer_id);
-                self.queued_messages.push_back(StateAction::Disconnect { peer_id, reason: None });
+                self.queued_messages.push_back(StateAction::Disconnect {
+                    peer_id,
+                    reason: Some(DisconnectReason::UselessPeer),
+                });
             }
             PeerAction::DiscoveryBanPeerId { peer_id, ip_addr } => {
                 self.ban_discovery(peer_id, ip_addr)
@@ -516,7 +541,13 @@ pub(crate) enum StateAction {
         fork_id: ForkId,
     },
     /// A new node was found through the discovery, possibly with a `ForkId`
-    DiscoveredNode { peer_id: PeerId, socket_addr: SocketAddr, fork_id: Option<ForkId> },
+    DiscoveredNode {
+        peer_id: PeerId,
+        socket_addr: SocketAddr,
+        fork_id: Option<ForkId>,
+        /// The discovery service that found the node.
+        source: PeerSource,
+    },
     /// A peer was added
     PeerAdded(PeerId),
     /// A peer was dropped
@@ -10,7 +10,7 @@ use futures::Stream;
 use reth_eth_wire::{
     capability::{Capabilities, CapabilityMessage},
     errors::EthStreamError,
-    EthVersion, Status,
+    DisconnectReason, EthVersion, Status,
 };
 use reth_network_peers::PeerId;
 use reth_provider::{BlockNumReader, BlockReader};
@@ -163,13 +163,18 @@ where
             SessionEvent::OutgoingPendingSessionClosed { remote_addr, peer_id, error } => {
                 Some(SwarmEvent::OutgoingPendingSessionClosed { remote_addr, peer_id, error })
             }
-            SessionEvent::Disconnected { peer_id, remote_addr } => {
+            SessionEvent::Disconnected { peer_id, remote_addr, reason } => {
                 self.state.on_session_closed(peer_id);
-                Some(SwarmEvent::SessionClosed { peer_id, remote_addr, error: None })
+                Some(SwarmEvent::SessionClosed { peer_id, remote_addr, reason, error: None })
             }
             SessionEvent::SessionClosedOnConnectionError { peer_id, remote_addr, error } => {
                 self.state.on_session_closed(peer_id);
-                Some(SwarmEvent::SessionClosed { peer_id, remote_addr, error: Some(error) })
+                Some(SwarmEvent::SessionClosed {
+                    peer_id,
+                    remote_addr,
+                    reason: None,
+                    error: Some(error),
+                })
             }
             SessionEvent::OutgoingConnectionError { remote_addr, peer_id, error } => {
                 Some(SwarmEvent::OutgoingConnectionError { peer_id, remote_addr, error })
@@ -247,14 +252,14 @@ where
             }
             StateAction::PeerAdded(peer_id) => return Some(SwarmEvent::PeerAdded(peer_id)),
             StateAction::PeerRemoved(peer_id) => return Some(SwarmEvent::PeerRemoved(peer_id)),
-            StateAction::DiscoveredNode { peer_id, socket_addr, fork_id } => {
+            StateAction::DiscoveredNode { peer_id, socket_addr, fork_id, source } => {
                 // Don't try to connect to peer if node is shutting down
                 if self.is_shutting_down() {
                     return None
                 }
                 // Insert peer only if no fork id or a valid fork id
                 if fork_id.map_or_else(|| true, |f| self.sessions.is_valid_fork_id(f)) {
-                    self.state_mut().peers_mut().add_peer(peer_id, socket_addr, fork_id);
+                    self.state_mut().peers_mut().add_peer(peer_id, socket_addr, fork_id, source);
                 }
             }
             StateAction::DiscoveredEnrForkId { peer_id, fork_id } => {
@@ -403,6 +408,8 @@ pub(crate) enum SwarmEvent {
     SessionClosed {
         peer_id: PeerId,
         remote_addr: SocketAddr,
+        /// The reason the peer sent, if it gracefully disconnected
+        reason: Option<DisconnectReason>,
         /// Whether the session was closed due to an error
         error: Option<EthStreamError>,
     },
@@ -0,0 +1,343 @@
+//! Selection of the pipeline sync target from the chain heads reported by peers.
+//!
+//! Without a consensus layer, e.g. on pre-merge chains, the node has no trusted source for the
+//! block it should sync to. The [`PeerSyncTarget`] picks the best head that enough distinct peers
+//! agree on, and only adopts it once the header of the head was fetched and validated, so peers
+//! can't make the node chase a head that doesn't exist.
+
+use crate::{
+    cache::LruCache,
+    peers::{AgreedHead, PeerHead, PeersHandle},
+};
+use reth_consensus::{Consensus, ConsensusError};
+use reth_network_p2p::{
+    error::RequestError, headers::client::HeadersClient, priority::Priority,
+    sync::SyncTargetProvider,
+};
+use reth_network_peers::PeerId;
+use reth_primitives::{BlockNumHash, BlockNumber, B256};
+use reth_provider::BlockNumReader;
+use std::{sync::Arc, time::Duration};
+use tokio::sync::watch;
+use tracing::{debug, info};
+
+/// The default number of distinct peers that have to report a head before it's adopted.
+pub const DEFAULT_SYNC_TARGET_MIN_PEERS: usize = 3;
+
+/// The default interval at which the sync target is re-evaluated.
+pub const DEFAULT_SYNC_TARGET_INTERVAL: Duration = Duration::from_secs(12);
+
+/// The number of invalid heads that are remembered, so they aren't fetched again.
+const REJECTED_HEADS_LIMIT: u32 = 1024;
+
+/// Configuration of the [`PeerSyncTarget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerSyncTargetConfig {
+    /// The number of distinct peers that have to report a head before it's adopted.
+    pub min_peers: usize,
+    /// The interval at which the sync target is re-evaluated.
+    pub interval: Duration,
+}
+
+impl Default for PeerSyncTargetConfig {
+    fn default() -> Self {
+        Self { min_peers: DEFAULT_SYNC_TARGET_MIN_PEERS, interval: DEFAULT_SYNC_TARGET_INTERVAL }
+    }
+}
+
+/// Why a head reported by peers was not adopted.
+#[derive(Debug, thiserror::Error)]
+enum InvalidHead {
+    #[error("failed to fetch the header: {0}")]
+    Request(#[from] RequestError),
+    #[error("the header is not available")]
+    Missing,
+    #[error("received the header {got} instead")]
+    HashMismatch { got: B256 },
+    #[error("the header is block {got}, but peers reported block {claimed}")]
+    NumberMismatch { got: BlockNumber, claimed: BlockNumber },
+    #[error(transparent)]
+    Consensus(#[from] ConsensusError),
+}
+
+impl InvalidHead {
+    /// Returns true if the header of the head is invalid.
+    ///
+    /// The other errors are caused by the peers that reported or served the head, and the head
+    /// may still be adopted later.
+    const fn is_invalid(&self) -> bool {
+        matches!(self, Self::Consensus(_))
+    }
+}
+
+/// Selects the sync target from the chain heads reported by peers.
+///
+/// A head is only considered once at least [`PeerSyncTargetConfig::min_peers`] distinct peers
+/// report it, and only adopted once its header was fetched and passed the consensus checks. The
+/// target never moves backwards: heads at or below the current target or the local checkpoint
+/// are ignored.
+#[derive(Debug)]
+pub struct PeerSyncTarget<Client> {
+    /// Client used to fetch the headers of the reported heads.
+    client: Client,
+    /// Consensus used to validate the headers of the reported heads.
+    consensus: Arc<dyn Consensus>,
+    /// The configuration.
+    config: PeerSyncTargetConfig,
+    /// The adopted target.
+    target: watch::Sender<Option<BlockNumHash>>,
+    /// Heads whose header was invalid.
+    rejected: LruCache<B256>,
+}
+
+impl<Client> PeerSyncTarget<Client>
+where
+    Client: HeadersClient,
+{
+    /// Creates a new selector that fetches headers with the given client.
+    pub fn new(
+        client: Client,
+        consensus: Arc<dyn Consensus>,
+        config: PeerSyncTargetConfig,
+    ) -> Self {
+        Self {
+            client,
+            consensus,
+            config,
+            target: watch::channel(None).0,
+            rejected: LruCache::new(REJECTED_HEADS_LIMIT),
+        }
+    }
+
+    /// Returns a handle to the selected target.
+    pub fn handle(&self) -> PeerSyncTargetHandle {
+        PeerSyncTargetHandle { target: self.target.subscribe() }
+    }
+
+    /// Returns the adopted target, if any.
+    pub fn target(&self) -> Option<BlockNumHash> {
+        *self.target.borrow()
+    }
+
+    /// Re-evaluates the target with the current heads of the peers and the local checkpoint.
+    ///
+    /// Returns the new target if it changed.
+    pub async fn update(
+        &mut self,
+        heads: impl IntoIterator<Item = (PeerId, PeerHead)>,
+        checkpoint: BlockNumber,
+    ) -> Option<BlockNumHash> {
+        let current = self.target();
+        let floor = current.map_or(checkpoint, |target| target.number.max(checkpoint));
+
+        for head in AgreedHead::from_peer_heads(heads, self.config.min_peers) {
+            if current.map_or(false, |target| target.hash == head.hash) {
+                // there's no better head than the current target
+                return None
+            }
+            if self.rejected.contains(&head.hash) ||
+                head.number.map_or(false, |number| number <= floor)
+            {
+                continue
+            }
+
+            match self.fetch_head(&head).await {
+                Ok(number) if number <= floor => {
+                    debug!(target: "net::sync_target", hash=%head.hash, number, floor, "Ignoring head below the sync target");
+                }
+                Ok(number) => {
+                    let target = BlockNumHash::new(number, head.hash);
+                    info!(target: "net::sync_target", number, hash=%head.hash, peers=head.peers.len(), "New sync target");
+                    self.target.send_replace(Some(target));
+                    return Some(target)
+                }
+                Err(err) if err.is_invalid() => {
+                    debug!(target: "net::sync_target", hash=%head.hash, peers=?head.peers, %err, "Rejecting head reported by peers");
+                    self.rejected.insert(head.hash);
+                }
+                Err(err) => {
+                    debug!(target: "net::sync_target", hash=%head.hash, peers=?head.peers, %err, "Skipping head reported by peers");
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Fetches and validates the header of the head and returns its number.
+    async fn fetch_head(&self, head: &AgreedHead) -> Result<BlockNumber, InvalidHead> {
+        let (peer_id, header) =
+            self.client.get_header_with_priority(head.hash.into(), Priority::High).await?.split();
+        let header = header.ok_or(InvalidHead::Missing)?.seal_slow();
+        if header.hash() != head.hash {
+            self.client.report_bad_message(peer_id);
+            return Err(InvalidHead::HashMismatch { got: header.hash() })
+        }
+        if let Some(claimed) = head.number.filter(|claimed| *claimed != header.number) {
+            return Err(InvalidHead::NumberMismatch { got: header.number, claimed })
+        }
+        self.consensus.validate_header(&header)?;
+        Ok(header.number)
+    }
+
+    /// Periodically re-evaluates the target with the heads of the active peers.
+    ///
+    /// The local checkpoint is the best block number of the provider.
+    pub async fn run<Provider>(mut self, peers: PeersHandle, provider: Provider)
+    where
+        Provider: BlockNumReader,
+    {
+        let mut interval = tokio::time::interval(self.config.interval);
+        loop {
+            interval.tick().await;
+            let checkpoint = provider.best_block_number().unwrap_or_default();
+            self.update(peers.peer_heads().await, checkpoint).await;
+        }
+    }
+}
+
+/// A cloneable handle to the target selected by the [`PeerSyncTarget`].
+#[derive(Debug, Clone)]
+pub struct PeerSyncTargetHandle {
+    target: watch::Receiver<Option<BlockNumHash>>,
+}
+
+impl SyncTargetProvider for PeerSyncTargetHandle {
+    fn sync_target(&self) -> Option<BlockNumHash> {
+        *self.target.borrow()
+    }
+
+    fn subscribe_sync_target(&self) -> watch::Receiver<Option<BlockNumHash>> {
+        self.target.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_consensus::test_utils::TestConsensus;
+    use reth_network_p2p::test_utils::TestFullBlockClient;
+    use reth_primitives::{BlockBody, Header, U256};
+
+    /// Inserts a chain of headers into the client and returns them.
+    fn insert_chain(client: &TestFullBlockClient, len: u64) -> Vec<BlockNumHash> {
+        let mut parent_hash = B256::ZERO;
+        (0..len)
+            .map(|number| {
+                let header = Header { number, parent_hash, ..Default::default() }.seal_slow();
+                parent_hash = header.hash();
+                client.insert(header.clone(), BlockBody::default());
+                header.num_hash()
+            })
+            .collect()
+    }
+
+    fn status(hash: B256) -> PeerHead {
+        PeerHead { hash, number: None, total_difficulty: Some(U256::from(1)) }
+    }
+
+    fn announced(block: BlockNumHash) -> PeerHead {
+        PeerHead { hash: block.hash, number: Some(block.number), total_difficulty: None }
+    }
+
+    #[tokio::test]
+    async fn honest_majority_and_liar() {
+        let client = TestFullBlockClient::default();
+        let chain = insert_chain(&client, 20);
+        let config = PeerSyncTargetConfig { min_peers: 3, ..Default::default() };
+        let mut selector = PeerSyncTarget::new(client, Arc::new(TestConsensus::default()), config);
+        let handle = selector.handle();
+
+        let honest = (0..3).map(|_| PeerId::random()).collect::<Vec<_>>();
+        let liar = PeerId::random();
+        let absurd = BlockNumHash::new(u64::MAX, B256::random());
+
+        // a head reported by too few peers is never considered
+        let mut heads = vec![(honest[0], status(chain[10].hash)), (liar, announced(absurd))];
+        assert_eq!(selector.update(heads.clone(), 0).await, None);
+
+        heads.extend(honest[1..].iter().map(|peer| (*peer, status(chain[10].hash))));
+        assert_eq!(selector.update(heads.clone(), 0).await, Some(chain[10]));
+        assert_eq!(handle.sync_target(), Some(chain[10]));
+
+        // the honest peers advance
+        let mut heads = honest.iter().map(|peer| (*peer, announced(chain[15]))).collect::<Vec<_>>();
+        heads.push((liar, announced(absurd)));
+        assert_eq!(selector.update(heads.clone(), 5).await, Some(chain[15]));
+        assert_eq!(handle.sync_target(), Some(chain[15]));
+
+        // nothing changes while the heads stay the same
+        assert_eq!(selector.update(heads, 5).await, None);
+        assert_eq!(*handle.subscribe_sync_target().borrow(), Some(chain[15]));
+    }
+
+    #[tokio::test]
+    async fn colluding_liars_are_bounded_by_the_header() {
+        let client = TestFullBlockClient::default();
+        let chain = insert_chain(&client, 20);
+        let consensus = Arc::new(TestConsensus::default());
+        let mut selector =
+            PeerSyncTarget::new(client, consensus.clone(), PeerSyncTargetConfig::default());
+
+        let honest = (0..3).map(|_| (PeerId::random(), announced(chain[12])));
+        let liars = (0..4)
+            .map(|_| (PeerId::random(), announced(BlockNumHash::new(1 << 40, B256::random()))));
+        let heads = honest.chain(liars).collect::<Vec<_>>();
+
+        // the unfetchable head of the liars is rejected in favour of the honest head
+        assert_eq!(selector.update(heads.clone(), 0).await, Some(chain[12]));
+
+        // peers that lie about the number of a real block are rejected
+        let misnumbered = BlockNumHash::new(chain[19].number + 100, chain[19].hash);
+        let heads = (0..3).map(|_| (PeerId::random(), announced(misnumbered))).collect::<Vec<_>>();
+        assert_eq!(selector.update(heads, 0).await, None);
+
+        // heads with an invalid header are rejected
+        consensus.set_fail_validation(true);
+        let heads = (0..3).map(|_| (PeerId::random(), announced(chain[18]))).collect::<Vec<_>>();
+        assert_eq!(selector.update(heads, 0).await, None);
+        assert_eq!(selector.target(), Some(chain[12]));
+    }
+
+    #[tokio::test]
+    async fn target_never_moves_backwards() {
+        let client = TestFullBlockClient::default();
+        let chain = insert_chain(&client, 20);
+        let config = PeerSyncTargetConfig { min_peers: 2, ..Default::default() };
+        let mut selector = PeerSyncTarget::new(client, Arc::new(TestConsensus::default()), config);
+
+        let peers = (0..2).map(|_| PeerId::random()).collect::<Vec<_>>();
+        let heads_at = |block: BlockNumHash| {
+            peers.iter().map(|peer| (*peer, status(block.hash))).collect::<Vec<_>>()
+        };
+
+        // heads at or below the local checkpoint are ignored
+        assert_eq!(selector.update(heads_at(chain[8]), 8).await, None);
+        assert_eq!(selector.update(heads_at(chain[9]), 8).await, Some(chain[9]));
+
+        // peers that fall behind, e.g. after a reorg, don't move the target backwards
+        assert_eq!(selector.update(heads_at(chain[7]), 8).await, None);
+        assert_eq!(selector.target(), Some(chain[9]));
+        assert_eq!(selector.update(heads_at(chain[14]), 8).await, Some(chain[14]));
+    }
+
+    #[tokio::test]
+    async fn unavailable_head_is_fetched_again() {
+        let client = TestFullBlockClient::default();
+        let chain = insert_chain(&client, 10);
+        let config = PeerSyncTargetConfig { min_peers: 2, ..Default::default() };
+        let mut selector =
+            PeerSyncTarget::new(client.clone(), Arc::new(TestConsensus::default()), config);
+
+        let next =
+            Header { number: 10, parent_hash: chain[9].hash, ..Default::default() }.seal_slow();
+        let heads = (0..2).map(|_| (PeerId::random(), status(next.hash()))).collect::<Vec<_>>();
+
+        // the header isn't available yet
+        assert_eq!(selector.update(heads.clone(), 0).await, None);
+
+        // once the header propagated, the head is adopted
+        client.insert(next.clone(), BlockBody::default());
+        assert_eq!(selector.update(heads, 0).await, Some(next.num_hash()));
+    }
+}
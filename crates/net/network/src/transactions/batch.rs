@@ -0,0 +1,177 @@
+//! Batching of pending transactions before they are propagated.
+//!
+//! Propagating every new pending transaction right away produces a lot of tiny messages per peer
+//! while the pool is busy. Instead, new pending transactions are collected for a short interval
+//! and then propagated at once, so every peer receives a single message per batch.
+
+use reth_primitives::TxHash;
+use reth_transaction_pool::TransactionOrigin;
+use std::{
+    collections::HashSet,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::time::Sleep;
+
+/// Why a batch of pending transactions is propagated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum FlushReason {
+    /// The batch interval elapsed.
+    Interval,
+    /// The batch reached its max size.
+    Size,
+    /// Local transactions that bypass batching.
+    Local,
+    /// The manager shuts down.
+    Shutdown,
+}
+
+/// Collects the hashes of new pending transactions until they are propagated.
+#[derive(Debug)]
+pub(super) struct PropagationBatch {
+    /// How long transactions are collected before the batch is propagated.
+    interval: Duration,
+    /// Max number of transactions in a batch.
+    max_size: usize,
+    /// Whether local transactions are propagated right away.
+    local_bypass: bool,
+    /// The transactions of the current batch, in the order they became pending.
+    hashes: Vec<TxHash>,
+    /// The transactions of the current batch, so that each is only propagated once per batch.
+    queued: HashSet<TxHash>,
+    /// When the current batch is due.
+    deadline: Option<Instant>,
+    /// Wakes the manager once the current batch is due.
+    timer: Option<Pin<Box<Sleep>>>,
+}
+
+impl PropagationBatch {
+    /// Creates a new batch that propagates transactions at least every `interval`, or as soon as
+    /// `max_size` transactions are collected.
+    pub(super) fn new(interval: Duration, max_size: usize, local_bypass: bool) -> Self {
+        Self {
+            interval,
+            max_size: max_size.max(1),
+            local_bypass,
+            hashes: Vec::new(),
+            queued: HashSet::default(),
+            deadline: None,
+            timer: None,
+        }
+    }
+
+    /// Returns `true` if transactions with the given origin are propagated right away instead of
+    /// being batched.
+    pub(super) fn bypasses(&self, origin: TransactionOrigin) -> bool {
+        self.interval.is_zero() || (self.local_bypass && origin.is_local())
+    }
+
+    /// Adds the transaction to the current batch, the first transaction of a batch starts its
+    /// interval.
+    pub(super) fn push(&mut self, hash: TxHash, now: Instant) {
+        if !self.queued.insert(hash) {
+            return
+        }
+        self.hashes.push(hash);
+        if self.deadline.is_none() {
+            let deadline = now + self.interval;
+            self.deadline = Some(deadline);
+            self.timer = Some(Box::pin(tokio::time::sleep_until(deadline.into())));
+        }
+    }
+
+    /// Returns why the current batch should be propagated, if it is due.
+    pub(super) fn flush_reason(&self, now: Instant) -> Option<FlushReason> {
+        if self.hashes.len() >= self.max_size {
+            return Some(FlushReason::Size)
+        }
+        self.deadline.filter(|deadline| now >= *deadline).map(|_| FlushReason::Interval)
+    }
+
+    /// Resolves once the interval of the current batch elapsed.
+    ///
+    /// Never resolves if the batch is empty.
+    pub(super) fn poll_interval(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        match self.timer.as_mut() {
+            Some(timer) => timer.as_mut().poll(cx),
+            None => Poll::Pending,
+        }
+    }
+
+    /// Takes the transactions of the current batch and starts a new one.
+    pub(super) fn take(&mut self) -> Vec<TxHash> {
+        self.queued.clear();
+        self.deadline = None;
+        self.timer = None;
+        std::mem::take(&mut self.hashes)
+    }
+
+    /// Returns the number of transactions in the current batch.
+    #[cfg(test)]
+    pub(super) fn len(&self) -> usize {
+        self.hashes.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::B256;
+
+    #[tokio::test]
+    async fn batches_until_interval_elapsed() {
+        let interval = Duration::from_millis(100);
+        let mut batch = PropagationBatch::new(interval, 10, true);
+        let now = Instant::now();
+        let (a, b) = (B256::random(), B256::random());
+
+        assert_eq!(batch.flush_reason(now), None);
+        batch.push(a, now);
+        // a later transaction doesn't extend the interval of the batch
+        batch.push(b, now + Duration::from_millis(60));
+        assert_eq!(batch.flush_reason(now + Duration::from_millis(99)), None);
+        assert_eq!(batch.flush_reason(now + interval), Some(FlushReason::Interval));
+        assert_eq!(batch.take(), vec![a, b]);
+
+        // the next batch starts with its first transaction
+        let later = now + Duration::from_millis(150);
+        assert_eq!(batch.flush_reason(later), None);
+        batch.push(a, later);
+        assert_eq!(batch.flush_reason(later + Duration::from_millis(99)), None);
+        assert_eq!(batch.flush_reason(later + interval), Some(FlushReason::Interval));
+    }
+
+    #[tokio::test]
+    async fn flushes_full_batch() {
+        let mut batch = PropagationBatch::new(Duration::from_secs(1), 2, true);
+        let now = Instant::now();
+        let hash = B256::random();
+
+        batch.push(hash, now);
+        // the same transaction is only included once
+        batch.push(hash, now);
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch.flush_reason(now), None);
+
+        batch.push(B256::random(), now);
+        assert_eq!(batch.flush_reason(now), Some(FlushReason::Size));
+        assert_eq!(batch.take().len(), 2);
+        assert_eq!(batch.flush_reason(now + Duration::from_secs(1)), None);
+    }
+
+    #[test]
+    fn local_bypass() {
+        let batch = PropagationBatch::new(Duration::from_millis(100), 10, true);
+        assert!(batch.bypasses(TransactionOrigin::Local));
+        assert!(!batch.bypasses(TransactionOrigin::External));
+
+        let batch = PropagationBatch::new(Duration::from_millis(100), 10, false);
+        assert!(!batch.bypasses(TransactionOrigin::Local));
+
+        // batching is disabled without an interval
+        let batch = PropagationBatch::new(Duration::ZERO, 10, false);
+        assert!(batch.bypasses(TransactionOrigin::External));
+    }
+}
@@ -1,15 +1,100 @@
 use super::{
+    constants::tx_manager::{
+        DEFAULT_DEEP_SYNC_THRESHOLD, DEFAULT_MAX_PROPAGATION_BATCH_SIZE,
+        DEFAULT_PROPAGATION_INTERVAL, DEFAULT_REJECTED_IMPORTS_THRESHOLD,
+        DEFAULT_REJECTED_IMPORTS_WINDOW,
+    },
     DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
     SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
 };
 use derive_more::Constructor;
+use std::time::Duration;
 
 /// Configuration for managing transactions within the network.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransactionsManagerConfig {
     /// Configuration for fetching transactions.
     pub transaction_fetcher_config: TransactionFetcherConfig,
+    /// Number of blocks the node can be behind the sync target before transaction gossip is
+    /// suppressed.
+    #[cfg_attr(feature = "serde", serde(default = "default_deep_sync_threshold"))]
+    pub deep_sync_threshold: u64,
+    /// Max number of transactions of a peer that may be rejected by the pool within
+    /// [`Self::rejected_imports_window`], for reasons that don't depend on the state, before the
+    /// peer is penalized.
+    ///
+    /// Transactions that are [bad](reth_transaction_pool::error::PoolError::is_bad_transaction)
+    /// are penalized right away.
+    #[cfg_attr(feature = "serde", serde(default = "default_rejected_imports_threshold"))]
+    pub rejected_imports_threshold: u32,
+    /// Window in which the rejected transactions of a peer are counted.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "default_rejected_imports_window", with = "humantime_serde")
+    )]
+    pub rejected_imports_window: Duration,
+    /// Interval in which new pending transactions are collected, before they are propagated to
+    /// peers with one message per peer.
+    ///
+    /// A zero interval propagates every new pending transaction right away.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "default_propagation_interval", with = "humantime_serde")
+    )]
+    pub propagation_interval: Duration,
+    /// Max number of new pending transactions that are collected, before they are propagated
+    /// even if the [`Self::propagation_interval`] hasn't elapsed yet.
+    #[cfg_attr(feature = "serde", serde(default = "default_max_propagation_batch_size"))]
+    pub max_propagation_batch_size: usize,
+    /// Whether [local](reth_transaction_pool::TransactionOrigin::Local) transactions are
+    /// propagated right away instead of being collected.
+    #[cfg_attr(feature = "serde", serde(default = "default_propagate_local_immediately"))]
+    pub propagate_local_immediately: bool,
+}
+
+impl Default for TransactionsManagerConfig {
+    fn default() -> Self {
+        Self {
+            transaction_fetcher_config: Default::default(),
+            deep_sync_threshold: DEFAULT_DEEP_SYNC_THRESHOLD,
+            rejected_imports_threshold: DEFAULT_REJECTED_IMPORTS_THRESHOLD,
+            rejected_imports_window: DEFAULT_REJECTED_IMPORTS_WINDOW,
+            propagation_interval: DEFAULT_PROPAGATION_INTERVAL,
+            max_propagation_batch_size: DEFAULT_MAX_PROPAGATION_BATCH_SIZE,
+            propagate_local_immediately: true,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+const fn default_deep_sync_threshold() -> u64 {
+    DEFAULT_DEEP_SYNC_THRESHOLD
+}
+
+#[cfg(feature = "serde")]
+const fn default_rejected_imports_threshold() -> u32 {
+    DEFAULT_REJECTED_IMPORTS_THRESHOLD
+}
+
+#[cfg(feature = "serde")]
+const fn default_rejected_imports_window() -> Duration {
+    DEFAULT_REJECTED_IMPORTS_WINDOW
+}
+
+#[cfg(feature = "serde")]
+const fn default_propagation_interval() -> Duration {
+    DEFAULT_PROPAGATION_INTERVAL
+}
+
+#[cfg(feature = "serde")]
+const fn default_max_propagation_batch_size() -> usize {
+    DEFAULT_MAX_PROPAGATION_BATCH_SIZE
+}
+
+#[cfg(feature = "serde")]
+const fn default_propagate_local_immediately() -> bool {
+    true
 }
 
 /// Configuration for fetching transactions.
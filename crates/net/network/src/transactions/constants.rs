@@ -36,6 +36,7 @@ pub const SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE: usize = 2 * 1024 *
 /// Constants used by [`TransactionsManager`](super::TransactionsManager).
 pub mod tx_manager {
     use super::SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE;
+    use std::time::Duration;
 
     /// Default limit for number of transactions to keep track of for a single peer.
     ///
@@ -53,6 +54,36 @@ pub mod tx_manager {
     ///
     /// Default is 10 KiB.
     pub const DEFAULT_CAPACITY_CACHE_BAD_IMPORTS: u32 = 100 * 1024;
+
+    /// Default number of blocks the node can be behind the sync target before transaction gossip
+    /// is suppressed.
+    ///
+    /// Default is 64 blocks, i.e. two epochs.
+    pub const DEFAULT_DEEP_SYNC_THRESHOLD: u64 = 64;
+
+    /// Default max number of transactions of a peer that may be rejected by the pool within
+    /// [`DEFAULT_REJECTED_IMPORTS_WINDOW`], before the peer is penalized.
+    ///
+    /// Default is 256 transactions.
+    pub const DEFAULT_REJECTED_IMPORTS_THRESHOLD: u32 = 256;
+
+    /// Default window in which the rejected transactions of a peer are counted.
+    ///
+    /// Default is 1 minute.
+    pub const DEFAULT_REJECTED_IMPORTS_WINDOW: Duration = Duration::from_secs(60);
+
+    /// Default interval in which new pending transactions are collected before they are
+    /// propagated.
+    ///
+    /// Default is 100 milliseconds.
+    pub const DEFAULT_PROPAGATION_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Default max number of new pending transactions that are collected before they are
+    /// propagated, regardless of the interval.
+    ///
+    /// Default is the number of hashes in one full announcement, which is spec'd at 4096 hashes.
+    pub const DEFAULT_MAX_PROPAGATION_BATCH_SIZE: usize =
+        SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE;
 }
 
 /// Constants used by [`TransactionFetcher`](super::TransactionFetcher).
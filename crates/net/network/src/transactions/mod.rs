@@ -48,24 +48,28 @@ use std::{
 };
 use tokio::sync::{mpsc, oneshot, oneshot::error::RecvError};
 use tokio_stream::wrappers::{ReceiverStream, UnboundedReceiverStream};
-use tracing::{debug, trace};
+use tracing::{debug, info, trace};
 
+mod batch;
 /// Aggregation on configurable parameters for [`TransactionsManager`].
 pub mod config;
 /// Default and spec'd bounds.
 pub mod constants;
 /// Component responsible for fetching transactions from [`NewPooledTransactionHashes`].
 pub mod fetcher;
+mod rejected;
 pub mod validation;
 pub use config::{TransactionFetcherConfig, TransactionsManagerConfig};
 
+use batch::{FlushReason, PropagationBatch};
 use constants::SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE;
 pub(crate) use fetcher::{FetchEvent, TransactionFetcher};
+use rejected::{is_rejected_import, RejectedImports};
 pub use validation::*;
 
 pub use self::constants::{
     tx_fetcher::DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
-    SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
+    tx_manager::DEFAULT_DEEP_SYNC_THRESHOLD, SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
 };
 use self::constants::{tx_manager::*, DEFAULT_SOFT_LIMIT_BYTE_SIZE_TRANSACTIONS_BROADCAST_MESSAGE};
 
@@ -222,6 +226,8 @@ pub struct TransactionsManager<Pool> {
     pending_pool_imports_info: PendingPoolImportsInfo,
     /// Bad imports.
     bad_imports: LruCache<TxHash>,
+    /// Peers whose transactions are rejected by the pool.
+    rejected_imports: RejectedImports,
     /// All the connected peers.
     peers: HashMap<PeerId, PeerMetadata>,
     /// Send half for the command channel.
@@ -242,10 +248,19 @@ pub struct TransactionsManager<Pool> {
     ///   - all dynamic fee requirements are (currently) met
     ///   - account has enough balance to cover the transaction's gas
     pending_transactions: ReceiverStream<TxHash>,
+    /// New pending transactions that are collected before they are propagated.
+    propagation_batch: PropagationBatch,
     /// Incoming events from the [`NetworkManager`](crate::NetworkManager).
     transaction_events: UnboundedMeteredReceiver<NetworkTransactionEvent>,
     /// `TransactionsManager` metrics
     metrics: TransactionsManagerMetrics,
+    /// Number of blocks the node can be behind the sync target before transaction gossip is
+    /// suppressed, see [`TransactionsManagerConfig::deep_sync_threshold`].
+    deep_sync_threshold: u64,
+    /// Whether transaction gossip was suppressed the last time the sync state was checked.
+    ///
+    /// Used to report transitions, see [`TransactionsManager::update_deep_sync_state`].
+    deep_syncing: bool,
 }
 
 impl<Pool: TransactionPool> TransactionsManager<Pool> {
@@ -286,15 +301,26 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
                 DEFAULT_MAX_COUNT_PENDING_POOL_IMPORTS,
             ),
             bad_imports: LruCache::new(DEFAULT_CAPACITY_CACHE_BAD_IMPORTS),
+            rejected_imports: RejectedImports::new(
+                transactions_manager_config.rejected_imports_threshold,
+                transactions_manager_config.rejected_imports_window,
+            ),
             peers: Default::default(),
             command_tx,
             command_rx: UnboundedReceiverStream::new(command_rx),
             pending_transactions: ReceiverStream::new(pending),
+            propagation_batch: PropagationBatch::new(
+                transactions_manager_config.propagation_interval,
+                transactions_manager_config.max_propagation_batch_size,
+                transactions_manager_config.propagate_local_immediately,
+            ),
             transaction_events: UnboundedMeteredReceiver::new(
                 from_network,
                 NETWORK_POOL_TRANSACTIONS_SCOPE,
             ),
             metrics,
+            deep_sync_threshold: transactions_manager_config.deep_sync_threshold,
+            deep_syncing: false,
         }
     }
 }
@@ -341,6 +367,37 @@ where
         metrics.acc_duration_poll_commands.set(acc_cmds.as_secs_f64());
     }
 
+    /// Returns `true` if the node is far behind the chain tip.
+    ///
+    /// This is the case during the initial (pipeline) sync, or while the local head is more than
+    /// [`TransactionsManagerConfig::deep_sync_threshold`] blocks behind the sync target. While
+    /// deep syncing, pool transactions are not propagated, requests for pooled transactions are
+    /// answered with empty responses and announced or broadcast transactions are dropped.
+    fn is_deep_syncing(&self) -> bool {
+        if self.network.is_initially_syncing() {
+            return true
+        }
+        self.network.sync_distance().is_some_and(|distance| distance > self.deep_sync_threshold)
+    }
+
+    /// Checks whether the node entered or left deep sync, and notifies the pool about it.
+    ///
+    /// See [`TransactionPool::set_syncing`].
+    fn update_deep_sync_state(&mut self) {
+        let deep_syncing = self.is_deep_syncing();
+        if deep_syncing == self.deep_syncing {
+            return
+        }
+        self.deep_syncing = deep_syncing;
+        self.pool.set_syncing(deep_syncing);
+
+        if deep_syncing {
+            info!(target: "net::tx", "Node is far behind the chain tip, suppressing transaction gossip");
+        } else {
+            info!(target: "net::tx", "Node caught up with the chain tip, resuming transaction gossip");
+        }
+    }
+
     /// Request handler for an incoming request for transactions
     fn on_get_pooled_transactions(
         &mut self,
@@ -348,8 +405,9 @@ where
         request: GetPooledTransactions,
         response: oneshot::Sender<RequestResult<PooledTransactions>>,
     ) {
+        let suppress_gossip = self.network.tx_gossip_disabled() || self.is_deep_syncing();
         if let Some(peer) = self.peers.get_mut(&peer_id) {
-            if self.network.tx_gossip_disabled() {
+            if suppress_gossip {
                 let _ = response.send(Ok(PooledTransactions::default()));
                 return
             }
@@ -380,21 +438,78 @@ where
     /// complete transaction object if it is unknown to them. The dissemination of complete
     /// transactions to a fraction of peers usually ensures that all nodes receive the transaction
     /// and won't need to request it.
+    ///
+    /// New pending transactions are collected for
+    /// [`TransactionsManagerConfig::propagation_interval`] and then propagated at once, so that
+    /// each peer receives a single message for the whole batch. Local transactions bypass the
+    /// batch if [`TransactionsManagerConfig::propagate_local_immediately`] is set.
     fn on_new_pending_transactions(&mut self, hashes: Vec<TxHash>) {
-        // Nothing to propagate while initially syncing
-        if self.network.is_initially_syncing() {
+        // Nothing to propagate while far behind the chain tip
+        if self.is_deep_syncing() {
             return
         }
         if self.network.tx_gossip_disabled() {
             return
         }
 
-        trace!(target: "net::tx", num_hashes=?hashes.len(), "Start propagating transactions");
-
+        let now = Instant::now();
+        let mut immediate = Vec::new();
         // This fetches all transaction from the pool, including the 4844 blob transactions but
         // __without__ their sidecar, because 4844 transactions are only ever announced as hashes.
+        for tx in self.pool.get_all(hashes) {
+            if self.propagation_batch.bypasses(tx.origin) {
+                immediate.push(tx);
+            } else {
+                self.propagation_batch.push(*tx.hash(), now);
+            }
+        }
+
+        if !immediate.is_empty() {
+            self.record_propagation_flush(FlushReason::Local, immediate.len());
+            self.propagate_pool_transactions(immediate);
+        }
+        if let Some(reason) = self.propagation_batch.flush_reason(now) {
+            self.flush_propagation_batch(reason);
+        }
+    }
+
+    /// Propagates the transactions collected in the current batch.
+    ///
+    /// Transactions that were removed from the pool in the meantime are skipped.
+    fn flush_propagation_batch(&mut self, reason: FlushReason) {
+        let hashes = self.propagation_batch.take();
+        if hashes.is_empty() {
+            return
+        }
+        if self.is_deep_syncing() || self.network.tx_gossip_disabled() {
+            return
+        }
+
+        self.record_propagation_flush(reason, hashes.len());
+        let transactions = self.pool.get_all(hashes);
+        self.propagate_pool_transactions(transactions);
+    }
+
+    /// Records a propagated batch of the given size in the metrics.
+    fn record_propagation_flush(&self, reason: FlushReason, size: usize) {
+        self.metrics.propagation_batch_size.record(size as f64);
+        match reason {
+            FlushReason::Interval => self.metrics.propagation_flushes_interval.increment(1),
+            FlushReason::Size => self.metrics.propagation_flushes_size.increment(1),
+            FlushReason::Local => self.metrics.propagation_flushes_local.increment(1),
+            FlushReason::Shutdown => self.metrics.propagation_flushes_shutdown.increment(1),
+        }
+    }
+
+    /// Propagates the pool transactions to all connected peers and notifies the pool.
+    fn propagate_pool_transactions(
+        &mut self,
+        transactions: Vec<Arc<ValidPoolTransaction<Pool::Transaction>>>,
+    ) {
+        trace!(target: "net::tx", num_txs=?transactions.len(), "Start propagating transactions");
+
         let propagated = self.propagate_transactions(
-            self.pool.get_all(hashes).into_iter().map(PropagateTransaction::new).collect(),
+            transactions.into_iter().map(PropagateTransaction::new).collect(),
         );
 
         // notify pool so events get fired
@@ -597,8 +712,8 @@ where
         peer_id: PeerId,
         msg: NewPooledTransactionHashes,
     ) {
-        // If the node is initially syncing, ignore transactions
-        if self.network.is_initially_syncing() {
+        // If the node is far behind the chain tip, ignore transactions
+        if self.is_deep_syncing() {
             return
         }
         if self.network.tx_gossip_disabled() {
@@ -886,10 +1001,13 @@ where
             NetworkEvent::SessionClosed { peer_id, .. } => {
                 // remove the peer
                 self.peers.remove(&peer_id);
+                self.rejected_imports.remove_peer(&peer_id);
             }
             NetworkEvent::SessionEstablished {
                 peer_id, client_version, messages, version, ..
             } => {
+                let suppress_gossip = self.is_deep_syncing() || self.network.tx_gossip_disabled();
+
                 // Insert a new peer into the peerset.
                 let peer = PeerMetadata::new(messages, version, client_version);
                 let peer = match self.peers.entry(peer_id) {
@@ -903,7 +1021,7 @@ where
                 // Send a `NewPooledTransactionHashes` to the peer with up to
                 // `SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE`
                 // transactions in the pool.
-                if self.network.is_initially_syncing() || self.network.tx_gossip_disabled() {
+                if suppress_gossip {
                     return
                 }
 
@@ -935,8 +1053,8 @@ where
         transactions: PooledTransactions,
         source: TransactionSource,
     ) {
-        // If the node is pipeline syncing, ignore transactions
-        if self.network.is_initially_syncing() {
+        // If the node is far behind the chain tip, ignore transactions
+        if self.is_deep_syncing() {
             return
         }
         if self.network.tx_gossip_disabled() {
@@ -1037,7 +1155,7 @@ where
 
                 let import = Box::pin(async move {
                     let added = new_txs.len();
-                    let res = pool.add_external_transactions(new_txs).await;
+                    let res = pool.add_peer_transactions(peer_id, new_txs).await;
 
                     // update metrics
                     metric_pending_pool_imports.decrement(added as f64);
@@ -1167,11 +1285,29 @@ where
     /// - kzg error
     /// - not blob transaction (tx type mismatch)
     /// - wrong versioned kzg commitment hash
+    ///
+    /// Peers that keep sending transactions that can never become valid, regardless of the state,
+    /// are penalized once they exceed [`TransactionsManagerConfig::rejected_imports_threshold`].
+    /// Peers that send [stale](PoolError::is_stale_transaction) transactions are not penalized.
     fn on_bad_import(&mut self, err: PoolError) {
         let peers = self.transactions_by_peers.remove(&err.hash);
 
         // if we're _currently_ syncing, we ignore a bad transaction
-        if !err.is_bad_transaction() || self.network.is_syncing() {
+        if self.network.is_syncing() {
+            return
+        }
+        if err.is_stale_transaction() {
+            // the transaction was valid until recently, e.g. before the block gas limit was
+            // lowered, so the peers aren't penalized, but it isn't requested again
+            self.bad_imports.insert(err.hash);
+            return
+        }
+        if !err.is_bad_transaction() {
+            if is_rejected_import(&err) {
+                for peer_id in peers.into_iter().flatten() {
+                    self.on_rejected_import(peer_id);
+                }
+            }
             return
         }
         // otherwise we penalize the peer that sent the bad transaction, with the assumption that
@@ -1185,6 +1321,14 @@ where
         self.bad_imports.insert(err.hash);
     }
 
+    /// Penalizes the peer if too many of its transactions were rejected recently.
+    fn on_rejected_import(&mut self, peer_id: PeerId) {
+        if self.rejected_imports.on_rejected(peer_id, Instant::now()) {
+            trace!(target: "net::tx", ?peer_id, "Penalizing peer for too many rejected transactions");
+            self.report_peer_bad_transactions(peer_id);
+        }
+    }
+
     /// Returns `true` if [`TransactionsManager`] has capacity to request pending hashes. Returns
     /// `false` if [`TransactionsManager`] is operating close to full capacity.
     fn has_capacity_for_fetching_pending_hashes(&self) -> bool {
@@ -1213,6 +1357,9 @@ where
 
         let this = self.get_mut();
 
+        // Suppress or resume transaction gossip depending on how far behind the chain tip we are.
+        this.update_deep_sync_state();
+
         // All streams are polled until their corresponding budget is exhausted, then we manually
         // yield back control to tokio. See `NetworkManager` for more context on the design
         // pattern.
@@ -1247,7 +1394,9 @@ where
             "Network events stream",
             DEFAULT_BUDGET_TRY_DRAIN_STREAM,
             this.network_events.poll_next_unpin(cx),
-            |event| this.on_network_event(event)
+            |event| this.on_network_event(event),
+            // the network shuts down, propagate what's left
+            this.flush_propagation_batch(FlushReason::Shutdown);
         );
 
         // Advances new __pending__ transactions, transactions that were successfully inserted into
@@ -1270,6 +1419,10 @@ where
         if !new_txs.is_empty() {
             this.on_new_pending_transactions(new_txs);
         }
+        // Propagate the batch of pending transactions once its interval elapsed.
+        if this.propagation_batch.poll_interval(cx).is_ready() {
+            this.flush_propagation_batch(FlushReason::Interval);
+        }
 
         // Advance inflight fetch requests (flush transaction fetcher and queue for
         // import to pool).
@@ -1617,7 +1770,11 @@ mod tests {
     };
     use reth_primitives::hex;
     use reth_provider::test_utils::NoopProvider;
-    use reth_transaction_pool::test_utils::{testing_pool, MockTransaction};
+    use reth_transaction_pool::{
+        error::PoolErrorKind,
+        test_utils::{testing_pool, MockTransaction},
+        TransactionOrigin,
+    };
     use secp256k1::SecretKey;
     use std::{fmt, future::poll_fn, hash};
     use tests::fetcher::TxFetchMetadata;
@@ -2020,6 +2177,196 @@ mod tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_batched_propagation() {
+        reth_tracing::init_test_tracing();
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let client = NoopProvider::default();
+        let pool = testing_pool();
+        let config = NetworkConfigBuilder::new(secret_key)
+            .disable_discovery()
+            .listener_port(0)
+            .build(client);
+        let transactions_manager_config = TransactionsManagerConfig {
+            propagation_interval: Duration::from_secs(60),
+            ..Default::default()
+        };
+        let (network_handle, _network, mut transactions, _) = NetworkManager::new(config)
+            .await
+            .unwrap()
+            .into_builder()
+            .transactions(pool.clone(), transactions_manager_config)
+            .split_with_handle();
+
+        // finish the initial sync
+        network_handle.update_sync_state(SyncState::Syncing);
+        network_handle.update_sync_state(SyncState::Idle);
+
+        let peer_id = PeerId::random();
+        let (peer, _to_mock_session_rx) = new_mock_session(peer_id, EthVersion::Eth68);
+        transactions.peers.insert(peer_id, peer);
+
+        let local = MockTransaction::eip1559();
+        let external = MockTransaction::eip1559();
+        pool.add_transaction(TransactionOrigin::Local, local.clone()).await.unwrap();
+        pool.add_transaction(TransactionOrigin::External, external.clone()).await.unwrap();
+
+        // the local transaction bypasses the batch
+        transactions.on_new_pending_transactions(vec![local.get_hash(), external.get_hash()]);
+        let seen = &transactions.peers[&peer_id].seen_transactions;
+        assert!(seen.contains(&local.get_hash()));
+        assert!(!seen.contains(&external.get_hash()));
+        assert_eq!(transactions.propagation_batch.len(), 1);
+
+        transactions.flush_propagation_batch(FlushReason::Interval);
+        assert!(transactions.peers[&peer_id].seen_transactions.contains(&external.get_hash()));
+        assert_eq!(transactions.propagation_batch.len(), 0);
+
+        // the transaction is batched again, but it's not announced to the peer a second time
+        transactions.on_new_pending_transactions(vec![external.get_hash()]);
+        assert_eq!(transactions.propagation_batch.len(), 1);
+        let batch = transactions.propagation_batch.take();
+        let propagated = transactions.propagate_transactions(
+            pool.get_all(batch).into_iter().map(PropagateTransaction::new).collect(),
+        );
+        assert!(propagated.0.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tx_gossip_suppressed_while_deep_syncing() {
+        reth_tracing::init_test_tracing();
+        let net = Testnet::create(2).await;
+
+        let mut handles = net.handles();
+        let handle0 = handles.next().unwrap();
+        let handle1 = handles.next().unwrap();
+
+        drop(handles);
+        let handle = net.spawn();
+
+        let listener0 = handle0.event_listener();
+
+        handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+
+        let client = NoopProvider::default();
+        let pool = testing_pool();
+        let config = NetworkConfigBuilder::new(secret_key)
+            .disable_discovery()
+            .listener_port(0)
+            .build(client);
+        let transactions_manager_config = config.transactions_manager_config.clone();
+        let (network_handle, network, mut transactions, _) = NetworkManager::new(config)
+            .await
+            .unwrap()
+            .into_builder()
+            .transactions(pool.clone(), transactions_manager_config)
+            .split_with_handle();
+        tokio::task::spawn(network);
+
+        // finish the initial sync
+        network_handle.update_sync_state(SyncState::Syncing);
+        network_handle.update_sync_state(SyncState::Idle);
+        assert!(!NetworkInfo::is_initially_syncing(&network_handle));
+
+        // wait for all initiator connections
+        let mut established = listener0.take(2);
+        while let Some(ev) = established.next().await {
+            match ev {
+                NetworkEvent::SessionEstablished {
+                    peer_id,
+                    remote_addr,
+                    client_version,
+                    capabilities,
+                    messages,
+                    status,
+                    version,
+                } => transactions.on_network_event(NetworkEvent::SessionEstablished {
+                    peer_id,
+                    remote_addr,
+                    client_version,
+                    capabilities,
+                    messages,
+                    status,
+                    version,
+                }),
+                NetworkEvent::PeerAdded(_peer_id) => continue,
+                ev => {
+                    error!("unexpected event {ev:?}")
+                }
+            }
+        }
+        handle.terminate().await;
+
+        let peer_id = *handle1.peer_id();
+        let tx = MockTransaction::eip1559();
+        pool.add_transaction(TransactionOrigin::External, tx.clone()).await.unwrap();
+
+        let get_pooled_transactions = |transactions: &mut TransactionsManager<_>| {
+            let (send, receive) = oneshot::channel::<RequestResult<PooledTransactions>>();
+            transactions.on_network_tx_event(NetworkTransactionEvent::GetPooledTransactions {
+                peer_id,
+                request: GetPooledTransactions(vec![tx.get_hash()]),
+                response: send,
+            });
+            receive
+        };
+
+        // a shallow sync doesn't suppress anything
+        network_handle.update_sync_state(SyncState::Syncing);
+        network_handle.update_sync_distance(DEFAULT_DEEP_SYNC_THRESHOLD);
+        transactions.update_deep_sync_state();
+        assert!(!transactions.deep_syncing);
+
+        // fall far behind the chain tip
+        network_handle.update_sync_distance(DEFAULT_DEEP_SYNC_THRESHOLD + 1);
+        transactions.update_deep_sync_state();
+        assert!(transactions.deep_syncing);
+
+        let PooledTransactions(pooled) =
+            get_pooled_transactions(&mut transactions).await.unwrap().unwrap();
+        assert!(pooled.is_empty());
+
+        let announced = MockTransaction::eip1559();
+        transactions.on_new_pooled_transaction_hashes(
+            peer_id,
+            NewPooledTransactionHashes::Eth66(NewPooledTransactionHashes66(vec![
+                announced.get_hash()
+            ])),
+        );
+        let peer = transactions.peers.get(&peer_id).unwrap();
+        assert!(!peer.seen_transactions.contains(&announced.get_hash()));
+
+        let err = pool
+            .add_transaction(TransactionOrigin::External, MockTransaction::eip1559())
+            .await
+            .unwrap_err();
+        assert!(matches!(err.kind, PoolErrorKind::NodeSyncing));
+        pool.add_transaction(TransactionOrigin::Local, MockTransaction::eip1559()).await.unwrap();
+
+        // caught up with the chain tip
+        network_handle.update_sync_state(SyncState::Idle);
+        transactions.update_deep_sync_state();
+        assert!(!transactions.deep_syncing);
+
+        let PooledTransactions(pooled) =
+            get_pooled_transactions(&mut transactions).await.unwrap().unwrap();
+        assert_eq!(pooled.len(), 1);
+
+        transactions.on_new_pooled_transaction_hashes(
+            peer_id,
+            NewPooledTransactionHashes::Eth66(NewPooledTransactionHashes66(vec![
+                announced.get_hash()
+            ])),
+        );
+        let peer = transactions.peers.get(&peer_id).unwrap();
+        assert!(peer.seen_transactions.contains(&announced.get_hash()));
+
+        pool.add_transaction(TransactionOrigin::External, MockTransaction::eip1559())
+            .await
+            .unwrap();
+    }
+
     #[tokio::test]
     async fn test_max_retries_tx_request() {
         reth_tracing::init_test_tracing();
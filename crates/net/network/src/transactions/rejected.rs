@@ -0,0 +1,158 @@
+//! Tracking of peers whose transactions are rejected by the pool.
+//!
+//! A transaction that is rejected by the pool, but isn't [bad](PoolError::is_bad_transaction),
+//! doesn't prove that the peer misbehaves, e.g. its fees may be too low for our pool but not for
+//! the pool of the peer. A peer that keeps sending transactions that never become valid however
+//! wastes our resources, so it's penalized once it exceeds a threshold of rejected transactions
+//! within a time window.
+
+use reth_network_peers::PeerId;
+use reth_primitives::InvalidTransactionError;
+use reth_transaction_pool::error::{InvalidPoolTransactionError, PoolError, PoolErrorKind};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Returns `true` if the pool rejected the transaction for a reason that doesn't depend on the
+/// state of the chain, the pool or the node, so the transaction can never become valid.
+///
+/// Rejections that depend on the state, e.g. a nonce gap, insufficient funds, a fee below the
+/// base fee or a full pool, aren't counted, because the peer may have seen a different state.
+///
+/// Some of these rejections, e.g. an unsupported transaction type, are also
+/// [bad](PoolError::is_bad_transaction), in which case the peer is penalized right away.
+pub(super) const fn is_rejected_import(err: &PoolError) -> bool {
+    matches!(
+        err.kind,
+        PoolErrorKind::InvalidTransaction(
+            InvalidPoolTransactionError::Consensus(
+                InvalidTransactionError::GasTooLow |
+                    InvalidTransactionError::GasTooHigh |
+                    InvalidTransactionError::TipAboveFeeCap |
+                    InvalidTransactionError::TxTypeNotSupported
+            ) | InvalidPoolTransactionError::IntrinsicGasTooLow |
+                InvalidPoolTransactionError::OversizedData(_, _)
+        )
+    )
+}
+
+/// Counts the rejected transactions of each peer within a fixed window.
+#[derive(Debug)]
+pub(super) struct RejectedImports {
+    /// Max number of rejected transactions of a peer within the window.
+    threshold: u32,
+    /// Length of the window.
+    window: Duration,
+    /// The current window of each peer that sent rejected transactions.
+    peers: HashMap<PeerId, RejectedWindow>,
+}
+
+impl RejectedImports {
+    /// Creates a new tracker that allows `threshold` rejected transactions per `window`.
+    pub(super) fn new(threshold: u32, window: Duration) -> Self {
+        Self { threshold, window, peers: HashMap::default() }
+    }
+
+    /// Records a rejected transaction of the peer.
+    ///
+    /// Returns `true` if the peer exceeded the threshold within the current window and should be
+    /// penalized. This starts a new window for the peer.
+    pub(super) fn on_rejected(&mut self, peer_id: PeerId, now: Instant) -> bool {
+        let window = self.peers.entry(peer_id).or_insert(RejectedWindow { start: now, count: 0 });
+        if now.saturating_duration_since(window.start) >= self.window {
+            *window = RejectedWindow { start: now, count: 0 };
+        }
+
+        window.count += 1;
+        if window.count > self.threshold {
+            *window = RejectedWindow { start: now, count: 0 };
+            return true
+        }
+        false
+    }
+
+    /// Stops tracking the peer, e.g. once it disconnected.
+    pub(super) fn remove_peer(&mut self, peer_id: &PeerId) {
+        self.peers.remove(peer_id);
+    }
+}
+
+/// The rejected transactions of a peer within the current window.
+#[derive(Debug)]
+struct RejectedWindow {
+    /// When the window started.
+    start: Instant,
+    /// Number of rejected transactions within the window.
+    count: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::B256;
+
+    #[test]
+    fn penalizes_only_peers_above_threshold() {
+        let mut rejected = RejectedImports::new(5, Duration::from_secs(60));
+        let (spammer, honest) = (PeerId::random(), PeerId::random());
+        let now = Instant::now();
+
+        // the spammer sends three rejected transactions for every one of the honest peer
+        let mut penalized = Vec::new();
+        for i in 0..12 {
+            let now = now + Duration::from_secs(i);
+            if rejected.on_rejected(spammer, now) {
+                penalized.push(spammer);
+            }
+            if i % 3 == 0 && rejected.on_rejected(honest, now) {
+                penalized.push(honest);
+            }
+        }
+
+        // the spammer exceeded the threshold on the 6th and the 12th rejected transaction
+        assert_eq!(penalized, vec![spammer, spammer]);
+    }
+
+    #[test]
+    fn resets_after_window() {
+        let window = Duration::from_secs(60);
+        let mut rejected = RejectedImports::new(2, window);
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        assert!(!rejected.on_rejected(peer_id, now));
+        assert!(!rejected.on_rejected(peer_id, now));
+        // the window expired, so the earlier rejections don't count anymore
+        assert!(!rejected.on_rejected(peer_id, now + window));
+        assert!(!rejected.on_rejected(peer_id, now + window));
+        assert!(rejected.on_rejected(peer_id, now + window));
+
+        rejected.remove_peer(&peer_id);
+        assert!(rejected.peers.is_empty());
+    }
+
+    #[test]
+    fn rejected_import_errors() {
+        let hash = B256::random();
+        for err in [
+            InvalidPoolTransactionError::Consensus(InvalidTransactionError::TipAboveFeeCap),
+            InvalidPoolTransactionError::Consensus(InvalidTransactionError::TxTypeNotSupported),
+            InvalidPoolTransactionError::IntrinsicGasTooLow,
+            InvalidPoolTransactionError::OversizedData(2, 1),
+        ] {
+            assert!(is_rejected_import(&PoolError::new(hash, err)));
+        }
+
+        for err in [
+            InvalidPoolTransactionError::Consensus(InvalidTransactionError::NonceNotConsistent),
+            InvalidPoolTransactionError::Consensus(InvalidTransactionError::FeeCapTooLow),
+            InvalidPoolTransactionError::Underpriced,
+        ] {
+            assert!(!is_rejected_import(&PoolError::new(hash, err)));
+        }
+        assert!(!is_rejected_import(&PoolError::new(hash, PoolErrorKind::DiscardedOnInsert)));
+        assert!(!is_rejected_import(&PoolError::new(hash, PoolErrorKind::AlreadyImported)));
+        assert!(!is_rejected_import(&PoolError::new(hash, PoolErrorKind::NodeSyncing)));
+    }
+}
@@ -2,12 +2,17 @@
 
 use futures::StreamExt;
 use rand::thread_rng;
-use reth_network::{test_utils::Testnet, NetworkEvent, NetworkEvents};
+use reth_network::{
+    test_utils::Testnet, transactions::constants::tx_manager::DEFAULT_REJECTED_IMPORTS_THRESHOLD,
+    NetworkEvent, NetworkEvents,
+};
 use reth_network_api::PeersInfo;
 use reth_primitives::{TransactionSigned, TxLegacy, U256};
 use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
-use reth_transaction_pool::{test_utils::TransactionGenerator, PoolTransaction, TransactionPool};
-use std::sync::Arc;
+use reth_transaction_pool::{
+    test_utils::TransactionGenerator, FullTransactionEvent, PoolTransaction, TransactionPool,
+};
+use std::{sync::Arc, time::Duration};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_tx_gossip() {
@@ -154,3 +159,65 @@ async fn test_sending_invalid_transactions() {
     // ensure txs never made it to the pool
     assert!(tx_listener.try_recv().is_err());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_penalize_peers_sending_rejected_transactions() {
+    reth_tracing::init_test_tracing();
+    let provider = MockEthProvider::default();
+    let net = Testnet::create_with(3, provider.clone()).await;
+    // install request handlers
+    let net = net.with_eth_pool();
+
+    let handle = net.spawn();
+
+    let peer0 = &handle.peers()[0];
+    let peer1 = &handle.peers()[1];
+    let receiver = &handle.peers()[2];
+
+    // connect all the peers
+    handle.connect_peers().await;
+
+    let mut pool_events = receiver.pool().unwrap().all_transactions_event_listener();
+    let reputation = |peer_id| async move {
+        receiver.peer_handle().peer_by_id(peer_id).await.unwrap().reputation()
+    };
+    let reputations_before =
+        (reputation(*peer0.peer_id()).await, reputation(*peer1.peer_id()).await);
+
+    // transactions with a tip above the fee cap can never become valid, but aren't bad either
+    let mut gen = TransactionGenerator::new(thread_rng());
+    let mut rejected_txs = |count: u32| {
+        (0..count)
+            .map(|_| {
+                let tx = gen
+                    .transaction()
+                    .max_fee_per_gas(gen.base_fee)
+                    .max_priority_fee_per_gas(gen.base_fee + 1)
+                    .into_eip1559();
+                Arc::new(tx)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // the first peer sends as many rejected transactions as it is allowed to, the second one
+    // sends one more
+    let threshold = DEFAULT_REJECTED_IMPORTS_THRESHOLD;
+    peer0.network().send_transactions(*receiver.peer_id(), rejected_txs(threshold));
+    let mut rejected = 0;
+    while rejected < threshold {
+        if let FullTransactionEvent::Invalid { .. } = pool_events.next().await.unwrap() {
+            rejected += 1;
+        }
+    }
+    peer1.network().send_transactions(*receiver.peer_id(), rejected_txs(threshold + 1));
+
+    // only the peer above the threshold is penalized
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while reputation(*peer1.peer_id()).await >= reputations_before.1 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("peer above the threshold is penalized");
+    assert_eq!(reputation(*peer0.peer_id()).await, reputations_before.0);
+}
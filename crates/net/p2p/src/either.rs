@@ -6,6 +6,7 @@ use crate::{
     headers::client::{HeadersClient, HeadersRequest},
     priority::Priority,
 };
+use reth_network_peers::PeerId;
 use reth_primitives::B256;
 
 pub use futures::future::Either;
@@ -65,4 +66,20 @@ where
             Self::Right(b) => Either::Right(b.get_headers_with_priority(request, priority)),
         }
     }
+
+    fn get_headers_excluding_peer(
+        &self,
+        request: HeadersRequest,
+        priority: Priority,
+        excluded_peer: PeerId,
+    ) -> Self::Output {
+        match self {
+            Self::Left(a) => {
+                Either::Left(a.get_headers_excluding_peer(request, priority, excluded_peer))
+            }
+            Self::Right(b) => {
+                Either::Right(b.get_headers_excluding_peer(request, priority, excluded_peer))
+            }
+        }
+    }
 }
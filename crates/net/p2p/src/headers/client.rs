@@ -1,6 +1,7 @@
 use crate::{download::DownloadClient, error::PeerRequestResult, priority::Priority};
 use futures::{Future, FutureExt};
 pub use reth_eth_wire_types::BlockHeaders;
+use reth_network_peers::PeerId;
 use reth_primitives::{BlockHashOrNumber, Header, HeadersDirection};
 use std::{
     fmt::Debug,
@@ -43,6 +44,21 @@ pub trait HeadersClient: DownloadClient {
         priority: Priority,
     ) -> Self::Output;
 
+    /// Sends the header request with priority set to a peer other than `excluded_peer` and returns
+    /// the header response received from that peer.
+    ///
+    /// This is used to cross-check a response against a second peer. Clients that can't choose
+    /// the peer send the request like [`HeadersClient::get_headers_with_priority`].
+    fn get_headers_excluding_peer(
+        &self,
+        request: HeadersRequest,
+        priority: Priority,
+        excluded_peer: PeerId,
+    ) -> Self::Output {
+        let _ = excluded_peer;
+        self.get_headers_with_priority(request, priority)
+    }
+
     /// Fetches a single header for the requested number or hash.
     fn get_header(&self, start: BlockHashOrNumber) -> SingleHeaderRequest<Self::Output> {
         self.get_header_with_priority(start, Priority::Normal)
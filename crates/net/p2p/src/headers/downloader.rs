@@ -2,6 +2,7 @@ use super::error::HeadersDownloaderResult;
 use crate::error::{DownloadError, DownloadResult};
 use futures::Stream;
 use reth_consensus::Consensus;
+use reth_network_peers::PeerId;
 use reth_primitives::{BlockHashOrNumber, SealedHeader, B256};
 /// A downloader capable of fetching and yielding block headers.
 ///
@@ -30,6 +31,15 @@ pub trait HeaderDownloader:
 
     /// Sets the headers batch size that the Stream should return.
     fn set_batch_size(&mut self, limit: usize);
+
+    /// Requests headers only from peers other than the given one, until this is reset with
+    /// `None`.
+    ///
+    /// This is used to confirm headers with a second peer. Downloaders that can't choose the
+    /// peer ignore this.
+    fn exclude_peer(&mut self, peer_id: Option<PeerId>) {
+        let _ = peer_id;
+    }
 }
 
 /// Specifies the target to sync for [`HeaderDownloader::update_sync_target`]
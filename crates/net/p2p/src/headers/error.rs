@@ -1,4 +1,5 @@
 use reth_consensus::ConsensusError;
+use reth_network_peers::PeerId;
 use reth_primitives::SealedHeader;
 use thiserror::Error;
 
@@ -19,5 +20,7 @@ pub enum HeadersDownloaderError {
         /// The error that occurred when attempting to attach the header.
         #[source]
         error: Box<ConsensusError>,
+        /// The peer that sent the header, if known.
+        peer_id: Option<PeerId>,
     },
 }
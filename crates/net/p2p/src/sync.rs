@@ -1,6 +1,7 @@
 //! Traits used when interacting with the sync status of the network.
 
-use reth_primitives::Head;
+use reth_primitives::{BlockNumHash, Head};
+use tokio::sync::watch;
 
 /// A type that provides information about whether the node is currently syncing and the network is
 /// currently serving syncing related requests.
@@ -11,6 +12,21 @@ pub trait SyncStateProvider: Send + Sync {
 
     /// Returns `true` if the network is undergoing an initial (pipeline) sync.
     fn is_initially_syncing(&self) -> bool;
+
+    /// Returns the number of blocks the local head is behind the sync target, if the network is
+    /// undergoing sync.
+    fn sync_distance(&self) -> Option<u64>;
+}
+
+/// A type that provides the block the pipeline should sync to if the node is not driven by a
+/// consensus layer.
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait SyncTargetProvider: Send + Sync {
+    /// Returns the current sync target, if one was selected.
+    fn sync_target(&self) -> Option<BlockNumHash>;
+
+    /// Returns a receiver that is notified whenever the sync target changes.
+    fn subscribe_sync_target(&self) -> watch::Receiver<Option<BlockNumHash>>;
 }
 
 /// An updater for updating the [SyncState] and status of the network.
@@ -25,6 +41,9 @@ pub trait NetworkSyncUpdater: std::fmt::Debug + Send + Sync + 'static {
     /// Notifies about a [SyncState] update.
     fn update_sync_state(&self, state: SyncState);
 
+    /// Notifies about the number of blocks the local head is behind the sync target.
+    fn update_sync_distance(&self, distance: u64);
+
     /// Updates the status of the p2p node
     fn update_status(&self, head: Head);
 }
@@ -61,9 +80,13 @@ impl SyncStateProvider for NoopSyncStateUpdater {
     fn is_initially_syncing(&self) -> bool {
         false
     }
+    fn sync_distance(&self) -> Option<u64> {
+        None
+    }
 }
 
 impl NetworkSyncUpdater for NoopSyncStateUpdater {
     fn update_sync_state(&self, _state: SyncState) {}
+    fn update_sync_distance(&self, _distance: u64) {}
     fn update_status(&self, _: Head) {}
 }
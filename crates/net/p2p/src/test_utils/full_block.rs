@@ -173,13 +173,14 @@ impl HeadersClient for TestFullBlockClient {
     ) -> Self::Output {
         let headers = self.headers.lock();
 
-        // Initializes the block hash or number.
-        let mut block: BlockHashOrNumber = match request.start {
+        // Initializes the block hash or number, responds with no headers if the start is unknown.
+        let Some(mut block) = match request.start {
             BlockHashOrNumber::Hash(hash) => headers.get(&hash).cloned(),
             BlockHashOrNumber::Number(num) => headers.values().find(|h| h.number == num).cloned(),
         }
-        .map(|h| h.number.into())
-        .unwrap();
+        .map(|h| BlockHashOrNumber::from(h.number)) else {
+            return futures::future::ready(Ok(WithPeerId::new(PeerId::random(), vec![])))
+        };
 
         // Retrieves headers based on the provided limit and request direction.
         let resp = (0..request.limit)
@@ -49,6 +49,9 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 use alloy_primitives::B512;
+use node_record::EnodeUrl;
+#[cfg(feature = "secp256k1")]
+use reth_ethereum_forks::{EnrForkIdEntry, ForkId};
 use std::str::FromStr;
 
 // Re-export PeerId for ease of use.
@@ -139,6 +142,17 @@ impl AnyNode {
             _ => None,
         }
     }
+
+    /// Returns the fork id of the `eth` entry of an ENR, if the node is an ENR with that entry.
+    #[cfg(feature = "secp256k1")]
+    pub fn eth_fork_id(&self) -> Option<ForkId> {
+        match self {
+            Self::Enr(enr) => {
+                enr.get_decodable::<EnrForkIdEntry>(b"eth")?.ok().map(|entry| entry.fork_id)
+            }
+            _ => None,
+        }
+    }
 }
 
 impl From<NodeRecord> for AnyNode {
@@ -155,24 +169,37 @@ impl From<Enr<secp256k1::SecretKey>> for AnyNode {
 }
 
 impl FromStr for AnyNode {
-    type Err = String;
+    type Err = NodeRecordParseError;
 
+    /// Parses an `enr:` record, an enode URL or a bare `<id>@<host>:<port>`, see
+    /// [`TrustedPeer`]. An enode without address only identifies the peer.
+    ///
+    /// The host must be an IP address.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some(rem) = s.strip_prefix("enode://") {
-            if let Ok(record) = NodeRecord::from_str(s) {
-                return Ok(Self::NodeRecord(record))
-            }
-            // incomplete enode
-            if let Ok(peer_id) = PeerId::from_str(rem) {
-                return Ok(Self::PeerId(peer_id))
+        let s = s.trim();
+        if s.starts_with("enr:") {
+            #[cfg(feature = "secp256k1")]
+            {
+                let enr = node_record::parse_enr(s)?;
+                // reject a malformed `eth` entry, instead of ignoring it later
+                if let Some(Err(err)) = enr.get_decodable::<EnrForkIdEntry>(b"eth") {
+                    return Err(NodeRecordParseError::InvalidEnr(format!(
+                        "invalid `eth` entry: {err}"
+                    )))
+                }
+                return Ok(Self::Enr(enr))
             }
-            return Err(format!("invalid public key: {rem}"))
+            #[cfg(not(feature = "secp256k1"))]
+            return Err(NodeRecordParseError::InvalidEnr(
+                "ENRs require the `secp256k1` feature".to_string(),
+            ))
         }
-        #[cfg(feature = "secp256k1")]
-        if s.starts_with("enr:") {
-            return Enr::from_str(s).map(AnyNode::Enr)
+
+        let EnodeUrl { id, address } = EnodeUrl::parse(s)?;
+        match address {
+            Some(address) => address.into_node_record(id).map(Self::NodeRecord),
+            None => Ok(Self::PeerId(id)),
         }
-        Err("missing 'enr:' prefix for base64-encoded record".to_string())
     }
 }
 
@@ -270,7 +297,43 @@ mod tests {
 
         let url = "enode://";
         let err = url.parse::<AnyNode>().unwrap_err();
-        assert_eq!(err, "invalid public key: ");
+        assert_eq!(err, NodeRecordParseError::IdLength(0));
+    }
+
+    #[test]
+    fn test_any_node_parse() {
+        let id = "6f8a80d14311c39f35f516fa664deaaaa13e85b2f7493f37f6144d86991ec012937307647bd3b9a82abe2974e1407241d54947bbb39763a4cac9f77166ad92a0";
+        let peer_id: PeerId = id.parse().unwrap();
+        let record = NodeRecord::new(([10, 3, 58, 6], 30303).into(), peer_id);
+
+        // input, parsed node, canonical form
+        let cases = [
+            (format!("enode://{id}"), AnyNode::PeerId(peer_id), None),
+            (format!("0x{id}"), AnyNode::PeerId(peer_id), Some(format!("enode://{id}"))),
+            (format!("enode://{id}@10.3.58.6:30303"), AnyNode::NodeRecord(record), None),
+            (
+                format!("{id}@10.3.58.6:30303?discport=30303"),
+                AnyNode::NodeRecord(record),
+                Some(format!("enode://{id}@10.3.58.6:30303")),
+            ),
+        ];
+        for (input, expected, canonical) in cases {
+            let node: AnyNode = input.parse().unwrap_or_else(|e| panic!("{input}: {e}"));
+            assert_eq!(node, expected, "{input}");
+            assert_eq!(node.to_string(), canonical.unwrap_or(input), "{node}");
+        }
+
+        let errors = [
+            (
+                format!("enode://{id}@my-domain:30303"),
+                NodeRecordParseError::UnresolvedHost("my-domain".into()),
+            ),
+            (format!("enode://{}", &id[..64]), NodeRecordParseError::IdLength(64)),
+            (format!("udp://{id}"), NodeRecordParseError::Scheme("udp".into())),
+        ];
+        for (input, expected) in errors {
+            assert_eq!(input.parse::<AnyNode>().unwrap_err(), expected, "{input}");
+        }
     }
 
     // <https://eips.ethereum.org/EIPS/eip-778>
@@ -286,6 +349,45 @@ mod tests {
                 .unwrap()
         );
         assert_eq!(node.to_string(), url);
+        assert_eq!(node.node_record(), None);
+        assert_eq!(node.eth_fork_id(), None);
+
+        // a changed content byte invalidates the signature
+        let mut tampered = url.to_string();
+        tampered.replace_range(tampered.len() - 2..tampered.len() - 1, "m");
+        assert!(matches!(tampered.parse::<AnyNode>(), Err(NodeRecordParseError::InvalidEnr(_))));
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn test_enr_eth_entry() {
+        let sk = secp256k1::SecretKey::new(&mut rand::thread_rng());
+        let fork_id = ForkId {
+            hash: reth_ethereum_forks::ForkHash([0xfc, 0x64, 0xec, 0x04]),
+            next: 1_150_000,
+        };
+        let enr = Enr::builder()
+            .ip4([10, 3, 58, 6].into())
+            .tcp4(30303)
+            .udp4(30303)
+            .add_value(b"eth", &EnrForkIdEntry::from(fork_id))
+            .build(&sk)
+            .unwrap();
+        let node: AnyNode = enr.to_base64().parse().unwrap();
+        assert_eq!(node.eth_fork_id(), Some(fork_id));
+        assert_eq!(
+            node.node_record(),
+            Some(NodeRecord::from_secret_key(([10, 3, 58, 6], 30303).into(), &sk))
+        );
+        assert_eq!(node.to_string(), enr.to_base64());
+
+        // a malformed `eth` entry is rejected
+        let enr = Enr::builder().add_value(b"eth", &1u64).build(&sk).unwrap();
+        let err = enr.to_base64().parse::<AnyNode>().unwrap_err();
+        assert!(
+            matches!(&err, NodeRecordParseError::InvalidEnr(reason) if reason.contains("`eth`")),
+            "{err}"
+        );
     }
 
     #[test]
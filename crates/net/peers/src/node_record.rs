@@ -3,14 +3,14 @@
 use std::{
     fmt,
     fmt::Write,
-    net::{IpAddr, Ipv4Addr, SocketAddr},
-    num::ParseIntError,
+    net::{IpAddr, Ipv6Addr, SocketAddr},
     str::FromStr,
 };
 
 use crate::PeerId;
 use alloy_rlp::{RlpDecodable, RlpEncodable};
 use serde_with::{DeserializeFromStr, SerializeDisplay};
+use url::Host;
 
 #[cfg(feature = "secp256k1")]
 use enr::Enr;
@@ -50,8 +50,8 @@ impl NodeRecord {
         Self::new(addr, id)
     }
 
-    /// Converts the `address` into an [`Ipv4Addr`] if the `address` is a mapped
-    /// [`Ipv6Addr`](std::net::Ipv6Addr).
+    /// Converts the `address` into an [`Ipv4Addr`](std::net::Ipv4Addr) if the `address` is a
+    /// mapped [`Ipv6Addr`].
     ///
     /// Returns `true` if the address was converted.
     ///
@@ -119,56 +119,63 @@ impl fmt::Display for NodeRecord {
     }
 }
 
-/// Possible error types when parsing a [`NodeRecord`]
-#[derive(Debug, thiserror::Error)]
+/// Possible error types when parsing a [`NodeRecord`], [`TrustedPeer`](crate::TrustedPeer) or
+/// [`AnyNode`](crate::AnyNode).
+///
+/// Each variant names the component of the input that failed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 pub enum NodeRecordParseError {
-    /// Invalid url
-    #[error("Failed to parse url: {0}")]
-    InvalidUrl(String),
+    /// The scheme is neither `enode://` nor `enr:`
+    #[error("unsupported scheme `{0}`, expected `enode://` or `enr:`")]
+    Scheme(String),
+    /// The node id doesn't have 64 bytes
+    #[error("node id must be 128 hex characters, got {0}")]
+    IdLength(usize),
     /// Invalid id
-    #[error("Failed to parse id")]
+    #[error("invalid node id: {0}")]
     InvalidId(String),
+    /// There's no `@<host>:<port>` after the node id
+    #[error("missing `@<host>:<port>` after the node id")]
+    MissingAddress,
+    /// Invalid host
+    #[error("invalid host `{host}`: {reason}")]
+    InvalidHost {
+        /// The host that failed to parse.
+        host: String,
+        /// Why the host is invalid.
+        reason: String,
+    },
+    /// The host is a hostname, but the record requires an IP address
+    #[error("hostname `{0}` is not supported here, use an IP address")]
+    UnresolvedHost(String),
+    /// There's no port after the host
+    #[error("missing port after the host")]
+    MissingPort,
+    /// Invalid port
+    #[error("invalid port `{0}`")]
+    InvalidPort(String),
     /// Invalid discport
-    #[error("Failed to discport query: {0}")]
-    Discport(ParseIntError),
+    #[error("invalid discport `{0}`")]
+    Discport(String),
+    /// Invalid ENR, e.g. bad base64 or signature
+    #[error("invalid ENR: {0}")]
+    InvalidEnr(String),
+    /// The ENR doesn't have a field required by the record
+    #[error("ENR has no {0}")]
+    EnrMissing(&'static str),
 }
 
 impl FromStr for NodeRecord {
     type Err = NodeRecordParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        use url::{Host, Url};
-
-        let url = Url::parse(s).map_err(|e| NodeRecordParseError::InvalidUrl(e.to_string()))?;
-
-        let address = match url.host() {
-            Some(Host::Ipv4(ip)) => IpAddr::V4(ip),
-            Some(Host::Ipv6(ip)) => IpAddr::V6(ip),
-            Some(Host::Domain(ip)) => IpAddr::V4(
-                Ipv4Addr::from_str(ip)
-                    .map_err(|e| NodeRecordParseError::InvalidUrl(e.to_string()))?,
-            ),
-            _ => return Err(NodeRecordParseError::InvalidUrl(format!("invalid host: {url:?}"))),
-        };
-        let port = url
-            .port()
-            .ok_or_else(|| NodeRecordParseError::InvalidUrl("no port specified".to_string()))?;
-
-        let udp_port = if let Some(discovery_port) = url
-            .query_pairs()
-            .find_map(|(maybe_disc, port)| (maybe_disc.as_ref() == "discport").then_some(port))
-        {
-            discovery_port.parse::<u16>().map_err(NodeRecordParseError::Discport)?
-        } else {
-            port
-        };
-
-        let id = url
-            .username()
-            .parse::<PeerId>()
-            .map_err(|e| NodeRecordParseError::InvalidId(e.to_string()))?;
+        #[cfg(feature = "secp256k1")]
+        if s.trim_start().starts_with("enr:") {
+            return Self::try_from(&parse_enr(s)?)
+        }
 
-        Ok(Self { address, id, tcp_port: port, udp_port })
+        let enode = EnodeUrl::parse(s)?;
+        enode.address.ok_or(NodeRecordParseError::MissingAddress)?.into_node_record(enode.id)
     }
 }
 
@@ -179,15 +186,15 @@ impl TryFrom<&Enr<secp256k1::SecretKey>> for NodeRecord {
     fn try_from(enr: &Enr<secp256k1::SecretKey>) -> Result<Self, Self::Error> {
         let Some(address) = enr.ip4().map(IpAddr::from).or_else(|| enr.ip6().map(IpAddr::from))
         else {
-            return Err(NodeRecordParseError::InvalidUrl("ip missing".to_string()))
+            return Err(NodeRecordParseError::EnrMissing("ip"))
         };
 
         let Some(udp_port) = enr.udp4().or_else(|| enr.udp6()) else {
-            return Err(NodeRecordParseError::InvalidUrl("udp port missing".to_string()))
+            return Err(NodeRecordParseError::EnrMissing("udp port"))
         };
 
         let Some(tcp_port) = enr.tcp4().or_else(|| enr.tcp6()) else {
-            return Err(NodeRecordParseError::InvalidUrl("tcp port missing".to_string()))
+            return Err(NodeRecordParseError::EnrMissing("tcp port"))
         };
 
         let id = crate::pk2id(&enr.public_key());
@@ -196,11 +203,137 @@ impl TryFrom<&Enr<secp256k1::SecretKey>> for NodeRecord {
     }
 }
 
+/// Parses a base64 `enr:` record, which verifies its signature.
+#[cfg(feature = "secp256k1")]
+pub(crate) fn parse_enr(s: &str) -> Result<Enr<secp256k1::SecretKey>, NodeRecordParseError> {
+    s.trim().parse().map_err(NodeRecordParseError::InvalidEnr)
+}
+
+/// The components of an enode URL, `enode://<id>@<host>:<port>?discport=<udp port>`.
+///
+/// The `enode://` scheme is optional, the id may be `0x` prefixed, IPv6 hosts must be enclosed in
+/// brackets and the host may be a hostname. The address is optional, `enode://<id>` only
+/// identifies the peer.
+#[derive(Debug)]
+pub(crate) struct EnodeUrl {
+    /// The node id.
+    pub(crate) id: PeerId,
+    /// The address after the `@`, if any.
+    pub(crate) address: Option<EnodeAddress>,
+}
+
+/// The address of an [`EnodeUrl`].
+#[derive(Debug)]
+pub(crate) struct EnodeAddress {
+    /// The IP address or hostname.
+    pub(crate) host: Host,
+    /// The TCP port.
+    pub(crate) tcp_port: u16,
+    /// The `discport` query parameter, or the TCP port.
+    pub(crate) udp_port: u16,
+}
+
+impl EnodeUrl {
+    /// Parses the enode URL, reporting the component that failed.
+    pub(crate) fn parse(s: &str) -> Result<Self, NodeRecordParseError> {
+        let s = s.trim();
+        let rest = match s.split_once("://") {
+            Some((scheme, rest)) if scheme.eq_ignore_ascii_case("enode") => rest,
+            Some((scheme, _)) => return Err(NodeRecordParseError::Scheme(scheme.to_string())),
+            None => s,
+        };
+
+        let Some((id, address)) = rest.split_once('@') else {
+            return Ok(Self { id: parse_id(rest)?, address: None })
+        };
+        let id = parse_id(id)?;
+
+        let (address, query) = address.split_once('?').unwrap_or((address, ""));
+        let (host, port) = split_host_port(address.trim_end_matches('/'))?;
+        let tcp_port = match port {
+            Some(port) if !port.is_empty() => parse_port(port)?,
+            _ => return Err(NodeRecordParseError::MissingPort),
+        };
+
+        let mut udp_port = tcp_port;
+        for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+            if key == "discport" {
+                udp_port =
+                    value.parse().map_err(|_| NodeRecordParseError::Discport(value.to_string()))?;
+            }
+        }
+
+        Ok(Self { id, address: Some(EnodeAddress { host, tcp_port, udp_port }) })
+    }
+}
+
+impl EnodeAddress {
+    /// Returns the [`NodeRecord`] of the node, if the host is an IP address.
+    pub(crate) fn into_node_record(self, id: PeerId) -> Result<NodeRecord, NodeRecordParseError> {
+        let address = match self.host {
+            Host::Ipv4(ip) => IpAddr::V4(ip),
+            Host::Ipv6(ip) => IpAddr::V6(ip),
+            Host::Domain(domain) => return Err(NodeRecordParseError::UnresolvedHost(domain)),
+        };
+        Ok(NodeRecord { address, tcp_port: self.tcp_port, udp_port: self.udp_port, id }
+            .into_ipv4_mapped())
+    }
+}
+
+/// Parses the hex node id, with an optional `0x` prefix.
+fn parse_id(id: &str) -> Result<PeerId, NodeRecordParseError> {
+    let hex = id.strip_prefix("0x").unwrap_or(id);
+    if hex.len() != 128 {
+        return Err(NodeRecordParseError::IdLength(hex.len()))
+    }
+    hex.parse::<PeerId>().map_err(|e| NodeRecordParseError::InvalidId(e.to_string()))
+}
+
+/// Splits `<host>:<port>` into the parsed host and the port, if any.
+fn split_host_port(address: &str) -> Result<(Host, Option<&str>), NodeRecordParseError> {
+    let invalid_host = |host: &str, reason: &str| NodeRecordParseError::InvalidHost {
+        host: host.to_string(),
+        reason: reason.to_string(),
+    };
+
+    if let Some(rest) = address.strip_prefix('[') {
+        let (ip, port) =
+            rest.split_once(']').ok_or_else(|| invalid_host(address, "missing closing bracket"))?;
+        let ip = ip.parse::<Ipv6Addr>().map_err(|e| invalid_host(ip, &e.to_string()))?;
+        let port = match port {
+            "" => None,
+            port => Some(
+                port.strip_prefix(':')
+                    .ok_or_else(|| NodeRecordParseError::InvalidPort(port.to_string()))?,
+            ),
+        };
+        return Ok((Host::Ipv6(ip), port))
+    }
+
+    let (host, port) = match address.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (address, None),
+    };
+    if host.contains(':') {
+        return Err(invalid_host(address, "IPv6 addresses must be enclosed in brackets"))
+    }
+    if host.is_empty() {
+        return Err(invalid_host(address, "empty host"))
+    }
+    let host = Host::parse(host).map_err(|e| invalid_host(host, &e.to_string()))?;
+    Ok((host, port))
+}
+
+/// Parses a port number.
+fn parse_port(port: &str) -> Result<u16, NodeRecordParseError> {
+    port.parse().map_err(|_| NodeRecordParseError::InvalidPort(port.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use alloy_rlp::Decodable;
     use rand::{thread_rng, Rng, RngCore};
-    use std::net::Ipv6Addr;
+    use std::net::{Ipv4Addr, Ipv6Addr};
 
     use super::*;
 
@@ -286,6 +419,141 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_parse_formats() {
+        let id = "6f8a80d14311c39f35f516fa664deaaaa13e85b2f7493f37f6144d86991ec012937307647bd3b9a82abe2974e1407241d54947bbb39763a4cac9f77166ad92a0";
+        let record = |address: IpAddr, tcp_port, udp_port| NodeRecord {
+            address,
+            tcp_port,
+            udp_port,
+            id: id.parse().unwrap(),
+        };
+        let v4 = IpAddr::V4([10, 3, 58, 6].into());
+        let v6 = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+
+        // input, parsed record, canonical form
+        let cases = [
+            (format!("enode://{id}@10.3.58.6:30303"), record(v4, 30303, 30303), None),
+            (
+                format!("enode://{id}@10.3.58.6:30303?discport=30301"),
+                record(v4, 30303, 30301),
+                None,
+            ),
+            (
+                format!("  enode://{id}@10.3.58.6:30303/  "),
+                record(v4, 30303, 30303),
+                Some(format!("enode://{id}@10.3.58.6:30303")),
+            ),
+            (
+                format!("{id}@10.3.58.6:30303"),
+                record(v4, 30303, 30303),
+                Some(format!("enode://{id}@10.3.58.6:30303")),
+            ),
+            (
+                format!("ENODE://0x{id}@10.3.58.6:30303?discport=30303"),
+                record(v4, 30303, 30303),
+                Some(format!("enode://{id}@10.3.58.6:30303")),
+            ),
+            (format!("enode://{id}@[2001:db8::1]:30303"), record(v6, 30303, 30303), None),
+            (
+                format!("enode://{id}@[2001:db8::1]:30303?foo=bar&discport=30301"),
+                record(v6, 30303, 30301),
+                Some(format!("enode://{id}@[2001:db8::1]:30303?discport=30301")),
+            ),
+            (
+                format!("enode://{id}@[::ffff:10.3.58.6]:30303"),
+                record(v4, 30303, 30303),
+                Some(format!("enode://{id}@10.3.58.6:30303")),
+            ),
+        ];
+
+        for (input, expected, canonical) in cases {
+            let record: NodeRecord = input.parse().unwrap_or_else(|e| panic!("{input}: {e}"));
+            assert_eq!(record, expected, "{input}");
+            assert_eq!(record.to_string(), canonical.unwrap_or(input), "{record}");
+            assert_eq!(record.to_string().parse::<NodeRecord>().unwrap(), record);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let id = "6f8a80d14311c39f35f516fa664deaaaa13e85b2f7493f37f6144d86991ec012937307647bd3b9a82abe2974e1407241d54947bbb39763a4cac9f77166ad92a0";
+        let invalid_host = |host: &str, reason: &str| NodeRecordParseError::InvalidHost {
+            host: host.to_string(),
+            reason: reason.to_string(),
+        };
+        let bad_id = format!("{}z", &id[1..]);
+
+        let cases = [
+            (format!("http://{id}@10.3.58.6:30303"), NodeRecordParseError::Scheme("http".into())),
+            (format!("enode://{}@10.3.58.6:30303", &id[2..]), NodeRecordParseError::IdLength(126)),
+            (
+                format!("enode://{bad_id}@10.3.58.6:30303"),
+                NodeRecordParseError::InvalidId(bad_id.parse::<PeerId>().unwrap_err().to_string()),
+            ),
+            (format!("enode://{id}"), NodeRecordParseError::MissingAddress),
+            (format!("enode://{id}@10.3.58.6"), NodeRecordParseError::MissingPort),
+            (format!("enode://{id}@10.3.58.6:"), NodeRecordParseError::MissingPort),
+            (format!("enode://{id}@[2001:db8::1]"), NodeRecordParseError::MissingPort),
+            (
+                format!("enode://{id}@10.3.58.6:70000"),
+                NodeRecordParseError::InvalidPort("70000".into()),
+            ),
+            (
+                format!("enode://{id}@[2001:db8::1]30303"),
+                NodeRecordParseError::InvalidPort("30303".into()),
+            ),
+            (
+                format!("enode://{id}@10.3.58.6:30303?discport=x"),
+                NodeRecordParseError::Discport("x".into()),
+            ),
+            (
+                format!("enode://{id}@2001:db8::1:30303"),
+                invalid_host("2001:db8::1:30303", "IPv6 addresses must be enclosed in brackets"),
+            ),
+            (
+                format!("enode://{id}@[2001:db8::1:30303"),
+                invalid_host("[2001:db8::1:30303", "missing closing bracket"),
+            ),
+            (
+                format!("enode://{id}@[2001:db8::x]:30303"),
+                invalid_host("2001:db8::x", "invalid IPv6 address syntax"),
+            ),
+            (format!("enode://{id}@:30303"), invalid_host(":30303", "empty host")),
+            (
+                format!("enode://{id}@my-domain:30303"),
+                NodeRecordParseError::UnresolvedHost("my-domain".into()),
+            ),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(input.parse::<NodeRecord>().unwrap_err(), expected, "{input}");
+        }
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn test_parse_enr() {
+        let sk = secp256k1::SecretKey::new(&mut thread_rng());
+        let enr =
+            Enr::builder().ip4([10, 3, 58, 6].into()).tcp4(30303).udp4(30301).build(&sk).unwrap();
+        let record: NodeRecord = enr.to_base64().parse().unwrap();
+        assert_eq!(
+            record,
+            NodeRecord {
+                udp_port: 30301,
+                ..NodeRecord::from_secret_key((Ipv4Addr::new(10, 3, 58, 6), 30303).into(), &sk)
+            }
+        );
+
+        // <https://eips.ethereum.org/EIPS/eip-778>, without tcp port
+        let enr = "enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8";
+        assert_eq!(
+            enr.parse::<NodeRecord>().unwrap_err(),
+            NodeRecordParseError::EnrMissing("tcp port")
+        );
+    }
+
     #[test]
     fn test_node_display() {
         let url = "enode://6f8a80d14311c39f35f516fa664deaaaa13e85b2f7493f37f6144d86991ec012937307647bd3b9a82abe2974e1407241d54947bbb39763a4cac9f77166ad92a0@10.3.58.6:30303";
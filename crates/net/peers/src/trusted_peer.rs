@@ -1,12 +1,14 @@
 //! `NodeRecord` type that uses a domain instead of an IP.
 
-use crate::{NodeRecord, PeerId};
+use crate::{
+    node_record::{EnodeAddress, EnodeUrl},
+    NodeRecord, NodeRecordParseError, PeerId,
+};
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 use std::{
     fmt::{self, Write},
     io::Error,
     net::IpAddr,
-    num::ParseIntError,
     str::FromStr,
 };
 use url::Host;
@@ -19,6 +21,10 @@ use url::Host;
 /// discoverable reliably using DNS.
 ///
 /// This should NOT be used for any use case other than in trusted peer lists.
+///
+/// It parses from an enode URL, `enode://<id>@<host>:<port>?discport=<udp port>`, where the scheme
+/// and the `discport` are optional and IPv6 hosts are enclosed in brackets, or from a signed
+/// `enr:` record with an IP address and ports.
 #[derive(Clone, Debug, Eq, PartialEq, Hash, SerializeDisplay, DeserializeFromStr)]
 pub struct TrustedPeer {
     /// The host of a node.
@@ -66,17 +72,32 @@ impl TrustedPeer {
         };
 
         // Resolve the domain to an IP address
-        let mut ips = tokio::net::lookup_host(format!("{domain}:0")).await?;
-        let ip = ips
-            .next()
-            .ok_or_else(|| Error::new(std::io::ErrorKind::AddrNotAvailable, "No IP found"))?;
-        Ok(NodeRecord {
-            address: ip.ip(),
-            id: self.id,
-            tcp_port: self.tcp_port,
-            udp_port: self.udp_port,
-        })
+        let ips = tokio::net::lookup_host(format!("{domain}:0"))
+            .await
+            .map_err(|e| Error::new(e.kind(), format!("failed to resolve host `{domain}`: {e}")))?;
+        let address = select_address(ips.map(|addr| addr.ip())).ok_or_else(|| {
+            Error::new(
+                std::io::ErrorKind::AddrNotAvailable,
+                format!("host `{domain}` resolved to no IP address"),
+            )
+        })?;
+        Ok(NodeRecord { address, id: self.id, tcp_port: self.tcp_port, udp_port: self.udp_port })
+    }
+}
+
+/// Selects the address to dial out of the addresses a hostname resolved to.
+///
+/// The order of the resolved addresses depends on the resolver, so the first IPv4 address is
+/// preferred over IPv6 addresses, which are not reachable on every host.
+fn select_address(addresses: impl IntoIterator<Item = IpAddr>) -> Option<IpAddr> {
+    let mut first = None;
+    for address in addresses {
+        if address.is_ipv4() {
+            return Some(address)
+        }
+        first.get_or_insert(address);
     }
+    first
 }
 
 impl fmt::Display for TrustedPeer {
@@ -96,56 +117,19 @@ impl fmt::Display for TrustedPeer {
     }
 }
 
-/// Possible error types when parsing a [`NodeRecord`]
-#[derive(Debug, thiserror::Error)]
-pub enum NodeRecordParseError {
-    /// Invalid url
-    #[error("Failed to parse url: {0}")]
-    InvalidUrl(String),
-    /// Invalid id
-    #[error("Failed to parse id")]
-    InvalidId(String),
-    /// Invalid discport
-    #[error("Failed to discport query: {0}")]
-    Discport(ParseIntError),
-}
-
 impl FromStr for TrustedPeer {
     type Err = NodeRecordParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        use url::Url;
-
-        // Parse the URL with enode prefix replaced with http.
-        // The enode prefix causes the parser to use parse_opaque() on
-        // the host str which only handles domains and ipv6, not ipv4.
-        let url = Url::parse(s.replace("enode://", "http://").as_str())
-            .map_err(|e| NodeRecordParseError::InvalidUrl(e.to_string()))?;
-
-        let host = url
-            .host()
-            .ok_or_else(|| NodeRecordParseError::InvalidUrl("no host specified".to_string()))?
-            .to_owned();
-
-        let port = url
-            .port()
-            .ok_or_else(|| NodeRecordParseError::InvalidUrl("no port specified".to_string()))?;
-
-        let udp_port = if let Some(discovery_port) = url
-            .query_pairs()
-            .find_map(|(maybe_disc, port)| (maybe_disc.as_ref() == "discport").then_some(port))
-        {
-            discovery_port.parse::<u16>().map_err(NodeRecordParseError::Discport)?
-        } else {
-            port
-        };
-
-        let id = url
-            .username()
-            .parse::<PeerId>()
-            .map_err(|e| NodeRecordParseError::InvalidId(e.to_string()))?;
+        #[cfg(feature = "secp256k1")]
+        if s.trim_start().starts_with("enr:") {
+            return NodeRecord::try_from(&crate::node_record::parse_enr(s)?).map(Into::into)
+        }
 
-        Ok(Self { host, id, tcp_port: port, udp_port })
+        let EnodeUrl { id, address } = EnodeUrl::parse(s)?;
+        let EnodeAddress { host, tcp_port, udp_port } =
+            address.ok_or(NodeRecordParseError::MissingAddress)?;
+        Ok(Self { host, id, tcp_port, udp_port })
     }
 }
 
@@ -273,6 +257,82 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_hosts() {
+        let id = "6f8a80d14311c39f35f516fa664deaaaa13e85b2f7493f37f6144d86991ec012937307647bd3b9a82abe2974e1407241d54947bbb39763a4cac9f77166ad92a0";
+        let peer = |host: Host, tcp_port, udp_port| TrustedPeer {
+            host,
+            tcp_port,
+            udp_port,
+            id: id.parse().unwrap(),
+        };
+
+        // input, parsed peer, canonical form
+        let cases = [
+            (
+                format!("enode://{id}@my-domain:30303?discport=30301"),
+                peer(Host::Domain("my-domain".into()), 30303, 30301),
+                None,
+            ),
+            (
+                format!("{id}@Node-1.Example.com:30303"),
+                peer(Host::Domain("node-1.example.com".into()), 30303, 30303),
+                Some(format!("enode://{id}@node-1.example.com:30303")),
+            ),
+            (
+                format!("enode://{id}@[2001:db8::1]:30303"),
+                peer(Host::Ipv6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)), 30303, 30303),
+                None,
+            ),
+            (
+                format!("enode://0x{id}@10.3.58.6:30303"),
+                peer(Host::Ipv4([10, 3, 58, 6].into()), 30303, 30303),
+                Some(format!("enode://{id}@10.3.58.6:30303")),
+            ),
+        ];
+
+        for (input, expected, canonical) in cases {
+            let peer: TrustedPeer = input.parse().unwrap_or_else(|e| panic!("{input}: {e}"));
+            assert_eq!(peer, expected, "{input}");
+            assert_eq!(peer.to_string(), canonical.unwrap_or(input), "{peer}");
+            assert_eq!(peer.to_string().parse::<TrustedPeer>().unwrap(), peer);
+        }
+
+        let errors = [
+            (format!("enode://{id}"), NodeRecordParseError::MissingAddress),
+            (format!("enode://{id}@my-domain"), NodeRecordParseError::MissingPort),
+        ];
+        for (input, expected) in errors {
+            assert_eq!(input.parse::<TrustedPeer>().unwrap_err(), expected, "{input}");
+        }
+        let err = format!("enode://{id}@my<domain:30303").parse::<TrustedPeer>().unwrap_err();
+        assert!(
+            matches!(&err, NodeRecordParseError::InvalidHost { host, .. } if host == "my<domain"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_select_address() {
+        let v4 = IpAddr::from([10, 3, 58, 6]);
+        let other_v4 = IpAddr::from([10, 3, 58, 7]);
+        let v6 = IpAddr::from(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+        let other_v6 = IpAddr::from(Ipv6Addr::LOCALHOST);
+
+        // resolved addresses, selected address
+        let cases = [
+            (vec![], None),
+            (vec![v4], Some(v4)),
+            (vec![v6], Some(v6)),
+            (vec![v4, other_v4], Some(v4)),
+            (vec![v6, other_v6, v4, other_v4], Some(v4)),
+            (vec![v6, other_v6], Some(v6)),
+        ];
+        for (addresses, expected) in cases {
+            assert_eq!(select_address(addresses.clone()), expected, "{addresses:?}");
+        }
+    }
+
     #[tokio::test]
     async fn test_resolve_dns_node_record() {
         // Set up tests
@@ -19,14 +19,28 @@ pub struct DatabaseArgs {
     /// NFS volume.
     #[arg(long = "db.exclusive")]
     pub exclusive: Option<bool>,
+    /// Enable OS read-ahead for the whole database. Speeds up linear scans on cold caches, but
+    /// slows down random access. Stages walking large ranges read ahead regardless.
+    #[arg(long = "db.read-ahead")]
+    pub read_ahead: Option<bool>,
+    /// Count the database operations of every transaction per table, and log the counts of
+    /// transactions with at least this many operations at debug level.
+    #[cfg(feature = "operation-counters")]
+    #[arg(long = "db.operation-counters", value_name = "LOG_THRESHOLD")]
+    pub operation_counters: Option<u64>,
 }
 
 impl DatabaseArgs {
     /// Returns default database arguments with configured log level and client version.
+    #[cfg_attr(not(feature = "operation-counters"), allow(clippy::let_and_return))]
     pub fn database_args(&self) -> reth_db::mdbx::DatabaseArguments {
-        reth_db::mdbx::DatabaseArguments::new(default_client_version())
+        let args = reth_db::mdbx::DatabaseArguments::new(default_client_version())
             .with_log_level(self.log_level)
             .with_exclusive(self.exclusive)
+            .with_read_ahead(self.read_ahead);
+        #[cfg(feature = "operation-counters")]
+        let args = args.with_operation_counters(self.operation_counters);
+        args
     }
 }
 
@@ -132,6 +146,26 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_command_parser_with_read_ahead() {
+        let cmd =
+            CommandParser::<DatabaseArgs>::try_parse_from(["reth", "--db.read-ahead", "true"])
+                .unwrap();
+        assert_eq!(cmd.args.read_ahead, Some(true));
+    }
+
+    #[cfg(feature = "operation-counters")]
+    #[test]
+    fn test_command_parser_with_operation_counters() {
+        let cmd = CommandParser::<DatabaseArgs>::try_parse_from([
+            "reth",
+            "--db.operation-counters",
+            "10000",
+        ])
+        .unwrap();
+        assert_eq!(cmd.args.operation_counters, Some(10_000));
+    }
+
     #[test]
     fn test_command_parser_without_log_level() {
         let cmd = CommandParser::<DatabaseArgs>::try_parse_from(["reth"]).unwrap();
@@ -1,8 +1,11 @@
 //! clap [Args](clap::Args) for debugging purposes
 
+use crate::args::utils::parse_duration_from_secs;
 use clap::Args;
+use humantime::parse_duration;
+use reth_consensus::CheckSeverity;
 use reth_primitives::B256;
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 /// Parameters for debugging purposes
 #[derive(Debug, Clone, Args, PartialEq, Eq, Default)]
@@ -55,6 +58,91 @@ pub struct DebugArgs {
     /// will be written to specified location.
     #[arg(long = "debug.engine-api-store", help_heading = "Debug", value_name = "PATH")]
     pub engine_api_store: Option<PathBuf>,
+
+    /// Overrides how pre-merge headers with a timestamp too far in the future are treated.
+    ///
+    /// Useful for devnets with skewed clocks. One of `enforce`, `warn` or `skip`.
+    #[arg(long = "debug.future-timestamp-check", help_heading = "Debug", value_name = "SEVERITY")]
+    pub future_timestamp_check: Option<CheckSeverity>,
+
+    /// Overrides the number of seconds pre-merge headers from the network may be ahead of the
+    /// local clock, 15 by default.
+    ///
+    /// Payloads of the consensus layer may never be ahead of the local clock.
+    #[arg(
+        long = "debug.future-timestamp-tolerance",
+        help_heading = "Debug",
+        value_name = "SECONDS"
+    )]
+    pub future_timestamp_tolerance: Option<u64>,
+
+    /// Backs up the sidechain and buffered blocks of the blockchain tree on shutdown and
+    /// re-inserts them on startup, so a restart during tip sync doesn't lose them.
+    #[arg(long = "debug.tree-blocks-backup", help_heading = "Debug")]
+    pub tree_blocks_backup: bool,
+
+    /// Backed up blockchain tree blocks older than this are discarded on startup.
+    #[arg(
+        long = "debug.tree-blocks-backup-max-age",
+        help_heading = "Debug",
+        value_parser = parse_duration_from_secs,
+        value_name = "SECONDS",
+        requires = "tree_blocks_backup"
+    )]
+    pub tree_blocks_backup_max_age: Option<Duration>,
+
+    /// Syncs to the best chain head reported by peers instead of waiting for a consensus client.
+    ///
+    /// A head is only used once enough peers agree on it and its header was fetched and
+    /// validated.
+    #[arg(
+        long = "debug.peer-sync-target",
+        help_heading = "Debug",
+        conflicts_with_all = ["tip", "etherscan", "rpc_consensus_ws"]
+    )]
+    pub peer_sync_target: bool,
+
+    /// The number of distinct peers that have to report a chain head before it's used as the
+    /// sync target.
+    #[arg(
+        long = "debug.peer-sync-target-min-peers",
+        help_heading = "Debug",
+        value_name = "PEERS",
+        requires = "peer_sync_target"
+    )]
+    pub peer_sync_target_min_peers: Option<usize>,
+
+    /// Cancels the execution of a payload in the blockchain tree once it takes longer than this.
+    ///
+    /// The execution is aborted between transactions and the payload is reported as syncing
+    /// instead of invalid, so the consensus client can retry it.
+    ///
+    /// Parses strings using [`humantime::parse_duration`]
+    /// --debug.tree-execution-soft-limit 2s
+    #[arg(
+        long = "debug.tree-execution-soft-limit",
+        help_heading = "Debug",
+        value_parser = parse_duration,
+        value_name = "DURATION",
+        verbatim_doc_comment
+    )]
+    pub tree_execution_soft_limit: Option<Duration>,
+
+    /// The number of threads the blockchain tree computes the storage roots of the state root
+    /// with.
+    ///
+    /// A dedicated thread pool of this size is created once at startup, `1` computes all storage
+    /// roots sequentially. By default, the global thread pool is used.
+    #[arg(long = "debug.state-root-threads", help_heading = "Debug", value_name = "THREADS")]
+    pub state_root_threads: Option<usize>,
+
+    /// The number of blocks the node can be behind the chain tip and still be reported as synced
+    /// by the health check.
+    ///
+    /// Further behind, the sync component is degraded while the node makes progress, and failed
+    /// otherwise.
+    #[arg(long = "debug.health-max-sync-distance", help_heading = "Debug", value_name = "BLOCKS")]
+    pub health_max_sync_distance: Option<u64>,
 }
 
 #[cfg(test)]
@@ -75,4 +163,70 @@ mod tests {
         let args = CommandParser::<DebugArgs>::parse_from(["reth"]).args;
         assert_eq!(args, default_args);
     }
+
+    #[test]
+    fn test_parse_future_timestamp_check() {
+        let args = CommandParser::<DebugArgs>::parse_from([
+            "reth",
+            "--debug.future-timestamp-check",
+            "warn",
+        ])
+        .args;
+        assert_eq!(args.future_timestamp_check, Some(CheckSeverity::Warn));
+        assert_eq!(args.future_timestamp_tolerance, None);
+
+        let args = CommandParser::<DebugArgs>::parse_from([
+            "reth",
+            "--debug.future-timestamp-tolerance",
+            "0",
+        ])
+        .args;
+        assert_eq!(args.future_timestamp_tolerance, Some(0));
+
+        assert!(CommandParser::<DebugArgs>::try_parse_from([
+            "reth",
+            "--debug.future-timestamp-check",
+            "relaxed",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_peer_sync_target() {
+        let args = CommandParser::<DebugArgs>::parse_from([
+            "reth",
+            "--debug.peer-sync-target",
+            "--debug.peer-sync-target-min-peers",
+            "5",
+        ])
+        .args;
+        assert!(args.peer_sync_target);
+        assert_eq!(args.peer_sync_target_min_peers, Some(5));
+
+        // the minimum requires the flag, and the flag conflicts with a manual tip
+        assert!(CommandParser::<DebugArgs>::try_parse_from([
+            "reth",
+            "--debug.peer-sync-target-min-peers",
+            "5",
+        ])
+        .is_err());
+        assert!(CommandParser::<DebugArgs>::try_parse_from([
+            "reth",
+            "--debug.peer-sync-target",
+            "--debug.tip",
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_health_max_sync_distance() {
+        let args = CommandParser::<DebugArgs>::parse_from([
+            "reth",
+            "--debug.health-max-sync-distance",
+            "8",
+        ])
+        .args;
+        assert_eq!(args.health_max_sync_distance, Some(8));
+    }
 }
@@ -22,6 +22,14 @@ pub struct GasPriceOracleArgs {
     #[arg(long = "gpo.maxprice", default_value_t = DEFAULT_MAX_GAS_PRICE.to())]
     pub max_price: u64,
 
+    /// Minimum transaction priority fee(or gasprice before London Fork) to be recommended by gpo
+    #[arg(long = "gpo.minprice")]
+    pub min_price: Option<u64>,
+
+    /// Priority fee(or gasprice before London Fork) to recommend if no recent block can be sampled
+    #[arg(long = "gpo.default")]
+    pub default: Option<u64>,
+
     /// The percentile of gas prices to use for the estimate
     #[arg(long = "gpo.percentile", default_value_t = DEFAULT_GAS_PRICE_PERCENTILE)]
     pub percentile: u32,
@@ -30,9 +38,11 @@ pub struct GasPriceOracleArgs {
 impl GasPriceOracleArgs {
     /// Returns a [`GasPriceOracleConfig`] from the arguments.
     pub fn gas_price_oracle_config(&self) -> GasPriceOracleConfig {
-        let Self { blocks, ignore_price, max_price, percentile } = self;
+        let Self { blocks, ignore_price, max_price, min_price, default, percentile } = self;
         GasPriceOracleConfig {
             max_price: Some(U256::from(*max_price)),
+            min_price: min_price.map(U256::from),
+            default: default.map(U256::from),
             ignore_price: Some(U256::from(*ignore_price)),
             percentile: *percentile,
             blocks: *blocks,
@@ -47,6 +57,8 @@ impl Default for GasPriceOracleArgs {
             blocks: DEFAULT_GAS_PRICE_BLOCKS,
             ignore_price: DEFAULT_IGNORE_GAS_PRICE.to(),
             max_price: DEFAULT_MAX_GAS_PRICE.to(),
+            min_price: None,
+            default: None,
             percentile: DEFAULT_GAS_PRICE_PERCENTILE,
         }
     }
@@ -72,6 +84,8 @@ mod tests {
                 blocks: DEFAULT_GAS_PRICE_BLOCKS,
                 ignore_price: DEFAULT_IGNORE_GAS_PRICE.to(),
                 max_price: DEFAULT_MAX_GAS_PRICE.to(),
+                min_price: None,
+                default: None,
                 percentile: DEFAULT_GAS_PRICE_PERCENTILE,
             }
         );
@@ -93,7 +93,16 @@ impl LogArgs {
 
     /// Initializes tracing with the configured options from cli args.
     pub fn init_tracing(&self) -> eyre::Result<Option<FileWorkerGuard>> {
-        let mut tracer = RethTracer::new();
+        self.init_tracing_with_stdout_to_stderr(false)
+    }
+
+    /// Initializes tracing with the configured options from cli args, optionally writing the
+    /// stdout logs to stderr to keep stdout free for the machine-readable output of a command.
+    pub fn init_tracing_with_stdout_to_stderr(
+        &self,
+        stdout_to_stderr: bool,
+    ) -> eyre::Result<Option<FileWorkerGuard>> {
+        let mut tracer = RethTracer::new().with_stdout_to_stderr(stdout_to_stderr);
 
         let stdout = self.layer(self.log_stdout_format, self.log_stdout_filter.clone(), true);
         tracer = tracer.with_stdout(stdout);
@@ -12,6 +12,11 @@ pub use rpc_server::RpcServerArgs;
 mod rpc_state_cache;
 pub use rpc_state_cache::RpcStateCacheArgs;
 
+/// `RpcReceiptsBackfillArgs` struct for configuring the re-execution of blocks with missing
+/// receipts
+mod rpc_receipts_backfill;
+pub use rpc_receipts_backfill::RpcReceiptsBackfillArgs;
+
 /// DebugArgs struct for debugging purposes
 mod debug;
 pub use debug::DebugArgs;
@@ -35,6 +40,10 @@ pub use payload_builder::PayloadBuilderArgs;
 mod stage;
 pub use stage::StageEnum;
 
+/// Output format of the offline commands
+mod output;
+pub use output::OutputFormat;
+
 /// Gas price oracle related arguments
 mod gas_price_oracle;
 pub use gas_price_oracle::GasPriceOracleArgs;
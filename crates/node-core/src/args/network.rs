@@ -11,7 +11,7 @@ use reth_discv5::{
 use reth_net_nat::NatResolver;
 use reth_network::{
     transactions::{
-        TransactionFetcherConfig, TransactionsManagerConfig,
+        TransactionFetcherConfig, TransactionsManagerConfig, DEFAULT_DEEP_SYNC_THRESHOLD,
         DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
         SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
     },
@@ -35,7 +35,9 @@ pub struct NetworkArgs {
     pub discovery: DiscoveryArgs,
 
     #[allow(clippy::doc_markdown)]
-    /// Comma separated enode URLs of trusted peers for P2P connections.
+    /// Comma separated enode URLs or ENRs of trusted peers for P2P connections.
+    ///
+    /// The host of an enode URL may be a hostname, which is resolved on startup.
     ///
     /// --trusted-peers enode://abcd@192.168.0.1:30303
     #[arg(long, value_delimiter = ',')]
@@ -45,7 +47,7 @@ pub struct NetworkArgs {
     #[arg(long)]
     pub trusted_only: bool,
 
-    /// Comma separated enode URLs for P2P discovery bootstrap.
+    /// Comma separated enode URLs or ENRs for P2P discovery bootstrap.
     ///
     /// Will fall back to a network-specific default if not specified.
     #[arg(long, value_delimiter = ',')]
@@ -114,6 +116,15 @@ pub struct NetworkArgs {
     /// Default is 128 KiB.
     #[arg(long = "pooled-tx-pack-soft-limit", value_name = "BYTES", default_value_t = DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ, verbatim_doc_comment)]
     pub soft_limit_byte_size_pooled_transactions_response_on_pack_request: usize,
+
+    /// Number of blocks the node can be behind the chain tip before transaction gossip is
+    /// suppressed and external transactions are rejected by the pool.
+    #[arg(long = "tx-gossip.deep-sync-threshold", value_name = "BLOCKS", default_value_t = DEFAULT_DEEP_SYNC_THRESHOLD)]
+    pub deep_sync_threshold: u64,
+
+    /// Serve account and storage ranges of the latest state to peers over the `snap/1` protocol.
+    #[arg(long = "snap.serve")]
+    pub serve_snap: bool,
 }
 
 impl NetworkArgs {
@@ -145,6 +156,8 @@ impl NetworkArgs {
                 self.soft_limit_byte_size_pooled_transactions_response,
                 self.soft_limit_byte_size_pooled_transactions_response_on_pack_request,
             ),
+            deep_sync_threshold: self.deep_sync_threshold,
+            ..Default::default()
         };
 
         // Configure basic network stack
@@ -232,6 +245,8 @@ impl Default for NetworkArgs {
             soft_limit_byte_size_pooled_transactions_response:
                 SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
             soft_limit_byte_size_pooled_transactions_response_on_pack_request: DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
+            deep_sync_threshold: DEFAULT_DEEP_SYNC_THRESHOLD,
+            serve_snap: false,
         }
     }
 }
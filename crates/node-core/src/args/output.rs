@@ -0,0 +1,21 @@
+//! Shared arguments related to the output of offline commands
+use derive_more::Display;
+
+/// The format the progress of a command is reported in.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, clap::ValueEnum, Display)]
+pub enum OutputFormat {
+    /// Human-readable logs.
+    #[default]
+    #[display(fmt = "human")]
+    Human,
+    /// One JSON event per line on stdout, logs are written to stderr instead.
+    #[display(fmt = "json")]
+    Json,
+}
+
+impl OutputFormat {
+    /// Returns `true` if the progress is reported as JSON events.
+    pub const fn is_json(&self) -> bool {
+        matches!(self, Self::Json)
+    }
+}
@@ -32,6 +32,7 @@ impl PruningArgs {
                     .map(|contract| PruneMode::Before(contract.block)),
                 account_history: Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)),
                 storage_history: Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)),
+                transaction_history: None,
                 receipts_log_filter: ReceiptsLogPruneConfig(
                     chain_spec
                         .deposit_contract
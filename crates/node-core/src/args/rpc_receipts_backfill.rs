@@ -0,0 +1,110 @@
+use crate::args::utils::parse_duration_from_secs;
+use clap::Args;
+use reth_provider::providers::{
+    ReceiptsBackfillConfig, DEFAULT_RECEIPTS_BACKFILL_CACHE_SIZE,
+    DEFAULT_RECEIPTS_BACKFILL_MAX_CONCURRENT, DEFAULT_RECEIPTS_BACKFILL_TIMEOUT,
+};
+use std::time::Duration;
+
+/// Parameters to configure the re-execution of blocks whose receipts are missing.
+#[derive(Debug, Clone, Copy, Args, PartialEq, Eq)]
+#[command(next_help_heading = "RPC Receipts Backfill")]
+pub struct RpcReceiptsBackfillArgs {
+    /// Re-execute canonical blocks to serve their receipts if they are missing in the database,
+    /// e.g. because the blocks were imported without execution.
+    ///
+    /// Blocks are only re-executed if the state of their parent is available, and their receipts
+    /// are only served if they match the receipts root of the header.
+    #[arg(long = "rpc.receipts-backfill")]
+    pub enabled: bool,
+
+    /// Max number of blocks that are re-executed concurrently.
+    #[arg(
+        long = "rpc.receipts-backfill.max-concurrent",
+        default_value_t = DEFAULT_RECEIPTS_BACKFILL_MAX_CONCURRENT,
+    )]
+    pub max_concurrent: usize,
+
+    /// Max time in seconds a request waits for other re-executions and re-executes its block.
+    #[arg(
+        long = "rpc.receipts-backfill.timeout",
+        value_parser = parse_duration_from_secs,
+        default_value = "10",
+    )]
+    pub timeout: Duration,
+
+    /// Max number of re-executed blocks whose receipts are kept in memory.
+    #[arg(
+        long = "rpc.receipts-backfill.max-cached",
+        default_value_t = DEFAULT_RECEIPTS_BACKFILL_CACHE_SIZE,
+    )]
+    pub max_cached: u32,
+
+    /// Don't write the receipts of re-executed blocks to the database.
+    #[arg(long = "rpc.receipts-backfill.no-persist")]
+    pub no_persist: bool,
+}
+
+impl RpcReceiptsBackfillArgs {
+    /// Returns the [`ReceiptsBackfillConfig`] if the backfill is enabled.
+    pub const fn receipts_backfill_config(&self) -> Option<ReceiptsBackfillConfig> {
+        if !self.enabled {
+            return None
+        }
+        Some(ReceiptsBackfillConfig {
+            max_concurrent: self.max_concurrent,
+            timeout: self.timeout,
+            persist: !self.no_persist,
+            cache_size: self.max_cached,
+        })
+    }
+}
+
+impl Default for RpcReceiptsBackfillArgs {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_concurrent: DEFAULT_RECEIPTS_BACKFILL_MAX_CONCURRENT,
+            timeout: DEFAULT_RECEIPTS_BACKFILL_TIMEOUT,
+            max_cached: DEFAULT_RECEIPTS_BACKFILL_CACHE_SIZE,
+            no_persist: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_receipts_backfill_args() {
+        let args = CommandParser::<RpcReceiptsBackfillArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, RpcReceiptsBackfillArgs::default());
+        assert_eq!(args.receipts_backfill_config(), None);
+
+        let args = CommandParser::<RpcReceiptsBackfillArgs>::parse_from([
+            "reth",
+            "--rpc.receipts-backfill",
+            "--rpc.receipts-backfill.timeout",
+            "30",
+            "--rpc.receipts-backfill.no-persist",
+        ])
+        .args;
+        assert_eq!(
+            args.receipts_backfill_config(),
+            Some(ReceiptsBackfillConfig {
+                max_concurrent: DEFAULT_RECEIPTS_BACKFILL_MAX_CONCURRENT,
+                timeout: Duration::from_secs(30),
+                persist: false,
+                cache_size: DEFAULT_RECEIPTS_BACKFILL_CACHE_SIZE,
+            })
+        );
+    }
+}
@@ -2,7 +2,7 @@
 
 use crate::args::{
     types::{MaxU32, ZeroAsNoneU64},
-    GasPriceOracleArgs, RpcStateCacheArgs,
+    GasPriceOracleArgs, RpcReceiptsBackfillArgs, RpcStateCacheArgs,
 };
 use alloy_rpc_types_engine::JwtSecret;
 use clap::{
@@ -163,6 +163,10 @@ pub struct RpcServerArgs {
     /// Gas price oracle configuration.
     #[command(flatten)]
     pub gas_price_oracle: GasPriceOracleArgs,
+
+    /// Receipts backfill configuration.
+    #[command(flatten)]
+    pub rpc_receipts_backfill: RpcReceiptsBackfillArgs,
 }
 
 impl RpcServerArgs {
@@ -288,6 +292,7 @@ impl Default for RpcServerArgs {
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP.into(),
             gas_price_oracle: GasPriceOracleArgs::default(),
             rpc_state_cache: RpcStateCacheArgs::default(),
+            rpc_receipts_backfill: RpcReceiptsBackfillArgs::default(),
         }
     }
 }
@@ -50,4 +50,12 @@ pub enum StageEnum {
     ///
     /// Manages historical data related to storage.
     StorageHistory,
+    /// The transaction history stage within the pipeline.
+    ///
+    /// Indexes the transactions of each address, if enabled.
+    TransactionHistory,
+    /// The header accumulator stage within the pipeline.
+    ///
+    /// Accumulates the canonical header hashes, if enabled.
+    HeaderAccumulator,
 }
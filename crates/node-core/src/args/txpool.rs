@@ -5,7 +5,8 @@ use clap::Args;
 use reth_primitives::Address;
 use reth_transaction_pool::{
     blobstore::disk::DEFAULT_MAX_CACHED_BLOBS, validate::DEFAULT_MAX_TX_INPUT_BYTES,
-    LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
+    LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit,
+    DEFAULT_MAX_CONCURRENT_VALIDATIONS, DEFAULT_MAX_QUEUED_LIFETIME, DEFAULT_PRICE_BUMP,
     REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
     TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
 };
@@ -63,6 +64,10 @@ pub struct TxPoolArgs {
     /// Flag to toggle local transaction propagation.
     #[arg(long = "txpool.no-local-transactions-propagation")]
     pub no_local_transactions_propagation: bool,
+
+    /// Persist rejections of transactions that can never become valid across restarts.
+    #[arg(long = "txpool.persist-rejections")]
+    pub persist_rejections: bool,
 }
 
 impl Default for TxPoolArgs {
@@ -82,6 +87,7 @@ impl Default for TxPoolArgs {
             no_locals: false,
             locals: Default::default(),
             no_local_transactions_propagation: false,
+            persist_rejections: false,
         }
     }
 }
@@ -116,6 +122,13 @@ impl RethTransactionPoolConfig for TxPoolArgs {
                 default_price_bump: self.price_bump,
                 replace_blob_tx_price_bump: self.blob_transaction_price_bump,
             },
+            recently_mined: Default::default(),
+            seen_transactions: Default::default(),
+            persistent_rejections: self.persist_rejections.then(Default::default),
+            invariant_check_interval: 0,
+            minimum_priority_fee: None,
+            max_concurrent_validations: DEFAULT_MAX_CONCURRENT_VALIDATIONS,
+            max_queued_lifetime: DEFAULT_MAX_QUEUED_LIFETIME,
         }
     }
 }
@@ -330,6 +330,21 @@ impl<D> ChainPath<D> {
         self.data_dir().join("txpool-transactions-backup.rlp")
     }
 
+    /// Returns the path to the directory where the transaction pool persists the rejections of
+    /// transactions that can never become valid.
+    ///
+    /// `<DIR>/<CHAIN_ID>/txpool-rejected`
+    pub fn txpool_rejected(&self) -> PathBuf {
+        self.data_dir().join("txpool-rejected")
+    }
+
+    /// Returns the path to the blockchain tree blocks backup file
+    ///
+    /// `<DIR>/<CHAIN_ID>/blockchain-tree-blocks-backup.rlp`
+    pub fn blockchain_tree_blocks(&self) -> PathBuf {
+        self.data_dir().join("blockchain-tree-blocks-backup.rlp")
+    }
+
     /// Returns the path to the config file for this chain.
     ///
     /// `<DIR>/<CHAIN_ID>/reth.toml`
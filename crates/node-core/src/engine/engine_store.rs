@@ -89,7 +89,8 @@ impl EngineMessageStore {
                 )?;
             }
             // noop
-            BeaconEngineMessage::TransitionConfigurationExchanged => (),
+            BeaconEngineMessage::TransitionConfigurationExchanged |
+            BeaconEngineMessage::SetHead { .. } => (),
         };
         Ok(())
     }
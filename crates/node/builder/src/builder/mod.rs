@@ -464,8 +464,12 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
     }
 
     /// Returns the transaction pool config of the node.
+    ///
+    /// This enforces the minimum priority fee of the chain.
     pub fn pool_config(&self) -> PoolConfig {
-        self.config().txpool.pool_config()
+        let mut config = self.config().txpool.pool_config();
+        config.minimum_priority_fee = self.chain_spec().fee_floors.min_priority_fee.map(u128::from);
+        config
     }
 
     /// Loads `MAINNET_KZG_TRUSTED_SETUP`.
@@ -497,10 +501,13 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
     where
         Pool: TransactionPool + Unpin + 'static,
     {
-        let (handle, network, txpool, eth) = builder
-            .transactions(pool, Default::default())
-            .request_handler(self.provider().clone())
-            .split_with_handle();
+        let mut builder =
+            builder.transactions(pool, Default::default()).request_handler(self.provider().clone());
+        if self.config().network.serve_snap {
+            let snap = builder.snap_request_handler(self.provider().clone(), Default::default());
+            self.executor.spawn_critical("p2p snap request handler", snap);
+        }
+        let (handle, network, txpool, eth) = builder.split_with_handle();
 
         self.executor.spawn_critical("p2p txpool", txpool);
         self.executor.spawn_critical("p2p eth request handler", eth);
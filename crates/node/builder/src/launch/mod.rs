@@ -5,6 +5,7 @@ use crate::{
     components::{NodeComponents, NodeComponentsBuilder},
     hooks::NodeHooks,
     node::FullNode,
+    setup::PipelineHandle,
     BuilderContext, NodeBuilderWithComponents, NodeHandle,
 };
 use futures::{future::Either, stream, stream_select, StreamExt};
@@ -13,13 +14,20 @@ use reth_beacon_consensus::{
     BeaconConsensusEngine,
 };
 use reth_blockchain_tree::{
-    noop::NoopBlockchainTree, BlockchainTree, BlockchainTreeConfig, ShareableBlockchainTree,
-    TreeExternals,
+    backup::backup_blocks_task, noop::NoopBlockchainTree, BlockchainTree, BlockchainTreeConfig,
+    BlocksBackupConfig, ShareableBlockchainTree, TreeExternals,
 };
 use reth_consensus::Consensus;
 use reth_consensus_debug_client::{DebugConsensusClient, EtherscanBlockProvider, RpcBlockProvider};
+use reth_db_api::database_metrics::DatabaseMetrics;
 use reth_exex::ExExManagerHandle;
-use reth_network::NetworkEvents;
+use reth_metrics::MetricsRegistrar;
+use reth_network::{
+    sync_target::{PeerSyncTarget, PeerSyncTargetConfig},
+    NetworkEvents,
+};
+use reth_network_api::PeersInfo;
+use reth_network_p2p::sync::SyncTargetProvider;
 use reth_node_api::{FullNodeComponents, FullNodeTypes};
 use reth_node_core::{
     dirs::{ChainPath, DataDirPath},
@@ -27,14 +35,21 @@ use reth_node_core::{
     exit::NodeExitFuture,
     version::{CARGO_PKG_VERSION, CLIENT_CODE, NAME_CLIENT, VERGEN_GIT_SHA},
 };
-use reth_node_events::{cl::ConsensusLayerHealthEvents, node};
+use reth_node_events::{
+    cl::ConsensusLayerHealthEvents,
+    health::{ComponentHealth, HealthCheck, SyncHealth, DEFAULT_MAX_SYNC_DISTANCE},
+    node,
+};
 
 use reth_primitives::format_ether;
-use reth_provider::providers::BlockchainProvider;
+use reth_provider::{
+    providers::{BlockchainProvider, ExecutionReceiptsBackfill},
+    BlockHashReader,
+};
 use reth_rpc_engine_api::EngineApi;
-use reth_rpc_types::engine::ClientVersionV1;
+use reth_rpc_types::engine::{ClientVersionV1, ForkchoiceState};
 use reth_tasks::TaskExecutor;
-use reth_tracing::tracing::{debug, info};
+use reth_tracing::tracing::{debug, info, warn};
 use reth_transaction_pool::TransactionPool;
 use std::{future::Future, sync::Arc};
 use tokio::sync::{mpsc::unbounded_channel, oneshot};
@@ -130,9 +145,14 @@ where
                 info!(target: "reth::cli", "\n{}", this.chain_spec().display_hardforks());
             });
 
+        // declare the metrics of all components in one place, now that the recorder is installed
+        let mut metrics_registrar = MetricsRegistrar::new();
+        database.register_metrics(&mut metrics_registrar);
+
         debug!(target: "reth::cli", "Spawning stages metrics listener task");
         let (sync_metrics_tx, sync_metrics_rx) = unbounded_channel();
         let sync_metrics_listener = reth_stages::MetricsListener::new(sync_metrics_rx);
+        sync_metrics_listener.register_metrics(&mut metrics_registrar);
         ctx.task_executor().spawn_critical("stages metrics listener task", sync_metrics_listener);
 
         // fetch the head block from the database
@@ -164,11 +184,21 @@ where
 
         let consensus: Arc<dyn Consensus> = Arc::new(components.consensus().clone());
 
-        let tree_externals = TreeExternals::new(
+        let mut tree_externals = TreeExternals::new(
             ctx.provider_factory().clone(),
             consensus.clone(),
             components.block_executor().clone(),
         );
+        if let Some(soft_limit) = ctx.node_config().debug.tree_execution_soft_limit {
+            tree_externals = tree_externals.with_execution_soft_limit(soft_limit);
+        }
+        if let Some(threads) = ctx.node_config().debug.state_root_threads {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .thread_name(|idx| format!("reth-state-root-{idx}"))
+                .build()?;
+            tree_externals = tree_externals.with_state_root_pool(Arc::new(pool));
+        }
         let tree = BlockchainTree::new(tree_externals, tree_config, ctx.prune_modes())?
             .with_sync_metrics_tx(sync_metrics_tx.clone())
             // Note: This is required because we need to ensure that both the components and the
@@ -177,12 +207,46 @@ where
             .with_canon_state_notification_sender(canon_state_notification_sender);
 
         let blockchain_tree = Arc::new(ShareableBlockchainTree::new(tree));
+        blockchain_tree.register_metrics(&mut metrics_registrar);
+
+        if ctx.node_config().debug.tree_blocks_backup {
+            let mut blocks_backup_config =
+                BlocksBackupConfig::with_blocks_backup(ctx.data_dir().blockchain_tree_blocks());
+            if let Some(max_age) = ctx.node_config().debug.tree_blocks_backup_max_age {
+                blocks_backup_config = blocks_backup_config.with_max_age(max_age);
+            }
+
+            let tree = blockchain_tree.tree.clone();
+            ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
+                "blockchain tree blocks backup task",
+                |shutdown| backup_blocks_task(shutdown, tree, blocks_backup_config),
+            );
+        }
+
+        let tree_congestion = blockchain_tree.subscribe_to_congestion();
 
         // Replace the tree component with the actual tree
-        let blockchain_db = blockchain_db.with_tree(blockchain_tree);
+        let mut blockchain_db = blockchain_db.with_tree(blockchain_tree);
+
+        if let Some(config) = ctx.node_config().rpc.rpc_receipts_backfill.receipts_backfill_config()
+        {
+            debug!(target: "reth::cli", ?config, "enabling receipts backfill");
+            blockchain_db =
+                blockchain_db.with_receipts_backfill(Arc::new(ExecutionReceiptsBackfill::new(
+                    ctx.provider_factory().clone(),
+                    components.block_executor().clone(),
+                    config,
+                )));
+        }
 
         debug!(target: "reth::cli", "configured blockchain tree");
 
+        components.pool().register_metrics(&mut metrics_registrar);
+        components.network().register_metrics(&mut metrics_registrar);
+        let registered_metrics =
+            metrics_registrar.metrics().iter().map(|metric| &metric.name).collect::<Vec<_>>();
+        debug!(target: "reth::cli", metrics = ?registered_metrics, "registered metrics");
+
         let NodeHooks { on_component_initialized, on_node_started, .. } = hooks;
 
         let node_adapter = NodeAdapter {
@@ -218,6 +282,13 @@ where
 
         let static_file_producer = ctx.static_file_producer();
         let static_file_producer_events = static_file_producer.lock().events();
+        let pipeline_handle = PipelineHandle::new(
+            ctx.toml_config().stages.clone(),
+            ctx.provider_factory().clone(),
+            consensus.clone(),
+            static_file_producer.clone(),
+            node_adapter.components.block_executor().clone(),
+        );
         hooks.add(StaticFileHook::new(
             static_file_producer.clone(),
             Box::new(ctx.task_executor().clone()),
@@ -323,11 +394,82 @@ where
         )?;
         info!(target: "reth::cli", "Consensus engine initialized");
 
+        if ctx.node_config().debug.peer_sync_target {
+            info!(target: "reth::cli", "Using the chain heads reported by peers as sync target");
+
+            let mut config = PeerSyncTargetConfig::default();
+            if let Some(min_peers) = ctx.node_config().debug.peer_sync_target_min_peers {
+                config.min_peers = min_peers;
+            }
+            let sync_target =
+                PeerSyncTarget::new(network_client.clone(), consensus.clone(), config);
+            let mut targets = sync_target.handle().subscribe_sync_target();
+            ctx.task_executor().spawn_critical(
+                "peer sync target",
+                sync_target.run(
+                    node_adapter.components.network().peers_handle().clone(),
+                    blockchain_db.clone(),
+                ),
+            );
+
+            // drive the engine to the selected target, like a consensus client would
+            let engine_handle = beacon_engine_handle.clone();
+            ctx.task_executor().spawn_critical("peer sync target updates", async move {
+                while targets.changed().await.is_ok() {
+                    let Some(target) = *targets.borrow_and_update() else { continue };
+                    let state =
+                        ForkchoiceState { head_block_hash: target.hash, ..Default::default() };
+                    if let Err(err) = engine_handle.fork_choice_updated(state, None).await {
+                        warn!(target: "reth::cli", %err, ?target, "Failed to update the sync target");
+                    }
+                }
+            });
+        }
+
+        // Register the components the readiness of the node depends on
+        let max_sync_distance =
+            ctx.node_config().debug.health_max_sync_distance.unwrap_or(DEFAULT_MAX_SYNC_DISTANCE);
+        let health = HealthCheck::default().with_sync(SyncHealth::new(max_sync_distance));
+        let provider_factory = ctx.provider_factory().clone();
+        health.register("database", move || match provider_factory.provider() {
+            Ok(_) => ComponentHealth::ok(),
+            Err(err) => ComponentHealth::failed(err.to_string()),
+        });
+        let provider_factory = ctx.provider_factory().clone();
+        let genesis_hash = ctx.genesis_hash();
+        health.register("genesis", move || match provider_factory.block_hash(0) {
+            Ok(Some(hash)) if hash == genesis_hash => ComponentHealth::ok(),
+            Ok(Some(hash)) => ComponentHealth::failed(format!(
+                "genesis mismatch, expected {genesis_hash}, found {hash}"
+            )),
+            Ok(None) => ComponentHealth::failed("genesis block missing"),
+            Err(err) => ComponentHealth::failed(err.to_string()),
+        });
+        health.register("tree", move || {
+            if tree_congestion.borrow().is_congested() {
+                ComponentHealth::degraded("congested, new blocks are deferred")
+            } else {
+                ComponentHealth::ok()
+            }
+        });
+        let network = node_adapter.components.network().clone();
+        health.register("network", move || match network.num_connected_peers() {
+            0 => ComponentHealth::degraded("no connected peers"),
+            peers => ComponentHealth::ok().with_message(format!("{peers} connected peers")),
+        });
+        let pool = node_adapter.components.pool().clone();
+        health.register("pool", move || {
+            ComponentHealth::ok().with_message(format!("{} transactions", pool.pool_size().total))
+        });
+
         let events = stream_select!(
             node_adapter.components.network().event_listener().map(Into::into),
             beacon_engine_handle.event_listener().map(Into::into),
             pipeline_events.map(Into::into),
-            if ctx.node_config().debug.tip.is_none() && !ctx.is_dev() {
+            if ctx.node_config().debug.tip.is_none() &&
+                !ctx.node_config().debug.peer_sync_target &&
+                !ctx.is_dev()
+            {
                 Either::Left(
                     ConsensusLayerHealthEvents::new(Box::new(blockchain_db.clone()))
                         .map(Into::into),
@@ -345,6 +487,7 @@ where
                 Some(head.number),
                 events,
                 database.clone(),
+                Some(health.clone()),
             ),
         );
 
@@ -438,12 +581,14 @@ where
             pool: node_adapter.components.pool().clone(),
             network: node_adapter.components.network().clone(),
             provider: node_adapter.provider.clone(),
+            pipeline: pipeline_handle,
             payload_builder: node_adapter.components.payload_builder().clone(),
             task_executor: ctx.task_executor().clone(),
             rpc_server_handles,
             rpc_registry,
             config: ctx.node_config().clone(),
             data_dir: ctx.data_dir().clone(),
+            health,
         };
         // Notify on node started
         on_node_started.on_event(full_node.clone())?;
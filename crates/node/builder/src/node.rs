@@ -1,4 +1,7 @@
-use crate::rpc::{RethRpcServerHandles, RpcRegistry};
+use crate::{
+    rpc::{RethRpcServerHandles, RpcRegistry},
+    setup::PipelineHandle,
+};
 use reth_network::NetworkHandle;
 use reth_node_api::FullNodeComponents;
 use reth_node_core::{
@@ -6,6 +9,7 @@ use reth_node_core::{
     node_config::NodeConfig,
     rpc::api::EngineApiClient,
 };
+use reth_node_events::health::{HealthCheck, HealthReport};
 use reth_payload_builder::PayloadBuilderHandle;
 use reth_primitives::ChainSpec;
 use reth_provider::ChainSpecProvider;
@@ -43,6 +47,8 @@ pub struct FullNode<Node: FullNodeComponents> {
     pub network: NetworkHandle,
     /// Provider to interact with the node's database
     pub provider: Node::Provider,
+    /// Builds pipelines that import blocks into the node's database.
+    pub pipeline: PipelineHandle<Node::DB, Node::Executor>,
     /// Handle to the node's payload builder service.
     pub payload_builder: PayloadBuilderHandle<Node::Engine>,
     /// Task executor for the node.
@@ -55,6 +61,8 @@ pub struct FullNode<Node: FullNodeComponents> {
     pub config: NodeConfig,
     /// The data dir of the node.
     pub data_dir: ChainPath<DataDirPath>,
+    /// The health of the node components, e.g. to serve readiness probes.
+    pub health: HealthCheck,
 }
 
 impl<Node: FullNodeComponents> FullNode<Node> {
@@ -63,6 +71,13 @@ impl<Node: FullNodeComponents> FullNode<Node> {
         self.provider.chain_spec()
     }
 
+    /// Evaluates the health of the node components.
+    ///
+    /// The node is ready if [`HealthReport::ready`] is set.
+    pub fn health_report(&self) -> HealthReport {
+        self.health.report()
+    }
+
     /// Returns the [`RpcServerHandle`] to the started rpc server.
     pub const fn rpc_server_handle(&self) -> &RpcServerHandle {
         &self.rpc_server_handles.rpc
@@ -104,12 +119,14 @@ impl<Node: FullNodeComponents> Clone for FullNode<Node> {
             pool: self.pool.clone(),
             network: self.network.clone(),
             provider: self.provider.clone(),
+            pipeline: self.pipeline.clone(),
             payload_builder: self.payload_builder.clone(),
             task_executor: self.task_executor.clone(),
             rpc_server_handles: self.rpc_server_handles.clone(),
             rpc_registry: self.rpc_registry.clone(),
             config: self.config.clone(),
             data_dir: self.data_dir.clone(),
+            health: self.health.clone(),
         }
     }
 }
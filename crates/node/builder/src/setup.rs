@@ -1,25 +1,31 @@
 //! Helpers for setting up parts of the node.
 
+use futures::{Stream, StreamExt};
 use reth_config::{config::StageConfig, PruneConfig};
 use reth_consensus::Consensus;
 use reth_db_api::database::Database;
 use reth_downloaders::{
-    bodies::bodies::BodiesDownloaderBuilder,
+    bodies::bodies::BodiesDownloaderBuilder, file_client::FileClient,
     headers::reverse_headers::ReverseHeadersDownloaderBuilder,
 };
 use reth_evm::execute::BlockExecutorProvider;
 use reth_exex::ExExManagerHandle;
 use reth_network_p2p::{
     bodies::{client::BodiesClient, downloader::BodyDownloader},
-    headers::{client::HeadersClient, downloader::HeaderDownloader},
+    headers::{
+        client::HeadersClient,
+        downloader::{HeaderDownloader, SyncTarget},
+    },
 };
 use reth_node_core::primitives::{BlockNumber, B256};
-use reth_provider::ProviderFactory;
-use reth_stages::{prelude::DefaultStages, stages::ExecutionStage, Pipeline, StageSet};
+use reth_node_events::node::NodeEvent;
+use reth_provider::{BlockNumReader, HeaderProvider, ProviderError, ProviderFactory};
+use reth_prune::PruneModes;
+use reth_stages::{prelude::DefaultStages, stages::ExecutionStage, Pipeline, StageId, StageSet};
 use reth_static_file::StaticFileProducer;
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::debug;
-use std::sync::Arc;
+use std::{fmt, sync::Arc};
 use tokio::sync::watch;
 
 /// Constructs a [Pipeline] that's wired to the network
@@ -123,6 +129,7 @@ where
                     prune_modes,
                     exex_manager_handle,
                 )
+                .with_prefetch_blocks(stage_config.execution.prefetch_blocks)
                 .with_metrics_tx(metrics_tx),
             ),
         )
@@ -130,3 +137,150 @@ where
 
     Ok(pipeline)
 }
+
+/// Builds the [Pipeline] that imports the blocks of the [`FileClient`] on top of the blocks in the
+/// database.
+///
+/// If configured to execute, all stages will run. Otherwise, only stages that don't require state
+/// will run.
+///
+/// Returns the pipeline and the stream of its events. The tip of the pipeline still has to be set
+/// to the tip of the file client before running it.
+pub async fn build_import_pipeline<DB, E>(
+    stage_config: &StageConfig,
+    provider_factory: ProviderFactory<DB>,
+    consensus: Arc<dyn Consensus>,
+    file_client: Arc<FileClient>,
+    static_file_producer: StaticFileProducer<DB>,
+    executor: E,
+    disable_exec: bool,
+) -> eyre::Result<(Pipeline<DB>, impl Stream<Item = NodeEvent>)>
+where
+    DB: Database + Clone + Unpin + 'static,
+    E: BlockExecutorProvider,
+{
+    if !file_client.has_canonical_blocks() {
+        eyre::bail!("unable to import non canonical blocks");
+    }
+    let (Some(tip), Some(min_block), Some(max_block)) =
+        (file_client.tip(), file_client.min_block(), file_client.max_block())
+    else {
+        eyre::bail!("no blocks to import");
+    };
+
+    // Retrieve latest header found in the database.
+    let last_block_number = provider_factory.last_block_number()?;
+    let local_head = provider_factory
+        .sealed_header(last_block_number)?
+        .ok_or(ProviderError::HeaderNotFound(last_block_number.into()))?;
+
+    let mut header_downloader = ReverseHeadersDownloaderBuilder::new(stage_config.headers)
+        .build(file_client.clone(), consensus.clone())
+        .into_task();
+    // TODO: The pipeline should correctly configure the downloader on its own.
+    // Find the possibility to remove unnecessary pre-configuration.
+    header_downloader.update_local_head(local_head);
+    header_downloader.update_sync_target(SyncTarget::Tip(tip));
+
+    let mut body_downloader = BodiesDownloaderBuilder::new(stage_config.bodies)
+        .build(file_client.clone(), consensus.clone(), provider_factory.clone())
+        .into_task();
+    // TODO: The pipeline should correctly configure the downloader on its own.
+    // Find the possibility to remove unnecessary pre-configuration.
+    body_downloader
+        .set_download_range(min_block..=max_block)
+        .expect("failed to set download range");
+
+    let (tip_tx, tip_rx) = watch::channel(B256::ZERO);
+
+    let pipeline = Pipeline::builder()
+        .with_tip_sender(tip_tx)
+        // we want to sync all blocks the file client provides
+        .with_max_block(max_block)
+        .add_stages(
+            DefaultStages::new(
+                provider_factory.clone(),
+                tip_rx,
+                consensus.clone(),
+                header_downloader,
+                body_downloader,
+                executor,
+                stage_config.clone(),
+                PruneModes::default(),
+            )
+            .builder()
+            .disable_all_if(&StageId::STATE_REQUIRED, || disable_exec),
+        )
+        .build(provider_factory, static_file_producer);
+
+    let events = pipeline.events().map(Into::into);
+
+    Ok((pipeline, events))
+}
+
+/// Builds pipelines that import blocks into the database of a launched node.
+///
+/// The pipelines are assembled like the pipeline of the `import` command, with the stage
+/// configuration, consensus and executor of the node, so component overrides apply to them. They
+/// share the static file producer of the node.
+///
+/// The imported blocks are written to the database, the blockchain tree of the node isn't notified
+/// of them. Blocks should only be imported while the consensus engine of the node isn't syncing.
+#[derive(Clone)]
+pub struct PipelineHandle<DB, E> {
+    stage_config: StageConfig,
+    provider_factory: ProviderFactory<DB>,
+    consensus: Arc<dyn Consensus>,
+    static_file_producer: StaticFileProducer<DB>,
+    executor: E,
+}
+
+impl<DB, E> PipelineHandle<DB, E>
+where
+    DB: Database + Clone + Unpin + 'static,
+    E: BlockExecutorProvider,
+{
+    /// Creates a new handle from the components of the node.
+    pub const fn new(
+        stage_config: StageConfig,
+        provider_factory: ProviderFactory<DB>,
+        consensus: Arc<dyn Consensus>,
+        static_file_producer: StaticFileProducer<DB>,
+        executor: E,
+    ) -> Self {
+        Self { stage_config, provider_factory, consensus, static_file_producer, executor }
+    }
+
+    /// Returns the provider factory the pipelines write to.
+    pub const fn provider_factory(&self) -> &ProviderFactory<DB> {
+        &self.provider_factory
+    }
+
+    /// Builds a pipeline that imports the blocks of the [`FileClient`], see
+    /// [`build_import_pipeline`].
+    pub async fn import_pipeline(
+        &self,
+        file_client: Arc<FileClient>,
+        disable_exec: bool,
+    ) -> eyre::Result<(Pipeline<DB>, impl Stream<Item = NodeEvent>)> {
+        build_import_pipeline(
+            &self.stage_config,
+            self.provider_factory.clone(),
+            self.consensus.clone(),
+            file_client,
+            self.static_file_producer.clone(),
+            self.executor.clone(),
+            disable_exec,
+        )
+        .await
+    }
+}
+
+impl<DB, E> fmt::Debug for PipelineHandle<DB, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PipelineHandle")
+            .field("stage_config", &self.stage_config)
+            .field("consensus", &self.consensus)
+            .finish_non_exhaustive()
+    }
+}
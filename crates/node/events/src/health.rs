@@ -0,0 +1,398 @@
+//! Readiness of the node, aggregated from the health of its components.
+//!
+//! Every subsystem of the node can register a cheap status closure with the [`HealthCheck`]. The
+//! closures are evaluated on demand, for example when the RPC server is asked for the readiness of
+//! the node, and summarized in a [`HealthReport`]. Registration is optional, so partial node
+//! configurations only report the components they run.
+
+use reth_primitives::BlockNumber;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Default number of blocks the node can be behind the chain tip and still be considered synced.
+pub const DEFAULT_MAX_SYNC_DISTANCE: u64 = 64;
+
+/// Default duration without sync progress after which a node that is not synced is no longer
+/// considered to be syncing.
+pub const DEFAULT_SYNC_PROGRESS_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The health of a component.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentStatus {
+    /// The component works as expected.
+    #[default]
+    Ok,
+    /// The component works, but not as expected, for example because it's still catching up.
+    Degraded,
+    /// The component doesn't work, the node is not ready.
+    Failed,
+}
+
+impl fmt::Display for ComponentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ok => f.write_str("ok"),
+            Self::Degraded => f.write_str("degraded"),
+            Self::Failed => f.write_str("failed"),
+        }
+    }
+}
+
+/// The result of a single health check of a component.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComponentHealth {
+    /// The health of the component.
+    pub status: ComponentStatus,
+    /// Human-readable details about the health of the component.
+    pub message: Option<String>,
+}
+
+impl ComponentHealth {
+    /// Creates a healthy [`ComponentHealth`].
+    pub const fn ok() -> Self {
+        Self { status: ComponentStatus::Ok, message: None }
+    }
+
+    /// Creates a degraded [`ComponentHealth`] with the given message.
+    pub fn degraded(message: impl Into<String>) -> Self {
+        Self { status: ComponentStatus::Degraded, message: Some(message.into()) }
+    }
+
+    /// Creates a failed [`ComponentHealth`] with the given message.
+    pub fn failed(message: impl Into<String>) -> Self {
+        Self { status: ComponentStatus::Failed, message: Some(message.into()) }
+    }
+
+    /// Sets the message of the [`ComponentHealth`].
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+/// The health of a single component in a [`HealthReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentReport {
+    /// The health of the component.
+    pub status: ComponentStatus,
+    /// Human-readable details about the health of the component.
+    pub message: Option<String>,
+    /// Milliseconds since the unix epoch at which the status or message of the component last
+    /// changed.
+    pub last_updated_ms: u64,
+}
+
+/// The health of all registered components.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthReport {
+    /// Whether the node is ready, i.e. none of its components failed.
+    pub ready: bool,
+    /// The health of the individual components, by name.
+    pub components: BTreeMap<String, ComponentReport>,
+}
+
+impl HealthReport {
+    /// Returns the worst status of all components.
+    pub fn status(&self) -> ComponentStatus {
+        self.components.values().map(|component| component.status).max().unwrap_or_default()
+    }
+
+    /// Returns all components that are not [`ComponentStatus::Ok`].
+    pub fn unhealthy(&self) -> impl Iterator<Item = (&String, &ComponentReport)> + '_ {
+        self.components.iter().filter(|(_, component)| component.status != ComponentStatus::Ok)
+    }
+}
+
+/// A status closure registered with the [`HealthCheck`].
+type CheckFn = Box<dyn Fn() -> ComponentHealth + Send + Sync>;
+
+/// A registered component.
+struct Component {
+    name: String,
+    check: CheckFn,
+    /// The last evaluated health and when it changed.
+    last: Mutex<Option<(ComponentHealth, u64)>>,
+}
+
+/// Aggregates the health of the components of the node.
+///
+/// The health check can be cloned and shared, all clones refer to the same components.
+#[derive(Clone, Default)]
+pub struct HealthCheck {
+    components: Arc<RwLock<Vec<Component>>>,
+    sync: Option<SyncHealth>,
+}
+
+impl HealthCheck {
+    /// Tracks the sync state of the node with the given [`SyncHealth`] and registers it as the
+    /// `sync` component.
+    pub fn with_sync(mut self, sync: SyncHealth) -> Self {
+        let check = sync.clone();
+        self.register("sync", move || check.check());
+        self.sync = Some(sync);
+        self
+    }
+
+    /// Returns the [`SyncHealth`] that tracks the sync state of the node, if configured.
+    pub const fn sync(&self) -> Option<&SyncHealth> {
+        self.sync.as_ref()
+    }
+
+    /// Registers a component with a closure that returns its current health.
+    ///
+    /// The closure is evaluated every time a report is requested, so it should be cheap. A
+    /// component that is registered twice replaces the previous registration.
+    pub fn register<F>(&self, name: impl Into<String>, check: F)
+    where
+        F: Fn() -> ComponentHealth + Send + Sync + 'static,
+    {
+        let component =
+            Component { name: name.into(), check: Box::new(check), last: Mutex::new(None) };
+        let mut components = self.components.write().unwrap_or_else(|err| err.into_inner());
+        components.retain(|existing| existing.name != component.name);
+        components.push(component);
+    }
+
+    /// Evaluates the health of all registered components.
+    pub fn report(&self) -> HealthReport {
+        let now =
+            SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+        let components = self.components.read().unwrap_or_else(|err| err.into_inner());
+
+        let components = components
+            .iter()
+            .map(|component| {
+                let health = (component.check)();
+
+                let mut last = component.last.lock().unwrap_or_else(|err| err.into_inner());
+                let last_updated_ms = match last.as_ref() {
+                    Some((last_health, updated)) if *last_health == health => *updated,
+                    _ => {
+                        *last = Some((health.clone(), now));
+                        now
+                    }
+                };
+
+                let ComponentHealth { status, message } = health;
+                (component.name.clone(), ComponentReport { status, message, last_updated_ms })
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let ready =
+            components.values().all(|component| component.status != ComponentStatus::Failed);
+        HealthReport { ready, components }
+    }
+
+    /// Returns `true` if none of the registered components failed.
+    pub fn is_ready(&self) -> bool {
+        self.report().ready
+    }
+}
+
+impl fmt::Debug for HealthCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let components = self.components.read().unwrap_or_else(|err| err.into_inner());
+        f.debug_struct("HealthCheck")
+            .field("components", &components.iter().map(|c| &c.name).collect::<Vec<_>>())
+            .field("sync", &self.sync)
+            .finish()
+    }
+}
+
+/// Sync progress as reported by the pipeline and the consensus engine.
+#[derive(Debug, Default)]
+struct SyncProgress {
+    /// The number of blocks the node is behind the chain tip, if known.
+    distance: Option<u64>,
+    /// The last time the node made progress towards the chain tip.
+    last_progress: Option<Instant>,
+}
+
+/// Tracks how far the node is behind the chain tip.
+///
+/// The node is healthy if it's at most `max_distance` blocks behind the tip. If it's further
+/// behind, but still making progress, it's degraded. Otherwise, including if the tip is not known
+/// yet, the sync component has failed.
+#[derive(Debug, Clone)]
+pub struct SyncHealth {
+    max_distance: u64,
+    progress_timeout: Duration,
+    progress: Arc<Mutex<SyncProgress>>,
+}
+
+impl SyncHealth {
+    /// Creates a new [`SyncHealth`] with the given maximum distance to the chain tip.
+    pub fn new(max_distance: u64) -> Self {
+        Self {
+            max_distance,
+            progress_timeout: DEFAULT_SYNC_PROGRESS_TIMEOUT,
+            progress: Default::default(),
+        }
+    }
+
+    /// Sets the duration without progress after which the node is no longer considered to be
+    /// syncing.
+    pub const fn with_progress_timeout(mut self, progress_timeout: Duration) -> Self {
+        self.progress_timeout = progress_timeout;
+        self
+    }
+
+    /// Records that the node synced up to `block`, with the chain tip at `target`, if known.
+    pub fn on_progress(&self, block: BlockNumber, target: Option<BlockNumber>) {
+        self.update(target.map(|target| target.saturating_sub(block)));
+    }
+
+    /// Records that the node is the given number of blocks behind the chain tip.
+    pub fn on_remaining_blocks(&self, remaining_blocks: u64) {
+        self.update(Some(remaining_blocks));
+    }
+
+    fn update(&self, distance: Option<u64>) {
+        let mut progress = self.progress.lock().unwrap_or_else(|err| err.into_inner());
+        if distance.is_some() {
+            progress.distance = distance;
+        }
+        progress.last_progress = Some(Instant::now());
+    }
+
+    /// Returns the current health of the sync component.
+    pub fn check(&self) -> ComponentHealth {
+        let progress = self.progress.lock().unwrap_or_else(|err| err.into_inner());
+        let is_progressing = progress
+            .last_progress
+            .map_or(false, |last_progress| last_progress.elapsed() < self.progress_timeout);
+
+        match progress.distance {
+            Some(distance) if distance <= self.max_distance => {
+                ComponentHealth::ok().with_message(format!("{distance} blocks behind the tip"))
+            }
+            Some(distance) if is_progressing => {
+                ComponentHealth::degraded(format!("syncing, {distance} blocks behind the tip"))
+            }
+            Some(distance) => ComponentHealth::failed(format!(
+                "{distance} blocks behind the tip and not making progress"
+            )),
+            None if is_progressing => ComponentHealth::degraded("syncing, chain tip unknown"),
+            None => ComponentHealth::failed("chain tip unknown"),
+        }
+    }
+}
+
+impl Default for SyncHealth {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_SYNC_DISTANCE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU8, Ordering};
+
+    /// A component whose status can be flipped from the test.
+    fn mock_component(health: &HealthCheck, name: &str) -> Arc<AtomicU8> {
+        let status = Arc::new(AtomicU8::new(0));
+        let check = Arc::clone(&status);
+        health.register(name, move || match check.load(Ordering::Relaxed) {
+            0 => ComponentHealth::ok(),
+            1 => ComponentHealth::degraded("lagging"),
+            _ => ComponentHealth::failed("down"),
+        });
+        status
+    }
+
+    #[test]
+    fn aggregate_readiness() {
+        let health = HealthCheck::default();
+        // nothing registered
+        assert!(health.is_ready());
+        assert_eq!(health.report().status(), ComponentStatus::Ok);
+
+        let db = mock_component(&health, "db");
+        let pool = mock_component(&health, "pool");
+
+        let report = health.report();
+        assert!(report.ready);
+        assert_eq!(report.components.keys().collect::<Vec<_>>(), ["db", "pool"]);
+        assert_eq!(report.unhealthy().count(), 0);
+
+        // degraded components don't affect readiness
+        pool.store(1, Ordering::Relaxed);
+        let report = health.report();
+        assert!(report.ready);
+        assert_eq!(report.status(), ComponentStatus::Degraded);
+        let unhealthy = report.unhealthy().collect::<Vec<_>>();
+        assert_eq!(unhealthy.len(), 1);
+        assert_eq!(unhealthy[0].0, "pool");
+        assert_eq!(unhealthy[0].1.message.as_deref(), Some("lagging"));
+
+        db.store(2, Ordering::Relaxed);
+        let report = health.report();
+        assert!(!report.ready);
+        assert_eq!(report.status(), ComponentStatus::Failed);
+        assert_eq!(report.components["db"].status, ComponentStatus::Failed);
+        assert_eq!(report.components["db"].message.as_deref(), Some("down"));
+
+        db.store(0, Ordering::Relaxed);
+        pool.store(0, Ordering::Relaxed);
+        assert!(health.is_ready());
+        assert_eq!(health.report().unhealthy().count(), 0);
+    }
+
+    #[test]
+    fn last_updated_tracks_changes() {
+        let health = HealthCheck::default();
+        let status = mock_component(&health, "network");
+
+        let first = health.report().components["network"].last_updated_ms;
+        std::thread::sleep(Duration::from_millis(5));
+        // unchanged status keeps the timestamp
+        assert_eq!(health.report().components["network"].last_updated_ms, first);
+
+        status.store(1, Ordering::Relaxed);
+        let changed = health.report().components["network"].last_updated_ms;
+        assert!(changed > first);
+
+        // registering a component again replaces it
+        health.register("network", ComponentHealth::ok);
+        assert_eq!(health.report().components.len(), 1);
+        assert_eq!(health.report().components["network"].status, ComponentStatus::Ok);
+    }
+
+    #[test]
+    fn sync_readiness() {
+        let sync = SyncHealth::new(10);
+        let health = HealthCheck::default().with_sync(sync);
+        let sync = health.sync().unwrap();
+
+        // the tip is not known yet
+        assert!(!health.is_ready());
+
+        sync.on_progress(100, None);
+        assert_eq!(health.report().components["sync"].status, ComponentStatus::Degraded);
+
+        // far behind, but syncing
+        sync.on_progress(100, Some(1000));
+        let report = health.report();
+        assert!(report.ready);
+        assert_eq!(report.components["sync"].status, ComponentStatus::Degraded);
+
+        sync.on_remaining_blocks(5);
+        let report = health.report();
+        assert!(report.ready);
+        assert_eq!(report.components["sync"].status, ComponentStatus::Ok);
+
+        // far behind and stalled
+        let stalled = SyncHealth::new(10).with_progress_timeout(Duration::ZERO);
+        stalled.on_progress(100, Some(1000));
+        assert_eq!(stalled.check().status, ComponentStatus::Failed);
+        stalled.on_progress(995, Some(1000));
+        assert_eq!(stalled.check().status, ComponentStatus::Ok);
+    }
+}
@@ -0,0 +1,299 @@
+//! Machine-readable output of the offline commands, like `reth import` and `reth stage run`.
+//!
+//! Every event is written as a single JSON object on its own line (NDJSON). Each line carries the
+//! [`SCHEMA_VERSION`], which is bumped whenever a field is removed or changes its meaning. Adding a
+//! new event or a new field is not considered a breaking change, so consumers should ignore unknown
+//! events and fields.
+
+use crate::node::NodeEvent;
+use futures::{Stream, StreamExt};
+use reth_primitives::BlockNumber;
+use reth_stages::{ExecOutput, PipelineEvent, StageId};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    io::{self, Stdout, Write},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tracing::warn;
+
+/// The version of the JSON output schema.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A single line of the JSON output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonEventLine {
+    /// The version of the schema the event conforms to.
+    pub schema_version: u32,
+    /// Milliseconds since the unix epoch at which the event was emitted.
+    pub timestamp_ms: u64,
+    /// The event.
+    #[serde(flatten)]
+    pub event: JsonEvent,
+}
+
+/// An event of the JSON output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JsonEvent {
+    /// The command started.
+    RunStarted {
+        /// The name of the command.
+        command: String,
+        /// Summary of the configuration the command runs with.
+        config: BTreeMap<String, serde_json::Value>,
+    },
+    /// A chunk of the input was processed.
+    ChunkProgress {
+        /// The 1-indexed number of the chunk.
+        chunk: u64,
+        /// The number of blocks in the chunk.
+        blocks: u64,
+        /// The number of transactions in the chunk.
+        transactions: u64,
+        /// The gas used by all blocks of the chunk.
+        gas: u64,
+        /// How long it took to process the chunk, in milliseconds.
+        duration_ms: u64,
+        /// The block number the node is synced to after the chunk, if known.
+        checkpoint: Option<BlockNumber>,
+    },
+    /// A stage made progress.
+    StageProgress {
+        /// The stage that made progress.
+        stage: String,
+        /// The number of blocks the stage processed.
+        blocks: u64,
+        /// The new checkpoint of the stage.
+        checkpoint: BlockNumber,
+        /// The block number up to which the stage is running, if known.
+        target: Option<BlockNumber>,
+        /// Whether the stage reached its target.
+        done: bool,
+        /// How long the stage was running, in milliseconds.
+        duration_ms: u64,
+    },
+    /// A stage was unwound.
+    StageUnwound {
+        /// The stage that was unwound.
+        stage: String,
+        /// The new checkpoint of the stage.
+        checkpoint: BlockNumber,
+    },
+    /// Something went wrong, but the command keeps running.
+    Warning {
+        /// The stage the warning is related to, if any.
+        stage: Option<String>,
+        /// Human-readable description of the warning.
+        message: String,
+    },
+    /// The command finished.
+    Summary {
+        /// The total number of processed blocks.
+        blocks: u64,
+        /// The total number of processed transactions, if known.
+        transactions: Option<u64>,
+        /// The total gas used by the processed blocks, if known.
+        gas: Option<u64>,
+        /// How long the command was running, in milliseconds.
+        duration_ms: u64,
+        /// How the command finished.
+        status: RunStatus,
+    },
+}
+
+/// How a command finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    /// The command finished successfully.
+    Success,
+    /// The command finished, but not all of the input was processed.
+    Partial,
+    /// The command was interrupted.
+    Interrupted,
+    /// The command failed.
+    Failed,
+}
+
+/// Writes [`JsonEvent`]s as NDJSON.
+///
+/// The writer can be cloned to emit events from multiple tasks, every event is written as a whole
+/// line.
+#[derive(Debug)]
+pub struct JsonEventWriter<W = Stdout> {
+    writer: Arc<Mutex<W>>,
+}
+
+impl JsonEventWriter {
+    /// Creates a new writer that emits the events to stdout.
+    pub fn stdout() -> Self {
+        Self::new(io::stdout())
+    }
+}
+
+impl<W: Write> JsonEventWriter<W> {
+    /// Creates a new writer that emits the events to the given writer.
+    pub fn new(writer: W) -> Self {
+        Self { writer: Arc::new(Mutex::new(writer)) }
+    }
+
+    /// Writes the event as a single line and flushes the writer.
+    pub fn emit(&self, event: JsonEvent) -> io::Result<()> {
+        let line = JsonEventLine {
+            schema_version: SCHEMA_VERSION,
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            event,
+        };
+        let mut buf = serde_json::to_vec(&line)?;
+        buf.push(b'\n');
+
+        let mut writer = self.writer.lock().unwrap_or_else(|err| err.into_inner());
+        writer.write_all(&buf)?;
+        writer.flush()
+    }
+
+    /// Returns the inner writer if this is the last handle to it.
+    pub fn into_inner(self) -> Option<W> {
+        Arc::into_inner(self.writer)
+            .map(|writer| writer.into_inner().unwrap_or_else(|err| err.into_inner()))
+    }
+}
+
+impl<W> Clone for JsonEventWriter<W> {
+    fn clone(&self) -> Self {
+        Self { writer: Arc::clone(&self.writer) }
+    }
+}
+
+/// Returns the duration in milliseconds, as used by the [`JsonEvent`]s.
+pub fn duration_ms(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
+/// The stage that is currently running.
+#[derive(Debug)]
+struct RunningStage {
+    stage_id: StageId,
+    checkpoint: BlockNumber,
+    target: Option<BlockNumber>,
+    started_at: Instant,
+}
+
+/// Renders the pipeline events of the [`NodeEvent`] stream as [`JsonEvent`]s.
+///
+/// This is the machine-readable counterpart of [`handle_events`](crate::node::handle_events) and
+/// completes once the event stream ends.
+pub async fn handle_json_events<E, W>(mut events: E, writer: JsonEventWriter<W>)
+where
+    E: Stream<Item = NodeEvent> + Unpin,
+    W: Write,
+{
+    let mut current_stage: Option<RunningStage> = None;
+
+    while let Some(event) = events.next().await {
+        let NodeEvent::Pipeline(event) = event else { continue };
+
+        let event = match event {
+            PipelineEvent::Run { stage_id, checkpoint, target, .. } => {
+                current_stage = Some(RunningStage {
+                    stage_id,
+                    checkpoint: checkpoint.unwrap_or_default().block_number,
+                    target,
+                    started_at: Instant::now(),
+                });
+                continue
+            }
+            PipelineEvent::Ran { stage_id, result: ExecOutput { checkpoint, done }, .. } => {
+                let (previous, target, started_at) = match current_stage.take() {
+                    Some(stage) if stage.stage_id == stage_id => {
+                        (stage.checkpoint, stage.target, stage.started_at)
+                    }
+                    _ => (checkpoint.block_number, None, Instant::now()),
+                };
+                JsonEvent::StageProgress {
+                    stage: stage_id.to_string(),
+                    blocks: checkpoint.block_number.saturating_sub(previous),
+                    checkpoint: checkpoint.block_number,
+                    target,
+                    done,
+                    duration_ms: duration_ms(started_at.elapsed()),
+                }
+            }
+            PipelineEvent::Unwound { stage_id, result } => JsonEvent::StageUnwound {
+                stage: stage_id.to_string(),
+                checkpoint: result.checkpoint.block_number,
+            },
+            PipelineEvent::Error { stage_id } => JsonEvent::Warning {
+                stage: Some(stage_id.to_string()),
+                message: "stage encountered an error".to_string(),
+            },
+            _ => continue,
+        };
+
+        if let Err(err) = writer.emit(event) {
+            warn!(target: "reth::cli", %err, "Failed to write JSON event");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_stages::{PipelineStagesProgress, StageCheckpoint};
+
+    fn lines(buf: Vec<u8>) -> Vec<JsonEventLine> {
+        String::from_utf8(buf)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn renders_pipeline_events() {
+        let progress = PipelineStagesProgress { current: 1, total: 1 };
+        let events = futures::stream::iter([
+            PipelineEvent::Run {
+                pipeline_stages_progress: progress.clone(),
+                stage_id: StageId::Headers,
+                checkpoint: Some(StageCheckpoint::new(10)),
+                target: Some(20),
+            },
+            PipelineEvent::Ran {
+                pipeline_stages_progress: progress,
+                stage_id: StageId::Headers,
+                result: ExecOutput { checkpoint: StageCheckpoint::new(20), done: true },
+            },
+            PipelineEvent::Error { stage_id: StageId::Bodies },
+        ])
+        .map(NodeEvent::from);
+
+        let writer = JsonEventWriter::new(Vec::new());
+        handle_json_events(events, writer.clone()).await;
+
+        let lines = lines(writer.into_inner().unwrap());
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.schema_version == SCHEMA_VERSION));
+        let JsonEvent::StageProgress { stage, blocks, checkpoint, target, done, .. } =
+            &lines[0].event
+        else {
+            panic!("unexpected event: {:?}", lines[0].event)
+        };
+        assert_eq!(
+            (stage.as_str(), *blocks, *checkpoint, *target, *done),
+            ("Headers", 10, 20, Some(20), true)
+        );
+        assert_eq!(
+            lines[1].event,
+            JsonEvent::Warning {
+                stage: Some("Bodies".to_string()),
+                message: "stage encountered an error".to_string()
+            }
+        );
+    }
+}
@@ -9,4 +9,6 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 pub mod cl;
+pub mod health;
+pub mod json;
 pub mod node;
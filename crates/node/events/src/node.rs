@@ -1,6 +1,6 @@
 //! Support for handling events emitted by node components.
 
-use crate::cl::ConsensusLayerHealthEvent;
+use crate::{cl::ConsensusLayerHealthEvent, health::HealthCheck};
 use futures::Stream;
 use reth_beacon_consensus::{
     BeaconConsensusEngineEvent, ConsensusEngineLiveSyncProgress, ForkchoiceStatus,
@@ -16,6 +16,7 @@ use reth_static_file::StaticFileProducerEvent;
 use std::{
     fmt::{Display, Formatter},
     future::Future,
+    net::SocketAddr,
     pin::Pin,
     task::{Context, Poll},
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
@@ -49,6 +50,9 @@ struct NodeState<DB> {
     safe_block_hash: Option<B256>,
     /// Hash of finalized block last set by fork choice update
     finalized_block_hash: Option<B256>,
+    /// Health of the node components, reported in the "Status" log message if any component is
+    /// not healthy.
+    health: Option<HealthCheck>,
 }
 
 impl<DB> NodeState<DB> {
@@ -56,6 +60,7 @@ impl<DB> NodeState<DB> {
         db: DB,
         network: Option<NetworkHandle>,
         latest_block: Option<BlockNumber>,
+        health: Option<HealthCheck>,
     ) -> Self {
         Self {
             db,
@@ -66,6 +71,7 @@ impl<DB> NodeState<DB> {
             head_block_hash: None,
             safe_block_hash: None,
             finalized_block_hash: None,
+            health,
         }
     }
 
@@ -73,6 +79,14 @@ impl<DB> NodeState<DB> {
         self.network.as_ref().map(|net| net.num_connected_peers()).unwrap_or_default()
     }
 
+    /// The endpoint advertised to other peers.
+    fn advertised_endpoint(&self) -> Option<SocketAddr> {
+        self.network
+            .as_ref()
+            .and_then(|net| net.peers_handle().advertised_endpoint())
+            .map(|record| record.tcp_addr())
+    }
+
     /// Processes an event emitted by the pipeline
     fn handle_pipeline_event(&mut self, event: PipelineEvent) {
         match event {
@@ -156,6 +170,13 @@ impl<DB> NodeState<DB> {
                     self.latest_block = Some(checkpoint.block_number);
                 }
 
+                if let Some(sync) = self.health.as_ref().and_then(|health| health.sync()) {
+                    sync.on_progress(
+                        checkpoint.block_number,
+                        self.current_stage.as_ref().and_then(|stage| stage.target),
+                    );
+                }
+
                 if let Some(current_stage) = self.current_stage.as_mut() {
                     current_stage.checkpoint = checkpoint;
                     current_stage.entities_checkpoint = checkpoint.entities();
@@ -265,6 +286,9 @@ impl<DB> NodeState<DB> {
                         remaining_blocks,
                         target,
                     } => {
+                        if let Some(sync) = self.health.as_ref().and_then(|health| health.sync()) {
+                            sync.on_remaining_blocks(remaining_blocks);
+                        }
                         info!(
                             remaining_blocks,
                             target_block_hash=?target,
@@ -292,6 +316,9 @@ impl<DB> NodeState<DB> {
             BeaconConsensusEngineEvent::CanonicalChainCommitted(head, elapsed) => {
                 self.latest_block = Some(head.number);
                 self.latest_block_time = Some(head.timestamp);
+                if let Some(sync) = self.health.as_ref().and_then(|health| health.sync()) {
+                    sync.on_remaining_blocks(0);
+                }
 
                 info!(number=head.number, hash=?head.hash(), ?elapsed, "Canonical chain committed");
             }
@@ -436,16 +463,20 @@ impl From<StaticFileProducerEvent> for NodeEvent {
 
 /// Displays relevant information to the user from components of the node, and periodically
 /// displays the high-level status of the node.
+///
+/// If a [`HealthCheck`] is given, the sync progress is reported to it and all components that are
+/// not healthy are logged together with the status.
 pub async fn handle_events<E, DB>(
     network: Option<NetworkHandle>,
     latest_block_number: Option<BlockNumber>,
     events: E,
     db: DB,
+    health: Option<HealthCheck>,
 ) where
     E: Stream<Item = NodeEvent> + Unpin,
     DB: DatabaseMetadata + Database + 'static,
 {
-    let state = NodeState::new(db, network, latest_block_number);
+    let state = NodeState::new(db, network, latest_block_number, health);
 
     let start = tokio::time::Instant::now() + Duration::from_secs(3);
     let mut info_interval = tokio::time::interval_at(start, INFO_MESSAGE_INTERVAL);
@@ -477,6 +508,21 @@ where
 
         while this.info_interval.poll_tick(cx).is_ready() {
             let freelist = OptionalField(this.state.freelist());
+            let endpoint = OptionalField(this.state.advertised_endpoint());
+
+            if let Some(health) = &this.state.health {
+                let report = health.report();
+                for (component, health) in report.unhealthy() {
+                    warn!(
+                        target: "reth::cli",
+                        %component,
+                        status = %health.status,
+                        message = %OptionalField(health.message.as_deref()),
+                        ready = report.ready,
+                        "Component is not healthy"
+                    );
+                }
+            }
 
             if let Some(CurrentStage { stage_id, eta, checkpoint, entities_checkpoint, target }) =
                 &this.state.current_stage
@@ -491,6 +537,7 @@ where
                             target: "reth::cli",
                             connected_peers = this.state.num_connected_peers(),
                             %freelist,
+                            %endpoint,
                             stage = %stage_id,
                             checkpoint = checkpoint.block_number,
                             target = %OptionalField(*target),
@@ -504,6 +551,7 @@ where
                             target: "reth::cli",
                             connected_peers = this.state.num_connected_peers(),
                             %freelist,
+                            %endpoint,
                             stage = %stage_id,
                             checkpoint = checkpoint.block_number,
                             target = %OptionalField(*target),
@@ -516,6 +564,7 @@ where
                             target: "reth::cli",
                             connected_peers = this.state.num_connected_peers(),
                             %freelist,
+                            %endpoint,
                             stage = %stage_id,
                             checkpoint = checkpoint.block_number,
                             target = %OptionalField(*target),
@@ -528,6 +577,7 @@ where
                             target: "reth::cli",
                             connected_peers = this.state.num_connected_peers(),
                             %freelist,
+                            %endpoint,
                             stage = %stage_id,
                             checkpoint = checkpoint.block_number,
                             target = %OptionalField(*target),
@@ -545,6 +595,7 @@ where
                         target: "reth::cli",
                         connected_peers = this.state.num_connected_peers(),
                         %freelist,
+                        %endpoint,
                         %latest_block,
                         "Status"
                     );
@@ -554,6 +605,7 @@ where
                     target: "reth::cli",
                     connected_peers = this.state.num_connected_peers(),
                     %freelist,
+                    %endpoint,
                     "Status"
                 );
             }
@@ -9,7 +9,10 @@
 // The `optimism` feature must be enabled to use this crate.
 #![cfg(feature = "optimism")]
 
-use reth_consensus::{Consensus, ConsensusError, PostExecutionInput};
+use reth_consensus::{
+    ensure_timestamp_not_in_future, Consensus, ConsensusError, PostExecutionInput,
+    DEFAULT_FUTURE_TIMESTAMP_TOLERANCE,
+};
 use reth_consensus_common::validation::{
     validate_block_pre_execution, validate_header_extradata, validate_header_standalone,
 };
@@ -28,6 +31,8 @@ pub use validation::validate_block_post_execution;
 pub struct OptimismBeaconConsensus {
     /// Configuration
     chain_spec: Arc<ChainSpec>,
+    /// Number of seconds a pre-Bedrock header may be ahead of the local clock.
+    future_timestamp_tolerance: u64,
 }
 
 impl OptimismBeaconConsensus {
@@ -38,7 +43,13 @@ impl OptimismBeaconConsensus {
     /// If given chain spec is not optimism [`ChainSpec::is_optimism`]
     pub fn new(chain_spec: Arc<ChainSpec>) -> Self {
         assert!(chain_spec.is_optimism(), "optimism consensus only valid for optimism chains");
-        Self { chain_spec }
+        Self { chain_spec, future_timestamp_tolerance: DEFAULT_FUTURE_TIMESTAMP_TOLERANCE }
+    }
+
+    /// Sets the number of seconds a pre-Bedrock header may be ahead of the local clock.
+    pub const fn with_future_timestamp_tolerance(mut self, tolerance: u64) -> Self {
+        self.future_timestamp_tolerance = tolerance;
+        self
     }
 }
 
@@ -88,16 +99,15 @@ impl Consensus for OptimismBeaconConsensus {
             // mixHash is used instead of difficulty inside EVM
             // https://eips.ethereum.org/EIPS/eip-4399#using-mixhash-field-instead-of-difficulty
         } else {
-            // Check if timestamp is in the future. Clock can drift but this can be consensus issue.
+            // Check if timestamp is in the future. Clock can drift, so the header may be ahead of
+            // our clock by the configured tolerance.
             let present_timestamp =
                 SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
-
-            if header.exceeds_allowed_future_timestamp(present_timestamp) {
-                return Err(ConsensusError::TimestampIsInFuture {
-                    timestamp: header.timestamp,
-                    present_timestamp,
-                })
-            }
+            ensure_timestamp_not_in_future(
+                header.timestamp,
+                present_timestamp,
+                self.future_timestamp_tolerance,
+            )?;
         }
 
         Ok(())
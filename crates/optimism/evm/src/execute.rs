@@ -4,7 +4,8 @@ use crate::{l1::ensure_create2_deployer, OptimismBlockExecutionError, OptimismEv
 use reth_evm::{
     execute::{
         BatchExecutor, BlockExecutionError, BlockExecutionInput, BlockExecutionOutput,
-        BlockExecutorProvider, BlockValidationError, Executor, ProviderError,
+        BlockExecutorProvider, BlockValidationError, ExecutionCancellation, Executor,
+        ProviderError,
     },
     ConfigureEvm,
 };
@@ -13,7 +14,7 @@ use reth_primitives::{
     BlockNumber, BlockWithSenders, ChainSpec, Hardfork, Header, Receipt, Receipts, TxType,
     Withdrawals, U256,
 };
-use reth_provider::ExecutionOutcome;
+use reth_provider::{ExecutionOutcome, TransactionOutcome};
 use reth_prune_types::PruneModes;
 use reth_revm::{
     batch::{BlockBatchRecord, BlockExecutorStats},
@@ -101,13 +102,23 @@ struct OpEvmExecutor<EvmConfig> {
     evm_config: EvmConfig,
 }
 
+/// The output of the execution of the transactions of a block.
+#[derive(Debug)]
+struct OpExecuteOutput {
+    receipts: Vec<Receipt>,
+    gas_used: u64,
+    /// The index and outcome of each transaction that reverted or halted.
+    unsuccessful: Vec<(usize, TransactionOutcome)>,
+}
+
 impl<EvmConfig> OpEvmExecutor<EvmConfig>
 where
     EvmConfig: ConfigureEvm,
 {
     /// Executes the transactions in the block and returns the receipts.
     ///
-    /// This applies the pre-execution changes, and executes the transactions.
+    /// This applies the pre-execution changes, and executes the transactions. The execution is
+    /// aborted between transactions once the cancellation is set.
     ///
     /// # Note
     ///
@@ -116,7 +127,8 @@ where
         &self,
         block: &BlockWithSenders,
         mut evm: Evm<'_, Ext, &mut State<DB>>,
-    ) -> Result<(Vec<Receipt>, u64), BlockExecutionError>
+        cancellation: Option<&ExecutionCancellation>,
+    ) -> Result<OpExecuteOutput, BlockExecutionError>
     where
         DB: Database<Error = ProviderError>,
     {
@@ -142,7 +154,18 @@ where
 
         let mut cumulative_gas_used = 0;
         let mut receipts = Vec::with_capacity(block.body.len());
+        let mut unsuccessful = Vec::new();
         for (sender, transaction) in block.transactions_with_sender() {
+            // abort between transactions if the execution was cancelled
+            if !receipts.is_empty() &&
+                cancellation.map_or(false, ExecutionCancellation::is_cancelled)
+            {
+                return Err(BlockExecutionError::Cancelled {
+                    executed: receipts.len(),
+                    total: block.body.len(),
+                })
+            }
+
             // The sum of the transaction’s gas limit, Tg, and the gas utilized in this block prior,
             // must be no greater than the block’s gasLimit.
             let block_available_gas = block.header.gas_limit - cumulative_gas_used;
@@ -194,6 +217,10 @@ where
 
             evm.db_mut().commit(state);
 
+            if !result.is_success() {
+                unsuccessful.push((receipts.len(), TransactionOutcome::from(result.clone())));
+            }
+
             // append gas used
             cumulative_gas_used += result.gas_used();
 
@@ -217,7 +244,7 @@ where
         }
         drop(evm);
 
-        Ok((receipts, cumulative_gas_used))
+        Ok(OpExecuteOutput { receipts, gas_used: cumulative_gas_used, unsuccessful })
     }
 }
 
@@ -276,29 +303,31 @@ where
 
     /// Execute a single block and apply the state changes to the internal state.
     ///
-    /// Returns the receipts of the transactions in the block and the total gas used.
+    /// Returns the receipts of the transactions in the block, the total gas used and the outcomes
+    /// of the transactions that reverted or halted.
     ///
     /// Returns an error if execution fails.
     fn execute_without_verification(
         &mut self,
         block: &BlockWithSenders,
         total_difficulty: U256,
-    ) -> Result<(Vec<Receipt>, u64), BlockExecutionError> {
+        cancellation: Option<&ExecutionCancellation>,
+    ) -> Result<OpExecuteOutput, BlockExecutionError> {
         // 1. prepare state on new block
         self.on_new_block(&block.header);
 
         // 2. configure the evm and execute
         let env = self.evm_env_for_block(&block.header, total_difficulty);
 
-        let (receipts, gas_used) = {
+        let output = {
             let evm = self.executor.evm_config.evm_with_env(&mut self.state, env);
-            self.executor.execute_pre_and_transactions(block, evm)
+            self.executor.execute_pre_and_transactions(block, evm, cancellation)
         }?;
 
         // 3. apply post execution changes
         self.post_execution(block, total_difficulty)?;
 
-        Ok((receipts, gas_used))
+        Ok(output)
     }
 
     /// Apply settings before a new block is executed.
@@ -351,8 +380,9 @@ where
     ///
     /// State changes are committed to the database.
     fn execute(mut self, input: Self::Input<'_>) -> Result<Self::Output, Self::Error> {
-        let BlockExecutionInput { block, total_difficulty } = input;
-        let (receipts, gas_used) = self.execute_without_verification(block, total_difficulty)?;
+        let BlockExecutionInput { block, total_difficulty, cancellation } = input;
+        let OpExecuteOutput { receipts, gas_used, unsuccessful } =
+            self.execute_without_verification(block, total_difficulty, cancellation)?;
 
         // NOTE: we need to merge keep the reverts for the bundle retention
         self.state.merge_transitions(BundleRetention::Reverts);
@@ -362,6 +392,7 @@ where
             receipts,
             requests: vec![],
             gas_used,
+            unsuccessful,
         })
     }
 }
@@ -401,9 +432,9 @@ where
     type Error = BlockExecutionError;
 
     fn execute_and_verify_one(&mut self, input: Self::Input<'_>) -> Result<(), Self::Error> {
-        let BlockExecutionInput { block, total_difficulty } = input;
-        let (receipts, _gas_used) =
-            self.executor.execute_without_verification(block, total_difficulty)?;
+        let BlockExecutionInput { block, total_difficulty, cancellation } = input;
+        let OpExecuteOutput { receipts, .. } =
+            self.executor.execute_without_verification(block, total_difficulty, cancellation)?;
 
         validate_block_post_execution(block, self.executor.chain_spec(), &receipts)?;
 
@@ -22,7 +22,7 @@ use reth_payload_builder::{PayloadBuilderHandle, PayloadBuilderService};
 use reth_provider::CanonStateSubscriptions;
 use reth_tracing::tracing::{debug, info};
 use reth_transaction_pool::{
-    blobstore::DiskFileBlobStore, CoinbaseTipOrdering, TransactionPool,
+    blobstore::DiskFileBlobStore, CoinbaseTipOrdering, PersistentRejections, TransactionPool,
     TransactionValidationTaskExecutor,
 };
 
@@ -145,12 +145,18 @@ where
             )
             .map(OpTransactionValidator::new);
 
-        let transaction_pool = reth_transaction_pool::Pool::new(
+        let pool_config = ctx.pool_config();
+        let persistent_rejections = pool_config.persistent_rejections;
+        let mut transaction_pool = reth_transaction_pool::Pool::new(
             validator,
             CoinbaseTipOrdering::default(),
             blob_store,
-            ctx.pool_config(),
+            pool_config,
         );
+        if let Some(config) = persistent_rejections {
+            let persistent = PersistentRejections::open(data_dir.txpool_rejected(), config)?;
+            transaction_pool = transaction_pool.with_persistent_rejections(persistent);
+        }
         info!(target: "reth::cli", "Transaction pool initialized");
         let transactions_path = data_dir.txpool_transactions();
 
@@ -173,6 +179,15 @@ where
                 },
             );
 
+            // spawn the task evicting expired transactions
+            ctx.task_executor().spawn_critical(
+                "txpool eviction task",
+                reth_transaction_pool::maintain::MaintenanceTask::new(
+                    pool.clone(),
+                    reth_transaction_pool::maintain::DEFAULT_MAINTENANCE_INTERVAL,
+                ),
+            );
+
             // spawn the maintenance task
             ctx.task_executor().spawn_critical(
                 "txpool maintenance task",
@@ -320,6 +335,10 @@ where
     type Consensus = OptimismBeaconConsensus;
 
     async fn build_consensus(self, ctx: &BuilderContext<Node>) -> eyre::Result<Self::Consensus> {
-        Ok(OptimismBeaconConsensus::new(ctx.chain_spec()))
+        let mut consensus = OptimismBeaconConsensus::new(ctx.chain_spec());
+        if let Some(tolerance) = ctx.config().debug.future_timestamp_tolerance {
+            consensus = consensus.with_future_timestamp_tolerance(tolerance);
+        }
+        Ok(consensus)
     }
 }
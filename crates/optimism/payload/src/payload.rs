@@ -137,9 +137,7 @@ impl PayloadBuilderAttributes for OptimismPayloadBuilderAttributes {
             gas_limit: U256::from(parent.gas_limit),
             // calculate basefee based on parent block's gas usage
             basefee: U256::from(
-                parent
-                    .next_block_base_fee(chain_spec.base_fee_params_at_timestamp(self.timestamp()))
-                    .unwrap_or_default(),
+                chain_spec.next_block_base_fee(parent, self.timestamp()).unwrap_or_default(),
             ),
             // calculate excess gas based on parent block's blob gas usage
             blob_excess_gas_and_price,
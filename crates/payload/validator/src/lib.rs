@@ -8,6 +8,10 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+use reth_consensus::{
+    ensure_timestamp_not_in_future, BlockLimits, ConsensusError,
+    CONSENSUS_LAYER_FUTURE_TIMESTAMP_TOLERANCE,
+};
 use reth_primitives::{ChainSpec, SealedBlock};
 use reth_rpc_types::{engine::MaybeCancunPayloadFields, ExecutionPayload, PayloadError};
 use reth_rpc_types_compat::engine::payload::try_into_block;
@@ -18,12 +22,23 @@ use std::sync::Arc;
 pub struct ExecutionPayloadValidator {
     /// Chain spec to validate against.
     chain_spec: Arc<ChainSpec>,
+    /// Structural bounds that are checked before the payload is decoded.
+    limits: BlockLimits,
 }
 
 impl ExecutionPayloadValidator {
     /// Create a new validator.
+    ///
+    /// The [`BlockLimits`] are read from the chain spec, see [`BlockLimits::from_chain_spec`].
     pub fn new(chain_spec: Arc<ChainSpec>) -> Self {
-        Self { chain_spec }
+        let limits = BlockLimits::from_chain_spec(&chain_spec);
+        Self { chain_spec, limits }
+    }
+
+    /// Sets the [`BlockLimits`] to use.
+    pub const fn with_block_limits(mut self, limits: BlockLimits) -> Self {
+        self.limits = limits;
+        self
     }
 
     /// Returns the chain spec used by the validator.
@@ -78,6 +93,37 @@ impl ExecutionPayloadValidator {
         Ok(())
     }
 
+    /// Ensures that the payload is within the [`BlockLimits`]: the gas limit, the number of
+    /// transactions, the size of each transaction and the size of the block.
+    ///
+    /// This only looks at the raw payload, so it's meant to reject absurd payloads before
+    /// [`Self::ensure_well_formed_payload`] decodes the transactions.
+    pub fn ensure_payload_within_limits(
+        &self,
+        payload: &ExecutionPayload,
+    ) -> Result<(), ConsensusError> {
+        let payload = payload.as_v1();
+        self.limits.ensure_gas_limit(payload.gas_limit)?;
+        self.limits.ensure_transactions(payload.transactions.iter().map(|tx| tx.len()))
+    }
+
+    /// Ensures that the payload's timestamp is not ahead of the `present_timestamp` of our clock.
+    ///
+    /// Unlike headers received from the network, payloads of the consensus layer are checked with
+    /// [`CONSENSUS_LAYER_FUTURE_TIMESTAMP_TOLERANCE`], because the consensus layer is authoritative
+    /// for their timestamps.
+    pub const fn ensure_payload_timestamp(
+        &self,
+        payload: &ExecutionPayload,
+        present_timestamp: u64,
+    ) -> Result<(), ConsensusError> {
+        ensure_timestamp_not_in_future(
+            payload.as_v1().timestamp,
+            present_timestamp,
+            CONSENSUS_LAYER_FUTURE_TIMESTAMP_TOLERANCE,
+        )
+    }
+
     /// Ensures that the given payload does not violate any consensus rules that concern the block's
     /// layout, like:
     ///    - missing or invalid base fee
@@ -0,0 +1,75 @@
+#![allow(missing_docs)]
+use alloy_rlp::{Decodable, Encodable};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use pprof::criterion::{Output, PProfProfiler};
+use reth_primitives::{keccak256, Header, SealedHeader, B256, U256};
+
+/// Benchmarks hashing a batch of downloaded headers, like the downloader does when validating a
+/// response, with and without the cached encoding of the decoded headers.
+pub fn header_hash(c: &mut Criterion) {
+    let raw = (0..1024u64)
+        .map(|number| {
+            let header = Header {
+                number,
+                parent_hash: B256::with_last_byte(number as u8),
+                difficulty: U256::from(number),
+                gas_limit: 30_000_000,
+                base_fee_per_gas: Some(7),
+                ..Default::default()
+            };
+            alloy_rlp::encode(header)
+        })
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("Header hash");
+
+    group.bench_function("decode + re-encode hash", |b| {
+        b.iter(|| {
+            for raw in &raw {
+                let header = Header::decode(&mut raw.as_slice()).unwrap();
+                black_box(header.hash_slow());
+            }
+        })
+    });
+
+    group.bench_function("decode sealed", |b| {
+        b.iter(|| {
+            for raw in &raw {
+                let header = SealedHeader::decode(&mut raw.as_slice()).unwrap();
+                black_box(header.hash());
+            }
+        })
+    });
+
+    let sealed = raw
+        .iter()
+        .map(|raw| SealedHeader::decode(&mut raw.as_slice()).unwrap())
+        .collect::<Vec<_>>();
+
+    group.bench_function("re-encode uncached", |b| {
+        b.iter(|| {
+            for header in &sealed {
+                let mut out = Vec::with_capacity(header.length());
+                header.header().encode(&mut out);
+                black_box(keccak256(&out));
+            }
+        })
+    });
+
+    group.bench_function("re-encode cached", |b| {
+        b.iter(|| {
+            for header in &sealed {
+                black_box(keccak256(header.encoded()));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
+    targets = header_hash
+}
+criterion_main!(benches);
@@ -0,0 +1,176 @@
+//! Accumulator of canonical header hashes, for proving that a block is canonical.
+//!
+//! The accumulator is a Merkle mountain range (MMR) whose leaves are the hashes of the canonical
+//! headers, starting with the genesis header: the leaf with index `n` is the hash of block `n`.
+//!
+//! # Encoding
+//!
+//! The encoding is stable, external consumers verify proofs against it:
+//!
+//! - A node at height `h > 0` with index `i` covers the leaves `i * 2^h..(i + 1) * 2^h`, and its
+//!   hash is `keccak256(left || right)` of its two children at height `h - 1`, i.e. the nodes with
+//!   the indices `2i` and `2i + 1`. The nodes at height `0` are the leaves.
+//! - The peaks of an accumulator of `n` leaves are the nodes of maximal height that cover its
+//!   leaves, ordered from the lowest leaves to the highest, i.e. there's one peak for every bit set
+//!   in `n`, from the highest to the lowest bit.
+//! - The root of an accumulator of `n` leaves is `keccak256(n || peak_0 || .. || peak_k)`, where
+//!   `n` is encoded as 8 big-endian bytes.
+
+use crate::{keccak256, BlockNumber, B256};
+use serde::{Deserialize, Serialize};
+
+/// Position of a node in the accumulator, the height of the node and its index at that height.
+pub type NodePosition = (u8, u64);
+
+/// Proof that a header hash is the leaf of an accumulator, i.e. that the block is canonical.
+///
+/// See [`verify_canonical_proof`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MmrProof {
+    /// The number of the proven block, which is the index of its leaf.
+    pub block_number: BlockNumber,
+    /// The number of leaves of the accumulator the proof is for, i.e. the number of the highest
+    /// accumulated block plus one.
+    pub leaf_count: u64,
+    /// The siblings on the path from the leaf to its peak, starting with the sibling leaf.
+    pub siblings: Vec<B256>,
+    /// All peaks of the accumulator, see the [module docs](self).
+    pub peaks: Vec<B256>,
+}
+
+/// Returns the hash of the parent node of `left` and `right`.
+pub fn merge_nodes(left: &B256, right: &B256) -> B256 {
+    let mut buf = [0u8; 64];
+    buf[..32].copy_from_slice(left.as_slice());
+    buf[32..].copy_from_slice(right.as_slice());
+    keccak256(buf)
+}
+
+/// Returns the positions of the peaks of an accumulator of `leaf_count` leaves, ordered from the
+/// lowest leaves to the highest.
+pub fn peak_positions(leaf_count: u64) -> Vec<NodePosition> {
+    let mut peaks = Vec::new();
+    let mut covered = 0u64;
+    for height in (0..u64::BITS as u8).rev() {
+        let width = 1u64 << height;
+        if leaf_count & width != 0 {
+            peaks.push((height, covered >> height));
+            covered += width;
+        }
+    }
+    peaks
+}
+
+/// Returns the root of an accumulator of `leaf_count` leaves with the given peaks.
+pub fn accumulator_root(leaf_count: u64, peaks: &[B256]) -> B256 {
+    let mut buf = Vec::with_capacity(8 + peaks.len() * 32);
+    buf.extend_from_slice(&leaf_count.to_be_bytes());
+    for peak in peaks {
+        buf.extend_from_slice(peak.as_slice());
+    }
+    keccak256(buf)
+}
+
+/// Verifies that `block_hash` is the hash of the canonical block of the `proof`, against the
+/// `root` of the accumulator with [`MmrProof::leaf_count`] leaves.
+///
+/// This only depends on the [encoding](self) of the accumulator, and can be used by external
+/// consumers that don't trust the node that created the proof.
+pub fn verify_canonical_proof(root: B256, block_hash: B256, proof: &MmrProof) -> bool {
+    if proof.block_number >= proof.leaf_count {
+        return false
+    }
+
+    let positions = peak_positions(proof.leaf_count);
+    if positions.len() != proof.peaks.len() {
+        return false
+    }
+    let Some(peak) =
+        positions.iter().position(|(height, index)| proof.block_number >> height == *index)
+    else {
+        return false
+    };
+    if proof.siblings.len() != positions[peak].0 as usize {
+        return false
+    }
+
+    let mut node = block_hash;
+    let mut index = proof.block_number;
+    for sibling in &proof.siblings {
+        node =
+            if index & 1 == 0 { merge_nodes(&node, sibling) } else { merge_nodes(sibling, &node) };
+        index >>= 1;
+    }
+
+    node == proof.peaks[peak] && accumulator_root(proof.leaf_count, &proof.peaks) == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds all nodes of the accumulator of the leaves, by height.
+    fn nodes(leaves: &[B256]) -> Vec<Vec<B256>> {
+        let mut nodes = vec![leaves.to_vec()];
+        while nodes.last().unwrap().len() > 1 {
+            let level =
+                nodes.last().unwrap().chunks_exact(2).map(|pair| merge_nodes(&pair[0], &pair[1]));
+            nodes.push(level.collect());
+        }
+        nodes
+    }
+
+    fn prove(leaves: &[B256], block_number: u64) -> (B256, MmrProof) {
+        let nodes = nodes(leaves);
+        let leaf_count = leaves.len() as u64;
+        let positions = peak_positions(leaf_count);
+        let peaks = positions
+            .iter()
+            .map(|(height, index)| nodes[*height as usize][*index as usize])
+            .collect::<Vec<_>>();
+        let (height, _) = positions.iter().find(|(h, i)| block_number >> h == *i).unwrap();
+        let siblings =
+            (0..*height).map(|h| nodes[h as usize][((block_number >> h) ^ 1) as usize]).collect();
+        (
+            accumulator_root(leaf_count, &peaks),
+            MmrProof { block_number, leaf_count, siblings, peaks },
+        )
+    }
+
+    #[test]
+    fn peaks() {
+        assert_eq!(peak_positions(0), vec![]);
+        assert_eq!(peak_positions(1), vec![(0, 0)]);
+        assert_eq!(peak_positions(6), vec![(2, 0), (1, 2)]);
+        assert_eq!(peak_positions(11), vec![(3, 0), (1, 4), (0, 10)]);
+    }
+
+    #[test]
+    fn root_encoding() {
+        let peaks = [B256::repeat_byte(1), B256::repeat_byte(2)];
+        let mut encoded = 3u64.to_be_bytes().to_vec();
+        encoded.extend_from_slice(&[1; 32]);
+        encoded.extend_from_slice(&[2; 32]);
+        assert_eq!(accumulator_root(3, &peaks), keccak256(encoded));
+        assert_eq!(merge_nodes(&peaks[0], &peaks[1]), keccak256([[1u8; 32], [2u8; 32]].concat()));
+    }
+
+    #[test]
+    fn verify_proofs() {
+        let leaves = (0..13u64).map(|n| keccak256(n.to_be_bytes())).collect::<Vec<_>>();
+        for leaf_count in 1..=leaves.len() {
+            for block_number in 0..leaf_count as u64 {
+                let (root, proof) = prove(&leaves[..leaf_count], block_number);
+                let hash = leaves[block_number as usize];
+                assert!(verify_canonical_proof(root, hash, &proof));
+
+                // a different hash, height or accumulator doesn't verify
+                assert!(!verify_canonical_proof(root, B256::ZERO, &proof));
+                let other = MmrProof { block_number: block_number ^ 1, ..proof.clone() };
+                assert!(!verify_canonical_proof(root, hash, &other));
+                let other = MmrProof { leaf_count: leaf_count as u64 + 1, ..proof.clone() };
+                assert!(!verify_canonical_proof(root, hash, &other));
+            }
+        }
+    }
+}
@@ -76,6 +76,7 @@ pub static MAINNET: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
             b256!("649bbc62d0e31342afea4e5cd82d4049e7e1ee912fc0889aa790803be39038c5"),
         )),
         base_fee_params: BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
+        fee_floors: FeeFloors::default(),
         prune_delete_limit: 3500,
     }
     .into()
@@ -118,6 +119,7 @@ pub static GOERLI: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
             b256!("649bbc62d0e31342afea4e5cd82d4049e7e1ee912fc0889aa790803be39038c5"),
         )),
         base_fee_params: BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
+        fee_floors: FeeFloors::default(),
         prune_delete_limit: 1700,
     }
     .into()
@@ -164,6 +166,7 @@ pub static SEPOLIA: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
             b256!("649bbc62d0e31342afea4e5cd82d4049e7e1ee912fc0889aa790803be39038c5"),
         )),
         base_fee_params: BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
+        fee_floors: FeeFloors::default(),
         prune_delete_limit: 1700,
     }
     .into()
@@ -205,6 +208,7 @@ pub static HOLESKY: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
             b256!("649bbc62d0e31342afea4e5cd82d4049e7e1ee912fc0889aa790803be39038c5"),
         )),
         base_fee_params: BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
+        fee_floors: FeeFloors::default(),
         prune_delete_limit: 1700,
     }
     .into()
@@ -493,6 +497,55 @@ impl From<Vec<(Hardfork, BaseFeeParams)>> for ForkBaseFeeParams {
     }
 }
 
+/// The key of the [`FeeFloors`] in the extra fields of the genesis config.
+pub const FEE_FLOORS_GENESIS_KEY: &str = "feeFloors";
+
+/// Protocol minimums of the fees of a chain.
+///
+/// Mainnet has none: the base fee can decay towards zero and transactions aren't required to pay
+/// a priority fee.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeFloors {
+    /// The minimum base fee of a block.
+    ///
+    /// The base fee computed for a child block is clamped to it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_base_fee: Option<u64>,
+    /// The minimum priority fee a transaction must pay at the base fee of the block that includes
+    /// it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_priority_fee: Option<u64>,
+}
+
+impl FeeFloors {
+    /// Reads the floors from the `feeFloors` object of the genesis config, e.g.
+    ///
+    /// ```json
+    /// "feeFloors": { "minBaseFee": 100000000, "minPriorityFee": 1000000 }
+    /// ```
+    ///
+    /// A missing or invalid object has no floors.
+    pub fn from_genesis(genesis: &Genesis) -> Self {
+        genesis
+            .config
+            .extra_fields
+            .get(FEE_FLOORS_GENESIS_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Clamps the given base fee to the minimum base fee.
+    pub fn clamp_base_fee(&self, base_fee: u64) -> u64 {
+        self.min_base_fee.map_or(base_fee, |min| base_fee.max(min))
+    }
+
+    /// Returns the minimum priority fee, zero if there is none.
+    pub fn min_priority_fee(&self) -> u64 {
+        self.min_priority_fee.unwrap_or_default()
+    }
+}
+
 /// An Ethereum chain specification.
 ///
 /// A chain specification describes:
@@ -530,6 +583,10 @@ pub struct ChainSpec {
     /// The parameters that configure how a block's base fee is computed
     pub base_fee_params: BaseFeeParamsKind,
 
+    /// The protocol minimums of the base fee and the priority fee
+    #[serde(default)]
+    pub fee_floors: FeeFloors,
+
     /// The delete limit for pruner, per block. In the actual pruner run it will be multiplied by
     /// the amount of blocks between pruner runs to account for the difference in amount of new
     /// data coming in.
@@ -547,6 +604,7 @@ impl Default for ChainSpec {
             hardforks: Default::default(),
             deposit_contract: Default::default(),
             base_fee_params: BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
+            fee_floors: Default::default(),
             prune_delete_limit: MAINNET.prune_delete_limit,
         }
     }
@@ -694,6 +752,18 @@ impl ChainSpec {
         }
     }
 
+    /// Calculates the base fee of the child of the given parent block at the given timestamp.
+    ///
+    /// The EIP-1559 base fee is clamped to the [minimum base fee](FeeFloors::min_base_fee) of the
+    /// chain.
+    ///
+    /// Returns `None` if the parent has no base fee.
+    pub fn next_block_base_fee(&self, parent: &Header, timestamp: u64) -> Option<u64> {
+        parent
+            .next_block_base_fee(self.base_fee_params_at_timestamp(timestamp))
+            .map(|base_fee| self.fee_floors.clamp_base_fee(base_fee))
+    }
+
     /// Get the [`BaseFeeParams`] for the chain at the given block number
     pub fn base_fee_params_at_block(&self, block_number: u64) -> BaseFeeParams {
         match self.base_fee_params {
@@ -1073,6 +1143,8 @@ impl From<Genesis> for ChainSpec {
         // have the deployment block in the genesis file, so we use block zero. We use the same
         // deposit topic as the mainnet contract if we have the deposit contract address in the
         // genesis json.
+        let fee_floors = FeeFloors::from_genesis(&genesis);
+
         let deposit_contract = genesis.config.deposit_contract_address.map(|address| {
             DepositContract { address, block: 0, topic: MAINNET_DEPOSIT_CONTRACT.topic }
         });
@@ -1086,6 +1158,7 @@ impl From<Genesis> for ChainSpec {
             deposit_contract,
             #[cfg(feature = "optimism")]
             base_fee_params: optimism_genesis_info.base_fee_params,
+            fee_floors,
             ..Default::default()
         }
     }
@@ -1332,9 +1405,11 @@ impl ChainSpecBuilder {
                 }
             })
         };
+        let genesis = self.genesis.expect("The genesis is required");
         ChainSpec {
             chain: self.chain.expect("The chain is required"),
-            genesis: self.genesis.expect("The genesis is required"),
+            fee_floors: FeeFloors::from_genesis(&genesis),
+            genesis,
             genesis_hash: None,
             hardforks: self.hardforks,
             paris_block_and_final_difficulty,
@@ -3246,6 +3321,78 @@ Post-merge hard forks (timestamp based):
         assert!(matches!(deserialized_chain_spec, AllGenesisFormats::Reth(_)))
     }
 
+    /// Returns the base fees of consecutive blocks on top of a parent with a base fee of 1 gwei,
+    /// where each block uses the given amount of gas.
+    fn next_base_fees(chain_spec: &ChainSpec, gas_used: &[u64]) -> Vec<u64> {
+        let mut parent = Header {
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        gas_used
+            .iter()
+            .map(|gas_used| {
+                parent.gas_used = *gas_used;
+                let base_fee = chain_spec.next_block_base_fee(&parent, parent.timestamp).unwrap();
+                parent.base_fee_per_gas = Some(base_fee);
+                base_fee
+            })
+            .collect()
+    }
+
+    #[test]
+    fn mainnet_has_no_fee_floors() {
+        assert_eq!(MAINNET.fee_floors, FeeFloors::default());
+        assert_eq!(MAINNET.fee_floors.min_priority_fee(), 0);
+
+        // empty blocks decay the base fee without a clamp
+        assert_eq!(
+            next_base_fees(&MAINNET, &[0; 4]),
+            vec![875_000_000, 765_625_000, 669_921_875, 586_181_641]
+        );
+
+        let parent =
+            Header { gas_limit: 30_000_000, base_fee_per_gas: Some(9), ..Default::default() };
+        assert_eq!(MAINNET.next_block_base_fee(&parent, 0), Some(8));
+        assert_eq!(
+            MAINNET.next_block_base_fee(&parent, 0),
+            parent.next_block_base_fee(BaseFeeParams::ethereum())
+        );
+        assert_eq!(MAINNET.next_block_base_fee(&Header::default(), 0), None);
+    }
+
+    #[test]
+    fn clamps_base_fee_to_fee_floor() {
+        let genesis: Genesis = serde_json::from_str(
+            r#"{
+                "config": {
+                    "chainId": 1337,
+                    "londonBlock": 0,
+                    "feeFloors": { "minBaseFee": 800000000, "minPriorityFee": 1000000 }
+                },
+                "difficulty": "0x0",
+                "gasLimit": "0x1c9c380",
+                "alloc": {}
+            }"#,
+        )
+        .unwrap();
+        let chain_spec = ChainSpec::from(genesis.clone());
+        assert_eq!(
+            chain_spec.fee_floors,
+            FeeFloors { min_base_fee: Some(800_000_000), min_priority_fee: Some(1_000_000) }
+        );
+        assert_eq!(
+            ChainSpecBuilder::default().chain(1337.into()).genesis(genesis).build().fee_floors,
+            chain_spec.fee_floors
+        );
+
+        // empty blocks decay the base fee until it reaches the floor, full blocks raise it again
+        assert_eq!(
+            next_base_fees(&chain_spec, &[0, 0, 0, 0, 30_000_000, 30_000_000]),
+            vec![875_000_000, 800_000_000, 800_000_000, 800_000_000, 900_000_000, 1_012_500_000]
+        );
+    }
+
     #[test]
     fn check_fork_id_chainspec_with_fork_condition_never() {
         let spec = ChainSpec {
@@ -17,7 +17,12 @@ use bytes::BufMut;
 use proptest::prelude::*;
 use reth_codecs::{add_arbitrary_tests, derive_arbitrary, main_codec, Compact};
 use serde::{Deserialize, Serialize};
-use std::{mem, ops::Deref};
+use std::{
+    hash::{Hash, Hasher},
+    mem,
+    ops::Deref,
+    sync::OnceLock,
+};
 
 /// Errors that can occur during header sanity checks.
 #[derive(Debug, PartialEq, Eq)]
@@ -276,17 +281,21 @@ impl Header {
 
     /// Seal the header with a known hash.
     ///
-    /// WARNING: This method does not perform validation whether the hash is correct.
+    /// WARNING: This method does not perform validation whether the hash is correct, unless the
+    /// `verify-seals` feature is enabled in a debug build. Use [`Header::seal_slow`] if the hash
+    /// isn't known from a trusted source, e.g. the `HeaderNumbers` table.
     #[inline]
-    pub const fn seal(self, hash: B256) -> SealedHeader {
-        SealedHeader { header: self, hash }
+    #[track_caller]
+    pub fn seal(self, hash: B256) -> SealedHeader {
+        SealedHeader::seal_with_hash(self, hash)
     }
 
     /// Calculate hash and seal the Header so that it can't be changed.
+    ///
+    /// The RLP encoding used to compute the hash is retained by the [`SealedHeader`].
     #[inline]
     pub fn seal_slow(self) -> SealedHeader {
-        let hash = self.hash_slow();
-        self.seal(hash)
+        SealedHeader::seal_slow(self)
     }
 
     /// Calculate a heuristic for the in-memory size of the [Header].
@@ -605,21 +614,68 @@ pub enum HeaderValidationError {
 
 /// A [`Header`] that is sealed at a precalculated hash, use [`SealedHeader::unseal()`] if you want
 /// to modify header.
-#[main_codec(no_arbitrary)]
+///
+/// If the header was decoded or sealed with [`SealedHeader::seal_slow`], the RLP encoding of the
+/// header is retained, so that re-encoding it is a copy. The cached encoding is not part of
+/// equality, hashing, serialization or the compact encoding.
 #[add_arbitrary_tests(rlp, compact)]
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SealedHeader {
     /// Locked Header hash.
     hash: BlockHash,
     /// Locked Header fields.
     header: Header,
+    /// The RLP encoding of the header, if known.
+    #[serde(skip)]
+    encoded: OnceLock<Bytes>,
 }
 
 impl SealedHeader {
     /// Creates the sealed header with the corresponding block hash.
+    ///
+    /// See [`SealedHeader::seal_with_hash`].
+    #[inline]
+    #[track_caller]
+    pub fn new(header: Header, hash: BlockHash) -> Self {
+        Self::seal_with_hash(header, hash)
+    }
+
+    /// Seals the header with a known hash.
+    ///
+    /// WARNING: This method does not perform validation whether the hash is correct, unless the
+    /// `verify-seals` feature is enabled. Then debug builds recompute the hash and panic on a
+    /// mismatch, which catches callers that pair a header with the hash of another header.
     #[inline]
-    pub const fn new(header: Header, hash: BlockHash) -> Self {
-        Self { header, hash }
+    #[track_caller]
+    pub fn seal_with_hash(header: Header, hash: BlockHash) -> Self {
+        #[cfg(feature = "verify-seals")]
+        debug_assert_eq!(
+            hash,
+            header.hash_slow(),
+            "trusted seal of block {} doesn't match the header hash",
+            header.number
+        );
+        Self { header, hash, encoded: OnceLock::new() }
+    }
+
+    /// Encodes the header once to calculate its hash and seals it, retaining the encoding.
+    #[inline]
+    pub fn seal_slow(header: Header) -> Self {
+        let encoded = Bytes::from(alloy_rlp::encode(&header));
+        let hash = keccak256(&encoded);
+        Self { header, hash, encoded: OnceLock::from(encoded) }
+    }
+
+    /// Returns the RLP encoding of the header.
+    ///
+    /// The header is encoded on first access if the encoding isn't cached already.
+    pub fn encoded(&self) -> Bytes {
+        self.encoded.get_or_init(|| Bytes::from(alloy_rlp::encode(&self.header))).clone()
+    }
+
+    /// Returns true if the RLP encoding of the header is cached.
+    pub fn has_cached_encoding(&self) -> bool {
+        self.encoded.get().is_some()
     }
 
     /// Returns the sealed Header fields.
@@ -637,7 +693,8 @@ impl SealedHeader {
     /// Updates the block header.
     #[cfg(any(test, feature = "test-utils"))]
     pub fn set_header(&mut self, header: Header) {
-        self.header = header
+        self.header = header;
+        self.encoded = OnceLock::new();
     }
 
     /// Updates the block hash.
@@ -649,25 +706,29 @@ impl SealedHeader {
     /// Updates the parent block hash.
     #[cfg(any(test, feature = "test-utils"))]
     pub fn set_parent_hash(&mut self, hash: BlockHash) {
-        self.header.parent_hash = hash
+        self.header.parent_hash = hash;
+        self.encoded = OnceLock::new();
     }
 
     /// Updates the block number.
     #[cfg(any(test, feature = "test-utils"))]
     pub fn set_block_number(&mut self, number: BlockNumber) {
         self.header.number = number;
+        self.encoded = OnceLock::new();
     }
 
     /// Updates the block state root.
     #[cfg(any(test, feature = "test-utils"))]
     pub fn set_state_root(&mut self, state_root: B256) {
         self.header.state_root = state_root;
+        self.encoded = OnceLock::new();
     }
 
     /// Updates the block difficulty.
     #[cfg(any(test, feature = "test-utils"))]
     pub fn set_difficulty(&mut self, difficulty: U256) {
         self.header.difficulty = difficulty;
+        self.encoded = OnceLock::new();
     }
 
     /// Checks the gas limit for consistency between parent and self headers.
@@ -798,18 +859,16 @@ impl SealedHeader {
         if chain_spec.fork(Hardfork::London).active_at_block(self.number) {
             let base_fee = self.base_fee_per_gas.ok_or(HeaderValidationError::BaseFeeMissing)?;
 
-            let expected_base_fee = if chain_spec
-                .fork(Hardfork::London)
-                .transitions_at_block(self.number)
-            {
-                constants::EIP1559_INITIAL_BASE_FEE
-            } else {
-                // This BaseFeeMissing will not happen as previous blocks are checked to have
-                // them.
-                parent
-                    .next_block_base_fee(chain_spec.base_fee_params_at_timestamp(self.timestamp))
-                    .ok_or(HeaderValidationError::BaseFeeMissing)?
-            };
+            let expected_base_fee =
+                if chain_spec.fork(Hardfork::London).transitions_at_block(self.number) {
+                    constants::EIP1559_INITIAL_BASE_FEE
+                } else {
+                    // This BaseFeeMissing will not happen as previous blocks are checked to have
+                    // them.
+                    chain_spec
+                        .next_block_base_fee(parent, self.timestamp)
+                        .ok_or(HeaderValidationError::BaseFeeMissing)?
+                };
             if expected_base_fee != base_fee {
                 return Err(HeaderValidationError::BaseFeeDiff(GotExpected {
                     expected: expected_base_fee,
@@ -915,9 +974,59 @@ impl Default for SealedHeader {
     }
 }
 
+impl PartialEq for SealedHeader {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && self.header == other.header
+    }
+}
+
+impl Eq for SealedHeader {}
+
+impl Hash for SealedHeader {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash.hash(state);
+        self.header.hash(state);
+    }
+}
+
+impl Compact for SealedHeader {
+    fn to_compact<B>(self, buf: &mut B) -> usize
+    where
+        B: BufMut + AsMut<[u8]>,
+    {
+        // Same layout as the derived implementation of a struct with `hash` and `header` fields.
+        let mut len = self.hash.to_compact(buf);
+        len += self.header.to_compact(buf);
+        len
+    }
+
+    fn from_compact(buf: &[u8], _len: usize) -> (Self, &[u8]) {
+        let (hash, buf) = B256::from_compact(buf, buf.len());
+        let (header, buf) = Header::from_compact(buf, buf.len());
+        (Self::seal_with_hash(header, hash), buf)
+    }
+}
+
+impl SealedHeader {
+    /// Used bytes by the bitflags of the compact encoding, which has none.
+    pub const fn bitflag_encoded_bytes() -> usize {
+        0
+    }
+}
+
 impl Encodable for SealedHeader {
     fn encode(&self, out: &mut dyn BufMut) {
-        self.header.encode(out);
+        match self.encoded.get() {
+            Some(encoded) => out.put_slice(encoded),
+            None => self.header.encode(out),
+        }
+    }
+
+    fn length(&self) -> usize {
+        match self.encoded.get() {
+            Some(encoded) => encoded.len(),
+            None => self.header.length(),
+        }
     }
 }
 
@@ -929,14 +1038,15 @@ impl Decodable for SealedHeader {
         // decode the header from temp buffer
         let header = Header::decode(b)?;
 
-        // hash the consumed bytes, the rlp encoded header
+        // hash the consumed bytes, the rlp encoded header, and retain them
         let consumed = started_len - b.len();
-        let hash = keccak256(&buf[..consumed]);
+        let encoded = Bytes::copy_from_slice(&buf[..consumed]);
+        let hash = keccak256(&encoded);
 
         // update original buffer
         *buf = *b;
 
-        Ok(Self { header, hash })
+        Ok(Self { header, hash, encoded: OnceLock::from(encoded) })
     }
 }
 
@@ -1400,4 +1510,83 @@ mod tests {
             })
         );
     }
+
+    #[cfg(feature = "verify-seals")]
+    mod verify_seals {
+        use crate::{Header, SealedHeader, B256};
+
+        #[test]
+        fn trusted_seal_with_matching_hash() {
+            let header = Header { number: 1, gas_limit: 30_000_000, ..Default::default() };
+            let hash = header.hash_slow();
+            assert_eq!(header.clone().seal(hash), header.seal_slow());
+        }
+
+        #[test]
+        #[cfg_attr(debug_assertions, should_panic(expected = "trusted seal of block 1"))]
+        fn trusted_seal_with_wrong_hash() {
+            let header = Header { number: 1, ..Default::default() };
+            let _ = SealedHeader::new(header, B256::ZERO);
+        }
+
+        #[test]
+        #[cfg_attr(debug_assertions, should_panic(expected = "trusted seal of block 2"))]
+        fn trusted_seal_with_hash_of_other_header() {
+            let parent = Header { number: 1, ..Default::default() };
+            let child = Header { number: 2, parent_hash: parent.hash_slow(), ..Default::default() };
+            let _ = child.seal(parent.hash_slow());
+        }
+    }
+
+    mod encoding_cache {
+        use crate::{block::valid_header_strategy, SealedHeader};
+        use alloy_rlp::Decodable;
+        use proptest::prelude::*;
+        use reth_codecs::Compact;
+
+        proptest! {
+            #[test]
+            fn cached_encoding_matches_fresh_encoding(header in valid_header_strategy()) {
+                let fresh = alloy_rlp::encode(&header);
+
+                let sealed = header.clone().seal_slow();
+                prop_assert!(sealed.has_cached_encoding());
+                prop_assert_eq!(sealed.encoded().as_ref(), fresh.as_slice());
+                prop_assert_eq!(alloy_rlp::encode(&sealed), fresh.clone());
+
+                let decoded = SealedHeader::decode(&mut fresh.as_slice()).unwrap();
+                prop_assert!(decoded.has_cached_encoding());
+                prop_assert_eq!(&decoded, &sealed);
+                prop_assert_eq!(alloy_rlp::encode(&decoded), fresh.clone());
+
+                let lazy = header.seal(sealed.hash());
+                prop_assert!(!lazy.has_cached_encoding());
+                prop_assert_eq!(&lazy, &sealed);
+                prop_assert_eq!(lazy.encoded().as_ref(), fresh.as_slice());
+                prop_assert!(lazy.has_cached_encoding());
+            }
+
+            #[test]
+            fn compact_roundtrip_ignores_cached_encoding(header in valid_header_strategy()) {
+                let sealed = header.seal_slow();
+                prop_assert!(sealed.has_cached_encoding());
+
+                let mut buf = Vec::new();
+                let len = sealed.clone().to_compact(&mut buf);
+                prop_assert_eq!(len, buf.len());
+
+                // the layout of the derived implementation, the hash followed by the header
+                let mut expected = Vec::new();
+                sealed.hash().to_compact(&mut expected);
+                sealed.header().clone().to_compact(&mut expected);
+                prop_assert_eq!(&buf, &expected);
+
+                let (decoded, rest) = SealedHeader::from_compact(&buf, len);
+                prop_assert!(rest.is_empty());
+                prop_assert!(!decoded.has_cached_encoding());
+                prop_assert_eq!(&decoded, &sealed);
+                prop_assert_eq!(decoded.encoded(), sealed.encoded());
+            }
+        }
+    }
 }
@@ -186,7 +186,7 @@ impl ReceiptWithBloom {
 
     #[inline]
     const fn as_encoder(&self) -> ReceiptWithBloomEncoder<'_> {
-        ReceiptWithBloomEncoder { receipt: &self.receipt, bloom: &self.bloom }
+        ReceiptWithBloomEncoder { receipt: &self.receipt, bloom: &self.bloom, post_state: None }
     }
 }
 
@@ -423,12 +423,24 @@ pub struct ReceiptWithBloomRef<'a> {
     pub bloom: Bloom,
     /// Main receipt body
     pub receipt: &'a Receipt,
+    /// State root after the transaction, which receipts of blocks before Byzantium contain
+    /// instead of the status.
+    pub post_state: Option<B256>,
 }
 
 impl<'a> ReceiptWithBloomRef<'a> {
     /// Create new [`ReceiptWithBloomRef`]
     pub const fn new(receipt: &'a Receipt, bloom: Bloom) -> Self {
-        Self { receipt, bloom }
+        Self { receipt, bloom, post_state: None }
+    }
+
+    /// Sets the state root after the transaction, which is encoded instead of the status.
+    ///
+    /// This is required to encode receipts of blocks before Byzantium, see
+    /// [EIP-658](https://eips.ethereum.org/EIPS/eip-658).
+    pub const fn with_post_state(mut self, post_state: B256) -> Self {
+        self.post_state = Some(post_state);
+        self
     }
 
     /// Encode receipt with or without the header data.
@@ -438,7 +450,11 @@ impl<'a> ReceiptWithBloomRef<'a> {
 
     #[inline]
     const fn as_encoder(&self) -> ReceiptWithBloomEncoder<'_> {
-        ReceiptWithBloomEncoder { receipt: self.receipt, bloom: &self.bloom }
+        ReceiptWithBloomEncoder {
+            receipt: self.receipt,
+            bloom: &self.bloom,
+            post_state: self.post_state.as_ref(),
+        }
     }
 }
 
@@ -454,13 +470,15 @@ impl<'a> Encodable for ReceiptWithBloomRef<'a> {
 impl<'a> From<&'a Receipt> for ReceiptWithBloomRef<'a> {
     fn from(receipt: &'a Receipt) -> Self {
         let bloom = receipt.bloom_slow();
-        ReceiptWithBloomRef { receipt, bloom }
+        ReceiptWithBloomRef { receipt, bloom, post_state: None }
     }
 }
 
 struct ReceiptWithBloomEncoder<'a> {
     bloom: &'a Bloom,
     receipt: &'a Receipt,
+    /// Encoded instead of the status if set.
+    post_state: Option<&'a B256>,
 }
 
 impl<'a> ReceiptWithBloomEncoder<'a> {
@@ -468,7 +486,10 @@ impl<'a> ReceiptWithBloomEncoder<'a> {
     fn receipt_rlp_header(&self) -> alloy_rlp::Header {
         let mut rlp_head = alloy_rlp::Header { list: true, payload_length: 0 };
 
-        rlp_head.payload_length += self.receipt.success.length();
+        rlp_head.payload_length += match self.post_state {
+            Some(post_state) => post_state.length(),
+            None => self.receipt.success.length(),
+        };
         rlp_head.payload_length += self.receipt.cumulative_gas_used.length();
         rlp_head.payload_length += self.bloom.length();
         rlp_head.payload_length += self.receipt.logs.length();
@@ -489,7 +510,10 @@ impl<'a> ReceiptWithBloomEncoder<'a> {
     /// Encodes the receipt data.
     fn encode_fields(&self, out: &mut dyn BufMut) {
         self.receipt_rlp_header().encode(out);
-        self.receipt.success.encode(out);
+        match self.post_state {
+            Some(post_state) => post_state.encode(out),
+            None => self.receipt.success.encode(out),
+        }
         self.receipt.cumulative_gas_used.encode(out);
         self.bloom.encode(out);
         self.receipt.logs.encode(out);
@@ -604,6 +628,31 @@ mod tests {
         assert_eq!(data, expected);
     }
 
+    // Receipts before Byzantium contain the state root after the transaction instead of the status
+    #[test]
+    fn encode_pre_byzantium_receipt() {
+        let expected = hex!("f90128a096a8e009d2b88b1483e6941e6812e32263b05683fac202abc622a3e31aed1957825208b9010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000c0");
+
+        let receipt = Receipt {
+            tx_type: TxType::Legacy,
+            success: true,
+            cumulative_gas_used: 21_000,
+            ..Default::default()
+        };
+        let post_state = b256!("96a8e009d2b88b1483e6941e6812e32263b05683fac202abc622a3e31aed1957");
+        let receipt = ReceiptWithBloomRef::new(&receipt, Bloom::ZERO).with_post_state(post_state);
+
+        let mut data = vec![];
+        receipt.encode(&mut data);
+        assert_eq!(receipt.length(), expected.len());
+        assert_eq!(data, expected);
+
+        // without the state root, the status is encoded
+        let mut data = vec![];
+        ReceiptWithBloomRef::new(receipt.receipt, Bloom::ZERO).encode(&mut data);
+        assert_eq!(&data[3..5], &[0x01, 0x82]);
+    }
+
     // Test vector from: https://eips.ethereum.org/EIPS/eip-2481
     #[test]
     fn decode_legacy_receipt() {
@@ -6,6 +6,7 @@ mod receipts_by_logs;
 mod sender_recovery;
 mod set;
 mod storage_history;
+mod transaction_history;
 mod transaction_lookup;
 mod transactions;
 
@@ -27,6 +28,7 @@ pub use set::SegmentSet;
 use std::{fmt::Debug, ops::RangeInclusive};
 pub use storage_history::StorageHistory;
 use tracing::error;
+pub use transaction_history::TransactionHistory;
 pub use transaction_lookup::TransactionLookup;
 pub use transactions::Transactions;
 
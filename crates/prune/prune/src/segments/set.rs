@@ -1,6 +1,6 @@
 use crate::segments::{
     AccountHistory, Receipts, ReceiptsByLogs, Segment, SenderRecovery, StorageHistory,
-    TransactionLookup,
+    TransactionHistory, TransactionLookup,
 };
 use reth_db_api::database::Database;
 use reth_prune_types::PruneModes;
@@ -44,6 +44,7 @@ impl<DB: Database> SegmentSet<DB> {
             receipts,
             account_history,
             storage_history,
+            transaction_history,
             receipts_log_filter,
         } = prune_modes;
 
@@ -63,6 +64,8 @@ impl<DB: Database> SegmentSet<DB> {
             .segment_opt(account_history.map(AccountHistory::new))
             // Storage history
             .segment_opt(storage_history.map(StorageHistory::new))
+            // Transaction history
+            .segment_opt(transaction_history.map(TransactionHistory::new))
     }
 }
 
@@ -0,0 +1,221 @@
+use crate::{
+    segments::{PruneInput, PruneOutput, PruneOutputCheckpoint, Segment},
+    PrunerError,
+};
+use reth_db::tables;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
+    database::Database,
+    models::StoredTxNumber,
+    transaction::DbTxMut,
+};
+use reth_provider::DatabaseProviderRW;
+use reth_prune_types::{PruneInterruptReason, PruneMode, PruneProgress, PruneSegment};
+use tracing::{instrument, trace};
+
+#[derive(Debug)]
+pub struct TransactionHistory {
+    mode: PruneMode,
+}
+
+impl TransactionHistory {
+    pub const fn new(mode: PruneMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl<DB: Database> Segment<DB> for TransactionHistory {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::TransactionHistory
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        input: PruneInput,
+    ) -> Result<PruneOutput, PrunerError> {
+        let tx_range_end = match input.get_next_tx_num_range(provider)? {
+            Some(range) => *range.end(),
+            None => {
+                trace!(target: "pruner", "No transaction history to prune");
+                return Ok(PruneOutput::done())
+            }
+        };
+
+        let mut limiter = input.limiter;
+        if limiter.is_limit_reached() {
+            return Ok(PruneOutput::not_done(
+                PruneInterruptReason::new(&limiter),
+                input.previous_checkpoint.map(|checkpoint| checkpoint.into()),
+            ))
+        }
+
+        // The transactions of an address are sorted by their number, so the entries to prune are
+        // the first ones of each address.
+        let mut cursor = provider.tx_ref().cursor_dup_write::<tables::AddressTransactions>()?;
+        let mut pruned = 0;
+        let mut last_pruned = None;
+        let mut entry = cursor.first()?;
+        let done = loop {
+            let Some((address, StoredTxNumber(tx_number))) = entry else { break true };
+            if limiter.is_limit_reached() {
+                break false
+            }
+
+            entry = if tx_number <= tx_range_end {
+                cursor.delete_current()?;
+                limiter.increment_deleted_entries_count();
+                pruned += 1;
+                last_pruned = Some((address, tx_number));
+                cursor.next()?
+            } else {
+                cursor.next_no_dup()?
+            };
+        };
+        trace!(target: "pruner", %pruned, ?last_pruned, %done, "Pruned transaction history");
+
+        // The table is sorted by address, so the entries that are left can't be described by a
+        // transaction number. The checkpoint only moves once all addresses are pruned, and the
+        // next run walks the addresses again, which is cheap for those without entries to prune.
+        let checkpoint = if done {
+            Some(PruneOutputCheckpoint {
+                block_number: Some(input.to_block),
+                tx_number: Some(tx_range_end),
+            })
+        } else {
+            input.previous_checkpoint.map(|checkpoint| checkpoint.into())
+        };
+
+        Ok(PruneOutput { progress: PruneProgress::new(done, &limiter), pruned, checkpoint })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{PruneInput, Segment, TransactionHistory};
+    use reth_db::tables;
+    use reth_primitives::B256;
+    use reth_provider::{PruneCheckpointReader, TransactionHistoryWriter};
+    use reth_prune_types::{
+        PruneInterruptReason, PruneLimiter, PruneMode, PruneProgress, PruneSegment,
+    };
+    use reth_stages::test_utils::{StorageKind, TestStageDB};
+    use reth_testing_utils::{generators, generators::random_block_range};
+
+    #[test]
+    fn prune() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 1..=10, B256::ZERO, 2..3);
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        let provider = db.factory.provider_rw().unwrap();
+        provider.index_transaction_history(1..=10, false).unwrap();
+        provider.commit().unwrap();
+        let entries = db.table::<tables::AddressTransactions>().unwrap();
+
+        let to_block = 5;
+        let last_pruned_tx =
+            blocks.iter().take(to_block as usize).map(|block| block.body.len()).sum::<usize>()
+                as u64 -
+                1;
+
+        let input =
+            PruneInput { previous_checkpoint: None, to_block, limiter: PruneLimiter::default() };
+        let provider = db.factory.provider_rw().unwrap();
+        let result = TransactionHistory::new(PruneMode::Before(to_block + 1))
+            .prune(&provider, input)
+            .unwrap();
+        provider.commit().unwrap();
+
+        assert_eq!(result.progress, PruneProgress::Finished);
+        assert_eq!(result.checkpoint.unwrap().tx_number, Some(last_pruned_tx));
+
+        let remaining = db.table::<tables::AddressTransactions>().unwrap();
+        assert_eq!(result.pruned, entries.len() - remaining.len());
+        assert_eq!(
+            remaining,
+            entries
+                .into_iter()
+                .filter(|(_, tx_number)| tx_number.0 > last_pruned_tx)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn prune_with_limit() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 1..=10, B256::ZERO, 2..3);
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        let provider = db.factory.provider_rw().unwrap();
+        provider.index_transaction_history(1..=10, false).unwrap();
+        provider.commit().unwrap();
+        let entries = db.table::<tables::AddressTransactions>().unwrap();
+
+        let to_block = 5;
+        let last_pruned_tx =
+            blocks.iter().take(to_block as usize).map(|block| block.body.len()).sum::<usize>()
+                as u64 -
+                1;
+        let expected = entries
+            .iter()
+            .filter(|(_, tx_number)| tx_number.0 > last_pruned_tx)
+            .cloned()
+            .collect::<Vec<_>>();
+        let deleted_entries_limit = 3;
+        assert!(entries.len() - expected.len() > deleted_entries_limit);
+
+        let prune_mode = PruneMode::Before(to_block + 1);
+        let segment = TransactionHistory::new(prune_mode);
+        let mut runs = 0;
+        loop {
+            runs += 1;
+            let previous_checkpoint = db
+                .factory
+                .provider()
+                .unwrap()
+                .get_prune_checkpoint(PruneSegment::TransactionHistory)
+                .unwrap();
+            let input = PruneInput {
+                previous_checkpoint,
+                to_block,
+                limiter: PruneLimiter::default().set_deleted_entries_limit(deleted_entries_limit),
+            };
+
+            let provider = db.factory.provider_rw().unwrap();
+            let result = segment.prune(&provider, input).unwrap();
+            assert!(result.pruned <= deleted_entries_limit);
+            if let Some(checkpoint) = result.checkpoint {
+                segment
+                    .save_checkpoint(&provider, checkpoint.as_prune_checkpoint(prune_mode))
+                    .unwrap();
+            }
+            provider.commit().unwrap();
+
+            if result.progress.is_finished() {
+                assert_eq!(result.checkpoint.unwrap().tx_number, Some(last_pruned_tx));
+                break
+            }
+
+            // the checkpoint doesn't move until all addresses are pruned
+            assert_eq!(
+                result.progress,
+                PruneProgress::HasMoreData(PruneInterruptReason::DeletedEntriesLimitReached)
+            );
+            assert_eq!(result.pruned, deleted_entries_limit);
+            assert_eq!(result.checkpoint, None);
+        }
+
+        assert!(runs > 1);
+        assert_eq!(db.table::<tables::AddressTransactions>().unwrap(), expected);
+    }
+}
@@ -5,7 +5,10 @@ use reth_execution_errors::BlockExecutionError;
 use reth_primitives::{Receipt, Receipts, Request, Requests};
 use reth_prune_types::{PruneMode, PruneModes, PruneSegmentError, MINIMUM_PRUNING_DISTANCE};
 use revm::db::states::bundle_state::BundleRetention;
-use std::time::Duration;
+use std::{
+    collections::{BTreeMap, HashSet},
+    time::Duration,
+};
 use tracing::debug;
 
 /// Takes care of:
@@ -29,10 +32,17 @@ pub struct BlockBatchRecord {
     /// A transaction may have zero or more requests, so the length of the inner vector is not
     /// guaranteed to be the same as the number of transactions.
     requests: Vec<Requests>,
-    /// Memoized address pruning filter.
+    /// The receipts log filter of the prune modes grouped by block for the current tip, see
+    /// [`ReceiptsLogPruneConfig::group_by_block`](reth_prune_types::ReceiptsLogPruneConfig).
+    ///
+    /// Computed for the first prunable block after the prune modes or the tip change.
+    contract_log_pruner: Option<BTreeMap<BlockNumber, Vec<Address>>>,
+    /// Memoized address pruning filter, and the next block of the
+    /// [`Self::contract_log_pruner`] whose addresses are not included yet.
+    ///
     /// Empty implies that there is going to be addresses to include in the filter in a future
     /// block. None means there isn't any kind of configuration.
-    pruning_address_filter: Option<(u64, Vec<Address>)>,
+    pruning_address_filter: Option<(BlockNumber, HashSet<Address>)>,
     /// First block will be initialized to `None`
     /// and be set to the block number of first block executed.
     first_block: Option<BlockNumber>,
@@ -49,6 +59,7 @@ impl BlockBatchRecord {
     /// Set prune modes.
     pub fn set_prune_modes(&mut self, prune_modes: PruneModes) {
         self.prune_modes = prune_modes;
+        self.reset_contract_log_pruner();
     }
 
     /// Set the first block number of the batch.
@@ -64,6 +75,16 @@ impl BlockBatchRecord {
     /// Set tip - highest known block number.
     pub fn set_tip(&mut self, tip: BlockNumber) {
         self.tip = Some(tip);
+        self.reset_contract_log_pruner();
+    }
+
+    /// Discards the grouped receipts log filter, so it's computed again for the next prunable
+    /// block. The addresses that are already in the address filter are kept.
+    fn reset_contract_log_pruner(&mut self) {
+        self.contract_log_pruner = None;
+        if let Some((next_block, _)) = &mut self.pruning_address_filter {
+            *next_block = 0;
+        }
     }
 
     /// Returns the tip of the batch if known.
@@ -148,21 +169,40 @@ impl BlockBatchRecord {
             return Ok(())
         }
 
-        let contract_log_pruner = self.prune_modes.receipts_log_filter.group_by_block(tip, None)?;
+        // Nothing to filter by
+        if self.prune_modes.receipts_log_filter.is_empty() && self.pruning_address_filter.is_none()
+        {
+            return Ok(())
+        }
+
+        if self.contract_log_pruner.is_none() {
+            let grouped = self.prune_modes.receipts_log_filter.group_by_block(tip, None)?;
+            self.contract_log_pruner = Some(
+                grouped
+                    .into_iter()
+                    .map(|(block, addresses)| (block, addresses.into_iter().copied().collect()))
+                    .collect(),
+            );
+        }
+        let contract_log_pruner = self.contract_log_pruner.as_ref().expect("computed above");
 
         if !contract_log_pruner.is_empty() {
-            let (prev_block, filter) = self.pruning_address_filter.get_or_insert((0, Vec::new()));
-            for (_, addresses) in contract_log_pruner.range(*prev_block..=block_number) {
-                filter.extend(addresses.iter().copied());
+            let (next_block, filter) =
+                self.pruning_address_filter.get_or_insert_with(|| (0, HashSet::new()));
+            // The addresses up to the previous block are already included
+            if block_number >= *next_block {
+                for (_, addresses) in contract_log_pruner.range(*next_block..=block_number) {
+                    filter.extend(addresses.iter().copied());
+                }
+                *next_block = block_number + 1;
             }
         }
 
-        for receipt in receipts.iter_mut() {
-            let inner_receipt = receipt.as_ref().expect("receipts have not been pruned");
-
-            // If there is an address_filter, and it does not contain any of the
-            // contract addresses, then remove this receipts
-            if let Some((_, filter)) = &self.pruning_address_filter {
+        // If there is an address_filter, and it does not contain any of the
+        // contract addresses, then remove this receipts
+        if let Some((_, filter)) = &self.pruning_address_filter {
+            for receipt in receipts.iter_mut() {
+                let inner_receipt = receipt.as_ref().expect("receipts have not been pruned");
                 if !inner_receipt.logs.iter().any(|log| filter.contains(&log.address)) {
                     receipt.take();
                 }
@@ -208,3 +248,99 @@ impl BlockExecutorStats {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Bytes, Log};
+    use reth_prune_types::ReceiptsLogPruneConfig;
+
+    /// Receipts with a log of each of the addresses, and a receipt without logs.
+    fn block_receipts(block_number: BlockNumber, addresses: &[Address]) -> Vec<Receipt> {
+        addresses
+            .iter()
+            .map(|address| vec![Log::new_unchecked(*address, vec![], Bytes::new())])
+            .chain(std::iter::once(Vec::new()))
+            .map(|logs| Receipt { cumulative_gas_used: block_number, logs, ..Default::default() })
+            .collect()
+    }
+
+    /// Prunes the receipts of each block like [`BlockBatchRecord::prune_receipts`] did before the
+    /// filter was advanced incrementally, i.e. by grouping the log filter for every block.
+    fn prune_naive(
+        log_filter: &ReceiptsLogPruneConfig,
+        blocks: &[(BlockNumber, BlockNumber, Vec<Receipt>)],
+    ) -> Vec<Vec<Option<Receipt>>> {
+        let mut filter: Option<Vec<Address>> = None;
+        blocks
+            .iter()
+            .map(|(block_number, tip, receipts)| {
+                let mut receipts = receipts.iter().cloned().map(Some).collect::<Vec<_>>();
+                if !PruneMode::Distance(MINIMUM_PRUNING_DISTANCE).should_prune(*block_number, *tip)
+                {
+                    return receipts
+                }
+
+                let grouped = log_filter.group_by_block(*tip, None).unwrap();
+                if !grouped.is_empty() {
+                    let filter = filter.get_or_insert_with(Vec::new);
+                    for (_, addresses) in grouped.range(0..=*block_number) {
+                        filter.extend(addresses.iter().copied());
+                    }
+                }
+                if let Some(filter) = &filter {
+                    for receipt in &mut receipts {
+                        let logs = &receipt.as_ref().unwrap().logs;
+                        if !logs.iter().any(|log| filter.contains(&log.address)) {
+                            receipt.take();
+                        }
+                    }
+                }
+                receipts
+            })
+            .collect()
+    }
+
+    #[test]
+    fn prune_receipts_by_logs_incrementally() {
+        let addresses = (0..5).map(|_| Address::random()).collect::<Vec<_>>();
+        let log_filter = ReceiptsLogPruneConfig(BTreeMap::from([
+            (addresses[0], PruneMode::Before(750)),
+            (addresses[1], PruneMode::Before(750)),
+            (addresses[2], PruneMode::Distance(300)),
+            (addresses[3], PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)),
+        ]));
+
+        for log_filter in [log_filter, ReceiptsLogPruneConfig::default()] {
+            // the tip moves in the middle of the batch
+            let blocks = (700..1000)
+                .map(|block_number| {
+                    let tip = if block_number < 850 { 1000 } else { 1100 };
+                    (block_number, tip, block_receipts(block_number, &addresses))
+                })
+                .collect::<Vec<_>>();
+
+            let mut record = BlockBatchRecord::new(PruneModes {
+                receipts_log_filter: log_filter.clone(),
+                ..PruneModes::none()
+            });
+            record.set_first_block(700);
+            for (_, tip, receipts) in &blocks {
+                if record.tip() != Some(*tip) {
+                    record.set_tip(*tip);
+                }
+                record.save_receipts(receipts.clone()).unwrap();
+            }
+
+            let expected = prune_naive(&log_filter, &blocks);
+            assert_eq!(record.receipts().receipt_vec, expected);
+            // receipts are pruned, but not all of them
+            let retained = expected.iter().flatten().filter(|receipt| receipt.is_some()).count();
+            if log_filter.is_empty() {
+                assert_eq!(retained, blocks.len() * (addresses.len() + 1));
+            } else {
+                assert!(retained > 0 && retained < blocks.len() * (addresses.len() + 1));
+            }
+        }
+    }
+}
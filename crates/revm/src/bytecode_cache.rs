@@ -0,0 +1,173 @@
+//! A bytecode cache shared between executors.
+//!
+//! Bytecode is immutable by its hash, so the cache never needs to be invalidated and can be shared
+//! by all executors of the process, e.g. the executors the blockchain tree creates per block.
+
+use parking_lot::Mutex;
+use reth_metrics::{
+    metrics::{Counter, Gauge},
+    Metrics,
+};
+use reth_primitives::B256;
+use revm::{interpreter::analysis::to_analysed, primitives::Bytecode};
+use schnellru::{LruMap, Unlimited};
+use std::{fmt, sync::Arc};
+
+/// Default byte budget of the [`BytecodeCache`], 64 MiB.
+pub const DEFAULT_BYTECODE_CACHE_SIZE: usize = 64 * 1024 * 1024;
+
+/// A bounded cache of analyzed bytecode by code hash.
+///
+/// Entries are evicted in least recently used order once the total size of the cached code
+/// exceeds the byte budget. The code is analyzed before it's cached, so the jump table analysis
+/// is only done once per contract. Cloning the cache is cheap and all clones share the entries.
+#[derive(Clone)]
+pub struct BytecodeCache {
+    inner: Arc<Mutex<BytecodeCacheInner>>,
+    metrics: BytecodeCacheMetrics,
+}
+
+impl BytecodeCache {
+    /// Creates a new cache that holds up to `max_size` bytes of code.
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(BytecodeCacheInner {
+                codes: LruMap::new(Unlimited),
+                size: 0,
+                max_size,
+            })),
+            metrics: Default::default(),
+        }
+    }
+
+    /// Returns the cached bytecode for the given code hash.
+    ///
+    /// Cloning the analyzed bytecode is cheap, since the code and jump table are reference
+    /// counted.
+    pub fn get(&self, code_hash: &B256) -> Option<Bytecode> {
+        let code = self.inner.lock().codes.get(code_hash).cloned();
+        if code.is_some() {
+            self.metrics.hits.increment(1);
+        } else {
+            self.metrics.misses.increment(1);
+        }
+        code
+    }
+
+    /// Analyzes the bytecode, inserts it into the cache and returns the analyzed bytecode.
+    ///
+    /// Code larger than the byte budget is returned without being cached.
+    pub fn insert(&self, code_hash: B256, code: Bytecode) -> Bytecode {
+        let code = to_analysed(code);
+        let code_size = code.len();
+
+        let mut inner = self.inner.lock();
+        if code_size > inner.max_size {
+            return code
+        }
+        if let Some(replaced) = inner.codes.remove(&code_hash) {
+            inner.size -= replaced.len();
+        }
+        while inner.size + code_size > inner.max_size {
+            let Some((_, evicted)) = inner.codes.pop_oldest() else { break };
+            inner.size -= evicted.len();
+        }
+        inner.codes.insert(code_hash, code.clone());
+        inner.size += code_size;
+
+        self.metrics.entries.set(inner.codes.len() as f64);
+        self.metrics.size.set(inner.size as f64);
+
+        code
+    }
+
+    /// Returns the number of cached contracts.
+    pub fn len(&self) -> usize {
+        self.inner.lock().codes.len()
+    }
+
+    /// Returns `true` if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the total size of the cached code.
+    pub fn size(&self) -> usize {
+        self.inner.lock().size
+    }
+}
+
+impl Default for BytecodeCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_BYTECODE_CACHE_SIZE)
+    }
+}
+
+impl fmt::Debug for BytecodeCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.inner.lock();
+        f.debug_struct("BytecodeCache")
+            .field("entries", &inner.codes.len())
+            .field("size", &inner.size)
+            .field("max_size", &inner.max_size)
+            .finish()
+    }
+}
+
+struct BytecodeCacheInner {
+    /// The analyzed bytecode by code hash.
+    codes: LruMap<B256, Bytecode, Unlimited>,
+    /// Total size of the cached code.
+    size: usize,
+    /// The byte budget of the cache.
+    max_size: usize,
+}
+
+/// Metrics for the bytecode cache.
+#[derive(Metrics, Clone)]
+#[metrics(scope = "executor.bytecode_cache")]
+struct BytecodeCacheMetrics {
+    /// The number of lookups that were served from the cache.
+    hits: Counter,
+    /// The number of lookups that were not served from the cache.
+    misses: Counter,
+    /// The number of cached contracts.
+    entries: Gauge,
+    /// The total size of the cached code.
+    size: Gauge,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::Bytes;
+
+    fn code(len: usize) -> Bytecode {
+        Bytecode::new_raw(Bytes::from(vec![0x5b; len]))
+    }
+
+    #[test]
+    fn evicts_least_recently_used_by_size() {
+        let (a, b, c) = (B256::with_last_byte(1), B256::with_last_byte(2), B256::with_last_byte(3));
+        let cache = BytecodeCache::new(100);
+
+        assert!(cache.get(&a).is_none());
+        // the cached code is analyzed
+        assert!(matches!(cache.insert(a, code(40)), Bytecode::LegacyAnalyzed(_)));
+        cache.insert(b, code(40));
+        assert!(matches!(cache.get(&a), Some(Bytecode::LegacyAnalyzed(_))));
+        assert_eq!(cache.size(), 80);
+
+        // `b` is the least recently used entry
+        cache.insert(c, code(40));
+        assert!(cache.get(&b).is_none());
+        assert!(cache.get(&a).is_some());
+        assert!(cache.get(&c).is_some());
+        assert_eq!(cache.size(), 80);
+
+        // code exceeding the budget is not cached
+        cache.insert(b, code(101));
+        assert!(cache.get(&b).is_none());
+        assert_eq!(cache.len(), 2);
+    }
+}
@@ -1,4 +1,7 @@
-use crate::primitives::alloy_primitives::{BlockNumber, StorageKey, StorageValue};
+use crate::{
+    bytecode_cache::BytecodeCache,
+    primitives::alloy_primitives::{BlockNumber, StorageKey, StorageValue},
+};
 use reth_primitives::{Account, Address, B256, KECCAK_EMPTY, U256};
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use revm::{
@@ -63,13 +66,22 @@ impl<T: reth_storage_api::StateProvider> EvmStateProvider for T {
 
 /// A [Database] and [`DatabaseRef`] implementation that uses [`EvmStateProvider`] as the underlying
 /// data source.
+///
+/// If a [`BytecodeCache`] is configured, bytecode is served from the cache before it's loaded from
+/// the provider.
 #[derive(Debug, Clone)]
-pub struct StateProviderDatabase<DB>(pub DB);
+pub struct StateProviderDatabase<DB>(pub DB, Option<BytecodeCache>);
 
 impl<DB> StateProviderDatabase<DB> {
     /// Create new State with generic `StateProvider`.
     pub const fn new(db: DB) -> Self {
-        Self(db)
+        Self(db, None)
+    }
+
+    /// Serves bytecode from the given cache and adds the bytecode loaded from the provider to it.
+    pub fn with_bytecode_cache(mut self, cache: BytecodeCache) -> Self {
+        self.1 = Some(cache);
+        self
     }
 
     /// Consume State and return inner `StateProvider`.
@@ -146,7 +158,18 @@ impl<DB: EvmStateProvider> DatabaseRef for StateProviderDatabase<DB> {
     ///
     /// Returns `Ok` with the bytecode if found, or the default bytecode otherwise.
     fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
-        Ok(self.bytecode_by_hash(code_hash)?.unwrap_or_default().0)
+        let Some(cache) = &self.1 else {
+            return Ok(self.bytecode_by_hash(code_hash)?.unwrap_or_default().0)
+        };
+
+        if let Some(code) = cache.get(&code_hash) {
+            return Ok(code)
+        }
+        // missing code is not cached
+        Ok(self
+            .bytecode_by_hash(code_hash)?
+            .map(|code| cache.insert(code_hash, code.0))
+            .unwrap_or_default())
     }
 
     /// Retrieves the storage value at a specific index for a given address.
@@ -13,6 +13,8 @@ pub mod database;
 
 pub mod batch;
 
+pub mod bytecode_cache;
+
 /// State changes that are not related to transactions.
 pub mod state_change;
 
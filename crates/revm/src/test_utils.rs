@@ -2,11 +2,13 @@ use reth_primitives::{
     keccak256, proofs::AccountProof, Account, Address, BlockNumber, Bytecode, Bytes, StorageKey,
     B256, U256,
 };
-use reth_storage_api::{AccountReader, BlockHashReader, StateProvider, StateRootProvider};
+use reth_storage_api::{
+    AccountReader, BlockHashReader, CanonicalHashesIter, StateProvider, StateRootProvider,
+};
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie::updates::TrieUpdates;
 use revm::db::BundleState;
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::Range};
 
 /// Mock state for testing
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
@@ -50,17 +52,13 @@ impl BlockHashReader for StateProviderTest {
         Ok(self.block_hash.get(&number).cloned())
     }
 
-    fn canonical_hashes_range(
+    fn canonical_hashes_iter(
         &self,
-        start: BlockNumber,
-        end: BlockNumber,
-    ) -> ProviderResult<Vec<B256>> {
-        let range = start..end;
-        Ok(self
-            .block_hash
-            .iter()
-            .filter_map(|(block, hash)| range.contains(block).then_some(*hash))
-            .collect())
+        range: Range<BlockNumber>,
+    ) -> ProviderResult<CanonicalHashesIter<'_>> {
+        Ok(Box::new(
+            range.filter_map(|number| self.block_hash.get(&number).map(|hash| Ok((number, *hash)))),
+        ))
     }
 }
 
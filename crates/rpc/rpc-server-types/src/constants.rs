@@ -64,6 +64,14 @@ pub mod gas_oracle {
 
     /// The default minimum gas price, under which the sample will be ignored
     pub const DEFAULT_IGNORE_GAS_PRICE: U256 = U256::from_limbs([2u64, 0, 0, 0]);
+
+    /// The priority fee suggested by the gas price oracle if no block yields a usable sample and
+    /// neither a default nor a minimum price is configured (1 gwei)
+    pub const DEFAULT_FALLBACK_GAS_PRICE: U256 = U256::from_limbs([1_000_000_000u64, 0, 0, 0]);
+
+    /// The minimum number of transactions a block must contain to be sampled by the gas price
+    /// oracle
+    pub const DEFAULT_MIN_BLOCK_TRANSACTIONS: usize = 1;
 }
 
 /// Cache specific constants
@@ -740,6 +740,11 @@ pub struct EthCallBundleTransactionResult {
     /// Contains the return data if the transaction reverted
     #[serde(skip_serializing_if = "Option::is_none")]
     pub revert: Option<Bytes>,
+    /// Contains the error if the transaction reverted or halted, e.g. `out of gas`
+    ///
+    /// Note: a halted transaction has no `revert` data
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 mod u256_numeric_string {
@@ -59,6 +59,10 @@ where
         let (cfg, block_env, at) = self.evm_env_at(at).await?;
 
         self.on_blocking_task(|this| async move {
+            if at.is_pending() {
+                let state = this.pending_state_with_overrides(state_override)?;
+                return this.estimate_gas_with(cfg, block_env, request, state, None)
+            }
             let state = this.state_at(at)?;
             this.estimate_gas_with(cfg, block_env, request, state, state_override)
         })
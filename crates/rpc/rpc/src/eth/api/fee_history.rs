@@ -370,14 +370,15 @@ impl FeeHistoryEntry {
         }
     }
 
-    /// Returns the base fee for the next block according to the EIP-1559 spec.
+    /// Returns the base fee for the next block according to the EIP-1559 spec, clamped to the
+    /// [minimum base fee](reth_primitives::FeeFloors::min_base_fee) of the chain.
     pub fn next_block_base_fee(&self, chain_spec: &ChainSpec) -> u64 {
-        calc_next_block_base_fee(
+        chain_spec.fee_floors.clamp_base_fee(calc_next_block_base_fee(
             self.gas_used as u128,
             self.gas_limit as u128,
             self.base_fee_per_gas as u128,
             chain_spec.base_fee_params_at_timestamp(self.timestamp),
-        ) as u64
+        ) as u64)
     }
 
     /// Returns the blob fee for the next block according to the EIP-4844 spec.
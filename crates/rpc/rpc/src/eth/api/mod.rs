@@ -9,6 +9,7 @@ use crate::eth::{
     cache::EthStateCache,
     error::{EthApiError, EthResult},
     gas_oracle::GasPriceOracle,
+    revm_utils::into_provider_state_overrides,
     signer::EthSigner,
     traits::RawTransactionForwarder,
 };
@@ -18,13 +19,13 @@ use reth_evm::ConfigureEvm;
 use reth_network_api::NetworkInfo;
 use reth_primitives::{
     revm_primitives::{BlockEnv, CfgEnvWithHandlerCfg},
-    Address, BlockId, BlockNumberOrTag, ChainInfo, SealedBlockWithSenders, SealedHeader, B256,
-    U256, U64,
+    Address, BlockId, BlockNumberOrTag, ChainInfo, SealedBlockWithSenders, B256, U256, U64,
 };
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderBox, StateProviderFactory,
+    providers::StateOverridesProvider, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider,
+    StateProviderBox, StateProviderFactory,
 };
-use reth_rpc_types::{SyncInfo, SyncStatus};
+use reth_rpc_types::{state::StateOverride, SyncInfo, SyncStatus};
 use reth_tasks::{pool::BlockingTaskPool, TaskSpawner, TokioTaskExecutor};
 use reth_transaction_pool::TransactionPool;
 use revm_primitives::{CfgEnv, SpecId};
@@ -49,6 +50,7 @@ mod sign;
 mod state;
 mod transactions;
 
+pub use state::AccountOverview;
 pub use transactions::{EthTransactions, TransactionSource};
 
 /// `Eth` API trait.
@@ -268,6 +270,21 @@ where
     pub fn latest_state(&self) -> RethResult<StateProviderBox> {
         Ok(self.provider().latest()?)
     }
+
+    /// Returns the _pending_ state with the given overrides applied on top of it.
+    ///
+    /// The overrides win over the state of the pending block, which is layered on top of the
+    /// canonical state. They only apply to the returned state, which is cheap to create for every
+    /// request.
+    pub fn pending_state_with_overrides(
+        &self,
+        overrides: Option<StateOverride>,
+    ) -> EthResult<StateProviderBox> {
+        let state = self.state_at_block_id(BlockNumberOrTag::Pending.into())?;
+        let Some(overrides) = overrides else { return Ok(state) };
+        let overrides = into_provider_state_overrides(overrides)?;
+        Ok(Box::new(StateOverridesProvider::new(state, overrides)))
+    }
 }
 
 impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
@@ -300,17 +317,17 @@ where
             // base fee of the child block
             let chain_spec = self.provider().chain_spec();
 
-            latest_header.base_fee_per_gas = latest_header.next_block_base_fee(
-                chain_spec.base_fee_params_at_timestamp(latest_header.timestamp),
-            );
+            latest_header.base_fee_per_gas =
+                chain_spec.next_block_base_fee(&latest_header, latest_header.timestamp);
 
             // update excess blob gas consumed above target
             latest_header.excess_blob_gas = latest_header.next_block_excess_blob_gas();
 
-            // we're reusing the same block hash because we need this to lookup the block's state
-            let latest = SealedHeader::new(latest_header, block_hash);
-
-            PendingBlockEnvOrigin::DerivedFromLatest(latest)
+            // the block's state is looked up by the hash of the latest block
+            PendingBlockEnvOrigin::DerivedFromLatest {
+                header: latest_header,
+                latest_hash: block_hash,
+            }
         };
 
         let mut cfg = CfgEnvWithHandlerCfg::new_with_spec_id(CfgEnv::default(), SpecId::LATEST);
@@ -344,7 +361,7 @@ where
             if let Some(pending_block) = lock.as_ref() {
                 // this is guaranteed to be the `latest` header
                 if pending.block_env.number.to::<u64>() == pending_block.block.number &&
-                    pending.origin.header().hash() == pending_block.block.parent_hash &&
+                    pending.origin.build_target_hash() == pending_block.block.parent_hash &&
                     now <= pending_block.expires_at
                 {
                     return Ok(Some(pending_block.block.clone()))
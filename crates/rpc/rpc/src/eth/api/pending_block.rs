@@ -10,7 +10,7 @@ use reth_primitives::{
         BlockEnv, CfgEnvWithHandlerCfg, EVMError, Env, InvalidTransaction, ResultAndState, SpecId,
     },
     Block, BlockId, BlockNumberOrTag, ChainSpec, Header, IntoRecoveredTransaction, Receipt,
-    Requests, SealedBlockWithSenders, SealedHeader, B256, EMPTY_OMMER_ROOT_HASH, U256,
+    Requests, SealedBlockWithSenders, B256, EMPTY_OMMER_ROOT_HASH, U256,
 };
 use reth_provider::{ChainSpecProvider, ExecutionOutcome, StateProviderFactory};
 use reth_revm::{
@@ -76,7 +76,7 @@ impl PendingBlockEnv {
             PendingBlockEnvOrigin::ActualPending(ref block) => {
                 (block.withdrawals.clone(), block.withdrawals_root)
             }
-            PendingBlockEnvOrigin::DerivedFromLatest(_) => (None, None),
+            PendingBlockEnvOrigin::DerivedFromLatest { .. } => (None, None),
         };
 
         let chain_spec = client.chain_spec();
@@ -369,7 +369,12 @@ pub(crate) enum PendingBlockEnvOrigin {
     ///  - the timestamp
     ///  - the block number
     ///  - fees
-    DerivedFromLatest(SealedHeader),
+    DerivedFromLatest {
+        /// The modified header.
+        header: Header,
+        /// The hash of the latest block, which identifies the state the block is built on.
+        latest_hash: B256,
+    },
 }
 
 impl PendingBlockEnvOrigin {
@@ -393,7 +398,7 @@ impl PendingBlockEnvOrigin {
     pub(crate) fn state_block_id(&self) -> BlockId {
         match self {
             Self::ActualPending(_) => BlockNumberOrTag::Pending.into(),
-            Self::DerivedFromLatest(header) => BlockId::Hash(header.hash().into()),
+            Self::DerivedFromLatest { latest_hash, .. } => BlockId::Hash((*latest_hash).into()),
         }
     }
 
@@ -402,18 +407,18 @@ impl PendingBlockEnvOrigin {
     /// For the [`PendingBlockEnvOrigin::ActualPending`] this is the parent hash of the block.
     /// For the [`PendingBlockEnvOrigin::DerivedFromLatest`] this is the hash of the _latest_
     /// header.
-    fn build_target_hash(&self) -> B256 {
+    pub(crate) fn build_target_hash(&self) -> B256 {
         match self {
             Self::ActualPending(block) => block.parent_hash,
-            Self::DerivedFromLatest(header) => header.hash(),
+            Self::DerivedFromLatest { latest_hash, .. } => *latest_hash,
         }
     }
 
     /// Returns the header this pending block is based on.
-    pub(crate) fn header(&self) -> &SealedHeader {
+    pub(crate) fn header(&self) -> &Header {
         match self {
-            Self::ActualPending(block) => &block.header,
-            Self::DerivedFromLatest(header) => header,
+            Self::ActualPending(block) => block.header.header(),
+            Self::DerivedFromLatest { header, .. } => header,
         }
     }
 }
@@ -428,8 +428,8 @@ where
 mod tests {
     use crate::{
         eth::{
-            cache::EthStateCache, gas_oracle::GasPriceOracle, FeeHistoryCache,
-            FeeHistoryCacheConfig,
+            api::fee_history::FeeHistoryEntry, cache::EthStateCache, gas_oracle::GasPriceOracle,
+            FeeHistoryCache, FeeHistoryCacheConfig,
         },
         EthApi,
     };
@@ -437,8 +437,8 @@ mod tests {
     use reth_evm_ethereum::EthEvmConfig;
     use reth_network_api::noop::NoopNetwork;
     use reth_primitives::{
-        constants::ETHEREUM_BLOCK_GAS_LIMIT, BaseFeeParams, Block, BlockNumberOrTag, Header,
-        TransactionSigned, B256, U64,
+        constants::ETHEREUM_BLOCK_GAS_LIMIT, BaseFeeParams, Block, BlockNumberOrTag,
+        ChainSpecBuilder, Header, TransactionSigned, B256, U64,
     };
     use reth_provider::{
         test_utils::{MockEthProvider, NoopProvider},
@@ -449,6 +449,7 @@ mod tests {
     use reth_tasks::pool::BlockingTaskPool;
     use reth_testing_utils::{generators, generators::Rng};
     use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+    use std::sync::Arc;
 
     fn build_test_eth_api<
         P: BlockReaderIdExt
@@ -684,6 +685,34 @@ mod tests {
         );
     }
 
+    /// The base fee of the next block is clamped to the minimum base fee of the chain
+    #[tokio::test]
+    async fn test_fee_history_next_base_fee_floor() {
+        let mut chain_spec = ChainSpecBuilder::mainnet().build();
+        chain_spec.fee_floors.min_base_fee = Some(950);
+        let mock_provider =
+            MockEthProvider { chain_spec: Arc::new(chain_spec.clone()), ..Default::default() };
+
+        // an empty block decays the base fee to 875, below the floor
+        let header = Header {
+            number: 1,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1000),
+            ..Default::default()
+        };
+        let block = Block { header: header.clone(), ..Default::default() };
+        mock_provider.add_block(B256::with_last_byte(1), block.clone());
+        mock_provider.add_header(B256::with_last_byte(1), header);
+        let eth_api = build_test_eth_api(mock_provider);
+
+        let fee_history = eth_api.fee_history(1, 1.into(), None).await.unwrap();
+        assert_eq!(fee_history.base_fee_per_gas, vec![1000, 950]);
+
+        // the same for the blocks in the fee history cache
+        let entry = FeeHistoryEntry::new(&block.seal_slow());
+        assert_eq!(entry.next_block_base_fee(&chain_spec), 950);
+    }
+
     /// Requesting all blocks should be ok
     #[tokio::test]
     async fn test_fee_history_all_blocks() {
@@ -1,17 +1,32 @@
 //! Contains RPC handler implementations specific to state.
 
 use crate::{
-    eth::error::{EthApiError, EthResult, RpcInvalidTransactionError},
+    eth::error::{EthApiError, EthResult},
     EthApi,
 };
 use reth_evm::ConfigureEvm;
-use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, B256, U256};
+use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, B256, KECCAK_EMPTY, U256};
 use reth_provider::{
     BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProvider, StateProviderFactory,
 };
 use reth_rpc_types::{serde_helpers::JsonStorageKey, EIP1186AccountProofResponse};
 use reth_rpc_types_compat::proof::from_primitive_account_proof;
-use reth_transaction_pool::{PoolTransaction, TransactionPool};
+use reth_transaction_pool::{PendingSenderInfo, TransactionPool};
+
+/// The state of an account at a block together with its transactions in the pool.
+///
+/// See [`EthApi::account_overview`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccountOverview {
+    /// Balance of the account.
+    pub balance: U256,
+    /// Nonce of the account.
+    pub nonce: u64,
+    /// Whether the account has code.
+    pub has_code: bool,
+    /// The pending transactions of the account in the pool, `None` for historical blocks.
+    pub pending: Option<PendingSenderInfo>,
+}
 
 impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
 where
@@ -38,27 +53,57 @@ where
 
     /// Returns the number of transactions sent from an address at the given block identifier.
     ///
-    /// If this is [`BlockNumberOrTag::Pending`] then this will look up the highest transaction in
-    /// pool and return the next nonce (highest + 1).
+    /// If this is [`BlockNumberOrTag::Pending`] then this will also count the gapless sequence of
+    /// transactions of the address in the pool that follows its nonce.
     pub(crate) fn get_transaction_count(
         &self,
         address: Address,
         block_id: Option<BlockId>,
     ) -> EthResult<U256> {
+        let state = self.state_at_block_id_or_latest(block_id)?;
+        let nonce = state.account_nonce(address)?.unwrap_or_default();
+
         if block_id == Some(BlockId::pending()) {
-            let address_txs = self.pool().get_transactions_by_sender(address);
-            if let Some(highest_nonce) =
-                address_txs.iter().map(|item| item.transaction.nonce()).max()
-            {
-                let tx_count = highest_nonce
-                    .checked_add(1)
-                    .ok_or(RpcInvalidTransactionError::NonceMaxValue)?;
-                return Ok(U256::from(tx_count))
-            }
+            return Ok(U256::from(self.pool().next_pending_nonce(address, nonce)))
         }
 
+        Ok(U256::from(nonce))
+    }
+
+    /// Returns the balance, the nonce and whether there's code of an account at the given block
+    /// identifier, together with its pending transactions in the pool.
+    ///
+    /// This answers what otherwise takes `eth_getBalance`, `eth_getTransactionCount` for the latest
+    /// and the pending block and `eth_getCode`, with a single state provider and a single read of
+    /// the pool. The account is read at once, so its balance and nonce are always consistent, and
+    /// the code itself is never loaded.
+    ///
+    /// The pending transactions are only included for the latest and the pending block.
+    pub fn account_overview(
+        &self,
+        address: Address,
+        block_id: Option<BlockId>,
+    ) -> EthResult<AccountOverview> {
         let state = self.state_at_block_id_or_latest(block_id)?;
-        Ok(U256::from(state.account_nonce(address)?.unwrap_or_default()))
+        let account = state.basic_account(address)?.unwrap_or_default();
+        // release the database transaction before the pool is locked
+        drop(state);
+
+        let include_pending = block_id.map_or(true, |block_id| {
+            matches!(
+                block_id,
+                BlockId::Number(BlockNumberOrTag::Latest | BlockNumberOrTag::Pending)
+            )
+        });
+        let pending =
+            include_pending.then(|| self.pool().pending_sender_info(address, account.nonce));
+
+        Ok(AccountOverview {
+            balance: account.balance,
+            nonce: account.nonce,
+            has_code: account.bytecode_hash.is_some_and(|hash| hash != KECCAK_EMPTY),
+            pending,
+        })
     }
 
     pub(crate) fn storage_at(
@@ -123,8 +168,37 @@ mod tests {
     use reth_primitives::{constants::ETHEREUM_BLOCK_GAS_LIMIT, StorageKey, StorageValue};
     use reth_provider::test_utils::{ExtendedAccount, MockEthProvider, NoopProvider};
     use reth_tasks::pool::BlockingTaskPool;
-    use reth_transaction_pool::test_utils::testing_pool;
-    use std::collections::HashMap;
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction, TestPool, TestPoolBuilder},
+        PoolConfig, PoolTransaction,
+    };
+    use std::{
+        collections::HashMap,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+    };
+
+    fn mock_eth_api(
+        provider: MockEthProvider,
+        pool: TestPool,
+    ) -> EthApi<MockEthProvider, TestPool, (), EthEvmConfig> {
+        let evm_config = EthEvmConfig::default();
+        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+        EthApi::new(
+            provider.clone(),
+            pool,
+            (),
+            cache.clone(),
+            GasPriceOracle::new(provider, Default::default(), cache.clone()),
+            ETHEREUM_BLOCK_GAS_LIMIT,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+            evm_config,
+            None,
+        )
+    }
 
     #[tokio::test]
     async fn test_storage() {
@@ -175,4 +249,85 @@ mod tests {
         let storage = eth_api.storage_at(address, storage_key.into(), None).unwrap();
         assert_eq!(storage, storage_value.to_be_bytes());
     }
+
+    #[tokio::test]
+    async fn account_overview_is_consistent_under_writes() {
+        const WRITES: u64 = 200;
+
+        let provider = MockEthProvider::default();
+        let config = PoolConfig { max_account_slots: WRITES as usize, ..Default::default() };
+        let pool: TestPool = TestPoolBuilder::default().with_config(config).into();
+        let eth_api = mock_eth_api(provider.clone(), pool.clone());
+
+        let account = Address::random();
+        let sender = MockTransaction::eip1559();
+        let done = Arc::new(AtomicBool::new(false));
+
+        let writer = {
+            let (provider, pool, done) = (provider.clone(), pool.clone(), done.clone());
+            let mut tx = sender.clone();
+            std::thread::spawn(move || {
+                for n in 1..=WRITES {
+                    // the balance of the account always matches its nonce
+                    provider.add_account(account, ExtendedAccount::new(n, U256::from(n)));
+                    futures::executor::block_on(pool.add_external_transaction(tx.clone())).unwrap();
+                    tx = tx.next();
+                }
+                done.store(true, Ordering::SeqCst);
+            })
+        };
+
+        while !done.load(Ordering::SeqCst) {
+            let overview = eth_api.account_overview(account, None).unwrap();
+            assert_eq!(overview.balance, U256::from(overview.nonce));
+
+            // the sender has no on-chain state, so all of its transactions are pending
+            let pending = eth_api.account_overview(sender.sender(), None).unwrap().pending.unwrap();
+            assert_eq!(pending.next_nonce, pending.pending_transactions as u64);
+        }
+        writer.join().unwrap();
+
+        let overview = eth_api.account_overview(sender.sender(), Some(BlockId::pending())).unwrap();
+        assert_eq!(
+            overview.pending,
+            Some(PendingSenderInfo { next_nonce: WRITES, pending_transactions: WRITES as usize })
+        );
+    }
+
+    #[tokio::test]
+    async fn account_overview_omits_pending_for_historical_blocks() {
+        let provider = MockEthProvider::default();
+        let pool = testing_pool();
+        let eth_api = mock_eth_api(provider.clone(), pool.clone());
+
+        let contract = MockTransaction::eip1559();
+        provider.add_account(
+            contract.sender(),
+            ExtendedAccount::new(0, U256::from(7)).with_bytecode(Bytes::from_static(&[0x00])),
+        );
+        pool.add_external_transaction(contract.clone()).await.unwrap();
+
+        let overview = eth_api.account_overview(contract.sender(), None).unwrap();
+        assert_eq!(
+            overview,
+            AccountOverview {
+                balance: U256::from(7),
+                nonce: 0,
+                has_code: true,
+                pending: Some(PendingSenderInfo { next_nonce: 1, pending_transactions: 1 }),
+            }
+        );
+
+        for block_id in
+            [BlockId::Number(BlockNumberOrTag::Number(0)), BlockId::Hash(B256::ZERO.into())]
+        {
+            let historical = eth_api.account_overview(contract.sender(), Some(block_id)).unwrap();
+            assert_eq!(historical, AccountOverview { pending: None, ..overview });
+        }
+
+        // unknown accounts are empty
+        let overview = eth_api.account_overview(Address::random(), None).unwrap();
+        assert_eq!(overview.pending, Some(PendingSenderInfo::default()));
+        assert!(!overview.has_code);
+    }
 }
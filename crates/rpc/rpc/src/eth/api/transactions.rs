@@ -824,17 +824,8 @@ where
     async fn transaction_receipt(&self, hash: B256) -> EthResult<Option<AnyTransactionReceipt>> {
         let result = self
             .on_blocking_task(|this| async move {
-                let (tx, meta) = match this.provider().transaction_by_hash_with_meta(hash)? {
-                    Some((tx, meta)) => (tx, meta),
-                    None => return Ok(None),
-                };
-
-                let receipt = match this.provider().receipt_by_hash(hash)? {
-                    Some(recpt) => recpt,
-                    None => return Ok(None),
-                };
-
-                Ok(Some((tx, meta, receipt)))
+                // read the transaction and its receipt from the same snapshot
+                Ok(this.provider().transaction_and_receipt_by_hash(hash)?)
             })
             .await?;
 
@@ -1063,7 +1054,7 @@ where
         &self,
         request: TransactionRequest,
         at: BlockId,
-        overrides: EvmOverrides,
+        mut overrides: EvmOverrides,
         f: F,
     ) -> EthResult<R>
     where
@@ -1075,7 +1066,11 @@ where
         self.inner
             .blocking_task_pool
             .spawn(move || {
-                let state = this.state_at(at)?;
+                let state = if at.is_pending() {
+                    this.pending_state_with_overrides(overrides.state.take())?
+                } else {
+                    this.state_at(at)?
+                };
                 let mut db = CacheDB::new(StateProviderDatabase::new(state));
 
                 let env = prepare_call_env(
@@ -1,7 +1,7 @@
 //! `Eth` bundle implementation and helpers.
 
 use crate::eth::{
-    error::{EthApiError, EthResult, RpcInvalidTransactionError},
+    error::{EthApiError, EthResult, RevertError, RpcInvalidTransactionError},
     revm_utils::FillableTransaction,
     utils::recover_raw_transaction,
     EthTransactions,
@@ -11,8 +11,9 @@ use reth_primitives::{
     constants::eip4844::MAINNET_KZG_TRUSTED_SETUP,
     keccak256,
     revm_primitives::db::{DatabaseCommit, DatabaseRef},
-    PooledTransactionsElement, U256,
+    Bytes, PooledTransactionsElement, U256,
 };
+use reth_provider::TransactionOutcome;
 use reth_revm::database::StateProviderDatabase;
 use reth_rpc_api::EthCallBundleApiServer;
 use reth_rpc_types::{EthCallBundle, EthCallBundleResponse, EthCallBundleTransactionResult};
@@ -156,13 +157,7 @@ where
                     coinbase_balance_before_tx = coinbase_balance_after_tx;
 
                     // set the return data for the response
-                    let (value, revert) = if result.is_success() {
-                        let value = result.into_output().unwrap_or_default();
-                        (Some(value), None)
-                    } else {
-                        let revert = result.into_output().unwrap_or_default();
-                        (None, Some(revert))
-                    };
+                    let (value, revert, error) = transaction_result_output(result.into());
 
                     let tx_res = EthCallBundleTransactionResult {
                         coinbase_diff,
@@ -175,6 +170,7 @@ where
                         tx_hash: tx.hash(),
                         value,
                         revert,
+                        error,
                     };
                     results.push(tx_res);
 
@@ -210,6 +206,24 @@ where
     }
 }
 
+/// Returns the return data, revert data and error of a transaction in the bundle.
+///
+/// Reverts and halts are both reported as errors, but only reverts have revert data.
+fn transaction_result_output(
+    outcome: TransactionOutcome,
+) -> (Option<Bytes>, Option<Bytes>, Option<String>) {
+    match outcome {
+        TransactionOutcome::Success { output, .. } => (Some(output.into_data()), None, None),
+        TransactionOutcome::Revert { output } => {
+            let error = RevertError::new(output.clone()).to_string();
+            (None, Some(output), Some(error))
+        }
+        TransactionOutcome::Halt { reason, gas_used } => {
+            (None, None, Some(RpcInvalidTransactionError::halt(reason, gas_used).to_string()))
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl<Eth> EthCallBundleApiServer for EthBundle<Eth>
 where
@@ -256,3 +270,36 @@ pub enum EthBundleError {
     #[error("blob gas usage exceeds the limit of {MAX_BLOB_GAS_PER_BLOCK} gas per block.")]
     Eip4844BlobGasExceeded,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::{HaltReason, OutOfGasError, Output};
+
+    #[test]
+    fn reverts_and_halts_are_distinct() {
+        let success = TransactionOutcome::Success {
+            output: Output::Call(Bytes::from_static(&[1])),
+            logs: Vec::new(),
+        };
+        assert_eq!(
+            transaction_result_output(success),
+            (Some(Bytes::from_static(&[1])), None, None)
+        );
+
+        let revert = TransactionOutcome::Revert { output: Bytes::new() };
+        assert_eq!(
+            transaction_result_output(revert),
+            (None, Some(Bytes::new()), Some("execution reverted".to_string()))
+        );
+
+        let halt = TransactionOutcome::Halt {
+            reason: HaltReason::OutOfGas(OutOfGasError::Basic),
+            gas_used: 50_000,
+        };
+        assert_eq!(
+            transaction_result_output(halt),
+            (None, None, Some("out of gas: gas required exceeds allowance: 50000".to_string()))
+        );
+    }
+}
@@ -594,6 +594,9 @@ impl From<PoolError> for RpcPoolError {
             }
             PoolErrorKind::InvalidTransaction(err) => err.into(),
             PoolErrorKind::Other(err) => Self::Other(err),
+            kind @ (PoolErrorKind::NodeSyncing |
+            PoolErrorKind::PreviouslyRejected(_) |
+            PoolErrorKind::PreviouslyRejectedStale(_)) => Self::Other(Box::new(kind)),
             PoolErrorKind::AlreadyImported => Self::AlreadyKnown,
             PoolErrorKind::ExistingConflictingTransactionType(_, _) => Self::AddressAlreadyReserved,
         }
@@ -604,7 +607,8 @@ impl From<InvalidPoolTransactionError> for RpcPoolError {
     fn from(err: InvalidPoolTransactionError) -> Self {
         match err {
             InvalidPoolTransactionError::Consensus(err) => Self::Invalid(err.into()),
-            InvalidPoolTransactionError::ExceedsGasLimit(_, _) => Self::ExceedsGasLimit,
+            InvalidPoolTransactionError::ExceedsGasLimit(_, _) |
+            InvalidPoolTransactionError::ExceedsLoweredGasLimit(_, _) => Self::ExceedsGasLimit,
             InvalidPoolTransactionError::ExceedsMaxInitCodeSize(_, _) => {
                 Self::ExceedsMaxInitCodeSize
             }
@@ -11,8 +11,10 @@ use core::fmt;
 
 use async_trait::async_trait;
 use jsonrpsee::{core::RpcResult, server::IdProvider};
-use reth_primitives::{ChainInfo, IntoRecoveredTransaction, TxHash};
-use reth_provider::{BlockIdReader, BlockReader, EvmEnvProvider, ProviderError};
+use reth_primitives::{ChainInfo, Header, IntoRecoveredTransaction, TxHash, B256};
+use reth_provider::{
+    BlockIdReader, BlockReader, CanonicalHashesIter, EvmEnvProvider, ProviderError, ProviderResult,
+};
 use reth_rpc_api::EthFilterApiServer;
 use reth_rpc_types::{
     BlockNumHash, Filter, FilterBlockOption, FilterChanges, FilterId, FilteredParams, Log,
@@ -162,11 +164,17 @@ where
                 // Note: we need to fetch the block hashes from inclusive range
                 // [start_block..best_block]
                 let end_block = best_number + 1;
-                let block_hashes = self
+                let hashes = self
                     .inner
                     .provider
-                    .canonical_hashes_range(start_block, end_block)
+                    .canonical_hashes_iter(start_block..end_block)
                     .map_err(|_| EthApiError::UnknownBlockNumber)?;
+                // the hashes are read straight into the response
+                let mut block_hashes = Vec::with_capacity((end_block - start_block) as usize);
+                for entry in hashes {
+                    let (_, hash) = entry.map_err(|_| EthApiError::UnknownBlockNumber)?;
+                    block_hashes.push(hash);
+                }
                 Ok(FilterChanges::Hashes(block_hashes))
             }
             FilterKind::Log(filter) => {
@@ -474,40 +482,40 @@ where
         {
             let headers = self.provider.headers_range(from..=to)?;
 
-            for (idx, header) in headers.iter().enumerate() {
-                // only if filter matches
-                if FilteredParams::matches_address(header.logs_bloom, &address_filter) &&
-                    FilteredParams::matches_topics(header.logs_bloom, &topics_filter)
-                {
-                    // these are consecutive headers, so we can use the parent hash of the next
-                    // block to get the current header's hash
-                    let block_hash = match headers.get(idx + 1) {
-                        Some(parent) => parent.parent_hash,
-                        None => self
-                            .provider
-                            .block_hash(header.number)?
-                            .ok_or(ProviderError::HeaderNotFound(header.number.into()))?,
-                    };
-
-                    if let Some(receipts) = self.eth_cache.get_receipts(block_hash).await? {
-                        append_matching_block_logs(
-                            &mut all_logs,
-                            &self.provider,
-                            &filter_params,
-                            BlockNumHash::new(header.number, block_hash),
-                            &receipts,
-                            false,
-                            header.timestamp,
-                        )?;
-
-                        // size check but only if range is multiple blocks, so we always return all
-                        // logs of a single block
-                        let is_multi_block_range = from_block != to_block;
-                        if is_multi_block_range && all_logs.len() > self.max_logs_per_response {
-                            return Err(FilterError::QueryExceedsMaxResults(
-                                self.max_logs_per_response,
-                            ))
-                        }
+            // only the blocks whose bloom matches the filter are candidates, their hashes are read
+            // alongside the headers
+            let candidates =
+                zip_block_hashes(headers, self.provider.canonical_hashes_iter(from..to + 1)?)
+                    .filter(|entry| {
+                        entry.as_ref().map_or(true, |(header, _)| {
+                            FilteredParams::matches_address(header.logs_bloom, &address_filter) &&
+                                FilteredParams::matches_topics(header.logs_bloom, &topics_filter)
+                        })
+                    })
+                    .map(|entry| {
+                        entry.map(|(header, hash)| {
+                            (BlockNumHash::new(header.number, hash), header.timestamp)
+                        })
+                    })
+                    .collect::<ProviderResult<Vec<_>>>()?;
+
+            for (block, timestamp) in candidates {
+                if let Some(receipts) = self.eth_cache.get_receipts(block.hash).await? {
+                    append_matching_block_logs(
+                        &mut all_logs,
+                        &self.provider,
+                        &filter_params,
+                        block,
+                        &receipts,
+                        false,
+                        timestamp,
+                    )?;
+
+                    // size check but only if range is multiple blocks, so we always return all
+                    // logs of a single block
+                    let is_multi_block_range = from_block != to_block;
+                    if is_multi_block_range && all_logs.len() > self.max_logs_per_response {
+                        return Err(FilterError::QueryExceedsMaxResults(self.max_logs_per_response))
                     }
                 }
             }
@@ -726,6 +734,27 @@ impl From<ProviderError> for FilterError {
     }
 }
 
+/// Pairs the headers with their canonical hashes.
+///
+/// Both the headers and the hashes skip missing blocks, so they are matched by block number.
+/// Headers without a hash are dropped.
+fn zip_block_hashes<'a>(
+    headers: Vec<Header>,
+    hashes: CanonicalHashesIter<'a>,
+) -> impl Iterator<Item = ProviderResult<(Header, B256)>> + 'a {
+    let mut hashes = hashes.peekable();
+    headers.into_iter().filter_map(move |header| {
+        // skip the hashes of the blocks without a header
+        while hashes
+            .next_if(|entry| entry.as_ref().is_ok_and(|(number, _)| *number < header.number))
+            .is_some()
+        {}
+        hashes
+            .next_if(|entry| entry.as_ref().map_or(true, |(number, _)| *number == header.number))
+            .map(|entry| entry.map(|(_, hash)| (header, hash)))
+    })
+}
+
 /// An iterator that yields _inclusive_ block ranges of a given step size
 #[derive(Debug)]
 struct BlockRangeInclusiveIter {
@@ -780,4 +809,21 @@ mod tests {
             assert_eq!(end, *range.end());
         }
     }
+
+    #[test]
+    fn test_zip_block_hashes() {
+        let hash = B256::with_last_byte;
+        let headers = [1, 2, 4, 5]
+            .into_iter()
+            .map(|number| Header { number, ..Default::default() })
+            .collect::<Vec<_>>();
+        let hashes: CanonicalHashesIter<'_> =
+            Box::new([1, 3, 4, 5].into_iter().map(|number| Ok((number, hash(number as u8)))));
+
+        let blocks = zip_block_hashes(headers, hashes)
+            .map(|entry| entry.map(|(header, hash)| (header.number, hash)))
+            .collect::<ProviderResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(blocks, vec![(1, hash(1)), (4, hash(4)), (5, hash(5))]);
+    }
 }
@@ -37,8 +37,19 @@ pub struct GasPriceOracleConfig {
     /// The maximum gas price to use for the estimate
     pub max_price: Option<U256>,
 
+    /// The minimum gas price to use for the estimate
+    pub min_price: Option<U256>,
+
     /// The minimum gas price, under which the sample will be ignored
     pub ignore_price: Option<U256>,
+
+    /// The minimum number of transactions a block must contain to be sampled
+    #[serde(default = "default_min_block_transactions")]
+    pub min_block_transactions: usize,
+}
+
+const fn default_min_block_transactions() -> usize {
+    DEFAULT_MIN_BLOCK_TRANSACTIONS
 }
 
 impl Default for GasPriceOracleConfig {
@@ -50,7 +61,9 @@ impl Default for GasPriceOracleConfig {
             max_block_history: MAX_HEADER_HISTORY,
             default: None,
             max_price: Some(DEFAULT_MAX_GAS_PRICE),
+            min_price: None,
             ignore_price: Some(DEFAULT_IGNORE_GAS_PRICE),
+            min_block_transactions: DEFAULT_MIN_BLOCK_TRANSACTIONS,
         }
     }
 }
@@ -106,6 +119,11 @@ where
     }
 
     /// Suggests a gas price estimate based on recent blocks, using the configured percentile.
+    ///
+    /// Blocks with fewer than the configured minimum number of transactions, or without any
+    /// usable tip, are skipped. If no block yields a sample, the
+    /// [fallback price](Self::fallback_price) is suggested instead. The result is always clamped
+    /// to the configured minimum and maximum price and cached for the current head.
     pub async fn suggest_tip_cap(&self) -> EthResult<U256> {
         let header = self
             .provider
@@ -152,9 +170,8 @@ where
                     (parent_hash, block_values)
                 };
 
-            if block_values.is_empty() {
-                results.push(U256::from(inner.last_price.price));
-            } else {
+            // skipped blocks don't contribute to the sample
+            if !block_values.is_empty() {
                 results.extend(block_values);
                 populated_blocks += 1;
             }
@@ -174,9 +191,16 @@ where
                 "gas price index is a percent of nonzero array length, so a value always exists",
             )
         } else {
-            inner.last_price.price
+            self.fallback_price()
         };
 
+        // constrain to the min price
+        if let Some(min_price) = self.oracle_config.min_price {
+            if price < min_price {
+                price = min_price;
+            }
+        }
+
         // constrain to the max price
         if let Some(max_price) = self.oracle_config.max_price {
             if price > max_price {
@@ -189,10 +213,24 @@ where
         Ok(price)
     }
 
+    /// Returns the price suggested if none of the recent blocks yields a sample.
+    ///
+    /// This is the configured default, or the configured minimum price if there is no default.
+    /// Since the suggestion is a priority fee, `eth_gasPrice` then resolves to the current base
+    /// fee plus this tip.
+    pub fn fallback_price(&self) -> U256 {
+        self.oracle_config
+            .default
+            .or(self.oracle_config.min_price)
+            .unwrap_or(DEFAULT_FALLBACK_GAS_PRICE)
+    }
+
     /// Get the `limit` lowest effective tip values for the given block. If the oracle has a
     /// configured `ignore_price` threshold, then tip values under that threshold will be ignored
     /// before returning a result.
     ///
+    /// Blocks with fewer than the configured minimum number of transactions yield no values.
+    ///
     /// If the block cannot be found, then this will return `None`.
     ///
     /// This method also returns the parent hash for the given block.
@@ -210,6 +248,11 @@ where
         let base_fee_per_gas = block.base_fee_per_gas;
         let parent_hash = block.parent_hash;
 
+        // ignore blocks that are too sparse to be representative
+        if block.body.len() < self.oracle_config.min_block_transactions {
+            return Ok(Some((parent_hash, Vec::new())))
+        }
+
         // sort the functions by ascending effective tip first
         block.body.sort_by_cached_key(|tx| tx.effective_tip_per_gas(base_fee_per_gas));
 
@@ -289,6 +332,68 @@ impl Default for GasPriceOracleResult {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{Address, Block, Header, Transaction, TransactionSigned, TxEip1559};
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_testing_utils::generators::{self, generate_keys, sign_tx_with_key_pair};
+
+    const BASE_FEE: u64 = 7;
+
+    /// Builds a chain on top of an empty genesis block with one block per entry in `blocks`.
+    fn build_chain(beneficiary: Address, blocks: Vec<Vec<TransactionSigned>>) -> MockEthProvider {
+        let provider = MockEthProvider::default();
+        let genesis = Header { base_fee_per_gas: Some(BASE_FEE), ..Default::default() };
+        let mut parent_hash = genesis.hash_slow();
+        provider.add_block(parent_hash, Block { header: genesis, ..Default::default() });
+
+        for (idx, body) in blocks.into_iter().enumerate() {
+            let header = Header {
+                number: idx as u64 + 1,
+                parent_hash,
+                beneficiary,
+                base_fee_per_gas: Some(BASE_FEE),
+                ..Default::default()
+            };
+            parent_hash = header.hash_slow();
+            provider.add_block(parent_hash, Block { header, body, ..Default::default() });
+        }
+
+        provider
+    }
+
+    /// Returns signed transactions from a fresh sender, one for each of the given tips.
+    fn txs_with_tips(rng: &mut impl generators::Rng, tips: &[u128]) -> Vec<TransactionSigned> {
+        let key_pair = generate_keys(rng, 1)[0];
+        tips.iter()
+            .enumerate()
+            .map(|(nonce, tip)| {
+                sign_tx_with_key_pair(
+                    key_pair,
+                    Transaction::Eip1559(TxEip1559 {
+                        chain_id: 1,
+                        nonce: nonce as u64,
+                        gas_limit: 21_000,
+                        max_priority_fee_per_gas: *tip,
+                        max_fee_per_gas: *tip + BASE_FEE as u128,
+                        ..Default::default()
+                    }),
+                )
+            })
+            .collect()
+    }
+
+    fn oracle(
+        provider: MockEthProvider,
+        config: GasPriceOracleConfig,
+    ) -> GasPriceOracle<MockEthProvider> {
+        let cache =
+            EthStateCache::spawn(provider.clone(), Default::default(), EthEvmConfig::default());
+        GasPriceOracle::new(provider, config, cache)
+    }
+
+    fn gwei(value: u64) -> U256 {
+        U256::from(value as u128 * GWEI_TO_WEI as u128)
+    }
 
     #[test]
     fn max_price_sanity() {
@@ -300,4 +405,63 @@ mod tests {
     fn ignore_price_sanity() {
         assert_eq!(DEFAULT_IGNORE_GAS_PRICE, U256::from(2u64));
     }
+
+    #[test]
+    fn config_without_min_block_transactions() {
+        let config = GasPriceOracleConfig { min_block_transactions: 2, ..Default::default() };
+        let mut json = serde_json::to_value(&config).unwrap();
+        json.as_object_mut().unwrap().remove("minBlockTransactions").unwrap();
+
+        let config: GasPriceOracleConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(config.min_block_transactions, DEFAULT_MIN_BLOCK_TRANSACTIONS);
+    }
+
+    #[tokio::test]
+    async fn empty_chain_suggests_fallback() {
+        let provider = build_chain(Address::ZERO, vec![]);
+
+        let gas_oracle = oracle(provider.clone(), Default::default());
+        assert_eq!(gas_oracle.suggest_tip_cap().await.unwrap(), DEFAULT_FALLBACK_GAS_PRICE);
+
+        let config = GasPriceOracleConfig { default: Some(gwei(3)), ..Default::default() };
+        let gas_oracle = oracle(provider, config);
+        assert_eq!(gas_oracle.suggest_tip_cap().await.unwrap(), gwei(3));
+    }
+
+    #[tokio::test]
+    async fn all_empty_blocks_suggest_fallback() {
+        let provider = build_chain(Address::ZERO, vec![vec![]; 5]);
+
+        let gas_oracle = oracle(provider.clone(), Default::default());
+        assert_eq!(gas_oracle.suggest_tip_cap().await.unwrap(), DEFAULT_FALLBACK_GAS_PRICE);
+
+        // without a default, the minimum price is used as the fallback tip
+        let config = GasPriceOracleConfig { min_price: Some(gwei(2)), ..Default::default() };
+        let gas_oracle = oracle(provider, config);
+        assert_eq!(gas_oracle.suggest_tip_cap().await.unwrap(), gwei(2));
+    }
+
+    #[tokio::test]
+    async fn single_whale_tx() {
+        let mut rng = generators::rng();
+        let whale = txs_with_tips(&mut rng, &[1_000 * GWEI_TO_WEI as u128]);
+
+        // a lone block is sampled as is, but capped at the max price
+        let provider = build_chain(Address::ZERO, vec![whale.clone()]);
+        let gas_oracle = oracle(provider, Default::default());
+        assert_eq!(gas_oracle.suggest_tip_cap().await.unwrap(), DEFAULT_MAX_GAS_PRICE);
+
+        // requiring more transactions per block skips the whale block entirely
+        let regular = txs_with_tips(&mut rng, &[GWEI_TO_WEI as u128, 2 * GWEI_TO_WEI as u128]);
+        let provider = build_chain(Address::ZERO, vec![regular, whale.clone()]);
+        let config = GasPriceOracleConfig { min_block_transactions: 2, ..Default::default() };
+        let gas_oracle = oracle(provider, config);
+        assert_eq!(gas_oracle.suggest_tip_cap().await.unwrap(), gwei(1));
+
+        // transactions of the block's beneficiary are not sampled
+        let beneficiary = whale[0].recover_signer().unwrap();
+        let provider = build_chain(beneficiary, vec![whale]);
+        let gas_oracle = oracle(provider, Default::default());
+        assert_eq!(gas_oracle.suggest_tip_cap().await.unwrap(), DEFAULT_FALLBACK_GAS_PRICE);
+    }
 }
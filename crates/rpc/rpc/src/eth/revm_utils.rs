@@ -9,6 +9,7 @@ use reth_primitives::{
     revm::env::fill_tx_env_with_recovered, Address, TransactionSigned,
     TransactionSignedEcRecovered, TxHash, TxKind, B256, U256,
 };
+use reth_provider::providers::{AccountStateOverride, StateOverrides};
 use reth_rpc_types::{
     state::{AccountOverride, StateOverride},
     BlockOverrides, TransactionRequest,
@@ -557,6 +558,36 @@ where
     Ok(())
 }
 
+/// Converts the given state overrides into [`StateOverrides`] for a
+/// [`StateOverridesProvider`](reth_provider::providers::StateOverridesProvider).
+pub(crate) fn into_provider_state_overrides(overrides: StateOverride) -> EthResult<StateOverrides> {
+    overrides
+        .into_iter()
+        .map(|(account, account_override)| {
+            let (storage, replace_storage) =
+                match (account_override.state, account_override.state_diff) {
+                    (Some(_), Some(_)) => {
+                        return Err(EthApiError::BothStateAndStateDiffInOverride(account))
+                    }
+                    (Some(state), None) => (state, true),
+                    (None, state_diff) => (state_diff.unwrap_or_default(), false),
+                };
+            let storage =
+                storage.into_iter().map(|(slot, value)| (slot, U256::from_be_bytes(value.0)));
+            Ok((
+                account,
+                AccountStateOverride {
+                    balance: account_override.balance,
+                    nonce: account_override.nonce.map(|nonce| nonce.to()),
+                    code: account_override.code,
+                    storage: storage.collect(),
+                    replace_storage,
+                },
+            ))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use reth_primitives::constants::GWEI_TO_WEI;
@@ -664,4 +695,42 @@ mod tests {
         );
         assert!(call_fees.is_err());
     }
+
+    #[test]
+    fn test_provider_state_overrides() {
+        let (account, slot) = (Address::with_last_byte(1), B256::with_last_byte(1));
+        let value = B256::with_last_byte(2);
+        let overrides = |state, state_diff| {
+            StateOverride::from([(
+                account,
+                AccountOverride {
+                    balance: Some(U256::from(100)),
+                    state,
+                    state_diff,
+                    ..Default::default()
+                },
+            )])
+        };
+
+        let converted =
+            into_provider_state_overrides(overrides(None, Some([(slot, value)].into()))).unwrap();
+        assert_eq!(
+            converted[&account],
+            AccountStateOverride {
+                balance: Some(U256::from(100)),
+                storage: [(slot, U256::from(2))].into(),
+                ..Default::default()
+            }
+        );
+
+        let converted =
+            into_provider_state_overrides(overrides(Some([(slot, value)].into()), None)).unwrap();
+        assert!(converted[&account].replace_storage);
+
+        let both = overrides(Some(Default::default()), Some(Default::default()));
+        assert!(matches!(
+            into_provider_state_overrides(both),
+            Err(EthApiError::BothStateAndStateDiffInOverride(address)) if address == account
+        ));
+    }
 }
@@ -45,10 +45,10 @@ where
         let AllPoolTransactions { pending, queued } = self.pool.all_transactions();
 
         let mut content = TxpoolContent::default();
-        for pending in pending {
+        for pending in pending.values().flatten() {
             insert(&pending.transaction, &mut content.pending);
         }
-        for queued in queued {
+        for queued in queued.values().flatten() {
             insert(&queued.transaction, &mut content.queued);
         }
 
@@ -69,7 +69,7 @@ where
     async fn txpool_status(&self) -> Result<TxpoolStatus> {
         trace!(target: "rpc::eth", "Serving txpool_status");
         let all = self.pool.all_transactions();
-        Ok(TxpoolStatus { pending: all.pending.len() as u64, queued: all.queued.len() as u64 })
+        Ok(TxpoolStatus { pending: all.pending_len() as u64, queued: all.queued_len() as u64 })
     }
 
     /// Returns a summary of all the transactions currently pending for inclusion in the next
@@ -102,11 +102,11 @@ where
         let AllPoolTransactions { pending, queued } = self.pool.all_transactions();
 
         Ok(TxpoolInspect {
-            pending: pending.iter().fold(Default::default(), |mut acc, tx| {
+            pending: pending.values().flatten().fold(Default::default(), |mut acc, tx| {
                 insert(&tx.transaction, &mut acc);
                 acc
             }),
-            queued: queued.iter().fold(Default::default(), |mut acc, tx| {
+            queued: queued.values().flatten().fold(Default::default(), |mut acc, tx| {
                 insert(&tx.transaction, &mut acc);
                 acc
             }),
@@ -1,4 +1,4 @@
-use crate::PipelineEvent;
+use crate::{PipelineEvent, StageId, UnwindHorizon};
 use reth_consensus::ConsensusError;
 use reth_errors::{BlockExecutionError, DatabaseError, RethError};
 use reth_network_p2p::error::DownloadError;
@@ -32,7 +32,11 @@ impl BlockErrorKind {
 #[derive(Error, Debug)]
 pub enum StageError {
     /// The stage encountered an error related to a block.
-    #[error("stage encountered an error in block #{number}: {error}", number = block.number)]
+    #[error(
+        "stage encountered an error in block #{number} ({hash}): {error}",
+        number = block.number,
+        hash = block.hash(),
+    )]
     Block {
         /// The block that caused the error.
         block: Box<SealedHeader>,
@@ -60,6 +64,31 @@ pub enum StageError {
         #[source]
         error: Box<ConsensusError>,
     },
+    /// The local head is no longer canonical, the downloaded headers attach to an ancestor of it.
+    #[error(
+        "local head #{head_number} ({head_hash}) was reorged, \
+         the downloaded headers attach to block #{fork_block}",
+        head_number = local_head.number,
+        head_hash = local_head.hash(),
+    )]
+    ReorgedHead {
+        /// The local head that is no longer canonical.
+        local_head: Box<SealedHeader>,
+        /// The last block the local chain and the downloaded headers have in common.
+        fork_block: BlockNumber,
+    },
+    /// The local head was reorged deeper than the stage is allowed to unwind on its own.
+    #[error(
+        "local head #{head_number} ({head_hash}) was reorged deeper than {max_depth} blocks",
+        head_number = local_head.number,
+        head_hash = local_head.hash(),
+    )]
+    ReorgTooDeep {
+        /// The local head that is no longer canonical.
+        local_head: Box<SealedHeader>,
+        /// The maximum number of blocks the stage unwinds on its own.
+        max_depth: u64,
+    },
     /// The headers stage is missing sync gap.
     #[error("missing sync gap")]
     MissingSyncGap,
@@ -87,7 +116,11 @@ pub enum StageError {
     #[error("invalid download response: {0}")]
     Download(#[from] DownloadError),
     /// Database is ahead of static file data.
-    #[error("missing static file data for block number: {number}", number = block.number)]
+    #[error(
+        "missing static file data for block #{number} ({hash})",
+        number = block.number,
+        hash = block.hash(),
+    )]
     MissingStaticFileData {
         /// Starting block with  missing data.
         block: Box<SealedHeader>,
@@ -96,11 +129,14 @@ pub enum StageError {
     },
     /// Unrecoverable inconsistency error related to a transaction number in a static file segment.
     #[error(
-        "inconsistent transaction number for {segment}. db: {database}, static_file: {static_file}"
+        "inconsistent transaction number for {segment} in block #{block_number}. \
+         db: {database}, static_file: {static_file}"
     )]
     InconsistentTxNumber {
         /// Static File segment where this error was encountered.
         segment: StaticFileSegment,
+        /// The block of the transaction.
+        block_number: BlockNumber,
         /// Expected database transaction number.
         database: TxNumber,
         /// Expected static file transaction number.
@@ -130,11 +166,29 @@ pub enum StageError {
     /// These types of errors stop the pipeline.
     #[error(transparent)]
     Fatal(Box<dyn std::error::Error + Send + Sync>),
+    /// An error of a stage, with the stage and the block it was encountered at.
+    ///
+    /// The [Pipeline][crate::Pipeline] adds this context to errors it doesn't handle, see
+    /// [`StageError::with_context`].
+    #[error("stage {stage_id} failed at block #{block_number}: {error}")]
+    Context {
+        /// The stage that encountered the error.
+        stage_id: StageId,
+        /// The block the error refers to, or the first block of the stage run if it refers to
+        /// no particular block.
+        block_number: BlockNumber,
+        /// The error without context.
+        #[source]
+        error: Box<StageError>,
+    },
 }
 
 impl StageError {
     /// If the error is fatal the pipeline will stop.
-    pub const fn is_fatal(&self) -> bool {
+    pub fn is_fatal(&self) -> bool {
+        if let Self::Context { error, .. } = self {
+            return error.is_fatal()
+        }
         matches!(
             self,
             Self::Database(_) |
@@ -143,6 +197,7 @@ impl StageError {
                 Self::StageCheckpoint(_) |
                 Self::MissingDownloadBuffer |
                 Self::MissingSyncGap |
+                Self::ReorgTooDeep { .. } |
                 Self::ChannelClosed |
                 Self::InconsistentBlockNumber { .. } |
                 Self::InconsistentTxNumber { .. } |
@@ -150,6 +205,63 @@ impl StageError {
                 Self::Fatal(_)
         )
     }
+
+    /// Returns the number of the block the error refers to, if any.
+    pub fn block_number(&self) -> Option<BlockNumber> {
+        match self {
+            Self::Block { block, .. } | Self::MissingStaticFileData { block, .. } => {
+                Some(block.number)
+            }
+            Self::DetachedHead { header, .. } => Some(header.number),
+            Self::ReorgedHead { local_head, .. } | Self::ReorgTooDeep { local_head, .. } => {
+                Some(local_head.number)
+            }
+            Self::InconsistentBlockNumber { database, .. } => Some(*database),
+            Self::InconsistentTxNumber { block_number, .. } => Some(*block_number),
+            Self::DatabaseIntegrity(error) | Self::Internal(RethError::Provider(error)) => {
+                error.block_number()
+            }
+            Self::Context { block_number, .. } => Some(*block_number),
+            _ => None,
+        }
+    }
+
+    /// Returns the stage that encountered the error, if the error has
+    /// [context](StageError::Context).
+    pub const fn stage_id(&self) -> Option<StageId> {
+        match self {
+            Self::Context { stage_id, .. } => Some(*stage_id),
+            _ => None,
+        }
+    }
+
+    /// Returns the error without [context](StageError::Context).
+    pub fn inner(&self) -> &Self {
+        match self {
+            Self::Context { error, .. } => error,
+            _ => self,
+        }
+    }
+
+    /// Consumes the error and returns it without [context](StageError::Context).
+    pub fn into_inner(self) -> Self {
+        match self {
+            Self::Context { error, .. } => *error,
+            _ => self,
+        }
+    }
+
+    /// Adds the stage and the block to the error, unless it already has
+    /// [context](StageError::Context).
+    ///
+    /// The block is taken from the error if it refers to one, and is `fallback_block` otherwise.
+    pub fn with_context(self, stage_id: StageId, fallback_block: BlockNumber) -> Self {
+        if matches!(self, Self::Context { .. }) {
+            return self
+        }
+        let block_number = self.block_number().unwrap_or(fallback_block);
+        Self::Context { stage_id, block_number, error: Box::new(self) }
+    }
 }
 
 impl From<std::io::Error> for StageError {
@@ -176,4 +288,17 @@ pub enum PipelineError {
     /// Internal error
     #[error(transparent)]
     Internal(#[from] RethError),
+    /// The unwind target is below the lowest block a stage can be unwound to, because the data
+    /// required to unwind it was pruned.
+    #[error(
+        "can't unwind to block {target}: {horizon}; \
+         unwind to block {block} or later, resync the node or restore it from a snapshot",
+        block = horizon.block
+    )]
+    UnwindBelowHorizon {
+        /// The unwind target.
+        target: BlockNumber,
+        /// The lowest block the stages can be unwound to.
+        horizon: UnwindHorizon,
+    },
 }
@@ -1,4 +1,5 @@
 use crate::{metrics::SyncMetrics, StageCheckpoint, StageId};
+use reth_metrics::MetricsRegistrar;
 use reth_primitives::{constants::MGAS_TO_GAS, BlockNumber};
 use std::{
     future::Future,
@@ -50,6 +51,11 @@ impl MetricsListener {
         Self { events_rx, sync_metrics: SyncMetrics::default() }
     }
 
+    /// Registers all metrics of the sync pipeline with the given registrar.
+    pub fn register_metrics(&self, registrar: &mut MetricsRegistrar) {
+        crate::metrics::register_metrics(registrar)
+    }
+
     fn handle_event(&mut self, event: MetricEvent) {
         trace!(target: "sync::metrics", ?event, "Metric event received");
         match event {
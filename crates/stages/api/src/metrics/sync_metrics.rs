@@ -1,10 +1,17 @@
 use crate::StageId;
 use reth_metrics::{
     metrics::{Counter, Gauge},
-    Metrics,
+    Metrics, MetricsRegistrar,
 };
 use std::collections::HashMap;
 
+/// Declares all metrics of the sync pipeline.
+pub(crate) fn register_metrics(registrar: &mut MetricsRegistrar) {
+    registrar
+        .describe_with_labels("sync", &["stage"], StageMetrics::describe)
+        .describe("sync", ExecutionStageMetrics::describe);
+}
+
 #[derive(Debug, Default)]
 pub(crate) struct SyncMetrics {
     pub(crate) stages: HashMap<StageId, StageMetrics>,
@@ -18,6 +18,8 @@ where
     /// A receiver for the current chain tip to sync to.
     tip_tx: Option<watch::Sender<B256>>,
     metrics_tx: Option<MetricEventsSender>,
+    /// Whether stages may be unwound below their unwind horizon.
+    lossy_unwind: bool,
 }
 
 impl<DB> PipelineBuilder<DB>
@@ -67,13 +69,20 @@ where
         self
     }
 
+    /// Allow unwinding stages below their [`UnwindHorizon`](crate::UnwindHorizon), if all of them
+    /// [can unwind lossy](Stage::can_unwind_lossy).
+    pub const fn with_lossy_unwind(mut self, lossy_unwind: bool) -> Self {
+        self.lossy_unwind = lossy_unwind;
+        self
+    }
+
     /// Builds the final [`Pipeline`] using the given database.
     pub fn build(
         self,
         provider_factory: ProviderFactory<DB>,
         static_file_producer: StaticFileProducer<DB>,
     ) -> Pipeline<DB> {
-        let Self { stages, max_block, tip_tx, metrics_tx } = self;
+        let Self { stages, max_block, tip_tx, metrics_tx, lossy_unwind } = self;
         Pipeline {
             provider_factory,
             stages,
@@ -83,13 +92,20 @@ where
             event_sender: Default::default(),
             progress: Default::default(),
             metrics_tx,
+            lossy_unwind,
         }
     }
 }
 
 impl<DB: Database> Default for PipelineBuilder<DB> {
     fn default() -> Self {
-        Self { stages: Vec::new(), max_block: None, tip_tx: None, metrics_tx: None }
+        Self {
+            stages: Vec::new(),
+            max_block: None,
+            tip_tx: None,
+            metrics_tx: None,
+            lossy_unwind: false,
+        }
     }
 }
 
@@ -98,6 +114,7 @@ impl<DB: Database> std::fmt::Debug for PipelineBuilder<DB> {
         f.debug_struct("PipelineBuilder")
             .field("stages", &self.stages.iter().map(|stage| stage.id()).collect::<Vec<StageId>>())
             .field("max_block", &self.max_block)
+            .field("lossy_unwind", &self.lossy_unwind)
             .finish()
     }
 }
@@ -12,6 +12,14 @@ pub enum ControlFlow {
         /// The block that caused the unwind.
         bad_block: Box<SealedHeader>,
     },
+    /// The local head is no longer canonical and an unwind to the last block it has in common
+    /// with the new canonical chain was requested. It must be performed before continuing.
+    ///
+    /// Unlike [`ControlFlow::Unwind`], no block was found to be invalid.
+    Reorg {
+        /// The block to unwind to.
+        target: BlockNumber,
+    },
     /// The pipeline made progress.
     Continue {
         /// Block number reached by the stage.
@@ -32,13 +40,13 @@ impl ControlFlow {
 
     /// Returns true if the control flow is unwind.
     pub const fn is_unwind(&self) -> bool {
-        matches!(self, Self::Unwind { .. })
+        matches!(self, Self::Unwind { .. } | Self::Reorg { .. })
     }
 
     /// Returns the pipeline block number the stage reached, if the state is not `Unwind`.
     pub const fn block_number(&self) -> Option<BlockNumber> {
         match self {
-            Self::Unwind { .. } => None,
+            Self::Unwind { .. } | Self::Reorg { .. } => None,
             Self::Continue { block_number } => Some(*block_number),
             Self::NoProgress { block_number } => *block_number,
         }
@@ -0,0 +1,66 @@
+use crate::StageId;
+use reth_primitives::BlockNumber;
+use reth_provider::{ProviderResult, PruneCheckpointReader};
+use reth_prune::PruneSegment;
+use std::fmt;
+
+/// The lowest block a stage can be unwound to, because the data required to unwind it further was
+/// pruned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnwindHorizon {
+    /// The lowest block the stage can be unwound to.
+    pub block: BlockNumber,
+    /// The stage that can't be unwound below the block.
+    pub stage_id: StageId,
+    /// The pruned segment the stage requires to unwind.
+    pub segment: PruneSegment,
+}
+
+impl fmt::Display for UnwindHorizon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "stage {} requires {}, which was pruned up to block {}",
+            self.stage_id, self.segment, self.block
+        )
+    }
+}
+
+/// Returns the pruned segments a stage reads to unwind.
+///
+/// The changesets of a block are pruned together with the history of the accounts and storage
+/// slots, and unwinding the state requires the changesets of every unwound block. Data in static
+/// files, like headers, transactions and receipts, is truncated on unwind and never limits it.
+pub fn unwind_requirements(stage_id: StageId) -> &'static [PruneSegment] {
+    match stage_id {
+        StageId::Execution | StageId::MerkleUnwind => {
+            &[PruneSegment::AccountHistory, PruneSegment::StorageHistory]
+        }
+        StageId::AccountHashing | StageId::IndexAccountHistory => &[PruneSegment::AccountHistory],
+        StageId::StorageHashing | StageId::IndexStorageHistory => &[PruneSegment::StorageHistory],
+        _ => &[],
+    }
+}
+
+/// Returns the highest [`UnwindHorizon`] of the stages, i.e. the lowest block all of them can be
+/// unwound to, or `None` if none of the data they require to unwind was pruned.
+pub fn unwind_horizon(
+    provider: &impl PruneCheckpointReader,
+    stages: impl IntoIterator<Item = StageId>,
+) -> ProviderResult<Option<UnwindHorizon>> {
+    let mut horizon: Option<UnwindHorizon> = None;
+    for stage_id in stages {
+        for segment in unwind_requirements(stage_id) {
+            let Some(block) = provider
+                .get_prune_checkpoint(*segment)?
+                .and_then(|checkpoint| checkpoint.block_number)
+            else {
+                continue
+            };
+            if horizon.map_or(true, |horizon| block > horizon.block) {
+                horizon = Some(UnwindHorizon { block, stage_id, segment: *segment });
+            }
+        }
+    }
+    Ok(horizon)
+}
@@ -11,7 +11,7 @@ use reth_primitives::{
 };
 use reth_provider::{
     providers::StaticFileWriter, FinalizedBlockReader, FinalizedBlockWriter, ProviderFactory,
-    StageCheckpointReader, StageCheckpointWriter, StaticFileProviderFactory,
+    PruneCheckpointReader, StageCheckpointReader, StageCheckpointWriter, StaticFileProviderFactory,
 };
 use reth_prune::PrunerBuilder;
 use reth_static_file::StaticFileProducer;
@@ -21,6 +21,8 @@ use tokio::sync::watch;
 use tracing::*;
 
 mod builder;
+mod horizon;
+mod plan;
 mod progress;
 mod set;
 
@@ -29,6 +31,8 @@ use crate::{
     StageError, StageExt, UnwindInput,
 };
 pub use builder::*;
+pub use horizon::{unwind_horizon, unwind_requirements, UnwindHorizon};
+pub use plan::{PipelinePlan, PlanIssue, StageAction, StagePlan};
 use progress::*;
 use reth_errors::RethResult;
 pub use set::*;
@@ -80,6 +84,8 @@ pub struct Pipeline<DB: Database> {
     /// A receiver for the current chain tip to sync to.
     tip_tx: Option<watch::Sender<B256>>,
     metrics_tx: Option<MetricEventsSender>,
+    /// Whether stages may be unwound below their unwind horizon.
+    lossy_unwind: bool,
 }
 
 impl<DB> Pipeline<DB>
@@ -126,6 +132,30 @@ where
         Ok(())
     }
 
+    /// Plans a run of the pipeline to the target block without executing it.
+    ///
+    /// Returns what each stage would do to reach the target, executing or unwinding, with an
+    /// estimate of its work, and the problems that would make the run fail midway, like missing
+    /// headers or bodies the stages require. Only a read-only transaction is opened.
+    pub fn plan(&self, target: BlockNumber) -> Result<PipelinePlan, PipelineError> {
+        let provider = self.provider_factory.provider()?;
+
+        let mut stages = Vec::with_capacity(self.stages.len());
+        for stage in &self.stages {
+            let stage_id = stage.id();
+            let checkpoint = provider.get_stage_checkpoint(stage_id)?;
+            let action = StageAction::new(checkpoint.unwrap_or_default().block_number, target);
+            let estimate = match action.range() {
+                Some(range) => stage.estimate(&provider, range.clone())?,
+                None => Default::default(),
+            };
+            stages.push(StagePlan { stage_id, checkpoint, action, estimate });
+        }
+
+        let issues = plan::find_issues(&provider, &stages, target)?;
+        Ok(PipelinePlan { target, stages, issues })
+    }
+
     /// Consume the pipeline and run it until it reaches the provided tip, if set. Return the
     /// pipeline and its result as a future.
     #[track_caller]
@@ -195,8 +225,8 @@ where
     ///
     /// Returns the control flow after it ran the pipeline.
     /// This will be [`ControlFlow::Continue`] or [`ControlFlow::NoProgress`] of the _last_ stage in
-    /// the pipeline (for example the `Finish` stage). Or [`ControlFlow::Unwind`] of the stage
-    /// that caused the unwind.
+    /// the pipeline (for example the `Finish` stage). Or [`ControlFlow::Unwind`] or
+    /// [`ControlFlow::Reorg`] of the stage that caused the unwind.
     pub async fn run_loop(&mut self) -> Result<ControlFlow, PipelineError> {
         self.move_to_static_files()?;
 
@@ -221,6 +251,10 @@ where
                     self.unwind(target, Some(bad_block.number))?;
                     return Ok(ControlFlow::Unwind { target, bad_block })
                 }
+                ControlFlow::Reorg { target } => {
+                    self.unwind(target, None)?;
+                    return Ok(ControlFlow::Reorg { target })
+                }
             }
 
             previous_stage = Some(
@@ -287,16 +321,21 @@ where
     /// Unwind the stages to the target block.
     ///
     /// If the unwind is due to a bad block the number of that block should be specified.
+    ///
+    /// Fails with [`PipelineError::UnwindBelowHorizon`] without unwinding any stage if the data a
+    /// stage requires to unwind to the target was pruned, unless lossy unwinds were allowed, see
+    /// [`PipelineBuilder::with_lossy_unwind`].
     pub fn unwind(
         &mut self,
         to: BlockNumber,
         bad_block: Option<BlockNumber>,
     ) -> Result<(), PipelineError> {
+        let mut provider_rw = self.provider_factory.provider_rw()?;
+        self.check_unwind_horizon(&*provider_rw, to)?;
+
         // Unwind stages in reverse order of execution
         let unwind_pipeline = self.stages.iter_mut().rev();
 
-        let mut provider_rw = self.provider_factory.provider_rw()?;
-
         for stage in unwind_pipeline {
             let stage_id = stage.id();
             let span = info_span!("Unwinding", stage = %stage_id);
@@ -375,7 +414,11 @@ where
                     Err(err) => {
                         self.event_sender.notify(PipelineEvent::Error { stage_id });
 
-                        return Err(PipelineError::Stage(StageError::Fatal(Box::new(err))))
+                        let block = err.block_number().unwrap_or(checkpoint.block_number);
+                        error!(target: "sync::pipeline", stage = %stage_id, block, error = %err, "Stage encountered an error while unwinding");
+                        return Err(PipelineError::Stage(
+                            err.with_context(stage_id, checkpoint.block_number),
+                        ))
                     }
                 }
             }
@@ -384,6 +427,48 @@ where
         Ok(())
     }
 
+    /// Returns an error if a stage that would be unwound can't be unwound to the target, because
+    /// the data it requires to unwind was pruned.
+    ///
+    /// Stages are only unwound below their horizon if lossy unwinds were allowed and every one of
+    /// them can unwind lossy.
+    fn check_unwind_horizon(
+        &self,
+        provider: &(impl StageCheckpointReader + PruneCheckpointReader),
+        to: BlockNumber,
+    ) -> Result<(), PipelineError> {
+        let mut horizon: Option<UnwindHorizon> = None;
+        let mut lossy = true;
+        for stage in &self.stages {
+            let stage_id = stage.id();
+            let checkpoint = provider.get_stage_checkpoint(stage_id)?.unwrap_or_default();
+            if checkpoint.block_number <= to {
+                continue
+            }
+            let Some(stage_horizon) = unwind_horizon(provider, [stage_id])?
+                .filter(|stage_horizon| stage_horizon.block > to)
+            else {
+                continue
+            };
+            lossy &= self.lossy_unwind && stage.can_unwind_lossy();
+            if horizon.map_or(true, |horizon| stage_horizon.block > horizon.block) {
+                horizon = Some(stage_horizon);
+            }
+        }
+
+        let Some(horizon) = horizon else { return Ok(()) };
+        if lossy {
+            warn!(
+                target: "sync::pipeline",
+                %to,
+                %horizon,
+                "Unwinding below the unwind horizon, unwound stages may be left inconsistent"
+            );
+            return Ok(())
+        }
+        Err(PipelineError::UnwindBelowHorizon { target: to, horizon })
+    }
+
     async fn execute_stage_to_completion(
         &mut self,
         previous_stage: Option<BlockNumber>,
@@ -512,6 +597,11 @@ fn on_stage_error<DB: Database>(
     prev_checkpoint: Option<StageCheckpoint>,
     err: StageError,
 ) -> Result<Option<ControlFlow>, PipelineError> {
+    // The block the stage run started at, for errors that don't refer to a particular block.
+    let first_block = prev_checkpoint.unwrap_or_default().block_number + 1;
+    let block_number = err.block_number().unwrap_or(first_block);
+    let err = err.into_inner();
+
     if let StageError::DetachedHead { local_head, header, error } = err {
         warn!(target: "sync::pipeline", stage = %stage_id, ?local_head, ?header, %error, "Stage encountered detached head");
 
@@ -519,6 +609,12 @@ fn on_stage_error<DB: Database>(
         let unwind_to =
             local_head.number.saturating_sub(BEACON_CONSENSUS_REORG_UNWIND_DEPTH).max(1);
         Ok(Some(ControlFlow::Unwind { target: unwind_to, bad_block: local_head }))
+    } else if let StageError::ReorgedHead { local_head, fork_block } = err {
+        warn!(target: "sync::pipeline", stage = %stage_id, ?local_head, %fork_block, "Stage encountered reorged head");
+
+        // The downloaded headers attach to an ancestor of the local head, so we unwind to it. The
+        // local head was only reorged out, it's not invalid.
+        Ok(Some(ControlFlow::Reorg { target: fork_block }))
     } else if let StageError::Block { block, error } = err {
         match error {
             BlockErrorKind::Validation(validation_error) => {
@@ -579,14 +675,15 @@ fn on_stage_error<DB: Database>(
 
         Ok(Some(ControlFlow::Unwind { target: block.number - 1, bad_block: block }))
     } else if err.is_fatal() {
-        error!(target: "sync::pipeline", stage = %stage_id, "Stage encountered a fatal error: {err}");
-        Err(err.into())
+        error!(target: "sync::pipeline", stage = %stage_id, block = block_number, error = %err, "Stage encountered a fatal error");
+        Err(err.with_context(stage_id, first_block).into())
     } else {
         // On other errors we assume they are recoverable if we discard the
         // transaction and run the stage again.
         warn!(
             target: "sync::pipeline",
             stage = %stage_id,
+            block = block_number,
             "Stage encountered a non-fatal error: {err}. Retrying..."
         );
         Ok(None)
@@ -598,6 +695,7 @@ impl<DB: Database> std::fmt::Debug for Pipeline<DB> {
         f.debug_struct("Pipeline")
             .field("stages", &self.stages.iter().map(|stage| stage.id()).collect::<Vec<StageId>>())
             .field("max_block", &self.max_block)
+            .field("lossy_unwind", &self.lossy_unwind)
             .field("event_sender", &self.event_sender)
             .finish()
     }
@@ -606,13 +704,18 @@ impl<DB: Database> std::fmt::Debug for Pipeline<DB> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{test_utils::TestStage, UnwindOutput};
+    use crate::{test_utils::TestStage, StageEstimate, UnwindOutput};
     use assert_matches::assert_matches;
     use reth_consensus::ConsensusError;
     use reth_errors::ProviderError;
-    use reth_provider::test_utils::create_test_provider_factory;
-    use reth_prune::PruneModes;
-    use reth_testing_utils::{generators, generators::random_header};
+    use reth_provider::{
+        test_utils::create_test_provider_factory, BlockWriter, PruneCheckpointWriter,
+    };
+    use reth_prune::{PruneCheckpoint, PruneMode, PruneModes, PruneSegment};
+    use reth_testing_utils::{
+        generators,
+        generators::{random_block_range, random_header},
+    };
     use tokio_stream::StreamExt;
 
     #[test]
@@ -642,6 +745,195 @@ mod tests {
         assert_eq!(progress.next_ctrl(), ControlFlow::Continue { block_number: 1 });
     }
 
+    /// Plans a run over a database with a gap in the bodies.
+    #[test]
+    fn plan_flags_missing_body() {
+        let provider_factory = create_test_provider_factory();
+        let mut rng = generators::rng();
+
+        let provider_rw = provider_factory.provider_rw().unwrap();
+        for block in random_block_range(&mut rng, 0..=10, B256::ZERO, 0..1) {
+            // leave a gap at block 5
+            if block.number != 5 {
+                provider_rw.insert_block(block.seal_with_senders().unwrap(), None).unwrap();
+            }
+        }
+        provider_rw.save_stage_checkpoint(StageId::Bodies, StageCheckpoint::new(10)).unwrap();
+        provider_rw.save_stage_checkpoint(StageId::Execution, StageCheckpoint::new(2)).unwrap();
+        provider_rw.commit().unwrap();
+
+        let pipeline = Pipeline::builder()
+            .add_stage(TestStage::new(StageId::Bodies))
+            .add_stage(TestStage::new(StageId::Execution))
+            .add_stage(TestStage::new(StageId::Other("A")))
+            .build(
+                provider_factory.clone(),
+                StaticFileProducer::new(provider_factory.clone(), PruneModes::default()),
+            );
+
+        let plan = pipeline.plan(10).unwrap();
+        assert_eq!(
+            plan.stages.iter().map(|stage| stage.action.clone()).collect::<Vec<_>>(),
+            vec![StageAction::Skip, StageAction::Execute(3..=10), StageAction::Execute(1..=10)]
+        );
+        // the execution would fail at the gap
+        assert_eq!(
+            plan.issues,
+            vec![PlanIssue::MissingBody { stage_id: StageId::Execution, block: 5 }]
+        );
+        assert_eq!(plan.stages[1].estimate, StageEstimate::Unknown);
+
+        // unwinding doesn't require the bodies
+        let plan = pipeline.plan(1).unwrap();
+        assert_eq!(plan.stages[1].action, StageAction::Unwind(2..=2));
+        assert!(plan.is_ok());
+    }
+
+    /// Unwinds a pipeline over a database with pruned history.
+    #[test]
+    fn unwind_below_prune_horizon() {
+        let provider_factory = create_test_provider_factory();
+
+        let provider_rw = provider_factory.provider_rw().unwrap();
+        for stage_id in [StageId::Execution, StageId::IndexAccountHistory, StageId::Other("A")] {
+            provider_rw.save_stage_checkpoint(stage_id, StageCheckpoint::new(100)).unwrap();
+        }
+        for (segment, pruned_to) in
+            [(PruneSegment::AccountHistory, 50), (PruneSegment::StorageHistory, 40)]
+        {
+            provider_rw
+                .save_prune_checkpoint(
+                    segment,
+                    PruneCheckpoint {
+                        block_number: Some(pruned_to),
+                        tx_number: None,
+                        prune_mode: PruneMode::Before(pruned_to + 1),
+                    },
+                )
+                .unwrap();
+        }
+        provider_rw.commit().unwrap();
+
+        let pipeline = |lossy_stages: bool, lossy_unwind: bool| {
+            Pipeline::builder()
+                .add_stage(
+                    TestStage::new(StageId::Execution)
+                        .add_unwind(Ok(UnwindOutput { checkpoint: StageCheckpoint::new(30) }))
+                        .with_lossy_unwind(lossy_stages),
+                )
+                .add_stage(
+                    TestStage::new(StageId::IndexAccountHistory)
+                        .add_unwind(Ok(UnwindOutput { checkpoint: StageCheckpoint::new(30) }))
+                        .with_lossy_unwind(lossy_stages),
+                )
+                .add_stage(
+                    TestStage::new(StageId::Other("A"))
+                        .add_unwind(Ok(UnwindOutput { checkpoint: StageCheckpoint::new(30) })),
+                )
+                .with_lossy_unwind(lossy_unwind)
+                .build(
+                    provider_factory.clone(),
+                    StaticFileProducer::new(provider_factory.clone(), PruneModes::default()),
+                )
+        };
+        let expected_horizon = UnwindHorizon {
+            block: 50,
+            stage_id: StageId::Execution,
+            segment: PruneSegment::AccountHistory,
+        };
+
+        // the plan flags the pruned history of both stages
+        assert_eq!(
+            pipeline(false, false).plan(30).unwrap().issues,
+            vec![
+                PlanIssue::PrunedData {
+                    stage_id: StageId::Execution,
+                    segment: PruneSegment::AccountHistory,
+                    target: 30,
+                    pruned_to: 50,
+                },
+                PlanIssue::PrunedData {
+                    stage_id: StageId::Execution,
+                    segment: PruneSegment::StorageHistory,
+                    target: 30,
+                    pruned_to: 40,
+                },
+                PlanIssue::PrunedData {
+                    stage_id: StageId::IndexAccountHistory,
+                    segment: PruneSegment::AccountHistory,
+                    target: 30,
+                    pruned_to: 50,
+                },
+            ]
+        );
+
+        // refused without lossy unwinds, or if the stages can't unwind lossy
+        for (lossy_stages, lossy_unwind) in [(true, false), (false, true)] {
+            assert_matches!(
+                pipeline(lossy_stages, lossy_unwind).unwind(30, None),
+                Err(PipelineError::UnwindBelowHorizon { target: 30, horizon })
+                    if horizon == expected_horizon
+            );
+        }
+        // nothing was unwound
+        let provider = provider_factory.provider().unwrap();
+        assert_eq!(
+            provider.get_stage_checkpoint(StageId::Execution).unwrap(),
+            Some(StageCheckpoint::new(100))
+        );
+        drop(provider);
+
+        pipeline(true, true).unwind(30, None).unwrap();
+        let provider = provider_factory.provider().unwrap();
+        for stage_id in [StageId::Execution, StageId::IndexAccountHistory, StageId::Other("A")] {
+            assert_eq!(
+                provider.get_stage_checkpoint(stage_id).unwrap(),
+                Some(StageCheckpoint::new(30))
+            );
+        }
+    }
+
+    /// Unwinds a pipeline to its prune horizon.
+    #[test]
+    fn unwind_to_prune_horizon() {
+        let provider_factory = create_test_provider_factory();
+
+        let provider_rw = provider_factory.provider_rw().unwrap();
+        provider_rw.save_stage_checkpoint(StageId::Execution, StageCheckpoint::new(100)).unwrap();
+        provider_rw
+            .save_prune_checkpoint(
+                PruneSegment::StorageHistory,
+                PruneCheckpoint {
+                    block_number: Some(50),
+                    tx_number: None,
+                    prune_mode: PruneMode::Before(51),
+                },
+            )
+            .unwrap();
+        provider_rw.commit().unwrap();
+
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Execution)
+                    .add_unwind(Ok(UnwindOutput { checkpoint: StageCheckpoint::new(50) })),
+            )
+            .build(
+                provider_factory.clone(),
+                StaticFileProducer::new(provider_factory.clone(), PruneModes::default()),
+            );
+
+        assert_matches!(
+            pipeline.unwind(49, None),
+            Err(PipelineError::UnwindBelowHorizon { target: 49, horizon })
+                if horizon.block == 50 && horizon.segment == PruneSegment::StorageHistory
+        );
+        pipeline.unwind(50, None).unwrap();
+        assert_eq!(
+            provider_factory.provider().unwrap().get_stage_checkpoint(StageId::Execution).unwrap(),
+            Some(StageCheckpoint::new(50))
+        );
+    }
+
     /// Runs a simple pipeline.
     #[tokio::test]
     async fn run_pipeline() {
@@ -1065,6 +1357,49 @@ mod tests {
         );
     }
 
+    /// Checks that a reorged head unwinds the pipeline to the fork block without reporting a bad
+    /// block.
+    #[tokio::test]
+    async fn run_pipeline_with_reorged_head() {
+        let provider_factory = create_test_provider_factory();
+
+        let mut pipeline = Pipeline::builder()
+            .add_stage(
+                TestStage::new(StageId::Other("A"))
+                    .add_exec(Ok(ExecOutput { checkpoint: StageCheckpoint::new(10), done: true }))
+                    .add_unwind(Ok(UnwindOutput { checkpoint: StageCheckpoint::new(7) })),
+            )
+            .add_stage(TestStage::new(StageId::Other("B")).add_exec(Err(StageError::ReorgedHead {
+                local_head: Box::new(random_header(&mut generators::rng(), 10, None)),
+                fork_block: 7,
+            })))
+            .with_max_block(10)
+            .build(
+                provider_factory.clone(),
+                StaticFileProducer::new(provider_factory.clone(), PruneModes::default()),
+            );
+        let events = pipeline.events();
+
+        let result = pipeline.run_loop().await;
+        assert_matches!(result, Ok(ControlFlow::Reorg { target: 7 }));
+        drop(pipeline);
+
+        let unwinds = events
+            .filter_map(|event| match event {
+                PipelineEvent::Unwind { stage_id, input } => Some((stage_id, input)),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            unwinds,
+            vec![(
+                StageId::Other("A"),
+                UnwindInput { checkpoint: StageCheckpoint::new(10), unwind_to: 7, bad_block: None }
+            )]
+        );
+    }
+
     /// Checks that the pipeline re-runs stages on non-fatal errors and stops on fatal ones.
     #[tokio::test]
     async fn pipeline_error_handling() {
@@ -1097,9 +1432,77 @@ mod tests {
         let result = pipeline.run().await;
         assert_matches!(
             result,
-            Err(PipelineError::Stage(StageError::DatabaseIntegrity(
-                ProviderError::BlockBodyIndicesNotFound(5)
-            )))
+            Err(PipelineError::Stage(StageError::Context {
+                stage_id: StageId::Other("Fatal"),
+                block_number: 5,
+                error,
+            })) if matches!(
+                *error,
+                StageError::DatabaseIntegrity(ProviderError::BlockBodyIndicesNotFound(5))
+            )
+        );
+    }
+
+    /// Checks that fatal errors carry the stage and the block they were encountered at.
+    #[tokio::test]
+    async fn pipeline_error_context() {
+        let run = |stage_id, checkpoint, error| async move {
+            let provider_factory = create_test_provider_factory();
+            let provider_rw = provider_factory.provider_rw().unwrap();
+            provider_rw.save_stage_checkpoint(stage_id, StageCheckpoint::new(checkpoint)).unwrap();
+            provider_rw.commit().unwrap();
+
+            let mut pipeline = Pipeline::builder()
+                .add_stage(TestStage::new(stage_id).add_exec(Err(error)))
+                .build(
+                    provider_factory.clone(),
+                    StaticFileProducer::new(provider_factory.clone(), PruneModes::default()),
+                );
+            match pipeline.run().await {
+                Err(PipelineError::Stage(err)) => err,
+                res => panic!("expected a stage error, got {res:?}"),
+            }
+        };
+
+        // Headers: the error refers to a block
+        let err = run(
+            StageId::Headers,
+            10,
+            StageError::DatabaseIntegrity(ProviderError::TotalDifficultyNotFound(7)),
+        )
+        .await;
+        assert_eq!(err.stage_id(), Some(StageId::Headers));
+        assert_eq!(err.block_number(), Some(7));
+        assert_eq!(
+            err.to_string(),
+            "stage Headers failed at block #7: \
+             database integrity error occurred: total difficulty not found for block #7"
+        );
+
+        // Headers: the error refers to no block, the first block of the run is used
+        let err = run(StageId::Headers, 10, StageError::MissingSyncGap).await;
+        assert_eq!(err.stage_id(), Some(StageId::Headers));
+        assert_eq!(err.block_number(), Some(11));
+        assert_matches!(err.inner(), StageError::MissingSyncGap);
+
+        // Execution: the error refers to a block
+        let err = run(
+            StageId::Execution,
+            20,
+            StageError::DatabaseIntegrity(ProviderError::HeaderNotFound(21.into())),
+        )
+        .await;
+        assert_eq!(err.stage_id(), Some(StageId::Execution));
+        assert_eq!(err.block_number(), Some(21));
+        assert!(err.is_fatal());
+
+        // Execution: an opaque fatal error
+        let err = run(StageId::Execution, 20, StageError::Fatal(Box::new(std::fmt::Error))).await;
+        assert_eq!(err.stage_id(), Some(StageId::Execution));
+        assert_eq!(err.block_number(), Some(21));
+        assert_eq!(
+            err.to_string(),
+            "stage Execution failed at block #21: an error occurred when formatting an argument"
         );
     }
 }
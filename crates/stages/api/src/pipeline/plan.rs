@@ -0,0 +1,250 @@
+use super::horizon::unwind_requirements;
+use crate::{StageCheckpoint, StageEstimate, StageId};
+use reth_db_api::database::Database;
+use reth_primitives::BlockNumber;
+use reth_provider::{
+    BlockHashReader, BlockReader, DatabaseProviderRO, ProviderResult, PruneCheckpointReader,
+};
+use reth_prune::PruneSegment;
+use std::{cmp::Ordering, fmt, ops::RangeInclusive};
+
+/// What a stage would do to reach the target of a [`PipelinePlan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StageAction {
+    /// The stage is already at the target.
+    Skip,
+    /// The stage would execute the block range.
+    Execute(RangeInclusive<BlockNumber>),
+    /// The stage would unwind the block range.
+    Unwind(RangeInclusive<BlockNumber>),
+}
+
+impl StageAction {
+    /// Returns the action that moves a stage from its checkpoint to the target.
+    pub(crate) fn new(checkpoint: BlockNumber, target: BlockNumber) -> Self {
+        match checkpoint.cmp(&target) {
+            Ordering::Less => Self::Execute(checkpoint + 1..=target),
+            Ordering::Equal => Self::Skip,
+            Ordering::Greater => Self::Unwind(target + 1..=checkpoint),
+        }
+    }
+
+    /// Returns the block range the stage would process, if any.
+    pub const fn range(&self) -> Option<&RangeInclusive<BlockNumber>> {
+        match self {
+            Self::Skip => None,
+            Self::Execute(range) | Self::Unwind(range) => Some(range),
+        }
+    }
+}
+
+impl fmt::Display for StageAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Skip => write!(f, "skip"),
+            Self::Execute(range) => write!(f, "execute {}..={}", range.start(), range.end()),
+            Self::Unwind(range) => write!(f, "unwind {}..={}", range.start(), range.end()),
+        }
+    }
+}
+
+/// The planned work of a single stage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagePlan {
+    /// The stage.
+    pub stage_id: StageId,
+    /// The current checkpoint of the stage.
+    pub checkpoint: Option<StageCheckpoint>,
+    /// What the stage would do.
+    pub action: StageAction,
+    /// The estimated work of the action.
+    pub estimate: StageEstimate,
+}
+
+/// A problem that would make the pipeline fail midway through the run.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PlanIssue {
+    /// A stage has no checkpoint, but a later stage made progress.
+    #[error("stage {stage_id} has no checkpoint, but stage {later_stage_id} is at block {later_checkpoint}")]
+    MissingCheckpoint {
+        /// The stage without checkpoint.
+        stage_id: StageId,
+        /// The later stage that made progress.
+        later_stage_id: StageId,
+        /// The checkpoint of the later stage.
+        later_checkpoint: BlockNumber,
+    },
+    /// A header below the checkpoint of the headers stage is missing.
+    #[error("header of block {block} is missing, but stage {stage_id} requires it")]
+    MissingHeader {
+        /// The first stage that requires the header.
+        stage_id: StageId,
+        /// The first block with a missing header.
+        block: BlockNumber,
+    },
+    /// A body below the checkpoint of the bodies stage is missing.
+    #[error("body of block {block} is missing, but stage {stage_id} requires it")]
+    MissingBody {
+        /// The first stage that requires the body.
+        stage_id: StageId,
+        /// The first block with a missing body.
+        block: BlockNumber,
+    },
+    /// Data required to unwind a stage was pruned.
+    #[error("stage {stage_id} can't unwind to block {target}, {segment} was pruned up to block {pruned_to}")]
+    PrunedData {
+        /// The stage that would be unwound.
+        stage_id: StageId,
+        /// The pruned segment.
+        segment: PruneSegment,
+        /// The unwind target.
+        target: BlockNumber,
+        /// The highest pruned block.
+        pruned_to: BlockNumber,
+    },
+}
+
+/// What a pipeline run to the target block would do, see [`Pipeline::plan`].
+///
+/// [`Pipeline::plan`]: crate::Pipeline::plan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipelinePlan {
+    /// The target block of the run.
+    pub target: BlockNumber,
+    /// The planned work of the stages in the order they would run.
+    pub stages: Vec<StagePlan>,
+    /// The problems that would make the run fail.
+    pub issues: Vec<PlanIssue>,
+}
+
+impl PipelinePlan {
+    /// Returns `true` if no problems were found.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for PipelinePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Pipeline plan to block {}", self.target)?;
+        for stage in &self.stages {
+            let checkpoint = stage.checkpoint.map_or_else(
+                || "none".to_string(),
+                |checkpoint| checkpoint.block_number.to_string(),
+            );
+            write!(f, "  {:<20} checkpoint {checkpoint:<10} {}", stage.stage_id, stage.action)?;
+            if stage.action != StageAction::Skip {
+                write!(f, " ({})", stage.estimate)?;
+            }
+            writeln!(f)?;
+        }
+
+        if self.issues.is_empty() {
+            writeln!(f, "No issues found")
+        } else {
+            writeln!(f, "Issues:")?;
+            for issue in &self.issues {
+                writeln!(f, "  {issue}")?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Finds the problems that would make a run of the planned stages to the target fail.
+///
+/// This checks that every header and body below the checkpoints of the headers and bodies stages
+/// that a later stage would process is stored, so it reads each of these blocks.
+pub(crate) fn find_issues<DB: Database>(
+    provider: &DatabaseProviderRO<DB>,
+    stages: &[StagePlan],
+    target: BlockNumber,
+) -> ProviderResult<Vec<PlanIssue>> {
+    let mut issues = Vec::new();
+
+    for (index, stage) in stages.iter().enumerate() {
+        if stage.checkpoint.is_some() {
+            continue
+        }
+        let later = stages[index + 1..].iter().find_map(|later| {
+            later
+                .checkpoint
+                .filter(|checkpoint| checkpoint.block_number > 0)
+                .map(|checkpoint| (later.stage_id, checkpoint.block_number))
+        });
+        if let Some((later_stage_id, later_checkpoint)) = later {
+            issues.push(PlanIssue::MissingCheckpoint {
+                stage_id: stage.stage_id,
+                later_stage_id,
+                later_checkpoint,
+            });
+        }
+    }
+
+    if let Some((stage_id, block)) = first_missing(stages, StageId::Headers, target, |block| {
+        Ok(provider.block_hash(block)?.is_none())
+    })? {
+        issues.push(PlanIssue::MissingHeader { stage_id, block });
+    }
+    if let Some((stage_id, block)) = first_missing(stages, StageId::Bodies, target, |block| {
+        Ok(provider.block_body_indices(block)?.is_none())
+    })? {
+        issues.push(PlanIssue::MissingBody { stage_id, block });
+    }
+
+    for stage in stages {
+        let StageAction::Unwind(_) = stage.action else { continue };
+        for segment in unwind_requirements(stage.stage_id) {
+            let pruned_to = provider
+                .get_prune_checkpoint(*segment)?
+                .and_then(|checkpoint| checkpoint.block_number);
+            if let Some(pruned_to) = pruned_to.filter(|pruned_to| *pruned_to > target) {
+                issues.push(PlanIssue::PrunedData {
+                    stage_id: stage.stage_id,
+                    segment: *segment,
+                    target,
+                    pruned_to,
+                });
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Returns the first block the stages after the source stage would execute, that should already
+/// be stored by the source stage but is missing, together with the first stage requiring it.
+fn first_missing(
+    stages: &[StagePlan],
+    source: StageId,
+    target: BlockNumber,
+    mut is_missing: impl FnMut(BlockNumber) -> ProviderResult<bool>,
+) -> ProviderResult<Option<(StageId, BlockNumber)>> {
+    let Some(index) = stages.iter().position(|stage| stage.stage_id == source) else {
+        return Ok(None)
+    };
+    let stored_to = stages[index].checkpoint.unwrap_or_default().block_number.min(target);
+
+    let consumers = stages[index + 1..]
+        .iter()
+        .filter_map(|stage| match &stage.action {
+            StageAction::Execute(range) => Some((stage.stage_id, range)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let Some(start) = consumers.iter().map(|(_, range)| *range.start()).min() else {
+        return Ok(None)
+    };
+
+    for block in start..=stored_to {
+        if is_missing(block)? {
+            let stage_id = consumers
+                .iter()
+                .find(|(_, range)| range.contains(&block))
+                .map(|(stage_id, _)| *stage_id)
+                .expect("block is in the range of a stage");
+            return Ok(Some((stage_id, block)))
+        }
+    }
+    Ok(None)
+}
@@ -1,9 +1,13 @@
 use crate::{error::StageError, StageCheckpoint, StageId};
 use reth_db_api::database::Database;
 use reth_primitives::{BlockNumber, TxNumber};
-use reth_provider::{BlockReader, DatabaseProviderRW, ProviderError, TransactionsProvider};
+use reth_provider::{
+    BlockReader, DatabaseProviderRO, DatabaseProviderRW, ProviderError, ProviderResult,
+    TransactionsProvider,
+};
 use std::{
     cmp::{max, min},
+    fmt,
     future::{poll_fn, Future},
     ops::{Range, RangeInclusive},
     task::{Context, Poll},
@@ -176,6 +180,50 @@ pub struct UnwindOutput {
     pub checkpoint: StageCheckpoint,
 }
 
+/// An estimate of the work a stage would do over a block range, see [`Stage::estimate`].
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum StageEstimate {
+    /// The stage can't estimate its work.
+    #[default]
+    Unknown,
+    /// The stage would process the given number of blocks.
+    Blocks(u64),
+    /// The stage would process the given number of transactions.
+    Transactions(u64),
+}
+
+impl StageEstimate {
+    /// Returns the number of blocks in the range.
+    pub fn blocks(range: RangeInclusive<BlockNumber>) -> Self {
+        Self::Blocks(range.end().saturating_sub(*range.start()) + 1)
+    }
+
+    /// Returns the number of transactions in the range according to the stored block body
+    /// indices, or [`StageEstimate::Unknown`] if the bodies of the range are not stored yet.
+    pub fn transactions<DB: Database>(
+        provider: &DatabaseProviderRO<DB>,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Self> {
+        let (Some(first), Some(last)) = (
+            provider.block_body_indices(*range.start())?,
+            provider.block_body_indices(*range.end())?,
+        ) else {
+            return Ok(Self::Unknown)
+        };
+        Ok(Self::Transactions(last.next_tx_num().saturating_sub(first.first_tx_num())))
+    }
+}
+
+impl fmt::Display for StageEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "unknown"),
+            Self::Blocks(blocks) => write!(f, "{blocks} blocks"),
+            Self::Transactions(transactions) => write!(f, "{transactions} transactions"),
+        }
+    }
+}
+
 /// A stage is a segmented part of the syncing process of the node.
 ///
 /// Each stage takes care of a well-defined task, such as downloading headers or executing
@@ -259,6 +307,27 @@ pub trait Stage<DB: Database>: Send + Sync {
     fn post_unwind_commit(&mut self) -> Result<(), StageError> {
         Ok(())
     }
+
+    /// Returns `true` if the stage can be unwound below the blocks whose data it requires to
+    /// unwind was pruned, leaving the unwound tables inconsistent.
+    ///
+    /// The pipeline only unwinds below the [`UnwindHorizon`](crate::UnwindHorizon) if lossy unwinds
+    /// were allowed and every stage below its horizon returns `true`.
+    fn can_unwind_lossy(&self) -> bool {
+        false
+    }
+
+    /// Estimates the work of executing or unwinding the given block range.
+    ///
+    /// This is used to plan a pipeline run, see [`Pipeline::plan`](crate::Pipeline::plan). It
+    /// must only read from the database.
+    fn estimate(
+        &self,
+        _provider: &DatabaseProviderRO<DB>,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> Result<StageEstimate, StageError> {
+        Ok(StageEstimate::Unknown)
+    }
 }
 
 /// [Stage] trait extension.
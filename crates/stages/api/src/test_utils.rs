@@ -13,11 +13,17 @@ pub struct TestStage {
     id: StageId,
     exec_outputs: VecDeque<Result<ExecOutput, StageError>>,
     unwind_outputs: VecDeque<Result<UnwindOutput, StageError>>,
+    lossy_unwind: bool,
 }
 
 impl TestStage {
     pub fn new(id: StageId) -> Self {
-        Self { id, exec_outputs: VecDeque::new(), unwind_outputs: VecDeque::new() }
+        Self {
+            id,
+            exec_outputs: VecDeque::new(),
+            unwind_outputs: VecDeque::new(),
+            lossy_unwind: false,
+        }
     }
 
     pub fn with_exec(mut self, exec_outputs: VecDeque<Result<ExecOutput, StageError>>) -> Self {
@@ -42,6 +48,11 @@ impl TestStage {
         self.unwind_outputs.push_back(output);
         self
     }
+
+    pub const fn with_lossy_unwind(mut self, lossy_unwind: bool) -> Self {
+        self.lossy_unwind = lossy_unwind;
+        self
+    }
 }
 
 impl<DB: Database> Stage<DB> for TestStage {
@@ -68,4 +79,8 @@ impl<DB: Database> Stage<DB> for TestStage {
             .pop_front()
             .unwrap_or_else(|| panic!("Test stage {} unwound too many times.", self.id))
     }
+
+    fn can_unwind_lossy(&self) -> bool {
+        self.lossy_unwind
+    }
 }
@@ -5,11 +5,18 @@ use pprof::criterion::{Output, PProfProfiler};
 use reth_config::config::{EtlConfig, TransactionLookupConfig};
 use reth_db::{test_utils::TempDatabase, DatabaseEnv};
 
+use reth_evm_ethereum::execute::EthExecutorProvider;
+use reth_exex::ExExManagerHandle;
 use reth_primitives::BlockNumber;
+use reth_provider::StageCheckpointWriter;
+use reth_prune_types::PruneModes;
 use reth_stages::{
-    stages::{MerkleStage, SenderRecoveryStage, TransactionLookupStage},
+    stages::{
+        ExecutionStage, ExecutionStageThresholds, MerkleStage, SenderRecoveryStage,
+        TransactionLookupStage, DEFAULT_PREFETCH_BLOCKS, MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD,
+    },
     test_utils::TestStageDB,
-    StageCheckpoint,
+    StageCheckpoint, StageId,
 };
 use reth_stages_api::{ExecInput, Stage, StageExt, UnwindInput};
 use std::{ops::RangeInclusive, sync::Arc};
@@ -37,12 +44,16 @@ fn benches() {
     account_hashing(&mut criterion, &runtime);
     senders(&mut criterion, &runtime);
     merkle(&mut criterion, &runtime);
+    execution(&mut criterion);
 }
 
 criterion_main!(benches);
 
 const DEFAULT_NUM_BLOCKS: u64 = 10_000;
 
+/// Blocks of the generated chain executed by the execution stage benchmark.
+const EXECUTION_NUM_BLOCKS: usize = 1_000;
+
 fn account_hashing(c: &mut Criterion, runtime: &Runtime) {
     let mut group = c.benchmark_group("Stages");
 
@@ -137,6 +148,45 @@ fn merkle(c: &mut Criterion, runtime: &Runtime) {
     );
 }
 
+fn execution(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Stages");
+    // don't need to run each stage for that many times
+    group.sample_size(10);
+
+    let (db, chain_spec, tip) = setup::prepare_execution(EXECUTION_NUM_BLOCKS);
+
+    // prefetching is turned off with zero blocks
+    for prefetch_blocks in [0, DEFAULT_PREFETCH_BLOCKS] {
+        let stage = || {
+            ExecutionStage::new(
+                EthExecutorProvider::ethereum(chain_spec.clone()),
+                ExecutionStageThresholds::default(),
+                MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD,
+                PruneModes::none(),
+                ExExManagerHandle::empty(),
+            )
+            .with_prefetch_blocks(prefetch_blocks)
+        };
+
+        group.bench_function(format!("Execution-prefetch-{prefetch_blocks}"), |b| {
+            b.iter_with_setup(
+                || {
+                    let mut stage = stage();
+                    setup::unwind_execution(&mut stage, &db);
+                    stage
+                },
+                |mut stage| {
+                    let provider = db.factory.provider_rw().unwrap();
+                    let input = ExecInput { target: Some(tip), checkpoint: None };
+                    let output = stage.execute(&provider, input).unwrap();
+                    provider.save_stage_checkpoint(StageId::Execution, output.checkpoint).unwrap();
+                    provider.commit().unwrap();
+                },
+            )
+        });
+    }
+}
+
 fn measure_stage<F, S>(
     runtime: &Runtime,
     group: &mut BenchmarkGroup<'_, WallTime>,
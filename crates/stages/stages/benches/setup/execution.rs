@@ -0,0 +1,72 @@
+#![allow(unreachable_pub)]
+
+use reth_db::tables;
+use reth_db_api::transaction::DbTxMut;
+use reth_evm_ethereum::{
+    execute::EthExecutorProvider,
+    test_utils::{ChainGenerator, Workload},
+};
+use reth_primitives::{BlockNumber, ChainSpec, ChainSpecBuilder, SealedBlock, StaticFileSegment};
+use reth_provider::{
+    providers::StaticFileWriter, StageCheckpointReader, StageCheckpointWriter,
+    StaticFileProviderFactory,
+};
+use reth_stages::{
+    stages::ExecutionStage,
+    test_utils::{StorageKind, TestStageDB},
+    StageCheckpoint, StageId,
+};
+use reth_stages_api::{Stage, UnwindInput};
+use std::sync::Arc;
+
+/// Prepares a database with a generated chain of `num_blocks` executable blocks for the
+/// [`ExecutionStage`].
+///
+/// The blocks deploy contracts and write their storage, so that executing them reads and writes
+/// state. Returns the database, the chain spec of the generated chain and the tip block number.
+pub fn prepare_execution(num_blocks: usize) -> (TestStageDB, Arc<ChainSpec>, BlockNumber) {
+    let mut generator = ChainGenerator::new(
+        &Arc::new(ChainSpecBuilder::mainnet().shanghai_activated().build()),
+        1,
+        32,
+    )
+    .with_workload(Workload::DeployAndCall)
+    .with_txs_per_block(32)
+    .with_withdrawals_per_block(4);
+    let blocks = generator.generate_blocks(num_blocks);
+    let genesis = SealedBlock::new(generator.genesis_header(), Default::default());
+
+    let db = TestStageDB::default();
+    db.insert_blocks(
+        std::iter::once(&genesis).chain(blocks.iter().map(|block| &block.block)),
+        StorageKind::Static,
+    )
+    .unwrap();
+    let mut receipts_writer =
+        db.factory.static_file_provider().latest_writer(StaticFileSegment::Receipts).unwrap();
+    receipts_writer.increment_block(StaticFileSegment::Receipts, 0).unwrap();
+    receipts_writer.commit().unwrap();
+    drop(receipts_writer);
+    db.commit(|tx| {
+        for (address, (account, _)) in generator.genesis_state() {
+            tx.put::<tables::PlainAccountState>(*address, *account)?;
+        }
+        Ok(())
+    })
+    .unwrap();
+
+    (db, generator.chain_spec(), generator.tip().number)
+}
+
+/// Unwinds the state written by a previous run of the [`ExecutionStage`] back to genesis.
+///
+/// The benchmark saves the checkpoint of every run, so that it is known which blocks to unwind.
+pub fn unwind_execution(stage: &mut ExecutionStage<EthExecutorProvider>, db: &TestStageDB) {
+    let provider = db.factory.provider_rw().unwrap();
+    let checkpoint = provider.get_stage_checkpoint(StageId::Execution).unwrap().unwrap_or_default();
+    if checkpoint.block_number > 0 {
+        stage.unwind(&provider, UnwindInput { checkpoint, unwind_to: 0, bad_block: None }).unwrap();
+        provider.save_stage_checkpoint(StageId::Execution, StageCheckpoint::new(0)).unwrap();
+    }
+    provider.commit().unwrap();
+}
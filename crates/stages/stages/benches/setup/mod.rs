@@ -25,6 +25,8 @@ mod constants;
 
 mod account_hashing;
 pub use account_hashing::*;
+mod execution;
+pub use execution::*;
 use reth_stages_api::{ExecInput, Stage, UnwindInput};
 
 pub(crate) type StageRange = (ExecInput, UnwindInput);
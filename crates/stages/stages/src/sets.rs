@@ -35,9 +35,9 @@
 //! ```
 use crate::{
     stages::{
-        AccountHashingStage, BodyStage, ExecutionStage, FinishStage, HeaderStage,
-        IndexAccountHistoryStage, IndexStorageHistoryStage, MerkleStage, SenderRecoveryStage,
-        StorageHashingStage, TransactionLookupStage,
+        AccountHashingStage, BodyStage, ExecutionStage, FinishStage, HeaderAccumulatorStage,
+        HeaderStage, IndexAccountHistoryStage, IndexStorageHistoryStage, MerkleStage,
+        SenderRecoveryStage, StorageHashingStage, TransactionHistoryStage, TransactionLookupStage,
     },
     StageSet, StageSetBuilder,
 };
@@ -47,7 +47,7 @@ use reth_db_api::database::Database;
 use reth_evm::execute::BlockExecutorProvider;
 use reth_network_p2p::{bodies::downloader::BodyDownloader, headers::downloader::HeaderDownloader};
 use reth_primitives::B256;
-use reth_provider::HeaderSyncGapProvider;
+use reth_provider::{HeaderProvider, HeaderSyncGapProvider};
 use reth_prune_types::PruneModes;
 use std::sync::Arc;
 use tokio::sync::watch;
@@ -72,6 +72,8 @@ use tokio::sync::watch;
 /// - [`TransactionLookupStage`]
 /// - [`IndexStorageHistoryStage`]
 /// - [`IndexAccountHistoryStage`]
+/// - [`TransactionHistoryStage`]
+/// - [`HeaderAccumulatorStage`]
 /// - [`FinishStage`]
 #[derive(Debug)]
 pub struct DefaultStages<Provider, H, B, EF> {
@@ -137,7 +139,7 @@ where
 
 impl<Provider, H, B, E, DB> StageSet<DB> for DefaultStages<Provider, H, B, E>
 where
-    Provider: HeaderSyncGapProvider + 'static,
+    Provider: HeaderSyncGapProvider + HeaderProvider + 'static,
     H: HeaderDownloader + 'static,
     B: BodyDownloader + 'static,
     E: BlockExecutorProvider,
@@ -189,7 +191,7 @@ impl<Provider, H, B> OnlineStages<Provider, H, B> {
 
 impl<Provider, H, B> OnlineStages<Provider, H, B>
 where
-    Provider: HeaderSyncGapProvider + 'static,
+    Provider: HeaderSyncGapProvider + HeaderProvider + 'static,
     H: HeaderDownloader + 'static,
     B: BodyDownloader + 'static,
 {
@@ -211,13 +213,16 @@ where
         stages_config: StageConfig,
     ) -> StageSetBuilder<DB> {
         StageSetBuilder::default()
-            .add_stage(HeaderStage::new(
-                provider,
-                header_downloader,
-                tip,
-                consensus.clone(),
-                stages_config.etl,
-            ))
+            .add_stage(
+                HeaderStage::new(
+                    provider,
+                    header_downloader,
+                    tip,
+                    consensus.clone(),
+                    stages_config.etl,
+                )
+                .with_max_auto_unwind_depth(stages_config.headers.max_auto_unwind_depth),
+            )
             .add_stage(bodies)
     }
 }
@@ -225,19 +230,22 @@ where
 impl<DB, Provider, H, B> StageSet<DB> for OnlineStages<Provider, H, B>
 where
     DB: Database,
-    Provider: HeaderSyncGapProvider + 'static,
+    Provider: HeaderSyncGapProvider + HeaderProvider + 'static,
     H: HeaderDownloader + 'static,
     B: BodyDownloader + 'static,
 {
     fn builder(self) -> StageSetBuilder<DB> {
         StageSetBuilder::default()
-            .add_stage(HeaderStage::new(
-                self.provider,
-                self.header_downloader,
-                self.tip,
-                self.consensus.clone(),
-                self.stages_config.etl.clone(),
-            ))
+            .add_stage(
+                HeaderStage::new(
+                    self.provider,
+                    self.header_downloader,
+                    self.tip,
+                    self.consensus.clone(),
+                    self.stages_config.etl.clone(),
+                )
+                .with_max_auto_unwind_depth(self.stages_config.headers.max_auto_unwind_depth),
+            )
             .add_stage(BodyStage::new(self.body_downloader))
     }
 }
@@ -383,5 +391,10 @@ impl<DB: Database> StageSet<DB> for HistoryIndexingStages {
                 self.stages_config.etl.clone(),
                 self.prune_modes.storage_history,
             ))
+            .add_stage(TransactionHistoryStage::new(
+                self.stages_config.transaction_history,
+                self.prune_modes.transaction_history,
+            ))
+            .add_stage(HeaderAccumulatorStage::new(self.stages_config.header_accumulator))
     }
 }
@@ -1,5 +1,6 @@
 use std::{
     cmp::Ordering,
+    ops::RangeInclusive,
     task::{ready, Context, Poll},
 };
 
@@ -13,15 +14,17 @@ use reth_db_api::{
     models::{StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals},
     transaction::DbTxMut,
 };
+use reth_consensus_common::validation::validate_withdrawal_index_continuity;
 use reth_network_p2p::bodies::{downloader::BodyDownloader, response::BlockResponse};
-use reth_primitives::{StaticFileSegment, TxNumber};
+use reth_primitives::{BlockNumber, StaticFileSegment, TxNumber};
 use reth_provider::{
     providers::{StaticFileProvider, StaticFileWriter},
-    BlockReader, DatabaseProviderRW, HeaderProvider, ProviderError, StatsReader,
+    BlockReader, DatabaseProviderRO, DatabaseProviderRW, HeaderProvider, ProviderError,
+    StatsReader, WithdrawalsProvider,
 };
 use reth_stages_api::{
-    EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageId,
-    UnwindInput, UnwindOutput,
+    BlockErrorKind, EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint, StageError,
+    StageEstimate, StageId, UnwindInput, UnwindOutput,
 };
 use reth_storage_errors::provider::ProviderResult;
 
@@ -77,6 +80,14 @@ impl<DB: Database, D: BodyDownloader> Stage<DB> for BodyStage<D> {
         StageId::Bodies
     }
 
+    fn estimate(
+        &self,
+        _provider: &DatabaseProviderRO<DB>,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<StageEstimate, StageError> {
+        Ok(StageEstimate::blocks(range))
+    }
+
     fn poll_execute_ready(
         &mut self,
         cx: &mut Context<'_>,
@@ -165,6 +176,11 @@ impl<DB: Database, D: BodyDownloader> Stage<DB> for BodyStage<D> {
 
         debug!(target: "sync::stages::bodies", stage_progress = from_block, target = to_block, start_tx_id = next_tx_num, "Commencing sync");
 
+        // The withdrawals of the new blocks have to continue the sequence of the last withdrawal
+        // below the range.
+        let mut last_withdrawal_index =
+            provider.latest_withdrawal_until(from_block.saturating_sub(1))?.map(|w| w.index);
+
         let buffer = self.buffer.take().ok_or(StageError::MissingDownloadBuffer)?;
         trace!(target: "sync::stages::bodies", bodies_len = buffer.len(), "Writing blocks");
         let mut highest_block = from_block;
@@ -213,6 +229,7 @@ impl<DB: Database, D: BodyDownloader> Stage<DB> for BodyStage<D> {
                             // items. It should be treated as an `expect()` failure.
                             return Err(StageError::InconsistentTxNumber {
                                 segment: StaticFileSegment::Transactions,
+                                block_number,
                                 database: next_tx_num,
                                 static_file: appended_tx_number,
                             })
@@ -230,6 +247,14 @@ impl<DB: Database, D: BodyDownloader> Stage<DB> for BodyStage<D> {
 
                     // Write withdrawals if any
                     if let Some(withdrawals) = block.withdrawals {
+                        validate_withdrawal_index_continuity(&withdrawals, last_withdrawal_index)
+                            .map_err(|error| StageError::Block {
+                                block: Box::new(block.header),
+                                error: BlockErrorKind::Validation(error),
+                            })?;
+                        if let Some(withdrawal) = withdrawals.last() {
+                            last_withdrawal_index = Some(withdrawal.index);
+                        }
                         if !withdrawals.is_empty() {
                             withdrawals_cursor
                                 .append(block_number, StoredBlockWithdrawals { withdrawals })?;
@@ -1,4 +1,7 @@
-use crate::stages::MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD;
+use crate::stages::{
+    prefetch::{BlockPrefetcher, PrefetchedBlock, DEFAULT_PREFETCH_BLOCKS},
+    MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD,
+};
 use num_traits::Zero;
 use reth_config::config::ExecutionConfig;
 use reth_db::{static_file::HeaderMask, tables};
@@ -7,8 +10,9 @@ use reth_evm::execute::{BatchExecutor, BlockExecutorProvider};
 use reth_exex::{ExExManagerHandle, ExExNotification};
 use reth_primitives::{BlockNumber, Header, StaticFileSegment};
 use reth_provider::{
+    bundle_state::{verify_receipts_gas, BlockGas},
     providers::{StaticFileProvider, StaticFileProviderRWRefMut, StaticFileWriter},
-    BlockReader, Chain, DatabaseProviderRW, ExecutionOutcome, HeaderProvider,
+    BlockReader, Chain, DatabaseProviderRO, DatabaseProviderRW, ExecutionOutcome, HeaderProvider,
     LatestStateProviderRef, OriginalValuesKnown, ProviderError, StateWriter, StatsReader,
     TransactionVariant,
 };
@@ -17,7 +21,7 @@ use reth_revm::database::StateProviderDatabase;
 use reth_stages_api::{
     BlockErrorKind, CheckpointBlockRange, EntitiesCheckpoint, ExecInput, ExecOutput,
     ExecutionCheckpoint, MetricEvent, MetricEventsSender, Stage, StageCheckpoint, StageError,
-    StageId, UnwindInput, UnwindOutput,
+    StageEstimate, StageId, UnwindInput, UnwindOutput,
 };
 use std::{
     cmp::Ordering,
@@ -81,6 +85,8 @@ pub struct ExecutionStage<E> {
     post_unwind_commit_input: Option<Chain>,
     /// Handle to communicate with `ExEx` manager.
     exex_manager_handle: ExExManagerHandle,
+    /// Number of blocks that are read ahead of the executed block, `0` disables prefetching.
+    prefetch_blocks: usize,
 }
 
 impl<E> ExecutionStage<E> {
@@ -101,6 +107,7 @@ impl<E> ExecutionStage<E> {
             post_execute_commit_input: None,
             post_unwind_commit_input: None,
             exex_manager_handle,
+            prefetch_blocks: DEFAULT_PREFETCH_BLOCKS,
         }
     }
 
@@ -131,6 +138,7 @@ impl<E> ExecutionStage<E> {
             prune_modes,
             ExExManagerHandle::empty(),
         )
+        .with_prefetch_blocks(config.prefetch_blocks)
     }
 
     /// Set the metric events sender.
@@ -139,6 +147,13 @@ impl<E> ExecutionStage<E> {
         self
     }
 
+    /// Set the number of blocks that are read ahead of the executed block, while it's executed.
+    /// Defaults to [`DEFAULT_PREFETCH_BLOCKS`], `0` disables prefetching.
+    pub const fn with_prefetch_blocks(mut self, prefetch_blocks: usize) -> Self {
+        self.prefetch_blocks = prefetch_blocks;
+        self
+    }
+
     /// Adjusts the prune modes related to changesets.
     ///
     /// This function verifies whether the [`super::MerkleStage`] or Hashing stages will run from
@@ -179,6 +194,14 @@ where
         StageId::Execution
     }
 
+    fn estimate(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<StageEstimate, StageError> {
+        Ok(StageEstimate::transactions(provider, range)?)
+    }
+
     fn poll_execute_ready(
         &mut self,
         cx: &mut Context<'_>,
@@ -217,7 +240,7 @@ where
             None
         };
 
-        let db = StateProviderDatabase(LatestStateProviderRef::new(
+        let db = StateProviderDatabase::new(LatestStateProviderRef::new(
             provider.tx_ref(),
             provider.static_file_provider().clone(),
         ));
@@ -237,19 +260,76 @@ where
         let mut cumulative_gas = 0;
         let batch_start = Instant::now();
 
+        // Blocks up to the last block of the batch are read ahead of their execution, as long as
+        // their headers and transactions are in the static files. The batch can end earlier, in
+        // which case the remaining blocks are discarded.
+        let prefetch_end = [
+            max_block,
+            start_block.saturating_add(self.thresholds.max_blocks.unwrap_or(u64::MAX)),
+            static_file_provider
+                .get_highest_static_file_block(StaticFileSegment::Headers)
+                .unwrap_or_default(),
+            static_file_provider
+                .get_highest_static_file_block(StaticFileSegment::Transactions)
+                .unwrap_or_default(),
+        ]
+        .into_iter()
+        .min()
+        .unwrap_or_default();
+        let mut prefetcher = (self.prefetch_blocks > 0 && start_block <= prefetch_end)
+            .then(|| BlockPrefetcher::spawn(static_file_provider.clone(), self.prefetch_blocks));
+        let mut next_prefetch = start_block;
+
         let mut blocks = Vec::new();
+        let mut blocks_gas = Vec::new();
         for block_number in start_block..=max_block {
             // Fetch the block
             let fetch_block_start = Instant::now();
 
+            // the total difficulty is read through the provider, which knows the final total
+            // difficulty of post-merge blocks and falls back to the database
             let td = provider
                 .header_td_by_number(block_number)?
                 .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?;
 
-            // we need the block's transactions but we don't need the transaction hashes
-            let block = provider
-                .block_with_senders(block_number.into(), TransactionVariant::NoHash)?
-                .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?;
+            let block = match prefetcher.as_mut().filter(|_| block_number <= prefetch_end) {
+                Some(prefetcher) => {
+                    // Keep the lookahead filled
+                    while next_prefetch <= prefetch_end &&
+                        prefetcher.pending() <= self.prefetch_blocks
+                    {
+                        let body = provider
+                            .block_body_indices(next_prefetch)?
+                            .ok_or(ProviderError::BlockBodyIndicesNotFound(next_prefetch))?;
+                        prefetcher.request(next_prefetch, body.tx_num_range());
+                        next_prefetch += 1;
+                    }
+
+                    let PrefetchedBlock { number, header, transactions } = prefetcher
+                        .next_block()
+                        .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))??;
+                    debug_assert_eq!(number, block_number, "prefetched blocks are out of order");
+
+                    provider
+                        .block_with_repaired_senders_from(
+                            block_number,
+                            header,
+                            transactions,
+                            TransactionVariant::NoHash,
+                        )?
+                        .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?
+                }
+                None => {
+                    // we need the block's transactions but we don't need the transaction hashes,
+                    // senders missing from the database are recovered and written back
+                    provider
+                        .block_with_repaired_senders(
+                            block_number.into(),
+                            TransactionVariant::NoHash,
+                        )?
+                        .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?
+                }
+            };
 
             fetch_block_duration += fetch_block_start.elapsed();
 
@@ -277,6 +357,7 @@ where
 
             stage_progress = block_number;
             stage_checkpoint.progress.processed += block.gas_used;
+            blocks_gas.push(BlockGas::new(&block.header, &block.body));
 
             // If we have ExExes we need to save the block in memory for later
             if self.exex_manager_handle.has_exexs() {
@@ -295,10 +376,15 @@ where
             }
         }
 
+        // discard the blocks that were read past the end of the batch
+        drop(prefetcher);
+
         // prepare execution output for writing
         let time = Instant::now();
         let ExecutionOutcome { bundle, receipts, requests, first_block } = executor.finalize();
         let state = ExecutionOutcome::new(bundle, receipts, first_block, requests);
+        // refuse to write receipts that are inconsistent with the gas used by their blocks
+        verify_receipts_gas(&blocks_gas, &state.receipts)?;
         let write_preparation_duration = time.elapsed();
 
         // log the gas per second for the range we just executed
@@ -701,15 +787,18 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::TestStageDB;
+    use crate::test_utils::{StorageKind, TestStageDB};
     use alloy_rlp::Decodable;
     use assert_matches::assert_matches;
-    use reth_db_api::{models::AccountBeforeTx, transaction::DbTxMut};
-    use reth_evm_ethereum::execute::EthExecutorProvider;
+    use reth_db_api::{cursor::DbDupCursorRO, models::AccountBeforeTx, transaction::DbTxMut};
+    use reth_evm_ethereum::{
+        execute::EthExecutorProvider,
+        test_utils::{ChainGenerator, Workload},
+    };
     use reth_execution_errors::BlockValidationError;
     use reth_primitives::{
-        address, hex_literal::hex, keccak256, Account, Address, Bytecode, ChainSpecBuilder,
-        SealedBlock, StorageEntry, B256, U256,
+        address, hex_literal::hex, keccak256, Account, Address, Bytecode, ChainSpec,
+        ChainSpecBuilder, SealedBlock, StorageEntry, B256, U256,
     };
     use reth_provider::{
         test_utils::create_test_provider_factory, AccountReader, ReceiptProvider,
@@ -720,9 +809,11 @@ mod tests {
     use std::collections::BTreeMap;
 
     fn stage() -> ExecutionStage<EthExecutorProvider> {
-        let executor_provider = EthExecutorProvider::ethereum(Arc::new(
-            ChainSpecBuilder::mainnet().berlin_activated().build(),
-        ));
+        stage_with_chain_spec(Arc::new(ChainSpecBuilder::mainnet().berlin_activated().build()))
+    }
+
+    fn stage_with_chain_spec(chain_spec: Arc<ChainSpec>) -> ExecutionStage<EthExecutorProvider> {
+        let executor_provider = EthExecutorProvider::ethereum(chain_spec);
         ExecutionStage::new(
             executor_provider,
             ExecutionStageThresholds {
@@ -1057,20 +1148,25 @@ mod tests {
 
     #[tokio::test]
     async fn sanity_execute_unwind() {
-        // TODO cleanup the setup after https://github.com/paradigmxyz/reth/issues/332
-        // is merged as it has similar framework
+        let mut generator = ChainGenerator::new(
+            &Arc::new(ChainSpecBuilder::mainnet().shanghai_activated().build()),
+            1,
+            2,
+        )
+        .with_workload(Workload::DeployAndCall)
+        .with_withdrawals_per_block(1);
+        let blocks = generator.generate_blocks(3);
+        let tip = generator.tip().number;
+        let gas_used = blocks.iter().map(|block| block.gas_used).sum::<u64>();
 
         let factory = create_test_provider_factory();
         let provider = factory.provider_rw().unwrap();
-        let input = ExecInput { target: Some(1), checkpoint: None };
-        let mut genesis_rlp = hex!("f901faf901f5a00000000000000000000000000000000000000000000000000000000000000000a01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347942adc25665018aa1fe0e6bc666dac8fc2697ff9baa045571b40ae66ca7480791bbb2887286e4e4c4b1b298b191c889d6959023a32eda056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421b901000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000083020000808502540be400808000a00000000000000000000000000000000000000000000000000000000000000000880000000000000000c0c0").as_slice();
-        let genesis = SealedBlock::decode(&mut genesis_rlp).unwrap();
-        let mut block_rlp = hex!("f90262f901f9a075c371ba45999d87f4542326910a11af515897aebce5265d3f6acd1f1161f82fa01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347942adc25665018aa1fe0e6bc666dac8fc2697ff9baa098f2dcd87c8ae4083e7017a05456c14eea4b1db2032126e27b3b1563d57d7cc0a08151d548273f6683169524b66ca9fe338b9ce42bc3540046c828fd939ae23bcba03f4e5c2ec5b2170b711d97ee755c160457bb58d8daa338e835ec02ae6860bbabb901000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000083020000018502540be40082a8798203e800a00000000000000000000000000000000000000000000000000000000000000000880000000000000000f863f861800a8405f5e10094100000000000000000000000000000000000000080801ba07e09e26678ed4fac08a249ebe8ed680bf9051a5e14ad223e4b2b9d26e0208f37a05f6e3f188e3e6eab7d7d3b6568f5eac7d687b08d307d3154ccd8c87b4630509bc0").as_slice();
-        let block = SealedBlock::decode(&mut block_rlp).unwrap();
+        let input = ExecInput { target: Some(tip), checkpoint: None };
+        let genesis = SealedBlock::new(generator.genesis_header(), Default::default());
         provider.insert_historical_block(genesis.try_seal_with_senders().unwrap(), None).unwrap();
-        provider
-            .insert_historical_block(block.clone().try_seal_with_senders().unwrap(), None)
-            .unwrap();
+        for block in blocks {
+            provider.insert_historical_block(block, None).unwrap();
+        }
         provider
             .static_file_provider()
             .latest_writer(StaticFileSegment::Headers)
@@ -1085,22 +1181,11 @@ mod tests {
         }
         provider.commit().unwrap();
 
-        // variables
-        let code = hex!("5a465a905090036002900360015500");
-        let balance = U256::from(0x3635c9adc5dea00000u128);
-        let code_hash = keccak256(code);
         // pre state
         let provider = factory.provider_rw().unwrap();
-
-        let db_tx = provider.tx_ref();
-        let acc1 = address!("1000000000000000000000000000000000000000");
-        let acc1_info = Account { nonce: 0, balance: U256::ZERO, bytecode_hash: Some(code_hash) };
-        let acc2 = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
-        let acc2_info = Account { nonce: 0, balance, bytecode_hash: None };
-
-        db_tx.put::<tables::PlainAccountState>(acc1, acc1_info).unwrap();
-        db_tx.put::<tables::PlainAccountState>(acc2, acc2_info).unwrap();
-        db_tx.put::<tables::Bytecodes>(code_hash, Bytecode::new_raw(code.to_vec().into())).unwrap();
+        for (address, (account, _)) in generator.genesis_state() {
+            provider.tx_ref().put::<tables::PlainAccountState>(*address, *account).unwrap();
+        }
         provider.commit().unwrap();
 
         // execute
@@ -1120,15 +1205,29 @@ mod tests {
             }
 
             // Test Execution
-            let mut execution_stage = stage();
+            let mut execution_stage = stage_with_chain_spec(generator.chain_spec());
             execution_stage.prune_modes = mode.clone().unwrap_or_default();
 
             let result = execution_stage.execute(&provider, input).unwrap();
             provider.commit().unwrap();
 
-            // Test Unwind
+            // assert the generated post state
             provider = factory.provider_rw().unwrap();
-            let mut stage = stage();
+            for (address, (account, storage)) in generator.state() {
+                assert_eq!(provider.basic_account(*address), Ok(Some(*account)));
+                for (slot, value) in storage {
+                    let entry = provider
+                        .tx_ref()
+                        .cursor_dup_read::<tables::PlainStorageState>()
+                        .unwrap()
+                        .seek_by_key_subkey(*address, *slot)
+                        .unwrap();
+                    assert_eq!(entry, Some(StorageEntry { key: *slot, value: *value }));
+                }
+            }
+
+            // Test Unwind
+            let mut stage = stage_with_chain_spec(generator.chain_spec());
             stage.prune_modes = mode.unwrap_or_default();
 
             let result = stage
@@ -1144,7 +1243,7 @@ mod tests {
                     stage_checkpoint: Some(StageUnitCheckpoint::Execution(ExecutionCheckpoint {
                         block_range: CheckpointBlockRange {
                             from: 1,
-                            to: 1,
+                            to,
                         },
                         progress: EntitiesCheckpoint {
                             processed: 0,
@@ -1152,31 +1251,103 @@ mod tests {
                         }
                     }))
                 }
-            } if total == block.gas_used);
+            } if to == tip && total == gas_used);
 
             // assert unwind stage
-            assert_eq!(
-                provider.basic_account(acc1),
-                Ok(Some(acc1_info)),
-                "Pre changed of a account"
-            );
-            assert_eq!(
-                provider.basic_account(acc2),
-                Ok(Some(acc2_info)),
-                "Post changed of a account"
-            );
-
-            let miner_acc = address!("2adc25665018aa1fe0e6bc666dac8fc2697ff9ba");
-            assert_eq!(
-                provider.basic_account(miner_acc),
-                Ok(None),
-                "Third account should be unwound"
-            );
+            for address in generator.state().keys() {
+                assert_eq!(
+                    provider.basic_account(*address),
+                    Ok(generator.genesis_state().get(address).map(|(account, _)| *account)),
+                    "Post state of an account should be unwound"
+                );
+            }
 
             assert_eq!(provider.receipt(0), Ok(None), "First receipt should be unwound");
         }
     }
 
+    #[test]
+    fn prefetch_matches_sequential_execution() {
+        let mut generator = ChainGenerator::new(
+            &Arc::new(ChainSpecBuilder::mainnet().shanghai_activated().build()),
+            1,
+            4,
+        )
+        .with_workload(Workload::DeployAndCall)
+        .with_withdrawals_per_block(1);
+        let blocks = generator.generate_blocks(8);
+        let tip = generator.tip().number;
+        let genesis = SealedBlock::new(generator.genesis_header(), Default::default());
+
+        // Executes the chain in batches of 3 blocks
+        let execute = |prefetch_blocks: usize| {
+            let db = TestStageDB::default();
+            db.insert_blocks(
+                std::iter::once(&genesis).chain(blocks.iter().map(|block| &block.block)),
+                StorageKind::Static,
+            )
+            .unwrap();
+            let mut receipts_writer = db
+                .factory
+                .static_file_provider()
+                .latest_writer(StaticFileSegment::Receipts)
+                .unwrap();
+            receipts_writer.increment_block(StaticFileSegment::Receipts, 0).unwrap();
+            receipts_writer.commit().unwrap();
+            drop(receipts_writer);
+            db.commit(|tx| {
+                for (address, (account, _)) in generator.genesis_state() {
+                    tx.put::<tables::PlainAccountState>(*address, *account)?;
+                }
+                Ok(())
+            })
+            .unwrap();
+
+            let mut stage =
+                stage_with_chain_spec(generator.chain_spec()).with_prefetch_blocks(prefetch_blocks);
+            stage.thresholds.max_blocks = Some(2);
+
+            let mut outputs = Vec::new();
+            let mut checkpoint = None;
+            loop {
+                let provider = db.factory.provider_rw().unwrap();
+                let output =
+                    stage.execute(&provider, ExecInput { target: Some(tip), checkpoint }).unwrap();
+                provider.commit().unwrap();
+                checkpoint = Some(output.checkpoint);
+                outputs.push(output.clone());
+                if output.done {
+                    break
+                }
+            }
+
+            let provider = db.factory.provider().unwrap();
+            let receipts = (1..=tip)
+                .map(|block_number| provider.receipts_by_block(block_number.into()).unwrap())
+                .collect::<Vec<_>>();
+            (
+                outputs,
+                receipts,
+                db.table::<tables::PlainAccountState>().unwrap(),
+                db.table::<tables::PlainStorageState>().unwrap(),
+                db.table::<tables::AccountChangeSets>().unwrap(),
+                db.table::<tables::TransactionSenders>().unwrap(),
+            )
+        };
+
+        let sequential = execute(0);
+        assert_eq!(sequential.0.len(), 3);
+        assert_eq!(
+            sequential.1,
+            generator.receipts().iter().cloned().map(Some).collect::<Vec<_>>()
+        );
+        assert_eq!(sequential.5.len(), blocks.iter().map(|block| block.senders.len()).sum());
+
+        assert_eq!(execute(DEFAULT_PREFETCH_BLOCKS), sequential);
+        assert_eq!(execute(1), sequential);
+        assert_eq!(execute(100), sequential);
+    }
+
     #[tokio::test]
     async fn test_selfdestruct() {
         let test_db = TestStageDB::default();
@@ -149,7 +149,9 @@ impl<DB: Database> Stage<DB> for AccountHashingStage {
             // clear table, load all accounts and hash it
             tx.clear::<tables::HashedAccounts>()?;
 
-            let mut accounts_cursor = tx.cursor_read::<RawTable<tables::PlainAccountState>>()?;
+            let mut accounts_cursor = tx
+                .cursor_read::<RawTable<tables::PlainAccountState>>()?
+                .with_sequential_access_hint();
             let mut collector =
                 Collector::new(self.etl_config.file_size, self.etl_config.dir.clone());
             let mut channels = Vec::with_capacity(MAXIMUM_CHANNELS);
@@ -89,7 +89,8 @@ impl<DB: Database> Stage<DB> for StorageHashingStage {
             // clear table, load all accounts and hash it
             tx.clear::<tables::HashedStorages>()?;
 
-            let mut storage_cursor = tx.cursor_read::<tables::PlainStorageState>()?;
+            let mut storage_cursor =
+                tx.cursor_read::<tables::PlainStorageState>()?.with_sequential_access_hint();
             let mut collector =
                 Collector::new(self.etl_config.file_size, self.etl_config.dir.clone());
             let mut channels = Vec::with_capacity(MAXIMUM_CHANNELS);
@@ -0,0 +1,244 @@
+use reth_config::config::HeaderAccumulatorConfig;
+use reth_db::tables;
+use reth_db_api::{database::Database, transaction::DbTxMut};
+use reth_primitives::BlockNumber;
+use reth_provider::{
+    DatabaseProviderRO, DatabaseProviderRW, HeaderAccumulatorProvider, HeaderAccumulatorWriter,
+};
+use reth_stages_api::{
+    EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageEstimate,
+    StageId, UnwindInput, UnwindOutput,
+};
+use std::ops::RangeInclusive;
+use tracing::*;
+
+/// The header accumulator stage.
+///
+/// This stage appends the canonical header hashes to the accumulator of canonical header hashes,
+/// see [`reth_primitives::accumulator`], whose nodes are written to
+/// [`tables::HeaderAccumulator`]. The accumulator proves that a block is canonical with a proof
+/// that is logarithmic in the chain length, see [`HeaderAccumulatorProvider::prove_canonical`].
+///
+/// The stage is disabled by default. While disabled, it only advances its checkpoint and removes
+/// the accumulator if one was built before. Enabling it builds the accumulator from genesis.
+#[derive(Debug, Clone, Default)]
+pub struct HeaderAccumulatorStage {
+    config: HeaderAccumulatorConfig,
+}
+
+impl HeaderAccumulatorStage {
+    /// Create new instance of [`HeaderAccumulatorStage`].
+    pub const fn new(config: HeaderAccumulatorConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl<DB: Database> Stage<DB> for HeaderAccumulatorStage {
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        StageId::HeaderAccumulator
+    }
+
+    fn estimate(
+        &self,
+        _provider: &DatabaseProviderRO<DB>,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<StageEstimate, StageError> {
+        if !self.config.enabled {
+            return Ok(StageEstimate::Blocks(0))
+        }
+        Ok(StageEstimate::blocks(range))
+    }
+
+    /// Append the canonical header hashes to the accumulator
+    fn execute(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: ExecInput,
+    ) -> Result<ExecOutput, StageError> {
+        let target = input.target();
+        let leaf_count = provider.header_accumulator_leaf_count()?;
+
+        if !self.config.enabled {
+            if leaf_count.is_some() {
+                provider.reset_header_accumulator(false)?;
+                info!(target: "sync::stages::header_accumulator", "Removed header accumulator");
+            }
+            return Ok(ExecOutput::done(StageCheckpoint::new(target)))
+        }
+
+        let leaf_count = match leaf_count {
+            Some(leaf_count) => leaf_count,
+            None => {
+                info!(target: "sync::stages::header_accumulator", "Building header accumulator");
+                provider.reset_header_accumulator(true)?;
+                0
+            }
+        };
+
+        // The accumulator doesn't follow the stage checkpoint: it's built from genesis once the
+        // stage is enabled, and blocks inserted outside of the pipeline are appended directly.
+        if leaf_count > target {
+            return Ok(ExecOutput::done(
+                StageCheckpoint::new(target)
+                    .with_entities_stage_checkpoint(stage_checkpoint(target, target)),
+            ))
+        }
+
+        let end_block = target.min(leaf_count + self.config.commit_threshold.max(1) - 1);
+        info!(target: "sync::stages::header_accumulator", range = ?leaf_count..=end_block, "Accumulating header hashes");
+        provider.append_header_accumulator(leaf_count..=end_block)?;
+
+        Ok(ExecOutput {
+            checkpoint: StageCheckpoint::new(end_block)
+                .with_entities_stage_checkpoint(stage_checkpoint(end_block, target)),
+            done: end_block == target,
+        })
+    }
+
+    /// Unwind the stage.
+    fn unwind(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        // This is a no-op if the accumulator is disabled
+        provider.truncate_header_accumulator(input.unwind_to + 1)?;
+        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(input.unwind_to) })
+    }
+}
+
+/// Returns the number of accumulated headers up to the `checkpoint` block out of the headers up to
+/// the `target` block.
+const fn stage_checkpoint(checkpoint: BlockNumber, target: BlockNumber) -> EntitiesCheckpoint {
+    EntitiesCheckpoint { processed: checkpoint + 1, total: target + 1 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{StorageKind, TestStageDB};
+    use reth_primitives::{accumulator::verify_canonical_proof, SealedBlock, B256};
+    use reth_testing_utils::generators::{self, random_block_range};
+
+    fn enabled() -> HeaderAccumulatorStage {
+        HeaderAccumulatorStage::new(HeaderAccumulatorConfig { enabled: true, commit_threshold: 4 })
+    }
+
+    /// Executes the stage to the target, committing after every batch.
+    fn execute(db: &TestStageDB, stage: &mut HeaderAccumulatorStage, target: u64) -> ExecOutput {
+        let mut checkpoint = None;
+        loop {
+            let provider = db.factory.provider_rw().unwrap();
+            let output = stage.execute(&provider, ExecInput { target: Some(target), checkpoint });
+            provider.commit().unwrap();
+            let output = output.unwrap();
+            if output.done {
+                return output
+            }
+            checkpoint = Some(output.checkpoint);
+        }
+    }
+
+    fn unwind(db: &TestStageDB, stage: &mut HeaderAccumulatorStage, from: u64, to: u64) {
+        let provider = db.factory.provider_rw().unwrap();
+        let input =
+            UnwindInput { checkpoint: StageCheckpoint::new(from), unwind_to: to, bad_block: None };
+        let output = stage.unwind(&provider, input).unwrap();
+        provider.commit().unwrap();
+        assert_eq!(output.checkpoint, StageCheckpoint::new(to));
+    }
+
+    /// Asserts that the blocks are proven to be canonical against the root at the last block.
+    fn assert_canonical(db: &TestStageDB, blocks: &[SealedBlock]) -> B256 {
+        let provider = db.factory.provider().unwrap();
+        let tip = blocks.last().unwrap().number;
+        let root = provider.accumulator_root_at(tip).unwrap().unwrap();
+        for block in blocks {
+            let proof = provider.prove_canonical(block.number).unwrap().unwrap();
+            assert_eq!(proof.leaf_count, tip + 1);
+            assert!(verify_canonical_proof(root, block.hash(), &proof));
+        }
+        assert_eq!(provider.prove_canonical(tip + 1).unwrap(), None);
+        root
+    }
+
+    #[test]
+    fn execute_disabled() {
+        let db = TestStageDB::default();
+
+        // No blocks are inserted, so any read of the headers would fail
+        let mut stage = HeaderAccumulatorStage::default();
+        assert_eq!(
+            execute(&db, &mut stage, 1_000_000),
+            ExecOutput::done(StageCheckpoint::new(1_000_000))
+        );
+        assert!(db.table_is_empty::<tables::HeaderAccumulator>().unwrap());
+        assert_eq!(db.factory.provider().unwrap().header_accumulator_leaf_count().unwrap(), None);
+    }
+
+    #[test]
+    fn disabling_removes_accumulator() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+        let blocks = random_block_range(&mut rng, 0..=10, B256::ZERO, 0..1);
+        db.insert_blocks(blocks.iter(), StorageKind::Static).unwrap();
+
+        let output = execute(&db, &mut enabled(), 10);
+        assert_eq!(
+            output.checkpoint.entities_stage_checkpoint(),
+            Some(EntitiesCheckpoint { processed: 11, total: 11 })
+        );
+        assert_eq!(
+            db.factory.provider().unwrap().header_accumulator_leaf_count().unwrap(),
+            Some(11)
+        );
+        assert_canonical(&db, &blocks);
+
+        execute(&db, &mut HeaderAccumulatorStage::default(), 10);
+        assert!(db.table_is_empty::<tables::HeaderAccumulator>().unwrap());
+        let provider = db.factory.provider().unwrap();
+        assert_eq!(provider.header_accumulator_leaf_count().unwrap(), None);
+        assert_eq!(provider.prove_canonical(0).unwrap(), None);
+    }
+
+    #[test]
+    fn reorg() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+        let mut blocks = random_block_range(&mut rng, 0..=10, B256::ZERO, 0..1);
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).unwrap();
+
+        let mut stage = enabled();
+        execute(&db, &mut stage, 10);
+        let root = assert_canonical(&db, &blocks);
+        let (old_hash, old_proof) =
+            (blocks[8].hash(), db.factory.prove_canonical(8).unwrap().unwrap());
+        let roots = (0..=10)
+            .map(|block| db.factory.accumulator_root_at(block).unwrap().unwrap())
+            .collect::<Vec<_>>();
+
+        // the roots at lower blocks are unchanged by the unwind
+        unwind(&db, &mut stage, 10, 6);
+        assert_eq!(assert_canonical(&db, &blocks[..=6]), roots[6]);
+        assert_eq!(db.factory.accumulator_root_at(7).unwrap(), None);
+
+        // replace the unwound blocks
+        let new_blocks = random_block_range(&mut rng, 7..=10, blocks[6].hash(), 0..1);
+        db.insert_blocks(new_blocks.iter(), StorageKind::Database(None)).unwrap();
+        blocks.truncate(7);
+        blocks.extend(new_blocks);
+
+        execute(&db, &mut stage, 10);
+        let new_root = assert_canonical(&db, &blocks);
+        assert_ne!(new_root, root);
+        assert_eq!(db.factory.accumulator_root_at(6).unwrap(), Some(roots[6]));
+
+        // the reverted block isn't canonical anymore
+        assert!(verify_canonical_proof(root, old_hash, &old_proof));
+        assert!(!verify_canonical_proof(new_root, old_hash, &old_proof));
+        let new_proof = db.factory.prove_canonical(8).unwrap().unwrap();
+        assert!(!verify_canonical_proof(new_root, old_hash, &new_proof));
+        assert!(verify_canonical_proof(new_root, blocks[8].hash(), &new_proof));
+    }
+}
@@ -1,7 +1,7 @@
 use futures_util::StreamExt;
 use reth_codecs::Compact;
 use reth_config::config::EtlConfig;
-use reth_consensus::Consensus;
+use reth_consensus::{Consensus, ConsensusError};
 use reth_db::{tables, RawKey, RawTable, RawValue};
 use reth_db_api::{
     cursor::{DbCursorRO, DbCursorRW},
@@ -10,23 +10,31 @@ use reth_db_api::{
 };
 use reth_etl::Collector;
 use reth_network_p2p::headers::{downloader::HeaderDownloader, error::HeadersDownloaderError};
+use reth_network_peers::PeerId;
 use reth_primitives::{BlockHash, BlockNumber, SealedHeader, StaticFileSegment, B256};
 use reth_provider::{
     providers::{StaticFileProvider, StaticFileWriter},
-    BlockHashReader, DatabaseProviderRW, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider,
+    BlockHashReader, DatabaseProviderRO, DatabaseProviderRW, HeaderProvider, HeaderSyncGap,
+    HeaderSyncGapProvider, StageCheckpointReader,
 };
 use reth_stages_api::{
     BlockErrorKind, CheckpointBlockRange, EntitiesCheckpoint, ExecInput, ExecOutput,
-    HeadersCheckpoint, Stage, StageCheckpoint, StageError, StageId, UnwindInput, UnwindOutput,
+    HeadersCheckpoint, Stage, StageCheckpoint, StageError, StageEstimate, StageId, UnwindInput,
+    UnwindOutput,
 };
 use reth_storage_errors::provider::ProviderError;
 use std::{
+    ops::RangeInclusive,
     sync::Arc,
     task::{ready, Context, Poll},
 };
 use tokio::sync::watch;
 use tracing::*;
 
+/// Default maximum number of blocks the [`HeaderStage`] unwinds on its own if the local head is no
+/// longer canonical.
+pub const DEFAULT_MAX_AUTO_UNWIND_DEPTH: u64 = 64;
+
 /// The headers stage.
 ///
 /// The headers stage downloads all block headers from the highest block in storage to
@@ -38,6 +46,12 @@ use tracing::*;
 /// NOTE: This stage downloads headers in reverse and pushes them to the ETL [`Collector`]. It then
 /// proceeds to push them sequentially to static files. The stage checkpoint is not updated until
 /// this stage is done.
+///
+/// If the downloaded headers don't attach to the local head, the download is restarted from an
+/// ancestor of the local head, up to [`HeaderStage::with_max_auto_unwind_depth`] blocks deep, and
+/// from another peer than the one that sent the detached header. If the headers downloaded the
+/// second time attach to an older canonical block, the local head was reorged and the stage
+/// unwinds its tables to that block before it writes the new branch.
 #[derive(Debug)]
 pub struct HeaderStage<Provider, Downloader: HeaderDownloader> {
     /// Database handle.
@@ -56,6 +70,27 @@ pub struct HeaderStage<Provider, Downloader: HeaderDownloader> {
     header_collector: Collector<BlockNumber, SealedHeader>,
     /// Returns true if the ETL collector has all necessary headers to fill the gap.
     is_etl_ready: bool,
+    /// The maximum number of blocks the stage unwinds on its own if the local head was reorged.
+    max_auto_unwind_depth: u64,
+    /// The local head the downloaded headers didn't attach to, if any.
+    detached_head: Option<DetachedHead>,
+}
+
+/// A local head that the downloaded headers didn't attach to.
+#[derive(Debug)]
+struct DetachedHead {
+    /// The local head we attempted to attach to.
+    local_head: Box<SealedHeader>,
+    /// The header we attempted to attach.
+    header: Box<SealedHeader>,
+    /// The error that occurred when attempting to attach the header.
+    error: Box<ConsensusError>,
+    /// The peer that sent the header, which doesn't serve the second download.
+    peer_id: Option<PeerId>,
+    /// The canonical ancestor of the local head the download was restarted from.
+    probe_head: SealedHeader,
+    /// The highest block at which the downloaded headers match the canonical chain, once known.
+    fork_block: Option<BlockNumber>,
 }
 
 // === impl HeaderStage ===
@@ -81,7 +116,36 @@ where
             hash_collector: Collector::new(etl_config.file_size / 2, etl_config.dir.clone()),
             header_collector: Collector::new(etl_config.file_size / 2, etl_config.dir),
             is_etl_ready: false,
+            max_auto_unwind_depth: DEFAULT_MAX_AUTO_UNWIND_DEPTH,
+            detached_head: None,
+        }
+    }
+
+    /// Sets the maximum number of blocks the stage unwinds on its own if the local head is no
+    /// longer canonical.
+    ///
+    /// If the downloaded headers attach to a block deeper than that, the stage fails with
+    /// [`StageError::ReorgTooDeep`]. `0` disables the automatic unwind.
+    pub const fn with_max_auto_unwind_depth(mut self, max_auto_unwind_depth: u64) -> Self {
+        self.max_auto_unwind_depth = max_auto_unwind_depth;
+        self
+    }
+
+    /// Clears the ETL collectors and the detached head.
+    fn clear(&mut self) {
+        self.hash_collector.clear();
+        self.header_collector.clear();
+        self.clear_detached_head();
+        self.is_etl_ready = false;
+    }
+
+    /// Clears the detached head and lets all peers serve the downloads again.
+    fn clear_detached_head(&mut self) -> Option<DetachedHead> {
+        let detached = self.detached_head.take();
+        if detached.as_ref().is_some_and(|detached| detached.peer_id.is_some()) {
+            self.downloader.exclude_peer(None);
         }
+        detached
     }
 
     /// Write downloaded headers to storage from ETL.
@@ -108,6 +172,9 @@ where
             .header_td_by_number(last_header_number)?
             .ok_or(ProviderError::TotalDifficultyNotFound(last_header_number))?;
 
+        // Headers below this block are already stored
+        let first_block = last_header_number + 1;
+
         // Although headers were downloaded in reverse order, the collector iterates it in ascending
         // order
         let mut writer = static_file_provider.latest_writer(StaticFileSegment::Headers)?;
@@ -120,24 +187,26 @@ where
             }
 
             let (sealed_header, _) = SealedHeader::from_compact(&header_buf, header_buf.len());
-            let (header, header_hash) = sealed_header.split();
-            if header.number == 0 {
+            // Skip the genesis header and, after a reorg of the local head, the headers the new
+            // branch has in common with the canonical chain
+            if sealed_header.number <= last_header_number {
                 continue
             }
-            last_header_number = header.number;
+            last_header_number = sealed_header.number;
 
             // Increase total difficulty
-            td += header.difficulty;
+            td += sealed_header.difficulty;
 
             // Header validation
-            self.consensus.validate_header_with_total_difficulty(&header, td).map_err(|error| {
-                StageError::Block {
-                    block: Box::new(header.clone().seal(header_hash)),
+            self.consensus.validate_header_with_total_difficulty(&sealed_header, td).map_err(
+                |error| StageError::Block {
+                    block: Box::new(sealed_header.clone()),
                     error: BlockErrorKind::Validation(error),
-                }
-            })?;
+                },
+            )?;
 
             // Append to Headers segment
+            let (header, header_hash) = sealed_header.split();
             writer.append_header(header, td, header_hash)?;
         }
 
@@ -160,21 +229,20 @@ where
         // in order (further syncs).
         for (index, hash_to_number) in self.hash_collector.iter()?.enumerate() {
             let (hash, number) = hash_to_number?;
+            let number = RawValue::<BlockNumber>::from_vec(number);
+
+            if !first_sync && number.value()? < first_block {
+                continue
+            }
 
             if index > 0 && index % interval == 0 && total_headers > 100 {
                 info!(target: "sync::stages::headers", progress = %format!("{:.2}%", (index as f64 / total_headers as f64) * 100.0), "Writing headers hash index");
             }
 
             if first_sync {
-                cursor_header_numbers.append(
-                    RawKey::<BlockHash>::from_vec(hash),
-                    RawValue::<BlockNumber>::from_vec(number),
-                )?;
+                cursor_header_numbers.append(RawKey::<BlockHash>::from_vec(hash), number)?;
             } else {
-                cursor_header_numbers.insert(
-                    RawKey::<BlockHash>::from_vec(hash),
-                    RawValue::<BlockNumber>::from_vec(number),
-                )?;
+                cursor_header_numbers.insert(RawKey::<BlockHash>::from_vec(hash), number)?;
             }
         }
 
@@ -185,7 +253,7 @@ where
 impl<DB, Provider, D> Stage<DB> for HeaderStage<Provider, D>
 where
     DB: Database,
-    Provider: HeaderSyncGapProvider,
+    Provider: HeaderSyncGapProvider + HeaderProvider,
     D: HeaderDownloader,
 {
     /// Return the id of the stage
@@ -193,6 +261,14 @@ where
         StageId::Headers
     }
 
+    fn estimate(
+        &self,
+        _provider: &DatabaseProviderRO<DB>,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<StageEstimate, StageError> {
+        Ok(StageEstimate::blocks(range))
+    }
+
     fn poll_execute_ready(
         &mut self,
         cx: &mut Context<'_>,
@@ -218,15 +294,22 @@ where
                 target = ?tip,
                 "Target block already reached"
             );
+            self.clear_detached_head();
             self.is_etl_ready = true;
             return Poll::Ready(Ok(()))
         }
 
         debug!(target: "sync::stages::headers", ?tip, head = ?gap.local_head.hash(), "Commencing sync");
-        let local_head_number = gap.local_head.number;
+
+        // If the downloaded headers didn't attach to the local head, we download from its ancestor
+        let local_head = match &self.detached_head {
+            Some(detached) => detached.probe_head.clone(),
+            None => gap.local_head,
+        };
+        let mut local_head_number = local_head.number;
 
         // let the downloader know what to sync
-        self.downloader.update_sync_gap(gap.local_head, gap.target);
+        self.downloader.update_sync_gap(local_head, gap.target);
 
         // We only want to stop once we have all the headers on ETL filespace (disk).
         loop {
@@ -236,20 +319,88 @@ where
                     for header in headers {
                         let header_number = header.number;
 
+                        // The first downloaded header that matches the canonical chain is the last
+                        // block the new branch and the local chain have in common
+                        if let Some(detached) = self
+                            .detached_head
+                            .as_mut()
+                            .filter(|detached| detached.fork_block.is_none())
+                            .filter(|detached| header_number <= detached.local_head.number)
+                        {
+                            if self
+                                .provider
+                                .sealed_header(header_number)?
+                                .is_some_and(|canonical| canonical.hash() == header.hash())
+                            {
+                                detached.fork_block = Some(header_number);
+                            }
+                        }
+
                         self.hash_collector.insert(header.hash(), header_number)?;
                         self.header_collector.insert(header_number, header)?;
 
                         // Headers are downloaded in reverse, so if we reach here, we know we have
                         // filled the gap.
                         if header_number == local_head_number + 1 {
+                            if let Some(detached) = &mut self.detached_head {
+                                // the headers attach to the ancestor we downloaded from
+                                detached.fork_block.get_or_insert(local_head_number);
+                            }
                             self.is_etl_ready = true;
                             return Poll::Ready(Ok(()))
                         }
                     }
                 }
-                Some(Err(HeadersDownloaderError::DetachedHead { local_head, header, error })) => {
-                    error!(target: "sync::stages::headers", %error, "Cannot attach header to head");
-                    return Poll::Ready(Err(StageError::DetachedHead { local_head, header, error }))
+                Some(Err(HeadersDownloaderError::DetachedHead {
+                    local_head,
+                    header,
+                    error,
+                    peer_id,
+                })) => {
+                    if let Some(detached) = self.clear_detached_head() {
+                        // The headers don't even attach to the ancestor of the local head
+                        error!(target: "sync::stages::headers", %error, max_depth = self.max_auto_unwind_depth, "Cannot attach header to ancestor of head");
+                        self.clear();
+                        return Poll::Ready(Err(StageError::ReorgTooDeep {
+                            local_head: detached.local_head,
+                            max_depth: self.max_auto_unwind_depth,
+                        }))
+                    }
+                    if self.max_auto_unwind_depth == 0 || local_head.number == 0 {
+                        error!(target: "sync::stages::headers", %error, "Cannot attach header to head");
+                        self.clear();
+                        return Poll::Ready(Err(StageError::DetachedHead {
+                            local_head,
+                            header,
+                            error,
+                        }))
+                    }
+
+                    // The local head might have been reorged. Before we unwind anything, we
+                    // download the headers again from another peer, this time from an ancestor of
+                    // the local head, so the new branch is confirmed by a second peer and we learn
+                    // where it attaches to the local chain.
+                    let probe_number = local_head.number.saturating_sub(self.max_auto_unwind_depth);
+                    let probe_head = self
+                        .provider
+                        .sealed_header(probe_number)?
+                        .ok_or_else(|| ProviderError::HeaderNotFound(probe_number.into()))?;
+                    warn!(target: "sync::stages::headers", head = local_head.number, %probe_number, "Local head may have been reorged, downloading headers from its ancestor");
+
+                    self.clear();
+                    if peer_id.is_some() {
+                        self.downloader.exclude_peer(peer_id);
+                    }
+                    self.downloader.update_local_head(probe_head.clone());
+                    local_head_number = probe_number;
+                    self.detached_head = Some(DetachedHead {
+                        local_head,
+                        header,
+                        error,
+                        peer_id,
+                        probe_head,
+                        fork_block: None,
+                    });
                 }
                 None => return Poll::Ready(Err(StageError::ChannelClosed)),
             }
@@ -275,6 +426,51 @@ where
             return Err(StageError::MissingDownloadBuffer)
         }
 
+        if let Some(detached) = self.clear_detached_head() {
+            let fork_block = detached.fork_block.unwrap_or(detached.probe_head.number);
+
+            if fork_block >= detached.local_head.number {
+                // The headers downloaded the second time attach to the local head, so the detached
+                // header was not part of the canonical chain.
+                warn!(
+                    target: "sync::stages::headers",
+                    head = detached.local_head.number,
+                    detached_header = ?detached.header.hash(),
+                    error = %detached.error,
+                    "Downloaded headers attach to the local head, ignoring detached header"
+                );
+            } else {
+                // If other stages already processed the reorged blocks, all stages have to be
+                // unwound, which is up to the pipeline.
+                for stage_id in StageId::ALL.into_iter().filter(|id| *id != StageId::Headers) {
+                    let checkpoint = provider.get_stage_checkpoint(stage_id)?.unwrap_or_default();
+                    if checkpoint.block_number > fork_block {
+                        self.clear();
+                        return Err(StageError::ReorgedHead {
+                            local_head: detached.local_head,
+                            fork_block,
+                        })
+                    }
+                }
+
+                warn!(
+                    target: "sync::stages::headers",
+                    head = detached.local_head.number,
+                    %fork_block,
+                    "Local head was reorged, unwinding headers"
+                );
+                // The downloaded headers stay in the ETL collectors and are written on the next
+                // execution, once the unwind is committed.
+                let input = UnwindInput {
+                    checkpoint: current_checkpoint,
+                    unwind_to: fork_block,
+                    bad_block: None,
+                };
+                let UnwindOutput { checkpoint } = self.unwind_headers(provider, input)?;
+                return Ok(ExecOutput { checkpoint, done: false })
+            }
+        }
+
         // Reset flag
         self.is_etl_ready = false;
 
@@ -314,6 +510,20 @@ where
     ) -> Result<UnwindOutput, StageError> {
         self.sync_gap.take();
 
+        self.unwind_headers(provider, input)
+    }
+}
+
+impl<Provider, Downloader> HeaderStage<Provider, Downloader>
+where
+    Downloader: HeaderDownloader,
+{
+    /// Unwinds the headers tables and static files to the given block.
+    fn unwind_headers<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
         // First unwind the db tables, until the unwind_to block number. use the walker to unwind
         // HeaderNumbers based on the index in CanonicalHeaders
         provider.unwind_table_by_walker::<tables::CanonicalHeaders, tables::HeaderNumbers>(
@@ -376,11 +586,15 @@ mod tests {
         stage_test_suite, ExecuteStageTestRunner, StageTestRunner, UnwindStageTestRunner,
     };
     use assert_matches::assert_matches;
-    use reth_primitives::{BlockBody, SealedBlock, SealedBlockWithSenders, B256};
+    use reth_downloaders::headers::reverse_headers::ReverseHeadersDownloader;
+    use reth_network_p2p::test_utils::TestHeadersClient;
+    use reth_primitives::{
+        BlockBody, BlockWithSenders, Header, SealedBlock, SealedBlockWithSenders, B256, U256,
+    };
     use reth_provider::{
-        BlockWriter, ExecutionOutcome, ProviderFactory, StaticFileProviderFactory,
+        BlockNumReader, BlockWriter, ExecutionOutcome, ProviderFactory, StaticFileProviderFactory,
     };
-    use reth_stages_api::StageUnitCheckpoint;
+    use reth_stages_api::{StageExt, StageUnitCheckpoint};
     use reth_testing_utils::generators::{self, random_header, random_header_range};
     use reth_trie::{updates::TrieUpdates, HashedPostState};
     use test_runner::HeadersTestRunner;
@@ -388,7 +602,7 @@ mod tests {
     mod test_runner {
         use super::*;
         use crate::test_utils::{TestRunnerError, TestStageDB};
-        use reth_consensus::test_utils::TestConsensus;
+        use reth_consensus::{test_utils::TestConsensus, PostExecutionInput};
         use reth_db::{test_utils::TempDatabase, DatabaseEnv};
         use reth_downloaders::headers::reverse_headers::{
             ReverseHeadersDownloader, ReverseHeadersDownloaderBuilder,
@@ -542,6 +756,69 @@ mod tests {
             }
         }
 
+        /// Consensus that only accepts headers that attach to their parent.
+        #[derive(Debug, Default)]
+        struct ParentHashConsensus;
+
+        impl Consensus for ParentHashConsensus {
+            fn validate_header(&self, _header: &SealedHeader) -> Result<(), ConsensusError> {
+                Ok(())
+            }
+
+            fn validate_header_against_parent(
+                &self,
+                header: &SealedHeader,
+                parent: &SealedHeader,
+            ) -> Result<(), ConsensusError> {
+                if header.parent_hash != parent.hash() {
+                    return Err(ConsensusError::ParentUnknown { hash: header.parent_hash })
+                }
+                Ok(())
+            }
+
+            fn validate_header_with_total_difficulty(
+                &self,
+                _header: &Header,
+                _total_difficulty: U256,
+            ) -> Result<(), ConsensusError> {
+                Ok(())
+            }
+
+            fn validate_block_pre_execution(
+                &self,
+                _block: &SealedBlock,
+            ) -> Result<(), ConsensusError> {
+                Ok(())
+            }
+
+            fn validate_block_post_execution(
+                &self,
+                _block: &BlockWithSenders,
+                _input: PostExecutionInput<'_>,
+            ) -> Result<(), ConsensusError> {
+                Ok(())
+            }
+        }
+
+        impl HeadersTestRunner<ReverseHeadersDownloader<TestHeadersClient>> {
+            /// Creates a runner with the linear downloader that detects headers which don't attach
+            /// to the local head.
+            pub(crate) fn with_detached_head_detection() -> Self {
+                let client = TestHeadersClient::default();
+                Self {
+                    client: client.clone(),
+                    channel: watch::channel(B256::ZERO),
+                    downloader_factory: Box::new(move || {
+                        ReverseHeadersDownloaderBuilder::default()
+                            .stream_batch_size(500)
+                            .build(client.clone(), Arc::new(ParentHashConsensus))
+                    }),
+                    db: TestStageDB::default(),
+                    consensus: Arc::new(TestConsensus::default()),
+                }
+            }
+        }
+
         impl<D: HeaderDownloader> HeadersTestRunner<D> {
             pub(crate) fn check_no_header_entry_above(
                 &self,
@@ -692,4 +969,100 @@ mod tests {
         assert!(runner.stage().hash_collector.is_empty());
         assert!(runner.stage().header_collector.is_empty());
     }
+
+    /// Executes the stage until it's done, committing after every execution like the pipeline.
+    async fn execute_to_completion<DB: Database, S: Stage<DB>>(
+        factory: &ProviderFactory<DB>,
+        stage: &mut S,
+        mut input: ExecInput,
+    ) -> Result<Vec<ExecOutput>, StageError> {
+        let mut outputs = Vec::new();
+        loop {
+            stage.execute_ready(input).await?;
+            let provider_rw = factory.provider_rw().unwrap();
+            let output = stage.execute(&provider_rw, input)?;
+            factory.static_file_provider().commit().unwrap();
+            provider_rw.commit().unwrap();
+
+            outputs.push(output);
+            if output.done {
+                return Ok(outputs)
+            }
+            input.checkpoint = Some(output.checkpoint);
+        }
+    }
+
+    /// Seeds the local chain up to block 1000 and a branch that replaces the last three blocks of
+    /// it up to block 1200, which the client serves twice.
+    async fn seed_reorged_head(
+        runner: &mut HeadersTestRunner<ReverseHeadersDownloader<TestHeadersClient>>,
+    ) -> (ExecInput, SealedHeader, Vec<SealedHeader>) {
+        let input = ExecInput { target: Some(1200), checkpoint: Some(StageCheckpoint::new(1000)) };
+        let headers = runner.seed_execution(input).expect("failed to seed execution");
+        let local_head = headers.first().cloned().unwrap();
+
+        let provider = runner.db().factory.provider().unwrap();
+        let fork = provider.sealed_header(997).unwrap().unwrap();
+        let canonical = (937..997)
+            .map(|number| provider.sealed_header(number).unwrap().unwrap())
+            .collect::<Vec<_>>();
+        let branch = random_header_range(&mut generators::rng(), 998..1201, fork.hash());
+
+        // the first response doesn't attach to the local head, the second one is served after the
+        // download was restarted from an ancestor of it
+        let first = branch.iter().rev().filter(|header| header.number > 1000);
+        let second =
+            branch.iter().rev().chain(std::iter::once(&fork)).chain(canonical.iter().rev());
+        runner.client.extend(first.chain(second).map(|header| header.clone().unseal())).await;
+        runner.send_tip(branch.last().unwrap().hash());
+
+        (input, local_head, branch)
+    }
+
+    /// Execute the stage on a local head that was reorged by the downloaded headers.
+    #[tokio::test]
+    async fn execute_with_reorged_local_head() {
+        let mut runner = HeadersTestRunner::with_detached_head_detection();
+        let (input, local_head, branch) = seed_reorged_head(&mut runner).await;
+
+        let factory = runner.db().factory.clone();
+        let mut stage = runner.stage();
+        let outputs = execute_to_completion(&factory, &mut stage, input).await.unwrap();
+
+        // the stage first unwinds to the fork block and then writes the new branch
+        let checkpoints = outputs
+            .iter()
+            .map(|output| (output.checkpoint.block_number, output.done))
+            .collect::<Vec<_>>();
+        assert_eq!(checkpoints, vec![(997, false), (1200, true)]);
+        let input = ExecInput { target: Some(1200), checkpoint: Some(StageCheckpoint::new(998)) };
+        assert!(runner.validate_execution(input, outputs.last().copied()).is_ok());
+
+        let provider = factory.provider().unwrap();
+        for header in &branch {
+            assert_eq!(provider.block_hash(header.number).unwrap(), Some(header.hash()));
+        }
+        assert_eq!(provider.block_number(local_head.hash()).unwrap(), None);
+        assert!(stage.hash_collector.is_empty());
+        assert!(stage.header_collector.is_empty());
+    }
+
+    /// Execute the stage on a local head that was reorged deeper than the stage unwinds on its own.
+    #[tokio::test]
+    async fn execute_with_reorged_local_head_too_deep() {
+        let mut runner = HeadersTestRunner::with_detached_head_detection();
+        let (input, local_head, _) = seed_reorged_head(&mut runner).await;
+
+        let factory = runner.db().factory.clone();
+        let mut stage = runner.stage().with_max_auto_unwind_depth(2);
+        let result = execute_to_completion(&factory, &mut stage, input).await;
+        assert_matches!(
+            result,
+            Err(StageError::ReorgTooDeep { local_head: head, max_depth: 2 }) if *head == local_head
+        );
+
+        // nothing was unwound
+        let provider = factory.provider().unwrap();
+        assert_eq!(provider.block_hash(1000).unwrap(), Some(local_head.hash()));
+    }
 }
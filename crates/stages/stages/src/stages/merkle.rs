@@ -158,6 +158,7 @@ impl<DB: Database> Stage<DB> for MerkleStage {
             Self::Both { clean_threshold } => *clean_threshold,
         };
 
+        let stage_id = <Self as Stage<DB>>::id(self);
         let range = input.next_block_range();
         let (from_block, to_block) = range.clone().into_inner();
         let current_block_number = input.checkpoint().block_number;
@@ -213,7 +214,7 @@ impl<DB: Database> Stage<DB> for MerkleStage {
                 .root_with_progress()
                 .map_err(|e| {
                     error!(target: "sync::stages::merkle", %e, ?current_block_number, ?to_block, "State root with progress failed! {INVALID_STATE_ROOT_ERROR_MESSAGE}");
-                    StageError::Fatal(Box::new(e))
+                    StageError::Fatal(Box::new(e)).with_context(stage_id, to_block)
                 })?;
             match progress {
                 StateRootProgress::Progress(state, hashed_entries_walked, updates) => {
@@ -250,7 +251,7 @@ impl<DB: Database> Stage<DB> for MerkleStage {
                 StateRoot::incremental_root_with_updates(provider.tx_ref(), range)
                     .map_err(|e| {
                         error!(target: "sync::stages::merkle", %e, ?current_block_number, ?to_block, "Incremental state root failed! {INVALID_STATE_ROOT_ERROR_MESSAGE}");
-                        StageError::Fatal(Box::new(e))
+                        StageError::Fatal(Box::new(e)).with_context(stage_id, to_block)
                     })?;
             updates.flush(provider.tx_ref())?;
 
@@ -316,7 +317,10 @@ impl<DB: Database> Stage<DB> for MerkleStage {
         // Unwind trie only if there are transitions
         if !range.is_empty() {
             let (block_root, updates) = StateRoot::incremental_root_with_updates(tx, range)
-                .map_err(|e| StageError::Fatal(Box::new(e)))?;
+                .map_err(|e| {
+                    StageError::Fatal(Box::new(e))
+                        .with_context(<Self as Stage<DB>>::id(self), input.unwind_to)
+                })?;
 
             // Validate the calculated state root
             let target = provider
@@ -8,6 +8,8 @@ mod finish;
 mod hashing_account;
 /// Storage hashing stage.
 mod hashing_storage;
+/// The header accumulator stage.
+mod header_accumulator;
 /// The headers stage.
 mod headers;
 /// Index history of account changes
@@ -18,6 +20,8 @@ mod index_storage_history;
 mod merkle;
 /// The sender recovery stage.
 mod sender_recovery;
+/// The transaction history stage
+mod transaction_history;
 /// The transaction lookup stage
 mod tx_lookup;
 
@@ -26,15 +30,20 @@ pub use execution::*;
 pub use finish::*;
 pub use hashing_account::*;
 pub use hashing_storage::*;
+pub use header_accumulator::*;
 pub use headers::*;
 pub use index_account_history::*;
 pub use index_storage_history::*;
 pub use merkle::*;
 
 pub use sender_recovery::*;
+pub use transaction_history::*;
 pub use tx_lookup::*;
 
+mod prefetch;
 mod utils;
+
+pub use prefetch::DEFAULT_PREFETCH_BLOCKS;
 use utils::*;
 
 #[cfg(test)]
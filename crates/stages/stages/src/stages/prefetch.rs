@@ -0,0 +1,181 @@
+use reth_primitives::{BlockNumber, Header, TransactionSignedNoHash, TxNumber};
+use reth_provider::{
+    providers::StaticFileProvider, HeaderProvider, ProviderError, ProviderResult,
+    TransactionsProvider,
+};
+use std::{
+    ops::Range,
+    sync::mpsc::{channel, sync_channel, Receiver, Sender},
+    thread::JoinHandle,
+};
+use tracing::*;
+
+/// Default number of blocks the execution stage reads ahead of the executed block.
+pub const DEFAULT_PREFETCH_BLOCKS: usize = 3;
+
+/// A block that was read ahead of its execution.
+#[derive(Debug)]
+pub(crate) struct PrefetchedBlock {
+    /// The number of the block.
+    pub(crate) number: BlockNumber,
+    /// The header of the block.
+    pub(crate) header: Header,
+    /// The transactions of the block.
+    pub(crate) transactions: Vec<TransactionSignedNoHash>,
+}
+
+/// Reads and decodes the headers and transactions of upcoming blocks from the static files on a
+/// separate thread, while the execution stage executes the current block.
+///
+/// The rest of the block (body indices, ommers, withdrawals and senders) and the total difficulty
+/// are read through the database provider, whose read-write transaction can't be shared with
+/// another thread. They are cheap to read compared to the transactions, and are read on the
+/// executing thread.
+///
+/// Blocks are requested with [`BlockPrefetcher::request`] and received in the same order with
+/// [`BlockPrefetcher::next_block`]. Dropping the prefetcher discards the blocks that were not
+/// received, and waits for the thread to exit.
+#[derive(Debug)]
+pub(crate) struct BlockPrefetcher {
+    /// The blocks to read, with the transaction range of each block.
+    requests: Option<Sender<(BlockNumber, Range<TxNumber>)>>,
+    /// The blocks that were read.
+    blocks: Receiver<ProviderResult<PrefetchedBlock>>,
+    /// Number of requested blocks that were not received yet.
+    pending: usize,
+    /// The handle of the prefetching thread.
+    handle: Option<JoinHandle<()>>,
+}
+
+impl BlockPrefetcher {
+    /// Spawns the prefetching thread, which reads at most `lookahead` blocks that were not
+    /// received yet.
+    pub(crate) fn spawn(static_file_provider: StaticFileProvider, lookahead: usize) -> Self {
+        let (requests_tx, requests_rx) = channel::<(BlockNumber, Range<TxNumber>)>();
+        let (blocks_tx, blocks_rx) = sync_channel(lookahead.max(1));
+
+        let handle = std::thread::Builder::new()
+            .name("execution-prefetch".to_string())
+            .spawn(move || {
+                for (number, tx_range) in requests_rx {
+                    let block = read_block(&static_file_provider, number, tx_range);
+                    // The receiver is gone if the stage stopped early
+                    if blocks_tx.send(block).is_err() {
+                        break
+                    }
+                }
+            })
+            .expect("failed to spawn execution prefetch thread");
+
+        Self { requests: Some(requests_tx), blocks: blocks_rx, pending: 0, handle: Some(handle) }
+    }
+
+    /// Returns the number of requested blocks that were not received yet.
+    pub(crate) const fn pending(&self) -> usize {
+        self.pending
+    }
+
+    /// Requests the block with the given transaction range.
+    pub(crate) fn request(&mut self, number: BlockNumber, tx_range: Range<TxNumber>) {
+        if let Some(requests) = &self.requests {
+            if requests.send((number, tx_range)).is_ok() {
+                self.pending += 1;
+            }
+        }
+    }
+
+    /// Waits for the next requested block.
+    ///
+    /// Returns `None` if there's no pending request or the prefetching thread stopped.
+    pub(crate) fn next_block(&mut self) -> Option<ProviderResult<PrefetchedBlock>> {
+        if self.pending == 0 {
+            return None
+        }
+        let block = self.blocks.recv().ok()?;
+        self.pending -= 1;
+        Some(block)
+    }
+}
+
+impl Drop for BlockPrefetcher {
+    fn drop(&mut self) {
+        // Stop accepting requests and drain the blocks that were read in the meantime, so the
+        // thread can't block on a full channel.
+        self.requests.take();
+        if self.pending > 0 {
+            trace!(
+                target: "sync::stages::execution",
+                pending = self.pending,
+                "Discarding prefetched blocks"
+            );
+        }
+        while self.blocks.recv().is_ok() {}
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Reads the header and the transactions of the block from the static files.
+fn read_block(
+    static_file_provider: &StaticFileProvider,
+    number: BlockNumber,
+    tx_range: Range<TxNumber>,
+) -> ProviderResult<PrefetchedBlock> {
+    let header = static_file_provider
+        .header_by_number(number)?
+        .ok_or_else(|| ProviderError::HeaderNotFound(number.into()))?;
+    let transactions = if tx_range.is_empty() {
+        Vec::new()
+    } else {
+        static_file_provider.transactions_by_tx_range(tx_range)?
+    };
+
+    Ok(PrefetchedBlock { number, header, transactions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{StorageKind, TestStageDB};
+    use reth_provider::{BlockReader, StaticFileProviderFactory};
+    use reth_testing_utils::generators::{self, random_block_range};
+
+    #[test]
+    fn prefetch_in_order() {
+        let mut rng = generators::rng();
+        let blocks = random_block_range(&mut rng, 0..=10, Default::default(), 0..4);
+        let db = TestStageDB::default();
+        db.insert_blocks(blocks.iter(), StorageKind::Static).unwrap();
+        let provider = db.factory.provider().unwrap();
+
+        let mut prefetcher = BlockPrefetcher::spawn(db.factory.static_file_provider(), 2);
+        assert!(prefetcher.next_block().is_none());
+
+        for block in &blocks {
+            let body = provider.block_body_indices(block.number).unwrap().unwrap();
+            prefetcher.request(block.number, body.tx_num_range());
+        }
+        assert_eq!(prefetcher.pending(), blocks.len());
+
+        for block in blocks.iter().take(5) {
+            let prefetched = prefetcher.next_block().unwrap().unwrap();
+            assert_eq!(prefetched.number, block.number);
+            assert_eq!(prefetched.header, block.header.clone().unseal());
+            assert_eq!(
+                prefetched.transactions,
+                block
+                    .body
+                    .iter()
+                    .cloned()
+                    .map(Into::into)
+                    .collect::<Vec<TransactionSignedNoHash>>()
+            );
+        }
+        assert_eq!(prefetcher.pending(), blocks.len() - 5);
+
+        // dropping with pending blocks must not block on the full channel
+        drop(prefetcher);
+    }
+}
@@ -6,17 +6,21 @@ use reth_db_api::{
     database::Database,
     transaction::{DbTx, DbTxMut},
 };
-use reth_primitives::{Address, StaticFileSegment, TransactionSignedNoHash, TxNumber};
+use reth_primitives::{Address, BlockNumber, StaticFileSegment, TransactionSignedNoHash, TxNumber};
 use reth_provider::{
-    BlockReader, DatabaseProviderRW, HeaderProvider, ProviderError, PruneCheckpointReader,
-    StatsReader,
+    BlockReader, DatabaseProviderRO, DatabaseProviderRW, HeaderProvider, ProviderError,
+    PruneCheckpointReader, StatsReader,
 };
 use reth_prune_types::PruneSegment;
 use reth_stages_api::{
     BlockErrorKind, EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint, StageError,
-    StageId, UnwindInput, UnwindOutput,
+    StageEstimate, StageId, UnwindInput, UnwindOutput,
+};
+use std::{
+    fmt::Debug,
+    ops::{Range, RangeInclusive},
+    sync::mpsc,
 };
-use std::{fmt::Debug, ops::Range, sync::mpsc};
 use thiserror::Error;
 use tracing::*;
 
@@ -57,6 +61,14 @@ impl<DB: Database> Stage<DB> for SenderRecoveryStage {
         StageId::SenderRecovery
     }
 
+    fn estimate(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<StageEstimate, StageError> {
+        Ok(StageEstimate::transactions(provider, range)?)
+    }
+
     /// Retrieve the range of transactions to iterate over by querying
     /// [`BlockBodyIndices`][reth_db::tables::BlockBodyIndices],
     /// collect transactions within that range, recover signer for each transaction and store
@@ -280,7 +292,7 @@ struct FailedSenderRecoveryError {
 mod tests {
     use assert_matches::assert_matches;
     use reth_db_api::cursor::DbCursorRO;
-    use reth_primitives::{BlockNumber, SealedBlock, TransactionSigned, B256};
+    use reth_primitives::{BlockNumber, SealedBlock, Signature, TransactionSigned, B256};
     use reth_provider::{
         providers::StaticFileWriter, PruneCheckpointWriter, StaticFileProviderFactory,
         TransactionsProvider,
@@ -427,6 +439,31 @@ mod tests {
         assert!(runner.validate_execution(first_input, result.ok()).is_ok(), "validation failed");
     }
 
+    /// A transaction whose sender can't be recovered fails the stage at the block of the
+    /// transaction
+    #[tokio::test]
+    async fn execute_unrecoverable_sender() {
+        let mut rng = generators::rng();
+
+        let runner = SenderRecoveryTestRunner::default();
+        let input = ExecInput { target: Some(20), checkpoint: Some(StageCheckpoint::new(0)) };
+
+        let bad_block = 10;
+        let mut blocks = random_block_range(&mut rng, 0..=input.target(), B256::ZERO, 1..2);
+        blocks[bad_block as usize].body[0].signature = Signature::default();
+        runner.db.insert_blocks(blocks.iter(), StorageKind::Static).expect("insert blocks");
+
+        let err = runner.execute(input).await.unwrap().unwrap_err();
+        assert_matches!(&err, StageError::Block { block, .. } if block.number == bad_block);
+
+        // the pipeline reports the block of the error instead of the first block of the run
+        let err = err.with_context(StageId::SenderRecovery, input.next_block());
+        assert_eq!(err.block_number(), Some(bad_block));
+        assert!(err
+            .to_string()
+            .starts_with(&format!("stage SenderRecovery failed at block #{bad_block}:")));
+    }
+
     #[test]
     fn stage_checkpoint_pruned() {
         let db = TestStageDB::default();
@@ -0,0 +1,359 @@
+use reth_config::config::TransactionHistoryConfig;
+use reth_db::tables;
+use reth_db_api::{database::Database, transaction::DbTxMut};
+use reth_primitives::BlockNumber;
+use reth_provider::{
+    BlockReader, DatabaseProviderRO, DatabaseProviderRW, PruneCheckpointReader,
+    PruneCheckpointWriter, StatsReader, TransactionHistoryProvider, TransactionHistorySettings,
+    TransactionHistoryWriter,
+};
+use reth_prune_types::{PruneCheckpoint, PruneMode, PrunePurpose, PruneSegment};
+use reth_stages_api::{
+    EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageEstimate,
+    StageId, UnwindInput, UnwindOutput,
+};
+use reth_storage_errors::provider::ProviderError;
+use std::ops::RangeInclusive;
+use tracing::*;
+
+/// Maximum number of blocks whose transactions are held in memory at once while indexing.
+const INDEX_BATCH_BLOCKS: u64 = 1_000;
+
+/// The transaction history stage.
+///
+/// This stage indexes the transactions of each block by the addresses involved in them, i.e. the
+/// sender and the recipient, and optionally the addresses of the emitted logs. This is written to
+/// [`tables::AddressTransactions`], and is used for walking the transactions of an address
+/// newest first, see [`TransactionHistoryProvider::transactions_by_address`].
+///
+/// The stage is disabled by default. While disabled, it only advances its checkpoint and removes
+/// the index if one was built before. Enabling it, or changing its settings, rebuilds the index
+/// from genesis.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionHistoryStage {
+    config: TransactionHistoryConfig,
+    prune_mode: Option<PruneMode>,
+}
+
+impl TransactionHistoryStage {
+    /// Create new instance of [`TransactionHistoryStage`].
+    pub const fn new(config: TransactionHistoryConfig, prune_mode: Option<PruneMode>) -> Self {
+        Self { config, prune_mode }
+    }
+
+    const fn settings(&self) -> TransactionHistorySettings {
+        TransactionHistorySettings { index_logs: self.config.index_logs }
+    }
+}
+
+impl<DB: Database> Stage<DB> for TransactionHistoryStage {
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        StageId::TransactionHistory
+    }
+
+    fn estimate(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<StageEstimate, StageError> {
+        if !self.config.enabled {
+            return Ok(StageEstimate::Transactions(0))
+        }
+        Ok(StageEstimate::transactions(provider, range)?)
+    }
+
+    /// Write address -> transaction number entries
+    fn execute(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        mut input: ExecInput,
+    ) -> Result<ExecOutput, StageError> {
+        let stored_settings = provider.transaction_history_settings()?;
+
+        if !self.config.enabled {
+            if stored_settings.is_some() {
+                let removed = provider.count_entries::<tables::AddressTransactions>()?;
+                provider.tx_ref().clear::<tables::AddressTransactions>()?;
+                provider.save_transaction_history_settings(None)?;
+                info!(target: "sync::stages::transaction_history", removed, "Removed transaction history index");
+            }
+            return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
+        }
+
+        if stored_settings != Some(self.settings()) {
+            info!(
+                target: "sync::stages::transaction_history",
+                settings = ?self.settings(),
+                previous = ?stored_settings,
+                "Rebuilding transaction history index"
+            );
+            provider.tx_ref().clear::<tables::AddressTransactions>()?;
+            provider.save_transaction_history_settings(Some(self.settings()))?;
+            input.checkpoint = Some(StageCheckpoint::new(0));
+        }
+
+        if let Some((target_prunable_block, prune_mode)) = self
+            .prune_mode
+            .map(|mode| {
+                mode.prune_target_block(
+                    input.target(),
+                    PruneSegment::TransactionHistory,
+                    PrunePurpose::User,
+                )
+            })
+            .transpose()?
+            .flatten()
+        {
+            if target_prunable_block > input.checkpoint().block_number {
+                input.checkpoint = Some(StageCheckpoint::new(target_prunable_block));
+
+                // Save prune checkpoint only if we don't have one already.
+                // Otherwise, pruner may skip the unpruned range of blocks.
+                if provider.get_prune_checkpoint(PruneSegment::TransactionHistory)?.is_none() {
+                    let target_prunable_tx_number = provider
+                        .block_body_indices(target_prunable_block)?
+                        .ok_or(ProviderError::BlockBodyIndicesNotFound(target_prunable_block))?
+                        .last_tx_num();
+
+                    provider.save_prune_checkpoint(
+                        PruneSegment::TransactionHistory,
+                        PruneCheckpoint {
+                            block_number: Some(target_prunable_block),
+                            tx_number: Some(target_prunable_tx_number),
+                            prune_mode,
+                        },
+                    )?;
+                }
+            }
+        }
+        if input.target_reached() {
+            return Ok(ExecOutput::done(input.checkpoint().with_entities_stage_checkpoint(
+                stage_checkpoint(provider, input.checkpoint().block_number, input.target())?,
+            )))
+        }
+
+        let (range, is_final_range) =
+            input.next_block_range_with_threshold(self.config.commit_threshold);
+        let end_block = *range.end();
+
+        info!(target: "sync::stages::transaction_history", ?range, "Indexing transactions");
+        let mut start = *range.start();
+        while start <= end_block {
+            let end = end_block.min(start.saturating_add(INDEX_BATCH_BLOCKS - 1));
+            provider.index_transaction_history(start..=end, self.config.index_logs)?;
+            start = end + 1;
+        }
+
+        let stage_checkpoint = stage_checkpoint(provider, end_block, input.target())?;
+        if is_final_range {
+            info!(
+                target: "sync::stages::transaction_history",
+                entries = provider.count_entries::<tables::AddressTransactions>()?,
+                "Transaction history index size"
+            );
+        }
+
+        Ok(ExecOutput {
+            checkpoint: StageCheckpoint::new(end_block)
+                .with_entities_stage_checkpoint(stage_checkpoint),
+            done: is_final_range,
+        })
+    }
+
+    /// Unwind the stage.
+    fn unwind(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        let (range, unwind_to, _) =
+            input.unwind_block_range_with_threshold(self.config.commit_threshold);
+
+        // The index may be disabled, or it may have been removed after the blocks were indexed
+        if provider.transaction_history_settings()?.is_none() {
+            return Ok(UnwindOutput { checkpoint: StageCheckpoint::new(unwind_to) })
+        }
+
+        provider.unindex_transaction_history(range)?;
+
+        Ok(UnwindOutput {
+            checkpoint: StageCheckpoint::new(unwind_to).with_entities_stage_checkpoint(
+                stage_checkpoint(provider, unwind_to, input.checkpoint.block_number)?,
+            ),
+        })
+    }
+}
+
+/// Returns the number of indexed transactions up to the `checkpoint` block out of the transactions
+/// up to the `target` block.
+fn stage_checkpoint<DB: Database>(
+    provider: &DatabaseProviderRW<DB>,
+    checkpoint: BlockNumber,
+    target: BlockNumber,
+) -> Result<EntitiesCheckpoint, StageError> {
+    let transactions = |block| -> Result<u64, StageError> {
+        Ok(provider.block_body_indices(block)?.map(|body| body.next_tx_num()).unwrap_or_default())
+    };
+    Ok(EntitiesCheckpoint { processed: transactions(checkpoint)?, total: transactions(target)? })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{StorageKind, TestStageDB};
+    use reth_primitives::{Address, SealedBlock, TxNumber, B256};
+    use reth_provider::{HistoryDirection, TransactionsProvider};
+    use reth_testing_utils::generators::{
+        self, generate_keys, random_block_range, random_tx, sign_tx_with_key_pair,
+    };
+
+    /// Inserts 10 blocks with 2 transactions each. The first transaction of every block is sent
+    /// by the returned address, so its transaction numbers are `0, 2, .., 18`.
+    fn insert_blocks(db: &TestStageDB) -> (Vec<SealedBlock>, Address) {
+        let mut rng = generators::rng();
+        let key_pair = generate_keys(&mut rng, 1)[0];
+        let mut blocks = random_block_range(&mut rng, 1..=10, B256::ZERO, 1..2);
+        for block in &mut blocks {
+            block.body.insert(0, sign_tx_with_key_pair(key_pair, random_tx(&mut rng)));
+        }
+        let sender = blocks[0].body[0].recover_signer().unwrap();
+        db.insert_blocks(blocks.iter(), StorageKind::Static).expect("insert blocks");
+        (blocks, sender)
+    }
+
+    fn enabled() -> TransactionHistoryStage {
+        TransactionHistoryStage::new(
+            TransactionHistoryConfig { enabled: true, ..Default::default() },
+            None,
+        )
+    }
+
+    fn execute(db: &TestStageDB, stage: &mut TransactionHistoryStage, target: u64) -> ExecOutput {
+        let provider = db.factory.provider_rw().unwrap();
+        let output =
+            stage.execute(&provider, ExecInput { target: Some(target), checkpoint: None }).unwrap();
+        provider.commit().unwrap();
+        output
+    }
+
+    fn tx_numbers(
+        db: &TestStageDB,
+        address: Address,
+        before: Option<TxNumber>,
+        limit: usize,
+        direction: HistoryDirection,
+    ) -> Vec<TxNumber> {
+        db.factory
+            .provider()
+            .unwrap()
+            .transactions_by_address(address, before, limit, direction)
+            .unwrap()
+            .into_iter()
+            .map(|(tx_number, _)| tx_number)
+            .collect()
+    }
+
+    #[test]
+    fn execute_disabled() {
+        let db = TestStageDB::default();
+
+        // No blocks are inserted, so any read of the block data would fail
+        let mut stage = TransactionHistoryStage::default();
+        assert_eq!(
+            execute(&db, &mut stage, 1_000_000),
+            ExecOutput::done(StageCheckpoint::new(1_000_000))
+        );
+        assert!(db.table_is_empty::<tables::AddressTransactions>().unwrap());
+        assert_eq!(db.factory.provider().unwrap().transaction_history_settings().unwrap(), None);
+    }
+
+    #[test]
+    fn disabling_removes_index() {
+        let db = TestStageDB::default();
+        insert_blocks(&db);
+
+        let output = execute(&db, &mut enabled(), 10);
+        assert_eq!(
+            output.checkpoint.entities_stage_checkpoint(),
+            Some(EntitiesCheckpoint { processed: 20, total: 20 })
+        );
+        assert!(!db.table_is_empty::<tables::AddressTransactions>().unwrap());
+
+        execute(&db, &mut TransactionHistoryStage::default(), 10);
+        assert!(db.table_is_empty::<tables::AddressTransactions>().unwrap());
+        assert_eq!(db.factory.provider().unwrap().transaction_history_settings().unwrap(), None);
+    }
+
+    #[test]
+    fn pagination() {
+        let db = TestStageDB::default();
+        let (blocks, sender) = insert_blocks(&db);
+        execute(&db, &mut enabled(), 10);
+
+        let backward = HistoryDirection::Backward;
+        assert_eq!(tx_numbers(&db, sender, None, 4, backward), vec![18, 16, 14, 12]);
+        assert_eq!(tx_numbers(&db, sender, Some(12), 4, backward), vec![10, 8, 6, 4]);
+        assert_eq!(tx_numbers(&db, sender, Some(4), 4, backward), vec![2, 0]);
+        assert_eq!(tx_numbers(&db, sender, Some(0), 4, backward), vec![]);
+        // cursors that are not transactions of the address
+        assert_eq!(tx_numbers(&db, sender, Some(13), 2, backward), vec![12, 10]);
+        assert_eq!(tx_numbers(&db, sender, Some(100), 2, backward), vec![18, 16]);
+
+        let forward = HistoryDirection::Forward;
+        assert_eq!(tx_numbers(&db, sender, None, 4, forward), vec![0, 2, 4, 6]);
+        assert_eq!(tx_numbers(&db, sender, Some(6), 4, forward), vec![8, 10, 12, 14]);
+        assert_eq!(tx_numbers(&db, sender, Some(14), 4, forward), vec![16, 18]);
+        assert_eq!(tx_numbers(&db, sender, Some(18), 4, forward), vec![]);
+        assert_eq!(tx_numbers(&db, sender, Some(15), 1, forward), vec![16]);
+
+        assert_eq!(tx_numbers(&db, sender, None, 0, backward), vec![]);
+        assert_eq!(tx_numbers(&db, Address::random(), None, 4, backward), vec![]);
+
+        // the recipient of the only other transaction of the block
+        let recipient = blocks[4].body[1].to().unwrap();
+        assert_eq!(tx_numbers(&db, recipient, None, 4, backward), vec![9]);
+
+        let provider = db.factory.provider().unwrap();
+        let (tx_number, meta) =
+            provider.transactions_by_address(sender, None, 1, backward).unwrap().remove(0);
+        assert_eq!(tx_number, 18);
+        assert_eq!(meta.tx_hash, blocks[9].body[0].hash());
+        assert_eq!(meta.block_hash, blocks[9].hash());
+        assert_eq!(meta.block_number, 10);
+        assert_eq!(meta.index, 0);
+        assert_eq!(
+            Some(meta.tx_hash),
+            provider.transaction_by_id(tx_number).unwrap().map(|tx| tx.hash)
+        );
+    }
+
+    #[test]
+    fn unwind() {
+        let db = TestStageDB::default();
+        let (_, sender) = insert_blocks(&db);
+        let mut stage = enabled();
+        execute(&db, &mut stage, 10);
+        let entries = db.table::<tables::AddressTransactions>().unwrap();
+
+        let provider = db.factory.provider_rw().unwrap();
+        let output = stage
+            .unwind(
+                &provider,
+                UnwindInput { checkpoint: StageCheckpoint::new(10), unwind_to: 5, bad_block: None },
+            )
+            .unwrap();
+        provider.commit().unwrap();
+
+        assert_eq!(output.checkpoint.block_number, 5);
+        // the last transaction of block 5 is 9
+        assert_eq!(
+            db.table::<tables::AddressTransactions>().unwrap(),
+            entries.into_iter().filter(|(_, tx_number)| tx_number.0 <= 9).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            tx_numbers(&db, sender, None, 10, HistoryDirection::Backward),
+            vec![8, 6, 4, 2, 0]
+        );
+    }
+}
@@ -7,17 +7,18 @@ use reth_db_api::{
     transaction::{DbTx, DbTxMut},
 };
 use reth_etl::Collector;
-use reth_primitives::{TxHash, TxNumber};
+use reth_primitives::{BlockNumber, TxHash, TxNumber};
 use reth_provider::{
-    BlockReader, DatabaseProviderRW, PruneCheckpointReader, PruneCheckpointWriter, StatsReader,
-    TransactionsProvider, TransactionsProviderExt,
+    BlockReader, DatabaseProviderRO, DatabaseProviderRW, PruneCheckpointReader,
+    PruneCheckpointWriter, StatsReader, TransactionsProvider, TransactionsProviderExt,
 };
 use reth_prune_types::{PruneCheckpoint, PruneMode, PrunePurpose, PruneSegment};
 use reth_stages_api::{
-    EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageId,
-    UnwindInput, UnwindOutput,
+    EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageEstimate,
+    StageId, UnwindInput, UnwindOutput,
 };
 use reth_storage_errors::provider::ProviderError;
+use std::ops::RangeInclusive;
 use tracing::*;
 
 /// The transaction lookup stage.
@@ -60,6 +61,14 @@ impl<DB: Database> Stage<DB> for TransactionLookupStage {
         StageId::TransactionLookup
     }
 
+    fn estimate(
+        &self,
+        provider: &DatabaseProviderRO<DB>,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<StageEstimate, StageError> {
+        Ok(StageEstimate::transactions(provider, range)?)
+    }
+
     /// Write transaction hash -> id entries
     fn execute(
         &mut self,
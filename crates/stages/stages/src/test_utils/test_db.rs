@@ -155,7 +155,8 @@ impl TestStageDB {
                 for block_number in 0..header.number {
                     let mut prev = header.clone().unseal();
                     prev.number = block_number;
-                    writer.append_header(prev, U256::ZERO, B256::ZERO)?;
+                    let hash = prev.hash_slow();
+                    writer.append_header(prev, U256::ZERO, hash)?;
                 }
             }
 
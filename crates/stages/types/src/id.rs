@@ -30,6 +30,10 @@ pub enum StageId {
     IndexStorageHistory,
     /// Index account history stage in the process.
     IndexAccountHistory,
+    /// Index transactions by address stage in the process.
+    TransactionHistory,
+    /// Header accumulator stage in the process.
+    HeaderAccumulator,
     /// Finish stage in the process.
     Finish,
     /// Other custom stage with a provided string identifier.
@@ -38,7 +42,7 @@ pub enum StageId {
 
 impl StageId {
     /// All supported Stages
-    pub const ALL: [Self; 12] = [
+    pub const ALL: [Self; 14] = [
         Self::Headers,
         Self::Bodies,
         Self::SenderRecovery,
@@ -50,6 +54,8 @@ impl StageId {
         Self::TransactionLookup,
         Self::IndexStorageHistory,
         Self::IndexAccountHistory,
+        Self::TransactionHistory,
+        Self::HeaderAccumulator,
         Self::Finish,
     ];
 
@@ -80,6 +86,8 @@ impl StageId {
             Self::TransactionLookup => "TransactionLookup",
             Self::IndexAccountHistory => "IndexAccountHistory",
             Self::IndexStorageHistory => "IndexStorageHistory",
+            Self::TransactionHistory => "TransactionHistory",
+            Self::HeaderAccumulator => "HeaderAccumulator",
             Self::Finish => "Finish",
             Self::Other(s) => s,
         }
@@ -124,6 +132,8 @@ mod tests {
         assert_eq!(StageId::IndexAccountHistory.to_string(), "IndexAccountHistory");
         assert_eq!(StageId::IndexStorageHistory.to_string(), "IndexStorageHistory");
         assert_eq!(StageId::TransactionLookup.to_string(), "TransactionLookup");
+        assert_eq!(StageId::TransactionHistory.to_string(), "TransactionHistory");
+        assert_eq!(StageId::HeaderAccumulator.to_string(), "HeaderAccumulator");
         assert_eq!(StageId::Finish.to_string(), "Finish");
 
         assert_eq!(StageId::Other("Foo").to_string(), "Foo");
@@ -4,7 +4,10 @@ use crate::{segments, segments::Segment, StaticFileProducerEvent};
 use parking_lot::Mutex;
 use rayon::prelude::*;
 use reth_db_api::database::Database;
-use reth_primitives::{static_file::HighestStaticFiles, BlockNumber};
+use reth_primitives::{
+    static_file::{find_fixed_range, HighestStaticFiles},
+    BlockNumber,
+};
 use reth_provider::{providers::StaticFileWriter, ProviderFactory, StaticFileProviderFactory};
 use reth_prune_types::PruneModes;
 use reth_storage_errors::provider::ProviderResult;
@@ -135,6 +138,16 @@ impl<DB: Database> StaticFileProducerInner<DB> {
             segments.push((Box::new(segments::Receipts), block_range));
         }
 
+        // Lengths of the data files the segments are appended to, so that only the new data is
+        // re-read for verification.
+        let static_file_provider = self.provider_factory.static_file_provider();
+        let data_file_lens = segments
+            .iter()
+            .map(|(segment, block_range)| {
+                static_file_provider.data_file_len(segment.segment(), *block_range.start())
+            })
+            .collect::<ProviderResult<Vec<_>>>()?;
+
         segments.par_iter().try_for_each(|(segment, block_range)| -> ProviderResult<()> {
             debug!(target: "static_file", segment = %segment.segment(), ?block_range, "StaticFileProducer segment");
             let start = Instant::now();
@@ -150,11 +163,23 @@ impl<DB: Database> StaticFileProducerInner<DB> {
             Ok(())
         })?;
 
-        self.provider_factory.static_file_provider().commit()?;
+        static_file_provider.commit()?;
+
+        // Re-read the new data and verify it against the checksums, before the index is updated
+        // and the data can be pruned from the database.
+        for ((segment, block_range), data_file_len) in segments.iter().zip(data_file_lens) {
+            let mut offset = data_file_len;
+            let mut block = *block_range.start();
+            while block <= *block_range.end() {
+                static_file_provider.verify_checksum(segment.segment(), block, offset)?;
+                // Any following static file was created by this run
+                offset = 0;
+                block = find_fixed_range(block).end() + 1;
+            }
+        }
+
         for (segment, block_range) in segments {
-            self.provider_factory
-                .static_file_provider()
-                .update_index(segment.segment(), Some(*block_range.end()))?;
+            static_file_provider.update_index(segment.segment(), Some(*block_range.end()))?;
         }
 
         let elapsed = start.elapsed(); // TODO(alexey): track in metrics
@@ -229,11 +254,19 @@ mod tests {
         StaticFileProducer, StaticFileProducerInner, StaticFileTargets,
     };
     use assert_matches::assert_matches;
-    use reth_db::{test_utils::TempDatabase, DatabaseEnv};
-    use reth_db_api::{database::Database, transaction::DbTx};
-    use reth_primitives::{static_file::HighestStaticFiles, StaticFileSegment, B256, U256};
+    use reth_db::{tables, test_utils::TempDatabase, DatabaseEnv};
+    use reth_db_api::{
+        cursor::{DbCursorRO, DbCursorRW},
+        database::Database,
+        transaction::{DbTx, DbTxMut},
+    };
+    use reth_primitives::{
+        static_file::{find_fixed_range, HighestStaticFiles},
+        StaticFileSegment, B256, U256,
+    };
     use reth_provider::{
-        providers::StaticFileWriter, ProviderError, ProviderFactory, StaticFileProviderFactory,
+        providers::StaticFileWriter, BlockReader, ProviderError, ProviderFactory,
+        StaticFileProviderFactory, TransactionsProvider,
     };
     use reth_prune_types::PruneModes;
     use reth_stages::test_utils::{StorageKind, TestStageDB};
@@ -356,6 +389,103 @@ mod tests {
         );
     }
 
+    #[test]
+    fn run_with_boundary() {
+        let mut rng = generators::rng();
+        let db = TestStageDB::default();
+        let blocks = random_block_range(&mut rng, 0..=999, B256::ZERO, 2..3);
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+        let provider_factory = db.factory.clone();
+        let static_file_provider = provider_factory.static_file_provider();
+
+        let boundary = 499;
+        let static_file_producer =
+            StaticFileProducerInner::new(provider_factory.clone(), PruneModes::default());
+        let targets =
+            StaticFileTargets { headers: None, receipts: None, transactions: Some(0..=boundary) };
+        assert_matches!(static_file_producer.run(targets), Ok(_));
+        assert_eq!(
+            static_file_provider.get_highest_static_file_block(StaticFileSegment::Transactions),
+            Some(boundary)
+        );
+
+        // Remove the produced transactions from the database, like the pruner does
+        let last_static_tx = provider_factory
+            .provider()
+            .unwrap()
+            .block_body_indices(boundary)
+            .unwrap()
+            .unwrap()
+            .last_tx_num();
+        let tx = provider_factory.db_ref().tx_mut().expect("init tx");
+        let mut cursor = tx.cursor_write::<tables::Transactions>().unwrap();
+        while let Some((tx_num, _)) = cursor.first().unwrap() {
+            if tx_num > last_static_tx {
+                break
+            }
+            cursor.delete_current().unwrap();
+        }
+        drop(cursor);
+        tx.commit().expect("commit tx");
+
+        // Transactions are read from static files up to the boundary and from the database above
+        let provider = provider_factory.provider().unwrap();
+        for block in &blocks {
+            let indices = provider.block_body_indices(block.number).unwrap().unwrap();
+            for (tx_num, transaction) in indices.tx_num_range().zip(&block.body) {
+                assert_eq!(provider.transaction_by_id(tx_num).unwrap().as_ref(), Some(transaction));
+            }
+        }
+        drop(provider);
+
+        let check_consistency = || {
+            static_file_provider
+                .check_consistency(&provider_factory.database_provider_ro().unwrap(), false)
+        };
+        assert_eq!(check_consistency(), Ok(None));
+
+        // A corrupted static file is detected on startup
+        let data_path = static_file_provider
+            .directory()
+            .join(StaticFileSegment::Transactions.filename(&find_fixed_range(boundary)));
+        let mut data = std::fs::read(&data_path).unwrap();
+        data[0] ^= 1;
+        std::fs::write(&data_path, data).unwrap();
+        assert_eq!(
+            check_consistency(),
+            Err(ProviderError::CorruptedStaticFile(StaticFileSegment::Transactions, data_path))
+        );
+    }
+
+    #[test]
+    fn corrupted_receipts() {
+        let (provider_factory, _temp_static_files_dir) = setup();
+        let static_file_provider = provider_factory.static_file_provider();
+
+        let static_file_producer =
+            StaticFileProducerInner::new(provider_factory.clone(), PruneModes::default());
+        let targets =
+            StaticFileTargets { headers: None, receipts: Some(0..=3), transactions: None };
+        assert_matches!(static_file_producer.run(targets), Ok(_));
+        assert_eq!(
+            static_file_provider.get_highest_static_file_block(StaticFileSegment::Receipts),
+            Some(3)
+        );
+
+        // The last receipt is within the tail that is verified on startup
+        let data_path = static_file_provider
+            .directory()
+            .join(StaticFileSegment::Receipts.filename(&find_fixed_range(3)));
+        let mut data = std::fs::read(&data_path).unwrap();
+        *data.last_mut().unwrap() ^= 1;
+        std::fs::write(&data_path, data).unwrap();
+        assert_eq!(
+            static_file_provider
+                .check_consistency(&provider_factory.database_provider_ro().unwrap(), false),
+            Err(ProviderError::CorruptedStaticFile(StaticFileSegment::Receipts, data_path))
+        );
+    }
+
     /// Tests that a cloneable [`StaticFileProducer`] type is not susceptible to any race condition.
     #[test]
     fn only_one() {
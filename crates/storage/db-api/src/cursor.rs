@@ -42,6 +42,9 @@ pub trait DbCursorRO<T: Table> {
         Self: Sized;
 
     /// Get an iterator that walks over a range of keys in the table.
+    ///
+    /// The walker starts at the first entry in the range and stops at its end bound, instead of
+    /// the end of the table.
     fn walk_range(
         &mut self,
         range: impl RangeBounds<T::Key>,
@@ -52,13 +55,24 @@ pub trait DbCursorRO<T: Table> {
     /// Get an iterator that walks through the table in reverse order.
     ///
     /// If `start_key` is `None`, then the walker will start from the last entry of the table,
-    /// otherwise it starts at the entry greater than or equal to the provided key.
+    /// otherwise it starts at the entry less than or equal to the provided key.
     fn walk_back(
         &mut self,
         start_key: Option<T::Key>,
     ) -> Result<ReverseWalker<'_, T, Self>, DatabaseError>
     where
         Self: Sized;
+
+    /// Hints that the cursor is going to walk a large range of the table in order, so that the
+    /// database can read ahead of it.
+    ///
+    /// The hint is advisory. The default implementation ignores it.
+    fn with_sequential_access_hint(self) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
 }
 
 /// A read-only cursor over the dup table `T`.
@@ -72,6 +86,12 @@ pub trait DbDupCursorRO<T: DupSort> {
     /// Positions the cursor at the next duplicate value of the current key.
     fn next_dup_val(&mut self) -> ValueOnlyResult<T>;
 
+    /// Positions the cursor at the previous duplicate value of the current key.
+    fn prev_dup_val(&mut self) -> ValueOnlyResult<T>;
+
+    /// Positions the cursor at the last duplicate value of the current key.
+    fn last_dup_val(&mut self) -> ValueOnlyResult<T>;
+
     /// Positions the cursor at the entry greater than or equal to the provided key/subkey pair.
     ///
     /// # Note
@@ -91,6 +111,8 @@ pub trait DbDupCursorRO<T: DupSort> {
     /// | `Some` | `None`   | [`DbCursorRO::seek()`]               |
     /// | `None` | `Some`   | [`DbDupCursorRO::seek_by_key_subkey()`] |
     /// | `Some` | `Some`   | [`DbDupCursorRO::seek_by_key_subkey()`] |
+    ///
+    /// The walker yields nothing if there's no entry at the starting position.
     fn walk_dup(
         &mut self,
         key: Option<T::Key>,
@@ -98,6 +120,12 @@ pub trait DbDupCursorRO<T: DupSort> {
     ) -> Result<DupWalker<'_, T, Self>, DatabaseError>
     where
         Self: Sized;
+
+    /// Get an iterator that walks through the duplicates of `key`, starting at its first
+    /// duplicate. The walker yields nothing if `key` doesn't exist.
+    fn walk_dup_key(&mut self, key: T::Key) -> Result<DupWalker<'_, T, Self>, DatabaseError>
+    where
+        Self: Sized;
 }
 
 /// Read write cursor over table.
@@ -180,6 +208,9 @@ impl<'cursor, T: Table, CURSOR: DbCursorRO<T>> Walker<'cursor, T, CURSOR> {
 
 impl<'cursor, T: Table, CURSOR: DbCursorRW<T> + DbCursorRO<T>> Walker<'cursor, T, CURSOR> {
     /// Delete current item that walker points to.
+    ///
+    /// The walk continues with the entry after the deleted one, so entries can be deleted while
+    /// walking.
     pub fn delete_current(&mut self) -> Result<(), DatabaseError> {
         self.start.take();
         self.cursor.delete_current()
@@ -223,6 +254,8 @@ impl<'cursor, T: Table, CURSOR: DbCursorRO<T>> ReverseWalker<'cursor, T, CURSOR>
 
 impl<'cursor, T: Table, CURSOR: DbCursorRW<T> + DbCursorRO<T>> ReverseWalker<'cursor, T, CURSOR> {
     /// Delete current item that walker points to.
+    ///
+    /// The walk continues with the entry before the deleted one.
     pub fn delete_current(&mut self) -> Result<(), DatabaseError> {
         self.start.take();
         self.cursor.delete_current()
@@ -322,6 +355,8 @@ impl<'cursor, T: Table, CURSOR: DbCursorRO<T>> RangeWalker<'cursor, T, CURSOR> {
 
 impl<'cursor, T: Table, CURSOR: DbCursorRW<T> + DbCursorRO<T>> RangeWalker<'cursor, T, CURSOR> {
     /// Delete current item that walker points to.
+    ///
+    /// The walk continues with the entry after the deleted one, up to the end of the range.
     pub fn delete_current(&mut self) -> Result<(), DatabaseError> {
         self.start.take();
         self.cursor.delete_current()
@@ -338,6 +373,8 @@ pub struct DupWalker<'cursor, T: DupSort, CURSOR: DbDupCursorRO<T>> {
     pub cursor: &'cursor mut CURSOR,
     /// Value where to start the walk.
     pub start: IterPairResult<T>,
+    /// flag whether is ended
+    is_done: bool,
 }
 
 impl<T, CURSOR> fmt::Debug for DupWalker<'_, T, CURSOR>
@@ -349,12 +386,26 @@ where
         f.debug_struct("DupWalker")
             .field("cursor", &self.cursor)
             .field("start", &self.start)
+            .field("is_done", &self.is_done)
             .finish()
     }
 }
 
+impl<'cursor, T: DupSort, CURSOR: DbDupCursorRO<T>> DupWalker<'cursor, T, CURSOR> {
+    /// construct `DupWalker`
+    ///
+    /// The walk is over if there's no `start`, regardless of the position of the cursor.
+    pub fn new(cursor: &'cursor mut CURSOR, start: IterPairResult<T>) -> Self {
+        let is_done = start.is_none();
+        Self { cursor, start, is_done }
+    }
+}
+
 impl<'cursor, T: DupSort, CURSOR: DbCursorRW<T> + DbDupCursorRO<T>> DupWalker<'cursor, T, CURSOR> {
     /// Delete current item that walker points to.
+    ///
+    /// Only the current duplicate is deleted, the walk continues with the next duplicate of the
+    /// key, if any.
     pub fn delete_current(&mut self) -> Result<(), DatabaseError> {
         self.start.take();
         self.cursor.delete_current()
@@ -364,6 +415,10 @@ impl<'cursor, T: DupSort, CURSOR: DbCursorRW<T> + DbDupCursorRO<T>> DupWalker<'c
 impl<'cursor, T: DupSort, CURSOR: DbDupCursorRO<T>> Iterator for DupWalker<'cursor, T, CURSOR> {
     type Item = Result<TableRow<T>, DatabaseError>;
     fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done {
+            return None
+        }
+
         let start = self.start.take();
         if start.is_some() {
             return start
@@ -1,4 +1,5 @@
 use metrics::{counter, gauge, histogram, Label};
+use reth_metrics::MetricsRegistrar;
 use std::sync::Arc;
 
 /// Represents a type that can report metrics, used mainly with the database. The `report_metrics`
@@ -33,12 +34,21 @@ pub trait DatabaseMetrics {
     fn histogram_metrics(&self) -> Vec<(&'static str, f64, Vec<Label>)> {
         vec![]
     }
+
+    /// Registers the metrics the database records while it's used with the given registrar.
+    fn register_metrics(&self, registrar: &mut MetricsRegistrar) {
+        let _ = registrar;
+    }
 }
 
 impl<DB: DatabaseMetrics> DatabaseMetrics for Arc<DB> {
     fn report_metrics(&self) {
         <DB as DatabaseMetrics>::report_metrics(self)
     }
+
+    fn register_metrics(&self, registrar: &mut MetricsRegistrar) {
+        <DB as DatabaseMetrics>::register_metrics(self, registrar)
+    }
 }
 
 /// The type used to store metadata about the database.
@@ -192,6 +192,14 @@ impl<T: DupSort> DbDupCursorRO<T> for CursorMock {
         Ok(None)
     }
 
+    fn prev_dup_val(&mut self) -> ValueOnlyResult<T> {
+        Ok(None)
+    }
+
+    fn last_dup_val(&mut self) -> ValueOnlyResult<T> {
+        Ok(None)
+    }
+
     fn seek_by_key_subkey(
         &mut self,
         _key: <T as Table>::Key,
@@ -205,7 +213,11 @@ impl<T: DupSort> DbDupCursorRO<T> for CursorMock {
         _key: Option<<T>::Key>,
         _subkey: Option<<T as DupSort>::SubKey>,
     ) -> Result<DupWalker<'_, T, Self>, DatabaseError> {
-        Ok(DupWalker { cursor: self, start: None })
+        Ok(DupWalker::new(self, None))
+    }
+
+    fn walk_dup_key(&mut self, _key: <T>::Key) -> Result<DupWalker<'_, T, Self>, DatabaseError> {
+        Ok(DupWalker::new(self, None))
     }
 }
 
@@ -1,7 +1,12 @@
 //! Block related models and types.
 
+use crate::{
+    table::{Compress, Decompress},
+    DatabaseError,
+};
 use reth_codecs::{main_codec, Compact};
 use reth_primitives::{Header, TxNumber, Withdrawals, B256};
+use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
 /// Total number of transactions.
@@ -83,6 +88,35 @@ pub struct StoredBlockWithdrawals {
     pub withdrawals: Withdrawals,
 }
 
+/// A transaction number stored as the value of a dupsort table.
+///
+/// Unlike [`TxNumber`] values, which are compressed with SCALE, it's stored as big-endian bytes,
+/// the same way as a [`TxNumber`] key. This keeps the duplicates of a key sorted by transaction
+/// number and allows seeking them by [`TxNumber`] subkey.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StoredTxNumber(pub TxNumber);
+
+impl From<TxNumber> for StoredTxNumber {
+    fn from(value: TxNumber) -> Self {
+        Self(value)
+    }
+}
+
+impl Compress for StoredTxNumber {
+    type Compressed = Vec<u8>;
+
+    fn compress_to_buf<B: bytes::BufMut + AsMut<[u8]>>(self, buf: &mut B) {
+        buf.put_slice(&self.0.to_be_bytes())
+    }
+}
+
+impl Decompress for StoredTxNumber {
+    fn decompress<B: AsRef<[u8]>>(value: B) -> Result<Self, DatabaseError> {
+        let bytes = value.as_ref().get(..8).ok_or(DatabaseError::Decode)?;
+        Ok(Self(TxNumber::from_be_bytes(bytes.try_into().map_err(|_| DatabaseError::Decode)?)))
+    }
+}
+
 /// Hash of the block header.
 pub type HeaderHash = B256;
 
@@ -102,6 +136,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn stored_tx_number() {
+        let tx_number = StoredTxNumber(0x0102);
+        let compressed = tx_number.compress();
+        assert_eq!(compressed, vec![0, 0, 0, 0, 0, 0, 1, 2]);
+        assert_eq!(StoredTxNumber::decompress(compressed).unwrap(), tx_number);
+    }
+
     #[test]
     fn block_indices() {
         let first_tx_num = 10;
@@ -152,7 +152,7 @@ pub trait TableImporter: DbTxMut {
         let mut cursor = source_tx.cursor_dup_read::<T>()?;
 
         while let Some((k, _)) = cursor.next_no_dup()? {
-            for kv in cursor.walk_dup(Some(k), None)? {
+            for kv in cursor.walk_dup_key(k)? {
                 let (k, v) = kv?;
                 destination_cursor.append_dup(k, v)?;
             }
@@ -9,3 +9,4 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 pub mod init;
+pub mod repair;
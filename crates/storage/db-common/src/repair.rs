@@ -0,0 +1,309 @@
+//! Reth database repair utility functions.
+
+use reth_db::tables;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbCursorRW},
+    database::Database,
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use reth_primitives::{BlockNumber, U256};
+use reth_provider::{HeaderProvider, ProviderError, ProviderFactory};
+use std::ops::RangeInclusive;
+use tracing::info;
+
+/// Default number of blocks that are repaired before committing.
+pub const DEFAULT_REPAIR_BATCH_SIZE: u64 = 100_000;
+
+/// Header total difficulty repair error type.
+#[derive(Debug, thiserror::Error, PartialEq, Eq, Clone)]
+pub enum RepairHeaderTdError {
+    /// The total difficulty of the block before the first repaired block is unknown, so the total
+    /// difficulties can't be recomputed.
+    #[error("total difficulty of block #{0} to start the repair from not found")]
+    MissingTrustedTd(BlockNumber),
+    /// A block is missing from the canonical headers, so the total difficulties of the blocks
+    /// above it can't be recomputed.
+    #[error("canonical header of block #{0} not found")]
+    MissingCanonicalHeader(BlockNumber),
+    /// Provider error.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+}
+
+impl From<DatabaseError> for RepairHeaderTdError {
+    fn from(error: DatabaseError) -> Self {
+        Self::Provider(ProviderError::Database(error))
+    }
+}
+
+/// A stored total difficulty that doesn't match the recomputed one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderTdMismatch {
+    /// The block number.
+    pub block_number: BlockNumber,
+    /// The stored total difficulty.
+    pub stored: U256,
+    /// The recomputed total difficulty.
+    pub expected: U256,
+}
+
+/// The outcome of [`repair_header_td`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeaderTdRepair {
+    /// The canonical blocks that were checked, `None` if there are no canonical headers to check.
+    pub checked: Option<RangeInclusive<BlockNumber>>,
+    /// The ranges of blocks whose missing total difficulty was inserted.
+    pub inserted: Vec<RangeInclusive<BlockNumber>>,
+    /// The blocks whose stored total difficulty doesn't match the recomputed one.
+    pub mismatches: Vec<HeaderTdMismatch>,
+    /// Whether the mismatching total difficulties were overwritten.
+    pub fixed: bool,
+}
+
+impl HeaderTdRepair {
+    /// Returns the number of blocks whose missing total difficulty was inserted.
+    pub fn inserted_count(&self) -> u64 {
+        self.inserted.iter().map(|range| range.end() - range.start() + 1).sum()
+    }
+
+    fn on_inserted(&mut self, block_number: BlockNumber) {
+        match self.inserted.last_mut() {
+            Some(range) if *range.end() + 1 == block_number => {
+                *range = *range.start()..=block_number
+            }
+            _ => self.inserted.push(block_number..=block_number),
+        }
+    }
+}
+
+/// Recomputes the total difficulties of the canonical headers in the database and inserts the
+/// missing [`tables::HeaderTerminalDifficulties`] entries.
+///
+/// The canonical headers are walked in ascending order from the `from` block, or from the first
+/// block of [`tables::CanonicalHeaders`] if `None`. The total difficulty is accumulated from the
+/// genesis difficulty, or from the stored total difficulty of the block before the first walked
+/// block, which is trusted.
+///
+/// Existing entries that don't match the recomputed total difficulty are reported, and only
+/// overwritten if `fix` is set. The recomputation continues with the recomputed total difficulty
+/// either way.
+///
+/// The repair is committed every `batch_size` blocks.
+pub fn repair_header_td<DB: Database>(
+    factory: &ProviderFactory<DB>,
+    from: Option<BlockNumber>,
+    fix: bool,
+    batch_size: u64,
+) -> Result<HeaderTdRepair, RepairHeaderTdError> {
+    let mut repair = HeaderTdRepair { fixed: fix, ..Default::default() };
+
+    let provider = factory.provider()?;
+    let from = match from {
+        Some(from) => from,
+        None => match provider.tx_ref().cursor_read::<tables::CanonicalHeaders>()?.first()? {
+            Some((first, _)) => first,
+            None => return Ok(repair),
+        },
+    };
+    let mut td = match from.checked_sub(1) {
+        Some(trusted) => provider
+            .header_td_by_number(trusted)?
+            .ok_or(RepairHeaderTdError::MissingTrustedTd(trusted))?,
+        None => U256::ZERO,
+    };
+    drop(provider);
+
+    let batch_size = batch_size.max(1);
+    let mut next_block = from;
+    loop {
+        let provider = factory.provider_rw()?;
+        let mut canonical_cursor = provider.tx_ref().cursor_read::<tables::CanonicalHeaders>()?;
+        let mut td_cursor =
+            provider.tx_ref().cursor_write::<tables::HeaderTerminalDifficulties>()?;
+
+        let batch_start = next_block;
+        for entry in canonical_cursor.walk_range(next_block..)?.take(batch_size as usize) {
+            let (block_number, _) = entry?;
+            if block_number != next_block {
+                return Err(RepairHeaderTdError::MissingCanonicalHeader(next_block))
+            }
+            let header = provider
+                .header_by_number(block_number)?
+                .ok_or(ProviderError::HeaderNotFound(block_number.into()))?;
+            td += header.difficulty;
+
+            match td_cursor.seek_exact(block_number)? {
+                None => {
+                    td_cursor.upsert(block_number, td.into())?;
+                    repair.on_inserted(block_number);
+                }
+                Some((_, stored)) if stored.0 != td => {
+                    repair.mismatches.push(HeaderTdMismatch {
+                        block_number,
+                        stored: stored.0,
+                        expected: td,
+                    });
+                    if fix {
+                        td_cursor.upsert(block_number, td.into())?;
+                    }
+                }
+                Some(_) => {}
+            }
+            next_block += 1;
+        }
+
+        // Nothing was walked, the previous batch reached the last canonical header
+        if next_block == batch_start {
+            break
+        }
+
+        provider.commit()?;
+        repair.checked = Some(from..=next_block - 1);
+        info!(
+            target: "reth::cli",
+            checked = ?batch_start..next_block,
+            inserted = repair.inserted_count(),
+            mismatches = repair.mismatches.len(),
+            "Repaired header total difficulties"
+        );
+
+        if next_block - batch_start < batch_size {
+            break
+        }
+    }
+
+    if next_block == from {
+        return Err(RepairHeaderTdError::MissingCanonicalHeader(from))
+    }
+
+    Ok(repair)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use reth_primitives::B256;
+    use reth_provider::test_utils::create_test_provider_factory;
+    use reth_testing_utils::generators::{self, random_header_range};
+
+    /// Inserts a chain of canonical headers, with their total difficulties, returning the total
+    /// difficulties.
+    fn insert_chain<DB: Database>(factory: &ProviderFactory<DB>, blocks: u64) -> Vec<U256> {
+        let mut rng = generators::rng();
+        let provider = factory.provider_rw().unwrap();
+        let mut td = U256::ZERO;
+        let mut tds = Vec::new();
+        for header in random_header_range(&mut rng, 0..blocks, B256::ZERO) {
+            td += header.difficulty;
+            tds.push(td);
+            let tx = provider.tx_ref();
+            tx.put::<tables::CanonicalHeaders>(header.number, header.hash()).unwrap();
+            tx.put::<tables::HeaderTerminalDifficulties>(header.number, td.into()).unwrap();
+            tx.put::<tables::Headers>(header.number, header.unseal()).unwrap();
+        }
+        provider.commit().unwrap();
+        tds
+    }
+
+    fn stored_tds<DB: Database>(factory: &ProviderFactory<DB>) -> Vec<(BlockNumber, U256)> {
+        let provider = factory.provider().unwrap();
+        let mut cursor =
+            provider.tx_ref().cursor_read::<tables::HeaderTerminalDifficulties>().unwrap();
+        cursor.walk(None).unwrap().map(|entry| entry.map(|(n, td)| (n, td.0)).unwrap()).collect()
+    }
+
+    #[test]
+    fn repair_missing_td() {
+        let factory = create_test_provider_factory();
+        let tds = insert_chain(&factory, 100);
+
+        // delete a random subset of the total difficulties
+        let mut rng = generators::rng();
+        let provider = factory.provider_rw().unwrap();
+        let mut deleted = Vec::new();
+        for block_number in 0..100 {
+            if rng.gen_bool(0.3) {
+                provider
+                    .tx_ref()
+                    .delete::<tables::HeaderTerminalDifficulties>(block_number, None)
+                    .unwrap();
+                deleted.push(block_number);
+            }
+        }
+        provider.commit().unwrap();
+        assert!(!deleted.is_empty());
+
+        let repair = repair_header_td(&factory, None, false, 7).unwrap();
+        assert_eq!(repair.checked, Some(0..=99));
+        assert_eq!(repair.inserted_count(), deleted.len() as u64);
+        assert!(repair.inserted.iter().flat_map(Clone::clone).eq(deleted.iter().copied()));
+        assert_eq!(repair.mismatches, vec![]);
+        assert_eq!(
+            stored_tds(&factory),
+            tds.iter().copied().enumerate().map(|(n, td)| (n as u64, td)).collect::<Vec<_>>()
+        );
+
+        // repairing from a trusted total difficulty
+        let provider = factory.provider_rw().unwrap();
+        for block_number in 50..60 {
+            provider
+                .tx_ref()
+                .delete::<tables::HeaderTerminalDifficulties>(block_number, None)
+                .unwrap();
+        }
+        provider.commit().unwrap();
+        let repair = repair_header_td(&factory, Some(50), false, 100).unwrap();
+        assert_eq!(repair.checked, Some(50..=99));
+        assert_eq!(repair.inserted, vec![50..=59]);
+        assert_eq!(stored_tds(&factory).len(), 100);
+        assert_eq!(
+            repair_header_td(&factory, Some(101), false, 100),
+            Err(RepairHeaderTdError::MissingTrustedTd(100))
+        );
+        assert_eq!(
+            repair_header_td(&factory, Some(100), false, 100),
+            Err(RepairHeaderTdError::MissingCanonicalHeader(100))
+        );
+    }
+
+    #[test]
+    fn report_mismatching_td() {
+        let factory = create_test_provider_factory();
+        let tds = insert_chain(&factory, 20);
+
+        let corrupted = U256::from(1);
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        tx.put::<tables::HeaderTerminalDifficulties>(10, corrupted.into()).unwrap();
+        tx.delete::<tables::HeaderTerminalDifficulties>(11, None).unwrap();
+        provider.commit().unwrap();
+
+        // the mismatch is reported but untouched, the following blocks are recomputed
+        let repair = repair_header_td(&factory, None, false, 100).unwrap();
+        let mismatch = HeaderTdMismatch { block_number: 10, stored: corrupted, expected: tds[10] };
+        assert_eq!(repair.mismatches, vec![mismatch]);
+        assert!(!repair.fixed);
+        assert_eq!(repair.inserted, vec![11..=11]);
+        let stored = stored_tds(&factory);
+        assert_eq!(stored[10], (10, corrupted));
+        assert_eq!(stored[11], (11, tds[11]));
+
+        // the mismatch is overwritten with the fix
+        let repair = repair_header_td(&factory, None, true, 100).unwrap();
+        assert_eq!(repair.mismatches, vec![mismatch]);
+        assert!(repair.fixed);
+        assert_eq!(stored_tds(&factory)[10], (10, tds[10]));
+        assert_eq!(repair_header_td(&factory, None, false, 100).unwrap().mismatches, vec![]);
+
+        // a gap in the canonical headers can't be repaired
+        let provider = factory.provider_rw().unwrap();
+        provider.tx_ref().delete::<tables::CanonicalHeaders>(5, None).unwrap();
+        provider.commit().unwrap();
+        assert_eq!(
+            repair_header_td(&factory, None, false, 100),
+            Err(RepairHeaderTdError::MissingCanonicalHeader(5))
+        );
+    }
+}
@@ -116,7 +116,8 @@ where
     });
 }
 
-/// Measures `SeqWrite`, `RandomWrite`, `SeqRead` and `RandomRead` using `cursor` and `tx.put`.
+/// Measures `SeqWrite`, `RandomWrite`, `SeqRead`, `SeqReadHinted` and `RandomRead` using `cursor`
+/// and `tx.put`.
 fn measure_table_db<T>(group: &mut BenchmarkGroup<'_, WallTime>)
 where
     T: Table,
@@ -194,6 +195,25 @@ where
         })
     });
 
+    group.bench_function(format!("{}.SeqReadHinted", T::NAME), |b| {
+        let db = set_up_db::<T>(bench_db_path, input);
+
+        b.iter(|| {
+            // Create TX
+            let tx = db.tx().expect("tx");
+
+            {
+                let mut cursor =
+                    tx.cursor_read::<T>().expect("cursor").with_sequential_access_hint();
+                let walker = cursor.walk(Some(input.first().unwrap().0.clone())).unwrap();
+                for element in walker {
+                    element.unwrap();
+                }
+            };
+            black_box(());
+        })
+    });
+
     group.bench_function(format!("{}.RandomRead", T::NAME), |b| {
         let db = set_up_db::<T>(bench_db_path, input);
 
@@ -0,0 +1,206 @@
+//! Per-table operation counters of transactions and their cursors.
+//!
+//! Counting is only compiled in with the `operation-counters` feature and enabled with
+//! `DatabaseEnv::with_operation_counters`. Without the feature, counting an operation is a no-op.
+
+use strum::{EnumCount, EnumIter};
+#[cfg(feature = "operation-counters")]
+use crate::{metrics::Labels, Tables};
+#[cfg(feature = "operation-counters")]
+use reth_db_api::table::Table;
+#[cfg(feature = "operation-counters")]
+use reth_metrics::{metrics::Counter, Metrics};
+#[cfg(feature = "operation-counters")]
+use reth_tracing::tracing::debug;
+#[cfg(feature = "operation-counters")]
+use std::{
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+#[cfg(feature = "operation-counters")]
+use strum::IntoEnumIterator;
+
+/// Operations counted per table and transaction.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, EnumCount, EnumIter)]
+pub(crate) enum CountedOperation {
+    /// Cursor positioning on a key, e.g. `seek`, `seek_exact`, `first` or `last`.
+    Seek,
+    /// Cursor move to the next entry.
+    Next,
+    /// Cursor move to the previous entry.
+    Prev,
+    /// Transaction get.
+    Get,
+    /// Transaction or cursor write.
+    Put,
+    /// Transaction or cursor delete.
+    Delete,
+}
+
+#[cfg(feature = "operation-counters")]
+impl CountedOperation {
+    /// Returns the operation as a string.
+    pub(crate) const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Seek => "seek",
+            Self::Next => "next",
+            Self::Prev => "prev",
+            Self::Get => "get",
+            Self::Put => "put",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+/// Exports the operation counts of finished transactions and logs the transactions that did more
+/// operations than the threshold.
+#[cfg(feature = "operation-counters")]
+#[derive(Debug)]
+pub(crate) struct OperationCounters {
+    /// Metric handles by table index and operation.
+    metrics: Vec<TableOperationMetrics>,
+    /// Number of operations from which a transaction logs a summary of its operations.
+    log_threshold: u64,
+}
+
+#[cfg(feature = "operation-counters")]
+impl OperationCounters {
+    pub(crate) fn new(log_threshold: u64) -> Self {
+        let metrics = (0..=Tables::COUNT)
+            .flat_map(|table_index| {
+                CountedOperation::iter().map(move |operation| {
+                    TableOperationMetrics::new_with_labels(&[
+                        (Labels::Table.as_str(), table_name(table_index)),
+                        (Labels::Operation.as_str(), operation.as_str()),
+                    ])
+                })
+            })
+            .collect();
+        Self { metrics, log_threshold }
+    }
+}
+
+/// Operation counts of a single transaction, shared with its cursors.
+///
+/// Counting an operation is a single relaxed atomic increment. The counts are exported and
+/// summarized once the transaction and all of its cursors are dropped.
+#[cfg(feature = "operation-counters")]
+#[derive(Debug)]
+pub(crate) struct TransactionOperationCounts {
+    /// ID of the transaction.
+    txn_id: u64,
+    /// Counts by table index and operation. The last index is used for tables that are not in
+    /// [`Tables::ALL`].
+    counts: Box<[[AtomicU64; CountedOperation::COUNT]]>,
+    counters: Arc<OperationCounters>,
+}
+
+#[cfg(feature = "operation-counters")]
+impl TransactionOperationCounts {
+    pub(crate) fn new(txn_id: u64, counters: Arc<OperationCounters>) -> Self {
+        Self { txn_id, counts: (0..=Tables::COUNT).map(|_| Default::default()).collect(), counters }
+    }
+
+    /// Counts an operation on the table.
+    #[inline]
+    pub(crate) fn increment<T: Table>(&self, operation: CountedOperation) {
+        self.counts[TableIndex::<T>::INDEX][operation as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of operations on the table.
+    #[cfg(test)]
+    pub(crate) fn get<T: Table>(&self, operation: CountedOperation) -> u64 {
+        self.counts[TableIndex::<T>::INDEX][operation as usize].load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(feature = "operation-counters")]
+impl Drop for TransactionOperationCounts {
+    fn drop(&mut self) {
+        let mut total = 0;
+        let mut tables = Vec::new();
+        for (table_index, counts) in self.counts.iter().enumerate() {
+            let mut table_total = 0;
+            let mut operations = Vec::new();
+            for (operation, count) in CountedOperation::iter().zip(counts) {
+                let count = count.load(Ordering::Relaxed);
+                if count > 0 {
+                    let metrics_index = table_index * CountedOperation::COUNT + operation as usize;
+                    self.counters.metrics[metrics_index].operations_total.increment(count);
+                    table_total += count;
+                    operations.push(format!("{}={count}", operation.as_str()));
+                }
+            }
+            if table_total > 0 {
+                total += table_total;
+                tables.push((table_total, table_index, operations));
+            }
+        }
+
+        if total >= self.counters.log_threshold {
+            tables.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+            let summary = tables
+                .into_iter()
+                .map(|(_, table_index, operations)| {
+                    format!("{}({})", table_name(table_index), operations.join(", "))
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            debug!(
+                target: "storage::db::mdbx",
+                txn_id = %self.txn_id,
+                total,
+                %summary,
+                "Transaction exceeded the operation count threshold"
+            );
+        }
+    }
+}
+
+/// Resolves the index of a table in the operation counts at compile time.
+#[cfg(feature = "operation-counters")]
+struct TableIndex<T>(PhantomData<T>);
+
+#[cfg(feature = "operation-counters")]
+impl<T: Table> TableIndex<T> {
+    const INDEX: usize = table_index(T::NAME);
+}
+
+/// Returns the index of the table in [`Tables::ALL`], or [`Tables::COUNT`] if it's not a table of
+/// the database.
+#[cfg(feature = "operation-counters")]
+const fn table_index(name: &str) -> usize {
+    let name = name.as_bytes();
+    let mut index = 0;
+    while index < Tables::COUNT {
+        let table = Tables::ALL[index].name().as_bytes();
+        if table.len() == name.len() {
+            let mut i = 0;
+            while i < name.len() && table[i] == name[i] {
+                i += 1;
+            }
+            if i == name.len() {
+                return index
+            }
+        }
+        index += 1;
+    }
+    Tables::COUNT
+}
+
+/// Returns the name of the table with the given index in the operation counts.
+#[cfg(feature = "operation-counters")]
+fn table_name(table_index: usize) -> &'static str {
+    Tables::ALL.get(table_index).map_or("other", |table| table.name())
+}
+
+#[cfg(feature = "operation-counters")]
+#[derive(Metrics, Clone)]
+#[metrics(scope = "database.table")]
+struct TableOperationMetrics {
+    /// Total number of operations on a table, recorded when the transaction is dropped
+    operations_total: Counter,
+}
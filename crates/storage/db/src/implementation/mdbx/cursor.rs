@@ -1,5 +1,8 @@
 //! Cursor wrapper for libmdbx-sys.
 
+use super::counters::CountedOperation;
+#[cfg(feature = "operation-counters")]
+use super::counters::TransactionOperationCounts;
 use crate::{
     metrics::{DatabaseEnvMetrics, Operation},
     tables::utils::*,
@@ -13,7 +16,7 @@ use reth_db_api::{
     },
     table::{Compress, Decode, Decompress, DupSort, Encode, Table},
 };
-use reth_libmdbx::{Error as MDBXError, TransactionKind, WriteFlags, RO, RW};
+use reth_libmdbx::{AccessPattern, Error as MDBXError, TransactionKind, WriteFlags, RO, RW};
 use reth_storage_errors::db::{DatabaseErrorInfo, DatabaseWriteError, DatabaseWriteOperation};
 use std::{borrow::Cow, collections::Bound, marker::PhantomData, ops::RangeBounds, sync::Arc};
 
@@ -31,6 +34,9 @@ pub struct Cursor<K: TransactionKind, T: Table> {
     buf: Vec<u8>,
     /// Reference to metric handles in the DB environment. If `None`, metrics are not recorded.
     metrics: Option<Arc<DatabaseEnvMetrics>>,
+    /// Operation counts of the transaction. If `None`, operations are not counted.
+    #[cfg(feature = "operation-counters")]
+    operation_counts: Option<Arc<TransactionOperationCounts>>,
     /// Phantom data to enforce encoding/decoding.
     _dbi: PhantomData<T>,
 }
@@ -40,9 +46,39 @@ impl<K: TransactionKind, T: Table> Cursor<K, T> {
         inner: reth_libmdbx::Cursor<K>,
         metrics: Option<Arc<DatabaseEnvMetrics>>,
     ) -> Self {
-        Self { inner, buf: Vec::new(), metrics, _dbi: PhantomData }
+        Self {
+            inner,
+            buf: Vec::new(),
+            metrics,
+            #[cfg(feature = "operation-counters")]
+            operation_counts: None,
+            _dbi: PhantomData,
+        }
+    }
+
+    /// Counts the operations of the cursor in the operation counts of its transaction.
+    #[cfg(feature = "operation-counters")]
+    pub(crate) fn with_operation_counts(
+        mut self,
+        operation_counts: Option<Arc<TransactionOperationCounts>>,
+    ) -> Self {
+        self.operation_counts = operation_counts;
+        self
+    }
+
+    /// Counts the operation, if operation counters are enabled.
+    #[cfg(feature = "operation-counters")]
+    #[inline]
+    fn count(&self, operation: CountedOperation) {
+        if let Some(counts) = &self.operation_counts {
+            counts.increment::<T>(operation)
+        }
     }
 
+    #[cfg(not(feature = "operation-counters"))]
+    #[inline(always)]
+    const fn count(&self, _operation: CountedOperation) {}
+
     /// If `self.metrics` is `Some(...)`, record a metric with the provided operation and value
     /// size.
     ///
@@ -90,26 +126,32 @@ macro_rules! compress_to_buf_or_ref {
 
 impl<K: TransactionKind, T: Table> DbCursorRO<T> for Cursor<K, T> {
     fn first(&mut self) -> PairResult<T> {
+        self.count(CountedOperation::Seek);
         decode::<T>(self.inner.first())
     }
 
     fn seek_exact(&mut self, key: <T as Table>::Key) -> PairResult<T> {
+        self.count(CountedOperation::Seek);
         decode::<T>(self.inner.set_key(key.encode().as_ref()))
     }
 
     fn seek(&mut self, key: <T as Table>::Key) -> PairResult<T> {
+        self.count(CountedOperation::Seek);
         decode::<T>(self.inner.set_range(key.encode().as_ref()))
     }
 
     fn next(&mut self) -> PairResult<T> {
+        self.count(CountedOperation::Next);
         decode::<T>(self.inner.next())
     }
 
     fn prev(&mut self) -> PairResult<T> {
+        self.count(CountedOperation::Prev);
         decode::<T>(self.inner.prev())
     }
 
     fn last(&mut self) -> PairResult<T> {
+        self.count(CountedOperation::Seek);
         decode::<T>(self.inner.last())
     }
 
@@ -119,6 +161,7 @@ impl<K: TransactionKind, T: Table> DbCursorRO<T> for Cursor<K, T> {
 
     fn walk(&mut self, start_key: Option<T::Key>) -> Result<Walker<'_, T, Self>, DatabaseError> {
         let start = if let Some(start_key) = start_key {
+            self.count(CountedOperation::Seek);
             decode::<T>(self.inner.set_range(start_key.encode().as_ref())).transpose()
         } else {
             self.first().transpose()
@@ -131,10 +174,18 @@ impl<K: TransactionKind, T: Table> DbCursorRO<T> for Cursor<K, T> {
         &mut self,
         range: impl RangeBounds<T::Key>,
     ) -> Result<RangeWalker<'_, T, Self>, DatabaseError> {
+        self.count(CountedOperation::Seek);
         let start = match range.start_bound().cloned() {
             Bound::Included(key) => self.inner.set_range(key.encode().as_ref()),
-            Bound::Excluded(_key) => {
-                unreachable!("Rust doesn't allow for Bound::Excluded in starting bounds");
+            Bound::Excluded(key) => {
+                let key = key.encode();
+                match self.inner.set_range::<Cow<'_, [u8]>, Cow<'_, [u8]>>(key.as_ref()) {
+                    // Skip all entries with the excluded key
+                    Ok(Some((found, _))) if found.as_ref() == key.as_ref() => {
+                        self.inner.next_nodup()
+                    }
+                    found => found,
+                }
             }
             Bound::Unbounded => self.inner.first(),
         };
@@ -147,7 +198,15 @@ impl<K: TransactionKind, T: Table> DbCursorRO<T> for Cursor<K, T> {
         start_key: Option<T::Key>,
     ) -> Result<ReverseWalker<'_, T, Self>, DatabaseError> {
         let start = if let Some(start_key) = start_key {
-            decode::<T>(self.inner.set_range(start_key.encode().as_ref()))
+            self.count(CountedOperation::Seek);
+            let start_key = start_key.encode();
+            let found = self.inner.set_range::<Cow<'_, [u8]>, Cow<'_, [u8]>>(start_key.as_ref());
+            match found {
+                // There's no entry with the key, start at the previous one
+                Ok(Some((key, _))) if key.as_ref() != start_key.as_ref() => self.prev(),
+                Ok(None) => self.last(),
+                found => decode::<T>(found),
+            }
         } else {
             self.last()
         }
@@ -155,21 +214,29 @@ impl<K: TransactionKind, T: Table> DbCursorRO<T> for Cursor<K, T> {
 
         Ok(ReverseWalker::new(self, start))
     }
+
+    fn with_sequential_access_hint(mut self) -> Self {
+        self.inner.set_access_pattern(AccessPattern::Sequential);
+        self
+    }
 }
 
 impl<K: TransactionKind, T: DupSort> DbDupCursorRO<T> for Cursor<K, T> {
     /// Returns the next `(key, value)` pair of a DUPSORT table.
     fn next_dup(&mut self) -> PairResult<T> {
+        self.count(CountedOperation::Next);
         decode::<T>(self.inner.next_dup())
     }
 
     /// Returns the next `(key, value)` pair skipping the duplicates.
     fn next_no_dup(&mut self) -> PairResult<T> {
+        self.count(CountedOperation::Next);
         decode::<T>(self.inner.next_nodup())
     }
 
     /// Returns the next `value` of a duplicate `key`.
     fn next_dup_val(&mut self) -> ValueOnlyResult<T> {
+        self.count(CountedOperation::Next);
         self.inner
             .next_dup()
             .map_err(|e| DatabaseError::Read(e.into()))?
@@ -177,11 +244,32 @@ impl<K: TransactionKind, T: DupSort> DbDupCursorRO<T> for Cursor<K, T> {
             .transpose()
     }
 
+    /// Returns the previous `value` of a duplicate `key`.
+    fn prev_dup_val(&mut self) -> ValueOnlyResult<T> {
+        self.count(CountedOperation::Prev);
+        self.inner
+            .prev_dup()
+            .map_err(|e| DatabaseError::Read(e.into()))?
+            .map(decode_value::<T>)
+            .transpose()
+    }
+
+    /// Returns the last `value` of a duplicate `key`.
+    fn last_dup_val(&mut self) -> ValueOnlyResult<T> {
+        self.count(CountedOperation::Seek);
+        self.inner
+            .last_dup()
+            .map_err(|e| DatabaseError::Read(e.into()))?
+            .map(decode_one::<T>)
+            .transpose()
+    }
+
     fn seek_by_key_subkey(
         &mut self,
         key: <T as Table>::Key,
         subkey: <T as DupSort>::SubKey,
     ) -> ValueOnlyResult<T> {
+        self.count(CountedOperation::Seek);
         self.inner
             .get_both_range(key.encode().as_ref(), subkey.encode().as_ref())
             .map_err(|e| DatabaseError::Read(e.into()))?
@@ -201,6 +289,7 @@ impl<K: TransactionKind, T: DupSort> DbDupCursorRO<T> for Cursor<K, T> {
     ) -> Result<DupWalker<'_, T, Self>, DatabaseError> {
         let start = match (key, subkey) {
             (Some(key), Some(subkey)) => {
+                self.count(CountedOperation::Seek);
                 // encode key and decode it after.
                 let key: Vec<u8> = key.encode().into();
                 self.inner
@@ -209,6 +298,7 @@ impl<K: TransactionKind, T: DupSort> DbDupCursorRO<T> for Cursor<K, T> {
                     .map(|val| decoder::<T>((Cow::Owned(key), val)))
             }
             (Some(key), None) => {
+                self.count(CountedOperation::Seek);
                 let key: Vec<u8> = key.encode().into();
                 self.inner
                     .set(key.as_ref())
@@ -217,6 +307,7 @@ impl<K: TransactionKind, T: DupSort> DbDupCursorRO<T> for Cursor<K, T> {
             }
             (None, Some(subkey)) => {
                 if let Some((key, _)) = self.first()? {
+                    self.count(CountedOperation::Seek);
                     let key: Vec<u8> = key.encode().into();
                     self.inner
                         .get_both_range(key.as_ref(), subkey.encode().as_ref())
@@ -229,7 +320,19 @@ impl<K: TransactionKind, T: DupSort> DbDupCursorRO<T> for Cursor<K, T> {
             (None, None) => self.first().transpose(),
         };
 
-        Ok(DupWalker::<'_, T, Self> { cursor: self, start })
+        Ok(DupWalker::new(self, start))
+    }
+
+    fn walk_dup_key(&mut self, key: T::Key) -> Result<DupWalker<'_, T, Self>, DatabaseError> {
+        self.count(CountedOperation::Seek);
+        let key: Vec<u8> = key.encode().into();
+        let start = self
+            .inner
+            .set(key.as_ref())
+            .map_err(|e| DatabaseError::Read(e.into()))?
+            .map(|val| decoder::<T>((Cow::Owned(key), val)));
+
+        Ok(DupWalker::new(self, start))
     }
 }
 
@@ -242,6 +345,7 @@ impl<T: Table> DbCursorRW<T> for Cursor<RW, T> {
     /// to properly upsert, you'll need to `seek_exact` & `delete_current` if the key+subkey was
     /// found, before calling `upsert`.
     fn upsert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.count(CountedOperation::Put);
         let key = key.encode();
         let value = compress_to_buf_or_ref!(self, value);
         self.execute_with_operation_metric(
@@ -264,6 +368,7 @@ impl<T: Table> DbCursorRW<T> for Cursor<RW, T> {
     }
 
     fn insert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.count(CountedOperation::Put);
         let key = key.encode();
         let value = compress_to_buf_or_ref!(self, value);
         self.execute_with_operation_metric(
@@ -288,6 +393,7 @@ impl<T: Table> DbCursorRW<T> for Cursor<RW, T> {
     /// Appends the data to the end of the table. Consequently, the append operation
     /// will fail if the inserted key is less than the last table key
     fn append(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.count(CountedOperation::Put);
         let key = key.encode();
         let value = compress_to_buf_or_ref!(self, value);
         self.execute_with_operation_metric(
@@ -310,6 +416,7 @@ impl<T: Table> DbCursorRW<T> for Cursor<RW, T> {
     }
 
     fn delete_current(&mut self) -> Result<(), DatabaseError> {
+        self.count(CountedOperation::Delete);
         self.execute_with_operation_metric(Operation::CursorDeleteCurrent, None, |this| {
             this.inner.del(WriteFlags::CURRENT).map_err(|e| DatabaseError::Delete(e.into()))
         })
@@ -318,12 +425,14 @@ impl<T: Table> DbCursorRW<T> for Cursor<RW, T> {
 
 impl<T: DupSort> DbDupCursorRW<T> for Cursor<RW, T> {
     fn delete_current_duplicates(&mut self) -> Result<(), DatabaseError> {
+        self.count(CountedOperation::Delete);
         self.execute_with_operation_metric(Operation::CursorDeleteCurrentDuplicates, None, |this| {
             this.inner.del(WriteFlags::NO_DUP_DATA).map_err(|e| DatabaseError::Delete(e.into()))
         })
     }
 
     fn append_dup(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.count(CountedOperation::Put);
         let key = key.encode();
         let value = compress_to_buf_or_ref!(self, value);
         self.execute_with_operation_metric(
@@ -7,7 +7,6 @@ use crate::{
     utils::default_page_size,
     DatabaseError,
 };
-use eyre::Context;
 use metrics::{gauge, Label};
 use reth_db_api::{
     cursor::{DbCursorRO, DbCursorRW},
@@ -20,6 +19,7 @@ use reth_libmdbx::{
     ffi, DatabaseFlags, Environment, EnvironmentFlags, Geometry, HandleSlowReadersReturnCode,
     MaxReadTransactionDuration, Mode, PageSize, SyncMode, RO, RW,
 };
+use reth_metrics::MetricsRegistrar;
 use reth_storage_errors::db::LogLevel;
 use reth_tracing::tracing::error;
 use std::{
@@ -33,11 +33,19 @@ use tx::Tx;
 pub mod cursor;
 pub mod tx;
 
+mod counters;
+
 const GIGABYTE: usize = 1024 * 1024 * 1024;
 const TERABYTE: usize = GIGABYTE * 1024;
 
+/// Default maximum size of the database, 4 terabytes.
+pub const DEFAULT_MAX_DB_SIZE: usize = 4 * TERABYTE;
+
+/// Default step in which the database file grows, 4 gigabytes.
+pub const DEFAULT_DB_GROWTH_STEP: usize = 4 * GIGABYTE;
+
 /// MDBX allows up to 32767 readers (`MDBX_READERS_LIMIT`), but we limit it to slightly below that
-const DEFAULT_MAX_READERS: u64 = 32_000;
+pub const DEFAULT_MAX_READERS: u64 = 32_000;
 
 /// Space that a read-only transaction can occupy until the warning is emitted.
 /// See [`reth_libmdbx::EnvironmentBuilder::set_handle_slow_readers`] for more information.
@@ -90,6 +98,30 @@ pub struct DatabaseArguments {
     ///
     /// This flag affects only at environment opening but can't be changed after.
     exclusive: Option<bool>,
+    /// Enable OS read-ahead for the whole environment. If [None], read-ahead is disabled.
+    ///
+    /// Read-ahead improves the performance of linear scans, but worsens it for random access,
+    /// which is our access pattern outside of sync. Cursors that walk large ranges of a table can
+    /// instead opt in with [`DbCursorRO::with_sequential_access_hint`].
+    read_ahead: Option<bool>,
+    /// Maximum size of the database in bytes. If [None], [`DEFAULT_MAX_DB_SIZE`] is used.
+    ///
+    /// Writes that would grow the database beyond it fail with `MDBX_MAP_FULL`.
+    max_size: Option<usize>,
+    /// Step in bytes in which the database file grows. If [None], [`DEFAULT_DB_GROWTH_STEP`] is
+    /// used.
+    growth_step: Option<usize>,
+    /// Maximum number of concurrent readers. If [None], [`DEFAULT_MAX_READERS`] is used.
+    max_readers: Option<u64>,
+    /// Sync mode of a read-write environment. If [None], [`SyncMode::Durable`] is used.
+    ///
+    /// Non-durable modes speed up writes at the cost of losing the last commits on a system crash,
+    /// which is only acceptable for data that can be recreated, e.g. in tests or imports.
+    sync_mode: Option<SyncMode>,
+    /// Log threshold of the operation counters, see [`DatabaseEnv::with_operation_counters`]. If
+    /// [None], the operations aren't counted.
+    #[cfg(feature = "operation-counters")]
+    operation_counters: Option<u64>,
 }
 
 impl DatabaseArguments {
@@ -100,6 +132,13 @@ impl DatabaseArguments {
             log_level: None,
             max_read_transaction_duration: None,
             exclusive: None,
+            read_ahead: None,
+            max_size: None,
+            growth_step: None,
+            max_readers: None,
+            sync_mode: None,
+            #[cfg(feature = "operation-counters")]
+            operation_counters: None,
         }
     }
 
@@ -124,12 +163,114 @@ impl DatabaseArguments {
         self
     }
 
+    /// Set whether OS read-ahead is enabled for the whole environment.
+    pub const fn with_read_ahead(mut self, read_ahead: Option<bool>) -> Self {
+        self.read_ahead = read_ahead;
+        self
+    }
+
+    /// Set the maximum size of the database in bytes.
+    pub const fn with_max_size(mut self, max_size: Option<usize>) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Set the step in bytes in which the database file grows.
+    pub const fn with_growth_step(mut self, growth_step: Option<usize>) -> Self {
+        self.growth_step = growth_step;
+        self
+    }
+
+    /// Set the maximum number of concurrent readers.
+    pub const fn with_max_readers(mut self, max_readers: Option<u64>) -> Self {
+        self.max_readers = max_readers;
+        self
+    }
+
+    /// Set the sync mode of a read-write environment.
+    pub const fn with_sync_mode(mut self, sync_mode: Option<SyncMode>) -> Self {
+        self.sync_mode = sync_mode;
+        self
+    }
+
+    /// Set the log threshold of the operation counters, which enables them.
+    #[cfg(feature = "operation-counters")]
+    pub const fn with_operation_counters(mut self, log_threshold: Option<u64>) -> Self {
+        self.operation_counters = log_threshold;
+        self
+    }
+
     /// Returns the client version if any.
     pub const fn client_version(&self) -> &ClientVersion {
         &self.client_version
     }
 }
 
+/// Page usage and entry count of a table, see [`DatabaseEnv::stats`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TableStats {
+    /// The table.
+    pub table: Tables,
+    /// Number of entries.
+    pub entries: usize,
+    /// Depth of the B-tree.
+    pub depth: u32,
+    /// Number of branch pages.
+    pub branch_pages: usize,
+    /// Number of leaf pages.
+    pub leaf_pages: usize,
+    /// Number of overflow pages, which hold values larger than a page.
+    pub overflow_pages: usize,
+    /// Size of the table in bytes, estimated from the number of pages.
+    pub size: usize,
+}
+
+impl TableStats {
+    /// Returns the total number of pages of the table.
+    pub const fn pages(&self) -> usize {
+        self.branch_pages + self.leaf_pages + self.overflow_pages
+    }
+}
+
+/// Statistics of the database, similar to the output of `mdbx_stat`, see
+/// [`DatabaseEnv::stats`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DatabaseStats {
+    /// Size of a page in bytes.
+    pub page_size: usize,
+    /// Current size of the memory map in bytes.
+    pub map_size: usize,
+    /// Number of the last used page.
+    pub last_page: usize,
+    /// Number of pages on the freelist, which are reused before the database file grows.
+    pub free_pages: usize,
+    /// Id of the last committed transaction.
+    pub last_txn_id: usize,
+    /// Maximum number of reader slots.
+    pub max_readers: usize,
+    /// Number of used reader slots.
+    pub num_readers: usize,
+    /// Statistics of all tables, in the order of [`Tables::ALL`].
+    pub tables: Vec<TableStats>,
+}
+
+impl DatabaseStats {
+    /// Returns the statistics of the table.
+    pub fn table(&self, table: Tables) -> Option<&TableStats> {
+        self.tables.iter().find(|stats| stats.table == table)
+    }
+
+    /// Returns the total size of all tables in bytes.
+    pub fn tables_size(&self) -> usize {
+        self.tables.iter().map(|stats| stats.size).sum()
+    }
+
+    /// Returns the size of the pages on the freelist in bytes.
+    pub const fn freelist_size(&self) -> usize {
+        self.free_pages * self.page_size
+    }
+}
+
 /// Wrapper for the libmdbx environment: [Environment]
 #[derive(Debug)]
 pub struct DatabaseEnv {
@@ -137,6 +278,9 @@ pub struct DatabaseEnv {
     inner: Environment,
     /// Cache for metric handles. If `None`, metrics are not recorded.
     metrics: Option<Arc<DatabaseEnvMetrics>>,
+    /// Per-table operation counters. If `None`, operations are not counted.
+    #[cfg(feature = "operation-counters")]
+    operation_counters: Option<Arc<counters::OperationCounters>>,
     /// Write lock for when dealing with a read-write environment.
     _lock_file: Option<StorageLock>,
 }
@@ -146,19 +290,25 @@ impl Database for DatabaseEnv {
     type TXMut = tx::Tx<RW>;
 
     fn tx(&self) -> Result<Self::TX, DatabaseError> {
-        Tx::new_with_metrics(
+        let tx = Tx::new_with_metrics(
             self.inner.begin_ro_txn().map_err(|e| DatabaseError::InitTx(e.into()))?,
             self.metrics.as_ref().cloned(),
         )
-        .map_err(|e| DatabaseError::InitTx(e.into()))
+        .map_err(|e| DatabaseError::InitTx(e.into()))?;
+        #[cfg(feature = "operation-counters")]
+        let tx = tx.with_operation_counters(self.operation_counters.as_ref());
+        Ok(tx)
     }
 
     fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError> {
-        Tx::new_with_metrics(
+        let tx = Tx::new_with_metrics(
             self.inner.begin_rw_txn().map_err(|e| DatabaseError::InitTx(e.into()))?,
             self.metrics.as_ref().cloned(),
         )
-        .map_err(|e| DatabaseError::InitTx(e.into()))
+        .map_err(|e| DatabaseError::InitTx(e.into()))?;
+        #[cfg(feature = "operation-counters")]
+        let tx = tx.with_operation_counters(self.operation_counters.as_ref());
+        Ok(tx)
     }
 }
 
@@ -169,66 +319,45 @@ impl DatabaseMetrics for DatabaseEnv {
         }
     }
 
+    fn register_metrics(&self, registrar: &mut MetricsRegistrar) {
+        crate::metrics::register_metrics(registrar)
+    }
+
     fn gauge_metrics(&self) -> Vec<(&'static str, f64, Vec<Label>)> {
         let mut metrics = Vec::new();
 
-        let _ = self
-            .view(|tx| {
-                for table in Tables::ALL.iter().map(Tables::name) {
-                    let table_db = tx.inner.open_db(Some(table)).wrap_err("Could not open db.")?;
-
-                    let stats = tx
-                        .inner
-                        .db_stat(&table_db)
-                        .wrap_err(format!("Could not find table: {table}"))?;
-
-                    let page_size = stats.page_size() as usize;
-                    let leaf_pages = stats.leaf_pages();
-                    let branch_pages = stats.branch_pages();
-                    let overflow_pages = stats.overflow_pages();
-                    let num_pages = leaf_pages + branch_pages + overflow_pages;
-                    let table_size = page_size * num_pages;
-                    let entries = stats.entries();
-
-                    metrics.push((
-                        "db.table_size",
-                        table_size as f64,
-                        vec![Label::new("table", table)],
-                    ));
-                    metrics.push((
-                        "db.table_pages",
-                        leaf_pages as f64,
-                        vec![Label::new("table", table), Label::new("type", "leaf")],
-                    ));
-                    metrics.push((
-                        "db.table_pages",
-                        branch_pages as f64,
-                        vec![Label::new("table", table), Label::new("type", "branch")],
-                    ));
-                    metrics.push((
-                        "db.table_pages",
-                        overflow_pages as f64,
-                        vec![Label::new("table", table), Label::new("type", "overflow")],
-                    ));
-                    metrics.push((
-                        "db.table_entries",
-                        entries as f64,
-                        vec![Label::new("table", table)],
-                    ));
-                }
-
-                Ok::<(), eyre::Report>(())
-            })
-            .map_err(|error| error!(%error, "Failed to read db table stats"));
-
-        if let Ok(freelist) =
-            self.freelist().map_err(|error| error!(%error, "Failed to read db.freelist"))
-        {
-            metrics.push(("db.freelist", freelist as f64, vec![]));
-        }
+        if let Ok(stats) = self.stats().map_err(|error| error!(%error, "Failed to read db stats")) {
+            for table_stats in &stats.tables {
+                let table = table_stats.table.name();
+                metrics.push((
+                    "db.table_size",
+                    table_stats.size as f64,
+                    vec![Label::new("table", table)],
+                ));
+                metrics.push((
+                    "db.table_pages",
+                    table_stats.leaf_pages as f64,
+                    vec![Label::new("table", table), Label::new("type", "leaf")],
+                ));
+                metrics.push((
+                    "db.table_pages",
+                    table_stats.branch_pages as f64,
+                    vec![Label::new("table", table), Label::new("type", "branch")],
+                ));
+                metrics.push((
+                    "db.table_pages",
+                    table_stats.overflow_pages as f64,
+                    vec![Label::new("table", table), Label::new("type", "overflow")],
+                ));
+                metrics.push((
+                    "db.table_entries",
+                    table_stats.entries as f64,
+                    vec![Label::new("table", table)],
+                ));
+            }
 
-        if let Ok(stat) = self.stat().map_err(|error| error!(%error, "Failed to read db.stat")) {
-            metrics.push(("db.page_size", stat.page_size() as f64, vec![]));
+            metrics.push(("db.freelist", stats.free_pages as f64, vec![]));
+            metrics.push(("db.page_size", stats.page_size as f64, vec![]));
         }
 
         metrics.push((
@@ -272,7 +401,7 @@ impl DatabaseEnv {
             DatabaseEnvKind::RW => {
                 // enable writemap mode in RW mode
                 inner_env.write_map();
-                Mode::ReadWrite { sync_mode: SyncMode::Durable }
+                Mode::ReadWrite { sync_mode: args.sync_mode.unwrap_or(SyncMode::Durable) }
             }
         };
 
@@ -281,10 +410,8 @@ impl DatabaseEnv {
         debug_assert!(Tables::ALL.len() <= 256, "number of tables exceed max dbs");
         inner_env.set_max_dbs(256);
         inner_env.set_geometry(Geometry {
-            // Maximum database size of 4 terabytes
-            size: Some(0..(4 * TERABYTE)),
-            // We grow the database in increments of 4 gigabytes
-            growth_step: Some(4 * GIGABYTE as isize),
+            size: Some(0..args.max_size.unwrap_or(DEFAULT_MAX_DB_SIZE)),
+            growth_step: Some(args.growth_step.unwrap_or(DEFAULT_DB_GROWTH_STEP) as isize),
             // The database never shrinks
             shrink_threshold: Some(0),
             page_size: Some(PageSize::Set(default_page_size())),
@@ -337,15 +464,16 @@ impl DatabaseEnv {
 
         inner_env.set_flags(EnvironmentFlags {
             mode,
-            // We disable readahead because it improves performance for linear scans, but
-            // worsens it for random access (which is our access pattern outside of sync)
-            no_rdahead: true,
+            // We disable readahead by default because it improves performance for linear scans,
+            // but worsens it for random access (which is our access pattern outside of sync).
+            // Linear scans use sequential access hints on their cursors instead.
+            no_rdahead: !args.read_ahead.unwrap_or_default(),
             coalesce: true,
             exclusive: args.exclusive.unwrap_or_default(),
             ..Default::default()
         });
         // Configure more readers
-        inner_env.set_max_readers(DEFAULT_MAX_READERS);
+        inner_env.set_max_readers(args.max_readers.unwrap_or(DEFAULT_MAX_READERS));
         // This parameter sets the maximum size of the "reclaimed list", and the unit of measurement
         // is "pages". Reclaimed list is the list of freed pages that's populated during the
         // lifetime of DB transaction, and through which MDBX searches when it needs to insert new
@@ -406,6 +534,10 @@ impl DatabaseEnv {
         let env = Self {
             inner: inner_env.open(path).map_err(|e| DatabaseError::Open(e.into()))?,
             metrics: None,
+            #[cfg(feature = "operation-counters")]
+            operation_counters: args
+                .operation_counters
+                .map(|log_threshold| counters::OperationCounters::new(log_threshold).into()),
             _lock_file,
         };
 
@@ -418,6 +550,18 @@ impl DatabaseEnv {
         self
     }
 
+    /// Enables per-table counters of the seek, next, prev, get, put and delete operations of
+    /// transactions and their cursors.
+    ///
+    /// The counts are exported as metrics once a transaction is dropped. Transactions that did at
+    /// least `log_threshold` operations log a summary of their operations per table at `debug`
+    /// level, which helps to find accidental quadratic access patterns.
+    #[cfg(feature = "operation-counters")]
+    pub fn with_operation_counters(mut self, log_threshold: u64) -> Self {
+        self.operation_counters = Some(counters::OperationCounters::new(log_threshold).into());
+        self
+    }
+
     /// Creates all the defined tables, if necessary.
     pub fn create_tables(&self) -> Result<(), DatabaseError> {
         let tx = self.inner.begin_rw_txn().map_err(|e| DatabaseError::InitTx(e.into()))?;
@@ -437,6 +581,45 @@ impl DatabaseEnv {
         Ok(())
     }
 
+    /// Returns the statistics of the environment and of all tables, read within a read-only
+    /// transaction.
+    pub fn stats(&self) -> Result<DatabaseStats, DatabaseError> {
+        let info = self.inner.info().map_err(|e| DatabaseError::Stats(e.into()))?;
+        let page_size = self.inner.stat().map_err(|e| DatabaseError::Stats(e.into()))?.page_size();
+        let free_pages = self.inner.freelist().map_err(|e| DatabaseError::Stats(e.into()))?;
+
+        let tx = self.inner.begin_ro_txn().map_err(|e| DatabaseError::InitTx(e.into()))?;
+        let tables = Tables::ALL
+            .iter()
+            .map(|&table| {
+                let db =
+                    tx.open_db(Some(table.name())).map_err(|e| DatabaseError::Open(e.into()))?;
+                let stat = tx.db_stat(&db).map_err(|e| DatabaseError::Stats(e.into()))?;
+                let pages = stat.branch_pages() + stat.leaf_pages() + stat.overflow_pages();
+                Ok(TableStats {
+                    table,
+                    entries: stat.entries(),
+                    depth: stat.depth(),
+                    branch_pages: stat.branch_pages(),
+                    leaf_pages: stat.leaf_pages(),
+                    overflow_pages: stat.overflow_pages(),
+                    size: pages * page_size as usize,
+                })
+            })
+            .collect::<Result<Vec<_>, DatabaseError>>()?;
+
+        Ok(DatabaseStats {
+            page_size: page_size as usize,
+            map_size: info.map_size(),
+            last_page: info.last_pgno(),
+            free_pages,
+            last_txn_id: info.last_txnid(),
+            max_readers: info.max_readers(),
+            num_readers: info.num_readers(),
+            tables,
+        })
+    }
+
     /// Records version that accesses the database with write privileges.
     pub fn record_client_version(&self, version: ClientVersion) -> Result<(), DatabaseError> {
         if version.is_empty() {
@@ -485,7 +668,7 @@ mod tests {
     use reth_libmdbx::Error;
     use reth_primitives::{Account, Address, Header, IntegerList, StorageEntry, B256, U256};
     use reth_storage_errors::db::{DatabaseWriteError, DatabaseWriteOperation};
-    use std::str::FromStr;
+    use std::{ops::Bound, str::FromStr};
     use tempfile::TempDir;
 
     /// Create database for testing
@@ -665,6 +848,16 @@ mod tests {
         assert_eq!(walker.next(), Some(Ok((3, B256::ZERO))));
         // next() returns None after walker is done
         assert_eq!(walker.next(), None);
+
+        // (1, 3]
+        let mut walker = cursor.walk_range((Bound::Excluded(1), Bound::Included(3))).unwrap();
+        assert_eq!(walker.next(), Some(Ok((2, B256::ZERO))));
+        assert_eq!(walker.next(), Some(Ok((3, B256::ZERO))));
+        assert_eq!(walker.next(), None);
+
+        // (3, ∞)
+        let mut walker = cursor.walk_range((Bound::Excluded(3), Bound::Unbounded)).unwrap();
+        assert_eq!(walker.next(), None);
     }
 
     #[test]
@@ -706,6 +899,13 @@ mod tests {
         assert_eq!(walker.next(), Some(Ok((1, AccountBeforeTx { address: address1, info: None }))));
         assert_eq!(walker.next(), Some(Ok((1, AccountBeforeTx { address: address2, info: None }))));
         assert_eq!(walker.next(), None);
+
+        // an excluded start key skips all of its duplicates
+        let mut walker = cursor.walk_range((Bound::Excluded(0), Bound::Excluded(2))).unwrap();
+        assert_eq!(walker.next(), Some(Ok((1, AccountBeforeTx { address: address0, info: None }))));
+        assert_eq!(walker.next(), Some(Ok((1, AccountBeforeTx { address: address1, info: None }))));
+        assert_eq!(walker.next(), Some(Ok((1, AccountBeforeTx { address: address2, info: None }))));
+        assert_eq!(walker.next(), None);
     }
 
     #[allow(clippy::reversed_empty_ranges)]
@@ -817,8 +1017,8 @@ mod tests {
         assert_eq!(reverse_walker.next(), Some(Ok((0, B256::ZERO))));
         assert_eq!(reverse_walker.next(), None);
 
+        // a missing key starts at the previous entry
         let mut reverse_walker = cursor.walk_back(Some(2)).unwrap();
-        assert_eq!(reverse_walker.next(), Some(Ok((3, B256::ZERO))));
         assert_eq!(reverse_walker.next(), Some(Ok((1, B256::ZERO))));
         assert_eq!(reverse_walker.next(), Some(Ok((0, B256::ZERO))));
         assert_eq!(reverse_walker.next(), None);
@@ -834,6 +1034,137 @@ mod tests {
         assert_eq!(reverse_walker.next(), Some(Ok((1, B256::ZERO))));
         assert_eq!(reverse_walker.next(), Some(Ok((0, B256::ZERO))));
         assert_eq!(reverse_walker.next(), None);
+
+        // no entry is less than or equal to the key
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        tx.delete::<CanonicalHeaders>(0, None).expect(ERROR_DEL);
+        tx.commit().expect(ERROR_COMMIT);
+        let tx = db.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+        assert_eq!(cursor.walk_back(Some(0)).unwrap().next(), None);
+        assert_eq!(
+            cursor.walk_back(Some(2)).unwrap().collect::<Result<Vec<_>, _>>(),
+            Ok(vec![(1, B256::ZERO)])
+        );
+    }
+
+    #[test]
+    fn db_walkers_delete_while_walking() {
+        let db: Arc<DatabaseEnv> = create_test_db(DatabaseEnvKind::RW);
+        let put_keys = |db: &DatabaseEnv| {
+            let tx = db.tx_mut().expect(ERROR_INIT_TX);
+            (0..10)
+                .try_for_each(|key| tx.put::<CanonicalHeaders>(key, B256::ZERO))
+                .expect(ERROR_PUT);
+            tx.commit().expect(ERROR_COMMIT);
+        };
+        let keys = |db: &DatabaseEnv| {
+            let tx = db.tx().expect(ERROR_INIT_TX);
+            let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+            cursor.walk(None).unwrap().map(|entry| entry.unwrap().0).collect::<Vec<_>>()
+        };
+
+        // delete every even key while walking forward
+        put_keys(&db);
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+        let mut walker = cursor.walk(None).unwrap();
+        let mut walked = Vec::new();
+        while let Some((key, _)) = walker.next().transpose().unwrap() {
+            walked.push(key);
+            if key % 2 == 0 {
+                walker.delete_current().expect(ERROR_DEL);
+            }
+        }
+        assert_eq!(walked, (0..10).collect::<Vec<_>>());
+        tx.commit().expect(ERROR_COMMIT);
+        assert_eq!(keys(&db), vec![1, 3, 5, 7, 9]);
+
+        // delete every even key of a range
+        put_keys(&db);
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+        let mut walker = cursor.walk_range(2..8).unwrap();
+        let mut walked = Vec::new();
+        while let Some((key, _)) = walker.next().transpose().unwrap() {
+            walked.push(key);
+            if key % 2 == 0 {
+                walker.delete_current().expect(ERROR_DEL);
+            }
+        }
+        assert_eq!(walked, (2..8).collect::<Vec<_>>());
+        tx.commit().expect(ERROR_COMMIT);
+        assert_eq!(keys(&db), vec![0, 1, 3, 5, 7, 8, 9]);
+
+        // delete every even key while walking back
+        put_keys(&db);
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+        let mut walker = cursor.walk_back(None).unwrap();
+        let mut walked = Vec::new();
+        while let Some((key, _)) = walker.next().transpose().unwrap() {
+            walked.push(key);
+            if key % 2 == 0 {
+                walker.delete_current().expect(ERROR_DEL);
+            }
+        }
+        assert_eq!(walked, (0..10).rev().collect::<Vec<_>>());
+        tx.commit().expect(ERROR_COMMIT);
+        assert_eq!(keys(&db), vec![1, 3, 5, 7, 9]);
+    }
+
+    #[test]
+    fn db_dup_walker_delete_while_walking() {
+        let db: Arc<DatabaseEnv> = create_test_db(DatabaseEnvKind::RW);
+        let (address, other) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let entry =
+            |slot: u8| StorageEntry { key: B256::with_last_byte(slot), value: U256::from(1) };
+
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+        for slot in 0..6 {
+            cursor.upsert(address, entry(slot)).expect(ERROR_UPSERT);
+            cursor.upsert(other, entry(slot)).expect(ERROR_UPSERT);
+        }
+
+        // only the current duplicate is deleted, the walk continues with the next one
+        let mut walker = cursor.walk_dup(Some(address), None).unwrap();
+        let mut walked = Vec::new();
+        while let Some((key, value)) = walker.next().transpose().unwrap() {
+            assert_eq!(key, address);
+            walked.push(value.key);
+            if value.key.0[31] % 2 == 0 {
+                walker.delete_current().expect(ERROR_DEL);
+            }
+        }
+        assert_eq!(walked, (0..6).map(B256::with_last_byte).collect::<Vec<_>>());
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = db.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_dup_read::<PlainStorageState>().unwrap();
+        assert_eq!(
+            cursor.walk(None).unwrap().collect::<Result<Vec<_>, _>>(),
+            Ok([1, 3, 5]
+                .into_iter()
+                .map(|slot| (address, entry(slot)))
+                .chain((0..6).map(|slot| (other, entry(slot))))
+                .collect())
+        );
+    }
+
+    #[test]
+    fn db_walkers_on_empty_table() {
+        let db: Arc<DatabaseEnv> = create_test_db(DatabaseEnvKind::RW);
+        let tx = db.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+
+        assert_eq!(cursor.walk(None).unwrap().next(), None);
+        assert_eq!(cursor.walk(Some(1)).unwrap().next(), None);
+        assert_eq!(cursor.walk_back(None).unwrap().next(), None);
+        assert_eq!(cursor.walk_back(Some(1)).unwrap().next(), None);
+        assert_eq!(cursor.walk_range(..).unwrap().next(), None);
+        assert_eq!(cursor.walk_range(1..3).unwrap().next(), None);
+        assert_eq!(cursor.walk_range(1..=3).unwrap().next(), None);
     }
 
     #[test]
@@ -1178,6 +1509,79 @@ mod tests {
         assert_eq!(result, Some(value))
     }
 
+    #[test]
+    fn db_max_size() {
+        let path = TempDir::new().expect(ERROR_TEMPDIR).into_path();
+        let args = |max_size| {
+            DatabaseArguments::new(ClientVersion::default())
+                .with_max_size(Some(max_size))
+                .with_growth_step(Some(256 * 1024))
+                .with_sync_mode(Some(SyncMode::SafeNoSync))
+        };
+        // Roughly 2 megabytes of headers
+        let insert_headers = |env: &DatabaseEnv| -> Result<(), DatabaseError> {
+            let tx = env.tx_mut()?;
+            for number in 0..2048 {
+                let header =
+                    Header { number, extra_data: vec![0xff; 1024].into(), ..Default::default() };
+                tx.put::<Headers>(number, header)?;
+            }
+            tx.commit()?;
+            Ok(())
+        };
+
+        {
+            let env = DatabaseEnv::open(&path, DatabaseEnvKind::RW, args(1024 * 1024))
+                .expect(ERROR_DB_CREATION);
+            env.create_tables().expect(ERROR_TABLE_CREATION);
+
+            let code = match insert_headers(&env) {
+                Err(DatabaseError::Write(err)) => err.info.code,
+                Err(DatabaseError::Commit(info)) => info.code,
+                result => panic!("expected the database to be full: {result:?}"),
+            };
+            assert_eq!(code, Error::MapFull.to_err_code());
+        }
+
+        // Reopening with a larger maximum size grows the database
+        let env = DatabaseEnv::open(&path, DatabaseEnvKind::RW, args(64 * 1024 * 1024))
+            .expect(ERROR_DB_CREATION);
+        insert_headers(&env).expect(ERROR_PUT);
+        assert_eq!(env.view(|tx| tx.entries::<Headers>()).unwrap(), Ok(2048));
+    }
+
+    #[test]
+    fn db_stats() {
+        let env = create_test_db(DatabaseEnvKind::RW);
+        let empty = env.stats().unwrap();
+        assert_eq!(empty.tables.len(), Tables::ALL.len());
+        assert!(empty.map_size > 0);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for number in 0..4096 {
+            tx.put::<Headers>(number, Header { number, ..Default::default() }).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let stats = env.stats().unwrap();
+        assert!(stats.last_txn_id > empty.last_txn_id);
+        assert!(stats.last_page > empty.last_page);
+
+        let headers = stats.table(Tables::Headers).unwrap();
+        assert_eq!(headers.entries, 4096);
+        assert!(headers.leaf_pages > 0);
+        assert!(headers.branch_pages > 0);
+        assert!(headers.depth > 1);
+        assert_eq!(headers.size, headers.pages() * stats.page_size);
+
+        // untouched tables don't use any pages
+        let storage = stats.table(Tables::PlainStorageState).unwrap();
+        assert_eq!(storage.entries, 0);
+        assert_eq!(storage.pages(), 0);
+        assert_eq!(storage.size, 0);
+        assert_eq!(stats.tables_size(), stats.tables.iter().map(|table| table.size).sum());
+    }
+
     #[test]
     fn db_dup_sort() {
         let env = create_test_db(DatabaseEnvKind::RW);
@@ -1222,6 +1626,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn db_dup_cursor_walk_dup_key() {
+        let env = create_test_db(DatabaseEnvKind::RW);
+        let keys = [1, 3, 5].map(Address::with_last_byte);
+        let entry =
+            |slot: u8| StorageEntry { key: B256::with_last_byte(slot), value: U256::from(slot) };
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in keys {
+            for slot in [2, 4, 6] {
+                tx.put::<PlainStorageState>(key, entry(slot)).expect(ERROR_PUT);
+            }
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_dup_read::<PlainStorageState>().unwrap();
+
+        // only the duplicates of the key are walked
+        for key in keys {
+            assert_eq!(
+                cursor.walk_dup_key(key).unwrap().collect::<Result<Vec<_>, _>>(),
+                Ok([2, 4, 6].map(|slot| (key, entry(slot))).to_vec())
+            );
+        }
+
+        // a missing key yields nothing, even if the cursor is positioned at another key
+        for missing in [0, 2, 4, 6].map(Address::with_last_byte) {
+            cursor.first().unwrap();
+            assert_eq!(cursor.walk_dup_key(missing).unwrap().next(), None);
+            assert_eq!(cursor.walk_dup(Some(missing), None).unwrap().next(), None);
+            let mut walker = cursor.walk_dup(Some(missing), Some(B256::ZERO)).unwrap();
+            assert_eq!(walker.next(), None);
+        }
+
+        // the first duplicate greater than or equal to the subkey, under the key only
+        assert_eq!(cursor.seek_by_key_subkey(keys[1], B256::with_last_byte(3)), Ok(Some(entry(4))));
+        assert_eq!(cursor.seek_by_key_subkey(keys[1], B256::with_last_byte(4)), Ok(Some(entry(4))));
+        assert_eq!(cursor.seek_by_key_subkey(keys[1], B256::with_last_byte(7)), Ok(None));
+        assert_eq!(cursor.seek_by_key_subkey(keys[2], B256::with_last_byte(7)), Ok(None));
+        assert_eq!(
+            cursor.walk_dup(Some(keys[1]), Some(B256::with_last_byte(7))).unwrap().next(),
+            None
+        );
+    }
+
     #[test]
     fn db_iterate_over_all_dup_values() {
         let env = create_test_db(DatabaseEnvKind::RW);
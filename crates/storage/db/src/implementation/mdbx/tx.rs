@@ -1,6 +1,8 @@
 //! Transaction wrapper for libmdbx-sys.
 
-use super::cursor::Cursor;
+#[cfg(feature = "operation-counters")]
+use super::counters::{OperationCounters, TransactionOperationCounts};
+use super::{counters::CountedOperation, cursor::Cursor};
 use crate::{
     metrics::{DatabaseEnvMetrics, Operation, TransactionMode, TransactionOutcome},
     tables::utils::decode_one,
@@ -37,6 +39,12 @@ pub struct Tx<K: TransactionKind> {
     ///
     /// If [Some], then metrics are reported.
     metrics_handler: Option<MetricsHandler<K>>,
+
+    /// Operation counts shared with the cursors of the transaction.
+    ///
+    /// If [Some], then operations are counted.
+    #[cfg(feature = "operation-counters")]
+    operation_counts: Option<Arc<TransactionOperationCounts>>,
 }
 
 impl<K: TransactionKind> Tx<K> {
@@ -66,9 +74,39 @@ impl<K: TransactionKind> Tx<K> {
 
     #[inline]
     const fn new_inner(inner: Transaction<K>, metrics_handler: Option<MetricsHandler<K>>) -> Self {
-        Self { inner, metrics_handler }
+        Self {
+            inner,
+            metrics_handler,
+            #[cfg(feature = "operation-counters")]
+            operation_counts: None,
+        }
+    }
+
+    /// Counts the operations of the transaction and its cursors, if the counters are [Some].
+    #[cfg(feature = "operation-counters")]
+    pub(crate) fn with_operation_counters(
+        mut self,
+        counters: Option<&Arc<OperationCounters>>,
+    ) -> Self {
+        let txn_id = self.id().unwrap_or_default();
+        self.operation_counts = counters
+            .map(|counters| Arc::new(TransactionOperationCounts::new(txn_id, counters.clone())));
+        self
+    }
+
+    /// Counts the operation on the table, if operation counters are enabled.
+    #[cfg(feature = "operation-counters")]
+    #[inline]
+    fn count<T: Table>(&self, operation: CountedOperation) {
+        if let Some(counts) = &self.operation_counts {
+            counts.increment::<T>(operation)
+        }
     }
 
+    #[cfg(not(feature = "operation-counters"))]
+    #[inline(always)]
+    const fn count<T: Table>(&self, _operation: CountedOperation) {}
+
     /// Gets this transaction ID.
     pub fn id(&self) -> reth_libmdbx::Result<u64> {
         self.metrics_handler.as_ref().map_or_else(|| self.inner.id(), |handler| Ok(handler.txn_id))
@@ -89,10 +127,13 @@ impl<K: TransactionKind> Tx<K> {
             .cursor_with_dbi(self.get_dbi::<T>()?)
             .map_err(|e| DatabaseError::InitCursor(e.into()))?;
 
-        Ok(Cursor::new_with_metrics(
+        let cursor = Cursor::new_with_metrics(
             inner,
             self.metrics_handler.as_ref().map(|h| h.env_metrics.clone()),
-        ))
+        );
+        #[cfg(feature = "operation-counters")]
+        let cursor = cursor.with_operation_counts(self.operation_counts.clone());
+        Ok(cursor)
     }
 
     /// If `self.metrics_handler == Some(_)`, measure the time it takes to execute the closure and
@@ -265,6 +306,7 @@ impl<K: TransactionKind> DbTx for Tx<K> {
     type DupCursor<T: DupSort> = Cursor<K, T>;
 
     fn get<T: Table>(&self, key: T::Key) -> Result<Option<<T as Table>::Value>, DatabaseError> {
+        self.count::<T>(CountedOperation::Get);
         self.execute_with_operation_metric::<T, _>(Operation::Get, None, |tx| {
             tx.get(self.get_dbi::<T>()?, key.encode().as_ref())
                 .map_err(|e| DatabaseError::Read(e.into()))?
@@ -323,6 +365,7 @@ impl DbTxMut for Tx<RW> {
     type DupCursorMut<T: DupSort> = Cursor<RW, T>;
 
     fn put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.count::<T>(CountedOperation::Put);
         let key = key.encode();
         let value = value.compress();
         self.execute_with_operation_metric::<T, _>(
@@ -347,6 +390,7 @@ impl DbTxMut for Tx<RW> {
         key: T::Key,
         value: Option<T::Value>,
     ) -> Result<bool, DatabaseError> {
+        self.count::<T>(CountedOperation::Delete);
         let mut data = None;
 
         let value = value.map(Compress::compress);
@@ -434,4 +478,56 @@ mod tests {
         // Backtrace is recorded.
         assert!(tx.metrics_handler.unwrap().backtrace_recorded.load(Ordering::Relaxed));
     }
+
+    #[cfg(feature = "operation-counters")]
+    #[test]
+    fn operation_counters() {
+        use crate::implementation::mdbx::counters::CountedOperation;
+        use reth_db_api::{
+            cursor::{DbCursorRO, DbCursorRW},
+            transaction::DbTxMut,
+        };
+        use reth_primitives::B256;
+
+        let dir = tempdir().unwrap();
+        let db = DatabaseEnv::open(
+            dir.path(),
+            DatabaseEnvKind::RW,
+            DatabaseArguments::new(ClientVersion::default()),
+        )
+        .unwrap()
+        .with_operation_counters(u64::MAX);
+        db.create_tables().unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        tx.put::<tables::CanonicalHeaders>(1, B256::ZERO).unwrap();
+        tx.put::<tables::CanonicalHeaders>(2, B256::ZERO).unwrap();
+        tx.put::<tables::CanonicalHeaders>(3, B256::ZERO).unwrap();
+        tx.get::<tables::CanonicalHeaders>(1).unwrap();
+        tx.delete::<tables::CanonicalHeaders>(3, None).unwrap();
+
+        let mut cursor = tx.cursor_read::<tables::CanonicalHeaders>().unwrap();
+        cursor.seek(0).unwrap();
+        cursor.next().unwrap();
+        cursor.prev().unwrap();
+        // walking seeks once and moves to the next entry until the end of the table
+        assert_eq!(cursor.walk_range(1..).unwrap().count(), 2);
+
+        tx.cursor_write::<tables::HeaderNumbers>().unwrap().upsert(B256::ZERO, 1).unwrap();
+
+        let counts = tx.operation_counts.clone().unwrap();
+        let canonical_headers = [
+            (CountedOperation::Seek, 2),
+            (CountedOperation::Next, 3),
+            (CountedOperation::Prev, 1),
+            (CountedOperation::Get, 1),
+            (CountedOperation::Put, 3),
+            (CountedOperation::Delete, 1),
+        ];
+        for (operation, count) in canonical_headers {
+            assert_eq!(counts.get::<tables::CanonicalHeaders>(operation), count, "{operation:?}");
+        }
+        assert_eq!(counts.get::<tables::HeaderNumbers>(CountedOperation::Put), 1);
+        assert_eq!(counts.get::<tables::HeaderNumbers>(CountedOperation::Seek), 0);
+    }
 }
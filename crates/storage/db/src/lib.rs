@@ -47,6 +47,7 @@ pub mod test_utils {
     };
     use reth_fs_util;
     use reth_libmdbx::MaxReadTransactionDuration;
+    use reth_metrics::MetricsRegistrar;
     use std::{
         path::{Path, PathBuf},
         sync::Arc,
@@ -113,6 +114,10 @@ pub mod test_utils {
         fn report_metrics(&self) {
             self.db().report_metrics()
         }
+
+        fn register_metrics(&self, registrar: &mut MetricsRegistrar) {
+            self.db().register_metrics(registrar)
+        }
     }
 
     impl<DB: DatabaseMetadata> DatabaseMetadata for TempDatabase<DB> {
@@ -1,6 +1,6 @@
 use crate::Tables;
 use metrics::{Gauge, Histogram};
-use reth_metrics::{metrics::Counter, Metrics};
+use reth_metrics::{metrics::Counter, Metrics, MetricsRegistrar};
 use rustc_hash::{FxHashMap, FxHasher};
 use std::{
     collections::HashMap,
@@ -11,6 +11,28 @@ use strum::{EnumCount, EnumIter, IntoEnumIterator};
 
 const LARGE_VALUE_THRESHOLD_BYTES: usize = 4096;
 
+/// Labels of the [`TransactionMetrics`].
+const TRANSACTION_LABELS: &[&str] = &[Labels::TransactionMode.as_str()];
+
+/// Labels of the [`TransactionOutcomeMetrics`].
+const TRANSACTION_OUTCOME_LABELS: &[&str] =
+    &[Labels::TransactionMode.as_str(), Labels::TransactionOutcome.as_str()];
+
+/// Labels of the [`OperationMetrics`].
+const OPERATION_LABELS: &[&str] = &[Labels::Table.as_str(), Labels::Operation.as_str()];
+
+/// Declares all metrics recorded by the database environment.
+pub(crate) fn register_metrics(registrar: &mut MetricsRegistrar) {
+    registrar
+        .describe_with_labels("database", TRANSACTION_LABELS, TransactionMetrics::describe)
+        .describe_with_labels(
+            "database",
+            TRANSACTION_OUTCOME_LABELS,
+            TransactionOutcomeMetrics::describe,
+        )
+        .describe_with_labels("database", OPERATION_LABELS, OperationMetrics::describe);
+}
+
 /// Caches metric handles for database environment to make sure handles are not re-created
 /// on every operation.
 ///
@@ -236,7 +258,7 @@ impl Operation {
 }
 
 /// Enum defining labels for various aspects used in metrics.
-enum Labels {
+pub(crate) enum Labels {
     /// Label representing a table.
     Table,
     /// Label representing a transaction mode.
@@ -27,7 +27,7 @@ use reth_db_api::{
         blocks::{HeaderHash, StoredBlockOmmers},
         client_version::ClientVersion,
         storage_sharded_key::StorageShardedKey,
-        CompactU256, ShardedKey, StoredBlockBodyIndices, StoredBlockWithdrawals,
+        CompactU256, ShardedKey, StoredBlockBodyIndices, StoredBlockWithdrawals, StoredTxNumber,
     },
     table::{Decode, DupSort, Encode, Table},
 };
@@ -410,6 +410,19 @@ tables! {
 
     /// Stores generic chain state info, like the last finalized block.
     table ChainState<Key = ChainStateKey, Value = BlockNumber>;
+
+    /// Stores the numbers of the transactions that involve an address, i.e. that were sent by it,
+    /// are addressed to it or, optionally, emitted a log from it.
+    ///
+    /// Only populated if the transaction history index is enabled.
+    table AddressTransactions<Key = Address, Value = StoredTxNumber, SubKey = TxNumber>;
+
+    /// Stores the internal nodes of the accumulator of canonical header hashes, keyed by
+    /// `height << 56 | index`, see [`reth_primitives::accumulator`]. The leaves are the canonical
+    /// header hashes themselves.
+    ///
+    /// Only populated if the header accumulator is enabled.
+    table HeaderAccumulator<Key = u64, Value = B256>;
 }
 
 /// Keys for the `ChainState` table.
@@ -2,7 +2,7 @@ use reth_primitives::{
     Address, BlockHash, BlockHashOrNumber, BlockNumber, GotExpected, StaticFileSegment,
     TxHashOrNumber, TxNumber, B256, U256,
 };
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 use thiserror::Error;
 
 /// Provider result type.
@@ -72,14 +72,63 @@ pub enum ProviderError {
     #[error("safe block does not exist")]
     SafeBlockNotFound,
     /// Mismatch of sender and transaction.
-    #[error("mismatch of sender and transaction id {tx_id}")]
+    #[error("mismatch of sender and transaction id {tx_id} in block #{block_number}")]
     MismatchOfTransactionAndSenderId {
+        /// The block of the transaction.
+        block_number: BlockNumber,
         /// The transaction ID.
         tx_id: TxNumber,
     },
+    /// Block body indices do not continue where the previous block ended.
+    #[error(
+        "block #{block_number} body indices are not contiguous: first tx number {first_tx_num}"
+    )]
+    BlockBodyIndicesGap {
+        /// The block with the inconsistent indices.
+        block_number: BlockNumber,
+        /// The first transaction number diff.
+        first_tx_num: GotExpected<TxNumber>,
+    },
+    /// The gas used by the receipts of a block is inconsistent with the block.
+    #[error("receipt {tx_index} of block #{block_number} is inconsistent: {kind}")]
+    ReceiptGasInconsistency {
+        /// The block of the receipt.
+        block_number: BlockNumber,
+        /// The index of the receipt's transaction in the block.
+        tx_index: usize,
+        /// The violated invariant.
+        kind: ReceiptGasError,
+    },
     /// Block body wrong transaction count.
-    #[error("stored block indices does not match transaction count")]
-    BlockBodyTransactionCount,
+    #[error("stored block indices of block #{0} does not match transaction count")]
+    BlockBodyTransactionCount(BlockNumber),
+    /// Senders are missing for a block whose transactions are missing as well, so they can't be
+    /// recovered.
+    #[error(
+        "cannot recover missing senders of block #{block_number}: transaction count {transactions}"
+    )]
+    UnrecoverableSenders {
+        /// The block with the missing senders.
+        block_number: BlockNumber,
+        /// The number of transactions found and expected by the block body indices.
+        transactions: GotExpected<u64>,
+    },
+    /// A node of the header accumulator is missing.
+    #[error("header accumulator node {index} at height {height} not found")]
+    HeaderAccumulatorNodeNotFound {
+        /// The height of the node.
+        height: u8,
+        /// The index of the node at its height.
+        index: u64,
+    },
+    /// Blocks are appended to the header accumulator out of order.
+    #[error("cannot append block #{block_number} to header accumulator of {leaf_count} leaves")]
+    HeaderAccumulatorGap {
+        /// The first appended block.
+        block_number: BlockNumber,
+        /// The number of leaves of the accumulator.
+        leaf_count: u64,
+    },
     /// Thrown when the cache service task dropped.
     #[error("cache service task stopped")]
     CacheServiceUnavailable,
@@ -122,6 +171,9 @@ pub enum ProviderError {
     /// Trying to insert data from an unexpected block number.
     #[error("trying to append data to {0} as block #{1} but expected block #{2}")]
     UnexpectedStaticFileBlockNumber(StaticFileSegment, BlockNumber, BlockNumber),
+    /// Static File data does not match the checksum written on its last commit.
+    #[error("{0} static file at {1:?} is corrupted, its data does not match its checksum")]
+    CorruptedStaticFile(StaticFileSegment, PathBuf),
     /// Static File Provider was initialized as read-only.
     #[error("cannot get a writer on a read-only environment.")]
     ReadOnlyStaticFileAccess,
@@ -136,6 +188,33 @@ pub enum ProviderError {
     StorageLockError(#[from] crate::lockfile::StorageLockError),
 }
 
+impl ProviderError {
+    /// Returns the number of the block the error refers to, if any.
+    pub const fn block_number(&self) -> Option<BlockNumber> {
+        match self {
+            Self::BlockBodyIndicesNotFound(number) |
+            Self::TotalDifficultyNotFound(number) |
+            Self::HeaderNotFound(BlockHashOrNumber::Number(number)) |
+            Self::BlockBodyTransactionCount(number) |
+            Self::StateAtBlockPruned(number) |
+            Self::MissingStaticFileBlock(_, number) |
+            Self::FinalizedStaticFile(_, number) |
+            Self::UnexpectedStaticFileBlockNumber(_, number, _) |
+            Self::StorageChangesetNotFound { block_number: number, .. } |
+            Self::AccountChangesetNotFound { block_number: number, .. } |
+            Self::MismatchOfTransactionAndSenderId { block_number: number, .. } |
+            Self::BlockBodyIndicesGap { block_number: number, .. } |
+            Self::ReceiptGasInconsistency { block_number: number, .. } |
+            Self::HeaderAccumulatorGap { block_number: number, .. } |
+            Self::UnrecoverableSenders { block_number: number, .. } => Some(*number),
+            Self::StateRootMismatch(mismatch) | Self::UnwindStateRootMismatch(mismatch) => {
+                Some(mismatch.block_number)
+            }
+            _ => None,
+        }
+    }
+}
+
 impl From<reth_fs_util::FsPathError> for ProviderError {
     fn from(err: reth_fs_util::FsPathError) -> Self {
         Self::FsPathError(err.to_string())
@@ -154,6 +233,32 @@ pub struct RootMismatch {
     pub block_hash: BlockHash,
 }
 
+/// An invariant of the gas used by the receipts of a block that doesn't hold.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum ReceiptGasError {
+    /// The cumulative gas used of the last receipt doesn't match the gas used of the block.
+    #[error("cumulative gas used doesn't match the gas used of the block: {0}")]
+    BlockGasUsed(GotExpected<u64>),
+    /// The cumulative gas used is lower than the one of a previous receipt.
+    #[error(
+        "cumulative gas used {cumulative_gas_used} is lower than {previous} of a previous receipt"
+    )]
+    Decreasing {
+        /// The cumulative gas used of the receipt.
+        cumulative_gas_used: u64,
+        /// The cumulative gas used of the previous receipt.
+        previous: u64,
+    },
+    /// The gas used by the transaction exceeds its gas limit.
+    #[error("gas used {gas_used} exceeds the gas limit {gas_limit} of the transaction")]
+    ExceedsGasLimit {
+        /// The gas used by the transaction.
+        gas_used: u64,
+        /// The gas limit of the transaction.
+        gas_limit: u64,
+    },
+}
+
 /// Consistent database view error.
 #[derive(Clone, Debug, Error, PartialEq, Eq)]
 pub enum ConsistentViewError {
@@ -169,6 +274,14 @@ pub enum ConsistentViewError {
         /// The tip diff.
         tip: GotExpected<Option<B256>>,
     },
+    /// Error thrown on attempt to read through a view that was kept open for too long.
+    #[error("consistent view expired after {age:?}, maximum age is {max_age:?}")]
+    Expired {
+        /// How long the view has been open.
+        age: Duration,
+        /// The maximum age of the view.
+        max_age: Duration,
+    },
 }
 
 impl From<ConsistentViewError> for ProviderError {
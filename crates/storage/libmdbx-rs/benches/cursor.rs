@@ -1,10 +1,10 @@
 #![allow(missing_docs)]
 mod utils;
 
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
 use pprof::criterion::{Output, PProfProfiler};
 use reth_libmdbx::{ffi::*, *};
-use std::ptr;
+use std::{path::Path, ptr};
 use utils::*;
 
 /// Benchmark of iterator sequential read performance.
@@ -104,9 +104,62 @@ fn bench_get_seq_raw(c: &mut Criterion) {
     });
 }
 
+/// Benchmark of cold cache sequential read performance with and without read-ahead hints.
+fn bench_get_seq_cold(c: &mut Criterion) {
+    let n = 1_000_000;
+    let (dir, env) = setup_bench_db(n);
+    drop(env);
+
+    let mut group = c.benchmark_group("bench_get_seq_cold");
+    group.sample_size(10);
+    for pattern in [AccessPattern::Random, AccessPattern::Sequential] {
+        group.bench_function(format!("{pattern:?}"), |b| {
+            b.iter_batched(
+                || open_cold(dir.path()),
+                |env| {
+                    {
+                        let txn = env.begin_ro_txn().unwrap();
+                        let db = txn.open_db(None).unwrap();
+                        let mut cursor = txn.cursor(&db).unwrap();
+                        cursor.set_access_pattern(pattern);
+
+                        let count = cursor
+                            .iter::<ObjectLength, ObjectLength>()
+                            .map(Result::unwrap)
+                            .fold(0, |count, _| count + 1);
+                        assert_eq!(count, n);
+                    }
+                    env
+                },
+                BatchSize::PerIteration,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// Opens the environment without OS read-ahead. On Linux, the data file is evicted from the page
+/// cache first, which doesn't require privileges to drop all caches. On other platforms, the cache
+/// stays warm and the benchmark only measures the overhead of the hints.
+fn open_cold(path: &Path) -> Environment {
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::fd::AsRawFd;
+
+        let file = std::fs::File::open(path.join("mdbx.dat")).unwrap();
+        // SAFETY: the file descriptor is valid until the file is dropped.
+        unsafe { libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED) };
+    }
+
+    Environment::builder()
+        .set_flags(EnvironmentFlags { no_rdahead: true, ..Default::default() })
+        .open(path)
+        .unwrap()
+}
+
 criterion_group! {
     name = benches;
     config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
-    targets = bench_get_seq_iter, bench_get_seq_cursor, bench_get_seq_raw
+    targets = bench_get_seq_iter, bench_get_seq_cursor, bench_get_seq_raw, bench_get_seq_cold
 }
 criterion_main!(benches);
@@ -2,6 +2,7 @@ use crate::{
     error::{mdbx_result, Error, Result},
     flags::*,
     mdbx_try_optional,
+    read_ahead::{AccessPattern, ReadAhead},
     transaction::{TransactionKind, RW},
     TableObject, Transaction,
 };
@@ -21,6 +22,7 @@ where
 {
     txn: Transaction<K>,
     cursor: *mut ffi::MDBX_cursor,
+    read_ahead: ReadAhead,
 }
 
 impl<K> Cursor<K>
@@ -34,7 +36,7 @@ where
                 mdbx_result(ffi::mdbx_cursor_open(txn_ptr, dbi, &mut cursor))
             })??;
         }
-        Ok(Self { txn, cursor })
+        Ok(Self { txn, cursor, read_ahead: ReadAhead::default() })
     }
 
     fn new_at_position(other: &Self) -> Result<Self> {
@@ -43,7 +45,11 @@ where
 
             let res = ffi::mdbx_cursor_copy(other.cursor(), cursor);
 
-            let s = Self { txn: other.txn.clone(), cursor };
+            let s = Self {
+                txn: other.txn.clone(),
+                cursor,
+                read_ahead: ReadAhead::new(other.read_ahead.pattern()),
+            };
 
             mdbx_result(res)?;
 
@@ -59,6 +65,17 @@ where
         self.cursor
     }
 
+    /// Returns the expected order of the reads of the cursor.
+    pub const fn access_pattern(&self) -> AccessPattern {
+        self.read_ahead.pattern()
+    }
+
+    /// Sets the expected order of the reads of the cursor, which is used to issue read-ahead
+    /// hints. Cursors created at the position of this cursor inherit the access pattern.
+    pub fn set_access_pattern(&mut self, pattern: AccessPattern) {
+        self.read_ahead = ReadAhead::new(pattern);
+    }
+
     /// Returns an iterator over the raw key value slices.
     #[allow(clippy::needless_lifetimes)]
     pub fn iter_slices<'a>(&'a self) -> IntoIter<'a, K, Cow<'a, [u8]>, Cow<'a, [u8]>> {
@@ -100,6 +117,7 @@ where
                     op,
                 ))?;
                 assert_ne!(data_ptr, data_val.iov_base);
+                self.read_ahead.advise(data_val.iov_base);
                 let key_out = {
                     // MDBX wrote in new key
                     if key_ptr != key_val.iov_base {
@@ -566,6 +584,7 @@ where
                     let result = cursor.txn.txn_execute(|txn| {
                         match ffi::mdbx_cursor_get(cursor.cursor(), &mut key, &mut data, op) {
                             ffi::MDBX_SUCCESS => {
+                                cursor.read_ahead.advise(data.iov_base);
                                 let key = match Key::decode_val::<K>(txn, key) {
                                     Ok(v) => v,
                                     Err(e) => return Some(Err(e)),
@@ -661,6 +680,7 @@ where
                     let result = cursor.txn.txn_execute(|txn| {
                         match ffi::mdbx_cursor_get(cursor.cursor(), &mut key, &mut data, op) {
                             ffi::MDBX_SUCCESS => {
+                                cursor.read_ahead.advise(data.iov_base);
                                 let key = match Key::decode_val::<K>(txn, key) {
                                     Ok(v) => v,
                                     Err(e) => return Some(Err(e)),
@@ -20,6 +20,7 @@ pub use crate::{
     },
     error::{Error, Result},
     flags::*,
+    read_ahead::AccessPattern,
     transaction::{CommitLatency, Transaction, TransactionKind, RO, RW},
 };
 
@@ -32,6 +33,7 @@ mod database;
 mod environment;
 mod error;
 mod flags;
+mod read_ahead;
 mod transaction;
 mod txn_manager;
 
@@ -0,0 +1,127 @@
+//! Read-ahead hints for cursors walking large ranges of a database.
+//!
+//! The environment is usually opened with [`EnvironmentFlags::no_rdahead`](crate::EnvironmentFlags)
+//! set, because OS read-ahead wastes I/O on random lookups. Cursors that walk a table in order can
+//! instead ask the OS to prefetch the part of the memory map they are about to read.
+
+use libc::c_void;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Size of the memory map window that is prefetched at once by a sequential cursor.
+///
+/// Must be a multiple of the page size of every supported platform.
+pub(crate) const READ_AHEAD_WINDOW: usize = 2 * 1024 * 1024;
+
+/// Expected order of the reads of a [`Cursor`](crate::Cursor).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AccessPattern {
+    /// Keys are read in no particular order. No hints are issued, and read-ahead is left to the
+    /// environment flags.
+    #[default]
+    Random,
+    /// Keys are read in order, e.g. when walking over a range of a table. The memory map around
+    /// the read position is prefetched with `madvise(MADV_WILLNEED)` on platforms that support it,
+    /// and no hints are issued elsewhere.
+    Sequential,
+}
+
+/// Issues read-ahead hints for the values read by a cursor.
+#[derive(Debug, Default)]
+pub(crate) struct ReadAhead {
+    pattern: AccessPattern,
+    /// Start address of the window that was prefetched last, or zero.
+    window: AtomicUsize,
+}
+
+impl ReadAhead {
+    pub(crate) const fn new(pattern: AccessPattern) -> Self {
+        Self { pattern, window: AtomicUsize::new(0) }
+    }
+
+    pub(crate) const fn pattern(&self) -> AccessPattern {
+        self.pattern
+    }
+
+    /// Prefetches the window of the memory map that contains the value at `data` and the window
+    /// after it, if the cursor is sequential and hasn't prefetched them yet.
+    ///
+    /// Values of write transactions may be in dirty pages outside of the memory map, which is
+    /// harmless because the hint doesn't change the contents of the memory.
+    #[inline]
+    pub(crate) fn advise(&self, data: *const c_void) {
+        if self.pattern != AccessPattern::Sequential || data.is_null() {
+            return
+        }
+
+        let window = data as usize & !(READ_AHEAD_WINDOW - 1);
+        if self.window.swap(window, Ordering::Relaxed) != window {
+            will_need(window, 2 * READ_AHEAD_WINDOW);
+        }
+    }
+}
+
+/// Advises the OS that the memory range will be read soon, returning `true` if the advice was
+/// accepted.
+///
+/// The advice is best effort: failures, e.g. because part of the range is not mapped, are ignored.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd"
+))]
+fn will_need(addr: usize, len: usize) -> bool {
+    // SAFETY: the advice doesn't change the contents of the memory, and unmapped ranges are
+    // rejected with `ENOMEM`.
+    unsafe { libc::madvise(addr as *mut c_void, len, libc::MADV_WILLNEED) == 0 }
+}
+
+/// Read-ahead hints are not supported on this platform.
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd"
+)))]
+const fn will_need(_addr: usize, _len: usize) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advise_is_best_effort() {
+        // random cursors never issue hints
+        let random = ReadAhead::default();
+        random.advise(READ_AHEAD_WINDOW as *const c_void);
+        assert_eq!(random.window.load(Ordering::Relaxed), 0);
+
+        // unmapped memory and null pointers don't fail
+        let sequential = ReadAhead::new(AccessPattern::Sequential);
+        sequential.advise(std::ptr::null());
+        sequential.advise((READ_AHEAD_WINDOW + 1) as *const c_void);
+        assert_eq!(sequential.window.load(Ordering::Relaxed), READ_AHEAD_WINDOW);
+
+        // the window of a mapped value is prefetched once
+        let value = vec![0u8; 16];
+        sequential.advise(value.as_ptr().cast());
+        let window = value.as_ptr() as usize & !(READ_AHEAD_WINDOW - 1);
+        assert_eq!(sequential.window.load(Ordering::Relaxed), window);
+    }
+
+    #[test]
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd"
+    )))]
+    fn unsupported_platform_is_noop() {
+        assert!(!will_need(READ_AHEAD_WINDOW, READ_AHEAD_WINDOW));
+    }
+}
@@ -334,3 +334,41 @@ fn test_put_del() {
         (Cow::Borrowed(b"key2" as &[u8]), Cow::Borrowed(b"val2" as &[u8]))
     );
 }
+
+#[test]
+fn test_sequential_access_pattern() {
+    let dir = tempdir().unwrap();
+    let env = Environment::builder().open(dir.path()).unwrap();
+
+    let txn = env.begin_rw_txn().unwrap();
+    let db = txn.create_db(None, DatabaseFlags::DUP_SORT).unwrap();
+    for i in 0..1000u32 {
+        txn.put(db.dbi(), (i / 10).to_be_bytes(), i.to_be_bytes(), WriteFlags::empty()).unwrap();
+    }
+    txn.commit().unwrap();
+
+    let txn = env.begin_ro_txn().unwrap();
+    let db = txn.open_db(None).unwrap();
+    let items = |cursor: &mut Cursor<RO>| {
+        cursor.iter_start::<[u8; 4], [u8; 4]>().collect::<Result<Vec<_>>>().unwrap()
+    };
+
+    let mut random = txn.cursor(&db).unwrap();
+    let mut sequential = txn.cursor(&db).unwrap();
+    assert_eq!(sequential.access_pattern(), AccessPattern::Random);
+    sequential.set_access_pattern(AccessPattern::Sequential);
+
+    // hints don't change what is read, and are no-ops where they are not supported
+    assert_eq!(items(&mut sequential), items(&mut random));
+    assert_eq!(sequential.first::<[u8; 4], [u8; 4]>().unwrap(), random.first().unwrap());
+    assert_eq!(sequential.last::<[u8; 4], [u8; 4]>().unwrap(), random.last().unwrap());
+
+    // cursors over duplicates inherit the access pattern
+    let dups = sequential
+        .iter_dup_start::<[u8; 4], [u8; 4]>()
+        .flatten()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(dups, items(&mut random));
+    assert_eq!(sequential.clone().access_pattern(), AccessPattern::Sequential);
+}
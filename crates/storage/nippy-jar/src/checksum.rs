@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    io::{self, Read, Seek, SeekFrom},
+};
+
+/// Number of data bytes between two checkpoints of a [`DataChecksum`].
+pub const CHECKSUM_CHECKPOINT_INTERVAL: u64 = 64 * 1024 * 1024;
+
+/// Size of the buffer used to read the data file when computing its checksum.
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// CRC32 checksum of the data file of a [`NippyJar`](crate::NippyJar).
+///
+/// The checksum is extended as data is appended. It also keeps the checksum at every multiple of
+/// [`CHECKSUM_CHECKPOINT_INTERVAL`] bytes, so that pruning rows only needs to re-read the data
+/// after the last checkpoint.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataChecksum {
+    /// Length of the checksummed data.
+    len: u64,
+    /// CRC32 of the data.
+    crc: u32,
+    /// CRC32 of the data up to each multiple of [`CHECKSUM_CHECKPOINT_INTERVAL`].
+    checkpoints: Vec<u32>,
+}
+
+impl DataChecksum {
+    /// Computes the checksum of all data read from the reader.
+    pub fn compute(reader: impl Read) -> io::Result<Self> {
+        let mut checksum = Self::default();
+        checksum.update_from(reader)?;
+        Ok(checksum)
+    }
+
+    /// Returns the length of the checksummed data.
+    pub const fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if no data was checksummed.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the CRC32 of the data.
+    pub const fn crc(&self) -> u32 {
+        self.crc
+    }
+
+    /// Extends the checksum with appended data.
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let to_checkpoint =
+                CHECKSUM_CHECKPOINT_INTERVAL - self.len % CHECKSUM_CHECKPOINT_INTERVAL;
+            let (chunk, rest) = data.split_at(data.len().min(to_checkpoint as usize));
+
+            let mut hasher = crc32fast::Hasher::new_with_initial(self.crc);
+            hasher.update(chunk);
+            self.crc = hasher.finalize();
+            self.len += chunk.len() as u64;
+
+            if self.len % CHECKSUM_CHECKPOINT_INTERVAL == 0 {
+                self.checkpoints.push(self.crc);
+            }
+            data = rest;
+        }
+    }
+
+    /// Brings the checksum to the first `len` bytes of the data.
+    ///
+    /// If the data shrunk, the checksum is reset to the last checkpoint before `len`. The data
+    /// between the checksummed length and `len` is then read from `reader`.
+    pub fn resize(&mut self, len: u64, mut reader: impl Read + Seek) -> io::Result<()> {
+        if len < self.len {
+            let checkpoints = (len / CHECKSUM_CHECKPOINT_INTERVAL) as usize;
+            self.checkpoints.truncate(checkpoints);
+            self.len = checkpoints as u64 * CHECKSUM_CHECKPOINT_INTERVAL;
+            self.crc = self.checkpoints.last().copied().unwrap_or_default();
+        }
+
+        if len > self.len {
+            reader.seek(SeekFrom::Start(self.len))?;
+            let remaining = len - self.len;
+            self.update_from(reader.take(remaining))?;
+            if self.len != len {
+                return Err(io::ErrorKind::UnexpectedEof.into())
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Extends the checksum with all data read from the reader.
+    fn update_from(&mut self, mut reader: impl Read) -> io::Result<()> {
+        let mut buf = vec![0; READ_BUFFER_SIZE];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => return Ok(()),
+                Ok(read) => self.update(&buf[..read]),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl fmt::Display for DataChecksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "crc32 {:#010x} of {} bytes", self.crc, self.len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn resize_from_checkpoint() {
+        let data = (0..CHECKSUM_CHECKPOINT_INTERVAL * 2 + 100).map(|i| i as u8).collect::<Vec<_>>();
+
+        let mut checksum = DataChecksum::default();
+        for chunk in data.chunks(1_000_003) {
+            checksum.update(chunk);
+        }
+        assert_eq!(checksum, DataChecksum::compute(data.as_slice()).unwrap());
+        assert_eq!(checksum.crc(), crc32fast::hash(&data));
+        assert_eq!(checksum.checkpoints.len(), 2);
+
+        // shrinking only re-reads the data after the last checkpoint
+        let len = CHECKSUM_CHECKPOINT_INTERVAL + 10;
+        let mut tail = Cursor::new(data.as_slice());
+        checksum.resize(len, &mut tail).unwrap();
+        assert_eq!(tail.position(), len);
+        assert_eq!(checksum, DataChecksum::compute(&data[..len as usize]).unwrap());
+
+        // growing reads the appended data
+        checksum.resize(data.len() as u64, Cursor::new(data.as_slice())).unwrap();
+        assert_eq!(checksum, DataChecksum::compute(data.as_slice()).unwrap());
+
+        // the data can't be shorter than the requested length
+        assert!(checksum.resize(data.len() as u64 + 1, Cursor::new(data.as_slice())).is_err());
+    }
+}
@@ -59,4 +59,13 @@ pub enum NippyJarError {
     FrozenJar,
     #[error("File is in an inconsistent state.")]
     InconsistentState,
+    #[error("data file {path:?} is corrupted: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        /// The path of the data file.
+        path: std::path::PathBuf,
+        /// The checksum written on the last commit.
+        expected: crate::DataChecksum,
+        /// The checksum of the data on disk.
+        actual: crate::DataChecksum,
+    },
 }
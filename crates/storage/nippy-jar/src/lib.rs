@@ -18,6 +18,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     error::Error as StdError,
     fs::{File, OpenOptions},
+    io::BufReader,
     ops::Range,
     path::{Path, PathBuf},
 };
@@ -37,6 +38,9 @@ use phf::{Fmph, Functions, GoFmph, PerfectHashingFunction};
 mod error;
 pub use error::NippyJarError;
 
+mod checksum;
+pub use checksum::{DataChecksum, CHECKSUM_CHECKPOINT_INTERVAL};
+
 mod cursor;
 pub use cursor::NippyJarCursor;
 
@@ -48,6 +52,7 @@ const NIPPY_JAR_VERSION: usize = 1;
 const INDEX_FILE_EXTENSION: &str = "idx";
 const OFFSETS_FILE_EXTENSION: &str = "off";
 const CONFIG_FILE_EXTENSION: &str = "conf";
+const CHECKSUM_FILE_EXTENSION: &str = "sum";
 
 /// A [`RefRow`] is a list of column value slices pointing to either an internal buffer or a
 /// memory-mapped file.
@@ -286,13 +291,63 @@ impl<H: NippyJarHeader> NippyJar<H> {
         self.path.with_extension(CONFIG_FILE_EXTENSION)
     }
 
+    /// Returns the path for the checksum file
+    pub fn checksum_path(&self) -> PathBuf {
+        self.path.with_extension(CHECKSUM_FILE_EXTENSION)
+    }
+
+    /// Loads the checksum of the data file, if the jar has one.
+    ///
+    /// Jars created before checksums were introduced don't have a checksum file.
+    pub fn load_checksum(&self) -> Result<Option<DataChecksum>, NippyJarError> {
+        let checksum_path = self.checksum_path();
+        if !checksum_path.exists() {
+            return Ok(None)
+        }
+        let checksum_file = File::open(&checksum_path)
+            .map_err(|err| reth_fs_util::FsPathError::open(err, checksum_path))?;
+        Ok(Some(bincode::deserialize_from(checksum_file)?))
+    }
+
+    /// Re-reads the data file from `offset` and verifies it against the checksum written on the
+    /// last commit.
+    ///
+    /// Verification starts at the last checksum checkpoint at or before `offset`, so `0` verifies
+    /// the whole data file. Only the committed data is verified, so that data appended by a
+    /// concurrent writer is ignored.
+    ///
+    /// Returns [`NippyJarError::ChecksumMismatch`] if the data is corrupted. Jars without a
+    /// checksum file are not verified.
+    pub fn verify_checksum(&self, offset: u64) -> Result<(), NippyJarError> {
+        let Some(expected) = self.load_checksum()? else { return Ok(()) };
+        let data_file = File::open(self.data_path())?;
+        let len = data_file.metadata()?.len().min(expected.len());
+        let mut data = BufReader::new(data_file);
+
+        let mut actual = expected.clone();
+        actual.resize(offset.min(len), &mut data)?;
+        actual.resize(len, &mut data)?;
+        if actual != expected {
+            return Err(NippyJarError::ChecksumMismatch {
+                path: self.data_path().to_path_buf(),
+                expected,
+                actual,
+            })
+        }
+        Ok(())
+    }
+
     /// Deletes from disk this [`NippyJar`] alongside every satellite file.
     pub fn delete(self) -> Result<(), NippyJarError> {
         // TODO(joshie): ensure consistency on unexpected shutdown
 
-        for path in
-            [self.data_path().into(), self.index_path(), self.offsets_path(), self.config_path()]
-        {
+        for path in [
+            self.data_path().into(),
+            self.index_path(),
+            self.offsets_path(),
+            self.config_path(),
+            self.checksum_path(),
+        ] {
             if path.exists() {
                 reth_fs_util::remove_file(path)?;
             }
@@ -432,26 +487,37 @@ impl<H: NippyJarHeader> NippyJar<H> {
 
     /// Writes all necessary configuration to file.
     fn freeze_config(&self) -> Result<(), NippyJarError> {
-        // Atomic writes are hard: <https://github.com/paradigmxyz/reth/issues/8622>
         let mut tmp_path = self.config_path();
         tmp_path.set_extension(".tmp");
+        atomic_write(&self.config_path(), &tmp_path, self)
+    }
 
-        // Write to temporary file
-        let mut file = File::create(&tmp_path)?;
-        bincode::serialize_into(&mut file, &self)?;
+    /// Writes the checksum of the data file to file.
+    fn freeze_checksum(&self, checksum: &DataChecksum) -> Result<(), NippyJarError> {
+        let tmp_path = self.path.with_extension(format!("{CHECKSUM_FILE_EXTENSION}.tmp"));
+        atomic_write(&self.checksum_path(), &tmp_path, checksum)
+    }
+}
 
-        // fsync() file
-        file.sync_all()?;
+/// Serializes the value to a temporary file and renames it to `path`.
+fn atomic_write(path: &Path, tmp_path: &Path, value: &impl Serialize) -> Result<(), NippyJarError> {
+    // Atomic writes are hard: <https://github.com/paradigmxyz/reth/issues/8622>
 
-        // Rename file, not move
-        reth_fs_util::rename(&tmp_path, self.config_path())?;
+    // Write to temporary file
+    let mut file = File::create(tmp_path)?;
+    bincode::serialize_into(&mut file, value)?;
 
-        // fsync() dir
-        if let Some(parent) = tmp_path.parent() {
-            OpenOptions::new().read(true).open(parent)?.sync_all()?;
-        }
-        Ok(())
+    // fsync() file
+    file.sync_all()?;
+
+    // Rename file, not move
+    reth_fs_util::rename(tmp_path, path)?;
+
+    // fsync() dir
+    if let Some(parent) = tmp_path.parent() {
+        OpenOptions::new().read(true).open(parent)?.sync_all()?;
     }
+    Ok(())
 }
 
 impl<H: NippyJarHeader> InclusionFilter for NippyJar<H> {
@@ -1093,6 +1159,38 @@ mod tests {
         test_append_consistency_partial_commit(file_path.path(), &col1, &col2);
     }
 
+    #[test]
+    fn test_checksum() {
+        let (col1, col2) = test_data(None);
+        let num_columns = 2;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        // The checksum is extended on every commit
+        append_two_rows(num_columns, file_path.path(), &col1, &col2);
+        let nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        nippy.verify_checksum(0).unwrap();
+
+        // and brought back to the remaining data on pruning
+        let mut writer = NippyJarWriter::new(nippy, ConsistencyFailStrategy::Heal).unwrap();
+        writer.prune_rows(1).unwrap();
+        let nippy = writer.into_jar();
+        nippy.verify_checksum(0).unwrap();
+        assert_eq!(
+            nippy.load_checksum().unwrap().unwrap().crc(),
+            crc32fast::hash(&[col1[0].as_slice(), col2[0].as_slice()].concat())
+        );
+
+        // A corrupted data file is detected
+        let mut data = std::fs::read(nippy.data_path()).unwrap();
+        data[0] ^= 1;
+        std::fs::write(nippy.data_path(), data).unwrap();
+        assert!(matches!(nippy.verify_checksum(0), Err(NippyJarError::ChecksumMismatch { .. })));
+
+        // Jars without a checksum file are not verified
+        std::fs::remove_file(nippy.checksum_path()).unwrap();
+        nippy.verify_checksum(0).unwrap();
+    }
+
     #[test]
     fn test_pruner() {
         let (col1, col2) = test_data(None);
@@ -1,4 +1,6 @@
-use crate::{compression::Compression, ColumnResult, NippyJar, NippyJarError, NippyJarHeader};
+use crate::{
+    compression::Compression, ColumnResult, DataChecksum, NippyJar, NippyJarError, NippyJarHeader,
+};
 use std::{
     cmp::Ordering,
     fs::{File, OpenOptions},
@@ -22,6 +24,10 @@ const OFFSET_SIZE_BYTES: u8 = 8;
 ///
 /// ## Data file layout
 /// The data file is represented just as a sequence of bytes of data without any delimiters
+///
+/// ## Checksum file layout
+/// The checksum file contains the [`DataChecksum`] of the data file, which is extended as data is
+/// appended and written on `commit()`.
 #[derive(Debug)]
 pub struct NippyJarWriter<H: NippyJarHeader = ()> {
     /// Associated [`NippyJar`], containing all necessary configurations for data
@@ -41,6 +47,8 @@ pub struct NippyJarWriter<H: NippyJarHeader = ()> {
     column: usize,
     /// Whether the writer has changed data that needs to be committed.
     dirty: bool,
+    /// Checksum of the data file including uncommitted data. Loaded when it's first needed.
+    checksum: Option<DataChecksum>,
 }
 
 impl<H: NippyJarHeader> NippyJarWriter<H> {
@@ -67,6 +75,7 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
             offsets: Vec::with_capacity(1_000_000),
             column: 0,
             dirty: false,
+            checksum: None,
         };
 
         // If we are opening a previously created jar, we need to check its consistency, and make
@@ -245,9 +254,27 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         self.offsets_file.seek(SeekFrom::End(0))?;
         self.data_file.seek(SeekFrom::End(0))?;
 
+        // The data file may have been truncated
+        self.checksum = None;
+
         Ok(())
     }
 
+    /// Returns the checksum of the data file, loading it and checksumming any data that was
+    /// written after it on first use.
+    ///
+    /// Must not be called while the data file has buffered writes that are not accounted for in
+    /// the checksum.
+    fn checksum(&mut self) -> Result<&mut DataChecksum, NippyJarError> {
+        if self.checksum.is_none() {
+            let mut checksum = self.jar.load_checksum()?.unwrap_or_default();
+            let data_len = self.data_file.get_ref().metadata()?.len();
+            checksum.resize(data_len, File::open(self.jar.data_path())?)?;
+            self.checksum = Some(checksum);
+        }
+        Ok(self.checksum.as_mut().expect("loaded"))
+    }
+
     /// Appends rows to data file.  `fn commit()` should be called to flush offsets and config to
     /// disk.
     ///
@@ -314,14 +341,20 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
 
     /// Writes column to data file. If it's the last column of the row, call `finalize_row()`
     fn write_column(&mut self, value: &[u8]) -> Result<usize, NippyJarError> {
+        self.checksum()?;
+        let checksum = self.checksum.as_mut().expect("loaded");
+
         self.uncompressed_row_size += value.len();
         let len = if let Some(compression) = &self.jar.compressor {
             let before = self.tmp_buf.len();
             let len = compression.compress_to(value, &mut self.tmp_buf)?;
-            self.data_file.write_all(&self.tmp_buf[before..before + len])?;
+            let compressed = &self.tmp_buf[before..before + len];
+            self.data_file.write_all(compressed)?;
+            checksum.update(compressed);
             len
         } else {
             self.data_file.write_all(value)?;
+            checksum.update(value);
             value.len()
         };
 
@@ -408,6 +441,11 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         self.offsets_file.seek(SeekFrom::End(0))?;
         self.data_file.seek(SeekFrom::End(0))?;
 
+        let data_len = self.data_file.get_ref().metadata()?.len();
+        let data_path = self.jar.data_path().to_path_buf();
+        self.checksum()?.resize(data_len, File::open(data_path)?)?;
+        self.freeze_checksum()?;
+
         self.jar.rows = self.jar.rows.saturating_sub(num_rows);
         if self.jar.rows == 0 {
             self.jar.max_row_size = 0;
@@ -434,6 +472,7 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         self.data_file.get_ref().sync_all()?;
 
         self.commit_offsets()?;
+        self.freeze_checksum()?;
 
         // Flushes `max_row_size` and total `rows` to disk.
         self.jar.freeze_config()?;
@@ -447,6 +486,7 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         self.data_file.flush()?;
 
         self.commit_offsets_without_sync_all()?;
+        self.freeze_checksum()?;
 
         // Flushes `max_row_size` and total `rows` to disk.
         self.jar.freeze_config()?;
@@ -455,6 +495,12 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         Ok(())
     }
 
+    /// Writes the checksum of the data file to disk.
+    fn freeze_checksum(&mut self) -> Result<(), NippyJarError> {
+        self.checksum()?;
+        self.jar.freeze_checksum(self.checksum.as_ref().expect("loaded"))
+    }
+
     /// Flushes offsets to disk.
     pub(crate) fn commit_offsets(&mut self) -> Result<(), NippyJarError> {
         self.commit_offsets_inner()?;
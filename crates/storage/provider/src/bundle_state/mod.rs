@@ -2,6 +2,7 @@
 //! This module contains all the logic related to bundle state.
 mod bundle_state_with_receipts;
 mod hashed_state_changes;
+mod receipts_gas;
 mod state_changes;
 mod state_reverts;
 
@@ -9,5 +10,6 @@ pub use bundle_state_with_receipts::{
     AccountRevertInit, BundleStateInit, ExecutionOutcome, OriginalValuesKnown, RevertsInit,
 };
 pub use hashed_state_changes::HashedStateChanges;
+pub use receipts_gas::{verify_receipts_gas, BlockGas};
 pub use state_changes::StateChanges;
 pub use state_reverts::{StateReverts, StorageRevertsIter};
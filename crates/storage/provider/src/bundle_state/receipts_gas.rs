@@ -0,0 +1,159 @@
+use reth_primitives::{BlockNumber, GotExpected, Header, Receipt, Receipts, TransactionSigned};
+use reth_storage_errors::provider::{ProviderError, ProviderResult, ReceiptGasError};
+
+/// The gas used by a block and the gas limits of its transactions, which the receipts of the block
+/// are checked against before they are written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockGas {
+    /// The block number.
+    pub number: BlockNumber,
+    /// The gas used by the block.
+    pub gas_used: u64,
+    /// The gas limits of the transactions of the block.
+    pub tx_gas_limits: Vec<u64>,
+}
+
+impl BlockGas {
+    /// Creates a new instance from the header and the transactions of a block.
+    pub fn new(header: &Header, transactions: &[TransactionSigned]) -> Self {
+        Self {
+            number: header.number,
+            gas_used: header.gas_used,
+            tx_gas_limits: transactions.iter().map(|tx| tx.gas_limit()).collect(),
+        }
+    }
+
+    /// Checks that the receipts of the block are consistent with the gas used by the block:
+    ///
+    /// - the cumulative gas used never decreases,
+    /// - the gas used by a transaction, the difference to the cumulative gas used of the previous
+    ///   receipt, doesn't exceed its gas limit,
+    /// - the cumulative gas used of the last receipt equals the gas used of the block.
+    ///
+    /// Missing receipts, e.g. because they were pruned, are skipped.
+    pub fn verify_receipts<'a>(
+        &self,
+        receipts: impl IntoIterator<Item = Option<&'a Receipt>>,
+    ) -> ProviderResult<()> {
+        let inconsistency = |tx_index, kind| ProviderError::ReceiptGasInconsistency {
+            block_number: self.number,
+            tx_index,
+            kind,
+        };
+
+        // the highest cumulative gas used so far
+        let mut highest = 0;
+        // the cumulative gas used of the previous receipt, if it's not missing
+        let mut previous = Some(0);
+        let mut last = None;
+        for (tx_index, receipt) in receipts.into_iter().enumerate() {
+            last = Some((tx_index, receipt.map(|receipt| receipt.cumulative_gas_used)));
+            let Some(receipt) = receipt else {
+                previous = None;
+                continue
+            };
+
+            let cumulative_gas_used = receipt.cumulative_gas_used;
+            if cumulative_gas_used < highest {
+                return Err(inconsistency(
+                    tx_index,
+                    ReceiptGasError::Decreasing { cumulative_gas_used, previous: highest },
+                ))
+            }
+            highest = cumulative_gas_used;
+
+            if let (Some(previous), Some(gas_limit)) = (previous, self.tx_gas_limits.get(tx_index))
+            {
+                let gas_used = cumulative_gas_used - previous;
+                if gas_used > *gas_limit {
+                    return Err(inconsistency(
+                        tx_index,
+                        ReceiptGasError::ExceedsGasLimit { gas_used, gas_limit: *gas_limit },
+                    ))
+                }
+            }
+            previous = Some(cumulative_gas_used);
+        }
+
+        if let Some((tx_index, Some(cumulative_gas_used))) = last {
+            if cumulative_gas_used != self.gas_used {
+                return Err(inconsistency(
+                    tx_index,
+                    ReceiptGasError::BlockGasUsed(GotExpected {
+                        got: cumulative_gas_used,
+                        expected: self.gas_used,
+                    }),
+                ))
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks the receipts of consecutive blocks, e.g. of an
+/// [`ExecutionOutcome`](crate::ExecutionOutcome), see [`BlockGas::verify_receipts`].
+///
+/// Blocks without receipts are skipped.
+pub fn verify_receipts_gas(blocks: &[BlockGas], receipts: &Receipts) -> ProviderResult<()> {
+    for (block, receipts) in blocks.iter().zip(receipts.iter()) {
+        block.verify_receipts(receipts.iter().map(Option::as_ref))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+
+    fn receipt(cumulative_gas_used: u64) -> Option<Receipt> {
+        Some(Receipt { cumulative_gas_used, ..Default::default() })
+    }
+
+    #[test]
+    fn verify_block_receipts() {
+        let block = BlockGas { number: 7, gas_used: 50_000, tx_gas_limits: vec![21_000, 30_000] };
+        let verify = |receipts: &[Option<Receipt>]| {
+            block.verify_receipts(receipts.iter().map(Option::as_ref))
+        };
+
+        assert_matches!(verify(&[receipt(21_000), receipt(50_000)]), Ok(()));
+        // pruned receipts are skipped
+        assert_matches!(verify(&[None, receipt(50_000)]), Ok(()));
+        assert_matches!(verify(&[None, None]), Ok(()));
+
+        assert_matches!(
+            verify(&[receipt(21_000), receipt(60_000)]),
+            Err(ProviderError::ReceiptGasInconsistency {
+                block_number: 7,
+                tx_index: 1,
+                kind: ReceiptGasError::ExceedsGasLimit { gas_used: 39_000, gas_limit: 30_000 },
+            })
+        );
+        assert_matches!(
+            verify(&[receipt(30_000), receipt(20_000)]),
+            Err(ProviderError::ReceiptGasInconsistency {
+                block_number: 7,
+                tx_index: 0,
+                kind: ReceiptGasError::ExceedsGasLimit { gas_used: 30_000, gas_limit: 21_000 },
+            })
+        );
+        assert_matches!(
+            verify(&[receipt(20_000), None, receipt(10_000)]),
+            Err(ProviderError::ReceiptGasInconsistency {
+                tx_index: 2,
+                kind: ReceiptGasError::Decreasing { cumulative_gas_used: 10_000, previous: 20_000 },
+                ..
+            })
+        );
+        assert_matches!(
+            verify(&[receipt(21_000), receipt(45_000)]),
+            Err(ProviderError::ReceiptGasInconsistency {
+                tx_index: 1,
+                kind: ReceiptGasError::BlockGasUsed(GotExpected { got: 45_000, expected: 50_000 }),
+                ..
+            })
+        );
+    }
+}
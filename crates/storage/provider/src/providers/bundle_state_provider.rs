@@ -1,10 +1,12 @@
 use crate::{
-    AccountReader, BlockHashReader, ExecutionDataProvider, StateProvider, StateRootProvider,
+    AccountReader, BlockHashReader, CanonicalHashesIter, ExecutionDataProvider, StateProvider,
+    StateRootProvider,
 };
 use reth_primitives::{proofs::AccountProof, Account, Address, BlockNumber, Bytecode, B256};
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use reth_trie::updates::TrieUpdates;
 use revm::db::BundleState;
+use std::ops::Range;
 
 /// A state provider that resolves to data from either a wrapped [`crate::ExecutionOutcome`]
 /// or an underlying state provider.
@@ -40,12 +42,22 @@ impl<SP: StateProvider, EDP: ExecutionDataProvider> BlockHashReader
         self.state_provider.block_hash(block_number)
     }
 
-    fn canonical_hashes_range(
+    fn canonical_hashes_iter(
         &self,
-        _start: BlockNumber,
-        _end: BlockNumber,
-    ) -> ProviderResult<Vec<B256>> {
-        unimplemented!()
+        range: Range<BlockNumber>,
+    ) -> ProviderResult<CanonicalHashesIter<'_>> {
+        // The execution data holds the hashes of the blocks on top of the state provider, so the
+        // range is split at the first block it knows.
+        let split = range
+            .clone()
+            .find(|number| self.block_execution_data_provider.block_hash(*number).is_some())
+            .unwrap_or(range.end);
+
+        let below = self.state_provider.canonical_hashes_iter(range.start..split)?;
+        let above = (split..range.end).map_while(|number| {
+            self.block_execution_data_provider.block_hash(number).map(|hash| Ok((number, hash)))
+        });
+        Ok(Box::new(below.chain(above)))
     }
 }
 
@@ -112,3 +124,64 @@ impl<SP: StateProvider, EDP: ExecutionDataProvider> StateProvider for BundleStat
         Err(ProviderError::StateRootNotAvailableForHistoricalBlock)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::create_test_provider_factory, ExecutionOutcome};
+    use reth_db::tables;
+    use reth_db_api::transaction::DbTxMut;
+    use std::collections::BTreeMap;
+
+    struct TestExecutionData {
+        execution_outcome: ExecutionOutcome,
+        block_hashes: BTreeMap<BlockNumber, B256>,
+    }
+
+    impl ExecutionDataProvider for TestExecutionData {
+        fn execution_outcome(&self) -> &ExecutionOutcome {
+            &self.execution_outcome
+        }
+
+        fn block_hash(&self, block_number: BlockNumber) -> Option<B256> {
+            self.block_hashes.get(&block_number).copied()
+        }
+    }
+
+    #[test]
+    fn canonical_hashes_across_database_and_execution_data() {
+        let hash = |number: u64| B256::with_last_byte(number as u8 + 1);
+
+        // blocks 0..5 are in the database, blocks 5..8 only in the execution data
+        let factory = create_test_provider_factory();
+        let provider_rw = factory.provider_rw().unwrap();
+        for number in 0..5 {
+            provider_rw.tx_ref().put::<tables::CanonicalHeaders>(number, hash(number)).unwrap();
+        }
+        provider_rw.commit().unwrap();
+
+        let provider = BundleStateProvider::new(
+            factory.latest().unwrap(),
+            TestExecutionData {
+                execution_outcome: ExecutionOutcome::default(),
+                block_hashes: (5..8).map(|number| (number, hash(number))).collect(),
+            },
+        );
+
+        let hashes = provider
+            .canonical_hashes_iter(2..7)
+            .unwrap()
+            .collect::<ProviderResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(hashes, (2..7).map(|number| (number, hash(number))).collect::<Vec<_>>());
+        assert_eq!(
+            provider.canonical_hashes_range(2, 7).unwrap(),
+            hashes.into_iter().map(|(_, hash)| hash).collect::<Vec<_>>()
+        );
+
+        // ranges on either side of the boundary and beyond the tip
+        assert_eq!(provider.canonical_hashes_range(0, 3).unwrap(), vec![hash(0), hash(1), hash(2)]);
+        assert_eq!(provider.canonical_hashes_range(6, 10).unwrap(), vec![hash(6), hash(7)]);
+        assert!(provider.canonical_hashes_range(8, 10).unwrap().is_empty());
+    }
+}
@@ -2,7 +2,10 @@ use crate::{BlockNumReader, DatabaseProviderFactory, DatabaseProviderRO, HeaderP
 use reth_db_api::database::Database;
 use reth_primitives::{GotExpected, B256};
 use reth_storage_errors::provider::ProviderResult;
-use std::marker::PhantomData;
+use std::{
+    marker::PhantomData,
+    time::{Duration, Instant},
+};
 
 pub use reth_storage_errors::provider::ConsistentViewError;
 
@@ -79,3 +82,106 @@ where
         Ok(provider_ro)
     }
 }
+
+/// The default maximum age of a [`ConsistentReadView`].
+pub const DEFAULT_CONSISTENT_READ_VIEW_MAX_AGE: Duration = Duration::from_secs(30);
+
+/// A read-only view pinned to a single database transaction.
+///
+/// Unlike [`ConsistentDbView`], which only detects that the tip moved, all reads through this view
+/// are served by the same read transaction and therefore observe the same committed database
+/// snapshot, even if a writer commits in between.
+///
+/// ## Usage
+///
+/// The view is meant for request handlers that need several reads to agree with each other, e.g.
+/// a transaction and its receipt. It should be dropped as soon as the reads are done, since an open
+/// read transaction prevents the database from reusing pages freed by newer commits. For this
+/// reason, the view closes its read transaction once it is older than its maximum age and returns
+/// [`ConsistentViewError::Expired`] instead of handing out its provider.
+///
+/// Note: static files are append-only and not part of the database transaction. Data that is only
+/// stored in static files can be newer than the pinned snapshot, so blocks should be resolved
+/// through the view (e.g. hash to number) before reading their static file data.
+#[derive(Debug)]
+pub struct ConsistentReadView<DB: Database, Provider> {
+    /// The factory the view was created from, used for staleness checks.
+    factory: Provider,
+    /// The pinned read-only provider, dropped once the view expired.
+    provider: Option<DatabaseProviderRO<DB>>,
+    /// The tip of the pinned snapshot, read on first use.
+    tip: Option<Option<B256>>,
+    /// When the view was created.
+    opened_at: Instant,
+    /// How long the view may be used.
+    max_age: Duration,
+}
+
+impl<DB, Provider> ConsistentReadView<DB, Provider>
+where
+    DB: Database,
+    Provider: DatabaseProviderFactory<DB>,
+{
+    /// Creates a new view pinned to a new read transaction.
+    pub fn new(factory: Provider, max_age: Duration) -> ProviderResult<Self> {
+        let provider = factory.database_provider_ro()?;
+        Ok(Self {
+            factory,
+            provider: Some(provider),
+            tip: None,
+            opened_at: Instant::now(),
+            max_age,
+        })
+    }
+
+    /// Returns the hash of the tip of the pinned snapshot.
+    ///
+    /// The tip is read on first use, see [`Self::provider`].
+    pub fn tip(&mut self) -> ProviderResult<Option<B256>> {
+        if let Some(tip) = self.tip {
+            return Ok(tip)
+        }
+        let tip = tip_hash(self.provider()?)?;
+        self.tip = Some(tip);
+        Ok(tip)
+    }
+
+    /// Returns the pinned provider.
+    ///
+    /// Returns [`ConsistentViewError::Expired`] if the view is older than its maximum age. The
+    /// read transaction is closed right away in that case.
+    pub fn provider(&mut self) -> ProviderResult<&DatabaseProviderRO<DB>> {
+        self.ensure_not_expired()?;
+        Ok(self.provider.as_ref().expect("provider is only dropped once the view expired"))
+    }
+
+    /// Consumes the view and returns the pinned provider.
+    ///
+    /// Returns [`ConsistentViewError::Expired`] if the view is older than its maximum age.
+    pub fn into_provider(mut self) -> ProviderResult<DatabaseProviderRO<DB>> {
+        self.ensure_not_expired()?;
+        Ok(self.provider.take().expect("provider is only dropped once the view expired"))
+    }
+
+    /// Returns `true` if no new block was committed since the pinned snapshot.
+    pub fn is_current(&mut self) -> ProviderResult<bool> {
+        Ok(tip_hash(&self.factory.database_provider_ro()?)? == self.tip()?)
+    }
+
+    /// Closes the read transaction and returns [`ConsistentViewError::Expired`] if the view is
+    /// older than its maximum age.
+    fn ensure_not_expired(&mut self) -> ProviderResult<()> {
+        let age = self.opened_at.elapsed();
+        if age > self.max_age {
+            self.provider = None;
+            return Err(ConsistentViewError::Expired { age, max_age: self.max_age }.into())
+        }
+        Ok(())
+    }
+}
+
+/// Returns the hash of the latest stored header.
+fn tip_hash<DB: Database>(provider: &DatabaseProviderRO<DB>) -> ProviderResult<Option<B256>> {
+    let last_num = provider.last_block_number()?;
+    Ok(provider.sealed_header(last_num)?.map(|h| h.hash()))
+}
@@ -1,4 +1,4 @@
-use metrics::Histogram;
+use metrics::{Counter, Histogram};
 use reth_metrics::Metrics;
 use std::time::{Duration, Instant};
 
@@ -147,3 +147,16 @@ impl DatabaseProviderMetrics {
         }
     }
 }
+
+/// Metrics of senders missing from the `TransactionSenders` table.
+///
+/// Missing senders are only expected if the table is damaged, e.g. by an interrupted sender
+/// recovery stage or a pruning misconfiguration.
+#[derive(Metrics)]
+#[metrics(scope = "storage.providers.database.senders")]
+pub(crate) struct MissingSendersMetrics {
+    /// Number of missing senders recovered from their transactions
+    pub(crate) recovered: Counter,
+    /// Number of recovered senders written back to the table
+    pub(crate) repaired: Counter,
+}
@@ -1,30 +1,38 @@
 use crate::{
-    providers::{state::latest::LatestStateProvider, StaticFileProvider},
+    providers::{
+        database::metrics::MissingSendersMetrics, state::latest::LatestStateProvider,
+        ConsistentReadView, StaticFileProvider, DEFAULT_CONSISTENT_READ_VIEW_MAX_AGE,
+    },
     to_range,
     traits::{BlockSource, ReceiptProvider},
-    BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider, DatabaseProviderFactory,
-    EvmEnvProvider, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, ProviderError,
+    BlockHashReader, BlockNumReader, BlockReader, CanonicalHashesIter, ChainSpecProvider,
+    DatabaseProviderFactory, EvmEnvProvider, HashedStateRangeProvider, HeaderAccumulatorProvider,
+    HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HistoryDirection, ProviderError,
     PruneCheckpointReader, RequestsProvider, StageCheckpointReader, StateProviderBox,
-    StaticFileProviderFactory, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    StaticFileProviderFactory, TransactionHistoryProvider, TransactionHistorySettings,
+    TransactionVariant, TransactionsProvider, WithdrawalsProvider,
 };
-use reth_db::{init_db, mdbx::DatabaseArguments, DatabaseEnv};
-use reth_db_api::{database::Database, models::StoredBlockBodyIndices};
+use reth_db::{init_db, mdbx::DatabaseArguments, tables, DatabaseEnv};
+use reth_db_api::{database::Database, models::StoredBlockBodyIndices, transaction::DbTx};
 use reth_errors::{RethError, RethResult};
 use reth_evm::ConfigureEvmEnv;
 use reth_primitives::{
-    Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, ChainInfo,
-    ChainSpec, Header, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader,
-    StaticFileSegment, TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash,
-    TxNumber, Withdrawal, Withdrawals, B256, U256,
+    accumulator::MmrProof, Address, Block, BlockHash, BlockHashOrNumber, BlockNumber,
+    BlockWithSenders, Bytes, ChainInfo, ChainSpec, Header, Receipt, SealedBlock,
+    SealedBlockWithSenders, SealedHeader, StaticFileSegment, StorageEntry, TransactionMeta,
+    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256,
+    U256,
 };
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
 use reth_storage_errors::provider::ProviderResult;
+use reth_trie::TrieAccount;
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use std::{
-    ops::{RangeBounds, RangeInclusive},
+    ops::{Range, RangeBounds, RangeInclusive},
     path::Path,
     sync::Arc,
+    time::Duration,
 };
 use tokio::sync::watch;
 use tracing::trace;
@@ -45,6 +53,10 @@ pub struct ProviderFactory<DB> {
     chain_spec: Arc<ChainSpec>,
     /// Static File Provider
     static_file_provider: StaticFileProvider,
+    /// Maximum age of views created by [`ProviderFactory::consistent_view`].
+    consistent_view_max_age: Duration,
+    /// Metrics of senders missing from the senders table, shared by all providers.
+    senders_metrics: Arc<MissingSendersMetrics>,
 }
 
 impl<DB> ProviderFactory<DB> {
@@ -54,7 +66,19 @@ impl<DB> ProviderFactory<DB> {
         chain_spec: Arc<ChainSpec>,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { db: Arc::new(db), chain_spec, static_file_provider }
+        Self {
+            db: Arc::new(db),
+            chain_spec,
+            static_file_provider,
+            consistent_view_max_age: DEFAULT_CONSISTENT_READ_VIEW_MAX_AGE,
+            senders_metrics: Default::default(),
+        }
+    }
+
+    /// Sets the maximum age of views created by [`ProviderFactory::consistent_view`].
+    pub const fn with_consistent_view_max_age(mut self, max_age: Duration) -> Self {
+        self.consistent_view_max_age = max_age;
+        self
     }
 
     /// Enables metrics on the static file provider.
@@ -88,6 +112,8 @@ impl ProviderFactory<DatabaseEnv> {
             db: Arc::new(init_db(path, args).map_err(RethError::msg)?),
             chain_spec,
             static_file_provider,
+            consistent_view_max_age: DEFAULT_CONSISTENT_READ_VIEW_MAX_AGE,
+            senders_metrics: Default::default(),
         })
     }
 }
@@ -98,10 +124,11 @@ impl<DB: Database> ProviderFactory<DB> {
     /// [`BlockHashReader`]. This may fail if the inner read database transaction fails to open.
     #[track_caller]
     pub fn provider(&self) -> ProviderResult<DatabaseProviderRO<DB>> {
-        Ok(DatabaseProvider::new(
+        Ok(DatabaseProvider::new_with_senders_metrics(
             self.db.tx()?,
             self.chain_spec.clone(),
             self.static_file_provider.clone(),
+            self.senders_metrics.clone(),
         ))
     }
 
@@ -111,13 +138,22 @@ impl<DB: Database> ProviderFactory<DB> {
     /// open.
     #[track_caller]
     pub fn provider_rw(&self) -> ProviderResult<DatabaseProviderRW<DB>> {
-        Ok(DatabaseProviderRW(DatabaseProvider::new_rw(
+        Ok(DatabaseProviderRW(DatabaseProvider::new_with_senders_metrics(
             self.db.tx_mut()?,
             self.chain_spec.clone(),
             self.static_file_provider.clone(),
+            self.senders_metrics.clone(),
         )))
     }
 
+    /// Returns a view that serves all reads from a single read transaction.
+    ///
+    /// See [`ConsistentReadView`] for more info.
+    #[track_caller]
+    pub fn consistent_view(&self) -> ProviderResult<ConsistentReadView<DB, Self>> {
+        ConsistentReadView::new(self.clone(), self.consistent_view_max_age)
+    }
+
     /// State provider for latest block
     #[track_caller]
     pub fn latest(&self) -> ProviderResult<StateProviderBox> {
@@ -137,13 +173,14 @@ impl<DB: Database> ProviderFactory<DB> {
 
     /// Storage provider for state at that given block hash
     pub fn history_by_block_hash(&self, block_hash: BlockHash) -> ProviderResult<StateProviderBox> {
-        let provider = self.provider()?;
+        let mut view = self.consistent_view()?;
 
-        let block_number = provider
+        let block_number = view
+            .provider()?
             .block_number(block_hash)?
             .ok_or(ProviderError::BlockHashNotFound(block_hash))?;
 
-        let state_provider = self.provider()?.state_provider_by_block_number(block_number)?;
+        let state_provider = view.into_provider()?.state_provider_by_block_number(block_number)?;
         trace!(target: "providers::db", ?block_number, %block_hash, "Returning historical state provider for block hash");
         Ok(state_provider)
     }
@@ -256,18 +293,13 @@ impl<DB: Database> BlockHashReader for ProviderFactory<DB> {
         )
     }
 
-    fn canonical_hashes_range(
+    fn canonical_hashes_iter(
         &self,
-        start: BlockNumber,
-        end: BlockNumber,
-    ) -> ProviderResult<Vec<B256>> {
-        self.static_file_provider.get_range_with_static_file_or_database(
-            StaticFileSegment::Headers,
-            start..end,
-            |static_file, range, _| static_file.canonical_hashes_range(range.start, range.end),
-            |range, _| self.provider()?.canonical_hashes_range(range.start, range.end),
-            |_| true,
-        )
+        range: Range<BlockNumber>,
+    ) -> ProviderResult<CanonicalHashesIter<'_>> {
+        // The cursor keeps the read transaction open until the iterator is dropped.
+        let cursor = self.provider()?.into_tx().cursor_read::<tables::CanonicalHeaders>()?;
+        Ok(self.static_file_provider.canonical_hashes_iter_with_database(range, cursor))
     }
 }
 
@@ -346,6 +378,13 @@ impl<DB: Database> BlockReader for ProviderFactory<DB> {
     ) -> ProviderResult<Vec<SealedBlockWithSenders>> {
         self.provider()?.sealed_block_with_senders_range(range)
     }
+
+    fn transaction_and_receipt_by_hash(
+        &self,
+        hash: TxHash,
+    ) -> ProviderResult<Option<(TransactionSigned, TransactionMeta, Receipt)>> {
+        self.consistent_view()?.provider()?.transaction_and_receipt_by_hash(hash)
+    }
 }
 
 impl<DB: Database> TransactionsProvider for ProviderFactory<DB> {
@@ -417,6 +456,10 @@ impl<DB: Database> TransactionsProvider for ProviderFactory<DB> {
         self.provider()?.senders_by_tx_range(range)
     }
 
+    fn senders_by_block(&self, id: BlockHashOrNumber) -> ProviderResult<Option<Vec<Address>>> {
+        self.provider()?.senders_by_block(id)
+    }
+
     fn transaction_sender(&self, id: TxNumber) -> ProviderResult<Option<Address>> {
         self.provider()?.transaction_sender(id)
     }
@@ -466,6 +509,10 @@ impl<DB: Database> WithdrawalsProvider for ProviderFactory<DB> {
     fn latest_withdrawal(&self) -> ProviderResult<Option<Withdrawal>> {
         self.provider()?.latest_withdrawal()
     }
+
+    fn latest_withdrawal_until(&self, block: BlockNumber) -> ProviderResult<Option<Withdrawal>> {
+        self.provider()?.latest_withdrawal_until(block)
+    }
 }
 
 impl<DB> RequestsProvider for ProviderFactory<DB>
@@ -491,6 +538,36 @@ impl<DB: Database> StageCheckpointReader for ProviderFactory<DB> {
     }
 }
 
+impl<DB: Database> TransactionHistoryProvider for ProviderFactory<DB> {
+    fn transaction_history_settings(&self) -> ProviderResult<Option<TransactionHistorySettings>> {
+        self.provider()?.transaction_history_settings()
+    }
+
+    fn transactions_by_address(
+        &self,
+        address: Address,
+        before_tx_number: Option<TxNumber>,
+        limit: usize,
+        direction: HistoryDirection,
+    ) -> ProviderResult<Vec<(TxNumber, TransactionMeta)>> {
+        self.provider()?.transactions_by_address(address, before_tx_number, limit, direction)
+    }
+}
+
+impl<DB: Database> HeaderAccumulatorProvider for ProviderFactory<DB> {
+    fn header_accumulator_leaf_count(&self) -> ProviderResult<Option<u64>> {
+        self.provider()?.header_accumulator_leaf_count()
+    }
+
+    fn accumulator_root_at(&self, block: BlockNumber) -> ProviderResult<Option<B256>> {
+        self.provider()?.accumulator_root_at(block)
+    }
+
+    fn prove_canonical(&self, block: BlockNumber) -> ProviderResult<Option<MmrProof>> {
+        self.provider()?.prove_canonical(block)
+    }
+}
+
 impl<DB: Database> EvmEnvProvider for ProviderFactory<DB> {
     fn fill_env_at<EvmConfig>(
         &self,
@@ -577,12 +654,49 @@ impl<DB: Database> PruneCheckpointReader for ProviderFactory<DB> {
     }
 }
 
+impl<DB: Database> HashedStateRangeProvider for ProviderFactory<DB> {
+    fn hashed_state_root(&self) -> ProviderResult<Option<B256>> {
+        self.provider()?.hashed_state_root()
+    }
+
+    fn hashed_account_range(
+        &self,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, TrieAccount)>> {
+        self.provider()?.hashed_account_range(start, limit)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<StorageEntry>> {
+        self.provider()?.hashed_storage_range(hashed_address, start, limit)
+    }
+
+    fn account_range_proof(&self, targets: &[B256]) -> ProviderResult<Vec<Bytes>> {
+        self.provider()?.account_range_proof(targets)
+    }
+
+    fn storage_range_proof(
+        &self,
+        hashed_address: B256,
+        targets: &[B256],
+    ) -> ProviderResult<Vec<Bytes>> {
+        self.provider()?.storage_range_proof(hashed_address, targets)
+    }
+}
+
 impl<DB> Clone for ProviderFactory<DB> {
     fn clone(&self) -> Self {
         Self {
             db: Arc::clone(&self.db),
             chain_spec: self.chain_spec.clone(),
             static_file_provider: self.static_file_provider.clone(),
+            consistent_view_max_age: self.consistent_view_max_age,
+            senders_metrics: self.senders_metrics.clone(),
         }
     }
 }
@@ -590,28 +704,41 @@ impl<DB> Clone for ProviderFactory<DB> {
 mod tests {
     use super::*;
     use crate::{
-        providers::{StaticFileProvider, StaticFileWriter},
-        test_utils::create_test_provider_factory,
-        BlockHashReader, BlockNumReader, BlockWriter, HeaderSyncGapProvider, TransactionsProvider,
+        providers::{ConsistentViewError, StaticFileProvider, StaticFileWriter},
+        test_utils::{blocks::BlockchainTestData, create_test_provider_factory},
+        BlockExecutionWriter, BlockHashReader, BlockNumReader, BlockWriter,
+        HeaderAccumulatorWriter, HeaderSyncGapProvider, TransactionsProvider,
     };
     use alloy_rlp::Decodable;
     use assert_matches::assert_matches;
+    use metrics::{
+        Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+    };
     use rand::Rng;
     use reth_db::{
         mdbx::DatabaseArguments,
         tables,
         test_utils::{create_test_static_files_dir, ERROR_TEMPDIR},
     };
+    use reth_db_api::{models::StoredBlockWithdrawals, transaction::DbTxMut};
     use reth_primitives::{
-        hex_literal::hex, ChainSpecBuilder, SealedBlock, StaticFileSegment, TxNumber, B256, U256,
+        accumulator::verify_canonical_proof, hex_literal::hex, ChainSpecBuilder, GotExpected,
+        SealedBlock, StaticFileSegment, TxNumber, B256, U256,
     };
     use reth_prune_types::{PruneMode, PruneModes};
-    use reth_storage_errors::provider::ProviderError;
+    use reth_storage_errors::provider::{ProviderError, ReceiptGasError};
     use reth_testing_utils::{
         generators,
         generators::{random_block, random_header},
     };
-    use std::{ops::RangeInclusive, sync::Arc};
+    use std::{
+        collections::HashMap,
+        ops::{Range, RangeInclusive},
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc,
+        },
+    };
     use tokio::sync::watch;
 
     #[test]
@@ -659,6 +786,86 @@ mod tests {
         provider.block_hash(0).unwrap();
     }
 
+    #[test]
+    fn consistent_view_reads_from_one_snapshot() {
+        let factory = create_test_provider_factory();
+        let mut rng = generators::rng();
+        let genesis = random_block(&mut rng, 0, None, Some(0), None);
+        let block = random_block(&mut rng, 1, Some(genesis.hash()), Some(3), None);
+
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw.insert_block(genesis.clone().try_seal_with_senders().unwrap(), None).unwrap();
+        provider_rw.commit().unwrap();
+
+        let mut view = factory.consistent_view().unwrap();
+        assert_eq!(view.provider().unwrap().last_block_number(), Ok(0));
+        assert_eq!(view.provider().unwrap().block_number(block.hash()), Ok(None));
+
+        // commit a new block in between two reads through the view
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw.insert_block(block.clone().try_seal_with_senders().unwrap(), None).unwrap();
+        provider_rw.commit().unwrap();
+        assert_eq!(factory.provider().unwrap().last_block_number(), Ok(1));
+
+        // the view still reflects the state before the commit
+        let provider = view.provider().unwrap();
+        assert_eq!(provider.last_block_number(), Ok(0));
+        assert_eq!(provider.block_number(block.hash()), Ok(None));
+        assert_eq!(provider.transaction_id(block.body[0].hash), Ok(None));
+        // the tip is read lazily, but from the pinned snapshot
+        assert_eq!(view.tip(), Ok(Some(genesis.hash())));
+        assert_eq!(view.is_current(), Ok(false));
+        assert_eq!(factory.consistent_view().unwrap().is_current(), Ok(true));
+    }
+
+    #[test]
+    fn consistent_view_expires() {
+        let factory = create_test_provider_factory().with_consistent_view_max_age(Duration::ZERO);
+        let mut view = factory.consistent_view().unwrap();
+        std::thread::sleep(Duration::from_millis(1));
+
+        assert_matches!(
+            view.provider(),
+            Err(ProviderError::ConsistentView(err))
+                if matches!(*err, ConsistentViewError::Expired { .. })
+        );
+        // the read transaction is closed, so the tip can't be read anymore either
+        assert_matches!(
+            view.tip(),
+            Err(ProviderError::ConsistentView(err))
+                if matches!(*err, ConsistentViewError::Expired { .. })
+        );
+    }
+
+    #[test]
+    fn latest_withdrawal_until_block() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        assert_eq!(provider.latest_withdrawal_until(10), Ok(None));
+
+        // blocks 1 and 3 have withdrawals, block 2 has none
+        for (block, indices) in [(1, 0..2), (3, 2..5)] {
+            let withdrawals = Withdrawals::new(
+                indices.map(|index| Withdrawal { index, ..Default::default() }).collect(),
+            );
+            provider
+                .tx_ref()
+                .put::<tables::BlockWithdrawals>(block, StoredBlockWithdrawals { withdrawals })
+                .unwrap();
+        }
+
+        let index = |block| provider.latest_withdrawal_until(block).unwrap().map(|w| w.index);
+        assert_eq!(index(0), None);
+        assert_eq!(index(1), Some(1));
+        assert_eq!(index(2), Some(1));
+        assert_eq!(index(3), Some(4));
+        assert_eq!(index(10), Some(4));
+
+        // unwinding the withdrawals restores the previous index
+        provider.unwind_table_by_num::<tables::BlockWithdrawals>(2).unwrap();
+        assert_eq!(index(10), Some(1));
+    }
+
     #[test]
     fn insert_block_with_prune_modes() {
         let factory = create_test_provider_factory();
@@ -739,6 +946,273 @@ mod tests {
         }
     }
 
+    /// Records the values of counters by name.
+    #[derive(Default)]
+    struct CounterRecorder(parking_lot::Mutex<HashMap<String, Arc<AtomicU64>>>);
+
+    impl CounterRecorder {
+        fn counter(&self, name: &str) -> u64 {
+            self.0.lock().get(name).map_or(0, |counter| counter.load(Ordering::Relaxed))
+        }
+    }
+
+    impl Recorder for CounterRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::from_arc(self.0.lock().entry(key.name().to_string()).or_default().clone())
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn senders_by_block_repairs_missing_senders() {
+        // the metrics are registered when the factory is created
+        let recorder = CounterRecorder::default();
+        let factory = metrics::with_local_recorder(&recorder, create_test_provider_factory);
+
+        let mut rng = generators::rng();
+        let block = random_block(&mut rng, 0, None, Some(5), None);
+        let senders = block.senders().unwrap();
+
+        let provider = factory.provider_rw().unwrap();
+        provider.insert_block(block.clone().try_seal_with_senders().unwrap(), None).unwrap();
+        for tx_num in [1, 3, 4] {
+            provider.tx_ref().delete::<tables::TransactionSenders>(tx_num, None).unwrap();
+        }
+
+        // the missing senders are recovered, but only written back by a read-write provider
+        assert_eq!(provider.senders_by_block(0.into()), Ok(Some(senders.clone())));
+        assert_eq!(provider.senders_by_tx_range(0..5).unwrap().len(), 2);
+        assert_eq!(provider.repaired_senders_by_block(0.into()), Ok(Some(senders.clone())));
+        assert_eq!(provider.senders_by_tx_range(0..5), Ok(senders.clone()));
+
+        // nothing is missing anymore
+        let block = provider
+            .block_with_repaired_senders(0.into(), TransactionVariant::NoHash)
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.senders, senders);
+        assert_eq!(recorder.counter("storage.providers.database.senders.recovered"), 6);
+        assert_eq!(recorder.counter("storage.providers.database.senders.repaired"), 3);
+
+        // senders can't be recovered without their transactions
+        provider.tx_ref().delete::<tables::Transactions>(4, None).unwrap();
+        provider.tx_ref().delete::<tables::TransactionSenders>(4, None).unwrap();
+        assert_eq!(
+            provider.senders_by_block(0.into()),
+            Err(ProviderError::UnrecoverableSenders {
+                block_number: 0,
+                transactions: GotExpected { got: 4, expected: 5 }
+            })
+        );
+    }
+
+    #[test]
+    fn verify_block_body_indices_invariants() {
+        let factory = create_test_provider_factory();
+
+        let mut rng = generators::rng();
+        let genesis = random_block(&mut rng, 0, None, Some(2), None);
+        let empty = random_block(&mut rng, 1, Some(genesis.hash()), Some(0), None);
+        let block = random_block(&mut rng, 2, Some(empty.hash()), Some(3), None);
+
+        let provider = factory.provider_rw().unwrap();
+        for block in [&genesis, &empty, &block] {
+            provider.insert_block(block.clone().try_seal_with_senders().unwrap(), None).unwrap();
+        }
+
+        assert_matches!(provider.verify_block_body_indices(0..=2), Ok(()));
+        assert_matches!(provider.verify_block_body_indices(1..=2), Ok(()));
+
+        // Transactions of a block are read by tx number without decoding neighbouring blocks.
+        assert_eq!(provider.transactions_by_block(2.into()).unwrap(), Some(block.body.clone()));
+        assert_eq!(provider.transaction_by_id(2).unwrap(), Some(block.body[0].clone()));
+        assert_matches!(provider.transaction_id(block.body[2].hash), Ok(Some(4)));
+
+        // Shift the last block so that it no longer continues where the previous one ended.
+        provider
+            .tx_ref()
+            .put::<tables::BlockBodyIndices>(
+                2,
+                StoredBlockBodyIndices { first_tx_num: 3, tx_count: 2 },
+            )
+            .unwrap();
+        assert_matches!(
+            provider.verify_block_body_indices(0..=2),
+            Err(ProviderError::BlockBodyIndicesGap { block_number: 2, first_tx_num })
+                if first_tx_num.got == 3 && first_tx_num.expected == 2
+        );
+
+        // Restore the indices, but drop the reverse lookup of the last transaction.
+        provider
+            .tx_ref()
+            .put::<tables::BlockBodyIndices>(
+                2,
+                StoredBlockBodyIndices { first_tx_num: 2, tx_count: 3 },
+            )
+            .unwrap();
+        provider.tx_ref().delete::<tables::TransactionBlocks>(4, None).unwrap();
+        assert_matches!(
+            provider.verify_block_body_indices(0..=2),
+            Err(ProviderError::BlockNumberForTransactionIndexNotFound)
+        );
+    }
+
+    #[test]
+    fn verify_receipts_gas_invariants() {
+        let factory = create_test_provider_factory();
+        let data = BlockchainTestData::default();
+        let (block, execution_outcome) = data.blocks[0].clone();
+
+        let provider = factory.provider_rw().unwrap();
+        provider.insert_block(data.genesis.try_seal_with_senders().unwrap(), None).unwrap();
+
+        // Receipts that don't add up to the gas used by their block are refused.
+        let mut inconsistent = execution_outcome.clone();
+        inconsistent.receipts[0][0].as_mut().unwrap().cumulative_gas_used = 200;
+        assert_matches!(
+            provider.append_blocks_with_state(
+                vec![block.clone()],
+                inconsistent,
+                Default::default(),
+                Default::default(),
+                None
+            ),
+            Err(ProviderError::ReceiptGasInconsistency {
+                block_number: 1,
+                tx_index: 0,
+                kind: ReceiptGasError::BlockGasUsed(GotExpected { got: 200, expected: 300 }),
+            })
+        );
+        assert_matches!(provider.block_body_indices(1), Ok(None));
+
+        provider
+            .append_blocks_with_state(
+                vec![block],
+                execution_outcome.clone(),
+                Default::default(),
+                Default::default(),
+                None,
+            )
+            .unwrap();
+        assert_matches!(provider.verify_block_receipts_gas(0..=1), Ok(()));
+
+        // Corrupt the stored receipt of the only transaction of the block.
+        let mut receipt = execution_outcome.receipts[0][0].clone().unwrap();
+        receipt.cumulative_gas_used = 500;
+        provider.tx_ref().put::<tables::Receipts>(0, receipt).unwrap();
+        assert_matches!(
+            provider.verify_block_receipts_gas(0..=1),
+            Err(ProviderError::ReceiptGasInconsistency {
+                block_number: 1,
+                tx_index: 0,
+                kind: ReceiptGasError::BlockGasUsed(GotExpected { got: 500, expected: 300 }),
+            })
+        );
+
+        // Pruned receipts are skipped.
+        provider.tx_ref().delete::<tables::Receipts>(0, None).unwrap();
+        assert_matches!(provider.verify_block_receipts_gas(0..=1), Ok(()));
+    }
+
+    #[test]
+    fn header_accumulator_follows_canonical_chain() {
+        let factory = create_test_provider_factory();
+        let data = BlockchainTestData::default();
+
+        // Returns the blocks with the given indices and their merged execution outcome.
+        let blocks = |indices: Range<usize>| {
+            let mut blocks = data.blocks[indices].iter().cloned();
+            let (block, mut execution_outcome) = blocks.next().unwrap();
+            let mut sealed_blocks = vec![block];
+            for (block, outcome) in blocks {
+                sealed_blocks.push(block);
+                execution_outcome.extend(outcome);
+            }
+            (sealed_blocks, execution_outcome)
+        };
+
+        let provider = factory.provider_rw().unwrap();
+        provider.insert_block(data.genesis.clone().try_seal_with_senders().unwrap(), None).unwrap();
+
+        // The accumulator isn't maintained until it's enabled.
+        let (sealed_blocks, execution_outcome) = blocks(0..1);
+        let hashed_state = execution_outcome.hash_state_slow();
+        provider
+            .append_blocks_with_state(
+                sealed_blocks,
+                execution_outcome,
+                hashed_state,
+                Default::default(),
+                None,
+            )
+            .unwrap();
+        assert_matches!(provider.header_accumulator_leaf_count(), Ok(None));
+        provider.take_block_and_execution_range(1..=1).unwrap();
+        assert_matches!(provider.header_accumulator_leaf_count(), Ok(None));
+
+        provider.reset_header_accumulator(true).unwrap();
+        provider.append_header_accumulator(0..=0).unwrap();
+
+        // Appended blocks are accumulated, and all of them can be proven against the root.
+        let (sealed_blocks, execution_outcome) = blocks(0..5);
+        let hashed_state = execution_outcome.hash_state_slow();
+        provider
+            .append_blocks_with_state(
+                sealed_blocks,
+                execution_outcome,
+                hashed_state,
+                Default::default(),
+                None,
+            )
+            .unwrap();
+        assert_matches!(provider.header_accumulator_leaf_count(), Ok(Some(6)));
+        let root = provider.accumulator_root_at(5).unwrap().unwrap();
+        let root_at_2 = provider.accumulator_root_at(2).unwrap().unwrap();
+        for block in 0..=5 {
+            let proof = provider.prove_canonical(block).unwrap().unwrap();
+            let hash = provider.block_hash(block).unwrap().unwrap();
+            assert!(verify_canonical_proof(root, hash, &proof));
+        }
+
+        // Taking blocks truncates the accumulator, and only leaves the nodes of the kept blocks.
+        provider.take_block_and_execution_range(3..=5).unwrap();
+        assert_matches!(provider.header_accumulator_leaf_count(), Ok(Some(3)));
+        assert_matches!(provider.accumulator_root_at(3), Ok(None));
+        assert_eq!(provider.accumulator_root_at(2).unwrap(), Some(root_at_2));
+        assert_eq!(provider.tx_ref().entries::<tables::HeaderAccumulator>().unwrap(), 1);
+
+        // Appending the taken blocks again restores the same accumulator.
+        let (sealed_blocks, execution_outcome) = blocks(2..5);
+        let hashed_state = execution_outcome.hash_state_slow();
+        provider
+            .append_blocks_with_state(
+                sealed_blocks,
+                execution_outcome,
+                hashed_state,
+                Default::default(),
+                None,
+            )
+            .unwrap();
+        assert_eq!(provider.accumulator_root_at(5).unwrap(), Some(root));
+
+        // It's the same accumulator that is built from scratch.
+        provider.reset_header_accumulator(true).unwrap();
+        provider.append_header_accumulator(0..=5).unwrap();
+        assert_eq!(provider.accumulator_root_at(5).unwrap(), Some(root));
+        assert_eq!(provider.tx_ref().entries::<tables::HeaderAccumulator>().unwrap(), 4);
+    }
+
     #[test]
     fn header_sync_gap_lookup() {
         let factory = create_test_provider_factory();
@@ -1,17 +1,26 @@
 use crate::{
-    bundle_state::{BundleStateInit, ExecutionOutcome, HashedStateChanges, RevertsInit},
-    providers::{database::metrics, static_file::StaticFileWriter, StaticFileProvider},
+    bundle_state::{
+        verify_receipts_gas, BlockGas, BundleStateInit, ExecutionOutcome, HashedStateChanges,
+        RevertsInit,
+    },
+    providers::{
+        database::metrics::{self, MissingSendersMetrics},
+        static_file::StaticFileWriter,
+        StaticFileProvider,
+    },
     to_range,
     traits::{
         AccountExtReader, BlockSource, ChangeSetReader, ReceiptProvider, StageCheckpointWriter,
     },
     AccountReader, BlockExecutionWriter, BlockHashReader, BlockNumReader, BlockReader, BlockWriter,
-    Chain, EvmEnvProvider, FinalizedBlockReader, FinalizedBlockWriter, HashingWriter,
-    HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HistoricalStateProvider, HistoryWriter,
-    LatestStateProvider, OriginalValuesKnown, ProviderError, PruneCheckpointReader,
-    PruneCheckpointWriter, RequestsProvider, StageCheckpointReader, StateProviderBox, StateWriter,
-    StatsReader, StorageReader, TransactionVariant, TransactionsProvider, TransactionsProviderExt,
-    WithdrawalsProvider,
+    CanonicalHashesIter, Chain, EvmEnvProvider, FinalizedBlockReader, FinalizedBlockWriter,
+    HashedStateRangeProvider, HashingWriter, HeaderAccumulatorProvider, HeaderAccumulatorWriter,
+    HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HistoricalStateProvider,
+    HistoryDirection, HistoryWriter, LatestStateProvider, OriginalValuesKnown, ProviderError,
+    PruneCheckpointReader, PruneCheckpointWriter, RequestsProvider, StageCheckpointReader,
+    StateProviderBox, StateWriter, StatsReader, StorageReader, TransactionHistoryProvider,
+    TransactionHistorySettings, TransactionHistoryWriter, TransactionVariant, TransactionsProvider,
+    TransactionsProviderExt, WithdrawalsProvider,
 };
 use itertools::{izip, Itertools};
 use reth_db::{tables, BlockNumberList};
@@ -22,17 +31,21 @@ use reth_db_api::{
     models::{
         sharded_key, storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress,
         ShardedKey, StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals,
+        StoredTxNumber,
     },
     table::{Table, TableRow},
     transaction::{DbTx, DbTxMut},
     DatabaseError,
 };
 use reth_evm::ConfigureEvmEnv;
+use reth_execution_errors::StateRootError;
 use reth_network_p2p::headers::downloader::SyncTarget;
 use reth_primitives::{
+    accumulator::{accumulator_root, merge_nodes, peak_positions, MmrProof},
     keccak256,
+    proofs::IntoTrieAccount,
     revm::{config::revm_spec, env::fill_block_env},
-    Account, Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders,
+    Account, Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, Bytes,
     ChainInfo, ChainSpec, GotExpected, Head, Header, Receipt, Requests, SealedBlock,
     SealedBlockWithSenders, SealedHeader, StaticFileSegment, StorageEntry, TransactionMeta,
     TransactionSigned, TransactionSignedEcRecovered, TransactionSignedNoHash, TxHash, TxNumber,
@@ -43,8 +56,9 @@ use reth_stages_types::{StageCheckpoint, StageId};
 use reth_storage_errors::provider::{ProviderResult, RootMismatch};
 use reth_trie::{
     prefix_set::{PrefixSet, PrefixSetMut, TriePrefixSets},
+    proof::Proof,
     updates::TrieUpdates,
-    HashedPostState, Nibbles, StateRoot,
+    HashedPostState, Nibbles, StateRoot, TrieAccount,
 };
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg, SpecId};
 use std::{
@@ -104,9 +118,21 @@ pub struct DatabaseProvider<TX> {
     chain_spec: Arc<ChainSpec>,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Metrics of senders missing from the senders table
+    senders_metrics: Arc<MissingSendersMetrics>,
 }
 
 impl<TX> DatabaseProvider<TX> {
+    /// Creates a provider that shares the given metrics with other providers.
+    pub(crate) const fn new_with_senders_metrics(
+        tx: TX,
+        chain_spec: Arc<ChainSpec>,
+        static_file_provider: StaticFileProvider,
+        senders_metrics: Arc<MissingSendersMetrics>,
+    ) -> Self {
+        Self { tx, chain_spec, static_file_provider, senders_metrics }
+    }
+
     /// Returns a static file provider
     pub const fn static_file_provider(&self) -> &StaticFileProvider {
         &self.static_file_provider
@@ -120,7 +146,7 @@ impl<TX: DbTxMut> DatabaseProvider<TX> {
         chain_spec: Arc<ChainSpec>,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, chain_spec, static_file_provider }
+        Self::new_with_senders_metrics(tx, chain_spec, static_file_provider, Default::default())
     }
 }
 
@@ -192,7 +218,8 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
             for block_number in 0..block.number {
                 let mut prev = block.header.clone().unseal();
                 prev.number = block_number;
-                writer.append_header(prev, U256::ZERO, B256::ZERO)?;
+                let hash = prev.hash_slow();
+                writer.append_header(prev, U256::ZERO, hash)?;
             }
         }
 
@@ -257,7 +284,7 @@ impl<TX: DbTx> DatabaseProvider<TX> {
         chain_spec: Arc<ChainSpec>,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, chain_spec, static_file_provider }
+        Self::new_with_senders_metrics(tx, chain_spec, static_file_provider, Default::default())
     }
 
     /// Consume `DbTx` or `DbTxMut`.
@@ -302,6 +329,87 @@ impl<TX: DbTx> DatabaseProvider<TX> {
             .collect::<Result<Vec<_>, DatabaseError>>()
     }
 
+    /// Verifies the invariants of the transaction number keyed body layout for the given block
+    /// range.
+    ///
+    /// Every block's [`StoredBlockBodyIndices::first_tx_num`] must continue where the previous
+    /// block ended, the last transaction of every non-empty block must exist and
+    /// [`tables::TransactionBlocks`] must point it back to the block it belongs to.
+    pub fn verify_block_body_indices(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        let mut tx_block_cursor = self.tx.cursor_read::<tables::TransactionBlocks>()?;
+
+        // The first block of the range is checked against its predecessor, if any.
+        let mut expected_first_tx_num = match range.start().checked_sub(1) {
+            Some(parent) => self.block_body_indices(parent)?.map(|body| body.next_tx_num()),
+            None => Some(0),
+        };
+
+        for block_number in range {
+            let body = self
+                .block_body_indices(block_number)?
+                .ok_or(ProviderError::BlockBodyIndicesNotFound(block_number))?;
+
+            if let Some(expected) = expected_first_tx_num {
+                if body.first_tx_num() != expected {
+                    return Err(ProviderError::BlockBodyIndicesGap {
+                        block_number,
+                        first_tx_num: GotExpected { got: body.first_tx_num(), expected },
+                    })
+                }
+            }
+            expected_first_tx_num = Some(body.next_tx_num());
+
+            if body.is_empty() {
+                continue
+            }
+
+            let last_tx_num = body.last_tx_num();
+            if self.transaction_by_id_no_hash(last_tx_num)?.is_none() {
+                return Err(ProviderError::TransactionNotFound(last_tx_num.into()))
+            }
+
+            match tx_block_cursor.seek_exact(last_tx_num)? {
+                Some((_, number)) if number == block_number => {}
+                _ => return Err(ProviderError::BlockNumberForTransactionIndexNotFound),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies that the stored receipts of the given block range are consistent with the gas used
+    /// by their blocks, see [`BlockGas::verify_receipts`].
+    ///
+    /// Pruned receipts are skipped.
+    pub fn verify_block_receipts_gas(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        for block_number in range {
+            let header = self
+                .header_by_number(block_number)?
+                .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?;
+            let body = self
+                .block_body_indices(block_number)?
+                .ok_or(ProviderError::BlockBodyIndicesNotFound(block_number))?;
+            let transactions = self.transactions_by_block(block_number.into())?.unwrap_or_default();
+            // Receipts are looked up one by one, so that a pruned receipt doesn't shift the
+            // transaction index of the following ones.
+            let receipts = body
+                .tx_num_range()
+                .map(|tx_num| self.receipt(tx_num))
+                .collect::<ProviderResult<Vec<_>>>()?;
+
+            BlockGas::new(&header, &transactions)
+                .verify_receipts(receipts.iter().map(Option::as_ref))?;
+        }
+
+        Ok(())
+    }
+
     /// Iterates over read only values in the given table and collects them into a vector.
     ///
     /// Early-returns if the range is empty, without opening a cursor transaction.
@@ -514,6 +622,243 @@ impl<TX: DbTx> DatabaseProvider<TX> {
             assemble_block(header, body, ommers, withdrawals, requests, senders)
         })
     }
+
+    /// Returns the block with senders, see [`BlockReader::block_with_senders`].
+    ///
+    /// Senders missing from the [`tables::TransactionSenders`] table are recovered with
+    /// [`Self::senders_with_recovery`].
+    fn block_with_recovered_senders(
+        &self,
+        id: BlockHashOrNumber,
+        transaction_kind: TransactionVariant,
+        on_recovered: impl FnOnce(Vec<(TxNumber, Address)>) -> ProviderResult<()>,
+    ) -> ProviderResult<Option<BlockWithSenders>> {
+        let Some(block_number) = self.convert_hash_or_number(id)? else { return Ok(None) };
+        let Some(header) = self.header_by_number(block_number)? else { return Ok(None) };
+
+        self.assemble_block_with_recovered_senders(
+            block_number,
+            header,
+            None,
+            transaction_kind,
+            on_recovered,
+        )
+    }
+
+    /// Assembles the block with senders from its header, see
+    /// [`Self::block_with_recovered_senders`]. The transactions are read unless they are given.
+    fn assemble_block_with_recovered_senders(
+        &self,
+        block_number: BlockNumber,
+        header: Header,
+        transactions: Option<Vec<TransactionSignedNoHash>>,
+        transaction_kind: TransactionVariant,
+        on_recovered: impl FnOnce(Vec<(TxNumber, Address)>) -> ProviderResult<()>,
+    ) -> ProviderResult<Option<BlockWithSenders>> {
+        let ommers = self.ommers(block_number.into())?.unwrap_or_default();
+        let withdrawals = self.withdrawals_by_block(block_number.into(), header.timestamp)?;
+        let requests = self.requests_by_block(block_number.into(), header.timestamp)?;
+
+        // Get the block body
+        //
+        // If the body indices are not found, this means that the transactions either do not exist
+        // in the database yet, or they do exit but are not indexed. If they exist but are not
+        // indexed, we don't have enough information to return the block anyways, so we return
+        // `None`.
+        let Some(body) = self.block_body_indices(block_number)? else { return Ok(None) };
+
+        let tx_range = body.tx_num_range();
+
+        let (transactions, senders) = if tx_range.is_empty() {
+            (vec![], vec![])
+        } else {
+            let transactions = match transactions {
+                Some(transactions) => transactions,
+                None => self.transactions_by_tx_range(tx_range.clone())?,
+            };
+            let senders = self.senders_with_recovery(
+                block_number,
+                tx_range,
+                Some(&transactions),
+                on_recovered,
+            )?;
+            (transactions, senders)
+        };
+
+        let body = transactions
+            .into_iter()
+            .map(|tx| match transaction_kind {
+                TransactionVariant::NoHash => TransactionSigned {
+                    // Caller explicitly asked for no hash, so we don't calculate it
+                    hash: B256::ZERO,
+                    signature: tx.signature,
+                    transaction: tx.transaction,
+                },
+                TransactionVariant::WithHash => tx.with_hash(),
+            })
+            .collect();
+
+        Block { header, body, ommers, withdrawals, requests }
+            // Note: we're using unchecked here because we know the block contains valid txs wrt to
+            // its height and can ignore the s value check so pre EIP-2 txs are allowed
+            .try_with_senders_unchecked(senders)
+            .map(Some)
+            .map_err(|_| ProviderError::SenderRecoveryError)
+    }
+
+    /// Returns the senders of the block's transactions, see
+    /// [`TransactionsProvider::senders_by_block`].
+    fn senders_by_block_with_recovery(
+        &self,
+        id: BlockHashOrNumber,
+        on_recovered: impl FnOnce(Vec<(TxNumber, Address)>) -> ProviderResult<()>,
+    ) -> ProviderResult<Option<Vec<Address>>> {
+        let Some(block_number) = self.convert_hash_or_number(id)? else { return Ok(None) };
+        let Some(body) = self.block_body_indices(block_number)? else { return Ok(None) };
+
+        self.senders_with_recovery(block_number, body.tx_num_range(), None, on_recovered).map(Some)
+    }
+
+    /// Returns the senders of the transactions in the `tx_range` of the block.
+    ///
+    /// Senders missing from the [`tables::TransactionSenders`] table are recovered from the
+    /// transactions, which are only read if they are not given. Unless the senders of the block
+    /// were pruned, the recovered senders are recorded in the metrics and passed to
+    /// `on_recovered`.
+    ///
+    /// Returns [`ProviderError::UnrecoverableSenders`] if the transactions don't match the range.
+    fn senders_with_recovery(
+        &self,
+        block_number: BlockNumber,
+        tx_range: Range<TxNumber>,
+        transactions: Option<&[TransactionSignedNoHash]>,
+        on_recovered: impl FnOnce(Vec<(TxNumber, Address)>) -> ProviderResult<()>,
+    ) -> ProviderResult<Vec<Address>> {
+        let stored = self
+            .tx
+            .cursor_read::<tables::TransactionSenders>()?
+            .walk_range(tx_range.clone())?
+            .collect::<Result<Vec<_>, _>>()?;
+        let expected = tx_range.end - tx_range.start;
+        if stored.len() as u64 == expected {
+            return Ok(stored.into_iter().map(|(_, sender)| sender).collect())
+        }
+
+        let read;
+        let transactions = match transactions {
+            Some(transactions) => transactions,
+            None => {
+                read = self.transactions_by_tx_range(tx_range.clone())?;
+                &read
+            }
+        };
+        if transactions.len() as u64 != expected {
+            return Err(ProviderError::UnrecoverableSenders {
+                block_number,
+                transactions: GotExpected { got: transactions.len() as u64, expected },
+            })
+        }
+
+        let mut stored = stored.into_iter().peekable();
+        let mut senders = Vec::with_capacity(transactions.len());
+        let mut recovered = Vec::new();
+        let mut buf = Vec::new();
+        for (tx_num, tx) in tx_range.zip(transactions) {
+            if let Some((_, sender)) = stored.next_if(|(stored_num, _)| *stored_num == tx_num) {
+                senders.push(sender);
+            } else {
+                let sender = tx
+                    .encode_and_recover_unchecked(&mut buf)
+                    .ok_or(ProviderError::SenderRecoveryError)?;
+                senders.push(sender);
+                recovered.push((tx_num, sender));
+            }
+        }
+
+        // Senders up to the prune checkpoint are expected to be missing
+        let pruned = self
+            .get_prune_checkpoint(PruneSegment::SenderRecovery)?
+            .and_then(|checkpoint| checkpoint.block_number)
+            .map_or(false, |pruned_block| block_number <= pruned_block);
+        if !pruned {
+            debug!(
+                target: "providers::db",
+                block_number,
+                missing = recovered.len(),
+                "Recovered senders missing from the senders table"
+            );
+            self.senders_metrics.recovered.increment(recovered.len() as u64);
+            on_recovered(recovered)?;
+        }
+
+        Ok(senders)
+    }
+
+    /// Returns the `(address, transaction number)` entries of the transaction history index for
+    /// the transactions of the block range, see [`TransactionHistoryWriter`].
+    ///
+    /// The recipient of a contract creation is the created contract. The addresses of the logs are
+    /// only returned if `index_logs` is set and the receipts of the range weren't pruned.
+    fn transaction_history_entries(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        index_logs: bool,
+    ) -> ProviderResult<BTreeSet<(Address, TxNumber)>> {
+        let mut entries = BTreeSet::new();
+        let (Some(first), Some(last)) =
+            (self.block_body_indices(*range.start())?, self.block_body_indices(*range.end())?)
+        else {
+            return Ok(entries)
+        };
+        let tx_range = first.first_tx_num()..last.next_tx_num();
+        if tx_range.is_empty() {
+            return Ok(entries)
+        }
+
+        let transactions = self.transactions_by_tx_range(tx_range.clone())?;
+        let senders = self.senders_with_recovery(
+            *range.end(),
+            tx_range.clone(),
+            Some(&transactions),
+            |_| Ok(()),
+        )?;
+        for ((tx_number, tx), sender) in tx_range.clone().zip(&transactions).zip(senders) {
+            let recipient =
+                tx.transaction.to().unwrap_or_else(|| sender.create(tx.transaction.nonce()));
+            entries.insert((sender, tx_number));
+            entries.insert((recipient, tx_number));
+        }
+
+        if index_logs {
+            let receipts = self.receipts_by_tx_range(tx_range.clone())?;
+            if receipts.len() == transactions.len() {
+                for (tx_number, receipt) in tx_range.zip(receipts) {
+                    entries.extend(receipt.logs.iter().map(|log| (log.address, tx_number)));
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Returns the node of the header accumulator at the given position, see
+    /// [`reth_primitives::accumulator`]. The leaves are the canonical header hashes.
+    fn header_accumulator_node(&self, height: u8, index: u64) -> ProviderResult<B256> {
+        let node = if height == 0 {
+            self.block_hash(index)?
+        } else {
+            self.tx.get::<tables::HeaderAccumulator>(header_accumulator_key(height, index))?
+        };
+        node.ok_or(ProviderError::HeaderAccumulatorNodeNotFound { height, index })
+    }
+
+    /// Returns the peaks of the header accumulator of `leaf_count` leaves.
+    fn header_accumulator_peaks(&self, leaf_count: u64) -> ProviderResult<Vec<B256>> {
+        peak_positions(leaf_count)
+            .into_iter()
+            .map(|(height, index)| self.header_accumulator_node(height, index))
+            .collect()
+    }
 }
 
 impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
@@ -522,6 +867,67 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
         Ok(self.tx.commit()?)
     }
 
+    /// Returns the block with senders like [`BlockReader::block_with_senders`], and writes the
+    /// senders missing from the [`tables::TransactionSenders`] table back to it.
+    pub fn block_with_repaired_senders(
+        &self,
+        id: BlockHashOrNumber,
+        transaction_kind: TransactionVariant,
+    ) -> ProviderResult<Option<BlockWithSenders>> {
+        self.block_with_recovered_senders(id, transaction_kind, |recovered| {
+            self.repair_senders(recovered)
+        })
+    }
+
+    /// Returns the block with senders like [`Self::block_with_repaired_senders`], with the header
+    /// and the transactions of the block already read, e.g. from the static files ahead of time.
+    ///
+    /// The `transactions` have to be the transactions of the block's body indices.
+    pub fn block_with_repaired_senders_from(
+        &self,
+        block_number: BlockNumber,
+        header: Header,
+        transactions: Vec<TransactionSignedNoHash>,
+        transaction_kind: TransactionVariant,
+    ) -> ProviderResult<Option<BlockWithSenders>> {
+        self.assemble_block_with_recovered_senders(
+            block_number,
+            header,
+            Some(transactions),
+            transaction_kind,
+            |recovered| self.repair_senders(recovered),
+        )
+    }
+
+    /// Returns the senders of the block's transactions like
+    /// [`TransactionsProvider::senders_by_block`], and writes the senders missing from the
+    /// [`tables::TransactionSenders`] table back to it.
+    pub fn repaired_senders_by_block(
+        &self,
+        id: BlockHashOrNumber,
+    ) -> ProviderResult<Option<Vec<Address>>> {
+        self.senders_by_block_with_recovery(id, |recovered| self.repair_senders(recovered))
+    }
+
+    /// Writes recovered senders that were missing to the [`tables::TransactionSenders`] table.
+    fn repair_senders(&self, recovered: Vec<(TxNumber, Address)>) -> ProviderResult<()> {
+        let Some(&(first_tx, _)) = recovered.first() else { return Ok(()) };
+
+        warn!(
+            target: "providers::db",
+            first_tx,
+            repaired = recovered.len(),
+            "Repairing senders missing from the senders table"
+        );
+        let repaired = recovered.len() as u64;
+        for (tx_num, sender) in recovered {
+            self.tx.put::<tables::TransactionSenders>(tx_num, sender)?;
+        }
+        self.senders_metrics.repaired.increment(repaired);
+
+        Ok(())
+    }
+
     // TODO(joshie) TEMPORARY should be moved to trait providers
     /// Unwind or peek at last N blocks of state recreating the [`ExecutionOutcome`].
     ///
@@ -848,15 +1254,18 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
                 let recovered = match (tx, sender) {
                     (Some((tx_id, tx)), Some((sender_tx_id, sender))) => {
                         if tx_id != sender_tx_id {
-                            Err(ProviderError::MismatchOfTransactionAndSenderId { tx_id })
+                            Err(ProviderError::MismatchOfTransactionAndSenderId {
+                                block_number,
+                                tx_id,
+                            })
                         } else {
                             Ok(TransactionSignedEcRecovered::from_signed_transaction(tx, sender))
                         }
                     }
                     (Some((tx_id, _)), _) | (_, Some((tx_id, _))) => {
-                        Err(ProviderError::MismatchOfTransactionAndSenderId { tx_id })
+                        Err(ProviderError::MismatchOfTransactionAndSenderId { block_number, tx_id })
                     }
-                    (None, None) => Err(ProviderError::BlockBodyTransactionCount),
+                    (None, None) => Err(ProviderError::BlockBodyTransactionCount(block_number)),
                 }?;
                 one_block_tx.push(recovered)
             }
@@ -1216,6 +1625,7 @@ impl<TX: DbTx> AccountExtReader for DatabaseProvider<TX> {
     ) -> ProviderResult<BTreeSet<Address>> {
         self.tx
             .cursor_read::<tables::AccountChangeSets>()?
+            .with_sequential_access_hint()
             .walk_range(range)?
             .map(|entry| {
                 entry.map(|(_, account_before)| account_before.address).map_err(Into::into)
@@ -1238,7 +1648,8 @@ impl<TX: DbTx> AccountExtReader for DatabaseProvider<TX> {
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<Address, Vec<u64>>> {
-        let mut changeset_cursor = self.tx.cursor_read::<tables::AccountChangeSets>()?;
+        let mut changeset_cursor =
+            self.tx.cursor_read::<tables::AccountChangeSets>()?.with_sequential_access_hint();
 
         let account_transitions = changeset_cursor.walk_range(range)?.try_fold(
             BTreeMap::new(),
@@ -1422,20 +1833,12 @@ impl<TX: DbTx> BlockHashReader for DatabaseProvider<TX> {
         )
     }
 
-    fn canonical_hashes_range(
+    fn canonical_hashes_iter(
         &self,
-        start: BlockNumber,
-        end: BlockNumber,
-    ) -> ProviderResult<Vec<B256>> {
-        self.static_file_provider.get_range_with_static_file_or_database(
-            StaticFileSegment::Headers,
-            start..end,
-            |static_file, range, _| static_file.canonical_hashes_range(range.start, range.end),
-            |range, _| {
-                self.cursor_read_collect::<tables::CanonicalHeaders>(range).map_err(Into::into)
-            },
-            |_| true,
-        )
+        range: Range<BlockNumber>,
+    ) -> ProviderResult<CanonicalHashesIter<'_>> {
+        let cursor = self.tx.cursor_read::<tables::CanonicalHeaders>()?;
+        Ok(self.static_file_provider.canonical_hashes_iter_with_database(range, cursor))
     }
 }
 
@@ -1550,48 +1953,7 @@ impl<TX: DbTx> BlockReader for DatabaseProvider<TX> {
         id: BlockHashOrNumber,
         transaction_kind: TransactionVariant,
     ) -> ProviderResult<Option<BlockWithSenders>> {
-        let Some(block_number) = self.convert_hash_or_number(id)? else { return Ok(None) };
-        let Some(header) = self.header_by_number(block_number)? else { return Ok(None) };
-
-        let ommers = self.ommers(block_number.into())?.unwrap_or_default();
-        let withdrawals = self.withdrawals_by_block(block_number.into(), header.timestamp)?;
-        let requests = self.requests_by_block(block_number.into(), header.timestamp)?;
-
-        // Get the block body
-        //
-        // If the body indices are not found, this means that the transactions either do not exist
-        // in the database yet, or they do exit but are not indexed. If they exist but are not
-        // indexed, we don't have enough information to return the block anyways, so we return
-        // `None`.
-        let Some(body) = self.block_body_indices(block_number)? else { return Ok(None) };
-
-        let tx_range = body.tx_num_range();
-
-        let (transactions, senders) = if tx_range.is_empty() {
-            (vec![], vec![])
-        } else {
-            (self.transactions_by_tx_range(tx_range.clone())?, self.senders_by_tx_range(tx_range)?)
-        };
-
-        let body = transactions
-            .into_iter()
-            .map(|tx| match transaction_kind {
-                TransactionVariant::NoHash => TransactionSigned {
-                    // Caller explicitly asked for no hash, so we don't calculate it
-                    hash: B256::ZERO,
-                    signature: tx.signature,
-                    transaction: tx.transaction,
-                },
-                TransactionVariant::WithHash => tx.with_hash(),
-            })
-            .collect();
-
-        Block { header, body, ommers, withdrawals, requests }
-            // Note: we're using unchecked here because we know the block contains valid txs wrt to
-            // its height and can ignore the s value check so pre EIP-2 txs are allowed
-            .try_with_senders_unchecked(senders)
-            .map(Some)
-            .map_err(|_| ProviderError::SenderRecoveryError)
+        self.block_with_recovered_senders(id, transaction_kind, |_| Ok(()))
     }
 
     fn block_range(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Vec<Block>> {
@@ -1867,6 +2229,10 @@ impl<TX: DbTx> TransactionsProvider for DatabaseProvider<TX> {
         self.cursor_read_collect::<tables::TransactionSenders>(range).map_err(Into::into)
     }
 
+    fn senders_by_block(&self, id: BlockHashOrNumber) -> ProviderResult<Option<Vec<Address>>> {
+        self.senders_by_block_with_recovery(id, |_| Ok(()))
+    }
+
     fn transaction_sender(&self, id: TxNumber) -> ProviderResult<Option<Address>> {
         Ok(self.tx.get::<tables::TransactionSenders>(id)?)
     }
@@ -1944,6 +2310,18 @@ impl<TX: DbTx> WithdrawalsProvider for DatabaseProvider<TX> {
         Ok(latest_block_withdrawal
             .and_then(|(_, mut block_withdrawal)| block_withdrawal.withdrawals.pop()))
     }
+
+    fn latest_withdrawal_until(&self, block: BlockNumber) -> ProviderResult<Option<Withdrawal>> {
+        let mut cursor = self.tx.cursor_read::<tables::BlockWithdrawals>()?;
+        // position the cursor right after the block and step back to the closest block with
+        // withdrawals
+        let block_withdrawal = if cursor.seek(block.saturating_add(1))?.is_some() {
+            cursor.prev()?
+        } else {
+            cursor.last()?
+        };
+        Ok(block_withdrawal.and_then(|(_, mut withdrawals)| withdrawals.withdrawals.pop()))
+    }
 }
 
 impl<TX: DbTx> RequestsProvider for DatabaseProvider<TX> {
@@ -2151,6 +2529,7 @@ impl<TX: DbTx> StorageReader for DatabaseProvider<TX> {
     ) -> ProviderResult<BTreeMap<Address, BTreeSet<B256>>> {
         self.tx
             .cursor_read::<tables::StorageChangeSets>()?
+            .with_sequential_access_hint()
             .walk_range(BlockNumberAddress::range(range))?
             // fold all storages and save its old state so we can remove it from HashedStorage
             // it is needed as it is dup table.
@@ -2165,7 +2544,8 @@ impl<TX: DbTx> StorageReader for DatabaseProvider<TX> {
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<(Address, B256), Vec<u64>>> {
-        let mut changeset_cursor = self.tx.cursor_read::<tables::StorageChangeSets>()?;
+        let mut changeset_cursor =
+            self.tx.cursor_read::<tables::StorageChangeSets>()?.with_sequential_access_hint();
 
         let storage_changeset_lists =
             changeset_cursor.walk_range(BlockNumberAddress::range(range))?.try_fold(
@@ -2195,6 +2575,7 @@ impl<TX: DbTxMut + DbTx> HashingWriter for DatabaseProvider<TX> {
         let hashed_accounts = self
             .tx
             .cursor_read::<tables::AccountChangeSets>()?
+            .with_sequential_access_hint()
             .walk_range(range)?
             .map(|entry| entry.map(|(_, e)| (keccak256(e.address), e.info)))
             .collect::<Result<Vec<_>, _>>()?
@@ -2237,7 +2618,8 @@ impl<TX: DbTxMut + DbTx> HashingWriter for DatabaseProvider<TX> {
         range: Range<BlockNumberAddress>,
     ) -> ProviderResult<HashMap<B256, BTreeSet<B256>>> {
         // Aggregate all block changesets and make list of accounts that have been changed.
-        let mut changesets = self.tx.cursor_read::<tables::StorageChangeSets>()?;
+        let mut changesets =
+            self.tx.cursor_read::<tables::StorageChangeSets>()?.with_sequential_access_hint();
         let mut hashed_storages = changesets
             .walk_range(range)?
             .map(|entry| {
@@ -2397,6 +2779,7 @@ impl<TX: DbTxMut + DbTx> HistoryWriter for DatabaseProvider<TX> {
         let mut last_indices = self
             .tx
             .cursor_read::<tables::AccountChangeSets>()?
+            .with_sequential_access_hint()
             .walk_range(range)?
             .map(|entry| entry.map(|(index, account)| (account.address, index)))
             .collect::<Result<Vec<_>, _>>()?;
@@ -2443,6 +2826,7 @@ impl<TX: DbTxMut + DbTx> HistoryWriter for DatabaseProvider<TX> {
         let mut storage_changesets = self
             .tx
             .cursor_read::<tables::StorageChangeSets>()?
+            .with_sequential_access_hint()
             .walk_range(range)?
             .map(|entry| {
                 entry.map(|(BlockNumberAddress((bn, address)), storage)| (address, storage.key, bn))
@@ -2505,6 +2889,296 @@ impl<TX: DbTxMut + DbTx> HistoryWriter for DatabaseProvider<TX> {
     }
 }
 
+impl<TX: DbTx> TransactionHistoryProvider for DatabaseProvider<TX> {
+    fn transaction_history_settings(&self) -> ProviderResult<Option<TransactionHistorySettings>> {
+        Ok(self
+            .get_stage_checkpoint_progress(StageId::TransactionHistory)?
+            .and_then(|buf| TransactionHistorySettings::decode(&buf)))
+    }
+
+    fn transactions_by_address(
+        &self,
+        address: Address,
+        before_tx_number: Option<TxNumber>,
+        limit: usize,
+        direction: HistoryDirection,
+    ) -> ProviderResult<Vec<(TxNumber, TransactionMeta)>> {
+        if limit == 0 {
+            return Ok(Vec::new())
+        }
+
+        let mut cursor = self.tx.cursor_dup_read::<tables::AddressTransactions>()?;
+        let mut tx_numbers = Vec::new();
+
+        match direction {
+            HistoryDirection::Forward => {
+                let mut entry = match before_tx_number {
+                    Some(tx_number) => match tx_number.checked_add(1) {
+                        Some(from) => cursor.seek_by_key_subkey(address, from)?,
+                        None => None,
+                    },
+                    None => cursor.seek_exact(address)?.map(|(_, entry)| entry),
+                };
+                while let Some(StoredTxNumber(tx_number)) = entry {
+                    tx_numbers.push(tx_number);
+                    if tx_numbers.len() == limit {
+                        break
+                    }
+                    entry = cursor.next_dup_val()?;
+                }
+            }
+            HistoryDirection::Backward => {
+                let above_cursor = match before_tx_number {
+                    Some(before) => cursor.seek_by_key_subkey(address, before)?.is_some(),
+                    None => false,
+                };
+                let mut entry = if above_cursor {
+                    // The cursor is at the first entry not below the pagination cursor
+                    cursor.prev_dup_val()?
+                } else {
+                    // All entries are below the pagination cursor, start at the last one
+                    match cursor.seek_exact(address)? {
+                        Some(_) => cursor.last_dup_val()?,
+                        None => None,
+                    }
+                };
+                while let Some(StoredTxNumber(tx_number)) = entry {
+                    tx_numbers.push(tx_number);
+                    if tx_numbers.len() == limit {
+                        break
+                    }
+                    entry = cursor.prev_dup_val()?;
+                }
+            }
+        }
+
+        let mut block_cursor = self.tx.cursor_read::<tables::TransactionBlocks>()?;
+        let mut block: Option<(BlockNumber, SealedHeader, StoredBlockBodyIndices)> = None;
+        let mut transactions = Vec::with_capacity(tx_numbers.len());
+        for tx_number in tx_numbers {
+            // Consecutive transactions are often in the same block
+            let (block_number, header, body) = match block.take() {
+                Some(block) if block.2.tx_num_range().contains(&tx_number) => block,
+                _ => {
+                    let block_number = block_cursor
+                        .seek(tx_number)?
+                        .map(|(_, block_number)| block_number)
+                        .ok_or(ProviderError::TransactionNotFound(tx_number.into()))?;
+                    let header = self
+                        .sealed_header(block_number)?
+                        .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?;
+                    let body = self
+                        .block_body_indices(block_number)?
+                        .ok_or(ProviderError::BlockBodyIndicesNotFound(block_number))?;
+                    (block_number, header, body)
+                }
+            };
+
+            let tx_hash = self
+                .transaction_by_id(tx_number)?
+                .ok_or(ProviderError::TransactionNotFound(tx_number.into()))?
+                .hash;
+            transactions.push((
+                tx_number,
+                TransactionMeta {
+                    tx_hash,
+                    index: tx_number - body.first_tx_num(),
+                    block_hash: header.hash(),
+                    block_number,
+                    base_fee: header.base_fee_per_gas,
+                    excess_blob_gas: header.excess_blob_gas,
+                    timestamp: header.timestamp,
+                },
+            ));
+            block = Some((block_number, header, body));
+        }
+
+        Ok(transactions)
+    }
+}
+
+impl<TX: DbTxMut + DbTx> TransactionHistoryWriter for DatabaseProvider<TX> {
+    fn save_transaction_history_settings(
+        &self,
+        settings: Option<TransactionHistorySettings>,
+    ) -> ProviderResult<()> {
+        let key = StageId::TransactionHistory.to_string();
+        match settings {
+            Some(settings) => {
+                self.tx.put::<tables::StageCheckpointProgresses>(key, settings.encode())?
+            }
+            None => {
+                self.tx.delete::<tables::StageCheckpointProgresses>(key, None)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn index_transaction_history(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        index_logs: bool,
+    ) -> ProviderResult<usize> {
+        let entries = self.transaction_history_entries(range, index_logs)?;
+        let mut cursor = self.tx.cursor_dup_write::<tables::AddressTransactions>()?;
+        for &(address, tx_number) in &entries {
+            cursor.upsert(address, StoredTxNumber(tx_number))?;
+        }
+        Ok(entries.len())
+    }
+
+    fn unindex_transaction_history(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<usize> {
+        let entries = self.transaction_history_entries(range, true)?;
+        let mut cursor = self.tx.cursor_dup_write::<tables::AddressTransactions>()?;
+        let mut removed = 0;
+        for (address, tx_number) in entries {
+            if cursor
+                .seek_by_key_subkey(address, tx_number)?
+                .filter(|entry| entry.0 == tx_number)
+                .is_some()
+            {
+                cursor.delete_current()?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// Returns the key of the node of the header accumulator at the given position in
+/// [`tables::HeaderAccumulator`].
+const fn header_accumulator_key(height: u8, index: u64) -> u64 {
+    ((height as u64) << 56) | index
+}
+
+impl<TX: DbTx> HeaderAccumulatorProvider for DatabaseProvider<TX> {
+    fn header_accumulator_leaf_count(&self) -> ProviderResult<Option<u64>> {
+        Ok(self
+            .get_stage_checkpoint_progress(StageId::HeaderAccumulator)?
+            .and_then(|buf| Some(u64::from_be_bytes(buf.try_into().ok()?))))
+    }
+
+    fn accumulator_root_at(&self, block: BlockNumber) -> ProviderResult<Option<B256>> {
+        if self.header_accumulator_leaf_count()?.map_or(true, |leaf_count| block >= leaf_count) {
+            return Ok(None)
+        }
+        let peaks = self.header_accumulator_peaks(block + 1)?;
+        Ok(Some(accumulator_root(block + 1, &peaks)))
+    }
+
+    fn prove_canonical(&self, block: BlockNumber) -> ProviderResult<Option<MmrProof>> {
+        let Some(leaf_count) = self.header_accumulator_leaf_count()?.filter(|count| block < *count)
+        else {
+            return Ok(None)
+        };
+
+        // The height of the peak that covers the block
+        let height = peak_positions(leaf_count)
+            .into_iter()
+            .find_map(|(height, index)| (block >> height == index).then_some(height))
+            .unwrap_or_default();
+        let siblings = (0..height)
+            .map(|height| self.header_accumulator_node(height, (block >> height) ^ 1))
+            .collect::<ProviderResult<_>>()?;
+
+        Ok(Some(MmrProof {
+            block_number: block,
+            leaf_count,
+            siblings,
+            peaks: self.header_accumulator_peaks(leaf_count)?,
+        }))
+    }
+}
+
+impl<TX: DbTxMut + DbTx> HeaderAccumulatorWriter for DatabaseProvider<TX> {
+    fn reset_header_accumulator(&self, enabled: bool) -> ProviderResult<()> {
+        self.tx.clear::<tables::HeaderAccumulator>()?;
+        let key = StageId::HeaderAccumulator.to_string();
+        if enabled {
+            self.tx.put::<tables::StageCheckpointProgresses>(key, 0u64.to_be_bytes().to_vec())?;
+        } else {
+            self.tx.delete::<tables::StageCheckpointProgresses>(key, None)?;
+        }
+        Ok(())
+    }
+
+    fn append_header_accumulator(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<()> {
+        let leaf_count = self.header_accumulator_leaf_count()?.unwrap_or_default();
+        if leaf_count != *range.start() {
+            return Err(ProviderError::HeaderAccumulatorGap {
+                block_number: *range.start(),
+                leaf_count,
+            })
+        }
+
+        // The peaks from the highest to the lowest, the appended leaves are merged with the peaks
+        // of the same height
+        let mut peaks = peak_positions(leaf_count)
+            .into_iter()
+            .map(|(height, _)| height)
+            .zip(self.header_accumulator_peaks(leaf_count)?)
+            .collect::<Vec<_>>();
+        let mut hashes = self.canonical_hashes_iter(*range.start()..range.end() + 1)?;
+        for block_number in range.clone() {
+            let hash = hashes
+                .next()
+                .transpose()?
+                .filter(|(number, _)| *number == block_number)
+                .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?
+                .1;
+
+            let (mut height, mut node) = (0, hash);
+            while let Some(&(peak_height, left)) = peaks.last() {
+                if peak_height != height {
+                    break
+                }
+                peaks.pop();
+                node = merge_nodes(&left, &node);
+                height += 1;
+                self.tx.put::<tables::HeaderAccumulator>(
+                    header_accumulator_key(height, block_number >> height),
+                    node,
+                )?;
+            }
+            peaks.push((height, node));
+        }
+
+        self.tx.put::<tables::StageCheckpointProgresses>(
+            StageId::HeaderAccumulator.to_string(),
+            (range.end() + 1).to_be_bytes().to_vec(),
+        )?;
+        Ok(())
+    }
+
+    fn truncate_header_accumulator(&self, leaf_count: u64) -> ProviderResult<()> {
+        let Some(current) = self.header_accumulator_leaf_count()? else { return Ok(()) };
+        if current <= leaf_count {
+            return Ok(())
+        }
+
+        // The nodes at each height that cover one of the removed leaves
+        let mut cursor = self.tx.cursor_write::<tables::HeaderAccumulator>()?;
+        for height in (1..u64::BITS as u8).take_while(|height| current >> height > 0) {
+            let mut walker = cursor.walk_range(
+                header_accumulator_key(height, leaf_count >> height)..
+                    header_accumulator_key(height, current >> height),
+            )?;
+            while walker.next().transpose()?.is_some() {
+                walker.delete_current()?;
+            }
+        }
+
+        self.tx.put::<tables::StageCheckpointProgresses>(
+            StageId::HeaderAccumulator.to_string(),
+            leaf_count.to_be_bytes().to_vec(),
+        )?;
+        Ok(())
+    }
+}
+
 impl<TX: DbTxMut + DbTx> BlockExecutionWriter for DatabaseProvider<TX> {
     /// Return range of blocks and its execution result
     fn get_or_take_block_and_execution_range<const TAKE: bool>(
@@ -2512,6 +3186,14 @@ impl<TX: DbTxMut + DbTx> BlockExecutionWriter for DatabaseProvider<TX> {
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<Chain> {
         if TAKE {
+            // Unwind the transaction history index while the transactions and receipts are still
+            // available.
+            if self.transaction_history_settings()?.is_some() {
+                self.unindex_transaction_history(range.clone())?;
+            }
+            // Remove the unwound blocks from the header accumulator, if it's enabled.
+            self.truncate_header_accumulator(*range.start())?;
+
             let storage_range = BlockNumberAddress::range(range.clone());
 
             // Unwind account hashes. Add changed accounts to account prefix set.
@@ -2760,6 +3442,13 @@ impl<TX: DbTxMut + DbTx> BlockWriter for DatabaseProvider<TX> {
         let last = blocks.last().unwrap();
         let last_block_number = last.number;
 
+        // refuse to write receipts that are inconsistent with the gas used by their blocks
+        let blocks_gas = blocks
+            .iter()
+            .map(|block| BlockGas::new(&block.header, &block.body))
+            .collect::<Vec<_>>();
+        verify_receipts_gas(&blocks_gas, &execution_outcome.receipts)?;
+
         let mut durations_recorder = metrics::DurationsRecorder::default();
 
         // Insert the blocks
@@ -2781,6 +3470,12 @@ impl<TX: DbTxMut + DbTx> BlockWriter for DatabaseProvider<TX> {
         durations_recorder.record_relative(metrics::Action::InsertHashes);
 
         self.update_history_indices(first_number..=last_block_number)?;
+        if let Some(settings) = self.transaction_history_settings()? {
+            self.index_transaction_history(first_number..=last_block_number, settings.index_logs)?;
+        }
+        if self.header_accumulator_leaf_count()? == Some(first_number) {
+            self.append_header_accumulator(first_number..=last_block_number)?;
+        }
         durations_recorder.record_relative(metrics::Action::InsertHistoryIndices);
 
         // Update pipeline progress
@@ -2825,6 +3520,66 @@ impl<TX: DbTx> StatsReader for DatabaseProvider<TX> {
     }
 }
 
+impl<TX: DbTx> HashedStateRangeProvider for DatabaseProvider<TX> {
+    fn hashed_state_root(&self) -> ProviderResult<Option<B256>> {
+        let best = self.best_block_number()?;
+        for stage_id in [StageId::AccountHashing, StageId::StorageHashing, StageId::MerkleExecute] {
+            let checkpoint = self.get_stage_checkpoint(stage_id)?.unwrap_or_default();
+            if checkpoint.block_number != best {
+                return Ok(None)
+            }
+        }
+        Ok(self.header_by_number(best)?.map(|header| header.state_root))
+    }
+
+    fn hashed_account_range(
+        &self,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, TrieAccount)>> {
+        let proof = Proof::new(&self.tx);
+        let mut accounts = Vec::new();
+        for entry in self.tx.cursor_read::<tables::HashedAccounts>()?.walk(Some(start))?.take(limit)
+        {
+            let (hashed_address, account) = entry?;
+            let storage_root = proof
+                .storage_root(hashed_address)
+                .map_err(|err| DatabaseError::from(StateRootError::from(err)))?;
+            accounts.push((hashed_address, (account, storage_root).to_trie_account()));
+        }
+        Ok(accounts)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<StorageEntry>> {
+        Ok(self
+            .tx
+            .cursor_dup_read::<tables::HashedStorages>()?
+            .walk_dup(Some(hashed_address), Some(start))?
+            .take(limit)
+            .map(|entry| entry.map(|(_, storage)| storage))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn account_range_proof(&self, targets: &[B256]) -> ProviderResult<Vec<Bytes>> {
+        Ok(Proof::new(&self.tx).account_proof_nodes(targets).map_err(DatabaseError::from)?)
+    }
+
+    fn storage_range_proof(
+        &self,
+        hashed_address: B256,
+        targets: &[B256],
+    ) -> ProviderResult<Vec<Bytes>> {
+        Ok(Proof::new(&self.tx)
+            .storage_proof_nodes(hashed_address, targets)
+            .map_err(|err| DatabaseError::from(StateRootError::from(err)))?)
+    }
+}
+
 impl<TX: DbTx> FinalizedBlockReader for DatabaseProvider<TX> {
     fn last_finalized_block_number(&self) -> ProviderResult<BlockNumber> {
         let mut finalized_blocks = self
@@ -1,11 +1,13 @@
 use crate::{
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
     BlockSource, BlockchainTreePendingStateProvider, CanonChainTracker, CanonStateNotifications,
-    CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory,
-    EvmEnvProvider, FullExecutionDataProvider, HeaderProvider, ProviderError,
+    CanonStateSubscriptions, CanonicalHashesIter, Chain, ChainSpecProvider, ChangeSetReader,
+    DatabaseProviderFactory, EvmEnvProvider, FullExecutionDataProvider, HashedStateRangeProvider,
+    HeaderAccumulatorProvider, HeaderProvider, HistoryDirection, ProviderError,
     PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt, RequestsProvider,
     StageCheckpointReader, StateProviderBox, StateProviderFactory, StaticFileProviderFactory,
-    TransactionVariant, TransactionsProvider, TreeViewer, WithdrawalsProvider,
+    TransactionHistoryProvider, TransactionHistorySettings, TransactionVariant,
+    TransactionsProvider, TreeViewer, WithdrawalsProvider,
 };
 use reth_blockchain_tree_api::{
     error::{CanonicalError, InsertBlockError},
@@ -18,18 +20,20 @@ use reth_db_api::{
 };
 use reth_evm::ConfigureEvmEnv;
 use reth_primitives::{
-    Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumHash, BlockNumber,
-    BlockNumberOrTag, BlockWithSenders, ChainInfo, ChainSpec, Header, Receipt, SealedBlock,
-    SealedBlockWithSenders, SealedHeader, TransactionMeta, TransactionSigned,
-    TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256, U256,
+    accumulator::MmrProof, Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId,
+    BlockNumHash, BlockNumber, BlockNumberOrTag, BlockWithSenders, Bytes, ChainInfo, ChainSpec,
+    Header, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, StorageEntry,
+    TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal,
+    Withdrawals, B256, U256,
 };
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
 use reth_storage_errors::provider::ProviderResult;
+use reth_trie::TrieAccount;
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use std::{
     collections::BTreeMap,
-    ops::{RangeBounds, RangeInclusive},
+    ops::{Range, RangeBounds, RangeInclusive},
     sync::Arc,
     time::Instant,
 };
@@ -46,19 +50,31 @@ pub use static_file::{
 
 mod state;
 pub use state::{
-    historical::{HistoricalStateProvider, HistoricalStateProviderRef},
+    historical::{HistoricalStateProvider, HistoricalStateProviderRef, HistoricalStorageIter},
     latest::{LatestStateProvider, LatestStateProviderRef},
 };
 
 mod bundle_state_provider;
 pub use bundle_state_provider::BundleStateProvider;
 
+mod state_overrides_provider;
+pub use state_overrides_provider::{AccountStateOverride, StateOverrides, StateOverridesProvider};
+
 mod chain_info;
 use chain_info::ChainInfoTracker;
 
+mod receipts_backfill;
+pub use receipts_backfill::{
+    ExecutionReceiptsBackfill, ReceiptsBackfill, ReceiptsBackfillConfig,
+    DEFAULT_RECEIPTS_BACKFILL_CACHE_SIZE, DEFAULT_RECEIPTS_BACKFILL_MAX_CONCURRENT,
+    DEFAULT_RECEIPTS_BACKFILL_TIMEOUT,
+};
+
 mod consistent_view;
 use alloy_rpc_types_engine::ForkchoiceState;
-pub use consistent_view::{ConsistentDbView, ConsistentViewError};
+pub use consistent_view::{
+    ConsistentDbView, ConsistentReadView, ConsistentViewError, DEFAULT_CONSISTENT_READ_VIEW_MAX_AGE,
+};
 
 /// The main type for interacting with the blockchain.
 ///
@@ -73,6 +89,8 @@ pub struct BlockchainProvider<DB> {
     tree: Arc<dyn TreeViewer>,
     /// Tracks the chain info wrt forkchoice updates
     chain_info: ChainInfoTracker,
+    /// Synthesizes receipts of canonical blocks that are missing in the database, if enabled.
+    receipts_backfill: Option<Arc<dyn ReceiptsBackfill>>,
 }
 
 impl<DB> Clone for BlockchainProvider<DB> {
@@ -81,6 +99,7 @@ impl<DB> Clone for BlockchainProvider<DB> {
             database: self.database.clone(),
             tree: self.tree.clone(),
             chain_info: self.chain_info.clone(),
+            receipts_backfill: self.receipts_backfill.clone(),
         }
     }
 }
@@ -93,7 +112,7 @@ impl<DB> BlockchainProvider<DB> {
         tree: Arc<dyn TreeViewer>,
         latest: SealedHeader,
     ) -> Self {
-        Self { database, tree, chain_info: ChainInfoTracker::new(latest) }
+        Self { database, tree, chain_info: ChainInfoTracker::new(latest), receipts_backfill: None }
     }
 
     /// Sets the treeviewer for the provider.
@@ -102,6 +121,13 @@ impl<DB> BlockchainProvider<DB> {
         self.tree = tree;
         self
     }
+
+    /// Synthesizes the receipts of canonical blocks that are missing in the database with the
+    /// given [`ReceiptsBackfill`], e.g. because the blocks were imported without execution.
+    pub fn with_receipts_backfill(mut self, receipts_backfill: Arc<dyn ReceiptsBackfill>) -> Self {
+        self.receipts_backfill = Some(receipts_backfill);
+        self
+    }
 }
 
 impl<DB> BlockchainProvider<DB>
@@ -121,6 +147,13 @@ where
             None => Err(ProviderError::HeaderNotFound(best.best_number.into())),
         }
     }
+
+    /// Returns a view that serves all database reads from a single read transaction.
+    ///
+    /// See [`ConsistentReadView`] for more info.
+    pub fn consistent_view(&self) -> ProviderResult<ConsistentReadView<DB, ProviderFactory<DB>>> {
+        self.database.consistent_view()
+    }
 }
 
 impl<DB> BlockchainProvider<DB>
@@ -144,6 +177,17 @@ where
             Ok(())
         }
     }
+
+    /// Synthesizes the receipt of the transaction at the given index of the canonical block, if
+    /// the receipts backfill is enabled.
+    fn backfill_receipt(&self, number: BlockNumber, index: u64) -> ProviderResult<Option<Receipt>> {
+        let Some(receipts_backfill) = &self.receipts_backfill else { return Ok(None) };
+
+        trace!(target: "providers::blockchain", number, index, "Synthesizing missing receipt");
+        Ok(receipts_backfill
+            .backfill_receipts(number)?
+            .and_then(|receipts| receipts.into_iter().nth(index as usize)))
+    }
 }
 
 impl<DB> DatabaseProviderFactory<DB> for BlockchainProvider<DB>
@@ -213,12 +257,11 @@ where
         self.database.block_hash(number)
     }
 
-    fn canonical_hashes_range(
+    fn canonical_hashes_iter(
         &self,
-        start: BlockNumber,
-        end: BlockNumber,
-    ) -> ProviderResult<Vec<B256>> {
-        self.database.canonical_hashes_range(start, end)
+        range: Range<BlockNumber>,
+    ) -> ProviderResult<CanonicalHashesIter<'_>> {
+        self.database.canonical_hashes_iter(range)
     }
 }
 
@@ -344,6 +387,25 @@ where
     ) -> ProviderResult<Vec<SealedBlockWithSenders>> {
         self.database.sealed_block_with_senders_range(range)
     }
+
+    fn transaction_and_receipt_by_hash(
+        &self,
+        hash: TxHash,
+    ) -> ProviderResult<Option<(TransactionSigned, TransactionMeta, Receipt)>> {
+        if let Some(found) = self.database.transaction_and_receipt_by_hash(hash)? {
+            return Ok(Some(found))
+        }
+        if self.receipts_backfill.is_none() {
+            return Ok(None)
+        }
+
+        let Some((transaction, meta)) = self.database.transaction_by_hash_with_meta(hash)? else {
+            return Ok(None)
+        };
+        Ok(self
+            .backfill_receipt(meta.block_number, meta.index)?
+            .map(|receipt| (transaction, meta, receipt)))
+    }
 }
 
 impl<DB> TransactionsProvider for BlockchainProvider<DB>
@@ -408,6 +470,10 @@ where
         self.database.senders_by_tx_range(range)
     }
 
+    fn senders_by_block(&self, id: BlockHashOrNumber) -> ProviderResult<Option<Vec<Address>>> {
+        self.database.senders_by_block(id)
+    }
+
     fn transaction_sender(&self, id: TxNumber) -> ProviderResult<Option<Address>> {
         self.database.transaction_sender(id)
     }
@@ -418,15 +484,44 @@ where
     DB: Database,
 {
     fn receipt(&self, id: TxNumber) -> ProviderResult<Option<Receipt>> {
-        self.database.receipt(id)
+        if let Some(receipt) = self.database.receipt(id)? {
+            return Ok(Some(receipt))
+        }
+        if self.receipts_backfill.is_none() {
+            return Ok(None)
+        }
+
+        let Some(number) = self.database.transaction_block(id)? else { return Ok(None) };
+        let Some(body) = self.database.block_body_indices(number)? else { return Ok(None) };
+        self.backfill_receipt(number, id - body.first_tx_num)
     }
 
     fn receipt_by_hash(&self, hash: TxHash) -> ProviderResult<Option<Receipt>> {
-        self.database.receipt_by_hash(hash)
+        if self.receipts_backfill.is_none() {
+            return self.database.receipt_by_hash(hash)
+        }
+
+        let Some(id) = self.database.transaction_id(hash)? else { return Ok(None) };
+        self.receipt(id)
     }
 
     fn receipts_by_block(&self, block: BlockHashOrNumber) -> ProviderResult<Option<Vec<Receipt>>> {
-        self.database.receipts_by_block(block)
+        let receipts = self.database.receipts_by_block(block)?;
+        let Some(receipts_backfill) = &self.receipts_backfill else { return Ok(receipts) };
+        let Some(stored) = &receipts else { return Ok(receipts) };
+
+        // the database only returns the receipts that are stored, so missing receipts are
+        // detected by comparing them with the transactions of the block
+        let Some(number) = self.database.convert_hash_or_number(block)? else {
+            return Ok(receipts)
+        };
+        let tx_count = self.database.block_body_indices(number)?.map_or(0, |body| body.tx_count);
+        if stored.len() as u64 >= tx_count {
+            return Ok(receipts)
+        }
+
+        trace!(target: "providers::blockchain", number, stored = stored.len(), tx_count, "Synthesizing missing receipts");
+        Ok(receipts_backfill.backfill_receipts(number)?.or(receipts))
     }
 
     fn receipts_by_tx_range(
@@ -479,6 +574,10 @@ where
     fn latest_withdrawal(&self) -> ProviderResult<Option<Withdrawal>> {
         self.database.latest_withdrawal()
     }
+
+    fn latest_withdrawal_until(&self, block: BlockNumber) -> ProviderResult<Option<Withdrawal>> {
+        self.database.latest_withdrawal_until(block)
+    }
 }
 
 impl<DB> RequestsProvider for BlockchainProvider<DB>
@@ -507,6 +606,42 @@ where
     }
 }
 
+impl<DB> TransactionHistoryProvider for BlockchainProvider<DB>
+where
+    DB: Database,
+{
+    fn transaction_history_settings(&self) -> ProviderResult<Option<TransactionHistorySettings>> {
+        self.database.transaction_history_settings()
+    }
+
+    fn transactions_by_address(
+        &self,
+        address: Address,
+        before_tx_number: Option<TxNumber>,
+        limit: usize,
+        direction: HistoryDirection,
+    ) -> ProviderResult<Vec<(TxNumber, TransactionMeta)>> {
+        self.database.transactions_by_address(address, before_tx_number, limit, direction)
+    }
+}
+
+impl<DB> HeaderAccumulatorProvider for BlockchainProvider<DB>
+where
+    DB: Database,
+{
+    fn header_accumulator_leaf_count(&self) -> ProviderResult<Option<u64>> {
+        self.database.header_accumulator_leaf_count()
+    }
+
+    fn accumulator_root_at(&self, block: BlockNumber) -> ProviderResult<Option<B256>> {
+        self.database.accumulator_root_at(block)
+    }
+
+    fn prove_canonical(&self, block: BlockNumber) -> ProviderResult<Option<MmrProof>> {
+        self.database.prove_canonical(block)
+    }
+}
+
 impl<DB> EvmEnvProvider for BlockchainProvider<DB>
 where
     DB: Database,
@@ -590,6 +725,44 @@ where
     }
 }
 
+impl<DB> HashedStateRangeProvider for BlockchainProvider<DB>
+where
+    DB: Database,
+{
+    fn hashed_state_root(&self) -> ProviderResult<Option<B256>> {
+        self.database.provider()?.hashed_state_root()
+    }
+
+    fn hashed_account_range(
+        &self,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, TrieAccount)>> {
+        self.database.provider()?.hashed_account_range(start, limit)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<StorageEntry>> {
+        self.database.provider()?.hashed_storage_range(hashed_address, start, limit)
+    }
+
+    fn account_range_proof(&self, targets: &[B256]) -> ProviderResult<Vec<Bytes>> {
+        self.database.provider()?.account_range_proof(targets)
+    }
+
+    fn storage_range_proof(
+        &self,
+        hashed_address: B256,
+        targets: &[B256],
+    ) -> ProviderResult<Vec<Bytes>> {
+        self.database.provider()?.storage_range_proof(hashed_address, targets)
+    }
+}
+
 impl<DB> ChainSpecProvider for BlockchainProvider<DB>
 where
     DB: Send + Sync,
@@ -715,6 +888,17 @@ where
     fn make_canonical(&self, block_hash: BlockHash) -> Result<CanonicalOutcome, CanonicalError> {
         self.tree.make_canonical(block_hash)
     }
+
+    fn unwind_canonical_chain(
+        &self,
+        unwind_to: BlockNumber,
+    ) -> Result<BlockNumber, CanonicalError> {
+        self.tree.unwind_canonical_chain(unwind_to)
+    }
+
+    fn notify_canonical_chain_unwound(&self, unwound: Chain) -> Result<(), CanonicalError> {
+        self.tree.notify_canonical_chain_unwound(unwound)
+    }
 }
 
 impl<DB> BlockchainTreeViewer for BlockchainProvider<DB>
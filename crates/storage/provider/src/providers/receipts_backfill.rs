@@ -0,0 +1,597 @@
+//! Synthesizes missing receipts of canonical blocks by re-executing them.
+
+use crate::{
+    BlockReader, ChainSpecProvider, HeaderProvider, ProviderError, ProviderFactory,
+    StaticFileProviderFactory, TransactionVariant,
+};
+use metrics::Counter;
+use parking_lot::{Condvar, Mutex};
+use reth_db::tables;
+use reth_db_api::{database::Database, transaction::DbTxMut};
+use reth_evm::execute::{
+    BlockExecutionInput, BlockExecutorProvider, ExecutionCancellation, Executor,
+};
+use reth_metrics::Metrics;
+use reth_primitives::{BlockNumber, BlockWithSenders, Receipt, StaticFileSegment, B256};
+use reth_revm::database::StateProviderDatabase;
+use reth_storage_errors::provider::ProviderResult;
+use schnellru::{ByLength, LruMap};
+use std::{
+    fmt,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+use tracing::{debug, trace, warn};
+
+/// Default maximum number of blocks that are re-executed concurrently.
+pub const DEFAULT_RECEIPTS_BACKFILL_MAX_CONCURRENT: usize = 2;
+
+/// Default time a request waits for the receipts of a block to be synthesized.
+pub const DEFAULT_RECEIPTS_BACKFILL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default number of synthesized receipts that are kept in memory.
+pub const DEFAULT_RECEIPTS_BACKFILL_CACHE_SIZE: u32 = 128;
+
+/// Provides receipts of canonical blocks that are missing in storage.
+///
+/// Nodes that imported blocks without executing them, or that pruned their receipts, can still
+/// serve the receipts of blocks whose parent state is available by executing them again.
+pub trait ReceiptsBackfill: Send + Sync {
+    /// Returns the receipts of the canonical block with the given number.
+    ///
+    /// Returns `None` if the receipts can't be synthesized, e.g. because the state of the parent
+    /// block is not available or the request timed out.
+    fn backfill_receipts(&self, block: BlockNumber) -> ProviderResult<Option<Vec<Receipt>>>;
+}
+
+/// Limits of the [`ExecutionReceiptsBackfill`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReceiptsBackfillConfig {
+    /// Maximum number of blocks that are re-executed concurrently.
+    pub max_concurrent: usize,
+    /// Maximum time a request waits for other executions and re-executes its block.
+    pub timeout: Duration,
+    /// Whether synthesized receipts are written to the database.
+    pub persist: bool,
+    /// Number of synthesized receipts that are kept in memory.
+    pub cache_size: u32,
+}
+
+impl Default for ReceiptsBackfillConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: DEFAULT_RECEIPTS_BACKFILL_MAX_CONCURRENT,
+            timeout: DEFAULT_RECEIPTS_BACKFILL_TIMEOUT,
+            persist: true,
+            cache_size: DEFAULT_RECEIPTS_BACKFILL_CACHE_SIZE,
+        }
+    }
+}
+
+/// A [`ReceiptsBackfill`] that re-executes blocks on top of the historical state of their parent.
+///
+/// The receipts are only served if their root matches the receipts root of the header. Blocks
+/// before Byzantium are never re-executed, because their receipts commit to intermediate state
+/// roots.
+pub struct ExecutionReceiptsBackfill<DB, E> {
+    /// Access to the blocks and the historical state.
+    provider_factory: ProviderFactory<DB>,
+    /// Re-executes the blocks.
+    executor: E,
+    config: ReceiptsBackfillConfig,
+    /// Number of blocks that are currently re-executed.
+    running: Mutex<usize>,
+    /// Notified whenever a re-execution finishes.
+    finished: Condvar,
+    /// Recently synthesized receipts.
+    cache: Mutex<LruMap<BlockNumber, Vec<Receipt>>>,
+    metrics: ReceiptsBackfillMetrics,
+}
+
+impl<DB, E> ExecutionReceiptsBackfill<DB, E> {
+    /// Creates a new instance with the given limits.
+    pub fn new(
+        provider_factory: ProviderFactory<DB>,
+        executor: E,
+        config: ReceiptsBackfillConfig,
+    ) -> Self {
+        Self {
+            provider_factory,
+            executor,
+            config,
+            running: Mutex::new(0),
+            finished: Condvar::new(),
+            cache: Mutex::new(LruMap::new(ByLength::new(config.cache_size))),
+            metrics: Default::default(),
+        }
+    }
+
+    /// Waits until fewer than the maximum number of blocks are re-executed, or until the
+    /// deadline passed.
+    fn acquire(&self, deadline: Instant) -> Option<ExecutionPermit<'_, DB, E>> {
+        let mut running = self.running.lock();
+        while *running >= self.config.max_concurrent {
+            if self.finished.wait_until(&mut running, deadline).timed_out() {
+                return None
+            }
+        }
+        *running += 1;
+        Some(ExecutionPermit(self))
+    }
+}
+
+impl<DB, E> ExecutionReceiptsBackfill<DB, E>
+where
+    DB: Database,
+    E: BlockExecutorProvider,
+{
+    /// Re-executes the block and returns its receipts if they match the header.
+    fn execute(
+        &self,
+        block: BlockNumber,
+        cancellation: &ExecutionCancellation,
+    ) -> ProviderResult<Option<Vec<Receipt>>> {
+        if !self.provider_factory.chain_spec().is_byzantium_active_at_block(block) {
+            trace!(target: "providers::receipts_backfill", block, "Not re-executing block before Byzantium");
+            return Ok(None)
+        }
+        let Some(parent) = block.checked_sub(1) else { return Ok(None) };
+
+        let state = match self.provider_factory.history_by_block_number(parent) {
+            Ok(state) => state,
+            Err(ProviderError::StateAtBlockPruned(_)) => {
+                trace!(target: "providers::receipts_backfill", block, "State of the parent block is pruned");
+                return Ok(None)
+            }
+            Err(err) => return Err(err),
+        };
+        let Some(block_with_senders) =
+            self.provider_factory.block_with_senders(block.into(), TransactionVariant::WithHash)?
+        else {
+            return Ok(None)
+        };
+        let total_difficulty = self
+            .provider_factory
+            .header_td_by_number(block)?
+            .ok_or(ProviderError::TotalDifficultyNotFound(block))?;
+
+        self.metrics.executions.increment(1);
+        let input = BlockExecutionInput::new(&block_with_senders, total_difficulty)
+            .with_cancellation(cancellation);
+        let output = match self.executor.executor(StateProviderDatabase::new(state)).execute(input)
+        {
+            Ok(output) => output,
+            Err(err) if err.is_cancelled() => {
+                debug!(target: "providers::receipts_backfill", block, "Timed out re-executing block");
+                self.metrics.timed_out.increment(1);
+                return Ok(None)
+            }
+            Err(err) => {
+                debug!(target: "providers::receipts_backfill", block, %err, "Failed to re-execute block");
+                self.metrics.failed.increment(1);
+                return Ok(None)
+            }
+        };
+
+        let receipts_root = self.receipts_root(&block_with_senders, &output.receipts);
+        if receipts_root != block_with_senders.receipts_root {
+            debug!(target: "providers::receipts_backfill", block, ?receipts_root, expected = ?block_with_senders.receipts_root, "Receipts of re-executed block don't match the header");
+            self.metrics.failed.increment(1);
+            return Ok(None)
+        }
+
+        Ok(Some(output.receipts))
+    }
+
+    /// Computes the receipts root of the block.
+    #[cfg(not(feature = "optimism"))]
+    fn receipts_root(&self, _block: &BlockWithSenders, receipts: &[Receipt]) -> B256 {
+        reth_primitives::proofs::calculate_receipt_root_no_memo(
+            &receipts.iter().collect::<Vec<_>>(),
+        )
+    }
+
+    /// Computes the receipts root of the block.
+    #[cfg(feature = "optimism")]
+    fn receipts_root(&self, block: &BlockWithSenders, receipts: &[Receipt]) -> B256 {
+        reth_primitives::proofs::calculate_receipt_root_no_memo_optimism(
+            &receipts.iter().collect::<Vec<_>>(),
+            &self.provider_factory.chain_spec(),
+            block.timestamp,
+        )
+    }
+}
+
+impl<DB, E> ExecutionReceiptsBackfill<DB, E>
+where
+    DB: Database + 'static,
+{
+    /// Writes the receipts of the block to the database and waits for the write until the
+    /// deadline.
+    ///
+    /// The write lock may be held by the pipeline or the tree for a long time, so the receipts
+    /// are written on a separate thread that outlives the request if needed. Failures are only
+    /// logged, because the receipts are valid either way.
+    fn persist(&self, block: BlockNumber, receipts: Vec<Receipt>, deadline: Instant) {
+        let provider_factory = self.provider_factory.clone();
+        let persisted = self.metrics.persisted.clone();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let result = write_receipts(&provider_factory, block, &receipts);
+            if let Ok(true) = result {
+                persisted.increment(1);
+            }
+            let _ = tx.send(result);
+        });
+
+        match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(Ok(_)) => {}
+            Ok(Err(err)) => {
+                warn!(target: "providers::receipts_backfill", block, %err, "Failed to persist synthesized receipts");
+            }
+            Err(_) => {
+                debug!(target: "providers::receipts_backfill", block, "Timed out waiting for synthesized receipts to be persisted");
+            }
+        }
+    }
+}
+
+/// Writes the receipts of the block to the database, unless the block is already covered by the
+/// receipts static files.
+///
+/// Returns `true` if the receipts were written.
+fn write_receipts<DB: Database>(
+    provider_factory: &ProviderFactory<DB>,
+    block: BlockNumber,
+    receipts: &[Receipt],
+) -> ProviderResult<bool> {
+    let provider = provider_factory.provider_rw()?;
+    let body = provider
+        .block_body_indices(block)?
+        .ok_or(ProviderError::BlockBodyIndicesNotFound(block))?;
+
+    let highest_static_file_tx = provider_factory
+        .static_file_provider()
+        .get_highest_static_file_tx(StaticFileSegment::Receipts);
+    if highest_static_file_tx.is_some_and(|tx| tx >= body.first_tx_num) {
+        trace!(target: "providers::receipts_backfill", block, "Block is covered by the receipts static files");
+        return Ok(false)
+    }
+
+    for (tx_num, receipt) in body.tx_num_range().zip(receipts) {
+        provider.tx_ref().put::<tables::Receipts>(tx_num, receipt.clone())?;
+    }
+    provider.commit()?;
+    Ok(true)
+}
+
+impl<DB, E> ReceiptsBackfill for ExecutionReceiptsBackfill<DB, E>
+where
+    DB: Database + 'static,
+    E: BlockExecutorProvider,
+{
+    fn backfill_receipts(&self, block: BlockNumber) -> ProviderResult<Option<Vec<Receipt>>> {
+        if let Some(receipts) = self.cache.lock().get(&block) {
+            return Ok(Some(receipts.clone()))
+        }
+
+        let deadline = Instant::now() + self.config.timeout;
+        let Some(_permit) = self.acquire(deadline) else {
+            debug!(target: "providers::receipts_backfill", block, "Timed out waiting for other re-executions");
+            self.metrics.timed_out.increment(1);
+            return Ok(None)
+        };
+
+        // the block may have been re-executed while waiting for the permit
+        if let Some(receipts) = self.cache.lock().get(&block) {
+            return Ok(Some(receipts.clone()))
+        }
+
+        let cancellation = ExecutionCancellation::with_soft_limit(
+            deadline.saturating_duration_since(Instant::now()),
+        );
+        let Some(receipts) = self.execute(block, &cancellation)? else { return Ok(None) };
+
+        self.cache.lock().insert(block, receipts.clone());
+        if self.config.persist {
+            self.persist(block, receipts.clone(), deadline);
+        }
+
+        Ok(Some(receipts))
+    }
+}
+
+impl<DB, E> fmt::Debug for ExecutionReceiptsBackfill<DB, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExecutionReceiptsBackfill")
+            .field("config", &self.config)
+            .field("running", &*self.running.lock())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Allows a request to re-execute a block, released once dropped.
+struct ExecutionPermit<'a, DB, E>(&'a ExecutionReceiptsBackfill<DB, E>);
+
+impl<DB, E> Drop for ExecutionPermit<'_, DB, E> {
+    fn drop(&mut self) {
+        *self.0.running.lock() -= 1;
+        self.0.finished.notify_one();
+    }
+}
+
+/// Metrics of the [`ExecutionReceiptsBackfill`].
+#[derive(Metrics)]
+#[metrics(scope = "storage.providers.receipts_backfill")]
+struct ReceiptsBackfillMetrics {
+    /// Number of blocks that were re-executed
+    executions: Counter,
+    /// Number of re-executed blocks whose execution failed or whose receipts didn't match
+    failed: Counter,
+    /// Number of requests that timed out
+    timed_out: Counter,
+    /// Number of blocks whose synthesized receipts were written to the database
+    persisted: Counter,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        providers::BlockchainProvider, test_utils::create_test_provider_factory_with_chain_spec,
+        BlockWriter, BlockchainTreePendingStateProvider, CanonStateNotificationSender,
+        CanonStateNotifications, CanonStateSubscriptions, Chain, ExecutionOutcome,
+        FullExecutionDataProvider, ReceiptProvider, TransactionsProvider,
+    };
+    use reth_blockchain_tree_api::{
+        error::{BlockchainTreeError, CanonicalError, InsertBlockError},
+        BlockValidationKind, BlockchainTreeEngine, BlockchainTreeViewer, CanonicalOutcome,
+        InsertPayloadOk,
+    };
+    use reth_db::{test_utils::TempDatabase, DatabaseEnv};
+    use reth_evm::test_utils::MockExecutorProvider;
+    use reth_primitives::{
+        proofs::calculate_receipt_root_no_memo, BlockHash, BlockNumHash, ChainSpecBuilder,
+        SealedBlock, SealedBlockWithSenders, SealedHeader,
+    };
+    use reth_testing_utils::generators::{self, random_block, random_receipt};
+    use std::{collections::BTreeMap, sync::Arc};
+
+    type TestProviderFactory = ProviderFactory<Arc<TempDatabase<DatabaseEnv>>>;
+
+    /// A tree without any blocks, so the [`BlockchainProvider`] only serves the database.
+    #[derive(Debug)]
+    struct EmptyTree;
+
+    impl BlockchainTreeEngine for EmptyTree {
+        fn buffer_block(&self, _block: SealedBlockWithSenders) -> Result<(), InsertBlockError> {
+            Ok(())
+        }
+
+        fn insert_block(
+            &self,
+            block: SealedBlockWithSenders,
+            _validation_kind: BlockValidationKind,
+        ) -> Result<InsertPayloadOk, InsertBlockError> {
+            Err(InsertBlockError::tree_error(
+                BlockchainTreeError::BlockHashNotFoundInChain { block_hash: block.hash() },
+                block.block,
+            ))
+        }
+
+        fn finalize_block(&self, _finalized_block: BlockNumber) -> ProviderResult<()> {
+            Ok(())
+        }
+
+        fn connect_buffered_blocks_to_canonical_hashes_and_finalize(
+            &self,
+            _last_finalized_block: BlockNumber,
+        ) -> Result<(), CanonicalError> {
+            Ok(())
+        }
+
+        fn update_block_hashes_and_clear_buffered(
+            &self,
+        ) -> Result<BTreeMap<BlockNumber, BlockHash>, CanonicalError> {
+            Ok(BTreeMap::new())
+        }
+
+        fn connect_buffered_blocks_to_canonical_hashes(&self) -> Result<(), CanonicalError> {
+            Ok(())
+        }
+
+        fn make_canonical(
+            &self,
+            block_hash: BlockHash,
+        ) -> Result<CanonicalOutcome, CanonicalError> {
+            Err(BlockchainTreeError::BlockHashNotFoundInChain { block_hash }.into())
+        }
+
+        fn unwind_canonical_chain(
+            &self,
+            unwind_to: BlockNumber,
+        ) -> Result<BlockNumber, CanonicalError> {
+            Ok(unwind_to)
+        }
+
+        fn notify_canonical_chain_unwound(&self, _unwound: Chain) -> Result<(), CanonicalError> {
+            Ok(())
+        }
+    }
+
+    impl BlockchainTreeViewer for EmptyTree {
+        fn header_by_hash(&self, _hash: BlockHash) -> Option<SealedHeader> {
+            None
+        }
+
+        fn block_by_hash(&self, _hash: BlockHash) -> Option<SealedBlock> {
+            None
+        }
+
+        fn block_with_senders_by_hash(&self, _hash: BlockHash) -> Option<SealedBlockWithSenders> {
+            None
+        }
+
+        fn buffered_header_by_hash(&self, _block_hash: BlockHash) -> Option<SealedHeader> {
+            None
+        }
+
+        fn is_canonical(&self, _block_hash: BlockHash) -> Result<bool, ProviderError> {
+            Ok(false)
+        }
+
+        fn lowest_buffered_ancestor(&self, _hash: BlockHash) -> Option<SealedBlockWithSenders> {
+            None
+        }
+
+        fn canonical_tip(&self) -> BlockNumHash {
+            Default::default()
+        }
+
+        fn pending_block_num_hash(&self) -> Option<BlockNumHash> {
+            None
+        }
+
+        fn pending_block_and_receipts(&self) -> Option<(SealedBlock, Vec<Receipt>)> {
+            None
+        }
+
+        fn receipts_by_block_hash(&self, _block_hash: BlockHash) -> Option<Vec<Receipt>> {
+            None
+        }
+    }
+
+    impl BlockchainTreePendingStateProvider for EmptyTree {
+        fn find_pending_state_provider(
+            &self,
+            _block_hash: BlockHash,
+        ) -> Option<Box<dyn FullExecutionDataProvider>> {
+            None
+        }
+    }
+
+    impl CanonStateSubscriptions for EmptyTree {
+        fn subscribe_to_canonical_state(&self) -> CanonStateNotifications {
+            CanonStateNotificationSender::new(1).subscribe()
+        }
+    }
+
+    /// Inserts the genesis block and a block with transactions, without executing them, and
+    /// returns the receipts of the block.
+    fn body_only_chain(receipts_root_matches: bool) -> (TestProviderFactory, Vec<Receipt>) {
+        let mut rng = generators::rng();
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().byzantium_activated().build());
+        let factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+
+        let genesis = SealedBlock::new(chain_spec.sealed_genesis_header(), Default::default());
+        let block = random_block(&mut rng, 1, Some(genesis.hash()), Some(3), Some(0));
+        let receipts =
+            block.body.iter().map(|tx| random_receipt(&mut rng, tx, Some(1))).collect::<Vec<_>>();
+
+        let mut header = block.header.clone().unseal();
+        if receipts_root_matches {
+            header.receipts_root =
+                calculate_receipt_root_no_memo(&receipts.iter().collect::<Vec<_>>());
+        }
+        let block = SealedBlock { header: header.seal_slow(), ..block };
+
+        let provider = factory.provider_rw().unwrap();
+        for block in [genesis, block] {
+            provider.insert_block(block.try_seal_with_senders().unwrap(), None).unwrap();
+        }
+        provider.commit().unwrap();
+
+        (factory, receipts)
+    }
+
+    /// Returns a [`BlockchainProvider`] that synthesizes missing receipts with an executor that
+    /// returns the given receipts once.
+    fn backfilling_provider(
+        factory: &TestProviderFactory,
+        receipts: Vec<Receipt>,
+        config: ReceiptsBackfillConfig,
+    ) -> (BlockchainProvider<Arc<TempDatabase<DatabaseEnv>>>, MockExecutorProvider) {
+        let executor = MockExecutorProvider::default();
+        executor.extend([ExecutionOutcome::new(
+            Default::default(),
+            receipts.into(),
+            1,
+            Vec::new(),
+        )]);
+        let backfill = ExecutionReceiptsBackfill::new(factory.clone(), executor.clone(), config);
+
+        let provider = BlockchainProvider::new(factory.clone(), Arc::new(EmptyTree))
+            .unwrap()
+            .with_receipts_backfill(Arc::new(backfill));
+        (provider, executor)
+    }
+
+    #[test]
+    fn backfills_missing_receipts_once() {
+        let (factory, receipts) = body_only_chain(true);
+        assert_eq!(factory.receipts_by_block(1.into()).unwrap(), Some(Vec::new()));
+
+        let (provider, executor) =
+            backfilling_provider(&factory, receipts.clone(), ReceiptsBackfillConfig::default());
+
+        let hash = provider.transaction_by_id(0).unwrap().unwrap().hash();
+        assert_eq!(provider.receipt_by_hash(hash).unwrap(), Some(receipts[0].clone()));
+        assert_eq!(executor.executions(), 1);
+
+        // the receipts were persisted and the next request doesn't execute the block again
+        assert_eq!(factory.receipts_by_block(1.into()).unwrap(), Some(receipts.clone()));
+        assert_eq!(provider.receipts_by_block(1.into()).unwrap(), Some(receipts));
+        assert_eq!(executor.executions(), 1);
+    }
+
+    #[test]
+    fn serves_cached_receipts_from_every_lookup() {
+        let (factory, receipts) = body_only_chain(true);
+        let config = ReceiptsBackfillConfig { persist: false, ..Default::default() };
+        let (provider, executor) = backfilling_provider(&factory, receipts.clone(), config);
+
+        assert_eq!(provider.receipts_by_block(1.into()).unwrap(), Some(receipts.clone()));
+        assert_eq!(provider.receipt(1).unwrap(), Some(receipts[1].clone()));
+
+        let transaction = provider.transaction_by_id(2).unwrap().unwrap();
+        assert_eq!(
+            provider.receipt_by_hash(transaction.hash()).unwrap(),
+            Some(receipts[2].clone())
+        );
+        let (found, meta, receipt) =
+            provider.transaction_and_receipt_by_hash(transaction.hash()).unwrap().unwrap();
+        assert_eq!((found, meta.index, receipt), (transaction, 2, receipts[2].clone()));
+
+        // all lookups were served by a single execution and nothing was written
+        assert_eq!(executor.executions(), 1);
+        assert_eq!(factory.receipts_by_block(1.into()).unwrap(), Some(Vec::new()));
+    }
+
+    #[test]
+    fn rejects_receipts_not_matching_header() {
+        let (factory, receipts) = body_only_chain(false);
+        let (provider, executor) =
+            backfilling_provider(&factory, receipts, ReceiptsBackfillConfig::default());
+
+        let hash = provider.transaction_by_id(0).unwrap().unwrap().hash();
+        assert_eq!(provider.receipt_by_hash(hash).unwrap(), None);
+        assert_eq!(executor.executions(), 1);
+        assert_eq!(factory.receipts_by_block(1.into()).unwrap(), Some(Vec::new()));
+    }
+
+    #[test]
+    fn times_out_waiting_for_permit() {
+        let (factory, _) = body_only_chain(true);
+        let config = ReceiptsBackfillConfig {
+            max_concurrent: 1,
+            timeout: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let executor = MockExecutorProvider::default();
+        let backfill = ExecutionReceiptsBackfill::new(factory, executor.clone(), config);
+
+        let _permit = backfill.acquire(Instant::now()).unwrap();
+        assert_eq!(backfill.backfill_receipts(1).unwrap(), None);
+        assert_eq!(executor.executions(), 0);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     providers::{state::macros::delegate_provider_impls, StaticFileProvider},
-    AccountReader, BlockHashReader, ProviderError, StateProvider, StateRootProvider,
+    AccountReader, BlockHashReader, CanonicalHashesIter, ProviderError, StateProvider,
+    StateRootProvider,
 };
 use reth_db::{tables, BlockNumberList};
 use reth_db_api::{
@@ -11,12 +12,12 @@ use reth_db_api::{
 };
 use reth_primitives::{
     constants::EPOCH_SLOTS, proofs::AccountProof, Account, Address, BlockNumber, Bytecode,
-    StaticFileSegment, StorageKey, StorageValue, B256,
+    StaticFileSegment, StorageEntry, StorageKey, StorageValue, B256,
 };
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie::{updates::TrieUpdates, HashedPostState};
 use revm::db::BundleState;
-use std::fmt::Debug;
+use std::{fmt::Debug, ops::Range};
 
 /// State provider for a given block number which takes a tx reference.
 ///
@@ -104,6 +105,22 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         )
     }
 
+    /// Returns an iterator over the storage of the account at the start of the provider's block,
+    /// ordered by slot and starting at the `start` slot, inclusive.
+    ///
+    /// See [`HistoricalStorageIter`] for details.
+    pub fn historical_storage_iter(
+        &self,
+        address: Address,
+        start: StorageKey,
+    ) -> ProviderResult<HistoricalStorageIter<TX>> {
+        if !self.lowest_available_blocks.is_storage_history_available(self.block_number) {
+            return Err(ProviderError::StateAtBlockPruned(self.block_number))
+        }
+
+        HistoricalStorageIter::new(self.tx, self.block_number, address, start)
+    }
+
     /// Retrieve revert hashed state for this history provider.
     fn revert_state(&self) -> ProviderResult<HashedPostState> {
         if !self.lowest_available_blocks.is_account_history_available(self.block_number) ||
@@ -230,28 +247,12 @@ impl<'b, TX: DbTx> BlockHashReader for HistoricalStateProviderRef<'b, TX> {
         )
     }
 
-    fn canonical_hashes_range(
+    fn canonical_hashes_iter(
         &self,
-        start: BlockNumber,
-        end: BlockNumber,
-    ) -> ProviderResult<Vec<B256>> {
-        self.static_file_provider.get_range_with_static_file_or_database(
-            StaticFileSegment::Headers,
-            start..end,
-            |static_file, range, _| static_file.canonical_hashes_range(range.start, range.end),
-            |range, _| {
-                self.tx
-                    .cursor_read::<tables::CanonicalHeaders>()
-                    .map(|mut cursor| {
-                        cursor
-                            .walk_range(range)?
-                            .map(|result| result.map(|(_, hash)| hash).map_err(Into::into))
-                            .collect::<ProviderResult<Vec<_>>>()
-                    })?
-                    .map_err(Into::into)
-            },
-            |_| true,
-        )
+        range: Range<BlockNumber>,
+    ) -> ProviderResult<CanonicalHashesIter<'_>> {
+        let cursor = self.tx.cursor_read::<tables::CanonicalHeaders>()?;
+        Ok(self.static_file_provider.canonical_hashes_iter_with_database(range, cursor))
     }
 }
 
@@ -355,6 +356,18 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
         self
     }
 
+    /// Returns an iterator over the storage of the account at the start of the provider's block,
+    /// ordered by slot and starting at the `start` slot, inclusive.
+    ///
+    /// See [`HistoricalStorageIter`] for details.
+    pub fn historical_storage_iter(
+        &self,
+        address: Address,
+        start: StorageKey,
+    ) -> ProviderResult<HistoricalStorageIter<TX>> {
+        self.as_ref().historical_storage_iter(address, start)
+    }
+
     /// Returns a new provider that takes the `TX` as reference
     #[inline(always)]
     fn as_ref(&self) -> HistoricalStateProviderRef<'_, TX> {
@@ -370,6 +383,190 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
 // Delegates all provider impls to [HistoricalStateProviderRef]
 delegate_provider_impls!(HistoricalStateProvider<TX> where [TX: DbTx]);
 
+impl<TX: DbTx> BlockHashReader for HistoricalStateProvider<TX> {
+    fn block_hash(&self, number: u64) -> ProviderResult<Option<B256>> {
+        self.as_ref().block_hash(number)
+    }
+
+    // Not delegated, the iterator must not borrow the provider returned by `as_ref`.
+    fn canonical_hashes_iter(
+        &self,
+        range: Range<BlockNumber>,
+    ) -> ProviderResult<CanonicalHashesIter<'_>> {
+        let cursor = self.tx.cursor_read::<tables::CanonicalHeaders>()?;
+        Ok(self.static_file_provider.canonical_hashes_iter_with_database(range, cursor))
+    }
+}
+
+/// Iterator over the storage of an account at the start of a historical block, ordered by slot.
+///
+/// Merges the current storage of the account in [`tables::PlainStorageState`] with the slots that
+/// [`tables::StoragesHistory`] records as changed at or after the block. The value of a changed
+/// slot is rolled back to the value before its first change at or after the block, which is read
+/// from [`tables::StorageChangeSets`]. This omits slots that were created later and resurrects
+/// slots that were deleted later.
+///
+/// Both tables are walked by cursors, one slot at a time, so memory usage doesn't depend on the
+/// size of the storage. Slots with a zero value are skipped.
+pub struct HistoricalStorageIter<TX: DbTx> {
+    /// Block at the start of which the storage is returned.
+    block_number: BlockNumber,
+    /// Account whose storage is returned.
+    address: Address,
+    /// Cursor over the current storage of the account.
+    plain: TX::DupCursor<tables::PlainStorageState>,
+    /// Cursor over the history index of the storage of the account.
+    history: TX::Cursor<tables::StoragesHistory>,
+    /// Cursor over the storage changesets, to roll back changed slots.
+    changesets: TX::DupCursor<tables::StorageChangeSets>,
+    /// The next slot of the current storage.
+    next_plain: Option<StorageEntry>,
+    /// The next slot with a history, and the first block at or after `block_number` that changed
+    /// it.
+    next_history: Option<(StorageKey, Option<BlockNumber>)>,
+}
+
+impl<TX: DbTx> HistoricalStorageIter<TX> {
+    fn new(
+        tx: &TX,
+        block_number: BlockNumber,
+        address: Address,
+        start: StorageKey,
+    ) -> ProviderResult<Self> {
+        let mut plain = tx.cursor_dup_read::<tables::PlainStorageState>()?;
+        let next_plain = plain.seek_by_key_subkey(address, start)?;
+        let mut iter = Self {
+            block_number,
+            address,
+            plain,
+            history: tx.cursor_read::<tables::StoragesHistory>()?,
+            changesets: tx.cursor_dup_read::<tables::StorageChangeSets>()?,
+            next_plain,
+            next_history: None,
+        };
+        iter.next_history = iter.seek_history(start)?;
+        Ok(iter)
+    }
+
+    /// Returns the first slot at or after the given one with a history, and the first block at or
+    /// after `block_number` that changed it.
+    fn seek_history(
+        &mut self,
+        slot: StorageKey,
+    ) -> ProviderResult<Option<(StorageKey, Option<BlockNumber>)>> {
+        let Some((key, chunk)) =
+            self.history.seek(StorageShardedKey::new(self.address, slot, self.block_number))?
+        else {
+            return Ok(None)
+        };
+        if key.address != self.address {
+            return Ok(None)
+        }
+
+        let slot = key.sharded_key.key;
+        if key.sharded_key.highest_block_number < self.block_number {
+            // the slot is the next one with a history, so the seek landed on its first shard
+            // instead of the one that covers our block
+            return self.seek_history(slot)
+        }
+
+        let chunk = chunk.0;
+        // Get the rank of the first entry at or after our block.
+        let mut rank = chunk.rank(self.block_number);
+        if rank.checked_sub(1).and_then(|rank| chunk.select(rank)) == Some(self.block_number) {
+            rank -= 1
+        };
+        Ok(Some((slot, chunk.select(rank))))
+    }
+
+    /// Advances the history to the slot after the given one.
+    fn next_history_slot(
+        &mut self,
+        slot: StorageKey,
+    ) -> ProviderResult<Option<(StorageKey, Option<BlockNumber>)>> {
+        // the last shard of a slot has the highest possible block number, so the entry after it
+        // is the first shard of the next slot
+        let next = match self.history.seek(StorageShardedKey::new(self.address, slot, u64::MAX))? {
+            Some((key, _)) if key.address == self.address && key.sharded_key.key == slot => {
+                self.history.next()?
+            }
+            entry => entry,
+        };
+        match next {
+            Some((key, _)) if key.address == self.address => self.seek_history(key.sharded_key.key),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the value of the slot before the given block changed it.
+    fn changeset_value(
+        &mut self,
+        slot: StorageKey,
+        block_number: BlockNumber,
+    ) -> ProviderResult<StorageValue> {
+        Ok(self
+            .changesets
+            .seek_by_key_subkey((block_number, self.address).into(), slot)?
+            .filter(|entry| entry.key == slot)
+            .ok_or_else(|| ProviderError::StorageChangesetNotFound {
+                block_number,
+                address: self.address,
+                storage_key: Box::new(slot),
+            })?
+            .value)
+    }
+
+    fn next_entry(&mut self) -> ProviderResult<Option<StorageEntry>> {
+        loop {
+            let plain_slot = self.next_plain.map(|entry| entry.key);
+            let entry = match self.next_history {
+                Some((slot, changed_at)) if plain_slot.map_or(true, |plain| slot <= plain) => {
+                    let value = if let Some(block_number) = changed_at {
+                        self.changeset_value(slot, block_number)?
+                    } else if plain_slot == Some(slot) {
+                        self.next_plain.map(|entry| entry.value).unwrap_or_default()
+                    } else {
+                        StorageValue::ZERO
+                    };
+                    if plain_slot == Some(slot) {
+                        self.next_plain = self.plain.next_dup_val()?;
+                    }
+                    self.next_history = self.next_history_slot(slot)?;
+                    StorageEntry { key: slot, value }
+                }
+                _ => {
+                    let Some(entry) = self.next_plain else { return Ok(None) };
+                    self.next_plain = self.plain.next_dup_val()?;
+                    entry
+                }
+            };
+
+            if !entry.value.is_zero() {
+                return Ok(Some(entry))
+            }
+        }
+    }
+}
+
+impl<TX: DbTx> Iterator for HistoricalStorageIter<TX> {
+    type Item = ProviderResult<StorageEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_entry().transpose()
+    }
+}
+
+impl<TX: DbTx> Debug for HistoricalStorageIter<TX> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HistoricalStorageIter")
+            .field("block_number", &self.block_number)
+            .field("address", &self.address)
+            .field("next_plain", &self.next_plain)
+            .field("next_history", &self.next_history)
+            .finish_non_exhaustive()
+    }
+}
+
 /// Lowest blocks at which different parts of the state are available.
 /// They may be [Some] if pruning is enabled.
 #[derive(Clone, Copy, Debug, Default)]
@@ -403,8 +600,8 @@ mod tests {
     use crate::{
         providers::state::historical::{HistoryInfo, LowestAvailableBlocks},
         test_utils::create_test_provider_factory,
-        AccountReader, HistoricalStateProvider, HistoricalStateProviderRef, StateProvider,
-        StaticFileProviderFactory,
+        AccountReader, HistoricalStateProvider, HistoricalStateProviderRef, HistoryWriter,
+        StateProvider, StaticFileProviderFactory,
     };
     use reth_db::{tables, BlockNumberList};
     use reth_db_api::{
@@ -413,6 +610,7 @@ mod tests {
     };
     use reth_primitives::{address, b256, Account, Address, StorageEntry, B256, U256};
     use reth_storage_errors::provider::ProviderError;
+    use std::collections::BTreeMap;
 
     const ADDRESS: Address = address!("0000000000000000000000000000000000000001");
     const HIGHER_ADDRESS: Address = address!("0000000000000000000000000000000000000005");
@@ -715,4 +913,90 @@ mod tests {
             Ok(HistoryInfo::MaybeInPlainState)
         );
     }
+
+    #[test]
+    fn history_provider_storage_iter() {
+        const BLOCKS: u64 = 20;
+        const SLOTS: u8 = 8;
+
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let static_file_provider = factory.static_file_provider();
+
+        // slots of both accounts churn every block: they are created, changed and deleted, and
+        // `snapshots[n]` is the storage of `ADDRESS` at the start of block `n`
+        let mut storages = BTreeMap::<Address, BTreeMap<B256, U256>>::new();
+        let mut snapshots = vec![BTreeMap::new()];
+        for block in 1..=BLOCKS {
+            for (address, salt) in [(ADDRESS, 0), (HIGHER_ADDRESS, 1)] {
+                let storage = storages.entry(address).or_default();
+                for slot in 0..SLOTS as u64 {
+                    if (block + slot + salt) % 3 == 0 {
+                        continue
+                    }
+                    let key = B256::with_last_byte(slot as u8);
+                    let value = if (block * (slot + 1) + salt) % 4 == 0 {
+                        U256::ZERO
+                    } else {
+                        U256::from(block * 100 + slot)
+                    };
+                    let previous = storage.get(&key).copied().unwrap_or_default();
+                    if previous == value {
+                        continue
+                    }
+
+                    let entry = StorageEntry { key, value: previous };
+                    provider
+                        .tx_ref()
+                        .put::<tables::StorageChangeSets>((block, address).into(), entry)
+                        .unwrap();
+                    if value.is_zero() {
+                        storage.remove(&key);
+                    } else {
+                        storage.insert(key, value);
+                    }
+                }
+            }
+            snapshots.push(storages[&ADDRESS].clone());
+        }
+        for (address, storage) in &storages {
+            for (&key, &value) in storage {
+                provider
+                    .tx_ref()
+                    .put::<tables::PlainStorageState>(*address, StorageEntry { key, value })
+                    .unwrap();
+            }
+        }
+        provider.update_history_indices(1..=BLOCKS).unwrap();
+        provider.commit().unwrap();
+
+        let tx = factory.provider().unwrap().into_tx();
+        let storage_at = |block_number, start| {
+            HistoricalStateProviderRef::new(&tx, block_number, static_file_provider.clone())
+                .historical_storage_iter(ADDRESS, start)
+                .unwrap()
+                .map(|entry| entry.map(|entry| (entry.key, entry.value)))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        };
+
+        for block_number in [1, 2, 5, 8, 13, BLOCKS, BLOCKS + 1] {
+            let expected =
+                snapshots[block_number as usize - 1].clone().into_iter().collect::<Vec<_>>();
+            assert_eq!(storage_at(block_number, B256::ZERO), expected, "block {block_number}");
+
+            // resuming from a slot returns the rest of the storage
+            if let Some(&(next, _)) = expected.get(2) {
+                assert_eq!(storage_at(block_number, next), expected[2..], "block {block_number}");
+            }
+
+            // the iterator agrees with the lookups of single slots
+            assert!(expected.iter().all(|(key, value)| {
+                HistoricalStateProviderRef::new(&tx, block_number, static_file_provider.clone())
+                    .storage(ADDRESS, *key)
+                    .unwrap() ==
+                    Some(*value)
+            }));
+        }
+    }
 }
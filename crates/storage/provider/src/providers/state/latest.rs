@@ -1,12 +1,9 @@
 use crate::{
     providers::{state::macros::delegate_provider_impls, StaticFileProvider},
-    AccountReader, BlockHashReader, StateProvider, StateRootProvider,
+    AccountReader, BlockHashReader, CanonicalHashesIter, StateProvider, StateRootProvider,
 };
 use reth_db::tables;
-use reth_db_api::{
-    cursor::{DbCursorRO, DbDupCursorRO},
-    transaction::DbTx,
-};
+use reth_db_api::{cursor::DbDupCursorRO, transaction::DbTx};
 use reth_primitives::{
     proofs::AccountProof, Account, Address, BlockNumber, Bytecode, StaticFileSegment, StorageKey,
     StorageValue, B256,
@@ -14,6 +11,7 @@ use reth_primitives::{
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use reth_trie::{proof::Proof, updates::TrieUpdates, HashedPostState};
 use revm::db::BundleState;
+use std::ops::Range;
 
 /// State provider over latest state that takes tx reference.
 #[derive(Debug)]
@@ -49,28 +47,12 @@ impl<'b, TX: DbTx> BlockHashReader for LatestStateProviderRef<'b, TX> {
         )
     }
 
-    fn canonical_hashes_range(
+    fn canonical_hashes_iter(
         &self,
-        start: BlockNumber,
-        end: BlockNumber,
-    ) -> ProviderResult<Vec<B256>> {
-        self.static_file_provider.get_range_with_static_file_or_database(
-            StaticFileSegment::Headers,
-            start..end,
-            |static_file, range, _| static_file.canonical_hashes_range(range.start, range.end),
-            |range, _| {
-                self.tx
-                    .cursor_read::<tables::CanonicalHeaders>()
-                    .map(|mut cursor| {
-                        cursor
-                            .walk_range(range)?
-                            .map(|result| result.map(|(_, hash)| hash).map_err(Into::into))
-                            .collect::<ProviderResult<Vec<_>>>()
-                    })?
-                    .map_err(Into::into)
-            },
-            |_| true,
-        )
+        range: Range<BlockNumber>,
+    ) -> ProviderResult<CanonicalHashesIter<'_>> {
+        let cursor = self.tx.cursor_read::<tables::CanonicalHeaders>()?;
+        Ok(self.static_file_provider.canonical_hashes_iter_with_database(range, cursor))
     }
 }
 
@@ -144,6 +126,21 @@ impl<TX: DbTx> LatestStateProvider<TX> {
 // Delegates all provider impls to [LatestStateProviderRef]
 delegate_provider_impls!(LatestStateProvider<TX> where [TX: DbTx]);
 
+impl<TX: DbTx> BlockHashReader for LatestStateProvider<TX> {
+    fn block_hash(&self, number: u64) -> ProviderResult<Option<B256>> {
+        self.as_ref().block_hash(number)
+    }
+
+    // Not delegated, the iterator must not borrow the provider returned by `as_ref`.
+    fn canonical_hashes_iter(
+        &self,
+        range: Range<BlockNumber>,
+    ) -> ProviderResult<CanonicalHashesIter<'_>> {
+        let cursor = self.db.cursor_read::<tables::CanonicalHeaders>()?;
+        Ok(self.static_file_provider.canonical_hashes_iter_with_database(range, cursor))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,313 @@
+use crate::{
+    AccountReader, BlockHashReader, CanonicalHashesIter, StateProvider, StateRootProvider,
+};
+use reth_primitives::{
+    proofs::AccountProof, Account, Address, BlockNumber, Bytecode, Bytes, StorageKey, StorageValue,
+    B256, KECCAK_EMPTY, U256,
+};
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use reth_trie::updates::TrieUpdates;
+use revm::db::BundleState;
+use std::{collections::HashMap, ops::Range};
+
+/// Overrides of the state of an account, see [`StateOverridesProvider`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountStateOverride {
+    /// The balance of the account.
+    pub balance: Option<U256>,
+    /// The nonce of the account.
+    pub nonce: Option<u64>,
+    /// The code of the account.
+    pub code: Option<Bytes>,
+    /// The values of storage slots of the account.
+    pub storage: HashMap<StorageKey, StorageValue>,
+    /// Whether the storage of the account is replaced by [`Self::storage`], i.e. all other slots
+    /// are empty. Otherwise they're read from the underlying state.
+    pub replace_storage: bool,
+}
+
+/// State overrides by account, see [`StateOverridesProvider`].
+pub type StateOverrides = HashMap<Address, AccountStateOverride>;
+
+/// A state provider that applies [`StateOverrides`] on top of an underlying state provider.
+///
+/// This is used to simulate calls against a state that differs from the actual state, e.g. with a
+/// higher balance or an instrumented contract. The overrides win over the underlying state,
+/// including the state of a pending block if the underlying provider is a
+/// [`BundleStateProvider`](crate::providers::BundleStateProvider).
+///
+/// The overrides only apply to this provider: a code override changes the code hash of the account
+/// within this provider, the underlying state and its caches are left untouched. The provider
+/// doesn't read anything when it's created, so it's cheap to create one per request.
+#[derive(Debug)]
+pub struct StateOverridesProvider<SP: StateProvider> {
+    /// The inner state provider.
+    pub state_provider: SP,
+    /// The overrides by account.
+    overrides: StateOverrides,
+    /// The code hashes of the accounts with overridden code.
+    code_hashes: HashMap<Address, B256>,
+    /// The overridden code by code hash.
+    bytecodes: HashMap<B256, Bytecode>,
+}
+
+impl<SP: StateProvider> StateOverridesProvider<SP> {
+    /// Create new state overrides provider
+    pub fn new(state_provider: SP, overrides: StateOverrides) -> Self {
+        let mut code_hashes = HashMap::new();
+        let mut bytecodes = HashMap::new();
+        for (address, account) in &overrides {
+            if let Some(code) = &account.code {
+                let bytecode = Bytecode::new_raw(code.clone());
+                let code_hash = bytecode.hash_slow();
+                code_hashes.insert(*address, code_hash);
+                bytecodes.insert(code_hash, bytecode);
+            }
+        }
+        Self { state_provider, overrides, code_hashes, bytecodes }
+    }
+
+    /// Returns the overrides of the provider.
+    pub const fn overrides(&self) -> &StateOverrides {
+        &self.overrides
+    }
+}
+
+/* Implement StateProvider traits */
+
+impl<SP: StateProvider> BlockHashReader for StateOverridesProvider<SP> {
+    fn block_hash(&self, block_number: BlockNumber) -> ProviderResult<Option<B256>> {
+        self.state_provider.block_hash(block_number)
+    }
+
+    fn canonical_hashes_iter(
+        &self,
+        range: Range<BlockNumber>,
+    ) -> ProviderResult<CanonicalHashesIter<'_>> {
+        self.state_provider.canonical_hashes_iter(range)
+    }
+}
+
+impl<SP: StateProvider> AccountReader for StateOverridesProvider<SP> {
+    fn basic_account(&self, address: Address) -> ProviderResult<Option<Account>> {
+        let Some(account_override) = self.overrides.get(&address) else {
+            return self.state_provider.basic_account(address)
+        };
+
+        // an overridden account exists, even if it doesn't exist in the underlying state
+        let mut account = self.state_provider.basic_account(address)?.unwrap_or_default();
+        if let Some(balance) = account_override.balance {
+            account.balance = balance;
+        }
+        if let Some(nonce) = account_override.nonce {
+            account.nonce = nonce;
+        }
+        if let Some(code_hash) = self.code_hashes.get(&address) {
+            account.bytecode_hash = (*code_hash != KECCAK_EMPTY).then_some(*code_hash);
+        }
+        Ok(Some(account))
+    }
+}
+
+impl<SP: StateProvider> StateRootProvider for StateOverridesProvider<SP> {
+    fn state_root(&self, _bundle_state: &BundleState) -> ProviderResult<B256> {
+        // the overridden state has no meaningful state root
+        Err(ProviderError::UnsupportedProvider)
+    }
+
+    fn state_root_with_updates(
+        &self,
+        _bundle_state: &BundleState,
+    ) -> ProviderResult<(B256, TrieUpdates)> {
+        Err(ProviderError::UnsupportedProvider)
+    }
+}
+
+impl<SP: StateProvider> StateProvider for StateOverridesProvider<SP> {
+    fn storage(
+        &self,
+        account: Address,
+        storage_key: StorageKey,
+    ) -> ProviderResult<Option<StorageValue>> {
+        if let Some(account_override) = self.overrides.get(&account) {
+            if let Some(value) = account_override.storage.get(&storage_key) {
+                return Ok(Some(*value))
+            }
+            if account_override.replace_storage {
+                return Ok(None)
+            }
+        }
+
+        self.state_provider.storage(account, storage_key)
+    }
+
+    fn bytecode_by_hash(&self, code_hash: B256) -> ProviderResult<Option<Bytecode>> {
+        if let Some(bytecode) = self.bytecodes.get(&code_hash) {
+            return Ok(Some(bytecode.clone()))
+        }
+
+        self.state_provider.bytecode_by_hash(code_hash)
+    }
+
+    fn proof(&self, _address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
+        Err(ProviderError::UnsupportedProvider)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        providers::BundleStateProvider, test_utils::create_test_provider_factory, ExecutionOutcome,
+    };
+    use reth_db::tables;
+    use reth_db_api::transaction::DbTxMut;
+    use reth_primitives::{
+        keccak256, revm::env::tx_env_with_recovered, StorageEntry, Transaction, TxKind, TxLegacy,
+    };
+    use reth_revm::database::StateProviderDatabase;
+    use reth_testing_utils::generators::{self, sign_tx_with_random_key_pair};
+    use revm::{
+        db::{states::bundle_state::BundleRetention, CacheDB, State},
+        Evm,
+    };
+
+    /// Stores 2 in slot 1.
+    const WRITE_SLOT: &[u8] = &[0x60, 0x02, 0x60, 0x01, 0x55, 0x00];
+    /// Returns the value of slot 1.
+    const READ_SLOT: &[u8] = &[0x60, 0x01, 0x54, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3];
+
+    /// Calls the contract and returns its output as a number.
+    fn simulate(state: impl StateProvider, to: Address) -> U256 {
+        let mut db = CacheDB::new(StateProviderDatabase::new(state));
+        let mut evm = Evm::builder()
+            .with_db(&mut db)
+            .modify_tx_env(|tx| tx.transact_to = TxKind::Call(to))
+            .build();
+        let output = evm.transact().unwrap().result.into_output().unwrap();
+        U256::from_be_slice(&output)
+    }
+
+    #[test]
+    fn overrides_on_top_of_pending_state() {
+        let (address, other) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let (slot, untouched) = (B256::with_last_byte(1), B256::with_last_byte(2));
+        let code = Bytes::from_static(WRITE_SLOT);
+        let code_hash = keccak256(&code);
+        let account = Account { nonce: 1, balance: U256::from(10), bytecode_hash: Some(code_hash) };
+
+        // the canonical state
+        let factory = create_test_provider_factory();
+        let provider_rw = factory.provider_rw().unwrap();
+        let tx = provider_rw.tx_ref();
+        tx.put::<tables::PlainAccountState>(address, account).unwrap();
+        tx.put::<tables::Bytecodes>(code_hash, Bytecode::new_raw(code.clone())).unwrap();
+        for key in [slot, untouched] {
+            let entry = StorageEntry { key, value: U256::from(1) };
+            tx.put::<tables::PlainStorageState>(address, entry).unwrap();
+        }
+        provider_rw.commit().unwrap();
+
+        // a pool transaction of the pending block calls the contract, which writes the slot
+        let transaction = sign_tx_with_random_key_pair(
+            &mut generators::rng(),
+            Transaction::Legacy(TxLegacy {
+                chain_id: Some(1),
+                gas_limit: 100_000,
+                to: TxKind::Call(address),
+                ..Default::default()
+            }),
+        )
+        .into_ecrecovered()
+        .unwrap();
+        let mut db = State::builder()
+            .with_database(StateProviderDatabase::new(factory.latest().unwrap()))
+            .with_bundle_update()
+            .build();
+        let mut evm = Evm::builder()
+            .with_db(&mut db)
+            .modify_tx_env(|tx| *tx = tx_env_with_recovered(&transaction))
+            .build();
+        assert!(evm.transact_commit().unwrap().is_success());
+        drop(evm);
+        db.merge_transitions(BundleRetention::Reverts);
+        let bundle = db.take_bundle();
+        let pending = || {
+            BundleStateProvider::new(
+                factory.latest().unwrap(),
+                ExecutionOutcome::new(bundle.clone(), Default::default(), 1, vec![]),
+            )
+        };
+
+        // without overrides the pending write is read
+        let view = StateOverridesProvider::new(pending(), StateOverrides::default());
+        assert_eq!(view.storage(address, slot).unwrap(), Some(U256::from(2)));
+        let read_only = StateOverrides::from([(
+            address,
+            AccountStateOverride {
+                code: Some(Bytes::from_static(READ_SLOT)),
+                ..Default::default()
+            },
+        )]);
+        assert_eq!(
+            simulate(StateOverridesProvider::new(pending(), read_only), address),
+            U256::from(2)
+        );
+
+        let new_code = Bytes::from_static(READ_SLOT);
+        let overrides = StateOverrides::from([
+            (
+                address,
+                AccountStateOverride {
+                    code: Some(new_code.clone()),
+                    storage: HashMap::from([(slot, U256::from(3))]),
+                    ..Default::default()
+                },
+            ),
+            (
+                other,
+                AccountStateOverride {
+                    balance: Some(U256::from(100)),
+                    replace_storage: true,
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        // a simulation reads the overridden slot with the overridden code
+        assert_eq!(
+            simulate(StateOverridesProvider::new(pending(), overrides.clone()), address),
+            U256::from(3)
+        );
+
+        // the override wins over the pending write, the other slots are read through
+        let view = StateOverridesProvider::new(pending(), overrides);
+        assert_eq!(view.storage(address, slot).unwrap(), Some(U256::from(3)));
+        assert_eq!(view.storage(address, untouched).unwrap(), Some(U256::from(1)));
+        assert_eq!(view.account_code(address).unwrap(), Some(Bytecode::new_raw(new_code.clone())));
+        assert_eq!(
+            view.basic_account(address).unwrap(),
+            Some(Account { bytecode_hash: Some(keccak256(&new_code)), ..account })
+        );
+        // the replaced code can still be looked up by its hash
+        let replaced = view.bytecode_by_hash(code_hash).unwrap();
+        assert_eq!(replaced.map(|code| code.hash_slow()), Some(code_hash));
+
+        // an overridden account exists
+        assert_eq!(view.account_balance(other).unwrap(), Some(U256::from(100)));
+        assert_eq!(view.account_nonce(other).unwrap(), Some(0));
+        assert_eq!(view.storage(other, slot).unwrap(), None);
+
+        // the pending and canonical state are untouched
+        let pending = pending();
+        assert_eq!(pending.storage(address, slot).unwrap(), Some(U256::from(2)));
+        assert_eq!(pending.basic_account(address).unwrap(), Some(account));
+        assert_eq!(pending.basic_account(other).unwrap(), None);
+        let latest = factory.latest().unwrap();
+        assert_eq!(latest.storage(address, slot).unwrap(), Some(U256::from(1)));
+        assert_eq!(
+            latest.account_code(address).unwrap().map(|code| code.hash_slow()),
+            Some(code_hash)
+        );
+    }
+}
@@ -3,8 +3,8 @@ use super::{
     LoadedJarRef,
 };
 use crate::{
-    to_range, BlockHashReader, BlockNumReader, HeaderProvider, ReceiptProvider,
-    TransactionsProvider,
+    to_range, BlockHashReader, BlockNumReader, CanonicalHashesIter, HeaderProvider,
+    ReceiptProvider, TransactionsProvider,
 };
 use reth_db::static_file::{HeaderMask, ReceiptMask, StaticFileCursor, TransactionMask};
 use reth_db_api::models::CompactU256;
@@ -14,7 +14,7 @@ use reth_primitives::{
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use std::{
-    ops::{Deref, RangeBounds},
+    ops::{Deref, Range, RangeBounds},
     sync::Arc,
 };
 
@@ -165,6 +165,19 @@ impl<'a> BlockHashReader for StaticFileJarProvider<'a> {
         }
         Ok(hashes)
     }
+
+    fn canonical_hashes_iter(
+        &self,
+        range: Range<BlockNumber>,
+    ) -> ProviderResult<CanonicalHashesIter<'_>> {
+        let mut cursor = self.cursor()?;
+        Ok(Box::new(range.filter_map(move |number| {
+            cursor
+                .get_one::<HeaderMask<BlockHash>>(number.into())
+                .transpose()
+                .map(|hash| Ok((number, hash?)))
+        })))
+    }
 }
 
 impl<'a> BlockNumReader for StaticFileJarProvider<'a> {
@@ -280,6 +293,12 @@ impl<'a> TransactionsProvider for StaticFileJarProvider<'a> {
             .ok_or(ProviderError::SenderRecoveryError)
     }
 
+    fn senders_by_block(&self, _id: BlockHashOrNumber) -> ProviderResult<Option<Vec<Address>>> {
+        // Related to indexing tables. Live database should get the tx_range and call static file
+        // provider with `senders_by_tx_range` instead.
+        Err(ProviderError::UnsupportedProvider)
+    }
+
     fn transaction_sender(&self, num: TxNumber) -> ProviderResult<Option<Address>> {
         Ok(self
             .cursor()?
@@ -3,9 +3,10 @@ use super::{
     StaticFileProviderRWRefMut, BLOCKS_PER_STATIC_FILE,
 };
 use crate::{
-    to_range, BlockHashReader, BlockNumReader, BlockReader, BlockSource, DatabaseProvider,
-    HeaderProvider, ReceiptProvider, RequestsProvider, StageCheckpointReader, StatsReader,
-    TransactionVariant, TransactionsProvider, TransactionsProviderExt, WithdrawalsProvider,
+    to_range, BlockHashReader, BlockNumReader, BlockReader, BlockSource, CanonicalHashesIter,
+    DatabaseProvider, HeaderProvider, ReceiptProvider, RequestsProvider, StageCheckpointReader,
+    StatsReader, TransactionVariant, TransactionsProvider, TransactionsProviderExt,
+    WithdrawalsProvider,
 };
 use dashmap::{mapref::entry::Entry as DashMapEntry, DashMap};
 use parking_lot::RwLock;
@@ -20,7 +21,7 @@ use reth_db_api::{
     table::Table,
     transaction::DbTx,
 };
-use reth_nippy_jar::NippyJar;
+use reth_nippy_jar::{NippyJar, NippyJarError, CHECKSUM_CHECKPOINT_INTERVAL};
 use reth_primitives::{
     keccak256,
     static_file::{find_fixed_range, HighestStaticFiles, SegmentHeader, SegmentRangeInclusive},
@@ -317,6 +318,43 @@ impl StaticFileProvider {
         Ok(())
     }
 
+    /// Returns the length of the data file of the static file that contains the block, or `0` if
+    /// it doesn't exist yet.
+    pub fn data_file_len(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+    ) -> ProviderResult<u64> {
+        let path = self.path.join(segment.filename(&find_fixed_range(block)));
+        match path.metadata() {
+            Ok(metadata) => Ok(metadata.len()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(err) => Err(reth_fs_util::FsPathError::metadata(err, path).into()),
+        }
+    }
+
+    /// Re-reads the data file of the static file that contains the block from `offset` and
+    /// verifies it against the checksum written on its last commit.
+    ///
+    /// See [`NippyJar::verify_checksum`]. Returns [`ProviderError::CorruptedStaticFile`] if the
+    /// data doesn't match.
+    pub fn verify_checksum(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+        offset: u64,
+    ) -> ProviderResult<()> {
+        let path = self.path.join(segment.filename(&find_fixed_range(block)));
+        let jar = NippyJar::<SegmentHeader>::load(&path)
+            .map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+        jar.verify_checksum(offset).map_err(|e| match e {
+            NippyJarError::ChecksumMismatch { .. } => {
+                ProviderError::CorruptedStaticFile(segment, path)
+            }
+            e => ProviderError::NippyJar(e.to_string()),
+        })
+    }
+
     /// Given a segment and block range it returns a cached
     /// [`StaticFileJarProvider`]. TODO(joshie): we should check the size and pop N if there's too
     /// many.
@@ -523,6 +561,12 @@ impl StaticFileProvider {
     ///
     /// Returns a [`Option`] of [`PipelineTarget::Unwind`] if any healing is further required.
     ///
+    /// Returns [`ProviderError::CorruptedStaticFile`] if the committed data of a segment doesn't
+    /// match its checksum. Only the last [`CHECKSUM_CHECKPOINT_INTERVAL`] bytes of the latest
+    /// static file of each segment are re-read, to keep startup fast. Older data is verified once,
+    /// right after it is produced, and corruption of it afterwards is not detected here. Use
+    /// [`Self::verify_checksum`] with an offset of `0` to verify a whole static file.
+    ///
     /// WARNING: No static file writer should be held before calling this function, otherwise it
     /// will deadlock.
     #[allow(clippy::while_let_loop)]
@@ -558,6 +602,13 @@ impl StaticFileProvider {
             //   accordingly.
             self.ensure_file_consistency(segment)?;
 
+            // Committed data may have been corrupted on disk afterwards, which can't be healed.
+            if let Some(highest_block) = self.get_highest_static_file_block(segment) {
+                let data_len = self.data_file_len(segment, highest_block)?;
+                let offset = data_len.saturating_sub(CHECKSUM_CHECKPOINT_INTERVAL);
+                self.verify_checksum(segment, highest_block, offset)?;
+            }
+
             // Only applies to block-based static files. (Headers)
             //
             // The updated `highest_block` may have decreased if we healed from a pruning
@@ -978,6 +1029,55 @@ impl StaticFileProvider {
         Ok(data)
     }
 
+    /// Returns an iterator over the canonical hashes of the range, reading them from the static
+    /// files and, above the highest static file block, from the database `cursor`.
+    ///
+    /// Missing hashes are skipped.
+    pub fn canonical_hashes_iter_with_database<'a, C>(
+        &self,
+        range: Range<BlockNumber>,
+        mut cursor: C,
+    ) -> CanonicalHashesIter<'a>
+    where
+        C: DbCursorRO<tables::CanonicalHeaders> + 'a,
+    {
+        let static_file_end = self
+            .get_highest_static_file_block(StaticFileSegment::Headers)
+            .map_or(range.start, |highest| (highest + 1).clamp(range.start, range.end));
+
+        let static_file_provider = self.clone();
+        let static_files = (range.start..static_file_end).filter_map(move |number| {
+            static_file_provider.block_hash(number).transpose().map(|hash| Ok((number, hash?)))
+        });
+
+        // The first call seeks to the start of the database range, the following ones advance
+        // the cursor until the end of the range.
+        let mut seek = Some(static_file_end);
+        let mut done = static_file_end >= range.end;
+        let database = std::iter::from_fn(move || {
+            if done {
+                return None
+            }
+            let entry = match seek.take() {
+                Some(start) => cursor.seek(start),
+                None => cursor.next(),
+            };
+            match entry {
+                Ok(Some((number, hash))) if number < range.end => Some(Ok((number, hash))),
+                Ok(_) => {
+                    done = true;
+                    None
+                }
+                Err(err) => {
+                    done = true;
+                    Some(Err(err.into()))
+                }
+            }
+        });
+
+        Box::new(static_files.chain(database))
+    }
+
     #[cfg(any(test, feature = "test-utils"))]
     /// Returns `static_files` directory
     pub fn path(&self) -> &Path {
@@ -1174,6 +1274,21 @@ impl BlockHashReader for StaticFileProvider {
             |_| true,
         )
     }
+
+    fn canonical_hashes_iter(
+        &self,
+        range: Range<BlockNumber>,
+    ) -> ProviderResult<CanonicalHashesIter<'_>> {
+        Ok(Box::new(self.fetch_range_iter(
+            StaticFileSegment::Headers,
+            range,
+            |cursor, number| {
+                Ok(cursor
+                    .get_one::<HeaderMask<BlockHash>>(number.into())?
+                    .map(|hash| (number, hash)))
+            },
+        )?))
+    }
 }
 
 impl ReceiptProvider for StaticFileProvider {
@@ -1378,6 +1493,11 @@ impl TransactionsProvider for StaticFileProvider {
             .ok_or(ProviderError::SenderRecoveryError)
     }
 
+    fn senders_by_block(&self, _id: BlockHashOrNumber) -> ProviderResult<Option<Vec<Address>>> {
+        // Required data not present in static_files
+        Err(ProviderError::UnsupportedProvider)
+    }
+
     fn transaction_sender(&self, id: TxNumber) -> ProviderResult<Option<Address>> {
         Ok(self.transaction_by_id_no_hash(id)?.and_then(|tx| tx.recover_signer()))
     }
@@ -1490,6 +1610,11 @@ impl WithdrawalsProvider for StaticFileProvider {
         // Required data not present in static_files
         Err(ProviderError::UnsupportedProvider)
     }
+
+    fn latest_withdrawal_until(&self, _block: BlockNumber) -> ProviderResult<Option<Withdrawal>> {
+        // Required data not present in static_files
+        Err(ProviderError::UnsupportedProvider)
+    }
 }
 
 impl RequestsProvider for StaticFileProvider {
@@ -20,7 +20,7 @@ use revm::{
 /// Assert genesis block
 pub fn assert_genesis_block<DB: Database>(provider: &DatabaseProviderRW<DB>, g: SealedBlock) {
     let n = g.number;
-    let h = B256::ZERO;
+    let h = g.hash();
     let tx = provider;
 
     // check if all tables are empty
@@ -74,7 +74,8 @@ pub struct BlockchainTestData {
 impl BlockchainTestData {
     /// Create test data with two blocks that are connected, specifying their block numbers.
     pub fn default_from_number(first: BlockNumber) -> Self {
-        let one = block1(first);
+        let genesis = genesis_at(first - 1);
+        let one = block1(first, genesis.hash());
         let mut extended_execution_outcome = one.1.clone();
         let two = block2(first + 1, one.0.hash(), &extended_execution_outcome);
         extended_execution_outcome.extend(two.1.clone());
@@ -83,30 +84,25 @@ impl BlockchainTestData {
         let four = block4(first + 3, three.0.hash(), &extended_execution_outcome);
         extended_execution_outcome.extend(four.1.clone());
         let five = block5(first + 4, four.0.hash(), &extended_execution_outcome);
-        Self { genesis: genesis(), blocks: vec![one, two, three, four, five] }
+        Self { genesis, blocks: vec![one, two, three, four, five] }
     }
 }
 
 impl Default for BlockchainTestData {
     fn default() -> Self {
-        let one = block1(1);
-        let mut extended_execution_outcome = one.1.clone();
-        let two = block2(2, one.0.hash(), &extended_execution_outcome);
-        extended_execution_outcome.extend(two.1.clone());
-        let three = block3(3, two.0.hash(), &extended_execution_outcome);
-        extended_execution_outcome.extend(three.1.clone());
-        let four = block4(4, three.0.hash(), &extended_execution_outcome);
-        extended_execution_outcome.extend(four.1.clone());
-        let five = block5(5, four.0.hash(), &extended_execution_outcome);
-        Self { genesis: genesis(), blocks: vec![one, two, three, four, five] }
+        Self::default_from_number(1)
     }
 }
 
 /// Genesis block
 pub fn genesis() -> SealedBlock {
+    genesis_at(0)
+}
+
+/// Empty block at the given height that the test blocks are built on.
+fn genesis_at(number: BlockNumber) -> SealedBlock {
     SealedBlock {
-        header: Header { number: 0, difficulty: U256::from(1), ..Default::default() }
-            .seal(B256::ZERO),
+        header: Header { number, difficulty: U256::from(1), ..Default::default() }.seal_slow(),
         body: vec![],
         ommers: vec![],
         withdrawals: Some(Withdrawals::default()),
@@ -137,7 +133,7 @@ fn bundle_state_root(execution_outcome: &ExecutionOutcome) -> B256 {
 }
 
 /// Block one that points to genesis
-fn block1(number: BlockNumber) -> (SealedBlockWithSenders, ExecutionOutcome) {
+fn block1(number: BlockNumber, parent_hash: B256) -> (SealedBlockWithSenders, ExecutionOutcome) {
     // block changes
     let account1: Address = [0x60; 20].into();
     let account2: Address = [0x61; 20].into();
@@ -181,8 +177,9 @@ fn block1(number: BlockNumber) -> (SealedBlockWithSenders, ExecutionOutcome) {
     block.withdrawals = Some(Withdrawals::new(vec![Withdrawal::default()]));
     let mut header = block.header.clone().unseal();
     header.number = number;
+    header.gas_used = 300;
     header.state_root = state_root;
-    header.parent_hash = B256::ZERO;
+    header.parent_hash = parent_hash;
     block.header = header.seal_slow();
 
     (SealedBlockWithSenders { block, senders: vec![Address::new([0x30; 20])] }, execution_outcome)
@@ -244,6 +241,7 @@ fn block2(
     block.withdrawals = Some(Withdrawals::new(vec![Withdrawal::default()]));
     let mut header = block.header.clone().unseal();
     header.number = number;
+    header.gas_used = 400;
     header.state_root = state_root;
     // parent_hash points to block1 hash
     header.parent_hash = parent_hash;
@@ -309,6 +307,7 @@ fn block3(
     block.withdrawals = Some(Withdrawals::new(vec![Withdrawal::default()]));
     let mut header = block.header.clone().unseal();
     header.number = number;
+    header.gas_used = 400;
     header.state_root = state_root;
     // parent_hash points to block1 hash
     header.parent_hash = parent_hash;
@@ -400,6 +399,7 @@ fn block4(
     block.withdrawals = Some(Withdrawals::new(vec![Withdrawal::default()]));
     let mut header = block.header.clone().unseal();
     header.number = number;
+    header.gas_used = 400;
     header.state_root = state_root;
     // parent_hash points to block1 hash
     header.parent_hash = parent_hash;
@@ -486,6 +486,7 @@ fn block5(
     block.withdrawals = Some(Withdrawals::new(vec![Withdrawal::default()]));
     let mut header = block.header.clone().unseal();
     header.number = number;
+    header.gas_used = 400;
     header.state_root = state_root;
     // parent_hash points to block1 hash
     header.parent_hash = parent_hash;
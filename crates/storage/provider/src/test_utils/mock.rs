@@ -1,9 +1,10 @@
 use crate::{
     traits::{BlockSource, ReceiptProvider},
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, FullExecutionDataProvider, HeaderProvider,
-    ReceiptProviderIdExt, RequestsProvider, StateProvider, StateProviderBox, StateProviderFactory,
-    StateRootProvider, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    CanonicalHashesIter, ChainSpecProvider, ChangeSetReader, EvmEnvProvider,
+    FullExecutionDataProvider, HeaderProvider, ReceiptProviderIdExt, RequestsProvider,
+    StateProvider, StateProviderBox, StateProviderFactory, StateRootProvider, TransactionVariant,
+    TransactionsProvider, WithdrawalsProvider,
 };
 use parking_lot::Mutex;
 use reth_db_api::models::{AccountBeforeTx, StoredBlockBodyIndices};
@@ -23,7 +24,7 @@ use revm::{
 };
 use std::{
     collections::{BTreeMap, HashMap},
-    ops::{RangeBounds, RangeInclusive},
+    ops::{Range, RangeBounds, RangeInclusive},
     sync::Arc,
 };
 
@@ -332,6 +333,10 @@ impl TransactionsProvider for MockEthProvider {
         Ok(transactions)
     }
 
+    fn senders_by_block(&self, id: BlockHashOrNumber) -> ProviderResult<Option<Vec<Address>>> {
+        Ok(self.block(id)?.and_then(|block| block.senders()))
+    }
+
     fn transaction_sender(&self, id: TxNumber) -> ProviderResult<Option<Address>> {
         self.transaction_by_id(id).map(|tx_option| tx_option.map(|tx| tx.recover_signer().unwrap()))
     }
@@ -368,19 +373,19 @@ impl BlockHashReader for MockEthProvider {
         Ok(hash)
     }
 
-    fn canonical_hashes_range(
+    fn canonical_hashes_iter(
         &self,
-        start: BlockNumber,
-        end: BlockNumber,
-    ) -> ProviderResult<Vec<B256>> {
-        let range = start..end;
+        range: Range<BlockNumber>,
+    ) -> ProviderResult<CanonicalHashesIter<'_>> {
         let lock = self.blocks.lock();
 
         let mut hashes: Vec<_> =
             lock.iter().filter(|(_, block)| range.contains(&block.number)).collect();
         hashes.sort_by_key(|(_, block)| block.number);
 
-        Ok(hashes.into_iter().map(|(hash, _)| *hash).collect())
+        let hashes =
+            hashes.into_iter().map(|(hash, block)| (block.number, *hash)).collect::<Vec<_>>();
+        Ok(Box::new(hashes.into_iter().map(Ok)))
     }
 }
 
@@ -468,10 +473,10 @@ impl BlockReader for MockEthProvider {
 
     fn block_with_senders(
         &self,
-        _id: BlockHashOrNumber,
+        id: BlockHashOrNumber,
         _transaction_kind: TransactionVariant,
     ) -> ProviderResult<Option<BlockWithSenders>> {
-        Ok(None)
+        Ok(self.block(id)?.and_then(|block| block.with_recovered_senders()))
     }
 
     fn block_range(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Vec<Block>> {
@@ -684,6 +689,9 @@ impl WithdrawalsProvider for MockEthProvider {
     fn latest_withdrawal(&self) -> ProviderResult<Option<Withdrawal>> {
         Ok(None)
     }
+    fn latest_withdrawal_until(&self, _block: BlockNumber) -> ProviderResult<Option<Withdrawal>> {
+        Ok(None)
+    }
 }
 
 impl RequestsProvider for MockEthProvider {
@@ -1,10 +1,10 @@
 use crate::{
     traits::{BlockSource, ReceiptProvider},
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider, PruneCheckpointReader,
-    ReceiptProviderIdExt, RequestsProvider, StageCheckpointReader, StateProvider, StateProviderBox,
-    StateProviderFactory, StateRootProvider, TransactionVariant, TransactionsProvider,
-    WithdrawalsProvider,
+    CanonicalHashesIter, ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider,
+    PruneCheckpointReader, ReceiptProviderIdExt, RequestsProvider, StageCheckpointReader,
+    StateProvider, StateProviderBox, StateProviderFactory, StateRootProvider, TransactionVariant,
+    TransactionsProvider, WithdrawalsProvider,
 };
 use reth_db_api::models::{AccountBeforeTx, StoredBlockBodyIndices};
 use reth_evm::ConfigureEvmEnv;
@@ -24,7 +24,7 @@ use revm::{
     primitives::{BlockEnv, CfgEnvWithHandlerCfg},
 };
 use std::{
-    ops::{RangeBounds, RangeInclusive},
+    ops::{Range, RangeBounds, RangeInclusive},
     sync::Arc,
 };
 
@@ -45,12 +45,11 @@ impl BlockHashReader for NoopProvider {
         Ok(None)
     }
 
-    fn canonical_hashes_range(
+    fn canonical_hashes_iter(
         &self,
-        _start: BlockNumber,
-        _end: BlockNumber,
-    ) -> ProviderResult<Vec<B256>> {
-        Ok(vec![])
+        _range: Range<BlockNumber>,
+    ) -> ProviderResult<CanonicalHashesIter<'_>> {
+        Ok(Box::new(std::iter::empty()))
     }
 }
 
@@ -223,6 +222,10 @@ impl TransactionsProvider for NoopProvider {
         Ok(Vec::default())
     }
 
+    fn senders_by_block(&self, _id: BlockHashOrNumber) -> ProviderResult<Option<Vec<Address>>> {
+        Ok(None)
+    }
+
     fn transaction_sender(&self, _id: TxNumber) -> ProviderResult<Option<Address>> {
         Ok(None)
     }
@@ -453,6 +456,9 @@ impl WithdrawalsProvider for NoopProvider {
     fn latest_withdrawal(&self) -> ProviderResult<Option<Withdrawal>> {
         Ok(None)
     }
+    fn latest_withdrawal_until(&self, _block: BlockNumber) -> ProviderResult<Option<Withdrawal>> {
+        Ok(None)
+    }
 }
 
 impl RequestsProvider for NoopProvider {
@@ -0,0 +1,37 @@
+use auto_impl::auto_impl;
+use reth_primitives::{accumulator::MmrProof, BlockNumber, B256};
+use reth_storage_errors::provider::ProviderResult;
+use std::ops::RangeInclusive;
+
+/// Client trait for proving that blocks are canonical with the accumulator of canonical header
+/// hashes, see [`reth_primitives::accumulator`].
+///
+/// The accumulator is only maintained if the `HeaderAccumulator` stage is enabled.
+#[auto_impl(&, Arc, Box)]
+pub trait HeaderAccumulatorProvider: Send + Sync {
+    /// Returns the number of accumulated leaves, i.e. the number of the highest accumulated block
+    /// plus one, or `None` if the accumulator is disabled.
+    fn header_accumulator_leaf_count(&self) -> ProviderResult<Option<u64>>;
+
+    /// Returns the root of the accumulator up to and including the given block, or `None` if the
+    /// block isn't accumulated.
+    fn accumulator_root_at(&self, block: BlockNumber) -> ProviderResult<Option<B256>>;
+
+    /// Returns the proof that the block is canonical, against the root of the accumulator up to
+    /// the highest accumulated block, or `None` if the block isn't accumulated.
+    fn prove_canonical(&self, block: BlockNumber) -> ProviderResult<Option<MmrProof>>;
+}
+
+/// Header accumulator writer.
+#[auto_impl(&, Arc, Box)]
+pub trait HeaderAccumulatorWriter: Send + Sync {
+    /// Enables the accumulator with no leaves, or disables it and removes all of its nodes.
+    fn reset_header_accumulator(&self, enabled: bool) -> ProviderResult<()>;
+
+    /// Appends the canonical header hashes of the block range to the accumulator. The range has to
+    /// start at the current leaf count.
+    fn append_header_accumulator(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<()>;
+
+    /// Removes the leaves from `leaf_count` on, and all nodes that cover them.
+    fn truncate_header_accumulator(&self, leaf_count: u64) -> ProviderResult<()>;
+}
@@ -33,6 +33,15 @@ pub use hashing::HashingWriter;
 mod history;
 pub use history::HistoryWriter;
 
+mod transaction_history;
+pub use transaction_history::{
+    HistoryDirection, TransactionHistoryProvider, TransactionHistorySettings,
+    TransactionHistoryWriter,
+};
+
+mod header_accumulator;
+pub use header_accumulator::{HeaderAccumulatorProvider, HeaderAccumulatorWriter};
+
 mod database_provider;
 pub use database_provider::DatabaseProviderFactory;
 
@@ -0,0 +1,88 @@
+use auto_impl::auto_impl;
+use reth_primitives::{Address, BlockNumber, TransactionMeta, TxNumber};
+use reth_storage_errors::provider::ProviderResult;
+use std::ops::RangeInclusive;
+
+/// The direction in which the transaction history of an address is walked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HistoryDirection {
+    /// From the newest transaction to the oldest one.
+    #[default]
+    Backward,
+    /// From the oldest transaction to the newest one.
+    Forward,
+}
+
+/// The settings of the transaction history index.
+///
+/// They're stored by the `TransactionHistory` stage while the index is enabled, so that the blocks
+/// written outside of the pipeline are indexed the same way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransactionHistorySettings {
+    /// Whether the transactions are also indexed by the addresses of the logs they emitted.
+    pub index_logs: bool,
+}
+
+impl TransactionHistorySettings {
+    /// Encodes the settings to be stored as the stage checkpoint progress.
+    pub fn encode(&self) -> Vec<u8> {
+        vec![self.index_logs as u8]
+    }
+
+    /// Decodes the settings stored as the stage checkpoint progress.
+    pub fn decode(buf: &[u8]) -> Option<Self> {
+        buf.first().map(|index_logs| Self { index_logs: *index_logs != 0 })
+    }
+}
+
+/// Client trait for fetching the transactions that involve an address.
+///
+/// The index is only populated if the `TransactionHistory` stage is enabled.
+#[auto_impl(&, Arc, Box)]
+pub trait TransactionHistoryProvider: Send + Sync {
+    /// Returns the settings of the transaction history index, or `None` if the index is disabled.
+    fn transaction_history_settings(&self) -> ProviderResult<Option<TransactionHistorySettings>>;
+
+    /// Returns at most `limit` transactions that involve the address, in the given direction.
+    ///
+    /// The transaction `before_tx_number`, if any, is the pagination cursor: the walk starts at the
+    /// transaction that follows it in `direction`, i.e. the transaction number of the last entry
+    /// of the previous page. Without a cursor, the walk starts at the newest transaction
+    /// ([`HistoryDirection::Backward`]) or the oldest one ([`HistoryDirection::Forward`]).
+    fn transactions_by_address(
+        &self,
+        address: Address,
+        before_tx_number: Option<TxNumber>,
+        limit: usize,
+        direction: HistoryDirection,
+    ) -> ProviderResult<Vec<(TxNumber, TransactionMeta)>>;
+}
+
+/// Transaction history writer.
+#[auto_impl(&, Arc, Box)]
+pub trait TransactionHistoryWriter: Send + Sync {
+    /// Saves the settings of the transaction history index, or removes them if the index is
+    /// disabled.
+    fn save_transaction_history_settings(
+        &self,
+        settings: Option<TransactionHistorySettings>,
+    ) -> ProviderResult<()>;
+
+    /// Indexes the transactions of the block range by their senders and recipients, and, if
+    /// `index_logs` is set, by the addresses of the logs they emitted.
+    ///
+    /// Returns the number of inserted entries.
+    fn index_transaction_history(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        index_logs: bool,
+    ) -> ProviderResult<usize>;
+
+    /// Removes the transactions of the block range from the index.
+    ///
+    /// Returns the number of removed entries.
+    fn unindex_transaction_history(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<usize>;
+}
@@ -5,7 +5,8 @@ use crate::{
 use reth_db_api::models::StoredBlockBodyIndices;
 use reth_primitives::{
     Block, BlockHashOrNumber, BlockId, BlockNumber, BlockNumberOrTag, BlockWithSenders, Header,
-    Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, B256,
+    Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, TransactionMeta, TransactionSigned,
+    TxHash, B256,
 };
 use reth_storage_errors::provider::ProviderResult;
 use std::ops::RangeInclusive;
@@ -136,6 +137,22 @@ pub trait BlockReader:
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<Vec<SealedBlockWithSenders>>;
+
+    /// Returns the transaction with matching hash, its metadata and its receipt.
+    ///
+    /// Implementers backed by a database should read all of them from the same snapshot, so the
+    /// receipt always belongs to the returned transaction.
+    ///
+    /// Returns `None` if the transaction or its receipt is not found.
+    fn transaction_and_receipt_by_hash(
+        &self,
+        hash: TxHash,
+    ) -> ProviderResult<Option<(TransactionSigned, TransactionMeta, Receipt)>> {
+        let Some((transaction, meta)) = self.transaction_by_hash_with_meta(hash)? else {
+            return Ok(None)
+        };
+        Ok(self.receipt_by_hash(hash)?.map(|receipt| (transaction, meta, receipt)))
+    }
 }
 
 /// Trait extension for `BlockReader`, for types that implement `BlockId` conversion.
@@ -1,5 +1,11 @@
 use reth_primitives::{BlockHashOrNumber, BlockNumber, B256};
 use reth_storage_errors::provider::ProviderResult;
+use std::ops::Range;
+
+/// An iterator over canonical block numbers and hashes, see
+/// [`BlockHashReader::canonical_hashes_iter`].
+pub type CanonicalHashesIter<'a> =
+    Box<dyn Iterator<Item = ProviderResult<(BlockNumber, B256)>> + 'a>;
 
 /// Client trait for fetching block hashes by number.
 #[auto_impl::auto_impl(&, Arc, Box)]
@@ -29,5 +35,19 @@ pub trait BlockHashReader: Send + Sync {
         &self,
         start: BlockNumber,
         end: BlockNumber,
-    ) -> ProviderResult<Vec<B256>>;
+    ) -> ProviderResult<Vec<B256>> {
+        self.canonical_hashes_iter(start..end)?.map(|entry| entry.map(|(_, hash)| hash)).collect()
+    }
+
+    /// Returns an iterator over the available canonical hashes of the range and their block
+    /// numbers, in ascending block order.
+    ///
+    /// Missing blocks are skipped, so the entries have to be matched by block number.
+    ///
+    /// Unlike [`BlockHashReader::canonical_hashes_range`], the hashes are read lazily, so
+    /// arbitrarily large ranges can be consumed without buffering them.
+    fn canonical_hashes_iter(
+        &self,
+        range: Range<BlockNumber>,
+    ) -> ProviderResult<CanonicalHashesIter<'_>>;
 }
@@ -74,6 +74,12 @@ pub trait TransactionsProvider: BlockNumReader + Send + Sync {
         range: impl RangeBounds<TxNumber>,
     ) -> ProviderResult<Vec<Address>>;
 
+    /// Get the senders of the transactions in a block.
+    ///
+    /// Senders that are not stored are recovered from their transactions. Returns None if the
+    /// block is not found.
+    fn senders_by_block(&self, id: BlockHashOrNumber) -> ProviderResult<Option<Vec<Address>>>;
+
     /// Get transaction sender.
     ///
     /// Returns None if the transaction is not found.
@@ -1,6 +1,6 @@
-use reth_primitives::B256;
+use reth_primitives::{Bytes, StorageEntry, B256};
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::updates::TrieUpdates;
+use reth_trie::{updates::TrieUpdates, TrieAccount};
 use revm::db::BundleState;
 
 /// A type that can compute the state root of a given post state.
@@ -22,3 +22,44 @@ pub trait StateRootProvider: Send + Sync {
         bundle_state: &BundleState,
     ) -> ProviderResult<(B256, TrieUpdates)>;
 }
+
+/// A type that can read ranges of the latest hashed state together with the trie nodes proving
+/// them, as served by the `snap` protocol.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait HashedStateRangeProvider: Send + Sync {
+    /// Returns the state root of the best block, if the hashed state and the trie nodes have been
+    /// brought up to it.
+    ///
+    /// Returns `None` while the hashing or merkle stages lag behind the best block, in which case
+    /// the hashed state tables do not match any state root.
+    fn hashed_state_root(&self) -> ProviderResult<Option<B256>>;
+
+    /// Returns up to `limit` accounts ordered by their hashed address, starting at `start`.
+    ///
+    /// Each account is returned in its trie representation, including its storage root.
+    fn hashed_account_range(
+        &self,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, TrieAccount)>>;
+
+    /// Returns up to `limit` storage slots of the account ordered by their hashed key, starting at
+    /// `start`.
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<StorageEntry>>;
+
+    /// Returns the account trie nodes proving the given hashed addresses, ordered by path.
+    fn account_range_proof(&self, targets: &[B256]) -> ProviderResult<Vec<Bytes>>;
+
+    /// Returns the storage trie nodes of the account proving the given hashed slots, ordered by
+    /// path.
+    fn storage_range_proof(
+        &self,
+        hashed_address: B256,
+        targets: &[B256],
+    ) -> ProviderResult<Vec<Bytes>>;
+}
@@ -1,4 +1,4 @@
-use reth_primitives::{BlockHashOrNumber, Withdrawal, Withdrawals};
+use reth_primitives::{BlockHashOrNumber, BlockNumber, Withdrawal, Withdrawals};
 use reth_storage_errors::provider::ProviderResult;
 
 ///  Client trait for fetching [Withdrawal] related data.
@@ -13,4 +13,8 @@ pub trait WithdrawalsProvider: Send + Sync {
 
     /// Get latest withdrawal from this block or earlier .
     fn latest_withdrawal(&self) -> ProviderResult<Option<Withdrawal>>;
+
+    /// Get the last withdrawal of the given block, or of the closest block before it that has any
+    /// withdrawals.
+    fn latest_withdrawal_until(&self, block: BlockNumber) -> ProviderResult<Option<Withdrawal>>;
 }
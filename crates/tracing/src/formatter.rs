@@ -1,8 +1,7 @@
 use crate::layers::BoxedLayer;
 use clap::ValueEnum;
 use std::{fmt, fmt::Display};
-use tracing_appender::non_blocking::NonBlocking;
-use tracing_subscriber::{EnvFilter, Layer, Registry};
+use tracing_subscriber::{fmt::MakeWriter, EnvFilter, Layer, Registry};
 
 /// Represents the logging format.
 ///
@@ -33,16 +32,19 @@ impl LogFormat {
     /// # Arguments
     /// * `filter` - An `EnvFilter` used to determine which log records to output.
     /// * `color` - An optional string that enables or disables ANSI color codes in the logs.
-    /// * `file_writer` - An optional `NonBlocking` writer for directing logs to a file.
+    /// * `writer` - An optional writer for directing logs to a file or stderr instead of stdout.
     ///
     /// # Returns
     /// A `BoxedLayer<Registry>` that can be added to a tracing subscriber.
-    pub fn apply(
+    pub fn apply<W>(
         &self,
         filter: EnvFilter,
         color: Option<String>,
-        file_writer: Option<NonBlocking>,
-    ) -> BoxedLayer<Registry> {
+        writer: Option<W>,
+    ) -> BoxedLayer<Registry>
+    where
+        W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+    {
         let ansi = if let Some(color) = color {
             std::env::var("RUST_LOG_STYLE").map(|val| val != "never").unwrap_or(color != "never")
         } else {
@@ -61,7 +63,7 @@ impl LogFormat {
                 let layer =
                     tracing_subscriber::fmt::layer().json().with_ansi(ansi).with_target(target);
 
-                if let Some(writer) = file_writer {
+                if let Some(writer) = writer {
                     layer.with_writer(writer).with_filter(filter).boxed()
                 } else {
                     layer.with_filter(filter).boxed()
@@ -71,7 +73,7 @@ impl LogFormat {
             Self::Terminal => {
                 let layer = tracing_subscriber::fmt::layer().with_ansi(ansi).with_target(target);
 
-                if let Some(writer) = file_writer {
+                if let Some(writer) = writer {
                     layer.with_writer(writer).with_filter(filter).boxed()
                 } else {
                     layer.with_filter(filter).boxed()
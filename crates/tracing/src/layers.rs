@@ -70,6 +70,7 @@ impl Layers {
     /// * `directive` - Directive for the default logging level.
     /// * `filter` - Additional filter directives as a string.
     /// * `color` - Optional color configuration for the log messages.
+    /// * `to_stderr` - Whether the logs are written to stderr instead of stdout.
     ///
     /// # Returns
     /// An `eyre::Result<()>` indicating the success or failure of the operation.
@@ -79,9 +80,14 @@ impl Layers {
         default_directive: Directive,
         filters: &str,
         color: Option<String>,
+        to_stderr: bool,
     ) -> eyre::Result<()> {
         let filter = build_env_filter(Some(default_directive), filters)?;
-        let layer = format.apply(filter, color, None);
+        let layer = if to_stderr {
+            format.apply(filter, color, Some(std::io::stderr))
+        } else {
+            format.apply(filter, color, None::<fn() -> std::io::Stdout>)
+        };
         self.inner.push(layer.boxed());
         Ok(())
     }
@@ -0,0 +1,85 @@
+#![allow(missing_docs)]
+use criterion::{
+    criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, Criterion,
+};
+use futures::executor::block_on;
+use pprof::criterion::{Output, PProfProfiler};
+use reth_primitives::TxHash;
+use reth_transaction_pool::{
+    test_utils::{testing_pool, MockTransaction, TestPool},
+    TransactionPool,
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+/// The number of transactions the pool is filled with before the lookups are measured.
+const POOL_SIZE: usize = 10_000;
+
+/// The number of transactions the writer inserts and removes again in a single batch.
+const WRITER_BATCH: usize = 100;
+
+/// Fills the pool with transactions and returns their hashes.
+fn fill_pool(pool: &TestPool, count: usize) -> Vec<TxHash> {
+    let txs = (0..count).map(|_| MockTransaction::eip1559()).collect::<Vec<_>>();
+    block_on(pool.add_external_transactions(txs)).into_iter().map(Result::unwrap).collect()
+}
+
+/// Looks up all hashes, with `writers` threads continuously inserting and removing transactions.
+fn lookup_all(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    pool: &TestPool,
+    hashes: &[TxHash],
+    writers: usize,
+) {
+    let stop = Arc::new(AtomicBool::new(false));
+    let writers = (0..writers)
+        .map(|_| {
+            let (pool, stop) = (pool.clone(), Arc::clone(&stop));
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let added = fill_pool(&pool, WRITER_BATCH);
+                    pool.remove_transactions(added);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let group_id = format!("get | pool size: {} | writers: {}", hashes.len(), writers.len());
+    group.bench_function(group_id, |b| {
+        b.iter(|| {
+            for hash in hashes {
+                std::hint::black_box(pool.get(hash));
+            }
+        })
+    });
+
+    stop.store(true, Ordering::Relaxed);
+    for writer in writers {
+        writer.join().unwrap();
+    }
+}
+
+fn txpool_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Transaction Pool Lookup");
+
+    let pool = testing_pool();
+    let hashes = fill_pool(&pool, POOL_SIZE);
+
+    // without writers this measures the raw lookup cost, with writers the lookups compete with
+    // insertions and removals
+    for writers in [0, 1, 4] {
+        lookup_all(&mut group, &pool, &hashes, writers);
+    }
+}
+
+criterion_group! {
+    name = lookup;
+    config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
+    targets = txpool_lookup
+}
+criterion_main!(lookup);
@@ -1,6 +1,6 @@
 use crate::{PoolSize, TransactionOrigin};
 use reth_primitives::{Address, EIP4844_TX_TYPE_ID};
-use std::collections::HashSet;
+use std::{collections::HashSet, time::Duration};
 /// Guarantees max transactions for one sender, compatible with geth/erigon
 pub const TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER: usize = 16;
 
@@ -18,6 +18,24 @@ pub const DEFAULT_PRICE_BUMP: u128 = 10;
 /// This enforces that a blob transaction requires a 100% price bump to be replaced
 pub const REPLACE_BLOB_PRICE_BUMP: u128 = 100;
 
+/// Default number of recent canonical blocks whose mined transactions are tracked by the pool.
+pub const DEFAULT_RECENTLY_MINED_BLOCKS: u64 = 64;
+
+/// Default maximum number of entries in the cache of recently seen transactions.
+pub const DEFAULT_SEEN_TRANSACTIONS_MAX_ENTRIES: u32 = 100_000;
+
+/// Default time after which an entry in the cache of recently seen transactions expires.
+pub const DEFAULT_SEEN_TRANSACTIONS_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Default maximum number of persisted rejections of transactions that can never become valid.
+pub const DEFAULT_PERSISTENT_REJECTIONS_MAX_ENTRIES: usize = 100_000;
+
+/// Default maximum number of transactions of a batch that are validated concurrently.
+pub const DEFAULT_MAX_CONCURRENT_VALIDATIONS: usize = 256;
+
+/// Default time after which a parked transaction is evicted from the pool, compatible with geth.
+pub const DEFAULT_MAX_QUEUED_LIFETIME: Duration = Duration::from_secs(3 * 60 * 60);
+
 /// Configuration options for the Transaction pool.
 #[derive(Debug, Clone)]
 pub struct PoolConfig {
@@ -36,6 +54,38 @@ pub struct PoolConfig {
     /// How to handle locally received transactions:
     /// [`TransactionOrigin::Local`](crate::TransactionOrigin).
     pub local_transactions_config: LocalTransactionConfig,
+    /// How to handle transactions that reuse the nonce of a recently mined transaction.
+    pub recently_mined: RecentlyMinedConfig,
+    /// Bounds of the cache of recently seen transactions.
+    pub seen_transactions: SeenTransactionsConfig,
+    /// Bounds of the persisted rejections of transactions that can never become valid, [None]
+    /// disables persistence.
+    ///
+    /// The store is opened by the node, see
+    /// [`Pool::with_persistent_rejections`](crate::Pool::with_persistent_rejections).
+    pub persistent_rejections: Option<PersistentRejectionsConfig>,
+    /// Check the internal invariants of the pool after every n-th mutation, `0` disables the
+    /// checks.
+    ///
+    /// This only has an effect in builds with debug assertions, the checks panic if the pool's
+    /// bookkeeping is out of sync with its contents.
+    pub invariant_check_interval: usize,
+    /// Minimum priority fee a transaction must pay at the base fee of the block to be yielded by
+    /// the best transactions iterators, e.g. the protocol floor of the chain, see
+    /// [`FeeFloors`](reth_primitives::FeeFloors).
+    ///
+    /// Note: this doesn't reject transactions, that's up to the validator.
+    pub minimum_priority_fee: Option<u128>,
+    /// Max number of transactions of a batch that are validated concurrently, e.g. the
+    /// transactions of a network message.
+    pub max_concurrent_validations: usize,
+    /// Max time a transaction is parked in the pool, e.g. because of a nonce gap that is never
+    /// filled, before it is evicted.
+    ///
+    /// Pending transactions and local transactions, unless local exemptions are disabled, are
+    /// never evicted by age. See
+    /// [`TransactionPoolExt::evict_expired`](crate::TransactionPoolExt::evict_expired).
+    pub max_queued_lifetime: Duration,
 }
 
 impl PoolConfig {
@@ -59,6 +109,13 @@ impl Default for PoolConfig {
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
             price_bumps: Default::default(),
             local_transactions_config: Default::default(),
+            recently_mined: Default::default(),
+            seen_transactions: Default::default(),
+            persistent_rejections: None,
+            invariant_check_interval: 0,
+            minimum_priority_fee: None,
+            max_concurrent_validations: DEFAULT_MAX_CONCURRENT_VALIDATIONS,
+            max_queued_lifetime: DEFAULT_MAX_QUEUED_LIFETIME,
         }
     }
 }
@@ -124,6 +181,78 @@ impl Default for PriceBumpConfig {
     }
 }
 
+/// Configuration for the cache of recently mined transactions.
+///
+/// The pool remembers the `(sender, nonce)` of every transaction mined in the last
+/// [`max_blocks`](Self::max_blocks) canonical blocks, so that a transaction colliding with a
+/// recently mined nonce can't sneak into the pool while a shallow reorg is still possible.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RecentlyMinedConfig {
+    /// Number of recent canonical blocks whose mined transactions are tracked.
+    pub max_blocks: u64,
+    /// How to handle a transaction that reuses the nonce of a recently mined transaction.
+    pub replacement_policy: MinedReplacementPolicy,
+}
+
+impl Default for RecentlyMinedConfig {
+    fn default() -> Self {
+        Self { max_blocks: DEFAULT_RECENTLY_MINED_BLOCKS, replacement_policy: Default::default() }
+    }
+}
+
+/// Configuration for the cache of recently seen transactions.
+///
+/// The pool remembers the hashes of transactions that were mined or rejected as invalid, so that
+/// the same transaction arriving again through the network is neither requested nor validated
+/// again. See [`Seen`](crate::pool::Seen).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SeenTransactionsConfig {
+    /// Maximum number of cached transaction hashes, `0` disables the cache.
+    pub max_entries: u32,
+    /// Time after which a cached entry is no longer trusted.
+    pub ttl: Duration,
+}
+
+impl Default for SeenTransactionsConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: DEFAULT_SEEN_TRANSACTIONS_MAX_ENTRIES,
+            ttl: DEFAULT_SEEN_TRANSACTIONS_TTL,
+        }
+    }
+}
+
+/// Configuration for the persisted rejections of transactions that can never become valid.
+///
+/// See [`PersistentRejections`](crate::pool::PersistentRejections).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PersistentRejectionsConfig {
+    /// Maximum number of persisted rejections, the oldest are evicted first.
+    pub max_entries: usize,
+    /// Number of rejections that are written to disk at once.
+    pub batch_size: usize,
+}
+
+impl Default for PersistentRejectionsConfig {
+    fn default() -> Self {
+        Self { max_entries: DEFAULT_PERSISTENT_REJECTIONS_MAX_ENTRIES, batch_size: 64 }
+    }
+}
+
+/// Determines what happens to a transaction that reuses the nonce of a recently mined transaction.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum MinedReplacementPolicy {
+    /// Reject the transaction because its nonce is too low.
+    #[default]
+    Reject,
+    /// Hold fee-bumped replacements of the mined transaction in a quarantine.
+    ///
+    /// Quarantined transactions are only inserted into the pool if the block that included the
+    /// mined transaction is reverted, and are dropped once that block leaves the tracked window.
+    /// All other colliding transactions are rejected.
+    Quarantine,
+}
+
 /// Configuration options for the locally received transactions:
 /// [`TransactionOrigin::Local`](crate::TransactionOrigin)
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -1,6 +1,7 @@
 //! Transaction pool errors
 
 use reth_primitives::{Address, BlobTransactionValidationError, InvalidTransactionError, TxHash};
+use std::sync::Arc;
 
 /// Transaction pool result type.
 pub type PoolResult<T> = Result<T, PoolError>;
@@ -59,6 +60,18 @@ pub enum PoolErrorKind {
     /// Thrown if the mutual exclusivity constraint (blob vs normal transaction) is violated.
     #[error("transaction type {1} conflicts with existing transaction for {0}")]
     ExistingConflictingTransactionType(Address, u8),
+    /// Thrown when an external transaction is submitted while the node is far behind the chain
+    /// tip, see [`TransactionPool::set_syncing`](crate::TransactionPool::set_syncing).
+    #[error("node is syncing")]
+    NodeSyncing,
+    /// Thrown when a transaction that was rejected recently is submitted again, see
+    /// [`Seen::Rejected`](crate::pool::Seen::Rejected).
+    #[error("transaction was rejected recently: {0}")]
+    PreviouslyRejected(Arc<str>),
+    /// Thrown when a stale transaction that was rejected recently is submitted again, see
+    /// [`Seen::Stale`](crate::pool::Seen::Stale).
+    #[error("stale transaction was rejected recently: {0}")]
+    PreviouslyRejectedStale(Arc<str>),
     /// Any other error that occurred while inserting/validating a transaction. e.g. IO database
     /// error
     #[error(transparent)]
@@ -133,8 +146,36 @@ impl PoolError {
                 // exclusivity (blob vs normal tx) for all senders
                 false
             }
+            PoolErrorKind::NodeSyncing => {
+                // rejected because of the state of the node, unrelated to the transaction
+                false
+            }
+            PoolErrorKind::PreviouslyRejected(_) => {
+                // only rejections of bad transactions are remembered
+                true
+            }
+            PoolErrorKind::PreviouslyRejectedStale(_) => {
+                // see `is_stale_transaction`
+                false
+            }
         }
     }
+
+    /// Returns `true` if the transaction was rejected by a rule that changed recently, e.g. it
+    /// exceeds a block gas limit that was lowered recently.
+    ///
+    /// Such a transaction can never become valid, but honest peers may still have it in their
+    /// pools, so it's not [bad](Self::is_bad_transaction). Its rejection is remembered so that it
+    /// isn't validated again.
+    pub const fn is_stale_transaction(&self) -> bool {
+        matches!(
+            self.kind,
+            PoolErrorKind::PreviouslyRejectedStale(_) |
+                PoolErrorKind::InvalidTransaction(
+                    InvalidPoolTransactionError::ExceedsLoweredGasLimit(_, _)
+                )
+        )
+    }
 }
 
 /// Represents all errors that can happen when validating transactions for the pool for EIP-4844
@@ -180,6 +221,12 @@ pub enum InvalidPoolTransactionError {
     /// respect the size limits of the pool.
     #[error("transaction's gas limit {0} exceeds block's gas limit {1}")]
     ExceedsGasLimit(u64, u64),
+    /// Thrown if the transaction's gas limit exceeds the block's gas limit, but not the gas limit
+    /// of a recent canonical block, i.e. the block gas limit was lowered recently.
+    #[error(
+        "transaction's gas limit {0} exceeds block's gas limit {1}, which was lowered recently"
+    )]
+    ExceedsLoweredGasLimit(u64, u64),
     /// Thrown when a new transaction is added to the pool, but then immediately discarded to
     /// respect the `max_init_code_size`.
     #[error("transaction's size {0} exceeds max_init_code_size {1}")]
@@ -253,6 +300,11 @@ impl InvalidPoolTransactionError {
                 }
             }
             Self::ExceedsGasLimit(_, _) => true,
+            Self::ExceedsLoweredGasLimit(_, _) => {
+                // the transaction was valid until recently, and honest peers may still have it in
+                // their pools
+                false
+            }
             Self::ExceedsMaxInitCodeSize(_, _) => true,
             Self::OversizedData(_, _) => true,
             Self::Underpriced => {
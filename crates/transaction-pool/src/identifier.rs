@@ -29,6 +29,12 @@ impl SenderIdentifiers {
         self.address_to_id.get(addr).copied()
     }
 
+    /// Returns the `SenderId` that belongs to the given address, or the one that would be assigned
+    /// to it next if it doesn't exist.
+    pub fn sender_id_or_next(&self, addr: &Address) -> SenderId {
+        self.sender_id(addr).unwrap_or(SenderId(self.id))
+    }
+
     /// Returns the existing `SendId` or assigns a new one if it's missing
     pub fn sender_id_or_create(&mut self, addr: Address) -> SenderId {
         self.sender_id(&addr).unwrap_or_else(|| {
@@ -150,27 +150,43 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![warn(clippy::missing_const_for_fn)]
 
-use crate::{identifier::TransactionId, pool::PoolInner};
+use crate::{
+    error::{PoolError, PoolErrorKind},
+    identifier::TransactionId,
+    pool::PoolInner,
+};
 use aquamarine as _;
+use futures_util::StreamExt;
 use reth_eth_wire_types::HandleMempoolData;
-use reth_primitives::{Address, BlobTransactionSidecar, PooledTransactionsElement, TxHash, U256};
+use reth_metrics::MetricsRegistrar;
+use reth_primitives::{
+    Address, BlobTransactionSidecar, BlockNumHash, PooledTransactionsElement,
+    SealedBlockWithSenders, TxHash, U256,
+};
 use reth_provider::StateProviderFactory;
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, sync::Arc, time::Instant};
 use tokio::sync::mpsc::Receiver;
-use tracing::{instrument, trace};
+use tracing::{info, instrument, trace};
 
 pub use crate::{
     blobstore::{BlobStore, BlobStoreError},
     config::{
-        LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
-        REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
-        TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
+        LocalTransactionConfig, MinedReplacementPolicy, PersistentRejectionsConfig, PoolConfig,
+        PriceBumpConfig, RecentlyMinedConfig, SeenTransactionsConfig, SubPoolLimit,
+        DEFAULT_MAX_CONCURRENT_VALIDATIONS, DEFAULT_MAX_QUEUED_LIFETIME,
+        DEFAULT_PERSISTENT_REJECTIONS_MAX_ENTRIES, DEFAULT_PRICE_BUMP,
+        DEFAULT_RECENTLY_MINED_BLOCKS, DEFAULT_SEEN_TRANSACTIONS_MAX_ENTRIES,
+        DEFAULT_SEEN_TRANSACTIONS_TTL, REPLACE_BLOB_PRICE_BUMP,
+        TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER, TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
+        TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
     },
     error::PoolResult,
     ordering::{CoinbaseTipOrdering, Priority, TransactionOrdering},
     pool::{
-        blob_tx_priority, fee_delta, state::SubPool, AllTransactionsEvents, FullTransactionEvent,
-        TransactionEvent, TransactionEvents,
+        blob_tx_priority, fee_delta, state::SubPool, AllTransactionsEvents,
+        BestTransactionsWithPolicy, FullTransactionEvent, PersistentRejections,
+        PersistentRejectionsError, RejectionCode, ReplacementSuggestion, ResubmittedTransaction,
+        Seen, TransactionEvent, TransactionEvents,
     },
     traits::*,
     validate::{
@@ -223,6 +239,13 @@ where
         Self { pool: Arc::new(PoolInner::new(validator, ordering, blob_store, config)) }
     }
 
+    /// Persists the rejections of transactions that can never become valid in the given store, so
+    /// that they are not validated again after a restart.
+    pub fn with_persistent_rejections(self, persistent: PersistentRejections) -> Self {
+        self.inner().set_persistent_rejections(persistent);
+        self
+    }
+
     /// Returns the wrapped pool.
     pub(crate) fn inner(&self) -> &PoolInner<V, T, S> {
         &self.pool
@@ -235,13 +258,16 @@ where
 
     /// Returns future that validates all transaction in the given iterator.
     ///
+    /// Up to [`PoolConfig::max_concurrent_validations`] transactions are validated concurrently.
     /// This returns the validated transactions in the iterator's order.
     async fn validate_all(
         &self,
         origin: TransactionOrigin,
         transactions: impl IntoIterator<Item = V::Transaction>,
     ) -> Vec<(TxHash, TransactionValidationOutcome<V::Transaction>)> {
-        futures_util::future::join_all(transactions.into_iter().map(|tx| self.validate(origin, tx)))
+        futures_util::stream::iter(transactions.into_iter().map(|tx| self.validate(origin, tx)))
+            .buffered(self.pool.config().max_concurrent_validations.max(1))
+            .collect()
             .await
     }
 
@@ -258,6 +284,62 @@ where
         (hash, outcome)
     }
 
+    /// Validates the given transactions and adds them to the pool.
+    ///
+    /// If the transactions were received from a peer, the pool attributes them to the peer.
+    async fn add_transactions_from(
+        &self,
+        origin: TransactionOrigin,
+        peer: Option<PeerId>,
+        transactions: Vec<V::Transaction>,
+    ) -> Vec<PoolResult<TxHash>> {
+        if transactions.is_empty() {
+            return Vec::new()
+        }
+        if origin.is_external() && self.pool.is_syncing() {
+            return transactions
+                .iter()
+                .map(|tx| Err(PoolError::new(*tx.hash(), PoolErrorKind::NodeSyncing)))
+                .collect()
+        }
+
+        // transactions that were seen recently are rejected without validating them again
+        let mut rejected = Vec::new();
+        let transactions = transactions
+            .into_iter()
+            .enumerate()
+            .filter_map(|(idx, tx)| match self.pool.ensure_unseen(tx.hash(), peer) {
+                Ok(()) => Some(tx),
+                Err(err) => {
+                    rejected.push((idx, Err(err)));
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        let validated = self.validate_all(origin, transactions).await;
+
+        let mut results =
+            self.pool.add_transactions_from(origin, peer, validated.into_iter().map(|(_, tx)| tx));
+        for (idx, result) in rejected {
+            results.insert(idx, result);
+        }
+        results
+    }
+
+    /// Returns an error if the transaction is rejected because the node is syncing.
+    ///
+    /// See also [`TransactionPool::set_syncing`].
+    fn ensure_not_syncing(
+        &self,
+        origin: TransactionOrigin,
+        transaction: &V::Transaction,
+    ) -> PoolResult<()> {
+        if origin.is_external() && self.pool.is_syncing() {
+            return Err(PoolError::new(*transaction.hash(), PoolErrorKind::NodeSyncing))
+        }
+        Ok(())
+    }
+
     /// Number of transactions in the entire pool
     pub fn len(&self) -> usize {
         self.pool.len()
@@ -272,6 +354,29 @@ where
     pub fn is_exceeded(&self) -> bool {
         self.pool.is_exceeded()
     }
+
+    /// Validates the transaction and checks whether the pool would accept it, without adding it.
+    ///
+    /// This returns an error exactly if [`TransactionPool::add_transaction`] would reject the
+    /// transaction in the current state of the pool.
+    pub async fn would_accept(
+        &self,
+        origin: TransactionOrigin,
+        transaction: V::Transaction,
+    ) -> PoolResult<()> {
+        self.ensure_not_syncing(origin, &transaction)?;
+        self.pool.check_unseen(transaction.hash())?;
+        let (_, tx) = self.validate(origin, transaction).await;
+        self.pool.would_accept(origin, tx)
+    }
+
+    /// Returns the minimum fees a replacement of the local transaction with the given hash must
+    /// pay to be accepted by the pool.
+    ///
+    /// Returns `None` if the transaction is not in the pool or was not submitted locally.
+    pub fn suggest_replacement(&self, tx_hash: &TxHash) -> Option<ReplacementSuggestion> {
+        self.pool.suggest_replacement(tx_hash)
+    }
 }
 
 impl<Client, S> EthTransactionPool<Client, S>
@@ -333,11 +438,23 @@ where
         self.pool.block_info()
     }
 
+    fn set_syncing(&self, syncing: bool) {
+        if self.pool.set_syncing(syncing) != syncing {
+            if syncing {
+                info!(target: "txpool", "Node is far behind the chain tip, rejecting external transactions");
+            } else {
+                info!(target: "txpool", "Node caught up with the chain tip, accepting external transactions");
+            }
+        }
+    }
+
     async fn add_transaction_and_subscribe(
         &self,
         origin: TransactionOrigin,
         transaction: Self::Transaction,
     ) -> PoolResult<TransactionEvents> {
+        self.ensure_not_syncing(origin, &transaction)?;
+        self.pool.ensure_unseen(transaction.hash(), None)?;
         let (_, tx) = self.validate(origin, transaction).await;
         self.pool.add_transaction_and_subscribe(origin, tx)
     }
@@ -347,6 +464,8 @@ where
         origin: TransactionOrigin,
         transaction: Self::Transaction,
     ) -> PoolResult<TxHash> {
+        self.ensure_not_syncing(origin, &transaction)?;
+        self.pool.ensure_unseen(transaction.hash(), None)?;
         let (_, tx) = self.validate(origin, transaction).await;
         let mut results = self.pool.add_transactions(origin, std::iter::once(tx));
         results.pop().expect("result length is the same as the input")
@@ -357,12 +476,15 @@ where
         origin: TransactionOrigin,
         transactions: Vec<Self::Transaction>,
     ) -> Vec<PoolResult<TxHash>> {
-        if transactions.is_empty() {
-            return Vec::new()
-        }
-        let validated = self.validate_all(origin, transactions).await;
+        self.add_transactions_from(origin, None, transactions).await
+    }
 
-        self.pool.add_transactions(origin, validated.into_iter().map(|(_, tx)| tx))
+    async fn add_peer_transactions(
+        &self,
+        peer_id: PeerId,
+        transactions: Vec<Self::Transaction>,
+    ) -> Vec<PoolResult<TxHash>> {
+        self.add_transactions_from(TransactionOrigin::External, Some(peer_id), transactions).await
     }
 
     fn transaction_event_listener(&self, tx_hash: TxHash) -> Option<TransactionEvents> {
@@ -389,11 +511,11 @@ where
     }
 
     fn pooled_transaction_hashes(&self) -> Vec<TxHash> {
-        self.pool.pooled_transactions_hashes()
+        self.pool.pooled_transactions_hashes(None)
     }
 
     fn pooled_transaction_hashes_max(&self, max: usize) -> Vec<TxHash> {
-        self.pooled_transaction_hashes().into_iter().take(max).collect()
+        self.pool.pooled_transactions_hashes(Some(max))
     }
 
     fn pooled_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
@@ -443,6 +565,12 @@ where
         self.pool.pending_transactions()
     }
 
+    fn pending_transactions_to_propagate(
+        &self,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        self.pool.pending_transactions_to_propagate()
+    }
+
     fn queued_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
         self.pool.queued_transactions()
     }
@@ -469,10 +597,25 @@ where
         self.inner().get(tx_hash)
     }
 
+    fn seen(&self, tx_hash: &TxHash) -> Option<Seen> {
+        self.pool.seen(tx_hash)
+    }
+
     fn get_all(&self, txs: Vec<TxHash>) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
         self.inner().get_all(txs)
     }
 
+    fn get_all_ordered(
+        &self,
+        txs: Vec<TxHash>,
+    ) -> Vec<Option<Arc<ValidPoolTransaction<Self::Transaction>>>> {
+        self.inner().get_all_ordered(txs)
+    }
+
+    fn contains_all(&self, txs: Vec<TxHash>) -> Vec<bool> {
+        self.inner().contains_all(txs)
+    }
+
     fn on_propagated(&self, txs: PropagatedTransactions) {
         self.inner().on_propagated(txs)
     }
@@ -484,6 +627,21 @@ where
         self.pool.get_transactions_by_sender(sender)
     }
 
+    fn get_pending_transactions_by_sender(
+        &self,
+        sender: Address,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        self.pool.pending_transactions_by_sender(sender)
+    }
+
+    fn next_pending_nonce(&self, sender: Address, on_chain_nonce: u64) -> u64 {
+        self.pool.next_pending_nonce(sender, on_chain_nonce)
+    }
+
+    fn pending_sender_info(&self, sender: Address, on_chain_nonce: u64) -> PendingSenderInfo {
+        self.pool.pending_sender_info(sender, on_chain_nonce)
+    }
+
     fn get_transactions_by_sender_and_nonce(
         &self,
         sender: Address,
@@ -522,6 +680,10 @@ where
     ) -> Result<Vec<BlobTransactionSidecar>, BlobStoreError> {
         self.pool.blob_store().get_exact(tx_hashes)
     }
+
+    fn register_metrics(&self, registrar: &mut MetricsRegistrar) {
+        crate::metrics::register_metrics(registrar)
+    }
 }
 
 impl<V, T, S> TransactionPoolExt for Pool<V, T, S>
@@ -555,6 +717,42 @@ where
     fn cleanup_blobs(&self) {
         self.pool.cleanup_blobs()
     }
+
+    fn evict_expired(&self) -> Vec<TxHash> {
+        self.pool.evict_expired()
+    }
+
+    fn on_mined_blocks(&self, blocks: Vec<&SealedBlockWithSenders>) {
+        self.pool.on_mined_blocks(blocks)
+    }
+
+    fn on_reverted_blocks(&self, blocks: Vec<BlockNumHash>) -> Vec<Self::Transaction> {
+        self.pool.on_reverted_blocks(blocks)
+    }
+
+    async fn resubmit_transactions(
+        &self,
+        transactions: Vec<Self::Transaction>,
+    ) -> Vec<PoolResult<TxHash>> {
+        if transactions.is_empty() {
+            return Vec::new()
+        }
+
+        let added = self.pool.take_reverted(transactions.iter().map(|tx| *tx.hash()));
+        let resubmitted = futures_util::stream::iter(transactions.into_iter().zip(added).map(
+            |(tx, added)| async move {
+                let (origin, timestamp) =
+                    added.unwrap_or_else(|| (TransactionOrigin::External, Instant::now()));
+                let (_, outcome) = self.validate(origin, tx).await;
+                ResubmittedTransaction { origin, timestamp, outcome }
+            },
+        ))
+        .buffered(self.pool.config().max_concurrent_validations.max(1))
+        .collect::<Vec<_>>()
+        .await;
+
+        self.pool.resubmit(resubmitted)
+    }
 }
 
 impl<V, T: TransactionOrdering, S> Clone for Pool<V, T, S> {
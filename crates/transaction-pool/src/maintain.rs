@@ -25,10 +25,17 @@ use reth_tasks::TaskSpawner;
 use std::{
     borrow::Borrow,
     collections::HashSet,
+    future::Future,
     hash::{Hash, Hasher},
     path::{Path, PathBuf},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    sync::oneshot,
+    time::{Instant, Interval, MissedTickBehavior},
 };
-use tokio::sync::oneshot;
 use tracing::{debug, error, info, trace, warn};
 
 /// Additional settings for maintaining the transaction pool
@@ -109,8 +116,8 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
         let info = BlockInfo {
             last_seen_block_hash: latest.hash(),
             last_seen_block_number: latest.number,
-            pending_basefee: latest
-                .next_block_base_fee(chain_spec.base_fee_params_at_timestamp(latest.timestamp + 12))
+            pending_basefee: chain_spec
+                .next_block_base_fee(&latest, latest.timestamp + 12)
                 .unwrap_or_default(),
             pending_blob_fee: latest.next_block_blob_fee(),
         };
@@ -265,10 +272,8 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                 let chain_spec = client.chain_spec();
 
                 // fees for the next block: `new_tip+1`
-                let pending_block_base_fee = new_tip
-                    .next_block_base_fee(
-                        chain_spec.base_fee_params_at_timestamp(new_tip.timestamp + 12),
-                    )
+                let pending_block_base_fee = chain_spec
+                    .next_block_base_fee(new_tip, new_tip.timestamp + 12)
                     .unwrap_or_default();
                 let pending_block_blob_fee = new_tip.next_block_blob_fee();
 
@@ -343,6 +348,13 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                     })
                     .collect::<Vec<_>>();
 
+                // unwind the reverted blocks from the recently mined transactions, this releases
+                // the quarantined replacements of transactions mined in the old chain
+                let released_transactions = pool.on_reverted_blocks(
+                    old_blocks.iter().map(|(_, block)| block.num_hash()).collect(),
+                );
+                pool.on_mined_blocks(new_blocks.iter().map(|(_, block)| block).collect());
+
                 // update the pool first
                 let update = CanonicalStateUpdate {
                     new_tip: &new_tip.block,
@@ -357,11 +369,17 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                 // all transactions that were mined in the old chain but not in the new chain need
                 // to be re-injected
                 //
-                // Note: we no longer know if the tx was local or external
+                // Note: transactions that were in the pool keep their origin and timestamp
                 // Because the transactions are not finalized, the corresponding blobs are still in
                 // blob store (if we previously received them from the network)
-                metrics.inc_reinserted_transactions(pruned_old_transactions.len());
-                let _ = pool.add_external_transactions(pruned_old_transactions).await;
+                //
+                // Released replacements go first, so that they take precedence over the original
+                // transactions they replace
+                metrics.inc_reinserted_transactions(
+                    released_transactions.len() + pruned_old_transactions.len(),
+                );
+                let _ = pool.add_external_transactions(released_transactions).await;
+                let _ = pool.resubmit_transactions(pruned_old_transactions).await;
 
                 // keep track of new mined blob transactions
                 blob_store_tracker.add_new_chain_blocks(&new_blocks);
@@ -372,11 +390,8 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                 let chain_spec = client.chain_spec();
 
                 // fees for the next block: `tip+1`
-                let pending_block_base_fee = tip
-                    .next_block_base_fee(
-                        chain_spec.base_fee_params_at_timestamp(tip.timestamp + 12),
-                    )
-                    .unwrap_or_default();
+                let pending_block_base_fee =
+                    chain_spec.next_block_base_fee(tip, tip.timestamp + 12).unwrap_or_default();
                 let pending_block_blob_fee = tip.next_block_blob_fee();
 
                 let first_block = blocks.first();
@@ -404,6 +419,7 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
 
                     // keep track of mined blob transactions
                     blob_store_tracker.add_new_chain_blocks(&blocks);
+                    pool.on_mined_blocks(blocks.iter().map(|(_, block)| block).collect());
 
                     continue
                 }
@@ -434,6 +450,7 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                     mined_transactions,
                 };
                 pool.on_canonical_state_change(update);
+                pool.on_mined_blocks(blocks.iter().map(|(_, block)| block).collect());
 
                 // keep track of mined blob transactions
                 blob_store_tracker.add_new_chain_blocks(&blocks);
@@ -674,6 +691,54 @@ pub async fn backup_local_transactions_task<P>(
     drop(graceful_guard)
 }
 
+/// Default interval at which the [`MaintenanceTask`] evicts expired transactions from the pool.
+pub const DEFAULT_MAINTENANCE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A task that periodically evicts the parked transactions that exceeded the max lifetime from the
+/// pool, see [`TransactionPoolExt::evict_expired`].
+///
+/// The future never resolves, it's intended to be spawned.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct MaintenanceTask<P> {
+    /// The pool to maintain.
+    pool: P,
+    /// Ticks whenever expired transactions should be evicted.
+    interval: Interval,
+}
+
+impl<P> MaintenanceTask<P> {
+    /// Creates a new task that evicts expired transactions every `interval`, starting one interval
+    /// from now.
+    ///
+    /// # Panics
+    ///
+    /// If `interval` is zero or if called outside of a tokio runtime.
+    pub fn new(pool: P, interval: Duration) -> Self {
+        let mut interval = tokio::time::interval_at(Instant::now() + interval, interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self { pool, interval }
+    }
+}
+
+impl<P> Future for MaintenanceTask<P>
+where
+    P: TransactionPoolExt + Unpin,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        while this.interval.poll_tick(cx).is_ready() {
+            let evicted = this.pool.evict_expired();
+            if !evicted.is_empty() {
+                debug!(target: "txpool", evicted = evicted.len(), "Evicted expired transactions");
+            }
+        }
+        Poll::Pending
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
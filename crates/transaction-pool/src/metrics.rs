@@ -1,10 +1,20 @@
 //! Transaction pool metrics.
 
 use reth_metrics::{
-    metrics::{Counter, Gauge},
-    Metrics,
+    metrics::{Counter, Gauge, Histogram},
+    Metrics, MetricsRegistrar,
 };
 
+/// Declares all metrics of the transaction pool.
+pub(crate) fn register_metrics(registrar: &mut MetricsRegistrar) {
+    registrar.describe("transaction_pool", || {
+        TxPoolMetrics::describe();
+        BlobStoreMetrics::describe();
+        MaintainPoolMetrics::describe();
+        AllTransactionsMetrics::describe();
+    });
+}
+
 /// Transaction pool metrics
 #[derive(Metrics)]
 #[metrics(scope = "transaction_pool")]
@@ -15,6 +25,16 @@ pub struct TxPoolMetrics {
     pub(crate) invalid_transactions: Counter,
     /// Number of removed transactions from the pool
     pub(crate) removed_transactions: Counter,
+    /// Number of transactions that replaced a transaction of the same sender and nonce
+    pub(crate) replaced_transactions: Counter,
+    /// Number of transactions discarded from the pool, because they became invalid or the pool
+    /// exceeded its limits
+    pub(crate) discarded_transactions: Counter,
+    /// Number of parked transactions evicted from the pool, because they exceeded the max
+    /// lifetime
+    pub(crate) expired_transactions: Counter,
+    /// How long mined transactions were in the pool in seconds
+    pub(crate) mined_transactions_time_in_pool: Histogram,
 
     /// Number of transactions in the pending sub-pool
     pub(crate) pending_pool_transactions: Gauge,
@@ -12,9 +12,10 @@ use crate::{
     },
     validate::ValidTransaction,
     AllPoolTransactions, AllTransactionsEvents, BestTransactions, BlockInfo, EthPoolTransaction,
-    EthPooledTransaction, NewTransactionEvent, PoolResult, PoolSize, PoolTransaction,
-    PooledTransactionsElement, PropagatedTransactions, TransactionEvents, TransactionOrigin,
-    TransactionPool, TransactionValidationOutcome, TransactionValidator, ValidPoolTransaction,
+    EthPooledTransaction, NewTransactionEvent, PendingSenderInfo, PoolResult, PoolSize,
+    PoolTransaction, PooledTransactionsElement, PropagatedTransactions, TransactionEvents,
+    TransactionOrigin, TransactionPool, TransactionValidationOutcome, TransactionValidator,
+    ValidPoolTransaction,
 };
 use reth_eth_wire_types::HandleMempoolData;
 use reth_primitives::{Address, BlobTransactionSidecar, TxHash, U256};
@@ -45,6 +46,8 @@ impl TransactionPool for NoopTransactionPool {
         }
     }
 
+    fn set_syncing(&self, _syncing: bool) {}
+
     async fn add_transaction_and_subscribe(
         &self,
         _origin: TransactionOrigin,
@@ -165,6 +168,12 @@ impl TransactionPool for NoopTransactionPool {
         vec![]
     }
 
+    fn pending_transactions_to_propagate(
+        &self,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        vec![]
+    }
+
     fn queued_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
         vec![]
     }
@@ -194,6 +203,17 @@ impl TransactionPool for NoopTransactionPool {
         vec![]
     }
 
+    fn get_all_ordered(
+        &self,
+        txs: Vec<TxHash>,
+    ) -> Vec<Option<Arc<ValidPoolTransaction<Self::Transaction>>>> {
+        vec![None; txs.len()]
+    }
+
+    fn contains_all(&self, txs: Vec<TxHash>) -> Vec<bool> {
+        vec![false; txs.len()]
+    }
+
     fn on_propagated(&self, _txs: PropagatedTransactions) {}
 
     fn get_transactions_by_sender(
@@ -203,6 +223,21 @@ impl TransactionPool for NoopTransactionPool {
         vec![]
     }
 
+    fn get_pending_transactions_by_sender(
+        &self,
+        _sender: Address,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        vec![]
+    }
+
+    fn next_pending_nonce(&self, _sender: Address, on_chain_nonce: u64) -> u64 {
+        on_chain_nonce
+    }
+
+    fn pending_sender_info(&self, _sender: Address, on_chain_nonce: u64) -> PendingSenderInfo {
+        PendingSenderInfo { next_nonce: on_chain_nonce, pending_transactions: 0 }
+    }
+
     fn get_transactions_by_sender_and_nonce(
         &self,
         _sender: Address,
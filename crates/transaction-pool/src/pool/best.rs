@@ -1,9 +1,9 @@
 use crate::{
-    identifier::TransactionId, pool::pending::PendingTransaction, PoolTransaction,
-    TransactionOrdering, ValidPoolTransaction,
+    identifier::{SenderId, TransactionId},
+    pool::pending::PendingTransaction,
+    InclusionPolicy, PoolTransaction, TransactionOrdering, ValidPoolTransaction,
 };
 use core::fmt;
-use reth_primitives::B256 as TxHash;
 use std::{
     collections::{BTreeMap, BTreeSet, HashSet},
     sync::Arc,
@@ -24,6 +24,15 @@ pub(crate) struct BestTransactionsWithFees<T: TransactionOrdering> {
     pub(crate) base_fee_per_blob_gas: u64,
 }
 
+impl<T: TransactionOrdering> BestTransactionsWithFees<T> {
+    /// Only yields transactions that pay at least the given priority fee at the base fee.
+    ///
+    /// See [`BestTransactions::set_minimum_priority_fee`].
+    pub(crate) fn set_minimum_priority_fee(&mut self, minimum_priority_fee: Option<u128>) {
+        self.best.set_minimum_priority_fee(minimum_priority_fee, self.base_fee)
+    }
+}
+
 impl<T: TransactionOrdering> crate::traits::BestTransactions for BestTransactionsWithFees<T> {
     fn mark_invalid(&mut self, tx: &Self::Item) {
         BestTransactions::mark_invalid(&mut self.best, tx)
@@ -82,8 +91,9 @@ pub(crate) struct BestTransactions<T: TransactionOrdering> {
     /// Once an `independent` transaction with the nonce `N` is returned, it unlocks `N+1`, which
     /// then can be moved from the `all` set to the `independent` set.
     pub(crate) independent: BTreeSet<PendingTransaction<T>>,
-    /// There might be the case where a yielded transactions is invalid, this will track it.
-    pub(crate) invalid: HashSet<TxHash>,
+    /// There might be the case where a yielded transactions is invalid, this will track its
+    /// sender, since none of the sender's subsequent transactions can be executed either.
+    pub(crate) invalid: HashSet<SenderId>,
     /// Used to receive any new pending transactions that have been added to the pool after this
     /// iterator was static fileted
     ///
@@ -92,12 +102,40 @@ pub(crate) struct BestTransactions<T: TransactionOrdering> {
     pub(crate) new_transaction_receiver: Option<Receiver<PendingTransaction<T>>>,
     /// Flag to control whether to skip blob transactions (EIP4844).
     pub(crate) skip_blobs: bool,
+    /// Minimum priority fee a transaction must pay at [`Self::base_fee`] to be yielded, e.g. the
+    /// protocol floor of the chain.
+    pub(crate) minimum_priority_fee: Option<u128>,
+    /// The base fee the [`Self::minimum_priority_fee`] is enforced at.
+    pub(crate) base_fee: u64,
 }
 
 impl<T: TransactionOrdering> BestTransactions<T> {
+    /// Only yields transactions that pay at least the given priority fee at the given base fee.
+    ///
+    /// Transactions below the floor are marked as invalid, so their descendants are skipped as
+    /// well.
+    pub(crate) fn set_minimum_priority_fee(
+        &mut self,
+        minimum_priority_fee: Option<u128>,
+        base_fee: u64,
+    ) {
+        self.minimum_priority_fee = minimum_priority_fee;
+        self.base_fee = base_fee;
+    }
+
+    /// Returns `true` if the transaction pays the minimum priority fee at the base fee.
+    fn satisfies_minimum_priority_fee(&self, tx: &ValidPoolTransaction<T::Transaction>) -> bool {
+        self.minimum_priority_fee.map_or(true, |minimum_priority_fee| {
+            tx.effective_tip_per_gas(self.base_fee).is_some_and(|tip| tip >= minimum_priority_fee)
+        })
+    }
+
     /// Mark the transaction and it's descendants as invalid.
+    ///
+    /// The descendant of a transaction that was already yielded may already be unlocked, hence
+    /// this marks the sender and skips all of its remaining transactions.
     pub(crate) fn mark_invalid(&mut self, tx: &Arc<ValidPoolTransaction<T::Transaction>>) {
-        self.invalid.insert(*tx.hash());
+        self.invalid.insert(tx.sender_id());
     }
 
     /// Returns the ancestor the given transaction, the transaction with `nonce - 1`.
@@ -174,8 +212,9 @@ impl<T: TransactionOrdering> Iterator for BestTransactions<T> {
             let best = self.independent.pop_last()?;
             let hash = best.transaction.hash();
 
-            // skip transactions that were marked as invalid
-            if self.invalid.contains(hash) {
+            // skip transactions of senders that were marked as invalid, this also skips their
+            // descendants since they are never unlocked
+            if self.invalid.contains(&best.transaction.sender_id()) {
                 debug!(
                     target: "txpool",
                     "[{:?}] skipping invalid transaction",
@@ -193,6 +232,9 @@ impl<T: TransactionOrdering> Iterator for BestTransactions<T> {
                 // blobs should be skipped, marking the as invalid will ensure that no dependent
                 // transactions are returned
                 self.mark_invalid(&best.transaction)
+            } else if !self.satisfies_minimum_priority_fee(&best.transaction) {
+                // the transaction can't be included, and neither can its descendants
+                self.mark_invalid(&best.transaction)
             } else {
                 return Some(best.transaction)
             }
@@ -264,13 +306,97 @@ impl<I: fmt::Debug, P> fmt::Debug for BestTransactionFilter<I, P> {
     }
 }
 
+/// A [`BestTransactions`](crate::traits::BestTransactions) implementation that only yields the
+/// transactions that fit the remaining budgets of an [`InclusionPolicy`].
+///
+/// Transactions that don't fit are skipped for the rest of the iteration, together with their
+/// descendants, but they remain in the pool and can be included in the next block. The budgets
+/// shrink as transactions are included, see [`Self::policy_mut`], so a transaction that was
+/// skipped for its gas limit doesn't keep smaller transactions from being yielded.
+///
+/// The iterator ends once the deadline of the policy has passed.
+pub struct BestTransactionsWithPolicy<T: PoolTransaction> {
+    best: Box<dyn crate::traits::BestTransactions<Item = Arc<ValidPoolTransaction<T>>>>,
+    policy: InclusionPolicy,
+}
+
+impl<T: PoolTransaction> BestTransactionsWithPolicy<T> {
+    /// Create a new [`BestTransactionsWithPolicy`] that enforces the given policy.
+    pub(crate) const fn new(
+        best: Box<dyn crate::traits::BestTransactions<Item = Arc<ValidPoolTransaction<T>>>>,
+        policy: InclusionPolicy,
+    ) -> Self {
+        Self { best, policy }
+    }
+
+    /// Returns the policy with the remaining budgets.
+    pub const fn policy(&self) -> &InclusionPolicy {
+        &self.policy
+    }
+
+    /// Returns a mutable reference to the policy, to update the remaining budgets, e.g. with
+    /// [`InclusionPolicy::on_included`].
+    pub fn policy_mut(&mut self) -> &mut InclusionPolicy {
+        &mut self.policy
+    }
+}
+
+impl<T: PoolTransaction> Iterator for BestTransactionsWithPolicy<T> {
+    type Item = Arc<ValidPoolTransaction<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.policy.is_past_deadline() {
+                return None
+            }
+            if self.policy.remaining_blobs == 0 {
+                // no blob transaction fits anymore
+                self.best.skip_blobs();
+            }
+
+            let best = self.best.next()?;
+            if self.policy.fits(&best) {
+                return Some(best)
+            }
+            // skips the transaction and its descendants for this iteration only
+            self.best.mark_invalid(&best);
+        }
+    }
+}
+
+impl<T: PoolTransaction> crate::traits::BestTransactions for BestTransactionsWithPolicy<T> {
+    fn mark_invalid(&mut self, tx: &Self::Item) {
+        self.best.mark_invalid(tx)
+    }
+
+    fn no_updates(&mut self) {
+        self.best.no_updates()
+    }
+
+    fn skip_blobs(&mut self) {
+        self.set_skip_blobs(true)
+    }
+
+    fn set_skip_blobs(&mut self, skip_blobs: bool) {
+        self.best.set_skip_blobs(skip_blobs)
+    }
+}
+
+impl<T: PoolTransaction> fmt::Debug for BestTransactionsWithPolicy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BestTransactionsWithPolicy").field("policy", &self.policy).finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
         pool::pending::PendingPool,
         test_utils::{MockOrdering, MockTransaction, MockTransactionFactory},
+        BestTransactionsAttributes,
     };
+    use reth_primitives::BlobTransactionSidecar;
 
     #[test]
     fn test_best_iter() {
@@ -321,4 +447,188 @@ mod tests {
         // iterator is empty
         assert!(best.next().is_none());
     }
+
+    #[test]
+    fn test_best_iter_mark_yielded_invalid() {
+        let mut pool = PendingPool::new(MockOrdering::default());
+        let mut f = MockTransactionFactory::default();
+
+        // insert 3 gapless tx for the first sender and 2 for the second sender
+        let invalid_sender = MockTransaction::eip1559();
+        for nonce in 0..3 {
+            let tx = invalid_sender.clone().rng_hash().with_nonce(nonce);
+            pool.add_transaction(Arc::new(f.validated(tx)), 0);
+        }
+        let valid_sender = MockTransaction::eip1559();
+        for nonce in 0..2 {
+            let tx = valid_sender.clone().rng_hash().with_nonce(nonce);
+            pool.add_transaction(Arc::new(f.validated(tx)), 0);
+        }
+
+        let mut best = pool.best();
+        let mut yielded = Vec::new();
+        while let Some(tx) = best.next() {
+            // the first tx of the sender fails after it was yielded, which already unlocked its
+            // descendant
+            if tx.sender() == invalid_sender.sender() {
+                assert_eq!(tx.nonce(), 0);
+                best.mark_invalid(&tx);
+            }
+            yielded.push((tx.sender(), tx.nonce()));
+        }
+
+        assert_eq!(yielded.len(), 3);
+        assert!(yielded.contains(&(invalid_sender.sender(), 0)));
+        assert!(yielded.contains(&(valid_sender.sender(), 0)));
+        assert!(yielded.contains(&(valid_sender.sender(), 1)));
+    }
+
+    #[test]
+    fn test_best_iter_minimum_priority_fee() {
+        let mut pool = PendingPool::new(MockOrdering::default());
+        let mut f = MockTransactionFactory::default();
+        let base_fee = 100;
+
+        // pays exactly the floor
+        let at_floor = MockTransaction::eip1559().with_max_fee(110).with_priority_fee(10);
+        // pays one below the floor, its descendant can't be included either
+        let below_floor = MockTransaction::eip1559().with_max_fee(200).with_priority_fee(9);
+        let descendant = below_floor.next().with_priority_fee(20);
+        // the fee cap limits the tip to one below the floor
+        let capped = MockTransaction::eip1559().with_max_fee(109).with_priority_fee(50);
+        for tx in [&at_floor, &below_floor, &descendant, &capped] {
+            pool.add_transaction(Arc::new(f.validated(tx.clone())), base_fee);
+        }
+
+        // without a floor all transactions are yielded
+        assert_eq!(pool.best().count(), 4);
+
+        let mut best = pool.best();
+        best.set_minimum_priority_fee(Some(10), base_fee);
+        let yielded = best.map(|tx| *tx.hash()).collect::<Vec<_>>();
+        assert_eq!(yielded, vec![at_floor.get_hash()]);
+
+        // the floor is enforced at the given base fee
+        let mut best = pool.best();
+        best.set_minimum_priority_fee(Some(10), base_fee - 1);
+        assert_eq!(best.count(), 3);
+    }
+
+    #[test]
+    fn test_best_with_policy_skips_oversized_transaction() {
+        let mut pool = PendingPool::new(MockOrdering::default());
+        let mut f = MockTransactionFactory::default();
+
+        // the huge transaction pays the highest tip, so it's yielded first if it fits
+        let huge = MockTransaction::eip1559()
+            .with_gas_limit(30_000_000)
+            .with_max_fee(200)
+            .with_priority_fee(50);
+        let small = (0..3)
+            .map(|_| {
+                MockTransaction::eip1559()
+                    .with_gas_limit(21_000)
+                    .with_max_fee(200)
+                    .with_priority_fee(10)
+            })
+            .collect::<Vec<_>>();
+        for tx in std::iter::once(&huge).chain(&small) {
+            pool.add_transaction(Arc::new(f.validated(tx.clone())), 0);
+        }
+
+        let policy = InclusionPolicy::new(BestTransactionsAttributes::base_fee(0), 30_000_000, 0);
+
+        // with the full block the huge transaction fits
+        let mut best = BestTransactionsWithPolicy::new(Box::new(pool.best()), policy);
+        assert_eq!(best.next().map(|tx| *tx.hash()), Some(huge.get_hash()));
+
+        // once some gas is used it doesn't fit anymore, but the small transactions still do
+        let mut best = BestTransactionsWithPolicy::new(Box::new(pool.best()), policy);
+        best.policy_mut().on_included(21_000, 0);
+        let mut yielded = Vec::new();
+        while let Some(tx) = best.next() {
+            best.policy_mut().on_included(tx.gas_limit(), 0);
+            yielded.push(*tx.hash());
+        }
+        assert_eq!(yielded.len(), small.len());
+        assert!(small.iter().all(|tx| yielded.contains(&tx.get_hash())));
+        assert_eq!(best.policy().remaining_gas, 30_000_000 - 4 * 21_000);
+
+        // the huge transaction is only skipped, not removed
+        assert_eq!(pool.len(), small.len() + 1);
+    }
+
+    #[test]
+    fn test_best_with_policy_blob_slots() {
+        let mut pool = PendingPool::new(MockOrdering::default());
+        let mut f = MockTransactionFactory::default();
+
+        let sidecar = || BlobTransactionSidecar {
+            blobs: vec![Default::default()],
+            commitments: vec![],
+            proofs: vec![],
+        };
+        // the blob transactions pay the highest tips
+        let blobs = (0..2)
+            .map(|_| {
+                MockTransaction::eip4844_with_sidecar(sidecar())
+                    .with_gas_limit(21_000)
+                    .with_max_fee(200)
+                    .with_priority_fee(50)
+            })
+            .collect::<Vec<_>>();
+        let regular = (0..2)
+            .map(|_| {
+                MockTransaction::eip1559()
+                    .with_gas_limit(21_000)
+                    .with_max_fee(200)
+                    .with_priority_fee(10)
+            })
+            .collect::<Vec<_>>();
+        for tx in blobs.iter().chain(&regular) {
+            pool.add_transaction(Arc::new(f.validated(tx.clone())), 0);
+        }
+
+        let attributes = BestTransactionsAttributes::new(0, Some(0));
+        let policy = InclusionPolicy::new(attributes, 30_000_000, 1);
+        let mut best = BestTransactionsWithPolicy::new(Box::new(pool.best()), policy);
+        let mut yielded = Vec::new();
+        while let Some(tx) = best.next() {
+            best.policy_mut().on_included(tx.gas_limit(), tx.transaction.blob_count() as u64);
+            yielded.push(tx.transaction.clone());
+        }
+
+        // only one blob transaction fits, the regular transactions continue
+        assert_eq!(yielded.iter().filter(|tx| tx.is_eip4844()).count(), 1);
+        assert!(regular.iter().all(|tx| yielded.contains(tx)));
+        assert_eq!(yielded.len(), 1 + regular.len());
+        assert_eq!(best.policy().remaining_blobs, 0);
+    }
+
+    #[test]
+    fn test_best_with_policy_deadline_and_tip() {
+        let mut pool = PendingPool::new(MockOrdering::default());
+        let mut f = MockTransactionFactory::default();
+        let base_fee = 100;
+
+        let tip = MockTransaction::eip1559().with_max_fee(200).with_priority_fee(10);
+        let low_tip = MockTransaction::eip1559().with_max_fee(200).with_priority_fee(5);
+        for tx in [&tip, &low_tip] {
+            pool.add_transaction(Arc::new(f.validated(tx.clone())), base_fee);
+        }
+
+        let policy =
+            InclusionPolicy::new(BestTransactionsAttributes::base_fee(base_fee), 30_000_000, 0);
+        let best = BestTransactionsWithPolicy::new(
+            Box::new(pool.best()),
+            policy.with_min_effective_tip(10),
+        );
+        assert_eq!(best.map(|tx| *tx.hash()).collect::<Vec<_>>(), vec![tip.get_hash()]);
+
+        let mut best = BestTransactionsWithPolicy::new(
+            Box::new(pool.best()),
+            policy.with_deadline(std::time::Instant::now()),
+        );
+        assert!(best.next().is_none());
+    }
 }
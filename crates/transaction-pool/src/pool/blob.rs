@@ -6,6 +6,7 @@ use crate::{
 use std::{
     cmp::Ordering,
     collections::{BTreeMap, BTreeSet},
+    ops::Bound::{Excluded, Unbounded},
     sync::Arc,
 };
 
@@ -215,7 +216,8 @@ impl<T: PoolTransaction> BlobTransactions<T> {
     /// Removes transactions until the pool satisfies its [`SubPoolLimit`].
     ///
     /// This is done by removing transactions according to their ordering in the pool, defined by
-    /// the [`BlobOrd`] struct.
+    /// the [`BlobOrd`] struct. Only transactions without a descendant in the pool are removed, so
+    /// a transaction is never removed while its descendant is kept.
     ///
     /// Removed transactions are returned in the order they were removed.
     pub(crate) fn truncate_pool(
@@ -225,14 +227,28 @@ impl<T: PoolTransaction> BlobTransactions<T> {
         let mut removed = Vec::new();
 
         while self.exceeds(&limit) {
-            let tx = self.all.last().expect("pool is not empty");
-            let id = *tx.transaction.id();
+            // the highest nonce transaction of each sender has no descendant in the pool
+            let id = self
+                .all
+                .iter()
+                .rev()
+                .map(|tx| *tx.transaction.id())
+                .find(|id| !self.has_descendant(id))
+                .expect("pool is not empty");
             removed.push(self.remove_transaction(&id).expect("transaction exists"));
         }
 
         removed
     }
 
+    /// Returns `true` if a transaction of the same sender with a higher nonce is in this pool.
+    fn has_descendant(&self, id: &TransactionId) -> bool {
+        self.by_id
+            .range((Excluded(id), Unbounded))
+            .next()
+            .map_or(false, |(other, _)| other.sender == id.sender)
+    }
+
     /// Returns `true` if the transaction with the given id is already included in this pool.
     pub(crate) fn contains(&self, id: &TransactionId) -> bool {
         self.by_id.contains_key(id)
@@ -243,10 +259,19 @@ impl<T: PoolTransaction> BlobTransactions<T> {
         self.by_id.get(id)
     }
 
-    /// Asserts that the bijection between `by_id` and `all` is valid.
-    #[cfg(any(test, feature = "test-utils"))]
+    /// Asserts that the bijection between `by_id` and `all` is valid and that the tracked size
+    /// matches the transactions.
+    ///
+    /// # Panics
+    ///
+    /// If any invariant is violated.
+    #[cfg(any(test, debug_assertions, feature = "test-utils"))]
     pub(crate) fn assert_invariants(&self) {
         assert_eq!(self.by_id.len(), self.all.len(), "by_id.len() != all.len()");
+        assert!(self.by_id.values().all(|tx| self.all.contains(tx)), "by_id != all");
+
+        let size = self.by_id.values().map(|tx| tx.transaction.size()).sum::<usize>();
+        assert_eq!(self.size(), size, "tracked size != size of all transactions");
     }
 }
 
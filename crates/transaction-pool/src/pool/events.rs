@@ -1,4 +1,7 @@
-use crate::{traits::PropagateKind, PoolTransaction, ValidPoolTransaction};
+use crate::{
+    traits::{PeerId, PropagateKind},
+    PoolTransaction, ValidPoolTransaction,
+};
 use reth_primitives::{TxHash, B256};
 use std::sync::Arc;
 
@@ -29,9 +32,21 @@ pub enum FullTransactionEvent<T: PoolTransaction> {
         replaced_by: TxHash,
     },
     /// Transaction was dropped due to configured limits.
-    Discarded(TxHash),
-    /// Transaction became invalid indefinitely.
-    Invalid(TxHash),
+    Discarded {
+        /// The hash of the discarded transaction.
+        tx_hash: TxHash,
+        /// The peer the transaction was received from, if any.
+        peer: Option<PeerId>,
+    },
+    /// Transaction was rejected by the validator and not added to the pool.
+    Invalid {
+        /// The hash of the rejected transaction.
+        tx_hash: TxHash,
+        /// Why the transaction was rejected.
+        reason: Arc<str>,
+        /// The peer the transaction was received from, if any.
+        peer: Option<PeerId>,
+    },
     /// Transaction was propagated to peers.
     Propagated(Arc<Vec<PropagateKind>>),
 }
@@ -47,8 +62,10 @@ impl<T: PoolTransaction> Clone for FullTransactionEvent<T> {
             Self::Replaced { transaction, replaced_by } => {
                 Self::Replaced { transaction: Arc::clone(transaction), replaced_by: *replaced_by }
             }
-            Self::Discarded(hash) => Self::Discarded(*hash),
-            Self::Invalid(hash) => Self::Invalid(*hash),
+            Self::Discarded { tx_hash, peer } => Self::Discarded { tx_hash: *tx_hash, peer: *peer },
+            Self::Invalid { tx_hash, reason, peer } => {
+                Self::Invalid { tx_hash: *tx_hash, reason: Arc::clone(reason), peer: *peer }
+            }
             Self::Propagated(propagated) => Self::Propagated(Arc::clone(propagated)),
         }
     }
@@ -70,8 +87,8 @@ pub enum TransactionEvent {
     Replaced(TxHash),
     /// Transaction was dropped due to configured limits.
     Discarded,
-    /// Transaction became invalid indefinitely.
-    Invalid,
+    /// Transaction was rejected by the validator for the given reason and not added to the pool.
+    Invalid(Arc<str>),
     /// Transaction was propagated to peers.
     Propagated(Arc<Vec<PropagateKind>>),
 }
@@ -80,6 +97,6 @@ impl TransactionEvent {
     /// Returns `true` if the event is final and no more events are expected for this transaction
     /// hash.
     pub const fn is_final(&self) -> bool {
-        matches!(self, Self::Replaced(_) | Self::Mined(_) | Self::Discarded)
+        matches!(self, Self::Replaced(_) | Self::Mined(_) | Self::Discarded | Self::Invalid(_))
     }
 }
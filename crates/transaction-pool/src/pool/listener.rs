@@ -1,8 +1,9 @@
 //! Listeners for the transaction-pool
 
 use crate::{
+    error::PoolError,
     pool::events::{FullTransactionEvent, TransactionEvent},
-    traits::PropagateKind,
+    traits::{PeerId, PropagateKind},
     PoolTransaction, ValidPoolTransaction,
 };
 use futures_util::Stream;
@@ -168,8 +169,26 @@ impl<T: PoolTransaction> PoolEventBroadcast<T> {
     }
 
     /// Notify listeners about a transaction that was discarded.
-    pub(crate) fn discarded(&mut self, tx: &TxHash) {
-        self.broadcast_event(tx, TransactionEvent::Discarded, FullTransactionEvent::Discarded(*tx));
+    ///
+    /// The peer the transaction was received from is only included in the [`FullTransactionEvent`].
+    pub(crate) fn discarded(&mut self, tx: &TxHash, peer: Option<PeerId>) {
+        self.broadcast_event(
+            tx,
+            TransactionEvent::Discarded,
+            FullTransactionEvent::Discarded { tx_hash: *tx, peer },
+        );
+    }
+
+    /// Notify listeners about a transaction that was rejected by the validator.
+    ///
+    /// The peer the transaction was received from is only included in the [`FullTransactionEvent`].
+    pub(crate) fn invalid(&mut self, err: &PoolError, peer: Option<PeerId>) {
+        let reason: Arc<str> = err.kind.to_string().into();
+        self.broadcast_event(
+            &err.hash,
+            TransactionEvent::Invalid(Arc::clone(&reason)),
+            FullTransactionEvent::Invalid { tx_hash: err.hash, reason, peer },
+        );
     }
 
     /// Notify listeners that the transaction was mined
@@ -0,0 +1,110 @@
+//! A sharded by-hash index of all transactions in the pool.
+//!
+//! Looking up a transaction by its hash is by far the most frequent pool access: every
+//! announcement and every RPC request does it. Serving these lookups from the [`TxPool`] means
+//! they compete with block updates and insertions for the pool lock. The [`TransactionLookup`]
+//! mirrors the by-hash map of the pool in independently locked shards, so readers never have to
+//! wait for the pool lock and only briefly contend with writers of the same shard.
+//!
+//! [`TxPool`]: crate::pool::txpool::TxPool
+
+use crate::{PoolTransaction, ValidPoolTransaction};
+use parking_lot::RwLock;
+use reth_primitives::TxHash;
+use std::{collections::HashMap, fmt, sync::Arc};
+
+/// The number of shards of the [`TransactionLookup`].
+const LOOKUP_SHARDS: usize = 16;
+
+/// A by-hash index of all transactions in the pool, split into independently locked shards.
+///
+/// The lookup is only modified while the pool lock is held for writing, together with the by-hash
+/// map of the pool, so a transaction is contained in the lookup if and only if it is in the pool.
+/// Transactions are removed from the lookup before they're removed from their subpool, so a
+/// reader never observes a transaction that was already evicted.
+pub(crate) struct TransactionLookup<T: PoolTransaction> {
+    shards: [RwLock<HashMap<TxHash, Arc<ValidPoolTransaction<T>>>>; LOOKUP_SHARDS],
+}
+
+impl<T: PoolTransaction> TransactionLookup<T> {
+    /// Returns the shard the given hash belongs to.
+    #[inline]
+    fn shard(&self, tx_hash: &TxHash) -> &RwLock<HashMap<TxHash, Arc<ValidPoolTransaction<T>>>> {
+        &self.shards[tx_hash[0] as usize % LOOKUP_SHARDS]
+    }
+
+    /// Returns the transaction for the given hash.
+    pub(crate) fn get(&self, tx_hash: &TxHash) -> Option<Arc<ValidPoolTransaction<T>>> {
+        self.shard(tx_hash).read().get(tx_hash).cloned()
+    }
+
+    /// Returns `true` if the transaction with the given hash is in the pool.
+    pub(crate) fn contains(&self, tx_hash: &TxHash) -> bool {
+        self.shard(tx_hash).read().contains_key(tx_hash)
+    }
+
+    /// Adds the transaction to the lookup.
+    pub(super) fn insert(&self, transaction: Arc<ValidPoolTransaction<T>>) {
+        let tx_hash = *transaction.hash();
+        self.shard(&tx_hash).write().insert(tx_hash, transaction);
+    }
+
+    /// Removes the transaction with the given hash from the lookup.
+    pub(super) fn remove(&self, tx_hash: &TxHash) {
+        self.shard(tx_hash).write().remove(tx_hash);
+    }
+
+    /// Returns the number of transactions in the lookup.
+    pub(crate) fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.read().len()).sum()
+    }
+
+    /// Returns the hashes of all transactions in the lookup.
+    #[cfg(any(test, debug_assertions, feature = "test-utils"))]
+    pub(crate) fn hashes(&self) -> Vec<TxHash> {
+        self.shards
+            .iter()
+            .flat_map(|shard| shard.read().keys().copied().collect::<Vec<_>>())
+            .collect()
+    }
+}
+
+impl<T: PoolTransaction> Default for TransactionLookup<T> {
+    fn default() -> Self {
+        Self { shards: std::array::from_fn(|_| Default::default()) }
+    }
+}
+
+impl<T: PoolTransaction> fmt::Debug for TransactionLookup<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransactionLookup").field("len", &self.len()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockTransaction, MockTransactionFactory};
+
+    #[test]
+    fn lookup_insert_remove() {
+        let mut f = MockTransactionFactory::default();
+        let lookup = TransactionLookup::<MockTransaction>::default();
+
+        let txs = (0..64).map(|_| f.validated_arc(MockTransaction::eip1559())).collect::<Vec<_>>();
+        for tx in &txs {
+            lookup.insert(Arc::clone(tx));
+        }
+        assert_eq!(lookup.len(), txs.len());
+        for tx in &txs {
+            assert_eq!(lookup.get(tx.hash()).unwrap().id(), tx.id());
+        }
+
+        for tx in &txs[..32] {
+            lookup.remove(tx.hash());
+        }
+        assert_eq!(lookup.len(), 32);
+        assert!(txs[..32].iter().all(|tx| !lookup.contains(tx.hash())));
+        assert!(txs[32..].iter().all(|tx| lookup.contains(tx.hash())));
+    }
+}
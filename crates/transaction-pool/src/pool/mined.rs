@@ -0,0 +1,366 @@
+//! Tracks the transactions mined in recent canonical blocks.
+//!
+//! After a transaction was mined, its sender is free to submit another transaction with the same
+//! nonce. If a shallow reorg then reverts the block that included the original transaction, both
+//! versions compete for the same nonce. The [`RecentlyMinedTransactions`] cache remembers the
+//! `(sender, nonce)` pairs mined in the last blocks so the pool can reject such collisions, or
+//! quarantine fee-bumped replacements until the mined block is actually reverted.
+
+use crate::{
+    config::{MinedReplacementPolicy, PriceBumpConfig, RecentlyMinedConfig},
+    traits::{PoolTransaction, TransactionOrigin},
+    validate::ValidPoolTransaction,
+};
+use reth_primitives::{
+    Address, BlockHash, BlockNumHash, BlockNumber, SealedBlockWithSenders, TxHash,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::Instant,
+};
+
+/// Outcome of checking a new transaction against the recently mined transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MinedNonceCheck {
+    /// The nonce of the transaction wasn't mined recently.
+    Unknown,
+    /// The transaction reuses the nonce of a recently mined transaction.
+    Reject,
+    /// The transaction is a fee-bumped replacement of a recently mined transaction and should be
+    /// quarantined.
+    Quarantine,
+}
+
+/// A transaction that was mined in a recent canonical block.
+#[derive(Debug, Clone)]
+struct MinedTransaction {
+    hash: TxHash,
+    block_hash: BlockHash,
+    tx_type: u8,
+    max_fee_per_gas: u128,
+    max_priority_fee_per_gas: Option<u128>,
+    /// The origin and the timestamp the transaction was added to the pool with, if it was pruned
+    /// from the pool when it was mined.
+    added: Option<(TransactionOrigin, Instant)>,
+}
+
+/// Cache of the `(sender, nonce)` pairs mined in the last
+/// [`max_blocks`](RecentlyMinedConfig::max_blocks) canonical blocks.
+#[derive(Debug)]
+pub(crate) struct RecentlyMinedTransactions<T> {
+    config: RecentlyMinedConfig,
+    /// The hash of every tracked block and the `(sender, nonce)` pairs mined in it.
+    blocks: BTreeMap<BlockNumber, (BlockHash, Vec<(Address, u64)>)>,
+    /// All tracked mined transactions.
+    mined: HashMap<(Address, u64), MinedTransaction>,
+    /// Fee-bumped replacements of mined transactions, released if the mined block is reverted.
+    quarantined: HashMap<(Address, u64), T>,
+    /// The origin and the timestamp of the pool transactions mined in the last reverted blocks.
+    reverted: HashMap<TxHash, (TransactionOrigin, Instant)>,
+}
+
+impl<T: PoolTransaction> RecentlyMinedTransactions<T> {
+    /// Creates an empty cache with the given settings.
+    pub(crate) fn new(config: RecentlyMinedConfig) -> Self {
+        Self {
+            config,
+            blocks: Default::default(),
+            mined: Default::default(),
+            quarantined: Default::default(),
+            reverted: Default::default(),
+        }
+    }
+
+    /// Returns the number of quarantined transactions.
+    pub(crate) fn quarantined_len(&self) -> usize {
+        self.quarantined.len()
+    }
+
+    /// Records the transactions of the given canonical blocks and evicts all blocks that left the
+    /// tracked window.
+    pub(crate) fn on_mined_blocks<'a>(
+        &mut self,
+        blocks: impl IntoIterator<Item = &'a SealedBlockWithSenders>,
+    ) {
+        if self.config.max_blocks == 0 {
+            return
+        }
+
+        for block in blocks {
+            // a canonical block at this height was replaced without being reverted first
+            if let Some((hash, _)) = self.blocks.get(&block.number) {
+                let hash = *hash;
+                self.remove_block(block.number, hash, true);
+            }
+
+            let mut mined = Vec::with_capacity(block.body.len());
+            for (sender, tx) in block.transactions_with_sender() {
+                let key = (*sender, tx.nonce());
+                self.mined.insert(
+                    key,
+                    MinedTransaction {
+                        hash: tx.hash(),
+                        block_hash: block.hash(),
+                        tx_type: tx.tx_type().into(),
+                        max_fee_per_gas: tx.max_fee_per_gas(),
+                        max_priority_fee_per_gas: tx.max_priority_fee_per_gas(),
+                        added: None,
+                    },
+                );
+                mined.push(key);
+            }
+            self.blocks.insert(block.number, (block.hash(), mined));
+        }
+
+        let Some(tip) = self.blocks.keys().next_back().copied() else { return };
+        let oldest = (tip + 1).saturating_sub(self.config.max_blocks);
+        while let Some((&number, &(hash, _))) = self.blocks.first_key_value() {
+            if number >= oldest {
+                break
+            }
+            // the mined transactions are final enough, so the quarantined replacements are dropped
+            self.remove_block(number, hash, false);
+        }
+    }
+
+    /// Remembers the origin and the timestamp of the given transactions that were pruned from the
+    /// pool because they were mined, so they can be restored if their block is reverted.
+    pub(crate) fn on_pruned(
+        &mut self,
+        transactions: impl IntoIterator<Item = Arc<ValidPoolTransaction<T>>>,
+    ) {
+        for tx in transactions {
+            if let Some(mined) = self.mined.get_mut(&(tx.sender(), tx.nonce())) {
+                if mined.hash == *tx.hash() {
+                    mined.added = Some((tx.origin, tx.timestamp));
+                }
+            }
+        }
+    }
+
+    /// Removes the given reverted blocks from the cache.
+    ///
+    /// Returns the quarantined replacements of all transactions mined in these blocks. The origin
+    /// and the timestamp of the reverted pool transactions are kept until the next reorg, see
+    /// [`Self::take_reverted`].
+    pub(crate) fn on_reverted_blocks(
+        &mut self,
+        blocks: impl IntoIterator<Item = BlockNumHash>,
+    ) -> Vec<T> {
+        self.reverted.clear();
+        blocks
+            .into_iter()
+            .flat_map(|block| self.remove_block(block.number, block.hash, true))
+            .collect()
+    }
+
+    /// Returns the origin and the timestamp the given transaction was first added to the pool
+    /// with, if it was pruned from the pool when it was mined in a reverted block.
+    pub(crate) fn take_reverted(&mut self, hash: &TxHash) -> Option<(TransactionOrigin, Instant)> {
+        self.reverted.remove(hash)
+    }
+
+    /// Checks whether the given transaction collides with a recently mined transaction.
+    ///
+    /// With [`MinedReplacementPolicy::Quarantine`], a transaction that bumps the fees of the mined
+    /// transaction, and of any replacement that is already quarantined, should be quarantined.
+    /// Blob transactions are never quarantined.
+    pub(crate) fn check(&self, transaction: &T, price_bumps: &PriceBumpConfig) -> MinedNonceCheck {
+        let key = (transaction.sender(), transaction.nonce());
+        let Some(mined) = self.mined.get(&key) else { return MinedNonceCheck::Unknown };
+
+        if self.config.replacement_policy != MinedReplacementPolicy::Quarantine ||
+            mined.hash == *transaction.hash() ||
+            transaction.is_eip4844()
+        {
+            return MinedNonceCheck::Reject
+        }
+
+        let price_bump = price_bumps.price_bump(mined.tx_type);
+        let bumps_mined = is_fee_bump(
+            (mined.max_fee_per_gas, mined.max_priority_fee_per_gas),
+            transaction,
+            price_bump,
+        );
+        let bumps_quarantined = self.quarantined.get(&key).map_or(true, |quarantined| {
+            is_fee_bump(
+                (quarantined.max_fee_per_gas(), quarantined.max_priority_fee_per_gas()),
+                transaction,
+                price_bump,
+            )
+        });
+
+        if bumps_mined && bumps_quarantined {
+            MinedNonceCheck::Quarantine
+        } else {
+            MinedNonceCheck::Reject
+        }
+    }
+
+    /// Quarantines the given replacement of a recently mined transaction.
+    ///
+    /// This replaces any previously quarantined replacement of the same transaction.
+    pub(crate) fn quarantine(&mut self, transaction: T) {
+        let key = (transaction.sender(), transaction.nonce());
+        if self.mined.contains_key(&key) {
+            self.quarantined.insert(key, transaction);
+        }
+    }
+
+    /// Removes the block from the cache if it's tracked with the given hash and returns the
+    /// quarantined replacements of its transactions.
+    ///
+    /// If the block was `reverted`, the origin and the timestamp of its pool transactions are
+    /// remembered.
+    fn remove_block(&mut self, number: BlockNumber, hash: BlockHash, reverted: bool) -> Vec<T> {
+        match self.blocks.get(&number) {
+            Some((tracked, _)) if *tracked == hash => {}
+            _ => return Vec::new(),
+        }
+        let (_, mined) = self.blocks.remove(&number).expect("block exists");
+
+        let mut released = Vec::new();
+        for key in mined {
+            // the same nonce could have been mined again in a later block
+            if self.mined.get(&key).is_some_and(|tx| tx.block_hash == hash) {
+                let tx = self.mined.remove(&key).expect("transaction exists");
+                if let Some(added) = tx.added.filter(|_| reverted) {
+                    self.reverted.insert(tx.hash, added);
+                }
+                released.extend(self.quarantined.remove(&key));
+            }
+        }
+        released
+    }
+}
+
+/// Returns `true` if the fees of the transaction exceed the given fees by the price bump.
+///
+/// This mirrors the underpriced check for replacements of pooled transactions.
+fn is_fee_bump<T: PoolTransaction>(
+    (max_fee_per_gas, max_priority_fee_per_gas): (u128, Option<u128>),
+    transaction: &T,
+    price_bump: u128,
+) -> bool {
+    if transaction.max_fee_per_gas() <= max_fee_per_gas * (100 + price_bump) / 100 {
+        return false
+    }
+
+    let existing_max_priority_fee_per_gas = max_priority_fee_per_gas.unwrap_or(0);
+    let replacement_max_priority_fee_per_gas = transaction.max_priority_fee_per_gas().unwrap_or(0);
+
+    !(replacement_max_priority_fee_per_gas <=
+        existing_max_priority_fee_per_gas * (100 + price_bump) / 100 &&
+        existing_max_priority_fee_per_gas != 0 &&
+        replacement_max_priority_fee_per_gas != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockTransaction, MockTransactionFactory};
+    use reth_primitives::{Header, IntoRecoveredTransaction, SealedBlock};
+
+    fn mined_block(number: BlockNumber, txs: &[&MockTransaction]) -> SealedBlockWithSenders {
+        let body = txs.iter().map(|tx| tx.to_recovered_transaction().into_signed()).collect();
+        let senders = txs.iter().map(|tx| tx.get_sender()).collect();
+        let header = Header { number, ..Default::default() }.seal_slow();
+        SealedBlockWithSenders::new(SealedBlock { header, body, ..Default::default() }, senders)
+            .unwrap()
+    }
+
+    fn quarantining() -> RecentlyMinedTransactions<MockTransaction> {
+        RecentlyMinedTransactions::new(RecentlyMinedConfig {
+            max_blocks: 2,
+            replacement_policy: MinedReplacementPolicy::Quarantine,
+        })
+    }
+
+    #[test]
+    fn rejects_recently_mined_nonce() {
+        let tx = MockTransaction::eip1559().with_gas_price(1_000);
+        let replacement = tx.inc_price_by(1_000).rng_hash();
+
+        let mut cache = RecentlyMinedTransactions::new(RecentlyMinedConfig::default());
+        assert_eq!(cache.check(&replacement, &Default::default()), MinedNonceCheck::Unknown);
+
+        cache.on_mined_blocks([&mined_block(1, &[&tx])]);
+        assert_eq!(cache.check(&replacement, &Default::default()), MinedNonceCheck::Reject);
+        assert_eq!(cache.check(&tx.next(), &Default::default()), MinedNonceCheck::Unknown);
+    }
+
+    #[test]
+    fn quarantines_fee_bumped_replacement() {
+        let tx = MockTransaction::eip1559().with_gas_price(1_000);
+        let underpriced = tx.inc_price_by(1).rng_hash();
+        let replacement = tx.inc_price_by(1_000).rng_hash();
+
+        let mut cache = quarantining();
+        let block = mined_block(1, &[&tx]);
+        cache.on_mined_blocks([&block]);
+
+        assert_eq!(cache.check(&underpriced, &Default::default()), MinedNonceCheck::Reject);
+        assert_eq!(cache.check(&tx, &Default::default()), MinedNonceCheck::Reject);
+        assert_eq!(cache.check(&replacement, &Default::default()), MinedNonceCheck::Quarantine);
+
+        cache.quarantine(replacement.clone());
+        assert_eq!(cache.quarantined_len(), 1);
+
+        let released = cache.on_reverted_blocks([block.num_hash()]);
+        assert_eq!(released, vec![replacement.clone()]);
+        assert_eq!(cache.check(&replacement, &Default::default()), MinedNonceCheck::Unknown);
+    }
+
+    #[test]
+    fn evicts_blocks_outside_window() {
+        let tx = MockTransaction::eip1559().with_gas_price(1_000);
+        let replacement = tx.inc_price_by(1_000).rng_hash();
+
+        let mut cache = quarantining();
+        let first = mined_block(1, &[&tx]);
+        cache.on_mined_blocks([&first]);
+        cache.quarantine(replacement.clone());
+
+        cache.on_mined_blocks([&mined_block(2, &[]), &mined_block(3, &[])]);
+        assert_eq!(cache.quarantined_len(), 0);
+        assert_eq!(cache.check(&replacement, &Default::default()), MinedNonceCheck::Unknown);
+        assert!(cache.on_reverted_blocks([first.num_hash()]).is_empty());
+    }
+
+    #[test]
+    fn keeps_origin_of_reverted_transactions() {
+        let tx = MockTransaction::eip1559();
+        let mut factory = MockTransactionFactory::default();
+        let valid = Arc::new(factory.validated_with_origin(TransactionOrigin::Local, tx.clone()));
+
+        let mut cache = quarantining();
+        let block = mined_block(1, &[&tx]);
+        cache.on_mined_blocks([&block]);
+        cache.on_pruned([valid.clone()]);
+        assert_eq!(cache.take_reverted(&tx.get_hash()), None);
+
+        cache.on_reverted_blocks([block.num_hash()]);
+        assert_eq!(
+            cache.take_reverted(&tx.get_hash()),
+            Some((TransactionOrigin::Local, valid.timestamp))
+        );
+        assert_eq!(cache.take_reverted(&tx.get_hash()), None);
+    }
+
+    #[test]
+    fn forgets_origin_of_evicted_transactions() {
+        let tx = MockTransaction::eip1559();
+        let mut factory = MockTransactionFactory::default();
+        let valid = Arc::new(factory.validated_with_origin(TransactionOrigin::Local, tx.clone()));
+
+        let mut cache = quarantining();
+        let first = mined_block(1, &[&tx]);
+        cache.on_mined_blocks([&first]);
+        cache.on_pruned([valid]);
+
+        cache.on_mined_blocks([&mined_block(2, &[]), &mined_block(3, &[])]);
+        cache.on_reverted_blocks([first.num_hash()]);
+        assert_eq!(cache.take_reverted(&tx.get_hash()), None);
+    }
+}
@@ -66,16 +66,19 @@
 //!    category (2.) and become pending.
 
 use crate::{
-    error::{PoolError, PoolErrorKind, PoolResult},
+    error::{InvalidPoolTransactionError, PoolError, PoolErrorKind, PoolResult},
     identifier::{SenderId, SenderIdentifiers, TransactionId},
     pool::{
         listener::PoolEventBroadcast,
+        mined::{MinedNonceCheck, RecentlyMinedTransactions},
+        rejected::{PersistentRejections, RejectionCode},
+        seen::SeenTransactions,
         state::SubPool,
         txpool::{SenderInfo, TxPool},
     },
     traits::{
-        AllPoolTransactions, BestTransactionsAttributes, BlockInfo, NewTransactionEvent, PoolSize,
-        PoolTransaction, PropagatedTransactions, TransactionOrigin,
+        AllPoolTransactions, BestTransactionsAttributes, BlockInfo, NewTransactionEvent, PeerId,
+        PendingSenderInfo, PoolSize, PoolTransaction, PropagatedTransactions, TransactionOrigin,
     },
     validate::{TransactionValidationOutcome, ValidPoolTransaction},
     CanonicalStateUpdate, ChangedAccount, PoolConfig, TransactionOrdering, TransactionValidator,
@@ -84,13 +87,17 @@ use best::BestTransactions;
 use parking_lot::{Mutex, RwLock, RwLockReadGuard};
 use reth_eth_wire_types::HandleMempoolData;
 use reth_primitives::{
-    Address, BlobTransaction, BlobTransactionSidecar, IntoRecoveredTransaction,
-    PooledTransactionsElement, TransactionSigned, TxHash, B256,
+    Address, BlobTransaction, BlobTransactionSidecar, BlockNumHash, IntoRecoveredTransaction,
+    InvalidTransactionError, PooledTransactionsElement, SealedBlockWithSenders, TransactionSigned,
+    TxHash, B256, U256,
 };
 use std::{
     collections::{HashMap, HashSet},
     fmt,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock,
+    },
     time::Instant,
 };
 use tokio::sync::mpsc;
@@ -103,18 +110,24 @@ use crate::{
     traits::{GetPooledTransactionLimit, NewBlobSidecar, TransactionListenerKind},
     validate::ValidTransaction,
 };
-pub use best::BestTransactionFilter;
+pub use best::{BestTransactionFilter, BestTransactionsWithPolicy};
 pub use blob::{blob_tx_priority, fee_delta};
 pub use events::{FullTransactionEvent, TransactionEvent};
 pub use listener::{AllTransactionsEvents, TransactionEvents};
 pub use parked::{BasefeeOrd, ParkedOrd, ParkedPool, QueuedOrd};
 pub use pending::PendingPool;
+pub use rejected::{PersistentRejections, PersistentRejectionsError, RejectionCode};
+pub use seen::Seen;
 
 mod best;
 mod blob;
 mod listener;
+mod lookup;
+mod mined;
 mod parked;
 pub(crate) mod pending;
+mod rejected;
+mod seen;
 pub(crate) mod size;
 pub(crate) mod state;
 pub mod txpool;
@@ -137,6 +150,8 @@ where
     blob_store: S,
     /// The internal pool that manages all transactions.
     pool: RwLock<TxPool<T>>,
+    /// Lookup of all transactions in the pool by hash that doesn't require the pool lock.
+    lookup: Arc<TransactionLookup<T::Transaction>>,
     /// Pool settings.
     config: PoolConfig,
     /// Manages listeners for transaction state change events.
@@ -149,6 +164,15 @@ where
     blob_transaction_sidecar_listener: Mutex<Vec<BlobTransactionSidecarListener>>,
     /// Metrics for the blob store
     blob_store_metrics: BlobStoreMetrics,
+    /// Transactions mined in recent canonical blocks, see [`PoolConfig::recently_mined`].
+    recently_mined: RwLock<RecentlyMinedTransactions<T::Transaction>>,
+    /// Transactions that were recently mined or rejected, see [`PoolConfig::seen_transactions`].
+    seen: RwLock<SeenTransactions>,
+    /// Rejections of transactions that can never become valid that survive restarts, if enabled.
+    persistent_rejections: OnceLock<PersistentRejections>,
+    /// Whether the node is far behind the chain tip, see
+    /// [`TransactionPool::set_syncing`](crate::traits::TransactionPool::set_syncing).
+    syncing: AtomicBool,
 }
 
 // === impl PoolInner ===
@@ -161,17 +185,23 @@ where
 {
     /// Create a new transaction pool instance.
     pub(crate) fn new(validator: V, ordering: T, blob_store: S, config: PoolConfig) -> Self {
+        let pool = TxPool::new(ordering, config.clone());
         Self {
             identifiers: Default::default(),
             validator,
             event_listener: Default::default(),
-            pool: RwLock::new(TxPool::new(ordering, config.clone())),
+            lookup: pool.lookup(),
+            pool: RwLock::new(pool),
+            recently_mined: RwLock::new(RecentlyMinedTransactions::new(config.recently_mined)),
+            seen: RwLock::new(SeenTransactions::new(config.seen_transactions)),
+            persistent_rejections: OnceLock::new(),
             pending_transaction_listener: Default::default(),
             transaction_listener: Default::default(),
             blob_transaction_sidecar_listener: Default::default(),
             config,
             blob_store,
             blob_store_metrics: Default::default(),
+            syncing: AtomicBool::new(false),
         }
     }
 
@@ -185,6 +215,18 @@ where
         self.get_pool_data().size()
     }
 
+    /// Returns up to `limit` transactions of the basefee subpool with the highest fee caps,
+    /// ordered by their fee cap in descending order.
+    ///
+    /// These are the transactions that are valid except for the pending base fee, the ones with
+    /// the highest fee caps are the first to be promoted once the base fee drops.
+    pub fn basefee_pool_snapshot(
+        &self,
+        limit: usize,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.get_pool_data().basefee_pool_snapshot(limit)
+    }
+
     /// Returns the currently tracked block
     pub(crate) fn block_info(&self) -> BlockInfo {
         self.get_pool_data().block_info()
@@ -194,6 +236,18 @@ where
         self.pool.write().set_block_info(info)
     }
 
+    /// Returns whether the node is far behind the chain tip.
+    pub(crate) fn is_syncing(&self) -> bool {
+        self.syncing.load(Ordering::Relaxed)
+    }
+
+    /// Sets whether the node is far behind the chain tip.
+    ///
+    /// Returns the previous value.
+    pub(crate) fn set_syncing(&self, syncing: bool) -> bool {
+        self.syncing.swap(syncing, Ordering::Relaxed)
+    }
+
     /// Returns the internal `SenderId` for this address
     pub(crate) fn get_sender_id(&self, addr: Address) -> SenderId {
         self.identifiers.write().sender_id_or_create(addr)
@@ -281,13 +335,14 @@ where
         self.pool.read()
     }
 
-    /// Returns hashes of _all_ transactions in the pool.
-    pub(crate) fn pooled_transactions_hashes(&self) -> Vec<TxHash> {
+    /// Returns hashes of _all_ transactions in the pool that can be propagated, at most `limit`.
+    pub(crate) fn pooled_transactions_hashes(&self, limit: Option<usize>) -> Vec<TxHash> {
         self.get_pool_data()
             .all()
             .transactions_iter()
             .filter(|tx| tx.propagate)
             .map(|tx| *tx.hash())
+            .take(limit.unwrap_or(usize::MAX))
             .collect()
     }
 
@@ -317,7 +372,8 @@ where
         let transactions = self.get_all(tx_hashes);
         let mut elements = Vec::with_capacity(transactions.len());
         let mut size = 0;
-        for transaction in transactions {
+        // transactions that must not be propagated, e.g. private ones, are never served to peers
+        for transaction in transactions.into_iter().filter(|tx| tx.propagate) {
             let encoded_len = transaction.encoded_length();
             let tx = transaction.to_recovered_transaction().into_signed();
             let pooled = if tx.is_eip4844() {
@@ -340,12 +396,13 @@ where
                 }
             };
 
+            // stop before the transaction that would exceed the limit, but always serve at least
+            // one transaction so that an oversized one can still be fetched
             size += encoded_len;
-            elements.push(pooled);
-
-            if limit.exceeds(size) {
+            if !elements.is_empty() && limit.exceeds(size) {
                 break
             }
+            elements.push(pooled);
         }
 
         elements
@@ -377,11 +434,14 @@ where
         let changed_senders = self.changed_senders(changed_accounts.into_iter());
 
         // update the pool
-        let outcome = self.pool.write().on_canonical_state_change(
-            block_info,
-            mined_transactions,
-            changed_senders,
-        );
+        let mut pool = self.pool.write();
+        // the mined pool transactions keep their origin in case their block is reverted
+        let pruned =
+            mined_transactions.iter().filter_map(|hash| pool.get(hash)).collect::<Vec<_>>();
+        let outcome =
+            pool.on_canonical_state_change(block_info, mined_transactions, changed_senders);
+        drop(pool);
+        self.recently_mined.write().on_pruned(pruned);
 
         // This will discard outdated transactions based on the account's nonce
         self.delete_discarded_blobs(outcome.discarded.iter());
@@ -390,6 +450,146 @@ where
         self.notify_on_new_state(outcome);
     }
 
+    /// Records the transactions of new canonical blocks in the cache of recently mined
+    /// transactions.
+    pub(crate) fn on_mined_blocks(&self, blocks: Vec<&SealedBlockWithSenders>) {
+        let now = Instant::now();
+        let mut seen = self.seen.write();
+        for block in &blocks {
+            for tx in &block.body {
+                seen.on_mined(tx.hash(), block.number, now);
+            }
+        }
+        drop(seen);
+
+        // persisted rejections of mined transactions were wrong
+        if let Some(persistent) = self.persistent_rejections.get() {
+            persistent
+                .remove_all(blocks.iter().flat_map(|block| block.body.iter().map(|tx| tx.hash())));
+        }
+
+        self.recently_mined.write().on_mined_blocks(blocks)
+    }
+
+    /// Removes the reverted blocks from the cache of recently mined transactions and returns the
+    /// quarantined replacements of transactions mined in these blocks.
+    pub(crate) fn on_reverted_blocks(&self, blocks: Vec<BlockNumHash>) -> Vec<T::Transaction> {
+        if let Some(first_reverted) = blocks.iter().map(|block| block.number).min() {
+            self.seen.write().on_reverted(first_reverted);
+        }
+
+        let released = self.recently_mined.write().on_reverted_blocks(blocks);
+        if !released.is_empty() {
+            debug!(target: "txpool", released=released.len(), "released quarantined transactions");
+        }
+        released
+    }
+
+    /// Returns the origin and the timestamp the given transactions were first added to the pool
+    /// with, if they were pruned from the pool when they were mined in the last reverted blocks.
+    pub(crate) fn take_reverted(
+        &self,
+        hashes: impl IntoIterator<Item = TxHash>,
+    ) -> Vec<Option<(TransactionOrigin, Instant)>> {
+        let mut recently_mined = self.recently_mined.write();
+        hashes.into_iter().map(|hash| recently_mined.take_reverted(&hash)).collect()
+    }
+
+    /// Sets the store that persists rejections of transactions that can never become valid.
+    ///
+    /// Only the first store is used.
+    pub(crate) fn set_persistent_rejections(&self, persistent: PersistentRejections) {
+        if self.persistent_rejections.set(persistent).is_err() {
+            warn!(target: "txpool", "persistent rejections are already set");
+        }
+    }
+
+    /// Returns what the pool knows about the transaction, if it has seen it recently or its
+    /// rejection was persisted.
+    pub(crate) fn seen(&self, tx_hash: &TxHash) -> Option<Seen> {
+        if self.lookup.contains(tx_hash) {
+            return Some(Seen::InPool)
+        }
+        let seen = self.seen.read().get(tx_hash, Instant::now());
+        match seen {
+            // persisted rejections don't expire
+            None | Some(Seen::Expired) => self.persisted_rejection(tx_hash).or(seen),
+            seen => seen,
+        }
+    }
+
+    /// Returns the persisted rejection of the transaction, if any.
+    fn persisted_rejection(&self, tx_hash: &TxHash) -> Option<Seen> {
+        let code = self.persistent_rejections.get()?.get(tx_hash)?;
+        Some(Seen::Rejected(code.as_str().into()))
+    }
+
+    /// Rejects a transaction the pool has seen recently, without validating it again.
+    ///
+    /// Transactions in the pool are rejected as already imported. Recently mined transactions
+    /// are rejected because their nonce is too low, and recently rejected transactions with
+    /// their original reason.
+    ///
+    /// Only mined and rejected transactions are discarded. The listeners of a pooled transaction
+    /// keep receiving its events.
+    pub(crate) fn ensure_unseen(&self, tx_hash: &TxHash, peer: Option<PeerId>) -> PoolResult<()> {
+        let Some((err, discarded)) = self.seen_error(tx_hash) else { return Ok(()) };
+        trace!(target: "txpool", hash=?tx_hash, %err, "rejected recently seen transaction");
+        if discarded {
+            self.event_listener.write().discarded(tx_hash, peer);
+        }
+        Err(err)
+    }
+
+    /// Checks whether the pool has seen the transaction recently, like [`Self::ensure_unseen`]
+    /// but without notifying any listeners.
+    pub(crate) fn check_unseen(&self, tx_hash: &TxHash) -> PoolResult<()> {
+        match self.seen_error(tx_hash) {
+            Some((err, _)) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the error a recently seen transaction is rejected with, together with whether the
+    /// transaction is discarded.
+    fn seen_error(&self, tx_hash: &TxHash) -> Option<(PoolError, bool)> {
+        let seen_error = match self.seen(tx_hash)? {
+            Seen::Expired => return None,
+            Seen::InPool => (PoolError::new(*tx_hash, PoolErrorKind::AlreadyImported), false),
+            Seen::Stale(reason) => {
+                (PoolError::new(*tx_hash, PoolErrorKind::PreviouslyRejectedStale(reason)), false)
+            }
+            Seen::Mined(_) => (mined_nonce_error(*tx_hash), true),
+            Seen::Rejected(reason) => {
+                (PoolError::new(*tx_hash, PoolErrorKind::PreviouslyRejected(reason)), true)
+            }
+        };
+        Some(seen_error)
+    }
+
+    /// Checks the transaction against the recently mined transactions.
+    ///
+    /// Returns the transaction back if its nonce wasn't mined recently. Otherwise the transaction
+    /// is either quarantined, or rejected because its nonce is too low.
+    fn check_recently_mined(
+        &self,
+        transaction: T::Transaction,
+        peer: Option<PeerId>,
+    ) -> Result<T::Transaction, PoolResult<TxHash>> {
+        let hash = *transaction.hash();
+        match self.recently_mined.read().check(&transaction, &self.config.price_bumps) {
+            MinedNonceCheck::Unknown => return Ok(transaction),
+            MinedNonceCheck::Reject => {}
+            MinedNonceCheck::Quarantine => {
+                trace!(target: "txpool", ?hash, "quarantined replacement of mined transaction");
+                self.recently_mined.write().quarantine(transaction);
+                return Err(Ok(hash))
+            }
+        }
+        self.event_listener.write().discarded(&hash, peer);
+        Err(Err(mined_nonce_error(hash)))
+    }
+
     /// Performs account updates on the pool.
     ///
     /// This will either promote or discard transactions based on the new account state.
@@ -400,22 +600,25 @@ where
         let mut listener = self.event_listener.write();
 
         promoted.iter().for_each(|tx| listener.pending(tx.hash(), None));
-        discarded.iter().for_each(|tx| listener.discarded(tx.hash()));
+        discarded.iter().for_each(|tx| listener.discarded(tx.hash(), tx.peer));
 
         // This deletes outdated blob txs from the blob store, based on the account's nonce. This is
         // called during txpool maintenance when the pool drifted.
         self.delete_discarded_blobs(discarded.iter());
     }
 
-    /// Add a single validated transaction into the pool.
+    /// Prepares a single validated transaction for insertion into the pool.
+    ///
+    /// Returns the result of the transaction if it is not inserted, e.g. because it is invalid.
     ///
     /// Note: this is only used internally by [`Self::add_transactions()`], all new transaction(s)
     /// come in through that function, either as a batch or `std::iter::once`.
-    fn add_transaction(
+    fn prepare_transaction(
         &self,
         origin: TransactionOrigin,
+        peer: Option<PeerId>,
         tx: TransactionValidationOutcome<T::Transaction>,
-    ) -> PoolResult<TxHash> {
+    ) -> Result<PreparedTransaction<T::Transaction>, PoolResult<TxHash>> {
         match tx {
             TransactionValidationOutcome::Valid {
                 balance,
@@ -423,75 +626,234 @@ where
                 transaction,
                 propagate,
             } => {
-                let sender_id = self.get_sender_id(transaction.sender());
-                let transaction_id = TransactionId::new(sender_id, transaction.nonce());
-
-                // split the valid transaction and the blob sidecar if it has any
-                let (transaction, maybe_sidecar) = match transaction {
-                    ValidTransaction::Valid(tx) => (tx, None),
-                    ValidTransaction::ValidWithSidecar { transaction, sidecar } => {
-                        debug_assert!(
-                            transaction.is_eip4844(),
-                            "validator returned sidecar for non EIP-4844 transaction"
-                        );
-                        (transaction, Some(sidecar))
-                    }
-                };
+                let (transaction, maybe_sidecar) = split_sidecar(transaction);
 
-                let tx = ValidPoolTransaction {
-                    transaction,
-                    transaction_id,
-                    propagate,
-                    timestamp: Instant::now(),
-                    origin,
-                };
+                // reject or quarantine transactions that collide with a recently mined nonce
+                let transaction = self.check_recently_mined(transaction, peer)?;
 
-                let added = self.pool.write().add_transaction(tx, balance, state_nonce)?;
-                let hash = *added.hash();
+                let transaction = self.valid_pool_transaction(origin, peer, transaction, propagate);
+                Ok(PreparedTransaction { transaction, balance, state_nonce, maybe_sidecar })
+            }
+            TransactionValidationOutcome::Invalid(tx, err) => {
+                // the nonce of a fee-bumped replacement of a mined transaction is already too low
+                // for the current state, but it could be quarantined
+                let tx = if matches!(
+                    err,
+                    InvalidPoolTransactionError::Consensus(
+                        InvalidTransactionError::NonceNotConsistent
+                    )
+                ) {
+                    self.check_recently_mined(tx, peer)?
+                } else {
+                    tx
+                };
 
-                // transaction was successfully inserted into the pool
-                if let Some(sidecar) = maybe_sidecar {
-                    // notify blob sidecar listeners
-                    self.on_new_blob_sidecar(&hash, &sidecar);
-                    // store the sidecar in the blob store
-                    self.insert_blob(hash, sidecar);
+                let err = PoolError::new(*tx.hash(), err);
+                if err.is_bad_transaction() && !tx.is_eip4844() {
+                    // the transaction can never become valid, so it's not validated again
+                    let reason = err.kind.to_string().into();
+                    self.seen.write().on_rejected(err.hash, reason, Instant::now());
+                    if let (Some(persistent), Some(code)) =
+                        (self.persistent_rejections.get(), RejectionCode::from_error(&err))
+                    {
+                        persistent.insert(err.hash, code);
+                    }
+                } else if err.is_stale_transaction() && !tx.is_eip4844() {
+                    // the transaction can never become valid either, but it isn't bad
+                    let reason = err.kind.to_string().into();
+                    self.seen.write().on_rejected_stale(err.hash, reason, Instant::now());
                 }
 
-                if let Some(replaced) = added.replaced_blob_transaction() {
-                    // delete the replaced transaction from the blob store
-                    self.delete_blob(replaced);
-                }
+                self.event_listener.write().invalid(&err, peer);
+                Err(Err(err))
+            }
+            TransactionValidationOutcome::Error(tx_hash, err) => {
+                let mut listener = self.event_listener.write();
+                listener.discarded(&tx_hash, peer);
+                Err(Err(PoolError::other(tx_hash, err)))
+            }
+        }
+    }
 
-                // Notify about new pending transactions
-                if let Some(pending) = added.as_pending() {
-                    self.on_new_pending_transaction(pending);
+    /// Puts a transaction of an unwound block back into the pool.
+    ///
+    /// Note: this is only used internally by [`Self::resubmit()`].
+    fn resubmit_transaction(
+        &self,
+        resubmitted: ResubmittedTransaction<T::Transaction>,
+    ) -> PoolResult<TxHash> {
+        let ResubmittedTransaction { origin, timestamp, outcome } = resubmitted;
+        match outcome {
+            TransactionValidationOutcome::Valid {
+                balance,
+                state_nonce,
+                transaction,
+                propagate,
+            } => {
+                let (transaction, maybe_sidecar) = split_sidecar(transaction);
+                let hash = *transaction.hash();
+
+                // the nonce was used by another transaction of the new chain
+                if transaction.nonce() < state_nonce {
+                    trace!(target: "txpool", ?hash, "rejected resubmitted transaction with stale nonce");
+                    self.event_listener.write().discarded(&hash, None);
+                    return Err(mined_nonce_error(hash))
                 }
 
-                // Notify tx event listeners
-                self.notify_event_listeners(&added);
+                let mut tx = self.valid_pool_transaction(origin, None, transaction, propagate);
+                tx.timestamp = timestamp;
 
-                if let Some(discarded) = added.discarded_transactions() {
-                    self.delete_discarded_blobs(discarded.iter());
+                let added = self.pool.write().add_transaction(tx, balance, state_nonce);
+                match added {
+                    Ok(added) => Ok(self.on_added_transaction(added, maybe_sidecar)),
+                    // the listeners of the transaction in the pool keep receiving its events
+                    Err(err) if matches!(err.kind, PoolErrorKind::AlreadyImported) => Err(err),
+                    Err(err) => {
+                        trace!(target: "txpool", ?hash, %err, "dropped resubmitted transaction");
+                        self.event_listener.write().discarded(&hash, None);
+                        Err(err)
+                    }
                 }
+            }
+            TransactionValidationOutcome::Invalid(tx, err) => {
+                let err = PoolError::new(*tx.hash(), err);
+                self.event_listener.write().invalid(&err, None);
+                Err(err)
+            }
+            TransactionValidationOutcome::Error(tx_hash, err) => {
+                self.event_listener.write().discarded(&tx_hash, None);
+                Err(PoolError::other(tx_hash, err))
+            }
+        }
+    }
+
+    /// Notifies all listeners about a transaction that was inserted into the pool and updates the
+    /// blob store.
+    fn on_added_transaction(
+        &self,
+        added: AddedTransaction<T::Transaction>,
+        maybe_sidecar: Option<BlobTransactionSidecar>,
+    ) -> TxHash {
+        let hash = *added.hash();
+
+        // transaction was successfully inserted into the pool
+        if let Some(sidecar) = maybe_sidecar {
+            // notify blob sidecar listeners
+            self.on_new_blob_sidecar(&hash, &sidecar);
+            // store the sidecar in the blob store
+            self.insert_blob(hash, sidecar);
+        }
+
+        if let Some(replaced) = added.replaced_blob_transaction() {
+            // delete the replaced transaction from the blob store
+            self.delete_blob(replaced);
+        }
+
+        // Notify about new pending transactions
+        if let Some(pending) = added.as_pending() {
+            self.on_new_pending_transaction(pending);
+        }
+
+        // Notify tx event listeners
+        self.notify_event_listeners(&added);
+
+        if let Some(discarded) = added.discarded_transactions() {
+            self.delete_discarded_blobs(discarded.iter());
+        }
+
+        // Notify listeners for _all_ transactions
+        self.on_new_transaction(added.into_new_transaction_event());
 
-                // Notify listeners for _all_ transactions
-                self.on_new_transaction(added.into_new_transaction_event());
+        hash
+    }
 
-                Ok(hash)
+    /// Checks whether [`Self::add_transactions`] would accept the validated transaction, without
+    /// adding it to the pool.
+    ///
+    /// A replacement of a recently mined transaction that would be quarantined counts as
+    /// accepted.
+    pub(crate) fn would_accept(
+        &self,
+        origin: TransactionOrigin,
+        tx: TransactionValidationOutcome<T::Transaction>,
+    ) -> PoolResult<()> {
+        match tx {
+            TransactionValidationOutcome::Valid {
+                balance,
+                state_nonce,
+                transaction,
+                propagate,
+            } => {
+                let (transaction, _) = split_sidecar(transaction);
+                match self.recently_mined.read().check(&transaction, &self.config.price_bumps) {
+                    MinedNonceCheck::Unknown => {}
+                    MinedNonceCheck::Reject => return Err(mined_nonce_error(*transaction.hash())),
+                    MinedNonceCheck::Quarantine => return Ok(()),
+                }
+
+                // unknown senders don't get an id, the next one isn't used by any transaction
+                let identifiers = self.identifiers.read();
+                let sender_id = identifiers.sender_id_or_next(&transaction.sender());
+                let tx = ValidPoolTransaction {
+                    transaction_id: TransactionId::new(sender_id, transaction.nonce()),
+                    transaction,
+                    propagate,
+                    timestamp: Instant::now(),
+                    origin,
+                    peer: None,
+                };
+                self.pool.read().would_accept(tx, balance, state_nonce)
             }
             TransactionValidationOutcome::Invalid(tx, err) => {
-                let mut listener = self.event_listener.write();
-                listener.discarded(tx.hash());
+                // a fee-bumped replacement of a mined transaction would be quarantined
+                if matches!(
+                    err,
+                    InvalidPoolTransactionError::Consensus(
+                        InvalidTransactionError::NonceNotConsistent
+                    )
+                ) {
+                    let check = self.recently_mined.read().check(&tx, &self.config.price_bumps);
+                    if matches!(check, MinedNonceCheck::Quarantine) {
+                        return Ok(())
+                    }
+                }
                 Err(PoolError::new(*tx.hash(), err))
             }
             TransactionValidationOutcome::Error(tx_hash, err) => {
-                let mut listener = self.event_listener.write();
-                listener.discarded(&tx_hash);
                 Err(PoolError::other(tx_hash, err))
             }
         }
     }
 
+    /// Returns the minimum fees a replacement of the local transaction with the given hash must
+    /// pay to be accepted by the pool.
+    ///
+    /// Returns `None` if the transaction is not in the pool or was not submitted locally.
+    pub(crate) fn suggest_replacement(&self, tx_hash: &TxHash) -> Option<ReplacementSuggestion> {
+        let existing = self.get(tx_hash).filter(|tx| tx.origin.is_local())?;
+        Some(self.pool.read().suggest_replacement(&existing))
+    }
+
+    /// Wraps the validated transaction for insertion into the pool.
+    fn valid_pool_transaction(
+        &self,
+        origin: TransactionOrigin,
+        peer: Option<PeerId>,
+        transaction: T::Transaction,
+        propagate: bool,
+    ) -> ValidPoolTransaction<T::Transaction> {
+        let sender_id = self.get_sender_id(transaction.sender());
+        let transaction_id = TransactionId::new(sender_id, transaction.nonce());
+        ValidPoolTransaction {
+            transaction,
+            transaction_id,
+            propagate,
+            timestamp: Instant::now(),
+            origin,
+            peer,
+        }
+    }
+
     pub(crate) fn add_transaction_and_subscribe(
         &self,
         origin: TransactionOrigin,
@@ -501,20 +863,100 @@ where
             let mut listener = self.event_listener.write();
             listener.subscribe(tx.tx_hash())
         };
-        let mut results = self.add_transactions(origin, std::iter::once(tx));
-        results.pop().expect("result length is the same as the input")?;
+        self.add_transaction(origin, tx)?;
         Ok(listener)
     }
 
+    /// Adds a single transaction to the pool, see [`Self::add_transactions`].
+    pub(crate) fn add_transaction(
+        &self,
+        origin: TransactionOrigin,
+        tx: TransactionValidationOutcome<T::Transaction>,
+    ) -> PoolResult<TxHash> {
+        let mut results = self.add_transactions(origin, std::iter::once(tx));
+        results.pop().expect("result length is the same as the input")
+    }
+
     /// Adds all transactions in the iterator to the pool, returning a list of results.
     pub fn add_transactions(
         &self,
         origin: TransactionOrigin,
         transactions: impl IntoIterator<Item = TransactionValidationOutcome<T::Transaction>>,
     ) -> Vec<PoolResult<TxHash>> {
-        let mut added =
-            transactions.into_iter().map(|tx| self.add_transaction(origin, tx)).collect::<Vec<_>>();
+        self.add_transactions_from(origin, None, transactions)
+    }
+
+    /// Adds all transactions in the iterator to the pool, returning a list of results.
+    ///
+    /// If the transactions were received from a peer, the peer is recorded in the
+    /// [`ValidPoolTransaction`]s and included in the events of the transactions.
+    pub(crate) fn add_transactions_from(
+        &self,
+        origin: TransactionOrigin,
+        peer: Option<PeerId>,
+        transactions: impl IntoIterator<Item = TransactionValidationOutcome<T::Transaction>>,
+    ) -> Vec<PoolResult<TxHash>> {
+        let mut results = Vec::new();
+        let mut prepared = Vec::new();
+        for (idx, tx) in transactions.into_iter().enumerate() {
+            match self.prepare_transaction(origin, peer, tx) {
+                Ok(tx) => {
+                    prepared.push((idx, tx));
+                    results.push(None);
+                }
+                Err(res) => results.push(Some(res)),
+            }
+        }
+
+        // insert all transactions in a single critical section, listeners are notified once the
+        // lock is released
+        let inserted = {
+            let mut pool = self.pool.write();
+            prepared
+                .into_iter()
+                .map(|(idx, prepared)| {
+                    let PreparedTransaction { transaction, balance, state_nonce, maybe_sidecar } =
+                        prepared;
+                    let added = pool.add_transaction(transaction, balance, state_nonce);
+                    (idx, added.map(|added| (added, maybe_sidecar)))
+                })
+                .collect::<Vec<_>>()
+        };
+        for (idx, added) in inserted {
+            results[idx] = Some(
+                added.map(|(added, maybe_sidecar)| self.on_added_transaction(added, maybe_sidecar)),
+            );
+        }
 
+        let added = results
+            .into_iter()
+            .map(|res| res.expect("every transaction has a result"))
+            .collect::<Vec<_>>();
+        self.enforce_size_limits(added)
+    }
+
+    /// Puts the transactions of unwound blocks back into the pool, returning a list of results.
+    ///
+    /// Unlike [`Self::add_transactions`], the transactions keep the origin and the timestamp they
+    /// were first added to the pool with. They are classified against the state they were
+    /// validated against again, and listeners are notified like for new transactions.
+    ///
+    /// A transaction whose nonce is stale in the new state is rejected. A transaction whose
+    /// sender and nonce are already taken by a transaction in the pool, e.g. a replacement that
+    /// was quarantined while the transaction was mined, must outbid it by the configured price
+    /// bump, like any other replacement.
+    pub fn resubmit(
+        &self,
+        transactions: impl IntoIterator<Item = ResubmittedTransaction<T::Transaction>>,
+    ) -> Vec<PoolResult<TxHash>> {
+        let added =
+            transactions.into_iter().map(|tx| self.resubmit_transaction(tx)).collect::<Vec<_>>();
+        self.enforce_size_limits(added)
+    }
+
+    /// Enforces the pool size limits after transactions were added, and turns the results of added
+    /// transactions that were discarded right away into errors.
+    fn enforce_size_limits(&self, mut added: Vec<PoolResult<TxHash>>) -> Vec<PoolResult<TxHash>> {
         // If at least one transaction was added successfully, then we enforce the pool size limits.
         let discarded =
             if added.iter().any(Result::is_ok) { self.discard_worst() } else { Default::default() };
@@ -523,11 +965,6 @@ where
             return added
         }
 
-        {
-            let mut listener = self.event_listener.write();
-            discarded.iter().for_each(|tx| listener.discarded(tx));
-        }
-
         // It may happen that a newly added transaction is immediately discarded, so we need to
         // adjust the result here
         for res in &mut added {
@@ -621,7 +1058,7 @@ where
 
         mined.iter().for_each(|tx| listener.mined(tx, block_hash));
         promoted.iter().for_each(|tx| listener.pending(tx.hash(), None));
-        discarded.iter().for_each(|tx| listener.discarded(tx.hash()));
+        discarded.iter().for_each(|tx| listener.discarded(tx.hash(), tx.peer));
     }
 
     /// Fire events for the newly added transaction if there are any.
@@ -634,7 +1071,7 @@ where
 
                 listener.pending(transaction.hash(), replaced.clone());
                 promoted.iter().for_each(|tx| listener.pending(tx.hash(), None));
-                discarded.iter().for_each(|tx| listener.discarded(tx.hash()));
+                discarded.iter().for_each(|tx| listener.discarded(tx.hash(), tx.peer));
             }
             AddedTransaction::Parked { transaction, replaced, .. } => {
                 listener.queued(transaction.hash());
@@ -665,18 +1102,28 @@ where
         self.get_pool_data().pending_transactions()
     }
 
+    /// Returns all transactions from the pending sub-pool that are allowed to be propagated to
+    /// peers.
+    pub(crate) fn pending_transactions_to_propagate(
+        &self,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.get_pool_data().pending_transactions_to_propagate()
+    }
+
     /// Returns all transactions from parked pools
     pub(crate) fn queued_transactions(&self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
         self.get_pool_data().queued_transactions()
     }
 
     /// Returns all transactions in the pool
+    ///
+    /// Both sub-pools are read under the same lock, so a transaction is either pending or queued.
     pub(crate) fn all_transactions(&self) -> AllPoolTransactions<T::Transaction> {
-        let pool = self.get_pool_data();
-        AllPoolTransactions {
-            pending: pool.pending_transactions(),
-            queued: pool.queued_transactions(),
-        }
+        let (pending, queued) = {
+            let pool = self.get_pool_data();
+            (pool.pending_transactions(), pool.queued_transactions())
+        };
+        AllPoolTransactions::new(pending, queued)
     }
 
     /// Removes and returns all matching transactions from the pool.
@@ -691,12 +1138,12 @@ where
 
         let mut listener = self.event_listener.write();
 
-        removed.iter().for_each(|tx| listener.discarded(tx.hash()));
+        removed.iter().for_each(|tx| listener.discarded(tx.hash(), tx.peer));
 
         removed
     }
 
-    /// Removes and returns all transactions that are present in the pool.
+    /// Removes all transactions that are present in the pool, or were recently mined or rejected.
     pub(crate) fn retain_unknown<A>(&self, announcement: &mut A)
     where
         A: HandleMempoolData,
@@ -704,8 +1151,13 @@ where
         if announcement.is_empty() {
             return
         }
-        let pool = self.get_pool_data();
-        announcement.retain_by_hash(|tx| !pool.contains(tx))
+        let now = Instant::now();
+        let seen = self.seen.read();
+        announcement.retain_by_hash(|tx| {
+            !self.lookup.contains(tx) &&
+                !seen.get(tx, now).is_some_and(|seen| seen.is_known()) &&
+                self.persisted_rejection(tx).is_none()
+        })
     }
 
     /// Returns the transaction by hash.
@@ -713,7 +1165,7 @@ where
         &self,
         tx_hash: &TxHash,
     ) -> Option<Arc<ValidPoolTransaction<T::Transaction>>> {
-        self.get_pool_data().get(tx_hash)
+        self.lookup.get(tx_hash)
     }
 
     /// Returns all transactions of the address
@@ -725,6 +1177,40 @@ where
         self.get_pool_data().get_transactions_by_sender(sender_id)
     }
 
+    /// Returns all pending transactions of the address
+    pub(crate) fn pending_transactions_by_sender(
+        &self,
+        sender: Address,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let Some(sender_id) = self.identifiers.read().sender_id(&sender) else { return Vec::new() };
+        self.get_pool_data().get_pending_transactions_by_sender(sender_id)
+    }
+
+    /// Returns the nonce that follows the gapless sequence of transactions of the address that
+    /// starts at the on-chain nonce.
+    ///
+    /// Unlike [`Self::get_sender_id`], this doesn't assign an id to unknown addresses.
+    pub(crate) fn next_pending_nonce(&self, sender: Address, on_chain_nonce: u64) -> u64 {
+        let Some(sender_id) = self.identifiers.read().sender_id(&sender) else {
+            return on_chain_nonce
+        };
+        self.get_pool_data().next_pending_nonce(sender_id, on_chain_nonce)
+    }
+
+    /// Returns the next pending nonce and the number of pending transactions of the address.
+    ///
+    /// Unlike [`Self::get_sender_id`], this doesn't assign an id to unknown addresses.
+    pub(crate) fn pending_sender_info(
+        &self,
+        sender: Address,
+        on_chain_nonce: u64,
+    ) -> PendingSenderInfo {
+        let Some(sender_id) = self.identifiers.read().sender_id(&sender) else {
+            return PendingSenderInfo { next_nonce: on_chain_nonce, pending_transactions: 0 }
+        };
+        self.get_pool_data().pending_sender_info(sender_id, on_chain_nonce)
+    }
+
     /// Returns all transactions that where submitted with the given [`TransactionOrigin`]
     pub(crate) fn get_transactions_by_origin(
         &self,
@@ -733,7 +1219,7 @@ where
         self.get_pool_data().all().transactions_iter().filter(|tx| tx.origin == origin).collect()
     }
 
-    /// Returns all the transactions belonging to the hashes.
+    /// Returns all the transactions belonging to the hashes, in the order of the hashes.
     ///
     /// If no transaction exists, it is skipped.
     pub(crate) fn get_all(
@@ -743,7 +1229,33 @@ where
         if txs.is_empty() {
             return Vec::new()
         }
-        self.get_pool_data().get_all(txs).collect()
+        txs.iter().filter_map(|tx_hash| self.lookup.get(tx_hash)).collect()
+    }
+
+    /// Returns the transaction for each of the hashes, `None` if it is not in the pool.
+    ///
+    /// All transactions are looked up while holding the pool lock once, so the result is a
+    /// consistent snapshot of the pool.
+    pub(crate) fn get_all_ordered(
+        &self,
+        txs: Vec<TxHash>,
+    ) -> Vec<Option<Arc<ValidPoolTransaction<T::Transaction>>>> {
+        if txs.is_empty() {
+            return Vec::new()
+        }
+        let pool = self.get_pool_data();
+        txs.iter().map(|tx_hash| pool.get(tx_hash)).collect()
+    }
+
+    /// Returns for each of the hashes whether the transaction is in the pool.
+    ///
+    /// Like [`Self::get_all_ordered`], this holds the pool lock once for all hashes.
+    pub(crate) fn contains_all(&self, txs: Vec<TxHash>) -> Vec<bool> {
+        if txs.is_empty() {
+            return Vec::new()
+        }
+        let pool = self.get_pool_data();
+        txs.iter().map(|tx_hash| pool.contains(tx_hash)).collect()
     }
 
     /// Notify about propagated transactions.
@@ -773,11 +1285,16 @@ where
 
     /// Enforces the size limits of pool and returns the discarded transactions if violated.
     ///
-    /// If some of the transactions are blob transactions, they are also removed from the blob
-    /// store.
+    /// Listeners are notified about the discarded transactions. If some of the transactions are
+    /// blob transactions, they are also removed from the blob store.
     pub(crate) fn discard_worst(&self) -> HashSet<TxHash> {
         let discarded = self.pool.write().discard_worst();
 
+        {
+            let mut listener = self.event_listener.write();
+            discarded.iter().for_each(|tx| listener.discarded(tx.hash(), tx.peer));
+        }
+
         // delete any blobs associated with discarded blob transactions
         self.delete_discarded_blobs(discarded.iter());
 
@@ -785,6 +1302,24 @@ where
         discarded.into_iter().map(|tx| *tx.hash()).collect()
     }
 
+    /// Evicts the parked transactions that exceeded [`PoolConfig::max_queued_lifetime`] and returns
+    /// their hashes.
+    ///
+    /// Listeners are notified about the evicted transactions, and the blobs of evicted blob
+    /// transactions are removed from the blob store.
+    pub(crate) fn evict_expired(&self) -> Vec<TxHash> {
+        let evicted = self.pool.write().evict_expired(Instant::now());
+
+        {
+            let mut listener = self.event_listener.write();
+            evicted.iter().for_each(|tx| listener.discarded(tx.hash(), tx.peer));
+        }
+
+        self.delete_discarded_blobs(evicted.iter());
+
+        evicted.into_iter().map(|tx| *tx.hash()).collect()
+    }
+
     /// Inserts a blob transaction into the blob store
     fn insert_blob(&self, hash: TxHash, blob: BlobTransactionSidecar) {
         if let Err(err) = self.blob_store.insert(hash, blob) {
@@ -832,6 +1367,49 @@ where
     }
 }
 
+/// A transaction of an unwound block that is put back into the pool, see [`PoolInner::resubmit`].
+#[derive(Debug)]
+pub struct ResubmittedTransaction<T: PoolTransaction> {
+    /// The origin the transaction was first added to the pool with.
+    pub origin: TransactionOrigin,
+    /// When the transaction was first added to the pool.
+    pub timestamp: Instant,
+    /// The outcome of validating the transaction against the state after the unwind.
+    pub outcome: TransactionValidationOutcome<T>,
+}
+
+/// A validated transaction that's ready to be inserted into the pool.
+struct PreparedTransaction<T: PoolTransaction> {
+    transaction: ValidPoolTransaction<T>,
+    balance: U256,
+    state_nonce: u64,
+    maybe_sidecar: Option<BlobTransactionSidecar>,
+}
+
+/// Splits the valid transaction and the blob sidecar if it has any.
+fn split_sidecar<T: PoolTransaction>(
+    transaction: ValidTransaction<T>,
+) -> (T, Option<BlobTransactionSidecar>) {
+    match transaction {
+        ValidTransaction::Valid(tx) => (tx, None),
+        ValidTransaction::ValidWithSidecar { transaction, sidecar } => {
+            debug_assert!(
+                transaction.is_eip4844(),
+                "validator returned sidecar for non EIP-4844 transaction"
+            );
+            (transaction, Some(sidecar))
+        }
+    }
+}
+
+/// Returns the error of a transaction rejected because its nonce was mined recently.
+fn mined_nonce_error(hash: TxHash) -> PoolError {
+    PoolError::new(
+        hash,
+        InvalidPoolTransactionError::Consensus(InvalidTransactionError::NonceNotConsistent),
+    )
+}
+
 impl<V, T: TransactionOrdering, S> fmt::Debug for PoolInner<V, T, S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("PoolInner").field("config", &self.config).finish_non_exhaustive()
@@ -1002,6 +1580,24 @@ where
     }
 }
 
+/// The minimum fees of a replacement for a local transaction, see
+/// [`Pool::suggest_replacement`](crate::Pool::suggest_replacement).
+///
+/// The fees satisfy the configured price bump of the replaced transaction, and the max fee also
+/// covers the base fee after a few blocks of maximum base fee increase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplacementSuggestion {
+    /// Hash of the transaction to replace.
+    pub hash: TxHash,
+    /// Minimum max fee per gas of the replacement.
+    pub max_fee_per_gas: u128,
+    /// Minimum max priority fee per gas of the replacement, if the replaced transaction has one.
+    pub max_priority_fee_per_gas: Option<u128>,
+    /// Minimum max fee per blob gas of the replacement, if the replaced transaction is a blob
+    /// transaction.
+    pub max_fee_per_blob_gas: Option<u128>,
+}
+
 /// Represents a transaction that was added into the pool and its state
 #[derive(Debug, Clone)]
 pub enum AddedTransaction<T: PoolTransaction> {
@@ -1131,14 +1727,29 @@ impl<T: PoolTransaction> OnNewCanonicalStateOutcome<T> {
 
 #[cfg(test)]
 mod tests {
+    use super::ResubmittedTransaction;
     use crate::{
         blobstore::{BlobStore, InMemoryBlobStore},
+        error::{InvalidPoolTransactionError, PoolErrorKind},
+        pool::{state::SubPool, FullTransactionEvent, TransactionEvent},
         test_utils::{MockTransaction, TestPoolBuilder},
+        traits::{GetPooledTransactionLimit, TransactionListenerKind},
         validate::ValidTransaction,
-        BlockInfo, PoolConfig, SubPoolLimit, TransactionOrigin, TransactionValidationOutcome, U256,
+        BlockInfo, PoolConfig, ReplacementSuggestion, SubPoolLimit, TransactionOrigin,
+        TransactionValidationOutcome, U256,
+    };
+    use assert_matches::assert_matches;
+    use reth_primitives::{kzg::Blob, transaction::generate_blob_sidecar, InvalidTransactionError};
+    use std::{
+        fs,
+        path::PathBuf,
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::{Duration, Instant},
     };
-    use reth_primitives::{kzg::Blob, transaction::generate_blob_sidecar};
-    use std::{fs, path::PathBuf};
 
     #[test]
     fn test_discard_blobs_on_blob_tx_eviction() {
@@ -1219,4 +1830,740 @@ mod tests {
         // Assert that the pool's blob store matches the expected blob store.
         assert_eq!(*test_pool.blob_store(), blob_store);
     }
+
+    #[test]
+    fn concurrent_lookups_never_observe_evicted_transactions() {
+        const TXS: usize = 2_000;
+        const WINDOW: usize = 64;
+        const READERS: usize = 4;
+
+        let pool = Arc::clone(&TestPoolBuilder::default().pool);
+        let txs = (0..TXS).map(|_| MockTransaction::eip1559()).collect::<Vec<_>>();
+        let hashes = Arc::new(txs.iter().map(|tx| tx.get_hash()).collect::<Vec<_>>());
+
+        // number of transactions that were inserted, all of them must be visible until evicted
+        let inserted = Arc::new(AtomicUsize::new(0));
+        // set before the eviction of a transaction starts
+        let evicting = Arc::new(AtomicUsize::new(0));
+        // set after a transaction was evicted, it must never be visible afterwards
+        let evicted = Arc::new(AtomicUsize::new(0));
+        let done = Arc::new(AtomicBool::new(false));
+
+        let readers = (0..READERS)
+            .map(|_| {
+                let (pool, hashes) = (Arc::clone(&pool), Arc::clone(&hashes));
+                let (inserted, evicting, evicted, done) =
+                    (inserted.clone(), evicting.clone(), evicted.clone(), done.clone());
+                thread::spawn(move || {
+                    while !done.load(Ordering::SeqCst) {
+                        let inserted = inserted.load(Ordering::SeqCst);
+                        for (idx, hash) in hashes[..inserted].iter().enumerate().rev() {
+                            let evicted = evicted.load(Ordering::SeqCst);
+                            let found = pool.get(hash);
+                            let evicting = evicting.load(Ordering::SeqCst);
+
+                            if idx < evicted {
+                                assert!(found.is_none(), "evicted transaction {idx} is visible");
+                            } else if idx >= evicting {
+                                let found = found.expect("inserted transaction is not visible");
+                                assert_eq!(found.hash(), hash);
+                            }
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for (idx, tx) in txs.into_iter().enumerate() {
+            pool.add_transaction(
+                TransactionOrigin::External,
+                TransactionValidationOutcome::Valid {
+                    balance: U256::MAX,
+                    state_nonce: 0,
+                    transaction: ValidTransaction::Valid(tx),
+                    propagate: true,
+                },
+            )
+            .unwrap();
+            inserted.store(idx + 1, Ordering::SeqCst);
+
+            if idx >= WINDOW {
+                let evict = idx - WINDOW;
+                evicting.store(evict + 1, Ordering::SeqCst);
+                assert_eq!(pool.remove_transactions(vec![hashes[evict]]).len(), 1);
+                evicted.store(evict + 1, Ordering::SeqCst);
+            }
+        }
+        done.store(true, Ordering::SeqCst);
+
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(pool.get_all(hashes.to_vec()).len(), WINDOW);
+        pool.get_pool_data().assert_invariants();
+    }
+
+    fn valid(tx: MockTransaction) -> TransactionValidationOutcome<MockTransaction> {
+        TransactionValidationOutcome::Valid {
+            balance: U256::MAX,
+            state_nonce: 0,
+            transaction: ValidTransaction::Valid(tx),
+            propagate: true,
+        }
+    }
+
+    #[test]
+    fn get_all_ordered_preserves_request_order() {
+        let pool = Arc::clone(&TestPoolBuilder::default().pool);
+        let known = (0..2).map(|_| MockTransaction::eip1559()).collect::<Vec<_>>();
+        let unknown = (0..2).map(|_| MockTransaction::eip1559().get_hash()).collect::<Vec<_>>();
+        for tx in &known {
+            pool.add_transaction(TransactionOrigin::External, valid(tx.clone())).unwrap();
+        }
+
+        let hashes = vec![unknown[0], known[1].get_hash(), unknown[1], known[0].get_hash()];
+        let found = pool
+            .get_all_ordered(hashes.clone())
+            .into_iter()
+            .map(|tx| tx.map(|tx| *tx.hash()))
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec![None, Some(hashes[1]), None, Some(hashes[3])]);
+        assert_eq!(pool.contains_all(hashes.clone()), vec![false, true, false, true]);
+        assert_eq!(
+            pool.get_all(hashes).iter().map(|tx| *tx.hash()).collect::<Vec<_>>(),
+            vec![known[1].get_hash(), known[0].get_hash()]
+        );
+        assert!(pool.get_all_ordered(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn pooled_transaction_elements_respect_size_limit() {
+        let pool = Arc::clone(&TestPoolBuilder::default().pool);
+        let known = (0..4).map(|_| MockTransaction::eip1559().with_size(100)).collect::<Vec<_>>();
+        for tx in &known {
+            pool.add_transaction(TransactionOrigin::External, valid(tx.clone())).unwrap();
+        }
+
+        // unknown hashes are skipped, the rest is returned in request order
+        let unknown = MockTransaction::eip1559().get_hash();
+        let hashes = vec![
+            unknown,
+            known[2].get_hash(),
+            known[0].get_hash(),
+            unknown,
+            known[3].get_hash(),
+            known[1].get_hash(),
+        ];
+        let elements = |limit| {
+            pool.get_pooled_transaction_elements(hashes.clone(), limit)
+                .iter()
+                .map(|element| *element.hash())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            elements(GetPooledTransactionLimit::None),
+            vec![hashes[1], hashes[2], hashes[4], hashes[5]]
+        );
+        // the third transaction would exceed the limit
+        assert_eq!(
+            elements(GetPooledTransactionLimit::ResponseSizeSoftLimit(250)),
+            vec![hashes[1], hashes[2]]
+        );
+        assert_eq!(
+            elements(GetPooledTransactionLimit::ResponseSizeSoftLimit(300)),
+            vec![hashes[1], hashes[2], hashes[4]]
+        );
+        // a transaction larger than the limit is still served on its own
+        assert_eq!(elements(GetPooledTransactionLimit::ResponseSizeSoftLimit(50)), vec![hashes[1]]);
+    }
+
+    #[test]
+    fn all_transactions_grouped_by_sender() {
+        let pool = Arc::clone(&TestPoolBuilder::default().pool);
+        let sender = MockTransaction::eip1559();
+        let other = MockTransaction::eip1559();
+
+        // nonce 3 has a gap, inserting nonce 0 after 1 promotes both
+        let txs = [sender.clone().with_nonce(1), sender.skip(2), sender.clone(), other.clone()];
+        for tx in &txs {
+            pool.add_transaction(TransactionOrigin::External, valid(tx.clone())).unwrap();
+        }
+
+        let all = pool.all_transactions();
+        let nonces = |txs: &Vec<Arc<ValidPoolTransaction<MockTransaction>>>| {
+            txs.iter().map(|tx| tx.nonce()).collect::<Vec<_>>()
+        };
+        assert_eq!(all.pending.len(), 2);
+        assert_eq!(nonces(&all.pending[&sender.sender()]), vec![0, 1]);
+        assert_eq!(nonces(&all.pending[&other.sender()]), vec![0]);
+        assert_eq!(all.queued.len(), 1);
+        assert_eq!(nonces(&all.queued[&sender.sender()]), vec![3]);
+        assert_eq!((all.pending_len(), all.queued_len()), (3, 1));
+
+        assert_eq!(pool.pooled_transactions_hashes(None).len(), 4);
+        assert_eq!(pool.pooled_transactions_hashes(Some(2)).len(), 2);
+    }
+
+    #[test]
+    fn get_all_ordered_never_observes_partial_eviction() {
+        const PAIRS: usize = 500;
+        const READERS: usize = 4;
+
+        let pool = Arc::clone(&TestPoolBuilder::default().pool);
+        let pairs = (0..PAIRS)
+            .map(|_| (MockTransaction::eip1559(), MockTransaction::eip1559()))
+            .collect::<Vec<_>>();
+        let hashes = Arc::new(
+            pairs.iter().map(|(a, b)| vec![a.get_hash(), b.get_hash()]).collect::<Vec<_>>(),
+        );
+
+        // index of the pair that is currently inserted and evicted
+        let current = Arc::new(AtomicUsize::new(0));
+        let done = Arc::new(AtomicBool::new(false));
+
+        let readers = (0..READERS)
+            .map(|_| {
+                let (pool, hashes) = (Arc::clone(&pool), Arc::clone(&hashes));
+                let (current, done) = (current.clone(), done.clone());
+                thread::spawn(move || {
+                    while !done.load(Ordering::SeqCst) {
+                        let pair = &hashes[current.load(Ordering::SeqCst)];
+
+                        // the first transaction is inserted first and both are evicted at once, so
+                        // the second one is never visible without the first one
+                        let found = pool.get_all_ordered(pair.clone());
+                        assert_eq!(found.len(), 2);
+                        assert!(found[0].is_some() || found[1].is_none(), "torn eviction");
+
+                        let contained = pool.contains_all(pair.clone());
+                        assert!(contained[0] || !contained[1], "torn eviction");
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for (idx, (a, b)) in pairs.into_iter().enumerate() {
+            current.store(idx, Ordering::SeqCst);
+            pool.add_transaction(TransactionOrigin::External, valid(a)).unwrap();
+            pool.add_transaction(TransactionOrigin::External, valid(b)).unwrap();
+            assert_eq!(pool.remove_transactions(hashes[idx].clone()).len(), 2);
+        }
+        done.store(true, Ordering::SeqCst);
+
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert!(pool.contains_all(hashes.concat()).iter().all(|contained| !contained));
+        pool.get_pool_data().assert_invariants();
+    }
+
+    #[test]
+    fn next_pending_nonce_is_consistent_with_insertions() {
+        const TXS: usize = 500;
+        const READERS: usize = 4;
+
+        let config = PoolConfig { max_account_slots: TXS, ..Default::default() };
+        let pool = Arc::clone(&TestPoolBuilder::default().with_config(config).pool);
+        let tx = MockTransaction::eip1559();
+        let sender = tx.get_sender();
+
+        // unknown senders don't get an id
+        assert_eq!(pool.next_pending_nonce(sender, 7), 7);
+        assert!(pool.pending_transactions_by_sender(sender).is_empty());
+        assert!(pool.identifiers.read().sender_id(&sender).is_none());
+
+        // number of transactions that were inserted
+        let inserted = Arc::new(AtomicUsize::new(0));
+        let done = Arc::new(AtomicBool::new(false));
+
+        let readers = (0..READERS)
+            .map(|_| {
+                let (pool, inserted, done) = (Arc::clone(&pool), inserted.clone(), done.clone());
+                thread::spawn(move || {
+                    let mut last = 0;
+                    while !done.load(Ordering::SeqCst) {
+                        let before = inserted.load(Ordering::SeqCst) as u64;
+                        let next_nonce = pool.next_pending_nonce(sender, 0);
+                        let after = inserted.load(Ordering::SeqCst) as u64;
+
+                        assert!(next_nonce >= before && next_nonce <= after + 1);
+                        assert!(next_nonce >= last, "next nonce went back to {next_nonce}");
+                        last = next_nonce;
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut next = tx;
+        for idx in 0..TXS {
+            pool.add_transaction(TransactionOrigin::External, valid(next.clone())).unwrap();
+            inserted.store(idx + 1, Ordering::SeqCst);
+            next = next.next();
+        }
+        done.store(true, Ordering::SeqCst);
+
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(pool.next_pending_nonce(sender, 0), TXS as u64);
+        assert_eq!(pool.pending_transactions_by_sender(sender).len(), TXS);
+    }
+
+    #[test]
+    fn suggested_replacement_is_accepted() {
+        let pool = &TestPoolBuilder::default().pool;
+        pool.set_block_info(BlockInfo { pending_basefee: 100, ..Default::default() });
+
+        let tx = MockTransaction::eip1559().with_max_fee(200).with_priority_fee(10);
+        let hash = tx.get_hash();
+        pool.add_transaction(TransactionOrigin::Local, valid(tx.clone())).unwrap();
+
+        // the default price bump of 10% applies, the projected base fee of 141 is lower
+        let suggestion = pool.suggest_replacement(&hash).unwrap();
+        assert_eq!(
+            suggestion,
+            ReplacementSuggestion {
+                hash,
+                max_fee_per_gas: 221,
+                max_priority_fee_per_gas: Some(12),
+                max_fee_per_blob_gas: None,
+            }
+        );
+
+        // 1000 -> 1125 -> 1265 -> 1423 after three full blocks, plus the priority fee
+        pool.set_block_info(BlockInfo { pending_basefee: 1000, ..Default::default() });
+        let suggestion = pool.suggest_replacement(&hash).unwrap();
+        assert_eq!(suggestion.max_fee_per_gas, 1435);
+
+        // any lower fee is underpriced
+        let underpriced = tx.clone().rng_hash().with_max_fee(1435).with_priority_fee(11);
+        assert!(pool.would_accept(TransactionOrigin::Local, valid(underpriced)).is_err());
+
+        let replacement = tx
+            .rng_hash()
+            .with_max_fee(suggestion.max_fee_per_gas)
+            .with_priority_fee(suggestion.max_priority_fee_per_gas.unwrap());
+        let replacement_hash = replacement.get_hash();
+        assert!(pool.would_accept(TransactionOrigin::Local, valid(replacement.clone())).is_ok());
+        pool.add_transaction(TransactionOrigin::Local, valid(replacement)).unwrap();
+        assert!(pool.get(&hash).is_none());
+        assert!(pool.get(&replacement_hash).is_some());
+
+        // only local transactions get suggestions
+        let external = MockTransaction::eip1559();
+        let external_hash = external.get_hash();
+        pool.add_transaction(TransactionOrigin::External, valid(external)).unwrap();
+        assert!(pool.suggest_replacement(&external_hash).is_none());
+    }
+
+    #[test]
+    fn would_accept_rejects_exactly_when_insertion_does() {
+        let pool = &TestPoolBuilder::default().pool;
+
+        let tx = MockTransaction::eip1559().with_max_fee(200).with_priority_fee(10);
+        let candidates = [
+            (tx.clone(), true),
+            // already imported
+            (tx.clone(), false),
+            // underpriced replacement
+            (tx.clone().rng_hash(), false),
+            (tx.clone().rng_hash().with_max_fee(1000).with_priority_fee(100), true),
+            // fee cap below the minimum protocol base fee
+            (tx.next().with_max_fee(1).with_priority_fee(1), false),
+            // exceeds the block gas limit
+            (tx.next().with_gas_limit(u64::MAX), false),
+            // conflicting transaction type of the sender
+            (MockTransaction::eip4844().with_sender(tx.get_sender()), false),
+            (tx.next(), true),
+        ];
+
+        for (candidate, accepted) in candidates {
+            let would_accept = pool
+                .would_accept(TransactionOrigin::External, valid(candidate.clone()))
+                .map_err(|err| err.to_string());
+            let added = pool
+                .add_transaction(TransactionOrigin::External, valid(candidate))
+                .map(drop)
+                .map_err(|err| err.to_string());
+            assert_eq!(would_accept, added);
+            assert_eq!(added.is_ok(), accepted, "{added:?}");
+        }
+
+        // checking a transaction of an unknown sender doesn't assign it an id
+        let unknown = MockTransaction::eip1559();
+        assert!(pool.would_accept(TransactionOrigin::External, valid(unknown.clone())).is_ok());
+        assert!(pool.identifiers.read().sender_id(&unknown.get_sender()).is_none());
+    }
+
+    fn resubmitted(
+        tx: MockTransaction,
+        state_nonce: u64,
+        origin: TransactionOrigin,
+        timestamp: Instant,
+    ) -> ResubmittedTransaction<MockTransaction> {
+        ResubmittedTransaction {
+            origin,
+            timestamp,
+            outcome: TransactionValidationOutcome::Valid {
+                balance: U256::MAX,
+                state_nonce,
+                transaction: ValidTransaction::Valid(tx),
+                propagate: true,
+            },
+        }
+    }
+
+    #[test]
+    fn resubmit_keeps_origin_and_timestamp() {
+        let pool = &TestPoolBuilder::default().pool;
+        let mut events = pool.add_all_transactions_event_listener();
+
+        let timestamp = Instant::now();
+        thread::sleep(Duration::from_millis(1));
+
+        let tx = MockTransaction::eip1559();
+        let gapped = tx.next().next();
+        let results = pool.resubmit([
+            resubmitted(tx.clone(), 0, TransactionOrigin::Local, timestamp),
+            resubmitted(gapped.clone(), 0, TransactionOrigin::External, timestamp),
+        ]);
+        assert_eq!(
+            results.into_iter().collect::<Result<Vec<_>, _>>().unwrap(),
+            vec![tx.get_hash(), gapped.get_hash()]
+        );
+
+        let pool_data = pool.get_pool_data();
+        let added = pool.get(&tx.get_hash()).unwrap();
+        assert_eq!(added.origin, TransactionOrigin::Local);
+        assert_eq!(added.timestamp, timestamp);
+        assert!(pool_data.subpool_contains(SubPool::Pending, added.id()));
+
+        // the nonce gap of the new state parks the transaction
+        let added = pool.get(&gapped.get_hash()).unwrap();
+        assert_eq!(added.origin, TransactionOrigin::External);
+        assert_eq!(added.timestamp, timestamp);
+        assert!(pool_data.subpool_contains(SubPool::Queued, added.id()));
+
+        assert_matches!(
+            events.events.try_recv(),
+            Ok(FullTransactionEvent::Pending(hash)) if hash == tx.get_hash()
+        );
+        assert_matches!(
+            events.events.try_recv(),
+            Ok(FullTransactionEvent::Queued(hash)) if hash == gapped.get_hash()
+        );
+    }
+
+    #[test]
+    fn resubmit_rejects_stale_nonce() {
+        let pool = &TestPoolBuilder::default().pool;
+        let mut events = pool.add_all_transactions_event_listener();
+
+        // another transaction of the sender with the same nonce was mined in the new chain
+        let tx = MockTransaction::eip1559();
+        let err = pool
+            .resubmit([resubmitted(tx.clone(), 1, TransactionOrigin::Local, Instant::now())])
+            .pop()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.hash, tx.get_hash());
+        assert_matches!(
+            err.kind,
+            PoolErrorKind::InvalidTransaction(InvalidPoolTransactionError::Consensus(
+                InvalidTransactionError::NonceNotConsistent
+            ))
+        );
+        assert!(pool.is_empty());
+
+        assert_matches!(
+            events.events.try_recv(),
+            Ok(FullTransactionEvent::Discarded { tx_hash, .. }) if tx_hash == tx.get_hash()
+        );
+    }
+
+    #[test]
+    fn resubmit_applies_replacement_rule() {
+        let pool = &TestPoolBuilder::default().pool;
+
+        let tx = MockTransaction::eip1559().with_max_fee(200).with_priority_fee(10);
+        let replacement = tx.clone().rng_hash().with_max_fee(1000).with_priority_fee(100);
+        pool.add_transaction(TransactionOrigin::External, valid(replacement.clone())).unwrap();
+        let mut events = pool.add_all_transactions_event_listener();
+
+        // the transaction in the pool is already imported
+        let err = pool
+            .resubmit([resubmitted(
+                replacement.clone(),
+                0,
+                TransactionOrigin::Local,
+                Instant::now(),
+            )])
+            .pop()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.hash, replacement.get_hash());
+        assert_matches!(err.kind, PoolErrorKind::AlreadyImported);
+        assert!(events.events.try_recv().is_err());
+
+        // a resubmitted transaction that doesn't outbid the replacement is dropped
+        let err = pool
+            .resubmit([resubmitted(tx.clone(), 0, TransactionOrigin::Local, Instant::now())])
+            .pop()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.hash, tx.get_hash());
+        assert_matches!(err.kind, PoolErrorKind::ReplacementUnderpriced);
+        assert_matches!(
+            events.events.try_recv(),
+            Ok(FullTransactionEvent::Discarded { tx_hash, .. }) if tx_hash == tx.get_hash()
+        );
+
+        // a resubmitted transaction that outbids the replacement replaces it
+        let outbidding = tx.clone().rng_hash().with_max_fee(5000).with_priority_fee(500);
+        let hash = pool
+            .resubmit([resubmitted(
+                outbidding.clone(),
+                0,
+                TransactionOrigin::Local,
+                Instant::now(),
+            )])
+            .pop()
+            .unwrap()
+            .unwrap();
+        assert_eq!(hash, outbidding.get_hash());
+        assert_matches!(
+            events.events.try_recv(),
+            Ok(FullTransactionEvent::Pending(hash)) if hash == outbidding.get_hash()
+        );
+        assert_matches!(
+            events.events.try_recv(),
+            Ok(FullTransactionEvent::Replaced { transaction, replaced_by })
+                if *transaction.hash() == replacement.get_hash() &&
+                    replaced_by == outbidding.get_hash()
+        );
+
+        assert_eq!(pool.len(), 1);
+        assert!(pool.get(&outbidding.get_hash()).is_some());
+        assert_eq!(pool.get(&outbidding.get_hash()).unwrap().origin, TransactionOrigin::Local);
+    }
+
+    #[test]
+    fn invalid_and_discarded_transactions_notify_listeners() {
+        let pending_limit = SubPoolLimit::new(1, usize::MAX);
+        let pool = &TestPoolBuilder::default()
+            .with_config(PoolConfig { pending_limit, ..Default::default() })
+            .pool;
+        let mut events = pool.add_all_transactions_event_listener();
+
+        // the transaction is rejected by the validator and never enters the pool
+        let invalid = MockTransaction::eip1559();
+        let mut invalid_events = pool.event_listener.write().subscribe(invalid.get_hash());
+        let err = pool
+            .add_transactions(
+                TransactionOrigin::External,
+                [TransactionValidationOutcome::Invalid(
+                    invalid.clone(),
+                    InvalidPoolTransactionError::IntrinsicGasTooLow,
+                )],
+            )
+            .pop()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.hash, invalid.get_hash());
+        let expected_reason = err.kind.to_string();
+        assert_matches!(
+            events.events.try_recv(),
+            Ok(FullTransactionEvent::Invalid { tx_hash, reason, peer: None })
+                if tx_hash == invalid.get_hash() && *reason == *expected_reason
+        );
+        assert_matches!(
+            invalid_events.events.try_recv(),
+            Ok(TransactionEvent::Invalid(reason)) if *reason == *expected_reason
+        );
+        assert!(pool.is_empty());
+
+        // the second transaction exceeds the pending limit, so the cheaper one is evicted
+        let cheap = MockTransaction::eip1559();
+        let expensive = MockTransaction::eip1559().inc_price_by(100);
+        let results = pool.add_transactions(
+            TransactionOrigin::External,
+            [valid(cheap.clone()), valid(expensive.clone())],
+        );
+        assert_matches!(
+            &results[0],
+            Err(err) if matches!(err.kind, PoolErrorKind::DiscardedOnInsert)
+        );
+        assert_matches!(&results[1], Ok(hash) if *hash == expensive.get_hash());
+
+        assert_matches!(
+            events.events.try_recv(),
+            Ok(FullTransactionEvent::Pending(hash)) if hash == cheap.get_hash()
+        );
+        assert_matches!(
+            events.events.try_recv(),
+            Ok(FullTransactionEvent::Pending(hash)) if hash == expensive.get_hash()
+        );
+        assert_matches!(
+            events.events.try_recv(),
+            Ok(FullTransactionEvent::Discarded { tx_hash, peer: None })
+                if tx_hash == cheap.get_hash()
+        );
+        assert!(events.events.try_recv().is_err());
+    }
+
+    #[test]
+    fn evicts_expired_parked_transactions() {
+        let max_queued_lifetime = Duration::from_millis(10);
+        let pool = &TestPoolBuilder::default()
+            .with_config(PoolConfig { max_queued_lifetime, ..Default::default() })
+            .pool;
+
+        let pending = MockTransaction::eip1559();
+        let gapped = pending.next().next();
+        let gapped_descendant = gapped.next();
+        let local_gapped = MockTransaction::eip1559().next().next();
+        let results = pool.add_transactions(
+            TransactionOrigin::External,
+            [valid(pending.clone()), valid(gapped.clone()), valid(gapped_descendant.clone())],
+        );
+        assert!(results.iter().all(Result::is_ok));
+        pool.add_transaction(TransactionOrigin::Local, valid(local_gapped.clone())).unwrap();
+        assert_eq!(pool.get_pool_data().queued().len(), 3);
+        let mut events = pool.add_all_transactions_event_listener();
+
+        // nothing expired yet
+        assert!(pool.evict_expired().is_empty());
+
+        thread::sleep(max_queued_lifetime);
+
+        // the descendant is evicted before its ancestor
+        let evicted = pool.evict_expired();
+        assert_eq!(evicted, vec![gapped_descendant.get_hash(), gapped.get_hash()]);
+        for hash in evicted {
+            assert_matches!(
+                events.events.try_recv(),
+                Ok(FullTransactionEvent::Discarded { tx_hash, peer: None }) if tx_hash == hash
+            );
+        }
+        assert!(events.events.try_recv().is_err());
+
+        // pending and local transactions are never evicted by age
+        assert_eq!(pool.len(), 2);
+        assert!(pool.get(&pending.get_hash()).is_some());
+        assert!(pool.get(&local_gapped.get_hash()).is_some());
+        assert!(pool.evict_expired().is_empty());
+    }
+
+    #[test]
+    fn peer_transactions_are_attributed() {
+        let pending_limit = SubPoolLimit::new(1, usize::MAX);
+        let pool = &TestPoolBuilder::default()
+            .with_config(PoolConfig { pending_limit, ..Default::default() })
+            .pool;
+        let mut events = pool.add_all_transactions_event_listener();
+        let peer_id = PeerId::random();
+
+        let invalid = MockTransaction::eip1559();
+        let mut invalid_events = pool.event_listener.write().subscribe(invalid.get_hash());
+        let err = pool
+            .add_transactions_from(
+                TransactionOrigin::External,
+                Some(peer_id),
+                [TransactionValidationOutcome::Invalid(
+                    invalid.clone(),
+                    InvalidPoolTransactionError::IntrinsicGasTooLow,
+                )],
+            )
+            .pop()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.hash, invalid.get_hash());
+        assert_matches!(
+            events.events.try_recv(),
+            Ok(FullTransactionEvent::Invalid { tx_hash, peer: Some(peer), .. })
+                if tx_hash == invalid.get_hash() && peer == peer_id
+        );
+        // the events of a single transaction don't carry the peer
+        assert_matches!(invalid_events.events.try_recv(), Ok(TransactionEvent::Invalid(_)));
+
+        // the transaction of the peer is evicted in favor of the local one
+        let cheap = MockTransaction::eip1559();
+        let expensive = MockTransaction::eip1559().inc_price_by(100);
+        pool.add_transactions_from(
+            TransactionOrigin::External,
+            Some(peer_id),
+            [valid(cheap.clone())],
+        )
+        .pop()
+        .unwrap()
+        .unwrap();
+        assert_eq!(pool.get(&cheap.get_hash()).unwrap().peer, Some(peer_id));
+        pool.add_transactions(TransactionOrigin::Local, [valid(expensive.clone())])
+            .pop()
+            .unwrap()
+            .unwrap();
+        assert_eq!(pool.get(&expensive.get_hash()).unwrap().peer, None);
+
+        assert_matches!(
+            events.events.try_recv(),
+            Ok(FullTransactionEvent::Pending(hash)) if hash == cheap.get_hash()
+        );
+        assert_matches!(
+            events.events.try_recv(),
+            Ok(FullTransactionEvent::Pending(hash)) if hash == expensive.get_hash()
+        );
+        assert_matches!(
+            events.events.try_recv(),
+            Ok(FullTransactionEvent::Discarded { tx_hash, peer: Some(peer) })
+                if tx_hash == cheap.get_hash() && peer == peer_id
+        );
+        assert!(events.events.try_recv().is_err());
+    }
+
+    #[test]
+    fn private_transactions_are_not_propagated() {
+        let pool = Arc::clone(&TestPoolBuilder::default().pool);
+        let mut all = pool.add_new_transaction_listener(TransactionListenerKind::All);
+        let mut propagate_only =
+            pool.add_new_transaction_listener(TransactionListenerKind::PropagateOnly);
+
+        let private = MockTransaction::eip1559();
+        let local = MockTransaction::eip1559();
+        pool.add_transaction(
+            TransactionOrigin::Private,
+            TransactionValidationOutcome::Valid {
+                balance: U256::MAX,
+                state_nonce: 0,
+                transaction: ValidTransaction::Valid(private.clone()),
+                propagate: false,
+            },
+        )
+        .unwrap();
+        pool.add_transaction(TransactionOrigin::Local, valid(local.clone())).unwrap();
+        assert_eq!(pool.pending_transactions().len(), 2);
+
+        let to_propagate = pool.pending_transactions_to_propagate();
+        assert_eq!(to_propagate.len(), 1);
+        assert_eq!(*to_propagate[0].hash(), local.get_hash());
+
+        // private transactions are never served to peers
+        let elements = pool.get_pooled_transaction_elements(
+            vec![private.get_hash(), local.get_hash()],
+            GetPooledTransactionLimit::None,
+        );
+        assert_eq!(elements.len(), 1);
+        assert_eq!(*elements[0].hash(), local.get_hash());
+
+        let event = all.try_recv().unwrap();
+        assert_eq!(event.origin(), TransactionOrigin::Private);
+        assert!(!event.is_propagate_allowed());
+        let event = all.try_recv().unwrap();
+        assert_eq!(event.origin(), TransactionOrigin::Local);
+        assert!(event.is_propagate_allowed());
+
+        let event = propagate_only.try_recv().unwrap();
+        assert_eq!(event.origin(), TransactionOrigin::Local);
+        assert!(propagate_only.try_recv().is_err());
+    }
 }
@@ -1,12 +1,13 @@
 use crate::{
     identifier::{SenderId, TransactionId},
     pool::size::SizeTracker,
-    PoolTransaction, SubPoolLimit, ValidPoolTransaction, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+    BasefeePoolStats, PoolTransaction, SubPoolLimit, ValidPoolTransaction,
+    TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
 };
 use rustc_hash::FxHashMap;
 use smallvec::SmallVec;
 use std::{
-    cmp::Ordering,
+    cmp::{Ordering, Reverse},
     collections::{hash_map::Entry, BTreeMap, BTreeSet},
     ops::{Bound::Unbounded, Deref},
     sync::Arc,
@@ -45,6 +46,12 @@ pub struct ParkedPool<T: ParkedOrd> {
     ///
     /// See also [`PoolTransaction::size`].
     size_of: SizeTracker,
+    /// The pending basefee the fee caps in `basefee_stats` are relative to.
+    stats_basefee: u64,
+    /// Aggregate stats of all transactions, kept up to date on every insertion and removal.
+    ///
+    /// Only reported for the basefee subpool.
+    basefee_stats: BasefeePoolStats,
 }
 
 // === impl ParkedPool ===
@@ -66,6 +73,7 @@ impl<T: ParkedOrd> ParkedPool<T> {
 
         // keep track of size
         self.size_of += tx.size();
+        self.basefee_stats.add(tx.max_fee_per_gas(), tx.gas_limit(), self.stats_basefee);
 
         // update or create sender entry
         self.add_sender_count(tx.sender_id(), submission_id);
@@ -147,6 +155,11 @@ impl<T: ParkedOrd> ParkedPool<T> {
 
         // keep track of size
         self.size_of -= tx.transaction.size();
+        self.basefee_stats.remove(
+            tx.transaction.max_fee_per_gas(),
+            tx.transaction.gas_limit(),
+            self.stats_basefee,
+        );
 
         Some(tx.transaction.into())
     }
@@ -255,16 +268,64 @@ impl<T: ParkedOrd> ParkedPool<T> {
         self.by_id.get(id)
     }
 
-    /// Asserts that the bijection between `by_id` and `best` is valid.
-    #[cfg(any(test, feature = "test-utils"))]
+    /// Asserts that the bijection between `by_id` and `best` is valid, that the tracked size
+    /// matches the transactions and that the per sender bookkeeping matches the transactions of
+    /// each sender.
+    ///
+    /// # Panics
+    ///
+    /// If any invariant is violated.
+    #[cfg(any(test, debug_assertions, feature = "test-utils"))]
     pub(crate) fn assert_invariants(&self) {
         assert_eq!(self.by_id.len(), self.best.len(), "by_id.len() != best.len()");
+        assert!(self.by_id.values().all(|tx| self.best.contains(tx)), "by_id != best");
+
+        let size = self.by_id.values().map(|tx| tx.transaction.size()).sum::<usize>();
+        assert_eq!(self.size(), size, "tracked size != size of all transactions");
+
+        let mut basefee_stats = BasefeePoolStats::default();
+        for tx in self.by_id.values() {
+            let tx = &tx.transaction;
+            basefee_stats.add(tx.max_fee_per_gas(), tx.gas_limit(), self.stats_basefee);
+        }
+        assert_eq!(self.basefee_stats, basefee_stats, "tracked stats != stats of all transactions");
 
         assert_eq!(
             self.last_sender_submission.len(),
             self.sender_transaction_count.len(),
             "last_sender_transaction.len() != sender_to_last_transaction.len()"
         );
+
+        let mut senders = FxHashMap::<SenderId, (u64, u64)>::default();
+        for tx in self.by_id.values() {
+            let (count, last_submission_id) =
+                senders.entry(tx.transaction.sender_id()).or_default();
+            *count += 1;
+            *last_submission_id = (*last_submission_id).max(tx.submission_id);
+        }
+        assert_eq!(
+            senders.len(),
+            self.sender_transaction_count.len(),
+            "number of senders != sender_transaction_count.len()"
+        );
+        for (sender, (count, last_submission_id)) in senders {
+            let tracked = self
+                .sender_transaction_count
+                .get(&sender)
+                .unwrap_or_else(|| panic!("sender {sender:?} not tracked"));
+            assert_eq!(tracked.count, count, "transaction count of sender {sender:?}");
+            // the tracked id is not updated on removal, so it may belong to a removed transaction
+            assert!(
+                tracked.last_submission_id >= last_submission_id,
+                "last submission id of sender {sender:?}: {} < {last_submission_id}",
+                tracked.last_submission_id
+            );
+            assert!(
+                self.last_sender_submission
+                    .contains(&SubmissionSenderId::new(sender, tracked.last_submission_id)),
+                "last submission of sender {sender:?} not tracked"
+            );
+        }
     }
 }
 
@@ -308,6 +369,36 @@ impl<T: PoolTransaction> ParkedPool<BasefeeOrd<T>> {
         transactions
     }
 
+    /// Returns up to `limit` transactions with the highest fee caps, ordered by their fee cap in
+    /// descending order.
+    pub(crate) fn best_fee_caps(&self, limit: usize) -> Vec<Arc<ValidPoolTransaction<T>>> {
+        let mut transactions = self.all().collect::<Vec<_>>();
+        transactions.sort_by_key(|tx| Reverse(tx.max_fee_per_gas()));
+        transactions.truncate(limit);
+        transactions
+    }
+
+    /// Returns aggregate stats of all transactions, relative to the pending basefee last set with
+    /// [`Self::set_stats_basefee`].
+    pub(crate) const fn stats(&self) -> BasefeePoolStats {
+        self.basefee_stats
+    }
+
+    /// Sets the pending basefee the stats are relative to.
+    ///
+    /// The fee caps of all transactions are bucketed again if the basefee changed.
+    pub(crate) fn set_stats_basefee(&mut self, basefee: u64) {
+        if self.stats_basefee == basefee {
+            return
+        }
+        self.stats_basefee = basefee;
+        self.basefee_stats = BasefeePoolStats::default();
+        for tx in self.by_id.values() {
+            let tx = &tx.transaction;
+            self.basefee_stats.add(tx.max_fee_per_gas(), tx.gas_limit(), basefee);
+        }
+    }
+
     /// Removes all transactions and their dependent transaction from the subpool that no longer
     /// satisfy the given basefee.
     ///
@@ -333,6 +424,8 @@ impl<T: ParkedOrd> Default for ParkedPool<T> {
             last_sender_submission: Default::default(),
             sender_transaction_count: Default::default(),
             size_of: Default::default(),
+            stats_basefee: Default::default(),
+            basefee_stats: Default::default(),
         }
     }
 }
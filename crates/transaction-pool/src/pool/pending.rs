@@ -14,6 +14,9 @@ use std::{
 };
 use tokio::sync::broadcast;
 
+#[cfg(any(test, debug_assertions, feature = "test-utils"))]
+use crate::pool::txpool::assert_same_ids;
+
 /// A pool of validated and gapless transactions that are ready to be executed on the current state
 /// and are waiting to be included in a block.
 ///
@@ -112,6 +115,8 @@ impl<T: TransactionOrdering> PendingPool<T> {
             invalid: Default::default(),
             new_transaction_receiver: Some(self.new_transaction_notifier.subscribe()),
             skip_blobs: false,
+            minimum_priority_fee: None,
+            base_fee: 0,
         }
     }
 
@@ -269,7 +274,13 @@ impl<T: TransactionOrdering> PendingPool<T> {
             // guaranteed because this pool is gapless.
             self.independent_transactions.insert(tx.clone());
         }
-        self.highest_nonces.insert(tx.clone());
+        if let Some(descendant) = self.by_id.get(&tx_id.descendant()) {
+            // the transaction is (re-)inserted in front of its descendant, e.g. when a transaction
+            // is replaced, so the descendant is no longer independent
+            self.independent_transactions.remove(descendant);
+        } else {
+            self.highest_nonces.insert(tx.clone());
+        }
     }
 
     /// Returns the ancestor the given transaction, the transaction with `nonce - 1`.
@@ -324,20 +335,20 @@ impl<T: TransactionOrdering> PendingPool<T> {
         &mut self,
         id: &TransactionId,
     ) -> Option<Arc<ValidPoolTransaction<T::Transaction>>> {
-        // mark the next as independent if it exists
-        if let Some(unlocked) = self.get(&id.descendant()) {
-            self.independent_transactions.insert(unlocked.clone());
-        }
         let tx = self.by_id.remove(id)?;
         self.size_of -= tx.transaction.size();
         self.all.remove(&tx);
         self.independent_transactions.remove(&tx);
+        self.highest_nonces.remove(&tx);
 
-        // switch out for the next ancestor if there is one
-        if self.highest_nonces.remove(&tx) {
-            if let Some(ancestor) = self.ancestor(id) {
-                self.highest_nonces.insert(ancestor.clone());
-            }
+        // mark the next as independent if it exists
+        if let Some(unlocked) = self.get(&id.descendant()) {
+            self.independent_transactions.insert(unlocked.clone());
+        }
+        // the ancestor is now the highest nonce of the sender, even if a descendant of the removed
+        // transaction is still in the pool
+        if let Some(ancestor) = self.ancestor(id) {
+            self.highest_nonces.insert(ancestor.clone());
         }
         Some(tx.transaction)
     }
@@ -517,22 +528,30 @@ impl<T: TransactionOrdering> PendingPool<T> {
         self.by_id.get(id)
     }
 
-    /// Asserts that the bijection between `by_id` and `all` is valid.
-    #[cfg(any(test, feature = "test-utils"))]
+    /// Asserts that the bijection between `by_id` and `all` is valid, that the tracked size matches
+    /// the transactions and that the `independent` and `highest_nonces` sets contain exactly the
+    /// lowest and highest nonce transaction of each sender.
+    ///
+    /// # Panics
+    ///
+    /// If any invariant is violated.
+    #[cfg(any(test, debug_assertions, feature = "test-utils"))]
     pub(crate) fn assert_invariants(&self) {
         assert_eq!(self.by_id.len(), self.all.len(), "by_id.len() != all.len()");
-        assert!(
-            self.independent_transactions.len() <= self.all.len(),
-            "independent.len() > all.len()"
-        );
-        assert!(
-            self.highest_nonces.len() <= self.all.len(),
-            "independent_descendants.len() > all.len()"
+        assert!(self.by_id.values().all(|tx| self.all.contains(tx)), "by_id != all");
+
+        let size = self.by_id.values().map(|tx| tx.transaction.size()).sum::<usize>();
+        assert_eq!(self.size(), size, "tracked size != size of all transactions");
+
+        assert_same_ids(
+            "pending independent transactions",
+            self.independent_transactions.iter().map(|tx| *tx.transaction.id()),
+            self.by_id.keys().filter(|id| self.ancestor(id).is_none()).copied(),
         );
-        assert_eq!(
-            self.highest_nonces.len(),
-            self.independent_transactions.len(),
-            "independent.len() = independent_descendants.len()"
+        assert_same_ids(
+            "pending highest nonces",
+            self.highest_nonces.iter().map(|tx| *tx.transaction.id()),
+            self.by_id.keys().filter(|id| !self.contains(&id.descendant())).copied(),
         );
     }
 }
@@ -658,6 +677,28 @@ mod tests {
         pool.assert_invariants();
     }
 
+    #[test]
+    fn replace_intermediate_transaction() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = PendingPool::new(MockOrdering::default());
+
+        let t = MockTransaction::eip1559();
+        let txs = [t.clone(), t.next(), t.next().next()].map(|tx| f.validated_arc(tx));
+        for tx in &txs {
+            pool.add_transaction(tx.clone(), 0);
+        }
+
+        // replace the transaction in the middle, like the pool does for a fee bump
+        pool.remove_transaction(txs[1].id());
+        pool.add_transaction(f.validated_arc(t.next().inc_price().rng_hash()), 0);
+        pool.assert_invariants();
+
+        assert_eq!(pool.independent_transactions.len(), 1);
+        assert_eq!(pool.independent_transactions.first().unwrap().transaction.id(), txs[0].id());
+        assert_eq!(pool.highest_nonces.len(), 1);
+        assert_eq!(pool.highest_nonces.first().unwrap().transaction.id(), txs[2].id());
+    }
+
     #[test]
     fn evict_worst() {
         let mut f = MockTransactionFactory::default();
@@ -0,0 +1,461 @@
+//! Persists the rejections of transactions that can never become valid.
+//!
+//! Spam campaigns keep sending the same invalid transactions for days, and the in-memory
+//! [`SeenTransactions`](super::seen::SeenTransactions) cache is lost on every restart. The
+//! [`PersistentRejections`] store keeps the hashes of transactions that were rejected for a
+//! state-independent reason, see [`RejectionCode`], in a small table on disk.
+//!
+//! Only a bloom filter of the stored hashes is kept in memory. The table is read only if the
+//! filter reports a possible match, and rejections are written in batches. Once the table holds
+//! more than the configured number of rows, the oldest rejections are evicted.
+//!
+//! The store only learns about blocks through the transactions mined in them, whose rejections
+//! are removed. Block import doesn't consult it, some of the persisted rejections are pool rules
+//! that don't apply to blocks.
+
+use crate::{
+    config::PersistentRejectionsConfig,
+    error::{InvalidPoolTransactionError, PoolError, PoolErrorKind},
+};
+use parking_lot::Mutex;
+use reth_libmdbx::{ffi::MDBX_dbi, Environment, Geometry, Transaction, WriteFlags, RW};
+use reth_primitives::{InvalidTransactionError, TxHash};
+use std::{
+    collections::HashMap,
+    fmt,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::{debug, warn};
+
+/// Maximum size of the rejections table on disk.
+const MAX_TABLE_SIZE: usize = 256 * 1024 * 1024;
+
+/// Size of a stored row: the rejection code followed by the big-endian insertion time in seconds
+/// since the unix epoch.
+const ROW_SIZE: usize = 9;
+
+/// A state-independent reason for the rejection of a transaction.
+///
+/// Transactions rejected for one of these reasons can never become valid, no matter the state of
+/// the chain, so their rejection can be persisted. Rejections that depend on the state, e.g. a
+/// too low nonce, an insufficient balance or the current block gas limit, don't have a code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum RejectionCode {
+    /// The chain id of the transaction doesn't match.
+    ChainIdMismatch = 1,
+    /// A legacy transaction before Spurious Dragon has a chain id.
+    OldLegacyChainId = 2,
+    /// The gas of the transaction overflows.
+    GasUintOverflow = 3,
+    /// The gas limit of the transaction is below its intrinsic gas.
+    IntrinsicGasTooLow = 4,
+    /// The init code of the transaction exceeds the maximum init code size.
+    ExceedsMaxInitCodeSize = 5,
+    /// The input data of the transaction exceeds the maximum size.
+    OversizedData = 6,
+}
+
+impl RejectionCode {
+    /// Returns the code of the rejection, if the transaction can never become valid.
+    pub const fn from_error(err: &PoolError) -> Option<Self> {
+        let PoolErrorKind::InvalidTransaction(err) = &err.kind else { return None };
+        match err {
+            InvalidPoolTransactionError::Consensus(err) => match err {
+                InvalidTransactionError::ChainIdMismatch => Some(Self::ChainIdMismatch),
+                InvalidTransactionError::OldLegacyChainId => Some(Self::OldLegacyChainId),
+                InvalidTransactionError::GasUintOverflow => Some(Self::GasUintOverflow),
+                _ => None,
+            },
+            InvalidPoolTransactionError::IntrinsicGasTooLow => Some(Self::IntrinsicGasTooLow),
+            InvalidPoolTransactionError::ExceedsMaxInitCodeSize(_, _) => {
+                Some(Self::ExceedsMaxInitCodeSize)
+            }
+            InvalidPoolTransactionError::OversizedData(_, _) => Some(Self::OversizedData),
+            _ => None,
+        }
+    }
+
+    /// Decodes a stored code.
+    const fn from_u8(code: u8) -> Option<Self> {
+        Some(match code {
+            1 => Self::ChainIdMismatch,
+            2 => Self::OldLegacyChainId,
+            3 => Self::GasUintOverflow,
+            4 => Self::IntrinsicGasTooLow,
+            5 => Self::ExceedsMaxInitCodeSize,
+            6 => Self::OversizedData,
+            _ => return None,
+        })
+    }
+
+    /// Returns the message of the original rejection.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::ChainIdMismatch => "transaction's chain ID does not match",
+            Self::OldLegacyChainId => {
+                "transactions before Spurious Dragon should not have a chain ID"
+            }
+            Self::GasUintOverflow => "gas overflow (maximum of u64)",
+            Self::IntrinsicGasTooLow => "intrinsic gas too low",
+            Self::ExceedsMaxInitCodeSize => "transaction's size exceeds max_init_code_size",
+            Self::OversizedData => "input data too large",
+        }
+    }
+}
+
+impl fmt::Display for RejectionCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Errors of the [`PersistentRejections`] store.
+#[derive(Debug, thiserror::Error)]
+pub enum PersistentRejectionsError {
+    /// Failed to create the directory of the table.
+    #[error(transparent)]
+    Io(#[from] reth_fs_util::FsPathError),
+    /// Failed to access the table.
+    #[error(transparent)]
+    Database(#[from] reth_libmdbx::Error),
+}
+
+/// Persistent store of the hashes of transactions that can never become valid.
+///
+/// Lookups that miss the in-memory bloom filter never touch the disk. Writes are buffered and
+/// flushed once [`PersistentRejectionsConfig::batch_size`] rejections are pending, and when the
+/// store is dropped.
+///
+/// The store can be used from multiple threads. The disk is accessed without holding the lock of
+/// the in-memory state.
+pub struct PersistentRejections {
+    env: Environment,
+    config: PersistentRejectionsConfig,
+    /// The bloom filter and the pending batch.
+    state: Mutex<State>,
+    /// Serializes the writes to the table, so that a rebuilt bloom filter doesn't miss a batch
+    /// that is being flushed.
+    writer: Mutex<()>,
+}
+
+/// The in-memory state of the [`PersistentRejections`] store.
+struct State {
+    /// Bloom filter of the hashes in the table and the pending batch.
+    bloom: Bloom,
+    /// Rejections that are not written to the table yet.
+    pending: HashMap<TxHash, (RejectionCode, u64)>,
+}
+
+impl PersistentRejections {
+    /// Opens or creates the store in the given directory and loads the bloom filter of the stored
+    /// hashes.
+    pub fn open(
+        path: impl AsRef<Path>,
+        config: PersistentRejectionsConfig,
+    ) -> Result<Self, PersistentRejectionsError> {
+        let path = path.as_ref();
+        reth_fs_util::create_dir_all(path)?;
+        let env = Environment::builder()
+            .set_geometry(Geometry { size: Some(0..MAX_TABLE_SIZE), ..Default::default() })
+            .open(path)?;
+
+        let state = State { bloom: Bloom::new(0), pending: HashMap::new() };
+        let this = Self { env, config, state: Mutex::new(state), writer: Mutex::new(()) };
+        this.rebuild_bloom()?;
+        debug!(target: "txpool", ?path, "opened persistent rejections");
+        Ok(this)
+    }
+
+    /// Returns the code of the persisted rejection of the transaction, if any.
+    pub fn get(&self, hash: &TxHash) -> Option<RejectionCode> {
+        {
+            let state = self.state.lock();
+            if !state.bloom.contains(hash) {
+                return None
+            }
+            if let Some((code, _)) = state.pending.get(hash) {
+                return Some(*code)
+            }
+        }
+        self.read(hash).unwrap_or_else(|err| {
+            debug!(target: "txpool", %err, ?hash, "failed to read persistent rejection");
+            None
+        })
+    }
+
+    /// Records the rejection of the transaction, and flushes the pending batch if it's full.
+    pub fn insert(&self, hash: TxHash, code: RejectionCode) {
+        self.insert_at(hash, code, unix_now());
+    }
+
+    /// Forgets the rejections of the transactions, e.g. because they were included in a block.
+    ///
+    /// The rejections are removed from the table in a single write, which only happens if one of
+    /// the transactions may have been rejected.
+    pub fn remove_all(&self, hashes: impl IntoIterator<Item = TxHash>) {
+        let _writer = self.writer.lock();
+        let stored = {
+            let mut state = self.state.lock();
+            hashes
+                .into_iter()
+                .filter(|hash| {
+                    state.pending.remove(hash);
+                    state.bloom.contains(hash)
+                })
+                .collect::<Vec<_>>()
+        };
+        if stored.is_empty() {
+            return
+        }
+        let res = self.write(|txn, dbi| {
+            for hash in &stored {
+                txn.del(dbi, hash, None)?;
+            }
+            Ok(())
+        });
+        if let Err(err) = res {
+            warn!(target: "txpool", %err, "failed to remove persistent rejections");
+        }
+    }
+
+    /// Writes all pending rejections to the table, and evicts the oldest rows if the table holds
+    /// more than [`PersistentRejectionsConfig::max_entries`] rows.
+    pub fn flush(&self) {
+        if let Err(err) = self.try_flush() {
+            warn!(target: "txpool", %err, "failed to flush persistent rejections");
+        }
+    }
+
+    fn insert_at(&self, hash: TxHash, code: RejectionCode, inserted_at: u64) {
+        let full = {
+            let mut state = self.state.lock();
+            state.bloom.insert(&hash);
+            state.pending.insert(hash, (code, inserted_at));
+            state.pending.len() >= self.config.batch_size
+        };
+        if full {
+            self.flush();
+        }
+    }
+
+    fn try_flush(&self) -> Result<(), PersistentRejectionsError> {
+        let _writer = self.writer.lock();
+        let pending = std::mem::take(&mut self.state.lock().pending);
+        if pending.is_empty() {
+            return Ok(())
+        }
+        let entries = self.write(|txn, dbi| {
+            for (hash, (code, inserted_at)) in &pending {
+                let mut row = [0u8; ROW_SIZE];
+                row[0] = *code as u8;
+                row[1..].copy_from_slice(&inserted_at.to_be_bytes());
+                txn.put(dbi, hash, row, WriteFlags::UPSERT)?;
+            }
+            Ok(txn.db_stat_with_dbi(dbi)?.entries())
+        })?;
+
+        if entries > self.config.max_entries {
+            self.evict(entries)?;
+        }
+        Ok(())
+    }
+
+    /// Evicts the oldest rows, leaving a tenth of the capacity free so that eviction doesn't run
+    /// on every flush, and rebuilds the bloom filter.
+    ///
+    /// Must be called while holding the writer lock.
+    fn evict(&self, entries: usize) -> Result<(), PersistentRejectionsError> {
+        let target = self.config.max_entries - self.config.max_entries / 10;
+        let mut rows = Vec::with_capacity(entries);
+        self.read_all(|hash, _, inserted_at| rows.push((inserted_at, hash)))?;
+        rows.sort_unstable();
+
+        let evicted = rows.len().saturating_sub(target);
+        self.write(|txn, dbi| {
+            for (_, hash) in &rows[..evicted] {
+                txn.del(dbi, hash, None)?;
+            }
+            Ok(())
+        })?;
+        debug!(target: "txpool", evicted, "evicted oldest persistent rejections");
+
+        self.rebuild_bloom()
+    }
+
+    /// Rebuilds the bloom filter from the hashes in the table.
+    ///
+    /// Must be called while holding the writer lock, or before the store is shared.
+    fn rebuild_bloom(&self) -> Result<(), PersistentRejectionsError> {
+        let mut bloom = Bloom::new(self.config.max_entries);
+        self.read_all(|hash, _, _| bloom.insert(&hash))?;
+        let mut state = self.state.lock();
+        for hash in state.pending.keys() {
+            bloom.insert(hash);
+        }
+        state.bloom = bloom;
+        Ok(())
+    }
+
+    fn read(&self, hash: &TxHash) -> Result<Option<RejectionCode>, reth_libmdbx::Error> {
+        let txn = self.env.begin_ro_txn()?;
+        let db = txn.open_db(None)?;
+        let row = txn.get::<[u8; ROW_SIZE]>(db.dbi(), hash.as_slice())?;
+        Ok(row.and_then(|row| RejectionCode::from_u8(row[0])))
+    }
+
+    /// Calls `f` with the hash, code and insertion time of every valid row in the table.
+    fn read_all(
+        &self,
+        mut f: impl FnMut(TxHash, RejectionCode, u64),
+    ) -> Result<(), reth_libmdbx::Error> {
+        let txn = self.env.begin_ro_txn()?;
+        let db = txn.open_db(None)?;
+        let mut cursor = txn.cursor(&db)?;
+        for row in cursor.iter_start::<[u8; 32], [u8; ROW_SIZE]>() {
+            let (hash, row) = row?;
+            if let Some(code) = RejectionCode::from_u8(row[0]) {
+                let inserted_at = u64::from_be_bytes(row[1..].try_into().expect("8 bytes"));
+                f(TxHash::from(hash), code, inserted_at);
+            }
+        }
+        Ok(())
+    }
+
+    fn write<R>(
+        &self,
+        f: impl FnOnce(&Transaction<RW>, MDBX_dbi) -> Result<R, reth_libmdbx::Error>,
+    ) -> Result<R, reth_libmdbx::Error> {
+        let txn = self.env.begin_rw_txn()?;
+        let db = txn.open_db(None)?;
+        let res = f(&txn, db.dbi())?;
+        txn.commit()?;
+        Ok(res)
+    }
+}
+
+impl Drop for PersistentRejections {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+impl fmt::Debug for PersistentRejections {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PersistentRejections")
+            .field("config", &self.config)
+            .field("pending", &self.state.lock().pending.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the current time in seconds since the unix epoch.
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Number of bits of the [`Bloom`] filter per expected entry, for a false positive rate of
+/// about 1%.
+const BLOOM_BITS_PER_ENTRY: usize = 10;
+
+/// Number of bits of the [`Bloom`] filter that are set per entry.
+const BLOOM_HASHES: u64 = 7;
+
+/// Bloom filter of transaction hashes.
+///
+/// Transaction hashes are uniformly distributed, so the bit positions are derived from the hash
+/// itself by double hashing.
+struct Bloom {
+    bits: Vec<u64>,
+}
+
+impl Bloom {
+    /// Creates an empty filter sized for the given number of entries.
+    fn new(entries: usize) -> Self {
+        let words = (entries * BLOOM_BITS_PER_ENTRY).div_ceil(64).max(1);
+        Self { bits: vec![0; words] }
+    }
+
+    fn positions(&self, hash: &TxHash) -> impl Iterator<Item = usize> {
+        let len = self.bits.len() as u64 * 64;
+        let h1 = u64::from_le_bytes(hash[..8].try_into().expect("8 bytes"));
+        let h2 = u64::from_le_bytes(hash[24..].try_into().expect("8 bytes")) | 1;
+        (0..BLOOM_HASHES).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % len) as usize)
+    }
+
+    fn insert(&mut self, hash: &TxHash) {
+        for pos in self.positions(hash) {
+            self.bits[pos / 64] |= 1 << (pos % 64);
+        }
+    }
+
+    fn contains(&self, hash: &TxHash) -> bool {
+        self.positions(hash).all(|pos| self.bits[pos / 64] & (1 << (pos % 64)) != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::keccak256;
+
+    fn config(max_entries: usize) -> PersistentRejectionsConfig {
+        PersistentRejectionsConfig { max_entries, batch_size: 4 }
+    }
+
+    fn hash(i: u64) -> TxHash {
+        keccak256(i.to_be_bytes())
+    }
+
+    #[test]
+    fn evicts_oldest_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PersistentRejections::open(dir.path(), config(2)).unwrap();
+
+        for i in 0..3 {
+            store.insert_at(hash(i), RejectionCode::IntrinsicGasTooLow, 100 + i);
+        }
+        store.flush();
+        assert_eq!(store.get(&hash(0)), None);
+        assert_eq!(store.get(&hash(1)), Some(RejectionCode::IntrinsicGasTooLow));
+        assert_eq!(store.get(&hash(2)), Some(RejectionCode::IntrinsicGasTooLow));
+
+        store.insert(hash(3), RejectionCode::GasUintOverflow);
+        store.remove_all([hash(1), hash(3), hash(4)]);
+        assert_eq!(store.get(&hash(1)), None);
+        assert_eq!(store.get(&hash(2)), Some(RejectionCode::IntrinsicGasTooLow));
+        assert_eq!(store.get(&hash(3)), None);
+    }
+
+    #[test]
+    fn bloom_false_positive_reads_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PersistentRejections::open(dir.path(), config(2)).unwrap();
+        store.insert(hash(0), RejectionCode::ChainIdMismatch);
+        store.insert(hash(1), RejectionCode::OversizedData);
+        store.flush();
+
+        // the filter of two entries has 64 bits, so some other hash collides with them
+        let collision = (2..1_000_000)
+            .map(hash)
+            .find(|hash| store.state.lock().bloom.contains(hash))
+            .expect("bloom filter collision");
+        assert_eq!(store.get(&collision), None);
+        assert_eq!(store.get(&hash(0)), Some(RejectionCode::ChainIdMismatch));
+        assert_eq!(store.get(&hash(1)), Some(RejectionCode::OversizedData));
+    }
+
+    #[test]
+    fn survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PersistentRejections::open(dir.path(), config(10)).unwrap();
+        store.insert(hash(0), RejectionCode::GasUintOverflow);
+        assert_eq!(store.get(&hash(0)), Some(RejectionCode::GasUintOverflow));
+        drop(store);
+
+        let store = PersistentRejections::open(dir.path(), config(10)).unwrap();
+        assert_eq!(store.get(&hash(0)), Some(RejectionCode::GasUintOverflow));
+        assert_eq!(store.get(&hash(1)), None);
+    }
+}
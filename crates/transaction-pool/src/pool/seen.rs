@@ -0,0 +1,157 @@
+//! Tracks the transactions the pool has seen recently.
+//!
+//! The same transaction can reach the pool through a gossiped announcement, inside a
+//! `PooledTransactions` response and in an imported block, often within seconds. The
+//! [`SeenTransactions`] cache remembers the hashes of transactions that were mined or rejected as
+//! invalid, so that the pool neither requests nor validates them again.
+//!
+//! Entries are keyed by the full transaction hash, which commits to the entire signed
+//! transaction, so an entry never applies to a transaction the pool hasn't seen. Rejected blob
+//! transactions are not cached, because their hash doesn't commit to the sidecar that may have
+//! caused the rejection.
+
+use crate::config::SeenTransactionsConfig;
+use reth_primitives::{BlockNumber, TxHash};
+use schnellru::{ByLength, LruMap};
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// What the pool knows about a transaction it has seen recently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Seen {
+    /// The transaction is in the pool.
+    InPool,
+    /// The transaction was mined in the canonical block with the given number.
+    Mined(BlockNumber),
+    /// The transaction was rejected for the given reason, and can never become valid.
+    Rejected(Arc<str>),
+    /// The transaction was rejected for the given reason by a rule that changed recently, e.g.
+    /// because the block gas limit was lowered. It can never become valid, but peers may still
+    /// send it in good faith.
+    ///
+    /// See [`PoolError::is_stale_transaction`](crate::error::PoolError::is_stale_transaction).
+    Stale(Arc<str>),
+    /// The transaction was mined or rejected, but too long ago for this to be trusted.
+    Expired,
+}
+
+impl Seen {
+    /// Returns `true` if the transaction doesn't need to be fetched or validated again.
+    pub const fn is_known(&self) -> bool {
+        matches!(self, Self::InPool | Self::Mined(_) | Self::Rejected(_) | Self::Stale(_))
+    }
+}
+
+/// Bounded cache of recently mined and rejected transactions by hash.
+///
+/// Entries are evicted in least recently inserted order once the cache is full, and reported as
+/// [`Seen::Expired`] once they are older than the configured TTL.
+pub(crate) struct SeenTransactions {
+    ttl: Duration,
+    /// The state of every tracked transaction and when it was recorded.
+    entries: LruMap<TxHash, (Seen, Instant), ByLength>,
+}
+
+impl SeenTransactions {
+    /// Creates an empty cache with the given bounds.
+    pub(crate) fn new(config: SeenTransactionsConfig) -> Self {
+        Self { ttl: config.ttl, entries: LruMap::new(ByLength::new(config.max_entries)) }
+    }
+
+    /// Returns the state of the transaction, if it was mined or rejected recently.
+    pub(crate) fn get(&self, hash: &TxHash, now: Instant) -> Option<Seen> {
+        let (seen, recorded) = self.entries.peek(hash)?;
+        if now.saturating_duration_since(*recorded) > self.ttl {
+            return Some(Seen::Expired)
+        }
+        Some(seen.clone())
+    }
+
+    /// Records that the transaction was mined in the given canonical block.
+    pub(crate) fn on_mined(&mut self, hash: TxHash, block: BlockNumber, now: Instant) {
+        self.entries.insert(hash, (Seen::Mined(block), now));
+    }
+
+    /// Records that the transaction was rejected and can never become valid.
+    pub(crate) fn on_rejected(&mut self, hash: TxHash, reason: Arc<str>, now: Instant) {
+        self.entries.insert(hash, (Seen::Rejected(reason), now));
+    }
+
+    /// Records that the transaction was rejected by a rule that changed recently.
+    ///
+    /// Such rejections are not persisted, because they depend on the recent blocks.
+    pub(crate) fn on_rejected_stale(&mut self, hash: TxHash, reason: Arc<str>, now: Instant) {
+        self.entries.insert(hash, (Seen::Stale(reason), now));
+    }
+
+    /// Forgets all transactions mined in the given block or later, because these blocks were
+    /// reverted.
+    pub(crate) fn on_reverted(&mut self, first_reverted: BlockNumber) {
+        let reverted = self
+            .entries
+            .iter()
+            .filter(|(_, (seen, _))| matches!(seen, Seen::Mined(block) if *block >= first_reverted))
+            .map(|(hash, _)| *hash)
+            .collect::<Vec<_>>();
+        for hash in reverted {
+            self.entries.remove(&hash);
+        }
+    }
+}
+
+impl fmt::Debug for SeenTransactions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SeenTransactions")
+            .field("ttl", &self.ttl)
+            .field("entries", &self.entries.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(max_entries: u32) -> SeenTransactions {
+        SeenTransactions::new(SeenTransactionsConfig { max_entries, ttl: Duration::from_secs(60) })
+    }
+
+    #[test]
+    fn expires_after_ttl() {
+        let (mined, rejected) = (TxHash::with_last_byte(1), TxHash::with_last_byte(2));
+        let now = Instant::now();
+        let mut cache = cache(10);
+
+        cache.on_mined(mined, 1, now);
+        cache.on_rejected(rejected, "intrinsic gas too low".into(), now);
+        assert_eq!(cache.get(&mined, now), Some(Seen::Mined(1)));
+        assert!(cache.get(&rejected, now).is_some_and(|seen| seen.is_known()));
+        assert_eq!(cache.get(&TxHash::with_last_byte(3), now), None);
+
+        let later = now + Duration::from_secs(61);
+        assert_eq!(cache.get(&mined, later), Some(Seen::Expired));
+        assert!(!Seen::Expired.is_known());
+
+        // seeing the transaction again renews the entry
+        cache.on_mined(mined, 2, later);
+        assert_eq!(cache.get(&mined, later), Some(Seen::Mined(2)));
+    }
+
+    #[test]
+    fn bounded_and_forgets_reverted_blocks() {
+        let now = Instant::now();
+        let mut cache = cache(2);
+
+        for block in 1..=3 {
+            cache.on_mined(TxHash::with_last_byte(block as u8), block, now);
+        }
+        assert_eq!(cache.get(&TxHash::with_last_byte(1), now), None);
+
+        cache.on_reverted(3);
+        assert_eq!(cache.get(&TxHash::with_last_byte(2), now), Some(Seen::Mined(2)));
+        assert_eq!(cache.get(&TxHash::with_last_byte(3), now), None);
+    }
+}
@@ -6,15 +6,19 @@ use crate::{
     identifier::{SenderId, TransactionId},
     metrics::{AllTransactionsMetrics, TxPoolMetrics},
     pool::{
-        best::BestTransactions,
+        best::{BestTransactions, BestTransactionsWithFees},
         blob::BlobTransactions,
+        lookup::TransactionLookup,
         parked::{BasefeeOrd, ParkedPool, QueuedOrd},
         pending::PendingPool,
         state::{SubPool, TxState},
         update::{Destination, PoolUpdate},
         AddedPendingTransaction, AddedTransaction, OnNewCanonicalStateOutcome,
+        ReplacementSuggestion,
+    },
+    traits::{
+        BasefeePoolStats, BestTransactionsAttributes, BlockInfo, PendingSenderInfo, PoolSize,
     },
-    traits::{BestTransactionsAttributes, BlockInfo, PoolSize},
     PoolConfig, PoolResult, PoolTransaction, PriceBumpConfig, TransactionOrdering,
     ValidPoolTransaction, U256,
 };
@@ -23,7 +27,7 @@ use reth_primitives::{
     constants::{
         eip4844::BLOB_TX_MIN_BLOB_GASPRICE, ETHEREUM_BLOCK_GAS_LIMIT, MIN_PROTOCOL_BASE_FEE,
     },
-    Address, TxHash, B256,
+    Address, BaseFeeParams, TxHash, B256,
 };
 use rustc_hash::FxHashMap;
 use smallvec::SmallVec;
@@ -33,6 +37,7 @@ use std::{
     fmt,
     ops::Bound::{Excluded, Unbounded},
     sync::Arc,
+    time::Instant,
 };
 use tracing::trace;
 
@@ -74,6 +79,11 @@ pub struct TxPool<T: TransactionOrdering> {
     all_transactions: AllTransactions<T::Transaction>,
     /// Transaction pool metrics
     metrics: TxPoolMetrics,
+    /// Number of mutations since the invariants were last checked.
+    ///
+    /// See also [`PoolConfig::invariant_check_interval`].
+    #[cfg(debug_assertions)]
+    mutations: usize,
 }
 
 // === impl TxPool ===
@@ -90,6 +100,8 @@ impl<T: TransactionOrdering> TxPool<T> {
             all_transactions: AllTransactions::new(&config),
             config,
             metrics: Default::default(),
+            #[cfg(debug_assertions)]
+            mutations: 0,
         }
     }
 
@@ -107,6 +119,13 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.all().txs_iter(sender).last().map(|(_, tx)| Arc::clone(&tx.transaction))
     }
 
+    /// Returns the lookup that mirrors all transactions of the pool by their hash.
+    ///
+    /// The lookup can be shared and queried without holding the lock of the pool.
+    pub(crate) fn lookup(&self) -> Arc<TransactionLookup<T::Transaction>> {
+        Arc::clone(&self.all_transactions.lookup)
+    }
+
     /// Returns access to the [`AllTransactions`] container.
     pub(crate) const fn all(&self) -> &AllTransactions<T::Transaction> {
         &self.all_transactions
@@ -129,9 +148,24 @@ impl<T: TransactionOrdering> TxPool<T> {
             blob: self.blob_pool.len(),
             blob_size: self.blob_pool.size(),
             total: self.all_transactions.len(),
+            basefee_stats: self.basefee_pool.stats(),
         }
     }
 
+    /// Returns aggregate stats of the basefee subpool, relative to the pending basefee.
+    pub(crate) const fn basefee_pool_stats(&self) -> BasefeePoolStats {
+        self.basefee_pool.stats()
+    }
+
+    /// Returns up to `limit` transactions of the basefee subpool with the highest fee caps,
+    /// ordered by their fee cap in descending order.
+    pub(crate) fn basefee_pool_snapshot(
+        &self,
+        limit: usize,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.basefee_pool.best_fee_caps(limit)
+    }
+
     /// Returns the currently tracked block values
     pub const fn block_info(&self) -> BlockInfo {
         BlockInfo {
@@ -192,6 +226,9 @@ impl<T: TransactionOrdering> TxPool<T> {
     /// transactions from the basefee pool.
     fn update_basefee(&mut self, mut pending_basefee: u64) -> Ordering {
         std::mem::swap(&mut self.all_transactions.pending_fees.base_fee, &mut pending_basefee);
+        // the stats are updated first, so that transactions moved below are counted with the new
+        // basefee
+        self.basefee_pool.set_stats_basefee(self.all_transactions.pending_fees.base_fee);
         match self.all_transactions.pending_fees.base_fee.cmp(&pending_basefee) {
             Ordering::Equal => {
                 // fee unchanged, nothing to update
@@ -251,12 +288,19 @@ impl<T: TransactionOrdering> TxPool<T> {
         if let Some(blob_fee) = pending_blob_fee {
             self.update_blob_fee(blob_fee, basefee_ordering)
         }
+
+        self.on_mutation();
     }
 
     /// Returns an iterator that yields transactions that are ready to be included in the block with
     /// the tracked fees.
     pub(crate) fn best_transactions(&self) -> BestTransactions<T> {
-        self.pending_pool.best()
+        let mut best = self.pending_pool.best();
+        best.set_minimum_priority_fee(
+            self.config.minimum_priority_fee,
+            self.all_transactions.pending_fees.base_fee,
+        );
+        best
     }
 
     /// Returns an iterator that yields transactions that are ready to be included in the block with
@@ -265,11 +309,24 @@ impl<T: TransactionOrdering> TxPool<T> {
     /// If the provided attributes differ from the currently tracked fees, this will also include
     /// transactions that are unlocked by the new fees, or exclude transactions that are no longer
     /// valid with the new fees.
+    ///
+    /// The configured minimum priority fee is enforced at the given base fee.
     pub(crate) fn best_transactions_with_attributes(
         &self,
         best_transactions_attributes: BestTransactionsAttributes,
     ) -> Box<dyn crate::traits::BestTransactions<Item = Arc<ValidPoolTransaction<T::Transaction>>>>
     {
+        let minimum_priority_fee = self.config.minimum_priority_fee;
+        let base_fee = best_transactions_attributes.basefee;
+        let with_floor = |mut best: BestTransactions<T>| {
+            best.set_minimum_priority_fee(minimum_priority_fee, base_fee);
+            best
+        };
+        let with_fees_floor = |mut best: BestTransactionsWithFees<T>| {
+            best.set_minimum_priority_fee(minimum_priority_fee);
+            best
+        };
+
         // First we need to check if the given base fee is different than what's currently being
         // tracked
         match best_transactions_attributes.basefee.cmp(&self.all_transactions.pending_fees.base_fee)
@@ -278,27 +335,33 @@ impl<T: TransactionOrdering> TxPool<T> {
                 // for EIP-4844 transactions we also need to check if the blob fee is now lower than
                 // what's currently being tracked, if so we need to include transactions from the
                 // blob pool that are valid with the lower blob fee
-                if best_transactions_attributes
-                    .blob_fee
-                    .map_or(false, |fee| fee < self.all_transactions.pending_fees.blob_fee as u64)
-                {
-                    let unlocked_by_blob_fee =
-                        self.blob_pool.satisfy_attributes(best_transactions_attributes);
-
-                    Box::new(self.pending_pool.best_with_unlocked(
-                        unlocked_by_blob_fee,
-                        self.all_transactions.pending_fees.base_fee,
-                    ))
-                } else {
-                    Box::new(self.pending_pool.best())
+                let tracked_blob_fee = self.all_transactions.pending_fees.blob_fee;
+                match best_transactions_attributes.blob_fee {
+                    Some(fee) if (fee as u128) < tracked_blob_fee => {
+                        let unlocked_by_blob_fee =
+                            self.blob_pool.satisfy_attributes(best_transactions_attributes);
+
+                        Box::new(with_floor(self.pending_pool.best_with_unlocked(
+                            unlocked_by_blob_fee,
+                            self.all_transactions.pending_fees.base_fee,
+                        )))
+                    }
+                    // blob fee increased, pending blob transactions may no longer satisfy it
+                    Some(fee) if (fee as u128) > tracked_blob_fee => {
+                        Box::new(with_fees_floor(self.pending_pool.best_with_basefee_and_blobfee(
+                            best_transactions_attributes.basefee,
+                            fee,
+                        )))
+                    }
+                    _ => Box::new(with_floor(self.pending_pool.best())),
                 }
             }
             Ordering::Greater => {
                 // base fee increased, we only need to enforce this on the pending pool
-                Box::new(self.pending_pool.best_with_basefee_and_blobfee(
+                Box::new(with_fees_floor(self.pending_pool.best_with_basefee_and_blobfee(
                     best_transactions_attributes.basefee,
                     best_transactions_attributes.blob_fee.unwrap_or_default(),
-                ))
+                )))
             }
             Ordering::Less => {
                 // base fee decreased, we need to move transactions from the basefee + blob pool to
@@ -310,10 +373,10 @@ impl<T: TransactionOrdering> TxPool<T> {
                 // also include blob pool transactions that are now unlocked
                 unlocked.extend(self.blob_pool.satisfy_attributes(best_transactions_attributes));
 
-                Box::new(
+                Box::new(with_floor(
                     self.pending_pool
                         .best_with_unlocked(unlocked, self.all_transactions.pending_fees.base_fee),
-                )
+                ))
             }
         }
     }
@@ -323,6 +386,13 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.pending_pool.all().collect()
     }
 
+    /// Returns all transactions from the pending sub-pool that are allowed to be propagated.
+    pub(crate) fn pending_transactions_to_propagate(
+        &self,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.pending_pool.all().filter(|tx| tx.propagate).collect()
+    }
+
     /// Returns all transactions from parked pools
     pub(crate) fn queued_transactions(&self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
         self.basefee_pool.all().chain(self.queued_pool.all()).collect()
@@ -341,8 +411,16 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.all_transactions.contains(tx_hash)
     }
 
+    /// Returns the transaction for the given hash.
+    pub(crate) fn get(
+        &self,
+        tx_hash: &TxHash,
+    ) -> Option<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.all_transactions.by_hash.get(tx_hash).cloned()
+    }
+
     /// Returns `true` if the transaction with the given id is already included in the given subpool
-    #[cfg(test)]
+    #[cfg(any(test, debug_assertions, feature = "test-utils"))]
     pub(crate) fn subpool_contains(&self, subpool: SubPool, id: &TransactionId) -> bool {
         match subpool {
             SubPool::Queued => self.queued_pool.contains(id),
@@ -358,28 +436,60 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.config.is_exceeded(self.size())
     }
 
-    /// Returns the transaction for the given hash.
-    pub(crate) fn get(
+    /// Returns all transactions sent from the given sender.
+    pub(crate) fn get_transactions_by_sender(
         &self,
-        tx_hash: &TxHash,
-    ) -> Option<Arc<ValidPoolTransaction<T::Transaction>>> {
-        self.all_transactions.by_hash.get(tx_hash).cloned()
+        sender: SenderId,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.all_transactions.txs_iter(sender).map(|(_, tx)| Arc::clone(&tx.transaction)).collect()
     }
 
-    /// Returns transactions for the multiple given hashes, if they exist.
-    pub(crate) fn get_all(
+    /// Returns all pending transactions sent from the given sender.
+    pub(crate) fn get_pending_transactions_by_sender(
         &self,
-        txs: Vec<TxHash>,
-    ) -> impl Iterator<Item = Arc<ValidPoolTransaction<T::Transaction>>> + '_ {
-        txs.into_iter().filter_map(|tx| self.get(&tx))
+        sender: SenderId,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.all_transactions
+            .txs_iter(sender)
+            .filter(|(_, tx)| tx.subpool.is_pending())
+            .map(|(_, tx)| Arc::clone(&tx.transaction))
+            .collect()
     }
 
-    /// Returns all transactions sent from the given sender.
-    pub(crate) fn get_transactions_by_sender(
+    /// Returns the nonce that follows the gapless sequence of transactions of the sender that
+    /// starts at the on-chain nonce.
+    ///
+    /// Every transaction of the sequence takes up its nonce, regardless of its subpool.
+    /// Transactions after a nonce gap are ignored, and if there's no transaction with the on-chain
+    /// nonce, the on-chain nonce is returned.
+    pub(crate) fn next_pending_nonce(&self, sender: SenderId, on_chain_nonce: u64) -> u64 {
+        let mut next_nonce = on_chain_nonce;
+        let on_chain_id = TransactionId::new(sender, on_chain_nonce);
+        for (id, _) in self.all_transactions.descendant_txs_inclusive(&on_chain_id) {
+            if id.nonce != next_nonce {
+                break
+            }
+            next_nonce = next_nonce.saturating_add(1);
+        }
+        next_nonce
+    }
+
+    /// Returns the next pending nonce and the number of pending transactions of the sender.
+    ///
+    /// See [`Self::next_pending_nonce`].
+    pub(crate) fn pending_sender_info(
         &self,
         sender: SenderId,
-    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
-        self.all_transactions.txs_iter(sender).map(|(_, tx)| Arc::clone(&tx.transaction)).collect()
+        on_chain_nonce: u64,
+    ) -> PendingSenderInfo {
+        PendingSenderInfo {
+            next_nonce: self.next_pending_nonce(sender, on_chain_nonce),
+            pending_transactions: self
+                .all_transactions
+                .txs_iter(sender)
+                .filter(|(_, tx)| tx.subpool.is_pending())
+                .count(),
+        }
     }
 
     /// Updates the transactions for the changed senders.
@@ -395,6 +505,7 @@ impl<T: TransactionOrdering> TxPool<T> {
         let update = self.process_updates(updates);
         // update the metrics after the update
         self.update_size_metrics();
+        self.on_mutation();
         update
     }
 
@@ -411,12 +522,16 @@ impl<T: TransactionOrdering> TxPool<T> {
         // update block info
         let block_hash = block_info.last_seen_block_hash;
         self.all_transactions.set_block_info(block_info);
+        self.basefee_pool.set_stats_basefee(self.all_transactions.pending_fees.base_fee);
 
         // Remove all transaction that were included in the block
         for tx_hash in &mined_transactions {
-            if self.prune_transaction_by_hash(tx_hash).is_some() {
+            if let Some(tx) = self.prune_transaction_by_hash(tx_hash) {
                 // Update removed transactions metric
                 self.metrics.removed_transactions.increment(1);
+                self.metrics
+                    .mined_transactions_time_in_pool
+                    .record(tx.timestamp.elapsed().as_secs_f64());
             }
         }
 
@@ -488,6 +603,9 @@ impl<T: TransactionOrdering> TxPool<T> {
                 self.add_new_transaction(transaction.clone(), replaced_tx.clone(), move_to);
                 // Update inserted transactions metric
                 self.metrics.inserted_transactions.increment(1);
+                if replaced_tx.is_some() {
+                    self.metrics.replaced_transactions.increment(1);
+                }
                 let UpdateOutcome { promoted, discarded } = self.process_updates(updates);
 
                 let replaced = replaced_tx.map(|(tx, _)| tx);
@@ -506,64 +624,46 @@ impl<T: TransactionOrdering> TxPool<T> {
 
                 // Update size metrics after adding and potentially moving transactions.
                 self.update_size_metrics();
+                self.on_mutation();
 
                 Ok(res)
             }
             Err(err) => {
                 // Update invalid transactions metric
                 self.metrics.invalid_transactions.increment(1);
-                match err {
-                    InsertErr::Underpriced { existing: _, transaction } => Err(PoolError::new(
-                        *transaction.hash(),
-                        PoolErrorKind::ReplacementUnderpriced,
-                    )),
-                    InsertErr::FeeCapBelowMinimumProtocolFeeCap { transaction, fee_cap } => {
-                        Err(PoolError::new(
-                            *transaction.hash(),
-                            PoolErrorKind::FeeCapBelowMinimumProtocolFeeCap(fee_cap),
-                        ))
-                    }
-                    InsertErr::ExceededSenderTransactionsCapacity { transaction } => {
-                        Err(PoolError::new(
-                            *transaction.hash(),
-                            PoolErrorKind::SpammerExceededCapacity(transaction.sender()),
-                        ))
-                    }
-                    InsertErr::TxGasLimitMoreThanAvailableBlockGas {
-                        transaction,
-                        block_gas_limit,
-                        tx_gas_limit,
-                    } => Err(PoolError::new(
-                        *transaction.hash(),
-                        PoolErrorKind::InvalidTransaction(
-                            InvalidPoolTransactionError::ExceedsGasLimit(
-                                block_gas_limit,
-                                tx_gas_limit,
-                            ),
-                        ),
-                    )),
-                    InsertErr::BlobTxHasNonceGap { transaction } => Err(PoolError::new(
-                        *transaction.hash(),
-                        PoolErrorKind::InvalidTransaction(
-                            Eip4844PoolTransactionError::Eip4844NonceGap.into(),
-                        ),
-                    )),
-                    InsertErr::Overdraft { transaction } => Err(PoolError::new(
-                        *transaction.hash(),
-                        PoolErrorKind::InvalidTransaction(InvalidPoolTransactionError::Overdraft),
-                    )),
-                    InsertErr::TxTypeConflict { transaction } => Err(PoolError::new(
-                        *transaction.hash(),
-                        PoolErrorKind::ExistingConflictingTransactionType(
-                            transaction.sender(),
-                            transaction.tx_type(),
-                        ),
-                    )),
-                }
+                Err(err.into_pool_error())
             }
         }
     }
 
+    /// Checks whether the transaction would be inserted by [`Self::add_transaction`], without
+    /// inserting it.
+    ///
+    /// This runs the same admission checks as an insert, including the replacement rules.
+    pub(crate) fn would_accept(
+        &self,
+        tx: ValidPoolTransaction<T::Transaction>,
+        on_chain_balance: U256,
+        on_chain_nonce: u64,
+    ) -> PoolResult<()> {
+        if self.contains(tx.hash()) {
+            return Err(PoolError::new(*tx.hash(), PoolErrorKind::AlreadyImported))
+        }
+        self.all_transactions
+            .check_insert(tx, on_chain_balance, on_chain_nonce)
+            .map(drop)
+            .map_err(InsertErr::into_pool_error)
+    }
+
+    /// Returns the minimum fees a replacement of the transaction must pay to be accepted by the
+    /// pool, see [`ReplacementSuggestion`].
+    pub(crate) fn suggest_replacement(
+        &self,
+        existing: &ValidPoolTransaction<T::Transaction>,
+    ) -> ReplacementSuggestion {
+        self.all_transactions.suggest_replacement(existing)
+    }
+
     /// Maintenance task to apply a series of updates.
     ///
     /// This will move/discard the given transaction according to the `PoolUpdate`
@@ -577,6 +677,7 @@ impl<T: TransactionOrdering> TxPool<T> {
                         outcome.discarded.push(tx);
                     }
                     self.metrics.removed_transactions.increment(1);
+                    self.metrics.discarded_transactions.increment(1);
                 }
                 Destination::Pool(move_to) => {
                     debug_assert_ne!(&move_to, &current, "destination must be different");
@@ -609,15 +710,21 @@ impl<T: TransactionOrdering> TxPool<T> {
 
     /// Removes and returns all matching transactions from the pool.
     ///
-    /// Note: this does not advance any descendants of the removed transactions and does not apply
-    /// any additional updates.
+    /// Note: this does not advance any descendants of the removed transactions. The removed
+    /// transactions leave a nonce gap, so their descendants are parked instead.
     pub(crate) fn remove_transactions(
         &mut self,
         hashes: Vec<TxHash>,
     ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
-        let txs =
+        let txs: Vec<_> =
             hashes.into_iter().filter_map(|hash| self.remove_transaction_by_hash(&hash)).collect();
+
+        let updates =
+            txs.iter().flat_map(|tx| self.all_transactions.park_descendants(tx.id())).collect();
+        self.process_updates(updates);
+
         self.update_size_metrics();
+        self.on_mutation();
         txs
     }
 
@@ -689,25 +796,21 @@ impl<T: TransactionOrdering> TxPool<T> {
 
     /// Removes _only_ the descendants of the given transaction from the __entire__ pool.
     ///
-    /// All removed transactions are added to the `removed` vec.
+    /// The descendants are removed highest nonce first, so that no remaining transaction is left
+    /// with a removed ancestor. All removed transactions are added to the `removed` vec.
     fn remove_descendants(
         &mut self,
         tx: &TransactionId,
         removed: &mut Vec<Arc<ValidPoolTransaction<T::Transaction>>>,
     ) {
-        let mut id = *tx;
-
-        // this will essentially pop _all_ descendant transactions one by one
-        loop {
-            let descendant =
-                self.all_transactions.descendant_txs_exclusive(&id).map(|(id, _)| *id).next();
-            if let Some(descendant) = descendant {
-                if let Some(tx) = self.remove_transaction(&descendant) {
-                    removed.push(tx)
-                }
-                id = descendant;
-            } else {
-                return
+        let descendants = self
+            .all_transactions
+            .descendant_txs_exclusive(tx)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        for id in descendants.into_iter().rev() {
+            if let Some(tx) = self.remove_transaction(&id) {
+                removed.push(tx)
             }
         }
     }
@@ -755,7 +858,13 @@ impl<T: TransactionOrdering> TxPool<T> {
     /// If the current size exceeds the given bounds, the worst transactions are evicted from the
     /// pool and returned.
     ///
-    /// This returns all transactions that were removed from the entire pool.
+    /// The sub-pools only evict transactions without descendants in the same sub-pool. Descendants
+    /// in other sub-pools are evicted together with the transaction, because they can't become
+    /// executable without it.
+    ///
+    /// This returns all transactions that were removed from the entire pool. Each evicted
+    /// transaction follows its evicted descendants, so the transactions of a sender are returned
+    /// highest nonce first.
     pub(crate) fn discard_worst(&mut self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
         let mut removed = Vec::new();
 
@@ -789,15 +898,15 @@ impl<T: TransactionOrdering> TxPool<T> {
 
                         // 2. remove all transactions from the total set
                         for tx in removed_from_subpool {
-                            $this.all_transactions.remove_transaction(tx.id());
-
                             let id = *tx.id();
 
-                            // keep track of removed transaction
-                            removed.push(tx);
-
-                            // 3. remove all its descendants from the entire pool
+                            // 3. remove all its descendants from the entire pool first
                             $this.remove_descendants(&id, &mut $removed);
+
+                            $this.all_transactions.remove_transaction(&id);
+
+                            // keep track of removed transaction
+                            $removed.push(tx);
                         }
                     }
 
@@ -814,6 +923,57 @@ impl<T: TransactionOrdering> TxPool<T> {
             ]
         );
 
+        self.metrics.discarded_transactions.increment(removed.len() as u64);
+        self.update_size_metrics();
+        self.on_mutation();
+        removed
+    }
+
+    /// Evicts the parked transactions that were added to the pool at least
+    /// [`PoolConfig::max_queued_lifetime`] before `now`.
+    ///
+    /// Pending transactions are never evicted by age, and local transactions only if local
+    /// exemptions are disabled. The descendants of an expired transaction are evicted together
+    /// with it, they are parked as well because they can't become executable without it.
+    ///
+    /// This returns all evicted transactions, the transactions of a sender highest nonce first.
+    pub(crate) fn evict_expired(
+        &mut self,
+        now: Instant,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let max_lifetime = self.config.max_queued_lifetime;
+        let local_transactions_config = &self.config.local_transactions_config;
+        let expired = self
+            .all_transactions
+            .txs
+            .iter()
+            .filter(|(_, tx)| {
+                !tx.subpool.is_pending() &&
+                    now.saturating_duration_since(tx.transaction.timestamp) >= max_lifetime &&
+                    !local_transactions_config
+                        .is_local(tx.transaction.origin, tx.transaction.sender())
+            })
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        let mut removed = Vec::new();
+        for id in expired {
+            // already evicted as the descendant of an expired transaction
+            if !self.all_transactions.txs.contains_key(&id) {
+                continue
+            }
+            self.remove_descendants(&id, &mut removed);
+            if let Some(tx) = self.remove_transaction(&id) {
+                removed.push(tx);
+            }
+        }
+
+        if !removed.is_empty() {
+            trace!(target: "txpool", evicted = removed.len(), "evicted expired transactions");
+            self.metrics.expired_transactions.increment(removed.len() as u64);
+            self.update_size_metrics();
+            self.on_mutation();
+        }
         removed
     }
 
@@ -827,14 +987,34 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.all_transactions.is_empty()
     }
 
+    /// Checks the invariants of the pool after it was mutated.
+    ///
+    /// In tests the invariants are checked after every mutation, in builds with debug assertions
+    /// after every [`PoolConfig::invariant_check_interval`]-th mutation.
+    fn on_mutation(&mut self) {
+        #[cfg(test)]
+        self.assert_invariants();
+
+        #[cfg(all(debug_assertions, not(test)))]
+        if self.config.invariant_check_interval > 0 {
+            self.mutations += 1;
+            if self.mutations % self.config.invariant_check_interval == 0 {
+                self.assert_invariants();
+            }
+        }
+    }
+
     /// Asserts all invariants of the  pool's:
     ///
     ///  - All maps are bijections (`by_id`, `by_hash`)
     ///  - Total size is equal to the sum of all sub-pools
+    ///  - The tracked sizes and counters match the transactions of each sub-pool
+    ///  - Every transaction is only in the sub-pool that's derived from its state
+    ///  - The pending transactions of a sender are gapless and follow pending transactions only
     ///
     /// # Panics
     /// if any invariant is violated
-    #[cfg(any(test, feature = "test-utils"))]
+    #[cfg(any(test, debug_assertions, feature = "test-utils"))]
     pub fn assert_invariants(&self) {
         let size = self.size();
         let actual = size.basefee + size.pending + size.queued + size.blob;
@@ -844,6 +1024,39 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.basefee_pool.assert_invariants();
         self.queued_pool.assert_invariants();
         self.blob_pool.assert_invariants();
+
+        let mut ancestor: Option<(&TransactionId, &PoolInternalTransaction<T::Transaction>)> = None;
+        for (id, tx) in &self.all_transactions.txs {
+            assert_eq!(
+                tx.subpool,
+                SubPool::from(tx.state),
+                "sub-pool of {id:?} doesn't match its state {:?}",
+                tx.state
+            );
+            let subpools = [SubPool::Pending, SubPool::BaseFee, SubPool::Queued, SubPool::Blob]
+                .into_iter()
+                .filter(|subpool| self.subpool_contains(*subpool, id))
+                .collect::<Vec<_>>();
+            assert_eq!(subpools, [tx.subpool], "{id:?} must only be in its sub-pool");
+
+            if tx.subpool.is_pending() {
+                // ancestors below the on chain nonce are about to be discarded
+                let ancestor = ancestor.filter(|(ancestor_id, _)| {
+                    ancestor_id.sender == id.sender &&
+                        self.sender_info
+                            .get(&id.sender)
+                            .map_or(true, |info| ancestor_id.nonce >= info.state_nonce)
+                });
+                if let Some((ancestor_id, ancestor)) = ancestor {
+                    assert!(
+                        ancestor_id.nonce + 1 == id.nonce && ancestor.subpool.is_pending(),
+                        "pending {id:?} follows {ancestor_id:?} in sub-pool {:?}",
+                        ancestor.subpool
+                    );
+                }
+            }
+            ancestor = Some((id, tx));
+        }
     }
 }
 
@@ -900,6 +1113,10 @@ pub(crate) struct AllTransactions<T: PoolTransaction> {
     max_account_slots: usize,
     /// _All_ transactions identified by their hash.
     by_hash: HashMap<TxHash, Arc<ValidPoolTransaction<T>>>,
+    /// Mirrors `by_hash` for lookups that don't hold the pool lock.
+    ///
+    /// Must be updated together with `by_hash`.
+    lookup: Arc<TransactionLookup<T>>,
     /// _All_ transaction in the pool sorted by their sender and nonce pair.
     txs: BTreeMap<TransactionId, PoolInternalTransaction<T>>,
     /// Tracks the number of transactions by sender that are currently in the pool.
@@ -1030,13 +1247,16 @@ impl<T: PoolTransaction> AllTransactions<T> {
         // The `unique_sender` loop will process the first transaction of all senders, update its
         // state and internally update all consecutive transactions
         'transactions: while let Some((id, tx)) = iter.next() {
-            macro_rules! next_sender {
+            macro_rules! park_sender {
                 ($iter:ident) => {
-                    'this: while let Some((peek, _)) = iter.peek() {
+                    // all remaining transactions of the sender are behind the nonce gap
+                    'this: while let Some((peek, ref mut tx)) = $iter.peek_mut() {
                         if peek.sender != id.sender {
                             break 'this
                         }
-                        iter.next();
+                        tx.state.remove(TxState::NO_NONCE_GAPS);
+                        Self::record_subpool_update(&mut updates, tx);
+                        $iter.next();
                     }
                 };
             }
@@ -1068,14 +1288,18 @@ impl<T: PoolTransaction> AllTransactions<T> {
                     } else {
                         tx.state.insert(TxState::ENOUGH_BALANCE);
                     }
+                } else {
+                    // the sender's next transaction is missing, e.g. after the nonce was reorged
+                    tx.state.remove(TxState::NO_NONCE_GAPS);
                 }
 
                 changed_balance = Some(info.balance);
             }
 
-            // If there's a nonce gap, we can shortcircuit, because there's nothing to update yet.
+            // If there's a nonce gap, we can shortcircuit, because there's nothing to promote yet.
             if tx.state.has_nonce_gap() {
-                next_sender!(iter);
+                Self::record_subpool_update(&mut updates, tx);
+                park_sender!(iter);
                 continue 'transactions
             }
 
@@ -1107,7 +1331,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
                     tx.state.insert(TxState::NO_NONCE_GAPS);
                 } else {
                     // can short circuit if there's still a nonce gap
-                    next_sender!(iter);
+                    park_sender!(iter);
                     continue 'transactions
                 }
 
@@ -1241,6 +1465,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
         tx_hash: &B256,
     ) -> Option<(Arc<ValidPoolTransaction<T>>, SubPool)> {
         let tx = self.by_hash.remove(tx_hash)?;
+        self.lookup.remove(tx_hash);
         let internal = self.txs.remove(&tx.transaction_id)?;
         // decrement the counter for the sender.
         self.tx_decr(tx.sender_id());
@@ -1262,6 +1487,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
         // decrement the counter for the sender.
         self.tx_decr(internal.transaction.sender_id());
 
+        self.lookup.remove(internal.transaction.hash());
         let result =
             self.by_hash.remove(internal.transaction.hash()).map(|tx| (tx, internal.subpool));
 
@@ -1270,6 +1496,19 @@ impl<T: PoolTransaction> AllTransactions<T> {
         result
     }
 
+    /// Parks all descendants of the given removed transaction, because the removed transaction
+    /// leaves a nonce gap.
+    ///
+    /// Returns the sub-pool updates for all descendants that were not parked yet.
+    pub(crate) fn park_descendants(&mut self, id: &TransactionId) -> Vec<PoolUpdate> {
+        let mut updates = Vec::new();
+        for (_, tx) in self.descendant_txs_mut(id) {
+            tx.state.remove(TxState::NO_NONCE_GAPS);
+            Self::record_subpool_update(&mut updates, tx);
+        }
+        updates
+    }
+
     /// Checks if the given transaction's type conflicts with an existing transaction.
     ///
     /// See also [`ValidPoolTransaction::tx_type_conflicts_with`].
@@ -1289,7 +1528,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
     ///
     /// This will enforce all additional rules in the context of this pool, such as:
     ///   - Spam protection: reject new non-local transaction from a sender that exhausted its slot
-    ///     capacity.
+    ///     capacity, unless it replaces one of the sender's transactions.
     ///   - Gas limit: reject transactions if they exceed a block's maximum gas.
     ///   - Ensures transaction types are not conflicting for the sender: blob vs normal
     ///     transactions are mutually exclusive for the same sender.
@@ -1297,7 +1536,9 @@ impl<T: PoolTransaction> AllTransactions<T> {
         &self,
         transaction: ValidPoolTransaction<T>,
     ) -> Result<ValidPoolTransaction<T>, InsertErr<T>> {
-        if !self.local_transactions_config.is_local(transaction.origin, transaction.sender()) {
+        if !self.local_transactions_config.is_local(transaction.origin, transaction.sender()) &&
+            !self.txs.contains_key(transaction.id())
+        {
             let current_txs =
                 self.tx_counter.get(&transaction.sender_id()).copied().unwrap_or_default();
             if current_txs >= self.max_account_slots {
@@ -1391,7 +1632,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
         let price_bump = price_bumps.price_bump(existing_transaction.tx_type());
 
         if maybe_replacement.max_fee_per_gas() <=
-            existing_transaction.max_fee_per_gas() * (100 + price_bump) / 100
+            bumped_fee(existing_transaction.max_fee_per_gas(), price_bump)
         {
             return true
         }
@@ -1402,7 +1643,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
             maybe_replacement.transaction.max_priority_fee_per_gas().unwrap_or(0);
 
         if replacement_max_priority_fee_per_gas <=
-            existing_max_priority_fee_per_gas * (100 + price_bump) / 100 &&
+            bumped_fee(existing_max_priority_fee_per_gas, price_bump) &&
             existing_max_priority_fee_per_gas != 0 &&
             replacement_max_priority_fee_per_gas != 0
         {
@@ -1417,7 +1658,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
             let replacement_max_blob_fee_per_gas =
                 maybe_replacement.transaction.max_fee_per_blob_gas().unwrap_or(0);
             if replacement_max_blob_fee_per_gas <=
-                existing_max_blob_fee_per_gas * (100 + price_bump) / 100
+                bumped_fee(existing_max_blob_fee_per_gas, price_bump)
             {
                 return true
             }
@@ -1426,6 +1667,96 @@ impl<T: PoolTransaction> AllTransactions<T> {
         false
     }
 
+    /// Returns the minimum fees a replacement of the existing transaction must pay to pass
+    /// [`Self::is_underpriced`] and to remain executable if the base fee rises over the next
+    /// [`REPLACEMENT_BASE_FEE_PROJECTION_BLOCKS`] blocks.
+    pub(crate) fn suggest_replacement(
+        &self,
+        existing_transaction: &ValidPoolTransaction<T>,
+    ) -> ReplacementSuggestion {
+        let price_bump = self.price_bumps.price_bump(existing_transaction.tx_type());
+
+        let max_priority_fee_per_gas = existing_transaction
+            .transaction
+            .max_priority_fee_per_gas()
+            .map(|fee| bumped_fee(fee, price_bump) + 1);
+        let max_fee_per_blob_gas = existing_transaction
+            .transaction
+            .max_fee_per_blob_gas()
+            .map(|fee| bumped_fee(fee, price_bump) + 1);
+
+        // assume full blocks, which raise the base fee by the maximum amount
+        let gas_limit = self.block_gas_limit as u128;
+        let mut projected_base_fee = self.pending_fees.base_fee as u128;
+        for _ in 0..REPLACEMENT_BASE_FEE_PROJECTION_BLOCKS {
+            projected_base_fee = BaseFeeParams::ethereum().next_block_base_fee(
+                gas_limit,
+                gas_limit,
+                projected_base_fee,
+            );
+        }
+        let max_fee_per_gas = (bumped_fee(existing_transaction.max_fee_per_gas(), price_bump) + 1)
+            .max(projected_base_fee + max_priority_fee_per_gas.unwrap_or_default());
+
+        ReplacementSuggestion {
+            hash: *existing_transaction.hash(),
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            max_fee_per_blob_gas,
+        }
+    }
+
+    /// Runs the checks of [`Self::insert_tx`] that can reject the transaction, without inserting
+    /// it.
+    ///
+    /// Returns the transaction and the identifier of its ancestor, if it's not the next
+    /// transaction of the sender.
+    pub(crate) fn check_insert(
+        &self,
+        transaction: ValidPoolTransaction<T>,
+        on_chain_balance: U256,
+        on_chain_nonce: u64,
+    ) -> Result<(ValidPoolTransaction<T>, Option<TransactionId>), InsertErr<T>> {
+        let mut transaction = self.ensure_valid(transaction)?;
+
+        // identifier of the ancestor transaction, will be None if the transaction is the next tx of
+        // the sender
+        let ancestor = TransactionId::ancestor(
+            transaction.transaction.nonce(),
+            on_chain_nonce,
+            transaction.transaction_id.sender,
+        );
+
+        // before attempting to insert a blob transaction, we need to ensure that additional
+        // constraints are met that only apply to blob transactions
+        if transaction.is_eip4844() {
+            transaction =
+                self.ensure_valid_blob_transaction(transaction, on_chain_balance, ancestor)?;
+        }
+
+        // Check dynamic fee
+        let fee_cap = transaction.max_fee_per_gas();
+        if fee_cap < self.minimal_protocol_basefee as u128 {
+            return Err(InsertErr::FeeCapBelowMinimumProtocolFeeCap {
+                transaction: Arc::new(transaction),
+                fee_cap,
+            })
+        }
+
+        // Transaction with the same nonce already exists: replacement candidate, which must not be
+        // underpriced
+        if let Some(existing) = self.txs.get(transaction.id()) {
+            if Self::is_underpriced(&existing.transaction, &transaction, &self.price_bumps) {
+                return Err(InsertErr::Underpriced {
+                    existing: *existing.transaction.hash(),
+                    transaction: Arc::new(transaction),
+                })
+            }
+        }
+
+        Ok((transaction, ancestor))
+    }
+
     /// Inserts a new _valid_ transaction into the pool.
     ///
     /// If the transaction already exists, it will be replaced if not underpriced.
@@ -1465,7 +1796,8 @@ impl<T: PoolTransaction> AllTransactions<T> {
     ) -> InsertResult<T> {
         assert!(on_chain_nonce <= transaction.nonce(), "Invalid transaction");
 
-        let mut transaction = self.ensure_valid(transaction)?;
+        let (transaction, ancestor) =
+            self.check_insert(transaction, on_chain_balance, on_chain_nonce)?;
 
         let inserted_tx_id = *transaction.id();
         let mut state = TxState::default();
@@ -1475,21 +1807,9 @@ impl<T: PoolTransaction> AllTransactions<T> {
         // Current tx does not exceed block gas limit after ensure_valid check
         state.insert(TxState::NOT_TOO_MUCH_GAS);
 
-        // identifier of the ancestor transaction, will be None if the transaction is the next tx of
-        // the sender
-        let ancestor = TransactionId::ancestor(
-            transaction.transaction.nonce(),
-            on_chain_nonce,
-            inserted_tx_id.sender,
-        );
-
-        // before attempting to insert a blob transaction, we need to ensure that additional
-        // constraints are met that only apply to blob transactions
         if transaction.is_eip4844() {
             state.insert(TxState::BLOB_TRANSACTION);
 
-            transaction =
-                self.ensure_valid_blob_transaction(transaction, on_chain_balance, ancestor)?;
             let blob_fee_cap = transaction.transaction.max_fee_per_blob_gas().unwrap_or_default();
             if blob_fee_cap >= self.pending_fees.blob_fee {
                 state.insert(TxState::ENOUGH_BLOB_FEE_CAP_BLOCK);
@@ -1507,13 +1827,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
             state.insert(TxState::NO_PARKED_ANCESTORS);
         }
 
-        // Check dynamic fee
-        let fee_cap = transaction.max_fee_per_gas();
-
-        if fee_cap < self.minimal_protocol_basefee as u128 {
-            return Err(InsertErr::FeeCapBelowMinimumProtocolFeeCap { transaction, fee_cap })
-        }
-        if fee_cap >= self.pending_fees.base_fee as u128 {
+        if transaction.max_fee_per_gas() >= self.pending_fees.base_fee as u128 {
             state.insert(TxState::ENOUGH_FEE_CAP_BLOCK);
         }
 
@@ -1532,25 +1846,18 @@ impl<T: PoolTransaction> AllTransactions<T> {
             Entry::Vacant(entry) => {
                 // Insert the transaction in both maps
                 self.by_hash.insert(*pool_tx.transaction.hash(), pool_tx.transaction.clone());
+                self.lookup.insert(pool_tx.transaction.clone());
                 entry.insert(pool_tx);
             }
             Entry::Occupied(mut entry) => {
-                // Transaction with the same nonce already exists: replacement candidate
-                let existing_transaction = entry.get().transaction.as_ref();
-                let maybe_replacement = transaction.as_ref();
-
-                // Ensure the new transaction is not underpriced
-                if Self::is_underpriced(existing_transaction, maybe_replacement, &self.price_bumps)
-                {
-                    return Err(InsertErr::Underpriced {
-                        transaction: pool_tx.transaction,
-                        existing: *entry.get().transaction.hash(),
-                    })
-                }
+                // Transaction with the same nonce already exists: the replacement was checked not
+                // to be underpriced
                 let new_hash = *pool_tx.transaction.hash();
                 let new_transaction = pool_tx.transaction.clone();
                 let replaced = entry.insert(pool_tx);
                 self.by_hash.remove(replaced.transaction.hash());
+                self.lookup.remove(replaced.transaction.hash());
+                self.lookup.insert(new_transaction.clone());
                 self.by_hash.insert(new_hash, new_transaction);
                 // also remove the hash
                 replaced_tx = Some((replaced.transaction, replaced.subpool));
@@ -1657,10 +1964,32 @@ impl<T: PoolTransaction> AllTransactions<T> {
         self.txs.is_empty()
     }
 
-    /// Asserts that the bijection between `by_hash` and `txs` is valid.
-    #[cfg(any(test, feature = "test-utils"))]
+    /// Asserts that the bijection between `by_hash` and `txs` is valid, that the lookup mirrors
+    /// `by_hash` and that the transaction counter matches the transactions of each sender.
+    #[cfg(any(test, debug_assertions, feature = "test-utils"))]
     pub(crate) fn assert_invariants(&self) {
         assert_eq!(self.by_hash.len(), self.txs.len(), "by_hash.len() != txs.len()");
+        assert_same_ids(
+            "transactions by hash",
+            self.by_hash.values().map(|tx| *tx.id()),
+            self.txs.values().map(|tx| *tx.transaction.id()),
+        );
+        let mut lookup_hashes = self.lookup.hashes();
+        let mut hashes = self.by_hash.keys().copied().collect::<Vec<_>>();
+        lookup_hashes.sort_unstable();
+        hashes.sort_unstable();
+        assert_eq!(lookup_hashes, hashes, "lookup hashes != by_hash hashes");
+        assert_same_ids(
+            "transactions by id",
+            self.txs.values().map(|tx| *tx.transaction.id()),
+            self.txs.keys().copied(),
+        );
+
+        let mut tx_counter = FxHashMap::<SenderId, usize>::default();
+        for id in self.txs.keys() {
+            *tx_counter.entry(id.sender).or_default() += 1;
+        }
+        assert_eq!(self.tx_counter, tx_counter, "tracked transaction counts per sender");
     }
 }
 
@@ -1681,6 +2010,7 @@ impl<T: PoolTransaction> Default for AllTransactions<T> {
             minimal_protocol_basefee: MIN_PROTOCOL_BASE_FEE,
             block_gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
             by_hash: Default::default(),
+            lookup: Default::default(),
             txs: Default::default(),
             tx_counter: Default::default(),
             last_seen_block_number: Default::default(),
@@ -1708,6 +2038,15 @@ impl Default for PendingFees {
     }
 }
 
+/// Number of blocks of maximum base fee increase a [`ReplacementSuggestion`] accounts for.
+pub(crate) const REPLACEMENT_BASE_FEE_PROJECTION_BLOCKS: usize = 3;
+
+/// Returns the fee a replacement must exceed to replace a transaction paying `fee`.
+#[inline]
+const fn bumped_fee(fee: u128, price_bump: u128) -> u128 {
+    fee * (100 + price_bump) / 100
+}
+
 /// Result type for inserting a transaction
 pub(crate) type InsertResult<T> = Result<InsertOk<T>, InsertErr<T>>;
 
@@ -1743,6 +2082,53 @@ pub(crate) enum InsertErr<T: PoolTransaction> {
     TxTypeConflict { transaction: Arc<ValidPoolTransaction<T>> },
 }
 
+impl<T: PoolTransaction> InsertErr<T> {
+    /// Converts the error into the [`PoolError`] returned to the caller of the pool.
+    pub(crate) fn into_pool_error(self) -> PoolError {
+        match self {
+            Self::Underpriced { existing: _, transaction } => {
+                PoolError::new(*transaction.hash(), PoolErrorKind::ReplacementUnderpriced)
+            }
+            Self::FeeCapBelowMinimumProtocolFeeCap { transaction, fee_cap } => PoolError::new(
+                *transaction.hash(),
+                PoolErrorKind::FeeCapBelowMinimumProtocolFeeCap(fee_cap),
+            ),
+            Self::ExceededSenderTransactionsCapacity { transaction } => PoolError::new(
+                *transaction.hash(),
+                PoolErrorKind::SpammerExceededCapacity(transaction.sender()),
+            ),
+            Self::TxGasLimitMoreThanAvailableBlockGas {
+                transaction,
+                block_gas_limit,
+                tx_gas_limit,
+            } => PoolError::new(
+                *transaction.hash(),
+                PoolErrorKind::InvalidTransaction(InvalidPoolTransactionError::ExceedsGasLimit(
+                    block_gas_limit,
+                    tx_gas_limit,
+                )),
+            ),
+            Self::BlobTxHasNonceGap { transaction } => PoolError::new(
+                *transaction.hash(),
+                PoolErrorKind::InvalidTransaction(
+                    Eip4844PoolTransactionError::Eip4844NonceGap.into(),
+                ),
+            ),
+            Self::Overdraft { transaction } => PoolError::new(
+                *transaction.hash(),
+                PoolErrorKind::InvalidTransaction(InvalidPoolTransactionError::Overdraft),
+            ),
+            Self::TxTypeConflict { transaction } => PoolError::new(
+                *transaction.hash(),
+                PoolErrorKind::ExistingConflictingTransactionType(
+                    transaction.sender(),
+                    transaction.tx_type(),
+                ),
+            ),
+        }
+    }
+}
+
 /// Transaction was successfully inserted into the pool
 #[derive(Debug)]
 pub(crate) struct InsertOk<T: PoolTransaction> {
@@ -1847,6 +2233,27 @@ impl SenderInfo {
     }
 }
 
+/// Asserts that the given `actual` transaction ids are the `expected` ones.
+///
+/// # Panics
+///
+/// If the ids differ, listing the missing and unexpected ids.
+#[cfg(any(test, debug_assertions, feature = "test-utils"))]
+#[track_caller]
+pub(crate) fn assert_same_ids(
+    what: &str,
+    actual: impl IntoIterator<Item = TransactionId>,
+    expected: impl IntoIterator<Item = TransactionId>,
+) {
+    let actual = actual.into_iter().collect::<std::collections::BTreeSet<_>>();
+    let expected = expected.into_iter().collect::<std::collections::BTreeSet<_>>();
+    if actual != expected {
+        let missing = expected.difference(&actual).collect::<Vec<_>>();
+        let unexpected = actual.difference(&expected).collect::<Vec<_>>();
+        panic!("{what} out of sync: missing {missing:?}, unexpected {unexpected:?}");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use reth_primitives::{address, TxType};
@@ -1857,6 +2264,7 @@ mod tests {
         traits::TransactionOrigin,
         SubPoolLimit,
     };
+    use proptest::prelude::*;
 
     #[test]
     fn test_insert_blob() {
@@ -2007,6 +2415,44 @@ mod tests {
         assert!(pool.blob_pool.is_empty());
     }
 
+    #[test]
+    fn best_with_higher_blob_fee_skips_underpriced_blob_tx() {
+        let on_chain_balance = U256::MAX;
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let blob_tx = MockTransaction::eip4844().inc_price().inc_limit();
+        let tx = MockTransaction::eip1559().inc_price().inc_limit();
+        let blob_validated = f.validated(blob_tx.clone());
+        let validated = f.validated(tx);
+        pool.add_transaction(blob_validated.clone(), on_chain_balance, on_chain_nonce).unwrap();
+        pool.add_transaction(validated.clone(), on_chain_balance, on_chain_nonce).unwrap();
+        assert_eq!(pool.pending_pool.len(), 2);
+
+        // the base fee is unchanged, but the blob fee exceeds the blob fee cap of the blob tx
+        let basefee = pool.all_transactions.pending_fees.base_fee;
+        let blob_fee = blob_tx.max_fee_per_blob_gas().unwrap() as u64 + 1;
+        let best = pool
+            .best_transactions_with_attributes(BestTransactionsAttributes::new(
+                basefee,
+                Some(blob_fee),
+            ))
+            .map(|tx| *tx.hash())
+            .collect::<Vec<_>>();
+        assert_eq!(best, vec![*validated.hash()]);
+
+        // the blob tx is still included at the tracked blob fee
+        let blob_fee = pool.all_transactions.pending_fees.blob_fee as u64;
+        let best = pool
+            .best_transactions_with_attributes(BestTransactionsAttributes::new(
+                basefee,
+                Some(blob_fee),
+            ))
+            .count();
+        assert_eq!(best, 2);
+    }
+
     /// A struct representing a txpool promotion test instance
     #[derive(Debug, PartialEq, Eq, Clone, Hash)]
     struct PromotionTest {
@@ -2395,6 +2841,91 @@ mod tests {
         assert_eq!(pool.len(), 1);
     }
 
+    #[test]
+    fn replace_with_configured_price_bump() {
+        let on_chain_balance = U256::from(u64::MAX);
+        let mut f = MockTransactionFactory::default();
+        let config = PoolConfig {
+            price_bumps: PriceBumpConfig { default_price_bump: 20, ..Default::default() },
+            ..Default::default()
+        };
+        let mut pool = TxPool::new(MockOrdering::default(), config);
+
+        let tx = MockTransaction::eip1559().with_max_fee(100).with_priority_fee(100);
+        let first = f.validated(tx.clone());
+        pool.add_transaction(first.clone(), on_chain_balance, 0).unwrap();
+
+        // a bump of exactly the configured 20% is not enough
+        let replacement = tx.rng_hash().with_max_fee(120).with_priority_fee(120);
+        let err = pool
+            .add_transaction(f.validated(replacement.clone()), on_chain_balance, 0)
+            .unwrap_err();
+        assert_eq!(err.hash, *replacement.hash());
+        assert!(matches!(err.kind, PoolErrorKind::ReplacementUnderpriced));
+        assert!(pool.contains(first.hash()));
+
+        let replacement = replacement.rng_hash().with_max_fee(121).with_priority_fee(121);
+        let added =
+            pool.add_transaction(f.validated(replacement.clone()), on_chain_balance, 0).unwrap();
+        assert_eq!(added.replaced().map(|tx| *tx.hash()), Some(*first.hash()));
+        assert!(!pool.contains(first.hash()));
+        assert!(pool.contains(replacement.hash()));
+        assert_eq!(pool.len(), 1);
+    }
+
+    #[test]
+    fn replace_parked_transaction_promotes_descendants() {
+        let on_chain_balance = U256::from(u64::MAX);
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+        pool.update_basefee(100);
+
+        // the fee cap of the first transaction is below the basefee, which parks its descendant
+        let tx = MockTransaction::eip1559().with_max_fee(50).with_priority_fee(10);
+        let first = f.validated(tx.clone());
+        let descendant = f.validated(tx.next().with_max_fee(200).with_priority_fee(20));
+        pool.add_transaction(first.clone(), on_chain_balance, 0).unwrap();
+        pool.add_transaction(descendant.clone(), on_chain_balance, 0).unwrap();
+        assert!(pool.subpool_contains(SubPool::BaseFee, first.id()));
+        assert!(!pool.subpool_contains(SubPool::Pending, descendant.id()));
+
+        let replacement = f.validated(tx.rng_hash().with_max_fee(200).with_priority_fee(20));
+        let added = pool.add_transaction(replacement.clone(), on_chain_balance, 0).unwrap();
+        assert_eq!(added.replaced().map(|tx| *tx.hash()), Some(*first.hash()));
+
+        // the replaced transaction is removed from its subpool and the descendant is promoted
+        assert!(!pool.contains(first.hash()));
+        assert!(pool.basefee_pool.is_empty());
+        assert!(pool.subpool_contains(SubPool::Pending, replacement.id()));
+        assert!(pool.subpool_contains(SubPool::Pending, descendant.id()));
+        assert_eq!(pool.pending_pool.len(), 2);
+    }
+
+    #[test]
+    fn replace_pending_transaction() {
+        let on_chain_balance = U256::from(u64::MAX);
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let tx = MockTransaction::eip1559().with_max_fee(100).with_priority_fee(10);
+        let first = f.validated(tx.clone());
+        let descendant = f.validated(tx.next());
+        pool.add_transaction(first.clone(), on_chain_balance, 0).unwrap();
+        pool.add_transaction(descendant.clone(), on_chain_balance, 0).unwrap();
+        assert_eq!(pool.pending_pool.len(), 2);
+
+        let replacement = f.validated(tx.rng_hash().with_max_fee(200).with_priority_fee(20));
+        let added = pool.add_transaction(replacement.clone(), on_chain_balance, 0).unwrap();
+        assert!(matches!(added, AddedTransaction::Pending(_)));
+        assert_eq!(added.replaced().map(|tx| *tx.hash()), Some(*first.hash()));
+
+        assert!(!pool.contains(first.hash()));
+        assert!(pool.subpool_contains(SubPool::Pending, replacement.id()));
+        assert!(pool.subpool_contains(SubPool::Pending, descendant.id()));
+        assert_eq!(pool.pending_pool.len(), 2);
+        assert_eq!(pool.len(), 2);
+    }
+
     #[test]
     fn insert_conflicting_type_normal_to_blob() {
         let on_chain_balance = U256::from(10_000);
@@ -2547,6 +3078,33 @@ mod tests {
         assert!(matches!(err, InsertErr::ExceededSenderTransactionsCapacity { .. }));
     }
 
+    #[test]
+    fn allows_replacement_at_capacity() {
+        let on_chain_balance = U256::from(1_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = AllTransactions::default();
+
+        let mut tx = MockTransaction::eip1559();
+        for _ in 0..pool.max_account_slots {
+            tx = tx.next();
+            pool.insert_tx(f.validated(tx.clone()), on_chain_balance, on_chain_nonce).unwrap();
+        }
+        let sender = f.ids.sender_id(&tx.get_sender()).unwrap();
+
+        let err =
+            pool.insert_tx(f.validated(tx.next()), on_chain_balance, on_chain_nonce).unwrap_err();
+        assert!(matches!(err, InsertErr::ExceededSenderTransactionsCapacity { .. }));
+
+        // replacing an existing nonce doesn't take up another slot
+        let replacement = f.validated(tx.rng_hash().inc_price());
+        let InsertOk { replaced_tx, .. } =
+            pool.insert_tx(replacement.clone(), on_chain_balance, on_chain_nonce).unwrap();
+        assert!(replaced_tx.is_some());
+        assert!(pool.contains(replacement.hash()));
+        assert_eq!(pool.max_account_slots, pool.tx_count(sender));
+    }
+
     #[test]
     fn allow_local_spamming() {
         let on_chain_balance = U256::from(1_000);
@@ -2650,6 +3208,87 @@ mod tests {
         assert_eq!(pool.all_transactions.txs.get(&id).unwrap().subpool, SubPool::BaseFee)
     }
 
+    /// Lowers the pending basefee to `to` and asserts that exactly the transactions of the basefee
+    /// subpool with a fee cap of at least `to` are promoted to the pending subpool.
+    fn promote_if_basefee_drops<T: TransactionOrdering>(pool: &mut TxPool<T>, to: u64) {
+        assert!(to <= pool.all_transactions.pending_fees.base_fee, "basefee doesn't drop");
+        let (promoted, parked): (Vec<_>, Vec<_>) =
+            pool.basefee_pool.all().partition(|tx| tx.max_fee_per_gas() >= to as u128);
+
+        pool.update_basefee(to);
+
+        for tx in promoted {
+            assert!(pool.subpool_contains(SubPool::Pending, tx.id()), "{tx:?} not promoted");
+            assert_eq!(pool.all_transactions.txs[tx.id()].subpool, SubPool::Pending);
+        }
+        for tx in parked {
+            assert!(pool.subpool_contains(SubPool::BaseFee, tx.id()), "{tx:?} promoted");
+        }
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn basefee_pool_snapshot_across_basefee_changes() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+        pool.update_basefee(100);
+
+        let fee_caps = [20, 40, 60, 80, 95, 100, 150];
+        for (idx, fee_cap) in fee_caps.into_iter().enumerate() {
+            let tx = MockTransaction::eip1559()
+                .with_max_fee(fee_cap)
+                .with_priority_fee(1)
+                .with_gas_limit(21_000 + idx as u64);
+            pool.add_transaction(f.validated(tx), U256::MAX, 0).unwrap();
+        }
+        let fee_caps_of = |txs: Vec<Arc<ValidPoolTransaction<MockTransaction>>>| {
+            txs.iter().map(|tx| tx.max_fee_per_gas()).collect::<Vec<_>>()
+        };
+
+        // everything below the basefee of 100 is priced out
+        assert_eq!(pool.pending_pool.len(), 2);
+        assert_eq!(fee_caps_of(pool.basefee_pool_snapshot(3)), vec![95, 80, 60]);
+        assert_eq!(fee_caps_of(pool.basefee_pool_snapshot(usize::MAX)), vec![95, 80, 60, 40, 20]);
+        assert_eq!(
+            pool.basefee_pool_stats(),
+            BasefeePoolStats {
+                count: 5,
+                total_gas: 5 * 21_000 + 10,
+                fee_cap_histogram: [1, 1, 1, 1, 1],
+            }
+        );
+
+        // a higher basefee prices out the pending transactions too
+        pool.update_basefee(200);
+        assert!(pool.pending_pool.is_empty());
+        assert_eq!(fee_caps_of(pool.basefee_pool_snapshot(2)), vec![150, 100]);
+        assert_eq!(
+            pool.basefee_pool_stats(),
+            BasefeePoolStats {
+                count: 7,
+                total_gas: 7 * 21_000 + 21,
+                fee_cap_histogram: [2, 3, 1, 1, 0],
+            }
+        );
+
+        // the basefee of a new block rebuckets the fee caps too
+        let block_info = BlockInfo { pending_basefee: 400, ..pool.block_info() };
+        pool.on_canonical_state_change(block_info, Vec::new(), HashMap::default());
+        assert_eq!(pool.size().basefee_stats.fee_cap_histogram, [5, 2, 0, 0, 0]);
+        pool.assert_invariants();
+
+        promote_if_basefee_drops(&mut pool, 80);
+        assert_eq!(pool.pending_pool.len(), 4);
+        assert_eq!(fee_caps_of(pool.basefee_pool_snapshot(usize::MAX)), vec![60, 40, 20]);
+        assert_eq!(pool.basefee_pool_stats().fee_cap_histogram, [0, 1, 1, 1, 0]);
+
+        promote_if_basefee_drops(&mut pool, 60);
+        promote_if_basefee_drops(&mut pool, 59);
+        promote_if_basefee_drops(&mut pool, 20);
+        assert!(pool.basefee_pool_snapshot(usize::MAX).is_empty());
+        assert_eq!(pool.basefee_pool_stats(), BasefeePoolStats::default());
+    }
+
     #[test]
     fn get_highest_transaction_by_sender_and_nonce() {
         // Set up a mock transaction factory and a new transaction pool.
@@ -2682,6 +3321,57 @@ mod tests {
         assert_eq!(highest_tx.as_ref().transaction, tx1);
     }
 
+    #[test]
+    fn next_pending_nonce_counts_replacements_once() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let tx = MockTransaction::eip1559();
+        let sender = f.ids.sender_id_or_create(tx.sender());
+        assert_eq!(pool.next_pending_nonce(sender, 0), 0);
+
+        let next = tx.next();
+        pool.add_transaction(f.validated(tx.clone()), U256::from(1_000), 0).unwrap();
+        pool.add_transaction(f.validated(next.clone()), U256::from(1_000), 0).unwrap();
+        let replacement = next.rng_hash().inc_price();
+        pool.add_transaction(f.validated(replacement), U256::from(1_000), 0).unwrap();
+        assert_eq!(pool.next_pending_nonce(sender, 0), 2);
+        assert_eq!(pool.get_pending_transactions_by_sender(sender).len(), 2);
+
+        // the pool may not have seen the block that included the first transaction yet
+        assert_eq!(pool.next_pending_nonce(sender, 1), 2);
+        assert_eq!(pool.next_pending_nonce(sender, 5), 5);
+    }
+
+    #[test]
+    fn next_pending_nonce_stops_at_gap() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        // the sender only has queued transactions, with a gap right at the on-chain nonce
+        let tx = MockTransaction::eip1559().with_nonce(1);
+        let sender = f.ids.sender_id_or_create(tx.sender());
+        pool.add_transaction(f.validated(tx.clone()), U256::from(1_000), 0).unwrap();
+        pool.add_transaction(f.validated(tx.next()), U256::from(1_000), 0).unwrap();
+        assert!(pool.get_pending_transactions_by_sender(sender).is_empty());
+        assert_eq!(pool.next_pending_nonce(sender, 0), 0);
+
+        // filling the gap makes the whole sequence pending
+        let first = tx.clone().with_nonce(0).rng_hash();
+        pool.add_transaction(f.validated(first), U256::from(1_000), 0).unwrap();
+        assert_eq!(pool.next_pending_nonce(sender, 0), 3);
+
+        // transactions after a later gap are ignored
+        pool.add_transaction(f.validated(tx.with_nonce(4).rng_hash()), U256::from(1_000), 0)
+            .unwrap();
+        assert_eq!(pool.next_pending_nonce(sender, 0), 3);
+        assert_eq!(pool.get_pending_transactions_by_sender(sender).len(), 3);
+        assert_eq!(
+            pool.pending_sender_info(sender, 0),
+            PendingSenderInfo { next_nonce: 3, pending_transactions: 3 }
+        );
+    }
+
     #[test]
     fn discard_nonce_too_low() {
         let mut f = MockTransactionFactory::default();
@@ -2848,6 +3538,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn discard_worst_keeps_ancestors_of_pending_txs() {
+        let mut f = MockTransactionFactory::default();
+        let pending_limit = SubPoolLimit::new(2, usize::MAX);
+        let mut pool = TxPool::new(
+            MockOrdering::default(),
+            PoolConfig { pending_limit, ..Default::default() },
+        );
+
+        // a cheap parent with an expensive child, and a single transaction of another sender
+        let parent = MockTransaction::eip1559();
+        let child = parent.next().inc_price_by(10);
+        let other = MockTransaction::eip1559().inc_price_by(20);
+        for tx in [&parent, &child, &other] {
+            pool.add_transaction(f.validated(tx.clone()), U256::from(1_000), 0).unwrap();
+        }
+        assert_eq!(pool.size().pending, 3);
+
+        let removed = pool.discard_worst().iter().map(|tx| *tx.hash()).collect::<Vec<_>>();
+        assert_eq!(removed, vec![child.get_hash()]);
+        assert!(pool.contains(&parent.get_hash()));
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn discard_worst_keeps_ancestors_of_blob_txs() {
+        let mut f = MockTransactionFactory::default();
+        let blob_limit = SubPoolLimit::new(2, usize::MAX);
+        let mut pool =
+            TxPool::new(MockOrdering::default(), PoolConfig { blob_limit, ..Default::default() });
+        pool.all_transactions.pending_fees.blob_fee = 10000;
+
+        // the parent takes the most blob fee jumps, but is needed by its child
+        let parent = MockTransaction::eip4844().inc_price_by(100).with_blob_fee(100);
+        let child = parent.next().with_blob_fee(1000);
+        let other = MockTransaction::eip4844().inc_price_by(100).with_blob_fee(2000);
+        for tx in [&parent, &child, &other] {
+            pool.add_transaction(f.validated(tx.clone()), U256::from(1_000), 0).unwrap();
+        }
+        assert_eq!(pool.size().blob, 3);
+
+        let removed = pool.discard_worst().iter().map(|tx| *tx.hash()).collect::<Vec<_>>();
+        assert_eq!(removed, vec![child.get_hash()]);
+        assert!(pool.contains(&parent.get_hash()));
+        pool.assert_invariants();
+    }
+
     #[test]
     fn account_updates_nonce_gap() {
         let on_chain_balance = U256::from(10_000);
@@ -2982,4 +3719,239 @@ mod tests {
             vec![1, 2, 3]
         );
     }
+
+    #[test]
+    fn remove_transactions_parks_descendants() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let tx_0 = MockTransaction::eip1559().inc_price_by(10);
+        let tx_1 = tx_0.next();
+        let tx_2 = tx_1.next();
+        for tx in [&tx_0, &tx_1, &tx_2] {
+            pool.add_transaction(f.validated(tx.clone()), U256::from(1_000), 0).unwrap();
+        }
+        assert_eq!(pool.pending_pool.len(), 3);
+
+        // removing the transaction in the middle leaves a nonce gap
+        let removed = pool.remove_transactions(vec![tx_1.get_hash()]);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(pool.pending_pool.len(), 1);
+        assert_eq!(pool.queued_pool.len(), 1);
+        assert_eq!(pool.best_transactions().map(|tx| tx.id().nonce).collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    fn account_update_with_lower_nonce_parks_sender() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let tx_1 = MockTransaction::eip1559().inc_price_by(10).with_nonce(1);
+        let tx_2 = tx_1.next();
+        for tx in [&tx_1, &tx_2] {
+            pool.add_transaction(f.validated(tx.clone()), U256::from(1_000), 1).unwrap();
+        }
+        assert_eq!(pool.pending_pool.len(), 2);
+
+        // a reorg drops the transaction with nonce 0, so the sender's transactions are gapped
+        let sender = f.tx_id(&tx_1).sender;
+        let info = SenderInfo { state_nonce: 0, balance: U256::from(1_000) };
+        let outcome = pool.update_accounts(HashMap::from([(sender, info)]));
+        assert!(outcome.discarded.is_empty());
+        assert!(pool.pending_pool.is_empty());
+        assert_eq!(pool.queued_pool.len(), 2);
+    }
+
+    #[test]
+    fn canonical_state_change_promotes_queued_descendant() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        // the balance of the sender only covers the first transaction
+        let tx = MockTransaction::eip1559().inc_price_by(10);
+        let mined = f.validated(tx.clone());
+        let next = f.validated(tx.next());
+        let balance = mined.transaction.cost() + next.transaction.cost() - U256::from(1);
+        pool.add_transaction(mined.clone(), balance, 0).unwrap();
+        pool.add_transaction(next.clone(), balance, 0).unwrap();
+        assert!(pool.subpool_contains(SubPool::Pending, mined.id()));
+        assert!(pool.subpool_contains(SubPool::Queued, next.id()));
+
+        // the pending transaction of another sender can no longer be afforded after the block
+        let other = f.validated(MockTransaction::eip1559().inc_price_by(10));
+        pool.add_transaction(other.clone(), U256::from(u64::MAX), 0).unwrap();
+        assert!(pool.subpool_contains(SubPool::Pending, other.id()));
+
+        // the block mines the first transaction, which spent less than its maximum cost
+        let block_info = BlockInfo { last_seen_block_number: 1, ..pool.block_info() };
+        let changed_senders = HashMap::from([
+            (mined.sender_id(), SenderInfo { state_nonce: 1, balance: next.transaction.cost() }),
+            (other.sender_id(), SenderInfo { state_nonce: 0, balance: U256::ZERO }),
+        ]);
+        let outcome =
+            pool.on_canonical_state_change(block_info, vec![*mined.hash()], changed_senders);
+
+        assert_eq!(outcome.mined, vec![*mined.hash()]);
+        let promoted = outcome.promoted.iter().map(|tx| *tx.hash()).collect::<Vec<_>>();
+        assert_eq!(promoted, vec![*next.hash()]);
+        assert!(outcome.discarded.is_empty());
+        assert!(!pool.contains(mined.hash()));
+        assert!(pool.subpool_contains(SubPool::Pending, next.id()));
+        assert!(pool.subpool_contains(SubPool::Queued, other.id()));
+        assert_eq!(pool.block_info().last_seen_block_number, 1);
+    }
+
+    /// An operation on the pool, applied by `random_operations_keep_invariants`.
+    #[derive(Debug, Clone)]
+    enum PoolOp {
+        /// Adds a transaction of the sender with the given offset to the on chain nonce, this
+        /// replaces an existing transaction if the fees are bumped enough.
+        Add { sender: u8, nonce_offset: u64, max_fee: u128, gas_limit: u64, size: usize },
+        /// Removes one of the added transactions, if it's still in the pool.
+        Remove { index: usize },
+        /// Sets the base fee of the pending block.
+        SetBaseFee { base_fee: u64 },
+        /// Updates the on chain nonce and balance of the sender, like a new block or a reorg.
+        UpdateAccount { sender: u8, nonce: u64, balance: u64 },
+        /// Evicts the worst transactions if the pool exceeds its limits.
+        DiscardWorst,
+    }
+
+    fn pool_op() -> impl Strategy<Value = PoolOp> {
+        prop_oneof![
+            4 => (0..3u8, 0..4u64, 7..20u128, 0..3u64, 1..200usize).prop_map(
+                |(sender, nonce_offset, max_fee, gas_limit, size)| PoolOp::Add {
+                    sender,
+                    nonce_offset,
+                    max_fee,
+                    gas_limit,
+                    size
+                }
+            ),
+            1 => any::<usize>().prop_map(|index| PoolOp::Remove { index }),
+            1 => (7..20u64).prop_map(|base_fee| PoolOp::SetBaseFee { base_fee }),
+            1 => (0..3u8, 0..4u64, 0..100u64).prop_map(|(sender, nonce, balance)| {
+                PoolOp::UpdateAccount { sender, nonce, balance }
+            }),
+            1 => Just(PoolOp::DiscardWorst),
+        ]
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn random_operations_keep_invariants(ops in proptest::collection::vec(pool_op(), 1..64)) {
+            let limit = SubPoolLimit { max_txs: 4, max_size: 400 };
+            let config = PoolConfig {
+                pending_limit: limit,
+                basefee_limit: limit,
+                queued_limit: limit,
+                blob_limit: limit,
+                ..Default::default()
+            };
+            let mut pool = TxPool::new(MockOrdering::default(), config);
+            let mut f = MockTransactionFactory::default();
+            let mut accounts = vec![SenderInfo { state_nonce: 0, balance: U256::from(50) }; 3];
+            let mut added = Vec::new();
+
+            for op in ops {
+                match op {
+                    PoolOp::Add { sender, nonce_offset, max_fee, gas_limit, size } => {
+                        let account = &accounts[sender as usize];
+                        let tx = MockTransaction::eip1559()
+                            .with_sender(Address::with_last_byte(sender + 1))
+                            .with_nonce(account.state_nonce + nonce_offset)
+                            .with_max_fee(max_fee)
+                            .with_priority_fee(max_fee - MIN_PROTOCOL_BASE_FEE as u128 + 1)
+                            .with_gas_limit(gas_limit)
+                            .with_size(size);
+                        added.push(tx.get_hash());
+                        let _ = pool.add_transaction(
+                            f.validated(tx),
+                            account.balance,
+                            account.state_nonce,
+                        );
+                    }
+                    PoolOp::Remove { index } => {
+                        if !added.is_empty() {
+                            pool.remove_transactions(vec![added[index % added.len()]]);
+                        }
+                    }
+                    PoolOp::SetBaseFee { base_fee } => {
+                        pool.set_block_info(BlockInfo {
+                            pending_basefee: base_fee,
+                            ..pool.block_info()
+                        });
+                    }
+                    PoolOp::UpdateAccount { sender, nonce, balance } => {
+                        let address = Address::with_last_byte(sender + 1);
+                        let info = SenderInfo { state_nonce: nonce, balance: U256::from(balance) };
+                        accounts[sender as usize] = info.clone();
+                        pool.update_accounts(HashMap::from([(
+                            f.ids.sender_id_or_create(address),
+                            info,
+                        )]));
+                    }
+                    PoolOp::DiscardWorst => {
+                        let discarded = pool.discard_worst();
+                        // no remaining transaction lost an ancestor
+                        for tx in &discarded {
+                            prop_assert!(pool
+                                .all_transactions
+                                .descendant_txs_exclusive(tx.id())
+                                .next()
+                                .is_none());
+                        }
+                        // descendants are discarded before their ancestors
+                        for (i, tx) in discarded.iter().enumerate() {
+                            prop_assert!(discarded[i + 1..].iter().all(|later| {
+                                later.sender_id() != tx.sender_id() || later.nonce() < tx.nonce()
+                            }));
+                        }
+                    }
+                }
+                pool.assert_invariants();
+            }
+        }
+    }
+
+    #[test]
+    fn size_tracks_inserts_and_evictions() {
+        let mut f = MockTransactionFactory::default();
+        let pending_limit = SubPoolLimit::new(2, usize::MAX);
+        let mut pool = TxPool::new(
+            MockOrdering::default(),
+            PoolConfig { pending_limit, ..Default::default() },
+        );
+        let size_of = |txs: &[&MockTransaction]| txs.iter().map(|tx| tx.size()).sum::<usize>();
+
+        let cheap = MockTransaction::eip1559();
+        let expensive = MockTransaction::eip1559().inc_price_by(10);
+        let other = MockTransaction::eip1559().inc_price_by(20);
+        let gapped = MockTransaction::eip1559().with_nonce(1);
+        for tx in [&cheap, &expensive, &other, &gapped] {
+            pool.add_transaction(f.validated(tx.clone()), U256::from(1_000), 0).unwrap();
+        }
+
+        let size = pool.size();
+        assert_eq!(size.pending, 3);
+        assert_eq!(size.pending_size, size_of(&[&cheap, &expensive, &other]));
+        assert_eq!(size.queued, 1);
+        assert_eq!(size.queued_size, size_of(&[&gapped]));
+        assert_eq!((size.basefee, size.basefee_size), (0, 0));
+
+        // the cheapest pending transaction is evicted
+        let removed = pool.discard_worst().iter().map(|tx| *tx.hash()).collect::<Vec<_>>();
+        assert_eq!(removed, vec![cheap.get_hash()]);
+        let size = pool.size();
+        assert_eq!(size.pending, 2);
+        assert_eq!(size.pending_size, size_of(&[&expensive, &other]));
+        assert_eq!(size.queued_size, size_of(&[&gapped]));
+
+        pool.remove_transactions(vec![gapped.get_hash()]);
+        let size = pool.size();
+        assert_eq!((size.queued, size.queued_size), (0, 0));
+        assert_eq!(size.total, 2);
+    }
 }
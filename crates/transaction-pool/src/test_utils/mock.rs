@@ -708,9 +708,9 @@ impl PoolTransaction for MockTransaction {
         }
     }
 
-    /// Returns the encoded length of the transaction.
+    /// Returns the encoded length of the transaction, which is its fake size.
     fn encoded_length(&self) -> usize {
-        0
+        self.size()
     }
 
     /// Returns the chain ID associated with the transaction.
@@ -722,6 +722,14 @@ impl PoolTransaction for MockTransaction {
             Self::Eip2930 { chain_id, .. } => Some(*chain_id),
         }
     }
+
+    /// Returns the number of blobs of the sidecar.
+    fn blob_count(&self) -> usize {
+        match self {
+            Self::Eip4844 { sidecar, .. } => sidecar.blobs.len(),
+            _ => 0,
+        }
+    }
 }
 
 impl EthPoolTransaction for MockTransaction {
@@ -732,13 +740,6 @@ impl EthPoolTransaction for MockTransaction {
         }
     }
 
-    fn blob_count(&self) -> usize {
-        match self {
-            Self::Eip4844 { sidecar, .. } => sidecar.blobs.len(),
-            _ => 0,
-        }
-    }
-
     fn validate_blob(
         &self,
         _blob: &BlobTransactionSidecar,
@@ -1136,6 +1137,7 @@ impl MockTransactionFactory {
             transaction,
             timestamp: Instant::now(),
             origin,
+            peer: None,
         }
     }
 
@@ -3,18 +3,22 @@
 use crate::{
     blobstore::BlobStoreError,
     error::PoolResult,
-    pool::{state::SubPool, BestTransactionFilter, TransactionEvents},
+    pool::{
+        state::SubPool, BestTransactionFilter, BestTransactionsWithPolicy, Seen, TransactionEvents,
+    },
     validate::ValidPoolTransaction,
     AllTransactionsEvents,
 };
 use futures_util::{ready, Stream};
 use reth_eth_wire_types::HandleMempoolData;
+use reth_metrics::MetricsRegistrar;
 use reth_primitives::{
     kzg::KzgSettings, transaction::TryFromRecoveredTransactionError, AccessList, Address,
-    BlobTransactionSidecar, BlobTransactionValidationError, FromRecoveredPooledTransaction,
-    IntoRecoveredTransaction, PooledTransactionsElement, PooledTransactionsElementEcRecovered,
-    SealedBlock, Transaction, TransactionSignedEcRecovered, TryFromRecoveredTransaction, TxHash,
-    TxKind, B256, EIP1559_TX_TYPE_ID, EIP4844_TX_TYPE_ID, U256,
+    BlobTransactionSidecar, BlobTransactionValidationError, BlockNumHash,
+    FromRecoveredPooledTransaction, IntoRecoveredTransaction, PooledTransactionsElement,
+    PooledTransactionsElementEcRecovered, SealedBlock, SealedBlockWithSenders, Transaction,
+    TransactionSignedEcRecovered, TryFromRecoveredTransaction, TxHash, TxKind, B256,
+    EIP1559_TX_TYPE_ID, EIP4844_TX_TYPE_ID, U256,
 };
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -25,6 +29,7 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::Instant,
 };
 use tokio::sync::mpsc::Receiver;
 
@@ -52,6 +57,13 @@ pub trait TransactionPool: Send + Sync + Clone {
     /// This tracks the block that the pool has last seen.
     fn block_info(&self) -> BlockInfo;
 
+    /// Notifies the pool whether the node is far behind the chain tip.
+    ///
+    /// While syncing, transactions with [`TransactionOrigin::External`] are rejected with
+    /// [`PoolErrorKind::NodeSyncing`](crate::error::PoolErrorKind::NodeSyncing), because they would
+    /// be stale by the time the node reaches the tip. Local transactions are still accepted.
+    fn set_syncing(&self, syncing: bool);
+
     /// Imports an _external_ transaction.
     ///
     /// This is intended to be used by the network to insert incoming transactions received over the
@@ -76,6 +88,20 @@ pub trait TransactionPool: Send + Sync + Clone {
         self.add_transactions(TransactionOrigin::External, transactions)
     }
 
+    /// Imports all _external_ transactions that were received from the given peer.
+    ///
+    /// This is the same as [`TransactionPool::add_external_transactions`], but allows the pool to
+    /// attribute the transactions to the peer, see [`ValidPoolTransaction::peer`].
+    ///
+    /// Consumer: P2P
+    fn add_peer_transactions(
+        &self,
+        _peer_id: PeerId,
+        transactions: Vec<Self::Transaction>,
+    ) -> impl Future<Output = Vec<PoolResult<TxHash>>> + Send {
+        self.add_external_transactions(transactions)
+    }
+
     /// Adds an _unvalidated_ transaction into the pool and subscribe to state changes.
     ///
     /// This is the same as [TransactionPool::add_transaction] but returns an event stream for the
@@ -220,6 +246,12 @@ pub trait TransactionPool: Send + Sync + Clone {
     ///
     /// If the transaction is a blob transaction, the sidecar will be included.
     ///
+    /// Transactions that must not be propagated, e.g. [`TransactionOrigin::Private`]
+    /// transactions, are skipped.
+    ///
+    /// Transactions are added until the next one would exceed the limit, measured by
+    /// [`PoolTransaction::encoded_length`]. The first transaction is always included.
+    ///
     /// Consumer: P2P
     fn get_pooled_transaction_elements(
         &self,
@@ -263,6 +295,23 @@ pub trait TransactionPool: Send + Sync + Clone {
         best_transactions_attributes: BestTransactionsAttributes,
     ) -> Box<dyn BestTransactions<Item = Arc<ValidPoolTransaction<Self::Transaction>>>>;
 
+    /// Returns an iterator that yields transactions that are ready for block production and fit
+    /// the remaining budgets of the given [`InclusionPolicy`].
+    ///
+    /// The budgets are updated while iterating with [`BestTransactionsWithPolicy::policy_mut`],
+    /// e.g. after every included transaction.
+    ///
+    /// Consumer: Block production
+    fn best_transactions_with_policy(
+        &self,
+        policy: InclusionPolicy,
+    ) -> BestTransactionsWithPolicy<Self::Transaction> {
+        BestTransactionsWithPolicy::new(
+            self.best_transactions_with_attributes(policy.attributes),
+            policy,
+        )
+    }
+
     /// Returns all transactions that can be included in the next block.
     ///
     /// This is primarily used for the `txpool_` RPC namespace:
@@ -274,6 +323,17 @@ pub trait TransactionPool: Send + Sync + Clone {
     /// Consumer: RPC
     fn pending_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
 
+    /// Returns all transactions that can be included in the next block and are allowed to be
+    /// propagated to peers.
+    ///
+    /// Unlike [`Self::pending_transactions`], this excludes transactions that must not be
+    /// propagated, e.g. [`TransactionOrigin::Private`] transactions.
+    ///
+    /// Consumer: P2P
+    fn pending_transactions_to_propagate(
+        &self,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
     /// Returns all transactions that can be included in _future_ blocks.
     ///
     /// This and [Self::pending_transactions] are mutually exclusive.
@@ -316,11 +376,38 @@ pub trait TransactionPool: Send + Sync + Clone {
     /// Returns the transaction for the given hash.
     fn get(&self, tx_hash: &TxHash) -> Option<Arc<ValidPoolTransaction<Self::Transaction>>>;
 
+    /// Returns what the pool knows about the transaction with the given hash, if it has seen it
+    /// recently.
+    ///
+    /// Transactions that are [known](Seen::is_known) are neither requested from peers nor
+    /// validated again. By default, only transactions in the pool are known.
+    fn seen(&self, tx_hash: &TxHash) -> Option<Seen> {
+        self.contains(tx_hash).then_some(Seen::InPool)
+    }
+
     /// Returns all transactions objects for the given hashes.
     ///
     /// Caution: This in case of blob transactions, this does not include the sidecar.
     fn get_all(&self, txs: Vec<TxHash>) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
 
+    /// Returns the transaction for each of the given hashes, in the same order, `None` if the
+    /// transaction is not in the pool.
+    ///
+    /// Unlike [`Self::get_all`], all transactions are looked up in a single snapshot of the pool.
+    ///
+    /// Caution: This in case of blob transactions, this does not include the sidecar.
+    fn get_all_ordered(
+        &self,
+        txs: Vec<TxHash>,
+    ) -> Vec<Option<Arc<ValidPoolTransaction<Self::Transaction>>>>;
+
+    /// Returns for each of the given hashes, in the same order, whether the transaction is in the
+    /// pool.
+    ///
+    /// This is a cheaper alternative to [`Self::get_all_ordered`] for callers that only need
+    /// membership.
+    fn contains_all(&self, txs: Vec<TxHash>) -> Vec<bool>;
+
     /// Notify the pool about transactions that are propagated to peers.
     ///
     /// Consumer: P2P
@@ -332,6 +419,26 @@ pub trait TransactionPool: Send + Sync + Clone {
         sender: Address,
     ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
 
+    /// Returns all pending transactions sent by a given user
+    fn get_pending_transactions_by_sender(
+        &self,
+        sender: Address,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
+    /// Returns the nonce that follows the gapless sequence of transactions of a given user that
+    /// starts at the given on-chain nonce.
+    ///
+    /// This is the nonce of the next transaction of the user, i.e. the transaction count of the
+    /// pending block. Transactions after a nonce gap are ignored.
+    fn next_pending_nonce(&self, sender: Address, on_chain_nonce: u64) -> u64;
+
+    /// Returns the next pending nonce and the number of pending transactions of a given user.
+    ///
+    /// Both are read under the same lock of the pool, so they are consistent with each other.
+    ///
+    /// See also [`Self::next_pending_nonce`].
+    fn pending_sender_info(&self, sender: Address, on_chain_nonce: u64) -> PendingSenderInfo;
+
     /// Returns a transaction sent by a given user with a given nonce
     fn get_transactions_by_sender_and_nonce(
         &self,
@@ -385,6 +492,13 @@ pub trait TransactionPool: Send + Sync + Clone {
         &self,
         tx_hashes: Vec<TxHash>,
     ) -> Result<Vec<BlobTransactionSidecar>, BlobStoreError>;
+
+    /// Declares all metrics of the pool to the given [`MetricsRegistrar`].
+    ///
+    /// Consumer: Node
+    fn register_metrics(&self, registrar: &mut MetricsRegistrar) {
+        let _ = registrar;
+    }
 }
 
 /// Extension for [TransactionPool] trait that allows to set the current block info.
@@ -421,6 +535,40 @@ pub trait TransactionPoolExt: TransactionPool {
 
     /// Maintenance function to cleanup blobs that are no longer needed.
     fn cleanup_blobs(&self);
+
+    /// Maintenance function to evict parked transactions that exceeded the max lifetime, see
+    /// [`PoolConfig::max_queued_lifetime`](crate::PoolConfig::max_queued_lifetime).
+    ///
+    /// Returns the hashes of the evicted transactions, listeners receive a
+    /// [`Discarded`](crate::FullTransactionEvent::Discarded) event for each of them.
+    fn evict_expired(&self) -> Vec<TxHash>;
+
+    /// Records the transactions of new canonical blocks in the cache of recently mined
+    /// transactions.
+    ///
+    /// See also [`RecentlyMinedConfig`](crate::RecentlyMinedConfig).
+    fn on_mined_blocks(&self, blocks: Vec<&SealedBlockWithSenders>);
+
+    /// Removes the reverted blocks from the cache of recently mined transactions.
+    ///
+    /// Returns all quarantined replacements of transactions mined in the reverted blocks. These
+    /// should be re-submitted to the pool _before_ the reverted transactions themselves, so that
+    /// the replacement takes precedence.
+    fn on_reverted_blocks(&self, blocks: Vec<BlockNumHash>) -> Vec<Self::Transaction>;
+
+    /// Puts the transactions mined in reverted blocks back into the pool.
+    ///
+    /// Unlike [`TransactionPool::add_external_transactions`], a transaction that was pruned from
+    /// the pool when it was mined keeps the origin and the timestamp it was first added with.
+    /// Transactions the pool never contained are added as external transactions.
+    ///
+    /// This must be called after [`TransactionPoolExt::on_reverted_blocks`] and after the released
+    /// replacements were added, a transaction whose sender and nonce are already taken in the pool
+    /// is dropped.
+    fn resubmit_transactions(
+        &self,
+        transactions: Vec<Self::Transaction>,
+    ) -> impl Future<Output = Vec<PoolResult<TxHash>>> + Send;
 }
 
 /// Determines what kind of new transactions should be emitted by a stream of transactions.
@@ -445,27 +593,60 @@ impl TransactionListenerKind {
 }
 
 /// A Helper type that bundles all transactions in the pool.
+///
+/// The transactions are grouped by sender, the transactions of a sender are sorted by nonce.
 #[derive(Debug, Clone)]
 pub struct AllPoolTransactions<T: PoolTransaction> {
     /// Transactions that are ready for inclusion in the next block.
-    pub pending: Vec<Arc<ValidPoolTransaction<T>>>,
+    pub pending: HashMap<Address, Vec<Arc<ValidPoolTransaction<T>>>>,
     /// Transactions that are ready for inclusion in _future_ blocks, but are currently parked,
     /// because they depend on other transactions that are not yet included in the pool (nonce gap)
     /// or otherwise blocked.
-    pub queued: Vec<Arc<ValidPoolTransaction<T>>>,
+    pub queued: HashMap<Address, Vec<Arc<ValidPoolTransaction<T>>>>,
 }
 
 // === impl AllPoolTransactions ===
 
 impl<T: PoolTransaction> AllPoolTransactions<T> {
+    /// Groups the given pending and queued transactions by sender.
+    pub fn new(
+        pending: Vec<Arc<ValidPoolTransaction<T>>>,
+        queued: Vec<Arc<ValidPoolTransaction<T>>>,
+    ) -> Self {
+        fn by_sender<T: PoolTransaction>(
+            transactions: Vec<Arc<ValidPoolTransaction<T>>>,
+        ) -> HashMap<Address, Vec<Arc<ValidPoolTransaction<T>>>> {
+            let mut by_sender = HashMap::<_, Vec<_>>::new();
+            for tx in transactions {
+                by_sender.entry(tx.sender()).or_default().push(tx);
+            }
+            for transactions in by_sender.values_mut() {
+                transactions.sort_unstable_by_key(|tx| tx.nonce());
+            }
+            by_sender
+        }
+
+        Self { pending: by_sender(pending), queued: by_sender(queued) }
+    }
+
+    /// Returns the number of pending transactions.
+    pub fn pending_len(&self) -> usize {
+        self.pending.values().map(Vec::len).sum()
+    }
+
+    /// Returns the number of queued transactions.
+    pub fn queued_len(&self) -> usize {
+        self.queued.values().map(Vec::len).sum()
+    }
+
     /// Returns an iterator over all pending [`TransactionSignedEcRecovered`] transactions.
     pub fn pending_recovered(&self) -> impl Iterator<Item = TransactionSignedEcRecovered> + '_ {
-        self.pending.iter().map(|tx| tx.transaction.to_recovered_transaction())
+        self.pending.values().flatten().map(|tx| tx.transaction.to_recovered_transaction())
     }
 
     /// Returns an iterator over all queued [`TransactionSignedEcRecovered`] transactions.
     pub fn queued_recovered(&self) -> impl Iterator<Item = TransactionSignedEcRecovered> + '_ {
-        self.queued.iter().map(|tx| tx.transaction.to_recovered_transaction())
+        self.queued.values().flatten().map(|tx| tx.transaction.to_recovered_transaction())
     }
 }
 
@@ -475,6 +656,16 @@ impl<T: PoolTransaction> Default for AllPoolTransactions<T> {
     }
 }
 
+/// The pool's view of the pending transactions of a sender.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PendingSenderInfo {
+    /// The nonce that follows the gapless sequence of transactions of the sender that starts at
+    /// the on-chain nonce.
+    pub next_nonce: u64,
+    /// Number of transactions of the sender in the pending sub-pool.
+    pub pending_transactions: usize,
+}
+
 /// Represents a transaction that was propagated over the network.
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
 pub struct PropagatedTransactions(pub HashMap<TxHash, Vec<PropagateKind>>);
@@ -519,6 +710,18 @@ pub struct NewTransactionEvent<T: PoolTransaction> {
     pub transaction: Arc<ValidPoolTransaction<T>>,
 }
 
+impl<T: PoolTransaction> NewTransactionEvent<T> {
+    /// Returns where the transaction originates from.
+    pub fn origin(&self) -> TransactionOrigin {
+        self.transaction.origin
+    }
+
+    /// Returns `true` if the transaction is allowed to be propagated to peers.
+    pub fn is_propagate_allowed(&self) -> bool {
+        self.transaction.propagate
+    }
+}
+
 impl<T: PoolTransaction> Clone for NewTransactionEvent<T> {
     fn clone(&self) -> Self {
         Self { subpool: self.subpool, transaction: self.transaction.clone() }
@@ -753,6 +956,80 @@ impl BestTransactionsAttributes {
     }
 }
 
+/// The remaining budgets of a block that is being built, which a transaction must fit to be
+/// included.
+///
+/// The payload builder updates the budgets as it includes transactions, and the pool only yields
+/// transactions that fit them, see [`TransactionPool::best_transactions_with_policy`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InclusionPolicy {
+    /// The base fee and blob fee of the block.
+    pub attributes: BestTransactionsAttributes,
+    /// The gas that is left in the block.
+    pub remaining_gas: u64,
+    /// The number of blobs that can still be included in the block.
+    pub remaining_blobs: u64,
+    /// The minimum tip per gas a transaction must pay at the base fee, if any.
+    pub min_effective_tip: Option<u128>,
+    /// No transactions are included after this instant, if any.
+    pub deadline: Option<Instant>,
+}
+
+// === impl InclusionPolicy ===
+
+impl InclusionPolicy {
+    /// Creates a new `InclusionPolicy` for an empty block with the given gas limit and maximum
+    /// number of blobs.
+    pub const fn new(
+        attributes: BestTransactionsAttributes,
+        gas_limit: u64,
+        max_blobs: u64,
+    ) -> Self {
+        Self {
+            attributes,
+            remaining_gas: gas_limit,
+            remaining_blobs: max_blobs,
+            min_effective_tip: None,
+            deadline: None,
+        }
+    }
+
+    /// Sets the minimum tip per gas a transaction must pay at the base fee.
+    pub const fn with_min_effective_tip(mut self, min_effective_tip: u128) -> Self {
+        self.min_effective_tip = Some(min_effective_tip);
+        self
+    }
+
+    /// Sets the instant after which no transactions are included.
+    pub const fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Returns `true` if the deadline has passed.
+    pub fn is_past_deadline(&self) -> bool {
+        self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Returns `true` if the transaction fits the remaining gas and blobs, and pays the minimum
+    /// tip.
+    pub fn fits<T: PoolTransaction>(&self, transaction: &ValidPoolTransaction<T>) -> bool {
+        transaction.gas_limit() <= self.remaining_gas &&
+            transaction.transaction.blob_count() as u64 <= self.remaining_blobs &&
+            self.min_effective_tip.map_or(true, |min_effective_tip| {
+                transaction
+                    .effective_tip_per_gas(self.attributes.basefee)
+                    .is_some_and(|tip| tip >= min_effective_tip)
+            })
+    }
+
+    /// Subtracts the gas used and the blobs of an included transaction from the remaining budgets.
+    pub fn on_included(&mut self, gas_used: u64, blob_count: u64) {
+        self.remaining_gas = self.remaining_gas.saturating_sub(gas_used);
+        self.remaining_blobs = self.remaining_blobs.saturating_sub(blob_count);
+    }
+}
+
 /// Trait for transaction types used inside the pool
 pub trait PoolTransaction:
     fmt::Debug
@@ -849,6 +1126,9 @@ pub trait PoolTransaction:
 
     /// Returns `chain_id`
     fn chain_id(&self) -> Option<u64>;
+
+    /// Returns the number of blobs this transaction has.
+    fn blob_count(&self) -> usize;
 }
 
 /// An extension trait that provides additional interfaces for the
@@ -857,9 +1137,6 @@ pub trait EthPoolTransaction: PoolTransaction {
     /// Extracts the blob sidecar from the transaction.
     fn take_blob(&mut self) -> EthBlobTransactionSidecar;
 
-    /// Returns the number of blobs this transaction has.
-    fn blob_count(&self) -> usize;
-
     /// Validates the blob sidecar of the transaction with the given settings.
     fn validate_blob(
         &self,
@@ -1092,6 +1369,13 @@ impl PoolTransaction for EthPooledTransaction {
     fn chain_id(&self) -> Option<u64> {
         self.transaction.chain_id()
     }
+
+    fn blob_count(&self) -> usize {
+        match &self.transaction.transaction {
+            Transaction::Eip4844(tx) => tx.blob_versioned_hashes.len(),
+            _ => 0,
+        }
+    }
 }
 
 impl EthPoolTransaction for EthPooledTransaction {
@@ -1103,13 +1387,6 @@ impl EthPoolTransaction for EthPooledTransaction {
         }
     }
 
-    fn blob_count(&self) -> usize {
-        match &self.transaction.transaction {
-            Transaction::Eip4844(tx) => tx.blob_versioned_hashes.len(),
-            _ => 0,
-        }
-    }
-
     fn validate_blob(
         &self,
         sidecar: &BlobTransactionSidecar,
@@ -1186,6 +1463,8 @@ pub struct PoolSize {
     ///
     /// Note: this is the sum of ```pending + basefee + queued```
     pub total: usize,
+    /// Aggregate stats of the transactions in the _basefee_ sub-pool.
+    pub basefee_stats: BasefeePoolStats,
 }
 
 // === impl PoolSize ===
@@ -1198,6 +1477,53 @@ impl PoolSize {
     }
 }
 
+/// Upper bounds of the buckets of [`BasefeePoolStats::fee_cap_histogram`], in percent of the
+/// pending base fee.
+pub const BASEFEE_HISTOGRAM_BUCKETS: [u128; 5] = [25, 50, 75, 90, 100];
+
+/// Aggregate stats of the transactions in the _basefee_ sub-pool, i.e. the transactions that are
+/// only priced out by the pending base fee.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BasefeePoolStats {
+    /// Number of transactions in the sub-pool.
+    pub count: usize,
+    /// Sum of the gas limits of the transactions in the sub-pool.
+    pub total_gas: u128,
+    /// Number of transactions per bucket of their fee cap relative to the pending base fee.
+    ///
+    /// A transaction is counted in the first bucket whose bound in [`BASEFEE_HISTOGRAM_BUCKETS`]
+    /// is above its fee cap, or in the last bucket if there is none.
+    pub fee_cap_histogram: [usize; BASEFEE_HISTOGRAM_BUCKETS.len()],
+}
+
+// === impl BasefeePoolStats ===
+
+impl BasefeePoolStats {
+    /// Adds a transaction with the given fee cap and gas limit to the stats.
+    pub(crate) fn add(&mut self, max_fee_per_gas: u128, gas_limit: u64, pending_basefee: u64) {
+        self.count += 1;
+        self.total_gas += gas_limit as u128;
+        self.fee_cap_histogram[Self::bucket(max_fee_per_gas, pending_basefee)] += 1;
+    }
+
+    /// Removes a transaction that was added with the same fee cap, gas limit and pending base fee
+    /// from the stats.
+    pub(crate) fn remove(&mut self, max_fee_per_gas: u128, gas_limit: u64, pending_basefee: u64) {
+        self.count -= 1;
+        self.total_gas -= gas_limit as u128;
+        self.fee_cap_histogram[Self::bucket(max_fee_per_gas, pending_basefee)] -= 1;
+    }
+
+    /// Returns the index of the histogram bucket of the given fee cap.
+    fn bucket(max_fee_per_gas: u128, pending_basefee: u64) -> usize {
+        let fee_cap = max_fee_per_gas.saturating_mul(100);
+        BASEFEE_HISTOGRAM_BUCKETS
+            .iter()
+            .position(|bound| fee_cap < bound * pending_basefee as u128)
+            .unwrap_or(BASEFEE_HISTOGRAM_BUCKETS.len() - 1)
+    }
+}
+
 /// Represents the current status of the pool.
 #[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
 pub struct BlockInfo {
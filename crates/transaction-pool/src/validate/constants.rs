@@ -16,3 +16,10 @@ pub const MAX_CODE_BYTE_SIZE: usize = 24576;
 
 /// Maximum initcode to permit in a creation transaction and create instructions.
 pub const MAX_INIT_CODE_BYTE_SIZE: usize = 2 * MAX_CODE_BYTE_SIZE;
+
+/// Default number of recent canonical blocks whose gas limits are remembered by the validator.
+///
+/// Transactions that exceed the block gas limit, but not the gas limit of one of these blocks, are
+/// rejected without being considered bad, see
+/// [`ExceedsLoweredGasLimit`](crate::error::InvalidPoolTransactionError::ExceedsLoweredGasLimit).
+pub const DEFAULT_GAS_LIMIT_HISTORY: u64 = 64;
@@ -1,6 +1,6 @@
 //! Ethereum transaction validator.
 
-use super::constants::DEFAULT_MAX_TX_INPUT_BYTES;
+use super::constants::{DEFAULT_GAS_LIMIT_HISTORY, DEFAULT_MAX_TX_INPUT_BYTES};
 use crate::{
     blobstore::BlobStore,
     error::{Eip4844PoolTransactionError, InvalidPoolTransactionError},
@@ -9,6 +9,7 @@ use crate::{
     EthBlobTransactionSidecar, EthPoolTransaction, LocalTransactionConfig, PoolTransaction,
     TransactionValidationOutcome, TransactionValidationTaskExecutor, TransactionValidator,
 };
+use parking_lot::RwLock;
 use reth_primitives::{
     constants::{
         eip4844::{MAINNET_KZG_TRUSTED_SETUP, MAX_BLOBS_PER_BLOCK},
@@ -16,12 +17,13 @@ use reth_primitives::{
     },
     kzg::KzgSettings,
     revm::compat::calculate_intrinsic_gas_after_merge,
-    ChainSpec, GotExpected, InvalidTransactionError, SealedBlock, EIP1559_TX_TYPE_ID,
+    BlockNumber, ChainSpec, GotExpected, InvalidTransactionError, SealedBlock, EIP1559_TX_TYPE_ID,
     EIP2930_TX_TYPE_ID, EIP4844_TX_TYPE_ID, LEGACY_TX_TYPE_ID,
 };
 use reth_provider::{AccountReader, BlockReaderIdExt, StateProviderFactory};
 use reth_tasks::TaskSpawner;
 use std::{
+    collections::VecDeque,
     marker::PhantomData,
     sync::{atomic::AtomicBool, Arc},
 };
@@ -119,8 +121,8 @@ pub(crate) struct EthTransactionValidatorInner<Client, T> {
     eip1559: bool,
     /// Fork indicator whether we are using EIP-4844 blob transactions.
     eip4844: bool,
-    /// The current max gas limit
-    block_gas_limit: u64,
+    /// The current max gas limit, and the gas limits of the recent canonical blocks
+    block_gas_limits: RwLock<GasLimitHistory>,
     /// Minimum priority fee to enforce for acceptance into the pool.
     minimum_priority_fee: Option<u128>,
     /// Stores the setup and parameters needed for validating KZG proofs.
@@ -215,15 +217,22 @@ where
 
         // Checks for gas limit
         let transaction_gas_limit = transaction.gas_limit();
-        if transaction_gas_limit > self.block_gas_limit {
-            return TransactionValidationOutcome::Invalid(
-                transaction,
-                InvalidPoolTransactionError::ExceedsGasLimit(
+        let block_gas_limits = self.block_gas_limits.read();
+        let block_gas_limit = block_gas_limits.current();
+        if transaction_gas_limit > block_gas_limit {
+            // the transaction may have been valid before the gas limit was lowered, in which case
+            // peers may still have it in their pools
+            let err = if transaction_gas_limit <= block_gas_limits.recent_max() {
+                InvalidPoolTransactionError::ExceedsLoweredGasLimit(
                     transaction_gas_limit,
-                    self.block_gas_limit,
-                ),
-            )
+                    block_gas_limit,
+                )
+            } else {
+                InvalidPoolTransactionError::ExceedsGasLimit(transaction_gas_limit, block_gas_limit)
+            };
+            return TransactionValidationOutcome::Invalid(transaction, err)
         }
+        drop(block_gas_limits);
 
         // Ensure max_priority_fee_per_gas (if EIP1559) is less than max_fee_per_gas if any.
         if transaction.max_priority_fee_per_gas() > Some(transaction.max_fee_per_gas()) {
@@ -245,6 +254,17 @@ where
             )
         }
 
+        // Drop transactions that can never pay the minimum priority fee of the chain, regardless of
+        // the base fee.
+        if transaction.priority_fee_or_price() <
+            u128::from(self.chain_spec.fee_floors.min_priority_fee())
+        {
+            return TransactionValidationOutcome::Invalid(
+                transaction,
+                InvalidPoolTransactionError::Underpriced,
+            )
+        }
+
         // Checks for chainid
         if let Some(chain_id) = transaction.chain_id() {
             if chain_id != self.chain_id() {
@@ -407,6 +427,8 @@ where
         if self.chain_spec.is_shanghai_active_at_timestamp(new_tip_block.timestamp) {
             self.fork_tracker.shanghai.store(true, std::sync::atomic::Ordering::Relaxed);
         }
+
+        self.block_gas_limits.write().on_new_head(new_tip_block.number, new_tip_block.gas_limit);
     }
 }
 
@@ -426,6 +448,8 @@ pub struct EthTransactionValidatorBuilder {
     eip4844: bool,
     /// The current max gas limit
     block_gas_limit: u64,
+    /// Number of recent canonical blocks whose gas limits are remembered
+    gas_limit_history: u64,
     /// Minimum priority fee to enforce for acceptance into the pool.
     minimum_priority_fee: Option<u128>,
     /// Determines how many additional tasks to spawn
@@ -454,6 +478,7 @@ impl EthTransactionValidatorBuilder {
         Self {
             chain_spec,
             block_gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+            gas_limit_history: DEFAULT_GAS_LIMIT_HISTORY,
             minimum_priority_fee: None,
             additional_tasks: 1,
             kzg_settings: Arc::clone(&MAINNET_KZG_TRUSTED_SETUP),
@@ -572,12 +597,25 @@ impl EthTransactionValidatorBuilder {
 
     /// Sets the block gas limit
     ///
-    /// Transactions with a gas limit greater than this will be rejected.
+    /// Transactions with a gas limit greater than this will be rejected. Once the validator is
+    /// notified of a new head block, the gas limit of the head block is enforced instead.
     pub const fn set_block_gas_limit(mut self, block_gas_limit: u64) -> Self {
         self.block_gas_limit = block_gas_limit;
         self
     }
 
+    /// Sets the number of recent canonical blocks whose gas limits are remembered.
+    ///
+    /// Transactions that exceed the gas limit of the head block, but not the gas limit of one of
+    /// these blocks, are rejected without being considered bad, because peers may still have them
+    /// in their pools from before the gas limit was lowered. `0` considers all of them bad.
+    ///
+    /// Default is [`DEFAULT_GAS_LIMIT_HISTORY`].
+    pub const fn with_gas_limit_history(mut self, blocks: u64) -> Self {
+        self.gas_limit_history = blocks;
+        self
+    }
+
     /// Builds a the [`EthTransactionValidator`] without spawning validator tasks.
     pub fn build<Client, Tx, S>(
         self,
@@ -595,6 +633,7 @@ impl EthTransactionValidatorBuilder {
             eip1559,
             eip4844,
             block_gas_limit,
+            gas_limit_history,
             minimum_priority_fee,
             kzg_settings,
             local_transactions_config,
@@ -612,7 +651,7 @@ impl EthTransactionValidatorBuilder {
             eip1559,
             fork_tracker,
             eip4844,
-            block_gas_limit,
+            block_gas_limits: RwLock::new(GasLimitHistory::new(block_gas_limit, gas_limit_history)),
             minimum_priority_fee,
             blob_store: Box::new(blob_store),
             kzg_settings,
@@ -689,6 +728,56 @@ impl ForkTracker {
     }
 }
 
+/// Keeps track of the enforced block gas limit and the gas limits of the recent canonical blocks.
+#[derive(Debug)]
+pub(crate) struct GasLimitHistory {
+    /// The gas limit of the head block, or the configured gas limit before the first head block.
+    current: u64,
+    /// The gas limits of the recent canonical blocks, ordered by block number.
+    recent: VecDeque<(BlockNumber, u64)>,
+    /// Number of recent blocks to remember, including the head block.
+    blocks: u64,
+}
+
+impl GasLimitHistory {
+    /// Creates a new history that enforces `block_gas_limit` until the first head block, and
+    /// remembers the gas limits of the last `blocks` canonical blocks.
+    pub(crate) const fn new(block_gas_limit: u64, blocks: u64) -> Self {
+        Self { current: block_gas_limit, recent: VecDeque::new(), blocks }
+    }
+
+    /// Returns the enforced block gas limit.
+    pub(crate) const fn current(&self) -> u64 {
+        self.current
+    }
+
+    /// Returns the highest gas limit of the recent canonical blocks.
+    ///
+    /// This is `0` if no recent block is remembered, the configured gas limit doesn't count.
+    pub(crate) fn recent_max(&self) -> u64 {
+        self.recent.iter().map(|(_, gas_limit)| *gas_limit).max().unwrap_or_default()
+    }
+
+    /// Records the gas limit of the new head block, which is enforced from now on.
+    ///
+    /// Blocks at or above the new head were reorged out, and are forgotten together with the
+    /// blocks that are no longer recent.
+    pub(crate) fn on_new_head(&mut self, number: BlockNumber, gas_limit: u64) {
+        self.current = gas_limit;
+        while self.recent.back().is_some_and(|(block, _)| *block >= number) {
+            self.recent.pop_back();
+        }
+        self.recent.push_back((number, gas_limit));
+        while self
+            .recent
+            .front()
+            .is_some_and(|(block, _)| block.saturating_add(self.blocks) <= number)
+        {
+            self.recent.pop_front();
+        }
+    }
+}
+
 /// Ensure that the code size is not greater than `max_init_code_size`.
 /// `max_init_code_size` should be configurable so this will take it as an argument.
 pub fn ensure_max_init_code_size<T: PoolTransaction>(
@@ -731,11 +820,13 @@ pub fn ensure_intrinsic_gas<T: PoolTransaction>(
 mod tests {
     use super::*;
     use crate::{
-        blobstore::InMemoryBlobStore, error::PoolErrorKind, CoinbaseTipOrdering,
-        EthPooledTransaction, Pool, TransactionPool,
+        blobstore::InMemoryBlobStore,
+        error::{PoolError, PoolErrorKind},
+        CoinbaseTipOrdering, EthPooledTransaction, Pool, Seen, TransactionPool,
     };
     use reth_primitives::{
-        hex, FromRecoveredPooledTransaction, PooledTransactionsElement, MAINNET, U256,
+        hex, FeeFloors, FromRecoveredPooledTransaction, Header, PooledTransactionsElement, MAINNET,
+        U256,
     };
     use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
 
@@ -814,4 +905,133 @@ mod tests {
         let tx = pool.get(transaction.hash());
         assert!(tx.is_none());
     }
+
+    #[tokio::test]
+    async fn lowered_gas_limit_within_history() {
+        // tx gas limit is 1_015_288
+        let transaction = get_transaction();
+
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            transaction.sender(),
+            ExtendedAccount::new(transaction.nonce(), U256::MAX),
+        );
+
+        let blob_store = InMemoryBlobStore::default();
+        let validator = EthTransactionValidatorBuilder::new(MAINNET.clone())
+            .with_gas_limit_history(3)
+            .build(provider, blob_store.clone());
+        let on_new_head = |number, gas_limit| {
+            let header = Header { number, gas_limit, ..Default::default() }.seal_slow();
+            validator.on_new_head_block(&SealedBlock { header, ..Default::default() });
+        };
+        let validate =
+            || match validator.validate_one(TransactionOrigin::External, transaction.clone()) {
+                TransactionValidationOutcome::Invalid(tx, err) => PoolError::new(*tx.hash(), err),
+                outcome => panic!("unexpected outcome {outcome:?}"),
+            };
+
+        on_new_head(1, 30_000_000);
+        assert!(validator
+            .validate_one(TransactionOrigin::External, transaction.clone())
+            .is_valid());
+
+        // the gas limit is lowered, the transaction was valid in a recent block
+        on_new_head(2, 1_000_000);
+        on_new_head(3, 1_000_000);
+        let err = validate();
+        assert!(matches!(
+            err.kind,
+            PoolErrorKind::InvalidTransaction(InvalidPoolTransactionError::ExceedsLoweredGasLimit(
+                1_015_288, 1_000_000
+            ))
+        ));
+        assert!(err.is_stale_transaction());
+        assert!(!err.is_bad_transaction());
+
+        // the last block with the higher gas limit is no longer recent
+        on_new_head(4, 1_000_000);
+        let err = validate();
+        assert!(matches!(
+            err.kind,
+            PoolErrorKind::InvalidTransaction(InvalidPoolTransactionError::ExceedsGasLimit(
+                1_015_288, 1_000_000
+            ))
+        ));
+        assert!(!err.is_stale_transaction());
+        assert!(err.is_bad_transaction());
+
+        // a reorg back to the higher gas limit, which is lowered again by the new head
+        on_new_head(2, 30_000_000);
+        on_new_head(3, 1_000_000);
+
+        // the rejection is remembered, but the transaction isn't bad
+        let pool = Pool::new(
+            validator.clone(),
+            CoinbaseTipOrdering::default(),
+            blob_store,
+            Default::default(),
+        );
+        let err = pool.add_external_transaction(transaction.clone()).await.unwrap_err();
+        assert!(err.is_stale_transaction());
+        assert!(matches!(pool.seen(transaction.hash()), Some(Seen::Stale(_))));
+        let err = pool.add_external_transaction(transaction.clone()).await.unwrap_err();
+        assert!(matches!(err.kind, PoolErrorKind::PreviouslyRejectedStale(_)));
+        assert!(err.is_stale_transaction());
+        assert!(!err.is_bad_transaction());
+    }
+
+    #[test]
+    fn gas_limit_history() {
+        let mut history = GasLimitHistory::new(30_000_000, 2);
+        assert_eq!((history.current(), history.recent_max()), (30_000_000, 0));
+
+        history.on_new_head(1, 20_000_000);
+        history.on_new_head(2, 10_000_000);
+        assert_eq!((history.current(), history.recent_max()), (10_000_000, 20_000_000));
+        history.on_new_head(3, 15_000_000);
+        assert_eq!((history.current(), history.recent_max()), (15_000_000, 15_000_000));
+
+        // reorged blocks are forgotten
+        history.on_new_head(3, 5_000_000);
+        assert_eq!((history.current(), history.recent_max()), (5_000_000, 10_000_000));
+
+        // no history
+        let mut history = GasLimitHistory::new(30_000_000, 0);
+        history.on_new_head(1, 20_000_000);
+        assert_eq!((history.current(), history.recent_max()), (20_000_000, 0));
+    }
+
+    #[test]
+    fn invalid_below_minimum_priority_fee() {
+        // the transaction pays a priority fee of 3 gwei
+        let transaction = get_transaction();
+        assert_eq!(transaction.max_priority_fee_per_gas(), Some(3_000_000_000));
+
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            transaction.sender(),
+            ExtendedAccount::new(transaction.nonce(), U256::MAX),
+        );
+
+        let validator = |min_priority_fee| {
+            let chain_spec = ChainSpec {
+                fee_floors: FeeFloors { min_priority_fee, ..Default::default() },
+                ..(*MAINNET).clone()
+            };
+            EthTransactionValidatorBuilder::new(Arc::new(chain_spec))
+                .build(provider.clone(), InMemoryBlobStore::default())
+        };
+
+        let outcome = validator(Some(3_000_000_000))
+            .validate_one(TransactionOrigin::Local, transaction.clone());
+        assert!(outcome.is_valid());
+
+        let outcome =
+            validator(Some(3_000_000_001)).validate_one(TransactionOrigin::Local, transaction);
+        assert!(matches!(
+            outcome,
+            TransactionValidationOutcome::Invalid(_, InvalidPoolTransactionError::Underpriced)
+        ));
+    }
 }
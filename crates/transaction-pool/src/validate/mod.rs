@@ -3,7 +3,7 @@
 use crate::{
     error::InvalidPoolTransactionError,
     identifier::{SenderId, TransactionId},
-    traits::{PoolTransaction, TransactionOrigin},
+    traits::{PeerId, PoolTransaction, TransactionOrigin},
 };
 use reth_primitives::{
     Address, BlobTransactionSidecar, IntoRecoveredTransaction, SealedBlock,
@@ -23,7 +23,8 @@ pub use task::{TransactionValidationTaskExecutor, ValidationTask};
 
 /// Validation constants.
 pub use constants::{
-    DEFAULT_MAX_TX_INPUT_BYTES, MAX_CODE_BYTE_SIZE, MAX_INIT_CODE_BYTE_SIZE, TX_SLOT_BYTE_SIZE,
+    DEFAULT_GAS_LIMIT_HISTORY, DEFAULT_MAX_TX_INPUT_BYTES, MAX_CODE_BYTE_SIZE,
+    MAX_INIT_CODE_BYTE_SIZE, TX_SLOT_BYTE_SIZE,
 };
 
 /// A Result type returned after checking a transaction's validity.
@@ -225,6 +226,11 @@ pub struct ValidPoolTransaction<T: PoolTransaction> {
     pub timestamp: Instant,
     /// Where this transaction originated from.
     pub origin: TransactionOrigin,
+    /// The peer an [external](TransactionOrigin::External) transaction was received from.
+    ///
+    /// This is only used to attribute transactions to peers within the node, e.g. in
+    /// [`FullTransactionEvent`](crate::FullTransactionEvent)s, and never exposed over RPC.
+    pub peer: Option<PeerId>,
 }
 
 // === impl ValidPoolTransaction ===
@@ -354,6 +360,7 @@ impl<T: PoolTransaction + Clone> Clone for ValidPoolTransaction<T> {
             propagate: self.propagate,
             timestamp: self.timestamp,
             origin: self.origin,
+            peer: self.peer,
         }
     }
 }
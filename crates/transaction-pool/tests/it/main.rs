@@ -7,6 +7,14 @@ mod evict;
 #[cfg(feature = "test-utils")]
 mod listeners;
 #[cfg(feature = "test-utils")]
+mod metrics;
+#[cfg(feature = "test-utils")]
+mod mined;
+#[cfg(feature = "test-utils")]
 mod pending;
+#[cfg(feature = "test-utils")]
+mod seen;
+#[cfg(feature = "test-utils")]
+mod validate;
 
 const fn main() {}
@@ -0,0 +1,43 @@
+use metrics_util::debugging::{DebugValue, DebuggingRecorder, Snapshotter};
+use reth_metrics::MetricsRegistrar;
+use reth_transaction_pool::{
+    test_utils::{MockTransactionFactory, TestPoolBuilder},
+    TransactionOrigin, TransactionPool,
+};
+
+fn counter(snapshotter: &Snapshotter, name: &str) -> Option<u64> {
+    snapshotter.snapshot().into_vec().into_iter().find_map(|(key, _, _, value)| match value {
+        DebugValue::Counter(value) if key.key().name() == name => Some(value),
+        _ => None,
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_registers_metrics() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    let mut registrar = MetricsRegistrar::new();
+    let txpool = metrics::with_local_recorder(&recorder, || {
+        let txpool = TestPoolBuilder::default();
+        txpool.register_metrics(&mut registrar);
+        txpool
+    });
+
+    for name in [
+        "transaction_pool.inserted_transactions",
+        "transaction_pool.pending_pool_transactions",
+        "transaction_pool.blobstore_entries",
+        "transaction_pool.dirty_accounts",
+        "transaction_pool.all_transactions_by_hash",
+    ] {
+        assert!(registrar.contains(name), "{name}");
+    }
+    assert_eq!(counter(&snapshotter, "transaction_pool.inserted_transactions"), Some(0));
+
+    let transaction = MockTransactionFactory::default().create_eip1559();
+    txpool
+        .add_transaction(TransactionOrigin::External, transaction.transaction.clone())
+        .await
+        .unwrap();
+    assert_eq!(counter(&snapshotter, "transaction_pool.inserted_transactions"), Some(1));
+}
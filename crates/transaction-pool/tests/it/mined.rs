@@ -0,0 +1,154 @@
+//! Tests for transactions that reuse the nonce of a recently mined transaction.
+
+use assert_matches::assert_matches;
+use reth_primitives::{
+    Header, IntoRecoveredTransaction, InvalidTransactionError, SealedBlock, SealedBlockWithSenders,
+    B256, U256,
+};
+use reth_transaction_pool::{
+    error::{InvalidPoolTransactionError, PoolErrorKind},
+    test_utils::{MockTransaction, TestPool, TestPoolBuilder},
+    CanonicalStateUpdate, ChangedAccount, MinedReplacementPolicy, PoolConfig, RecentlyMinedConfig,
+    TransactionOrigin, TransactionPool, TransactionPoolExt,
+};
+
+fn block(number: u64, fork: u8, txs: &[&MockTransaction]) -> SealedBlockWithSenders {
+    let body = txs.iter().map(|tx| tx.to_recovered_transaction().into_signed()).collect();
+    let senders = txs.iter().map(|tx| tx.get_sender()).collect();
+    let header =
+        Header { number, mix_hash: B256::with_last_byte(fork), ..Default::default() }.seal_slow();
+    SealedBlockWithSenders::new(SealedBlock { header, body, ..Default::default() }, senders)
+        .unwrap()
+}
+
+/// Makes the block canonical, like the pool maintenance task does.
+fn commit(
+    pool: &TestPool,
+    block: &SealedBlockWithSenders,
+    sender_nonce: u64,
+    tx: &MockTransaction,
+) {
+    pool.on_canonical_state_change(CanonicalStateUpdate {
+        new_tip: &block.block,
+        pending_block_base_fee: 0,
+        pending_block_blob_fee: None,
+        changed_accounts: vec![ChangedAccount {
+            address: tx.get_sender(),
+            nonce: sender_nonce,
+            balance: U256::MAX,
+        }],
+        mined_transactions: block.body.iter().map(|tx| tx.hash()).collect(),
+    });
+    pool.on_mined_blocks(vec![block]);
+}
+
+/// Reorgs the `old` block out in favor of the `new` block, like the pool maintenance task does.
+async fn reorg(
+    pool: &TestPool,
+    old: &SealedBlockWithSenders,
+    new: &SealedBlockWithSenders,
+    tx: &MockTransaction,
+) {
+    let released = pool.on_reverted_blocks(vec![old.num_hash()]);
+    commit(pool, new, tx.get_nonce(), tx);
+
+    let reinserted = released.into_iter().chain(std::iter::once(tx.clone())).collect();
+    let _ = pool.add_external_transactions(reinserted).await;
+}
+
+fn assert_nonce_too_low(kind: PoolErrorKind) {
+    assert_matches!(
+        kind,
+        PoolErrorKind::InvalidTransaction(InvalidPoolTransactionError::Consensus(
+            InvalidTransactionError::NonceNotConsistent
+        ))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_replacement_of_mined_transaction() {
+    let pool: TestPool = TestPoolBuilder::default().into();
+
+    let tx = MockTransaction::eip1559().with_gas_price(1_000);
+    pool.add_transaction(TransactionOrigin::External, tx.clone()).await.unwrap();
+
+    let mined = block(1, 0, &[&tx]);
+    commit(&pool, &mined, tx.get_nonce() + 1, &tx);
+    assert!(pool.is_empty());
+
+    // the sender replaces the mined transaction right away
+    let replacement = tx.inc_price_by(1_000).rng_hash();
+    let err = pool.add_transaction(TransactionOrigin::External, replacement).await.unwrap_err();
+    assert_nonce_too_low(err.kind);
+
+    // a shallow reorg un-mines the original transaction
+    reorg(&pool, &mined, &block(1, 1, &[]), &tx).await;
+
+    assert_eq!(pool.len(), 1);
+    assert!(pool.contains(&tx.get_hash()));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn quarantines_replacement_until_reorg() {
+    let config = PoolConfig {
+        recently_mined: RecentlyMinedConfig {
+            replacement_policy: MinedReplacementPolicy::Quarantine,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let pool: TestPool = TestPoolBuilder::default().with_config(config).into();
+
+    let tx = MockTransaction::eip1559().with_gas_price(1_000);
+    pool.add_transaction(TransactionOrigin::External, tx.clone()).await.unwrap();
+
+    let mined = block(1, 0, &[&tx]);
+    commit(&pool, &mined, tx.get_nonce() + 1, &tx);
+
+    // replacements that don't bump the fees of the mined transaction are rejected
+    let underpriced = tx.inc_price_by(1).rng_hash();
+    let err = pool.add_transaction(TransactionOrigin::External, underpriced).await.unwrap_err();
+    assert_nonce_too_low(err.kind);
+
+    // a fee-bumped replacement is accepted, but held back while the original is mined
+    let replacement = tx.inc_price_by(1_000).rng_hash();
+    let hash = pool.add_transaction(TransactionOrigin::External, replacement.clone()).await;
+    assert_eq!(hash.unwrap(), replacement.get_hash());
+    assert!(pool.is_empty());
+
+    // a shallow reorg un-mines the original transaction, the replacement takes its place
+    reorg(&pool, &mined, &block(1, 1, &[]), &tx).await;
+
+    assert_eq!(pool.len(), 1);
+    assert!(pool.contains(&replacement.get_hash()));
+    assert!(!pool.contains(&tx.get_hash()));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn requarantines_replacement_if_original_is_mined_again() {
+    let config = PoolConfig {
+        recently_mined: RecentlyMinedConfig {
+            replacement_policy: MinedReplacementPolicy::Quarantine,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let pool: TestPool = TestPoolBuilder::default().with_config(config).into();
+
+    let tx = MockTransaction::eip1559().with_gas_price(1_000);
+    let mined = block(1, 0, &[&tx]);
+    commit(&pool, &mined, tx.get_nonce() + 1, &tx);
+
+    let replacement = tx.inc_price_by(1_000).rng_hash();
+    pool.add_transaction(TransactionOrigin::External, replacement.clone()).await.unwrap();
+    assert!(pool.is_empty());
+
+    // the new chain includes the original transaction as well
+    let released = pool.on_reverted_blocks(vec![mined.num_hash()]);
+    assert_eq!(released, vec![replacement]);
+    commit(&pool, &block(1, 1, &[&tx]), tx.get_nonce() + 1, &tx);
+
+    // the released replacement collides with the re-mined original and is quarantined again
+    let _ = pool.add_external_transactions(released).await;
+    assert!(pool.is_empty());
+}
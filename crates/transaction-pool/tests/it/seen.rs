@@ -0,0 +1,242 @@
+//! Tests for transactions that reach the pool more than once.
+
+use assert_matches::assert_matches;
+use reth_eth_wire_types::PartiallyValidData;
+use reth_primitives::{
+    Header, IntoRecoveredTransaction, InvalidTransactionError, SealedBlock, SealedBlockWithSenders,
+    TxHash, U256,
+};
+use reth_transaction_pool::{
+    blobstore::InMemoryBlobStore,
+    error::{InvalidPoolTransactionError, PoolErrorKind},
+    noop::MockTransactionValidator,
+    test_utils::{MockOrdering, MockTransaction},
+    CanonicalStateUpdate, ChangedAccount, PersistentRejections, Pool, PoolConfig, Seen,
+    SeenTransactionsConfig, TransactionEvent, TransactionOrigin, TransactionPool,
+    TransactionPoolExt, TransactionValidationOutcome, TransactionValidator,
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio_stream::StreamExt;
+
+/// Gas limit above which the [`CountingValidator`] rejects transactions.
+const MAX_GAS_LIMIT: u64 = 1_000_000;
+
+/// Counts the validated transactions and rejects those above [`MAX_GAS_LIMIT`].
+///
+/// A gas limit of [`u64::MAX`] is rejected as an overflow, which never becomes valid.
+#[derive(Debug, Default, Clone)]
+struct CountingValidator {
+    inner: MockTransactionValidator<MockTransaction>,
+    validated: Arc<AtomicUsize>,
+}
+
+impl CountingValidator {
+    fn validated(&self) -> usize {
+        self.validated.load(Ordering::Relaxed)
+    }
+}
+
+impl TransactionValidator for CountingValidator {
+    type Transaction = MockTransaction;
+
+    async fn validate_transaction(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+    ) -> TransactionValidationOutcome<Self::Transaction> {
+        self.validated.fetch_add(1, Ordering::Relaxed);
+        let gas_limit = transaction.get_gas_limit();
+        if gas_limit == u64::MAX {
+            return TransactionValidationOutcome::Invalid(
+                transaction,
+                InvalidPoolTransactionError::Consensus(InvalidTransactionError::GasUintOverflow),
+            )
+        }
+        if gas_limit > MAX_GAS_LIMIT {
+            return TransactionValidationOutcome::Invalid(
+                transaction,
+                InvalidPoolTransactionError::ExceedsGasLimit(gas_limit, MAX_GAS_LIMIT),
+            )
+        }
+        self.inner.validate_transaction(origin, transaction).await
+    }
+}
+
+type CountingPool = Pool<CountingValidator, MockOrdering, InMemoryBlobStore>;
+
+fn pool(validator: CountingValidator, config: PoolConfig) -> CountingPool {
+    Pool::new(validator, MockOrdering::default(), InMemoryBlobStore::default(), config)
+}
+
+/// Returns the number of announced hashes the pool would fetch.
+fn unknown(pool: &CountingPool, hash: TxHash) -> usize {
+    let mut announcement = PartiallyValidData::from_raw_data(HashMap::from([(hash, ())]), None);
+    pool.retain_unknown(&mut announcement);
+    announcement.len()
+}
+
+/// Makes a block with the transaction canonical, like the pool maintenance task does.
+fn mine(pool: &CountingPool, number: u64, tx: &MockTransaction) {
+    let header = Header { number, ..Default::default() }.seal_slow();
+    let body = vec![tx.to_recovered_transaction().into_signed()];
+    let senders = vec![tx.get_sender()];
+    let block =
+        SealedBlockWithSenders::new(SealedBlock { header, body, ..Default::default() }, senders)
+            .unwrap();
+
+    pool.on_canonical_state_change(CanonicalStateUpdate {
+        new_tip: &block.block,
+        pending_block_base_fee: 0,
+        pending_block_blob_fee: None,
+        changed_accounts: vec![ChangedAccount {
+            address: tx.get_sender(),
+            nonce: tx.get_nonce() + 1,
+            balance: U256::MAX,
+        }],
+        mined_transactions: vec![tx.get_hash()],
+    });
+    pool.on_mined_blocks(vec![&block]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn validates_replayed_transaction_once() {
+    let validator = CountingValidator::default();
+    let pool = pool(validator.clone(), Default::default());
+
+    // the transaction arrives in a `PooledTransactions` response
+    let tx = MockTransaction::eip1559();
+    let hash = tx.get_hash();
+    pool.add_external_transaction(tx.clone()).await.unwrap();
+    assert_eq!(pool.seen(&hash), Some(Seen::InPool));
+
+    // it's announced and sent again by other peers
+    assert_eq!(unknown(&pool, hash), 0);
+    let err = pool.add_external_transaction(tx.clone()).await.unwrap_err();
+    assert_matches!(err.kind, PoolErrorKind::AlreadyImported);
+
+    // it's mined in an imported block, and announced and sent again by peers that are behind
+    mine(&pool, 1, &tx);
+    assert_eq!(pool.seen(&hash), Some(Seen::Mined(1)));
+    assert_eq!(unknown(&pool, hash), 0);
+    let err = pool.add_external_transaction(tx.clone()).await.unwrap_err();
+    assert_matches!(err.kind, PoolErrorKind::InvalidTransaction(_));
+
+    // a transaction that's rejected is neither fetched nor validated again
+    let invalid = tx.next().with_gas_limit(MAX_GAS_LIMIT + 1);
+    let invalid_hash = invalid.get_hash();
+    for _ in 0..3 {
+        assert!(pool.add_external_transaction(invalid.clone()).await.is_err());
+    }
+    assert_matches!(pool.seen(&invalid_hash), Some(Seen::Rejected(_)));
+    assert_eq!(unknown(&pool, invalid_hash), 0);
+    let results = pool.add_external_transactions(vec![invalid.clone(), invalid]).await;
+    assert!(results.iter().all(|result| {
+        matches!(result, Err(err) if matches!(err.kind, PoolErrorKind::PreviouslyRejected(_)))
+    }));
+
+    assert_eq!(validator.validated(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn replayed_transaction_keeps_subscribers() {
+    let pool = pool(CountingValidator::default(), Default::default());
+
+    let tx = MockTransaction::eip1559();
+    let mut events =
+        pool.add_transaction_and_subscribe(TransactionOrigin::External, tx.clone()).await.unwrap();
+    assert_matches!(events.next().await, Some(TransactionEvent::Pending));
+
+    // the pooled transaction is sent again, which doesn't end the event stream of its subscriber
+    let err = pool.add_external_transaction(tx.clone()).await.unwrap_err();
+    assert_matches!(err.kind, PoolErrorKind::AlreadyImported);
+
+    mine(&pool, 1, &tx);
+    assert_matches!(events.next().await, Some(TransactionEvent::Mined(_)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn would_accept_rejects_seen_transactions() {
+    let validator = CountingValidator::default();
+    let pool = pool(validator.clone(), Default::default());
+
+    let tx = MockTransaction::eip1559();
+    let mut events =
+        pool.add_transaction_and_subscribe(TransactionOrigin::External, tx.clone()).await.unwrap();
+    assert_matches!(events.next().await, Some(TransactionEvent::Pending));
+    let invalid = tx.next().with_gas_limit(MAX_GAS_LIMIT + 1);
+    assert!(pool.add_external_transaction(invalid.clone()).await.is_err());
+    assert_eq!(validator.validated(), 2);
+
+    // seen transactions are rejected like `add_transaction` does, without validating them again
+    let err = pool.would_accept(TransactionOrigin::External, tx.clone()).await.unwrap_err();
+    assert_matches!(err.kind, PoolErrorKind::AlreadyImported);
+    let err = pool.would_accept(TransactionOrigin::External, invalid).await.unwrap_err();
+    assert_matches!(err.kind, PoolErrorKind::PreviouslyRejected(_));
+    assert_eq!(validator.validated(), 2);
+
+    // the check has no side effects on the listeners of the pooled transaction
+    mine(&pool, 1, &tx);
+    assert_matches!(events.next().await, Some(TransactionEvent::Mined(_)));
+    let err = pool.would_accept(TransactionOrigin::External, tx).await.unwrap_err();
+    assert_matches!(err.kind, PoolErrorKind::InvalidTransaction(_));
+    assert_eq!(validator.validated(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn revalidates_after_ttl() {
+    let validator = CountingValidator::default();
+    let ttl = Duration::from_millis(100);
+    let config = PoolConfig {
+        seen_transactions: SeenTransactionsConfig { ttl, ..Default::default() },
+        ..Default::default()
+    };
+    let pool = pool(validator.clone(), config);
+
+    let invalid = MockTransaction::eip1559().with_gas_limit(MAX_GAS_LIMIT + 1);
+    let hash = invalid.get_hash();
+    assert!(pool.add_external_transaction(invalid.clone()).await.is_err());
+    assert!(pool.add_external_transaction(invalid.clone()).await.is_err());
+    assert_eq!(validator.validated(), 1);
+
+    std::thread::sleep(ttl * 2);
+    assert_eq!(pool.seen(&hash), Some(Seen::Expired));
+    assert_eq!(unknown(&pool, hash), 1);
+    assert!(pool.add_external_transaction(invalid).await.is_err());
+    assert_eq!(validator.validated(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn persists_never_valid_rejections() {
+    let dir = tempfile::tempdir().unwrap();
+    let open = || PersistentRejections::open(dir.path(), Default::default()).unwrap();
+
+    let never_valid = MockTransaction::eip1559().with_gas_limit(u64::MAX);
+    let state_dependent = never_valid.next().with_gas_limit(MAX_GAS_LIMIT + 1);
+
+    let validator = CountingValidator::default();
+    let first = pool(validator.clone(), Default::default()).with_persistent_rejections(open());
+    assert!(first.add_external_transaction(never_valid.clone()).await.is_err());
+    assert!(first.add_external_transaction(state_dependent.clone()).await.is_err());
+    assert_eq!(validator.validated(), 2);
+    drop(first);
+
+    // after a restart only the rejection that can never become valid is remembered
+    let validator = CountingValidator::default();
+    let second = pool(validator.clone(), Default::default()).with_persistent_rejections(open());
+    assert_matches!(second.seen(&never_valid.get_hash()), Some(Seen::Rejected(_)));
+    assert_eq!(unknown(&second, never_valid.get_hash()), 0);
+    let err = second.add_external_transaction(never_valid).await.unwrap_err();
+    assert_matches!(err.kind, PoolErrorKind::PreviouslyRejected(_));
+    assert_eq!(validator.validated(), 0);
+
+    assert_eq!(second.seen(&state_dependent.get_hash()), None);
+    assert!(second.add_external_transaction(state_dependent).await.is_err());
+    assert_eq!(validator.validated(), 1);
+}
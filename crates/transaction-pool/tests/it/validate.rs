@@ -0,0 +1,92 @@
+//! Tests for validating batches of transactions.
+
+use reth_primitives::TxHash;
+use reth_transaction_pool::{
+    blobstore::InMemoryBlobStore,
+    noop::MockTransactionValidator,
+    test_utils::{MockOrdering, MockTransaction},
+    Pool, PoolConfig, TransactionOrigin, TransactionPool, TransactionValidationOutcome,
+    TransactionValidator,
+};
+use std::time::{Duration, Instant};
+
+/// Sleeps for as many milliseconds as the gas limit of the transaction before validating it.
+#[derive(Debug, Default, Clone)]
+struct SleepingValidator {
+    inner: MockTransactionValidator<MockTransaction>,
+}
+
+impl TransactionValidator for SleepingValidator {
+    type Transaction = MockTransaction;
+
+    async fn validate_transaction(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+    ) -> TransactionValidationOutcome<Self::Transaction> {
+        tokio::time::sleep(Duration::from_millis(transaction.get_gas_limit())).await;
+        self.inner.validate_transaction(origin, transaction).await
+    }
+}
+
+fn pool(
+    max_concurrent_validations: usize,
+) -> Pool<SleepingValidator, MockOrdering, InMemoryBlobStore> {
+    Pool::new(
+        SleepingValidator::default(),
+        MockOrdering::default(),
+        InMemoryBlobStore::default(),
+        PoolConfig { max_concurrent_validations, ..Default::default() },
+    )
+}
+
+/// Returns transactions of different senders that take `delay_ms` to validate each.
+fn transactions(count: usize, delay_ms: u64) -> Vec<MockTransaction> {
+    (0..count).map(|_| MockTransaction::eip1559().with_gas_limit(delay_ms)).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn validates_batch_concurrently() {
+    const COUNT: usize = 32;
+    const DELAY_MS: u64 = 20;
+    let serial = Duration::from_millis(COUNT as u64 * DELAY_MS);
+
+    let pool = pool(COUNT);
+    let start = Instant::now();
+    let results = pool.add_external_transactions(transactions(COUNT, DELAY_MS)).await;
+    let elapsed = start.elapsed();
+    assert!(results.iter().all(Result::is_ok));
+    assert_eq!(pool.len(), COUNT);
+    // the whole batch takes about as long as a single validation
+    assert!(elapsed < serial / 4, "validating the batch took {elapsed:?}");
+
+    // at most 4 transactions are validated at a time
+    let pool = self::pool(4);
+    let start = Instant::now();
+    let results = pool.add_external_transactions(transactions(COUNT, DELAY_MS)).await;
+    assert!(results.iter().all(Result::is_ok));
+    assert!(start.elapsed() >= serial / 4);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn batch_results_keep_input_order() {
+    let pool = pool(16);
+
+    // later transactions finish their validation first
+    let txs = (0..16u64)
+        .map(|idx| MockTransaction::eip1559().with_gas_limit(2 * (16 - idx)))
+        .collect::<Vec<_>>();
+    let hashes = txs.iter().map(|tx| tx.get_hash()).collect::<Vec<_>>();
+
+    let results = pool.add_transactions(TransactionOrigin::Local, txs).await;
+    let added = results.into_iter().map(Result::unwrap).collect::<Vec<TxHash>>();
+    assert_eq!(added, hashes);
+
+    // the hash of an invalid transaction keeps its position as well
+    let valid = MockTransaction::eip1559().with_gas_limit(10);
+    let duplicate = pool.get(&hashes[0]).unwrap().transaction.clone();
+    let results =
+        pool.add_transactions(TransactionOrigin::Local, vec![duplicate, valid.clone()]).await;
+    assert_eq!(results[0].as_ref().unwrap_err().hash, hashes[0]);
+    assert_eq!(results[1].as_ref().unwrap(), &valid.get_hash());
+}
@@ -12,7 +12,7 @@ use reth_trie::{
     hashed_cursor::HashedPostStateCursorFactory, HashedPostState, HashedStorage, StateRoot,
 };
 use reth_trie_parallel::{async_root::AsyncStateRoot, parallel_root::ParallelStateRoot};
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 pub fn calculate_state_root(c: &mut Criterion) {
     let mut group = c.benchmark_group("Calculate State Root");
@@ -74,6 +74,37 @@ pub fn calculate_state_root(c: &mut Criterion) {
     }
 }
 
+pub fn calculate_state_root_with_threads(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Calculate Parallel State Root");
+    group.sample_size(20);
+
+    // storage roots of the updated accounts dominate the calculation
+    let (db_state, updated_state) = generate_test_data(3_000);
+    let provider_factory = create_test_provider_factory();
+    {
+        let provider_rw = provider_factory.provider_rw().unwrap();
+        HashedStateChanges(db_state).write_to_db(provider_rw.tx_ref()).unwrap();
+        let (_, updates) = StateRoot::from_tx(provider_rw.tx_ref()).root_with_updates().unwrap();
+        updates.flush(provider_rw.tx_ref()).unwrap();
+        provider_rw.commit().unwrap();
+    }
+
+    let view = ConsistentDbView::new(provider_factory, None);
+
+    for threads in [1, 2, 4, 8] {
+        let pool = Arc::new(ThreadPoolBuilder::new().num_threads(threads).build().unwrap());
+        group.bench_function(BenchmarkId::new("threads", threads), |b| {
+            b.iter_with_setup(
+                || {
+                    ParallelStateRoot::new(view.clone(), updated_state.clone())
+                        .with_thread_pool(pool.clone())
+                },
+                |calculator| calculator.incremental_root(),
+            );
+        });
+    }
+}
+
 fn generate_test_data(size: usize) -> (HashedPostState, HashedPostState) {
     let storage_size = 1_000;
     let mut runner = TestRunner::new(ProptestConfig::default());
@@ -131,5 +162,5 @@ fn generate_test_data(size: usize) -> (HashedPostState, HashedPostState) {
     )
 }
 
-criterion_group!(state_root, calculate_state_root);
+criterion_group!(state_root, calculate_state_root, calculate_state_root_with_threads);
 criterion_main!(state_root);
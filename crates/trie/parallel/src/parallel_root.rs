@@ -1,19 +1,22 @@
 use crate::{stats::ParallelTrieTracker, storage_root_targets::StorageRootTargets};
 use alloy_rlp::{BufMut, Encodable};
-use rayon::prelude::*;
+use rayon::{prelude::*, ThreadPool};
 use reth_db_api::database::Database;
 use reth_execution_errors::StorageRootError;
 use reth_primitives::{proofs::IntoTrieAccount, B256};
-use reth_provider::{providers::ConsistentDbView, DatabaseProviderFactory, ProviderError};
+use reth_provider::{
+    providers::ConsistentDbView, DatabaseProviderFactory, DatabaseProviderRO, ProviderError,
+};
 use reth_trie::{
     hashed_cursor::{HashedCursorFactory, HashedPostStateCursorFactory},
     node_iter::{TrieElement, TrieNodeIter},
+    prefix_set::PrefixSet,
     trie_cursor::TrieCursorFactory,
     updates::TrieUpdates,
     walker::TrieWalker,
-    HashBuilder, HashedPostState, Nibbles, StorageRoot,
+    HashBuilder, HashedPostState, HashedPostStateSorted, Nibbles, StorageRoot,
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 use thiserror::Error;
 use tracing::*;
 
@@ -31,6 +34,11 @@ use crate::metrics::ParallelStateRootMetrics;
 /// the last transaction is open.
 /// See docs of using [`ConsistentDbView`] for caveats.
 ///
+/// The thread pool used for the storage roots can be configured with
+/// [`ParallelStateRoot::with_thread_pool`]. Each thread opens its own read transaction, while the
+/// accounts trie is always walked on the calling thread, so the resulting root and trie updates
+/// don't depend on the number of threads.
+///
 /// If possible, use more optimized `AsyncStateRoot` instead.
 #[derive(Debug)]
 pub struct ParallelStateRoot<DB, Provider> {
@@ -38,6 +46,8 @@ pub struct ParallelStateRoot<DB, Provider> {
     view: ConsistentDbView<DB, Provider>,
     /// Changed hashed state.
     hashed_state: HashedPostState,
+    /// The thread pool to compute storage roots on, the global rayon pool is used if unset.
+    pool: Option<Arc<ThreadPool>>,
     /// Parallel state root metrics.
     #[cfg(feature = "metrics")]
     metrics: ParallelStateRootMetrics,
@@ -49,10 +59,21 @@ impl<DB, Provider> ParallelStateRoot<DB, Provider> {
         Self {
             view,
             hashed_state,
+            pool: None,
             #[cfg(feature = "metrics")]
             metrics: ParallelStateRootMetrics::default(),
         }
     }
+
+    /// Set the thread pool to compute storage roots on instead of the global rayon pool.
+    ///
+    /// The pool is meant to be built once and shared by all calculations. If it has a single
+    /// thread, all storage roots are computed sequentially on the calling thread using one read
+    /// transaction.
+    pub fn with_thread_pool(mut self, pool: Arc<ThreadPool>) -> Self {
+        self.pool = Some(pool);
+        self
+    }
 }
 
 impl<DB, Provider> ParallelStateRoot<DB, Provider>
@@ -86,23 +107,49 @@ where
 
         // Pre-calculate storage roots in parallel for accounts which were changed.
         tracker.set_precomputed_storage_roots(storage_root_targets.len() as u64);
-        debug!(target: "trie::parallel_state_root", len = storage_root_targets.len(), "pre-calculating storage roots");
-        let mut storage_roots = storage_root_targets
-            .into_par_iter()
-            .map(|(hashed_address, prefix_set)| {
-                let provider_ro = self.view.provider_ro()?;
-                let storage_root_result = StorageRoot::new_hashed(
-                    provider_ro.tx_ref(),
-                    HashedPostStateCursorFactory::new(provider_ro.tx_ref(), &hashed_state_sorted),
-                    hashed_address,
-                    #[cfg(feature = "metrics")]
-                    self.metrics.storage_trie.clone(),
-                )
-                .with_prefix_set(prefix_set)
-                .calculate(retain_updates);
-                Ok((hashed_address, storage_root_result?))
-            })
-            .collect::<Result<HashMap<_, _>, ParallelStateRootError>>()?;
+        let threads = self.pool.as_ref().map(|pool| pool.current_num_threads());
+        debug!(target: "trie::parallel_state_root", len = storage_root_targets.len(), ?threads, "pre-calculating storage roots");
+        let mut storage_roots = if threads == Some(1) {
+            let provider_ro = self.view.provider_ro()?;
+            storage_root_targets
+                .into_iter()
+                .map(|(hashed_address, prefix_set)| {
+                    let storage_root_result = self.storage_root(
+                        &provider_ro,
+                        &hashed_state_sorted,
+                        hashed_address,
+                        prefix_set,
+                        retain_updates,
+                    );
+                    Ok((hashed_address, storage_root_result?))
+                })
+                .collect::<Result<HashMap<_, _>, ParallelStateRootError>>()?
+        } else {
+            let calculate = || {
+                storage_root_targets
+                    .into_par_iter()
+                    // reuse one read transaction per rayon job instead of opening one per account
+                    .map_init(
+                        || self.view.provider_ro(),
+                        |provider_ro, (hashed_address, prefix_set)| {
+                            let provider_ro = provider_ro.as_ref().map_err(Clone::clone)?;
+                            let storage_root_result = self.storage_root(
+                                provider_ro,
+                                &hashed_state_sorted,
+                                hashed_address,
+                                prefix_set,
+                                retain_updates,
+                            );
+                            Ok((hashed_address, storage_root_result?))
+                        },
+                    )
+                    .collect::<Result<HashMap<_, _>, ParallelStateRootError>>()
+            };
+            match &self.pool {
+                Some(pool) => pool.install(calculate)?,
+                None => calculate()?,
+            }
+        };
 
         trace!(target: "trie::parallel_state_root", "calculating state root");
         let mut trie_updates = TrieUpdates::default();
@@ -185,6 +232,26 @@ where
 
         Ok((root, trie_updates))
     }
+
+    /// Calculate the storage root of a single account.
+    fn storage_root(
+        &self,
+        provider_ro: &DatabaseProviderRO<DB>,
+        hashed_state_sorted: &HashedPostStateSorted,
+        hashed_address: B256,
+        prefix_set: PrefixSet,
+        retain_updates: bool,
+    ) -> Result<(B256, usize, TrieUpdates), StorageRootError> {
+        StorageRoot::new_hashed(
+            provider_ro.tx_ref(),
+            HashedPostStateCursorFactory::new(provider_ro.tx_ref(), hashed_state_sorted),
+            hashed_address,
+            #[cfg(feature = "metrics")]
+            self.metrics.storage_trie.clone(),
+        )
+        .with_prefix_set(prefix_set)
+        .calculate(retain_updates)
+    }
 }
 
 /// Error during parallel state root calculation.
@@ -212,10 +279,13 @@ impl From<ParallelStateRootError> for ProviderError {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rand::Rng;
+    use rand::{seq::IteratorRandom, Rng};
     use reth_primitives::{keccak256, Account, Address, StorageEntry, U256};
-    use reth_provider::{test_utils::create_test_provider_factory, HashingWriter};
+    use reth_provider::{
+        bundle_state::HashedStateChanges, test_utils::create_test_provider_factory, HashingWriter,
+    };
     use reth_trie::{test_utils, HashedStorage};
+    use std::collections::BTreeMap;
 
     #[tokio::test]
     async fn random_parallel_root() {
@@ -299,4 +369,92 @@ mod tests {
             test_utils::state_root(state)
         );
     }
+
+    #[test]
+    fn thread_count_does_not_affect_root() {
+        let factory = create_test_provider_factory();
+        let mut rng = rand::thread_rng();
+
+        // a few large contracts among plain accounts with little storage
+        let mut state = BTreeMap::<B256, (Account, BTreeMap<B256, U256>)>::new();
+        let mut changes = HashedPostState::default();
+        for idx in 0..50 {
+            let account =
+                Account { balance: U256::from(rng.gen::<u64>() | 1), ..Default::default() };
+            let slots = if idx < 5 { 2_000 } else { rng.gen_range(0..10) };
+            let storage = (0..slots)
+                .map(|_| (B256::random(), U256::from(rng.gen::<u64>() | 1)))
+                .collect::<BTreeMap<_, _>>();
+            let hashed_address = B256::random();
+            changes.accounts.insert(hashed_address, Some(account));
+            changes
+                .storages
+                .insert(hashed_address, HashedStorage::from_iter(false, storage.clone()));
+            state.insert(hashed_address, (account, storage));
+        }
+
+        let pools = [1, 2, 8].map(|threads| {
+            Arc::new(rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap())
+        });
+
+        for block in 0..4 {
+            // the first block creates the state, all subsequent blocks update it
+            if block > 0 {
+                changes = HashedPostState::default();
+                for (hashed_address, (account, storage)) in state.iter_mut().take(10) {
+                    if rng.gen_bool(0.5) {
+                        account.balance += U256::from(1);
+                        changes.accounts.insert(*hashed_address, Some(*account));
+                    }
+
+                    let mut storage_changes = HashedStorage::new(false);
+                    let updated =
+                        storage.keys().copied().choose_multiple(&mut rng, storage.len() / 5);
+                    for (idx, slot) in updated.into_iter().enumerate() {
+                        // delete every third updated slot
+                        let value = if idx % 3 == 0 {
+                            U256::ZERO
+                        } else {
+                            U256::from(rng.gen::<u64>() | 1)
+                        };
+                        storage_changes.storage.insert(slot, value);
+                    }
+                    for _ in 0..storage.len() / 10 {
+                        storage_changes
+                            .storage
+                            .insert(B256::random(), U256::from(rng.gen::<u64>() | 1));
+                    }
+                    for (slot, value) in &storage_changes.storage {
+                        if value.is_zero() {
+                            storage.remove(slot);
+                        } else {
+                            storage.insert(*slot, *value);
+                        }
+                    }
+                    changes.storages.insert(*hashed_address, storage_changes);
+                }
+            }
+
+            let view = ConsistentDbView::new(factory.clone(), None);
+            let (root, updates) = ParallelStateRoot::new(view.clone(), changes.clone())
+                .incremental_root_with_updates()
+                .unwrap();
+            assert_eq!(root, test_utils::state_root_prehashed(state.clone()));
+
+            for pool in &pools {
+                let result = ParallelStateRoot::new(view.clone(), changes.clone())
+                    .with_thread_pool(pool.clone())
+                    .incremental_root_with_updates()
+                    .unwrap();
+                let threads = pool.current_num_threads();
+                assert_eq!(result, (root, updates.clone()), "threads: {threads}");
+            }
+
+            // persist the block, so the next one is computed incrementally
+            let provider_rw = factory.provider_rw().unwrap();
+            HashedStateChanges(changes.clone()).write_to_db(provider_rw.tx_ref()).unwrap();
+            updates.flush(provider_rw.tx_ref()).unwrap();
+            provider_rw.commit().unwrap();
+        }
+    }
 }
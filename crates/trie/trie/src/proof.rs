@@ -14,7 +14,7 @@ use reth_primitives::{
     constants::EMPTY_ROOT_HASH,
     keccak256,
     proofs::{AccountProof, IntoTrieAccount, StorageProof},
-    Address, B256,
+    Address, Bytes, B256,
 };
 use reth_trie_types::proof::ProofRetainer;
 /// A struct for generating merkle proofs.
@@ -99,6 +99,92 @@ where
         Ok(account_proof)
     }
 
+    /// Generate the account trie nodes proving the given hashed addresses.
+    ///
+    /// The targets do not need to exist in the trie, which allows proving the boundaries of a
+    /// range of accounts. Nodes shared between the targets are only returned once, ordered by
+    /// their path.
+    pub fn account_proof_nodes(&self, targets: &[B256]) -> Result<Vec<Bytes>, StateRootError> {
+        let target_nibbles = targets.iter().map(|key| Nibbles::unpack(key)).collect::<Vec<_>>();
+
+        let hashed_account_cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
+        let trie_cursor =
+            DatabaseAccountTrieCursor::new(self.tx.cursor_read::<tables::AccountsTrie>()?);
+        let prefix_set = PrefixSetMut::from(target_nibbles.clone()).freeze();
+        let walker = TrieWalker::new(trie_cursor, prefix_set);
+
+        let retainer = ProofRetainer::from_iter(target_nibbles);
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
+
+        let mut account_rlp = Vec::with_capacity(128);
+        let mut account_node_iter = TrieNodeIter::new(walker, hashed_account_cursor);
+        while let Some(account_node) = account_node_iter.try_next()? {
+            match account_node {
+                TrieElement::Branch(node) => {
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                TrieElement::Leaf(hashed_address, account) => {
+                    let storage_root = self.storage_root(hashed_address)?;
+                    account_rlp.clear();
+                    let account = IntoTrieAccount::to_trie_account((account, storage_root));
+                    account.encode(&mut account_rlp as &mut dyn BufMut);
+                    hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
+                }
+            }
+        }
+
+        let _ = hash_builder.root();
+
+        Ok(hash_builder.take_proofs().into_values().collect())
+    }
+
+    /// Generate the storage trie nodes of the account proving the given hashed slots.
+    ///
+    /// See [`Self::account_proof_nodes`] for the ordering of the returned nodes. The proof is
+    /// empty if the account has no storage.
+    pub fn storage_proof_nodes(
+        &self,
+        hashed_address: B256,
+        targets: &[B256],
+    ) -> Result<Vec<Bytes>, StorageRootError> {
+        let mut hashed_storage_cursor =
+            self.hashed_cursor_factory.hashed_storage_cursor(hashed_address)?;
+
+        // short circuit on empty storage
+        if hashed_storage_cursor.is_storage_empty()? {
+            return Ok(Vec::new())
+        }
+
+        let target_nibbles = targets.iter().map(|key| Nibbles::unpack(key)).collect::<Vec<_>>();
+        let prefix_set = PrefixSetMut::from(target_nibbles.clone()).freeze();
+        let trie_cursor = DatabaseStorageTrieCursor::new(
+            self.tx.cursor_dup_read::<tables::StoragesTrie>()?,
+            hashed_address,
+        );
+        let walker = TrieWalker::new(trie_cursor, prefix_set);
+
+        let retainer = ProofRetainer::from_iter(target_nibbles);
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
+        let mut storage_node_iter = TrieNodeIter::new(walker, hashed_storage_cursor);
+        while let Some(node) = storage_node_iter.try_next()? {
+            match node {
+                TrieElement::Branch(node) => {
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                TrieElement::Leaf(hashed_slot, value) => {
+                    hash_builder.add_leaf(
+                        Nibbles::unpack(hashed_slot),
+                        alloy_rlp::encode_fixed_size(&value).as_ref(),
+                    );
+                }
+            }
+        }
+
+        let _ = hash_builder.root();
+
+        Ok(hash_builder.take_proofs().into_values().collect())
+    }
+
     /// Compute storage root.
     pub fn storage_root(&self, hashed_address: B256) -> Result<B256, StorageRootError> {
         let (storage_root, _) = self.storage_root_with_proofs(hashed_address, &[])?;
@@ -317,6 +403,31 @@ mod tests {
         assert_eq!(account_proof.verify(root), Ok(()));
     }
 
+    #[test]
+    fn testspec_account_proof_nodes() {
+        // Create test database and insert genesis accounts.
+        let factory = create_test_provider_factory();
+        insert_genesis(&factory, TEST_SPEC.clone()).unwrap();
+
+        let targets = [
+            Address::from_str("0x33f0fc440b8477fcfbe9d0bf8649e7dea9baedb2").unwrap(),
+            Address::from_str("0x1ed9b1dd266b607ee278726d324b855a093394a6").unwrap(),
+        ];
+
+        let provider = factory.provider().unwrap();
+        let proof = Proof::new(provider.tx_ref());
+        let proofs = targets.map(|target| proof.account_proof(target, &[]).unwrap().proof);
+
+        // A single target yields the same nodes as the account proof.
+        assert_eq!(proof.account_proof_nodes(&[keccak256(targets[0])]).unwrap(), proofs[0]);
+
+        // Multiple targets share their common nodes.
+        let nodes = proof.account_proof_nodes(&targets.map(keccak256)).unwrap();
+        let expected = proofs.iter().flatten().collect::<std::collections::HashSet<_>>();
+        assert_eq!(nodes.len(), expected.len());
+        assert!(nodes.iter().all(|node| expected.contains(node)));
+    }
+
     #[test]
     fn mainnet_genesis_account_proof() {
         // Create test database and insert genesis accounts.
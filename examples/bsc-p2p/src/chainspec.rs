@@ -17,6 +17,7 @@ pub(crate) fn bsc_chain_spec() -> Arc<ChainSpec> {
         hardforks: BTreeMap::from([(Hardfork::Shanghai, ForkCondition::Timestamp(SHANGHAI_TIME))]),
         deposit_contract: None,
         base_fee_params: reth_primitives::BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
+        fee_floors: Default::default(),
         prune_delete_limit: 0,
     }
     .into()
@@ -0,0 +1,138 @@
+//! Mocked consensus layer.
+
+use reth_beacon_consensus::{
+    BeaconConsensusEngineHandle, BeaconForkChoiceUpdateError, BeaconOnNewPayloadError,
+};
+use reth_ethereum_engine_primitives::EthEngineTypes;
+use reth_primitives::SealedBlock;
+use reth_rpc_types::engine::{
+    CancunPayloadFields, ForkchoiceState, ForkchoiceUpdated, PayloadStatus, PayloadStatusEnum,
+};
+use reth_rpc_types_compat::engine::payload::block_to_payload;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How long a message is sent again while the engine is syncing.
+const SYNC_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Delay between two attempts to send a message while the engine is syncing.
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The status the consensus layer expects in response to a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expect {
+    /// The payload or the head is valid.
+    ///
+    /// The message is sent again as long as the engine responds with `SYNCING`, because the
+    /// engine may need to download or sync blocks first.
+    Valid,
+    /// The engine doesn't know the parent of the payload or the head yet and is syncing.
+    Syncing,
+    /// The payload or the head is invalid.
+    Invalid,
+}
+
+impl Expect {
+    /// Returns `true` if the status is the expected one.
+    pub const fn matches(&self, status: &PayloadStatusEnum) -> bool {
+        matches!(
+            (self, status),
+            (Self::Valid, PayloadStatusEnum::Valid) |
+                (Self::Syncing, PayloadStatusEnum::Syncing) |
+                (Self::Invalid, PayloadStatusEnum::Invalid { .. })
+        )
+    }
+
+    /// Returns `true` if the message should be sent again after a response with the status.
+    const fn retry(&self, status: &PayloadStatusEnum) -> bool {
+        matches!(self, Self::Valid) && matches!(status, PayloadStatusEnum::Syncing)
+    }
+}
+
+/// A consensus layer that drives the engine with scripted messages, like a beacon node would
+/// through the engine API.
+#[derive(Debug, Clone)]
+pub struct MockConsensusLayer {
+    engine: BeaconConsensusEngineHandle<EthEngineTypes>,
+}
+
+impl MockConsensusLayer {
+    /// Creates a new consensus layer that sends its messages to the given engine.
+    pub const fn new(engine: BeaconConsensusEngineHandle<EthEngineTypes>) -> Self {
+        Self { engine }
+    }
+
+    /// Returns the handle to the engine.
+    pub const fn engine(&self) -> &BeaconConsensusEngineHandle<EthEngineTypes> {
+        &self.engine
+    }
+
+    /// Sends the block to the engine as a `newPayload` message.
+    pub async fn new_payload(
+        &self,
+        block: &SealedBlock,
+    ) -> Result<PayloadStatus, BeaconOnNewPayloadError> {
+        let versioned_hashes = block.blob_versioned_hashes_iter().copied().collect();
+        let (payload, parent_beacon_block_root) = block_to_payload(block.clone());
+        let cancun_fields = parent_beacon_block_root.map(|parent_beacon_block_root| {
+            CancunPayloadFields { parent_beacon_block_root, versioned_hashes }
+        });
+        self.engine.new_payload(payload, cancun_fields).await
+    }
+
+    /// Sends a `forkchoiceUpdated` message without payload attributes to the engine.
+    pub async fn forkchoice_updated(
+        &self,
+        state: ForkchoiceState,
+    ) -> Result<ForkchoiceUpdated, BeaconForkChoiceUpdateError> {
+        self.engine.fork_choice_updated(state, None).await
+    }
+
+    /// Sends the block as a `newPayload` message and asserts the response.
+    ///
+    /// # Panics
+    ///
+    /// If the engine is unavailable or doesn't respond with the expected status.
+    pub async fn send_new_payload(&self, block: &SealedBlock, expect: Expect) -> PayloadStatus {
+        let deadline = Instant::now() + SYNC_TIMEOUT;
+        loop {
+            let status = self.new_payload(block).await.expect("engine should be available");
+            if expect.retry(&status.status) && Instant::now() < deadline {
+                tokio::time::sleep(RETRY_INTERVAL).await;
+                continue
+            }
+            assert!(
+                expect.matches(&status.status),
+                "unexpected status for payload #{} ({}): {status:?}, expected {expect:?}",
+                block.number,
+                block.hash(),
+            );
+            return status
+        }
+    }
+
+    /// Sends a `forkchoiceUpdated` message and asserts the response.
+    ///
+    /// # Panics
+    ///
+    /// If the engine is unavailable or doesn't respond with the expected status.
+    pub async fn send_forkchoice_updated(
+        &self,
+        state: ForkchoiceState,
+        expect: Expect,
+    ) -> ForkchoiceUpdated {
+        let deadline = Instant::now() + SYNC_TIMEOUT;
+        loop {
+            let updated = self.forkchoice_updated(state).await.expect("engine should be available");
+            if expect.retry(&updated.payload_status.status) && Instant::now() < deadline {
+                tokio::time::sleep(RETRY_INTERVAL).await;
+                continue
+            }
+            assert!(
+                expect.matches(&updated.payload_status.status),
+                "unexpected status for forkchoice {state:?}: {updated:?}, expected {expect:?}",
+            );
+            return updated
+        }
+    }
+}
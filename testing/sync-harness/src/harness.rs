@@ -0,0 +1,396 @@
+//! Assembly of the components that take part in syncing.
+
+use crate::{MockConsensusLayer, NotificationRecorder, Script, Step};
+use reth_beacon_consensus::{
+    hooks::EngineHooks, BeaconConsensusEngine, BeaconConsensusEngineError, EthBeaconConsensus,
+    MIN_BLOCKS_FOR_PIPELINE_RUN,
+};
+use reth_blockchain_tree::{
+    BlockchainTree, BlockchainTreeConfig, ShareableBlockchainTree, TreeExternals,
+};
+use reth_config::config::StageConfig;
+use reth_consensus::Consensus;
+use reth_db::{test_utils::TempDatabase, DatabaseEnv};
+use reth_db_common::init::init_genesis;
+use reth_downloaders::{
+    bodies::bodies::BodiesDownloaderBuilder,
+    headers::reverse_headers::ReverseHeadersDownloaderBuilder,
+};
+use reth_ethereum_engine_primitives::EthEngineTypes;
+use reth_evm_ethereum::{execute::EthExecutorProvider, test_utils::GeneratedState};
+use reth_network_p2p::{sync::NoopSyncStateUpdater, test_utils::TestFullBlockClient};
+use reth_payload_builder::test_utils::spawn_test_payload_service;
+use reth_primitives::{
+    BlockNumHash, ChainSpec, SealedBlockWithSenders, TryFromRecoveredTransaction, TxHash, B256,
+};
+use reth_provider::{
+    providers::BlockchainProvider, test_utils::create_test_provider_factory_with_chain_spec,
+    BlockHashReader, BlockNumReader, CanonStateSubscriptions, StateProviderFactory,
+    TransactionsProvider,
+};
+use reth_prune_types::PruneModes;
+use reth_stages::{sets::DefaultStages, Pipeline};
+use reth_static_file::StaticFileProducer;
+use reth_tasks::TokioTaskExecutor;
+use reth_transaction_pool::{
+    blobstore::InMemoryBlobStore, maintain::maintain_transaction_pool_future, EthPooledTransaction,
+    EthTransactionPool, Pool, PoolConfig, TransactionPool, TransactionValidationTaskExecutor,
+};
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use tokio::{
+    sync::{oneshot, watch},
+    time::Instant,
+};
+
+/// How long to wait for background tasks to catch up with the engine.
+const CATCH_UP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The database of the harness.
+type HarnessDatabase = Arc<TempDatabase<DatabaseEnv>>;
+
+/// The provider of the harness, backed by the database and the blockchain tree.
+pub type HarnessProvider = BlockchainProvider<HarnessDatabase>;
+
+/// The transaction pool of the harness.
+pub type HarnessPool = EthTransactionPool<HarnessProvider, InMemoryBlobStore>;
+
+/// Builder for a [`SyncHarness`].
+#[derive(Debug)]
+pub struct SyncHarnessBuilder {
+    chain_spec: Arc<ChainSpec>,
+    tree_config: BlockchainTreeConfig,
+    pipeline_run_threshold: u64,
+    pool_config: PoolConfig,
+}
+
+impl SyncHarnessBuilder {
+    /// Creates a new builder for a harness of the given chain.
+    ///
+    /// The genesis of the chain spec is written to the database, so it should be the chain spec
+    /// of the generator of the blocks the scenario uses.
+    pub fn new(chain_spec: Arc<ChainSpec>) -> Self {
+        Self {
+            chain_spec,
+            tree_config: BlockchainTreeConfig::default(),
+            pipeline_run_threshold: MIN_BLOCKS_FOR_PIPELINE_RUN,
+            pool_config: PoolConfig::default(),
+        }
+    }
+
+    /// Sets the configuration of the blockchain tree.
+    pub const fn with_tree_config(mut self, tree_config: BlockchainTreeConfig) -> Self {
+        self.tree_config = tree_config;
+        self
+    }
+
+    /// Sets the distance to the canonical tip above which the engine syncs with the pipeline
+    /// instead of the tree.
+    pub const fn with_pipeline_run_threshold(mut self, pipeline_run_threshold: u64) -> Self {
+        self.pipeline_run_threshold = pipeline_run_threshold;
+        self
+    }
+
+    /// Sets the configuration of the transaction pool.
+    pub fn with_pool_config(mut self, pool_config: PoolConfig) -> Self {
+        self.pool_config = pool_config;
+        self
+    }
+
+    /// Assembles the components and spawns the engine and the background tasks.
+    ///
+    /// # Panics
+    ///
+    /// If called outside of a tokio runtime, or if a component fails to initialize.
+    pub fn build(self) -> SyncHarness {
+        reth_tracing::init_test_tracing();
+        let Self { chain_spec, tree_config, pipeline_run_threshold, pool_config } = self;
+
+        let provider_factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+        init_genesis(provider_factory.clone()).expect("failed to write genesis");
+
+        let consensus: Arc<dyn Consensus> = Arc::new(EthBeaconConsensus::new(chain_spec.clone()));
+        let executor = EthExecutorProvider::ethereum(chain_spec.clone());
+        let peers = TestFullBlockClient::default();
+
+        // Setup pipeline
+        let (tip_tx, tip_rx) = watch::channel(B256::ZERO);
+        let header_downloader = ReverseHeadersDownloaderBuilder::default()
+            .build(peers.clone(), consensus.clone())
+            .into_task();
+        let body_downloader = BodiesDownloaderBuilder::default()
+            .build(peers.clone(), consensus.clone(), provider_factory.clone())
+            .into_task();
+        let pipeline = Pipeline::builder()
+            .with_tip_sender(tip_tx)
+            .add_stages(DefaultStages::new(
+                provider_factory.clone(),
+                tip_rx,
+                consensus.clone(),
+                header_downloader,
+                body_downloader,
+                executor.clone(),
+                StageConfig::default(),
+                PruneModes::none(),
+            ))
+            .build(
+                provider_factory.clone(),
+                StaticFileProducer::new(provider_factory.clone(), PruneModes::none()),
+            );
+
+        // Setup blockchain tree
+        let externals = TreeExternals::new(provider_factory.clone(), consensus, executor);
+        let tree =
+            BlockchainTree::new(externals, tree_config, None).expect("failed to create tree");
+        let provider =
+            BlockchainProvider::new(provider_factory, Arc::new(ShareableBlockchainTree::new(tree)))
+                .expect("failed to create provider");
+
+        // Subscribe before the engine can make any block canonical
+        let notifications = NotificationRecorder::spawn(provider.canonical_state_stream());
+
+        // Setup transaction pool and its maintenance
+        let blob_store = InMemoryBlobStore::default();
+        let validator = TransactionValidationTaskExecutor::eth_builder(chain_spec.clone())
+            .with_head_timestamp(chain_spec.genesis.timestamp)
+            .build_with_tasks(provider.clone(), TokioTaskExecutor::default(), blob_store.clone());
+        let pool = Pool::eth_pool(validator, blob_store, pool_config);
+        tokio::spawn(maintain_transaction_pool_future(
+            provider.clone(),
+            pool.clone(),
+            provider.canonical_state_stream(),
+            TokioTaskExecutor::default(),
+            Default::default(),
+        ));
+
+        // Setup consensus engine
+        let (engine, handle) = BeaconConsensusEngine::new(
+            peers.clone(),
+            pipeline,
+            provider.clone(),
+            Box::<TokioTaskExecutor>::default(),
+            Box::<NoopSyncStateUpdater>::default(),
+            None,
+            spawn_test_payload_service::<EthEngineTypes>(),
+            None,
+            pipeline_run_threshold,
+            EngineHooks::new(),
+        )
+        .expect("failed to create consensus engine");
+        let (engine_tx, engine_rx) = oneshot::channel();
+        tokio::spawn(async move {
+            let _ = engine_tx.send(engine.await);
+        });
+
+        SyncHarness {
+            provider,
+            pool,
+            peers,
+            cl: MockConsensusLayer::new(handle),
+            notifications,
+            engine_rx,
+        }
+    }
+}
+
+/// A node reduced to the components that take part in syncing, driven by a
+/// [`MockConsensusLayer`] and scripted peers.
+///
+/// See the [crate] docs for an overview.
+#[derive(Debug)]
+pub struct SyncHarness {
+    provider: HarnessProvider,
+    pool: HarnessPool,
+    peers: TestFullBlockClient,
+    cl: MockConsensusLayer,
+    notifications: NotificationRecorder,
+    engine_rx: oneshot::Receiver<Result<(), BeaconConsensusEngineError>>,
+}
+
+impl SyncHarness {
+    /// Returns the provider, backed by the database and the blockchain tree.
+    pub const fn provider(&self) -> &HarnessProvider {
+        &self.provider
+    }
+
+    /// Returns the transaction pool.
+    pub const fn pool(&self) -> &HarnessPool {
+        &self.pool
+    }
+
+    /// Returns the client of the peers that serve headers and bodies.
+    pub const fn peers(&self) -> &TestFullBlockClient {
+        &self.peers
+    }
+
+    /// Returns the consensus layer that drives the engine.
+    pub const fn cl(&self) -> &MockConsensusLayer {
+        &self.cl
+    }
+
+    /// Returns the recorder of canonical state notifications.
+    pub const fn notifications(&self) -> &NotificationRecorder {
+        &self.notifications
+    }
+
+    /// Makes the peers serve the headers and bodies of the blocks.
+    pub fn serve<'a>(&self, blocks: impl IntoIterator<Item = &'a SealedBlockWithSenders>) {
+        for block in blocks {
+            let (header, body) = block.block.clone().split_header_body();
+            self.peers.insert(header, body);
+        }
+    }
+
+    /// Runs the steps of the script in order.
+    ///
+    /// # Panics
+    ///
+    /// If the engine doesn't respond to a message with the expected status, or if the engine
+    /// exited.
+    pub async fn run(&mut self, script: &Script) {
+        for step in script.steps() {
+            match step {
+                Step::NewPayload { block, expect } => {
+                    self.cl.send_new_payload(block, *expect).await;
+                }
+                Step::ForkchoiceUpdated { state, expect } => {
+                    self.cl.send_forkchoice_updated(*state, *expect).await;
+                }
+                Step::Silence(duration) => tokio::time::sleep(*duration).await,
+                Step::Serve(blocks) => self.serve(blocks),
+            }
+            self.assert_engine_running();
+        }
+    }
+
+    /// Asserts that the engine is still running.
+    ///
+    /// # Panics
+    ///
+    /// If the engine exited.
+    pub fn assert_engine_running(&mut self) {
+        match self.engine_rx.try_recv() {
+            Err(oneshot::error::TryRecvError::Empty) => {}
+            Err(oneshot::error::TryRecvError::Closed) => panic!("consensus engine panicked"),
+            Ok(result) => panic!("consensus engine exited: {result:?}"),
+        }
+    }
+
+    /// Submits the transactions of the blocks to the pool, as if they were received from peers,
+    /// and returns their hashes.
+    ///
+    /// # Panics
+    ///
+    /// If the pool rejects a transaction.
+    pub async fn submit_transactions<'a>(
+        &self,
+        blocks: impl IntoIterator<Item = &'a SealedBlockWithSenders>,
+    ) -> Vec<TxHash> {
+        let transactions = blocks
+            .into_iter()
+            .flat_map(|block| block.clone().into_transactions_ecrecovered())
+            .map(|tx| {
+                EthPooledTransaction::try_from_recovered_transaction(tx)
+                    .expect("generated transactions can be pooled")
+            })
+            .collect();
+        self.pool
+            .add_external_transactions(transactions)
+            .await
+            .into_iter()
+            .map(|result| result.expect("transaction should be valid"))
+            .collect()
+    }
+
+    /// Asserts that the blocks are canonical, that the last of them is the tip, and that the
+    /// latest state of the provider contains the accounts and storage of `state`.
+    ///
+    /// # Panics
+    ///
+    /// If the provider state differs.
+    pub fn assert_canonical_chain(
+        &self,
+        blocks: &[SealedBlockWithSenders],
+        state: &GeneratedState,
+    ) {
+        let tip = blocks.last().expect("at least one block is canonical");
+        assert_eq!(
+            self.provider.best_block_number().unwrap(),
+            tip.number,
+            "unexpected canonical tip"
+        );
+        for block in blocks {
+            assert_eq!(
+                self.provider.block_hash(block.number).unwrap(),
+                Some(block.hash()),
+                "block #{} is not canonical",
+                block.number,
+            );
+        }
+
+        let latest = self.provider.latest().unwrap();
+        for (address, (account, storage)) in state {
+            assert_eq!(
+                latest.basic_account(*address).unwrap(),
+                Some(*account),
+                "unexpected account {address}",
+            );
+            for (slot, value) in storage {
+                assert_eq!(
+                    latest.storage(*address, *slot).unwrap(),
+                    Some(*value),
+                    "unexpected storage slot {slot} of {address}",
+                );
+            }
+        }
+    }
+
+    /// Waits until the pool maintenance caught up with the block.
+    ///
+    /// # Panics
+    ///
+    /// If the pool doesn't track the block within the timeout.
+    pub async fn wait_for_pool(&self, block: BlockNumHash) {
+        let deadline = Instant::now() + CATCH_UP_TIMEOUT;
+        while self.pool.block_info().last_seen_block_hash != block.hash {
+            assert!(
+                Instant::now() < deadline,
+                "pool is at block #{}, expected #{}",
+                self.pool.block_info().last_seen_block_number,
+                block.number,
+            );
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    /// Returns the hashes of all transactions in the pool.
+    pub fn pooled_transactions(&self) -> HashSet<TxHash> {
+        self.pool.pooled_transaction_hashes().into_iter().collect()
+    }
+
+    /// Asserts that the pool doesn't contain transactions that are included in the canonical
+    /// chain, or whose nonce was already used by the canonical chain.
+    ///
+    /// # Panics
+    ///
+    /// If the pool contains such a transaction.
+    pub fn assert_pool_consistent(&self) {
+        let latest = self.provider.latest().unwrap();
+        for transaction in self.pool.pooled_transactions() {
+            let hash = *transaction.hash();
+            assert!(
+                self.provider.transaction_by_hash(hash).unwrap().is_none(),
+                "pooled transaction {hash} is canonical",
+            );
+            let nonce = latest
+                .basic_account(transaction.sender())
+                .unwrap()
+                .map(|account| account.nonce)
+                .unwrap_or_default();
+            assert!(
+                transaction.nonce() >= nonce,
+                "pooled transaction {hash} has nonce {}, account nonce is {nonce}",
+                transaction.nonce(),
+            );
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! End-to-end harness for the sync state machine.
+//!
+//! The [`SyncHarness`] assembles the real components of a node that take part in syncing: an MDBX
+//! database in a temporary directory, the blockchain tree with the real executor, the pipeline
+//! with the default stages, the beacon consensus engine and the transaction pool with the real
+//! validator and its maintenance task.
+//!
+//! The outside world is scripted: a [`MockConsensusLayer`] drives the engine with `newPayload`
+//! and `forkchoiceUpdated` messages, and a set of peers serves the headers and bodies that a
+//! [`Script`] makes available. The chains are generated with the deterministic
+//! [`ChainGenerator`](reth_evm_ethereum::test_utils::ChainGenerator), so every run of a scenario
+//! sees the same blocks.
+//!
+//! After a scenario ran, the harness asserts the canonical chain and state of the provider, the
+//! contents of the pool and the canonical state notifications that were emitted.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+mod cl;
+pub use cl::{Expect, MockConsensusLayer};
+
+mod harness;
+pub use harness::{HarnessPool, HarnessProvider, SyncHarness, SyncHarnessBuilder};
+
+mod notifications;
+pub use notifications::{Notification, NotificationRecorder};
+
+mod script;
+pub use script::{Script, Step};
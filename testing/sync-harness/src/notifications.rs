@@ -0,0 +1,89 @@
+//! Recording of canonical state notifications.
+
+use futures_util::StreamExt;
+use parking_lot::Mutex;
+use reth_primitives::{BlockNumHash, SealedBlockWithSenders};
+use reth_provider::{CanonStateNotification, CanonStateNotificationStream, Chain};
+use std::{sync::Arc, time::Duration};
+use tokio::time::Instant;
+
+/// A canonical state notification, reduced to the blocks it reverted and committed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Notification {
+    /// The canonical chain was extended by the blocks.
+    Commit(Vec<BlockNumHash>),
+    /// The canonical chain was reorged.
+    Reorg {
+        /// The blocks that are no longer canonical.
+        reverted: Vec<BlockNumHash>,
+        /// The blocks that became canonical.
+        committed: Vec<BlockNumHash>,
+    },
+}
+
+impl Notification {
+    /// Returns the expected notification of committing the blocks.
+    pub fn commit<'a>(blocks: impl IntoIterator<Item = &'a SealedBlockWithSenders>) -> Self {
+        Self::Commit(blocks.into_iter().map(|block| block.num_hash()).collect())
+    }
+
+    /// Returns the expected notification of reorging the `reverted` blocks to the `committed`
+    /// blocks.
+    pub fn reorg<'a>(
+        reverted: impl IntoIterator<Item = &'a SealedBlockWithSenders>,
+        committed: impl IntoIterator<Item = &'a SealedBlockWithSenders>,
+    ) -> Self {
+        Self::Reorg {
+            reverted: reverted.into_iter().map(|block| block.num_hash()).collect(),
+            committed: committed.into_iter().map(|block| block.num_hash()).collect(),
+        }
+    }
+}
+
+impl From<&CanonStateNotification> for Notification {
+    fn from(notification: &CanonStateNotification) -> Self {
+        let blocks =
+            |chain: &Chain| chain.blocks_iter().map(|block| block.num_hash()).collect::<Vec<_>>();
+        match notification {
+            CanonStateNotification::Commit { new } => Self::Commit(blocks(new)),
+            CanonStateNotification::Reorg { old, new } => {
+                Self::Reorg { reverted: blocks(old), committed: blocks(new) }
+            }
+        }
+    }
+}
+
+/// Records all canonical state notifications in the background.
+#[derive(Debug, Clone, Default)]
+pub struct NotificationRecorder {
+    notifications: Arc<Mutex<Vec<Notification>>>,
+}
+
+impl NotificationRecorder {
+    /// Spawns a task that records the notifications of the stream.
+    pub fn spawn(mut stream: CanonStateNotificationStream) -> Self {
+        let recorder = Self::default();
+        let notifications = recorder.notifications.clone();
+        tokio::spawn(async move {
+            while let Some(notification) = stream.next().await {
+                notifications.lock().push(Notification::from(&notification));
+            }
+        });
+        recorder
+    }
+
+    /// Returns the notifications recorded so far.
+    pub fn recorded(&self) -> Vec<Notification> {
+        self.notifications.lock().clone()
+    }
+
+    /// Waits until at least `count` notifications were recorded, or the timeout elapsed, and
+    /// returns the recorded notifications.
+    pub async fn wait_for(&self, count: usize, timeout: Duration) -> Vec<Notification> {
+        let deadline = Instant::now() + timeout;
+        while self.notifications.lock().len() < count && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        self.recorded()
+    }
+}
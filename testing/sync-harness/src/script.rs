@@ -0,0 +1,89 @@
+//! Scripts of consensus layer messages and peer behavior.
+
+use crate::Expect;
+use reth_primitives::{SealedBlock, SealedBlockWithSenders};
+use reth_rpc_types::engine::ForkchoiceState;
+use std::time::Duration;
+
+/// A single step of a [`Script`].
+#[derive(Debug, Clone)]
+pub enum Step {
+    /// The consensus layer sends the block as a `newPayload` message.
+    NewPayload {
+        /// The block to send.
+        block: SealedBlock,
+        /// The expected status.
+        expect: Expect,
+    },
+    /// The consensus layer sends a `forkchoiceUpdated` message.
+    ForkchoiceUpdated {
+        /// The forkchoice state to send.
+        state: ForkchoiceState,
+        /// The expected status.
+        expect: Expect,
+    },
+    /// The consensus layer doesn't send anything for the given duration.
+    Silence(Duration),
+    /// The peers start serving the headers and bodies of the blocks.
+    Serve(Vec<SealedBlockWithSenders>),
+}
+
+/// A sequence of [`Step`]s that a [`SyncHarness`](crate::SyncHarness) runs in order.
+#[derive(Debug, Clone, Default)]
+pub struct Script {
+    steps: Vec<Step>,
+}
+
+impl Script {
+    /// Appends a step.
+    pub fn step(mut self, step: Step) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Appends a `newPayload` message for the block.
+    pub fn new_payload(self, block: &SealedBlock, expect: Expect) -> Self {
+        self.step(Step::NewPayload { block: block.clone(), expect })
+    }
+
+    /// Appends a `forkchoiceUpdated` message that makes the block the head, safe and finalized
+    /// block.
+    pub fn forkchoice_updated(self, head: &SealedBlock, expect: Expect) -> Self {
+        let state = ForkchoiceState {
+            head_block_hash: head.hash(),
+            safe_block_hash: head.hash(),
+            finalized_block_hash: head.hash(),
+        };
+        self.step(Step::ForkchoiceUpdated { state, expect })
+    }
+
+    /// Appends a `forkchoiceUpdated` message that makes the block the head, without changing the
+    /// safe and finalized blocks.
+    pub fn head(self, head: &SealedBlock, expect: Expect) -> Self {
+        let state = ForkchoiceState { head_block_hash: head.hash(), ..Default::default() };
+        self.step(Step::ForkchoiceUpdated { state, expect })
+    }
+
+    /// Appends a valid `newPayload` and a valid `forkchoiceUpdated` message for each block, like
+    /// a consensus layer that follows the tip of the chain.
+    pub fn follow<'a>(self, blocks: impl IntoIterator<Item = &'a SealedBlockWithSenders>) -> Self {
+        blocks.into_iter().fold(self, |script, block| {
+            script.new_payload(&block.block, Expect::Valid).head(&block.block, Expect::Valid)
+        })
+    }
+
+    /// Appends a period of silence of the consensus layer.
+    pub fn silence(self, duration: Duration) -> Self {
+        self.step(Step::Silence(duration))
+    }
+
+    /// Appends a step after which the peers serve the blocks.
+    pub fn serve<'a>(self, blocks: impl IntoIterator<Item = &'a SealedBlockWithSenders>) -> Self {
+        self.step(Step::Serve(blocks.into_iter().cloned().collect()))
+    }
+
+    /// Returns the steps of the script.
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+}
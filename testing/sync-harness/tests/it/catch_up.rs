@@ -0,0 +1,44 @@
+use crate::utils::{generator, NOTIFICATION_TIMEOUT};
+use reth_beacon_consensus::MIN_BLOCKS_FOR_PIPELINE_RUN;
+use reth_sync_harness::{Expect, Notification, Script, SyncHarnessBuilder};
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn catches_up_with_pipeline_after_silence() {
+    let mut generator = generator(4);
+    let followed = generator.generate_blocks(2);
+    // enough blocks for the engine to sync them with the pipeline instead of the tree
+    let missed = generator.generate_blocks(MIN_BLOCKS_FOR_PIPELINE_RUN as usize + 16);
+    let resumed = generator.generate_blocks(2);
+
+    let mut harness = SyncHarnessBuilder::new(generator.chain_spec()).build();
+    // the transactions of the blocks after the catch-up are already known to the pool
+    harness.submit_transactions(&resumed).await;
+
+    let script = Script::default()
+        .follow(&followed)
+        // the consensus layer is silent while the chain advances, and peers learn about it
+        .silence(Duration::from_millis(100))
+        .serve(followed.iter().chain(&missed))
+        // the consensus layer comes back with a head far ahead of the node
+        .head(&missed.last().unwrap().block, Expect::Valid)
+        .follow(&resumed);
+    harness.run(&script).await;
+
+    let canonical = [followed.as_slice(), missed.as_slice(), resumed.as_slice()].concat();
+    let tip = canonical.last().unwrap();
+    harness.assert_canonical_chain(&canonical, generator.state());
+
+    harness.wait_for_pool(tip.num_hash()).await;
+    harness.assert_pool_consistent();
+    assert!(harness.pooled_transactions().is_empty());
+
+    // the blocks synced by the pipeline are not announced as canonical state notifications
+    let notifications = harness.notifications().wait_for(4, NOTIFICATION_TIMEOUT).await;
+    let expected = followed
+        .iter()
+        .chain(&resumed)
+        .map(|block| Notification::commit([block]))
+        .collect::<Vec<_>>();
+    assert_eq!(notifications, expected);
+}
@@ -0,0 +1,10 @@
+//! Sync scenarios that drive the [`SyncHarness`](reth_sync_harness::SyncHarness) with a scripted
+//! consensus layer and scripted peers.
+
+mod catch_up;
+mod out_of_order;
+mod reorg;
+mod tip;
+mod utils;
+
+const fn main() {}
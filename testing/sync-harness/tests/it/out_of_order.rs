@@ -0,0 +1,28 @@
+use crate::utils::{generator, NOTIFICATION_TIMEOUT};
+use reth_sync_harness::{Expect, Notification, Script, SyncHarnessBuilder};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connects_payload_that_arrived_before_its_parent() {
+    let mut generator = generator(5);
+    let blocks = generator.generate_blocks(3);
+    let (parent, child) = (&blocks[1], &blocks[2]);
+
+    let mut harness = SyncHarnessBuilder::new(generator.chain_spec()).build();
+
+    let script = Script::default()
+        .follow(&blocks[..1])
+        // the child is buffered until its parent arrives
+        .new_payload(&child.block, Expect::Syncing)
+        .new_payload(&parent.block, Expect::Valid)
+        .head(&child.block, Expect::Valid);
+    harness.run(&script).await;
+
+    harness.assert_canonical_chain(&blocks, generator.state());
+
+    harness.wait_for_pool(child.num_hash()).await;
+    harness.assert_pool_consistent();
+
+    let notifications = harness.notifications().wait_for(2, NOTIFICATION_TIMEOUT).await;
+    let expected = vec![Notification::commit(&blocks[..1]), Notification::commit([parent, child])];
+    assert_eq!(notifications, expected);
+}
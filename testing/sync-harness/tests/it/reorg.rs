@@ -0,0 +1,38 @@
+use crate::utils::{generator, NOTIFICATION_TIMEOUT};
+use reth_sync_harness::{Expect, Notification, Script, SyncHarnessBuilder};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reorgs_three_blocks() {
+    let mut generator = generator(2);
+    let common = generator.generate_blocks(2);
+    let mut fork = generator.fork(3);
+    let old = generator.generate_blocks(3);
+    let new = fork.generate_blocks(3);
+
+    let mut harness = SyncHarnessBuilder::new(generator.chain_spec()).build();
+    // the transactions of the old blocks are mined, and go back to the pool on the reorg
+    harness.submit_transactions(&old).await;
+
+    let script = new
+        .iter()
+        .fold(Script::default().follow(common.iter().chain(&old)), |script, block| {
+            // the new blocks are valid side chain blocks until the head moves to them
+            script.new_payload(&block.block, Expect::Valid)
+        })
+        .head(&new.last().unwrap().block, Expect::Valid);
+    harness.run(&script).await;
+
+    let canonical = [common.as_slice(), new.as_slice()].concat();
+    let tip = canonical.last().unwrap();
+    harness.assert_canonical_chain(&canonical, fork.state());
+
+    // transactions of the old blocks that reuse nonces of the new blocks are dropped
+    harness.wait_for_pool(tip.num_hash()).await;
+    harness.assert_pool_consistent();
+
+    let notifications = harness.notifications().wait_for(6, NOTIFICATION_TIMEOUT).await;
+    let mut expected =
+        common.iter().chain(&old).map(|block| Notification::commit([block])).collect::<Vec<_>>();
+    expected.push(Notification::reorg(&old, &new));
+    assert_eq!(notifications, expected);
+}
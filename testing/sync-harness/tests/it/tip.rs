@@ -0,0 +1,29 @@
+use crate::utils::{generator, NOTIFICATION_TIMEOUT};
+use reth_sync_harness::{Notification, Script, SyncHarnessBuilder};
+use std::collections::HashSet;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn follows_tip() {
+    let mut generator = generator(1);
+    let blocks = generator.generate_blocks(8);
+    let state = generator.state().clone();
+    // the consensus layer never sends this block, so its transactions stay in the pool
+    let next = generator.generate_block();
+
+    let mut harness = SyncHarnessBuilder::new(generator.chain_spec()).build();
+    harness.submit_transactions(blocks.iter().chain([&next])).await;
+
+    harness.run(&Script::default().follow(&blocks)).await;
+
+    let tip = blocks.last().unwrap();
+    harness.assert_canonical_chain(&blocks, &state);
+
+    harness.wait_for_pool(tip.num_hash()).await;
+    harness.assert_pool_consistent();
+    let next_transactions = next.body.iter().map(|tx| tx.hash()).collect::<HashSet<_>>();
+    assert_eq!(harness.pooled_transactions(), next_transactions);
+
+    let notifications = harness.notifications().wait_for(blocks.len(), NOTIFICATION_TIMEOUT).await;
+    let expected = blocks.iter().map(|block| Notification::commit([block])).collect::<Vec<_>>();
+    assert_eq!(notifications, expected);
+}
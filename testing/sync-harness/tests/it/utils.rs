@@ -0,0 +1,18 @@
+use reth_evm_ethereum::test_utils::ChainGenerator;
+use reth_primitives::{ChainSpecBuilder, MAINNET};
+use std::{sync::Arc, time::Duration};
+
+/// How long to wait for the expected canonical state notifications.
+pub(crate) const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Returns a generator of a chain with four funded accounts and Shanghai active at genesis.
+pub(crate) fn generator(seed: u64) -> ChainGenerator {
+    let base = Arc::new(
+        ChainSpecBuilder::default()
+            .chain(MAINNET.chain)
+            .genesis(MAINNET.genesis.clone())
+            .shanghai_activated()
+            .build(),
+    );
+    ChainGenerator::new(&base, seed, 4)
+}